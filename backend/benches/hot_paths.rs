@@ -0,0 +1,74 @@
+//! Benchmarks of service layer hot paths
+//!
+//! Run with `cargo bench --bench hot_paths`. The inputs come from the same
+//! generators as the `/api/load-profile` targets, so a regression found here
+//! can be confirmed under load against a running server.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use vyos_web_ui_backend::models::config::ConfigNode;
+use vyos_web_ui_backend::models::load_profile::ConfigTreeProfile;
+use vyos_web_ui_backend::models::monitoring::MetricsStatistics;
+use vyos_web_ui_backend::services::load_profile::{
+    config_tree_size, generate_config_tree, generate_metric_samples,
+};
+use vyos_web_ui_backend::services::ConfigService;
+
+/// Tree shapes from a small edge router to a large core router
+const TREE_PROFILES: &[(usize, usize)] = &[(8, 2), (8, 3), (10, 4)];
+
+/// Samples of one metric over an hour, a day, and a week at one per minute
+const SAMPLE_COUNTS: &[usize] = &[60, 1440, 10_080];
+
+fn tree_profiles() -> impl Iterator<Item = (String, ConfigTreeProfile)> {
+    TREE_PROFILES.iter().map(|&(breadth, depth)| {
+        let profile = ConfigTreeProfile { breadth, depth };
+        (format!("{}x{}", breadth, depth), profile)
+    })
+}
+
+fn config_tree_serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("config_tree");
+    for (label, profile) in tree_profiles() {
+        let tree = generate_config_tree(profile, 0);
+        let json = serde_json::to_vec(&tree).unwrap();
+        group.throughput(Throughput::Bytes(json.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("serialize", &label), &tree, |b, tree| {
+            b.iter(|| serde_json::to_vec(black_box(tree)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("deserialize", &label), &json, |b, json| {
+            b.iter(|| serde_json::from_slice::<ConfigNode>(black_box(json)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn config_diff(c: &mut Criterion) {
+    let mut group = c.benchmark_group("config_diff");
+    for (label, profile) in tree_profiles() {
+        let trees = (generate_config_tree(profile, 0), generate_config_tree(profile, 1));
+        group.throughput(Throughput::Elements(config_tree_size(profile).unwrap() as u64));
+
+        group.bench_with_input(BenchmarkId::from_parameter(&label), &trees, |b, (old, new)| {
+            b.iter(|| ConfigService::calculate_diff(black_box(old), black_box(new)))
+        });
+    }
+    group.finish();
+}
+
+fn metric_aggregation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("metric_statistics");
+    for &count in SAMPLE_COUNTS {
+        let samples = generate_metric_samples(count);
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(BenchmarkId::from_parameter(count), &samples, |b, samples| {
+            b.iter(|| MetricsStatistics::from_values(black_box(samples)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, config_tree_serialization, config_diff, metric_aggregation);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+// k6 load profile for the service layer hot paths
+//
+// Start the backend with LOAD_PROFILE_ENABLED=true, then run
+//
+//     k6 run benches/load_profile.js
+//
+// BASE_URL overrides the server address, e.g.
+// k6 run -e BASE_URL=http://staging:8080 benches/load_profile.js
+
+import http from 'k6/http';
+import { check } from 'k6';
+
+const BASE_URL = __ENV.BASE_URL || 'http://127.0.0.1:8080';
+
+const TARGETS = {
+  ping: '/api/load-profile/ping',
+  config_tree: '/api/load-profile/config-tree?breadth=8&depth=3',
+  config_diff: '/api/load-profile/config-diff?breadth=8&depth=3',
+  metrics: '/api/load-profile/metrics?samples=1440',
+  database: '/api/load-profile/database',
+};
+
+export const options = {
+  scenarios: Object.fromEntries(
+    Object.keys(TARGETS).map((target) => [
+      target,
+      {
+        executor: 'constant-vus',
+        vus: 10,
+        duration: '30s',
+        env: { TARGET: target },
+        tags: { target },
+      },
+    ]),
+  ),
+  thresholds: {
+    http_req_failed: ['rate<0.01'],
+    'http_req_duration{target:ping}': ['p(95)<20'],
+    'http_req_duration{target:database}': ['p(95)<50'],
+  },
+};
+
+export default function () {
+  const response = http.get(`${BASE_URL}${TARGETS[__ENV.TARGET]}`);
+  check(response, { 'status is 200': (r) => r.status === 200 });
+}
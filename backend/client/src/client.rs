@@ -0,0 +1,293 @@
+//! REST API Client
+
+use reqwest::{Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+use crate::models::*;
+use crate::ws::WsClient;
+
+/// Client for the backend REST API
+///
+/// Holds the access token of the current session; [`Client::login`] and
+/// [`Client::register`] set it, [`Client::with_token`] reuses an existing one.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl Client {
+    /// Create a client for the backend at `base_url` (e.g. `http://localhost:8080`)
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self::with_http_client(base_url, http))
+    }
+
+    /// Create a client using a preconfigured HTTP client (proxies, custom CAs, ...)
+    pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Use an existing access token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Base URL of the backend
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Access token of the current session
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    // ========================================================================
+    // Requests
+    // ========================================================================
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}/api/{}", self.base_url, path.trim_start_matches('/'));
+        let builder = self.http.request(method, url);
+        match &self.token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, builder: RequestBuilder) -> Result<T> {
+        let response = builder.send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+
+        if !status.is_success() {
+            return Err(Error::from_response(status.as_u16(), &body));
+        }
+
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// GET an API path (relative to `/api`)
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(self.request(Method::GET, path)).await
+    }
+
+    /// POST a JSON body to an API path (relative to `/api`)
+    pub async fn post<T: DeserializeOwned>(&self, path: &str, body: &impl Serialize) -> Result<T> {
+        self.send(self.request(Method::POST, path).json(body)).await
+    }
+
+    /// PUT a JSON body to an API path (relative to `/api`)
+    pub async fn put<T: DeserializeOwned>(&self, path: &str, body: &impl Serialize) -> Result<T> {
+        self.send(self.request(Method::PUT, path).json(body)).await
+    }
+
+    /// DELETE an API path (relative to `/api`)
+    pub async fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(self.request(Method::DELETE, path)).await
+    }
+
+    // ========================================================================
+    // Authentication
+    // ========================================================================
+
+    /// Log in and keep the session's access token
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<LoginResponse> {
+        let request = LoginRequest {
+            username: username.to_string(),
+            password: password.to_string(),
+        };
+        let response: LoginResponse = self.post("auth/login", &request).await?;
+        self.token = Some(response.access_token.clone());
+        Ok(response)
+    }
+
+    /// Create an account and keep the new session's access token
+    pub async fn register(&mut self, request: &RegisterRequest) -> Result<LoginResponse> {
+        let response: LoginResponse = self.post("auth/register", request).await?;
+        self.token = Some(response.access_token.clone());
+        Ok(response)
+    }
+
+    /// End the session
+    pub async fn logout(&mut self) -> Result<()> {
+        let _: serde_json::Value = self.post("auth/logout", &serde_json::json!({})).await?;
+        self.token = None;
+        Ok(())
+    }
+
+    /// User of the current session
+    pub async fn current_user(&self) -> Result<User> {
+        self.get("auth/me").await
+    }
+
+    // ========================================================================
+    // Teams
+    // ========================================================================
+
+    /// Teams visible to the current user
+    pub async fn list_teams(&self) -> Result<TeamListResponse> {
+        self.get("teams").await
+    }
+
+    /// Create a team (administrators only)
+    pub async fn create_team(&self, request: &CreateTeamRequest) -> Result<Team> {
+        self.post("teams", request).await
+    }
+
+    // ========================================================================
+    // Nodes
+    // ========================================================================
+
+    /// List nodes
+    pub async fn list_nodes(&self, query: &NodeListQuery) -> Result<NodeListResponse> {
+        self.send(self.request(Method::GET, "nodes").query(query)).await
+    }
+
+    /// Get a node
+    pub async fn get_node(&self, node_id: Uuid) -> Result<Node> {
+        self.get(&format!("nodes/{}", node_id)).await
+    }
+
+    /// Register a node
+    pub async fn create_node(&self, request: &CreateNodeRequest) -> Result<Node> {
+        self.post("nodes", request).await
+    }
+
+    /// Update a node
+    pub async fn update_node(&self, node_id: Uuid, request: &UpdateNodeRequest) -> Result<Node> {
+        self.put(&format!("nodes/{}", node_id), request).await
+    }
+
+    /// Remove a node
+    pub async fn delete_node(&self, node_id: Uuid) -> Result<()> {
+        let _: serde_json::Value = self.delete(&format!("nodes/{}", node_id)).await?;
+        Ok(())
+    }
+
+    /// Test the connection to a node
+    pub async fn test_node(&self, node_id: Uuid) -> Result<NodeTestResult> {
+        self.post(&format!("nodes/{}/test", node_id), &serde_json::json!({})).await
+    }
+
+    /// Health of a node as of its last check
+    pub async fn node_health(&self, node_id: Uuid) -> Result<NodeHealthInfo> {
+        self.get(&format!("nodes/{}/health", node_id)).await
+    }
+
+    /// System information reported by a node
+    pub async fn node_info(&self, node_id: Uuid) -> Result<NodeData<NodeInfo>> {
+        self.get(&format!("nodes/{}/info", node_id)).await
+    }
+
+    /// Network interfaces of a node
+    pub async fn node_interfaces(&self, node_id: Uuid) -> Result<NodeData<Vec<NodeInterface>>> {
+        self.get(&format!("nodes/{}/interfaces", node_id)).await
+    }
+
+    /// Run a show command on a node (e.g. `show version`)
+    pub async fn node_show(&self, node_id: Uuid, command: &str) -> Result<ShowResult> {
+        self.post(
+            &format!("nodes/{}/show", node_id),
+            &serde_json::json!({ "command": command }),
+        )
+        .await
+    }
+
+    /// Configuration of a node, optionally limited to a path
+    pub async fn node_config(&self, node_id: Uuid, path: Option<&str>) -> Result<NodeData<serde_json::Value>> {
+        self.post(
+            &format!("nodes/{}/config", node_id),
+            &serde_json::json!({ "path": path }),
+        )
+        .await
+    }
+
+    /// API features supported by a node, as of its last probe
+    pub async fn node_capabilities(&self, node_id: Uuid) -> Result<NodeCapabilities> {
+        self.get(&format!("nodes/{}/capabilities", node_id)).await
+    }
+
+    /// Probe a node again for the API features it supports
+    pub async fn probe_node_capabilities(&self, node_id: Uuid) -> Result<NodeCapabilities> {
+        self.post(&format!("nodes/{}/capabilities", node_id), &serde_json::json!({})).await
+    }
+
+    // ========================================================================
+    // Announcements
+    // ========================================================================
+
+    /// Announcements currently shown; no session needed
+    pub async fn list_announcements(&self) -> Result<AnnouncementListResponse> {
+        self.get("announcements").await
+    }
+
+    /// Publish an announcement to all users (administrators only)
+    pub async fn create_announcement(&self, request: &CreateAnnouncementRequest) -> Result<Announcement> {
+        self.post("announcements", request).await
+    }
+
+    /// Remove an announcement (administrators only)
+    pub async fn delete_announcement(&self, announcement_id: Uuid) -> Result<()> {
+        let _: serde_json::Value = self.delete(&format!("announcements/{}", announcement_id)).await?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // Configuration
+    // ========================================================================
+
+    /// Retrieve the configuration tree of a node
+    pub async fn retrieve_config(&self, request: &ConfigRetrieveRequest) -> Result<ConfigRetrieveResponse> {
+        self.post("config/retrieve", request).await
+    }
+
+    /// Set a configuration value on a node
+    pub async fn set_config(&self, request: &ConfigSetRequest) -> Result<ConfigSetResponse> {
+        self.post("config/configure", request).await
+    }
+
+    /// Record the running configuration of a node in its history
+    pub async fn generate_config(&self, request: &ConfigGenerateRequest) -> Result<ConfigGenerateResponse> {
+        self.post("config/generate", request).await
+    }
+
+    // ========================================================================
+    // WebSocket
+    // ========================================================================
+
+    /// Open the WebSocket channel, authenticated with the current session
+    pub async fn websocket(&self) -> Result<WsClient> {
+        let token = self.token.as_deref().ok_or(Error::NotAuthenticated)?;
+        WsClient::connect(&self.base_url, token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_urls() {
+        let client = Client::new("http://localhost:8080/").unwrap().with_token("abc");
+        assert_eq!(client.base_url(), "http://localhost:8080");
+
+        let request = client.request(Method::GET, "/nodes").build().unwrap();
+        assert_eq!(request.url().as_str(), "http://localhost:8080/api/nodes");
+        assert_eq!(request.headers()["Authorization"], "Bearer abc");
+    }
+}
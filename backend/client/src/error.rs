@@ -0,0 +1,86 @@
+//! Client Errors
+
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Error returned by client operations
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The backend rejected the request
+    #[error("API error ({status}): {message}")]
+    Api { status: u16, message: String },
+
+    /// The request could not be sent or the response could not be read
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// WebSocket connection errors
+    #[error("WebSocket error: {0}")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+
+    /// Unexpected message or payload contents
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// The operation needs a session but the client is not logged in
+    #[error("Not authenticated")]
+    NotAuthenticated,
+}
+
+impl Error {
+    /// HTTP status code of an API error
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Api { status, .. } => Some(*status),
+            Error::Http(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Build an API error from a failed response body
+    ///
+    /// The backend answers errors with `{"error": ..., "status_code": ...}`;
+    /// other bodies are passed through as the message.
+    pub(crate) fn from_response(status: u16, body: &str) -> Self {
+        #[derive(Deserialize)]
+        struct ErrorResponse {
+            error: String,
+        }
+
+        let message = serde_json::from_str::<ErrorResponse>(body)
+            .map(|r| r.error)
+            .unwrap_or_else(|_| body.trim().to_string());
+
+        Error::Api { status, message }
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        Error::WebSocket(Box::new(err))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::InvalidResponse(err.to_string())
+    }
+}
+
+/// Result type alias for client operations
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_from_response() {
+        let err = Error::from_response(404, r#"{"error":"Not found: node","status_code":404}"#);
+        assert_eq!(err.status(), Some(404));
+        assert_eq!(err.to_string(), "API error (404): Not found: node");
+
+        let err = Error::from_response(502, "Bad Gateway\n");
+        assert_eq!(err.to_string(), "API error (502): Bad Gateway");
+    }
+}
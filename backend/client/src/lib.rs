@@ -0,0 +1,30 @@
+//! VyOS Web UI Client
+//!
+//! Typed Rust bindings to the REST and WebSocket API of the VyOS Web UI
+//! backend, for tools that automate node and configuration management.
+//!
+//! ```no_run
+//! use vyos_webui_client::{Client, models::NodeListQuery};
+//!
+//! # async fn example() -> vyos_webui_client::Result<()> {
+//! let mut client = Client::new("https://vyos-ui.example.com")?;
+//! client.login("alice", "secret-password").await?;
+//!
+//! for node in client.list_nodes(&NodeListQuery::default()).await?.nodes {
+//!     println!("{} ({})", node.name, node.host);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Endpoints without a dedicated method can be called through
+//! [`Client::get`], [`Client::post`], [`Client::put`], and [`Client::delete`].
+
+pub mod error;
+pub mod models;
+pub mod ws;
+
+mod client;
+
+pub use client::Client;
+pub use error::{Error, Result};
@@ -0,0 +1,410 @@
+//! API Models
+//!
+//! Request and response payloads of the backend API. Field names and
+//! encodings follow the backend's JSON; timestamps are RFC 3339 in UTC.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+// ============================================================================
+// Authentication
+// ============================================================================
+
+/// Login request payload
+#[derive(Debug, Clone, Serialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+/// Register request payload
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisterRequest {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    pub full_name: Option<String>,
+}
+
+/// Session issued on login and registration
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoginResponse {
+    pub user: User,
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Access token lifetime in seconds
+    pub expires_in: i64,
+}
+
+/// User role
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    Admin,
+    Operator,
+    Viewer,
+}
+
+/// User status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserStatus {
+    Active,
+    Disabled,
+    Locked,
+}
+
+/// User account
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub full_name: Option<String>,
+    pub role: UserRole,
+    pub status: UserStatus,
+    /// IANA timezone used to display timestamps
+    pub timezone: String,
+    pub last_login: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Teams
+// ============================================================================
+
+/// Team owning nodes
+#[derive(Debug, Clone, Deserialize)]
+pub struct Team {
+    pub id: Uuid,
+    pub name: String,
+    pub alias: String,
+    pub description: Option<String>,
+    pub member_count: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create team request
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateTeamRequest {
+    pub name: String,
+    pub alias: String,
+    pub description: Option<String>,
+}
+
+/// Team list response
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeamListResponse {
+    pub teams: Vec<Team>,
+    pub total: u64,
+}
+
+// ============================================================================
+// Nodes
+// ============================================================================
+
+/// Node status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeStatus {
+    Online,
+    Offline,
+    Error,
+    Testing,
+    Rebooting,
+}
+
+/// VyOS node managed by the backend
+#[derive(Debug, Clone, Deserialize)]
+pub struct Node {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub host: String,
+    pub port: u16,
+    pub api_key: String,
+    pub status: NodeStatus,
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>,
+    pub version: Option<String>,
+    pub uptime: Option<u64>,
+    pub use_https: bool,
+    pub verify_ssl: bool,
+    pub tags: Vec<String>,
+    pub timeout: u64,
+    pub team_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create node request
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateNodeRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    pub api_key: String,
+    pub use_https: Option<bool>,
+    pub verify_ssl: Option<bool>,
+    pub tags: Option<Vec<String>>,
+    pub timeout: Option<u64>,
+    pub team_id: Option<Uuid>,
+}
+
+/// Update node request; fields left `None` are unchanged
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateNodeRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_https: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_ssl: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+/// Node list filters and paging
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NodeListQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<NodeStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<String>,
+}
+
+/// Paginated node list
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeListResponse {
+    pub nodes: Vec<Node>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+}
+
+/// Node connection test result
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeTestResult {
+    pub success: bool,
+    pub message: String,
+    pub latency_ms: Option<u64>,
+    pub version: Option<String>,
+    pub hostname: Option<String>,
+    pub uptime: Option<u64>,
+}
+
+/// Node health information
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeHealthInfo {
+    pub node_id: Uuid,
+    pub status: NodeStatus,
+    pub last_check: DateTime<Utc>,
+    pub latency_ms: Option<u64>,
+    pub error_message: Option<String>,
+}
+
+/// API features supported by a node
+///
+/// Capability names are those of the backend (`show`, `retrieve`,
+/// `graphql`, ...); unknown names are kept so newer backends stay readable.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeCapabilities {
+    pub node_id: Uuid,
+    pub version: String,
+    /// Release train code name (e.g. `sagitta`)
+    pub release: Option<String>,
+    pub capabilities: BTreeMap<String, bool>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl NodeCapabilities {
+    /// Whether the node supports a capability
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.get(capability).copied().unwrap_or(false)
+    }
+}
+
+/// Data read from a node
+///
+/// `stale` is set when the node could not be reached and `data` is the last
+/// data read from it, `age_secs` ago.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeData<T> {
+    pub data: T,
+    pub stale: bool,
+    pub fetched_at: DateTime<Utc>,
+    pub age_secs: u64,
+    /// Why the node could not be read, for stale data
+    pub error: Option<String>,
+}
+
+/// VyOS system information reported by a node
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeInfo {
+    pub hostname: String,
+    pub version: String,
+    pub uptime_seconds: u64,
+    pub boot_time: Option<DateTime<Utc>>,
+    pub architecture: String,
+    pub kernel_version: String,
+}
+
+/// Network interface of a node
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeInterface {
+    pub name: String,
+    pub description: Option<String>,
+    pub address: Option<String>,
+    pub netmask: Option<String>,
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    pub mac_address: Option<String>,
+    /// Vendor of the MAC address, if known
+    pub vendor: Option<String>,
+    pub is_up: bool,
+    pub mtu: Option<u32>,
+    pub speed: Option<String>,
+    pub duplex: Option<String>,
+}
+
+/// Output of a show command run on a node
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShowResult {
+    pub success: bool,
+    pub output: String,
+    pub error: Option<String>,
+    pub data: Option<serde_json::Value>,
+}
+
+// ============================================================================
+// Announcements
+// ============================================================================
+
+/// Announcement severity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementSeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Announcement shown to all users, such as a maintenance banner
+#[derive(Debug, Clone, Deserialize)]
+pub struct Announcement {
+    pub id: Uuid,
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+    pub starts_at: DateTime<Utc>,
+    /// Shown until deleted when unset
+    pub ends_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create announcement request
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateAnnouncementRequest {
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+    /// Defaults to now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Announcement list response
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnnouncementListResponse {
+    pub announcements: Vec<Announcement>,
+    pub total: u64,
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Configuration retrieve request
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigRetrieveRequest {
+    pub node_id: Uuid,
+    pub path: Option<String>,
+    pub include_defaults: bool,
+    pub include_readonly: bool,
+}
+
+/// Retrieved configuration tree
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigRetrieveResponse {
+    pub node_id: Uuid,
+    pub config_tree: serde_json::Value,
+    pub retrieved_at: DateTime<Utc>,
+    /// Whether the tree is the last one read from an unreachable node
+    pub stale: bool,
+    pub node_count: usize,
+}
+
+/// Configuration set request
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSetRequest {
+    pub node_id: Uuid,
+    pub path: String,
+    pub value: Option<String>,
+    pub validate: bool,
+}
+
+/// Configuration set response
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigSetResponse {
+    pub success: bool,
+    pub message: String,
+    pub changes_made: Vec<String>,
+}
+
+/// Configuration generate (commit) request
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigGenerateRequest {
+    pub node_id: Uuid,
+    pub comment: String,
+    pub save: bool,
+    pub validate: bool,
+}
+
+/// Configuration generate response
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigGenerateResponse {
+    pub success: bool,
+    pub message: String,
+    pub config_snapshot_id: Option<Uuid>,
+    pub warnings: Vec<String>,
+    pub git_commit: Option<String>,
+}
@@ -0,0 +1,157 @@
+//! WebSocket Client
+//!
+//! Real-time channel at `/ws`. Messages are JSON objects tagged with `type`
+//! and carrying their fields in `data`, e.g.
+//! `{"type":"Subscribe","data":{"channel":"metrics"}}`.
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use crate::error::{Error, Result};
+
+/// WebSocket message types
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum WsMessage {
+    /// Heartbeat/ping message
+    Ping,
+
+    /// Heartbeat/pong response
+    Pong,
+
+    /// Authentication message
+    Auth { token: String },
+
+    /// Subscribe to updates
+    Subscribe { channel: String },
+
+    /// Unsubscribe from updates
+    Unsubscribe { channel: String },
+
+    /// Server broadcast
+    ///
+    /// Events from the event bus carry their sequence number.
+    Broadcast {
+        channel: String,
+        data: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sequence: Option<u64>,
+    },
+
+    /// Replay the events of the subscribed channels published after a
+    /// sequence number
+    Replay { after: u64 },
+
+    /// Sent after the replayed broadcasts, with the sequence number to
+    /// continue from
+    Replayed { cursor: u64, has_more: bool },
+
+    /// Error message
+    Error { message: String },
+}
+
+/// Open WebSocket connection to the backend
+pub struct WsClient {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl WsClient {
+    /// Connect to the backend at `base_url` and authenticate with `token`
+    pub async fn connect(base_url: &str, token: &str) -> Result<Self> {
+        let (stream, _) = connect_async(websocket_url(base_url)).await?;
+        let mut client = Self { stream };
+        client.send(&WsMessage::Auth { token: token.to_string() }).await?;
+        Ok(client)
+    }
+
+    /// Send a message
+    pub async fn send(&mut self, message: &WsMessage) -> Result<()> {
+        let text = serde_json::to_string(message)?;
+        self.stream.send(Message::Text(text)).await?;
+        Ok(())
+    }
+
+    /// Subscribe to a channel's broadcasts
+    pub async fn subscribe(&mut self, channel: &str) -> Result<()> {
+        self.send(&WsMessage::Subscribe { channel: channel.to_string() }).await
+    }
+
+    /// Stop receiving a channel's broadcasts
+    pub async fn unsubscribe(&mut self, channel: &str) -> Result<()> {
+        self.send(&WsMessage::Unsubscribe { channel: channel.to_string() }).await
+    }
+
+    /// Request the broadcasts missed since a sequence number
+    ///
+    /// The server answers with the missed broadcasts followed by
+    /// [`WsMessage::Replayed`].
+    pub async fn replay(&mut self, after: u64) -> Result<()> {
+        self.send(&WsMessage::Replay { after }).await
+    }
+
+    /// Receive the next message, or `None` once the connection is closed
+    ///
+    /// Protocol-level pings are answered automatically and not returned.
+    pub async fn next(&mut self) -> Option<Result<WsMessage>> {
+        while let Some(frame) = self.stream.next().await {
+            match frame {
+                Ok(Message::Text(text)) => return Some(serde_json::from_str(&text).map_err(Error::from)),
+                Ok(Message::Close(_)) => return None,
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        None
+    }
+
+    /// Close the connection
+    pub async fn close(mut self) -> Result<()> {
+        self.stream.close(None).await?;
+        Ok(())
+    }
+}
+
+/// WebSocket URL of the backend at an HTTP base URL
+fn websocket_url(base_url: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let url = if let Some(rest) = base_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = base_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        base_url.to_string()
+    };
+    format!("{}/ws", url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(websocket_url("http://localhost:8080/"), "ws://localhost:8080/ws");
+        assert_eq!(websocket_url("https://vyos-ui.example.com"), "wss://vyos-ui.example.com/ws");
+    }
+
+    #[test]
+    fn test_ws_message_format() {
+        assert_eq!(serde_json::to_string(&WsMessage::Ping).unwrap(), r#"{"type":"Ping"}"#);
+
+        let message: WsMessage = serde_json::from_str(
+            r#"{"type":"Broadcast","data":{"channel":"metrics","data":{"cpu":12},"sequence":3}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            message,
+            WsMessage::Broadcast {
+                channel: "metrics".to_string(),
+                data: serde_json::json!({ "cpu": 12 }),
+                sequence: Some(3),
+            }
+        );
+    }
+}
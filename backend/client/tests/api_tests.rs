@@ -0,0 +1,161 @@
+//! Client tests against a running backend
+//!
+//! Each test starts the real backend on a random local port, backed by an
+//! in-memory SQLite database with the sample data set from
+//! `vyos_web_ui_backend::seed`.
+
+use actix_web::{App, HttpServer};
+use sqlx::sqlite::SqlitePoolOptions;
+
+use vyos_web_ui_backend::app::AppState;
+use vyos_web_ui_backend::config::AppConfig;
+use vyos_web_ui_backend::db::create_database;
+use vyos_web_ui_backend::seed::{self, SEED_PASSWORD};
+use vyos_webui_client::models::{
+    AnnouncementSeverity, CreateAnnouncementRequest, CreateNodeRequest, NodeListQuery, NodeStatus,
+    UpdateNodeRequest,
+};
+use vyos_webui_client::ws::WsMessage;
+use vyos_webui_client::{Client, Error};
+
+/// Start a seeded backend and return its base URL
+async fn start_backend() -> String {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let db = create_database(pool).await.unwrap();
+    let state = AppState::new(AppConfig::from_env().unwrap(), db);
+    seed::seed(&state).await.unwrap();
+
+    let server = HttpServer::new(move || {
+        let state = state.clone();
+        App::new().configure(move |cfg| state.configure(cfg))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let address = server.addrs()[0];
+    actix_web::rt::spawn(server.run());
+
+    format!("http://{}", address)
+}
+
+#[actix_web::test]
+async fn test_login_and_current_user() {
+    let mut client = Client::new(start_backend().await).unwrap();
+
+    let err = client.current_user().await.unwrap_err();
+    assert_eq!(err.status(), Some(401));
+
+    let session = client.login("alice", SEED_PASSWORD).await.unwrap();
+    assert_eq!(session.user.id, seed::ADMIN_USER_ID);
+    assert_eq!(client.token(), Some(session.access_token.as_str()));
+
+    let user = client.current_user().await.unwrap();
+    assert_eq!(user.username, "alice");
+
+    let err = Client::new(client.base_url())
+        .unwrap()
+        .login("alice", "wrong-password")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Api { status: 401, .. }));
+}
+
+#[actix_web::test]
+async fn test_node_management() {
+    let mut client = Client::new(start_backend().await).unwrap();
+    client.login("alice", SEED_PASSWORD).await.unwrap();
+
+    let nodes = client.list_nodes(&NodeListQuery::default()).await.unwrap();
+    assert_eq!(nodes.total, 3);
+
+    let edge = client.get_node(seed::EDGE_NODE_ID).await.unwrap();
+    assert_eq!(edge.name, "edge-1");
+    assert_eq!(edge.status, NodeStatus::Offline);
+
+    let node = client
+        .create_node(&CreateNodeRequest {
+            name: "lab-1".to_string(),
+            host: "192.0.2.10".to_string(),
+            api_key: "lab-key".to_string(),
+            tags: Some(vec!["lab".to_string()]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(node.tags, ["lab"]);
+
+    let node = client
+        .update_node(
+            node.id,
+            &UpdateNodeRequest {
+                description: Some("Lab router".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(node.description.as_deref(), Some("Lab router"));
+
+    let search = NodeListQuery {
+        search: Some("lab".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(client.list_nodes(&search).await.unwrap().total, 1);
+
+    client.delete_node(node.id).await.unwrap();
+    let err = client.get_node(node.id).await.unwrap_err();
+    assert_eq!(err.status(), Some(404));
+}
+
+#[actix_web::test]
+async fn test_announcements_are_pushed_over_websocket() {
+    let mut client = Client::new(start_backend().await).unwrap();
+    client.login("alice", SEED_PASSWORD).await.unwrap();
+    let mut ws = client.websocket().await.unwrap();
+
+    // A ping round trip ensures the connection is registered and authenticated
+    ws.send(&WsMessage::Ping).await.unwrap();
+    assert_eq!(ws.next().await.unwrap().unwrap(), WsMessage::Pong);
+
+    let announcement = client
+        .create_announcement(&CreateAnnouncementRequest {
+            message: "Maintenance tonight".to_string(),
+            severity: AnnouncementSeverity::Warning,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let sequence = match ws.next().await.unwrap().unwrap() {
+        WsMessage::Broadcast { channel, data, sequence } => {
+            assert_eq!(channel, "announcements");
+            assert_eq!(data["event"], "created");
+            assert_eq!(data["announcement"]["id"], announcement.id.to_string());
+            sequence.expect("event bus broadcasts carry a sequence number")
+        }
+        other => panic!("unexpected message: {:?}", other),
+    };
+
+    // A reconnecting client catches up from the sequence before the event
+    ws.replay(sequence - 1).await.unwrap();
+    assert!(matches!(
+        ws.next().await.unwrap().unwrap(),
+        WsMessage::Broadcast { sequence: Some(replayed), .. } if replayed == sequence
+    ));
+    assert_eq!(
+        ws.next().await.unwrap().unwrap(),
+        WsMessage::Replayed { cursor: sequence, has_more: false }
+    );
+
+    let anonymous = Client::new(client.base_url()).unwrap();
+    let current = anonymous.list_announcements().await.unwrap();
+    assert_eq!(current.total, 1);
+    assert_eq!(current.announcements[0].severity, AnnouncementSeverity::Warning);
+
+    client.delete_announcement(announcement.id).await.unwrap();
+    ws.close().await.unwrap();
+}
@@ -0,0 +1,96 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (002): Teams and shared ownership
+
+SET NAMES utf8mb4;
+SET FOREIGN_KEY_CHECKS = 0;
+
+-- ============================================================================
+-- Teams Table
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `teams` (
+    `id` CHAR(36) NOT NULL,
+    `name` VARCHAR(100) NOT NULL,
+    `alias` VARCHAR(50) NOT NULL,
+    `description` TEXT,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `updated_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `idx_teams_name` (`name`),
+    UNIQUE KEY `idx_teams_alias` (`alias`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Team Members Junction Table
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `team_members` (
+    `team_id` CHAR(36) NOT NULL,
+    `user_id` BIGINT UNSIGNED NOT NULL,
+    `role` VARCHAR(20) NOT NULL DEFAULT 'member',
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`team_id`, `user_id`),
+    KEY `idx_team_members_user_id` (`user_id`),
+    CONSTRAINT `fk_team_members_team_id` FOREIGN KEY (`team_id`) REFERENCES `teams` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_team_members_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Nodes Table (rebuilt with UUID ids and team ownership)
+-- The table from 001 was never written to by the application, so it is
+-- replaced instead of migrated.
+-- ============================================================================
+ALTER TABLE `config_history` DROP FOREIGN KEY `fk_config_history_node_id`;
+ALTER TABLE `config_history` MODIFY `node_id` CHAR(36) NOT NULL;
+ALTER TABLE `monitoring_data` DROP FOREIGN KEY `fk_monitoring_data_node_id`;
+ALTER TABLE `monitoring_data` MODIFY `node_id` CHAR(36) NOT NULL;
+
+DROP TABLE IF EXISTS `nodes`;
+
+CREATE TABLE IF NOT EXISTS `nodes` (
+    `id` CHAR(36) NOT NULL,
+    `name` VARCHAR(100) NOT NULL,
+    `description` TEXT,
+    `host` VARCHAR(255) NOT NULL,
+    `port` INT NOT NULL DEFAULT 8443,
+    `api_key` VARCHAR(255) NOT NULL,
+    `status` VARCHAR(20) NOT NULL DEFAULT 'offline',
+    `last_seen` VARCHAR(40) NULL,
+    `version` VARCHAR(100) NULL,
+    `uptime` BIGINT UNSIGNED NULL,
+    `use_https` TINYINT(1) NOT NULL DEFAULT 1,
+    `verify_ssl` TINYINT(1) NOT NULL DEFAULT 0,
+    `tags` TEXT NOT NULL,
+    `timeout` INT NOT NULL DEFAULT 30,
+    `team_id` CHAR(36) NULL,
+    `created_at` VARCHAR(40) NOT NULL,
+    `updated_at` VARCHAR(40) NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `idx_nodes_name` (`name`),
+    KEY `idx_nodes_host` (`host`),
+    KEY `idx_nodes_status` (`status`),
+    KEY `idx_nodes_team_id` (`team_id`),
+    CONSTRAINT `fk_nodes_team_id` FOREIGN KEY (`team_id`) REFERENCES `teams` (`id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+ALTER TABLE `config_history` ADD CONSTRAINT `fk_config_history_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE;
+ALTER TABLE `monitoring_data` ADD CONSTRAINT `fk_monitoring_data_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE;
+
+-- ============================================================================
+-- Team Permissions
+-- ============================================================================
+INSERT IGNORE INTO `permissions` (`name`, `resource`, `action`, `description`) VALUES
+    ('teams.read', 'teams', 'read', 'View teams and their members'),
+    ('teams.write', 'teams', 'write', 'Create, update, or delete teams');
+
+INSERT IGNORE INTO `role_permissions` (`role_id`, `permission_id`)
+SELECT r.id, p.id
+FROM `roles` r
+JOIN `permissions` p ON p.name IN ('teams.read', 'teams.write')
+WHERE r.name = 'admin';
+
+INSERT IGNORE INTO `role_permissions` (`role_id`, `permission_id`)
+SELECT r.id, p.id
+FROM `roles` r
+JOIN `permissions` p ON p.name = 'teams.read'
+WHERE r.name IN ('operator', 'viewer');
+
+SET FOREIGN_KEY_CHECKS = 1;
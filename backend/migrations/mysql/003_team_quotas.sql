@@ -0,0 +1,19 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (003): Per-team quotas
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Team Quotas Table
+-- NULL limits fall back to the server-wide defaults
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `team_quotas` (
+    `team_id` CHAR(36) NOT NULL,
+    `max_nodes` INT UNSIGNED NULL,
+    `max_templates` INT UNSIGNED NULL,
+    `max_api_keys` INT UNSIGNED NULL,
+    `metric_retention_days` INT UNSIGNED NULL,
+    `updated_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`team_id`),
+    CONSTRAINT `fk_team_quotas_team_id` FOREIGN KEY (`team_id`) REFERENCES `teams` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,11 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (004): UTC timestamps and per-user timezone
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Per-user timezone preference (IANA name, e.g. 'Europe/Berlin')
+-- TIMESTAMP columns are already stored in UTC by MySQL; the preference only
+-- affects display
+-- ============================================================================
+ALTER TABLE `users` ADD COLUMN `timezone` VARCHAR(64) NOT NULL DEFAULT 'UTC' AFTER `is_superuser`;
@@ -0,0 +1,81 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (005): UUID primary keys for all tables
+--
+-- Integer ids are converted to the UUID the API already exposed for them
+-- ('00000000-0000-4000-8000-' followed by the id in hex), so user IDs held
+-- by clients and in issued tokens remain valid.
+
+SET NAMES utf8mb4;
+SET FOREIGN_KEY_CHECKS = 0;
+
+-- ============================================================================
+-- Drop foreign keys on the columns being converted
+-- ============================================================================
+ALTER TABLE `user_roles` DROP FOREIGN KEY `fk_user_roles_user_id`, DROP FOREIGN KEY `fk_user_roles_role_id`;
+ALTER TABLE `role_permissions` DROP FOREIGN KEY `fk_role_permissions_role_id`, DROP FOREIGN KEY `fk_role_permissions_permission_id`;
+ALTER TABLE `sessions` DROP FOREIGN KEY `fk_sessions_user_id`;
+ALTER TABLE `team_members` DROP FOREIGN KEY `fk_team_members_user_id`;
+ALTER TABLE `config_history` DROP FOREIGN KEY `fk_config_history_user_id`;
+
+-- ============================================================================
+-- Convert id columns
+-- ============================================================================
+ALTER TABLE `users` MODIFY `id` CHAR(36) NOT NULL;
+ALTER TABLE `roles` MODIFY `id` CHAR(36) NOT NULL;
+ALTER TABLE `permissions` MODIFY `id` CHAR(36) NOT NULL;
+ALTER TABLE `sessions` MODIFY `id` CHAR(36) NOT NULL, MODIFY `user_id` CHAR(36) NOT NULL;
+ALTER TABLE `team_members` MODIFY `user_id` CHAR(36) NOT NULL;
+ALTER TABLE `config_history` MODIFY `id` CHAR(36) NOT NULL, MODIFY `user_id` CHAR(36) NULL;
+ALTER TABLE `monitoring_data` MODIFY `id` CHAR(36) NOT NULL;
+
+-- Junction tables are keyed by the pair they link
+ALTER TABLE `user_roles`
+    MODIFY `id` BIGINT UNSIGNED NOT NULL,
+    DROP PRIMARY KEY,
+    DROP COLUMN `id`,
+    DROP INDEX `idx_user_roles_user_role`,
+    MODIFY `user_id` CHAR(36) NOT NULL,
+    MODIFY `role_id` CHAR(36) NOT NULL,
+    ADD PRIMARY KEY (`user_id`, `role_id`);
+ALTER TABLE `role_permissions`
+    MODIFY `id` BIGINT UNSIGNED NOT NULL,
+    DROP PRIMARY KEY,
+    DROP COLUMN `id`,
+    DROP INDEX `idx_role_permissions_role_permission`,
+    MODIFY `role_id` CHAR(36) NOT NULL,
+    MODIFY `permission_id` CHAR(36) NOT NULL,
+    ADD PRIMARY KEY (`role_id`, `permission_id`);
+
+-- ============================================================================
+-- Rewrite existing ids
+-- ============================================================================
+UPDATE `users` SET `id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`id` AS UNSIGNED))), 12, '0'));
+UPDATE `roles` SET `id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`id` AS UNSIGNED))), 12, '0'));
+UPDATE `permissions` SET `id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`id` AS UNSIGNED))), 12, '0'));
+UPDATE `user_roles` SET `user_id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`user_id` AS UNSIGNED))), 12, '0')), `role_id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`role_id` AS UNSIGNED))), 12, '0'));
+UPDATE `role_permissions` SET `role_id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`role_id` AS UNSIGNED))), 12, '0')), `permission_id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`permission_id` AS UNSIGNED))), 12, '0'));
+UPDATE `sessions` SET `id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`id` AS UNSIGNED))), 12, '0')), `user_id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`user_id` AS UNSIGNED))), 12, '0'));
+UPDATE `team_members` SET `user_id` = CONCAT('00000000-0000-4000-8000-', LPAD(LOWER(HEX(CAST(`user_id` AS UNSIGNED))), 12, '0'));
+
+-- Configuration history and monitoring rows referenced the integer node ids
+-- dropped in 002 and cannot be converted
+DELETE FROM `config_history`;
+DELETE FROM `monitoring_data`;
+
+-- ============================================================================
+-- Restore foreign keys
+-- ============================================================================
+ALTER TABLE `user_roles`
+    ADD CONSTRAINT `fk_user_roles_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE,
+    ADD CONSTRAINT `fk_user_roles_role_id` FOREIGN KEY (`role_id`) REFERENCES `roles` (`id`) ON DELETE CASCADE;
+ALTER TABLE `role_permissions`
+    ADD CONSTRAINT `fk_role_permissions_role_id` FOREIGN KEY (`role_id`) REFERENCES `roles` (`id`) ON DELETE CASCADE,
+    ADD CONSTRAINT `fk_role_permissions_permission_id` FOREIGN KEY (`permission_id`) REFERENCES `permissions` (`id`) ON DELETE CASCADE;
+ALTER TABLE `sessions`
+    ADD CONSTRAINT `fk_sessions_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE;
+ALTER TABLE `team_members`
+    ADD CONSTRAINT `fk_team_members_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE;
+ALTER TABLE `config_history`
+    ADD CONSTRAINT `fk_config_history_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE SET NULL;
+
+SET FOREIGN_KEY_CHECKS = 1;
@@ -0,0 +1,19 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (006): Cached node capabilities
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Node Capabilities Table
+-- Result of the last capability probe of each node; capabilities holds a JSON
+-- object mapping capability names to whether the node supports them
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `node_capabilities` (
+    `node_id` CHAR(36) NOT NULL,
+    `version` VARCHAR(100) NOT NULL,
+    `release` VARCHAR(20) NULL,
+    `capabilities` JSON NOT NULL,
+    `checked_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`node_id`),
+    CONSTRAINT `fk_node_capabilities_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
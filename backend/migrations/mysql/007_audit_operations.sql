@@ -0,0 +1,47 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (007): Audit log and operations
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Audit Log Table
+-- One row per user action; user and node IDs are kept without foreign keys so
+-- entries outlive the accounts and nodes they refer to
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `audit_log` (
+    `id` CHAR(36) NOT NULL,
+    `user_id` CHAR(36) NULL,
+    `username` VARCHAR(50) NULL,
+    `node_id` CHAR(36) NULL,
+    `action` VARCHAR(100) NOT NULL,
+    `target` VARCHAR(255) NULL,
+    `result` VARCHAR(20) NOT NULL,
+    `details` JSON NULL,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_audit_log_created_at` (`created_at`),
+    INDEX `idx_audit_log_user_id` (`user_id`),
+    INDEX `idx_audit_log_node_id` (`node_id`),
+    INDEX `idx_audit_log_action` (`action`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Operations Table
+-- Long-running system operations (reboots, image changes, resets) and their
+-- outcome, keyed by the operation ID returned to the client
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `operations` (
+    `id` VARCHAR(64) NOT NULL,
+    `kind` VARCHAR(50) NOT NULL,
+    `node_id` CHAR(36) NULL,
+    `user_id` CHAR(36) NULL,
+    `status` VARCHAR(20) NOT NULL,
+    `message` TEXT NOT NULL,
+    `started_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `completed_at` TIMESTAMP NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_operations_started_at` (`started_at`),
+    INDEX `idx_operations_user_id` (`user_id`),
+    INDEX `idx_operations_node_id` (`node_id`),
+    INDEX `idx_operations_kind` (`kind`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
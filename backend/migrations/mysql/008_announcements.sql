@@ -0,0 +1,24 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (008): Announcements
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Announcements Table
+-- Admin-managed banners (e.g. maintenance windows) shown to all users while
+-- the current time lies between starts_at and ends_at; a NULL ends_at keeps
+-- the announcement up until it is deleted
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `announcements` (
+    `id` CHAR(36) NOT NULL,
+    `message` TEXT NOT NULL,
+    `severity` VARCHAR(20) NOT NULL DEFAULT 'info',
+    `starts_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `ends_at` TIMESTAMP NULL,
+    `created_by` CHAR(36) NULL,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `updated_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_announcements_window` (`starts_at`, `ends_at`),
+    CONSTRAINT `fk_announcements_created_by` FOREIGN KEY (`created_by`) REFERENCES `users` (`id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
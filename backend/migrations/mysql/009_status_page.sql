@@ -0,0 +1,17 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (009): Status page
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Status Page Components Table
+-- Who may see each component of the fleet status page: 'public' (also
+-- anonymous wallboards), 'authenticated', or 'hidden'; components without a
+-- row are shown to authenticated users
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `status_page_components` (
+    `component` VARCHAR(50) NOT NULL,
+    `visibility` VARCHAR(20) NOT NULL DEFAULT 'authenticated',
+    `updated_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`component`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,62 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (010): Incidents
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Incidents Table
+-- Groups of correlated alerts on one node; an alert joins the node's open
+-- incident when it triggers within the correlation window of the incident's
+-- last alert, otherwise it opens a new incident
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `incidents` (
+    `id` CHAR(36) NOT NULL,
+    `node_id` VARCHAR(255) NOT NULL,
+    `title` VARCHAR(255) NOT NULL,
+    `severity` VARCHAR(20) NOT NULL,
+    `status` VARCHAR(20) NOT NULL DEFAULT 'open',
+    `opened_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `last_alert_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `acknowledged_at` TIMESTAMP NULL,
+    `acknowledged_by` VARCHAR(255) NULL,
+    `resolved_at` TIMESTAMP NULL,
+    `resolved_by` VARCHAR(255) NULL,
+    `updated_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_incidents_node_status` (`node_id`, `status`),
+    INDEX `idx_incidents_opened_at` (`opened_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Incident Alerts Table
+-- Alerts grouped into an incident; alerts only live in memory, so the fields
+-- needed for a post-mortem are copied here
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `incident_alerts` (
+    `alert_id` CHAR(36) NOT NULL,
+    `incident_id` CHAR(36) NOT NULL,
+    `title` VARCHAR(255) NOT NULL,
+    `severity` VARCHAR(20) NOT NULL,
+    `metric_name` VARCHAR(255) NULL,
+    `actual_value` DOUBLE NULL,
+    `triggered_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`alert_id`),
+    INDEX `idx_incident_alerts_incident` (`incident_id`),
+    CONSTRAINT `fk_incident_alerts_incident` FOREIGN KEY (`incident_id`) REFERENCES `incidents` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Incident Comments Table
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `incident_comments` (
+    `id` CHAR(36) NOT NULL,
+    `incident_id` CHAR(36) NOT NULL,
+    `user_id` CHAR(36) NULL,
+    `username` VARCHAR(255) NOT NULL,
+    `body` TEXT NOT NULL,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_incident_comments_incident` (`incident_id`, `created_at`),
+    CONSTRAINT `fk_incident_comments_incident` FOREIGN KEY (`incident_id`) REFERENCES `incidents` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_incident_comments_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,45 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (011): Reports
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Report Schedules Table
+-- Recurring reports; each run covers the interval since the previous one
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `report_schedules` (
+    `id` CHAR(36) NOT NULL,
+    `kind` VARCHAR(50) NOT NULL,
+    `format` VARCHAR(10) NOT NULL,
+    `frequency` VARCHAR(20) NOT NULL,
+    `recipients` JSON NOT NULL,
+    `created_by` VARCHAR(255) NULL,
+    `next_run_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `last_run_at` TIMESTAMP NULL,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_report_schedules_next_run` (`next_run_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Reports Table
+-- Rendered report artifacts, generated on demand or by a schedule
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `reports` (
+    `id` CHAR(36) NOT NULL,
+    `kind` VARCHAR(50) NOT NULL,
+    `format` VARCHAR(10) NOT NULL,
+    `title` VARCHAR(255) NOT NULL,
+    `period_start` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `period_end` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `content` LONGBLOB NOT NULL,
+    `size_bytes` BIGINT NOT NULL,
+    `schedule_id` CHAR(36) NULL,
+    `created_by` VARCHAR(255) NULL,
+    `emailed_to` JSON NOT NULL,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_reports_kind_created` (`kind`, `created_at`),
+    INDEX `idx_reports_created_at` (`created_at`),
+    CONSTRAINT `fk_reports_schedule` FOREIGN KEY (`schedule_id`) REFERENCES `report_schedules` (`id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,21 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (012): Uploads
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Uploads Table
+-- Metadata of uploaded files; the content is stored below the upload directory
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `uploads` (
+    `id` CHAR(36) NOT NULL,
+    `kind` VARCHAR(50) NOT NULL,
+    `file_name` VARCHAR(255) NOT NULL,
+    `content_type` VARCHAR(255) NULL,
+    `size_bytes` BIGINT NOT NULL,
+    `sha256` CHAR(64) NOT NULL,
+    `uploaded_by` VARCHAR(255) NULL,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_uploads_kind_created` (`kind`, `created_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
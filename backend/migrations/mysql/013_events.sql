@@ -0,0 +1,20 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (013): Domain events
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Events Table
+-- Domain events broadcast over WebSocket, kept so clients can replay the
+-- events they missed while disconnected
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `events` (
+    `sequence` BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+    `channel` VARCHAR(100) NOT NULL,
+    `data` JSON NOT NULL,
+    `to_all` BOOLEAN NOT NULL DEFAULT FALSE,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`sequence`),
+    INDEX `idx_events_channel_sequence` (`channel`, `sequence`),
+    INDEX `idx_events_created_at` (`created_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,17 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (014): Leader leases
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Leader Leases Table
+-- Time-limited leases that elect one instance of a cluster to run the
+-- background jobs; the holder renews its lease before it expires
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `leader_leases` (
+    `name` VARCHAR(100) NOT NULL,
+    `holder` VARCHAR(255) NOT NULL,
+    `acquired_at` TIMESTAMP(3) NOT NULL,
+    `expires_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
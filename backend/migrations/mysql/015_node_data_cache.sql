@@ -0,0 +1,18 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (015): Node data cache
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Node Data Cache Table
+-- Last data successfully read from each node, served marked as stale while
+-- the node cannot be reached
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `node_data_cache` (
+    `node_id` CHAR(36) NOT NULL,
+    `data_key` VARCHAR(512) NOT NULL,
+    `data` JSON NOT NULL,
+    `fetched_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`node_id`, `data_key`),
+    CONSTRAINT `fk_node_data_cache_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,16 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (016): MAC vendor database
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- OUI Vendors Table
+-- Vendor of each IEEE MAC address block, as last downloaded by an
+-- administrator; the database bundled with the server is used while empty
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `oui_vendors` (
+    `prefix` VARCHAR(9) NOT NULL,
+    `vendor` VARCHAR(255) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`prefix`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,33 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (017): Firewall logs
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Firewall Logs Table
+-- Packets logged by node firewall rules, received over syslog and tied to
+-- the rule that logged them
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `firewall_logs` (
+    `id` BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+    `node_id` CHAR(36) NOT NULL,
+    `logged_at` TIMESTAMP(3) NOT NULL,
+    `rule_id` VARCHAR(255) NULL,
+    `chain` VARCHAR(255) NOT NULL,
+    `rule_number` INT UNSIGNED NULL,
+    `action` VARCHAR(20) NOT NULL,
+    `protocol` VARCHAR(20) NULL,
+    `in_interface` VARCHAR(64) NULL,
+    `out_interface` VARCHAR(64) NULL,
+    `src_ip` VARCHAR(45) NULL,
+    `src_port` SMALLINT UNSIGNED NULL,
+    `dst_ip` VARCHAR(45) NULL,
+    `dst_port` SMALLINT UNSIGNED NULL,
+    `length` INT UNSIGNED NULL,
+    `message` TEXT NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_firewall_logs_node_logged_at` (`node_id`, `logged_at`),
+    INDEX `idx_firewall_logs_rule_logged_at` (`rule_id`, `logged_at`),
+    INDEX `idx_firewall_logs_logged_at` (`logged_at`),
+    CONSTRAINT `fk_firewall_logs_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,22 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (018): Configuration locks
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Config Locks Table
+-- Configuration subtrees that only a team, or holders of a team role, may
+-- modify
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `config_locks` (
+    `id` CHAR(36) NOT NULL,
+    `path` VARCHAR(512) NOT NULL,
+    `team_id` CHAR(36) NULL,
+    `role` VARCHAR(20) NULL,
+    `description` TEXT NULL,
+    `created_by` VARCHAR(255) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_config_locks_path` (`path`),
+    CONSTRAINT `fk_config_locks_team_id` FOREIGN KEY (`team_id`) REFERENCES `teams` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
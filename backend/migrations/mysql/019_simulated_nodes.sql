@@ -0,0 +1,26 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (019): Simulated nodes
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Node Kind
+-- 'vyos' nodes are routers reached over the API; 'simulated' nodes answer
+-- from a recording
+-- ============================================================================
+ALTER TABLE `nodes` ADD COLUMN `kind` VARCHAR(20) NOT NULL DEFAULT 'vyos' AFTER `timeout`;
+
+-- ============================================================================
+-- Node Recordings Table
+-- Configuration snapshot and show command outputs a simulated node answers
+-- with
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `node_recordings` (
+    `node_id` CHAR(36) NOT NULL,
+    `recording` JSON NOT NULL,
+    `recorded_from` CHAR(36) NULL,
+    `recorded_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`node_id`),
+    CONSTRAINT `fk_node_recordings_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_node_recordings_recorded_from` FOREIGN KEY (`recorded_from`) REFERENCES `nodes` (`id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,15 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (020): Compressed storage
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Upload Compression
+-- How an upload's content is compressed at rest, and its size on disk;
+-- uploads stored before compression keep their content as is
+-- ============================================================================
+ALTER TABLE `uploads`
+    ADD COLUMN `compression` VARCHAR(20) NOT NULL DEFAULT 'none' AFTER `sha256`,
+    ADD COLUMN `stored_bytes` BIGINT NOT NULL DEFAULT 0 AFTER `compression`;
+
+UPDATE `uploads` SET `stored_bytes` = `size_bytes`;
@@ -0,0 +1,11 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (021): Forced password resets
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Password Reset
+-- Set when an administrator requires a user to choose a new password;
+-- cleared when the user changes their password
+-- ============================================================================
+ALTER TABLE `users` ADD COLUMN `password_reset_required` TINYINT(1) NOT NULL DEFAULT 0 AFTER `timezone`;
@@ -0,0 +1,20 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (022): Verified email changes
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Email Changes Table
+-- Pending changes of a user's email address, applied once the new address
+-- confirms them; only the SHA-256 hash of the emailed token is stored
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `email_changes` (
+    `user_id` CHAR(36) NOT NULL,
+    `new_email` VARCHAR(255) NOT NULL,
+    `token_hash` CHAR(64) NOT NULL,
+    `expires_at` TIMESTAMP(3) NOT NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`user_id`),
+    UNIQUE KEY `idx_email_changes_token_hash` (`token_hash`),
+    CONSTRAINT `fk_email_changes_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,25 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (023): Login history
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- User Logins Table
+-- Successful logins with the client's address, device, and country, and
+-- whether the device or country was new to the user
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `user_logins` (
+    `id` CHAR(36) NOT NULL,
+    `user_id` CHAR(36) NOT NULL,
+    `ip_address` VARCHAR(45) NULL,
+    `user_agent` VARCHAR(512) NULL,
+    `device` VARCHAR(100) NOT NULL,
+    `country_code` CHAR(2) NULL,
+    `country_name` VARCHAR(100) NULL,
+    `new_device` TINYINT(1) NOT NULL DEFAULT 0,
+    `new_location` TINYINT(1) NOT NULL DEFAULT 0,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_user_logins_user_id` (`user_id`, `created_at`),
+    CONSTRAINT `fk_user_logins_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,26 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (024): Configuration change history
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Config Changes Table
+-- Snapshots of the configuration tree taken by commits, rollbacks, and
+-- imports through the configuration API, which rollback and diff operate on.
+-- Per-node configuration backups are kept in config_history.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `config_changes` (
+    `id` CHAR(36) NOT NULL,
+    `snapshot_id` CHAR(36) NOT NULL,
+    `config_hash` CHAR(64) NOT NULL,
+    `snapshot` LONGTEXT NOT NULL,
+    `change_type` VARCHAR(20) NOT NULL,
+    `changed_by` VARCHAR(100) NOT NULL,
+    `description` TEXT NOT NULL,
+    `is_rollback_point` TINYINT(1) NOT NULL DEFAULT 0,
+    `commit_status` VARCHAR(20) NOT NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_config_changes_snapshot_id` (`snapshot_id`),
+    INDEX `idx_config_changes_created_at` (`created_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,29 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (025): Configuration sessions
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Config Sessions Table
+-- Set and delete operations staged against a node and committed together.
+-- A commit with a confirm timeout keeps what it replaced in `restore` and is
+-- rolled back unless confirmed before `confirm_by`.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `config_sessions` (
+    `id` CHAR(36) NOT NULL,
+    `node_id` CHAR(36) NOT NULL,
+    `user_id` CHAR(36) NOT NULL,
+    `username` VARCHAR(100) NOT NULL,
+    `status` VARCHAR(20) NOT NULL DEFAULT 'open',
+    `operations` LONGTEXT NOT NULL,
+    `comment` TEXT NULL,
+    `restore` LONGTEXT NULL,
+    `confirm_by` TIMESTAMP(3) NULL,
+    `error` TEXT NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_config_sessions_node_id` (`node_id`, `status`),
+    INDEX `idx_config_sessions_status` (`status`),
+    CONSTRAINT `fk_config_sessions_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
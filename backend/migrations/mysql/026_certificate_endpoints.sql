@@ -0,0 +1,30 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (026): Certificate expiry monitoring
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Certificate Endpoints Table
+-- TLS endpoints whose certificates are checked for expiry. The fields of the
+-- last certificate seen are kept with the endpoint; `last_error` is set when
+-- the last check could not read a certificate.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `certificate_endpoints` (
+    `id` CHAR(36) NOT NULL,
+    `name` VARCHAR(100) NOT NULL,
+    `host` VARCHAR(255) NOT NULL,
+    `port` INT UNSIGNED NOT NULL,
+    `node_id` CHAR(36) NULL,
+    `warning_days` INT UNSIGNED NOT NULL,
+    `critical_days` INT UNSIGNED NOT NULL,
+    `subject` VARCHAR(255) NULL,
+    `issuer` VARCHAR(255) NULL,
+    `not_after` TIMESTAMP(3) NULL,
+    `last_checked_at` TIMESTAMP(3) NULL,
+    `last_error` TEXT NULL,
+    `created_by` VARCHAR(100) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_certificate_endpoints_node_id` (`node_id`),
+    CONSTRAINT `fk_certificate_endpoints_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,29 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (027): Refresh tokens
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Refresh Tokens Table
+-- One row per login session. The refresh token is replaced on every use;
+-- only SHA-256 hashes are stored, and the previous hash is kept so a reused
+-- token can be recognized and its session revoked.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `refresh_tokens` (
+    `id` CHAR(36) NOT NULL,
+    `user_id` CHAR(36) NOT NULL,
+    `token_hash` CHAR(64) NOT NULL,
+    `previous_token_hash` CHAR(64) NULL,
+    `ip_address` VARCHAR(45) NULL,
+    `user_agent` VARCHAR(512) NULL,
+    `device` VARCHAR(100) NOT NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `last_used_at` TIMESTAMP(3) NOT NULL,
+    `expires_at` TIMESTAMP(3) NOT NULL,
+    `revoked_at` TIMESTAMP(3) NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `idx_refresh_tokens_token_hash` (`token_hash`),
+    INDEX `idx_refresh_tokens_user_id` (`user_id`, `revoked_at`),
+    INDEX `idx_refresh_tokens_previous_token_hash` (`previous_token_hash`),
+    CONSTRAINT `fk_refresh_tokens_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
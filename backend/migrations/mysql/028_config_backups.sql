@@ -0,0 +1,26 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (028): Node configuration backups
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Config Backups Table
+-- Full configurations pulled from the nodes on a schedule or on demand,
+-- compressed as described by `compression`. Older backups are pruned by the
+-- retention policy.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `config_backups` (
+    `id` CHAR(36) NOT NULL,
+    `node_id` CHAR(36) NOT NULL,
+    `kind` VARCHAR(20) NOT NULL,
+    `config_hash` CHAR(64) NOT NULL,
+    `compression` VARCHAR(20) NOT NULL,
+    `size_bytes` BIGINT NOT NULL,
+    `stored_bytes` BIGINT NOT NULL,
+    `content` LONGBLOB NOT NULL,
+    `created_by` VARCHAR(100) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_config_backups_node_created` (`node_id`, `created_at`),
+    CONSTRAINT `fk_config_backups_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
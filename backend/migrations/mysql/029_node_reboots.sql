@@ -0,0 +1,20 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (029): Node reboots
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Node Reboots Table
+-- Nodes going through a reboot, with the time by which they are expected to
+-- answer again. Rows are removed once the node is verified after coming
+-- back, or given up on.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `node_reboots` (
+    `node_id` CHAR(36) NOT NULL,
+    `operation` VARCHAR(50) NOT NULL,
+    `requested_by` VARCHAR(100) NULL,
+    `started_at` TIMESTAMP(3) NOT NULL,
+    `expected_back_by` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`node_id`),
+    CONSTRAINT `fk_node_reboots_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,23 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (030): Setup wizards
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Wizards Table
+-- Multi-step setup flows spanning several nodes. Answers are kept by step
+-- until the generated configuration is applied to every node at once.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `wizards` (
+    `id` CHAR(36) NOT NULL,
+    `kind` VARCHAR(50) NOT NULL,
+    `user_id` CHAR(36) NOT NULL,
+    `username` VARCHAR(100) NOT NULL,
+    `status` VARCHAR(20) NOT NULL DEFAULT 'open',
+    `answers` LONGTEXT NOT NULL,
+    `error` TEXT NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_wizards_user_id` (`user_id`, `status`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
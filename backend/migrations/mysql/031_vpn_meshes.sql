@@ -0,0 +1,59 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (031): WireGuard VPN meshes
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- VPN Meshes Table
+-- WireGuard meshes among managed nodes, either fully meshed or with every
+-- node connected to a hub.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `vpn_meshes` (
+    `id` CHAR(36) NOT NULL,
+    `name` VARCHAR(100) NOT NULL,
+    `topology` VARCHAR(20) NOT NULL,
+    `hub_node_id` CHAR(36) NULL,
+    `address_pool` VARCHAR(64) NOT NULL,
+    `interface` VARCHAR(32) NOT NULL,
+    `listen_port` INT NOT NULL,
+    `status` VARCHAR(20) NOT NULL DEFAULT 'pending',
+    `error` TEXT NULL,
+    `created_by` VARCHAR(100) NOT NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `idx_vpn_meshes_name` (`name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- VPN Mesh Members Table
+-- Nodes of a mesh with their tunnel address and WireGuard key pair. The
+-- private key is only ever sent to the node itself.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `vpn_mesh_members` (
+    `mesh_id` CHAR(36) NOT NULL,
+    `node_id` CHAR(36) NOT NULL,
+    `endpoint` VARCHAR(255) NOT NULL,
+    `tunnel_address` VARCHAR(64) NOT NULL,
+    `public_key` VARCHAR(64) NOT NULL,
+    `private_key` VARCHAR(64) NOT NULL,
+    PRIMARY KEY (`mesh_id`, `node_id`),
+    INDEX `idx_vpn_mesh_members_node_id` (`node_id`),
+    CONSTRAINT `fk_vpn_mesh_members_mesh_id` FOREIGN KEY (`mesh_id`) REFERENCES `vpn_meshes` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_vpn_mesh_members_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- VPN Mesh Tunnels Table
+-- Tunnels between two members of a mesh, with their health as last checked.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `vpn_mesh_tunnels` (
+    `mesh_id` CHAR(36) NOT NULL,
+    `node_a` CHAR(36) NOT NULL,
+    `node_b` CHAR(36) NOT NULL,
+    `status` VARCHAR(20) NOT NULL DEFAULT 'unknown',
+    `last_handshake_at` TIMESTAMP(3) NULL,
+    `checked_at` TIMESTAMP(3) NULL,
+    PRIMARY KEY (`mesh_id`, `node_a`, `node_b`),
+    CONSTRAINT `fk_vpn_mesh_tunnels_mesh_id` FOREIGN KEY (`mesh_id`) REFERENCES `vpn_meshes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,14 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (032): Per-node configuration change history
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Config Change Nodes
+-- Node whose configuration a change snapshotted. Changes recorded before
+-- the configuration API addressed nodes have none and are no longer listed.
+-- ============================================================================
+ALTER TABLE `config_changes`
+    ADD COLUMN `node_id` CHAR(36) NULL AFTER `id`,
+    ADD INDEX `idx_config_changes_node_created` (`node_id`, `created_at`),
+    ADD CONSTRAINT `fk_config_changes_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE;
@@ -0,0 +1,33 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (033): Permissions granted to individual users
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Secrets Permission
+-- Reading secrets in node configuration, such as pre-shared keys and SNMP
+-- communities. Administrators hold it; anyone else needs a grant.
+-- ============================================================================
+INSERT IGNORE INTO `permissions` (`id`, `name`, `resource`, `action`, `description`) VALUES
+    ('00000000-0000-4000-8000-000000000101', 'secrets:read', 'secrets', 'read', 'Read secrets in node configuration');
+
+INSERT IGNORE INTO `role_permissions` (`role_id`, `permission_id`)
+SELECT r.id, p.id
+FROM `roles` r
+JOIN `permissions` p ON p.name = 'secrets:read'
+WHERE r.name = 'admin';
+
+-- ============================================================================
+-- User Permissions Table
+-- Permissions granted to a user directly, on top of those of their roles.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `user_permissions` (
+    `user_id` CHAR(36) NOT NULL,
+    `permission_id` CHAR(36) NOT NULL,
+    `granted_by` VARCHAR(100) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`user_id`, `permission_id`),
+    INDEX `idx_user_permissions_permission_id` (`permission_id`),
+    CONSTRAINT `fk_user_permissions_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_user_permissions_permission_id` FOREIGN KEY (`permission_id`) REFERENCES `permissions` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,42 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (034): Node notes and attachments
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Node Note Versions Table
+-- Every saved version of the markdown notes of a node. The content is kept
+-- in the artifact store; the highest version is the current one.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `node_note_versions` (
+    `node_id` CHAR(36) NOT NULL,
+    `version` INT NOT NULL,
+    `size_bytes` BIGINT NOT NULL,
+    `sha256` CHAR(64) NOT NULL,
+    `author` VARCHAR(100) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`node_id`, `version`),
+    CONSTRAINT `fk_node_note_versions_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Node Attachments Table
+-- Files attached to a node, such as rack photos and cabling diagrams.
+-- Attaching a file under a name already in use adds a version of it.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `node_attachments` (
+    `id` CHAR(36) NOT NULL,
+    `node_id` CHAR(36) NOT NULL,
+    `file_name` VARCHAR(255) NOT NULL,
+    `version` INT NOT NULL,
+    `content_type` VARCHAR(255) NULL,
+    `size_bytes` BIGINT NOT NULL,
+    `sha256` CHAR(64) NOT NULL,
+    `compression` VARCHAR(20) NOT NULL,
+    `stored_bytes` BIGINT NOT NULL,
+    `uploaded_by` VARCHAR(100) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `idx_node_attachments_node_file_version` (`node_id`, `file_name`, `version`),
+    CONSTRAINT `fk_node_attachments_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
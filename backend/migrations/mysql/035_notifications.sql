@@ -0,0 +1,66 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (035): Alert notifications
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Notification Channels Table
+-- Destinations of alert notifications: email recipients, a generic webhook,
+-- or a Slack-compatible webhook. Settings hold the kind's JSON fields.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `notification_channels` (
+    `id` CHAR(36) NOT NULL,
+    `name` VARCHAR(100) NOT NULL,
+    `kind` VARCHAR(20) NOT NULL,
+    `settings` TEXT NOT NULL,
+    `enabled` TINYINT(1) NOT NULL DEFAULT 1,
+    `created_by` VARCHAR(100) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `idx_notification_channels_name` (`name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Notification Routes Table
+-- Which alerts go to which channel, by alert rule, node, and severity.
+-- Alert rules live in memory, so rule IDs are not foreign keys.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `notification_routes` (
+    `id` CHAR(36) NOT NULL,
+    `name` VARCHAR(100) NOT NULL,
+    `channel_id` CHAR(36) NOT NULL,
+    `rule_id` CHAR(36) NULL,
+    `node_id` CHAR(36) NULL,
+    `min_severity` VARCHAR(20) NOT NULL,
+    `notify_resolved` TINYINT(1) NOT NULL DEFAULT 1,
+    `enabled` TINYINT(1) NOT NULL DEFAULT 1,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_notification_routes_channel_id` (`channel_id`),
+    CONSTRAINT `fk_notification_routes_channel_id` FOREIGN KEY (`channel_id`) REFERENCES `notification_channels` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Notification Deliveries Table
+-- Delivery log with one entry per alert, channel, and event; failed
+-- deliveries are retried and update their entry.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `notification_deliveries` (
+    `id` CHAR(36) NOT NULL,
+    `channel_id` CHAR(36) NOT NULL,
+    `route_id` CHAR(36) NULL,
+    `alert_id` CHAR(36) NULL,
+    `event` VARCHAR(20) NOT NULL,
+    `status` VARCHAR(20) NOT NULL,
+    `attempts` INT NOT NULL DEFAULT 1,
+    `error` TEXT NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `idx_notification_deliveries_alert` (`alert_id`, `channel_id`, `event`),
+    INDEX `idx_notification_deliveries_created_at` (`created_at`),
+    CONSTRAINT `fk_notification_deliveries_channel_id` FOREIGN KEY (`channel_id`) REFERENCES `notification_channels` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_notification_deliveries_route_id` FOREIGN KEY (`route_id`) REFERENCES `notification_routes` (`id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,40 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (036): Node inventory custom fields
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Node Custom Fields Table
+-- Admin-defined fields of the node inventory, such as a site code, circuit
+-- ID, or asset tag. Options hold the JSON array of choices of select fields.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `node_custom_fields` (
+    `id` CHAR(36) NOT NULL,
+    `name` VARCHAR(64) NOT NULL,
+    `label` VARCHAR(100) NOT NULL,
+    `field_type` VARCHAR(20) NOT NULL,
+    `required` TINYINT(1) NOT NULL DEFAULT 0,
+    `options` JSON NOT NULL,
+    `pattern` TEXT NULL,
+    `description` VARCHAR(500) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_node_custom_fields_name` (`name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Node Custom Field Values Table
+-- Value of a custom field on a node, as text in the canonical form of the
+-- field's type so that equal values compare equal.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `node_custom_field_values` (
+    `node_id` CHAR(36) NOT NULL,
+    `field_id` CHAR(36) NOT NULL,
+    `value` VARCHAR(1024) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`node_id`, `field_id`),
+    INDEX `idx_node_custom_field_values_field` (`field_id`, `value`(191)),
+    CONSTRAINT `fk_node_custom_field_values_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_node_custom_field_values_field_id` FOREIGN KEY (`field_id`) REFERENCES `node_custom_fields` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
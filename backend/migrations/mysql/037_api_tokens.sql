@@ -0,0 +1,29 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (037): Read-only API tokens
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- API Tokens Table
+-- Expiring read-only tokens for wallboards, acting for the user who created
+-- them on the GET endpoints of their scopes. Only the SHA-256 of a token is
+-- kept; the prefix identifies it in listings. Node IDs, when set, hold the
+-- JSON array of the only nodes the token may read.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `api_tokens` (
+    `id` CHAR(36) NOT NULL,
+    `user_id` CHAR(36) NOT NULL,
+    `name` VARCHAR(100) NOT NULL,
+    `token_prefix` VARCHAR(16) NOT NULL,
+    `token_hash` CHAR(64) NOT NULL,
+    `scopes` JSON NOT NULL,
+    `node_ids` JSON NULL,
+    `expires_at` TIMESTAMP(3) NOT NULL,
+    `last_used_at` TIMESTAMP(3) NULL,
+    `revoked_at` TIMESTAMP(3) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_api_tokens_token_hash` (`token_hash`),
+    INDEX `idx_api_tokens_user_id` (`user_id`),
+    CONSTRAINT `fk_api_tokens_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,39 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (038): Network topology links and positions
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Topology Links Table
+-- Links entered by hand, for connections discovery cannot see such as
+-- carrier circuits. Endpoints are node IDs or the IDs of discovered devices,
+-- e.g. `lldp:core-sw1`.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `topology_links` (
+    `id` CHAR(36) NOT NULL,
+    `source` VARCHAR(191) NOT NULL,
+    `target` VARCHAR(191) NOT NULL,
+    `link_type` VARCHAR(20) NOT NULL,
+    `source_interface` VARCHAR(64) NULL,
+    `target_interface` VARCHAR(64) NULL,
+    `bandwidth_mbps` BIGINT UNSIGNED NULL,
+    `description` VARCHAR(500) NULL,
+    `created_by` VARCHAR(255) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_topology_links_source` (`source`),
+    INDEX `idx_topology_links_target` (`target`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- Topology Positions Table
+-- Where each topology node was placed in the map, by node or device ID.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `topology_positions` (
+    `node_key` VARCHAR(191) NOT NULL,
+    `x` DOUBLE NOT NULL,
+    `y` DOUBLE NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`node_key`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
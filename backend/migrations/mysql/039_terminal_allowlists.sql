@@ -0,0 +1,19 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (039): Terminal command allowlists
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Terminal Allowlists Table
+-- Operational commands a user may run in WebSocket terminal sessions, as a
+-- JSON array of command prefixes. Users without an entry may not open a
+-- session; administrators are not restricted.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `terminal_allowlists` (
+    `user_id` CHAR(36) NOT NULL,
+    `commands` JSON NOT NULL,
+    `updated_by` VARCHAR(100) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`user_id`),
+    CONSTRAINT `fk_terminal_allowlists_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,16 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (040): Node transports
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Node Transport
+-- 'api' nodes are reached over the REST API; 'ssh' nodes are driven through
+-- the VyOS CLI over SSH, with a password or a private key
+-- ============================================================================
+ALTER TABLE `nodes`
+    ADD COLUMN `transport` VARCHAR(20) NOT NULL DEFAULT 'api' AFTER `kind`,
+    ADD COLUMN `ssh_port` INT NULL AFTER `transport`,
+    ADD COLUMN `ssh_username` VARCHAR(255) NULL AFTER `ssh_port`,
+    ADD COLUMN `ssh_password` VARCHAR(255) NULL AFTER `ssh_username`,
+    ADD COLUMN `ssh_private_key` TEXT NULL AFTER `ssh_password`;
@@ -0,0 +1,16 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (041): Scheduled commits
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Scheduled Commits
+-- A 'scheduled' session is committed by the scheduler at `apply_at`. `diff`
+-- holds the effect of each operation on the configuration when the commit
+-- was scheduled, and `restore` what the operations would replace, so a
+-- change made to those paths in the meantime stops the commit.
+-- ============================================================================
+ALTER TABLE `config_sessions`
+    ADD COLUMN `apply_at` TIMESTAMP(3) NULL AFTER `confirm_by`,
+    ADD COLUMN `diff` LONGTEXT NULL AFTER `apply_at`,
+    ADD INDEX `idx_config_sessions_apply_at` (`status`, `apply_at`);
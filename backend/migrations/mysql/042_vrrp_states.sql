@@ -0,0 +1,41 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (042): VRRP states
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- VRRP Group States Table
+-- State of each VRRP group of a node as last read, which changes of state
+-- are detected against. Nodes reporting a group of the same name and VRID
+-- are peers of one HA pair.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `vrrp_group_states` (
+    `node_id` CHAR(36) NOT NULL,
+    `group_name` VARCHAR(100) NOT NULL,
+    `vrid` TINYINT UNSIGNED NOT NULL,
+    `state` VARCHAR(20) NOT NULL,
+    `priority` TINYINT UNSIGNED NOT NULL,
+    `checked_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`node_id`, `group_name`),
+    INDEX `idx_vrrp_group_states_group` (`group_name`, `vrid`),
+    CONSTRAINT `fk_vrrp_group_states_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- ============================================================================
+-- VRRP Transitions Table
+-- Changes of state of the VRRP groups of a node, newest kept
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `vrrp_transitions` (
+    `id` CHAR(36) NOT NULL,
+    `node_id` CHAR(36) NOT NULL,
+    `group_name` VARCHAR(100) NOT NULL,
+    `vrid` TINYINT UNSIGNED NOT NULL,
+    `from_state` VARCHAR(20) NOT NULL,
+    `to_state` VARCHAR(20) NOT NULL,
+    `priority` TINYINT UNSIGNED NOT NULL,
+    `unexpected` TINYINT(1) NOT NULL DEFAULT 0,
+    `occurred_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    INDEX `idx_vrrp_transitions_node_id` (`node_id`, `occurred_at`),
+    CONSTRAINT `fk_vrrp_transitions_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,23 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (043): Configuration templates
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Configuration Templates Table
+-- Reusable configuration blueprints: a Handlebars body rendering to set and
+-- delete commands, and the variables it takes as JSON
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `config_templates` (
+    `id` CHAR(36) NOT NULL,
+    `name` VARCHAR(100) NOT NULL,
+    `description` VARCHAR(1000) NULL,
+    `body` MEDIUMTEXT NOT NULL,
+    `variables` TEXT NOT NULL,
+    `created_by` CHAR(36) NULL,
+    `created_at` TIMESTAMP(3) NOT NULL,
+    `updated_at` TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_config_templates_name` (`name`),
+    CONSTRAINT `fk_config_templates_created_by` FOREIGN KEY (`created_by`) REFERENCES `users` (`id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
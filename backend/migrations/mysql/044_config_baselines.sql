@@ -0,0 +1,21 @@
+-- VyOS Web UI Database Schema
+-- MySQL Migration (044): Configuration baselines
+
+SET NAMES utf8mb4;
+
+-- ============================================================================
+-- Config Baselines Table
+-- Stored snapshot each node's running configuration is expected to match,
+-- with the outcome of the last drift check against it
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS `config_baselines` (
+    `node_id` CHAR(36) NOT NULL,
+    `snapshot_id` CHAR(36) NOT NULL,
+    `alert_on_drift` TINYINT(1) NOT NULL DEFAULT 1,
+    `set_by` VARCHAR(100) NOT NULL,
+    `set_at` TIMESTAMP(3) NOT NULL,
+    `drifted` TINYINT(1) NULL,
+    `checked_at` TIMESTAMP(3) NULL,
+    PRIMARY KEY (`node_id`),
+    CONSTRAINT `fk_config_baselines_node_id` FOREIGN KEY (`node_id`) REFERENCES `nodes` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
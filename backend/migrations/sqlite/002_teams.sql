@@ -0,0 +1,80 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (002): Teams and shared ownership
+
+-- ============================================================================
+-- Teams Table
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS teams (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    alias TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- ============================================================================
+-- Team Members Junction Table
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS team_members (
+    team_id TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    role TEXT NOT NULL DEFAULT 'member',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (team_id, user_id),
+    FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_team_members_user_id ON team_members(user_id);
+
+-- ============================================================================
+-- Nodes Table (rebuilt with UUID ids and team ownership)
+-- The table from 001 was never written to by the application, so it is
+-- replaced instead of migrated.
+-- ============================================================================
+DROP TABLE IF EXISTS nodes;
+
+CREATE TABLE IF NOT EXISTS nodes (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    host TEXT NOT NULL,
+    port INTEGER NOT NULL DEFAULT 8443,
+    api_key TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'offline',
+    last_seen TEXT,
+    version TEXT,
+    uptime INTEGER,
+    use_https INTEGER NOT NULL DEFAULT 1,
+    verify_ssl INTEGER NOT NULL DEFAULT 0,
+    tags TEXT NOT NULL DEFAULT '[]',
+    timeout INTEGER NOT NULL DEFAULT 30,
+    team_id TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_nodes_host ON nodes(host);
+CREATE INDEX IF NOT EXISTS idx_nodes_status ON nodes(status);
+CREATE INDEX IF NOT EXISTS idx_nodes_team_id ON nodes(team_id);
+
+-- ============================================================================
+-- Team Permissions
+-- ============================================================================
+INSERT OR IGNORE INTO permissions (name, resource, action, description) VALUES
+    ('teams.read', 'teams', 'read', 'View teams and their members'),
+    ('teams.write', 'teams', 'write', 'Create, update, or delete teams');
+
+INSERT OR IGNORE INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r
+JOIN permissions p ON p.name IN ('teams.read', 'teams.write')
+WHERE r.name = 'admin';
+
+INSERT OR IGNORE INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r
+JOIN permissions p ON p.name = 'teams.read'
+WHERE r.name IN ('operator', 'viewer');
@@ -0,0 +1,16 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (003): Per-team quotas
+
+-- ============================================================================
+-- Team Quotas Table
+-- NULL limits fall back to the server-wide defaults
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS team_quotas (
+    team_id TEXT PRIMARY KEY,
+    max_nodes INTEGER,
+    max_templates INTEGER,
+    max_api_keys INTEGER,
+    metric_retention_days INTEGER,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE
+);
@@ -0,0 +1,89 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (004): UTC timestamps and per-user timezone
+
+-- ============================================================================
+-- Per-user timezone preference (IANA name, e.g. 'Europe/Berlin')
+-- Timestamps are always stored in UTC; the preference only affects display
+-- ============================================================================
+ALTER TABLE users ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
+
+-- ============================================================================
+-- Triggers for automatic updated_at timestamps
+-- Dropped first so that normalizing users and roles below does not bump
+-- their updated_at, then recreated to write UTC RFC3339
+-- ============================================================================
+DROP TRIGGER IF EXISTS update_users_updated_at;
+DROP TRIGGER IF EXISTS update_roles_updated_at;
+DROP TRIGGER IF EXISTS update_sessions_updated_at;
+
+-- ============================================================================
+-- Normalize existing timestamps to UTC RFC3339 (YYYY-MM-DDTHH:MM:SS.sssZ)
+-- Values that cannot be parsed are left unchanged
+-- ============================================================================
+UPDATE users SET
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at),
+    updated_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', updated_at), updated_at),
+    last_login = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', last_login), last_login);
+
+UPDATE roles SET
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at),
+    updated_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', updated_at), updated_at);
+
+UPDATE permissions SET
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at);
+
+UPDATE user_roles SET
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at);
+
+UPDATE role_permissions SET
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at);
+
+UPDATE sessions SET
+    expires_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', expires_at), expires_at),
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at),
+    updated_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', updated_at), updated_at);
+
+UPDATE nodes SET
+    last_seen = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', last_seen), last_seen),
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at),
+    updated_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', updated_at), updated_at);
+
+UPDATE config_history SET
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at);
+
+UPDATE monitoring_data SET
+    timestamp = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', timestamp), timestamp);
+
+UPDATE teams SET
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at),
+    updated_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', updated_at), updated_at);
+
+UPDATE team_members SET
+    created_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at);
+
+UPDATE team_quotas SET
+    updated_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', updated_at), updated_at);
+
+-- Users table updated_at trigger
+CREATE TRIGGER IF NOT EXISTS update_users_updated_at
+AFTER UPDATE ON users
+FOR EACH ROW
+BEGIN
+    UPDATE users SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+-- Roles table updated_at trigger
+CREATE TRIGGER IF NOT EXISTS update_roles_updated_at
+AFTER UPDATE ON roles
+FOR EACH ROW
+BEGIN
+    UPDATE roles SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+-- Sessions table updated_at trigger
+CREATE TRIGGER IF NOT EXISTS update_sessions_updated_at
+AFTER UPDATE ON sessions
+FOR EACH ROW
+BEGIN
+    UPDATE sessions SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
@@ -0,0 +1,243 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (005): UUID primary keys for all tables
+--
+-- Tables from 001 used INTEGER AUTOINCREMENT ids while later tables use UUID
+-- strings. Every integer id is converted to the UUID the API already exposed
+-- for it ('00000000-0000-4000-8000-' followed by the id in hex), so user IDs
+-- held by clients and in issued tokens remain valid.
+--
+-- Replacement tables are created under a *_new name and referenced by that
+-- name, so dropping the old tables does not cascade into the copied rows.
+-- Renaming them afterwards rewrites the foreign key references.
+
+-- ============================================================================
+-- Users Table
+-- ============================================================================
+CREATE TABLE users_new (
+    id TEXT PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE,
+    email TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    full_name TEXT NOT NULL,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    is_superuser INTEGER NOT NULL DEFAULT 0,
+    timezone TEXT NOT NULL DEFAULT 'UTC',
+    last_login TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+INSERT INTO users_new (id, username, email, password_hash, full_name, is_active, is_superuser,
+                       timezone, last_login, created_at, updated_at)
+SELECT '00000000-0000-4000-8000-' || printf('%012x', id), username, email, password_hash,
+       full_name, is_active, is_superuser, timezone, last_login, created_at, updated_at
+FROM users;
+
+-- ============================================================================
+-- Roles Table
+-- ============================================================================
+CREATE TABLE roles_new (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+INSERT INTO roles_new (id, name, description, created_at, updated_at)
+SELECT '00000000-0000-4000-8000-' || printf('%012x', id), name, description, created_at, updated_at
+FROM roles;
+
+-- ============================================================================
+-- Permissions Table
+-- ============================================================================
+CREATE TABLE permissions_new (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    resource TEXT NOT NULL,
+    action TEXT NOT NULL,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+INSERT INTO permissions_new (id, name, resource, action, description, created_at)
+SELECT '00000000-0000-4000-8000-' || printf('%012x', id), name, resource, action, description, created_at
+FROM permissions;
+
+-- ============================================================================
+-- User Roles Junction Table
+-- ============================================================================
+CREATE TABLE user_roles_new (
+    user_id TEXT NOT NULL,
+    role_id TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    PRIMARY KEY (user_id, role_id),
+    FOREIGN KEY (user_id) REFERENCES users_new(id) ON DELETE CASCADE,
+    FOREIGN KEY (role_id) REFERENCES roles_new(id) ON DELETE CASCADE
+);
+
+INSERT INTO user_roles_new (user_id, role_id, created_at)
+SELECT '00000000-0000-4000-8000-' || printf('%012x', user_id),
+       '00000000-0000-4000-8000-' || printf('%012x', role_id), created_at
+FROM user_roles;
+
+-- ============================================================================
+-- Role Permissions Junction Table
+-- ============================================================================
+CREATE TABLE role_permissions_new (
+    role_id TEXT NOT NULL,
+    permission_id TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    PRIMARY KEY (role_id, permission_id),
+    FOREIGN KEY (role_id) REFERENCES roles_new(id) ON DELETE CASCADE,
+    FOREIGN KEY (permission_id) REFERENCES permissions_new(id) ON DELETE CASCADE
+);
+
+INSERT INTO role_permissions_new (role_id, permission_id, created_at)
+SELECT '00000000-0000-4000-8000-' || printf('%012x', role_id),
+       '00000000-0000-4000-8000-' || printf('%012x', permission_id), created_at
+FROM role_permissions;
+
+-- ============================================================================
+-- Sessions Table
+-- ============================================================================
+CREATE TABLE sessions_new (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    session_token TEXT NOT NULL UNIQUE,
+    ip_address TEXT,
+    user_agent TEXT,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    FOREIGN KEY (user_id) REFERENCES users_new(id) ON DELETE CASCADE
+);
+
+INSERT INTO sessions_new (id, user_id, session_token, ip_address, user_agent, expires_at,
+                          created_at, updated_at)
+SELECT '00000000-0000-4000-8000-' || printf('%012x', id),
+       '00000000-0000-4000-8000-' || printf('%012x', user_id),
+       session_token, ip_address, user_agent, expires_at, created_at, updated_at
+FROM sessions;
+
+-- ============================================================================
+-- Team Members Junction Table
+-- ============================================================================
+CREATE TABLE team_members_new (
+    team_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL DEFAULT 'member',
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    PRIMARY KEY (team_id, user_id),
+    FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users_new(id) ON DELETE CASCADE
+);
+
+INSERT INTO team_members_new (team_id, user_id, role, created_at)
+SELECT team_id, '00000000-0000-4000-8000-' || printf('%012x', user_id), role, created_at
+FROM team_members;
+
+-- ============================================================================
+-- Configuration History and Monitoring Data Tables
+-- Their rows referenced the integer node ids dropped in 002, so the tables
+-- are recreated empty with UUID references.
+-- ============================================================================
+CREATE TABLE config_history_new (
+    id TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    user_id TEXT,
+    version TEXT NOT NULL,
+    config_data TEXT NOT NULL,
+    change_summary TEXT,
+    is_rollback_point INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users_new(id) ON DELETE SET NULL
+);
+
+CREATE TABLE monitoring_data_new (
+    id TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    metric_type TEXT NOT NULL,
+    metric_name TEXT NOT NULL,
+    value REAL NOT NULL,
+    unit TEXT,
+    timestamp TEXT NOT NULL,
+    tags TEXT,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+-- ============================================================================
+-- Swap in the new tables (children first, so no cascades fire)
+-- ============================================================================
+DROP TABLE user_roles;
+DROP TABLE role_permissions;
+DROP TABLE sessions;
+DROP TABLE team_members;
+DROP TABLE config_history;
+DROP TABLE monitoring_data;
+DROP TABLE users;
+DROP TABLE roles;
+DROP TABLE permissions;
+
+ALTER TABLE users_new RENAME TO users;
+ALTER TABLE roles_new RENAME TO roles;
+ALTER TABLE permissions_new RENAME TO permissions;
+ALTER TABLE user_roles_new RENAME TO user_roles;
+ALTER TABLE role_permissions_new RENAME TO role_permissions;
+ALTER TABLE sessions_new RENAME TO sessions;
+ALTER TABLE team_members_new RENAME TO team_members;
+ALTER TABLE config_history_new RENAME TO config_history;
+ALTER TABLE monitoring_data_new RENAME TO monitoring_data;
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+CREATE INDEX IF NOT EXISTS idx_users_is_active ON users(is_active);
+CREATE INDEX IF NOT EXISTS idx_permissions_resource ON permissions(resource);
+CREATE INDEX IF NOT EXISTS idx_permissions_action ON permissions(action);
+CREATE INDEX IF NOT EXISTS idx_user_roles_role_id ON user_roles(role_id);
+CREATE INDEX IF NOT EXISTS idx_role_permissions_permission_id ON role_permissions(permission_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_expires_at ON sessions(expires_at);
+CREATE INDEX IF NOT EXISTS idx_team_members_user_id ON team_members(user_id);
+CREATE INDEX IF NOT EXISTS idx_config_history_node_id ON config_history(node_id);
+CREATE INDEX IF NOT EXISTS idx_config_history_user_id ON config_history(user_id);
+CREATE INDEX IF NOT EXISTS idx_config_history_version ON config_history(version);
+CREATE INDEX IF NOT EXISTS idx_config_history_created_at ON config_history(created_at);
+CREATE INDEX IF NOT EXISTS idx_config_history_rollback_point ON config_history(is_rollback_point);
+CREATE INDEX IF NOT EXISTS idx_config_history_node_created ON config_history(node_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_monitoring_data_node_id ON monitoring_data(node_id);
+CREATE INDEX IF NOT EXISTS idx_monitoring_data_metric_type ON monitoring_data(metric_type);
+CREATE INDEX IF NOT EXISTS idx_monitoring_data_metric_name ON monitoring_data(metric_name);
+CREATE INDEX IF NOT EXISTS idx_monitoring_data_timestamp ON monitoring_data(timestamp);
+CREATE INDEX IF NOT EXISTS idx_monitoring_node_metric_time ON monitoring_data(node_id, metric_type, timestamp DESC);
+CREATE INDEX IF NOT EXISTS idx_monitoring_type_time ON monitoring_data(metric_type, timestamp DESC);
+
+-- ============================================================================
+-- Triggers for automatic updated_at timestamps (dropped with the old tables)
+-- ============================================================================
+
+-- Users table updated_at trigger
+CREATE TRIGGER IF NOT EXISTS update_users_updated_at
+AFTER UPDATE ON users
+FOR EACH ROW
+BEGIN
+    UPDATE users SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+-- Roles table updated_at trigger
+CREATE TRIGGER IF NOT EXISTS update_roles_updated_at
+AFTER UPDATE ON roles
+FOR EACH ROW
+BEGIN
+    UPDATE roles SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+-- Sessions table updated_at trigger
+CREATE TRIGGER IF NOT EXISTS update_sessions_updated_at
+AFTER UPDATE ON sessions
+FOR EACH ROW
+BEGIN
+    UPDATE sessions SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
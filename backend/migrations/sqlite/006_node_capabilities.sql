@@ -0,0 +1,16 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (006): Cached node capabilities
+
+-- ============================================================================
+-- Node Capabilities Table
+-- Result of the last capability probe of each node; capabilities holds a JSON
+-- object mapping capability names to whether the node supports them
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS node_capabilities (
+    node_id TEXT PRIMARY KEY,
+    version TEXT NOT NULL,
+    release TEXT,
+    capabilities TEXT NOT NULL DEFAULT '{}',
+    checked_at TEXT NOT NULL,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
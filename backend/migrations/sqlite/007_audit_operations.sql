@@ -0,0 +1,45 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (007): Audit log and operations
+
+-- ============================================================================
+-- Audit Log Table
+-- One row per user action; user and node IDs are kept without foreign keys so
+-- entries outlive the accounts and nodes they refer to
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS audit_log (
+    id TEXT PRIMARY KEY,
+    user_id TEXT,
+    username TEXT,
+    node_id TEXT,
+    action TEXT NOT NULL,
+    target TEXT,
+    result TEXT NOT NULL,
+    details TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_created_at ON audit_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_user_id ON audit_log(user_id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_node_id ON audit_log(node_id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_action ON audit_log(action);
+
+-- ============================================================================
+-- Operations Table
+-- Long-running system operations (reboots, image changes, resets) and their
+-- outcome, keyed by the operation ID returned to the client
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS operations (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    node_id TEXT,
+    user_id TEXT,
+    status TEXT NOT NULL,
+    message TEXT NOT NULL DEFAULT '',
+    started_at TEXT NOT NULL,
+    completed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_operations_started_at ON operations(started_at);
+CREATE INDEX IF NOT EXISTS idx_operations_user_id ON operations(user_id);
+CREATE INDEX IF NOT EXISTS idx_operations_node_id ON operations(node_id);
+CREATE INDEX IF NOT EXISTS idx_operations_kind ON operations(kind);
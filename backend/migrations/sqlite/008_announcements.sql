@@ -0,0 +1,22 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (008): Announcements
+
+-- ============================================================================
+-- Announcements Table
+-- Admin-managed banners (e.g. maintenance windows) shown to all users while
+-- the current time lies between starts_at and ends_at; a NULL ends_at keeps
+-- the announcement up until it is deleted
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS announcements (
+    id TEXT PRIMARY KEY,
+    message TEXT NOT NULL,
+    severity TEXT NOT NULL DEFAULT 'info',
+    starts_at TEXT NOT NULL,
+    ends_at TEXT,
+    created_by TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_announcements_window ON announcements(starts_at, ends_at);
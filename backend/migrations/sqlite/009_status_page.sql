@@ -0,0 +1,14 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (009): Status page
+
+-- ============================================================================
+-- Status Page Components Table
+-- Who may see each component of the fleet status page: 'public' (also
+-- anonymous wallboards), 'authenticated', or 'hidden'; components without a
+-- row are shown to authenticated users
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS status_page_components (
+    component TEXT PRIMARY KEY,
+    visibility TEXT NOT NULL DEFAULT 'authenticated',
+    updated_at TEXT NOT NULL
+);
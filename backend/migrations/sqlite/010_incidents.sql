@@ -0,0 +1,60 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (010): Incidents
+
+-- ============================================================================
+-- Incidents Table
+-- Groups of correlated alerts on one node; an alert joins the node's open
+-- incident when it triggers within the correlation window of the incident's
+-- last alert, otherwise it opens a new incident
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS incidents (
+    id TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'open',
+    opened_at TEXT NOT NULL,
+    last_alert_at TEXT NOT NULL,
+    acknowledged_at TEXT,
+    acknowledged_by TEXT,
+    resolved_at TEXT,
+    resolved_by TEXT,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_incidents_node_status ON incidents(node_id, status);
+CREATE INDEX IF NOT EXISTS idx_incidents_opened_at ON incidents(opened_at);
+
+-- ============================================================================
+-- Incident Alerts Table
+-- Alerts grouped into an incident; alerts only live in memory, so the fields
+-- needed for a post-mortem are copied here
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS incident_alerts (
+    alert_id TEXT PRIMARY KEY,
+    incident_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    metric_name TEXT,
+    actual_value REAL,
+    triggered_at TEXT NOT NULL,
+    FOREIGN KEY (incident_id) REFERENCES incidents(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_incident_alerts_incident ON incident_alerts(incident_id);
+
+-- ============================================================================
+-- Incident Comments Table
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS incident_comments (
+    id TEXT PRIMARY KEY,
+    incident_id TEXT NOT NULL,
+    user_id TEXT,
+    username TEXT NOT NULL,
+    body TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (incident_id) REFERENCES incidents(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_incident_comments_incident ON incident_comments(incident_id, created_at);
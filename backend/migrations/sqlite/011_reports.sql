@@ -0,0 +1,43 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (011): Reports
+
+-- ============================================================================
+-- Report Schedules Table
+-- Recurring reports; each run covers the interval since the previous one
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS report_schedules (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    format TEXT NOT NULL,
+    frequency TEXT NOT NULL,
+    recipients TEXT NOT NULL DEFAULT '[]',
+    created_by TEXT,
+    next_run_at TEXT NOT NULL,
+    last_run_at TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_schedules_next_run ON report_schedules(next_run_at);
+
+-- ============================================================================
+-- Reports Table
+-- Rendered report artifacts, generated on demand or by a schedule
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS reports (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    format TEXT NOT NULL,
+    title TEXT NOT NULL,
+    period_start TEXT NOT NULL,
+    period_end TEXT NOT NULL,
+    content BLOB NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    schedule_id TEXT,
+    created_by TEXT,
+    emailed_to TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (schedule_id) REFERENCES report_schedules(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_reports_kind_created ON reports(kind, created_at);
+CREATE INDEX IF NOT EXISTS idx_reports_created_at ON reports(created_at);
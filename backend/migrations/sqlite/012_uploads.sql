@@ -0,0 +1,19 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (012): Uploads
+
+-- ============================================================================
+-- Uploads Table
+-- Metadata of uploaded files; the content is stored below the upload directory
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS uploads (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content_type TEXT,
+    size_bytes INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    uploaded_by TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_uploads_kind_created ON uploads(kind, created_at);
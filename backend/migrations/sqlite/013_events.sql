@@ -0,0 +1,18 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (013): Domain events
+
+-- ============================================================================
+-- Events Table
+-- Domain events broadcast over WebSocket, kept so clients can replay the
+-- events they missed while disconnected
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS events (
+    sequence INTEGER PRIMARY KEY AUTOINCREMENT,
+    channel TEXT NOT NULL,
+    data TEXT NOT NULL,
+    to_all INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_events_channel_sequence ON events(channel, sequence);
+CREATE INDEX IF NOT EXISTS idx_events_created_at ON events(created_at);
@@ -0,0 +1,14 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (014): Leader leases
+
+-- ============================================================================
+-- Leader Leases Table
+-- Time-limited leases that elect one instance of a cluster to run the
+-- background jobs; the holder renews its lease before it expires
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS leader_leases (
+    name TEXT PRIMARY KEY,
+    holder TEXT NOT NULL,
+    acquired_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
@@ -0,0 +1,16 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (015): Node data cache
+
+-- ============================================================================
+-- Node Data Cache Table
+-- Last data successfully read from each node, served marked as stale while
+-- the node cannot be reached
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS node_data_cache (
+    node_id TEXT NOT NULL,
+    data_key TEXT NOT NULL,
+    data TEXT NOT NULL,
+    fetched_at TEXT NOT NULL,
+    PRIMARY KEY (node_id, data_key),
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
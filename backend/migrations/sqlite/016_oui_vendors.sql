@@ -0,0 +1,13 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (016): MAC vendor database
+
+-- ============================================================================
+-- OUI Vendors Table
+-- Vendor of each IEEE MAC address block, as last downloaded by an
+-- administrator; the database bundled with the server is used while empty
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS oui_vendors (
+    prefix TEXT PRIMARY KEY,
+    vendor TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
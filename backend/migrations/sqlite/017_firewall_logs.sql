@@ -0,0 +1,31 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (017): Firewall logs
+
+-- ============================================================================
+-- Firewall Logs Table
+-- Packets logged by node firewall rules, received over syslog and tied to
+-- the rule that logged them
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS firewall_logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    node_id TEXT NOT NULL,
+    logged_at TEXT NOT NULL,
+    rule_id TEXT,
+    chain TEXT NOT NULL,
+    rule_number INTEGER,
+    action TEXT NOT NULL,
+    protocol TEXT,
+    in_interface TEXT,
+    out_interface TEXT,
+    src_ip TEXT,
+    src_port INTEGER,
+    dst_ip TEXT,
+    dst_port INTEGER,
+    length INTEGER,
+    message TEXT NOT NULL,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_firewall_logs_node_logged_at ON firewall_logs(node_id, logged_at);
+CREATE INDEX IF NOT EXISTS idx_firewall_logs_rule_logged_at ON firewall_logs(rule_id, logged_at);
+CREATE INDEX IF NOT EXISTS idx_firewall_logs_logged_at ON firewall_logs(logged_at);
@@ -0,0 +1,20 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (018): Configuration locks
+
+-- ============================================================================
+-- Config Locks Table
+-- Configuration subtrees that only a team, or holders of a team role, may
+-- modify
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS config_locks (
+    id TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    team_id TEXT,
+    role TEXT,
+    description TEXT,
+    created_by TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_config_locks_path ON config_locks(path);
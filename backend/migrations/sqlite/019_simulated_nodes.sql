@@ -0,0 +1,23 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (019): Simulated nodes
+
+-- ============================================================================
+-- Node Kind
+-- 'vyos' nodes are routers reached over the API; 'simulated' nodes answer
+-- from a recording
+-- ============================================================================
+ALTER TABLE nodes ADD COLUMN kind TEXT NOT NULL DEFAULT 'vyos';
+
+-- ============================================================================
+-- Node Recordings Table
+-- Configuration snapshot and show command outputs a simulated node answers
+-- with
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS node_recordings (
+    node_id TEXT PRIMARY KEY,
+    recording TEXT NOT NULL,
+    recorded_from TEXT,
+    recorded_at TEXT NOT NULL,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE,
+    FOREIGN KEY (recorded_from) REFERENCES nodes(id) ON DELETE SET NULL
+);
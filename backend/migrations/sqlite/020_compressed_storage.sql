@@ -0,0 +1,12 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (020): Compressed storage
+
+-- ============================================================================
+-- Upload Compression
+-- How an upload's content is compressed at rest, and its size on disk;
+-- uploads stored before compression keep their content as is
+-- ============================================================================
+ALTER TABLE uploads ADD COLUMN compression TEXT NOT NULL DEFAULT 'none';
+ALTER TABLE uploads ADD COLUMN stored_bytes INTEGER NOT NULL DEFAULT 0;
+
+UPDATE uploads SET stored_bytes = size_bytes;
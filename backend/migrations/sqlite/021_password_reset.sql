@@ -0,0 +1,9 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (021): Forced password resets
+
+-- ============================================================================
+-- Password Reset
+-- Set when an administrator requires a user to choose a new password;
+-- cleared when the user changes their password
+-- ============================================================================
+ALTER TABLE users ADD COLUMN password_reset_required INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,16 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (022): Verified email changes
+
+-- ============================================================================
+-- Email Changes Table
+-- Pending changes of a user's email address, applied once the new address
+-- confirms them; only the SHA-256 hash of the emailed token is stored
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS email_changes (
+    user_id TEXT PRIMARY KEY,
+    new_email TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
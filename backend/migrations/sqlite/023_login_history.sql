@@ -0,0 +1,23 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (023): Login history
+
+-- ============================================================================
+-- User Logins Table
+-- Successful logins with the client's address, device, and country, and
+-- whether the device or country was new to the user
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS user_logins (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    ip_address TEXT,
+    user_agent TEXT,
+    device TEXT NOT NULL,
+    country_code TEXT,
+    country_name TEXT,
+    new_device INTEGER NOT NULL DEFAULT 0,
+    new_location INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_logins_user_id ON user_logins(user_id, created_at);
@@ -0,0 +1,23 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (024): Configuration change history
+
+-- ============================================================================
+-- Config Changes Table
+-- Snapshots of the configuration tree taken by commits, rollbacks, and
+-- imports through the configuration API, which rollback and diff operate on.
+-- Per-node configuration backups are kept in config_history.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS config_changes (
+    id TEXT PRIMARY KEY,
+    snapshot_id TEXT NOT NULL UNIQUE,
+    config_hash TEXT NOT NULL,
+    snapshot TEXT NOT NULL,
+    change_type TEXT NOT NULL,
+    changed_by TEXT NOT NULL,
+    description TEXT NOT NULL,
+    is_rollback_point INTEGER NOT NULL DEFAULT 0,
+    commit_status TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_config_changes_created_at ON config_changes(created_at);
@@ -0,0 +1,27 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (025): Configuration sessions
+
+-- ============================================================================
+-- Config Sessions Table
+-- Set and delete operations staged against a node and committed together.
+-- A commit with a confirm timeout keeps what it replaced in `restore` and is
+-- rolled back unless confirmed before `confirm_by`.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS config_sessions (
+    id TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    username TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'open',
+    operations TEXT NOT NULL DEFAULT '[]',
+    comment TEXT,
+    restore TEXT,
+    confirm_by TEXT,
+    error TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_config_sessions_node_id ON config_sessions(node_id, status);
+CREATE INDEX IF NOT EXISTS idx_config_sessions_status ON config_sessions(status);
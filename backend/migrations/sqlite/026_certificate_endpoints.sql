@@ -0,0 +1,28 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (026): Certificate expiry monitoring
+
+-- ============================================================================
+-- Certificate Endpoints Table
+-- TLS endpoints whose certificates are checked for expiry. The fields of the
+-- last certificate seen are kept with the endpoint; `last_error` is set when
+-- the last check could not read a certificate.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS certificate_endpoints (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    host TEXT NOT NULL,
+    port INTEGER NOT NULL,
+    node_id TEXT,
+    warning_days INTEGER NOT NULL,
+    critical_days INTEGER NOT NULL,
+    subject TEXT,
+    issuer TEXT,
+    not_after TEXT,
+    last_checked_at TEXT,
+    last_error TEXT,
+    created_by TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_certificate_endpoints_node_id ON certificate_endpoints(node_id);
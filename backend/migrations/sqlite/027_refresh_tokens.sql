@@ -0,0 +1,26 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (027): Refresh tokens
+
+-- ============================================================================
+-- Refresh Tokens Table
+-- One row per login session. The refresh token is replaced on every use;
+-- only SHA-256 hashes are stored, and the previous hash is kept so a reused
+-- token can be recognized and its session revoked.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    previous_token_hash TEXT,
+    ip_address TEXT,
+    user_agent TEXT,
+    device TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    last_used_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    revoked_at TEXT,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_user_id ON refresh_tokens(user_id, revoked_at);
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_previous_token_hash ON refresh_tokens(previous_token_hash);
@@ -0,0 +1,24 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (028): Node configuration backups
+
+-- ============================================================================
+-- Config Backups Table
+-- Full configurations pulled from the nodes on a schedule or on demand,
+-- compressed as described by `compression`. Older backups are pruned by the
+-- retention policy.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS config_backups (
+    id TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    config_hash TEXT NOT NULL,
+    compression TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    stored_bytes INTEGER NOT NULL,
+    content BLOB NOT NULL,
+    created_by TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_config_backups_node_created ON config_backups(node_id, created_at);
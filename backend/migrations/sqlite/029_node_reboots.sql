@@ -0,0 +1,17 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (029): Node reboots
+
+-- ============================================================================
+-- Node Reboots Table
+-- Nodes going through a reboot, with the time by which they are expected to
+-- answer again. Rows are removed once the node is verified after coming
+-- back, or given up on.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS node_reboots (
+    node_id TEXT PRIMARY KEY,
+    operation TEXT NOT NULL,
+    requested_by TEXT,
+    started_at TEXT NOT NULL,
+    expected_back_by TEXT NOT NULL,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
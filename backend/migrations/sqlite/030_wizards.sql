@@ -0,0 +1,21 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (030): Setup wizards
+
+-- ============================================================================
+-- Wizards Table
+-- Multi-step setup flows spanning several nodes. Answers are kept by step
+-- until the generated configuration is applied to every node at once.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS wizards (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    username TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'open',
+    answers TEXT NOT NULL DEFAULT '{}',
+    error TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_wizards_user_id ON wizards(user_id, status);
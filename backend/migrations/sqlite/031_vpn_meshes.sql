@@ -0,0 +1,56 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (031): WireGuard VPN meshes
+
+-- ============================================================================
+-- VPN Meshes Table
+-- WireGuard meshes among managed nodes, either fully meshed or with every
+-- node connected to a hub.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS vpn_meshes (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    topology TEXT NOT NULL,
+    hub_node_id TEXT,
+    address_pool TEXT NOT NULL,
+    interface TEXT NOT NULL,
+    listen_port INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    error TEXT,
+    created_by TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- ============================================================================
+-- VPN Mesh Members Table
+-- Nodes of a mesh with their tunnel address and WireGuard key pair. The
+-- private key is only ever sent to the node itself.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS vpn_mesh_members (
+    mesh_id TEXT NOT NULL,
+    node_id TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    tunnel_address TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    private_key TEXT NOT NULL,
+    PRIMARY KEY (mesh_id, node_id),
+    FOREIGN KEY (mesh_id) REFERENCES vpn_meshes(id) ON DELETE CASCADE,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_vpn_mesh_members_node_id ON vpn_mesh_members(node_id);
+
+-- ============================================================================
+-- VPN Mesh Tunnels Table
+-- Tunnels between two members of a mesh, with their health as last checked.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS vpn_mesh_tunnels (
+    mesh_id TEXT NOT NULL,
+    node_a TEXT NOT NULL,
+    node_b TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'unknown',
+    last_handshake_at TEXT,
+    checked_at TEXT,
+    PRIMARY KEY (mesh_id, node_a, node_b),
+    FOREIGN KEY (mesh_id) REFERENCES vpn_meshes(id) ON DELETE CASCADE
+);
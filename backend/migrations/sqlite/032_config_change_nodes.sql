@@ -0,0 +1,11 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (032): Per-node configuration change history
+
+-- ============================================================================
+-- Config Change Nodes
+-- Node whose configuration a change snapshotted. Changes recorded before
+-- the configuration API addressed nodes have none and are no longer listed.
+-- ============================================================================
+ALTER TABLE config_changes ADD COLUMN node_id TEXT REFERENCES nodes(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_config_changes_node_created ON config_changes(node_id, created_at);
@@ -0,0 +1,32 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (033): Permissions granted to individual users
+
+-- ============================================================================
+-- Secrets Permission
+-- Reading secrets in node configuration, such as pre-shared keys and SNMP
+-- communities. Administrators hold it; anyone else needs a grant.
+-- ============================================================================
+INSERT OR IGNORE INTO permissions (id, name, resource, action, description) VALUES
+    ('00000000-0000-4000-8000-000000000101', 'secrets:read', 'secrets', 'read', 'Read secrets in node configuration');
+
+INSERT OR IGNORE INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r
+JOIN permissions p ON p.name = 'secrets:read'
+WHERE r.name = 'admin';
+
+-- ============================================================================
+-- User Permissions Table
+-- Permissions granted to a user directly, on top of those of their roles.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS user_permissions (
+    user_id TEXT NOT NULL,
+    permission_id TEXT NOT NULL,
+    granted_by TEXT,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, permission_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (permission_id) REFERENCES permissions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_permissions_permission_id ON user_permissions(permission_id);
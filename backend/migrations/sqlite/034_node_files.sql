@@ -0,0 +1,39 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (034): Node notes and attachments
+
+-- ============================================================================
+-- Node Note Versions Table
+-- Every saved version of the markdown notes of a node. The content is kept
+-- in the artifact store; the highest version is the current one.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS node_note_versions (
+    node_id TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    author TEXT,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (node_id, version),
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+-- ============================================================================
+-- Node Attachments Table
+-- Files attached to a node, such as rack photos and cabling diagrams.
+-- Attaching a file under a name already in use adds a version of it.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS node_attachments (
+    id TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    content_type TEXT,
+    size_bytes INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    compression TEXT NOT NULL,
+    stored_bytes INTEGER NOT NULL,
+    uploaded_by TEXT,
+    created_at TEXT NOT NULL,
+    UNIQUE (node_id, file_name, version),
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
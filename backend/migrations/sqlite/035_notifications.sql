@@ -0,0 +1,62 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (035): Alert notifications
+
+-- ============================================================================
+-- Notification Channels Table
+-- Destinations of alert notifications: email recipients, a generic webhook,
+-- or a Slack-compatible webhook. Settings hold the kind's JSON fields.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS notification_channels (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    kind TEXT NOT NULL,
+    settings TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_by TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- ============================================================================
+-- Notification Routes Table
+-- Which alerts go to which channel, by alert rule, node, and severity.
+-- Alert rules live in memory, so rule IDs are not foreign keys.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS notification_routes (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    channel_id TEXT NOT NULL,
+    rule_id TEXT,
+    node_id TEXT,
+    min_severity TEXT NOT NULL,
+    notify_resolved INTEGER NOT NULL DEFAULT 1,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (channel_id) REFERENCES notification_channels(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_routes_channel_id ON notification_routes(channel_id);
+
+-- ============================================================================
+-- Notification Deliveries Table
+-- Delivery log with one entry per alert, channel, and event; failed
+-- deliveries are retried and update their entry.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS notification_deliveries (
+    id TEXT PRIMARY KEY,
+    channel_id TEXT NOT NULL,
+    route_id TEXT,
+    alert_id TEXT,
+    event TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 1,
+    error TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (channel_id) REFERENCES notification_channels(id) ON DELETE CASCADE,
+    FOREIGN KEY (route_id) REFERENCES notification_routes(id) ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notification_deliveries_alert ON notification_deliveries(alert_id, channel_id, event);
+CREATE INDEX IF NOT EXISTS idx_notification_deliveries_created_at ON notification_deliveries(created_at);
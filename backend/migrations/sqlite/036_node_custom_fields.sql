@@ -0,0 +1,37 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (036): Node inventory custom fields
+
+-- ============================================================================
+-- Node Custom Fields Table
+-- Admin-defined fields of the node inventory, such as a site code, circuit
+-- ID, or asset tag. Options hold the JSON array of choices of select fields.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS node_custom_fields (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    label TEXT NOT NULL,
+    field_type TEXT NOT NULL,
+    required INTEGER NOT NULL DEFAULT 0,
+    options TEXT NOT NULL DEFAULT '[]',
+    pattern TEXT,
+    description TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- ============================================================================
+-- Node Custom Field Values Table
+-- Value of a custom field on a node, as text in the canonical form of the
+-- field's type so that equal values compare equal.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS node_custom_field_values (
+    node_id TEXT NOT NULL,
+    field_id TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (node_id, field_id),
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE,
+    FOREIGN KEY (field_id) REFERENCES node_custom_fields(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_node_custom_field_values_field ON node_custom_field_values(field_id, value);
@@ -0,0 +1,26 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (037): Read-only API tokens
+
+-- ============================================================================
+-- API Tokens Table
+-- Expiring read-only tokens for wallboards, acting for the user who created
+-- them on the GET endpoints of their scopes. Only the SHA-256 of a token is
+-- kept; the prefix identifies it in listings. Node IDs, when set, hold the
+-- JSON array of the only nodes the token may read.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    token_prefix TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    scopes TEXT NOT NULL,
+    node_ids TEXT,
+    expires_at TEXT NOT NULL,
+    last_used_at TEXT,
+    revoked_at TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user_id ON api_tokens(user_id);
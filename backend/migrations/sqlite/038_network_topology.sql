@@ -0,0 +1,36 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (038): Network topology links and positions
+
+-- ============================================================================
+-- Topology Links Table
+-- Links entered by hand, for connections discovery cannot see such as
+-- carrier circuits. Endpoints are node IDs or the IDs of discovered devices,
+-- e.g. `lldp:core-sw1`.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS topology_links (
+    id TEXT PRIMARY KEY,
+    source TEXT NOT NULL,
+    target TEXT NOT NULL,
+    link_type TEXT NOT NULL,
+    source_interface TEXT,
+    target_interface TEXT,
+    bandwidth_mbps INTEGER,
+    description TEXT,
+    created_by TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_topology_links_source ON topology_links(source);
+CREATE INDEX IF NOT EXISTS idx_topology_links_target ON topology_links(target);
+
+-- ============================================================================
+-- Topology Positions Table
+-- Where each topology node was placed in the map, by node or device ID.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS topology_positions (
+    node_key TEXT PRIMARY KEY,
+    x REAL NOT NULL,
+    y REAL NOT NULL,
+    updated_at TEXT NOT NULL
+);
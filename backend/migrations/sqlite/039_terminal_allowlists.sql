@@ -0,0 +1,16 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (039): Terminal command allowlists
+
+-- ============================================================================
+-- Terminal Allowlists Table
+-- Operational commands a user may run in WebSocket terminal sessions, as a
+-- JSON array of command prefixes. Users without an entry may not open a
+-- session; administrators are not restricted.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS terminal_allowlists (
+    user_id TEXT PRIMARY KEY NOT NULL,
+    commands TEXT NOT NULL,
+    updated_by TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
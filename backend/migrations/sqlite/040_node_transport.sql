@@ -0,0 +1,13 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (040): Node transports
+
+-- ============================================================================
+-- Node Transport
+-- 'api' nodes are reached over the REST API; 'ssh' nodes are driven through
+-- the VyOS CLI over SSH, with a password or a private key
+-- ============================================================================
+ALTER TABLE nodes ADD COLUMN transport TEXT NOT NULL DEFAULT 'api';
+ALTER TABLE nodes ADD COLUMN ssh_port INTEGER;
+ALTER TABLE nodes ADD COLUMN ssh_username TEXT;
+ALTER TABLE nodes ADD COLUMN ssh_password TEXT;
+ALTER TABLE nodes ADD COLUMN ssh_private_key TEXT;
@@ -0,0 +1,14 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (041): Scheduled commits
+
+-- ============================================================================
+-- Scheduled Commits
+-- A 'scheduled' session is committed by the scheduler at `apply_at`. `diff`
+-- holds the effect of each operation on the configuration when the commit
+-- was scheduled, and `restore` what the operations would replace, so a
+-- change made to those paths in the meantime stops the commit.
+-- ============================================================================
+ALTER TABLE config_sessions ADD COLUMN apply_at TEXT;
+ALTER TABLE config_sessions ADD COLUMN diff TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_config_sessions_apply_at ON config_sessions(status, apply_at);
@@ -0,0 +1,40 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (042): VRRP states
+
+-- ============================================================================
+-- VRRP Group States Table
+-- State of each VRRP group of a node as last read, which changes of state
+-- are detected against. Nodes reporting a group of the same name and VRID
+-- are peers of one HA pair.
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS vrrp_group_states (
+    node_id TEXT NOT NULL,
+    group_name TEXT NOT NULL,
+    vrid INTEGER NOT NULL,
+    state TEXT NOT NULL,
+    priority INTEGER NOT NULL,
+    checked_at TEXT NOT NULL,
+    PRIMARY KEY (node_id, group_name),
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_vrrp_group_states_group ON vrrp_group_states(group_name, vrid);
+
+-- ============================================================================
+-- VRRP Transitions Table
+-- Changes of state of the VRRP groups of a node, newest kept
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS vrrp_transitions (
+    id TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    group_name TEXT NOT NULL,
+    vrid INTEGER NOT NULL,
+    from_state TEXT NOT NULL,
+    to_state TEXT NOT NULL,
+    priority INTEGER NOT NULL,
+    unexpected INTEGER NOT NULL DEFAULT 0,
+    occurred_at TEXT NOT NULL,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_vrrp_transitions_node_id ON vrrp_transitions(node_id, occurred_at);
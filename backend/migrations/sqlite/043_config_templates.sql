@@ -0,0 +1,19 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (043): Configuration templates
+
+-- ============================================================================
+-- Configuration Templates Table
+-- Reusable configuration blueprints: a Handlebars body rendering to set and
+-- delete commands, and the variables it takes as JSON
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS config_templates (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    body TEXT NOT NULL,
+    variables TEXT NOT NULL,
+    created_by TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
@@ -0,0 +1,18 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (044): Configuration baselines
+
+-- ============================================================================
+-- Config Baselines Table
+-- Stored snapshot each node's running configuration is expected to match,
+-- with the outcome of the last drift check against it
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS config_baselines (
+    node_id TEXT PRIMARY KEY,
+    snapshot_id TEXT NOT NULL,
+    alert_on_drift INTEGER NOT NULL DEFAULT 1,
+    set_by TEXT NOT NULL,
+    set_at TEXT NOT NULL,
+    drifted INTEGER,
+    checked_at TEXT,
+    FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,18 @@
+-- VyOS Web UI Database Schema
+-- SQLite Migration (045): User role assignments
+
+-- ============================================================================
+-- Built-in Roles of Existing Users
+-- Every user holds one of the built-in roles (admin, operator, viewer) in
+-- user_roles. Users created before roles were assigned get admin if they are
+-- superusers, viewer otherwise.
+-- ============================================================================
+INSERT INTO user_roles (user_id, role_id)
+SELECT u.id, r.id
+FROM users u
+JOIN roles r ON r.name = CASE WHEN u.is_superuser = 1 THEN 'admin' ELSE 'viewer' END
+WHERE NOT EXISTS (
+    SELECT 1 FROM user_roles ur
+    JOIN roles br ON br.id = ur.role_id
+    WHERE ur.user_id = u.id AND br.name IN ('admin', 'operator', 'viewer')
+);
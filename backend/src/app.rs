@@ -0,0 +1,743 @@
+//! Application Factory
+//!
+//! This module wires the services and HTTP routes of the backend together so
+//! the server binary and integration tests build the same application.
+
+use actix_web::web;
+
+use crate::config::{AppConfig, ConfigReloader};
+use crate::db::Database;
+use crate::handlers;
+use crate::models::client_error::CLIENT_ERROR_MAX_BYTES;
+use crate::middleware::auth::OptionalAuthMiddleware;
+use crate::middleware::health::HealthTrackingMiddleware;
+use crate::middleware::legacy_user_id::LegacyUserIdMiddleware;
+use crate::middleware::rate_limit::RateLimitMiddleware;
+use crate::middleware::request_id::RequestIdMiddleware;
+use crate::services::{AnnouncementService, ApiTokenService, AuditService, AuthService, BackendHealthService, BackupService, BaselineService, CertificateService, ChannelAccessService, ChaosService, ClientErrorService, ConfigDriftService, ConfigLockService, ConfigService, ConfigSessionService, ConfigTemplateService, CustomFieldService, DesiredStateService, EventBus, FirewallLogService, FirewallService, GeoIpService, HaService, IncidentService, IpamService, Ipv6Service, LeaderElection, LoadProfileService, LoginHistoryService, MacVendorService, Mailer, MetricsCollector, MonitoringService, NetworkService, NodeCircuitBreaker, NodeFileService, NodeHealthChecker, NodeService, NotificationService, PermissionService, QuotaService, RateLimiter, ReportService, RouterLoginService, RoutingService, StatusPageService, StorageUsageService, SubnetService, SupportBundleService, SystemService, TeamService, TerminalService, TopologyService, UploadService, UserService, VpnMeshService, WizardService};
+use crate::websocket::{self, ConnectionManager};
+
+/// Shared application state
+///
+/// Holds one instance of every service. Services are cheap to clone, so the
+/// state is cloned into each worker and registered as app data there.
+#[derive(Clone)]
+pub struct AppState {
+    pub config: AppConfig,
+    pub config_reloader: ConfigReloader,
+    pub db: web::Data<Database>,
+    pub auth_service: AuthService,
+    pub backend_health_service: BackendHealthService,
+    pub backup_service: BackupService,
+    pub channel_access_service: ChannelAccessService,
+    pub chaos_service: ChaosService,
+    pub load_profile_service: LoadProfileService,
+    pub login_history_service: LoginHistoryService,
+    pub announcement_service: AnnouncementService,
+    pub api_token_service: ApiTokenService,
+    pub audit_service: AuditService,
+    pub client_error_service: ClientErrorService,
+    pub rate_limiter: RateLimiter,
+    pub user_service: UserService,
+    pub permission_service: PermissionService,
+    pub config_service: ConfigService,
+    pub config_lock_service: ConfigLockService,
+    pub config_session_service: ConfigSessionService,
+    pub desired_state_service: DesiredStateService,
+    pub ipv6_service: Ipv6Service,
+    pub firewall_service: FirewallService,
+    pub network_service: NetworkService,
+    pub routing_service: RoutingService,
+    pub router_login_service: RouterLoginService,
+    pub baseline_service: BaselineService,
+    pub config_template_service: ConfigTemplateService,
+    pub config_drift_service: ConfigDriftService,
+    pub system_service: SystemService,
+    pub monitoring_service: MonitoringService,
+    pub incident_service: IncidentService,
+    pub quota_service: QuotaService,
+    pub status_page_service: StatusPageService,
+    pub storage_usage_service: StorageUsageService,
+    pub report_service: ReportService,
+    pub support_bundle_service: SupportBundleService,
+    pub node_service: NodeService,
+    pub custom_field_service: CustomFieldService,
+    pub node_health_checker: NodeHealthChecker,
+    pub metrics_collector: MetricsCollector,
+    pub certificate_service: CertificateService,
+    pub ha_service: HaService,
+    pub ipam_service: IpamService,
+    pub subnet_service: SubnetService,
+    pub topology_service: TopologyService,
+    pub mac_vendor_service: MacVendorService,
+    pub geoip_service: GeoIpService,
+    pub firewall_log_service: FirewallLogService,
+    pub team_service: TeamService,
+    pub terminal_service: TerminalService,
+    pub upload_service: UploadService,
+    pub node_file_service: NodeFileService,
+    pub notification_service: NotificationService,
+    pub vpn_mesh_service: VpnMeshService,
+    pub wizard_service: WizardService,
+    pub connection_manager: ConnectionManager,
+    pub event_bus: EventBus,
+    pub leader_election: LeaderElection,
+}
+
+impl AppState {
+    /// Create the services on top of an initialized database
+    pub fn new(config: AppConfig, db: web::Data<Database>) -> Self {
+        db.query_metrics()
+            .set_slow_threshold(std::time::Duration::from_millis(config.database_slow_query_ms));
+        let db_clone = db.get_ref().clone();
+        let config_reloader = ConfigReloader::new(config.clone());
+        let auth_service = AuthService::new(&config, db_clone.clone());
+        let storage_usage_service = StorageUsageService::new(&config, db_clone.clone());
+        let backup_service = BackupService::new(&config, db_clone.clone(), storage_usage_service.clone());
+        let chaos_service = ChaosService::new(&config);
+        let load_profile_service = LoadProfileService::new(db_clone.clone(), &config);
+        let connection_manager = ConnectionManager::with_queue_capacity(config.websocket_queue_capacity);
+        let event_bus = EventBus::new(db_clone.clone(), connection_manager.clone());
+        let announcement_service = AnnouncementService::new(db_clone.clone(), event_bus.clone());
+        let api_token_service = ApiTokenService::new(db_clone.clone());
+        let audit_service = AuditService::new(db_clone.clone());
+        let client_error_service = ClientErrorService::new(&config);
+        let rate_limiter = RateLimiter::new(&config);
+        let mailer = Mailer::new(&config);
+        let user_service = UserService::new(db_clone.clone(), mailer.clone());
+        let permission_service = PermissionService::new(db_clone.clone());
+        let config_lock_service = ConfigLockService::new(db_clone.clone());
+        let system_service = SystemService::new(config.clone());
+        let monitoring_service = MonitoringService::new(config.clone());
+        let incident_service = IncidentService::new(db_clone.clone(), monitoring_service.clone(), event_bus.clone());
+        let quota_service = QuotaService::new(db_clone.clone(), &config);
+        let status_page_service = StatusPageService::new(db_clone.clone(), monitoring_service.clone());
+        let custom_field_service = CustomFieldService::new(db_clone.clone());
+        let node_service = NodeService::new(
+            db_clone.clone(),
+            quota_service.clone(),
+            custom_field_service.clone(),
+            NodeCircuitBreaker::new(&config),
+            &config,
+        );
+        let node_health_checker =
+            NodeHealthChecker::new(node_service.clone(), event_bus.clone(), monitoring_service.clone(), &config);
+        let metrics_collector =
+            MetricsCollector::new(node_service.clone(), monitoring_service.clone(), connection_manager.clone());
+        let certificate_service =
+            CertificateService::new(db_clone.clone(), node_service.clone(), monitoring_service.clone());
+        let ha_service = HaService::new(db_clone.clone(), node_service.clone(), monitoring_service.clone());
+        let report_service = ReportService::new(
+            db_clone.clone(),
+            monitoring_service.clone(),
+            node_service.clone(),
+            custom_field_service.clone(),
+            mailer.clone(),
+        );
+        let backend_health_service = BackendHealthService::new(monitoring_service.clone(), report_service.clone());
+        let support_bundle_service =
+            SupportBundleService::new(config.clone(), db_clone.clone(), node_service.clone(), audit_service.clone());
+        let upload_service =
+            UploadService::new(&config, db_clone.clone(), node_service.clone(), storage_usage_service.clone());
+        let node_file_service = NodeFileService::new(
+            &config,
+            db_clone.clone(),
+            permission_service.clone(),
+            storage_usage_service.clone(),
+        );
+        let ipam_service = IpamService::new(node_service.clone());
+        let subnet_service = SubnetService::new(ipam_service.clone());
+        let topology_service = TopologyService::new(db_clone.clone(), node_service.clone(), permission_service.clone());
+        let mac_vendor_service = MacVendorService::new(&config, db_clone.clone());
+        let geoip_service = GeoIpService::new(&config);
+        let login_history_service = LoginHistoryService::new(db_clone.clone(), geoip_service.clone(), mailer.clone());
+        let firewall_log_service = FirewallLogService::new(db_clone.clone(), node_service.clone(), geoip_service.clone());
+        let config_session_service = ConfigSessionService::new(
+            db_clone.clone(),
+            node_service.clone(),
+            config_lock_service.clone(),
+            audit_service.clone(),
+            config_reloader.clone(),
+        );
+        let desired_state_service = DesiredStateService::new(
+            node_service.clone(),
+            config_lock_service.clone(),
+            config_session_service.clone(),
+        );
+        let ipv6_service = Ipv6Service::new(
+            node_service.clone(),
+            config_lock_service.clone(),
+            config_session_service.clone(),
+        );
+        let firewall_service = FirewallService::new(
+            node_service.clone(),
+            config_lock_service.clone(),
+            config_session_service.clone(),
+        );
+        let network_service = NetworkService::new(
+            node_service.clone(),
+            config_lock_service.clone(),
+            config_session_service.clone(),
+        );
+        let routing_service = RoutingService::new(
+            node_service.clone(),
+            config_lock_service.clone(),
+            config_session_service.clone(),
+        );
+        let config_service = ConfigService::new(
+            db_clone.clone(),
+            config.clone(),
+            config_lock_service.clone(),
+            node_service.clone(),
+            config_session_service.clone(),
+            desired_state_service.clone(),
+            permission_service.clone(),
+        );
+        let leader_election = LeaderElection::new(&config, db_clone.clone());
+        let team_service = TeamService::new(db_clone.clone());
+        let notification_service = NotificationService::new(
+            db_clone.clone(),
+            monitoring_service.clone(),
+            node_service.clone(),
+            team_service.clone(),
+            mailer.clone(),
+        );
+        let router_login_service = RouterLoginService::new(
+            node_service.clone(),
+            team_service.clone(),
+            config_lock_service.clone(),
+            config_session_service.clone(),
+        );
+        let baseline_service = BaselineService::new(
+            node_service.clone(),
+            team_service.clone(),
+            config_lock_service.clone(),
+            config_session_service.clone(),
+        );
+        let config_template_service = ConfigTemplateService::new(
+            db_clone.clone(),
+            node_service.clone(),
+            team_service.clone(),
+            config_lock_service.clone(),
+            config_session_service.clone(),
+        );
+        let config_drift_service = ConfigDriftService::new(
+            db_clone.clone(),
+            config_service.clone(),
+            node_service.clone(),
+            monitoring_service.clone(),
+        );
+        let channel_access_service = ChannelAccessService::new(node_service.clone(), team_service.clone());
+        let terminal_service = TerminalService::new(
+            db_clone.clone(),
+            node_service.clone(),
+            team_service.clone(),
+            audit_service.clone(),
+        );
+        let vpn_mesh_service = VpnMeshService::new(
+            db_clone.clone(),
+            node_service.clone(),
+            team_service.clone(),
+            config_session_service.clone(),
+        );
+        let wizard_service = WizardService::new(
+            db_clone,
+            node_service.clone(),
+            team_service.clone(),
+            config_session_service.clone(),
+        );
+
+        Self {
+            config,
+            config_reloader,
+            db,
+            auth_service,
+            backend_health_service,
+            backup_service,
+            channel_access_service,
+            chaos_service,
+            load_profile_service,
+            login_history_service,
+            announcement_service,
+            api_token_service,
+            audit_service,
+            client_error_service,
+            rate_limiter,
+            user_service,
+            permission_service,
+            config_service,
+            config_lock_service,
+            config_session_service,
+            desired_state_service,
+            ipv6_service,
+            firewall_service,
+            network_service,
+            routing_service,
+            router_login_service,
+            baseline_service,
+            config_template_service,
+            config_drift_service,
+            system_service,
+            monitoring_service,
+            incident_service,
+            quota_service,
+            status_page_service,
+            storage_usage_service,
+            report_service,
+            support_bundle_service,
+            node_service,
+            custom_field_service,
+            node_health_checker,
+            metrics_collector,
+            certificate_service,
+            ha_service,
+            ipam_service,
+            subnet_service,
+            topology_service,
+            mac_vendor_service,
+            geoip_service,
+            firewall_log_service,
+            team_service,
+            terminal_service,
+            upload_service,
+            node_file_service,
+            notification_service,
+            vpn_mesh_service,
+            wizard_service,
+            connection_manager,
+            event_bus,
+            leader_election,
+        }
+    }
+
+    /// Register the services as app data, limit request bodies to the
+    /// configured size, and mount all routes
+    ///
+    /// Use with `App::new().configure(|cfg| state.configure(cfg))`. App-wide
+    /// middleware such as locale negotiation, CORS, and logging is left to the
+    /// caller.
+    pub fn configure(&self, cfg: &mut web::ServiceConfig) {
+        cfg.app_data(web::JsonConfig::default().limit(self.config.max_payload_bytes))
+            .app_data(web::PayloadConfig::new(self.config.max_payload_bytes))
+            .app_data(web::Data::new(self.config.clone()))
+            .app_data(web::Data::new(self.config_reloader.clone()))
+            .app_data(self.db.clone())
+            .app_data(web::Data::new(self.auth_service.clone()))
+            .app_data(web::Data::new(self.backend_health_service.clone()))
+            .app_data(web::Data::new(self.backup_service.clone()))
+            .app_data(web::Data::new(self.channel_access_service.clone()))
+            .app_data(web::Data::new(self.chaos_service.clone()))
+            .app_data(web::Data::new(self.load_profile_service.clone()))
+            .app_data(web::Data::new(self.login_history_service.clone()))
+            .app_data(web::Data::new(self.announcement_service.clone()))
+            .app_data(web::Data::new(self.api_token_service.clone()))
+            .app_data(web::Data::new(self.audit_service.clone()))
+            .app_data(web::Data::new(self.client_error_service.clone()))
+            .app_data(web::Data::new(self.rate_limiter.clone()))
+            .app_data(web::Data::new(self.user_service.clone()))
+            .app_data(web::Data::new(self.permission_service.clone()))
+            .app_data(web::Data::new(self.config_service.clone()))
+            .app_data(web::Data::new(self.config_lock_service.clone()))
+            .app_data(web::Data::new(self.config_session_service.clone()))
+            .app_data(web::Data::new(self.desired_state_service.clone()))
+            .app_data(web::Data::new(self.ipv6_service.clone()))
+            .app_data(web::Data::new(self.firewall_service.clone()))
+            .app_data(web::Data::new(self.network_service.clone()))
+            .app_data(web::Data::new(self.routing_service.clone()))
+            .app_data(web::Data::new(self.router_login_service.clone()))
+            .app_data(web::Data::new(self.baseline_service.clone()))
+            .app_data(web::Data::new(self.config_template_service.clone()))
+            .app_data(web::Data::new(self.config_drift_service.clone()))
+            .app_data(web::Data::new(self.system_service.clone()))
+            .app_data(web::Data::new(self.monitoring_service.clone()))
+            .app_data(web::Data::new(self.certificate_service.clone()))
+            .app_data(web::Data::new(self.ha_service.clone()))
+            .app_data(web::Data::new(self.incident_service.clone()))
+            .app_data(web::Data::new(self.node_service.clone()))
+            .app_data(web::Data::new(self.custom_field_service.clone()))
+            .app_data(web::Data::new(self.ipam_service.clone()))
+            .app_data(web::Data::new(self.subnet_service.clone()))
+            .app_data(web::Data::new(self.topology_service.clone()))
+            .app_data(web::Data::new(self.mac_vendor_service.clone()))
+            .app_data(web::Data::new(self.geoip_service.clone()))
+            .app_data(web::Data::new(self.firewall_log_service.clone()))
+            .app_data(web::Data::new(self.team_service.clone()))
+            .app_data(web::Data::new(self.terminal_service.clone()))
+            .app_data(web::Data::new(self.quota_service.clone()))
+            .app_data(web::Data::new(self.status_page_service.clone()))
+            .app_data(web::Data::new(self.storage_usage_service.clone()))
+            .app_data(web::Data::new(self.report_service.clone()))
+            .app_data(web::Data::new(self.support_bundle_service.clone()))
+            .app_data(web::Data::new(self.upload_service.clone()))
+            .app_data(web::Data::new(self.node_file_service.clone()))
+            .app_data(web::Data::new(self.notification_service.clone()))
+            .app_data(web::Data::new(self.vpn_mesh_service.clone()))
+            .app_data(web::Data::new(self.wizard_service.clone()))
+            .app_data(web::Data::new(self.connection_manager.clone()))
+            .app_data(web::Data::new(self.event_bus.clone()))
+            .app_data(web::Data::new(self.leader_election.clone()));
+
+        configure_routes(cfg);
+    }
+}
+
+/// JSON body limit of the authentication endpoints, which only receive
+/// credentials and tokens
+pub const AUTH_JSON_LIMIT: usize = 16 * 1024;
+
+/// Mount all HTTP and WebSocket routes
+///
+/// JSON bodies are limited to the configured maximum payload size, except
+/// for the route groups that set a tighter limit. Uploads are streamed and
+/// limited per kind by the [`UploadService`], node attachments by the
+/// [`NodeFileService`].
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api")
+            .wrap(LegacyUserIdMiddleware)
+            .wrap(RateLimitMiddleware)
+            .wrap(OptionalAuthMiddleware)
+            .wrap(HealthTrackingMiddleware)
+            .wrap(RequestIdMiddleware)
+            // Health check endpoints
+            .route("/health", web::get().to(handlers::health::health_check))
+            .route("/health/detailed", web::get().to(handlers::health::detailed_health_check))
+            .route("/metrics", web::get().to(handlers::health::prometheus_metrics))
+            // Load test targets
+            .service(
+                web::scope("/load-profile")
+                    .route("/ping", web::get().to(handlers::load_profile::profile_ping))
+                    .route("/config-tree", web::get().to(handlers::load_profile::profile_config_tree))
+                    .route("/config-diff", web::get().to(handlers::load_profile::profile_config_diff))
+                    .route("/metrics", web::get().to(handlers::load_profile::profile_metrics))
+                    .route("/database", web::get().to(handlers::load_profile::profile_database)),
+            )
+            // Authentication endpoints
+            .service(
+                web::scope("/auth")
+                    .app_data(web::JsonConfig::default().limit(AUTH_JSON_LIMIT))
+                    .route("/register", web::post().to(handlers::auth::register))
+                    .route("/login", web::post().to(handlers::auth::login))
+                    .route("/logout", web::post().to(handlers::auth::logout))
+                    .route("/refresh", web::post().to(handlers::auth::refresh_token))
+                    .route("/sessions", web::get().to(handlers::auth::list_sessions))
+                    .route("/sessions", web::delete().to(handlers::auth::revoke_all_sessions))
+                    .route("/sessions/{id}", web::delete().to(handlers::auth::revoke_session))
+                    .route("/validate", web::post().to(handlers::auth::validate_token))
+                    .route("/me", web::get().to(handlers::auth::get_current_user)),
+            )
+            // User endpoints
+            .route("/users/me", web::get().to(handlers::user::get_profile))
+            .route("/users/me", web::put().to(handlers::user::update_profile))
+            .route("/users/me/password", web::post().to(handlers::user::change_password))
+            .route("/users/me/email", web::post().to(handlers::user::request_email_change))
+            .route("/users/me/logins", web::get().to(handlers::user::list_my_logins))
+            .route("/users/email/confirm", web::post().to(handlers::user::confirm_email_change))
+            .route("/users", web::get().to(handlers::user::list_users))
+            .route("/users", web::post().to(handlers::user::create_user))
+            .route("/users/{id}", web::put().to(handlers::user::update_user))
+            .route("/users/{id}", web::delete().to(handlers::user::delete_user))
+            .route("/users/{id}/permissions", web::get().to(handlers::user::get_user_permissions))
+            .route("/users/{id}/permissions", web::put().to(handlers::user::set_user_permissions))
+            .route("/users/{id}/terminal-allowlist", web::get().to(handlers::user::get_terminal_allowlist))
+            .route("/users/{id}/terminal-allowlist", web::put().to(handlers::user::set_terminal_allowlist))
+            .route("/users/bulk/import", web::post().to(handlers::user_bulk::bulk_import_users))
+            .route("/users/bulk/roles", web::post().to(handlers::user_bulk::bulk_set_user_role))
+            .route("/users/bulk/teams", web::post().to(handlers::user_bulk::bulk_assign_user_team))
+            .route("/users/bulk/deactivate", web::post().to(handlers::user_bulk::bulk_deactivate_users))
+            .route("/users/bulk/password-reset", web::post().to(handlers::user_bulk::bulk_require_password_reset))
+            // API token endpoints
+            .route("/api-tokens", web::get().to(handlers::api_token::list_api_tokens))
+            .route("/api-tokens", web::post().to(handlers::api_token::create_api_token))
+            .route("/api-tokens/{id}", web::delete().to(handlers::api_token::revoke_api_token))
+            // Team endpoints
+            .route("/teams", web::get().to(handlers::team::list_teams))
+            .route("/teams", web::post().to(handlers::team::create_team))
+            .route("/teams/{id}", web::get().to(handlers::team::get_team))
+            .route("/teams/{id}", web::put().to(handlers::team::update_team))
+            .route("/teams/{id}", web::delete().to(handlers::team::delete_team))
+            .route("/teams/{id}/members", web::post().to(handlers::team::add_team_member))
+            .route("/teams/{id}/members/{user_id}", web::delete().to(handlers::team::remove_team_member))
+            .route("/teams/{id}/quota", web::get().to(handlers::team::get_team_quota))
+            .route("/teams/{id}/quota", web::put().to(handlers::team::update_team_quota))
+            .route("/teams/{id}/usage", web::get().to(handlers::team::get_team_usage))
+            // Node endpoints
+            .route("/nodes", web::get().to(handlers::node::list_nodes))
+            .route("/nodes", web::post().to(handlers::node::create_node))
+            .route("/nodes/simulated", web::post().to(handlers::node::create_simulated_node))
+            .route("/nodes/export", web::get().to(handlers::node::export_nodes))
+            .route("/nodes/custom-fields", web::get().to(handlers::custom_field::list_custom_fields))
+            .route("/nodes/custom-fields", web::post().to(handlers::custom_field::create_custom_field))
+            .route("/nodes/custom-fields/{id}", web::get().to(handlers::custom_field::get_custom_field))
+            .route("/nodes/custom-fields/{id}", web::put().to(handlers::custom_field::update_custom_field))
+            .route("/nodes/custom-fields/{id}", web::delete().to(handlers::custom_field::delete_custom_field))
+            .route("/nodes/stats", web::get().to(handlers::node::get_node_statistics))
+            .route("/nodes/health/all", web::get().to(handlers::node::get_all_nodes_health))
+            .route("/nodes/health/check-all", web::post().to(handlers::node::check_all_nodes_health))
+            .route("/nodes/{id}", web::get().to(handlers::node::get_node))
+            .route("/nodes/{id}", web::put().to(handlers::node::update_node))
+            .route("/nodes/{id}", web::delete().to(handlers::node::delete_node))
+            .route("/nodes/{id}/owner", web::put().to(handlers::node::set_node_owner))
+            .route("/nodes/{id}/test", web::post().to(handlers::node::test_connection))
+            .route("/nodes/{id}/reboot", web::get().to(handlers::node::get_node_reboot))
+            .route("/nodes/{id}/reboot", web::post().to(handlers::node::reboot_node))
+            .route("/nodes/{id}/health", web::get().to(handlers::node::get_node_health))
+            .route("/nodes/{id}/config", web::post().to(handlers::node::retrieve_node_config))
+            .route("/nodes/{id}/config/desired", web::post().to(handlers::desired_state::apply_desired_state))
+            .route("/nodes/{id}/backups", web::get().to(handlers::config_backup::list_config_backups))
+            .route("/nodes/{id}/backups", web::post().to(handlers::config_backup::create_config_backup))
+            .route("/nodes/{id}/backups/{backup_id}", web::get().to(handlers::config_backup::download_config_backup))
+            .route(
+                "/nodes/{id}/backups/{backup_id}/restore",
+                web::post().to(handlers::config_backup::restore_config_backup),
+            )
+            .route("/nodes/{id}/info", web::get().to(handlers::node::get_node_info))
+            .route("/nodes/{id}/api-usage", web::get().to(handlers::node::get_node_api_usage))
+            .route("/nodes/{id}/capabilities", web::get().to(handlers::node::get_node_capabilities))
+            .route("/nodes/{id}/capabilities", web::post().to(handlers::node::probe_node_capabilities))
+            .route("/nodes/{id}/interfaces", web::get().to(handlers::node::get_node_interfaces))
+            .route("/nodes/{id}/interfaces/{kind}/{name}/config", web::get().to(handlers::network::get_interface_config))
+            .route("/nodes/{id}/interfaces/{kind}/{name}/config", web::put().to(handlers::network::configure_interface))
+            .route("/nodes/{id}/interfaces/{kind}/{name}/status", web::get().to(handlers::network::get_interface_status))
+            .route(
+                "/nodes/{id}/interfaces/{name}/bandwidth-alert",
+                web::post().to(handlers::monitoring::create_interface_bandwidth_alert),
+            )
+            .route("/nodes/{id}/network/routes", web::get().to(handlers::network::get_static_routes))
+            .route("/nodes/{id}/network/routes", web::post().to(handlers::network::set_static_route))
+            .route("/nodes/{id}/network/routes", web::delete().to(handlers::network::delete_static_route))
+            .route("/nodes/{id}/network/policy-routes", web::get().to(handlers::network::get_policy_routes))
+            .route("/nodes/{id}/network/policy-routes/{family}/{name}", web::put().to(handlers::network::set_policy_route))
+            .route("/nodes/{id}/network/policy-routes/{family}/{name}", web::delete().to(handlers::network::delete_policy_route))
+            .route("/nodes/{id}/login/users", web::get().to(handlers::router_login::get_login_users))
+            .route("/nodes/{id}/login/users/{name}", web::put().to(handlers::router_login::set_login_user))
+            .route("/nodes/{id}/login/users/{name}", web::delete().to(handlers::router_login::delete_login_user))
+            .route("/login/keys/rotate", web::post().to(handlers::router_login::rotate_login_key))
+            .route("/login/drift", web::post().to(handlers::router_login::get_login_drift))
+            .route("/nodes/{id}/baseline", web::get().to(handlers::baseline::get_baseline))
+            .route("/nodes/{id}/baseline", web::put().to(handlers::baseline::set_baseline))
+            .route("/baseline/push", web::post().to(handlers::baseline::push_baseline))
+            .route("/templates", web::get().to(handlers::config_template::list_templates))
+            .route("/templates", web::post().to(handlers::config_template::create_template))
+            .route("/templates/{id}", web::get().to(handlers::config_template::get_template))
+            .route("/templates/{id}", web::put().to(handlers::config_template::update_template))
+            .route("/templates/{id}", web::delete().to(handlers::config_template::delete_template))
+            .route("/templates/{id}/render", web::post().to(handlers::config_template::render_template))
+            .route("/templates/{id}/apply", web::post().to(handlers::config_template::apply_template))
+            .route("/nodes/{id}/drift", web::get().to(handlers::config_drift::get_config_drift))
+            .route("/nodes/{id}/drift/baseline", web::get().to(handlers::config_drift::get_config_baseline))
+            .route("/nodes/{id}/drift/baseline", web::put().to(handlers::config_drift::set_config_baseline))
+            .route("/nodes/{id}/drift/baseline", web::delete().to(handlers::config_drift::clear_config_baseline))
+            .route("/nodes/{id}/ha", web::get().to(handlers::ha::get_ha_status))
+            .route("/nodes/{id}/ha/transitions", web::get().to(handlers::ha::get_vrrp_transitions))
+            .route("/nodes/{id}/show", web::post().to(handlers::node::execute_show_command))
+            .route("/nodes/{id}/recording", web::get().to(handlers::node::get_node_recording))
+            .route("/nodes/{id}/notes", web::get().to(handlers::node_file::get_node_note))
+            .route("/nodes/{id}/notes", web::put().to(handlers::node_file::update_node_note))
+            .route("/nodes/{id}/notes/versions", web::get().to(handlers::node_file::list_node_note_versions))
+            .route("/nodes/{id}/notes/versions/{version}", web::get().to(handlers::node_file::get_node_note_version))
+            .route("/nodes/{id}/attachments", web::get().to(handlers::node_file::list_node_attachments))
+            .route("/nodes/{id}/attachments", web::post().to(handlers::node_file::create_node_attachment))
+            .route("/nodes/{id}/attachments/{attachment_id}", web::get().to(handlers::node_file::get_node_attachment))
+            .route(
+                "/nodes/{id}/attachments/{attachment_id}",
+                web::delete().to(handlers::node_file::delete_node_attachment),
+            )
+            .route(
+                "/nodes/{id}/attachments/{attachment_id}/download",
+                web::get().to(handlers::node_file::download_node_attachment),
+            )
+            .route("/nodes/{id}/ipv6/router-advert", web::get().to(handlers::ipv6::get_router_adverts))
+            .route("/nodes/{id}/ipv6/router-advert/{interface}", web::put().to(handlers::ipv6::set_router_advert))
+            .route("/nodes/{id}/ipv6/router-advert/{interface}", web::delete().to(handlers::ipv6::delete_router_advert))
+            .route("/nodes/{id}/ipv6/dhcpv6-server", web::get().to(handlers::ipv6::get_dhcpv6_networks))
+            .route("/nodes/{id}/ipv6/dhcpv6-server/{name}", web::put().to(handlers::ipv6::set_dhcpv6_network))
+            .route("/nodes/{id}/ipv6/dhcpv6-server/{name}", web::delete().to(handlers::ipv6::delete_dhcpv6_network))
+            .route("/nodes/{id}/ipv6/neighbors", web::get().to(handlers::ipv6::get_ipv6_neighbors))
+            .route("/nodes/{id}/ipv6/prefixes", web::get().to(handlers::ipv6::get_ipv6_prefixes))
+            .route("/nodes/{id}/firewall", web::get().to(handlers::firewall::get_firewall))
+            .route("/nodes/{id}/firewall/{family}/rule-sets/{name}", web::get().to(handlers::firewall::get_rule_set))
+            .route("/nodes/{id}/firewall/{family}/rule-sets/{name}", web::put().to(handlers::firewall::set_rule_set))
+            .route("/nodes/{id}/firewall/{family}/rule-sets/{name}", web::delete().to(handlers::firewall::delete_rule_set))
+            .route("/nodes/{id}/firewall/{family}/rule-sets/{name}/reorder", web::post().to(handlers::firewall::reorder_rules))
+            .route("/nodes/{id}/firewall/{family}/rule-sets/{name}/rules/{number}", web::put().to(handlers::firewall::set_rule))
+            .route("/nodes/{id}/firewall/{family}/rule-sets/{name}/rules/{number}", web::delete().to(handlers::firewall::delete_rule))
+            .route("/nodes/{id}/firewall/groups/{kind}/{name}", web::put().to(handlers::firewall::set_group))
+            .route("/nodes/{id}/firewall/groups/{kind}/{name}", web::delete().to(handlers::firewall::delete_group))
+            .route("/nodes/{id}/firewall/zones/{name}", web::put().to(handlers::firewall::set_zone))
+            .route("/nodes/{id}/firewall/zones/{name}", web::delete().to(handlers::firewall::delete_zone))
+            // Network endpoints
+            .route("/network/ipam", web::get().to(handlers::ipam::get_ipam))
+            .route("/network/firewall/logs", web::get().to(handlers::firewall_log::list_firewall_logs))
+            .route("/network/firewall/rules/hits", web::get().to(handlers::firewall_log::get_firewall_rule_hits))
+            // Tool endpoints
+            .route("/tools/subnet", web::get().to(handlers::subnet::describe_subnet))
+            .route("/tools/subnet/split", web::post().to(handlers::subnet::split_subnet))
+            .route("/tools/subnet/summarize", web::post().to(handlers::subnet::summarize_subnets))
+            .route("/tools/subnet/contains", web::post().to(handlers::subnet::subnet_contains))
+            .route("/tools/subnet/next-available", web::post().to(handlers::subnet::next_available_subnets))
+            .route("/tools/mac-lookup", web::get().to(handlers::mac_vendor::lookup_mac))
+            .route("/tools/mac-lookup", web::post().to(handlers::mac_vendor::lookup_macs))
+            .route("/tools/geoip", web::get().to(handlers::geoip::lookup_ip))
+            .route("/tools/geoip", web::post().to(handlers::geoip::lookup_ips))
+            // Configuration endpoints
+            .route("/config/retrieve", web::post().to(handlers::config::retrieve_config))
+            .route("/config/configure", web::post().to(handlers::config::set_config))
+            .route("/config/delete", web::post().to(handlers::config::delete_config))
+            .route("/config/generate", web::post().to(handlers::config::generate_config))
+            .route("/config/history", web::get().to(handlers::config::get_history))
+            .route("/config/history/{id}", web::get().to(handlers::config::get_history_entry))
+            .route("/config/rollback", web::post().to(handlers::config::rollback_config))
+            .route("/config/diff/{id1}/{id2}", web::get().to(handlers::config::diff_configs))
+            .route("/config/search", web::post().to(handlers::config::search_config))
+            .route("/config/bulk", web::post().to(handlers::config::bulk_config_change))
+            .route("/config/validate", web::post().to(handlers::config::validate_config))
+            .route("/config/value", web::post().to(handlers::config::get_config_value))
+            .route("/config/subtree", web::post().to(handlers::config::get_config_subtree))
+            .route("/config/compare", web::post().to(handlers::config::compare_configs))
+            .route("/config/discard", web::post().to(handlers::config::discard_config))
+            .route("/config/stats", web::get().to(handlers::config::get_config_stats))
+            .route("/config/import", web::post().to(handlers::upload::import_config))
+            .route("/config/locks", web::get().to(handlers::config_lock::list_config_locks))
+            .route("/config/locks", web::post().to(handlers::config_lock::create_config_lock))
+            .route("/config/locks/{id}", web::delete().to(handlers::config_lock::delete_config_lock))
+            .route("/config/session", web::get().to(handlers::config_session::list_config_sessions))
+            .route("/config/session", web::post().to(handlers::config_session::open_config_session))
+            .route("/config/session/{id}", web::get().to(handlers::config_session::get_config_session))
+            .route("/config/session/{id}", web::delete().to(handlers::config_session::discard_config_session))
+            .route("/config/session/{id}/operations", web::post().to(handlers::config_session::stage_config_operations))
+            .route("/config/session/{id}/commit", web::post().to(handlers::config_session::commit_config_session))
+            .route("/config/session/{id}/confirm", web::post().to(handlers::config_session::confirm_config_session))
+            .route("/config/session/{id}/schedule", web::post().to(handlers::config_session::schedule_config_session))
+            .route("/config/session/{id}/schedule", web::delete().to(handlers::config_session::unschedule_config_session))
+            // Setup wizard endpoints
+            .route("/wizards", web::get().to(handlers::wizard::list_wizards))
+            .route("/wizards", web::post().to(handlers::wizard::start_wizard))
+            .route("/wizards/{id}", web::get().to(handlers::wizard::get_wizard))
+            .route("/wizards/{id}", web::delete().to(handlers::wizard::discard_wizard))
+            .route("/wizards/{id}/steps/{step}", web::put().to(handlers::wizard::answer_wizard_step))
+            .route("/wizards/{id}/plan", web::get().to(handlers::wizard::get_wizard_plan))
+            .route("/wizards/{id}/apply", web::post().to(handlers::wizard::apply_wizard))
+            // VPN mesh endpoints
+            .route("/vpn-meshes", web::get().to(handlers::vpn_mesh::list_vpn_meshes))
+            .route("/vpn-meshes", web::post().to(handlers::vpn_mesh::create_vpn_mesh))
+            .route("/vpn-meshes/{id}", web::get().to(handlers::vpn_mesh::get_vpn_mesh))
+            .route("/vpn-meshes/{id}", web::delete().to(handlers::vpn_mesh::delete_vpn_mesh))
+            .route("/vpn-meshes/{id}/plan", web::get().to(handlers::vpn_mesh::get_vpn_mesh_plan))
+            .route("/vpn-meshes/{id}/apply", web::post().to(handlers::vpn_mesh::apply_vpn_mesh))
+            .route("/vpn-meshes/{id}/check", web::post().to(handlers::vpn_mesh::check_vpn_mesh))
+            // System endpoints
+            .route("/system/reboot", web::post().to(handlers::system::reboot))
+            .route("/system/poweroff", web::post().to(handlers::system::poweroff))
+            .route("/system/reset", web::post().to(handlers::system::reset_configuration))
+            .route("/system/images", web::get().to(handlers::system::list_images))
+            .route("/system/images", web::post().to(handlers::system::manage_images))
+            .route("/system/images/add", web::post().to(handlers::system::add_image))
+            .route("/system/images/delete", web::post().to(handlers::system::delete_image))
+            .route("/system/images/set-default", web::post().to(handlers::system::set_default_image))
+            .route("/system/show", web::post().to(handlers::system::execute_show_command))
+            .route("/system/info", web::get().to(handlers::system::get_system_info))
+            .route("/system/operations/{operation_id}", web::get().to(handlers::system::check_operation_status))
+            .route("/system/health", web::get().to(handlers::system::system_health_check))
+            // Announcement endpoints
+            .route("/announcements", web::get().to(handlers::announcement::list_announcements))
+            .route("/announcements", web::post().to(handlers::announcement::create_announcement))
+            .route("/announcements/{id}", web::put().to(handlers::announcement::update_announcement))
+            .route("/announcements/{id}", web::delete().to(handlers::announcement::delete_announcement))
+            // Event endpoints
+            .route("/events", web::get().to(handlers::event::replay_events))
+            // Incident endpoints
+            .route("/incidents", web::get().to(handlers::incident::list_incidents))
+            .route("/incidents/{id}", web::get().to(handlers::incident::get_incident))
+            .route("/incidents/{id}", web::put().to(handlers::incident::update_incident))
+            .route("/incidents/{id}/comments", web::post().to(handlers::incident::add_incident_comment))
+            // Status page endpoints
+            .route("/status", web::get().to(handlers::status_page::get_status_page))
+            .route("/status/settings", web::get().to(handlers::status_page::get_status_page_settings))
+            .route("/status/settings", web::put().to(handlers::status_page::update_status_page_settings))
+            // Report endpoints
+            .route("/reports", web::get().to(handlers::report::list_reports))
+            .route("/reports", web::post().to(handlers::report::generate_report))
+            .route("/reports/schedules", web::get().to(handlers::report::list_report_schedules))
+            .route("/reports/schedules", web::post().to(handlers::report::create_report_schedule))
+            .route("/reports/schedules/{id}", web::delete().to(handlers::report::delete_report_schedule))
+            .route("/reports/{id}", web::get().to(handlers::report::get_report))
+            .route("/reports/{id}", web::delete().to(handlers::report::delete_report))
+            .route("/reports/{id}/download", web::get().to(handlers::report::download_report))
+            // Upload endpoints
+            .route("/uploads", web::get().to(handlers::upload::list_uploads))
+            .route("/uploads", web::post().to(handlers::upload::upload_file))
+            .route("/uploads/{id}", web::get().to(handlers::upload::get_upload))
+            .route("/uploads/{id}", web::delete().to(handlers::upload::delete_upload))
+            .route("/uploads/{id}/download", web::get().to(handlers::upload::download_upload))
+            // Administration endpoints
+            .route("/admin/config", web::get().to(handlers::runtime_config::get_runtime_settings))
+            .route("/admin/config/reload", web::post().to(handlers::runtime_config::reload_config))
+            .route("/admin/support-bundle", web::get().to(handlers::support_bundle::download_support_bundle))
+            .route("/admin/cluster", web::get().to(handlers::cluster::get_cluster_status))
+            .route("/admin/db/backups", web::get().to(handlers::backup::list_backups))
+            .route("/admin/db/backup", web::post().to(handlers::backup::create_backup))
+            .route("/admin/db/restore", web::post().to(handlers::backup::restore_backup))
+            .route("/admin/storage", web::get().to(handlers::storage::get_storage_usage))
+            .route("/admin/backend-health", web::get().to(handlers::backend_health::get_backend_health))
+            .route("/admin/oui", web::get().to(handlers::mac_vendor::get_oui_database))
+            .route("/admin/oui/update", web::post().to(handlers::mac_vendor::update_oui_database))
+            .route("/admin/geoip", web::get().to(handlers::geoip::get_geoip_status))
+            .route("/admin/chaos", web::get().to(handlers::chaos::get_chaos_status))
+            .route("/admin/chaos", web::delete().to(handlers::chaos::reset_chaos))
+            .route("/admin/chaos/nodes/{id}", web::put().to(handlers::chaos::set_node_fault))
+            .route("/admin/chaos/nodes/{id}", web::delete().to(handlers::chaos::clear_node_fault))
+            .route("/admin/chaos/database", web::put().to(handlers::chaos::set_database_delay))
+            // Frontend error reporting
+            .service(
+                web::resource("/client-errors")
+                    .app_data(web::JsonConfig::default().limit(CLIENT_ERROR_MAX_BYTES))
+                    .route(web::post().to(handlers::client_error::report_client_error)),
+            )
+            // Audit and operation history endpoints
+            .route("/audit", web::get().to(handlers::audit::list_audit_entries))
+            .route("/audit/export", web::get().to(handlers::audit::export_audit_entries))
+            .route("/operations", web::get().to(handlers::audit::list_operations))
+            .route("/operations/export", web::get().to(handlers::audit::export_operations))
+            .route("/operations/{id}", web::get().to(handlers::audit::get_operation))
+            // Monitoring endpoints
+            .route("/monitoring/system", web::get().to(handlers::monitoring::get_system_metrics))
+            .route("/monitoring/network", web::get().to(handlers::monitoring::get_network_statistics))
+            .route("/monitoring/history", web::get().to(handlers::monitoring::get_history))
+            .route("/monitoring/export", web::get().to(handlers::monitoring::export_metrics))
+            .route("/monitoring/top", web::get().to(handlers::monitoring::get_top_nodes))
+            .route("/monitoring/alerts", web::get().to(handlers::monitoring::get_alerts))
+            .route("/monitoring/alerts", web::post().to(handlers::monitoring::create_alert))
+            .route("/monitoring/alerts/{id}", web::put().to(handlers::monitoring::update_alert))
+            .route("/monitoring/alerts/{id}", web::delete().to(handlers::monitoring::delete_alert))
+            .route("/monitoring/alerts/rules", web::get().to(handlers::monitoring::get_alert_rules))
+            .route("/monitoring/alerts/bundles", web::get().to(handlers::monitoring::get_alert_bundles))
+            .route("/monitoring/alerts/bundles/apply", web::post().to(handlers::monitoring::apply_alert_bundles))
+            .route("/monitoring/alerts/bundles/{bundle}", web::delete().to(handlers::monitoring::remove_alert_bundle))
+            .route("/monitoring/alerts/rules/{id}", web::get().to(handlers::monitoring::get_alert_rule))
+            .route("/certificates", web::get().to(handlers::certificate::list_certificate_endpoints))
+            .route("/certificates", web::post().to(handlers::certificate::create_certificate_endpoint))
+            .route("/certificates/{id}", web::delete().to(handlers::certificate::delete_certificate_endpoint))
+            .route("/certificates/{id}/check", web::post().to(handlers::certificate::check_certificate_endpoint))
+            .route("/monitoring/alerts/rules/{id}/targets", web::get().to(handlers::monitoring::get_alert_rule_targets))
+            .route("/monitoring/topology", web::get().to(handlers::topology::get_topology))
+            .route("/monitoring/topology/links", web::post().to(handlers::topology::create_topology_link))
+            .route("/monitoring/topology/links/{id}", web::put().to(handlers::topology::update_topology_link))
+            .route("/monitoring/topology/links/{id}", web::delete().to(handlers::topology::delete_topology_link))
+            .route("/monitoring/topology/positions", web::post().to(handlers::topology::update_topology_positions))
+            .route("/monitoring/notifications/channels", web::get().to(handlers::notification::list_notification_channels))
+            .route("/monitoring/notifications/channels", web::post().to(handlers::notification::create_notification_channel))
+            .route("/monitoring/notifications/channels/{id}", web::get().to(handlers::notification::get_notification_channel))
+            .route("/monitoring/notifications/channels/{id}", web::put().to(handlers::notification::update_notification_channel))
+            .route("/monitoring/notifications/channels/{id}", web::delete().to(handlers::notification::delete_notification_channel))
+            .route("/monitoring/notifications/channels/{id}/test", web::post().to(handlers::notification::test_notification_channel))
+            .route("/monitoring/notifications/routes", web::get().to(handlers::notification::list_notification_routes))
+            .route("/monitoring/notifications/routes", web::post().to(handlers::notification::create_notification_route))
+            .route("/monitoring/notifications/routes/{id}", web::get().to(handlers::notification::get_notification_route))
+            .route("/monitoring/notifications/routes/{id}", web::put().to(handlers::notification::update_notification_route))
+            .route("/monitoring/notifications/routes/{id}", web::delete().to(handlers::notification::delete_notification_route))
+            .route("/monitoring/notifications/deliveries", web::get().to(handlers::notification::list_notification_deliveries))
+    )
+    .route("/ws", web::get().to(websocket::websocket_handler))
+    .route("/ws/info", web::get().to(websocket::ws_info));
+}
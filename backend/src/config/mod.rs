@@ -1,15 +1,90 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
 use std::env;
+use std::fmt::Write as _;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
 
 use actix_web::web::Data;
+use chrono::NaiveDate;
 use serde::Deserialize;
 use sqlx::sqlite::SqlitePoolOptions;
 use sqlx::SqlitePool;
-use tracing::info;
+use tracing::field::{Field, Visit};
+use tracing::{info, Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{EnvFilter, Registry};
 
 use crate::error::AppError;
+use crate::models::config::ConfigLintRule;
+use crate::models::config_session::FreezeWindow;
+use crate::models::quota::TeamQuota;
+use crate::models::redact::sanitized_debug;
+use crate::models::storage::ArtifactStorage;
+use crate::models::timestamp::db_now;
+use crate::services::chaos::ChaosService;
+
+pub mod reload;
+pub mod secrets;
+
+pub use reload::*;
+pub use secrets::*;
+
+/// Number of log lines kept in memory for support bundles
+pub const RECENT_LOG_LINES: usize = 2000;
+
+/// Upper bound for the number of HTTP worker threads
+pub const MAX_SERVER_WORKERS: usize = 512;
+
+/// Lower bound for the request body limit, in bytes
+pub const MIN_PAYLOAD_BYTES: usize = 1024;
+
+/// Lower bound for the leader lease duration, in seconds
+pub const MIN_LEADER_LEASE_SECS: u64 = 3;
+
+/// Lower bound for the node failure threshold
+pub const MIN_NODE_FAILURE_THRESHOLD: u32 = 1;
+
+/// Smallest part S3 accepts in a multipart upload, except for the last one
+pub const MIN_S3_PART_SIZE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Configuration paths holding secrets when `CONFIG_SECRET_PATHS` is unset
+pub const DEFAULT_SECRET_PATHS: &[&str] = &[
+    "interfaces wireguard * private-key",
+    "interfaces wireguard * peer * preshared-key",
+    "interfaces pppoe * authentication password",
+    "interfaces wwan * authentication password",
+    "vpn ipsec authentication psk * secret",
+    "vpn ipsec site-to-site peer * authentication pre-shared-secret",
+    "vpn l2tp remote-access authentication local-users username * password",
+    "vpn openconnect authentication local-users username * password",
+    "service snmp community",
+    "service snmp v3 user * auth encrypted-password",
+    "service snmp v3 user * auth plaintext-password",
+    "service snmp v3 user * privacy encrypted-password",
+    "service snmp v3 user * privacy plaintext-password",
+    "system login user * authentication encrypted-password",
+    "system login user * authentication plaintext-password",
+    "protocols bgp neighbor * password",
+    "protocols bgp * neighbor * password",
+    "high-availability vrrp group * authentication password",
+];
+
+/// Settings that take effect when the configuration is reloaded; changes to
+/// any other setting require a restart
+pub const RELOADABLE_SETTINGS: &[&str] = &[
+    "log_level",
+    "cors_allowed_origins",
+    "incident_sync_interval_secs",
+    "feature_flags",
+    "change_freeze_windows",
+];
 
 /// Application configuration loaded from environment variables
-#[derive(Debug, Clone, Deserialize)]
+///
+/// Secret settings are redacted from its `Debug` output.
+#[derive(Clone, Deserialize)]
 pub struct AppConfig {
     /// Server host address
     pub server_host: String,
@@ -17,18 +92,153 @@ pub struct AppConfig {
     /// Server port
     pub server_port: u16,
 
+    /// Number of HTTP worker threads
+    pub server_workers: usize,
+
+    /// Seconds idle client connections are kept open (0 disables keep-alive)
+    pub server_keep_alive_secs: u64,
+
+    /// Largest request body accepted, in bytes
+    pub max_payload_bytes: usize,
+
+    /// Directory uploaded files are stored in
+    pub upload_dir: String,
+
+    /// Largest file upload accepted, in bytes
+    pub max_upload_bytes: u64,
+
+    /// Largest file accepted as a node attachment, in bytes
+    pub max_attachment_bytes: u64,
+
     /// Application environment (development, staging, production)
     pub app_env: String,
 
-    /// Database connection URL
+    /// Database connection URL; redacted from `Debug` output, as it may hold
+    /// a password
     pub database_url: String,
 
+    /// Maximum number of pooled database connections
+    pub database_max_connections: u32,
+
+    /// Number of database connections kept open when idle
+    pub database_min_connections: u32,
+
+    /// Queries taking at least this many milliseconds are logged as slow
+    pub database_slow_query_ms: u64,
+
+    /// Whether pending database migrations are applied at startup
+    pub database_auto_migrate: bool,
+
+    /// Directory database backups are written to
+    pub backup_dir: String,
+
+    /// Disk space the database, backups, and uploads may take together, in
+    /// bytes; new uploads and backups are refused once it is used up
+    pub storage_cap_bytes: Option<u64>,
+
+    /// Share of the storage cap, in percent, at which usage is reported as
+    /// approaching it
+    pub storage_warning_percent: u8,
+
+    /// Where uploads and database backups are kept
+    pub artifact_storage: ArtifactStorage,
+
+    /// URL of the S3-compatible service holding the artifact bucket
+    pub artifact_s3_endpoint: Option<String>,
+
+    /// Bucket artifacts are stored in, below `uploads/` and `backups/`
+    pub artifact_s3_bucket: Option<String>,
+
+    /// Region requests to the artifact bucket are signed for
+    pub artifact_s3_region: String,
+
+    /// Access key of the artifact bucket
+    pub artifact_s3_access_key_id: Option<String>,
+
+    /// Secret key of the artifact bucket
+    pub artifact_s3_secret_access_key: Option<String>,
+
+    /// Whether the bucket is addressed in the URL path rather than the host name
+    pub artifact_s3_path_style: bool,
+
+    /// Size of the parts large artifacts are uploaded in, in bytes
+    pub artifact_s3_part_size_bytes: u64,
+
+    /// ID this instance competes for leadership of a cluster under
+    pub instance_id: String,
+
+    /// Seconds a leader lease lasts without renewal
+    pub leader_lease_secs: u64,
+
     /// JWT secret key for token signing
     pub jwt_secret_key: String,
 
     /// JWT token expiration time in minutes
     pub jwt_expiration_minutes: u64,
 
+    /// Days a login session may go without refreshing its tokens
+    pub refresh_token_expiration_days: u64,
+
+    /// Day from which user URLs with legacy integer IDs answer 410 Gone
+    /// instead of being served as the matching UUID
+    pub legacy_user_ids_sunset: NaiveDate,
+
+    /// Key used to encrypt node credentials at rest
+    pub node_encryption_key: Option<String>,
+
+    /// Consecutive transport failures after which a node's circuit opens
+    pub node_failure_threshold: u32,
+
+    /// Seconds an open node circuit waits before the node is tried again
+    pub node_retry_after_secs: u64,
+
+    /// Seconds between background health checks of every node; 0 disables them
+    pub node_health_check_interval_secs: u64,
+
+    /// Seconds a node's clock may drift from the backend's before an alert is raised
+    pub node_clock_drift_threshold_secs: u64,
+
+    /// Seconds a rebooting node is given to answer again before it is marked failed
+    pub node_reboot_grace_secs: u64,
+
+    /// Seconds show command results are cached for; 0 disables the cache
+    pub show_cache_ttl_secs: u64,
+
+    /// Cache TTLs of particular show commands, overriding `show_cache_ttl_secs`
+    pub show_cache_command_ttls: BTreeMap<String, u64>,
+
+    /// Seconds between collections of system metrics from every node; 0 disables them
+    pub metrics_collection_interval_secs: u64,
+
+    /// Frames queued per WebSocket connection before frames are dropped;
+    /// live updates waiting to be sent are replaced by newer ones rather
+    /// than queued
+    pub websocket_queue_capacity: usize,
+
+    /// Seconds between dispatches of alert notifications; 0 disables them
+    pub notification_interval_secs: u64,
+
+    /// Where administrators download the IEEE MAC vendor database from
+    pub oui_database_url: String,
+
+    /// GeoIP country or city database (MMDB); GeoIP enrichment is off without it
+    pub geoip_database_path: Option<String>,
+
+    /// GeoIP ASN database (MMDB)
+    pub geoip_asn_database_path: Option<String>,
+
+    /// UDP address syslog messages from nodes are received on; firewall
+    /// logs are only collected when set
+    pub syslog_listen_addr: Option<SocketAddr>,
+
+    /// Whether administrators may inject node and database failures for
+    /// testing; refused in production
+    pub chaos_enabled: bool,
+
+    /// Whether the unauthenticated load test targets under
+    /// `/api/load-profile` are served; refused in production
+    pub load_profile_enabled: bool,
+
     /// Log level (trace, debug, info, warn, error)
     pub log_level: String,
 
@@ -40,24 +250,182 @@ pub struct AppConfig {
 
     /// VyOS API password
     pub vyos_api_password: Option<String>,
+
+    /// Local Git repository that applied configuration snapshots are committed to
+    pub git_export_repo_path: Option<String>,
+
+    /// Git remote to push snapshot commits to (commits stay local when unset);
+    /// redacted from `Debug` output, as it may hold a token
+    pub git_export_remote: Option<String>,
+
+    /// Git branch that snapshot commits are made on
+    pub git_export_branch: String,
+
+    /// Lint rules skipped when configuration is committed
+    pub config_lint_disabled_rules: BTreeSet<ConfigLintRule>,
+
+    /// Configuration paths whose values are secrets, with `*` matching any
+    /// single word
+    pub config_secret_paths: Vec<String>,
+
+    /// Key that secrets in stored configuration snapshots and backups are
+    /// encrypted with (they are stored in the clear when unset)
+    pub config_secrets_key: Option<String>,
+
+    /// Default quota limits for teams without explicit overrides
+    pub default_team_quota: TeamQuota,
+
+    /// Frontend error reports accepted per client and minute
+    pub client_error_rate_limit: u32,
+
+    /// Seconds between scheduled configuration backups of every node (0
+    /// disables them)
+    pub config_backup_interval_secs: u64,
+
+    /// Newest configuration backups of a node kept regardless of age
+    pub config_backup_keep_last: u32,
+
+    /// Days for which the newest configuration backup of each day is kept
+    pub config_backup_keep_daily_days: u32,
+
+    /// Sign-in and registration attempts accepted per IP address and minute
+    /// (0 disables the limit)
+    pub rate_limit_auth_per_minute: u32,
+
+    /// Requests to a node's endpoints accepted per user and minute (0
+    /// disables the limit)
+    pub rate_limit_node_per_minute: u32,
+
+    /// Reverse proxies whose `X-Forwarded-For` header is trusted for the
+    /// client address; it is ignored on connections from anywhere else
+    pub trusted_proxies: Vec<IpAddr>,
+
+    /// SMTP relay used to send email (email is disabled when unset)
+    pub smtp_host: Option<String>,
+
+    /// SMTP relay port
+    pub smtp_port: u16,
+
+    /// SMTP username
+    pub smtp_username: Option<String>,
+
+    /// SMTP password
+    pub smtp_password: Option<String>,
+
+    /// Sender address of outgoing email
+    pub smtp_from: String,
+
+    /// Whether to upgrade SMTP connections with STARTTLS
+    pub smtp_starttls: bool,
+
+    /// Origins allowed to make cross-origin requests outside development
+    pub cors_allowed_origins: Vec<String>,
+
+    /// Seconds between grouping new alerts into incidents
+    pub incident_sync_interval_secs: u64,
+
+    /// Enabled feature flags
+    pub feature_flags: BTreeSet<String>,
+
+    /// Periods during which scheduled configuration commits are held back
+    pub change_freeze_windows: Vec<FreezeWindow>,
+
+    /// Secret store references that secret settings were loaded from, keyed
+    /// by setting name
+    #[serde(default)]
+    pub secret_references: BTreeMap<String, String>,
 }
 
+sanitized_debug!(AppConfig {
+    server_host, server_port, server_workers, server_keep_alive_secs, max_payload_bytes, upload_dir, max_upload_bytes,
+    max_attachment_bytes, app_env, database_max_connections, database_min_connections, database_slow_query_ms,
+    database_auto_migrate, backup_dir, storage_cap_bytes, storage_warning_percent, artifact_storage,
+    artifact_s3_endpoint, artifact_s3_bucket, artifact_s3_region, artifact_s3_access_key_id, artifact_s3_path_style,
+    artifact_s3_part_size_bytes, instance_id, leader_lease_secs, jwt_expiration_minutes, refresh_token_expiration_days,
+    legacy_user_ids_sunset, node_failure_threshold, node_retry_after_secs, node_health_check_interval_secs,
+    node_clock_drift_threshold_secs, node_reboot_grace_secs, show_cache_ttl_secs, show_cache_command_ttls,
+    metrics_collection_interval_secs, websocket_queue_capacity, notification_interval_secs, oui_database_url,
+    geoip_database_path, geoip_asn_database_path, syslog_listen_addr, chaos_enabled, load_profile_enabled, log_level,
+    vyos_api_url, vyos_api_username, git_export_repo_path, git_export_branch, config_lint_disabled_rules,
+    config_secret_paths, default_team_quota, client_error_rate_limit, config_backup_interval_secs,
+    config_backup_keep_last, config_backup_keep_daily_days, rate_limit_auth_per_minute, rate_limit_node_per_minute,
+    trusted_proxies, smtp_host, smtp_port, smtp_username, smtp_from, smtp_starttls, cors_allowed_origins,
+    incident_sync_interval_secs, feature_flags, change_freeze_windows, secret_references,
+} secret {
+    database_url, artifact_s3_secret_access_key, jwt_secret_key, node_encryption_key, vyos_api_password,
+    git_export_remote, config_secrets_key, smtp_password,
+});
+
 impl AppConfig {
     /// Load configuration from environment variables
     pub fn from_env() -> Result<Self, AppError> {
-        dotenv::dotenv().ok();
+        PROCESS_ENV.get_or_init(|| env::vars_os().filter_map(|(key, _)| key.into_string().ok()).collect());
+        dotenvy::dotenv().ok();
+
+        Self::from_current_env()
+    }
+
+    /// Load configuration again after re-reading the `.env` file
+    ///
+    /// Variables set in the process environment at startup keep precedence
+    /// over the file, like they do in [`AppConfig::from_env`].
+    pub fn reload_from_env() -> Result<Self, AppError> {
+        let process_env = PROCESS_ENV.get_or_init(HashSet::new);
+        if let Ok(entries) = dotenvy::dotenv_iter() {
+            for entry in entries {
+                let (key, value) = entry.map_err(|e| AppError::Config(format!("Invalid .env file: {}", e)))?;
+                if !process_env.contains(&key) {
+                    env::set_var(key, value);
+                }
+            }
+        }
 
+        Self::from_current_env()
+    }
+
+    fn from_current_env() -> Result<Self, AppError> {
         let app_env = env::var("APP_ENV").unwrap_or_else(|_| "development".to_string());
+        let default_max_connections = if app_env == "development" { 5 } else { 10 };
 
-        Ok(Self {
+        let config = Self {
             server_host: env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
             server_port: env::var("SERVER_PORT")
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .map_err(|e| AppError::Config(format!("Invalid server port: {}", e)))?,
+            server_workers: optional_env("SERVER_WORKERS")?
+                .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get())),
+            server_keep_alive_secs: optional_env("SERVER_KEEP_ALIVE_SECS")?.unwrap_or(5),
+            max_payload_bytes: optional_env("MAX_PAYLOAD_BYTES")?.unwrap_or(1024 * 1024),
+            upload_dir: env::var("UPLOAD_DIR").unwrap_or_else(|_| "data/uploads".to_string()),
+            max_upload_bytes: optional_env("MAX_UPLOAD_BYTES")?.unwrap_or(4 * 1024 * 1024 * 1024),
+            max_attachment_bytes: optional_env("MAX_ATTACHMENT_BYTES")?.unwrap_or(16 * 1024 * 1024),
             app_env: app_env.clone(),
             database_url: env::var("DATABASE_URL")
                 .unwrap_or_else(|_| "sqlite:data/database.db?mode=rwc".to_string()),
+            database_max_connections: optional_env("DATABASE_MAX_CONNECTIONS")?.unwrap_or(default_max_connections),
+            database_min_connections: optional_env("DATABASE_MIN_CONNECTIONS")?.unwrap_or(0),
+            database_slow_query_ms: optional_env("DATABASE_SLOW_QUERY_MS")?
+                .unwrap_or(crate::db::metrics::DEFAULT_SLOW_QUERY_MS),
+            database_auto_migrate: env::var("DATABASE_AUTO_MIGRATE").map(|v| v != "false").unwrap_or(true),
+            backup_dir: env::var("BACKUP_DIR").unwrap_or_else(|_| "data/backups".to_string()),
+            storage_cap_bytes: optional_env("STORAGE_CAP_BYTES")?,
+            storage_warning_percent: optional_env("STORAGE_WARNING_PERCENT")?.unwrap_or(80),
+            artifact_storage: optional_env("ARTIFACT_STORAGE")?.unwrap_or_default(),
+            artifact_s3_endpoint: env::var("ARTIFACT_S3_ENDPOINT").ok().filter(|url| !url.is_empty()),
+            artifact_s3_bucket: env::var("ARTIFACT_S3_BUCKET").ok().filter(|bucket| !bucket.is_empty()),
+            artifact_s3_region: env::var("ARTIFACT_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            artifact_s3_access_key_id: env::var("ARTIFACT_S3_ACCESS_KEY_ID").ok().filter(|key| !key.is_empty()),
+            artifact_s3_secret_access_key: env::var("ARTIFACT_S3_SECRET_ACCESS_KEY")
+                .ok()
+                .filter(|key| !key.is_empty()),
+            artifact_s3_path_style: env::var("ARTIFACT_S3_PATH_STYLE").map(|v| v != "false").unwrap_or(true),
+            artifact_s3_part_size_bytes: optional_env("ARTIFACT_S3_PART_SIZE_BYTES")?.unwrap_or(8 * 1024 * 1024),
+            instance_id: env::var("INSTANCE_ID").ok().filter(|id| !id.trim().is_empty()).unwrap_or_else(|| {
+                let host = env::var("HOSTNAME").unwrap_or_else(|_| "vyos-webui".to_string());
+                format!("{}-{}", host, &uuid::Uuid::new_v4().simple().to_string()[..8])
+            }),
+            leader_lease_secs: optional_env("LEADER_LEASE_SECS")?.unwrap_or(30),
             jwt_secret_key: env::var("JWT_SECRET_KEY").unwrap_or_else(|_| {
                 "default_secret_key_replace_in_production".to_string()
             }),
@@ -65,11 +433,153 @@ impl AppConfig {
                 .unwrap_or_else(|_| "60".to_string())
                 .parse()
                 .unwrap_or(60),
+            refresh_token_expiration_days: optional_env("REFRESH_TOKEN_EXPIRATION_DAYS")?.unwrap_or(30),
+            legacy_user_ids_sunset: optional_env("LEGACY_USER_IDS_SUNSET")?
+                .unwrap_or_else(|| NaiveDate::from_ymd_opt(2027, 4, 1).expect("valid date")),
+            node_encryption_key: env::var("NODE_ENCRYPTION_KEY").ok().filter(|key| !key.is_empty()),
+            node_failure_threshold: optional_env("NODE_FAILURE_THRESHOLD")?.unwrap_or(3),
+            node_retry_after_secs: optional_env("NODE_RETRY_AFTER_SECS")?.unwrap_or(30),
+            node_health_check_interval_secs: optional_env("NODE_HEALTH_CHECK_INTERVAL_SECS")?.unwrap_or(60),
+            node_clock_drift_threshold_secs: optional_env("NODE_CLOCK_DRIFT_THRESHOLD_SECS")?.unwrap_or(30),
+            node_reboot_grace_secs: optional_env("NODE_REBOOT_GRACE_SECS")?.unwrap_or(300),
+            show_cache_ttl_secs: optional_env("SHOW_CACHE_TTL_SECS")?.unwrap_or(5),
+            show_cache_command_ttls: list_env("SHOW_CACHE_COMMAND_TTLS")
+                .iter()
+                .map(|item| {
+                    let (command, ttl) = item.rsplit_once('=').ok_or_else(|| {
+                        AppError::Config(format!("Invalid SHOW_CACHE_COMMAND_TTLS entry {}: expected command=seconds", item))
+                    })?;
+                    let ttl = ttl.trim().parse().map_err(|e| {
+                        AppError::Config(format!("Invalid SHOW_CACHE_COMMAND_TTLS entry {}: {}", item, e))
+                    })?;
+                    Ok((command.trim().to_string(), ttl))
+                })
+                .collect::<Result<_, AppError>>()?,
+            metrics_collection_interval_secs: optional_env("METRICS_COLLECTION_INTERVAL_SECS")?.unwrap_or(60),
+            websocket_queue_capacity: optional_env("WEBSOCKET_QUEUE_CAPACITY")?.unwrap_or(256),
+            notification_interval_secs: optional_env("NOTIFICATION_INTERVAL_SECS")?.unwrap_or(30),
+            oui_database_url: env::var("OUI_DATABASE_URL")
+                .unwrap_or_else(|_| "https://standards-oui.ieee.org/oui/oui.csv".to_string()),
+            geoip_database_path: env::var("GEOIP_DATABASE_PATH").ok().filter(|path| !path.is_empty()),
+            geoip_asn_database_path: env::var("GEOIP_ASN_DATABASE_PATH").ok().filter(|path| !path.is_empty()),
+            syslog_listen_addr: optional_env("SYSLOG_LISTEN_ADDR")?,
+            chaos_enabled: env::var("CHAOS_ENABLED").map(|v| v == "true").unwrap_or(false),
+            load_profile_enabled: env::var("LOAD_PROFILE_ENABLED").map(|v| v == "true").unwrap_or(false),
             log_level: env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
             vyos_api_url: env::var("VYOS_API_URL").ok(),
             vyos_api_username: env::var("VYOS_API_USERNAME").ok(),
             vyos_api_password: env::var("VYOS_API_PASSWORD").ok(),
-        })
+            git_export_repo_path: env::var("GIT_EXPORT_REPO_PATH").ok(),
+            git_export_remote: env::var("GIT_EXPORT_REMOTE").ok(),
+            git_export_branch: env::var("GIT_EXPORT_BRANCH").unwrap_or_else(|_| "main".to_string()),
+            config_lint_disabled_rules: list_env("CONFIG_LINT_DISABLED_RULES")
+                .iter()
+                .map(|rule| rule.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|e| AppError::Config(format!("Invalid CONFIG_LINT_DISABLED_RULES: {}", e)))?,
+            config_secret_paths: Some(list_env("CONFIG_SECRET_PATHS"))
+                .filter(|paths| !paths.is_empty())
+                .unwrap_or_else(|| DEFAULT_SECRET_PATHS.iter().map(|path| path.to_string()).collect()),
+            config_secrets_key: env::var("CONFIG_SECRETS_KEY").ok().filter(|key| !key.is_empty()),
+            default_team_quota: TeamQuota {
+                max_nodes: optional_env("QUOTA_DEFAULT_MAX_NODES")?,
+                max_templates: optional_env("QUOTA_DEFAULT_MAX_TEMPLATES")?,
+                max_api_keys: optional_env("QUOTA_DEFAULT_MAX_API_KEYS")?,
+                metric_retention_days: optional_env("QUOTA_DEFAULT_METRIC_RETENTION_DAYS")?,
+            },
+            client_error_rate_limit: optional_env("CLIENT_ERROR_RATE_LIMIT")?.unwrap_or(30),
+            config_backup_interval_secs: optional_env("CONFIG_BACKUP_INTERVAL_SECS")?.unwrap_or(86400),
+            config_backup_keep_last: optional_env("CONFIG_BACKUP_KEEP_LAST")?.unwrap_or(10),
+            config_backup_keep_daily_days: optional_env("CONFIG_BACKUP_KEEP_DAILY_DAYS")?.unwrap_or(30),
+            rate_limit_auth_per_minute: optional_env("RATE_LIMIT_AUTH_PER_MINUTE")?.unwrap_or(10),
+            trusted_proxies: list_env("TRUSTED_PROXIES")
+                .iter()
+                .map(|proxy| proxy.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|e| AppError::Config(format!("Invalid TRUSTED_PROXIES: {}", e)))?,
+            rate_limit_node_per_minute: optional_env("RATE_LIMIT_NODE_PER_MINUTE")?.unwrap_or(300),
+            smtp_host: env::var("SMTP_HOST").ok().filter(|host| !host.trim().is_empty()),
+            smtp_port: env::var("SMTP_PORT")
+                .unwrap_or_else(|_| "587".to_string())
+                .parse()
+                .map_err(|e| AppError::Config(format!("Invalid SMTP port: {}", e)))?,
+            smtp_username: env::var("SMTP_USERNAME").ok(),
+            smtp_password: env::var("SMTP_PASSWORD").ok(),
+            smtp_from: env::var("SMTP_FROM").unwrap_or_else(|_| "VyOS Web UI <noreply@localhost>".to_string()),
+            smtp_starttls: env::var("SMTP_STARTTLS").map(|v| v != "false").unwrap_or(true),
+            cors_allowed_origins: list_env("CORS_ALLOWED_ORIGINS"),
+            incident_sync_interval_secs: optional_env("INCIDENT_SYNC_INTERVAL_SECS")?
+                .filter(|secs| *secs > 0)
+                .unwrap_or(60),
+            feature_flags: list_env("FEATURE_FLAGS").into_iter().collect(),
+            change_freeze_windows: list_env("CHANGE_FREEZE_WINDOWS")
+                .iter()
+                .map(|window| window.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|e| AppError::Config(format!("Invalid CHANGE_FREEZE_WINDOWS: {}", e)))?,
+            secret_references: BTreeMap::new(),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that the server and database tuning settings are usable
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !(1..=MAX_SERVER_WORKERS).contains(&self.server_workers) {
+            return Err(AppError::Config(format!(
+                "SERVER_WORKERS must be between 1 and {}",
+                MAX_SERVER_WORKERS
+            )));
+        }
+        if self.max_payload_bytes < MIN_PAYLOAD_BYTES {
+            return Err(AppError::Config(format!(
+                "MAX_PAYLOAD_BYTES must be at least {}",
+                MIN_PAYLOAD_BYTES
+            )));
+        }
+        if self.leader_lease_secs < MIN_LEADER_LEASE_SECS {
+            return Err(AppError::Config(format!(
+                "LEADER_LEASE_SECS must be at least {}",
+                MIN_LEADER_LEASE_SECS
+            )));
+        }
+        if self.node_failure_threshold < MIN_NODE_FAILURE_THRESHOLD {
+            return Err(AppError::Config(format!(
+                "NODE_FAILURE_THRESHOLD must be at least {}",
+                MIN_NODE_FAILURE_THRESHOLD
+            )));
+        }
+        if self.database_max_connections == 0 {
+            return Err(AppError::Config("DATABASE_MAX_CONNECTIONS must be at least 1".to_string()));
+        }
+        if self.database_min_connections > self.database_max_connections {
+            return Err(AppError::Config(
+                "DATABASE_MIN_CONNECTIONS must not exceed DATABASE_MAX_CONNECTIONS".to_string(),
+            ));
+        }
+        if !(1..=100).contains(&self.storage_warning_percent) {
+            return Err(AppError::Config("STORAGE_WARNING_PERCENT must be between 1 and 100".to_string()));
+        }
+        if self.artifact_storage == ArtifactStorage::S3 {
+            if self.artifact_s3_endpoint.is_none() || self.artifact_s3_bucket.is_none() {
+                return Err(AppError::Config(
+                    "ARTIFACT_S3_ENDPOINT and ARTIFACT_S3_BUCKET are required for S3 artifact storage".to_string(),
+                ));
+            }
+            if self.artifact_s3_part_size_bytes < MIN_S3_PART_SIZE_BYTES {
+                return Err(AppError::Config(format!(
+                    "ARTIFACT_S3_PART_SIZE_BYTES must be at least {}",
+                    MIN_S3_PART_SIZE_BYTES
+                )));
+            }
+        }
+        if self.chaos_enabled && self.is_production() {
+            return Err(AppError::Config("CHAOS_ENABLED must not be set in production".to_string()));
+        }
+        if self.load_profile_enabled && self.is_production() {
+            return Err(AppError::Config("LOAD_PROFILE_ENABLED must not be set in production".to_string()));
+        }
+        Ok(())
     }
 
     /// Get the server address in format "host:port"
@@ -86,14 +596,47 @@ impl AppConfig {
     pub fn is_production(&self) -> bool {
         self.app_env == "production"
     }
+
+    /// Check if a feature flag is enabled
+    pub fn feature_enabled(&self, flag: &str) -> bool {
+        self.feature_flags.contains(flag)
+    }
+}
+
+/// Names of the variables in the process environment before `.env` was loaded
+static PROCESS_ENV: OnceLock<HashSet<String>> = OnceLock::new();
+
+/// Parse an optional numeric environment variable
+fn optional_env<T>(name: &str) -> Result<Option<T>, AppError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match env::var(name) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|e| AppError::Config(format!("Invalid {}: {}", name, e))),
+        _ => Ok(None),
+    }
+}
+
+/// Parse a comma-separated environment variable, skipping empty items
+fn list_env(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
 }
 
 /// Initialize database connection pool
 pub async fn init_database(config: &AppConfig) -> Result<SqlitePool, AppError> {
     info!("Initializing database connection...");
 
-    let max_connections = if config.is_development() { 5 } else { 10 };
-
     // Create data directory if it doesn't exist
     if config.database_url.starts_with("sqlite:") {
         let db_path = config.database_url.strip_prefix("sqlite:").unwrap_or(&config.database_url);
@@ -105,8 +648,13 @@ pub async fn init_database(config: &AppConfig) -> Result<SqlitePool, AppError> {
         }
     }
 
-    let pool = SqlitePoolOptions::new()
-        .max_connections(max_connections)
+    let mut options = SqlitePoolOptions::new()
+        .max_connections(config.database_max_connections)
+        .min_connections(config.database_min_connections);
+    if config.chaos_enabled {
+        options = ChaosService::delay_queries(options);
+    }
+    let pool = options
         .connect(&config.database_url)
         .await
         .map_err(|e| AppError::Database(format!("Failed to connect to database: {}", e)))?;
@@ -116,11 +664,140 @@ pub async fn init_database(config: &AppConfig) -> Result<SqlitePool, AppError> {
 }
 
 /// Initialize logging
+///
+/// Besides writing to stdout, the most recent lines are kept in memory so
+/// they can be included in support bundles.
 pub fn init_logging(config: &AppConfig) {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(&config.log_level)),
-        )
+    let (filter, handle) = tracing_subscriber::reload::Layer::new(log_filter(&config.log_level));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(RecentLogLayer)
         .init();
-}
\ No newline at end of file
+    LOG_FILTER.set(handle).ok();
+}
+
+static LOG_FILTER: OnceLock<tracing_subscriber::reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Log filter for a log level, overridden by `RUST_LOG` when set
+fn log_filter(log_level: &str) -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(log_level))
+}
+
+/// Switch the log level of the running process
+///
+/// Does nothing unless logging was set up with [`init_logging`].
+pub fn set_log_level(log_level: &str) -> Result<(), AppError> {
+    EnvFilter::try_new(log_level)
+        .map_err(|e| AppError::Validation(format!("Invalid log level '{}': {}", log_level, e)))?;
+
+    if let Some(handle) = LOG_FILTER.get() {
+        handle
+            .reload(log_filter(log_level))
+            .map_err(|e| AppError::Internal(format!("Failed to change log level: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Log line kept in memory
+#[derive(Debug, Clone)]
+pub struct RecentLogLine {
+    pub level: Level,
+    pub line: String,
+}
+
+static RECENT_LOGS: OnceLock<Mutex<VecDeque<RecentLogLine>>> = OnceLock::new();
+
+/// Most recent log lines, oldest first
+///
+/// Empty unless logging was set up with [`init_logging`].
+pub fn recent_logs() -> Vec<RecentLogLine> {
+    RECENT_LOGS
+        .get()
+        .and_then(|logs| logs.lock().ok().map(|logs| logs.iter().cloned().collect()))
+        .unwrap_or_default()
+}
+
+/// Layer keeping the last [`RECENT_LOG_LINES`] log lines in memory
+struct RecentLogLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = LogLineVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let line = RecentLogLine {
+            level: *metadata.level(),
+            line: format!(
+                "{} {:>5} {}: {}{}",
+                db_now(),
+                metadata.level(),
+                metadata.target(),
+                visitor.message,
+                visitor.fields
+            ),
+        };
+
+        let logs = RECENT_LOGS.get_or_init(|| Mutex::new(VecDeque::with_capacity(RECENT_LOG_LINES)));
+        if let Ok(mut logs) = logs.lock() {
+            if logs.len() == RECENT_LOG_LINES {
+                logs.pop_front();
+            }
+            logs.push_back(line);
+        }
+    }
+}
+
+/// Formats an event's message followed by its other fields
+#[derive(Default)]
+struct LogLineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LogLineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_tuning_settings() {
+        let config = AppConfig::from_env().unwrap();
+        assert!(config.validate().is_ok());
+
+        let mut invalid = config.clone();
+        invalid.server_workers = 0;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.max_payload_bytes = MIN_PAYLOAD_BYTES - 1;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.leader_lease_secs = MIN_LEADER_LEASE_SECS - 1;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.node_failure_threshold = MIN_NODE_FAILURE_THRESHOLD - 1;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.database_max_connections = 0;
+        invalid.database_min_connections = 0;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config;
+        invalid.database_min_connections = invalid.database_max_connections + 1;
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Configuration Reloading
+//!
+//! The running configuration is published through a watch channel. A reload,
+//! triggered by SIGHUP or the admin API, publishes new values of the
+//! [`RELOADABLE_SETTINGS`] to it. Subsystems that can change these settings
+//! on the fly subscribe to the channel; everything else keeps the values the
+//! process was started with.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use super::{set_log_level, AppConfig, RELOADABLE_SETTINGS};
+use crate::error::AppError;
+use crate::models::config_session::FreezeWindow;
+
+/// Outcome of a configuration reload
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReloadResult {
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub reloaded_at: DateTime<Utc>,
+    /// Changed settings that took effect
+    pub applied: Vec<&'static str>,
+    /// Changed settings that only take effect after a restart
+    pub requires_restart: Vec<&'static str>,
+}
+
+/// Values of the reloadable settings currently in effect
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeSettings {
+    pub log_level: String,
+    pub cors_allowed_origins: Vec<String>,
+    pub incident_sync_interval_secs: u64,
+    pub feature_flags: BTreeSet<String>,
+    pub change_freeze_windows: Vec<FreezeWindow>,
+}
+
+impl From<&AppConfig> for RuntimeSettings {
+    fn from(config: &AppConfig) -> Self {
+        Self {
+            log_level: config.log_level.clone(),
+            cors_allowed_origins: config.cors_allowed_origins.clone(),
+            incident_sync_interval_secs: config.incident_sync_interval_secs,
+            feature_flags: config.feature_flags.clone(),
+            change_freeze_windows: config.change_freeze_windows.clone(),
+        }
+    }
+}
+
+/// Publishes the running configuration to subscribed subsystems
+#[derive(Clone)]
+pub struct ConfigReloader {
+    sender: Arc<watch::Sender<Arc<AppConfig>>>,
+}
+
+impl ConfigReloader {
+    /// Create a reloader publishing the startup configuration
+    pub fn new(config: AppConfig) -> Self {
+        let (sender, _) = watch::channel(Arc::new(config));
+        Self { sender: Arc::new(sender) }
+    }
+
+    /// Configuration currently in effect
+    pub fn current(&self) -> Arc<AppConfig> {
+        self.sender.borrow().clone()
+    }
+
+    /// Receiver that is notified whenever reloadable settings change
+    pub fn subscribe(&self) -> watch::Receiver<Arc<AppConfig>> {
+        self.sender.subscribe()
+    }
+
+    /// Reload the configuration from the environment and the `.env` file
+    ///
+    /// Secrets loaded from secret stores are not fetched again.
+    pub fn reload(&self) -> Result<ConfigReloadResult, AppError> {
+        let mut config = AppConfig::reload_from_env()?;
+        config.keep_resolved_secrets(&self.current());
+        self.apply(config)
+    }
+
+    /// Apply the reloadable settings of a newly loaded configuration
+    pub fn apply(&self, config: AppConfig) -> Result<ConfigReloadResult, AppError> {
+        let mut outcome = Ok((Vec::new(), Vec::new()));
+        self.sender.send_if_modified(|current| {
+            let (applied, requires_restart): (Vec<_>, Vec<_>) = changed_settings(current, &config)
+                .into_iter()
+                .partition(|setting| RELOADABLE_SETTINGS.contains(setting));
+
+            if applied.contains(&"log_level") {
+                if let Err(e) = set_log_level(&config.log_level) {
+                    outcome = Err(e);
+                    return false;
+                }
+            }
+
+            let modified = !applied.is_empty();
+            if modified {
+                let mut next = AppConfig::clone(current);
+                next.log_level = config.log_level.clone();
+                next.cors_allowed_origins = config.cors_allowed_origins.clone();
+                next.incident_sync_interval_secs = config.incident_sync_interval_secs;
+                next.feature_flags = config.feature_flags.clone();
+                next.change_freeze_windows = config.change_freeze_windows.clone();
+                *current = Arc::new(next);
+            }
+            outcome = Ok((applied, requires_restart));
+            modified
+        });
+
+        let (applied, requires_restart) = outcome?;
+        info!("Configuration reloaded, applied changes: {:?}", applied);
+        if !requires_restart.is_empty() {
+            warn!("Configuration changes that require a restart: {:?}", requires_restart);
+        }
+
+        Ok(ConfigReloadResult {
+            reloaded_at: Utc::now(),
+            applied,
+            requires_restart,
+        })
+    }
+}
+
+/// Names of the settings that differ between two configurations
+fn changed_settings(old: &AppConfig, new: &AppConfig) -> Vec<&'static str> {
+    let mut changed = Vec::new();
+    macro_rules! compare {
+        ($($field:ident),* $(,)?) => {
+            $(
+                if old.$field != new.$field {
+                    changed.push(stringify!($field));
+                }
+            )*
+        };
+    }
+
+    compare!(
+        server_host,
+        server_port,
+        server_workers,
+        server_keep_alive_secs,
+        max_payload_bytes,
+        upload_dir,
+        max_upload_bytes,
+        max_attachment_bytes,
+        app_env,
+        database_url,
+        database_max_connections,
+        database_min_connections,
+        database_slow_query_ms,
+        database_auto_migrate,
+        backup_dir,
+        storage_cap_bytes,
+        storage_warning_percent,
+        artifact_storage,
+        artifact_s3_endpoint,
+        artifact_s3_bucket,
+        artifact_s3_region,
+        artifact_s3_access_key_id,
+        artifact_s3_secret_access_key,
+        artifact_s3_path_style,
+        artifact_s3_part_size_bytes,
+        instance_id,
+        leader_lease_secs,
+        jwt_secret_key,
+        jwt_expiration_minutes,
+        refresh_token_expiration_days,
+        legacy_user_ids_sunset,
+        node_encryption_key,
+        node_failure_threshold,
+        node_retry_after_secs,
+        node_health_check_interval_secs,
+        node_clock_drift_threshold_secs,
+        node_reboot_grace_secs,
+        show_cache_ttl_secs,
+        show_cache_command_ttls,
+        metrics_collection_interval_secs,
+        websocket_queue_capacity,
+        notification_interval_secs,
+        oui_database_url,
+        geoip_database_path,
+        geoip_asn_database_path,
+        syslog_listen_addr,
+        chaos_enabled,
+        load_profile_enabled,
+        log_level,
+        vyos_api_url,
+        vyos_api_username,
+        vyos_api_password,
+        git_export_repo_path,
+        git_export_remote,
+        git_export_branch,
+        config_lint_disabled_rules,
+        config_secret_paths,
+        config_secrets_key,
+        default_team_quota,
+        client_error_rate_limit,
+        config_backup_interval_secs,
+        config_backup_keep_last,
+        config_backup_keep_daily_days,
+        rate_limit_auth_per_minute,
+        rate_limit_node_per_minute,
+        trusted_proxies,
+        smtp_host,
+        smtp_port,
+        smtp_username,
+        smtp_password,
+        smtp_from,
+        smtp_starttls,
+        cors_allowed_origins,
+        incident_sync_interval_secs,
+        feature_flags,
+        change_freeze_windows,
+    );
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_publishes_reloadable_settings_only() {
+        let config = AppConfig::from_env().unwrap();
+        let reloader = ConfigReloader::new(config.clone());
+        let mut receiver = reloader.subscribe();
+
+        let mut changed = config.clone();
+        changed.cors_allowed_origins = vec!["https://ops.example.com".to_string()];
+        changed.feature_flags.insert("beta-dashboard".to_string());
+        changed.server_port = config.server_port.wrapping_add(1);
+
+        let result = reloader.apply(changed).unwrap();
+        assert_eq!(result.applied, vec!["cors_allowed_origins", "feature_flags"]);
+        assert_eq!(result.requires_restart, vec!["server_port"]);
+        assert!(receiver.has_changed().unwrap());
+
+        let current = receiver.borrow_and_update().clone();
+        assert_eq!(current.cors_allowed_origins, vec!["https://ops.example.com".to_string()]);
+        assert!(current.feature_enabled("beta-dashboard"));
+        assert_eq!(current.server_port, config.server_port);
+
+        // Nothing reloadable changed, so subscribers are not woken up
+        let mut restart_only = AppConfig::clone(&current);
+        restart_only.smtp_from = "Ops <ops@example.com>".to_string();
+        let result = reloader.apply(restart_only).unwrap();
+        assert!(result.applied.is_empty());
+        assert_eq!(result.requires_restart, vec!["smtp_from"]);
+        assert!(!receiver.has_changed().unwrap());
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_log_level() {
+        let config = AppConfig::from_env().unwrap();
+        let reloader = ConfigReloader::new(config.clone());
+
+        let mut changed = config;
+        changed.log_level = "verbose=[".to_string();
+        assert!(matches!(reloader.apply(changed), Err(AppError::Validation(_))));
+        assert_ne!(reloader.current().log_level, "verbose=[");
+    }
+}
@@ -0,0 +1,638 @@
+//! Secret Providers
+//!
+//! Secret settings such as `JWT_SECRET_KEY` and `NODE_ENCRYPTION_KEY` can
+//! name a secret in an external store instead of holding the value itself.
+//! A value of the form `<scheme>:<reference>` is fetched from the
+//! [`SecretProvider`] registered for the scheme when the server starts:
+//!
+//! - `file:/run/secrets/jwt` reads a file, e.g. a Docker or Kubernetes secret
+//! - `vault:secret/data/vyos-webui#jwt_secret` reads a HashiCorp Vault KV
+//!   secret (`VAULT_ADDR`, `VAULT_TOKEN`)
+//! - `aws-sm:vyos-webui/jwt#key` reads an AWS Secrets Manager secret
+//!   (`AWS_REGION`, `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`,
+//!   `AWS_SESSION_TOKEN`)
+//! - `gcp-sm:projects/p/secrets/jwt#key` reads a Google Cloud Secret Manager
+//!   secret with `GOOGLE_OAUTH_ACCESS_TOKEN` or the instance's service
+//!   account
+//!
+//! The part after `#` selects a key when the secret is a JSON object; Vault
+//! secrets always need one. Values without a known scheme are used as they
+//! are.
+
+use std::env;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64ct::{Base64, Encoding};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use super::AppConfig;
+use crate::error::AppError;
+
+/// Settings that may name a stored secret
+pub const SECRET_SETTINGS: &[&str] = &[
+    "jwt_secret_key",
+    "node_encryption_key",
+    "config_secrets_key",
+    "artifact_s3_secret_access_key",
+];
+
+/// Timeout of requests to secret stores
+const SECRET_STORE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Source of secret values
+#[async_trait]
+pub trait SecretProvider: Send + Sync {
+    /// Prefix, without the colon, of the values this provider resolves
+    fn scheme(&self) -> &'static str;
+
+    /// Fetch the secret a reference (the value after the scheme) points to
+    async fn fetch(&self, reference: &str) -> Result<String, AppError>;
+}
+
+/// Resolves secret settings through the registered providers
+pub struct SecretResolver {
+    providers: Vec<Box<dyn SecretProvider>>,
+}
+
+impl SecretResolver {
+    /// Resolver without any providers
+    pub fn new() -> Self {
+        Self { providers: Vec::new() }
+    }
+
+    /// Resolver with the file, Vault, AWS, and Google Cloud providers,
+    /// configured from the environment
+    pub fn from_env() -> Result<Self, AppError> {
+        let client = Client::builder()
+            .timeout(SECRET_STORE_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Config(format!("Failed to create secret store client: {}", e)))?;
+
+        Ok(Self::new()
+            .with_provider(FileSecretProvider)
+            .with_provider(VaultSecretProvider::from_env(client.clone()))
+            .with_provider(AwsSecretsManagerProvider::from_env(client.clone()))
+            .with_provider(GcpSecretManagerProvider::from_env(client)))
+    }
+
+    /// Register a provider, replacing any provider with the same scheme
+    pub fn with_provider(mut self, provider: impl SecretProvider + 'static) -> Self {
+        self.providers.retain(|existing| existing.scheme() != provider.scheme());
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Provider and reference for a value naming a stored secret
+    fn provider_for<'a>(&self, value: &'a str) -> Option<(&dyn SecretProvider, &'a str)> {
+        let (scheme, reference) = value.split_once(':')?;
+        self.providers
+            .iter()
+            .find(|provider| provider.scheme() == scheme)
+            .map(|provider| (provider.as_ref(), reference))
+    }
+
+    /// Whether a value names a stored secret
+    pub fn is_reference(&self, value: &str) -> bool {
+        self.provider_for(value).is_some()
+    }
+
+    /// The secret a value names, or the value itself
+    pub async fn resolve(&self, value: &str) -> Result<String, AppError> {
+        match self.provider_for(value) {
+            Some((provider, reference)) => provider.fetch(reference).await,
+            None => Ok(value.to_string()),
+        }
+    }
+}
+
+impl Default for SecretResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AppConfig {
+    /// Replace references to stored secrets with the secrets
+    ///
+    /// The references are kept in `secret_references`, so a reloaded
+    /// configuration can tell whether they changed.
+    pub async fn resolve_secrets(&mut self, resolver: &SecretResolver) -> Result<(), AppError> {
+        for setting in SECRET_SETTINGS {
+            let Some(reference) = self.secret_setting(setting).filter(|value| resolver.is_reference(value)).cloned()
+            else {
+                continue;
+            };
+            let secret = resolver
+                .resolve(&reference)
+                .await
+                .map_err(|e| AppError::Config(format!("Failed to load {}: {}", setting, e)))?;
+            info!("Loaded {} from a {} secret", setting, reference.split(':').next().unwrap_or_default());
+            self.set_secret_setting(setting, secret);
+            self.secret_references.insert(setting.to_string(), reference);
+        }
+        Ok(())
+    }
+
+    /// Reuse the secrets `current` resolved where the references did not
+    /// change
+    ///
+    /// Secrets are only fetched at startup; a reload keeps them and reports
+    /// changed references as requiring a restart.
+    pub fn keep_resolved_secrets(&mut self, current: &AppConfig) {
+        for (setting, reference) in &current.secret_references {
+            if self.secret_setting(setting) == Some(reference) {
+                if let Some(secret) = current.secret_setting(setting).cloned() {
+                    self.set_secret_setting(setting, secret);
+                }
+            }
+        }
+        self.secret_references = current.secret_references.clone();
+    }
+
+    fn secret_setting(&self, setting: &str) -> Option<&String> {
+        match setting {
+            "jwt_secret_key" => Some(&self.jwt_secret_key),
+            "node_encryption_key" => self.node_encryption_key.as_ref(),
+            "config_secrets_key" => self.config_secrets_key.as_ref(),
+            "artifact_s3_secret_access_key" => self.artifact_s3_secret_access_key.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn set_secret_setting(&mut self, setting: &str, value: String) {
+        match setting {
+            "jwt_secret_key" => self.jwt_secret_key = value,
+            "node_encryption_key" => self.node_encryption_key = Some(value),
+            "config_secrets_key" => self.config_secrets_key = Some(value),
+            "artifact_s3_secret_access_key" => self.artifact_s3_secret_access_key = Some(value),
+            _ => {}
+        }
+    }
+}
+
+/// Reads secrets from files
+pub struct FileSecretProvider;
+
+#[async_trait]
+impl SecretProvider for FileSecretProvider {
+    fn scheme(&self) -> &'static str {
+        "file"
+    }
+
+    async fn fetch(&self, reference: &str) -> Result<String, AppError> {
+        let content = tokio::fs::read_to_string(reference)
+            .await
+            .map_err(|e| AppError::Config(format!("Failed to read secret file {}: {}", reference, e)))?;
+        Ok(content.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// Reads secrets from HashiCorp Vault's KV secrets engine
+///
+/// References are API paths below `/v1/` followed by `#key`, e.g.
+/// `secret/data/vyos-webui#jwt_secret` for KV version 2.
+pub struct VaultSecretProvider {
+    client: Client,
+    address: Option<String>,
+    token: Option<String>,
+}
+
+impl VaultSecretProvider {
+    /// Provider for the Vault server at `address`
+    pub fn new(client: Client, address: Option<String>, token: Option<String>) -> Self {
+        Self { client, address, token }
+    }
+
+    /// Provider configured with `VAULT_ADDR` and `VAULT_TOKEN`
+    pub fn from_env(client: Client) -> Self {
+        Self::new(client, env::var("VAULT_ADDR").ok(), env::var("VAULT_TOKEN").ok())
+    }
+}
+
+#[async_trait]
+impl SecretProvider for VaultSecretProvider {
+    fn scheme(&self) -> &'static str {
+        "vault"
+    }
+
+    async fn fetch(&self, reference: &str) -> Result<String, AppError> {
+        let (Some(address), Some(token)) = (&self.address, &self.token) else {
+            return Err(AppError::Config("VAULT_ADDR and VAULT_TOKEN must be set".to_string()));
+        };
+        let (path, key) = split_key(reference);
+        let key = key.ok_or_else(|| AppError::Config(format!("Vault reference {} needs a #key", reference)))?;
+
+        let response: Value = self
+            .client
+            .get(format!("{}/v1/{}", address.trim_end_matches('/'), path.trim_start_matches('/')))
+            .header("X-Vault-Token", token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // KV version 2 nests the secret in a second `data` object
+        let data = match response["data"].get("data") {
+            Some(data) if data.is_object() => data,
+            _ => &response["data"],
+        };
+        json_string(data, key)
+    }
+}
+
+/// Reads secrets from AWS Secrets Manager
+///
+/// References are secret names or ARNs, optionally followed by `#key`.
+pub struct AwsSecretsManagerProvider {
+    client: Client,
+    region: Option<String>,
+    credentials: Option<AwsCredentials>,
+    /// Overrides the regional endpoint, e.g. for VPC endpoints
+    endpoint: Option<String>,
+}
+
+/// Static AWS credentials
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl AwsSecretsManagerProvider {
+    /// Provider for a region
+    pub fn new(client: Client, region: Option<String>, credentials: Option<AwsCredentials>) -> Self {
+        Self { client, region, credentials, endpoint: None }
+    }
+
+    /// Send requests to `endpoint` instead of the regional endpoint
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Provider configured with the standard AWS environment variables
+    pub fn from_env(client: Client) -> Self {
+        let region = env::var("AWS_REGION").or_else(|_| env::var("AWS_DEFAULT_REGION")).ok();
+        let credentials = match (env::var("AWS_ACCESS_KEY_ID"), env::var("AWS_SECRET_ACCESS_KEY")) {
+            (Ok(access_key_id), Ok(secret_access_key)) => Some(AwsCredentials {
+                access_key_id,
+                secret_access_key,
+                session_token: env::var("AWS_SESSION_TOKEN").ok(),
+            }),
+            _ => None,
+        };
+        let provider = Self::new(client, region, credentials);
+        match env::var("AWS_ENDPOINT_URL_SECRETS_MANAGER") {
+            Ok(endpoint) => provider.with_endpoint(endpoint),
+            Err(_) => provider,
+        }
+    }
+}
+
+#[async_trait]
+impl SecretProvider for AwsSecretsManagerProvider {
+    fn scheme(&self) -> &'static str {
+        "aws-sm"
+    }
+
+    async fn fetch(&self, reference: &str) -> Result<String, AppError> {
+        let (Some(region), Some(credentials)) = (&self.region, &self.credentials) else {
+            return Err(AppError::Config(
+                "AWS_REGION, AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY must be set".to_string(),
+            ));
+        };
+        let (secret_id, key) = split_key(reference);
+
+        let endpoint = self
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://secretsmanager.{}.amazonaws.com", region));
+        let host = endpoint.split("://").last().unwrap_or_default().trim_end_matches('/').to_string();
+        let body = serde_json::json!({ "SecretId": secret_id }).to_string();
+
+        let mut request = AwsRequest {
+            host,
+            target: "secretsmanager.GetSecretValue",
+            body: &body,
+            headers: Vec::new(),
+        };
+        request.sign(credentials, region, "secretsmanager", Utc::now());
+
+        let mut builder = self.client.post(endpoint.trim_end_matches('/').to_string() + "/").body(body.clone());
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        let response: Value = builder.send().await?.error_for_status()?.json().await?;
+
+        let secret = response["SecretString"]
+            .as_str()
+            .ok_or_else(|| AppError::Config(format!("Secret {} has no string value", secret_id)))?;
+        select_key(secret, key)
+    }
+}
+
+/// AWS JSON protocol request signed with Signature Version 4
+struct AwsRequest<'a> {
+    host: String,
+    target: &'static str,
+    body: &'a str,
+    /// Headers to send, including the signature
+    headers: Vec<(&'static str, String)>,
+}
+
+impl AwsRequest<'_> {
+    fn sign(&mut self, credentials: &AwsCredentials, region: &str, service: &str, now: DateTime<Utc>) {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+
+        // Canonical headers must be sorted by name
+        let mut headers = vec![
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("host", self.host.clone()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        headers.push(("x-amz-target", self.target.to_string()));
+
+        let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+        let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+        let canonical_request = format!(
+            "POST\n/\n\n{}\n{}\n{:x}",
+            canonical_headers,
+            signed_headers,
+            Sha256::digest(self.body.as_bytes())
+        );
+        let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+            amz_date,
+            scope,
+            Sha256::digest(canonical_request.as_bytes())
+        );
+        let signature = aws_signature(&credentials.secret_access_key, &date, region, service, &string_to_sign);
+
+        headers.retain(|(name, _)| *name != "host");
+        headers.push((
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                credentials.access_key_id, scope, signed_headers, signature
+            ),
+        ));
+        self.headers = headers;
+    }
+}
+
+/// Hex-encoded Signature Version 4 of a string to sign
+pub(crate) fn aws_signature(secret_access_key: &str, date: &str, region: &str, service: &str, string_to_sign: &str) -> String {
+    let key = [date, region, service, "aws4_request"]
+        .iter()
+        .fold(format!("AWS4{}", secret_access_key).into_bytes(), |key, part| hmac_sha256(&key, part));
+    hmac_sha256(&key, string_to_sign).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Reads secrets from Google Cloud Secret Manager
+///
+/// References are secret resource names, optionally with a version
+/// (default: latest) and followed by `#key`.
+pub struct GcpSecretManagerProvider {
+    client: Client,
+    access_token: Option<String>,
+    endpoint: String,
+    metadata_endpoint: String,
+}
+
+impl GcpSecretManagerProvider {
+    /// Provider authenticating with `access_token`, or with the instance's
+    /// service account when unset
+    pub fn new(client: Client, access_token: Option<String>) -> Self {
+        Self {
+            client,
+            access_token,
+            endpoint: "https://secretmanager.googleapis.com".to_string(),
+            metadata_endpoint: "http://metadata.google.internal".to_string(),
+        }
+    }
+
+    /// Send requests to other Secret Manager and metadata server endpoints
+    pub fn with_endpoints(mut self, endpoint: impl Into<String>, metadata_endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self.metadata_endpoint = metadata_endpoint.into();
+        self
+    }
+
+    /// Provider configured with `GOOGLE_OAUTH_ACCESS_TOKEN`
+    pub fn from_env(client: Client) -> Self {
+        Self::new(client, env::var("GOOGLE_OAUTH_ACCESS_TOKEN").ok())
+    }
+
+    async fn access_token(&self) -> Result<String, AppError> {
+        if let Some(token) = &self.access_token {
+            return Ok(token.clone());
+        }
+
+        let response: Value = self
+            .client
+            .get(format!(
+                "{}/computeMetadata/v1/instance/service-accounts/default/token",
+                self.metadata_endpoint
+            ))
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        json_string(&response, "access_token")
+    }
+}
+
+#[async_trait]
+impl SecretProvider for GcpSecretManagerProvider {
+    fn scheme(&self) -> &'static str {
+        "gcp-sm"
+    }
+
+    async fn fetch(&self, reference: &str) -> Result<String, AppError> {
+        let (name, key) = split_key(reference);
+        let name = name.trim_matches('/');
+        let version = if name.contains("/versions/") {
+            name.to_string()
+        } else {
+            format!("{}/versions/latest", name)
+        };
+
+        let response: Value = self
+            .client
+            .get(format!("{}/v1/{}:access", self.endpoint, version))
+            .bearer_auth(self.access_token().await?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let payload = json_string(&response["payload"], "data")?;
+        let secret = Base64::decode_vec(&payload)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| AppError::Config(format!("Secret {} is not UTF-8 text", name)))?;
+        select_key(&secret, key)
+    }
+}
+
+/// Split `path#key` into the path and the key
+fn split_key(reference: &str) -> (&str, Option<&str>) {
+    match reference.rsplit_once('#') {
+        Some((path, key)) if !key.is_empty() => (path, Some(key)),
+        _ => (reference, None),
+    }
+}
+
+/// A secret, or one key of a secret holding a JSON object
+fn select_key(secret: &str, key: Option<&str>) -> Result<String, AppError> {
+    match key {
+        Some(key) => {
+            let object: Value = serde_json::from_str(secret)
+                .map_err(|_| AppError::Config(format!("Secret is not a JSON object with key {}", key)))?;
+            json_string(&object, key)
+        }
+        None => Ok(secret.to_string()),
+    }
+}
+
+fn json_string(object: &Value, key: &str) -> Result<String, AppError> {
+    object[key]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::Config(format!("Secret has no string value for key {}", key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Provider returning fixed values, for resolution tests
+    struct StaticProvider;
+
+    #[async_trait]
+    impl SecretProvider for StaticProvider {
+        fn scheme(&self) -> &'static str {
+            "static"
+        }
+
+        async fn fetch(&self, reference: &str) -> Result<String, AppError> {
+            Ok(format!("secret-for-{}", reference))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_secrets_keeps_references() {
+        let resolver = SecretResolver::new().with_provider(StaticProvider);
+        let mut config = AppConfig::from_env().unwrap();
+        config.jwt_secret_key = "static:jwt".to_string();
+        config.node_encryption_key = Some("plain-key".to_string());
+
+        config.resolve_secrets(&resolver).await.unwrap();
+        assert_eq!(config.jwt_secret_key, "secret-for-jwt");
+        assert_eq!(config.node_encryption_key.as_deref(), Some("plain-key"));
+        assert_eq!(config.secret_references.get("jwt_secret_key").map(String::as_str), Some("static:jwt"));
+
+        // A reload sees the reference again and keeps the resolved secret
+        let mut reloaded = config.clone();
+        reloaded.jwt_secret_key = "static:jwt".to_string();
+        reloaded.secret_references.clear();
+        reloaded.keep_resolved_secrets(&config);
+        assert_eq!(reloaded.jwt_secret_key, "secret-for-jwt");
+    }
+
+    #[tokio::test]
+    async fn test_file_secret_provider() {
+        let path = std::env::temp_dir().join(format!("vyos-webui-secret-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "s3cret\n").unwrap();
+
+        let resolver = SecretResolver::new().with_provider(FileSecretProvider);
+        let value = format!("file:{}", path.display());
+        assert_eq!(resolver.resolve(&value).await.unwrap(), "s3cret");
+        assert_eq!(resolver.resolve("not-a-reference").await.unwrap(), "not-a-reference");
+        assert!(resolver.resolve("file:/nonexistent/secret").await.is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_vault_and_gcp_secret_providers() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/vyos-webui"))
+            .and(header("X-Vault-Token", "vault-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "data": { "jwt_secret_key": "from-vault" }, "metadata": { "version": 3 } }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/computeMetadata/v1/instance/service-accounts/default/token"))
+            .and(header("Metadata-Flavor", "Google"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "access_token": "gcp-token" })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/ops/secrets/jwt/versions/latest:access"))
+            .and(header("Authorization", "Bearer gcp-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "payload": { "data": Base64::encode_string(b"from-gcp") }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::new();
+        let resolver = SecretResolver::new()
+            .with_provider(VaultSecretProvider::new(client.clone(), Some(server.uri()), Some("vault-token".to_string())))
+            .with_provider(GcpSecretManagerProvider::new(client, None).with_endpoints(server.uri(), server.uri()));
+
+        assert_eq!(resolver.resolve("vault:secret/data/vyos-webui#jwt_secret_key").await.unwrap(), "from-vault");
+        assert!(resolver.resolve("vault:secret/data/vyos-webui").await.is_err());
+        assert!(resolver.resolve("vault:secret/data/vyos-webui#missing").await.is_err());
+        assert_eq!(resolver.resolve("gcp-sm:projects/ops/secrets/jwt").await.unwrap(), "from-gcp");
+    }
+
+    #[test]
+    fn test_select_key() {
+        assert_eq!(select_key("plain", None).unwrap(), "plain");
+        assert_eq!(select_key(r#"{"jwt":"abc"}"#, Some("jwt")).unwrap(), "abc");
+        assert!(select_key("plain", Some("jwt")).is_err());
+        assert_eq!(split_key("secret/data/app#jwt"), ("secret/data/app", Some("jwt")));
+        assert_eq!(split_key("app"), ("app", None));
+    }
+
+    #[test]
+    fn test_aws_signature() {
+        // Example from the AWS Signature Version 4 documentation
+        let string_to_sign = "AWS4-HMAC-SHA256\n20150830T123600Z\n20150830/us-east-1/iam/aws4_request\n\
+            f536975d06c0309214f805bb90ccff089219ecd68b2577efef23edd43b7e1a59";
+        assert_eq!(
+            aws_signature("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20150830", "us-east-1", "iam", string_to_sign),
+            "5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+}
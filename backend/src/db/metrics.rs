@@ -0,0 +1,155 @@
+//! Query metrics
+//!
+//! Repository methods time themselves with [`Database::query_timer`]. The
+//! durations are kept as a histogram per method, rendered in the Prometheus
+//! text format on `/metrics`, and queries slower than `DATABASE_SLOW_QUERY_MS`
+//! are logged.
+//!
+//! [`Database::query_timer`]: super::Database::query_timer
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+/// Upper bounds of the histogram buckets, in seconds
+const BUCKETS: [f64; 12] = [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// Slow query threshold until the configured one is set
+pub const DEFAULT_SLOW_QUERY_MS: u64 = 500;
+
+/// Durations of one query
+#[derive(Debug, Default, Clone)]
+struct QueryHistogram {
+    /// Observations per bucket, not cumulative; the last counts the
+    /// observations above every bound
+    buckets: [u64; BUCKETS.len() + 1],
+    count: u64,
+    sum_secs: f64,
+    slow: u64,
+}
+
+/// Timing histograms of the repository queries
+#[derive(Debug)]
+pub struct QueryMetrics {
+    queries: Mutex<BTreeMap<&'static str, QueryHistogram>>,
+    slow_threshold_ms: AtomicU64,
+}
+
+impl Default for QueryMetrics {
+    fn default() -> Self {
+        Self {
+            queries: Mutex::default(),
+            slow_threshold_ms: AtomicU64::new(DEFAULT_SLOW_QUERY_MS),
+        }
+    }
+}
+
+impl QueryMetrics {
+    /// Set the duration above which queries are logged as slow
+    pub fn set_slow_threshold(&self, threshold: Duration) {
+        self.slow_threshold_ms
+            .store(threshold.as_millis().try_into().unwrap_or(u64::MAX), Ordering::Relaxed);
+    }
+
+    /// Record one execution of a query
+    pub fn observe(&self, query: &'static str, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        let slow = elapsed.as_millis() >= u128::from(self.slow_threshold_ms.load(Ordering::Relaxed));
+        if slow {
+            warn!("Slow query {} took {} ms", query, elapsed.as_millis());
+        }
+
+        let mut queries = self.queries.lock().unwrap_or_else(|e| e.into_inner());
+        let histogram = queries.entry(query).or_default();
+        let bucket = BUCKETS.iter().position(|bound| secs <= *bound).unwrap_or(BUCKETS.len());
+        histogram.buckets[bucket] += 1;
+        histogram.count += 1;
+        histogram.sum_secs += secs;
+        if slow {
+            histogram.slow += 1;
+        }
+    }
+
+    /// Render the histograms in the Prometheus text exposition format
+    pub fn render_prometheus(&self, out: &mut String) {
+        let queries = self.queries.lock().unwrap_or_else(|e| e.into_inner()).clone();
+
+        out.push_str("# HELP vyos_webui_db_query_duration_seconds Duration of database queries by repository method\n");
+        out.push_str("# TYPE vyos_webui_db_query_duration_seconds histogram\n");
+        for (query, histogram) in &queries {
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "vyos_webui_db_query_duration_seconds_bucket{{query=\"{}\",le=\"{}\"}} {}",
+                    query, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "vyos_webui_db_query_duration_seconds_bucket{{query=\"{}\",le=\"+Inf\"}} {}",
+                query, histogram.count
+            );
+            let _ = writeln!(out, "vyos_webui_db_query_duration_seconds_sum{{query=\"{}\"}} {}", query, histogram.sum_secs);
+            let _ = writeln!(out, "vyos_webui_db_query_duration_seconds_count{{query=\"{}\"}} {}", query, histogram.count);
+        }
+
+        out.push_str("# HELP vyos_webui_db_slow_queries_total Database queries slower than DATABASE_SLOW_QUERY_MS\n");
+        out.push_str("# TYPE vyos_webui_db_slow_queries_total counter\n");
+        for (query, histogram) in &queries {
+            let _ = writeln!(out, "vyos_webui_db_slow_queries_total{{query=\"{}\"}} {}", query, histogram.slow);
+        }
+    }
+}
+
+/// Times a query until dropped
+#[must_use = "the query is timed until the timer is dropped"]
+pub struct QueryTimer<'a> {
+    metrics: &'a QueryMetrics,
+    query: &'static str,
+    start: Instant,
+}
+
+impl<'a> QueryTimer<'a> {
+    pub(super) fn new(metrics: &'a QueryMetrics, query: &'static str) -> Self {
+        Self {
+            metrics,
+            query,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for QueryTimer<'_> {
+    fn drop(&mut self) {
+        self.metrics.observe(self.query, self.start.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_cumulative_buckets() {
+        let metrics = QueryMetrics::default();
+        metrics.set_slow_threshold(Duration::from_millis(100));
+        metrics.observe("list_users", Duration::from_micros(500));
+        metrics.observe("list_users", Duration::from_millis(20));
+        metrics.observe("list_users", Duration::from_secs(10));
+
+        let mut out = String::new();
+        metrics.render_prometheus(&mut out);
+        assert!(out.contains("vyos_webui_db_query_duration_seconds_bucket{query=\"list_users\",le=\"0.001\"} 1\n"));
+        assert!(out.contains("vyos_webui_db_query_duration_seconds_bucket{query=\"list_users\",le=\"0.025\"} 2\n"));
+        assert!(out.contains("vyos_webui_db_query_duration_seconds_bucket{query=\"list_users\",le=\"5\"} 2\n"));
+        assert!(out.contains("vyos_webui_db_query_duration_seconds_bucket{query=\"list_users\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("vyos_webui_db_query_duration_seconds_count{query=\"list_users\"} 3\n"));
+        assert!(out.contains("vyos_webui_db_slow_queries_total{query=\"list_users\"} 1\n"));
+    }
+}
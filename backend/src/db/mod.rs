@@ -1,20 +1,40 @@
 use actix_web::web::Data;
+use sqlx::migrate::{Migrate, Migrator};
 use sqlx::SqlitePool;
-use tracing::info;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
 
 use crate::error::AppError;
+use crate::models::config::{ConfigChangeRecord, ConfigHistoryRecord};
+use crate::models::quota::TeamQuota;
+use crate::models::team::{TeamMemberRecord, TeamRecord};
+use crate::models::timestamp::db_now;
 use crate::models::user::{UserRecord, UserListQuery, UserRole, UserStatus};
 
+pub mod metrics;
+
+pub use metrics::{QueryMetrics, QueryTimer};
+
+/// Schema migrations, embedded from `migrations/sqlite` at build time
+///
+/// `migrations/mysql` holds the same migrations for MySQL.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
 /// Database connection pool wrapper
 #[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
+    metrics: Arc<QueryMetrics>,
 }
 
 impl Database {
     /// Create a new database instance from a connection pool
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            metrics: Arc::default(),
+        }
     }
 
     /// Get reference to the connection pool
@@ -22,78 +42,129 @@ impl Database {
         &self.pool
     }
 
-    /// Initialize the database schema
-    pub async fn init_schema(&self) -> Result<(), AppError> {
-        info!("Initializing database schema...");
-
-        // Check if the users table already exists
-        let table_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name='users')"
-        )
-        .fetch_one(self.pool())
-        .await?;
+    /// Timing histograms of the queries run through this database
+    pub fn query_metrics(&self) -> &QueryMetrics {
+        &self.metrics
+    }
 
-        if table_exists {
-            info!("Database schema already exists, skipping initialization");
-            return Ok(());
-        }
+    /// Time a query, named after the repository method running it, until
+    /// the returned timer is dropped
+    pub fn query_timer(&self, query: &'static str) -> QueryTimer<'_> {
+        QueryTimer::new(&self.metrics, query)
+    }
 
-        // Read and execute the migration file
-        let migration_sql = include_str!("../../migrations/001_initial_schema.sql");
+    /// Run database migrations
+    ///
+    /// Applies every migration in [`MIGRATOR`] not yet recorded in the
+    /// `_sqlx_migrations` table, in order, each in its own transaction.
+    pub async fn run_migrations(&self) -> Result<(), AppError> {
+        info!("Running database migrations...");
 
-        // Execute the entire migration as a batch
-        // SQLite doesn't support multiple statements in a single execute,
-        // so we split by semicolons and execute each statement
-        for statement in migration_sql.split(';') {
-            let statement = statement.trim();
-            if statement.is_empty() || statement.starts_with("--") {
-                continue;
-            }
-            if let Err(e) = sqlx::query(statement).execute(self.pool()).await {
-                // Log but don't fail for certain errors (like PRAGMA statements)
-                if !e.to_string().contains("query returned no rows") {
-                    tracing::warn!("Migration statement warning: {} - Statement: {}", e, statement);
-                }
-            }
-        }
+        self.adopt_legacy_migrations().await?;
+        MIGRATOR
+            .run(self.pool())
+            .await
+            .map_err(|e| AppError::Database(format!("Migration failed: {}", e)))?;
 
-        info!("Database schema initialization complete");
+        info!("Database migrations completed");
 
         Ok(())
     }
 
-    /// Run database migrations
-    pub async fn run_migrations(&self) -> Result<(), AppError> {
-        info!("Running database migrations...");
+    /// Migrations not yet applied, as (version, description)
+    pub async fn pending_migrations(&self) -> Result<Vec<(i64, String)>, AppError> {
+        let applied: Vec<i64> = if self.table_exists("_sqlx_migrations").await? {
+            sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+                .fetch_all(self.pool())
+                .await?
+        } else if self.table_exists("users").await? {
+            self.legacy_applied_versions().await?
+        } else {
+            Vec::new()
+        };
+
+        Ok(MIGRATOR
+            .iter()
+            .filter(|migration| !applied.contains(&migration.version))
+            .map(|migration| (migration.version, migration.description.to_string()))
+            .collect())
+    }
 
-        // In a production setup, you would use sqlx::migrate! here
-        // The schema initialization above handles the initial migration
-        // Future migrations would be tracked in a _migrations table
+    /// Record the migrations applied before migrations were tracked by sqlx
+    ///
+    /// Databases created by earlier releases have the initial schema, which
+    /// was applied untracked, and the later migrations listed in a
+    /// `_migrations` table. They are recorded as applied so that
+    /// [`MIGRATOR`] only runs the ones that are missing.
+    async fn adopt_legacy_migrations(&self) -> Result<(), AppError> {
+        if self.table_exists("_sqlx_migrations").await? || !self.table_exists("users").await? {
+            return Ok(());
+        }
 
-        info!("Database migrations completed");
+        let applied = self.legacy_applied_versions().await?;
+        info!("Adopting {} migrations applied by an earlier release", applied.len());
+
+        let mut conn = self.pool().acquire().await?;
+        conn.ensure_migrations_table()
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to create migrations table: {}", e)))?;
+        for migration in MIGRATOR.iter().filter(|migration| applied.contains(&migration.version)) {
+            sqlx::query(
+                "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+                 VALUES (?, ?, TRUE, ?, 0)",
+            )
+            .bind(migration.version)
+            .bind(migration.description.as_ref())
+            .bind(migration.checksum.as_ref())
+            .execute(&mut *conn)
+            .await?;
+        }
 
         Ok(())
     }
 
+    /// Versions applied to a database created by an earlier release
+    async fn legacy_applied_versions(&self) -> Result<Vec<i64>, AppError> {
+        let mut versions = vec![1];
+        if self.table_exists("_migrations").await? {
+            versions.extend(
+                sqlx::query_scalar::<_, i64>("SELECT version FROM _migrations")
+                    .fetch_all(self.pool())
+                    .await?,
+            );
+        }
+        Ok(versions)
+    }
+
+    async fn table_exists(&self, name: &str) -> Result<bool, AppError> {
+        Ok(
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)")
+                .bind(name)
+                .fetch_one(self.pool())
+                .await?,
+        )
+    }
+
     // ============================================================================
     // User Operations
     // ============================================================================
 
     /// Find a user by username
     pub async fn find_user_by_username(&self, username: &str) -> Result<Option<UserRecord>, AppError> {
+        let _timer = self.query_timer("find_user_by_username");
         let query = r#"
             SELECT id, username, email, password_hash, full_name, is_active, is_superuser,
-                   last_login, created_at, updated_at
+                   timezone, last_login, created_at, updated_at, password_reset_required
             FROM users
             WHERE username = ?
         "#;
 
-        let row = sqlx::query_as::<_, (i64, String, String, String, Option<String>, bool, bool, Option<String>, String, String)>(query)
+        let row = sqlx::query_as::<_, (String, String, String, String, Option<String>, bool, bool, String, Option<String>, String, String, bool)>(query)
             .bind(username)
             .fetch_optional(self.pool())
             .await?;
 
-        Ok(row.map(|(id, username, email, password_hash, full_name, is_active, is_superuser, last_login, created_at, updated_at)| {
+        Ok(row.map(|(id, username, email, password_hash, full_name, is_active, is_superuser, timezone, last_login, created_at, updated_at, password_reset_required)| {
             UserRecord {
                 id,
                 username,
@@ -102,28 +173,31 @@ impl Database {
                 full_name,
                 is_active,
                 is_superuser,
+                timezone,
                 last_login,
                 created_at,
                 updated_at,
+                password_reset_required,
             }
         }))
     }
 
     /// Find a user by email
     pub async fn find_user_by_email(&self, email: &str) -> Result<Option<UserRecord>, AppError> {
+        let _timer = self.query_timer("find_user_by_email");
         let query = r#"
             SELECT id, username, email, password_hash, full_name, is_active, is_superuser,
-                   last_login, created_at, updated_at
+                   timezone, last_login, created_at, updated_at, password_reset_required
             FROM users
             WHERE email = ?
         "#;
 
-        let row = sqlx::query_as::<_, (i64, String, String, String, Option<String>, bool, bool, Option<String>, String, String)>(query)
+        let row = sqlx::query_as::<_, (String, String, String, String, Option<String>, bool, bool, String, Option<String>, String, String, bool)>(query)
             .bind(email)
             .fetch_optional(self.pool())
             .await?;
 
-        Ok(row.map(|(id, username, email, password_hash, full_name, is_active, is_superuser, last_login, created_at, updated_at)| {
+        Ok(row.map(|(id, username, email, password_hash, full_name, is_active, is_superuser, timezone, last_login, created_at, updated_at, password_reset_required)| {
             UserRecord {
                 id,
                 username,
@@ -132,28 +206,31 @@ impl Database {
                 full_name,
                 is_active,
                 is_superuser,
+                timezone,
                 last_login,
                 created_at,
                 updated_at,
+                password_reset_required,
             }
         }))
     }
 
     /// Find a user by ID
-    pub async fn find_user_by_id(&self, user_id: i64) -> Result<Option<UserRecord>, AppError> {
+    pub async fn find_user_by_id(&self, user_id: &str) -> Result<Option<UserRecord>, AppError> {
+        let _timer = self.query_timer("find_user_by_id");
         let query = r#"
             SELECT id, username, email, password_hash, full_name, is_active, is_superuser,
-                   last_login, created_at, updated_at
+                   timezone, last_login, created_at, updated_at, password_reset_required
             FROM users
             WHERE id = ?
         "#;
 
-        let row = sqlx::query_as::<_, (i64, String, String, String, Option<String>, bool, bool, Option<String>, String, String)>(query)
+        let row = sqlx::query_as::<_, (String, String, String, String, Option<String>, bool, bool, String, Option<String>, String, String, bool)>(query)
             .bind(user_id)
             .fetch_optional(self.pool())
             .await?;
 
-        Ok(row.map(|(id, username, email, password_hash, full_name, is_active, is_superuser, last_login, created_at, updated_at)| {
+        Ok(row.map(|(id, username, email, password_hash, full_name, is_active, is_superuser, timezone, last_login, created_at, updated_at, password_reset_required)| {
             UserRecord {
                 id,
                 username,
@@ -162,9 +239,11 @@ impl Database {
                 full_name,
                 is_active,
                 is_superuser,
+                timezone,
                 last_login,
                 created_at,
                 updated_at,
+                password_reset_required,
             }
         }))
     }
@@ -172,35 +251,51 @@ impl Database {
     /// Create a new user
     pub async fn create_user(
         &self,
+        user_id: &str,
         username: &str,
         email: &str,
         password_hash: &str,
         full_name: Option<&str>,
-    ) -> Result<i64, AppError> {
+    ) -> Result<(), AppError> {
+        let _timer = self.query_timer("create_user");
         let query = r#"
-            INSERT INTO users (username, email, password_hash, full_name, is_active, is_superuser)
-            VALUES (?, ?, ?, ?, 1, 0)
-            RETURNING id
+            INSERT INTO users (id, username, email, password_hash, full_name, is_active, is_superuser,
+                               created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, 1, 0, ?, ?)
         "#;
 
-        let id: i64 = sqlx::query_scalar(query)
+        let now = db_now();
+        let mut tx = self.pool().begin().await?;
+        sqlx::query(query)
+            .bind(user_id)
             .bind(username)
             .bind(email)
             .bind(password_hash)
             .bind(full_name.unwrap_or(""))
-            .fetch_one(self.pool())
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
             .await?;
+        sqlx::query("INSERT INTO user_roles (user_id, role_id, created_at) SELECT ?, id, ? FROM roles WHERE name = ?")
+            .bind(user_id)
+            .bind(&now)
+            .bind(UserRole::Viewer.as_str())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
 
-        Ok(id)
+        Ok(())
     }
 
     /// Update a user's profile
     pub async fn update_user_profile(
         &self,
-        user_id: i64,
+        user_id: &str,
         email: Option<&str>,
         full_name: Option<&str>,
+        timezone: Option<&str>,
     ) -> Result<(), AppError> {
+        let _timer = self.query_timer("update_user_profile");
         let mut updates = vec![];
         let mut bind_values: Vec<&str> = vec![];
 
@@ -214,6 +309,11 @@ impl Database {
             bind_values.push(fn_);
         }
 
+        if let Some(tz) = timezone {
+            updates.push("timezone = ?");
+            bind_values.push(tz);
+        }
+
         if updates.is_empty() {
             return Ok(());
         }
@@ -236,13 +336,14 @@ impl Database {
         Ok(())
     }
 
-    /// Update a user's password
+    /// Update a user's password, clearing any required reset
     pub async fn update_user_password(
         &self,
-        user_id: i64,
+        user_id: &str,
         password_hash: &str,
     ) -> Result<(), AppError> {
-        let query = "UPDATE users SET password_hash = ? WHERE id = ?";
+        let _timer = self.query_timer("update_user_password");
+        let query = "UPDATE users SET password_hash = ?, password_reset_required = 0 WHERE id = ?";
         sqlx::query(query)
             .bind(password_hash)
             .bind(user_id)
@@ -253,9 +354,11 @@ impl Database {
     }
 
     /// Update a user's last login timestamp
-    pub async fn update_last_login(&self, user_id: i64) -> Result<(), AppError> {
-        let query = "UPDATE users SET last_login = datetime('now') WHERE id = ?";
+    pub async fn update_last_login(&self, user_id: &str) -> Result<(), AppError> {
+        let _timer = self.query_timer("update_last_login");
+        let query = "UPDATE users SET last_login = ? WHERE id = ?";
         sqlx::query(query)
+            .bind(db_now())
             .bind(user_id)
             .execute(self.pool())
             .await?;
@@ -264,7 +367,8 @@ impl Database {
     }
 
     /// Update a user's status
-    pub async fn update_user_status(&self, user_id: i64, is_active: bool) -> Result<(), AppError> {
+    pub async fn update_user_status(&self, user_id: &str, is_active: bool) -> Result<(), AppError> {
+        let _timer = self.query_timer("update_user_status");
         let query = "UPDATE users SET is_active = ? WHERE id = ?";
         sqlx::query(query)
             .bind(is_active)
@@ -275,11 +379,11 @@ impl Database {
         Ok(())
     }
 
-    /// Update a user's superuser status
-    pub async fn update_user_superuser(&self, user_id: i64, is_superuser: bool) -> Result<(), AppError> {
-        let query = "UPDATE users SET is_superuser = ? WHERE id = ?";
+    /// Require a user to choose a new password
+    pub async fn set_password_reset_required(&self, user_id: &str) -> Result<(), AppError> {
+        let _timer = self.query_timer("set_password_reset_required");
+        let query = "UPDATE users SET password_reset_required = 1 WHERE id = ?";
         sqlx::query(query)
-            .bind(is_superuser)
             .bind(user_id)
             .execute(self.pool())
             .await?;
@@ -287,8 +391,73 @@ impl Database {
         Ok(())
     }
 
+    /// Update a user's superuser status, making them an admin or a viewer
+    pub async fn update_user_superuser(&self, user_id: &str, is_superuser: bool) -> Result<(), AppError> {
+        let role = if is_superuser { UserRole::Admin } else { UserRole::Viewer };
+        self.set_user_role(user_id, &role).await
+    }
+
+    /// Give a user one of the built-in roles in place of the one they held
+    ///
+    /// Admins are superusers. Other roles the user holds are kept.
+    pub async fn set_user_role(&self, user_id: &str, role: &UserRole) -> Result<(), AppError> {
+        let _timer = self.query_timer("set_user_role");
+        let mut tx = self.pool().begin().await?;
+        sqlx::query("UPDATE users SET is_superuser = ? WHERE id = ?")
+            .bind(matches!(role, UserRole::Admin))
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let builtin = vec!["?"; UserRole::ALL.len()].join(", ");
+        let query = format!(
+            "DELETE FROM user_roles WHERE user_id = ? AND role_id IN (SELECT id FROM roles WHERE name IN ({}))",
+            builtin
+        );
+        let mut delete = sqlx::query(&query).bind(user_id);
+        for builtin_role in &UserRole::ALL {
+            delete = delete.bind(builtin_role.as_str());
+        }
+        delete.execute(&mut *tx).await?;
+
+        sqlx::query("INSERT INTO user_roles (user_id, role_id, created_at) SELECT ?, id, ? FROM roles WHERE name = ?")
+            .bind(user_id)
+            .bind(db_now())
+            .bind(role.as_str())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Names of the roles of users, sorted, by user ID
+    pub async fn find_user_role_names(&self, user_ids: &[String]) -> Result<HashMap<String, Vec<String>>, AppError> {
+        let _timer = self.query_timer("find_user_role_names");
+        let mut roles: HashMap<String, Vec<String>> = HashMap::new();
+        if user_ids.is_empty() {
+            return Ok(roles);
+        }
+
+        let query = format!(
+            "SELECT ur.user_id, r.name FROM user_roles ur JOIN roles r ON r.id = ur.role_id \
+             WHERE ur.user_id IN ({}) ORDER BY r.name",
+            vec!["?"; user_ids.len()].join(", ")
+        );
+        let mut rows = sqlx::query_as::<_, (String, String)>(&query);
+        for user_id in user_ids {
+            rows = rows.bind(user_id);
+        }
+        for (user_id, name) in rows.fetch_all(self.pool()).await? {
+            roles.entry(user_id).or_default().push(name);
+        }
+
+        Ok(roles)
+    }
+
     /// Delete a user
-    pub async fn delete_user(&self, user_id: i64) -> Result<(), AppError> {
+    pub async fn delete_user(&self, user_id: &str) -> Result<(), AppError> {
+        let _timer = self.query_timer("delete_user");
         let query = "DELETE FROM users WHERE id = ?";
         sqlx::query(query)
             .bind(user_id)
@@ -300,6 +469,7 @@ impl Database {
 
     /// List users with optional filtering and pagination
     pub async fn list_users(&self, query_params: UserListQuery) -> Result<(Vec<UserRecord>, u64), AppError> {
+        let _timer = self.query_timer("list_users");
         let page = query_params.page.unwrap_or(1).max(1);
         let per_page = query_params.per_page.unwrap_or(20).min(100);
         let offset = (page - 1) * per_page;
@@ -319,12 +489,14 @@ impl Database {
             bind_values.push(if is_active { "1".to_string() } else { "0".to_string() });
         }
 
-        // Note: Role filtering would need to join with user_roles table
-        // For now, we filter based on is_superuser for admin role
-        if let Some(role) = &query_params.role {
-            let is_superuser = matches!(role, UserRole::Admin);
-            where_clauses.push("is_superuser = ?".to_string());
-            bind_values.push(if is_superuser { "1".to_string() } else { "0".to_string() });
+        let roles = query_params.roles()?;
+        if !roles.is_empty() {
+            where_clauses.push(format!(
+                "EXISTS (SELECT 1 FROM user_roles ur JOIN roles r ON r.id = ur.role_id \
+                 WHERE ur.user_id = users.id AND r.name IN ({}))",
+                vec!["?"; roles.len()].join(", ")
+            ));
+            bind_values.extend(roles.iter().map(|role| role.as_str().to_string()));
         }
 
         let where_clause = where_clauses.join(" AND ");
@@ -339,12 +511,12 @@ impl Database {
 
         // Data query
         let data_query = format!(
-            "SELECT id, username, email, password_hash, full_name, is_active, is_superuser, last_login, created_at, updated_at \
+            "SELECT id, username, email, password_hash, full_name, is_active, is_superuser, timezone, last_login, created_at, updated_at, password_reset_required \
              FROM users WHERE {} ORDER BY created_at DESC LIMIT ? OFFSET ?",
             where_clause
         );
 
-        let rows = sqlx::query_as::<_, (i64, String, String, String, Option<String>, bool, bool, Option<String>, String, String)>(&data_query);
+        let rows = sqlx::query_as::<_, (String, String, String, String, Option<String>, bool, bool, String, Option<String>, String, String, bool)>(&data_query);
         let mut rows_builder = rows;
         for value in &bind_values {
             rows_builder = rows_builder.bind(value);
@@ -355,7 +527,7 @@ impl Database {
 
         let users = rows_result
             .into_iter()
-            .map(|(id, username, email, password_hash, full_name, is_active, is_superuser, last_login, created_at, updated_at)| {
+            .map(|(id, username, email, password_hash, full_name, is_active, is_superuser, timezone, last_login, created_at, updated_at, password_reset_required)| {
                 UserRecord {
                     id,
                     username,
@@ -364,9 +536,11 @@ impl Database {
                     full_name,
                     is_active,
                     is_superuser,
+                    timezone,
                     last_login,
                     created_at,
                     updated_at,
+                    password_reset_required,
                 }
             })
             .collect();
@@ -376,23 +550,621 @@ impl Database {
 
     /// Get total user count
     pub async fn count_users(&self) -> Result<u64, AppError> {
+        let _timer = self.query_timer("count_users");
         let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
             .fetch_one(self.pool())
             .await?;
 
         Ok(count as u64)
     }
+
+    // ============================================================================
+    // Team Operations
+    // ============================================================================
+
+    /// List teams, optionally restricted to the teams a user belongs to
+    pub async fn list_teams(&self, member_user_id: Option<&str>) -> Result<Vec<TeamRecord>, AppError> {
+        let _timer = self.query_timer("list_teams");
+        let query = r#"
+            SELECT t.id, t.name, t.alias, t.description,
+                   (SELECT COUNT(*) FROM team_members m WHERE m.team_id = t.id) AS member_count,
+                   t.created_at, t.updated_at
+            FROM teams t
+            WHERE ? IS NULL OR EXISTS (
+                SELECT 1 FROM team_members m WHERE m.team_id = t.id AND m.user_id = ?
+            )
+            ORDER BY t.name
+        "#;
+
+        let rows = sqlx::query_as::<_, (String, String, String, Option<String>, i64, String, String)>(query)
+            .bind(member_user_id)
+            .bind(member_user_id)
+            .fetch_all(self.pool())
+            .await?;
+
+        Ok(rows.into_iter().map(team_record_from_row).collect())
+    }
+
+    /// Find a team by ID
+    pub async fn find_team_by_id(&self, team_id: &str) -> Result<Option<TeamRecord>, AppError> {
+        let _timer = self.query_timer("find_team_by_id");
+        self.find_team_by("id", team_id).await
+    }
+
+    /// Find a team by alias
+    pub async fn find_team_by_alias(&self, alias: &str) -> Result<Option<TeamRecord>, AppError> {
+        let _timer = self.query_timer("find_team_by_alias");
+        self.find_team_by("alias", alias).await
+    }
+
+    async fn find_team_by(&self, column: &str, value: &str) -> Result<Option<TeamRecord>, AppError> {
+        let query = format!(
+            "SELECT t.id, t.name, t.alias, t.description, \
+                    (SELECT COUNT(*) FROM team_members m WHERE m.team_id = t.id) AS member_count, \
+                    t.created_at, t.updated_at \
+             FROM teams t WHERE t.{} = ?",
+            column
+        );
+
+        let row = sqlx::query_as::<_, (String, String, String, Option<String>, i64, String, String)>(&query)
+            .bind(value)
+            .fetch_optional(self.pool())
+            .await?;
+
+        Ok(row.map(team_record_from_row))
+    }
+
+    /// Create a new team
+    pub async fn create_team(
+        &self,
+        team_id: &str,
+        name: &str,
+        alias: &str,
+        description: Option<&str>,
+    ) -> Result<(), AppError> {
+        let _timer = self.query_timer("create_team");
+        let query = r#"
+            INSERT INTO teams (id, name, alias, description, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+        "#;
+
+        let now = db_now();
+        sqlx::query(query)
+            .bind(team_id)
+            .bind(name)
+            .bind(alias)
+            .bind(description)
+            .bind(&now)
+            .bind(&now)
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Update a team
+    pub async fn update_team(
+        &self,
+        team_id: &str,
+        name: Option<&str>,
+        alias: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<(), AppError> {
+        let _timer = self.query_timer("update_team");
+        let mut updates = vec![];
+        let mut bind_values: Vec<&str> = vec![];
+
+        if let Some(n) = name {
+            updates.push("name = ?");
+            bind_values.push(n);
+        }
+
+        if let Some(a) = alias {
+            updates.push("alias = ?");
+            bind_values.push(a);
+        }
+
+        if let Some(d) = description {
+            updates.push("description = ?");
+            bind_values.push(d);
+        }
+
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let now = db_now();
+        updates.push("updated_at = ?");
+        bind_values.push(&now);
+
+        let query = format!("UPDATE teams SET {} WHERE id = ?", updates.join(", "));
+
+        let mut query_builder = sqlx::query(&query);
+        for value in bind_values {
+            query_builder = query_builder.bind(value);
+        }
+        query_builder = query_builder.bind(team_id);
+
+        query_builder.execute(self.pool()).await?;
+
+        Ok(())
+    }
+
+    /// Delete a team
+    ///
+    /// Memberships are removed and owned resources become unowned through the
+    /// foreign key actions.
+    pub async fn delete_team(&self, team_id: &str) -> Result<bool, AppError> {
+        let _timer = self.query_timer("delete_team");
+        let result = sqlx::query("DELETE FROM teams WHERE id = ?")
+            .bind(team_id)
+            .execute(self.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List the members of a team
+    pub async fn list_team_members(&self, team_id: &str) -> Result<Vec<TeamMemberRecord>, AppError> {
+        let _timer = self.query_timer("list_team_members");
+        let query = r#"
+            SELECT u.id, u.username, u.email, m.role, m.created_at
+            FROM team_members m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.team_id = ?
+            ORDER BY u.username
+        "#;
+
+        let rows = sqlx::query_as::<_, (String, String, String, String, String)>(query)
+            .bind(team_id)
+            .fetch_all(self.pool())
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, username, email, role, created_at)| TeamMemberRecord {
+                user_id,
+                username,
+                email,
+                role,
+                created_at,
+            })
+            .collect())
+    }
+
+    /// Add a user to a team, or update their role if already a member
+    pub async fn upsert_team_member(&self, team_id: &str, user_id: &str, role: &str) -> Result<(), AppError> {
+        let _timer = self.query_timer("upsert_team_member");
+        let query = r#"
+            INSERT INTO team_members (team_id, user_id, role, created_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (team_id, user_id) DO UPDATE SET role = excluded.role
+        "#;
+
+        sqlx::query(query)
+            .bind(team_id)
+            .bind(user_id)
+            .bind(role)
+            .bind(db_now())
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove a user from a team
+    pub async fn remove_team_member(&self, team_id: &str, user_id: &str) -> Result<bool, AppError> {
+        let _timer = self.query_timer("remove_team_member");
+        let result = sqlx::query("DELETE FROM team_members WHERE team_id = ? AND user_id = ?")
+            .bind(team_id)
+            .bind(user_id)
+            .execute(self.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get a user's role within a team, if they are a member
+    pub async fn find_team_role(&self, team_id: &str, user_id: &str) -> Result<Option<String>, AppError> {
+        let _timer = self.query_timer("find_team_role");
+        let role = sqlx::query_scalar::<_, String>(
+            "SELECT role FROM team_members WHERE team_id = ? AND user_id = ?"
+        )
+        .bind(team_id)
+        .bind(user_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(role)
+    }
+
+    /// Get the IDs of all teams a user belongs to
+    pub async fn find_team_ids_for_user(&self, user_id: &str) -> Result<Vec<String>, AppError> {
+        let _timer = self.query_timer("find_team_ids_for_user");
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT team_id FROM team_members WHERE user_id = ?"
+        )
+        .bind(user_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(ids)
+    }
+
+    // ============================================================================
+    // Quota Operations
+    // ============================================================================
+
+    /// Get the quota overrides configured for a team
+    pub async fn find_team_quota(&self, team_id: &str) -> Result<Option<TeamQuota>, AppError> {
+        let _timer = self.query_timer("find_team_quota");
+        let query = r#"
+            SELECT max_nodes, max_templates, max_api_keys, metric_retention_days
+            FROM team_quotas
+            WHERE team_id = ?
+        "#;
+
+        let row = sqlx::query_as::<_, (Option<i64>, Option<i64>, Option<i64>, Option<i64>)>(query)
+            .bind(team_id)
+            .fetch_optional(self.pool())
+            .await?;
+
+        let to_limit = |v: Option<i64>| v.map(|v| v.max(0) as u32);
+        Ok(row.map(|(max_nodes, max_templates, max_api_keys, metric_retention_days)| TeamQuota {
+            max_nodes: to_limit(max_nodes),
+            max_templates: to_limit(max_templates),
+            max_api_keys: to_limit(max_api_keys),
+            metric_retention_days: to_limit(metric_retention_days),
+        }))
+    }
+
+    /// Replace the quota overrides of a team
+    pub async fn upsert_team_quota(&self, team_id: &str, quota: &TeamQuota) -> Result<(), AppError> {
+        let _timer = self.query_timer("upsert_team_quota");
+        let query = r#"
+            INSERT INTO team_quotas (team_id, max_nodes, max_templates, max_api_keys, metric_retention_days,
+                                     updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (team_id) DO UPDATE SET
+                max_nodes = excluded.max_nodes,
+                max_templates = excluded.max_templates,
+                max_api_keys = excluded.max_api_keys,
+                metric_retention_days = excluded.metric_retention_days,
+                updated_at = excluded.updated_at
+        "#;
+
+        sqlx::query(query)
+            .bind(team_id)
+            .bind(quota.max_nodes.map(i64::from))
+            .bind(quota.max_templates.map(i64::from))
+            .bind(quota.max_api_keys.map(i64::from))
+            .bind(quota.metric_retention_days.map(i64::from))
+            .bind(db_now())
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Count the nodes owned by a team
+    pub async fn count_team_nodes(&self, team_id: &str) -> Result<u64, AppError> {
+        let _timer = self.query_timer("count_team_nodes");
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM nodes WHERE team_id = ?")
+            .bind(team_id)
+            .fetch_one(self.pool())
+            .await?;
+
+        Ok(count as u64)
+    }
+
+    /// Get each node's owning team and that team's retention override
+    pub async fn list_node_retention_overrides(&self) -> Result<Vec<(String, Option<i64>)>, AppError> {
+        let _timer = self.query_timer("list_node_retention_overrides");
+        let query = r#"
+            SELECT n.id, q.metric_retention_days
+            FROM nodes n
+            LEFT JOIN team_quotas q ON q.team_id = n.team_id
+        "#;
+
+        let rows = sqlx::query_as::<_, (String, Option<i64>)>(query)
+            .fetch_all(self.pool())
+            .await?;
+
+        Ok(rows)
+    }
+
+    // ============================================================================
+    // Configuration History Operations
+    // ============================================================================
+
+    /// Insert a configuration history entry
+    ///
+    /// An empty `created_at` is stored as the current time.
+    pub async fn create_config_history(&self, entry: &ConfigHistoryRecord) -> Result<(), AppError> {
+        let _timer = self.query_timer("create_config_history");
+        let query = r#"
+            INSERT INTO config_history
+                (id, node_id, user_id, version, config_data, change_summary, is_rollback_point, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#;
+
+        let created_at = if entry.created_at.is_empty() { db_now() } else { entry.created_at.clone() };
+        sqlx::query(query)
+            .bind(&entry.id)
+            .bind(&entry.node_id)
+            .bind(&entry.user_id)
+            .bind(&entry.version)
+            .bind(&entry.config_data)
+            .bind(&entry.change_summary)
+            .bind(entry.is_rollback_point)
+            .bind(created_at)
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Find a configuration history entry by ID
+    pub async fn find_config_history(&self, id: &str) -> Result<Option<ConfigHistoryRecord>, AppError> {
+        let _timer = self.query_timer("find_config_history");
+        let query = r#"
+            SELECT id, node_id, user_id, version, config_data, change_summary, is_rollback_point, created_at
+            FROM config_history
+            WHERE id = ?
+        "#;
+
+        let row = sqlx::query_as::<_, ConfigHistoryRow>(query)
+            .bind(id)
+            .fetch_optional(self.pool())
+            .await?;
+
+        Ok(row.map(config_history_record_from_row))
+    }
+
+    /// List a node's configuration history, newest first
+    pub async fn list_config_history(&self, node_id: &str) -> Result<Vec<ConfigHistoryRecord>, AppError> {
+        let _timer = self.query_timer("list_config_history");
+        let query = r#"
+            SELECT id, node_id, user_id, version, config_data, change_summary, is_rollback_point, created_at
+            FROM config_history
+            WHERE node_id = ?
+            ORDER BY created_at DESC
+        "#;
+
+        let rows = sqlx::query_as::<_, ConfigHistoryRow>(query)
+            .bind(node_id)
+            .fetch_all(self.pool())
+            .await?;
+
+        Ok(rows.into_iter().map(config_history_record_from_row).collect())
+    }
+
+    // ============================================================================
+    // Configuration Change Operations
+    // ============================================================================
+
+    /// Insert a configuration change
+    pub async fn create_config_change(&self, change: &ConfigChangeRecord) -> Result<(), AppError> {
+        let _timer = self.query_timer("create_config_change");
+        let query = r#"
+            INSERT INTO config_changes
+                (id, node_id, snapshot_id, config_hash, snapshot, change_type, changed_by, description,
+                 is_rollback_point, commit_status, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#;
+
+        sqlx::query(query)
+            .bind(&change.id)
+            .bind(&change.node_id)
+            .bind(&change.snapshot_id)
+            .bind(&change.config_hash)
+            .bind(&change.snapshot)
+            .bind(&change.change_type)
+            .bind(&change.changed_by)
+            .bind(&change.description)
+            .bind(change.is_rollback_point)
+            .bind(&change.commit_status)
+            .bind(&change.created_at)
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Find a configuration change of a node by ID
+    pub async fn find_config_change(&self, node_id: &str, id: &str) -> Result<Option<ConfigChangeRecord>, AppError> {
+        let _timer = self.query_timer("find_config_change");
+        let query = format!("SELECT {} FROM config_changes WHERE id = ? AND node_id = ?", CONFIG_CHANGE_COLUMNS);
+
+        let row = sqlx::query_as::<_, ConfigChangeRow>(&query)
+            .bind(id)
+            .bind(node_id)
+            .fetch_optional(self.pool())
+            .await?;
+
+        Ok(row.map(config_change_record_from_row))
+    }
+
+    /// Find the configuration change that produced a snapshot of a node
+    pub async fn find_config_change_by_snapshot(
+        &self,
+        node_id: &str,
+        snapshot_id: &str,
+    ) -> Result<Option<ConfigChangeRecord>, AppError> {
+        let _timer = self.query_timer("find_config_change_by_snapshot");
+        let query = format!(
+            "SELECT {} FROM config_changes WHERE snapshot_id = ? AND node_id = ?",
+            CONFIG_CHANGE_COLUMNS
+        );
+
+        let row = sqlx::query_as::<_, ConfigChangeRow>(&query)
+            .bind(snapshot_id)
+            .bind(node_id)
+            .fetch_optional(self.pool())
+            .await?;
+
+        Ok(row.map(config_change_record_from_row))
+    }
+
+    /// List a page of a node's configuration changes, newest first, with the
+    /// total number of its changes
+    pub async fn list_config_changes(
+        &self,
+        node_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<ConfigChangeRecord>, i64), AppError> {
+        let _timer = self.query_timer("list_config_changes");
+        let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM config_changes WHERE node_id = ?")
+            .bind(node_id)
+            .fetch_one(self.pool())
+            .await?;
+
+        let query = format!(
+            "SELECT {} FROM config_changes WHERE node_id = ? ORDER BY created_at DESC, rowid DESC LIMIT ? OFFSET ?",
+            CONFIG_CHANGE_COLUMNS
+        );
+        let rows = sqlx::query_as::<_, ConfigChangeRow>(&query)
+            .bind(node_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.pool())
+            .await?;
+
+        Ok((rows.into_iter().map(config_change_record_from_row).collect(), total))
+    }
+}
+
+/// Config change columns in the order of [`ConfigChangeRow`]
+const CONFIG_CHANGE_COLUMNS: &str = "id, node_id, snapshot_id, config_hash, snapshot, change_type, changed_by, description, \
+                                     is_rollback_point, commit_status, created_at";
+
+/// Config change columns as selected by the queries above
+type ConfigChangeRow = (String, String, String, String, String, String, String, String, bool, String, String);
+
+/// Build a config change record from a query row
+fn config_change_record_from_row(
+    (id, node_id, snapshot_id, config_hash, snapshot, change_type, changed_by, description, is_rollback_point, commit_status, created_at): ConfigChangeRow,
+) -> ConfigChangeRecord {
+    ConfigChangeRecord {
+        id,
+        node_id,
+        snapshot_id,
+        config_hash,
+        snapshot,
+        change_type,
+        changed_by,
+        description,
+        is_rollback_point,
+        commit_status,
+        created_at,
+    }
+}
+
+/// Config history columns as selected by the queries above
+type ConfigHistoryRow = (String, String, Option<String>, String, String, Option<String>, bool, String);
+
+/// Build a config history record from a query row
+fn config_history_record_from_row(
+    (id, node_id, user_id, version, config_data, change_summary, is_rollback_point, created_at): ConfigHistoryRow,
+) -> ConfigHistoryRecord {
+    ConfigHistoryRecord {
+        id,
+        node_id,
+        user_id,
+        version,
+        config_data,
+        change_summary,
+        is_rollback_point,
+        created_at,
+    }
+}
+
+/// Build a team record from a query row
+fn team_record_from_row(
+    (id, name, alias, description, member_count, created_at, updated_at): (String, String, String, Option<String>, i64, String, String),
+) -> TeamRecord {
+    TeamRecord {
+        id,
+        name,
+        alias,
+        description,
+        member_count,
+        created_at,
+        updated_at,
+    }
 }
 
 /// Helper function to create database from config
 pub async fn create_database(pool: SqlitePool) -> Result<Data<Database>, AppError> {
     let db = Database::new(pool);
-    db.init_schema().await?;
     db.run_migrations().await?;
 
     Ok(Data::new(db))
 }
 
+/// Open a database without migrating it, warning about pending migrations
+pub async fn open_database(pool: SqlitePool) -> Result<Data<Database>, AppError> {
+    let db = Database::new(pool);
+    let pending = db.pending_migrations().await?;
+    if !pending.is_empty() {
+        let names: Vec<String> = pending
+            .iter()
+            .map(|(version, description)| format!("{:03} {}", version, description))
+            .collect();
+        warn!(
+            "Skipping {} pending database migrations: {}",
+            pending.len(),
+            names.join(", ")
+        );
+    }
+
+    Ok(Data::new(db))
+}
+
+/// Split a SQL script into individual statements
+///
+/// Comment lines are dropped and `CREATE TRIGGER ... BEGIN ... END` bodies are
+/// kept together even though they contain semicolons.
+pub fn split_sql_statements(sql: &str) -> Vec<String> {
+    let mut statements = vec![];
+    let mut current = String::new();
+
+    for line in sql.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("--") {
+            continue;
+        }
+
+        current.push_str(line);
+        current.push('\n');
+
+        if !trimmed.ends_with(';') {
+            continue;
+        }
+
+        let upper = current.trim_start().to_uppercase();
+        let in_trigger = upper.starts_with("CREATE TRIGGER")
+            && !trimmed.trim_end_matches(';').trim().eq_ignore_ascii_case("END");
+        if in_trigger {
+            continue;
+        }
+
+        let statement = current.trim().trim_end_matches(';').trim().to_string();
+        if !statement.is_empty() {
+            statements.push(statement);
+        }
+        current.clear();
+    }
+
+    let statement = current.trim().trim_end_matches(';').trim().to_string();
+    if !statement.is_empty() {
+        statements.push(statement);
+    }
+
+    statements
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -402,4 +1174,206 @@ mod tests {
         // This would be expanded with actual tests in the future
         assert!(true);
     }
+
+    #[test]
+    fn test_split_sql_statements() {
+        let sql = "-- Header comment\n\
+                   CREATE TABLE a (id INTEGER);\n\
+                   \n\
+                   -- Section\n\
+                   INSERT INTO a (id) VALUES\n    (1),\n    (2);\n\
+                   CREATE TRIGGER t AFTER UPDATE ON a\n\
+                   BEGIN\n    UPDATE a SET id = 1;\nEND;\n";
+
+        let statements = split_sql_statements(sql);
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], "CREATE TABLE a (id INTEGER)");
+        assert!(statements[1].starts_with("INSERT INTO a"));
+        assert!(statements[2].starts_with("CREATE TRIGGER t"));
+        assert!(statements[2].ends_with("END"));
+    }
+
+    #[tokio::test]
+    async fn test_schema_and_team_operations() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Database::new(pool);
+        db.run_migrations().await.unwrap();
+        // Running again must not re-apply anything
+        db.run_migrations().await.unwrap();
+
+        let admin = db.find_user_by_username("admin").await.unwrap().unwrap();
+        assert!(admin.is_superuser);
+
+        db.create_team("team-1", "Network Ops", "netops", None).await.unwrap();
+        db.upsert_team_member("team-1", &admin.id, "member").await.unwrap();
+        db.upsert_team_member("team-1", &admin.id, "maintainer").await.unwrap();
+
+        let members = db.list_team_members("team-1").await.unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].role, "maintainer");
+
+        let team = db.find_team_by_alias("netops").await.unwrap().unwrap();
+        assert_eq!(team.member_count, 1);
+        assert_eq!(db.list_teams(Some(&admin.id)).await.unwrap().len(), 1);
+        assert_eq!(db.list_teams(Some("00000000-0000-4000-8000-000000000002")).await.unwrap().len(), 0);
+
+        let quota = TeamQuota {
+            max_nodes: Some(3),
+            metric_retention_days: Some(7),
+            ..Default::default()
+        };
+        db.upsert_team_quota("team-1", &quota).await.unwrap();
+        assert_eq!(db.find_team_quota("team-1").await.unwrap(), Some(quota));
+        assert_eq!(db.count_team_nodes("team-1").await.unwrap(), 0);
+
+        assert!(db.delete_team("team-1").await.unwrap());
+        assert!(db.find_team_ids_for_user(&admin.id).await.unwrap().is_empty());
+        assert!(db.find_team_quota("team-1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_user_roles() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Database::new(pool);
+        db.run_migrations().await.unwrap();
+
+        let admin = db.find_user_by_username("admin").await.unwrap().unwrap();
+        db.create_user("user-1", "alice", "alice@example.com", "hash", None).await.unwrap();
+        db.create_user("user-2", "bob", "bob@example.com", "hash", None).await.unwrap();
+        db.set_user_role("user-2", &UserRole::Operator).await.unwrap();
+        db.set_user_role("user-1", &UserRole::Admin).await.unwrap();
+        db.update_user_superuser("user-1", false).await.unwrap();
+
+        let ids = [admin.id.clone(), "user-1".to_string(), "user-2".to_string()];
+        let roles = db.find_user_role_names(&ids).await.unwrap();
+        assert_eq!(roles[&admin.id], ["admin"]);
+        assert_eq!(roles["user-1"], ["viewer"]);
+        assert_eq!(roles["user-2"], ["operator"]);
+        assert!(!db.find_user_by_id("user-1").await.unwrap().unwrap().is_superuser);
+
+        let query = |role: &str| UserListQuery {
+            status: None,
+            role: Some(role.to_string()),
+            search: None,
+            page: None,
+            per_page: None,
+        };
+        let (users, total) = db.list_users(query("operator, admin")).await.unwrap();
+        assert_eq!(total, 2);
+        let mut usernames: Vec<String> = users.into_iter().map(|user| user.username).collect();
+        usernames.sort();
+        assert_eq!(usernames, ["admin", "bob"]);
+        assert!(matches!(db.list_users(query("admn")).await, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_timestamps_are_normalized() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Database::new(pool);
+        db.run_migrations().await.unwrap();
+
+        // The seeded admin was created with SQLite's datetime('now') format
+        let admin = db.find_user_by_username("admin").await.unwrap().unwrap();
+        assert_eq!(admin.timezone, "UTC");
+        assert!(admin.created_at.contains('T') && admin.created_at.ends_with('Z'));
+
+        db.update_user_profile(&admin.id, None, None, Some("Asia/Tokyo")).await.unwrap();
+        db.update_last_login(&admin.id).await.unwrap();
+
+        let admin = db.find_user_by_id(&admin.id).await.unwrap().unwrap();
+        assert_eq!(admin.timezone, "Asia/Tokyo");
+        assert!(admin.updated_at.ends_with('Z'));
+        assert!(admin.last_login.unwrap().ends_with('Z'));
+    }
+
+    #[tokio::test]
+    async fn test_user_ids_are_uuids() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Database::new(pool);
+        db.run_migrations().await.unwrap();
+
+        // The seeded admin keeps the ID it was previously exposed under
+        let admin = db.find_user_by_username("admin").await.unwrap().unwrap();
+        assert_eq!(admin.id, "00000000-0000-4000-8000-000000000001");
+        assert_eq!(admin.to_user().id.to_string(), admin.id);
+
+        let user_id = uuid::Uuid::new_v4().to_string();
+        db.create_user(&user_id, "operator", "operator@example.com", "hash", None)
+            .await
+            .unwrap();
+        let user = db.find_user_by_id(&user_id).await.unwrap().unwrap();
+        assert_eq!(user.username, "operator");
+
+        db.delete_user(&user_id).await.unwrap();
+        assert!(db.find_user_by_id(&user_id).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_migrations_exist_for_both_backends() {
+        let list = |backend: &str| {
+            let dir = format!("{}/migrations/{}", env!("CARGO_MANIFEST_DIR"), backend);
+            let mut names: Vec<String> = std::fs::read_dir(dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(list("sqlite"), list("mysql"));
+
+        let versions: Vec<i64> = MIGRATOR.iter().map(|migration| migration.version).collect();
+        assert_eq!(versions, (1..=versions.len() as i64).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_migrations_are_adopted() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Database::new(pool);
+
+        // Schema of an earlier release, one migration behind
+        let latest = MIGRATOR.iter().last().unwrap().version;
+        sqlx::query("CREATE TABLE _migrations (version INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        for migration in MIGRATOR.iter().filter(|migration| migration.version < latest) {
+            for statement in split_sql_statements(&migration.sql) {
+                sqlx::query(&statement).execute(db.pool()).await.unwrap();
+            }
+            if migration.version > 1 {
+                sqlx::query("INSERT INTO _migrations (version, name) VALUES (?, ?)")
+                    .bind(migration.version)
+                    .bind(migration.description.as_ref())
+                    .execute(db.pool())
+                    .await
+                    .unwrap();
+            }
+        }
+        let pending = db.pending_migrations().await.unwrap();
+        assert_eq!(pending.iter().map(|(version, _)| *version).collect::<Vec<_>>(), [latest]);
+
+        db.run_migrations().await.unwrap();
+        assert!(db.pending_migrations().await.unwrap().is_empty());
+        assert!(db.find_user_by_username("admin").await.unwrap().is_some());
+    }
 }
\ No newline at end of file
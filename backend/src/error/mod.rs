@@ -5,6 +5,8 @@ use serde::Serialize;
 use std::fmt;
 use thiserror::Error;
 
+use crate::i18n;
+
 /// Main application error type
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -47,6 +49,26 @@ pub enum AppError {
     /// HTTP client errors
     #[error("HTTP client error: {0}")]
     HttpClient(String),
+
+    /// Quota limit errors
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    /// Operations the target node does not support
+    #[error("Not supported: {0}")]
+    Unsupported(String),
+
+    /// Clients sending more requests than allowed
+    #[error("Too many requests: {0}")]
+    RateLimited(String),
+
+    /// Request bodies larger than allowed
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    /// Resources and URL forms that were removed
+    #[error("Gone: {0}")]
+    Gone(String),
 }
 
 impl AppError {
@@ -62,14 +84,44 @@ impl AppError {
             AppError::ExternalApi(_) => StatusCode::BAD_GATEWAY,
             AppError::Jwt(_) => StatusCode::UNAUTHORIZED,
             AppError::HttpClient(_) => StatusCode::BAD_GATEWAY,
+            AppError::QuotaExceeded(_) => StatusCode::CONFLICT,
+            AppError::Unsupported(_) => StatusCode::NOT_IMPLEMENTED,
+            AppError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::Gone(_) => StatusCode::GONE,
         }
     }
+
+    /// Render the error for clients in the current request locale
+    ///
+    /// The `Display` output stays in English so logs remain uniform.
+    pub fn localized_message(&self) -> String {
+        let (key, detail) = match self {
+            AppError::Config(d) => ("error.config", d),
+            AppError::Database(d) => ("error.database", d),
+            AppError::Auth(d) => ("error.auth", d),
+            AppError::Forbidden(d) => ("error.forbidden", d),
+            AppError::Validation(d) => ("error.validation", d),
+            AppError::NotFound(d) => ("error.not_found", d),
+            AppError::Internal(d) => ("error.internal", d),
+            AppError::ExternalApi(d) => ("error.external_api", d),
+            AppError::Jwt(d) => ("error.jwt", d),
+            AppError::HttpClient(d) => ("error.http_client", d),
+            AppError::QuotaExceeded(d) => ("error.quota_exceeded", d),
+            AppError::Unsupported(d) => ("error.unsupported", d),
+            AppError::RateLimited(d) => ("error.rate_limited", d),
+            AppError::PayloadTooLarge(d) => ("error.payload_too_large", d),
+            AppError::Gone(d) => ("error.gone", d),
+        };
+
+        i18n::t_args(key, &[("detail", detail)])
+    }
 }
 
 impl ResponseError for AppError {
     fn error_response(&self) -> HttpResponse {
         HttpResponse::build(self.status_code()).json(ErrorResponse {
-            error: self.to_string(),
+            error: self.localized_message(),
             status_code: self.status_code().as_u16(),
         })
     }
@@ -116,7 +168,7 @@ impl From<serde_json::Error> for AppError {
 /// Convert validator errors to AppError
 impl From<validator::ValidationErrors> for AppError {
     fn from(err: validator::ValidationErrors) -> Self {
-        AppError::Validation(format!("Validation failed: {:?}", err))
+        AppError::Validation(i18n::validation_message(&err))
     }
 }
 
@@ -148,6 +200,13 @@ impl From<actix_web::Error> for AppError {
     }
 }
 
+/// Convert multipart errors to AppError
+impl From<actix_multipart::MultipartError> for AppError {
+    fn from(err: actix_multipart::MultipartError) -> Self {
+        AppError::Validation(format!("Invalid multipart request: {}", err))
+    }
+}
+
 /// Parse integer errors
 impl From<std::num::ParseIntError> for AppError {
     fn from(err: std::num::ParseIntError) -> Self {
@@ -165,5 +224,18 @@ mod tests {
         assert_eq!(AppError::Forbidden("test".to_string()).status_code(), StatusCode::FORBIDDEN);
         assert_eq!(AppError::NotFound("test".to_string()).status_code(), StatusCode::NOT_FOUND);
         assert_eq!(AppError::Validation("test".to_string()).status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(AppError::QuotaExceeded("test".to_string()).status_code(), StatusCode::CONFLICT);
+        assert_eq!(AppError::Unsupported("test".to_string()).status_code(), StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(AppError::RateLimited("test".to_string()).status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(AppError::PayloadTooLarge("test".to_string()).status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_localized_message() {
+        let err = AppError::NotFound("node".to_string());
+        assert_eq!(err.localized_message(), err.to_string());
+
+        let message = i18n::with_locale(i18n::Locale::Ja, async { err.localized_message() }).await;
+        assert_eq!(message, "見つかりません: node");
     }
 }
\ No newline at end of file
@@ -0,0 +1,124 @@
+//! Announcement Handlers Module
+//!
+//! This module contains HTTP request handlers for announcements. Anyone may
+//! read the current announcements; managing them requires an administrator.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{AppError, AppResult};
+use crate::models::announcement::{
+    AnnouncementListQuery, CreateAnnouncementRequest, UpdateAnnouncementRequest,
+};
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::services::{AnnouncementService, AuditService};
+
+/// List announcements
+///
+/// GET /api/announcements
+///
+/// Returns the announcements currently shown, also to anonymous users so the
+/// login page can display maintenance banners. Administrators can pass
+/// `?all=true` to include scheduled and expired announcements.
+pub async fn list_announcements(
+    claims: Option<Claims>,
+    query: web::Query<AnnouncementListQuery>,
+    service: web::Data<AnnouncementService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_announcements request");
+
+    if query.all {
+        let claims = claims.ok_or_else(|| AppError::Auth("Authentication required".to_string()))?;
+        audit_service.ensure_admin(&claims).await?;
+    }
+
+    let response = service.list_announcements(query.all).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Create an announcement
+///
+/// POST /api/announcements
+///
+/// Stores the announcement and broadcasts it to all connected UIs.
+pub async fn create_announcement(
+    claims: Claims,
+    request: web::Json<CreateAnnouncementRequest>,
+    service: web::Data<AnnouncementService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_announcement request");
+
+    audit_service.ensure_admin(&claims).await?;
+    request.validate()?;
+
+    let announcement = service.create_announcement(&claims, request.into_inner()).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "announcement.create", AuditResult::Success)
+                .with_target(announcement.id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(announcement))
+}
+
+/// Update an announcement
+///
+/// PUT /api/announcements/:id
+pub async fn update_announcement(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateAnnouncementRequest>,
+    service: web::Data<AnnouncementService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_announcement request");
+
+    audit_service.ensure_admin(&claims).await?;
+    request.validate()?;
+
+    let id = path.into_inner();
+    let announcement = service.update_announcement(id, request.into_inner()).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "announcement.update", AuditResult::Success)
+                .with_target(id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(announcement))
+}
+
+/// Delete an announcement
+///
+/// DELETE /api/announcements/:id
+pub async fn delete_announcement(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<AnnouncementService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling delete_announcement request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let id = path.into_inner();
+    service.delete_announcement(id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "announcement.delete", AuditResult::Success)
+                .with_target(id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Announcement deleted successfully",
+        "id": id
+    })))
+}
@@ -0,0 +1,113 @@
+//! API Token Handlers Module
+//!
+//! This module contains HTTP request handlers for managing read-only API
+//! tokens. Users manage their own tokens; administrators can list and
+//! revoke everyone's.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::api_token::{ApiTokenListQuery, CreateApiTokenRequest};
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::services::{ApiTokenService, AuditService, NodeService, TeamService};
+
+/// List API tokens
+///
+/// GET /api/api-tokens
+///
+/// Query parameters:
+/// - all: List the tokens of every user (administrators only)
+pub async fn list_api_tokens(
+    claims: Claims,
+    query: web::Query<ApiTokenListQuery>,
+    service: web::Data<ApiTokenService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_api_tokens request");
+
+    let tokens = if query.all {
+        audit_service.ensure_admin(&claims).await?;
+        service.list_tokens(None).await?
+    } else {
+        service.list_tokens(Some(claims.user_id()?)).await?
+    };
+
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+/// Create a read-only API token
+///
+/// POST /api/api-tokens
+///
+/// Request body:
+/// ```json
+/// { "name": "NOC wallboard", "scopes": ["metrics", "nodes"], "expires_at": "2025-01-01T00:00:00Z" }
+/// ```
+///
+/// Scopes are metrics, alerts, nodes, and status; `node_ids` limits the
+/// token to some nodes. The response holds the token, which is not shown
+/// again.
+pub async fn create_api_token(
+    claims: Claims,
+    request: web::Json<CreateApiTokenRequest>,
+    service: web::Data<ApiTokenService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_api_token request");
+
+    let request = request.into_inner();
+    request.validate()?;
+    for node_id in request.node_ids.iter().flatten() {
+        crate::handlers::node::authorize_node(&node_service, &team_service, &claims, *node_id).await?;
+    }
+    let created = service.create_token(&claims, request).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "api_token.create", AuditResult::Success)
+                .with_target(created.api_token.name.clone())
+                .with_details(serde_json::json!({
+                    "prefix": created.api_token.prefix,
+                    "scopes": created.api_token.scopes,
+                    "node_ids": created.api_token.node_ids,
+                    "expires_at": created.api_token.expires_at.to_rfc3339(),
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(created))
+}
+
+/// Revoke an API token
+///
+/// DELETE /api/api-tokens/{id}
+///
+/// Users revoke their own tokens; administrators revoke anyone's.
+pub async fn revoke_api_token(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ApiTokenService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling revoke_api_token request");
+
+    let token = service.get_token(path.into_inner()).await?;
+    if token.user_id != claims.sub {
+        audit_service.ensure_admin(&claims).await?;
+    }
+    let token = service.revoke_token(token.id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "api_token.revoke", AuditResult::Success)
+                .with_target(token.name.clone())
+                .with_details(serde_json::json!({ "prefix": token.prefix, "owner": token.username })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(token))
+}
@@ -0,0 +1,116 @@
+//! Audit Handlers Module
+//!
+//! This module contains HTTP request handlers for the audit log and the
+//! operation history. All endpoints are restricted to administrators.
+
+use actix_web::{web, HttpResponse};
+use futures::StreamExt;
+use tracing::debug;
+
+use crate::error::{AppError, AppResult};
+use crate::models::audit::{AuditListQuery, OperationListQuery};
+use crate::models::auth::Claims;
+use crate::services::AuditService;
+
+/// Content type of NDJSON exports
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// List audit log entries
+///
+/// GET /api/audit
+///
+/// Filters by user, node, action (exact or `prefix.`), result, and date
+/// range; sorts by any column in `AUDIT_SORT_COLUMNS`. The range may be
+/// relative (`from=-6h`, `window=7d`) or a named `preset`.
+pub async fn list_audit_entries(
+    claims: Claims,
+    query: web::Query<AuditListQuery>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_audit_entries request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let response = audit_service.list_entries(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Export audit log entries
+///
+/// GET /api/audit/export
+///
+/// Streams all entries matching the list filters as NDJSON.
+pub async fn export_audit_entries(
+    claims: Claims,
+    query: web::Query<AuditListQuery>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling export_audit_entries request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let lines = audit_service.export_entries(query.into_inner())?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(NDJSON_CONTENT_TYPE)
+        .streaming(lines.map(|chunk| chunk.map(web::Bytes::from))))
+}
+
+/// List recorded operations
+///
+/// GET /api/operations
+///
+/// Filters by user, node, kind, status, and start date range; sorts by any
+/// column in `OPERATION_SORT_COLUMNS`. The range takes the same relative
+/// forms as the audit log's.
+pub async fn list_operations(
+    claims: Claims,
+    query: web::Query<OperationListQuery>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_operations request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let response = audit_service.list_operations(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Export recorded operations
+///
+/// GET /api/operations/export
+///
+/// Streams all operations matching the list filters as NDJSON.
+pub async fn export_operations(
+    claims: Claims,
+    query: web::Query<OperationListQuery>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling export_operations request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let lines = audit_service.export_operations(query.into_inner())?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(NDJSON_CONTENT_TYPE)
+        .streaming(lines.map(|chunk| chunk.map(web::Bytes::from))))
+}
+
+/// Get a recorded operation
+///
+/// GET /api/operations/{id}
+pub async fn get_operation(
+    claims: Claims,
+    path: web::Path<String>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_operation request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let operation_id = path.into_inner();
+    let operation = audit_service
+        .get_operation(&operation_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Operation {} not found", operation_id)))?;
+
+    Ok(HttpResponse::Ok().json(operation))
+}
@@ -1,13 +1,15 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::Serialize;
 use validator::Validate;
-use tracing::info;
-use uuid::Uuid;
+use tracing::{info, warn};
 
 use crate::error::{AppError, AppResult};
-use crate::models::auth::{Claims, LoginRequest, LoginResponse, RegisterRequest, SimpleLoginResponse, UserResponse};
-use crate::models::user::{UserStatus, extract_db_id_from_uuid};
-use crate::services::AuthService;
+use crate::models::auth::{
+    Claims, LoginRequest, LoginResponse, RefreshTokenRequest, RegisterRequest, UserResponse, UserSessionListResponse,
+};
+use crate::models::user::UserStatus;
+use crate::services::{AuthService, LoginHistoryService};
+use crate::utils::client_ip::client_ip;
 
 /// Health check endpoint
 #[derive(Serialize)]
@@ -26,14 +28,24 @@ pub async fn health_check() -> AppResult<HttpResponse> {
     }))
 }
 
+/// Client address and user agent of a request
+fn client_of(req: &HttpRequest) -> (Option<String>, Option<&str>) {
+    let ip_address = client_ip(req).map(|ip| ip.to_string());
+    let user_agent = req
+        .headers()
+        .get(actix_web::http::header::USER_AGENT)
+        .and_then(|value| value.to_str().ok());
+    (ip_address, user_agent)
+}
+
 /// Register handler
 pub async fn register(
+    http_req: HttpRequest,
     req: web::Json<RegisterRequest>,
     auth_service: web::Data<AuthService>,
 ) -> AppResult<HttpResponse> {
     // Validate request
-    req.validate()
-        .map_err(|e| AppError::Validation(format!("Validation failed: {:?}", e)))?;
+    req.validate()?;
 
     let user = auth_service
         .register(
@@ -44,10 +56,11 @@ pub async fn register(
         )
         .await?;
 
-    // Generate tokens for the new user
-    let user_id_str = user.id.to_string();
-    let access_token = auth_service.generate_token(&user_id_str, &user.username)?;
-    let refresh_token = auth_service.generate_refresh_token(&user_id_str, &user.username)?;
+    // Start a session for the new user
+    let (ip_address, user_agent) = client_of(&http_req);
+    let session = auth_service
+        .start_session(&user, ip_address.as_deref(), user_agent)
+        .await?;
     let expires_in = auth_service.get_expiration();
 
     info!("User registered successfully: {}", user.username);
@@ -60,34 +73,47 @@ pub async fn register(
             full_name: user.full_name,
             role: user.role,
             status: UserStatus::Active,
+            timezone: user.timezone,
+            password_reset_required: user.password_reset_required,
             last_login: user.last_login,
             created_at: user.created_at,
             updated_at: user.updated_at,
         },
-        access_token,
-        refresh_token,
+        access_token: session.access_token,
+        refresh_token: session.refresh_token,
         expires_in,
     }))
 }
 
 /// Login handler - authenticate user and generate JWT token
+///
+/// Successful logins are added to the user's login history.
 pub async fn login(
+    http_req: HttpRequest,
     req: web::Json<LoginRequest>,
     auth_service: web::Data<AuthService>,
+    login_history: web::Data<LoginHistoryService>,
 ) -> AppResult<HttpResponse> {
     // Validate request
-    req.validate()
-        .map_err(|e| AppError::Validation(format!("Validation failed: {:?}", e)))?;
+    req.validate()?;
 
     // Authenticate user
     let user = auth_service
         .authenticate(&req.username, &req.password)
         .await?;
 
-    // Generate tokens
-    let user_id_str = user.id.to_string();
-    let access_token = auth_service.generate_token(&user_id_str, &user.username)?;
-    let refresh_token = auth_service.generate_refresh_token(&user_id_str, &user.username)?;
+    let (ip_address, user_agent) = client_of(&http_req);
+    if let Err(e) = login_history
+        .record(&user, ip_address.as_deref(), user_agent)
+        .await
+    {
+        warn!("Failed to record login of {}: {}", user.username, e);
+    }
+
+    // Start a session
+    let session = auth_service
+        .start_session(&user, ip_address.as_deref(), user_agent)
+        .await?;
     let expires_in = auth_service.get_expiration();
 
     info!("User logged in successfully: {}", user.username);
@@ -100,29 +126,27 @@ pub async fn login(
             full_name: user.full_name,
             role: user.role,
             status: UserStatus::Active,
+            timezone: user.timezone,
+            password_reset_required: user.password_reset_required,
             last_login: user.last_login,
             created_at: user.created_at,
             updated_at: user.updated_at,
         },
-        access_token,
-        refresh_token,
+        access_token: session.access_token,
+        refresh_token: session.refresh_token,
         expires_in,
     }))
 }
 
 /// Logout handler
+///
+/// Revokes the session the access token was issued for, so its refresh
+/// token stops working.
 pub async fn logout(
     claims: Claims,
     auth_service: web::Data<AuthService>,
 ) -> AppResult<HttpResponse> {
-    // Parse user ID from claims (UUID string -> extract i64)
-    let uuid = Uuid::parse_str(&claims.sub)
-        .map_err(|_| AppError::Auth("Invalid user ID in token".to_string()))?;
-    let user_id: i64 = extract_db_id_from_uuid(&uuid);
-
-    auth_service.logout(user_id).await?;
-
-    info!("User logged out: {}", claims.username);
+    auth_service.logout(&claims).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Logged out successfully"
@@ -130,20 +154,66 @@ pub async fn logout(
 }
 
 /// Refresh token handler
+///
+/// POST /api/auth/refresh
+///
+/// Exchanges a refresh token for a new access token and a new refresh
+/// token; the one exchanged stops working.
 pub async fn refresh_token(
+    req: web::Json<RefreshTokenRequest>,
+    auth_service: web::Data<AuthService>,
+) -> AppResult<HttpResponse> {
+    let tokens = auth_service.refresh_token(&req.refresh_token).await?;
+
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+/// List the caller's active sessions
+///
+/// GET /api/auth/sessions
+///
+/// Each session carries the address and device it was started from; the
+/// session of the request is marked as current.
+pub async fn list_sessions(
     claims: Claims,
     auth_service: web::Data<AuthService>,
 ) -> AppResult<HttpResponse> {
-    // Generate new token from existing claims
-    let (new_token, new_claims) = auth_service.refresh_token(&claims)?;
+    let sessions = auth_service
+        .list_sessions(claims.user_id()?, claims.session_id())
+        .await?;
 
-    info!("Token refreshed for user: {}", claims.username);
+    Ok(HttpResponse::Ok().json(UserSessionListResponse { sessions }))
+}
 
-    Ok(HttpResponse::Ok().json(SimpleLoginResponse {
-        token: new_token,
-        user_id: new_claims.sub.clone(),
-        username: new_claims.username.clone(),
-    }))
+/// Revoke one of the caller's sessions
+///
+/// DELETE /api/auth/sessions/{id}
+pub async fn revoke_session(
+    claims: Claims,
+    path: web::Path<uuid::Uuid>,
+    auth_service: web::Data<AuthService>,
+) -> AppResult<HttpResponse> {
+    let session_id = path.into_inner();
+    auth_service.revoke_session(claims.user_id()?, session_id).await?;
+
+    info!("User {} revoked session {}", claims.username, session_id);
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Log out of all of the caller's sessions
+///
+/// DELETE /api/auth/sessions
+///
+/// Revokes every session including the current one; access tokens already
+/// issued remain valid until they expire.
+pub async fn revoke_all_sessions(
+    claims: Claims,
+    auth_service: web::Data<AuthService>,
+) -> AppResult<HttpResponse> {
+    let revoked = auth_service.revoke_all_sessions(claims.user_id()?).await?;
+
+    info!("User {} logged out of {} sessions", claims.username, revoked);
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "revoked": revoked })))
 }
 
 /// Validate token handler
@@ -164,10 +234,7 @@ pub async fn get_current_user(
     claims: Claims,
     auth_service: web::Data<AuthService>,
 ) -> AppResult<HttpResponse> {
-    // Parse user ID from claims (UUID string -> extract i64)
-    let uuid = Uuid::parse_str(&claims.sub)
-        .map_err(|_| AppError::Auth("Invalid user ID in token".to_string()))?;
-    let user_id: i64 = extract_db_id_from_uuid(&uuid);
+    let user_id = claims.user_id()?;
 
     // Fetch user from database
     let user_record = auth_service
@@ -184,6 +251,8 @@ pub async fn get_current_user(
         full_name: user.full_name,
         role: user.role,
         status: user.status,
+        timezone: user.timezone,
+        password_reset_required: user.password_reset_required,
         last_login: user.last_login,
         created_at: user.created_at,
         updated_at: user.updated_at,
@@ -0,0 +1,28 @@
+//! Backend Health Handlers Module
+//!
+//! This module contains HTTP request handlers that report how the backend
+//! itself is doing.
+
+use actix_web::{web, HttpResponse};
+
+use crate::error::AppResult;
+use crate::models::auth::Claims;
+use crate::services::{AuditService, BackendHealthService};
+
+/// Get the backend's health metrics and its unresolved alerts
+///
+/// GET /api/admin/backend-health
+///
+/// Backend alerts are also listed under `/api/monitoring/alerts` with the
+/// nil node ID, and grouped into incidents like router alerts.
+pub async fn get_backend_health(
+    claims: Claims,
+    service: web::Data<BackendHealthService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let health = service.get_health().await?;
+
+    Ok(HttpResponse::Ok().json(health))
+}
@@ -0,0 +1,100 @@
+//! Database Backup Handlers Module
+//!
+//! This module contains HTTP request handlers that let administrators back
+//! up and restore the SQLite database while the server keeps running.
+
+use actix_web::{web, HttpResponse};
+use tracing::{info, warn};
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::backup::{DatabaseBackupListResponse, RestoreBackupRequest};
+use crate::services::{AuditService, BackupService};
+
+/// List database backups
+///
+/// GET /api/admin/db/backups
+pub async fn list_backups(
+    claims: Claims,
+    service: web::Data<BackupService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let backups = service.list_backups().await?;
+
+    Ok(HttpResponse::Ok().json(DatabaseBackupListResponse { backups }))
+}
+
+/// Back up the database
+///
+/// POST /api/admin/db/backup
+///
+/// Writes a consistent snapshot to a timestamped file in the backup
+/// directory without interrupting other requests.
+pub async fn create_backup(
+    claims: Claims,
+    service: web::Data<BackupService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_backup request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let backup = service.create_backup().await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "database.backup", AuditResult::Success)
+                .with_target(backup.file_name.clone())
+                .with_details(serde_json::json!({ "size_bytes": backup.size_bytes })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(backup))
+}
+
+/// Restore the database from a backup
+///
+/// POST /api/admin/db/restore
+///
+/// The backup must pass SQLite's integrity check and match the current
+/// schema version. The current contents are backed up first; the response
+/// names that safety backup so the restore can be undone.
+pub async fn restore_backup(
+    claims: Claims,
+    request: web::Json<RestoreBackupRequest>,
+    service: web::Data<BackupService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling restore_backup request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let result = match service.restore_backup(&request.file_name).await {
+        Ok(result) => result,
+        Err(e) => {
+            warn!("Database restore from {} failed: {}", request.file_name, e);
+            audit_service
+                .record(
+                    AuditEvent::new(Some(&claims), "database.restore", AuditResult::Failure)
+                        .with_target(request.file_name.clone())
+                        .with_details(serde_json::json!({ "error": e.to_string() })),
+                )
+                .await;
+            return Err(e);
+        }
+    };
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "database.restore", AuditResult::Success)
+                .with_target(result.restored_from.file_name.clone())
+                .with_details(serde_json::json!({
+                    "safety_backup": result.safety_backup.file_name,
+                    "tables": result.tables,
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(result))
+}
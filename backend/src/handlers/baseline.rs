@@ -0,0 +1,105 @@
+//! Baseline Handlers Module
+//!
+//! This module contains HTTP request handlers for the NTP, syslog, and SNMP
+//! settings of nodes, and for pushing them to many nodes at once.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::baseline::{BaselineSettings, PushBaselineRequest};
+use crate::services::{AuditService, BaselineService, NodeService, TeamService};
+
+/// Names of the services a change replaces, for the audit log
+fn changed_services(settings: &BaselineSettings) -> Vec<&'static str> {
+    [
+        ("ntp", settings.ntp.is_some()),
+        ("syslog", settings.syslog.is_some()),
+        ("snmp", settings.snmp.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, changed)| changed.then_some(name))
+    .collect()
+}
+
+/// Get the NTP, syslog, and SNMP settings of a node
+///
+/// GET /api/nodes/{id}/baseline
+pub async fn get_baseline(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<BaselineService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_baseline request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let settings = service.settings(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+/// Replace the NTP, syslog, or SNMP settings of a node
+///
+/// PUT /api/nodes/{id}/baseline
+///
+/// Services left out of the request are not touched.
+pub async fn set_baseline(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    req: web::Json<BaselineSettings>,
+    service: web::Data<BaselineService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    info!("Handling set_baseline request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let settings = service.set_settings(node_id, req.into_inner(), &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "baseline.update", AuditResult::Success)
+                .with_node(node_id)
+                .with_details(serde_json::json!({ "services": changed_services(&settings) })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+/// Push NTP, syslog, or SNMP settings to many nodes
+///
+/// POST /api/baseline/push
+///
+/// One commit per node. Returns the outcome on every node.
+pub async fn push_baseline(
+    claims: Claims,
+    req: web::Json<PushBaselineRequest>,
+    service: web::Data<BaselineService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling push_baseline request");
+
+    let request = req.into_inner();
+    request.validate()?;
+    let mut details = serde_json::json!({ "services": changed_services(&request.settings) });
+    let results = service.push(request, &claims).await?;
+    let failed = results.iter().filter(|result| !result.success).count();
+    let outcome = if failed == 0 { AuditResult::Success } else { AuditResult::Failure };
+    details["nodes"] = serde_json::json!(results.len());
+    details["failed"] = serde_json::json!(failed);
+    audit_service
+        .record(AuditEvent::new(Some(&claims), "baseline.push", outcome).with_details(details))
+        .await;
+
+    Ok(HttpResponse::Ok().json(results))
+}
@@ -0,0 +1,98 @@
+//! Certificate Expiry Handlers Module
+//!
+//! This module contains HTTP request handlers for managing the TLS endpoints
+//! whose certificates are monitored for expiry.
+
+use actix_web::{web, HttpResponse};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::certificate::CreateCertificateEndpointRequest;
+use crate::services::{AuditService, CertificateService};
+
+/// List monitored certificate endpoints
+///
+/// GET /api/certificates
+pub async fn list_certificate_endpoints(
+    _claims: Claims,
+    service: web::Data<CertificateService>,
+) -> AppResult<HttpResponse> {
+    let endpoints = service.list().await?;
+
+    Ok(HttpResponse::Ok().json(endpoints))
+}
+
+/// Monitor the certificate of a TLS endpoint
+///
+/// POST /api/certificates
+///
+/// The certificate is checked right away. Requires administrator access.
+pub async fn create_certificate_endpoint(
+    claims: Claims,
+    req: web::Json<CreateCertificateEndpointRequest>,
+    service: web::Data<CertificateService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_certificate_endpoint request for {}", req.name);
+
+    audit_service.ensure_admin(&claims).await?;
+    req.validate()?;
+
+    let endpoint = service.create(req.into_inner(), &claims).await?;
+    let mut event = AuditEvent::new(Some(&claims), "certificate.create", AuditResult::Success)
+        .with_target(format!("{}:{}", endpoint.host, endpoint.port))
+        .with_details(serde_json::json!({ "endpoint_id": endpoint.id, "name": endpoint.name }));
+    if let Some(node_id) = endpoint.node_id {
+        event = event.with_node(node_id);
+    }
+    audit_service.record(event).await;
+
+    Ok(HttpResponse::Created().json(endpoint))
+}
+
+/// Check the certificate of an endpoint now
+///
+/// POST /api/certificates/{id}/check
+///
+/// Requires administrator access.
+pub async fn check_certificate_endpoint(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<CertificateService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+    let endpoint = service.check(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(endpoint))
+}
+
+/// Stop monitoring a certificate endpoint
+///
+/// DELETE /api/certificates/{id}
+///
+/// Requires administrator access.
+pub async fn delete_certificate_endpoint(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<CertificateService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let endpoint_id = path.into_inner();
+    info!("Handling delete_certificate_endpoint request for endpoint {}", endpoint_id);
+
+    audit_service.ensure_admin(&claims).await?;
+    service.delete(endpoint_id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "certificate.delete", AuditResult::Success)
+                .with_target(endpoint_id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
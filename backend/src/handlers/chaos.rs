@@ -0,0 +1,124 @@
+//! Failure Injection Handlers Module
+//!
+//! This module contains HTTP request handlers that inject node and database
+//! failures for testing. They answer 404 unless `CHAOS_ENABLED` is set.
+
+use actix_web::{web, HttpResponse};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::chaos::{DatabaseDelayRequest, NodeFault};
+use crate::services::{AuditService, ChaosService};
+
+/// Get the failures currently injected
+///
+/// GET /api/admin/chaos
+pub async fn get_chaos_status(
+    claims: Claims,
+    service: web::Data<ChaosService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    Ok(HttpResponse::Ok().json(service.status()))
+}
+
+/// Inject a failure into requests to a node
+///
+/// PUT /api/admin/chaos/nodes/:id
+///
+/// Replaces any failure already injected into the node.
+pub async fn set_node_fault(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<NodeFault>,
+    service: web::Data<ChaosService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling set_node_fault request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let node_id = path.into_inner();
+    let entry = service.set_node_fault(node_id, request.into_inner())?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "chaos.node_fault.set", AuditResult::Success)
+                .with_node(node_id)
+                .with_details(serde_json::to_value(&entry.fault)?),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(entry))
+}
+
+/// Stop injecting failures into requests to a node
+///
+/// DELETE /api/admin/chaos/nodes/:id
+pub async fn clear_node_fault(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ChaosService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling clear_node_fault request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let node_id = path.into_inner();
+    service.clear_node_fault(node_id)?;
+    audit_service
+        .record(AuditEvent::new(Some(&claims), "chaos.node_fault.clear", AuditResult::Success).with_node(node_id))
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Delay database queries
+///
+/// PUT /api/admin/chaos/database
+///
+/// A delay of 0 stops delaying queries.
+pub async fn set_database_delay(
+    claims: Claims,
+    request: web::Json<DatabaseDelayRequest>,
+    service: web::Data<ChaosService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling set_database_delay request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let status = service.set_database_delay(request.delay_ms)?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "chaos.database_delay", AuditResult::Success)
+                .with_details(serde_json::json!({ "delay_ms": request.delay_ms })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// Remove all injected failures
+///
+/// DELETE /api/admin/chaos
+pub async fn reset_chaos(
+    claims: Claims,
+    service: web::Data<ChaosService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling reset_chaos request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    service.reset()?;
+    audit_service
+        .record(AuditEvent::new(Some(&claims), "chaos.reset", AuditResult::Success))
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
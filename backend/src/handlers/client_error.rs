@@ -0,0 +1,43 @@
+//! Client Error Handlers Module
+//!
+//! This module contains the HTTP request handler frontend error reports are
+//! sent to.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::auth::Claims;
+use crate::models::client_error::{ClientErrorAccepted, ClientErrorReport};
+use crate::services::ClientErrorService;
+use crate::utils::client_ip::client_ip;
+
+/// Report a frontend error
+///
+/// POST /api/client-errors
+///
+/// Accepts reports from anonymous and authenticated users alike; reports are
+/// attributed to the session's user when a token is sent. Bodies larger than
+/// `CLIENT_ERROR_MAX_BYTES` are rejected and reports beyond the per-client
+/// rate limit answered with 429.
+pub async fn report_client_error(
+    req: HttpRequest,
+    claims: Option<Claims>,
+    report: web::Json<ClientErrorReport>,
+    service: web::Data<ClientErrorService>,
+) -> AppResult<HttpResponse> {
+    report.validate()?;
+
+    let client = match &claims {
+        Some(claims) => format!("user:{}", claims.sub),
+        None => client_ip(&req).map_or_else(|| "ip:unknown".to_string(), |ip| format!("ip:{}", ip)),
+    };
+    let user_agent = req
+        .headers()
+        .get(actix_web::http::header::USER_AGENT)
+        .and_then(|value| value.to_str().ok());
+
+    let id = service.ingest(report.into_inner(), claims.as_ref(), &client, user_agent)?;
+
+    Ok(HttpResponse::Accepted().json(ClientErrorAccepted { id }))
+}
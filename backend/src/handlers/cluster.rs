@@ -0,0 +1,27 @@
+//! Cluster Handlers Module
+//!
+//! This module contains HTTP request handlers that show which instance of a
+//! cluster runs the background jobs.
+
+use actix_web::{web, HttpResponse};
+
+use crate::error::AppResult;
+use crate::models::auth::Claims;
+use crate::services::{AuditService, LeaderElection};
+
+/// Get the leadership status
+///
+/// GET /api/admin/cluster
+///
+/// Reports the answering instance and the current leader lease.
+pub async fn get_cluster_status(
+    claims: Claims,
+    leader_election: web::Data<LeaderElection>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let status = leader_election.status().await?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
@@ -1,42 +1,58 @@
 use actix_web::{web, HttpResponse};
 use serde::Deserialize;
+use uuid::Uuid;
 
 use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::auth::Claims;
+use crate::models::fields::FieldSelection;
 use crate::models::config::{
-    ConfigDeleteRequest, ConfigGenerateRequest, ConfigRetrieveRequest,
-    ConfigRollbackRequest, ConfigSearchRequest, ConfigSetRequest,
+    ConfigDeleteRequest, ConfigDiffFormat, ConfigDiffQuery, ConfigGenerateRequest, ConfigGenerateResponse,
+    ConfigNodeQuery, ConfigRetrieveRequest, ConfigRollbackRequest, ConfigSearchRequest, ConfigSetRequest,
 };
-use crate::services::ConfigService;
+use crate::models::response::ApiResponse;
+use crate::services::{ConfigService, NodeService, TeamService};
 
-/// Retrieve configuration from VyOS
+/// Retrieve configuration from a node
 ///
 /// POST /api/config/retrieve
 ///
-/// Retrieves the current running configuration from VyOS and returns it
-/// as a hierarchical tree structure.
+/// Retrieves the running configuration of the node given by `node_id` and
+/// returns it as a hierarchical tree structure. The `fields` query parameter
+/// selects the fields returned, e.g. `fields=node_count,config_tree.name`.
 pub async fn retrieve_config(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<ConfigRetrieveRequest>,
+    fields: FieldSelection,
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
     let result = service
-        .retrieve_config(req.into_inner())
+        .retrieve_config(req.into_inner(), &claims)
         .await?;
 
-    Ok(HttpResponse::Ok().json(result))
+    Ok(HttpResponse::Ok().json(fields.project(&result)?))
 }
 
 /// Set configuration value
 ///
 /// POST /api/config/configure
 ///
-/// Sets a configuration value at the specified path. If the value is None,
-/// the configuration at that path is deleted.
+/// Sets a configuration value at the specified path of a node. If the value
+/// is None, the configuration at that path is deleted. Paths under a
+/// configuration lock the caller does not hold are rejected with 403.
 pub async fn set_config(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<ConfigSetRequest>,
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
     let result = service
-        .set_config(req.into_inner())
+        .set_config(req.into_inner(), &claims)
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
@@ -46,13 +62,18 @@ pub async fn set_config(
 ///
 /// POST /api/config/delete
 ///
-/// Deletes configuration at the specified path.
+/// Deletes configuration at the specified path of a node. Rejected with 403
+/// if the path or anything below it is locked to someone else.
 pub async fn delete_config(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<ConfigDeleteRequest>,
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
     let result = service
-        .delete_config(req.into_inner())
+        .delete_config(req.into_inner(), &claims)
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
@@ -62,31 +83,40 @@ pub async fn delete_config(
 ///
 /// POST /api/config/generate
 ///
-/// Commits the pending configuration changes to the running configuration.
-/// Optionally saves to startup config.
+/// Records the running configuration of a node in its history as a commit.
+/// Commit warnings and lint findings are the warnings of the response.
 pub async fn generate_config(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<ConfigGenerateRequest>,
-    // TODO: Extract changed_by from JWT claims
-) -> AppResult<HttpResponse> {
-    let result = service
-        .generate_config(req.into_inner(), "system".to_string())
+) -> AppResult<ApiResponse<ConfigGenerateResponse>> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
+    let mut result = service
+        .generate_config(req.into_inner(), claims.username.clone())
         .await?;
 
-    Ok(HttpResponse::Ok().json(result))
+    let warnings = std::mem::take(&mut result.warnings);
+    Ok(ApiResponse::new(result).with_warnings(warnings))
 }
 
 /// Get configuration history
 ///
-/// GET /api/config/history
+/// GET /api/config/history?node_id={id}
 ///
-/// Retrieves the configuration change history.
+/// Retrieves the configuration change history of a node, newest first.
+/// Pages are selected with `limit` and `offset`.
 pub async fn get_history(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     query: web::Query<HistoryQueryParams>,
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, query.node_id).await?;
     let result = service
-        .get_history(query.limit)
+        .get_history(query.node_id, query.limit, query.offset, &claims)
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
@@ -94,17 +124,22 @@ pub async fn get_history(
 
 /// Get specific history entry
 ///
-/// GET /api/config/history/{id}
+/// GET /api/config/history/{id}?node_id={node_id}
 ///
-/// Retrieves a specific configuration history entry.
+/// Retrieves a specific entry of a node's configuration history.
 pub async fn get_history_entry(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     path: web::Path<String>,
+    query: web::Query<ConfigNodeQuery>,
 ) -> AppResult<HttpResponse> {
     let id = uuid::Uuid::parse_str(&path.into_inner())
         .map_err(|e| crate::error::AppError::Validation(format!("Invalid UUID: {}", e)))?;
 
-    let result = service.get_history_entry(id).await?;
+    authorize_node(&node_service, &team_service, &claims, query.node_id).await?;
+    let result = service.get_history_entry(query.node_id, id, &claims).await?;
 
     Ok(HttpResponse::Ok().json(result))
 }
@@ -113,14 +148,17 @@ pub async fn get_history_entry(
 ///
 /// POST /api/config/rollback
 ///
-/// Rolls back the configuration to a previous state.
+/// Rolls the configuration of a node back to an entry of its history.
 pub async fn rollback_config(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<ConfigRollbackRequest>,
-    // TODO: Extract changed_by from JWT claims
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
     let result = service
-        .rollback_config(req.into_inner(), "system".to_string())
+        .rollback_config(req.into_inner(), &claims)
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
@@ -128,12 +166,17 @@ pub async fn rollback_config(
 
 /// Compare configuration snapshots
 ///
-/// GET /api/config/diff/{id1}/{id2}
+/// GET /api/config/diff/{id1}/{id2}?node_id={node_id}
 ///
-/// Compares two configuration snapshots and returns the differences.
+/// Compares two configuration snapshots of a node and returns the
+/// differences; `format=unified` also renders them as unified diff text.
 pub async fn diff_configs(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     path: web::Path<(String, String)>,
+    query: web::Query<ConfigDiffQuery>,
 ) -> AppResult<HttpResponse> {
     let (id1_str, id2_str) = path.into_inner();
 
@@ -143,7 +186,8 @@ pub async fn diff_configs(
     let id2 = uuid::Uuid::parse_str(&id2_str)
         .map_err(|e| crate::error::AppError::Validation(format!("Invalid UUID for id2: {}", e)))?;
 
-    let result = service.diff_configs(id1, id2).await?;
+    authorize_node(&node_service, &team_service, &claims, query.node_id).await?;
+    let result = service.diff_configs(query.node_id, id1, id2, query.format, &claims).await?;
 
     Ok(HttpResponse::Ok().json(result))
 }
@@ -152,13 +196,18 @@ pub async fn diff_configs(
 ///
 /// POST /api/config/search
 ///
-/// Searches the configuration for paths and/or values matching the search term.
+/// Searches the configuration of a node for paths and/or values matching
+/// the search term.
 pub async fn search_config(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<ConfigSearchRequest>,
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
     let result = service
-        .search_config(req.into_inner())
+        .search_config(req.into_inner(), &claims)
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
@@ -168,14 +217,17 @@ pub async fn search_config(
 ///
 /// POST /api/config/bulk
 ///
-/// Applies multiple configuration changes in a single operation.
+/// Applies multiple configuration changes to a node in a single operation.
 pub async fn bulk_config_change(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<crate::models::config::BulkConfigChangeRequest>,
-    // TODO: Extract changed_by from JWT claims
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
     let result = service
-        .bulk_config_change(req.into_inner(), "system".to_string())
+        .bulk_config_change(req.into_inner(), &claims)
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
@@ -185,11 +237,16 @@ pub async fn bulk_config_change(
 ///
 /// POST /api/config/validate
 ///
-/// Validates the current pending configuration.
+/// Validates the current configuration of a node.
 pub async fn validate_config(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    req: web::Json<ConfigNodeQuery>,
 ) -> AppResult<HttpResponse> {
-    let warnings = service.validate_configuration().await?;
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
+    let warnings = service.validate_configuration(req.node_id).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "is_valid": true,
@@ -201,13 +258,19 @@ pub async fn validate_config(
 /// Query parameters for history endpoint
 #[derive(Debug, Deserialize)]
 pub struct HistoryQueryParams {
+    /// Node whose history is listed
+    node_id: Uuid,
     /// Maximum number of history entries to return
     limit: Option<usize>,
+    /// Number of newer history entries to skip
+    offset: Option<usize>,
 }
 
 /// Configuration node value request
 #[derive(Debug, Deserialize)]
 pub struct ConfigValueRequest {
+    /// Node to read the value from
+    node_id: Uuid,
     /// Path to the configuration node
     path: String,
 }
@@ -216,21 +279,26 @@ pub struct ConfigValueRequest {
 ///
 /// POST /api/config/value
 ///
-/// Retrieves the value of a specific configuration node.
+/// Retrieves the value of a specific configuration node of a node.
 pub async fn get_config_value(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<ConfigValueRequest>,
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
     let retrieve_request = ConfigRetrieveRequest {
+        node_id: req.node_id,
         path: Some(req.path.clone()),
         include_defaults: true,
         include_readonly: false,
     };
 
-    let result = service.retrieve_config(retrieve_request).await?;
+    let result = service.retrieve_config(retrieve_request, &claims).await?;
 
-    // Find the node at the requested path and return its value
-    let value = find_node_value(&result.config_tree, &req.path);
+    // The tree is rooted at the requested path
+    let value = find_node_value(&result.config_tree, &result.config_tree.path);
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "path": req.path,
@@ -241,7 +309,7 @@ pub async fn get_config_value(
 
 /// Helper function to find a node's value by path
 fn find_node_value(node: &crate::models::config::ConfigNode, path: &str) -> Option<String> {
-    if node.path == path {
+    if node.path == path && node.value.is_some() {
         return node.value.clone();
     }
 
@@ -257,6 +325,8 @@ fn find_node_value(node: &crate::models::config::ConfigNode, path: &str) -> Opti
 /// Configuration subtree request
 #[derive(Debug, Deserialize)]
 pub struct ConfigSubtreeRequest {
+    /// Node to read the subtree from
+    node_id: Uuid,
     /// Path to the configuration subtree
     path: String,
 }
@@ -265,18 +335,24 @@ pub struct ConfigSubtreeRequest {
 ///
 /// POST /api/config/subtree
 ///
-/// Retrieves a subtree of the configuration starting from the specified path.
+/// Retrieves a subtree of a node's configuration starting from the
+/// specified path.
 pub async fn get_config_subtree(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<ConfigSubtreeRequest>,
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
     let retrieve_request = ConfigRetrieveRequest {
+        node_id: req.node_id,
         path: Some(req.path.clone()),
         include_defaults: true,
         include_readonly: false,
     };
 
-    let result = service.retrieve_config(retrieve_request).await?;
+    let result = service.retrieve_config(retrieve_request, &claims).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "path": req.path,
@@ -288,24 +364,33 @@ pub async fn get_config_subtree(
 /// Configuration comparison request
 #[derive(Debug, Deserialize)]
 pub struct ConfigCompareRequest {
+    /// Node the snapshots were taken of
+    node_id: Uuid,
     /// First snapshot ID or revision number
     id1: uuid::Uuid,
     /// Second snapshot ID or revision number
     id2: uuid::Uuid,
+    #[serde(default)]
+    format: ConfigDiffFormat,
 }
 
 /// Compare configurations (POST alternative)
 ///
 /// POST /api/config/compare
 ///
-/// Compares two configuration snapshots and returns the differences.
-/// This is an alternative to the GET endpoint with path parameters.
+/// Compares two configuration snapshots of a node and returns the
+/// differences. This is an alternative to the GET endpoint with path
+/// parameters.
 pub async fn compare_configs(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
     req: web::Json<ConfigCompareRequest>,
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
     let result = service
-        .diff_configs(req.id1, req.id2)
+        .diff_configs(req.node_id, req.id1, req.id2, req.format, &claims)
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
@@ -315,12 +400,16 @@ pub async fn compare_configs(
 ///
 /// POST /api/config/discard
 ///
-/// Discards all pending configuration changes.
+/// Changes made through the configuration API are committed on the node as
+/// they are applied, so a node never has pending changes to discard; staged
+/// changes live in configuration sessions instead.
 pub async fn discard_config(
-    service: web::Data<ConfigService>,
+    claims: Claims,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    req: web::Json<ConfigNodeQuery>,
 ) -> AppResult<HttpResponse> {
-    // TODO: Integrate with vyos_client module
-    // This would call the VyOS API to discard pending changes
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "success": true,
@@ -330,73 +419,34 @@ pub async fn discard_config(
 
 /// Get configuration statistics
 ///
-/// GET /api/config/stats
+/// GET /api/config/stats?node_id={id}
 ///
-/// Returns statistics about the current configuration.
+/// Returns statistics about the current configuration of a node.
 pub async fn get_config_stats(
+    claims: Claims,
     service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    query: web::Query<ConfigNodeQuery>,
 ) -> AppResult<HttpResponse> {
+    authorize_node(&node_service, &team_service, &claims, query.node_id).await?;
     let retrieve_request = ConfigRetrieveRequest {
+        node_id: query.node_id,
         path: None,
         include_defaults: true,
         include_readonly: true,
     };
 
-    let result = service.retrieve_config(retrieve_request).await?;
-
-    let leaf_nodes = count_leaf_nodes(&result.config_tree);
-    let container_nodes = count_container_nodes(&result.config_tree);
-    let max_depth = calculate_max_depth(&result.config_tree, 0);
+    let result = service.retrieve_config(retrieve_request, &claims).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
-        "total_nodes": result.node_count,
-        "leaf_nodes": leaf_nodes,
-        "container_nodes": container_nodes,
-        "max_depth": max_depth,
-        "retrieved_at": result.retrieved_at
+        "node_id": result.node_id,
+        "total_nodes": result.stats.total_nodes,
+        "leaf_nodes": result.stats.leaf_nodes,
+        "container_nodes": result.stats.container_nodes,
+        "max_depth": result.stats.max_depth,
+        "config_hash": result.config_hash,
+        "retrieved_at": result.retrieved_at,
+        "stale": result.stale
     })))
 }
-
-/// Helper function to count leaf nodes
-fn count_leaf_nodes(node: &crate::models::config::ConfigNode) -> usize {
-    if matches!(
-        node.node_type,
-        crate::models::config::ConfigNodeType::Leaf
-    ) {
-        1
-    } else {
-        node.children.iter().map(count_leaf_nodes).sum::<usize>()
-    }
-}
-
-/// Helper function to count container nodes
-fn count_container_nodes(node: &crate::models::config::ConfigNode) -> usize {
-    let container_count = if matches!(
-        node.node_type,
-        crate::models::config::ConfigNodeType::Container
-    ) {
-        1
-    } else {
-        0
-    };
-
-    container_count
-        + node
-            .children
-            .iter()
-            .map(count_container_nodes)
-            .sum::<usize>()
-}
-
-/// Helper function to calculate max depth
-fn calculate_max_depth(node: &crate::models::config::ConfigNode, current_depth: usize) -> usize {
-    if node.children.is_empty() {
-        current_depth
-    } else {
-        node.children
-            .iter()
-            .map(|child| calculate_max_depth(child, current_depth + 1))
-            .max()
-            .unwrap_or(current_depth)
-    }
-}
\ No newline at end of file
@@ -0,0 +1,128 @@
+//! Configuration Backup Handlers Module
+//!
+//! This module contains HTTP request handlers for listing, taking,
+//! downloading, and restoring backups of a node's configuration.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::config_backup::RestoreConfigBackupRequest;
+use crate::services::{AuditService, ConfigService, NodeService, TeamService};
+
+/// List the configuration backups of a node
+///
+/// GET /api/nodes/{id}/backups
+pub async fn list_config_backups(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling list_config_backups request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let backups = service.list_backups(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(backups))
+}
+
+/// Back up the configuration of a node now
+///
+/// POST /api/nodes/{id}/backups
+pub async fn create_config_backup(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    info!("Handling create_config_backup request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let backup = service.backup_node(node_id, &claims.username).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config.backup.create", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(backup.id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(backup))
+}
+
+/// Download the configuration of a backup as JSON
+///
+/// GET /api/nodes/{id}/backups/{backup_id}
+///
+/// Secrets are redacted for users without the `secrets:read` permission.
+pub async fn download_config_backup(
+    claims: Claims,
+    path: web::Path<(Uuid, Uuid)>,
+    service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, backup_id) = path.into_inner();
+    debug!("Handling download_config_backup request for {} of node {}", backup_id, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let artifact = service.download_backup(node_id, backup_id, &claims).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", artifact.file_name()),
+        ))
+        .body(artifact.content))
+}
+
+/// Restore the configuration of a backup to its node
+///
+/// POST /api/nodes/{id}/backups/{backup_id}/restore
+///
+/// Converges the node to the backed up configuration as one commit. With
+/// `dry_run`, the operations are only returned.
+pub async fn restore_config_backup(
+    claims: Claims,
+    path: web::Path<(Uuid, Uuid)>,
+    req: Option<web::Json<RestoreConfigBackupRequest>>,
+    service: web::Data<ConfigService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, backup_id) = path.into_inner();
+    info!("Handling restore_config_backup request for {} of node {}", backup_id, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let request = req.map(web::Json::into_inner).unwrap_or_default();
+    let comment = request.comment.clone();
+    let plan = service.restore_backup(node_id, backup_id, request, &claims).await?;
+    if plan.applied {
+        audit_service
+            .record(
+                AuditEvent::new(Some(&claims), "config.backup.restore", AuditResult::Success)
+                    .with_node(node_id)
+                    .with_target(backup_id.to_string())
+                    .with_details(serde_json::json!({
+                        "comment": comment,
+                        "deletes": plan.deletes,
+                        "sets": plan.sets,
+                    })),
+            )
+            .await;
+    }
+
+    Ok(HttpResponse::Ok().json(plan))
+}
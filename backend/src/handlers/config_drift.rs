@@ -0,0 +1,113 @@
+//! Configuration Drift Handlers Module
+//!
+//! This module contains HTTP request handlers for the configuration
+//! baselines of nodes, and the drift of their running configuration from
+//! them.
+
+use actix_web::web;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::config_drift::{ConfigBaseline, ConfigDriftReport, SetConfigBaselineRequest};
+use crate::models::response::{ApiResponse, ApiResultExt};
+use crate::services::{AuditService, ConfigDriftService, NodeService, TeamService};
+
+/// Compare the running configuration of a node with its baseline
+///
+/// GET /api/nodes/{id}/drift
+///
+/// The number of differing paths is the `changes` metadata of the response.
+pub async fn get_config_drift(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigDriftService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<ApiResponse<ConfigDriftReport>> {
+    let node_id = path.into_inner();
+    debug!("Handling get_config_drift request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let report = service.drift(node_id, &claims).await?;
+    let changes = report.additions.len() + report.deletions.len() + report.modifications.len();
+
+    Ok(ApiResponse::new(report).with_meta("changes", changes))
+}
+
+/// Get the configuration baseline of a node
+///
+/// GET /api/nodes/{id}/drift/baseline
+pub async fn get_config_baseline(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigDriftService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<ApiResponse<ConfigBaseline>> {
+    let node_id = path.into_inner();
+    debug!("Handling get_config_baseline request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.get_baseline(node_id).await.enveloped()
+}
+
+/// Mark a stored configuration snapshot as the baseline of a node
+///
+/// PUT /api/nodes/{id}/drift/baseline
+pub async fn set_config_baseline(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<SetConfigBaselineRequest>,
+    service: web::Data<ConfigDriftService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<ApiResponse<ConfigBaseline>> {
+    let node_id = path.into_inner();
+    info!("Handling set_config_baseline request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let baseline = service.set_baseline(node_id, request.into_inner(), &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config_drift.baseline_set", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(baseline.snapshot_id.to_string())
+                .with_details(serde_json::json!({ "alert_on_drift": baseline.alert_on_drift })),
+        )
+        .await;
+
+    Ok(ApiResponse::new(baseline))
+}
+
+/// Stop comparing a node with a configuration baseline
+///
+/// DELETE /api/nodes/{id}/drift/baseline
+pub async fn clear_config_baseline(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigDriftService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<ApiResponse<serde_json::Value>> {
+    let node_id = path.into_inner();
+    info!("Handling clear_config_baseline request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.clear_baseline(node_id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config_drift.baseline_cleared", AuditResult::Success).with_node(node_id),
+        )
+        .await;
+
+    Ok(ApiResponse::new(serde_json::json!({
+        "message": "Configuration baseline cleared successfully",
+        "node_id": node_id
+    })))
+}
@@ -0,0 +1,85 @@
+//! Configuration Lock Handlers Module
+//!
+//! This module contains HTTP request handlers for managing the locks that
+//! reserve configuration subtrees to a team or team role.
+
+use actix_web::{web, HttpResponse};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::config_lock::CreateConfigLockRequest;
+use crate::services::{AuditService, ConfigLockService};
+
+/// List configuration locks
+///
+/// GET /api/config/locks
+pub async fn list_config_locks(
+    _claims: Claims,
+    service: web::Data<ConfigLockService>,
+) -> AppResult<HttpResponse> {
+    let locks = service.list().await?;
+
+    Ok(HttpResponse::Ok().json(locks))
+}
+
+/// Lock a configuration subtree
+///
+/// POST /api/config/locks
+///
+/// Requires administrator access.
+pub async fn create_config_lock(
+    claims: Claims,
+    req: web::Json<CreateConfigLockRequest>,
+    service: web::Data<ConfigLockService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_config_lock request for path {}", req.path);
+
+    audit_service.ensure_admin(&claims).await?;
+    req.validate()?;
+
+    let lock = service.create(req.into_inner(), &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config_lock.create", AuditResult::Success)
+                .with_target(lock.path.clone())
+                .with_details(serde_json::json!({
+                    "lock_id": lock.id,
+                    "team_id": lock.team_id,
+                    "role": lock.role,
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(lock))
+}
+
+/// Remove a configuration lock
+///
+/// DELETE /api/config/locks/{id}
+///
+/// Requires administrator access.
+pub async fn delete_config_lock(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigLockService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let lock_id = path.into_inner();
+    info!("Handling delete_config_lock request for lock {}", lock_id);
+
+    audit_service.ensure_admin(&claims).await?;
+    service.delete(lock_id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config_lock.delete", AuditResult::Success)
+                .with_target(lock_id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,288 @@
+//! Configuration Session Handlers Module
+//!
+//! This module contains HTTP request handlers for configuration sessions,
+//! which stage set and delete operations against a node and commit them,
+//! right away or at a scheduled time, optionally rolling back unless the
+//! commit is confirmed in time.
+
+use std::collections::HashMap;
+
+use actix_web::{web, HttpResponse};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::config_session::{
+    CommitConfigSessionRequest, ConfigSession, ConfigSessionListQuery, OpenConfigSessionRequest,
+    ScheduleConfigSessionRequest, StageConfigOperationsRequest,
+};
+use crate::services::{AuditService, ConfigSessionService, NodeService, TeamService};
+
+/// Audit event for an action on a session, with the error of a failed action
+fn session_audit_event(
+    claims: &Claims,
+    action: &str,
+    node_id: Uuid,
+    session_id: Uuid,
+    result: &AppResult<ConfigSession>,
+) -> AuditEvent {
+    let event = match result {
+        Ok(_) => AuditEvent::new(Some(claims), action, AuditResult::Success),
+        Err(e) => AuditEvent::new(Some(claims), action, AuditResult::Failure)
+            .with_details(serde_json::json!({ "error": e.to_string() })),
+    };
+    event.with_node(node_id).with_target(session_id.to_string())
+}
+
+/// Fetch a session and ensure the caller's teams may access its node
+async fn authorize_session(
+    service: &ConfigSessionService,
+    node_service: &NodeService,
+    team_service: &TeamService,
+    claims: &Claims,
+    session_id: Uuid,
+) -> AppResult<ConfigSession> {
+    let session = service.get(session_id).await?;
+    authorize_node(node_service, team_service, claims, session.node_id).await?;
+    Ok(session)
+}
+
+/// Open a configuration session against a node
+///
+/// POST /api/config/session
+pub async fn open_config_session(
+    claims: Claims,
+    req: web::Json<OpenConfigSessionRequest>,
+    service: web::Data<ConfigSessionService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling open_config_session request for node {}", req.node_id);
+
+    authorize_node(&node_service, &team_service, &claims, req.node_id).await?;
+    let session = service.open(req.node_id, &claims).await?;
+
+    Ok(HttpResponse::Created().json(session))
+}
+
+/// List configuration sessions
+///
+/// GET /api/config/session
+///
+/// Lists open sessions and commits awaiting confirmation on the nodes the
+/// caller may access; `all=true` includes closed sessions too.
+pub async fn list_config_sessions(
+    claims: Claims,
+    query: web::Query<ConfigSessionListQuery>,
+    service: web::Data<ConfigSessionService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    if let Some(node_id) = query.node_id {
+        authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    }
+
+    let mut response = service.list(&query).await?;
+    let scope = team_service.access_scope(&claims).await?;
+    let mut accessible = HashMap::new();
+    let mut sessions = Vec::with_capacity(response.sessions.len());
+    for session in response.sessions {
+        let allowed = match accessible.get(&session.node_id) {
+            Some(allowed) => *allowed,
+            None => {
+                let node = node_service.get_node(session.node_id).await?;
+                let allowed = node.is_some_and(|node| scope.can_access(node.team_id));
+                accessible.insert(session.node_id, allowed);
+                allowed
+            }
+        };
+        if allowed {
+            sessions.push(session);
+        }
+    }
+    response.sessions = sessions;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Get a configuration session
+///
+/// GET /api/config/session/{id}
+pub async fn get_config_session(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigSessionService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let session = authorize_session(&service, &node_service, &team_service, &claims, path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(session))
+}
+
+/// Stage operations in a configuration session
+///
+/// POST /api/config/session/{id}/operations
+///
+/// Appends set and delete operations to those already staged. Paths under
+/// a configuration lock the caller does not hold are rejected with 403.
+pub async fn stage_config_operations(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    req: web::Json<StageConfigOperationsRequest>,
+    service: web::Data<ConfigSessionService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let session_id = path.into_inner();
+    authorize_session(&service, &node_service, &team_service, &claims, session_id).await?;
+
+    let session = service.stage(session_id, req.into_inner().operations, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(session))
+}
+
+/// Commit a configuration session to its node
+///
+/// POST /api/config/session/{id}/commit
+///
+/// With `confirm_timeout_secs`, the commit is rolled back unless it is
+/// confirmed within that many seconds.
+pub async fn commit_config_session(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    req: web::Json<CommitConfigSessionRequest>,
+    service: web::Data<ConfigSessionService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let session_id = path.into_inner();
+    info!("Handling commit_config_session request for session {}", session_id);
+
+    let session = authorize_session(&service, &node_service, &team_service, &claims, session_id).await?;
+    let confirm_timeout_secs = req.confirm_timeout_secs;
+    let result = service.commit(session_id, req.into_inner(), &claims).await;
+    let mut details = serde_json::json!({
+        "operations": session.operations.len(),
+        "confirm_timeout_secs": confirm_timeout_secs,
+    });
+    if let Err(e) = &result {
+        details["error"] = serde_json::json!(e.to_string());
+    }
+    audit_service
+        .record(
+            session_audit_event(&claims, "config.session.commit", session.node_id, session_id, &result)
+                .with_details(details),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(result?))
+}
+
+/// Schedule a configuration session to be committed later
+///
+/// POST /api/config/session/{id}/schedule
+///
+/// The response previews the effect of each staged operation on the node's
+/// configuration as it is now. The scheduler commits the session at
+/// `apply_at` unless the node is not online, a change freeze is in effect,
+/// or the configuration the operations change has been changed since.
+pub async fn schedule_config_session(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    req: web::Json<ScheduleConfigSessionRequest>,
+    service: web::Data<ConfigSessionService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let session_id = path.into_inner();
+    info!("Handling schedule_config_session request for session {}", session_id);
+
+    let session = authorize_session(&service, &node_service, &team_service, &claims, session_id).await?;
+    let apply_at = req.apply_at;
+    let result = service.schedule(session_id, req.into_inner(), &claims).await;
+    let mut details = serde_json::json!({
+        "operations": session.operations.len(),
+        "apply_at": crate::models::timestamp::format_timestamp(&apply_at),
+    });
+    if let Err(e) = &result {
+        details["error"] = serde_json::json!(e.to_string());
+    }
+    audit_service
+        .record(
+            session_audit_event(&claims, "config.session.schedule", session.node_id, session_id, &result)
+                .with_details(details),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(result?))
+}
+
+/// Cancel the scheduled commit of a configuration session, reopening it
+///
+/// DELETE /api/config/session/{id}/schedule
+pub async fn unschedule_config_session(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigSessionService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let session_id = path.into_inner();
+    info!("Handling unschedule_config_session request for session {}", session_id);
+
+    let session = authorize_session(&service, &node_service, &team_service, &claims, session_id).await?;
+    let result = service.unschedule(session_id, &claims).await;
+    audit_service
+        .record(session_audit_event(&claims, "config.session.unschedule", session.node_id, session_id, &result))
+        .await;
+
+    Ok(HttpResponse::Ok().json(result?))
+}
+
+/// Confirm a commit awaiting confirmation, keeping it
+///
+/// POST /api/config/session/{id}/confirm
+pub async fn confirm_config_session(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigSessionService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let session_id = path.into_inner();
+    info!("Handling confirm_config_session request for session {}", session_id);
+
+    let session = authorize_session(&service, &node_service, &team_service, &claims, session_id).await?;
+    let result = service.confirm(session_id, &claims).await;
+    audit_service
+        .record(session_audit_event(&claims, "config.session.confirm", session.node_id, session_id, &result))
+        .await;
+
+    Ok(HttpResponse::Ok().json(result?))
+}
+
+/// Discard an open configuration session
+///
+/// DELETE /api/config/session/{id}
+pub async fn discard_config_session(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigSessionService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let session_id = path.into_inner();
+    authorize_session(&service, &node_service, &team_service, &claims, session_id).await?;
+
+    let session = service.discard(session_id, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(session))
+}
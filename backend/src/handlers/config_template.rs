@@ -0,0 +1,171 @@
+//! Configuration Template Handlers Module
+//!
+//! This module contains HTTP request handlers for configuration templates.
+//! Any user may list, preview, and apply templates to the nodes they can
+//! access; managing templates requires an administrator.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::config_template::{
+    ApplyTemplateRequest, CreateConfigTemplateRequest, RenderTemplateRequest, UpdateConfigTemplateRequest,
+};
+use crate::services::{AuditService, ConfigTemplateService};
+
+/// List configuration templates
+///
+/// GET /api/templates
+pub async fn list_templates(_claims: Claims, service: web::Data<ConfigTemplateService>) -> AppResult<HttpResponse> {
+    debug!("Handling list_templates request");
+
+    let response = service.list_templates().await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Get a configuration template
+///
+/// GET /api/templates/:id
+pub async fn get_template(
+    _claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigTemplateService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_template request");
+
+    let template = service.get_template(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(template))
+}
+
+/// Create a configuration template
+///
+/// POST /api/templates
+pub async fn create_template(
+    claims: Claims,
+    request: web::Json<CreateConfigTemplateRequest>,
+    service: web::Data<ConfigTemplateService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_template request");
+
+    audit_service.ensure_admin(&claims).await?;
+    request.validate()?;
+
+    let template = service.create_template(&claims, request.into_inner()).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config_template.create", AuditResult::Success)
+                .with_target(template.id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(template))
+}
+
+/// Update a configuration template
+///
+/// PUT /api/templates/:id
+pub async fn update_template(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateConfigTemplateRequest>,
+    service: web::Data<ConfigTemplateService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_template request");
+
+    audit_service.ensure_admin(&claims).await?;
+    request.validate()?;
+
+    let id = path.into_inner();
+    let template = service.update_template(id, request.into_inner()).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config_template.update", AuditResult::Success)
+                .with_target(id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(template))
+}
+
+/// Delete a configuration template
+///
+/// DELETE /api/templates/:id
+pub async fn delete_template(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ConfigTemplateService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling delete_template request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let id = path.into_inner();
+    service.delete_template(id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config_template.delete", AuditResult::Success)
+                .with_target(id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Configuration template deleted successfully",
+        "id": id
+    })))
+}
+
+/// Preview the commands a configuration template renders to
+///
+/// POST /api/templates/:id/render
+pub async fn render_template(
+    _claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<RenderTemplateRequest>,
+    service: web::Data<ConfigTemplateService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling render_template request");
+
+    let preview = service.render(path.into_inner(), &request.variables).await?;
+
+    Ok(HttpResponse::Ok().json(preview))
+}
+
+/// Render a configuration template and apply it to many nodes
+///
+/// POST /api/templates/:id/apply
+///
+/// One commit per node. Returns the outcome on every node.
+pub async fn apply_template(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<ApplyTemplateRequest>,
+    service: web::Data<ConfigTemplateService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling apply_template request");
+
+    request.validate()?;
+
+    let id = path.into_inner();
+    let results = service.apply(id, request.into_inner(), &claims).await?;
+    let failed = results.iter().filter(|result| !result.success).count();
+    let outcome = if failed == 0 { AuditResult::Success } else { AuditResult::Failure };
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config_template.apply", outcome)
+                .with_target(id.to_string())
+                .with_details(serde_json::json!({ "nodes": results.len(), "failed": failed })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(results))
+}
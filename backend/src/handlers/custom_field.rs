@@ -0,0 +1,129 @@
+//! Custom Field Handlers Module
+//!
+//! This module contains HTTP request handlers for the custom fields of the
+//! node inventory. Anyone signed in can read the definitions, which forms
+//! need to render the fields; only administrators change them. Values are
+//! set through the node endpoints.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::custom_field::{CreateCustomFieldRequest, UpdateCustomFieldRequest};
+use crate::services::{AuditService, CustomFieldService};
+
+/// List custom fields
+///
+/// GET /api/nodes/custom-fields
+pub async fn list_custom_fields(
+    _claims: Claims,
+    service: web::Data<CustomFieldService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_custom_fields request");
+
+    let fields = service.list_fields().await?;
+
+    Ok(HttpResponse::Ok().json(fields))
+}
+
+/// Get a custom field
+///
+/// GET /api/nodes/custom-fields/{id}
+pub async fn get_custom_field(
+    _claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<CustomFieldService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_custom_field request");
+
+    let field = service.get_field(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(field))
+}
+
+/// Define a custom field
+///
+/// POST /api/nodes/custom-fields
+///
+/// Request body:
+/// ```json
+/// { "name": "site_code", "label": "Site code", "field_type": "text", "pattern": "[A-Z]{3}[0-9]" }
+/// ```
+///
+/// `field_type` is text, integer, number, boolean, date, or select; select
+/// fields list their `options`.
+pub async fn create_custom_field(
+    claims: Claims,
+    request: web::Json<CreateCustomFieldRequest>,
+    service: web::Data<CustomFieldService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_custom_field request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let request = request.into_inner();
+    request.validate()?;
+    let field = service.create_field(request).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "custom_field.create", AuditResult::Success)
+                .with_target(field.name.clone())
+                .with_details(serde_json::json!({ "field_type": field.field_type, "required": field.required })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(field))
+}
+
+/// Update a custom field
+///
+/// PUT /api/nodes/custom-fields/{id}
+pub async fn update_custom_field(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateCustomFieldRequest>,
+    service: web::Data<CustomFieldService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_custom_field request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let request = request.into_inner();
+    request.validate()?;
+    let field = service.update_field(path.into_inner(), request).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "custom_field.update", AuditResult::Success)
+                .with_target(field.name.clone()),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(field))
+}
+
+/// Delete a custom field with its values on every node
+///
+/// DELETE /api/nodes/custom-fields/{id}
+pub async fn delete_custom_field(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<CustomFieldService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling delete_custom_field request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let field = service.delete_field(path.into_inner()).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "custom_field.delete", AuditResult::Success)
+                .with_target(field.name),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
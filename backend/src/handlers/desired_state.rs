@@ -0,0 +1,65 @@
+//! Desired State Handlers Module
+//!
+//! This module contains the HTTP request handler converging a node to a
+//! desired configuration, given as a configuration tree or `set` commands.
+
+use actix_web::{web, HttpResponse};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::desired_state::DesiredStateRequest;
+use crate::services::{AuditService, DesiredStateService, NodeService, TeamService};
+
+/// Converge a node to a desired configuration
+///
+/// POST /api/nodes/{id}/config/desired
+///
+/// Computes the set and delete operations turning the node's configuration
+/// below `path` into the desired one and applies them as one commit. With
+/// `dry_run`, the operations are only returned; passing the preview's
+/// `base_hash` when applying refuses the apply if the node changed since.
+pub async fn apply_desired_state(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    req: web::Json<DesiredStateRequest>,
+    service: web::Data<DesiredStateService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    info!("Handling apply_desired_state request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let request = req.into_inner();
+    if request.dry_run {
+        let plan = service.converge(node_id, request, &claims).await?;
+        return Ok(HttpResponse::Ok().json(plan));
+    }
+
+    let scope = request.path.clone();
+    let comment = request.comment.clone();
+    let result = service.converge(node_id, request, &claims).await;
+    let (event, details) = match &result {
+        Ok(plan) => (
+            AuditEvent::new(Some(&claims), "config.desired_state.apply", AuditResult::Success),
+            serde_json::json!({
+                "path": scope,
+                "comment": comment,
+                "deletes": plan.deletes,
+                "sets": plan.sets,
+            }),
+        ),
+        Err(e) => (
+            AuditEvent::new(Some(&claims), "config.desired_state.apply", AuditResult::Failure),
+            serde_json::json!({ "path": scope, "comment": comment, "error": e.to_string() }),
+        ),
+    };
+    audit_service.record(event.with_node(node_id).with_details(details)).await;
+
+    Ok(HttpResponse::Ok().json(result?))
+}
@@ -0,0 +1,40 @@
+//! Event Handlers Module
+//!
+//! This module contains HTTP request handlers for replaying the domain events
+//! published over the WebSocket.
+
+use actix_web::{web, HttpResponse};
+use tracing::debug;
+
+use crate::error::AppResult;
+use crate::models::auth::Claims;
+use crate::models::event::EventReplayQuery;
+use crate::services::{ChannelAccessService, EventBus};
+
+/// Replay events
+///
+/// GET /api/events?after=42
+///
+/// Returns the events published after a sequence number, oldest first, so
+/// clients that connect late or reconnect can catch up. Continue with the
+/// returned `cursor` while `has_more` is set. Events on channels the
+/// caller may not subscribe to are left out, and asking for such a channel
+/// is refused.
+///
+/// Query parameters:
+/// - after: Sequence number of the last event seen (default: 0)
+/// - channels: Comma-separated channels to include; events sent to every
+///   connection are always included
+/// - limit: Maximum number of events (default and maximum: 1000)
+pub async fn replay_events(
+    claims: Claims,
+    query: web::Query<EventReplayQuery>,
+    event_bus: web::Data<EventBus>,
+    channel_access: web::Data<ChannelAccessService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling replay_events request");
+
+    let response = event_bus.replay(query.into_inner(), &claims, &channel_access).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
@@ -0,0 +1,331 @@
+//! Firewall Handlers Module
+//!
+//! This module contains HTTP request handlers for the rule sets, rules,
+//! groups, and zones of a node's firewall.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{AppError, AppResult};
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::firewall::{
+    FirewallFamily, FirewallGroupKind, FirewallGroupSettings, FirewallRuleSetSettings, FirewallRuleSettings,
+    FirewallZoneSettings, ReorderFirewallRulesRequest,
+};
+use crate::services::{AuditService, FirewallService, NodeService, TeamService};
+
+fn family(name: &str) -> AppResult<FirewallFamily> {
+    FirewallFamily::from_name(name)
+        .ok_or_else(|| AppError::Validation(format!("Unknown address family '{}'; use ipv4 or ipv6", name)))
+}
+
+fn group_kind(name: &str) -> AppResult<FirewallGroupKind> {
+    FirewallGroupKind::from_name(name).ok_or_else(|| AppError::Validation(format!("Unknown group kind '{}'", name)))
+}
+
+/// Get the rule sets, groups, and zones of a node
+///
+/// GET /api/nodes/{id}/firewall
+pub async fn get_firewall(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_firewall request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let firewall = service.firewall(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(firewall))
+}
+
+/// Get one rule set of a node
+///
+/// GET /api/nodes/{id}/firewall/{family}/rule-sets/{name}
+pub async fn get_rule_set(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, family_name, name) = path.into_inner();
+    debug!("Handling get_rule_set request for {} {} on node {}", family_name, name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let rule_set = service.rule_set(node_id, family(&family_name)?, &name).await?;
+
+    Ok(HttpResponse::Ok().json(rule_set))
+}
+
+/// Create or replace a rule set with all of its rules
+///
+/// PUT /api/nodes/{id}/firewall/{family}/rule-sets/{name}
+///
+/// The names `forward`, `input`, and `output` stand for the base chains of
+/// the family; other names are named rule sets.
+pub async fn set_rule_set(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    req: web::Json<FirewallRuleSetSettings>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, family_name, name) = path.into_inner();
+    info!("Handling set_rule_set request for {} {} on node {}", family_name, name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let rule_set = service
+        .set_rule_set(node_id, family(&family_name)?, &name, req.into_inner(), &claims)
+        .await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "firewall.rule_set.update", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(rule_set.path.clone())
+                .with_details(serde_json::to_value(&rule_set.settings)?),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(rule_set))
+}
+
+/// Remove a rule set that no rule or zone refers to
+///
+/// DELETE /api/nodes/{id}/firewall/{family}/rule-sets/{name}
+pub async fn delete_rule_set(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, family_name, name) = path.into_inner();
+    info!("Handling delete_rule_set request for {} {} on node {}", family_name, name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.delete_rule_set(node_id, family(&family_name)?, &name, &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "firewall.rule_set.delete", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(format!("{} {}", family_name, name)),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Put the rules of a rule set in a new order
+///
+/// POST /api/nodes/{id}/firewall/{family}/rule-sets/{name}/reorder
+pub async fn reorder_rules(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    req: web::Json<ReorderFirewallRulesRequest>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, family_name, name) = path.into_inner();
+    info!("Handling reorder_rules request for {} {} on node {}", family_name, name, node_id);
+
+    req.validate()?;
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let rule_set = service
+        .reorder_rules(node_id, family(&family_name)?, &name, &req.rules, &claims)
+        .await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "firewall.rule_set.reorder", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(rule_set.path.clone())
+                .with_details(serde_json::json!({ "order": req.rules })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(rule_set))
+}
+
+/// Create or replace one rule of a rule set
+///
+/// PUT /api/nodes/{id}/firewall/{family}/rule-sets/{name}/rules/{number}
+pub async fn set_rule(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String, u32)>,
+    req: web::Json<FirewallRuleSettings>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, family_name, name, number) = path.into_inner();
+    info!("Handling set_rule request for {} {} rule {} on node {}", family_name, name, number, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let rule = service
+        .set_rule(node_id, family(&family_name)?, &name, number, req.into_inner(), &claims)
+        .await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "firewall.rule.update", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(format!("{} {} rule {}", family_name, name, number))
+                .with_details(serde_json::to_value(&rule.settings)?),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(rule))
+}
+
+/// Remove one rule of a rule set
+///
+/// DELETE /api/nodes/{id}/firewall/{family}/rule-sets/{name}/rules/{number}
+pub async fn delete_rule(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String, u32)>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, family_name, name, number) = path.into_inner();
+    info!("Handling delete_rule request for {} {} rule {} on node {}", family_name, name, number, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.delete_rule(node_id, family(&family_name)?, &name, number, &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "firewall.rule.delete", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(format!("{} {} rule {}", family_name, name, number)),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Create or replace a group
+///
+/// PUT /api/nodes/{id}/firewall/groups/{kind}/{name}
+pub async fn set_group(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    req: web::Json<FirewallGroupSettings>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, kind_name, name) = path.into_inner();
+    info!("Handling set_group request for {} {} on node {}", kind_name, name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let group = service
+        .set_group(node_id, group_kind(&kind_name)?, &name, req.into_inner(), &claims)
+        .await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "firewall.group.update", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(format!("{} {}", kind_name, name))
+                .with_details(serde_json::to_value(&group.settings)?),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(group))
+}
+
+/// Remove a group that no rule refers to
+///
+/// DELETE /api/nodes/{id}/firewall/groups/{kind}/{name}
+pub async fn delete_group(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, kind_name, name) = path.into_inner();
+    info!("Handling delete_group request for {} {} on node {}", kind_name, name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.delete_group(node_id, group_kind(&kind_name)?, &name, &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "firewall.group.delete", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(format!("{} {}", kind_name, name)),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Create or replace a zone
+///
+/// PUT /api/nodes/{id}/firewall/zones/{name}
+pub async fn set_zone(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    req: web::Json<FirewallZoneSettings>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, name) = path.into_inner();
+    info!("Handling set_zone request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let zone = service.set_zone(node_id, &name, req.into_inner(), &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "firewall.zone.update", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(name)
+                .with_details(serde_json::to_value(&zone.settings)?),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(zone))
+}
+
+/// Remove a zone that no other zone filters traffic from
+///
+/// DELETE /api/nodes/{id}/firewall/zones/{name}
+pub async fn delete_zone(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    service: web::Data<FirewallService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, name) = path.into_inner();
+    info!("Handling delete_zone request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.delete_zone(node_id, &name, &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "firewall.zone.delete", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(name),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,48 @@
+//! Firewall Log Handlers Module
+//!
+//! This module contains HTTP request handlers for the packets that node
+//! firewall rules log over syslog.
+
+use actix_web::{web, HttpResponse};
+
+use crate::error::AppResult;
+use crate::models::auth::Claims;
+use crate::models::firewall_log::{FirewallLogQuery, FirewallRuleHitsQuery};
+use crate::services::{FirewallLogService, TeamService};
+
+/// List firewall log entries
+///
+/// GET /api/network/firewall/logs
+///
+/// Filters by node, rule, action, protocol, addresses, destination port,
+/// and time range, which may be relative (`from=-1h`, `window=30m`) or a
+/// named `preset`. Newest entries come first.
+pub async fn list_firewall_logs(
+    claims: Claims,
+    query: web::Query<FirewallLogQuery>,
+    service: web::Data<FirewallLogService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let scope = team_service.access_scope(&claims).await?;
+    let logs = service.list(query.into_inner(), &scope).await?;
+
+    Ok(HttpResponse::Ok().json(logs))
+}
+
+/// Get the recent hits of each firewall rule
+///
+/// GET /api/network/firewall/rules/hits
+///
+/// Returns the number of packets each rule logged since a point in time,
+/// 24 hours ago by default, with its most recent entries.
+pub async fn get_firewall_rule_hits(
+    claims: Claims,
+    query: web::Query<FirewallRuleHitsQuery>,
+    service: web::Data<FirewallLogService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let scope = team_service.access_scope(&claims).await?;
+    let hits = service.rule_hits(query.into_inner(), &scope).await?;
+
+    Ok(HttpResponse::Ok().json(hits))
+}
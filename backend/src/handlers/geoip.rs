@@ -0,0 +1,69 @@
+//! GeoIP Handlers Module
+//!
+//! This module contains HTTP request handlers that tag IP addresses with
+//! their country and autonomous system.
+
+use actix_web::{web, HttpResponse};
+
+use crate::error::{AppError, AppResult};
+use crate::models::auth::Claims;
+use crate::models::geoip::{GeoIpBatchRequest, GeoIpBatchResponse, GeoIpQuery, MAX_GEOIP_LOOKUPS};
+use crate::services::{AuditService, GeoIpService};
+
+/// Look up the location of an IP address
+///
+/// GET /api/tools/geoip?ip=203.0.113.7
+///
+/// `geo` is null for internal addresses and when no GeoIP database is
+/// configured.
+pub async fn lookup_ip(
+    _claims: Claims,
+    query: web::Query<GeoIpQuery>,
+    service: web::Data<GeoIpService>,
+) -> AppResult<HttpResponse> {
+    let lookup = service.lookup(&query.ip)?;
+
+    Ok(HttpResponse::Ok().json(lookup))
+}
+
+/// Look up the locations of several IP addresses
+///
+/// POST /api/tools/geoip
+///
+/// Accepts up to 1000 addresses.
+pub async fn lookup_ips(
+    _claims: Claims,
+    request: web::Json<GeoIpBatchRequest>,
+    service: web::Data<GeoIpService>,
+) -> AppResult<HttpResponse> {
+    if request.ips.len() > MAX_GEOIP_LOOKUPS {
+        return Err(AppError::Validation(format!(
+            "At most {} IP addresses can be looked up at once",
+            MAX_GEOIP_LOOKUPS
+        )));
+    }
+
+    let results = request
+        .ips
+        .iter()
+        .map(|ip| service.lookup(ip))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(HttpResponse::Ok().json(GeoIpBatchResponse {
+        enabled: service.is_enabled(),
+        results,
+    }))
+}
+
+/// Get the state of the GeoIP databases
+///
+/// GET /api/admin/geoip
+pub async fn get_geoip_status(
+    claims: Claims,
+    service: web::Data<GeoIpService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    Ok(HttpResponse::Ok().json(service.status()))
+}
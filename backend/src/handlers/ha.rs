@@ -0,0 +1,53 @@
+//! High Availability Handlers Module
+//!
+//! This module contains HTTP request handlers for the VRRP and
+//! conntrack-sync state of nodes, and their history of VRRP transitions.
+
+use actix_web::{web, HttpResponse};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::auth::Claims;
+use crate::models::ha::VrrpTransitionQuery;
+use crate::services::{HaService, NodeService, TeamService};
+
+/// Get the VRRP groups and conntrack-sync statistics of a node
+///
+/// GET /api/nodes/{id}/ha
+pub async fn get_ha_status(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<HaService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_ha_status request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let status = service.status(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// Get the VRRP transitions of a node, newest first
+///
+/// GET /api/nodes/{id}/ha/transitions
+pub async fn get_vrrp_transitions(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    query: web::Query<VrrpTransitionQuery>,
+    service: web::Data<HaService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_vrrp_transitions request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let transitions = service.transitions(node_id, query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(transitions))
+}
@@ -1,6 +1,8 @@
-use actix_web::HttpResponse;
+use actix_web::{web, HttpResponse};
 
+use crate::db::Database;
 use crate::error::AppResult;
+use crate::websocket::ConnectionManager;
 
 /// Handle GET /api/health
 pub async fn health_check() -> AppResult<HttpResponse> {
@@ -18,6 +20,22 @@ pub async fn detailed_health_check() -> AppResult<HttpResponse> {
         "service": "vyos-web-ui-backend",
         "version": env!("CARGO_PKG_VERSION"),
         "database": "connected",
-        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "timestamp": crate::models::timestamp::db_now(),
     })))
-}
\ No newline at end of file
+}
+/// Handle GET /api/metrics
+///
+/// Database query timings and WebSocket queue metrics in the Prometheus
+/// text exposition format.
+pub async fn prometheus_metrics(
+    db: web::Data<Database>,
+    connections: web::Data<ConnectionManager>,
+) -> AppResult<HttpResponse> {
+    let mut body = String::new();
+    db.query_metrics().render_prometheus(&mut body);
+    connections.render_prometheus(&mut body);
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(body))
+}
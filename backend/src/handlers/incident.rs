@@ -0,0 +1,105 @@
+//! Incident Handlers Module
+//!
+//! This module contains HTTP request handlers for incidents. Alerts are
+//! grouped into incidents before incidents are read, so the API is current
+//! even between runs of the background correlation task.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::incident::{CreateIncidentCommentRequest, IncidentListQuery, UpdateIncidentRequest};
+use crate::services::{AuditService, IncidentService};
+
+/// List incidents
+///
+/// GET /api/incidents
+///
+/// Query parameters:
+/// - status: open, acknowledged or resolved
+/// - node_id: Node the incident's alerts came from
+/// - page, page_size: Pagination
+pub async fn list_incidents(
+    _claims: Claims,
+    query: web::Query<IncidentListQuery>,
+    service: web::Data<IncidentService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_incidents request");
+
+    service.sync_alerts().await?;
+    let response = service.list_incidents(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Get an incident
+///
+/// GET /api/incidents/:id
+///
+/// Returns the incident with its alerts, comments, and the configuration
+/// commits made on the node while it was unresolved.
+pub async fn get_incident(
+    _claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<IncidentService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_incident request");
+
+    service.sync_alerts().await?;
+    let detail = service.get_incident_detail(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(detail))
+}
+
+/// Acknowledge, resolve, or reopen an incident
+///
+/// PUT /api/incidents/:id
+///
+/// Request body:
+/// ```json
+/// { "status": "acknowledged" }
+/// ```
+pub async fn update_incident(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateIncidentRequest>,
+    service: web::Data<IncidentService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_incident request");
+
+    let id = path.into_inner();
+    let status = request.into_inner().status;
+    let incident = service.update_status(id, &claims, status).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "incident.update", AuditResult::Success)
+                .with_target(id.to_string())
+                .with_details(serde_json::json!({ "status": status })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(incident))
+}
+
+/// Comment on an incident
+///
+/// POST /api/incidents/:id/comments
+pub async fn add_incident_comment(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<CreateIncidentCommentRequest>,
+    service: web::Data<IncidentService>,
+) -> AppResult<HttpResponse> {
+    request.validate()?;
+
+    let comment = service
+        .add_comment(path.into_inner(), &claims, request.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Created().json(comment))
+}
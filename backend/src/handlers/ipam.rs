@@ -0,0 +1,30 @@
+//! IPAM Handlers Module
+//!
+//! This module contains HTTP request handlers for the address plan built
+//! from the interfaces of all nodes.
+
+use actix_web::{web, HttpResponse};
+use tracing::debug;
+
+use crate::error::AppResult;
+use crate::models::auth::Claims;
+use crate::services::{IpamService, TeamService};
+
+/// Get the address plan
+///
+/// GET /api/network/ipam
+///
+/// Lists the subnets in use on the nodes the caller can access, with their
+/// utilization, and reports addressing conflicts between interfaces.
+pub async fn get_ipam(
+    claims: Claims,
+    ipam_service: web::Data<IpamService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_ipam request");
+
+    let scope = team_service.access_scope(&claims).await?;
+    let plan = ipam_service.address_plan(&scope).await?;
+
+    Ok(HttpResponse::Ok().json(plan))
+}
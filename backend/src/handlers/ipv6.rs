@@ -0,0 +1,220 @@
+//! IPv6 Handlers Module
+//!
+//! This module contains HTTP request handlers for router advertisements,
+//! the DHCPv6 server with prefix delegation, and the IPv6 neighbor and
+//! prefix state of a node.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::ipv6::{Dhcpv6NetworkSettings, RouterAdvertSettings};
+use crate::services::{AuditService, Ipv6Service, NodeService, TeamService};
+
+/// Get the router advertisement settings of a node
+///
+/// GET /api/nodes/{id}/ipv6/router-advert
+pub async fn get_router_adverts(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<Ipv6Service>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_router_adverts request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let adverts = service.router_adverts(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(adverts))
+}
+
+/// Replace the router advertisement settings of an interface
+///
+/// PUT /api/nodes/{id}/ipv6/router-advert/{interface}
+///
+/// Prefixes, flags, and name servers left out of the request are removed
+/// from the interface.
+pub async fn set_router_advert(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    req: web::Json<RouterAdvertSettings>,
+    service: web::Data<Ipv6Service>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, interface) = path.into_inner();
+    info!("Handling set_router_advert request for {} on node {}", interface, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let advert = service
+        .set_router_advert(node_id, &interface, req.into_inner(), &claims)
+        .await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "ipv6.router_advert.update", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(interface)
+                .with_details(serde_json::to_value(&advert.settings)?),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(advert))
+}
+
+/// Stop sending router advertisements on an interface
+///
+/// DELETE /api/nodes/{id}/ipv6/router-advert/{interface}
+pub async fn delete_router_advert(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    service: web::Data<Ipv6Service>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, interface) = path.into_inner();
+    info!("Handling delete_router_advert request for {} on node {}", interface, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.delete_router_advert(node_id, &interface, &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "ipv6.router_advert.delete", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(interface),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Get the DHCPv6 shared networks of a node
+///
+/// GET /api/nodes/{id}/ipv6/dhcpv6-server
+pub async fn get_dhcpv6_networks(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<Ipv6Service>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_dhcpv6_networks request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let networks = service.dhcpv6_networks(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(networks))
+}
+
+/// Create or replace a DHCPv6 shared network
+///
+/// PUT /api/nodes/{id}/ipv6/dhcpv6-server/{name}
+///
+/// Each subnet may lease addresses from ranges and delegate prefixes from
+/// pools to requesting routers.
+pub async fn set_dhcpv6_network(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    req: web::Json<Dhcpv6NetworkSettings>,
+    service: web::Data<Ipv6Service>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, name) = path.into_inner();
+    info!("Handling set_dhcpv6_network request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let network = service
+        .set_dhcpv6_network(node_id, &name, req.into_inner(), &claims)
+        .await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "ipv6.dhcpv6_server.update", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(name)
+                .with_details(serde_json::to_value(&network.settings)?),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(network))
+}
+
+/// Remove a DHCPv6 shared network
+///
+/// DELETE /api/nodes/{id}/ipv6/dhcpv6-server/{name}
+pub async fn delete_dhcpv6_network(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    service: web::Data<Ipv6Service>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, name) = path.into_inner();
+    info!("Handling delete_dhcpv6_network request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.delete_dhcpv6_network(node_id, &name, &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "ipv6.dhcpv6_server.delete", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(name),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Get the IPv6 neighbor cache of a node
+///
+/// GET /api/nodes/{id}/ipv6/neighbors
+///
+/// Returns the last-known neighbors marked as stale while the node cannot
+/// be reached.
+pub async fn get_ipv6_neighbors(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<Ipv6Service>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_ipv6_neighbors request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let neighbors = service.neighbors(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(neighbors))
+}
+
+/// Get the IPv6 prefixes on the interfaces of a node
+///
+/// GET /api/nodes/{id}/ipv6/prefixes
+///
+/// Each prefix carries its scope and whether the interface announces it in
+/// router advertisements.
+pub async fn get_ipv6_prefixes(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<Ipv6Service>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_ipv6_prefixes request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let prefixes = service.prefixes(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(prefixes))
+}
@@ -0,0 +1,65 @@
+//! Load Profile Handlers Module
+//!
+//! This module contains the HTTP targets for load generators such as k6 or
+//! oha. They need no authentication and answer 404 unless
+//! `LOAD_PROFILE_ENABLED` is set.
+
+use actix_web::{web, HttpResponse};
+
+use crate::error::AppResult;
+use crate::models::load_profile::{ConfigTreeProfile, MetricsProfile};
+use crate::services::LoadProfileService;
+
+/// Answer without touching the service layer, as a baseline
+///
+/// GET /api/load-profile/ping
+pub async fn profile_ping(service: web::Data<LoadProfileService>) -> AppResult<HttpResponse> {
+    service.ensure_enabled()?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "status": "ok" })))
+}
+
+/// Generate and serialize a configuration tree
+///
+/// GET /api/load-profile/config-tree?breadth=8&depth=3
+pub async fn profile_config_tree(
+    query: web::Query<ConfigTreeProfile>,
+    service: web::Data<LoadProfileService>,
+) -> AppResult<HttpResponse> {
+    let tree = service.config_tree(query.into_inner())?;
+
+    Ok(HttpResponse::Ok().json(tree))
+}
+
+/// Diff two generated configuration trees
+///
+/// GET /api/load-profile/config-diff?breadth=8&depth=3
+pub async fn profile_config_diff(
+    query: web::Query<ConfigTreeProfile>,
+    service: web::Data<LoadProfileService>,
+) -> AppResult<HttpResponse> {
+    let diff = service.config_diff(query.into_inner())?;
+
+    Ok(HttpResponse::Ok().json(diff))
+}
+
+/// Aggregate generated metric samples
+///
+/// GET /api/load-profile/metrics?samples=1000
+pub async fn profile_metrics(
+    query: web::Query<MetricsProfile>,
+    service: web::Data<LoadProfileService>,
+) -> AppResult<HttpResponse> {
+    let statistics = service.metrics(query.into_inner())?;
+
+    Ok(HttpResponse::Ok().json(statistics))
+}
+
+/// Run one query through the database connection pool
+///
+/// GET /api/load-profile/database
+pub async fn profile_database(service: web::Data<LoadProfileService>) -> AppResult<HttpResponse> {
+    let result = service.database().await?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
@@ -0,0 +1,106 @@
+//! MAC Vendor Handlers Module
+//!
+//! This module contains HTTP request handlers that resolve MAC addresses to
+//! vendor names and let administrators update the vendor database.
+
+use actix_web::{web, HttpResponse};
+use tracing::{info, warn};
+
+use crate::error::{AppError, AppResult};
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::mac_vendor::{
+    MacLookupBatchRequest, MacLookupBatchResponse, MacLookupQuery, MAX_MAC_LOOKUPS,
+};
+use crate::services::{AuditService, MacVendorService};
+
+/// Look up the vendor of a MAC address
+///
+/// GET /api/tools/mac-lookup?mac=00:50:56:ab:cd:ef
+pub async fn lookup_mac(
+    _claims: Claims,
+    query: web::Query<MacLookupQuery>,
+    service: web::Data<MacVendorService>,
+) -> AppResult<HttpResponse> {
+    let lookup = service.lookup(&query.mac).await?;
+
+    Ok(HttpResponse::Ok().json(lookup))
+}
+
+/// Look up the vendors of several MAC addresses
+///
+/// POST /api/tools/mac-lookup
+///
+/// Accepts up to 1000 addresses, e.g. the entries of an ARP table.
+pub async fn lookup_macs(
+    _claims: Claims,
+    request: web::Json<MacLookupBatchRequest>,
+    service: web::Data<MacVendorService>,
+) -> AppResult<HttpResponse> {
+    if request.macs.len() > MAX_MAC_LOOKUPS {
+        return Err(AppError::Validation(format!(
+            "At most {} MAC addresses can be looked up at once",
+            MAX_MAC_LOOKUPS
+        )));
+    }
+
+    let mut results = Vec::with_capacity(request.macs.len());
+    for mac in &request.macs {
+        results.push(service.lookup(mac).await?);
+    }
+
+    Ok(HttpResponse::Ok().json(MacLookupBatchResponse { results }))
+}
+
+/// Get the state of the MAC vendor database
+///
+/// GET /api/admin/oui
+pub async fn get_oui_database(
+    claims: Claims,
+    service: web::Data<MacVendorService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let status = service.status().await?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// Update the MAC vendor database
+///
+/// POST /api/admin/oui/update
+///
+/// Downloads the IEEE registry from the configured URL and replaces the
+/// vendor database with it.
+pub async fn update_oui_database(
+    claims: Claims,
+    service: web::Data<MacVendorService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_oui_database request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let status = match service.update().await {
+        Ok(status) => status,
+        Err(e) => {
+            warn!("MAC vendor database update failed: {}", e);
+            audit_service
+                .record(
+                    AuditEvent::new(Some(&claims), "oui.update", AuditResult::Failure)
+                        .with_details(serde_json::json!({ "error": e.to_string() })),
+                )
+                .await;
+            return Err(e);
+        }
+    };
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "oui.update", AuditResult::Success)
+                .with_details(serde_json::json!({ "entries": status.entries })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(status))
+}
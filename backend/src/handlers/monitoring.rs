@@ -3,15 +3,25 @@
 //! This module contains handlers for all monitoring-related API endpoints
 //! including metrics retrieval, alerts, network statistics, and historical data.
 
+use std::collections::{HashMap, HashSet};
+
 use actix_web::{web, HttpResponse};
+use futures::StreamExt;
 use uuid::Uuid;
+use validator::Validate;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::handlers::node::authorize_node;
+use crate::models::auth::Claims;
+use crate::models::fields::FieldSelection;
 use crate::models::monitoring::{
-    AlertOperator, AlertSeverity, AlertStatus, MetricsQuery,
-    MetricType,
+    AlertCondition, AlertOperator, AlertRuleBundle, AlertSeverity, AlertStatus,
+    ApplyAlertBundlesRequest, ConditionCombinator, InterfaceBandwidthAlertRequest, MetricsExportQuery, MetricsQuery,
+    MetricType, ThresholdOverride, TopNodesQuery,
 };
 use crate::services::monitoring::{AlertRuleCreate, AlertRuleUpdate, MonitoringService};
+use crate::services::{AuditService, NodeService, TeamService};
 
 /// Get system metrics (CPU, memory, disk, network)
 ///
@@ -19,14 +29,16 @@ use crate::services::monitoring::{AlertRuleCreate, AlertRuleUpdate, MonitoringSe
 ///
 /// Query parameters:
 /// - node_id: Optional node ID filter (defaults to 'default')
+/// - fields: Optional comma-separated fields to return (e.g. `cpu.usage_percent,memory`)
 pub async fn get_system_metrics(
     service: web::Data<MonitoringService>,
     query: web::Query<SystemMetricsQuery>,
+    fields: FieldSelection,
 ) -> AppResult<HttpResponse> {
     let node_id = query.node_id.as_deref();
     let metrics = service.get_system_metrics(node_id).await?;
 
-    Ok(HttpResponse::Ok().json(metrics))
+    Ok(HttpResponse::Ok().json(fields.project(&metrics)?))
 }
 
 /// Get network traffic statistics
@@ -58,8 +70,10 @@ pub async fn get_network_statistics(
 /// - node_id: Optional node ID filter
 /// - metric_name: Optional metric name filter
 /// - metric_type: Optional metric type filter
-/// - start_time: Optional start time (ISO 8601)
-/// - end_time: Optional end time (ISO 8601)
+/// - start_time (or start): Optional start time (ISO 8601, or relative such as `-6h`)
+/// - end_time (or end): Optional end time (ISO 8601, or relative)
+/// - window: Optional span such as `7d`, ending at end_time or now
+/// - preset: Optional named range (`last_hour`, `last_24h`, `today`, ...)
 /// - limit: Optional result limit
 /// - sort_order: Optional sort order (asc/desc)
 pub async fn get_history(
@@ -72,6 +86,95 @@ pub async fn get_history(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Export metrics of nodes as CSV
+///
+/// GET /api/monitoring/export?metrics=cpu_usage_percent,interface_rx_bps&node_ids=...&window=30d&format=wide
+///
+/// Query parameters:
+/// - metrics: Comma-separated metric names
+/// - node_ids: Optional comma-separated node IDs; all accessible nodes if unset
+/// - start_time, end_time, window, preset: Time range, as for the history
+/// - format: `long` (default), one row per data point, or `wide`, one row
+///   per node and time with a column per metric and label set
+///
+/// The CSV is streamed from the metrics history in time order.
+pub async fn export_metrics(
+    claims: Claims,
+    query: web::Query<MetricsExportQuery>,
+    service: web::Data<MonitoringService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let query = query.into_inner();
+    let requested = query
+        .node_ids
+        .as_deref()
+        .map(|ids| {
+            ids.split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(|id| {
+                    Uuid::parse_str(id).map_err(|_| AppError::Validation(format!("'{}' is not a node ID", id)))
+                })
+                .collect::<Result<HashSet<Uuid>, _>>()
+        })
+        .transpose()?;
+
+    let scope = team_service.access_scope(&claims).await?;
+    let nodes: HashMap<String, String> = node_service
+        .list_all_nodes()
+        .await?
+        .into_iter()
+        .filter(|node| scope.can_access(node.team_id))
+        .filter(|node| requested.as_ref().is_none_or(|requested| requested.contains(&node.id)))
+        .map(|node| (node.id.to_string(), node.name))
+        .collect();
+    if let Some(missing) = requested.iter().flatten().find(|id| !nodes.contains_key(&id.to_string())) {
+        return Err(AppError::NotFound(format!("Node {} not found", missing)));
+    }
+
+    let chunks = service.export_metrics(query, nodes)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(("Content-Disposition", "attachment; filename=\"metrics.csv\""))
+        .streaming(chunks.map(|chunk| chunk.map(web::Bytes::from))))
+}
+
+/// Get the nodes with the worst values of a metric
+///
+/// GET /api/monitoring/top?metric=cpu_usage_percent&n=10&window=24h
+///
+/// Query parameters:
+/// - metric: Metric name
+/// - n: Number of nodes, at most 100 (default 10)
+/// - order: `desc` (default) ranks the highest values worst, `asc` the lowest
+/// - start_time, end_time, window, preset: Window to average over, as for
+///   the history; the last hour by default
+///
+/// Returns the accessible nodes ranked by their latest value and by their
+/// average over the window.
+pub async fn get_top_nodes(
+    claims: Claims,
+    query: web::Query<TopNodesQuery>,
+    service: web::Data<MonitoringService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let scope = team_service.access_scope(&claims).await?;
+    let nodes: HashMap<String, String> = node_service
+        .list_all_nodes()
+        .await?
+        .into_iter()
+        .filter(|node| scope.can_access(node.team_id))
+        .map(|node| (node.id.to_string(), node.name))
+        .collect();
+
+    let response = service.top_nodes(query.into_inner(), &nodes).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 /// Get system alerts
 ///
 /// GET /api/monitoring/alerts
@@ -111,9 +214,21 @@ pub async fn get_alerts(
 ///   "operator": "greater_than",
 ///   "severity": "critical",
 ///   "for_seconds": 300,
-///   "labels": []
+///   "labels": [],
+///   "node_tag": "edge",
+///   "threshold_overrides": [
+///     { "node_id": "5f0c…", "threshold": 75.0 }
+///   ],
+///   "conditions": [
+///     { "metric_name": "load_average_1m", "metric_type": "load", "operator": "greater_than", "threshold": 8.0 }
+///   ],
+///   "combine": "and"
 /// }
 /// ```
+///
+/// With `node_tag` set the rule covers every node carrying the tag, including
+/// nodes tagged later. `conditions` are checked together with the rule's own
+/// metric, all of them (`and`, the default) or any (`or`) for `for_seconds`.
 pub async fn create_alert(
     service: web::Data<MonitoringService>,
     rule: web::Json<AlertRuleCreateRequest>,
@@ -130,6 +245,10 @@ pub async fn create_alert(
         severity: request.severity,
         for_seconds: request.for_seconds,
         labels: request.labels,
+        node_tag: request.node_tag,
+        threshold_overrides: request.threshold_overrides,
+        conditions: request.conditions,
+        combine: request.combine,
     };
 
     let created_rule = service.create_alert_rule(rule_create).await?;
@@ -137,6 +256,66 @@ pub async fn create_alert(
     Ok(HttpResponse::Created().json(created_rule))
 }
 
+/// Alert when an interface of a node stays busy
+///
+/// POST /api/nodes/{id}/interfaces/{name}/bandwidth-alert
+///
+/// Creates a rule on the interface's utilization, by default above 80% of
+/// its link speed for 10 minutes, replacing the rule created for the
+/// interface before. The link speed is read from the node; interfaces
+/// whose speed it cannot report are rejected.
+///
+/// Request body (all optional):
+/// ```json
+/// {
+///   "threshold_percent": 80,
+///   "for_minutes": 10,
+///   "severity": "warning"
+/// }
+/// ```
+pub async fn create_interface_bandwidth_alert(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    request: Option<web::Json<InterfaceBandwidthAlertRequest>>,
+    service: web::Data<MonitoringService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, interface) = path.into_inner();
+    let request = request.map(|request| request.into_inner()).unwrap_or_default();
+    request.validate()?;
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let link_speed_mbps = node_service.get_link_speed(node_id, &interface).await?.ok_or_else(|| {
+        AppError::Validation(format!(
+            "Node {} reports no link speed for {}; is the link up?",
+            node_id, interface
+        ))
+    })?;
+    let rule = service
+        .create_bandwidth_alert(node_id, &interface, link_speed_mbps, request)
+        .await?;
+
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "alert_rule.bandwidth_preset", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(interface)
+                .with_details(serde_json::json!({
+                    "rule_id": rule.id,
+                    "threshold_percent": rule.threshold,
+                    "link_speed_mbps": link_speed_mbps,
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({
+        "rule": rule,
+        "link_speed_mbps": link_speed_mbps
+    })))
+}
+
 /// Update an alert rule
 ///
 /// PUT /api/monitoring/alerts/{id}
@@ -168,6 +347,10 @@ pub async fn update_alert(
         for_seconds: request.for_seconds,
         enabled: request.enabled,
         labels: request.labels,
+        node_tag: request.node_tag,
+        threshold_overrides: request.threshold_overrides,
+        conditions: request.conditions,
+        combine: request.combine,
     };
 
     let updated_rule = service.update_alert_rule(&id, rule_update).await?;
@@ -201,11 +384,38 @@ pub async fn get_alert_rule(
     let rule = rules
         .into_iter()
         .find(|r| r.id == id)
-        .ok_or_else(|| crate::error::AppError::NotFound(format!("Alert rule {} not found", id)))?;
+        .ok_or_else(|| AppError::NotFound(format!("Alert rule {} not found", id)))?;
 
     Ok(HttpResponse::Ok().json(rule))
 }
 
+/// Get the nodes an alert rule is evaluated on
+///
+/// GET /api/monitoring/alerts/rules/{id}/targets
+///
+/// Resolves the rule's tag against the current nodes and reports the
+/// threshold used on each, so per-node overrides can be checked. Only nodes
+/// the caller may access are listed.
+pub async fn get_alert_rule_targets(
+    claims: Claims,
+    rule_id: web::Path<Uuid>,
+    service: web::Data<MonitoringService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let scope = team_service.access_scope(&claims).await?;
+    let nodes: Vec<_> = node_service
+        .list_all_nodes()
+        .await?
+        .into_iter()
+        .filter(|node| scope.can_access(node.team_id))
+        .collect();
+
+    let response = service.expand_alert_rule(&rule_id.into_inner(), &nodes).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 /// Get all alert rules
 ///
 /// GET /api/monitoring/alerts/rules
@@ -220,6 +430,84 @@ pub async fn get_alert_rules(
     })))
 }
 
+/// List the default alert rule bundles
+///
+/// GET /api/monitoring/alerts/bundles
+pub async fn get_alert_bundles(
+    service: web::Data<MonitoringService>,
+) -> AppResult<HttpResponse> {
+    let bundles = service.get_alert_bundles().await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "bundles": bundles })))
+}
+
+/// Apply default alert rule bundles to all nodes
+///
+/// POST /api/monitoring/alerts/bundles/apply
+///
+/// Creates each bundle's rule for every node and keeps the bundles applied,
+/// so nodes registered later get the rules as well. Requires an administrator.
+///
+/// Request body:
+/// ```json
+/// {
+///   "bundles": ["cpu", "disk", "memory", "interface_errors"]
+/// }
+/// ```
+pub async fn apply_alert_bundles(
+    claims: Claims,
+    request: Option<web::Json<ApplyAlertBundlesRequest>>,
+    service: web::Data<MonitoringService>,
+    node_service: web::Data<NodeService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let bundles = request
+        .and_then(|request| request.into_inner().bundles)
+        .unwrap_or_else(|| AlertRuleBundle::ALL.to_vec());
+    let node_ids: Vec<Uuid> = node_service.list_all_nodes().await?.iter().map(|node| node.id).collect();
+    let response = service.apply_alert_bundles(&bundles, &node_ids).await;
+
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "alert_bundle.apply", AuditResult::Success)
+                .with_details(serde_json::json!({ "bundles": bundles, "created": response.created })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Stop applying a default alert rule bundle
+///
+/// DELETE /api/monitoring/alerts/bundles/{bundle}
+///
+/// Deletes all rules created from the bundle. Requires an administrator.
+pub async fn remove_alert_bundle(
+    claims: Claims,
+    bundle: web::Path<AlertRuleBundle>,
+    service: web::Data<MonitoringService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let bundle = bundle.into_inner();
+    let removed = service.remove_alert_bundle(bundle).await;
+
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "alert_bundle.remove", AuditResult::Success)
+                .with_target(bundle.as_str()),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "bundle": bundle,
+        "removed": removed
+    })))
+}
+
 // Query parameter structures
 
 /// Query parameters for system metrics
@@ -267,6 +555,18 @@ pub struct AlertRuleCreateRequest {
     pub severity: AlertSeverity,
     pub for_seconds: u32,
     pub labels: Vec<crate::models::monitoring::MetricLabel>,
+    /// Only evaluate the rule on nodes carrying this tag
+    #[serde(default)]
+    pub node_tag: Option<String>,
+    /// Per-node thresholds replacing `threshold`
+    #[serde(default)]
+    pub threshold_overrides: Vec<ThresholdOverride>,
+    /// Further conditions checked together with `metric_name`
+    #[serde(default)]
+    pub conditions: Vec<AlertCondition>,
+    /// How the conditions are combined (`and` or `or`)
+    #[serde(default)]
+    pub combine: ConditionCombinator,
 }
 
 /// Request to update an alert rule
@@ -280,6 +580,11 @@ pub struct AlertRuleUpdateRequest {
     pub for_seconds: Option<u32>,
     pub enabled: Option<bool>,
     pub labels: Option<Vec<crate::models::monitoring::MetricLabel>>,
+    /// Tag to restrict the rule to; an empty tag targets all nodes again
+    pub node_tag: Option<String>,
+    pub threshold_overrides: Option<Vec<ThresholdOverride>>,
+    pub conditions: Option<Vec<AlertCondition>>,
+    pub combine: Option<ConditionCombinator>,
 }
 
 /// Helper function to parse alert severity from string
@@ -1,84 +1,274 @@
+//! Network Handlers Module
+//!
+//! This module contains HTTP request handlers for the addressing of a
+//! node's interfaces: addresses, MTU, description, the administrative
+//! state, VLAN sub-interfaces, and the connection settings and state of
+//! PPPoE and WWAN interfaces; and for its static and policy routes.
+
 use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::network::{InterfaceConfigRequest, InterfaceKind};
+use crate::models::routing::{
+    PolicyRouteQuery, PolicyRouteRequest, RouteFamily, RoutingPlan, StaticRouteQuery, StaticRouteRequest,
+};
+use crate::services::{AuditService, NetworkService, NodeService, RoutingService, TeamService};
+
+fn interface_kind(name: &str) -> AppResult<InterfaceKind> {
+    InterfaceKind::from_name(name)
+        .ok_or_else(|| AppError::Validation(format!("Unknown interface kind '{}'", name)))
+}
 
-use crate::error::AppResult;
+fn route_family(name: &str) -> AppResult<RouteFamily> {
+    RouteFamily::from_name(name)
+        .ok_or_else(|| AppError::Validation(format!("Unknown address family '{}'; use ipv4 or ipv6", name)))
+}
 
-/// Get all network interfaces
-pub async fn get_interfaces() -> AppResult<HttpResponse> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "interfaces": [],
-        "message": "Network interfaces endpoint"
-    })))
+/// Audit event of an applied routing change
+fn routing_audit_event(claims: &Claims, action: &str, node_id: Uuid, target: String, plan: &RoutingPlan) -> AuditEvent {
+    AuditEvent::new(Some(claims), action, AuditResult::Success)
+        .with_node(node_id)
+        .with_target(target)
+        .with_details(serde_json::json!({ "commands": plan.commands }))
 }
 
-/// Get specific network interface details
-pub async fn get_interface_details(
-    _interface_id: web::Path<String>,
+/// Get the addressing settings of an interface
+///
+/// GET /api/nodes/{id}/interfaces/{kind}/{name}/config
+pub async fn get_interface_config(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    service: web::Data<NetworkService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "id": _interface_id.into_inner(),
-        "message": "Interface details endpoint"
-    })))
+    let (node_id, kind, name) = path.into_inner();
+    debug!("Handling get_interface_config request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let interface = service.interface(node_id, interface_kind(&kind)?, &name).await?;
+
+    Ok(HttpResponse::Ok().json(interface))
 }
 
-/// Configure network interface
+/// Change the addressing settings of an interface
+///
+/// PUT /api/nodes/{id}/interfaces/{kind}/{name}/config
+///
+/// Addresses, MTU, description, the disabled flag, and VLANs left out of
+/// the request are removed from the interface; its other settings are kept.
+/// Returns the set and delete operations making the change; with `dry_run`,
+/// they are only returned.
 pub async fn configure_interface(
-    _interface_id: web::Path<String>,
-    _config: web::Json<serde_json::Value>,
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    req: web::Json<InterfaceConfigRequest>,
+    service: web::Data<NetworkService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
 ) -> AppResult<HttpResponse> {
-    Ok(HttpResponse::Accepted().json(serde_json::json!({
-        "message": "Interface configuration accepted",
-        "interface_id": _interface_id.into_inner()
-    })))
+    let (node_id, kind, name) = path.into_inner();
+    info!("Handling configure_interface request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let plan = service
+        .configure_interface(node_id, interface_kind(&kind)?, &name, req.into_inner(), &claims)
+        .await?;
+    if plan.applied {
+        audit_service
+            .record(
+                AuditEvent::new(Some(&claims), "interface.update", AuditResult::Success)
+                    .with_node(node_id)
+                    .with_target(name)
+                    .with_details(serde_json::to_value(&plan.operations)?),
+            )
+            .await;
+    }
+
+    Ok(HttpResponse::Ok().json(plan))
 }
 
-/// Get routing table
-pub async fn get_routing_table() -> AppResult<HttpResponse> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "routes": [],
-        "message": "Routing table endpoint"
-    })))
+/// Get the operational state of a PPPoE or WWAN interface
+///
+/// GET /api/nodes/{id}/interfaces/{kind}/{name}/status
+///
+/// Returns the link state, the addresses the provider assigned, and the
+/// uptime and authentication protocol of a PPPoE session or the state of a
+/// WWAN interface's modem.
+pub async fn get_interface_status(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    service: web::Data<NetworkService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, kind, name) = path.into_inner();
+    debug!("Handling get_interface_status request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let status = service.dialer_status(node_id, interface_kind(&kind)?, &name).await?;
+
+    Ok(HttpResponse::Ok().json(status))
 }
 
-/// Add static route
-pub async fn add_route(
-    _route: web::Json<serde_json::Value>,
+/// Get the static routes of a node
+///
+/// GET /api/nodes/{id}/network/routes
+pub async fn get_static_routes(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<RoutingService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
-    Ok(HttpResponse::Accepted().json(serde_json::json!({
-        "message": "Route added successfully"
-    })))
+    let node_id = path.into_inner();
+    debug!("Handling get_static_routes request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let routes = service.static_routes(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(routes))
 }
 
-/// Delete route
-pub async fn delete_route(
-    _route_id: web::Path<String>,
+/// Add a static route, or replace the route to the same destination
+///
+/// POST /api/nodes/{id}/network/routes
+///
+/// Returns the generated configuration commands; with `dry_run`, they are
+/// only returned.
+pub async fn set_static_route(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    req: web::Json<StaticRouteRequest>,
+    service: web::Data<RoutingService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
 ) -> AppResult<HttpResponse> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "message": "Route deleted successfully"
-    })))
+    let node_id = path.into_inner();
+    info!("Handling set_static_route request for {} on node {}", req.destination, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let request = req.into_inner();
+    let destination = request.destination.clone();
+    let plan = service.set_static_route(node_id, request, &claims).await?;
+    if plan.applied {
+        audit_service
+            .record(routing_audit_event(&claims, "routing.static_route.update", node_id, destination, &plan))
+            .await;
+    }
+
+    Ok(HttpResponse::Ok().json(plan))
 }
 
-/// Get firewall rules
-pub async fn get_firewall_rules() -> AppResult<HttpResponse> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "rules": [],
-        "message": "Firewall rules endpoint"
-    })))
+/// Remove a static route
+///
+/// DELETE /api/nodes/{id}/network/routes?destination=10.0.0.0/8&table=10
+///
+/// Returns the generated configuration commands; with `dry_run=true`, they
+/// are only returned.
+pub async fn delete_static_route(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    query: web::Query<StaticRouteQuery>,
+    service: web::Data<RoutingService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    info!("Handling delete_static_route request for {} on node {}", query.destination, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let query = query.into_inner();
+    let destination = query.destination.clone();
+    let plan = service.delete_static_route(node_id, query, &claims).await?;
+    if plan.applied {
+        audit_service
+            .record(routing_audit_event(&claims, "routing.static_route.delete", node_id, destination, &plan))
+            .await;
+    }
+
+    Ok(HttpResponse::Ok().json(plan))
+}
+
+/// Get the policy routes of a node
+///
+/// GET /api/nodes/{id}/network/policy-routes
+pub async fn get_policy_routes(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<RoutingService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_policy_routes request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let policies = service.policy_routes(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(policies))
 }
 
-/// Add firewall rule
-pub async fn add_firewall_rule(
-    _rule: web::Json<serde_json::Value>,
+/// Create or replace a policy route with all of its rules
+///
+/// PUT /api/nodes/{id}/network/policy-routes/{family}/{name}
+pub async fn set_policy_route(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    req: web::Json<PolicyRouteRequest>,
+    service: web::Data<RoutingService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
 ) -> AppResult<HttpResponse> {
-    Ok(HttpResponse::Accepted().json(serde_json::json!({
-        "message": "Firewall rule added successfully"
-    })))
+    let (node_id, family, name) = path.into_inner();
+    info!("Handling set_policy_route request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let plan = service
+        .set_policy_route(node_id, route_family(&family)?, &name, req.into_inner(), &claims)
+        .await?;
+    if plan.applied {
+        audit_service
+            .record(routing_audit_event(&claims, "routing.policy_route.update", node_id, name, &plan))
+            .await;
+    }
+
+    Ok(HttpResponse::Ok().json(plan))
 }
 
-/// Delete firewall rule
-pub async fn delete_firewall_rule(
-    _rule_id: web::Path<String>,
+/// Remove a policy route
+///
+/// DELETE /api/nodes/{id}/network/policy-routes/{family}/{name}
+pub async fn delete_policy_route(
+    claims: Claims,
+    path: web::Path<(Uuid, String, String)>,
+    query: web::Query<PolicyRouteQuery>,
+    service: web::Data<RoutingService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
 ) -> AppResult<HttpResponse> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "message": "Firewall rule deleted successfully"
-    })))
-}
\ No newline at end of file
+    let (node_id, family, name) = path.into_inner();
+    info!("Handling delete_policy_route request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let plan = service
+        .delete_policy_route(node_id, route_family(&family)?, &name, query.dry_run, &claims)
+        .await?;
+    if plan.applied {
+        audit_service
+            .record(routing_audit_event(&claims, "routing.policy_route.delete", node_id, name, &plan))
+            .await;
+    }
+
+    Ok(HttpResponse::Ok().json(plan))
+}
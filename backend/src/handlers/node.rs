@@ -2,16 +2,55 @@
 //!
 //! This module contains HTTP request handlers for node management endpoints.
 
-use actix_web::{web, HttpResponse};
+use actix_web::{http::header, web, HttpResponse};
 use tracing::{debug, error, info};
 use uuid::Uuid;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::i18n::t;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::fields::FieldSelection;
 use crate::models::node::{
-    CreateNodeRequest, NodeListQuery, NodeListResponse, NodeStatistics,
-    NodeTestResult, UpdateNodeRequest,
+    node_channel, CreateNodeRequest, CreateSimulatedNodeRequest, Node, NodeEvent, NodeListQuery,
+    NodeListResponse, NodeOwnerRequest, NodeStatistics, NodeStatus, NodeTestResult, ShowCacheQuery,
+    UpdateNodeRequest,
 };
-use crate::services::NodeService;
+use crate::services::config_lock::path_segments;
+use crate::services::{
+    AuditService, ConfigService, EventBus, MacVendorService, MonitoringService, NodeFileService, NodeService,
+    TeamService,
+};
+
+/// Fetch a node and ensure the caller's teams may access it
+pub(crate) async fn authorize_node(
+    node_service: &NodeService,
+    team_service: &TeamService,
+    claims: &Claims,
+    node_id: Uuid,
+) -> AppResult<Node> {
+    let node = node_service
+        .get_node(node_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+    let scope = team_service.access_scope(claims).await?;
+    if !scope.can_access(node.team_id) {
+        return Err(AppError::Forbidden("Node is owned by another team".to_string()));
+    }
+
+    Ok(node)
+}
+
+/// Audit event for an action on a node, with the error of a failed action
+fn node_audit_event<T>(claims: &Claims, action: &str, node_id: Uuid, result: &AppResult<T>) -> AuditEvent {
+    match result {
+        Ok(_) => AuditEvent::new(Some(claims), action, AuditResult::Success).with_node(node_id),
+        Err(e) => AuditEvent::new(Some(claims), action, AuditResult::Failure)
+            .with_node(node_id)
+            .with_details(serde_json::json!({ "error": e.to_string() })),
+    }
+}
 
 // ============================================================================
 // Node Handlers
@@ -22,15 +61,43 @@ use crate::services::NodeService;
 /// GET /api/nodes
 ///
 /// Returns a paginated list of all registered nodes with optional filtering.
+/// `fields` selects the fields returned for each node.
 pub async fn list_nodes(
+    claims: Claims,
     query: web::Query<NodeListQuery>,
+    fields: FieldSelection,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
     debug!("Handling list_nodes request");
 
-    let response = node_service.list_nodes(query.into_inner()).await?;
+    let scope = team_service.access_scope(&claims).await?;
+    let response = node_service.list_nodes(query.into_inner(), &scope).await?;
 
-    Ok(HttpResponse::Ok().json(response))
+    Ok(HttpResponse::Ok().json(fields.project_items(&response, "nodes")?))
+}
+
+/// Export the node inventory
+///
+/// GET /api/nodes/export
+///
+/// Returns every node matching the list filters as CSV, with a column per
+/// custom field.
+pub async fn export_nodes(
+    claims: Claims,
+    query: web::Query<NodeListQuery>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling export_nodes request");
+
+    let scope = team_service.access_scope(&claims).await?;
+    let csv = node_service.export_nodes(query.into_inner(), &scope).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(("Content-Disposition", "attachment; filename=\"nodes.csv\""))
+        .body(csv))
 }
 
 /// Create a new node
@@ -39,47 +106,133 @@ pub async fn list_nodes(
 ///
 /// Creates a new VyOS node with the provided configuration.
 pub async fn create_node(
+    claims: Claims,
     request: web::Json<CreateNodeRequest>,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+    monitoring_service: web::Data<MonitoringService>,
 ) -> AppResult<HttpResponse> {
     info!("Handling create_node request for node: {}", request.name);
 
+    team_service.ensure_can_assign(&claims, request.team_id).await?;
+
+    let name = request.name.clone();
     match node_service.create_node(request.into_inner()).await {
         Ok(node) => {
             info!("Node created successfully: {}", node.id);
+            monitoring_service.sync_node_alert_bundles(&node.id).await;
+            audit_service
+                .record(AuditEvent::new(Some(&claims), "node.create", AuditResult::Success).with_node(node.id))
+                .await;
             Ok(HttpResponse::Created().json(node))
         }
         Err(e) => {
             error!("Failed to create node: {}", e);
+            audit_service
+                .record(
+                    AuditEvent::new(Some(&claims), "node.create", AuditResult::Failure)
+                        .with_target(name)
+                        .with_details(serde_json::json!({ "error": e.to_string() })),
+                )
+                .await;
             Err(e)
         }
     }
 }
 
+/// Create a simulated node
+///
+/// POST /api/nodes/simulated
+///
+/// Creates a node that answers from a recording instead of a router, for
+/// training environments and demos. The recording is part of the request or
+/// is taken of the node named by `record_from`, which the caller must be
+/// able to access.
+pub async fn create_simulated_node(
+    claims: Claims,
+    request: web::Json<CreateSimulatedNodeRequest>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+    monitoring_service: web::Data<MonitoringService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_simulated_node request for node: {}", request.name);
+
+    team_service.ensure_can_assign(&claims, request.team_id).await?;
+    if let Some(source) = request.record_from {
+        authorize_node(&node_service, &team_service, &claims, source).await?;
+    }
+
+    let name = request.name.clone();
+    let record_from = request.record_from;
+    match node_service.create_simulated_node(request.into_inner()).await {
+        Ok(node) => {
+            info!("Simulated node created successfully: {}", node.id);
+            monitoring_service.sync_node_alert_bundles(&node.id).await;
+            audit_service
+                .record(
+                    AuditEvent::new(Some(&claims), "node.create", AuditResult::Success)
+                        .with_node(node.id)
+                        .with_details(serde_json::json!({ "kind": node.kind, "recorded_from": record_from })),
+                )
+                .await;
+            Ok(HttpResponse::Created().json(node))
+        }
+        Err(e) => {
+            error!("Failed to create simulated node: {}", e);
+            audit_service
+                .record(
+                    AuditEvent::new(Some(&claims), "node.create", AuditResult::Failure)
+                        .with_target(name)
+                        .with_details(serde_json::json!({ "error": e.to_string() })),
+                )
+                .await;
+            Err(e)
+        }
+    }
+}
+
+/// Get the recording a simulated node answers from
+///
+/// GET /api/nodes/:id/recording
+///
+/// The response's `recording` can be used to create the same simulated node
+/// elsewhere.
+pub async fn get_node_recording(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_node_recording request");
+
+    let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let recording = node_service.get_recording(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(recording))
+}
+
 /// Get a specific node by ID
 ///
 /// GET /api/nodes/:id
 ///
-/// Returns detailed information about a specific node.
+/// Returns detailed information about a specific node, limited to the
+/// fields listed in `fields`, if given.
 pub async fn get_node(
+    claims: Claims,
     path: web::Path<Uuid>,
+    fields: FieldSelection,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
     debug!("Handling get_node request");
 
     let node_id = path.into_inner();
-    match node_service.get_node(node_id).await {
-        Ok(Some(node)) => {
-            Ok(HttpResponse::Ok().json(node))
-        }
-        Ok(None) => {
-            Ok(HttpResponse::NotFound().json(serde_json::json!({
-                "error": "Node not found",
-                "node_id": node_id.to_string()
-            })))
-        }
-        Err(e) => Err(e),
-    }
+    let node = authorize_node(&node_service, &team_service, &claims, node_id).await?;
+
+    Ok(HttpResponse::Ok().json(fields.project(&node)?))
 }
 
 /// Update a node
@@ -88,14 +241,20 @@ pub async fn get_node(
 ///
 /// Updates an existing node's configuration.
 pub async fn update_node(
+    claims: Claims,
     path: web::Path<Uuid>,
     request: web::Json<UpdateNodeRequest>,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
 ) -> AppResult<HttpResponse> {
     info!("Handling update_node request");
 
     let node_id = path.into_inner();
-    match node_service.update_node(node_id, request.into_inner()).await {
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let result = node_service.update_node(node_id, request.into_inner()).await;
+    audit_service.record(node_audit_event(&claims, "node.update", node_id, &result)).await;
+    match result {
         Ok(node) => {
             info!("Node updated successfully: {}", node_id);
             Ok(HttpResponse::Ok().json(node))
@@ -113,15 +272,25 @@ pub async fn update_node(
 ///
 /// Deletes a node from the system.
 pub async fn delete_node(
+    claims: Claims,
     path: web::Path<Uuid>,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+    monitoring_service: web::Data<MonitoringService>,
+    node_file_service: web::Data<NodeFileService>,
 ) -> AppResult<HttpResponse> {
     info!("Handling delete_node request");
 
     let node_id = path.into_inner();
-    match node_service.delete_node(node_id).await {
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let result = node_service.delete_node(node_id).await;
+    audit_service.record(node_audit_event(&claims, "node.delete", node_id, &result)).await;
+    match result {
         Ok(_) => {
             info!("Node deleted successfully: {}", node_id);
+            monitoring_service.remove_node_alert_bundles(&node_id).await;
+            node_file_service.remove_node_files(node_id).await;
             Ok(HttpResponse::Ok().json(serde_json::json!({
                 "message": "Node deleted successfully",
                 "node_id": node_id.to_string()
@@ -134,18 +303,52 @@ pub async fn delete_node(
     }
 }
 
+/// Change the team owning a node
+///
+/// PUT /api/nodes/:id/owner
+///
+/// Assigns the node to a team, or shares it with everyone when `team_id` is null.
+pub async fn set_node_owner(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<NodeOwnerRequest>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling set_node_owner request");
+
+    let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    team_service.ensure_can_assign(&claims, request.team_id).await?;
+
+    let result = node_service.set_node_owner(node_id, request.team_id).await;
+    let event = node_audit_event(&claims, "node.set_owner", node_id, &result);
+    let event = match request.team_id {
+        Some(team_id) => event.with_target(format!("team:{}", team_id)),
+        None => event,
+    };
+    audit_service.record(event).await;
+    let node = result?;
+
+    Ok(HttpResponse::Ok().json(node))
+}
+
 /// Test connection to a node
 ///
 /// POST /api/nodes/:id/test
 ///
 /// Tests the connection to a specific VyOS node and returns the result.
 pub async fn test_connection(
+    claims: Claims,
     path: web::Path<Uuid>,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
     info!("Handling test_connection request");
 
     let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
     match node_service.test_connection(node_id).await {
         Ok(result) => {
             info!("Connection test completed for node: {}", node_id);
@@ -158,18 +361,80 @@ pub async fn test_connection(
     }
 }
 
+/// Reboot a node
+///
+/// POST /api/nodes/:id/reboot
+///
+/// Starts the reboot and returns at once. The node is marked rebooting
+/// until the health check finds it answering again.
+pub async fn reboot_node(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+    event_bus: web::Data<EventBus>,
+) -> AppResult<HttpResponse> {
+    info!("Handling reboot_node request");
+
+    let node_id = path.into_inner();
+    let node = authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let result = node_service.reboot_node(node_id, &claims.username).await;
+    audit_service.record(node_audit_event(&claims, "node.reboot", node_id, &result)).await;
+    let reboot = result?;
+
+    event_bus
+        .publish(
+            &node_channel(node_id),
+            &NodeEvent::StatusChanged {
+                node_id,
+                previous: node.status,
+                status: NodeStatus::Rebooting,
+                last_check: reboot.started_at,
+                error_message: None,
+            },
+        )
+        .await;
+
+    Ok(HttpResponse::Accepted().json(reboot))
+}
+
+/// Get the reboot of a node in progress
+///
+/// GET /api/nodes/:id/reboot
+pub async fn get_node_reboot(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_node_reboot request");
+
+    let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let reboot = node_service
+        .get_reboot(node_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Node {} is not rebooting", node_id)))?;
+
+    Ok(HttpResponse::Ok().json(reboot))
+}
+
 /// Get node health information
 ///
 /// GET /api/nodes/:id/health
 ///
 /// Returns the health status of a specific node.
 pub async fn get_node_health(
+    claims: Claims,
     path: web::Path<Uuid>,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
     debug!("Handling get_node_health request");
 
     let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
     match node_service.get_node_health(node_id).await {
         Ok(health) => {
             Ok(HttpResponse::Ok().json(health))
@@ -182,13 +447,16 @@ pub async fn get_node_health(
 ///
 /// GET /api/nodes/health/all
 ///
-/// Returns health status for all registered nodes.
+/// Returns health status for all nodes the caller's teams can access.
 pub async fn get_all_nodes_health(
+    claims: Claims,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
     debug!("Handling get_all_nodes_health request");
 
-    match node_service.get_all_nodes_health().await {
+    let scope = team_service.access_scope(&claims).await?;
+    match node_service.get_all_nodes_health(&scope).await {
         Ok(health_infos) => {
             Ok(HttpResponse::Ok().json(health_infos))
         }
@@ -200,22 +468,32 @@ pub async fn get_all_nodes_health(
 ///
 /// POST /api/nodes/:id/config
 ///
-/// Retrieves the configuration from a specific node.
+/// Retrieves the configuration from a specific node. While the node cannot
+/// be reached, the last configuration read from it is returned marked as
+/// stale. Secrets are redacted for users without the `secrets:read`
+/// permission.
 pub async fn retrieve_node_config(
+    claims: Claims,
     path: web::Path<Uuid>,
     request: Option<web::Json<serde_json::Value>>,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    config_service: web::Data<ConfigService>,
 ) -> AppResult<HttpResponse> {
     info!("Handling retrieve_node_config request");
 
     let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
     let path_str = request
+        .as_ref()
         .and_then(|r| r.get("path"))
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
 
-    match node_service.retrieve_node_config(node_id, path_str).await {
-        Ok(config) => {
+    match node_service.retrieve_node_config(node_id, path_str.clone()).await {
+        Ok(mut config) => {
+            let scope = path_segments(path_str.as_deref().unwrap_or_default());
+            config_service.conceal_secrets(&claims, &scope, &mut config.data).await?;
             Ok(HttpResponse::Ok().json(config))
         }
         Err(e) => {
@@ -229,17 +507,23 @@ pub async fn retrieve_node_config(
 ///
 /// GET /api/nodes/:id/info
 ///
-/// Returns system information from a specific VyOS node.
+/// Returns system information from a specific VyOS node, or the last-known
+/// information marked as stale while the node cannot be reached. `fields`
+/// selects the fields returned.
 pub async fn get_node_info(
+    claims: Claims,
     path: web::Path<Uuid>,
+    fields: FieldSelection,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
     debug!("Handling get_node_info request");
 
     let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
     match node_service.get_node_info(node_id).await {
         Ok(info) => {
-            Ok(HttpResponse::Ok().json(info))
+            Ok(HttpResponse::Ok().json(fields.project(&info)?))
         }
         Err(e) => {
             error!("Failed to get info from node {}: {}", node_id, e);
@@ -248,20 +532,110 @@ pub async fn get_node_info(
     }
 }
 
+/// Get the VyOS API calls made to a node
+///
+/// GET /api/nodes/:id/api-usage
+///
+/// Returns the calls made to the node since the backend started, per
+/// operation, with error rates and average latency.
+pub async fn get_node_api_usage(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_node_api_usage request");
+
+    let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let usage = node_service.get_api_usage(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(usage))
+}
+
+/// Get the API features supported by a node
+///
+/// GET /api/nodes/:id/capabilities
+///
+/// Returns the node's VyOS release and which API features it supports,
+/// probing the node on first use.
+pub async fn get_node_capabilities(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_node_capabilities request");
+
+    let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    match node_service.get_node_capabilities(node_id).await {
+        Ok(capabilities) => {
+            Ok(HttpResponse::Ok().json(capabilities))
+        }
+        Err(e) => {
+            error!("Failed to get capabilities of node {}: {}", node_id, e);
+            Err(e)
+        }
+    }
+}
+
+/// Probe a node for the API features it supports
+///
+/// POST /api/nodes/:id/capabilities
+///
+/// Probes the node again, e.g. after an upgrade, and returns the result.
+pub async fn probe_node_capabilities(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling probe_node_capabilities request");
+
+    let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    match node_service.probe_capabilities(node_id).await {
+        Ok(capabilities) => {
+            Ok(HttpResponse::Ok().json(capabilities))
+        }
+        Err(e) => {
+            error!("Failed to probe capabilities of node {}: {}", node_id, e);
+            Err(e)
+        }
+    }
+}
+
 /// Get network interfaces from a node
 ///
 /// GET /api/nodes/:id/interfaces
 ///
-/// Returns network interface information from a specific VyOS node.
+/// Returns network interface information from a specific VyOS node, or the
+/// last-known interfaces marked as stale while the node cannot be reached.
+/// Interfaces are annotated with the vendor of their MAC address.
+///
+/// Query parameters:
+/// - bypass_cache: Ask the node even if its interfaces were read within the
+///   show cache TTL
 pub async fn get_node_interfaces(
+    claims: Claims,
     path: web::Path<Uuid>,
+    query: web::Query<ShowCacheQuery>,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    mac_vendor_service: web::Data<MacVendorService>,
 ) -> AppResult<HttpResponse> {
     debug!("Handling get_node_interfaces request");
 
     let node_id = path.into_inner();
-    match node_service.get_node_interfaces(node_id).await {
-        Ok(interfaces) => {
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    match node_service.read_node_interfaces(node_id, query.bypass_cache).await {
+        Ok(mut interfaces) => {
+            for interface in &mut interfaces.data {
+                if let Some(mac) = &interface.mac_address {
+                    interface.vendor = mac_vendor_service.vendor_of(mac).await;
+                }
+            }
             Ok(HttpResponse::Ok().json(interfaces))
         }
         Err(e) => {
@@ -275,23 +649,36 @@ pub async fn get_node_interfaces(
 ///
 /// POST /api/nodes/:id/show
 ///
-/// Executes a show command on a specific VyOS node.
+/// Executes a show command on a specific VyOS node. Results are cached for
+/// a few seconds; a result answered from the cache has `cached` set and an
+/// `Age` header, and `age_secs` tells how old it is.
+///
+/// Query parameters:
+/// - bypass_cache: Run the command on the node even if its result is cached
 pub async fn execute_show_command(
+    claims: Claims,
     path: web::Path<Uuid>,
+    query: web::Query<ShowCacheQuery>,
     request: web::Json<serde_json::Value>,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
     info!("Handling execute_show_command request");
 
     let node_id = path.into_inner();
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
     let command = request
         .get("command")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| crate::error::AppError::Validation("Command is required".to_string()))?;
+        .ok_or_else(|| AppError::Validation(t("validation.command_required")))?;
 
-    match node_service.execute_show_command(node_id, command).await {
+    match node_service.execute_show_command(node_id, command, query.bypass_cache).await {
         Ok(result) => {
-            Ok(HttpResponse::Ok().json(result))
+            let mut response = HttpResponse::Ok();
+            if result.cached {
+                response.insert_header((header::AGE, result.age_secs.to_string()));
+            }
+            Ok(response.json(result))
         }
         Err(e) => {
             error!("Failed to execute show command on node {}: {}", node_id, e);
@@ -304,13 +691,16 @@ pub async fn execute_show_command(
 ///
 /// GET /api/nodes/stats
 ///
-/// Returns summary statistics for all nodes.
+/// Returns summary statistics for all nodes the caller's teams can access.
 pub async fn get_node_statistics(
+    claims: Claims,
     node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
 ) -> AppResult<HttpResponse> {
     debug!("Handling get_node_statistics request");
 
-    match node_service.get_statistics().await {
+    let scope = team_service.access_scope(&claims).await?;
+    match node_service.get_statistics(&scope).await {
         Ok(stats) => {
             Ok(HttpResponse::Ok().json(stats))
         }
@@ -322,12 +712,17 @@ pub async fn get_node_statistics(
 ///
 /// POST /api/nodes/health/check-all
 ///
-/// Triggers a health check for all registered nodes.
+/// Triggers a health check for all registered nodes. Requires an
+/// administrator.
 pub async fn check_all_nodes_health(
+    claims: Claims,
     node_service: web::Data<NodeService>,
+    audit_service: web::Data<AuditService>,
 ) -> AppResult<HttpResponse> {
     info!("Handling check_all_nodes_health request");
 
+    audit_service.ensure_admin(&claims).await?;
+
     match node_service.check_all_nodes_health().await {
         Ok(health_infos) => {
             Ok(HttpResponse::Ok().json(health_infos))
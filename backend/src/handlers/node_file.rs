@@ -0,0 +1,247 @@
+//! Node File Handlers Module
+//!
+//! This module contains HTTP request handlers for the markdown notes of a
+//! node and the files attached to it. Reading them takes access to the
+//! node; changing them also takes the `nodes.write` permission.
+
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::node_file::{NodeAttachmentListQuery, UpdateNodeNoteRequest};
+use crate::services::{AuditService, NodeFileService, NodeService, TeamService};
+
+// ============================================================================
+// Notes
+// ============================================================================
+
+/// Get the current notes of a node
+///
+/// GET /api/nodes/{id}/notes
+pub async fn get_node_note(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<NodeFileService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_node_note request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let note = service.get_note(node_id, None).await?;
+
+    Ok(HttpResponse::Ok().json(note))
+}
+
+/// Save a new version of the notes of a node
+///
+/// PUT /api/nodes/{id}/notes
+pub async fn update_node_note(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateNodeNoteRequest>,
+    service: web::Data<NodeFileService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    info!("Handling update_node_note request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.ensure_can_write(&claims).await?;
+    let note = service.save_note(&claims, node_id, request.into_inner().content).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "node.notes.update", AuditResult::Success)
+                .with_node(node_id)
+                .with_details(serde_json::json!({ "version": note.version })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(note))
+}
+
+/// List the saved versions of the notes of a node, newest first
+///
+/// GET /api/nodes/{id}/notes/versions
+pub async fn list_node_note_versions(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<NodeFileService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling list_node_note_versions request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let versions = service.list_note_versions(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(versions))
+}
+
+/// Get a saved version of the notes of a node
+///
+/// GET /api/nodes/{id}/notes/versions/{version}
+pub async fn get_node_note_version(
+    claims: Claims,
+    path: web::Path<(Uuid, u32)>,
+    service: web::Data<NodeFileService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, version) = path.into_inner();
+    debug!("Handling get_node_note_version request for version {} of node {}", version, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let note = service.get_note(node_id, Some(version)).await?;
+
+    Ok(HttpResponse::Ok().json(note))
+}
+
+// ============================================================================
+// Attachments
+// ============================================================================
+
+/// Attach a file to a node
+///
+/// POST /api/nodes/{id}/attachments
+///
+/// The file is sent in the `file` field of a multipart/form-data body. A
+/// file named like an existing attachment becomes its next version.
+pub async fn create_node_attachment(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    multipart: Multipart,
+    service: web::Data<NodeFileService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    info!("Handling create_node_attachment request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.ensure_can_write(&claims).await?;
+    let attachment = service.attach(&claims, node_id, multipart).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "node.attachment.create", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(attachment.id.to_string())
+                .with_details(serde_json::json!({
+                    "file_name": attachment.file_name,
+                    "version": attachment.version,
+                    "size_bytes": attachment.size_bytes,
+                    "sha256": attachment.sha256,
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(attachment))
+}
+
+/// List the attachments of a node
+///
+/// GET /api/nodes/{id}/attachments
+///
+/// Query parameters:
+/// - file_name: List every version of this file instead of the latest
+///   version of each
+pub async fn list_node_attachments(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    query: web::Query<NodeAttachmentListQuery>,
+    service: web::Data<NodeFileService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling list_node_attachments request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let attachments = service.list_attachments(node_id, query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(attachments))
+}
+
+/// Get the metadata of an attachment
+///
+/// GET /api/nodes/{id}/attachments/{attachment_id}
+pub async fn get_node_attachment(
+    claims: Claims,
+    path: web::Path<(Uuid, Uuid)>,
+    service: web::Data<NodeFileService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, attachment_id) = path.into_inner();
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let attachment = service.get_attachment(node_id, attachment_id).await?;
+
+    Ok(HttpResponse::Ok().json(attachment))
+}
+
+/// Download an attachment
+///
+/// GET /api/nodes/{id}/attachments/{attachment_id}/download
+pub async fn download_node_attachment(
+    claims: Claims,
+    path: web::Path<(Uuid, Uuid)>,
+    service: web::Data<NodeFileService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, attachment_id) = path.into_inner();
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let (attachment, content) = service.open_attachment(node_id, attachment_id).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(attachment.content_type.as_deref().unwrap_or("application/octet-stream"))
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", attachment.file_name),
+        ))
+        .streaming(content))
+}
+
+/// Delete a version of an attachment
+///
+/// DELETE /api/nodes/{id}/attachments/{attachment_id}
+pub async fn delete_node_attachment(
+    claims: Claims,
+    path: web::Path<(Uuid, Uuid)>,
+    service: web::Data<NodeFileService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, attachment_id) = path.into_inner();
+    info!("Handling delete_node_attachment request for {} of node {}", attachment_id, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.ensure_can_write(&claims).await?;
+    let attachment = service.delete_attachment(node_id, attachment_id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "node.attachment.delete", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(attachment_id.to_string())
+                .with_details(serde_json::json!({
+                    "file_name": attachment.file_name,
+                    "version": attachment.version,
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
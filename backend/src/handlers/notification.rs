@@ -0,0 +1,320 @@
+//! Notification Handlers Module
+//!
+//! This module contains HTTP request handlers for alert notification
+//! channels, the routes sending alerts to them, and the delivery log. All of
+//! them are restricted to administrators.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::notification::{
+    CreateNotificationChannelRequest, CreateNotificationRouteRequest, DeliveryListQuery,
+    UpdateNotificationChannelRequest,
+};
+use crate::services::{AuditService, NotificationService};
+
+// ============================================================================
+// Channels
+// ============================================================================
+
+/// List notification channels
+///
+/// GET /api/monitoring/notifications/channels
+pub async fn list_notification_channels(
+    claims: Claims,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_notification_channels request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let channels = service.list_channels().await?;
+
+    Ok(HttpResponse::Ok().json(channels))
+}
+
+/// Get a notification channel
+///
+/// GET /api/monitoring/notifications/channels/{id}
+pub async fn get_notification_channel(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_notification_channel request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let channel = service.get_channel(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(channel))
+}
+
+/// Create a notification channel
+///
+/// POST /api/monitoring/notifications/channels
+///
+/// Request body:
+/// ```json
+/// {
+///   "name": "on-call",
+///   "target": { "kind": "webhook", "url": "https://hooks.example.com/alerts", "secret": "..." }
+/// }
+/// ```
+///
+/// Email targets list `recipients`, which may be team aliases like `@noc`;
+/// Slack targets set `webhook_url`.
+pub async fn create_notification_channel(
+    claims: Claims,
+    request: web::Json<CreateNotificationChannelRequest>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_notification_channel request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let request = request.into_inner();
+    request.validate()?;
+    let channel = service.create_channel(&claims, request).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "notification.channel.create", AuditResult::Success)
+                .with_target(channel.id.to_string())
+                .with_details(serde_json::json!({ "name": channel.name, "kind": channel.target.kind() })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(channel))
+}
+
+/// Update a notification channel
+///
+/// PUT /api/monitoring/notifications/channels/{id}
+pub async fn update_notification_channel(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateNotificationChannelRequest>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_notification_channel request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let request = request.into_inner();
+    request.validate()?;
+    let channel = service.update_channel(path.into_inner(), request).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "notification.channel.update", AuditResult::Success)
+                .with_target(channel.id.to_string())
+                .with_details(serde_json::json!({ "name": channel.name, "enabled": channel.enabled })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(channel))
+}
+
+/// Delete a notification channel with its routes
+///
+/// DELETE /api/monitoring/notifications/channels/{id}
+pub async fn delete_notification_channel(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling delete_notification_channel request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let id = path.into_inner();
+    service.delete_channel(id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "notification.channel.delete", AuditResult::Success)
+                .with_target(id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Send a test notification through a channel
+///
+/// POST /api/monitoring/notifications/channels/{id}/test
+///
+/// Returns the delivery log entry of the attempt, which tells whether the
+/// channel works.
+pub async fn test_notification_channel(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling test_notification_channel request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let delivery = service.test_channel(&claims, path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(delivery))
+}
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// List notification routes
+///
+/// GET /api/monitoring/notifications/routes
+pub async fn list_notification_routes(
+    claims: Claims,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_notification_routes request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let routes = service.list_routes().await?;
+
+    Ok(HttpResponse::Ok().json(routes))
+}
+
+/// Get a notification route
+///
+/// GET /api/monitoring/notifications/routes/{id}
+pub async fn get_notification_route(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_notification_route request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let route = service.get_route(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(route))
+}
+
+/// Create a notification route
+///
+/// POST /api/monitoring/notifications/routes
+///
+/// Request body:
+/// ```json
+/// {
+///   "name": "critical on edge-1",
+///   "channel_id": "...",
+///   "node_id": "...",
+///   "min_severity": "critical"
+/// }
+/// ```
+///
+/// `rule_id` and `node_id` narrow the route to alerts of one alert rule or
+/// node; `notify_resolved` (default true) also sends resolved alerts.
+pub async fn create_notification_route(
+    claims: Claims,
+    request: web::Json<CreateNotificationRouteRequest>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_notification_route request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let request = request.into_inner();
+    request.validate()?;
+    let route = service.create_route(request).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "notification.route.create", AuditResult::Success)
+                .with_target(route.id.to_string())
+                .with_details(serde_json::json!({ "name": route.name, "channel_id": route.channel_id })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(route))
+}
+
+/// Replace a notification route
+///
+/// PUT /api/monitoring/notifications/routes/{id}
+///
+/// Takes the same body as creating a route.
+pub async fn update_notification_route(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<CreateNotificationRouteRequest>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_notification_route request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let request = request.into_inner();
+    request.validate()?;
+    let route = service.update_route(path.into_inner(), request).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "notification.route.update", AuditResult::Success)
+                .with_target(route.id.to_string())
+                .with_details(serde_json::json!({ "name": route.name, "enabled": route.enabled })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(route))
+}
+
+/// Delete a notification route
+///
+/// DELETE /api/monitoring/notifications/routes/{id}
+pub async fn delete_notification_route(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling delete_notification_route request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let id = path.into_inner();
+    service.delete_route(id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "notification.route.delete", AuditResult::Success)
+                .with_target(id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// ============================================================================
+// Delivery log
+// ============================================================================
+
+/// List the delivery log, newest first
+///
+/// GET /api/monitoring/notifications/deliveries
+///
+/// Query parameters:
+/// - channel_id: Deliveries through this channel
+/// - alert_id: Deliveries of this alert
+/// - status: delivered or failed
+/// - limit: Number of entries (default 100)
+pub async fn list_notification_deliveries(
+    claims: Claims,
+    query: web::Query<DeliveryListQuery>,
+    service: web::Data<NotificationService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_notification_deliveries request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let deliveries = service.list_deliveries(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(deliveries))
+}
@@ -0,0 +1,206 @@
+//! Report Handlers Module
+//!
+//! This module contains HTTP request handlers for generated reports and
+//! report schedules. Reports are restricted to administrators.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::report::{CreateReportScheduleRequest, GenerateReportRequest, ReportListQuery};
+use crate::services::{AuditService, ReportService};
+
+/// List generated reports
+///
+/// GET /api/reports
+///
+/// Query parameters:
+/// - kind: fleet_health, sla, config_changes or compliance
+/// - page, page_size: Pagination
+pub async fn list_reports(
+    claims: Claims,
+    query: web::Query<ReportListQuery>,
+    service: web::Data<ReportService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_reports request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let response = service.list_reports(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Generate a report
+///
+/// POST /api/reports
+///
+/// Request body:
+/// ```json
+/// { "kind": "sla", "format": "pdf", "period_days": 30, "recipients": ["ops@example.com"] }
+/// ```
+///
+/// Emailing requires an SMTP relay to be configured.
+pub async fn generate_report(
+    claims: Claims,
+    request: web::Json<GenerateReportRequest>,
+    service: web::Data<ReportService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling generate_report request");
+
+    audit_service.ensure_admin(&claims).await?;
+    request.validate()?;
+
+    let report = service.generate_report(&claims, request.into_inner()).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "report.generate", AuditResult::Success)
+                .with_target(report.id.to_string())
+                .with_details(serde_json::json!({
+                    "kind": report.kind,
+                    "format": report.format,
+                    "emailed_to": report.emailed_to,
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(report))
+}
+
+/// Get a report's metadata
+///
+/// GET /api/reports/:id
+pub async fn get_report(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ReportService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let report = service.get_report(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Download a rendered report
+///
+/// GET /api/reports/:id/download
+pub async fn download_report(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ReportService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling download_report request");
+
+    audit_service.ensure_admin(&claims).await?;
+    let artifact = service.get_artifact(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(artifact.report.format.content_type())
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", artifact.file_name()),
+        ))
+        .body(artifact.content))
+}
+
+/// Delete a report
+///
+/// DELETE /api/reports/:id
+pub async fn delete_report(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ReportService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let id = path.into_inner();
+    service.delete_report(id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "report.delete", AuditResult::Success)
+                .with_target(id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// List report schedules
+///
+/// GET /api/reports/schedules
+pub async fn list_report_schedules(
+    claims: Claims,
+    service: web::Data<ReportService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let schedules = service.list_schedules().await?;
+
+    Ok(HttpResponse::Ok().json(schedules))
+}
+
+/// Schedule a recurring report
+///
+/// POST /api/reports/schedules
+///
+/// Request body:
+/// ```json
+/// { "kind": "fleet_health", "format": "html", "frequency": "weekly", "recipients": [] }
+/// ```
+pub async fn create_report_schedule(
+    claims: Claims,
+    request: web::Json<CreateReportScheduleRequest>,
+    service: web::Data<ReportService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_report_schedule request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let schedule = service.create_schedule(&claims, request.into_inner()).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "report_schedule.create", AuditResult::Success)
+                .with_target(schedule.id.to_string())
+                .with_details(serde_json::json!({
+                    "kind": schedule.kind,
+                    "frequency": schedule.frequency,
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(schedule))
+}
+
+/// Delete a report schedule
+///
+/// DELETE /api/reports/schedules/:id
+pub async fn delete_report_schedule(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<ReportService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let id = path.into_inner();
+    service.delete_schedule(id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "report_schedule.delete", AuditResult::Success)
+                .with_target(id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
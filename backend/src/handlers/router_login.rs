@@ -0,0 +1,150 @@
+//! Router Login Handlers Module
+//!
+//! This module contains HTTP request handlers for the login users of nodes
+//! and their SSH keys, including key rotation and drift detection across
+//! the fleet.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::handlers::node::authorize_node;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::router_login::{LoginDriftRequest, RotateSshKeyRequest, RouterLoginUserSettings};
+use crate::services::{AuditService, NodeService, RouterLoginService, TeamService};
+
+/// Get the login users of a node
+///
+/// GET /api/nodes/{id}/login/users
+pub async fn get_login_users(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<RouterLoginService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    let node_id = path.into_inner();
+    debug!("Handling get_login_users request for node {}", node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let users = service.users(node_id).await?;
+
+    Ok(HttpResponse::Ok().json(users))
+}
+
+/// Create or replace a login user
+///
+/// PUT /api/nodes/{id}/login/users/{name}
+///
+/// Keys left out of the request are removed from the user; the password is
+/// kept unless a new one is given.
+pub async fn set_login_user(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    req: web::Json<RouterLoginUserSettings>,
+    service: web::Data<RouterLoginService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, name) = path.into_inner();
+    info!("Handling set_login_user request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    let request = req.into_inner();
+    let password_changed = request.password.is_some();
+    let user = service.set_user(node_id, &name, request, &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "router_login.user.update", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(name)
+                .with_details(serde_json::json!({
+                    "password_changed": password_changed,
+                    "ssh_keys": user.ssh_keys.iter().map(|key| &key.name).collect::<Vec<_>>(),
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(user))
+}
+
+/// Remove a login user
+///
+/// DELETE /api/nodes/{id}/login/users/{name}
+pub async fn delete_login_user(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    service: web::Data<RouterLoginService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let (node_id, name) = path.into_inner();
+    info!("Handling delete_login_user request for {} on node {}", name, node_id);
+
+    authorize_node(&node_service, &team_service, &claims, node_id).await?;
+    service.delete_user(node_id, &name, &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "router_login.user.delete", AuditResult::Success)
+                .with_node(node_id)
+                .with_target(name),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Rotate an SSH key of a login user across nodes
+///
+/// POST /api/login/keys/rotate
+///
+/// Adds the key to the user on each node and removes the key it replaces,
+/// one commit per node. Returns the outcome on every node.
+pub async fn rotate_login_key(
+    claims: Claims,
+    req: web::Json<RotateSshKeyRequest>,
+    service: web::Data<RouterLoginService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling rotate_login_key request for {}", req.user);
+
+    let request = req.into_inner();
+    request.validate()?;
+    let mut details = serde_json::json!({
+        "user": request.user,
+        "key": request.key.name,
+        "replaces": request.replaces,
+    });
+    let results = service.rotate_key(request, &claims).await?;
+    let failed = results.iter().filter(|result| !result.success).count();
+    let outcome = if failed == 0 { AuditResult::Success } else { AuditResult::Failure };
+    details["nodes"] = serde_json::json!(results.len());
+    details["failed"] = serde_json::json!(failed);
+    audit_service
+        .record(AuditEvent::new(Some(&claims), "router_login.key.rotate", outcome).with_details(details))
+        .await;
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
+/// Compare the login users of nodes with the desired operators
+///
+/// POST /api/login/drift
+pub async fn get_login_drift(
+    claims: Claims,
+    req: web::Json<LoginDriftRequest>,
+    service: web::Data<RouterLoginService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_login_drift request");
+
+    let request = req.into_inner();
+    request.validate()?;
+    let drift = service.drift(request, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(drift))
+}
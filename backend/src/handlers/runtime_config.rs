@@ -0,0 +1,55 @@
+//! Runtime Configuration Handlers Module
+//!
+//! This module contains HTTP request handlers that let administrators
+//! inspect and reload the settings that can change without a restart.
+
+use actix_web::{web, HttpResponse};
+use tracing::info;
+
+use crate::config::{ConfigReloader, RuntimeSettings};
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::services::AuditService;
+
+/// Get the reloadable settings currently in effect
+///
+/// GET /api/admin/config
+pub async fn get_runtime_settings(
+    claims: Claims,
+    reloader: web::Data<ConfigReloader>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    Ok(HttpResponse::Ok().json(RuntimeSettings::from(reloader.current().as_ref())))
+}
+
+/// Reload the configuration
+///
+/// POST /api/admin/config/reload
+///
+/// Re-reads the environment and the `.env` file, the same as sending SIGHUP
+/// to the process. Only the reloadable settings take effect; the response
+/// lists any other changed settings, which require a restart.
+pub async fn reload_config(
+    claims: Claims,
+    reloader: web::Data<ConfigReloader>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling reload_config request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let result = reloader.reload()?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config.reload", AuditResult::Success).with_details(serde_json::json!({
+                "applied": result.applied,
+                "requires_restart": result.requires_restart,
+            })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(result))
+}
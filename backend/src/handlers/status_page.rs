@@ -0,0 +1,79 @@
+//! Status Page Handlers Module
+//!
+//! This module contains HTTP request handlers for the fleet status page.
+//! Components an administrator made public can be read without signing in,
+//! e.g. by an ops wallboard.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::status_page::UpdateStatusPageSettingsRequest;
+use crate::services::{AuditService, StatusPageService};
+
+/// Get the fleet status page
+///
+/// GET /api/status
+///
+/// Returns the overall fleet status plus the components the caller may see.
+/// Anonymous callers get 401 unless at least one component is public.
+pub async fn get_status_page(
+    claims: Option<Claims>,
+    service: web::Data<StatusPageService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_status_page request");
+
+    let page = service.get_status_page(claims.is_some()).await?;
+
+    Ok(HttpResponse::Ok().json(page))
+}
+
+/// Get status page component visibility
+///
+/// GET /api/status/settings
+pub async fn get_status_page_settings(
+    claims: Claims,
+    service: web::Data<StatusPageService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let settings = service.get_settings().await?;
+
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+/// Change status page component visibility
+///
+/// PUT /api/status/settings
+///
+/// Request body:
+/// ```json
+/// {
+///   "components": { "nodes": "public", "alerts": "public", "last_incident": "hidden" }
+/// }
+/// ```
+pub async fn update_status_page_settings(
+    claims: Claims,
+    request: web::Json<UpdateStatusPageSettingsRequest>,
+    service: web::Data<StatusPageService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_status_page_settings request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let request = request.into_inner();
+    let details = serde_json::to_value(&request.components).unwrap_or_default();
+    let settings = service.update_settings(request).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "status_page.update", AuditResult::Success)
+                .with_details(details),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(settings))
+}
@@ -0,0 +1,28 @@
+//! Storage Handlers Module
+//!
+//! This module contains HTTP request handlers that report the disk space
+//! taken by the backend's data.
+
+use actix_web::{web, HttpResponse};
+
+use crate::error::AppResult;
+use crate::models::auth::Claims;
+use crate::services::{AuditService, StorageUsageService};
+
+/// Get the disk usage of each storage category
+///
+/// GET /api/admin/storage
+///
+/// `warnings` is non-empty once usage approaches the configured cap. Past
+/// the cap, new uploads and backups are refused.
+pub async fn get_storage_usage(
+    claims: Claims,
+    service: web::Data<StorageUsageService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let usage = service.get_usage().await?;
+
+    Ok(HttpResponse::Ok().json(usage))
+}
@@ -0,0 +1,94 @@
+//! Subnet Calculator Handlers Module
+//!
+//! This module contains HTTP request handlers for CIDR arithmetic used when
+//! planning addresses, so clients need not implement it themselves.
+
+use actix_web::{web, HttpResponse};
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::auth::Claims;
+use crate::models::subnet::{
+    ContainsRequest, NextAvailableRequest, SplitSubnetRequest, SubnetQuery, SummarizeSubnetsRequest,
+};
+use crate::services::{SubnetService, TeamService};
+
+/// Describe a subnet
+///
+/// GET /api/tools/subnet?cidr=192.0.2.0/24
+///
+/// Returns the network, masks, host range, and capacity of the subnet.
+pub async fn describe_subnet(
+    _claims: Claims,
+    query: web::Query<SubnetQuery>,
+    service: web::Data<SubnetService>,
+) -> AppResult<HttpResponse> {
+    let info = service.describe(&query.cidr)?;
+
+    Ok(HttpResponse::Ok().json(info))
+}
+
+/// Split a subnet
+///
+/// POST /api/tools/subnet/split
+///
+/// Splits a subnet into subnets of a longer prefix length, at most 1024.
+pub async fn split_subnet(
+    _claims: Claims,
+    request: web::Json<SplitSubnetRequest>,
+    service: web::Data<SubnetService>,
+) -> AppResult<HttpResponse> {
+    let result = service.split(&request)?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Summarize subnets
+///
+/// POST /api/tools/subnet/summarize
+///
+/// Merges adjacent and nested subnets into the fewest covering prefixes.
+pub async fn summarize_subnets(
+    _claims: Claims,
+    request: web::Json<SummarizeSubnetsRequest>,
+    service: web::Data<SubnetService>,
+) -> AppResult<HttpResponse> {
+    let result = service.summarize(&request)?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Check subnet membership
+///
+/// POST /api/tools/subnet/contains
+///
+/// Reports for each address or subnet whether it falls into the subnet.
+pub async fn subnet_contains(
+    _claims: Claims,
+    request: web::Json<ContainsRequest>,
+    service: web::Data<SubnetService>,
+) -> AppResult<HttpResponse> {
+    let result = service.contains(&request)?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Find free subnets
+///
+/// POST /api/tools/subnet/next-available
+///
+/// Returns the lowest subnets of the requested size inside an address block
+/// that overlap no prefix in use on the caller's nodes.
+pub async fn next_available_subnets(
+    claims: Claims,
+    request: web::Json<NextAvailableRequest>,
+    service: web::Data<SubnetService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    request.validate()?;
+
+    let scope = team_service.access_scope(&claims).await?;
+    let result = service.next_available(&request, &scope).await?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
@@ -0,0 +1,50 @@
+//! Support Bundle Handlers Module
+//!
+//! This module contains the HTTP request handler that lets administrators
+//! download diagnostics to attach to bug reports.
+
+use actix_web::{web, HttpResponse};
+use tracing::info;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::support_bundle::SupportBundleQuery;
+use crate::services::{AuditService, SupportBundleService};
+
+/// Download a support bundle
+///
+/// GET /api/admin/support-bundle
+///
+/// Returns a `.tar.gz` with versions, backend and node configuration, recent
+/// logs, and recent failures. Secrets are always redacted.
+///
+/// Query parameters:
+/// - anonymize: Replace IP/MAC addresses and hostnames (default: true)
+pub async fn download_support_bundle(
+    claims: Claims,
+    query: web::Query<SupportBundleQuery>,
+    service: web::Data<SupportBundleService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling download_support_bundle request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let anonymize = query.anonymize;
+    let bundle = service.build_bundle(&claims, anonymize).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "support_bundle.download", AuditResult::Success)
+                .with_details(serde_json::json!({ "anonymized": anonymize })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/gzip")
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", bundle.file_name),
+        ))
+        .body(bundle.content))
+}
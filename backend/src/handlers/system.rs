@@ -1,19 +1,24 @@
 use actix_web::{web, HttpResponse};
 
 use crate::error::AppResult;
+use crate::i18n::t;
+use crate::models::auth::Claims;
 use crate::models::system::{
     AddImageRequest, DeleteImageRequest, ImageManagementRequest, ResetConfigRequest,
     SetDefaultImageRequest, ShowCommandRequest,
 };
-use crate::services::SystemService;
+use crate::services::{AuditService, SystemService};
 
 /// Reboot the system
 ///
 /// POST /api/system/reboot
 pub async fn reboot(
+    claims: Option<Claims>,
     service: web::Data<SystemService>,
+    audit_service: web::Data<AuditService>,
 ) -> AppResult<HttpResponse> {
     let result = service.reboot().await?;
+    audit_service.record_operation("reboot", claims.as_ref(), &result).await;
 
     if result.success {
         Ok(HttpResponse::Accepted().json(result))
@@ -26,9 +31,12 @@ pub async fn reboot(
 ///
 /// POST /api/system/poweroff
 pub async fn poweroff(
+    claims: Option<Claims>,
     service: web::Data<SystemService>,
+    audit_service: web::Data<AuditService>,
 ) -> AppResult<HttpResponse> {
     let result = service.poweroff().await?;
+    audit_service.record_operation("poweroff", claims.as_ref(), &result).await;
 
     if result.success {
         Ok(HttpResponse::Accepted().json(result))
@@ -41,10 +49,13 @@ pub async fn poweroff(
 ///
 /// POST /api/system/reset
 pub async fn reset_configuration(
+    claims: Option<Claims>,
     service: web::Data<SystemService>,
+    audit_service: web::Data<AuditService>,
     request: web::Json<ResetConfigRequest>,
 ) -> AppResult<HttpResponse> {
     let result = service.reset_configuration(request.into_inner()).await?;
+    audit_service.record_operation("reset", claims.as_ref(), &result).await;
 
     if result.success {
         Ok(HttpResponse::Ok().json(result))
@@ -71,10 +82,13 @@ pub async fn list_images(
 ///
 /// POST /api/system/images/add
 pub async fn add_image(
+    claims: Option<Claims>,
     service: web::Data<SystemService>,
+    audit_service: web::Data<AuditService>,
     request: web::Json<AddImageRequest>,
 ) -> AppResult<HttpResponse> {
     let result = service.add_image(request.into_inner()).await?;
+    audit_service.record_operation("add_image", claims.as_ref(), &result).await;
 
     if result.success {
         Ok(HttpResponse::Accepted().json(result))
@@ -87,10 +101,13 @@ pub async fn add_image(
 ///
 /// POST /api/system/images/delete
 pub async fn delete_image(
+    claims: Option<Claims>,
     service: web::Data<SystemService>,
+    audit_service: web::Data<AuditService>,
     request: web::Json<DeleteImageRequest>,
 ) -> AppResult<HttpResponse> {
     let result = service.delete_image(request.into_inner()).await?;
+    audit_service.record_operation("delete_image", claims.as_ref(), &result).await;
 
     if result.success {
         Ok(HttpResponse::Ok().json(result))
@@ -103,10 +120,13 @@ pub async fn delete_image(
 ///
 /// POST /api/system/images/set-default
 pub async fn set_default_image(
+    claims: Option<Claims>,
     service: web::Data<SystemService>,
+    audit_service: web::Data<AuditService>,
     request: web::Json<SetDefaultImageRequest>,
 ) -> AppResult<HttpResponse> {
     let result = service.set_default_image(request.into_inner()).await?;
+    audit_service.record_operation("set_default_image", claims.as_ref(), &result).await;
 
     if result.success {
         Ok(HttpResponse::Ok().json(result))
@@ -119,16 +139,23 @@ pub async fn set_default_image(
 ///
 /// POST /api/system/images
 pub async fn manage_images(
+    claims: Option<Claims>,
     service: web::Data<SystemService>,
+    audit_service: web::Data<AuditService>,
     request: web::Json<ImageManagementRequest>,
 ) -> AppResult<HttpResponse> {
     let request = request.into_inner();
 
+    let kind = match request.operation {
+        crate::models::system::ImageOperation::Add => "add_image",
+        crate::models::system::ImageOperation::Delete => "delete_image",
+        crate::models::system::ImageOperation::SetDefault => "set_default_image",
+    };
     let result = match request.operation {
         crate::models::system::ImageOperation::Add => {
             let add_request = AddImageRequest {
                 url: request.url.ok_or_else(|| {
-                    crate::error::AppError::Validation("URL is required for add operation".to_string())
+                    crate::error::AppError::Validation(t("validation.url_required_for_add"))
                 })?,
                 checksum: request.checksum,
                 checksum_algorithm: request.checksum_algorithm,
@@ -138,7 +165,7 @@ pub async fn manage_images(
         crate::models::system::ImageOperation::Delete => {
             let delete_request = DeleteImageRequest {
                 name: request.name.ok_or_else(|| {
-                    crate::error::AppError::Validation("Name is required for delete operation".to_string())
+                    crate::error::AppError::Validation(t("validation.name_required_for_delete"))
                 })?,
             };
             service.delete_image(delete_request).await?
@@ -146,12 +173,13 @@ pub async fn manage_images(
         crate::models::system::ImageOperation::SetDefault => {
             let set_default_request = SetDefaultImageRequest {
                 name: request.name.ok_or_else(|| {
-                    crate::error::AppError::Validation("Name is required for set-default operation".to_string())
+                    crate::error::AppError::Validation(t("validation.name_required_for_set_default"))
                 })?,
             };
             service.set_default_image(set_default_request).await?
         }
     };
+    audit_service.record_operation(kind, claims.as_ref(), &result).await;
 
     if result.success {
         Ok(HttpResponse::Ok().json(result))
@@ -190,15 +218,22 @@ pub async fn get_system_info(
 /// Check operation status
 ///
 /// GET /api/system/operations/{operation_id}
+///
+/// Falls back to the recorded operation when the system has no live status.
 pub async fn check_operation_status(
     service: web::Data<SystemService>,
+    audit_service: web::Data<AuditService>,
     operation_id: web::Path<String>,
 ) -> AppResult<HttpResponse> {
     let id = operation_id.into_inner();
     let result = service.check_operation_status(&id).await?;
 
-    match result {
-        Some(operation_result) => Ok(HttpResponse::Ok().json(operation_result)),
+    if let Some(operation_result) = result {
+        return Ok(HttpResponse::Ok().json(operation_result));
+    }
+
+    match audit_service.get_operation(&id).await? {
+        Some(operation) => Ok(HttpResponse::Ok().json(operation)),
         None => Ok(HttpResponse::NotFound().json(serde_json::json!({
             "error": "Operation not found",
             "operation_id": id
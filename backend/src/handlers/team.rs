@@ -0,0 +1,233 @@
+//! Team Handlers Module
+//!
+//! This module contains HTTP request handlers for team management endpoints.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{AppError, AppResult};
+use crate::models::auth::Claims;
+use crate::models::quota::UpdateTeamQuotaRequest;
+use crate::models::team::{AddTeamMemberRequest, CreateTeamRequest, UpdateTeamRequest};
+use crate::services::{QuotaService, TeamService};
+
+/// List teams
+///
+/// GET /api/teams
+///
+/// Returns all teams for administrators, or the caller's own teams otherwise.
+pub async fn list_teams(
+    claims: Claims,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_teams request");
+
+    let response = team_service.list_teams(&claims).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Create a team
+///
+/// POST /api/teams
+///
+/// Creates a new team. Only administrators can create teams.
+pub async fn create_team(
+    claims: Claims,
+    request: web::Json<CreateTeamRequest>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_team request for team: {}", request.name);
+
+    request.validate()?;
+
+    let team = team_service.create_team(&claims, request.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(team))
+}
+
+/// Get a team
+///
+/// GET /api/teams/:id
+///
+/// Returns a team with its members.
+pub async fn get_team(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_team request");
+
+    let team = team_service.get_team(&claims, path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(team))
+}
+
+/// Update a team
+///
+/// PUT /api/teams/:id
+///
+/// Updates a team's name, alias, or description.
+pub async fn update_team(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateTeamRequest>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_team request");
+
+    request.validate()?;
+
+    let team = team_service
+        .update_team(&claims, path.into_inner(), request.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(team))
+}
+
+/// Delete a team
+///
+/// DELETE /api/teams/:id
+///
+/// Deletes a team. Resources owned by the team become unowned.
+pub async fn delete_team(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling delete_team request");
+
+    let team_id = path.into_inner();
+    team_service.delete_team(&claims, team_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Team deleted successfully",
+        "team_id": team_id.to_string()
+    })))
+}
+
+/// Add a team member
+///
+/// POST /api/teams/:id/members
+///
+/// Adds a user to the team, or changes their role if already a member.
+pub async fn add_team_member(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<AddTeamMemberRequest>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling add_team_member request");
+
+    let members = team_service
+        .add_member(&claims, path.into_inner(), request.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(members))
+}
+
+/// Remove a team member
+///
+/// DELETE /api/teams/:id/members/:user_id
+///
+/// Removes a user from the team.
+pub async fn remove_team_member(
+    claims: Claims,
+    path: web::Path<(Uuid, Uuid)>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling remove_team_member request");
+
+    let (team_id, user_id) = path.into_inner();
+    team_service.remove_member(&claims, team_id, user_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Team member removed successfully",
+        "team_id": team_id.to_string(),
+        "user_id": user_id.to_string()
+    })))
+}
+
+/// Get a team's quota
+///
+/// GET /api/teams/:id/quota
+///
+/// Returns the team's quota overrides and the limits in effect.
+pub async fn get_team_quota(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    team_service: web::Data<TeamService>,
+    quota_service: web::Data<QuotaService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_team_quota request");
+
+    let team_id = path.into_inner();
+    ensure_team_visible(&team_service, &claims, team_id).await?;
+
+    let quota = quota_service.get_quota(team_id).await?;
+
+    Ok(HttpResponse::Ok().json(quota))
+}
+
+/// Update a team's quota
+///
+/// PUT /api/teams/:id/quota
+///
+/// Replaces the team's quota overrides. Only administrators can change quotas.
+pub async fn update_team_quota(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateTeamQuotaRequest>,
+    team_service: web::Data<TeamService>,
+    quota_service: web::Data<QuotaService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_team_quota request");
+
+    let user = team_service.current_user(&claims).await?;
+    if !user.is_superuser {
+        return Err(AppError::Forbidden("Only administrators can change team quotas".to_string()));
+    }
+
+    let quota = quota_service
+        .set_quota(path.into_inner(), request.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(quota))
+}
+
+/// Get a team's quota usage
+///
+/// GET /api/teams/:id/usage
+///
+/// Returns the team's consumption of each quota resource against its limit.
+pub async fn get_team_usage(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    team_service: web::Data<TeamService>,
+    quota_service: web::Data<QuotaService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_team_usage request");
+
+    let team_id = path.into_inner();
+    ensure_team_visible(&team_service, &claims, team_id).await?;
+
+    let usage = quota_service.get_usage(team_id).await?;
+
+    Ok(HttpResponse::Ok().json(usage))
+}
+
+/// Ensure the caller is an administrator or a member of the team
+async fn ensure_team_visible(
+    team_service: &TeamService,
+    claims: &Claims,
+    team_id: Uuid,
+) -> AppResult<()> {
+    let scope = team_service.access_scope(claims).await?;
+    if !scope.includes_team(team_id) {
+        return Err(AppError::Forbidden("You are not a member of this team".to_string()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! Network Topology Handlers Module
+//!
+//! This module contains HTTP request handlers for the network topology:
+//! the discovered map of the nodes the caller can access, and the links and
+//! positions entered by hand.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::monitoring::{
+    CreateTopologyLinkRequest, LinkStatus, TopologyQuery, UpdateTopologyLinkRequest, UpdateTopologyPositionsRequest,
+};
+use crate::services::topology::StoredTopologyLink;
+use crate::services::{AuditService, NodeService, TeamService, TopologyService};
+
+/// Authorize access to the nodes among link or position endpoints
+///
+/// Device IDs such as `lldp:core-sw1` are not nodes and need no access.
+async fn authorize_endpoints<'a>(
+    node_service: &NodeService,
+    team_service: &TeamService,
+    claims: &Claims,
+    endpoints: impl IntoIterator<Item = &'a str>,
+) -> AppResult<()> {
+    for endpoint in endpoints {
+        if let Ok(node_id) = Uuid::parse_str(endpoint.trim()) {
+            crate::handlers::node::authorize_node(node_service, team_service, claims, node_id).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Get the network topology
+///
+/// GET /api/monitoring/topology
+///
+/// Query parameters:
+/// - discover: Read LLDP neighbors and ARP tables from the nodes (default
+///   true); `false` builds the map from interfaces and stored links only
+pub async fn get_topology(
+    claims: Claims,
+    query: web::Query<TopologyQuery>,
+    service: web::Data<TopologyService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling get_topology request");
+
+    let scope = team_service.access_scope(&claims).await?;
+    let nodes: Vec<_> = node_service
+        .list_all_nodes()
+        .await?
+        .into_iter()
+        .filter(|node| scope.can_access(node.team_id))
+        .collect();
+
+    let topology = service.topology(nodes, query.discover).await?;
+
+    Ok(HttpResponse::Ok().json(topology))
+}
+
+/// Add a topology link by hand
+///
+/// POST /api/monitoring/topology/links
+///
+/// Request body:
+/// ```json
+/// { "source": "<node id>", "target": "isp:acme", "link_type": "fiber", "source_interface": "eth0" }
+/// ```
+pub async fn create_topology_link(
+    claims: Claims,
+    request: web::Json<CreateTopologyLinkRequest>,
+    service: web::Data<TopologyService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_topology_link request");
+
+    let request = request.into_inner();
+    request.validate()?;
+    service.ensure_can_write(&claims).await?;
+    authorize_endpoints(&node_service, &team_service, &claims, [request.source.as_str(), request.target.as_str()])
+        .await?;
+
+    let link = service.create_link(&claims, request).await?;
+    record_link(&audit_service, &claims, "topology.link.create", &link).await;
+
+    Ok(HttpResponse::Created().json(link.to_link(LinkStatus::Unknown)))
+}
+
+/// Change a topology link entered by hand
+///
+/// PUT /api/monitoring/topology/links/{id}
+pub async fn update_topology_link(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateTopologyLinkRequest>,
+    service: web::Data<TopologyService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_topology_link request");
+
+    let request = request.into_inner();
+    request.validate()?;
+    service.ensure_can_write(&claims).await?;
+    let link = service.get_link(path.into_inner()).await?;
+    authorize_endpoints(&node_service, &team_service, &claims, [link.source.as_str(), link.target.as_str()]).await?;
+
+    let link = service.update_link(link.id, request).await?;
+    record_link(&audit_service, &claims, "topology.link.update", &link).await;
+
+    Ok(HttpResponse::Ok().json(link.to_link(LinkStatus::Unknown)))
+}
+
+/// Delete a topology link entered by hand
+///
+/// DELETE /api/monitoring/topology/links/{id}
+pub async fn delete_topology_link(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<TopologyService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling delete_topology_link request");
+
+    service.ensure_can_write(&claims).await?;
+    let link = service.get_link(path.into_inner()).await?;
+    authorize_endpoints(&node_service, &team_service, &claims, [link.source.as_str(), link.target.as_str()]).await?;
+
+    let link = service.delete_link(link.id).await?;
+    record_link(&audit_service, &claims, "topology.link.delete", &link).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Place topology nodes in the map
+///
+/// POST /api/monitoring/topology/positions
+///
+/// Request body:
+/// ```json
+/// { "positions": [{ "node_id": "<node id>", "x": 120.0, "y": 80.0 }, { "node_id": "lldp:core-sw1", "x": 0, "y": 0 }] }
+/// ```
+///
+/// Nodes left out keep their positions.
+pub async fn update_topology_positions(
+    claims: Claims,
+    request: web::Json<UpdateTopologyPositionsRequest>,
+    service: web::Data<TopologyService>,
+    node_service: web::Data<NodeService>,
+    team_service: web::Data<TeamService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling update_topology_positions request");
+
+    let request = request.into_inner();
+    request.validate()?;
+    service.ensure_can_write(&claims).await?;
+    authorize_endpoints(
+        &node_service,
+        &team_service,
+        &claims,
+        request.positions.iter().map(|position| position.node_id.as_str()),
+    )
+    .await?;
+
+    let updated = service.set_positions(request).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "topology.positions", AuditResult::Success)
+                .with_details(serde_json::json!({ "updated": updated })),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "updated": updated })))
+}
+
+async fn record_link(audit_service: &AuditService, claims: &Claims, action: &str, link: &StoredTopologyLink) {
+    audit_service
+        .record(
+            AuditEvent::new(Some(claims), action, AuditResult::Success)
+                .with_target(link.id.to_string())
+                .with_details(serde_json::json!({
+                    "source": link.source,
+                    "target": link.target,
+                    "link_type": link.link_type,
+                    "source_interface": link.source_interface,
+                    "target_interface": link.target_interface,
+                })),
+        )
+        .await;
+}
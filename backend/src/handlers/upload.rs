@@ -0,0 +1,167 @@
+//! Upload Handlers Module
+//!
+//! This module contains HTTP request handlers for file uploads sent as
+//! multipart/form-data. Uploads are restricted to administrators.
+
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::upload::{UploadListQuery, UploadQuery};
+use crate::services::{AuditService, UploadService};
+
+/// Upload a file
+///
+/// POST /api/uploads?kind=image
+///
+/// The file is sent in the `file` field of a multipart/form-data body.
+///
+/// Query parameters:
+/// - kind: image (VyOS `.iso`), ca_certificate (PEM) or config (JSON)
+pub async fn upload_file(
+    claims: Claims,
+    query: web::Query<UploadQuery>,
+    multipart: Multipart,
+    service: web::Data<UploadService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling upload_file request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let upload = service.upload(&claims, query.kind, multipart).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "upload.create", AuditResult::Success)
+                .with_target(upload.id.to_string())
+                .with_details(serde_json::json!({
+                    "kind": upload.kind,
+                    "file_name": upload.file_name,
+                    "size_bytes": upload.size_bytes,
+                    "sha256": upload.sha256,
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(upload))
+}
+
+/// List uploads
+///
+/// GET /api/uploads
+///
+/// Query parameters:
+/// - kind: Only list uploads of this kind
+pub async fn list_uploads(
+    claims: Claims,
+    query: web::Query<UploadListQuery>,
+    service: web::Data<UploadService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let uploads = service.list_uploads(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(uploads))
+}
+
+/// Get an upload's metadata
+///
+/// GET /api/uploads/:id
+pub async fn get_upload(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<UploadService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let upload = service.get_upload(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(upload))
+}
+
+/// Download an uploaded file
+///
+/// GET /api/uploads/:id/download
+///
+/// The content is streamed from disk and decompressed on the way.
+pub async fn download_upload(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<UploadService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let (upload, content) = service.open_upload(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(upload.content_type.as_deref().unwrap_or("application/octet-stream"))
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", upload.file_name),
+        ))
+        .streaming(content))
+}
+
+/// Delete an upload
+///
+/// DELETE /api/uploads/:id
+pub async fn delete_upload(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<UploadService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let id = path.into_inner();
+    service.delete_upload(id).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "upload.delete", AuditResult::Success)
+                .with_target(id.to_string()),
+        )
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Import a configuration file
+///
+/// POST /api/config/import
+///
+/// Multipart/form-data fields:
+/// - file: Configuration as a JSON object
+/// - node_id: Node whose configuration history receives the import
+/// - comment: Optional description of the change
+pub async fn import_config(
+    claims: Claims,
+    multipart: Multipart,
+    service: web::Data<UploadService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling import_config request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let result = service.import_config(&claims, multipart).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "config.import", AuditResult::Success)
+                .with_target(result.node_id.to_string())
+                .with_details(serde_json::json!({
+                    "history_id": result.history_id,
+                    "version": result.version,
+                    "file_name": result.upload.file_name,
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(result))
+}
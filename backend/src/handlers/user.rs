@@ -2,12 +2,19 @@ use actix_web::{web, HttpRequest};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 use tracing::info;
+use uuid::Uuid;
 
 use crate::error::AppResult;
+use crate::i18n::{t, t_args};
 use crate::middleware::auth::extract_claims;
-use crate::models::auth::RegisterRequest;
-use crate::models::user::{ChangePasswordRequest, UpdateProfileRequest, UpdateUserRequest, User, UserListQuery, UserListResponse};
-use crate::services::UserService;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::{Claims, RegisterRequest};
+use crate::models::login_history::LoginHistoryQuery;
+use crate::models::permission::SetUserPermissionsRequest;
+use crate::models::terminal::SetTerminalAllowlistRequest;
+use crate::models::timestamp::format_timestamp;
+use crate::models::user::{ChangeEmailRequest, ChangePasswordRequest, ConfirmEmailChangeRequest, UpdateProfileRequest, UpdateUserRequest, User, UserListQuery, UserListResponse};
+use crate::services::{AuditService, LoginHistoryService, PermissionService, TerminalService, UserService};
 
 /// User information structure for response
 #[derive(Serialize, Deserialize)]
@@ -19,36 +26,46 @@ pub struct UserInfo {
     pub role: String,
     pub is_active: bool,
     pub is_superuser: bool,
+    pub timezone: String,
+    pub password_reset_required: bool,
     pub last_login: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl From<User> for UserInfo {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id.to_string(),
+            username: user.username,
+            email: user.email,
+            full_name: user.full_name,
+            role: format!("{:?}", user.role).to_lowercase(),
+            is_active: matches!(user.status, crate::models::user::UserStatus::Active),
+            is_superuser: matches!(user.role, crate::models::user::UserRole::Admin),
+            timezone: user.timezone,
+            password_reset_required: user.password_reset_required,
+            last_login: user.last_login.as_ref().map(format_timestamp),
+            created_at: format_timestamp(&user.created_at),
+            updated_at: format_timestamp(&user.updated_at),
+        }
+    }
+}
+
 /// Get current user profile
 pub async fn get_profile(
     req: HttpRequest,
     user_service: web::Data<UserService>,
 ) -> AppResult<actix_web::HttpResponse> {
     let claims = extract_claims(&req)?;
-    let user_id: i64 = claims.sub.parse().unwrap_or(0);
+    let user_id = claims.user_id()?;
 
     let user = user_service
         .get_user(user_id)
         .await?
         .ok_or_else(|| crate::error::AppError::NotFound("User not found".to_string()))?;
 
-    Ok(actix_web::HttpResponse::Ok().json(UserInfo {
-        id: user.id.to_string(),
-        username: user.username,
-        email: user.email,
-        full_name: user.full_name,
-        role: format!("{:?}", user.role).to_lowercase(),
-        is_active: matches!(user.status, crate::models::user::UserStatus::Active),
-        is_superuser: matches!(user.role, crate::models::user::UserRole::Admin),
-        last_login: user.last_login.map(|dt| dt.to_rfc3339()),
-        created_at: user.created_at.to_rfc3339(),
-        updated_at: user.updated_at.to_rfc3339(),
-    }))
+    Ok(actix_web::HttpResponse::Ok().json(UserInfo::from(user)))
 }
 
 /// Update user profile
@@ -58,26 +75,14 @@ pub async fn update_profile(
     user_service: web::Data<UserService>,
 ) -> AppResult<actix_web::HttpResponse> {
     let claims = extract_claims(&req)?;
-    let user_id: i64 = claims.sub.parse().unwrap_or(0);
+    let user_id = claims.user_id()?;
 
     // Validate request
-    profile.validate()
-        .map_err(|e| crate::error::AppError::Validation(format!("Validation failed: {:?}", e)))?;
+    profile.validate()?;
 
     let user = user_service.update_profile(user_id, profile.into_inner()).await?;
 
-    Ok(actix_web::HttpResponse::Ok().json(UserInfo {
-        id: user.id.to_string(),
-        username: user.username,
-        email: user.email,
-        full_name: user.full_name,
-        role: format!("{:?}", user.role).to_lowercase(),
-        is_active: matches!(user.status, crate::models::user::UserStatus::Active),
-        is_superuser: matches!(user.role, crate::models::user::UserRole::Admin),
-        last_login: user.last_login.map(|dt| dt.to_rfc3339()),
-        created_at: user.created_at.to_rfc3339(),
-        updated_at: user.updated_at.to_rfc3339(),
-    }))
+    Ok(actix_web::HttpResponse::Ok().json(UserInfo::from(user)))
 }
 
 /// Change password
@@ -87,11 +92,10 @@ pub async fn change_password(
     user_service: web::Data<UserService>,
 ) -> AppResult<actix_web::HttpResponse> {
     let claims = extract_claims(&req)?;
-    let user_id: i64 = claims.sub.parse().unwrap_or(0);
+    let user_id = claims.user_id()?;
 
     // Validate request
-    password_data.validate()
-        .map_err(|e| crate::error::AppError::Validation(format!("Validation failed: {:?}", e)))?;
+    password_data.validate()?;
 
     user_service
         .change_password(user_id, password_data.into_inner())
@@ -102,6 +106,60 @@ pub async fn change_password(
     })))
 }
 
+/// Start changing the current user's email address
+///
+/// Sends a confirmation token to the new address and a notice to the current
+/// one. The address changes once the token is confirmed.
+pub async fn request_email_change(
+    req: HttpRequest,
+    email_data: web::Json<ChangeEmailRequest>,
+    user_service: web::Data<UserService>,
+) -> AppResult<actix_web::HttpResponse> {
+    let claims = extract_claims(&req)?;
+    let user_id = claims.user_id()?;
+
+    // Validate request
+    email_data.validate()?;
+
+    let pending = user_service
+        .request_email_change(user_id, email_data.into_inner())
+        .await?;
+
+    Ok(actix_web::HttpResponse::Accepted().json(pending))
+}
+
+/// Confirm an email change with the token sent to the new address
+///
+/// The token identifies the change, so no login is needed; the link may be
+/// opened on another device.
+pub async fn confirm_email_change(
+    confirmation: web::Json<ConfirmEmailChangeRequest>,
+    user_service: web::Data<UserService>,
+) -> AppResult<actix_web::HttpResponse> {
+    let user = user_service.confirm_email_change(&confirmation.token).await?;
+
+    info!("Email change confirmed for user: {}", user.username);
+
+    Ok(actix_web::HttpResponse::Ok().json(UserInfo::from(user)))
+}
+
+/// List the current user's recent logins, newest first
+///
+/// Logins from a device or country the user had not signed in from before
+/// are flagged with `new_device` or `new_location`.
+pub async fn list_my_logins(
+    req: HttpRequest,
+    query: web::Query<LoginHistoryQuery>,
+    login_history: web::Data<LoginHistoryService>,
+) -> AppResult<actix_web::HttpResponse> {
+    let claims = extract_claims(&req)?;
+    let user_id = claims.user_id()?;
+
+    let response = login_history.list(user_id, query.into_inner()).await?;
+
+    Ok(actix_web::HttpResponse::Ok().json(response))
+}
+
 /// Get all users (admin only)
 pub async fn list_users(
     req: HttpRequest,
@@ -110,7 +168,7 @@ pub async fn list_users(
 ) -> AppResult<actix_web::HttpResponse> {
     // Verify user is admin
     let claims = extract_claims(&req)?;
-    let user_id: i64 = claims.sub.parse().unwrap_or(0);
+    let user_id = claims.user_id()?;
 
     let user = user_service
         .get_user(user_id)
@@ -133,7 +191,7 @@ pub async fn create_user(
 ) -> AppResult<actix_web::HttpResponse> {
     // Verify user is admin
     let claims = extract_claims(&req)?;
-    let user_id: i64 = claims.sub.parse().unwrap_or(0);
+    let user_id = claims.user_id()?;
 
     let user = user_service
         .get_user(user_id)
@@ -145,8 +203,7 @@ pub async fn create_user(
     }
 
     // Validate request
-    user_data.validate()
-        .map_err(|e| crate::error::AppError::Validation(format!("Validation failed: {:?}", e)))?;
+    user_data.validate()?;
 
     let new_user = user_service
         .create_user(
@@ -159,18 +216,7 @@ pub async fn create_user(
 
     info!("User created by admin: {}", new_user.username);
 
-    Ok(actix_web::HttpResponse::Created().json(UserInfo {
-        id: new_user.id.to_string(),
-        username: new_user.username,
-        email: new_user.email,
-        full_name: new_user.full_name,
-        role: format!("{:?}", new_user.role).to_lowercase(),
-        is_active: matches!(new_user.status, crate::models::user::UserStatus::Active),
-        is_superuser: matches!(new_user.role, crate::models::user::UserRole::Admin),
-        last_login: new_user.last_login.map(|dt| dt.to_rfc3339()),
-        created_at: new_user.created_at.to_rfc3339(),
-        updated_at: new_user.updated_at.to_rfc3339(),
-    }))
+    Ok(actix_web::HttpResponse::Created().json(UserInfo::from(new_user)))
 }
 
 /// Update user (admin only)
@@ -182,7 +228,7 @@ pub async fn update_user(
 ) -> AppResult<actix_web::HttpResponse> {
     // Verify user is admin
     let claims = extract_claims(&req)?;
-    let requesting_user_id: i64 = claims.sub.parse().unwrap_or(0);
+    let requesting_user_id = claims.user_id()?;
 
     let requesting_user = user_service
         .get_user(requesting_user_id)
@@ -194,13 +240,11 @@ pub async fn update_user(
     }
 
     // Parse user ID from path
-    let target_user_id: i64 = user_id_path
-        .parse()
-        .map_err(|e| crate::error::AppError::Validation(format!("Invalid user ID: {}", e)))?;
+    let target_user_id = Uuid::parse_str(&user_id_path)
+        .map_err(|e| crate::error::AppError::Validation(t_args("validation.invalid_user_id", &[("error", &e)])))?;
 
     // Validate request
-    user_data.validate()
-        .map_err(|e| crate::error::AppError::Validation(format!("Validation failed: {:?}", e)))?;
+    user_data.validate()?;
 
     let updated_user = user_service
         .update_user(target_user_id, user_data.into_inner())
@@ -208,18 +252,7 @@ pub async fn update_user(
 
     info!("User updated by admin: {}", updated_user.username);
 
-    Ok(actix_web::HttpResponse::Ok().json(UserInfo {
-        id: updated_user.id.to_string(),
-        username: updated_user.username,
-        email: updated_user.email,
-        full_name: updated_user.full_name,
-        role: format!("{:?}", updated_user.role).to_lowercase(),
-        is_active: matches!(updated_user.status, crate::models::user::UserStatus::Active),
-        is_superuser: matches!(updated_user.role, crate::models::user::UserRole::Admin),
-        last_login: updated_user.last_login.map(|dt| dt.to_rfc3339()),
-        created_at: updated_user.created_at.to_rfc3339(),
-        updated_at: updated_user.updated_at.to_rfc3339(),
-    }))
+    Ok(actix_web::HttpResponse::Ok().json(UserInfo::from(updated_user)))
 }
 
 /// Delete user (admin only)
@@ -230,7 +263,7 @@ pub async fn delete_user(
 ) -> AppResult<actix_web::HttpResponse> {
     // Verify user is admin
     let claims = extract_claims(&req)?;
-    let requesting_user_id: i64 = claims.sub.parse().unwrap_or(0);
+    let requesting_user_id = claims.user_id()?;
 
     let requesting_user = user_service
         .get_user(requesting_user_id)
@@ -242,15 +275,12 @@ pub async fn delete_user(
     }
 
     // Parse user ID from path
-    let target_user_id: i64 = user_id_path
-        .parse()
-        .map_err(|e| crate::error::AppError::Validation(format!("Invalid user ID: {}", e)))?;
+    let target_user_id = Uuid::parse_str(&user_id_path)
+        .map_err(|e| crate::error::AppError::Validation(t_args("validation.invalid_user_id", &[("error", &e)])))?;
 
     // Prevent users from deleting themselves
     if target_user_id == requesting_user_id {
-        return Err(crate::error::AppError::Validation(
-            "Cannot delete your own account".to_string(),
-        ));
+        return Err(crate::error::AppError::Validation(t("validation.cannot_delete_self")));
     }
 
     user_service.delete_user(target_user_id).await?;
@@ -260,4 +290,96 @@ pub async fn delete_user(
     Ok(actix_web::HttpResponse::Ok().json(serde_json::json!({
         "message": "User deleted successfully"
     })))
-}
\ No newline at end of file
+}
+/// Get the permissions of a user (admin only)
+///
+/// GET /api/users/{id}/permissions
+pub async fn get_user_permissions(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    permission_service: web::Data<PermissionService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<actix_web::HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let permissions = permission_service.get_user_permissions(path.into_inner()).await?;
+
+    Ok(actix_web::HttpResponse::Ok().json(permissions))
+}
+
+/// Replace the permissions granted to a user directly (admin only)
+///
+/// PUT /api/users/{id}/permissions
+pub async fn set_user_permissions(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<SetUserPermissionsRequest>,
+    permission_service: web::Data<PermissionService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<actix_web::HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let user_id = path.into_inner();
+    let permissions = permission_service
+        .set_user_permissions(user_id, request.into_inner(), &claims.username)
+        .await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "user.permissions", AuditResult::Success)
+                .with_target(user_id.to_string())
+                .with_details(serde_json::json!({ "granted": permissions.granted })),
+        )
+        .await;
+
+    Ok(actix_web::HttpResponse::Ok().json(permissions))
+}
+
+/// Get the terminal allowlist of a user (admin only)
+///
+/// GET /api/users/{id}/terminal-allowlist
+pub async fn get_terminal_allowlist(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    terminal_service: web::Data<TerminalService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<actix_web::HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let allowlist = terminal_service.get_allowlist(path.into_inner()).await?;
+
+    Ok(actix_web::HttpResponse::Ok().json(allowlist))
+}
+
+/// Replace the terminal allowlist of a user (admin only)
+///
+/// PUT /api/users/{id}/terminal-allowlist
+///
+/// Request body:
+/// ```json
+/// { "commands": ["show interfaces", "show ip route"] }
+/// ```
+///
+/// Entries are command prefixes of whole words; `*` allows every command.
+pub async fn set_terminal_allowlist(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    request: web::Json<SetTerminalAllowlistRequest>,
+    terminal_service: web::Data<TerminalService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<actix_web::HttpResponse> {
+    audit_service.ensure_admin(&claims).await?;
+
+    let request = request.into_inner();
+    request.validate()?;
+    let user_id = path.into_inner();
+    let allowlist = terminal_service.set_allowlist(user_id, request, &claims.username).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "user.terminal_allowlist", AuditResult::Success)
+                .with_target(user_id.to_string())
+                .with_details(serde_json::json!({ "commands": allowlist.commands })),
+        )
+        .await;
+
+    Ok(actix_web::HttpResponse::Ok().json(allowlist))
+}
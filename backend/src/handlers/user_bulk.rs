@@ -0,0 +1,176 @@
+//! Bulk User Management Handlers Module
+//!
+//! This module contains HTTP request handlers for changing many users at
+//! once: importing them from CSV, assigning roles and teams, deactivating
+//! them, and forcing password resets. Every operation requires an
+//! administrator and accepts a dry run that reports what would change.
+
+use actix_web::web;
+use tracing::info;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::team::TeamRole;
+use crate::models::user::{
+    BulkImportQuery, BulkOutcome, BulkRoleRequest, BulkTeamRequest, BulkUserResponse, BulkUserResult,
+    BulkUsersRequest,
+};
+use crate::models::response::{ApiResponse, ApiWarning};
+use crate::services::{AuditService, UserService};
+
+/// Import users from CSV
+///
+/// POST /api/users/bulk/import
+///
+/// The body is CSV whose header row names the columns `username`, `email`,
+/// and optionally `full_name`, `role`, and `password`. Users without a
+/// password get a temporary one, returned once in the response. Pass
+/// `?dry_run=true` to validate the file without creating anyone.
+pub async fn bulk_import_users(
+    claims: Claims,
+    query: web::Query<BulkImportQuery>,
+    body: String,
+    user_service: web::Data<UserService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<ApiResponse<BulkUserResponse>> {
+    info!("Handling bulk_import_users request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let results = user_service.bulk_import(&body, query.dry_run).await?;
+
+    respond(&claims, &audit_service, "user.bulk_import", query.dry_run, results).await
+}
+
+/// Assign a role to several users
+///
+/// POST /api/users/bulk/roles
+pub async fn bulk_set_user_role(
+    claims: Claims,
+    request: web::Json<BulkRoleRequest>,
+    user_service: web::Data<UserService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<ApiResponse<BulkUserResponse>> {
+    info!("Handling bulk_set_user_role request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let request = request.into_inner();
+    let results = user_service
+        .bulk_set_role(claims.user_id()?, &request.user_ids, request.role, request.dry_run)
+        .await?;
+
+    respond(&claims, &audit_service, "user.bulk_role", request.dry_run, results).await
+}
+
+/// Add several users to a team
+///
+/// POST /api/users/bulk/teams
+///
+/// Users already in the team get the requested role within it.
+pub async fn bulk_assign_user_team(
+    claims: Claims,
+    request: web::Json<BulkTeamRequest>,
+    user_service: web::Data<UserService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<ApiResponse<BulkUserResponse>> {
+    info!("Handling bulk_assign_user_team request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let request = request.into_inner();
+    let role = request.role.unwrap_or(TeamRole::Member);
+    let results = user_service
+        .bulk_assign_team(&request.user_ids, request.team_id, role, request.dry_run)
+        .await?;
+
+    respond(&claims, &audit_service, "user.bulk_team", request.dry_run, results).await
+}
+
+/// Deactivate several users
+///
+/// POST /api/users/bulk/deactivate
+pub async fn bulk_deactivate_users(
+    claims: Claims,
+    request: web::Json<BulkUsersRequest>,
+    user_service: web::Data<UserService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<ApiResponse<BulkUserResponse>> {
+    info!("Handling bulk_deactivate_users request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let request = request.into_inner();
+    let results = user_service
+        .bulk_deactivate(claims.user_id()?, &request.user_ids, request.dry_run)
+        .await?;
+
+    respond(&claims, &audit_service, "user.bulk_deactivate", request.dry_run, results).await
+}
+
+/// Require several users to choose a new password
+///
+/// POST /api/users/bulk/password-reset
+pub async fn bulk_require_password_reset(
+    claims: Claims,
+    request: web::Json<BulkUsersRequest>,
+    user_service: web::Data<UserService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<ApiResponse<BulkUserResponse>> {
+    info!("Handling bulk_require_password_reset request");
+
+    audit_service.ensure_admin(&claims).await?;
+
+    let request = request.into_inner();
+    let results = user_service
+        .bulk_require_password_reset(&request.user_ids, request.dry_run)
+        .await?;
+
+    respond(&claims, &audit_service, "user.bulk_password_reset", request.dry_run, results).await
+}
+
+/// Summarize the results, recording applied operations in the audit log
+///
+/// Every failed user is also a warning of the response.
+async fn respond(
+    claims: &Claims,
+    audit_service: &AuditService,
+    action: &str,
+    dry_run: bool,
+    results: Vec<BulkUserResult>,
+) -> AppResult<ApiResponse<BulkUserResponse>> {
+    let response = BulkUserResponse::new(dry_run, results);
+
+    if !dry_run {
+        let users: Vec<_> = response
+            .results
+            .iter()
+            .filter(|result| matches!(result.outcome, BulkOutcome::Created | BulkOutcome::Changed))
+            .map(|result| &result.username)
+            .collect();
+        audit_service
+            .record(AuditEvent::new(Some(claims), action, AuditResult::Success).with_details(serde_json::json!({
+                "created": response.created,
+                "changed": response.changed,
+                "unchanged": response.unchanged,
+                "failed": response.failed,
+                "users": users,
+            })))
+            .await;
+    }
+
+    let warnings: Vec<ApiWarning> = response
+        .results
+        .iter()
+        .filter_map(|result| {
+            let error = result.error.as_deref()?;
+            let user = result.username.clone().or_else(|| result.user_id.map(|id| id.to_string()));
+            Some(match user {
+                Some(user) => ApiWarning::new(error).with_path(user),
+                None => ApiWarning::new(error),
+            })
+        })
+        .collect();
+    Ok(ApiResponse::new(response).with_warnings(warnings))
+}
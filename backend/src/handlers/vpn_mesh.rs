@@ -0,0 +1,149 @@
+//! VPN Mesh Handlers Module
+//!
+//! This module contains HTTP request handlers for WireGuard meshes among
+//! managed nodes: creating them, pushing their configuration to every
+//! member, and checking the health of their tunnels.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::vpn_mesh::CreateVpnMeshRequest;
+use crate::services::{AuditService, VpnMeshService};
+
+/// Create a VPN mesh
+///
+/// POST /api/vpn-meshes
+///
+/// Allocates the members' tunnel addresses and key pairs; nothing is
+/// pushed to the nodes yet.
+pub async fn create_vpn_mesh(
+    claims: Claims,
+    req: web::Json<CreateVpnMeshRequest>,
+    service: web::Data<VpnMeshService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling create_vpn_mesh request for {}", req.name);
+
+    let mesh = service.create(req.into_inner(), &claims).await?;
+    audit_service
+        .record(
+            AuditEvent::new(Some(&claims), "vpn_mesh.create", AuditResult::Success)
+                .with_target(mesh.id.to_string())
+                .with_details(serde_json::json!({
+                    "name": mesh.name,
+                    "topology": mesh.topology.as_str(),
+                    "members": mesh.members.len(),
+                })),
+        )
+        .await;
+
+    Ok(HttpResponse::Created().json(mesh))
+}
+
+/// List VPN meshes
+///
+/// GET /api/vpn-meshes
+pub async fn list_vpn_meshes(claims: Claims, service: web::Data<VpnMeshService>) -> AppResult<HttpResponse> {
+    debug!("Handling list_vpn_meshes request");
+
+    let meshes = service.list(&claims).await?;
+
+    Ok(HttpResponse::Ok().json(meshes))
+}
+
+/// Get a VPN mesh with its members and tunnels
+///
+/// GET /api/vpn-meshes/{id}
+pub async fn get_vpn_mesh(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<VpnMeshService>,
+) -> AppResult<HttpResponse> {
+    let mesh_id = path.into_inner();
+    debug!("Handling get_vpn_mesh request for {}", mesh_id);
+
+    let mesh = service.get(mesh_id, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(mesh))
+}
+
+/// Preview the configuration a VPN mesh would push to each member
+///
+/// GET /api/vpn-meshes/{id}/plan
+pub async fn get_vpn_mesh_plan(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<VpnMeshService>,
+) -> AppResult<HttpResponse> {
+    let mesh_id = path.into_inner();
+    debug!("Handling get_vpn_mesh_plan request for {}", mesh_id);
+
+    let plan = service.plan(mesh_id, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(plan))
+}
+
+/// Push a VPN mesh to all of its members
+///
+/// POST /api/vpn-meshes/{id}/apply
+///
+/// If any member fails, the members already configured are rolled back.
+pub async fn apply_vpn_mesh(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<VpnMeshService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let mesh_id = path.into_inner();
+    info!("Handling apply_vpn_mesh request for {}", mesh_id);
+
+    let result = service.push(mesh_id, &claims).await;
+    let event = match &result {
+        Ok(_) => AuditEvent::new(Some(&claims), "vpn_mesh.push", AuditResult::Success),
+        Err(e) => AuditEvent::new(Some(&claims), "vpn_mesh.push", AuditResult::Failure)
+            .with_details(serde_json::json!({ "error": e.to_string() })),
+    };
+    audit_service.record(event.with_target(mesh_id.to_string())).await;
+
+    Ok(HttpResponse::Ok().json(result?))
+}
+
+/// Check the tunnels of a VPN mesh now
+///
+/// POST /api/vpn-meshes/{id}/check
+pub async fn check_vpn_mesh(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<VpnMeshService>,
+) -> AppResult<HttpResponse> {
+    let mesh_id = path.into_inner();
+    debug!("Handling check_vpn_mesh request for {}", mesh_id);
+
+    let mesh = service.check(mesh_id, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(mesh))
+}
+
+/// Remove a VPN mesh from its members and delete it
+///
+/// DELETE /api/vpn-meshes/{id}
+pub async fn delete_vpn_mesh(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<VpnMeshService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let mesh_id = path.into_inner();
+    info!("Handling delete_vpn_mesh request for {}", mesh_id);
+
+    service.delete(mesh_id, &claims).await?;
+    audit_service
+        .record(AuditEvent::new(Some(&claims), "vpn_mesh.delete", AuditResult::Success).with_target(mesh_id.to_string()))
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,144 @@
+//! Setup Wizard Handlers Module
+//!
+//! This module contains HTTP request handlers for setup wizards, which
+//! collect the answers to a multi-step setup flow spanning two nodes and
+//! apply the resulting configuration to both at the end.
+
+use actix_web::{web, HttpResponse};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::wizard::{StartWizardRequest, WizardListQuery};
+use crate::services::{AuditService, WizardService};
+
+/// Start a setup wizard
+///
+/// POST /api/wizards
+pub async fn start_wizard(
+    claims: Claims,
+    req: web::Json<StartWizardRequest>,
+    service: web::Data<WizardService>,
+) -> AppResult<HttpResponse> {
+    info!("Handling start_wizard request for {}", req.kind.as_str());
+
+    let wizard = service.start(req.kind, &claims).await?;
+
+    Ok(HttpResponse::Created().json(wizard))
+}
+
+/// List setup wizards
+///
+/// GET /api/wizards
+///
+/// Lists the caller's open wizards, or everyone's for administrators;
+/// `all=true` includes closed wizards too.
+pub async fn list_wizards(
+    claims: Claims,
+    query: web::Query<WizardListQuery>,
+    service: web::Data<WizardService>,
+) -> AppResult<HttpResponse> {
+    debug!("Handling list_wizards request");
+
+    let wizards = service.list(&query, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(wizards))
+}
+
+/// Get a setup wizard with its answers so far
+///
+/// GET /api/wizards/{id}
+pub async fn get_wizard(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<WizardService>,
+) -> AppResult<HttpResponse> {
+    let wizard_id = path.into_inner();
+    debug!("Handling get_wizard request for {}", wizard_id);
+
+    let wizard = service.get(wizard_id, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(wizard))
+}
+
+/// Answer a step of a setup wizard
+///
+/// PUT /api/wizards/{id}/steps/{step}
+///
+/// Steps are answered in order; answering a step again replaces its
+/// answers.
+pub async fn answer_wizard_step(
+    claims: Claims,
+    path: web::Path<(Uuid, String)>,
+    req: web::Json<serde_json::Value>,
+    service: web::Data<WizardService>,
+) -> AppResult<HttpResponse> {
+    let (wizard_id, step) = path.into_inner();
+    info!("Handling answer_wizard_step request for step {} of {}", step, wizard_id);
+
+    let wizard = service.answer(wizard_id, &step, req.into_inner(), &claims).await?;
+
+    Ok(HttpResponse::Ok().json(wizard))
+}
+
+/// Preview the configuration a setup wizard would apply to each node
+///
+/// GET /api/wizards/{id}/plan
+pub async fn get_wizard_plan(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<WizardService>,
+) -> AppResult<HttpResponse> {
+    let wizard_id = path.into_inner();
+    debug!("Handling get_wizard_plan request for {}", wizard_id);
+
+    let plan = service.plan(wizard_id, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(plan))
+}
+
+/// Apply a completed setup wizard to all of its nodes
+///
+/// POST /api/wizards/{id}/apply
+///
+/// If any node fails, the nodes already configured are rolled back and the
+/// wizard stays open with the error.
+pub async fn apply_wizard(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<WizardService>,
+    audit_service: web::Data<AuditService>,
+) -> AppResult<HttpResponse> {
+    let wizard_id = path.into_inner();
+    info!("Handling apply_wizard request for {}", wizard_id);
+
+    let kind = service.get(wizard_id, &claims).await?.kind;
+    let result = service.apply(wizard_id, &claims).await;
+    let event = match &result {
+        Ok(_) => AuditEvent::new(Some(&claims), "wizard.apply", AuditResult::Success)
+            .with_details(serde_json::json!({ "kind": kind.as_str() })),
+        Err(e) => AuditEvent::new(Some(&claims), "wizard.apply", AuditResult::Failure)
+            .with_details(serde_json::json!({ "kind": kind.as_str(), "error": e.to_string() })),
+    };
+    audit_service.record(event.with_target(wizard_id.to_string())).await;
+
+    Ok(HttpResponse::Ok().json(result?))
+}
+
+/// Close a setup wizard without applying it
+///
+/// DELETE /api/wizards/{id}
+pub async fn discard_wizard(
+    claims: Claims,
+    path: web::Path<Uuid>,
+    service: web::Data<WizardService>,
+) -> AppResult<HttpResponse> {
+    let wizard_id = path.into_inner();
+    info!("Handling discard_wizard request for {}", wizard_id);
+
+    let wizard = service.discard(wizard_id, &claims).await?;
+
+    Ok(HttpResponse::Ok().json(wizard))
+}
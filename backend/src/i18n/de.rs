@@ -0,0 +1,64 @@
+//! German message catalog
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    // Error categories
+    ("error.config", "Konfigurationsfehler: {detail}"),
+    ("error.database", "Datenbankfehler: {detail}"),
+    ("error.auth", "Authentifizierungsfehler: {detail}"),
+    ("error.forbidden", "Berechtigungsfehler: {detail}"),
+    ("error.validation", "Validierungsfehler: {detail}"),
+    ("error.not_found", "Nicht gefunden: {detail}"),
+    ("error.internal", "Interner Fehler: {detail}"),
+    ("error.external_api", "Fehler der externen API: {detail}"),
+    ("error.jwt", "JWT-Fehler: {detail}"),
+    ("error.http_client", "HTTP-Client-Fehler: {detail}"),
+    ("error.quota_exceeded", "Kontingent überschritten: {detail}"),
+    ("error.unsupported", "Nicht unterstützt: {detail}"),
+    ("error.rate_limited", "Zu viele Anfragen: {detail}"),
+    ("error.payload_too_large", "Nutzlast zu groß: {detail}"),
+    ("error.gone", "Nicht mehr verfügbar: {detail}"),
+    // Field validation
+    ("validation.field", "{field}: {message}"),
+    ("validation.length_between", "muss zwischen {min} und {max} Zeichen lang sein"),
+    ("validation.length_min", "muss mindestens {min} Zeichen lang sein"),
+    ("validation.length_max", "darf höchstens {max} Zeichen lang sein"),
+    ("validation.length_equal", "muss genau {equal} Zeichen lang sein"),
+    ("validation.email", "muss eine gültige E-Mail-Adresse sein"),
+    ("validation.url", "muss eine gültige URL sein"),
+    ("validation.range", "liegt außerhalb des zulässigen Bereichs"),
+    ("validation.timezone", "muss ein gültiger IANA-Zeitzonenname sein (z. B. Europe/Berlin)"),
+    ("validation.required", "ist erforderlich"),
+    ("validation.invalid", "ist ungültig"),
+    // Request validation
+    ("validation.username_too_short", "Der Benutzername muss mindestens {min} Zeichen lang sein"),
+    ("validation.username_too_long", "Der Benutzername darf höchstens {max} Zeichen lang sein"),
+    ("validation.invalid_email", "Ungültige E-Mail-Adresse"),
+    ("validation.password_too_short", "Das Passwort muss mindestens {min} Zeichen lang sein"),
+    ("validation.username_exists", "Der Benutzername ist bereits vergeben"),
+    ("validation.email_exists", "Die E-Mail-Adresse ist bereits vergeben"),
+    ("validation.command_required", "Ein Befehl ist erforderlich"),
+    ("validation.url_required_for_add", "Für das Hinzufügen ist eine URL erforderlich"),
+    ("validation.name_required_for_delete", "Für das Löschen ist ein Name erforderlich"),
+    ("validation.name_required_for_set_default", "Für das Festlegen als Standard ist ein Name erforderlich"),
+    ("validation.cannot_delete_self", "Das eigene Konto kann nicht gelöscht werden"),
+    ("validation.invalid_user_id", "Ungültige Benutzer-ID: {error}"),
+    (
+        "validation.team_alias_format",
+        "Der Team-Alias muss mit einem Kleinbuchstaben beginnen und darf nur Kleinbuchstaben, Ziffern und Bindestriche enthalten",
+    ),
+    ("validation.team_alias_in_use", "Der Team-Alias '{alias}' wird bereits verwendet"),
+    // Operation status
+    ("operation.reboot_initiated", "Neustart des Systems wurde eingeleitet"),
+    ("operation.reboot_failed", "Neustart konnte nicht eingeleitet werden: {error}"),
+    ("operation.poweroff_initiated", "Herunterfahren des Systems wurde eingeleitet"),
+    ("operation.poweroff_failed", "Herunterfahren konnte nicht eingeleitet werden: {error}"),
+    ("operation.reset_not_confirmed", "Zurücksetzen der Konfiguration nicht bestätigt. Setzen Sie confirmed=true, um fortzufahren."),
+    ("operation.reset_completed", "Zurücksetzen der Konfiguration ({reset_type}) erfolgreich abgeschlossen"),
+    ("operation.reset_failed", "Konfiguration konnte nicht zurückgesetzt werden: {error}"),
+    ("operation.image_add_initiated", "Download und Installation des Images wurden gestartet"),
+    ("operation.image_add_failed", "Image konnte nicht hinzugefügt werden: {error}"),
+    ("operation.image_deleted", "Image '{name}' wurde gelöscht"),
+    ("operation.image_delete_failed", "Image konnte nicht gelöscht werden: {error}"),
+    ("operation.image_default_set", "Standard-Boot-Image wurde auf '{name}' gesetzt"),
+    ("operation.image_default_failed", "Standard-Image konnte nicht gesetzt werden: {error}"),
+];
@@ -0,0 +1,64 @@
+//! English message catalog (fallback for all other locales)
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    // Error categories
+    ("error.config", "Configuration error: {detail}"),
+    ("error.database", "Database error: {detail}"),
+    ("error.auth", "Authentication error: {detail}"),
+    ("error.forbidden", "Authorization error: {detail}"),
+    ("error.validation", "Validation error: {detail}"),
+    ("error.not_found", "Not found: {detail}"),
+    ("error.internal", "Internal error: {detail}"),
+    ("error.external_api", "External API error: {detail}"),
+    ("error.jwt", "JWT error: {detail}"),
+    ("error.http_client", "HTTP client error: {detail}"),
+    ("error.quota_exceeded", "Quota exceeded: {detail}"),
+    ("error.unsupported", "Not supported: {detail}"),
+    ("error.rate_limited", "Too many requests: {detail}"),
+    ("error.payload_too_large", "Payload too large: {detail}"),
+    ("error.gone", "Gone: {detail}"),
+    // Field validation
+    ("validation.field", "{field}: {message}"),
+    ("validation.length_between", "must be between {min} and {max} characters"),
+    ("validation.length_min", "must be at least {min} characters"),
+    ("validation.length_max", "must be at most {max} characters"),
+    ("validation.length_equal", "must be exactly {equal} characters"),
+    ("validation.email", "must be a valid email address"),
+    ("validation.url", "must be a valid URL"),
+    ("validation.range", "is out of range"),
+    ("validation.timezone", "must be a valid IANA timezone name (e.g. Europe/Berlin)"),
+    ("validation.required", "is required"),
+    ("validation.invalid", "is invalid"),
+    // Request validation
+    ("validation.username_too_short", "Username must be at least {min} characters long"),
+    ("validation.username_too_long", "Username must be at most {max} characters long"),
+    ("validation.invalid_email", "Invalid email address"),
+    ("validation.password_too_short", "Password must be at least {min} characters long"),
+    ("validation.username_exists", "Username already exists"),
+    ("validation.email_exists", "Email already exists"),
+    ("validation.command_required", "Command is required"),
+    ("validation.url_required_for_add", "URL is required for add operation"),
+    ("validation.name_required_for_delete", "Name is required for delete operation"),
+    ("validation.name_required_for_set_default", "Name is required for set-default operation"),
+    ("validation.cannot_delete_self", "Cannot delete your own account"),
+    ("validation.invalid_user_id", "Invalid user ID: {error}"),
+    (
+        "validation.team_alias_format",
+        "Team alias must start with a lowercase letter and contain only lowercase letters, digits, and hyphens",
+    ),
+    ("validation.team_alias_in_use", "Team alias '{alias}' is already in use"),
+    // Operation status
+    ("operation.reboot_initiated", "System reboot initiated successfully"),
+    ("operation.reboot_failed", "Failed to initiate reboot: {error}"),
+    ("operation.poweroff_initiated", "System poweroff initiated successfully"),
+    ("operation.poweroff_failed", "Failed to initiate poweroff: {error}"),
+    ("operation.reset_not_confirmed", "Configuration reset not confirmed. Set confirmed=true to proceed."),
+    ("operation.reset_completed", "Configuration reset ({reset_type}) completed successfully"),
+    ("operation.reset_failed", "Failed to reset configuration: {error}"),
+    ("operation.image_add_initiated", "Image download and installation initiated"),
+    ("operation.image_add_failed", "Failed to add image: {error}"),
+    ("operation.image_deleted", "Image '{name}' deleted successfully"),
+    ("operation.image_delete_failed", "Failed to delete image: {error}"),
+    ("operation.image_default_set", "Default boot image set to '{name}' successfully"),
+    ("operation.image_default_failed", "Failed to set default image: {error}"),
+];
@@ -0,0 +1,64 @@
+//! Japanese message catalog
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    // Error categories
+    ("error.config", "設定エラー: {detail}"),
+    ("error.database", "データベースエラー: {detail}"),
+    ("error.auth", "認証エラー: {detail}"),
+    ("error.forbidden", "権限エラー: {detail}"),
+    ("error.validation", "入力エラー: {detail}"),
+    ("error.not_found", "見つかりません: {detail}"),
+    ("error.internal", "内部エラー: {detail}"),
+    ("error.external_api", "外部APIエラー: {detail}"),
+    ("error.jwt", "JWTエラー: {detail}"),
+    ("error.http_client", "HTTPクライアントエラー: {detail}"),
+    ("error.quota_exceeded", "クォータ超過: {detail}"),
+    ("error.unsupported", "サポートされていません: {detail}"),
+    ("error.rate_limited", "リクエストが多すぎます: {detail}"),
+    ("error.payload_too_large", "ペイロードが大きすぎます: {detail}"),
+    ("error.gone", "提供を終了しました: {detail}"),
+    // Field validation
+    ("validation.field", "{field}: {message}"),
+    ("validation.length_between", "{min}〜{max}文字で入力してください"),
+    ("validation.length_min", "{min}文字以上で入力してください"),
+    ("validation.length_max", "{max}文字以内で入力してください"),
+    ("validation.length_equal", "{equal}文字で入力してください"),
+    ("validation.email", "有効なメールアドレスを入力してください"),
+    ("validation.url", "有効なURLを入力してください"),
+    ("validation.range", "範囲外の値です"),
+    ("validation.timezone", "有効なIANAタイムゾーン名を入力してください (例: Asia/Tokyo)"),
+    ("validation.required", "必須項目です"),
+    ("validation.invalid", "無効な値です"),
+    // Request validation
+    ("validation.username_too_short", "ユーザー名は{min}文字以上にしてください"),
+    ("validation.username_too_long", "ユーザー名は{max}文字以内にしてください"),
+    ("validation.invalid_email", "メールアドレスが無効です"),
+    ("validation.password_too_short", "パスワードは{min}文字以上にしてください"),
+    ("validation.username_exists", "このユーザー名は既に使用されています"),
+    ("validation.email_exists", "このメールアドレスは既に使用されています"),
+    ("validation.command_required", "コマンドは必須です"),
+    ("validation.url_required_for_add", "追加操作にはURLが必要です"),
+    ("validation.name_required_for_delete", "削除操作には名前が必要です"),
+    ("validation.name_required_for_set_default", "デフォルト設定操作には名前が必要です"),
+    ("validation.cannot_delete_self", "自分自身のアカウントは削除できません"),
+    ("validation.invalid_user_id", "ユーザーIDが無効です: {error}"),
+    (
+        "validation.team_alias_format",
+        "チームエイリアスは英小文字で始め、英小文字・数字・ハイフンのみを使用してください",
+    ),
+    ("validation.team_alias_in_use", "チームエイリアス '{alias}' は既に使用されています"),
+    // Operation status
+    ("operation.reboot_initiated", "システムの再起動を開始しました"),
+    ("operation.reboot_failed", "再起動を開始できませんでした: {error}"),
+    ("operation.poweroff_initiated", "システムの電源オフを開始しました"),
+    ("operation.poweroff_failed", "電源オフを開始できませんでした: {error}"),
+    ("operation.reset_not_confirmed", "設定のリセットが確認されていません。confirmed=true を指定してください。"),
+    ("operation.reset_completed", "設定のリセット ({reset_type}) が完了しました"),
+    ("operation.reset_failed", "設定をリセットできませんでした: {error}"),
+    ("operation.image_add_initiated", "イメージのダウンロードとインストールを開始しました"),
+    ("operation.image_add_failed", "イメージを追加できませんでした: {error}"),
+    ("operation.image_deleted", "イメージ '{name}' を削除しました"),
+    ("operation.image_delete_failed", "イメージを削除できませんでした: {error}"),
+    ("operation.image_default_set", "デフォルトの起動イメージを '{name}' に設定しました"),
+    ("operation.image_default_failed", "デフォルトイメージを設定できませんでした: {error}"),
+];
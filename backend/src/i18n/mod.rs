@@ -0,0 +1,241 @@
+//! Localization of API messages
+//!
+//! Messages returned to clients are looked up by key in per-locale catalogs.
+//! The locale of a request is negotiated from its `Accept-Language` header by
+//! [`LocaleMiddleware`](crate::middleware::LocaleMiddleware) and made available
+//! to services and handlers through [`current_locale`], so message lookups do
+//! not need the request to be threaded through every call.
+
+mod de;
+mod en;
+mod ja;
+
+use std::fmt;
+use std::future::Future;
+
+use validator::{ValidationError, ValidationErrors};
+
+/// Supported response locales
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+    De,
+}
+
+impl Locale {
+    /// All supported locales
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Ja, Locale::De];
+
+    /// BCP 47 language tag of the locale
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+            Locale::De => "de",
+        }
+    }
+
+    /// Match a language tag (e.g. `de-AT`) against the supported locales
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next()?.trim().to_ascii_lowercase();
+        Locale::ALL.into_iter().find(|l| l.as_str() == primary)
+    }
+
+    /// Pick the best supported locale from an `Accept-Language` header value
+    ///
+    /// Languages are tried in order of their quality value; ties keep header
+    /// order. Falls back to English when nothing matches.
+    pub fn negotiate(accept_language: &str) -> Self {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .find_map(|(tag, _)| Locale::from_tag(tag))
+            .unwrap_or_default()
+    }
+
+    /// Message catalog of the locale
+    fn messages(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => en::MESSAGES,
+            Locale::Ja => ja::MESSAGES,
+            Locale::De => de::MESSAGES,
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_LOCALE: Locale;
+}
+
+/// Locale of the request being handled (English outside of a request)
+pub fn current_locale() -> Locale {
+    CURRENT_LOCALE.try_with(|l| *l).unwrap_or_default()
+}
+
+/// Run a future with the given locale as the current locale
+pub async fn with_locale<F: Future>(locale: Locale, future: F) -> F::Output {
+    CURRENT_LOCALE.scope(locale, future).await
+}
+
+/// Translate a message key into the current locale
+pub fn t(key: &str) -> String {
+    translate(current_locale(), key, &[])
+}
+
+/// Translate a message key into the current locale, filling `{name}` placeholders
+pub fn t_args(key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    translate(current_locale(), key, args)
+}
+
+/// Translate a message key into a specific locale
+///
+/// Keys missing from the locale's catalog fall back to English, and unknown
+/// keys are returned unchanged.
+pub fn translate(locale: Locale, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let template = lookup(locale, key)
+        .or_else(|| lookup(Locale::En, key))
+        .unwrap_or(key);
+
+    args.iter().fold(template.to_string(), |message, (name, value)| {
+        message.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+/// Render validator errors as a localized, field-by-field message
+pub fn validation_message(errors: &ValidationErrors) -> String {
+    let mut fields: Vec<_> = errors.field_errors().into_iter().collect();
+    fields.sort_by_key(|(field, _)| *field);
+
+    fields
+        .into_iter()
+        .flat_map(|(field, errors)| {
+            errors.iter().map(move |error| {
+                t_args(
+                    "validation.field",
+                    &[("field", &field), ("message", &field_error_message(error))],
+                )
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Localize a single field validation error
+fn field_error_message(error: &ValidationError) -> String {
+    let param = |name: &str| error.params.get(name).map(|v| v.to_string());
+
+    match error.code.as_ref() {
+        "length" => match (param("min"), param("max"), param("equal")) {
+            (_, _, Some(equal)) => t_args("validation.length_equal", &[("equal", &equal)]),
+            (Some(min), Some(max), _) => {
+                t_args("validation.length_between", &[("min", &min), ("max", &max)])
+            }
+            (Some(min), None, _) => t_args("validation.length_min", &[("min", &min)]),
+            (None, Some(max), _) => t_args("validation.length_max", &[("max", &max)]),
+            (None, None, _) => t("validation.invalid"),
+        },
+        "email" => t("validation.email"),
+        "url" => t("validation.url"),
+        "range" => t("validation.range"),
+        "required" => t("validation.required"),
+        "timezone" => t("validation.timezone"),
+        _ => t("validation.invalid"),
+    }
+}
+
+/// Find a key in a locale's catalog
+fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    locale
+        .messages()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, message)| *message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validator::Validate;
+
+    #[test]
+    fn test_negotiate_locale() {
+        assert_eq!(Locale::negotiate("ja-JP,ja;q=0.9,en;q=0.8"), Locale::Ja);
+        assert_eq!(Locale::negotiate("fr-FR, de;q=0.7, en;q=0.5"), Locale::De);
+        assert_eq!(Locale::negotiate("en;q=0.3, de-AT;q=0.8"), Locale::De);
+        assert_eq!(Locale::negotiate("de;q=0, ja"), Locale::Ja);
+        assert_eq!(Locale::negotiate("fr, *"), Locale::En);
+        assert_eq!(Locale::negotiate(""), Locale::En);
+    }
+
+    #[test]
+    fn test_catalogs_are_complete() {
+        for locale in Locale::ALL {
+            for (key, _) in en::MESSAGES {
+                assert!(lookup(locale, key).is_some(), "{} is missing {}", locale, key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_translate_with_fallback() {
+        assert_eq!(
+            translate(Locale::De, "operation.image_deleted", &[("name", &"1.4")]),
+            "Image '1.4' wurde gelöscht"
+        );
+        assert_eq!(translate(Locale::Ja, "no.such.key", &[]), "no.such.key");
+    }
+
+    #[tokio::test]
+    async fn test_current_locale_scope() {
+        assert_eq!(current_locale(), Locale::En);
+        let message = with_locale(Locale::Ja, async { t("validation.command_required") }).await;
+        assert_eq!(message, "コマンドは必須です");
+    }
+
+    #[derive(Validate)]
+    struct Sample {
+        #[validate(length(min = 3, max = 50))]
+        username: String,
+        #[validate(email)]
+        email: String,
+    }
+
+    #[tokio::test]
+    async fn test_validation_message() {
+        let errors = Sample {
+            username: "ab".to_string(),
+            email: "not-an-email".to_string(),
+        }
+        .validate()
+        .unwrap_err();
+
+        assert_eq!(
+            validation_message(&errors),
+            "email: must be a valid email address; username: must be between 3 and 50 characters"
+        );
+
+        let message = with_locale(Locale::De, async { validation_message(&errors) }).await;
+        assert!(message.starts_with("email: muss eine gültige E-Mail-Adresse sein"));
+    }
+}
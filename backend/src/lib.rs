@@ -0,0 +1,22 @@
+//! VyOS Web UI Backend
+//!
+//! Library crate behind the `vyos-web-ui-backend` server binary. The
+//! application is assembled by [`app::AppState`], which integration tests use
+//! to drive the real handlers and services.
+
+// The support bundle's configuration summary is a large `json!` literal
+#![recursion_limit = "256"]
+
+pub mod app;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod handlers;
+pub mod i18n;
+pub mod middleware;
+pub mod models;
+pub mod seed;
+pub mod services;
+pub mod utils;
+pub mod vyos_client;
+pub mod websocket;
@@ -1,139 +1,261 @@
-mod config;
-mod db;
-mod error;
-mod handlers;
-mod middleware;
-mod models;
-mod services;
-mod websocket;
-
 use actix_cors::Cors;
-use actix_web::{web, App, HttpServer, middleware::Logger};
-use std::env;
+use actix_web::{App, HttpServer, http::KeepAlive, middleware::Logger};
+use tokio::sync::watch;
 use tracing::info;
 
-use config::{AppConfig, init_database, init_logging};
-use db::{Database, create_database};
-use error::AppResult;
-use services::{AuthService, ConfigService, MonitoringService, SystemService, UserService};
-use websocket::ConnectionManager;
+use vyos_web_ui_backend::app::AppState;
+use vyos_web_ui_backend::config::{AppConfig, ConfigReloader, SecretResolver, init_database, init_logging};
+use vyos_web_ui_backend::db::{create_database, open_database};
+use vyos_web_ui_backend::error::{AppError, AppResult};
+use vyos_web_ui_backend::middleware::locale::LocaleMiddleware;
+use vyos_web_ui_backend::seed;
+use vyos_web_ui_backend::models::event::EVENT_RETENTION;
+use vyos_web_ui_backend::models::firewall_log::FIREWALL_LOG_RETENTION;
+use vyos_web_ui_backend::services::{BackendHealthService, CertificateService, ConfigDriftService, ConfigService, ConfigSessionService, EventBus, FirewallLogService, HaService, IncidentService, LeaderElection, MetricsCollector, MonitoringService, NodeHealthChecker, NotificationService, QuotaService, ReportService, SyslogReceiver, VpnMeshService, BACKEND_HEALTH_CHECK_INTERVAL, CERTIFICATE_CHECK_INTERVAL, CONFIG_DRIFT_CHECK_INTERVAL, HA_CHECK_INTERVAL, VPN_MESH_CHECK_INTERVAL};
+
+/// Command line usage
+const USAGE: &str =
+    "Usage: vyos-web-ui-backend [serve | seed [--serve] | migrate] [--migrate | --skip-migrations]";
+
+/// Subcommand selected on the command line
+#[derive(Debug, PartialEq)]
+enum Command {
+    /// Run the HTTP server
+    Serve,
+    /// Populate the database with sample data, optionally serving afterwards
+    Seed { serve: bool },
+    /// Apply pending database migrations and exit
+    Migrate,
+}
+
+/// Parsed command line
+#[derive(Debug, PartialEq)]
+struct Cli {
+    command: Command,
+    /// Whether to migrate the database at startup, overriding
+    /// `DATABASE_AUTO_MIGRATE`
+    migrate: Option<bool>,
+}
+
+impl Cli {
+    /// Parse the arguments following the program name
+    fn parse<I: IntoIterator<Item = String>>(args: I) -> AppResult<Self> {
+        let args: Vec<String> = args.into_iter().collect();
+        let mut migrate = None;
+        let mut command_args = Vec::new();
+        for arg in &args {
+            match arg.as_str() {
+                "--migrate" => migrate = Some(true),
+                "--skip-migrations" => migrate = Some(false),
+                arg => command_args.push(arg),
+            }
+        }
+
+        let command = match command_args.as_slice() {
+            [] | ["serve"] => Command::Serve,
+            ["seed"] => Command::Seed { serve: false },
+            ["seed", "--serve"] => Command::Seed { serve: true },
+            ["migrate"] if migrate != Some(false) => Command::Migrate,
+            _ => return Err(AppError::Config(format!("Invalid arguments {:?}. {}", args, USAGE))),
+        };
+        Ok(Self { command, migrate })
+    }
+}
 
 #[actix_web::main]
 async fn main() -> AppResult<()> {
+    let cli = Cli::parse(std::env::args().skip(1))?;
+
     // Load configuration
-    let config = AppConfig::from_env()?;
+    let mut config = AppConfig::from_env()?;
 
     // Initialize logging
     init_logging(&config);
 
+    // Load secrets kept in secret stores
+    config.resolve_secrets(&SecretResolver::from_env()?).await?;
+
     info!("Starting VyOS Web UI Backend");
     info!("Environment: {}", config.app_env);
-    info!("Server: {}", config.server_address());
+    info!("Server: {} ({} workers)", config.server_address(), config.server_workers);
 
     // Initialize database
     let pool = init_database(&config).await?;
-    let db = create_database(pool).await?;
+    let migrate = cli.command == Command::Migrate || cli.migrate.unwrap_or(config.database_auto_migrate);
+    let db = if migrate {
+        create_database(pool).await?
+    } else {
+        open_database(pool).await?
+    };
+    if cli.command == Command::Migrate {
+        return Ok(());
+    }
 
     // Create services
-    let db_clone = db.get_ref().clone();
-    let auth_service = AuthService::new(&config, db_clone.clone());
-    let user_service = UserService::new(db_clone.clone());
-    let config_service = ConfigService::new(db_clone.clone(), config.clone());
-    let system_service = SystemService::new(config.clone());
-    let monitoring_service = MonitoringService::new(config.clone());
+    let state = AppState::new(config.clone(), db);
+
+    if let Command::Seed { serve } = cli.command {
+        seed::seed(&state).await?;
+        if !serve {
+            return Ok(());
+        }
+    }
+
+    // Elect the instance that runs the background jobs below
+    let leader_election = state.leader_election.clone();
+    tokio::spawn(leader_election.clone().run());
+
+    // Enforce per-team metric retention
+    spawn_retention_task(
+        state.quota_service.clone(),
+        state.monitoring_service.clone(),
+        state.backend_health_service.clone(),
+        leader_election.clone(),
+    );
+
+    // Drop persisted events that are too old to replay
+    spawn_event_retention_task(state.event_bus.clone(), state.backend_health_service.clone(), leader_election.clone());
+
+    // Reload the configuration on SIGHUP
+    spawn_reload_task(state.config_reloader.clone());
+
+    // Group new alerts into incidents
+    spawn_incident_task(
+        state.incident_service.clone(),
+        state.config_reloader.clone(),
+        state.backend_health_service.clone(),
+        leader_election.clone(),
+    );
+
+    // Generate scheduled reports
+    spawn_report_task(state.report_service.clone(), state.backend_health_service.clone(), leader_election.clone());
+
+    // Commit configuration sessions scheduled for later
+    spawn_scheduled_commit_task(
+        state.config_session_service.clone(),
+        state.backend_health_service.clone(),
+        leader_election.clone(),
+    );
+
+    // Raise alerts about the backend itself
+    spawn_backend_health_task(state.backend_health_service.clone(), leader_election.clone());
+
+    // Poll the nodes and publish their status changes
+    if config.node_health_check_interval_secs > 0 {
+        spawn_node_health_task(
+            state.node_health_checker.clone(),
+            std::time::Duration::from_secs(config.node_health_check_interval_secs),
+            state.backend_health_service.clone(),
+            leader_election.clone(),
+        );
+    }
+
+    // Read the system metrics of every node
+    if config.metrics_collection_interval_secs > 0 {
+        spawn_metrics_collection_task(
+            state.metrics_collector.clone(),
+            std::time::Duration::from_secs(config.metrics_collection_interval_secs),
+            state.backend_health_service.clone(),
+            leader_election.clone(),
+        );
+    }
+
+    // Send alerts to the notification channels routed to
+    if config.notification_interval_secs > 0 {
+        spawn_notification_task(
+            state.notification_service.clone(),
+            std::time::Duration::from_secs(config.notification_interval_secs),
+            state.backend_health_service.clone(),
+            leader_election.clone(),
+        );
+    }
+
+    // Alert on certificates about to expire
+    spawn_certificate_task(
+        state.certificate_service.clone(),
+        state.backend_health_service.clone(),
+        leader_election.clone(),
+    );
+
+    // Check the tunnels of the pushed VPN meshes
+    spawn_vpn_mesh_task(
+        state.vpn_mesh_service.clone(),
+        state.backend_health_service.clone(),
+        leader_election.clone(),
+    );
+
+    // Record VRRP transitions and alert on unexpected ones
+    spawn_ha_task(state.ha_service.clone(), state.backend_health_service.clone(), leader_election.clone());
+    spawn_config_drift_task(
+        state.config_drift_service.clone(),
+        state.backend_health_service.clone(),
+        leader_election.clone(),
+    );
+
+    // Back up the configuration of every node
+    if config.config_backup_interval_secs > 0 {
+        spawn_config_backup_task(
+            state.config_service.clone(),
+            std::time::Duration::from_secs(config.config_backup_interval_secs),
+            state.backend_health_service.clone(),
+            leader_election.clone(),
+        );
+    }
+
+    // Roll back commits left unconfirmed, including any whose deadline passed
+    // while the backend was down
+    state.config_session_service.resume().await?;
 
-    // Create WebSocket connection manager
-    let connection_manager = ConnectionManager::new();
+    // Collect firewall logs sent by the nodes over syslog
+    if let Some(addr) = config.syslog_listen_addr {
+        let receiver =
+            SyslogReceiver::bind(addr, state.node_service.clone(), state.firewall_log_service.clone()).await?;
+        info!("Syslog receiver listening on {}", addr);
+        tokio::spawn(receiver.run());
+    }
+
+    // Drop firewall log entries past retention
+    spawn_firewall_log_retention_task(
+        state.firewall_log_service.clone(),
+        state.backend_health_service.clone(),
+        leader_election.clone(),
+    );
 
     // Build the HTTP server
     let bind_address = config.server_address();
+    let workers = config.server_workers;
+    let keep_alive = match config.server_keep_alive_secs {
+        0 => KeepAlive::Disabled,
+        secs => KeepAlive::Timeout(std::time::Duration::from_secs(secs)),
+    };
     let server = HttpServer::new(move || {
-        // Configure CORS
+        // Configure CORS; allowed origins follow configuration reloads
         let cors = if config.is_development() {
             Cors::permissive()
         } else {
+            let settings = state.config_reloader.subscribe();
             Cors::default()
-                .allowed_origin("https://your-domain.com")
+                .allowed_origin_fn(move |origin, _| {
+                    settings
+                        .borrow()
+                        .cors_allowed_origins
+                        .iter()
+                        .any(|allowed| origin.as_bytes() == allowed.as_bytes())
+                })
                 .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
-                .allowed_headers(vec!["Authorization", "Content-Type"])
+                .allowed_headers(vec!["Authorization", "Content-Type", "X-Request-Id", "traceparent"])
+                .expose_headers(vec!["X-Request-Id", "Retry-After"])
                 .max_age(3600)
         };
 
         App::new()
-            .app_data(web::Data::new(config.clone()))
-            .app_data(db.clone())
-            .app_data(web::Data::new(auth_service.clone()))
-            .app_data(web::Data::new(user_service.clone()))
-            .app_data(web::Data::new(config_service.clone()))
-            .app_data(web::Data::new(system_service.clone()))
-            .app_data(web::Data::new(monitoring_service.clone()))
-            .app_data(web::Data::new(connection_manager.clone()))
+            .configure(|cfg| state.configure(cfg))
+            .wrap(LocaleMiddleware)
             .wrap(cors)
             .wrap(Logger::default())
-            .service(
-                web::scope("/api")
-                    // Health check endpoints
-                    .route("/health", web::get().to(handlers::health::health_check))
-                    .route("/health/detailed", web::get().to(handlers::health::detailed_health_check))
-                    // Authentication endpoints
-                    .route("/auth/register", web::post().to(handlers::auth::register))
-                    .route("/auth/login", web::post().to(handlers::auth::login))
-                    .route("/auth/logout", web::post().to(handlers::auth::logout))
-                    .route("/auth/refresh", web::post().to(handlers::auth::refresh_token))
-                    .route("/auth/validate", web::post().to(handlers::auth::validate_token))
-                    .route("/auth/me", web::get().to(handlers::auth::get_current_user))
-                    // User endpoints
-                    .route("/users/me", web::get().to(handlers::user::get_profile))
-                    .route("/users/me", web::put().to(handlers::user::update_profile))
-                    .route("/users/me/password", web::post().to(handlers::user::change_password))
-                    .route("/users", web::get().to(handlers::user::list_users))
-                    .route("/users", web::post().to(handlers::user::create_user))
-                    .route("/users/{id}", web::put().to(handlers::user::update_user))
-                    .route("/users/{id}", web::delete().to(handlers::user::delete_user))
-                    // Configuration endpoints
-                    .route("/config/retrieve", web::post().to(handlers::config::retrieve_config))
-                    .route("/config/configure", web::post().to(handlers::config::set_config))
-                    .route("/config/delete", web::post().to(handlers::config::delete_config))
-                    .route("/config/generate", web::post().to(handlers::config::generate_config))
-                    .route("/config/history", web::get().to(handlers::config::get_history))
-                    .route("/config/history/{id}", web::get().to(handlers::config::get_history_entry))
-                    .route("/config/rollback", web::post().to(handlers::config::rollback_config))
-                    .route("/config/diff/{id1}/{id2}", web::get().to(handlers::config::diff_configs))
-                    .route("/config/search", web::post().to(handlers::config::search_config))
-                    .route("/config/bulk", web::post().to(handlers::config::bulk_config_change))
-                    .route("/config/validate", web::post().to(handlers::config::validate_config))
-                    .route("/config/value", web::post().to(handlers::config::get_config_value))
-                    .route("/config/subtree", web::post().to(handlers::config::get_config_subtree))
-                    .route("/config/compare", web::post().to(handlers::config::compare_configs))
-                    .route("/config/discard", web::post().to(handlers::config::discard_config))
-                    .route("/config/stats", web::get().to(handlers::config::get_config_stats))
-                    // System endpoints
-                    .route("/system/reboot", web::post().to(handlers::system::reboot))
-                    .route("/system/poweroff", web::post().to(handlers::system::poweroff))
-                    .route("/system/reset", web::post().to(handlers::system::reset_configuration))
-                    .route("/system/images", web::get().to(handlers::system::list_images))
-                    .route("/system/images", web::post().to(handlers::system::manage_images))
-                    .route("/system/images/add", web::post().to(handlers::system::add_image))
-                    .route("/system/images/delete", web::post().to(handlers::system::delete_image))
-                    .route("/system/images/set-default", web::post().to(handlers::system::set_default_image))
-                    .route("/system/show", web::post().to(handlers::system::execute_show_command))
-                    .route("/system/info", web::get().to(handlers::system::get_system_info))
-                    .route("/system/operations/{operation_id}", web::get().to(handlers::system::check_operation_status))
-                    .route("/system/health", web::get().to(handlers::system::system_health_check))
-                    // Monitoring endpoints
-                    .route("/monitoring/system", web::get().to(handlers::monitoring::get_system_metrics))
-                    .route("/monitoring/network", web::get().to(handlers::monitoring::get_network_statistics))
-                    .route("/monitoring/history", web::get().to(handlers::monitoring::get_history))
-                    .route("/monitoring/alerts", web::get().to(handlers::monitoring::get_alerts))
-                    .route("/monitoring/alerts", web::post().to(handlers::monitoring::create_alert))
-                    .route("/monitoring/alerts/{id}", web::put().to(handlers::monitoring::update_alert))
-                    .route("/monitoring/alerts/{id}", web::delete().to(handlers::monitoring::delete_alert))
-                    .route("/monitoring/alerts/rules", web::get().to(handlers::monitoring::get_alert_rules))
-                    .route("/monitoring/alerts/rules/{id}", web::get().to(handlers::monitoring::get_alert_rule))
-            )
-            .route("/ws", web::get().to(websocket::websocket_handler))
-            .route("/ws/info", web::get().to(websocket::ws_info))
     })
+    .workers(workers)
+    .keep_alive(keep_alive)
     .bind(&bind_address)?;
 
     info!("Server listening on {}", bind_address);
@@ -142,5 +264,395 @@ async fn main() -> AppResult<()> {
 
     info!("Server shutting down");
 
+    // Let another instance take over the background jobs right away
+    if let Err(e) = leader_election.release().await {
+        tracing::warn!("Failed to release the leader lease: {}", e);
+    }
+
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Wait for the next tick of a background job's interval while this
+/// instance is the leader
+///
+/// An instance that becomes the leader runs the job right away instead of
+/// waiting for the rest of the interval.
+async fn next_leader_tick(interval: &mut tokio::time::Interval, leadership: &mut watch::Receiver<bool>) {
+    loop {
+        if !*leadership.borrow_and_update() {
+            if leadership.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+            interval.reset_immediately();
+            continue;
+        }
+        tokio::select! {
+            _ = interval.tick() => return,
+            _ = leadership.changed() => {}
+        }
+    }
+}
+
+/// Periodically drop metrics that are older than their team's retention
+fn spawn_retention_task(
+    quota_service: QuotaService,
+    monitoring_service: MonitoringService,
+    health: BackendHealthService,
+    leader: LeaderElection,
+) {
+    tokio::spawn(async move {
+        let period = std::time::Duration::from_secs(3600);
+        let mut interval = tokio::time::interval(period);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("metric_retention", period);
+            match quota_service.metric_retention_cutoffs().await {
+                Ok(cutoffs) => {
+                    monitoring_service.prune_metrics_history(&cutoffs).await;
+                }
+                Err(e) => tracing::warn!("Failed to compute metric retention cutoffs: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically drop persisted events older than [`EVENT_RETENTION`]
+fn spawn_event_retention_task(event_bus: EventBus, health: BackendHealthService, leader: LeaderElection) {
+    tokio::spawn(async move {
+        let period = std::time::Duration::from_secs(3600);
+        let mut interval = tokio::time::interval(period);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("event_retention", period);
+            match event_bus.prune(chrono::Utc::now() - EVENT_RETENTION).await {
+                Ok(pruned) if pruned > 0 => info!("Pruned {} events past retention", pruned),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to prune events: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically drop firewall log entries older than [`FIREWALL_LOG_RETENTION`]
+fn spawn_firewall_log_retention_task(
+    firewall_log_service: FirewallLogService,
+    health: BackendHealthService,
+    leader: LeaderElection,
+) {
+    tokio::spawn(async move {
+        let period = std::time::Duration::from_secs(3600);
+        let mut interval = tokio::time::interval(period);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("firewall_log_retention", period);
+            match firewall_log_service.prune(chrono::Utc::now() - FIREWALL_LOG_RETENTION).await {
+                Ok(pruned) if pruned > 0 => info!("Pruned {} firewall log entries past retention", pruned),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to prune firewall log entries: {}", e),
+            }
+        }
+    });
+}
+
+/// Reload the configuration whenever the process receives SIGHUP
+#[cfg(unix)]
+fn spawn_reload_task(config_reloader: ConfigReloader) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async move {
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(hangups) => hangups,
+            Err(e) => {
+                tracing::warn!("Failed to listen for SIGHUP, configuration reloads are API only: {}", e);
+                return;
+            }
+        };
+        while hangups.recv().await.is_some() {
+            info!("Received SIGHUP, reloading configuration");
+            if let Err(e) = config_reloader.reload() {
+                tracing::warn!("Failed to reload configuration: {}", e);
+            }
+        }
+    });
+}
+
+/// Configuration is only reloaded through the API without SIGHUP
+#[cfg(not(unix))]
+fn spawn_reload_task(_config_reloader: ConfigReloader) {}
+
+/// Periodically group new alerts into incidents
+///
+/// The interval follows configuration reloads.
+fn spawn_incident_task(
+    incident_service: IncidentService,
+    config_reloader: ConfigReloader,
+    health: BackendHealthService,
+    leader: LeaderElection,
+) {
+    tokio::spawn(async move {
+        let mut settings = config_reloader.subscribe();
+        let mut leadership = leader.subscribe();
+        loop {
+            let period = std::time::Duration::from_secs(settings.borrow_and_update().incident_sync_interval_secs);
+            if *leadership.borrow_and_update() {
+                health.record_job_run("incident_sync", period);
+                if let Err(e) = incident_service.sync_alerts().await {
+                    tracing::warn!("Failed to group alerts into incidents: {}", e);
+                }
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(period) => {}
+                changed = settings.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                }
+                _ = leadership.changed() => {}
+            }
+        }
+    });
+}
+
+/// Periodically generate the reports of due schedules
+fn spawn_report_task(report_service: ReportService, health: BackendHealthService, leader: LeaderElection) {
+    tokio::spawn(async move {
+        let period = std::time::Duration::from_secs(300);
+        let mut interval = tokio::time::interval(period);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("scheduled_reports", period);
+            if let Err(e) = report_service.run_due_schedules().await {
+                tracing::warn!("Failed to generate scheduled reports: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically commit the configuration sessions whose scheduled time
+/// has come, holding them back during change freezes
+fn spawn_scheduled_commit_task(
+    config_session_service: ConfigSessionService,
+    health: BackendHealthService,
+    leader: LeaderElection,
+) {
+    tokio::spawn(async move {
+        let period = std::time::Duration::from_secs(30);
+        let mut interval = tokio::time::interval(period);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("scheduled_commits", period);
+            if let Err(e) = config_session_service.run_scheduled().await {
+                tracing::warn!("Failed to commit scheduled configuration sessions: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically check the backend's own health and alert on it
+fn spawn_backend_health_task(health: BackendHealthService, leader: LeaderElection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(BACKEND_HEALTH_CHECK_INTERVAL);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            if let Err(e) = health.check().await {
+                tracing::warn!("Failed to check the backend's health: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically check the health of every node
+fn spawn_node_health_task(
+    checker: NodeHealthChecker,
+    period: std::time::Duration,
+    health: BackendHealthService,
+    leader: LeaderElection,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        // A round over many slow nodes may outlast the period
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("node_health_check", period);
+            if let Err(e) = checker.check().await {
+                tracing::warn!("Failed to check the health of the nodes: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically collect the system metrics of every node
+fn spawn_metrics_collection_task(
+    collector: MetricsCollector,
+    period: std::time::Duration,
+    health: BackendHealthService,
+    leader: LeaderElection,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("metrics_collection", period);
+            if let Err(e) = collector.collect().await {
+                tracing::warn!("Failed to collect the metrics of the nodes: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically send new and resolved alerts to their notification channels
+fn spawn_notification_task(
+    notifications: NotificationService,
+    period: std::time::Duration,
+    health: BackendHealthService,
+    leader: LeaderElection,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("notification_dispatch", period);
+            if let Err(e) = notifications.dispatch().await {
+                tracing::warn!("Failed to dispatch alert notifications: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically check the monitored certificates for expiry
+fn spawn_certificate_task(certificates: CertificateService, health: BackendHealthService, leader: LeaderElection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CERTIFICATE_CHECK_INTERVAL);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("certificate_expiry", CERTIFICATE_CHECK_INTERVAL);
+            match certificates.check_all().await {
+                Ok(raised) if raised > 0 => info!("Raised {} certificate expiry alerts", raised),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to check certificates for expiry: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically check the tunnels of every pushed VPN mesh
+fn spawn_vpn_mesh_task(meshes: VpnMeshService, health: BackendHealthService, leader: LeaderElection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(VPN_MESH_CHECK_INTERVAL);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("vpn_mesh_health", VPN_MESH_CHECK_INTERVAL);
+            match meshes.check_all().await {
+                Ok(down) if down > 0 => tracing::warn!("{} VPN mesh tunnels are down", down),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to check VPN mesh tunnels: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically read the VRRP groups of every node
+fn spawn_ha_task(ha: HaService, health: BackendHealthService, leader: LeaderElection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(HA_CHECK_INTERVAL);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("ha_state", HA_CHECK_INTERVAL);
+            match ha.check_all().await {
+                Ok(unexpected) if unexpected > 0 => tracing::warn!("{} unexpected VRRP transitions", unexpected),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to check the VRRP groups of the nodes: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically compare the running configuration of every node with a
+/// baseline against it
+fn spawn_config_drift_task(drift: ConfigDriftService, health: BackendHealthService, leader: LeaderElection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CONFIG_DRIFT_CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("config_drift", CONFIG_DRIFT_CHECK_INTERVAL);
+            match drift.check_all().await {
+                Ok(drifted) if drifted > 0 => {
+                    tracing::warn!("{} nodes drifted from their configuration baselines", drifted)
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to check the configuration drift of the nodes: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically back up the configuration of every node
+fn spawn_config_backup_task(
+    config_service: ConfigService,
+    period: std::time::Duration,
+    health: BackendHealthService,
+    leader: LeaderElection,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut leadership = leader.subscribe();
+        loop {
+            next_leader_tick(&mut interval, &mut leadership).await;
+            health.record_job_run("config_backup", period);
+            match config_service.backup_all_nodes().await {
+                Ok(stored) if stored > 0 => info!("Backed up the configuration of {} nodes", stored),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to back up node configurations: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> AppResult<Command> {
+        Cli::parse(args.iter().map(|a| a.to_string())).map(|cli| cli.command)
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse(&[]).unwrap(), Command::Serve);
+        assert_eq!(parse(&["serve"]).unwrap(), Command::Serve);
+        assert_eq!(parse(&["seed"]).unwrap(), Command::Seed { serve: false });
+        assert_eq!(parse(&["seed", "--serve"]).unwrap(), Command::Seed { serve: true });
+        assert_eq!(parse(&["migrate"]).unwrap(), Command::Migrate);
+        assert!(parse(&["seed", "--force"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_migration_flags() {
+        let cli = |args: &[&str]| Cli::parse(args.iter().map(|a| a.to_string()));
+        assert_eq!(cli(&[]).unwrap().migrate, None);
+        assert_eq!(cli(&["--skip-migrations"]).unwrap(), Cli { command: Command::Serve, migrate: Some(false) });
+        assert_eq!(
+            cli(&["seed", "--migrate", "--serve"]).unwrap(),
+            Cli { command: Command::Seed { serve: true }, migrate: Some(true) }
+        );
+        assert!(cli(&["migrate", "--skip-migrations"]).is_err());
+    }
+}
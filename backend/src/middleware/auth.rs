@@ -1,9 +1,12 @@
 use actix_web::{
+    body::EitherBody,
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
     error::ErrorUnauthorized,
-    web, Error, FromRequest, HttpMessage,
+    http::Method,
+    web, Error, FromRequest, HttpMessage, ResponseError,
 };
 use futures_util::future::LocalBoxFuture;
+use serde::Deserialize;
 use std::{
     cell::RefCell,
     future::{ready, Ready},
@@ -12,7 +15,7 @@ use std::{
 
 use crate::error::AppError;
 use crate::models::auth::Claims;
-use crate::services::AuthService;
+use crate::services::{is_api_token, ApiTokenService, AuthService};
 
 /// Extract claims from request extension
 /// This helper function is used by handlers to get the validated claims
@@ -44,6 +47,49 @@ fn validate_jwt_token_from_request(req: &ServiceRequest, token: &str) -> Result<
     auth_service.validate_token(token)
 }
 
+/// Query parameters read by the authentication of API tokens
+#[derive(Deserialize)]
+struct ApiTokenQuery {
+    token: Option<String>,
+    node_id: Option<String>,
+}
+
+/// Read-only API token of a request, sent as a bearer token or in the
+/// `token` query parameter so that it can be embedded in a URL
+fn api_token_from_request(req: &ServiceRequest) -> Option<String> {
+    let bearer = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .filter(|token| is_api_token(token))
+        .map(str::to_string);
+
+    bearer.or_else(|| {
+        web::Query::<ApiTokenQuery>::from_query(req.query_string())
+            .ok()
+            .and_then(|query| query.into_inner().token)
+            .filter(|token| is_api_token(token))
+    })
+}
+
+/// Authenticate a request with a read-only API token
+async fn authenticate_api_token(req: &ServiceRequest, token: &str) -> Result<Claims, AppError> {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return Err(AppError::Forbidden("API tokens are read-only".to_string()));
+    }
+
+    let api_token_service = req
+        .app_data::<web::Data<ApiTokenService>>()
+        .cloned()
+        .ok_or_else(|| AppError::Internal("API token service not available".to_string()))?;
+    let node_id = web::Query::<ApiTokenQuery>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.into_inner().node_id);
+
+    api_token_service.authenticate(token, req.path(), node_id.as_deref()).await
+}
+
 /// Authentication middleware factory
 pub struct AuthMiddleware;
 
@@ -119,7 +165,8 @@ where
 }
 
 /// Optional authentication middleware
-/// Allows requests without authentication but attaches claims if token is present
+/// Allows requests without authentication but attaches claims if token is present;
+/// read-only API tokens must be valid for the request
 pub struct OptionalAuthMiddleware;
 
 impl<S, B> Transform<S, ServiceRequest> for OptionalAuthMiddleware
@@ -128,7 +175,7 @@ where
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Transform = OptionalAuthMiddlewareService<S>;
     type InitError = ();
@@ -152,7 +199,7 @@ where
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
 
@@ -162,6 +209,21 @@ where
         let service = self.service.clone();
 
         Box::pin(async move {
+            // API tokens are checked against their scopes, and rejected
+            // rather than ignored, so a wallboard shows why it gets no data
+            if let Some(token) = api_token_from_request(&req) {
+                match authenticate_api_token(&req, &token).await {
+                    Ok(claims) => {
+                        req.extensions_mut().insert(claims);
+                        return Ok(service.call(req).await?.map_into_left_body());
+                    }
+                    Err(e) => {
+                        let response = e.error_response();
+                        return Ok(req.into_response(response).map_into_right_body());
+                    }
+                }
+            }
+
             // Try to extract and validate the token
             let auth_header = req
                 .headers()
@@ -180,7 +242,7 @@ where
             }
 
             // Continue regardless of authentication
-            service.call(req).await
+            Ok(service.call(req).await?.map_into_left_body())
         })
     }
 }
\ No newline at end of file
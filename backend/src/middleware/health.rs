@@ -0,0 +1,80 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error,
+};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use crate::error::AppError;
+use crate::services::BackendHealthService;
+
+/// Response tracking middleware factory
+///
+/// Counts every response, and among them server errors and failures of the
+/// database, into the [`BackendHealthService`] registered as app data.
+pub struct HealthTrackingMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for HealthTrackingMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = HealthTrackingMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(HealthTrackingMiddlewareService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+/// Response tracking middleware service
+pub struct HealthTrackingMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for HealthTrackingMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let health = req.app_data::<web::Data<BackendHealthService>>().cloned();
+
+        Box::pin(async move {
+            let res = service.call(req).await;
+            if let Some(health) = health {
+                let (server_error, db_error) = match &res {
+                    Ok(res) => (
+                        res.status().is_server_error(),
+                        res.response()
+                            .error()
+                            .is_some_and(|e| matches!(e.as_error::<AppError>(), Some(AppError::Database(_)))),
+                    ),
+                    Err(e) => (
+                        e.as_response_error().status_code().is_server_error(),
+                        matches!(e.as_error::<AppError>(), Some(AppError::Database(_))),
+                    ),
+                };
+                health.record_response(server_error, db_error);
+            }
+            res
+        })
+    }
+}
@@ -0,0 +1,213 @@
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{HeaderName, HeaderValue, USER_AGENT},
+        uri::PathAndQuery,
+        Uri,
+    },
+    web, Error, HttpMessage, ResponseError,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::utils::client_ip::client_ip;
+
+/// Unix time integer user IDs in URLs were deprecated at, 2026-10-17
+const LEGACY_USER_IDS_DEPRECATED_AT: i64 = 1_792_195_200;
+
+/// Largest integer user ID, the last 12 hex digits of the matching UUID
+const MAX_LEGACY_USER_ID: u64 = 0xffff_ffff_ffff;
+
+/// Dual-serving middleware for the legacy integer user IDs in URLs
+///
+/// Before user IDs became UUIDs they were integers, and migration 005 turned
+/// every one into the UUID the API already exposed for it. Until the
+/// configured sunset, `/api/users/{id}` URLs with an integer are served as
+/// the matching UUID, with `Deprecation`, `Sunset`, and a `Link` to the UUID
+/// form on the response, and each call is logged with its caller so they can
+/// be told to migrate. From the sunset day on, such URLs answer `410 Gone`.
+/// Must run after authentication so callers are logged by user.
+pub struct LegacyUserIdMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for LegacyUserIdMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = LegacyUserIdMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LegacyUserIdMiddlewareService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+/// Legacy user ID middleware service
+pub struct LegacyUserIdMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LegacyUserIdMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        let Some(path) = uuid_user_path(req.path()) else {
+            return Box::pin(async move { Ok(service.call(req).await?.map_into_left_body()) });
+        };
+
+        let sunset = req
+            .app_data::<web::Data<AppConfig>>()
+            .map(|config| config.legacy_user_ids_sunset)
+            .unwrap_or(NaiveDate::MAX);
+        let caller = req
+            .extensions()
+            .get::<Claims>()
+            .map(|claims| claims.username.clone())
+            .unwrap_or_else(|| "anonymous".to_string());
+        let ip = client_ip(req.request()).map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+        let user_agent = req.headers().get(USER_AGENT).and_then(|value| value.to_str().ok()).unwrap_or("-");
+        warn!(
+            "{} {} from {} ({}, {}) uses a legacy integer user ID; the URL is {}",
+            req.method(),
+            req.path(),
+            caller,
+            ip,
+            user_agent,
+            path
+        );
+
+        let headers = deprecation_headers(sunset, &path);
+        if Utc::now().date_naive() >= sunset {
+            let mut response = AppError::Gone(format!(
+                "Integer user IDs are no longer accepted since {}; use {}",
+                sunset, path
+            ))
+            .error_response();
+            for (name, value) in headers {
+                response.headers_mut().insert(name, value);
+            }
+            return Box::pin(ready(Ok(req.into_response(response).map_into_right_body())));
+        }
+
+        let path_and_query = match req.query_string() {
+            "" => path,
+            query => format!("{}?{}", path, query),
+        };
+        let mut parts = req.head().uri.clone().into_parts();
+        parts.path_and_query = PathAndQuery::try_from(path_and_query).ok();
+        if let Ok(uri) = Uri::from_parts(parts) {
+            req.match_info_mut().get_mut().update(&uri);
+            req.head_mut().uri = uri;
+        }
+
+        Box::pin(async move {
+            let mut response = service.call(req).await?;
+            for (name, value) in headers {
+                response.headers_mut().insert(name, value);
+            }
+            Ok(response.map_into_left_body())
+        })
+    }
+}
+
+/// UUID form of a user URL with a legacy integer ID
+fn uuid_user_path(path: &str) -> Option<String> {
+    let rest = path.strip_prefix("/api/users/")?;
+    let (id, tail) = rest.split_once('/').map_or((rest, None), |(id, tail)| (id, Some(tail)));
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let id: u64 = id.parse().ok().filter(|id| *id <= MAX_LEGACY_USER_ID)?;
+
+    let uuid = legacy_user_uuid(id);
+    Some(match tail {
+        Some(tail) => format!("/api/users/{}/{}", uuid, tail),
+        None => format!("/api/users/{}", uuid),
+    })
+}
+
+/// UUID migration 005 gave the user with an integer ID
+fn legacy_user_uuid(id: u64) -> Uuid {
+    Uuid::from_u128((0x4000_8000_u128 << 48) | id as u128)
+}
+
+/// `Deprecation`, `Sunset`, and successor `Link` headers of a legacy URL
+fn deprecation_headers(sunset: NaiveDate, successor: &str) -> Vec<(HeaderName, HeaderValue)> {
+    let sunset_at = DateTime::<Utc>::from_naive_utc_and_offset(sunset.and_hms_opt(0, 0, 0).unwrap_or_default(), Utc);
+    let values = [
+        ("deprecation", format!("@{}", LEGACY_USER_IDS_DEPRECATED_AT)),
+        ("sunset", sunset_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()),
+        ("link", format!("<{}>; rel=\"successor-version\"", successor)),
+    ];
+
+    values
+        .into_iter()
+        .filter_map(|(name, value)| {
+            Some((HeaderName::from_static(name), HeaderValue::from_str(&value).ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_user_uuid() {
+        assert_eq!(legacy_user_uuid(1).to_string(), "00000000-0000-4000-8000-000000000001");
+        assert_eq!(legacy_user_uuid(255).to_string(), "00000000-0000-4000-8000-0000000000ff");
+    }
+
+    #[test]
+    fn test_uuid_user_path() {
+        assert_eq!(
+            uuid_user_path("/api/users/42").as_deref(),
+            Some("/api/users/00000000-0000-4000-8000-00000000002a")
+        );
+        assert_eq!(
+            uuid_user_path("/api/users/42/permissions").as_deref(),
+            Some("/api/users/00000000-0000-4000-8000-00000000002a/permissions")
+        );
+        assert_eq!(uuid_user_path("/api/users/me"), None);
+        assert_eq!(uuid_user_path("/api/users/00000000-0000-4000-8000-00000000002a"), None);
+        assert_eq!(uuid_user_path("/api/users/"), None);
+        assert_eq!(uuid_user_path("/api/users/281474976710656"), None);
+        assert_eq!(uuid_user_path("/api/teams/42"), None);
+    }
+
+    #[test]
+    fn test_deprecation_headers() {
+        let headers = deprecation_headers(NaiveDate::from_ymd_opt(2027, 4, 1).unwrap(), "/api/users/x");
+        let value = |name: &str| headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.to_str().unwrap());
+        assert_eq!(value("deprecation"), Some("@1792195200"));
+        assert_eq!(value("sunset"), Some("Thu, 01 Apr 2027 00:00:00 GMT"));
+        assert_eq!(value("link"), Some("</api/users/x>; rel=\"successor-version\""));
+    }
+}
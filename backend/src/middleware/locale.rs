@@ -0,0 +1,75 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderValue, ACCEPT_LANGUAGE, CONTENT_LANGUAGE},
+    Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use crate::i18n::{with_locale, Locale};
+
+/// Locale negotiation middleware factory
+///
+/// Picks the response locale from the `Accept-Language` header, makes it the
+/// current locale while the request is handled, and reports it back in the
+/// `Content-Language` response header.
+pub struct LocaleMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for LocaleMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = LocaleMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LocaleMiddlewareService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+/// Locale negotiation middleware service
+pub struct LocaleMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LocaleMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        let locale = req
+            .headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|h| h.to_str().ok())
+            .map(Locale::negotiate)
+            .unwrap_or_default();
+        req.extensions_mut().insert(locale);
+
+        Box::pin(with_locale(locale, async move {
+            let mut res = service.call(req).await?;
+            res.headers_mut()
+                .insert(CONTENT_LANGUAGE, HeaderValue::from_static(locale.as_str()));
+            Ok(res)
+        }))
+    }
+}
@@ -4,6 +4,16 @@
 //! authentication, logging, etc.
 
 pub mod auth;
+pub mod health;
+pub mod legacy_user_id;
+pub mod locale;
+pub mod rate_limit;
+pub mod request_id;
 
 // Re-export middleware for convenience
-pub use auth::*;
\ No newline at end of file
+pub use auth::*;
+pub use health::*;
+pub use legacy_user_id::*;
+pub use locale::*;
+pub use rate_limit::*;
+pub use request_id::*;
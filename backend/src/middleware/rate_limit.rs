@@ -0,0 +1,126 @@
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header::RETRY_AFTER, Method},
+    web, Error, HttpMessage, ResponseError,
+};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::services::{RateLimitBucket, RateLimiter};
+use crate::utils::client_ip::client_ip;
+
+/// Rate limiting middleware factory
+///
+/// Counts sign-in and registration attempts per client address (see
+/// [`client_ip`]), and requests that reach a node per user, against the
+/// [`RateLimiter`] registered as app data. Requests over the limit are
+/// refused with `429 Too Many Requests` and a `Retry-After` header. Must run
+/// after authentication so callers are counted by user.
+pub struct RateLimitMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimitMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimitMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddlewareService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+/// Rate limiting middleware service
+pub struct RateLimitMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        let limiter = req.app_data::<web::Data<RateLimiter>>().cloned();
+        if let (Some(limiter), Some(bucket)) = (limiter, bucket_of(req.method(), req.path())) {
+            let client = match (bucket, req.extensions().get::<Claims>()) {
+                (RateLimitBucket::NodeProxy, Some(claims)) => format!("user:{}", claims.sub),
+                _ => client_ip(req.request()).map_or_else(|| "ip:unknown".to_string(), |ip| format!("ip:{}", ip)),
+            };
+            if let Err(exceeded) = limiter.check(bucket, &client) {
+                warn!("Rate limited {:?} request from {} to {}", bucket, client, req.path());
+                let retry_after = exceeded.retry_after.as_secs().max(1);
+                let mut response = AppError::from(exceeded).error_response();
+                response.headers_mut().insert(RETRY_AFTER, retry_after.into());
+                return Box::pin(ready(Ok(req.into_response(response).map_into_right_body())));
+            }
+        }
+
+        Box::pin(async move { Ok(service.call(req).await?.map_into_left_body()) })
+    }
+}
+
+/// Limit a request counts against, if any
+///
+/// Node requests are those under `/api/nodes/{id}/`, plus the configuration
+/// and system endpoints that name their node in the body or query.
+fn bucket_of(method: &Method, path: &str) -> Option<RateLimitBucket> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["api", "auth", "login" | "register"] if method == Method::POST => Some(RateLimitBucket::Auth),
+        ["api", "nodes", id, _, ..] if Uuid::parse_str(id).is_ok() => Some(RateLimitBucket::NodeProxy),
+        ["api", "config", endpoint, ..] if method == Method::POST && *endpoint != "locks" => {
+            Some(RateLimitBucket::NodeProxy)
+        }
+        ["api", "system", _, ..] => Some(RateLimitBucket::NodeProxy),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_of_request() {
+        let node = "/api/nodes/67e55044-10b1-426f-9247-bb680e5fe0c8";
+        assert_eq!(bucket_of(&Method::POST, "/api/auth/login"), Some(RateLimitBucket::Auth));
+        assert_eq!(bucket_of(&Method::POST, "/api/auth/register"), Some(RateLimitBucket::Auth));
+        assert_eq!(bucket_of(&Method::POST, "/api/auth/refresh"), None);
+        assert_eq!(bucket_of(&Method::GET, "/api/auth/login"), None);
+        assert_eq!(
+            bucket_of(&Method::GET, &format!("{}/interfaces", node)),
+            Some(RateLimitBucket::NodeProxy)
+        );
+        assert_eq!(bucket_of(&Method::GET, node), None);
+        assert_eq!(bucket_of(&Method::GET, "/api/nodes/stats/overview"), None);
+        assert_eq!(bucket_of(&Method::POST, "/api/config/retrieve"), Some(RateLimitBucket::NodeProxy));
+        assert_eq!(bucket_of(&Method::GET, "/api/system/info"), Some(RateLimitBucket::NodeProxy));
+        assert_eq!(bucket_of(&Method::POST, "/api/config/locks"), None);
+        assert_eq!(bucket_of(&Method::GET, "/api/config/history"), None);
+    }
+}
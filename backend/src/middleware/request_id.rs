@@ -0,0 +1,194 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    future::{ready, Future, Ready},
+    rc::Rc,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header carrying the request ID, read from requests and set on responses
+/// and outbound VyOS calls
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// W3C trace context header
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Longest request ID accepted from a client
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Trace context of the request being handled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// ID of the request, from `X-Request-Id` or generated
+    pub request_id: String,
+    /// 32 hex digit trace ID, from `traceparent` or generated
+    pub trace_id: String,
+    /// Trace flags of the incoming `traceparent`, `01` when sampled
+    pub flags: String,
+}
+
+impl TraceContext {
+    /// Context of a request from its `X-Request-Id` and `traceparent`
+    /// headers
+    ///
+    /// Invalid or missing values are replaced by generated ones. Without a
+    /// request ID, the trace ID doubles as one.
+    pub fn from_headers(request_id: Option<&str>, traceparent: Option<&str>) -> Self {
+        let (trace_id, flags) = traceparent
+            .and_then(parse_traceparent)
+            .unwrap_or_else(|| (Uuid::new_v4().simple().to_string(), "01".to_string()));
+        let request_id = request_id
+            .map(str::trim)
+            .filter(|id| {
+                !id.is_empty()
+                    && id.len() <= MAX_REQUEST_ID_LEN
+                    && id.bytes().all(|b| b.is_ascii_graphic())
+            })
+            .map(str::to_string)
+            .unwrap_or_else(|| trace_id.clone());
+        Self { request_id, trace_id, flags }
+    }
+
+    /// `traceparent` for an outbound call made on behalf of the request
+    ///
+    /// Each call gets its own span ID within the request's trace.
+    pub fn child_traceparent(&self) -> String {
+        let span_id = &Uuid::new_v4().simple().to_string()[..16];
+        format!("00-{}-{}-{}", self.trace_id, span_id, self.flags)
+    }
+}
+
+/// Trace ID and flags of a version 00 `traceparent` header
+fn parse_traceparent(header: &str) -> Option<(String, String)> {
+    let mut parts = header.trim().split('-');
+    let (version, trace_id, parent_id, flags) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    let hex = |s: &str, len: usize| {
+        s.len() == len && s.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    };
+    let valid = version == "00"
+        && parts.next().is_none()
+        && hex(trace_id, 32)
+        && hex(parent_id, 16)
+        && hex(flags, 2)
+        && trace_id.bytes().any(|b| b != b'0')
+        && parent_id.bytes().any(|b| b != b'0');
+    valid.then(|| (trace_id.to_string(), flags.to_string()))
+}
+
+tokio::task_local! {
+    static CURRENT_TRACE: TraceContext;
+}
+
+/// Trace context of the request being handled, if any
+pub fn current_trace() -> Option<TraceContext> {
+    CURRENT_TRACE.try_with(Clone::clone).ok()
+}
+
+/// Run a future with the given trace context as the current one
+pub async fn with_trace<F: Future>(trace: TraceContext, future: F) -> F::Output {
+    CURRENT_TRACE.scope(trace, future).await
+}
+
+/// Request ID middleware factory
+///
+/// Takes the request ID and trace context from the `X-Request-Id` and
+/// `traceparent` headers, or generates them, and makes them current while
+/// the request is handled so VyOS calls pass them on. The request is
+/// handled in an `http_request` span carrying the IDs, and the request ID
+/// is echoed in the response.
+pub struct RequestIdMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestIdMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdMiddlewareService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+/// Request ID middleware service
+pub struct RequestIdMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        let header = |name: &str| req.headers().get(name).and_then(|h| h.to_str().ok());
+        let trace = TraceContext::from_headers(header(REQUEST_ID_HEADER), header(TRACEPARENT_HEADER));
+        let span = tracing::info_span!(
+            "http_request",
+            request_id = %trace.request_id,
+            trace_id = %trace.trace_id,
+            method = %req.method(),
+            path = %req.path(),
+        );
+        req.extensions_mut().insert(trace.clone());
+
+        let request_id = HeaderValue::from_str(&trace.request_id).ok();
+        Box::pin(
+            with_trace(trace, async move {
+                let mut res = service.call(req).await?;
+                if let Some(request_id) = request_id {
+                    res.headers_mut()
+                        .insert(HeaderName::from_static(REQUEST_ID_HEADER), request_id);
+                }
+                Ok(res)
+            })
+            .instrument(span),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_context_from_headers() {
+        let trace = TraceContext::from_headers(
+            Some("ui-42"),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+        assert_eq!(trace.request_id, "ui-42");
+        assert_eq!(trace.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+
+        let child = trace.child_traceparent();
+        assert!(child.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+        assert!(child.ends_with("-01"));
+        assert!(parse_traceparent(&child).is_some());
+
+        let generated = TraceContext::from_headers(Some("bad id\n"), Some("00-0000-zz-01"));
+        assert_eq!(generated.trace_id.len(), 32);
+        assert_eq!(generated.request_id, generated.trace_id);
+        assert!(parse_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none());
+    }
+}
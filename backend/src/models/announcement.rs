@@ -0,0 +1,149 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// WebSocket channel announcement changes are broadcast on
+pub const ANNOUNCEMENTS_CHANNEL: &str = "announcements";
+
+/// Announcement severity, deciding how prominently the UI shows it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementSeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl AnnouncementSeverity {
+    /// Convert severity to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnnouncementSeverity::Info => "info",
+            AnnouncementSeverity::Warning => "warning",
+            AnnouncementSeverity::Critical => "critical",
+        }
+    }
+
+    /// Parse severity from database string
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "warning" => AnnouncementSeverity::Warning,
+            "critical" => AnnouncementSeverity::Critical,
+            _ => AnnouncementSeverity::Info,
+        }
+    }
+}
+
+/// Announcement shown to all users, such as a maintenance banner
+#[derive(Debug, Clone, Serialize)]
+pub struct Announcement {
+    pub id: Uuid,
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+    /// Start of the display window
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub starts_at: DateTime<Utc>,
+    /// End of the display window; shown until deleted when unset
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub ends_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Announcement {
+    /// Whether the announcement is to be shown at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now && self.ends_at.is_none_or(|ends_at| now < ends_at)
+    }
+}
+
+/// Create announcement request
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAnnouncementRequest {
+    #[validate(length(min = 1, max = 2000))]
+    pub message: String,
+    #[serde(default)]
+    pub severity: AnnouncementSeverity,
+    /// Defaults to now
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Update announcement request
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAnnouncementRequest {
+    #[validate(length(min = 1, max = 2000))]
+    pub message: Option<String>,
+    pub severity: Option<AnnouncementSeverity>,
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Announcement list query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct AnnouncementListQuery {
+    /// Include scheduled and expired announcements (administrators only)
+    #[serde(default)]
+    pub all: bool,
+}
+
+/// Announcement list response
+#[derive(Debug, Serialize)]
+pub struct AnnouncementListResponse {
+    pub announcements: Vec<Announcement>,
+    pub total: u64,
+}
+
+/// Announcement change broadcast on [`ANNOUNCEMENTS_CHANNEL`]
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum AnnouncementEvent {
+    Created { announcement: Announcement },
+    Updated { announcement: Announcement },
+    Deleted { id: Uuid },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_announcement_window() {
+        let now = Utc::now();
+        let mut announcement = Announcement {
+            id: Uuid::new_v4(),
+            message: "Maintenance tonight".to_string(),
+            severity: AnnouncementSeverity::Warning,
+            starts_at: now - Duration::hours(1),
+            ends_at: None,
+            created_by: None,
+            created_at: now,
+            updated_at: now,
+        };
+        assert!(announcement.is_active(now));
+
+        announcement.ends_at = Some(now);
+        assert!(!announcement.is_active(now));
+
+        announcement.starts_at = now + Duration::hours(1);
+        announcement.ends_at = None;
+        assert!(!announcement.is_active(now));
+    }
+
+    #[test]
+    fn test_announcement_event_format() {
+        let id = Uuid::nil();
+        let json = serde_json::to_value(AnnouncementEvent::Deleted { id }).unwrap();
+        assert_eq!(json, serde_json::json!({ "event": "deleted", "id": id }));
+    }
+}
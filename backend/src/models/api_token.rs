@@ -0,0 +1,212 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::redact::sanitized_debug;
+
+/// Start of every read-only API token, telling them apart from access tokens
+pub const API_TOKEN_PREFIX: &str = "vwr_";
+
+/// Longest lifetime of an API token, in days
+pub const MAX_API_TOKEN_DAYS: i64 = 365;
+
+/// Unrevoked tokens a user may hold at once
+pub const MAX_API_TOKENS_PER_USER: i64 = 25;
+
+/// Data an API token may read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiTokenScope {
+    /// System metrics, network statistics, and metric history
+    Metrics,
+    /// Alerts and incidents
+    Alerts,
+    /// Nodes, their health, and fleet statistics
+    Nodes,
+    /// The status page
+    Status,
+}
+
+impl ApiTokenScope {
+    /// GET routes the scope opens
+    ///
+    /// `{node}` stands for the ID of a node, `{id}` for any other ID.
+    pub fn routes(&self) -> &'static [&'static str] {
+        match self {
+            ApiTokenScope::Metrics => &["/api/monitoring/system", "/api/monitoring/network", "/api/monitoring/history"],
+            ApiTokenScope::Alerts => &["/api/monitoring/alerts", "/api/incidents", "/api/incidents/{id}"],
+            ApiTokenScope::Nodes => &["/api/nodes/stats", "/api/nodes/health/all", "/api/nodes/{node}", "/api/nodes/{node}/health"],
+            ApiTokenScope::Status => &["/api/status"],
+        }
+    }
+}
+
+/// Match a request path against a route of [`ApiTokenScope::routes`]
+///
+/// Returns the node the path names, if the route has a node segment, or
+/// `None` if the path does not match.
+pub fn match_token_route(route: &str, path: &str) -> Option<Option<Uuid>> {
+    let route: Vec<&str> = route.split('/').collect();
+    let path: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    if route.len() != path.len() {
+        return None;
+    }
+
+    let mut node = None;
+    for (expected, actual) in route.iter().zip(&path) {
+        match *expected {
+            "{node}" => node = Some(Uuid::parse_str(actual).ok()?),
+            "{id}" => {
+                Uuid::parse_str(actual).ok()?;
+            }
+            expected if expected == *actual => {}
+            _ => return None,
+        }
+    }
+    Some(node)
+}
+
+/// Read-only API token, as listed; the token itself is only shown once
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    /// Start of the token, to recognize it by
+    pub prefix: String,
+    /// User the token acts for
+    pub user_id: String,
+    pub username: String,
+    pub scopes: Vec<ApiTokenScope>,
+    /// Only nodes the token may read; every node the user can see if unset
+    pub node_ids: Option<Vec<Uuid>>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub last_used_at: Option<DateTime<Utc>>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    /// Check whether the token is neither revoked nor expired
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+
+    /// Check whether the token may read a path
+    ///
+    /// Tokens limited to some nodes only read routes naming one of them, in
+    /// the path or in the `node_id` query parameter; routes covering every
+    /// node are closed to them.
+    pub fn permits(&self, path: &str, query_node_id: Option<&str>) -> bool {
+        let Some(path_node) = self
+            .scopes
+            .iter()
+            .flat_map(|scope| scope.routes())
+            .find_map(|route| match_token_route(route, path))
+        else {
+            return false;
+        };
+
+        match &self.node_ids {
+            None => true,
+            Some(allowed) => path_node
+                .or_else(|| query_node_id.and_then(|id| Uuid::parse_str(id).ok()))
+                .is_some_and(|node| allowed.contains(&node)),
+        }
+    }
+}
+
+/// Newly created API token, with the token itself
+#[derive(Clone, Serialize)]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+    /// Sent as `?token=` or as a bearer token; not retrievable later
+    pub token: String,
+}
+
+sanitized_debug!(CreatedApiToken { api_token } secret { token });
+
+/// Request to create an API token
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateApiTokenRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<ApiTokenScope>,
+    #[validate(length(min = 1, max = 100))]
+    pub node_ids: Option<Vec<Uuid>>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Query parameters for listing API tokens
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ApiTokenListQuery {
+    /// List the tokens of every user; administrators only
+    #[serde(default)]
+    pub all: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn token(scopes: Vec<ApiTokenScope>, node_ids: Option<Vec<Uuid>>) -> ApiToken {
+        ApiToken {
+            id: Uuid::new_v4(),
+            name: "wallboard".to_string(),
+            prefix: "vwr_0123abcd".to_string(),
+            user_id: Uuid::new_v4().to_string(),
+            username: "noc".to_string(),
+            scopes,
+            node_ids,
+            expires_at: Utc::now() + Duration::days(1),
+            last_used_at: None,
+            revoked_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_match_token_route() {
+        let node = Uuid::new_v4();
+        assert_eq!(match_token_route("/api/nodes/stats", "/api/nodes/stats"), Some(None));
+        assert_eq!(match_token_route("/api/nodes/{node}/health", &format!("/api/nodes/{}/health", node)), Some(Some(node)));
+        assert_eq!(match_token_route("/api/nodes/{node}", "/api/nodes/export"), None);
+        assert_eq!(match_token_route("/api/nodes/{node}", &format!("/api/nodes/{}/config", node)), None);
+        assert_eq!(match_token_route("/api/incidents/{id}", "/api/incidents/latest"), None);
+    }
+
+    #[test]
+    fn test_permits_scoped_routes() {
+        let node = Uuid::new_v4();
+        let fleet = token(vec![ApiTokenScope::Metrics, ApiTokenScope::Nodes], None);
+        assert!(fleet.permits("/api/monitoring/history", None));
+        assert!(fleet.permits("/api/nodes/health/all", None));
+        assert!(fleet.permits(&format!("/api/nodes/{}", node), None));
+        assert!(!fleet.permits("/api/monitoring/alerts", None));
+        assert!(!fleet.permits("/api/users", None));
+
+        let single = token(vec![ApiTokenScope::Metrics, ApiTokenScope::Nodes], Some(vec![node]));
+        assert!(single.permits(&format!("/api/nodes/{}/health", node), None));
+        assert!(single.permits("/api/monitoring/system", Some(&node.to_string())));
+        assert!(!single.permits(&format!("/api/nodes/{}/health", Uuid::new_v4()), None));
+        assert!(!single.permits("/api/monitoring/system", None));
+        assert!(!single.permits("/api/nodes/health/all", None));
+    }
+
+    #[test]
+    fn test_is_active() {
+        let mut api_token = token(vec![ApiTokenScope::Status], None);
+        assert!(api_token.is_active(Utc::now()));
+        assert!(!api_token.is_active(Utc::now() + Duration::days(2)));
+        api_token.revoked_at = Some(Utc::now());
+        assert!(!api_token.is_active(Utc::now()));
+    }
+}
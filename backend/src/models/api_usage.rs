@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// VyOS API calls the backend made to a node since it started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeApiUsage {
+    pub node_id: Uuid,
+    /// Start of the counting, i.e. when the backend started
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub since: DateTime<Utc>,
+    pub total_calls: u64,
+    pub total_errors: u64,
+    /// Share of calls that failed, from 0 to 1
+    pub error_rate: f64,
+    /// Usage per operation, busiest first
+    pub operations: Vec<ApiOperationUsage>,
+}
+
+/// Calls of one operation, such as `show` or `retrieve_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiOperationUsage {
+    pub operation: String,
+    pub calls: u64,
+    pub errors: u64,
+    /// Share of calls that failed, from 0 to 1
+    pub error_rate: f64,
+    pub avg_latency_ms: f64,
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
+    pub last_called_at: Option<DateTime<Utc>>,
+    /// Message of the most recent failure
+    pub last_error: Option<String>,
+}
@@ -0,0 +1,242 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::time_range::{RelativeDuration, TimePreset};
+
+/// Columns audit entries can be sorted by
+pub const AUDIT_SORT_COLUMNS: &[&str] = &["created_at", "user_id", "username", "node_id", "action", "result"];
+
+/// Columns operations can be sorted by
+pub const OPERATION_SORT_COLUMNS: &[&str] =
+    &["started_at", "completed_at", "user_id", "node_id", "kind", "status"];
+
+/// Outcome of an audited action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditResult {
+    Success,
+    Failure,
+}
+
+impl AuditResult {
+    /// Stored representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditResult::Success => "success",
+            AuditResult::Failure => "failure",
+        }
+    }
+
+    /// Parse the stored representation; unknown values count as failures
+    pub fn from_db(s: &str) -> Self {
+        match s {
+            "success" => AuditResult::Success,
+            _ => AuditResult::Failure,
+        }
+    }
+}
+
+/// Audit log entry
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    /// Username at the time of the action
+    pub username: Option<String>,
+    pub node_id: Option<Uuid>,
+    /// Dotted action name (e.g. `node.update`)
+    pub action: String,
+    /// Object the action was applied to, if not the node itself
+    pub target: Option<String>,
+    pub result: AuditResult,
+    pub details: Option<serde_json::Value>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Action to be written to the audit log
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    pub user_id: Option<Uuid>,
+    pub username: Option<String>,
+    pub node_id: Option<Uuid>,
+    pub action: String,
+    pub target: Option<String>,
+    pub result: AuditResult,
+    pub details: Option<serde_json::Value>,
+}
+
+impl AuditEvent {
+    /// Action performed by the authenticated user
+    pub fn new(claims: Option<&crate::models::auth::Claims>, action: &str, result: AuditResult) -> Self {
+        Self {
+            user_id: claims.and_then(|c| c.user_id().ok()),
+            username: claims.map(|c| c.username.clone()),
+            node_id: None,
+            action: action.to_string(),
+            target: None,
+            result,
+            details: None,
+        }
+    }
+
+    /// Set the node the action applied to
+    pub fn with_node(mut self, node_id: Uuid) -> Self {
+        self.node_id = Some(node_id);
+        self
+    }
+
+    /// Set the object the action applied to
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Attach details such as the error of a failed action
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// Audit log query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditListQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub user_id: Option<Uuid>,
+    pub node_id: Option<Uuid>,
+    /// Action name, or a prefix ending in `.` (e.g. `node.`)
+    pub action: Option<String>,
+    pub result: Option<AuditResult>,
+    /// Only entries at or after this time, absolute or relative (`-6h`)
+    #[serde(default, deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub from: Option<DateTime<Utc>>,
+    /// Only entries before this time
+    #[serde(default, deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub to: Option<DateTime<Utc>>,
+    /// Span ending at `to`, or now, such as `7d`
+    pub window: Option<RelativeDuration>,
+    /// Named time range replacing `from` and `to`
+    pub preset: Option<TimePreset>,
+    /// One of [`AUDIT_SORT_COLUMNS`]; defaults to `created_at`
+    pub sort_by: Option<String>,
+    /// `asc` or `desc` (default)
+    pub sort_order: Option<String>,
+}
+
+/// Paginated audit log response
+#[derive(Debug, Serialize)]
+pub struct AuditListResponse {
+    pub entries: Vec<AuditEntry>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+}
+
+/// Operation status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationStatus {
+    /// Accepted and still running on the node
+    Running,
+    Completed,
+    Failed,
+}
+
+impl OperationStatus {
+    /// Stored representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationStatus::Running => "running",
+            OperationStatus::Completed => "completed",
+            OperationStatus::Failed => "failed",
+        }
+    }
+
+    /// Parse the stored representation; unknown values count as failures
+    pub fn from_db(s: &str) -> Self {
+        match s {
+            "running" => OperationStatus::Running,
+            "completed" => OperationStatus::Completed,
+            _ => OperationStatus::Failed,
+        }
+    }
+}
+
+/// Recorded system operation
+#[derive(Debug, Clone, Serialize)]
+pub struct Operation {
+    /// Operation ID as returned when the operation was started
+    pub id: String,
+    /// Operation kind (e.g. `reboot`, `add_image`)
+    pub kind: String,
+    pub node_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub status: OperationStatus,
+    pub message: String,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub started_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Operation list query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct OperationListQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub user_id: Option<Uuid>,
+    pub node_id: Option<Uuid>,
+    pub kind: Option<String>,
+    pub status: Option<OperationStatus>,
+    /// Only operations started at or after this time, absolute or relative (`-6h`)
+    #[serde(default, deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub from: Option<DateTime<Utc>>,
+    /// Only operations started before this time
+    #[serde(default, deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub to: Option<DateTime<Utc>>,
+    /// Span ending at `to`, or now, such as `7d`
+    pub window: Option<RelativeDuration>,
+    /// Named time range replacing `from` and `to`
+    pub preset: Option<TimePreset>,
+    /// One of [`OPERATION_SORT_COLUMNS`]; defaults to `started_at`
+    pub sort_by: Option<String>,
+    /// `asc` or `desc` (default)
+    pub sort_order: Option<String>,
+}
+
+/// Paginated operation list response
+#[derive(Debug, Serialize)]
+pub struct OperationListResponse {
+    pub operations: Vec<Operation>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_result_round_trip() {
+        for result in [AuditResult::Success, AuditResult::Failure] {
+            assert_eq!(AuditResult::from_db(result.as_str()), result);
+        }
+        assert_eq!(
+            serde_json::to_string(&AuditResult::Failure).unwrap(),
+            "\"failure\""
+        );
+    }
+
+    #[test]
+    fn test_operation_status_round_trip() {
+        for status in [OperationStatus::Running, OperationStatus::Completed, OperationStatus::Failed] {
+            assert_eq!(OperationStatus::from_db(status.as_str()), status);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::models::redact::sanitized_debug;
+
 /// JWT Claims structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
@@ -15,10 +17,27 @@ pub struct Claims {
 
     /// Issued at time (Unix timestamp)
     pub iat: i64,
+
+    /// Login session the token was issued for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+}
+
+impl Claims {
+    /// Get the ID of the authenticated user
+    pub fn user_id(&self) -> Result<uuid::Uuid, crate::error::AppError> {
+        uuid::Uuid::parse_str(&self.sub)
+            .map_err(|_| crate::error::AppError::Auth("Invalid user ID in token".to_string()))
+    }
+
+    /// Get the login session the token was issued for, if any
+    pub fn session_id(&self) -> Option<uuid::Uuid> {
+        self.sid.as_deref().and_then(|sid| uuid::Uuid::parse_str(sid).ok())
+    }
 }
 
 /// Login request payload
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Deserialize, Validate)]
 pub struct LoginRequest {
     #[validate(length(min = 3, max = 50))]
     pub username: String,
@@ -27,20 +46,86 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+sanitized_debug!(LoginRequest { username } secret { password });
+
 /// Login response payload
-#[derive(Debug, Serialize)]
+#[derive(Serialize)]
 pub struct LoginResponse {
+    pub user: UserResponse,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+}
+
+sanitized_debug!(LoginResponse { user, expires_in } secret { access_token, refresh_token });
+
+/// Simple token response payload
+#[derive(Serialize)]
+pub struct SimpleLoginResponse {
     pub token: String,
     pub user_id: String,
     pub username: String,
 }
 
+sanitized_debug!(SimpleLoginResponse { user_id, username } secret { token });
+
 /// Refresh token request
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize)]
 pub struct RefreshTokenRequest {
     pub refresh_token: String,
 }
 
+sanitized_debug!(RefreshTokenRequest {} secret { refresh_token });
+
+/// Tokens issued in exchange for a refresh token
+///
+/// The refresh token is rotated: the one exchanged is no longer valid, and
+/// presenting it again revokes the session.
+#[derive(Serialize)]
+pub struct RefreshTokenResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+}
+
+sanitized_debug!(RefreshTokenResponse { expires_in } secret { access_token, refresh_token });
+
+/// Tokens of a new login session
+#[derive(Clone)]
+pub struct SessionTokens {
+    pub session_id: uuid::Uuid,
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+sanitized_debug!(SessionTokens { session_id } secret { access_token, refresh_token });
+
+/// Active login session of a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSession {
+    pub id: uuid::Uuid,
+    /// Client address at login, as reported by the proxy if there is one
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// Browser and operating system, e.g. `Firefox on Linux`
+    pub device: String,
+    /// Whether this is the session of the request listing the sessions
+    pub current: bool,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// When the session last refreshed its tokens
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub last_used_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Active login sessions of a user, most recently used first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserSessionListResponse {
+    pub sessions: Vec<UserSession>,
+}
+
 /// Token validation response
 #[derive(Debug, Serialize)]
 pub struct TokenValidationResponse {
@@ -51,7 +136,7 @@ pub struct TokenValidationResponse {
 }
 
 /// Register request payload
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Deserialize, Validate)]
 pub struct RegisterRequest {
     #[validate(length(min = 3, max = 50))]
     pub username: String,
@@ -65,6 +150,8 @@ pub struct RegisterRequest {
     pub full_name: Option<String>,
 }
 
+sanitized_debug!(RegisterRequest { username, email, full_name } secret { password });
+
 /// User response payload
 #[derive(Debug, Serialize)]
 pub struct UserResponse {
@@ -74,7 +161,13 @@ pub struct UserResponse {
     pub full_name: Option<String>,
     pub role: crate::models::user::UserRole,
     pub status: crate::models::user::UserStatus,
+    pub timezone: String,
+    /// Whether the user must choose a new password
+    pub password_reset_required: bool,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
     pub last_login: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
\ No newline at end of file
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Node ID under which alerts about the backend itself are raised
+///
+/// Backend alerts flow through the same alert list, incidents, and
+/// notifications as router alerts; the nil UUID never names a real node.
+pub const BACKEND_NODE_ID: Uuid = Uuid::nil();
+
+/// Health metric the backend reports about itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendMetric {
+    /// Requests failed by a database error during the last minute
+    DbErrors,
+    /// Report schedules past their run time
+    JobQueueDepth,
+    /// Seconds the most delayed background job is behind its schedule
+    CollectorLag,
+    /// Share of responses during the last minute that were server errors
+    HttpErrors,
+    /// Requests handled during the last minute
+    Requests,
+}
+
+impl BackendMetric {
+    /// All backend metrics
+    pub const ALL: [BackendMetric; 5] = [
+        BackendMetric::DbErrors,
+        BackendMetric::JobQueueDepth,
+        BackendMetric::CollectorLag,
+        BackendMetric::HttpErrors,
+        BackendMetric::Requests,
+    ];
+
+    /// Metric name alert rules refer to
+    pub fn metric_name(&self) -> &'static str {
+        match self {
+            BackendMetric::DbErrors => "backend_db_errors_per_minute",
+            BackendMetric::JobQueueDepth => "backend_job_queue_depth",
+            BackendMetric::CollectorLag => "backend_collector_lag_seconds",
+            BackendMetric::HttpErrors => "backend_http_5xx_percent",
+            BackendMetric::Requests => "backend_requests_per_minute",
+        }
+    }
+}
+
+/// Lag of a periodic background job
+#[derive(Debug, Clone, Serialize)]
+pub struct BackgroundJobStatus {
+    /// Job name
+    pub name: String,
+    /// Seconds between runs
+    pub period_secs: u64,
+    /// When the job last ran on this instance
+    pub last_run_at: DateTime<Utc>,
+    /// Seconds the job is overdue
+    pub lag_secs: u64,
+}
+
+/// Current health metrics of the backend
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendHealthSnapshot {
+    pub db_errors_per_minute: u64,
+    pub job_queue_depth: u64,
+    pub collector_lag_secs: u64,
+    pub http_5xx_percent: f64,
+    pub requests_per_minute: u64,
+    pub jobs: Vec<BackgroundJobStatus>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl BackendHealthSnapshot {
+    /// Value of a metric
+    pub fn value(&self, metric: BackendMetric) -> f64 {
+        match metric {
+            BackendMetric::DbErrors => self.db_errors_per_minute as f64,
+            BackendMetric::JobQueueDepth => self.job_queue_depth as f64,
+            BackendMetric::CollectorLag => self.collector_lag_secs as f64,
+            BackendMetric::HttpErrors => self.http_5xx_percent,
+            BackendMetric::Requests => self.requests_per_minute as f64,
+        }
+    }
+
+    /// Metric values by name, as alert rules evaluate them
+    pub fn values(&self) -> HashMap<String, f64> {
+        BackendMetric::ALL
+            .iter()
+            .map(|metric| (metric.metric_name().to_string(), self.value(*metric)))
+            .collect()
+    }
+}
+
+/// Response of the backend health endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendHealthResponse {
+    #[serde(flatten)]
+    pub snapshot: BackendHealthSnapshot,
+    /// Unresolved alerts about the backend
+    pub alerts: Vec<crate::models::monitoring::Alert>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_values_cover_all_metrics() {
+        let snapshot = BackendHealthSnapshot {
+            db_errors_per_minute: 3,
+            job_queue_depth: 1,
+            collector_lag_secs: 90,
+            http_5xx_percent: 12.5,
+            requests_per_minute: 40,
+            jobs: vec![],
+            checked_at: Utc::now(),
+        };
+
+        let values = snapshot.values();
+        assert_eq!(values.len(), BackendMetric::ALL.len());
+        assert_eq!(values["backend_db_errors_per_minute"], 3.0);
+        assert_eq!(values["backend_collector_lag_seconds"], 90.0);
+        assert_eq!(values["backend_http_5xx_percent"], 12.5);
+    }
+}
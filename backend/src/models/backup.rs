@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// File name prefix of database backups
+pub const BACKUP_FILE_PREFIX: &str = "vyos-webui-";
+
+/// File name extension of database backups
+pub const BACKUP_FILE_EXTENSION: &str = ".sqlite";
+
+/// SQLite database backup stored in the backup directory
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseBackup {
+    pub file_name: String,
+    pub size_bytes: u64,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Backup list response
+#[derive(Debug, Serialize)]
+pub struct DatabaseBackupListResponse {
+    /// Newest first
+    pub backups: Vec<DatabaseBackup>,
+}
+
+/// Request to restore the database from a backup
+#[derive(Debug, Deserialize)]
+pub struct RestoreBackupRequest {
+    /// Name of a file in the backup directory
+    pub file_name: String,
+}
+
+/// Outcome of a database restore
+#[derive(Debug, Serialize)]
+pub struct RestoreBackupResponse {
+    pub restored_from: DatabaseBackup,
+    /// Backup of the database as it was right before the restore
+    pub safety_backup: DatabaseBackup,
+    /// Number of tables whose rows were replaced
+    pub tables: usize,
+}
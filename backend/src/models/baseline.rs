@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Syslog facilities VyOS accepts
+pub const SYSLOG_FACILITIES: &[&str] = &[
+    "all", "auth", "authpriv", "cron", "daemon", "kern", "lpr", "mail", "mark", "news", "protocols", "security",
+    "syslog", "user", "uucp", "local0", "local1", "local2", "local3", "local4", "local5", "local6", "local7",
+];
+
+/// Syslog severities VyOS accepts, from the most severe
+pub const SYSLOG_LEVELS: &[&str] = &["emerg", "alert", "crit", "err", "warning", "notice", "info", "debug", "all"];
+
+/// Time server a node synchronizes its clock with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NtpServer {
+    /// Address or host name
+    pub address: String,
+    /// Whether the name resolves to a pool of servers
+    #[serde(default)]
+    pub pool: bool,
+    /// Whether the server is preferred over the others
+    #[serde(default)]
+    pub prefer: bool,
+}
+
+/// NTP settings of a node
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NtpSettings {
+    #[serde(default)]
+    pub servers: Vec<NtpServer>,
+}
+
+/// Facility a syslog remote receives messages of, from a severity up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyslogFacility {
+    /// Facility, e.g. `auth` or `all`
+    pub facility: String,
+    /// Least severe level sent, e.g. `warning`
+    pub level: String,
+}
+
+/// Transport of messages to a syslog remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogProtocol {
+    Udp,
+    Tcp,
+}
+
+impl SyslogProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Udp => "udp",
+            Self::Tcp => "tcp",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "udp" => Some(Self::Udp),
+            "tcp" => Some(Self::Tcp),
+            _ => None,
+        }
+    }
+}
+
+/// Remote syslog server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyslogRemote {
+    /// Address or host name
+    pub host: String,
+    /// Port; 514 if unset
+    pub port: Option<u16>,
+    /// Transport; UDP if unset
+    pub protocol: Option<SyslogProtocol>,
+    pub facilities: Vec<SyslogFacility>,
+}
+
+/// Syslog settings of a node
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyslogSettings {
+    #[serde(default)]
+    pub remotes: Vec<SyslogRemote>,
+}
+
+/// Access an SNMP community or group grants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnmpAccess {
+    Ro,
+    Rw,
+}
+
+impl SnmpAccess {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ro => "ro",
+            Self::Rw => "rw",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ro" => Some(Self::Ro),
+            "rw" => Some(Self::Rw),
+            _ => None,
+        }
+    }
+}
+
+/// SNMPv1/v2c community
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnmpCommunity {
+    pub name: String,
+    pub authorization: SnmpAccess,
+    /// Networks allowed to use the community; any if empty
+    #[serde(default)]
+    pub networks: Vec<String>,
+}
+
+/// SNMPv3 view: the subtrees of the MIB a group may see
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnmpV3View {
+    pub name: String,
+    /// Object identifiers of the subtrees, e.g. `1` for everything
+    pub oids: Vec<String>,
+}
+
+/// SNMPv3 group of users sharing a view and an access mode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnmpV3Group {
+    pub name: String,
+    pub view: String,
+    pub mode: SnmpAccess,
+}
+
+/// Authentication or privacy protocol of an SNMPv3 user with its password
+///
+/// The password is only ever written; the node stores it encrypted, and an
+/// unset password keeps the one the node has.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnmpV3Secret {
+    /// `md5` or `sha` for authentication, `des` or `aes` for privacy
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,
+}
+
+/// SNMPv3 user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnmpV3User {
+    pub name: String,
+    pub group: String,
+    pub auth: SnmpV3Secret,
+    /// Encryption of the user's traffic; requires authentication
+    pub privacy: Option<SnmpV3Secret>,
+}
+
+/// SNMP settings of a node
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnmpSettings {
+    pub contact: Option<String>,
+    pub location: Option<String>,
+    #[serde(default)]
+    pub communities: Vec<SnmpCommunity>,
+    #[serde(default)]
+    pub v3_views: Vec<SnmpV3View>,
+    #[serde(default)]
+    pub v3_groups: Vec<SnmpV3Group>,
+    #[serde(default)]
+    pub v3_users: Vec<SnmpV3User>,
+}
+
+/// Baseline services of a node: NTP, syslog, and SNMP
+///
+/// In a change, services left out are not touched; those given replace the
+/// node's settings of the service.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineSettings {
+    pub ntp: Option<NtpSettings>,
+    pub syslog: Option<SyslogSettings>,
+    pub snmp: Option<SnmpSettings>,
+}
+
+/// Request to apply the same baseline services to many nodes
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct PushBaselineRequest {
+    pub settings: BaselineSettings,
+    /// Nodes to change; every node the caller can access if unset
+    #[validate(length(min = 1, max = 1000))]
+    pub node_ids: Option<Vec<Uuid>>,
+}
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Days before expiry a certificate raises a warning alert unless the
+/// endpoint sets its own threshold
+pub const DEFAULT_WARNING_DAYS: u32 = 30;
+
+/// Days before expiry a certificate raises a critical alert unless the
+/// endpoint sets its own threshold
+pub const DEFAULT_CRITICAL_DAYS: u32 = 7;
+
+/// TLS endpoint whose certificate is checked for expiry
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateEndpoint {
+    pub id: Uuid,
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    /// Router whose API certificate this is; alerts are raised on the node
+    pub node_id: Option<Uuid>,
+    pub warning_days: u32,
+    pub critical_days: u32,
+    /// Common name of the last certificate seen
+    pub subject: Option<String>,
+    /// Common name or organization of the issuer of the last certificate seen
+    pub issuer: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub not_after: Option<DateTime<Utc>>,
+    /// Whole days until `not_after`, negative once expired
+    pub days_remaining: Option<i64>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub last_checked_at: Option<DateTime<Utc>>,
+    /// Why the last check could not read a certificate
+    pub last_error: Option<String>,
+    pub created_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to monitor a TLS endpoint
+///
+/// Either `url`, such as `https://vyos.example.com` or `mail.example.com:465`,
+/// or `node_id` names the endpoint; a node is checked at its API address.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCertificateEndpointRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 1, max = 2048))]
+    pub url: Option<String>,
+    pub node_id: Option<Uuid>,
+    #[validate(range(min = 1, max = 365))]
+    pub warning_days: Option<u32>,
+    #[validate(range(min = 1, max = 365))]
+    pub critical_days: Option<u32>,
+}
+
+/// Monitored TLS endpoints, ordered by name
+#[derive(Debug, Serialize)]
+pub struct CertificateEndpointListResponse {
+    pub endpoints: Vec<CertificateEndpoint>,
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Longest delay that can be injected, in milliseconds
+pub const MAX_INJECTED_DELAY_MS: u64 = 120_000;
+
+/// How requests to a node fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeFaultKind {
+    /// The node does not answer, like an unreachable router; counts against
+    /// the node's circuit breaker
+    Timeout,
+    /// The node answers with a 5xx status
+    ServerError,
+    /// The node answers normally, after `delay_ms`
+    Delay,
+}
+
+fn default_status() -> u16 {
+    503
+}
+
+/// Failure injected into requests to a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeFault {
+    pub kind: NodeFaultKind,
+    /// Status of `server_error` faults, 500 to 599
+    #[serde(default = "default_status")]
+    pub status: u16,
+    /// Milliseconds each request waits before it fails or proceeds
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// Number of requests to affect before the fault clears itself, or
+    /// `None` to affect requests until the fault is removed
+    #[serde(default)]
+    pub remaining: Option<u32>,
+}
+
+/// Failure injected into requests to one node
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeFaultEntry {
+    pub node_id: Uuid,
+    #[serde(flatten)]
+    pub fault: NodeFault,
+}
+
+/// Request to delay database queries
+#[derive(Debug, Deserialize)]
+pub struct DatabaseDelayRequest {
+    /// Milliseconds added before each query, 0 to stop delaying
+    pub delay_ms: u64,
+}
+
+/// Failures currently injected
+#[derive(Debug, Serialize)]
+pub struct ChaosStatus {
+    /// Whether failure injection is enabled with `CHAOS_ENABLED`
+    pub enabled: bool,
+    pub node_faults: Vec<NodeFaultEntry>,
+    /// Milliseconds added before each database query
+    pub database_delay_ms: u64,
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Largest accepted report body in bytes
+pub const CLIENT_ERROR_MAX_BYTES: usize = 16 * 1024;
+
+/// Longest kept error message in bytes; longer messages are truncated
+const MAX_MESSAGE_BYTES: usize = 2 * 1024;
+
+/// Longest kept stack trace in bytes; longer traces are truncated
+const MAX_STACK_BYTES: usize = 8 * 1024;
+
+/// Longest kept URL, source file, or similar short field in bytes
+const MAX_FIELD_BYTES: usize = 512;
+
+/// Error reported by the frontend
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ClientErrorReport {
+    /// Error message
+    #[validate(length(min = 1))]
+    pub message: String,
+
+    /// Error type (e.g. `TypeError`)
+    pub name: Option<String>,
+
+    /// JavaScript stack trace
+    pub stack: Option<String>,
+
+    /// React component stack, for errors caught by an error boundary
+    pub component_stack: Option<String>,
+
+    /// Page the error occurred on
+    pub url: Option<String>,
+
+    /// Script the error was raised in
+    pub source: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+
+    /// Frontend build the error occurred in
+    pub release: Option<String>,
+
+    /// Browser session the error occurred in, for correlating reports
+    #[validate(length(max = 128))]
+    pub session_id: Option<String>,
+
+    /// Time the error occurred in the browser
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+impl ClientErrorReport {
+    /// Cut overly long fields down to their size caps
+    pub fn truncate(&mut self) {
+        truncate_bytes(&mut self.message, MAX_MESSAGE_BYTES);
+        for (field, max) in [
+            (&mut self.name, MAX_FIELD_BYTES),
+            (&mut self.stack, MAX_STACK_BYTES),
+            (&mut self.component_stack, MAX_STACK_BYTES),
+            (&mut self.url, MAX_FIELD_BYTES),
+            (&mut self.source, MAX_FIELD_BYTES),
+            (&mut self.release, MAX_FIELD_BYTES),
+        ] {
+            if let Some(value) = field {
+                truncate_bytes(value, max);
+            }
+        }
+    }
+}
+
+/// Acknowledgement of an ingested report
+#[derive(Debug, Serialize)]
+pub struct ClientErrorAccepted {
+    /// ID the report was logged under
+    pub id: Uuid,
+}
+
+/// Truncate a string to at most `max` bytes on a character boundary
+fn truncate_bytes(value: &mut String, max: usize) {
+    if value.len() <= max {
+        return;
+    }
+    let mut end = max;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    value.truncate(end);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_report() {
+        let mut report: ClientErrorReport = serde_json::from_value(serde_json::json!({
+            "message": "é".repeat(MAX_MESSAGE_BYTES),
+            "stack": "x".repeat(MAX_STACK_BYTES + 10),
+            "url": "/nodes",
+        }))
+        .unwrap();
+
+        report.truncate();
+        assert_eq!(report.message.len(), MAX_MESSAGE_BYTES);
+        assert_eq!(report.stack.unwrap().len(), MAX_STACK_BYTES);
+        assert_eq!(report.url.as_deref(), Some("/nodes"));
+    }
+}
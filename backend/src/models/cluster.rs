@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Lease whose holder runs the scheduled background jobs
+pub const BACKGROUND_JOBS_LEASE: &str = "background-jobs";
+
+/// Time-limited leadership of one instance
+#[derive(Debug, Clone, Serialize)]
+pub struct LeaderLease {
+    pub name: String,
+    /// Instance ID of the leader
+    pub holder: String,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub acquired_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Leadership as seen by the instance answering the request
+#[derive(Debug, Serialize)]
+pub struct ClusterStatus {
+    pub instance_id: String,
+    /// Whether this instance runs the background jobs
+    pub is_leader: bool,
+    /// Current lease, if any instance holds one
+    pub lease: Option<LeaderLease>,
+}
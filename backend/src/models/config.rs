@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::models::timestamp::parse_db_timestamp;
+
 /// Configuration node representing a tree structure for VyOS configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigNode {
@@ -77,6 +79,8 @@ pub enum ValidationRule {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigHistory {
     pub id: Uuid,
+    /// Node whose configuration the entry snapshotted
+    pub node_id: Uuid,
     pub config_snapshot: ConfigSnapshot,
     pub change_type: ConfigChangeType,
     pub changed_by: String,
@@ -116,9 +120,111 @@ pub enum ConfigCommitStatus {
     Partial,
 }
 
+impl ConfigChangeType {
+    /// Convert change type to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigChangeType::Retrieve => "retrieve",
+            ConfigChangeType::Configure => "configure",
+            ConfigChangeType::Generate => "generate",
+            ConfigChangeType::Rollback => "rollback",
+            ConfigChangeType::Import => "import",
+        }
+    }
+
+    /// Parse change type from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "retrieve" => ConfigChangeType::Retrieve,
+            "configure" => ConfigChangeType::Configure,
+            "rollback" => ConfigChangeType::Rollback,
+            "import" => ConfigChangeType::Import,
+            _ => ConfigChangeType::Generate,
+        }
+    }
+}
+
+impl ConfigCommitStatus {
+    /// Convert commit status to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigCommitStatus::Pending => "pending",
+            ConfigCommitStatus::Success => "success",
+            ConfigCommitStatus::Failed => "failed",
+            ConfigCommitStatus::Partial => "partial",
+        }
+    }
+
+    /// Parse commit status from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "pending" => ConfigCommitStatus::Pending,
+            "failed" => ConfigCommitStatus::Failed,
+            "partial" => ConfigCommitStatus::Partial,
+            _ => ConfigCommitStatus::Success,
+        }
+    }
+}
+
+/// Check run over a configuration before it is committed
+///
+/// Findings are reported as warnings and never block the commit. Rules can be
+/// turned off with `CONFIG_LINT_DISABLED_RULES`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigLintRule {
+    /// An interface facing the internet has no firewall
+    WanFirewall,
+    /// SSH accepts connections on every address
+    SshOpenToAny,
+    /// Neither a static default route nor DHCP provides one
+    DefaultRoute,
+    /// Neither a name server nor DHCP provides DNS
+    DnsUnset,
+}
+
+impl ConfigLintRule {
+    /// Every lint rule
+    pub const ALL: [ConfigLintRule; 4] = [
+        ConfigLintRule::WanFirewall,
+        ConfigLintRule::SshOpenToAny,
+        ConfigLintRule::DefaultRoute,
+        ConfigLintRule::DnsUnset,
+    ];
+
+    /// Name the rule is configured by
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigLintRule::WanFirewall => "wan-firewall",
+            ConfigLintRule::SshOpenToAny => "ssh-open-to-any",
+            ConfigLintRule::DefaultRoute => "default-route",
+            ConfigLintRule::DnsUnset => "dns-unset",
+        }
+    }
+}
+
+impl std::str::FromStr for ConfigLintRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|rule| rule.as_str() == s)
+            .ok_or_else(|| format!("unknown config lint rule '{}'", s))
+    }
+}
+
+/// Node a configuration endpoint taking nothing else acts on
+#[derive(Debug, Deserialize)]
+pub struct ConfigNodeQuery {
+    pub node_id: Uuid,
+}
+
 /// Configuration retrieve request
 #[derive(Debug, Deserialize)]
 pub struct ConfigRetrieveRequest {
+    /// Node to retrieve the configuration of
+    pub node_id: Uuid,
     /// Optional path to retrieve specific subtree
     pub path: Option<String>,
     /// Include default values
@@ -130,14 +236,34 @@ pub struct ConfigRetrieveRequest {
 /// Configuration retrieve response
 #[derive(Debug, Serialize)]
 pub struct ConfigRetrieveResponse {
+    pub node_id: Uuid,
     pub config_tree: ConfigNode,
+    /// When the tree was read from the node
     pub retrieved_at: DateTime<Utc>,
+    /// Whether the tree is the last one read from the node, as it cannot be
+    /// reached
+    pub stale: bool,
     pub node_count: usize,
+    /// SHA-256 of the paths, values, and node types of the tree
+    pub config_hash: String,
+    pub stats: ConfigTreeStats,
+}
+
+/// Shape of a configuration tree, computed in the same pass as its hash
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigTreeStats {
+    pub total_nodes: usize,
+    pub leaf_nodes: usize,
+    pub container_nodes: usize,
+    /// Depth of the deepest node, counting the root as depth 0
+    pub max_depth: usize,
 }
 
 /// Configuration set request
 #[derive(Debug, Deserialize)]
 pub struct ConfigSetRequest {
+    /// Node to configure
+    pub node_id: Uuid,
     /// Path to set configuration at
     pub path: String,
     /// Value to set (None for delete)
@@ -149,6 +275,8 @@ pub struct ConfigSetRequest {
 /// Configuration delete request
 #[derive(Debug, Deserialize)]
 pub struct ConfigDeleteRequest {
+    /// Node to configure
+    pub node_id: Uuid,
     /// Path to delete
     pub path: String,
     /// Whether to validate before deletion
@@ -166,6 +294,8 @@ pub struct ConfigSetResponse {
 /// Configuration generate (commit) request
 #[derive(Debug, Deserialize)]
 pub struct ConfigGenerateRequest {
+    /// Node whose configuration is committed
+    pub node_id: Uuid,
     /// Comment for the commit
     pub comment: String,
     /// Whether to save running config to startup config
@@ -180,7 +310,12 @@ pub struct ConfigGenerateResponse {
     pub success: bool,
     pub message: String,
     pub config_snapshot_id: Option<Uuid>,
+    /// Commit warnings and lint findings, answered as the warnings of the
+    /// response envelope
+    #[serde(skip)]
     pub warnings: Vec<String>,
+    /// Commit hash of the snapshot in the Git export repository
+    pub git_commit: Option<String>,
 }
 
 /// Configuration history response
@@ -193,6 +328,8 @@ pub struct ConfigHistoryResponse {
 /// Configuration rollback request
 #[derive(Debug, Deserialize)]
 pub struct ConfigRollbackRequest {
+    /// Node to roll back
+    pub node_id: Uuid,
     /// History entry ID to rollback to
     pub history_id: Uuid,
     /// Comment for the rollback
@@ -208,6 +345,9 @@ pub struct ConfigRollbackResponse {
     pub message: String,
     pub rolled_back_to: ConfigSnapshot,
     pub new_history_id: Uuid,
+    /// Commit hash of the snapshot in the Git export repository
+    pub git_commit: Option<String>,
+    pub warnings: Vec<String>,
 }
 
 /// Configuration diff result
@@ -219,9 +359,32 @@ pub struct ConfigDiffResult {
     pub additions: Vec<ConfigChange>,
     pub deletions: Vec<ConfigChange>,
     pub modifications: Vec<ConfigChange>,
+    /// Changes as unified diff text, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unified: Option<String>,
     pub generated_at: DateTime<Utc>,
 }
 
+/// How a configuration diff is rendered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigDiffFormat {
+    /// Additions, deletions, and modifications only
+    #[default]
+    Structured,
+    /// Also as unified diff text
+    Unified,
+}
+
+/// Configuration diff query parameters
+#[derive(Debug, Deserialize)]
+pub struct ConfigDiffQuery {
+    /// Node the snapshots were taken of
+    pub node_id: Uuid,
+    #[serde(default)]
+    pub format: ConfigDiffFormat,
+}
+
 /// Configuration change in diff
 #[derive(Debug, Serialize)]
 pub struct ConfigChange {
@@ -287,12 +450,23 @@ pub enum ValidationWarningType {
 /// Bulk configuration change request
 #[derive(Debug, Deserialize)]
 pub struct BulkConfigChangeRequest {
-    pub changes: Vec<ConfigSetRequest>,
+    /// Node to configure
+    pub node_id: Uuid,
+    pub changes: Vec<ConfigValueChange>,
     pub comment: String,
     pub validate: bool,
     pub stop_on_error: bool,
 }
 
+/// One change of a bulk configuration change
+#[derive(Debug, Deserialize)]
+pub struct ConfigValueChange {
+    /// Path to set configuration at
+    pub path: String,
+    /// Value to set (None for delete)
+    pub value: Option<String>,
+}
+
 /// Bulk configuration change response
 #[derive(Debug, Serialize)]
 pub struct BulkConfigChangeResponse {
@@ -312,6 +486,8 @@ pub struct ConfigChangeFailure {
 /// Configuration search request
 #[derive(Debug, Deserialize)]
 pub struct ConfigSearchRequest {
+    /// Node whose configuration is searched
+    pub node_id: Uuid,
     pub search_term: String,
     pub search_type: SearchType,
     pub path_filter: Option<String>,
@@ -331,4 +507,60 @@ pub enum SearchType {
 pub struct ConfigSearchResponse {
     pub results: Vec<ConfigNode>,
     pub total_count: usize,
-}
\ No newline at end of file
+}
+/// Configuration history database record
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigHistoryRecord {
+    pub id: String,
+    pub node_id: String,
+    pub user_id: Option<String>,
+    pub version: String,
+    pub config_data: String,
+    pub change_summary: Option<String>,
+    pub is_rollback_point: bool,
+    pub created_at: String,
+}
+
+/// Configuration change database record, holding the snapshot it produced
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigChangeRecord {
+    pub id: String,
+    pub node_id: String,
+    pub snapshot_id: String,
+    pub config_hash: String,
+    /// Configuration tree as JSON
+    pub snapshot: String,
+    pub change_type: String,
+    pub changed_by: String,
+    pub description: String,
+    pub is_rollback_point: bool,
+    pub commit_status: String,
+    pub created_at: String,
+}
+
+impl ConfigChangeRecord {
+    /// The snapshot the change produced
+    pub fn to_snapshot(&self) -> Result<ConfigSnapshot, serde_json::Error> {
+        Ok(ConfigSnapshot {
+            id: Uuid::parse_str(&self.snapshot_id).unwrap_or_else(|_| Uuid::nil()),
+            config_tree: serde_json::from_str(&self.snapshot)?,
+            hash: self.config_hash.clone(),
+            created_at: parse_db_timestamp(&self.created_at),
+        })
+    }
+
+    /// Convert database record to a history entry
+    pub fn to_history(&self) -> Result<ConfigHistory, serde_json::Error> {
+        Ok(ConfigHistory {
+            id: Uuid::parse_str(&self.id).unwrap_or_else(|_| Uuid::nil()),
+            node_id: Uuid::parse_str(&self.node_id).unwrap_or_else(|_| Uuid::nil()),
+            config_snapshot: self.to_snapshot()?,
+            change_type: ConfigChangeType::parse(&self.change_type),
+            changed_by: self.changed_by.clone(),
+            changed_at: parse_db_timestamp(&self.created_at),
+            description: self.description.clone(),
+            is_rollback_point: self.is_rollback_point,
+            commit_status: ConfigCommitStatus::parse(&self.commit_status),
+        })
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::storage::Compression;
+
+/// How a configuration backup was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigBackupKind {
+    /// Pulled by the periodic backup job
+    Scheduled,
+    /// Requested by a user
+    Manual,
+}
+
+impl ConfigBackupKind {
+    /// Convert kind to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigBackupKind::Scheduled => "scheduled",
+            ConfigBackupKind::Manual => "manual",
+        }
+    }
+
+    /// Parse kind from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "scheduled" => ConfigBackupKind::Scheduled,
+            _ => ConfigBackupKind::Manual,
+        }
+    }
+}
+
+/// Full configuration of a node, as pulled at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBackup {
+    pub id: Uuid,
+    pub node_id: Uuid,
+    pub kind: ConfigBackupKind,
+    /// SHA-256 of the configuration as JSON
+    pub config_hash: String,
+    pub compression: Compression,
+    /// Bytes of the configuration as JSON
+    pub size_bytes: u64,
+    /// Bytes stored in the database
+    pub stored_bytes: u64,
+    /// User who requested a manual backup
+    pub created_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Configuration backup with its content
+#[derive(Debug, Clone)]
+pub struct ConfigBackupArtifact {
+    pub backup: ConfigBackup,
+    /// Configuration as JSON, decompressed
+    pub content: Vec<u8>,
+}
+
+impl ConfigBackupArtifact {
+    /// File name offered to browsers
+    pub fn file_name(&self) -> String {
+        format!(
+            "config-{}-{}.json",
+            self.backup.node_id,
+            self.backup.created_at.format("%Y%m%d-%H%M%S")
+        )
+    }
+}
+
+/// Backups of a node, newest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfigBackupListResponse {
+    pub backups: Vec<ConfigBackup>,
+}
+
+/// Request to restore a backup to its node
+#[derive(Debug, Default, Deserialize)]
+pub struct RestoreConfigBackupRequest {
+    /// Only compute the operations restoring the backup, without applying
+    /// them
+    #[serde(default)]
+    pub dry_run: bool,
+    pub comment: Option<String>,
+}
+
+/// How many backups of a node are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigBackupRetention {
+    /// Newest backups kept regardless of age; at least one is always kept
+    pub keep_last: usize,
+    /// Days for which the newest backup of each day is kept
+    pub keep_daily_days: u32,
+}
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::config::ConfigChange;
+
+/// Stored snapshot a node's running configuration is expected to match
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigBaseline {
+    pub node_id: Uuid,
+    /// Snapshot of the node's configuration history, as returned by a commit
+    pub snapshot_id: Uuid,
+    /// Whether the scheduled check raises an alert when the node drifts
+    pub alert_on_drift: bool,
+    pub set_by: String,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub set_at: DateTime<Utc>,
+    /// Outcome of the last check; unset until the node is first checked
+    pub drifted: Option<bool>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+/// Request to mark a stored snapshot as the baseline of a node
+#[derive(Debug, Deserialize)]
+pub struct SetConfigBaselineRequest {
+    pub snapshot_id: Uuid,
+    #[serde(default = "default_alert_on_drift")]
+    pub alert_on_drift: bool,
+}
+
+fn default_alert_on_drift() -> bool {
+    true
+}
+
+/// Differences of a node's running configuration from its baseline
+#[derive(Debug, Serialize)]
+pub struct ConfigDriftReport {
+    pub baseline: ConfigBaseline,
+    pub drifted: bool,
+    /// Paths configured on the node but not in the baseline
+    pub additions: Vec<ConfigChange>,
+    /// Paths of the baseline missing from the node
+    pub deletions: Vec<ConfigChange>,
+    /// Paths whose value on the node differs from the baseline
+    pub modifications: Vec<ConfigChange>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub checked_at: DateTime<Utc>,
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::team::TeamRole;
+
+/// Lock on a configuration subtree
+///
+/// Only users the lock is assigned to may set or delete configuration at or
+/// below its path; administrators are never locked out. A lock names a team,
+/// a team role, or both:
+///
+/// - a team: any member of the team
+/// - a team and a role: members holding at least that role in the team
+/// - a role: users holding at least that role in any team
+/// - neither: administrators only
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigLock {
+    pub id: Uuid,
+    /// Locked configuration path, e.g. `protocols bgp`
+    pub path: String,
+    pub team_id: Option<Uuid>,
+    pub team_name: Option<String>,
+    pub role: Option<TeamRole>,
+    pub description: Option<String>,
+    pub created_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to lock a configuration subtree
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateConfigLockRequest {
+    /// Configuration path, with words separated by spaces or slashes
+    #[validate(length(min = 1, max = 512))]
+    pub path: String,
+    pub team_id: Option<Uuid>,
+    pub role: Option<TeamRole>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+/// Configuration locks, ordered by path
+#[derive(Debug, Serialize)]
+pub struct ConfigLockListResponse {
+    pub locks: Vec<ConfigLock>,
+}
@@ -0,0 +1,214 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::vyos_client::ConfigOperationKind;
+
+/// Most operations a session may stage
+pub const MAX_SESSION_OPERATIONS: usize = 500;
+
+/// Longest confirm timeout of a commit, in seconds
+pub const MAX_CONFIRM_TIMEOUT_SECS: u64 = 3600;
+
+/// Furthest ahead a commit may be scheduled, in days
+pub const MAX_SCHEDULE_AHEAD_DAYS: i64 = 90;
+
+/// State of a configuration session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSessionStatus {
+    /// Staging operations
+    Open,
+    /// Waiting to be committed by the scheduler at `apply_at`
+    Scheduled,
+    /// Committed, and rolled back unless confirmed in time
+    PendingConfirm,
+    /// Committed with a confirm timeout, and confirmed
+    Confirmed,
+    /// Committed without a confirm timeout
+    Committed,
+    /// Rolled back because it was not confirmed in time
+    RolledBack,
+    /// Closed without committing
+    Discarded,
+    /// The commit or the rollback failed; see the session's error
+    Failed,
+}
+
+impl ConfigSessionStatus {
+    /// Convert status to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigSessionStatus::Open => "open",
+            ConfigSessionStatus::Scheduled => "scheduled",
+            ConfigSessionStatus::PendingConfirm => "pending_confirm",
+            ConfigSessionStatus::Confirmed => "confirmed",
+            ConfigSessionStatus::Committed => "committed",
+            ConfigSessionStatus::RolledBack => "rolled_back",
+            ConfigSessionStatus::Discarded => "discarded",
+            ConfigSessionStatus::Failed => "failed",
+        }
+    }
+
+    /// Parse status from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "open" => ConfigSessionStatus::Open,
+            "scheduled" => ConfigSessionStatus::Scheduled,
+            "pending_confirm" => ConfigSessionStatus::PendingConfirm,
+            "confirmed" => ConfigSessionStatus::Confirmed,
+            "committed" => ConfigSessionStatus::Committed,
+            "rolled_back" => ConfigSessionStatus::RolledBack,
+            "discarded" => ConfigSessionStatus::Discarded,
+            _ => ConfigSessionStatus::Failed,
+        }
+    }
+}
+
+/// Set or delete operation staged in a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StagedOperation {
+    pub op: ConfigOperationKind,
+    /// Configuration path, with words separated by spaces or slashes
+    pub path: String,
+    /// Value to set; valueless nodes such as `disable` have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// What a staged operation changes in the configuration it was checked
+/// against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChangeEffect {
+    /// Sets a node or value that does not exist yet
+    Add,
+    /// Replaces the value of a single-value node
+    Replace,
+    /// Deletes an existing node
+    Remove,
+    /// Sets what is already set, or deletes what does not exist
+    Unchanged,
+}
+
+/// Staged operation with its effect, as previewed when a commit was
+/// scheduled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigChangePreview {
+    #[serde(flatten)]
+    pub operation: StagedOperation,
+    pub effect: ConfigChangeEffect,
+}
+
+/// Configuration change session against one node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSession {
+    pub id: Uuid,
+    pub node_id: Uuid,
+    /// User who opened the session
+    pub username: String,
+    pub status: ConfigSessionStatus,
+    pub operations: Vec<StagedOperation>,
+    pub comment: Option<String>,
+    /// Deadline for confirming a commit, after which it is rolled back
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
+    pub confirm_by: Option<DateTime<Utc>>,
+    /// When the scheduler commits a scheduled session
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
+    pub apply_at: Option<DateTime<Utc>>,
+    /// Effect of each operation on the configuration when the commit was
+    /// scheduled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diff: Vec<ConfigChangePreview>,
+    pub error: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to open a session
+#[derive(Debug, Deserialize)]
+pub struct OpenConfigSessionRequest {
+    pub node_id: Uuid,
+}
+
+/// Request to stage operations, appended to those already staged
+#[derive(Debug, Deserialize)]
+pub struct StageConfigOperationsRequest {
+    pub operations: Vec<StagedOperation>,
+}
+
+/// Request to commit a session
+#[derive(Debug, Default, Deserialize)]
+pub struct CommitConfigSessionRequest {
+    /// Roll the commit back unless it is confirmed within this many seconds
+    pub confirm_timeout_secs: Option<u64>,
+    pub comment: Option<String>,
+}
+
+/// Request to commit a session later
+#[derive(Debug, Deserialize)]
+pub struct ScheduleConfigSessionRequest {
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub apply_at: DateTime<Utc>,
+    pub comment: Option<String>,
+}
+
+/// Period during which scheduled commits are held back, given as
+/// `start/end` in RFC 3339
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FreezeWindow {
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub starts_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub ends_at: DateTime<Utc>,
+}
+
+impl FreezeWindow {
+    /// Whether a time falls in the window
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.starts_at <= at && at < self.ends_at
+    }
+}
+
+impl FromStr for FreezeWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (starts_at, ends_at) = s
+            .split_once('/')
+            .ok_or_else(|| format!("{} is not a start/end pair", s))?;
+        let parse = |time: &str| {
+            DateTime::parse_from_rfc3339(time.trim())
+                .map(|time| time.with_timezone(&Utc))
+                .map_err(|e| format!("{}: {}", time.trim(), e))
+        };
+        let window = Self {
+            starts_at: parse(starts_at)?,
+            ends_at: parse(ends_at)?,
+        };
+        if window.starts_at >= window.ends_at {
+            return Err(format!("{} ends before it starts", s));
+        }
+        Ok(window)
+    }
+}
+
+/// Session list query parameters
+#[derive(Debug, Deserialize)]
+pub struct ConfigSessionListQuery {
+    pub node_id: Option<Uuid>,
+    /// Include committed, rolled back, discarded, and failed sessions, not
+    /// only open, scheduled, and unconfirmed ones
+    #[serde(default)]
+    pub all: bool,
+}
+
+/// Sessions, newest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfigSessionListResponse {
+    pub sessions: Vec<ConfigSession>,
+}
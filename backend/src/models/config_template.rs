@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::vyos_client::ConfigOperation;
+
+/// Kind of value a template variable takes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateVariableKind {
+    /// Any text without quotes or line breaks
+    #[default]
+    String,
+    /// IPv4 or IPv6 address, e.g. `192.0.2.1`
+    Address,
+    /// Address with its prefix length, e.g. `192.0.2.1/24`
+    Prefix,
+    Integer,
+}
+
+/// Variable a template is rendered with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateVariable {
+    /// Name the body refers to the variable by, e.g. `lan_subnet`
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub kind: TemplateVariableKind,
+    /// Value used when rendering without one
+    pub default: Option<String>,
+    /// Whether a value must be given when the variable has no default;
+    /// optional variables without a value render as empty
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Reusable, parameterized configuration blueprint
+///
+/// The body is a Handlebars template rendering to `set` and `delete`
+/// configuration commands, one per line; blank lines and lines starting
+/// with `#` are ignored.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub body: String,
+    pub variables: Vec<TemplateVariable>,
+    pub created_by: Option<Uuid>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create configuration template request
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateConfigTemplateRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 100000))]
+    pub body: String,
+    #[serde(default)]
+    #[validate(length(max = 100))]
+    pub variables: Vec<TemplateVariable>,
+}
+
+/// Update configuration template request
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateConfigTemplateRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 100000))]
+    pub body: Option<String>,
+    #[validate(length(max = 100))]
+    pub variables: Option<Vec<TemplateVariable>>,
+}
+
+/// Configuration template list response
+#[derive(Debug, Serialize)]
+pub struct ConfigTemplateListResponse {
+    pub templates: Vec<ConfigTemplate>,
+    pub total: u64,
+}
+
+/// Request to render a template without applying it
+#[derive(Debug, Default, Deserialize)]
+pub struct RenderTemplateRequest {
+    /// Values of the template's variables, by name
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+/// Configuration a template renders to
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplatePreview {
+    /// Rendered commands, as applied
+    pub commands: Vec<String>,
+    pub operations: Vec<ConfigOperation>,
+}
+
+/// Request to apply a rendered template to nodes
+#[derive(Debug, Deserialize, Validate)]
+pub struct ApplyTemplateRequest {
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Nodes to change; every node the caller can access if unset
+    #[validate(length(min = 1, max = 1000))]
+    pub node_ids: Option<Vec<Uuid>>,
+}
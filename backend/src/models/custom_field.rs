@@ -0,0 +1,257 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::AppError;
+
+/// Custom fields that can be defined
+pub const MAX_CUSTOM_FIELDS: usize = 50;
+
+/// Longest text value of a custom field, in characters
+pub const MAX_CUSTOM_FIELD_TEXT: usize = 1024;
+
+/// Date format of date fields
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Type of the values of a custom field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    Text,
+    Integer,
+    Number,
+    Boolean,
+    /// Calendar date written as `YYYY-MM-DD`
+    Date,
+    /// One of the field's options
+    Select,
+}
+
+impl CustomFieldType {
+    /// Convert type to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Integer => "integer",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Boolean => "boolean",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Select => "select",
+        }
+    }
+
+    /// Parse type from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "integer" => CustomFieldType::Integer,
+            "number" => CustomFieldType::Number,
+            "boolean" => CustomFieldType::Boolean,
+            "date" => CustomFieldType::Date,
+            "select" => CustomFieldType::Select,
+            _ => CustomFieldType::Text,
+        }
+    }
+
+    /// Turn a value stored for a field of this type back into a typed value
+    pub fn decode(&self, stored: &str) -> Value {
+        let typed = match self {
+            CustomFieldType::Integer => stored.parse::<i64>().ok().map(Value::from),
+            CustomFieldType::Number => stored.parse::<f64>().ok().map(Value::from),
+            CustomFieldType::Boolean => stored.parse::<bool>().ok().map(Value::from),
+            _ => None,
+        };
+        typed.unwrap_or_else(|| Value::String(stored.to_string()))
+    }
+}
+
+/// Admin-defined field of the node inventory, such as a site code or an
+/// asset tag
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomField {
+    pub id: Uuid,
+    /// Key of the field in `custom_fields` of nodes, e.g. `site_code`
+    pub name: String,
+    /// Name shown to people, e.g. "Site code"
+    pub label: String,
+    pub field_type: CustomFieldType,
+    /// Whether nodes must be created with a value, which cannot be cleared
+    pub required: bool,
+    /// Values of select fields
+    pub options: Vec<String>,
+    /// Regular expression text values must match as a whole
+    pub pattern: Option<String>,
+    pub description: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CustomField {
+    /// Check a value given for this field, returning it as stored
+    ///
+    /// Values are stored as text in a canonical form, so equal values
+    /// compare equal when filtering.
+    pub fn normalize(&self, value: &Value) -> Result<String, AppError> {
+        let invalid = |expected: &str| AppError::Validation(format!("Field {} takes {}", self.name, expected));
+
+        match self.field_type {
+            CustomFieldType::Text => {
+                let text = value.as_str().ok_or_else(|| invalid("text"))?;
+                if text.chars().count() > MAX_CUSTOM_FIELD_TEXT {
+                    return Err(invalid(&format!("at most {} characters", MAX_CUSTOM_FIELD_TEXT)));
+                }
+                if let Some(pattern) = &self.pattern {
+                    if !anchored_pattern(pattern)?.is_match(text) {
+                        return Err(invalid(&format!("text matching {}", pattern)));
+                    }
+                }
+                Ok(text.to_string())
+            }
+            CustomFieldType::Integer => value.as_i64().map(|n| n.to_string()).ok_or_else(|| invalid("an integer")),
+            CustomFieldType::Number => value
+                .as_f64()
+                .filter(|n| n.is_finite())
+                .map(|n| n.to_string())
+                .ok_or_else(|| invalid("a number")),
+            CustomFieldType::Boolean => value.as_bool().map(|b| b.to_string()).ok_or_else(|| invalid("true or false")),
+            CustomFieldType::Date => value
+                .as_str()
+                .and_then(|s| NaiveDate::parse_from_str(s, DATE_FORMAT).ok())
+                .map(|date| date.format(DATE_FORMAT).to_string())
+                .ok_or_else(|| invalid("a date like 2026-01-31")),
+            CustomFieldType::Select => value
+                .as_str()
+                .filter(|s| self.options.iter().any(|option| option == s))
+                .map(str::to_string)
+                .ok_or_else(|| invalid(&format!("one of {}", self.options.join(", ")))),
+        }
+    }
+
+    /// Check a value written as text, as in query strings
+    pub fn normalize_text(&self, text: &str) -> Result<String, AppError> {
+        let value = match self.field_type {
+            CustomFieldType::Integer | CustomFieldType::Number | CustomFieldType::Boolean => {
+                serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+            }
+            _ => Value::String(text.to_string()),
+        };
+        self.normalize(&value)
+    }
+
+    /// Turn a stored value back into a typed value
+    pub fn decode(&self, stored: &str) -> Value {
+        self.field_type.decode(stored)
+    }
+}
+
+/// Compile a pattern so that it has to match a whole value
+pub fn anchored_pattern(pattern: &str) -> Result<Regex, AppError> {
+    Regex::new(&format!("^(?:{})$", pattern))
+        .map_err(|e| AppError::Validation(format!("Invalid pattern {}: {}", pattern, e)))
+}
+
+/// Custom field value as plain text, for exports; empty when unset
+pub fn custom_field_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Request to define a custom field
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateCustomFieldRequest {
+    /// Lowercase letters, digits, and underscores, starting with a letter
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    #[validate(length(min = 1, max = 100))]
+    pub label: String,
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub options: Vec<String>,
+    pub pattern: Option<String>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+/// Request to update a custom field; absent fields are kept
+///
+/// The name and type of a field cannot change, since values are stored by
+/// them.
+#[derive(Debug, Clone, Default, Deserialize, Validate)]
+pub struct UpdateCustomFieldRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub label: Option<String>,
+    pub required: Option<bool>,
+    pub options: Option<Vec<String>>,
+    pub pattern: Option<String>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(field_type: CustomFieldType) -> CustomField {
+        CustomField {
+            id: Uuid::new_v4(),
+            name: "field".to_string(),
+            label: "Field".to_string(),
+            field_type,
+            required: false,
+            options: vec!["ams1".to_string(), "fra2".to_string()],
+            pattern: None,
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_typed_values() {
+        let integer = field(CustomFieldType::Integer);
+        assert_eq!(integer.normalize(&json!(42)).unwrap(), "42");
+        assert!(integer.normalize(&json!("42")).is_err());
+        assert!(integer.normalize(&json!(4.2)).is_err());
+        assert_eq!(integer.normalize_text("42").unwrap(), "42");
+        assert_eq!(integer.decode("42"), json!(42));
+
+        let number = field(CustomFieldType::Number);
+        assert_eq!(number.normalize(&json!(2.5)).unwrap(), "2.5");
+        assert_eq!(number.normalize_text("2.50").unwrap(), "2.5");
+        assert_eq!(number.decode("2.5"), json!(2.5));
+
+        let boolean = field(CustomFieldType::Boolean);
+        assert_eq!(boolean.normalize(&json!(true)).unwrap(), "true");
+        assert!(boolean.normalize_text("yes").is_err());
+        assert_eq!(boolean.decode("false"), json!(false));
+
+        let date = field(CustomFieldType::Date);
+        assert_eq!(date.normalize(&json!("2026-02-09")).unwrap(), "2026-02-09");
+        assert!(date.normalize(&json!("2026-02-30")).is_err());
+        assert_eq!(date.decode("2026-02-09"), json!("2026-02-09"));
+
+        let select = field(CustomFieldType::Select);
+        assert_eq!(select.normalize(&json!("fra2")).unwrap(), "fra2");
+        assert!(select.normalize(&json!("lon1")).is_err());
+    }
+
+    #[test]
+    fn test_text_pattern_matches_whole_value() {
+        let mut text = field(CustomFieldType::Text);
+        text.pattern = Some("[A-Z]{3}[0-9]".to_string());
+        assert_eq!(text.normalize(&json!("AMS1")).unwrap(), "AMS1");
+        assert!(text.normalize(&json!("AMS12")).is_err());
+        assert!(text.normalize(&json!(1)).is_err());
+        assert!(anchored_pattern("(").is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::vyos_client::ConfigOperation;
+
+/// Most `set` commands a desired state may consist of
+pub const MAX_DESIRED_COMMANDS: usize = 10_000;
+
+/// Desired configuration of a node
+///
+/// Exactly one of `config` and `commands` must be given. Everything below
+/// `path` that the desired state leaves out is deleted from the node.
+#[derive(Debug, Default, Deserialize)]
+pub struct DesiredStateRequest {
+    /// Part of the configuration the desired state covers, as space-separated
+    /// words such as `interfaces ethernet`; the whole configuration if unset
+    pub path: Option<String>,
+    /// Desired configuration below `path`, shaped like a retrieved
+    /// configuration
+    pub config: Option<Value>,
+    /// Desired configuration as `set` commands with full paths, one per item
+    pub commands: Option<Vec<String>>,
+    /// Only compute the operations, without applying them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Hash of the configuration a preview was computed against; the apply
+    /// is refused if the node's configuration has changed since
+    pub base_hash: Option<String>,
+    pub comment: Option<String>,
+}
+
+/// Operations converging a node to its desired state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DesiredStatePlan {
+    pub node_id: Uuid,
+    /// Hash of the node's configuration below the path, before the change
+    pub base_hash: String,
+    /// Deletes followed by sets, applied as one commit
+    pub operations: Vec<ConfigOperation>,
+    pub deletes: usize,
+    pub sets: usize,
+    /// Whether the operations were applied; false for dry runs and when the
+    /// node already is in the desired state
+    pub applied: bool,
+}
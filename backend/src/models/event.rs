@@ -0,0 +1,104 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// Persisted events are kept this long for replay
+pub const EVENT_RETENTION: Duration = Duration::days(7);
+
+/// Events returned by one replay request unless a smaller limit is asked for
+pub const MAX_REPLAY_EVENTS: u32 = 1000;
+
+/// Prefixes of channels carrying the data of a single node, as in
+/// `config:{node_id}`
+pub const NODE_CHANNEL_PREFIXES: [&str; 3] = ["node", "config", "metrics"];
+
+/// Prefix of channels only administrators may subscribe to
+pub const ADMIN_CHANNEL_PREFIX: &str = "admin";
+
+/// Who may subscribe to a WebSocket channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelScope {
+    /// Any authenticated user, e.g. `incidents`
+    Public,
+    /// Users who may read the node
+    Node(Uuid),
+    /// Administrators only
+    Admin,
+}
+
+impl ChannelScope {
+    /// Determine the scope of a channel from its name
+    pub fn of(channel: &str) -> Result<Self, AppError> {
+        let Some((prefix, rest)) = channel.split_once(':') else {
+            return Ok(ChannelScope::Public);
+        };
+
+        if prefix == ADMIN_CHANNEL_PREFIX {
+            return Ok(ChannelScope::Admin);
+        }
+        if NODE_CHANNEL_PREFIXES.contains(&prefix) {
+            return Uuid::parse_str(rest)
+                .map(ChannelScope::Node)
+                .map_err(|_| AppError::Validation(format!("Invalid node ID in channel {}", channel)));
+        }
+
+        Err(AppError::Validation(format!("Unknown channel {}", channel)))
+    }
+}
+
+/// Domain event published on a WebSocket channel
+///
+/// Every event is persisted with a sequence number before it is broadcast,
+/// so clients that missed events while disconnected can replay them from
+/// the last sequence number they saw.
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainEvent {
+    /// Increases with every published event
+    pub sequence: u64,
+    pub channel: String,
+    pub data: serde_json::Value,
+    /// Sent to every connection, not just subscribers of the channel
+    pub to_all: bool,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Event replay query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct EventReplayQuery {
+    /// Return events with a higher sequence number (default: 0)
+    #[serde(default)]
+    pub after: u64,
+    /// Comma-separated channels; events sent to every connection are always
+    /// included
+    pub channels: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// Replayed events, oldest first
+#[derive(Debug, Serialize)]
+pub struct EventReplayResponse {
+    pub events: Vec<DomainEvent>,
+    /// Sequence number to continue from
+    pub cursor: u64,
+    /// Whether more events than the limit were available
+    pub has_more: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_scope() {
+        let node_id = Uuid::new_v4();
+        assert_eq!(ChannelScope::of("incidents").unwrap(), ChannelScope::Public);
+        assert_eq!(ChannelScope::of(&format!("config:{}", node_id)).unwrap(), ChannelScope::Node(node_id));
+        assert_eq!(ChannelScope::of(&format!("metrics:{}", node_id)).unwrap(), ChannelScope::Node(node_id));
+        assert_eq!(ChannelScope::of("admin:cluster").unwrap(), ChannelScope::Admin);
+        assert!(matches!(ChannelScope::of("config:edge-1"), Err(AppError::Validation(_))));
+        assert!(matches!(ChannelScope::of("teams:netops"), Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,188 @@
+//! Sparse fieldsets
+//!
+//! Endpoints returning large objects accept a `fields` query parameter
+//! listing the fields to return, e.g. `?fields=id,name,cpu.usage_percent`.
+//! Dotted paths select fields of nested objects, and a selection applied to
+//! an array applies to each element. Selecting a field keeps it whole;
+//! unknown fields are ignored, since optional fields may be left out of a
+//! response anyway. Without the parameter, objects are returned in full.
+
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::future::{ready, Ready};
+
+use crate::error::AppError;
+
+/// Maximum number of field paths in one selection
+pub const MAX_SELECTED_FIELDS: usize = 64;
+
+/// Maximum nesting depth of a field path
+const MAX_FIELD_DEPTH: usize = 8;
+
+/// Fields selected by the `fields` query parameter
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldSelection {
+    /// Selected fields by name; `None` selects everything
+    fields: Option<BTreeMap<String, FieldSelection>>,
+}
+
+impl FieldSelection {
+    /// Select every field
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Parse a comma-separated list of dotted field paths
+    pub fn parse(fields: &str) -> Result<Self, AppError> {
+        let paths: Vec<&str> = fields.split(',').map(str::trim).filter(|path| !path.is_empty()).collect();
+        if paths.is_empty() {
+            return Ok(Self::all());
+        }
+        if paths.len() > MAX_SELECTED_FIELDS {
+            return Err(AppError::Validation(format!(
+                "At most {} fields can be selected",
+                MAX_SELECTED_FIELDS
+            )));
+        }
+
+        let mut selection = Self::default();
+        for path in paths {
+            let names: Vec<&str> = path.split('.').collect();
+            if names.len() > MAX_FIELD_DEPTH || names.iter().any(|name| name.is_empty()) {
+                return Err(AppError::Validation(format!("Invalid field path: {}", path)));
+            }
+            selection.insert(&names);
+        }
+
+        Ok(selection)
+    }
+
+    fn insert(&mut self, names: &[&str]) {
+        let Some((name, rest)) = names.split_first() else {
+            return;
+        };
+        let fields = self.fields.get_or_insert_with(BTreeMap::new);
+        if rest.is_empty() {
+            fields.insert(name.to_string(), Self::all());
+            return;
+        }
+        let child = fields.entry(name.to_string()).or_insert_with(|| Self {
+            fields: Some(BTreeMap::new()),
+        });
+        // Nothing to narrow down when the whole field is selected already
+        if !child.is_all() {
+            child.insert(rest);
+        }
+    }
+
+    /// Whether every field is selected
+    pub fn is_all(&self) -> bool {
+        self.fields.is_none()
+    }
+
+    /// Serialize a value, keeping only the selected fields
+    pub fn project<T: Serialize>(&self, value: &T) -> Result<Value, AppError> {
+        let value = serde_json::to_value(value)?;
+        Ok(self.apply(value))
+    }
+
+    /// Serialize a list response, keeping only the selected fields of the
+    /// items under `items` and the rest of the response as is
+    pub fn project_items<T: Serialize>(&self, value: &T, items: &str) -> Result<Value, AppError> {
+        let mut value = serde_json::to_value(value)?;
+        if let Some(list) = value.get_mut(items) {
+            *list = self.apply(list.take());
+        }
+        Ok(value)
+    }
+
+    fn apply(&self, value: Value) -> Value {
+        let Some(fields) = &self.fields else {
+            return value;
+        };
+        match value {
+            Value::Object(object) => {
+                let mut projected = Map::new();
+                for (name, field) in object {
+                    if let Some(selection) = fields.get(&name) {
+                        projected.insert(name, selection.apply(field));
+                    }
+                }
+                Value::Object(projected)
+            }
+            Value::Array(items) => Value::Array(items.into_iter().map(|item| self.apply(item)).collect()),
+            scalar => scalar,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FieldsQuery {
+    fields: Option<String>,
+}
+
+/// Extract the selection from the `fields` query parameter
+impl FromRequest for FieldSelection {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let selection = web::Query::<FieldsQuery>::from_query(req.query_string())
+            .map_err(|e| AppError::Validation(format!("Invalid query string: {}", e)))
+            .and_then(|query| match &query.fields {
+                Some(fields) => FieldSelection::parse(fields),
+                None => Ok(FieldSelection::all()),
+            });
+        ready(selection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_project_nested_fields() {
+        let metrics = json!({
+            "node_id": "edge-1",
+            "cpu": { "usage_percent": 25.5, "core_count": 4 },
+            "network": [
+                { "interface": "eth0", "rx_bytes": 10 },
+                { "interface": "eth1", "rx_bytes": 20 },
+            ],
+        });
+
+        let selection = FieldSelection::parse("node_id, cpu.usage_percent,network.interface,missing").unwrap();
+        assert_eq!(
+            selection.project(&metrics).unwrap(),
+            json!({
+                "node_id": "edge-1",
+                "cpu": { "usage_percent": 25.5 },
+                "network": [{ "interface": "eth0" }, { "interface": "eth1" }],
+            })
+        );
+
+        // Selecting a whole field wins over selecting some of its fields
+        let selection = FieldSelection::parse("cpu.core_count,cpu").unwrap();
+        assert_eq!(selection, FieldSelection::parse("cpu").unwrap());
+        assert_eq!(selection.project(&metrics).unwrap()["cpu"], metrics["cpu"]);
+
+        assert!(FieldSelection::parse("").unwrap().is_all());
+        assert_eq!(FieldSelection::all().project(&metrics).unwrap(), metrics);
+        assert!(FieldSelection::parse("cpu..usage_percent").is_err());
+    }
+
+    #[test]
+    fn test_project_items_keeps_pagination() {
+        let list = json!({ "nodes": [{ "id": 1, "name": "edge-1", "host": "10.0.0.1" }], "total": 1 });
+
+        let selection = FieldSelection::parse("id,name").unwrap();
+        assert_eq!(
+            selection.project_items(&list, "nodes").unwrap(),
+            json!({ "nodes": [{ "id": 1, "name": "edge-1" }], "total": 1 })
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Rule set names standing for the base chains of a family rather than for
+/// named rule sets
+pub const BASE_CHAINS: &[&str] = &["forward", "input", "output"];
+
+/// Highest rule number VyOS accepts
+pub const MAX_RULE_NUMBER: u32 = 999_999;
+
+/// Address family of a rule set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirewallFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl FirewallFamily {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ipv4 => "ipv4",
+            Self::Ipv6 => "ipv6",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ipv4" => Some(Self::Ipv4),
+            "ipv6" => Some(Self::Ipv6),
+            _ => None,
+        }
+    }
+}
+
+/// What a rule, or a rule set by default, does with a matching packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirewallAction {
+    Accept,
+    Drop,
+    Reject,
+    /// Continue in the rule set named by the rule's `jump_target`
+    Jump,
+    /// Return to the rule set that jumped here
+    Return,
+    /// Go on with the next rule
+    Continue,
+}
+
+impl FirewallAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accept => "accept",
+            Self::Drop => "drop",
+            Self::Reject => "reject",
+            Self::Jump => "jump",
+            Self::Return => "return",
+            Self::Continue => "continue",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "accept" => Some(Self::Accept),
+            "drop" => Some(Self::Drop),
+            "reject" => Some(Self::Reject),
+            "jump" => Some(Self::Jump),
+            "return" => Some(Self::Return),
+            "continue" => Some(Self::Continue),
+            _ => None,
+        }
+    }
+}
+
+/// Connection tracking state a rule matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionState {
+    Established,
+    Related,
+    New,
+    Invalid,
+}
+
+impl ConnectionState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Established => "established",
+            Self::Related => "related",
+            Self::New => "new",
+            Self::Invalid => "invalid",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "established" => Some(Self::Established),
+            "related" => Some(Self::Related),
+            "new" => Some(Self::New),
+            "invalid" => Some(Self::Invalid),
+            _ => None,
+        }
+    }
+}
+
+/// Source or destination a rule matches
+///
+/// Unset criteria match everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallMatch {
+    /// Address or network, e.g. `192.0.2.0/24`
+    pub address: Option<String>,
+    /// Ports, e.g. `22`, `8000-8080`, or `http,https`
+    pub port: Option<String>,
+    /// Address group of the rule set's family
+    pub address_group: Option<String>,
+    /// Network group of the rule set's family
+    pub network_group: Option<String>,
+    pub port_group: Option<String>,
+}
+
+impl FirewallMatch {
+    /// Whether no criterion is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Settings of a firewall rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallRuleSettings {
+    pub action: FirewallAction,
+    /// Rule set to continue in, for the `jump` action
+    pub jump_target: Option<String>,
+    pub description: Option<String>,
+    /// Protocol name or number, e.g. `tcp`, `tcp_udp`, or `47`
+    pub protocol: Option<String>,
+    #[serde(default, skip_serializing_if = "FirewallMatch::is_empty")]
+    pub source: FirewallMatch,
+    #[serde(default, skip_serializing_if = "FirewallMatch::is_empty")]
+    pub destination: FirewallMatch,
+    #[serde(default)]
+    pub state: Vec<ConnectionState>,
+    /// Whether matching packets are logged
+    #[serde(default)]
+    pub log: bool,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+/// Numbered rule of a rule set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallRule {
+    pub number: u32,
+    #[serde(flatten)]
+    pub settings: FirewallRuleSettings,
+}
+
+/// Settings of a rule set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallRuleSetSettings {
+    /// Action for packets no rule matched; base chains accept or drop
+    pub default_action: Option<FirewallAction>,
+    pub description: Option<String>,
+    /// Rules, evaluated in ascending order of their numbers
+    #[serde(default)]
+    pub rules: Vec<FirewallRule>,
+}
+
+/// Rule set of a node: a base chain (`forward`, `input`, or `output`) or a
+/// named rule set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FirewallRuleSet {
+    pub family: FirewallFamily,
+    pub name: String,
+    /// Configuration path of the rule set, e.g. `firewall ipv4 name WAN_IN`
+    pub path: String,
+    #[serde(flatten)]
+    pub settings: FirewallRuleSetSettings,
+}
+
+/// Request to reorder the rules of a rule set
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ReorderFirewallRulesRequest {
+    /// Every rule number of the rule set, in the new order; the rules take
+    /// the set's existing numbers in ascending order
+    #[validate(length(min = 1, max = 10000))]
+    pub rules: Vec<u32>,
+}
+
+/// Kind of a firewall group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FirewallGroupKind {
+    AddressGroup,
+    NetworkGroup,
+    PortGroup,
+    Ipv6AddressGroup,
+    Ipv6NetworkGroup,
+}
+
+impl FirewallGroupKind {
+    pub const ALL: [Self; 5] = [
+        Self::AddressGroup,
+        Self::NetworkGroup,
+        Self::PortGroup,
+        Self::Ipv6AddressGroup,
+        Self::Ipv6NetworkGroup,
+    ];
+
+    /// Name of the kind, as in the configuration
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AddressGroup => "address-group",
+            Self::NetworkGroup => "network-group",
+            Self::PortGroup => "port-group",
+            Self::Ipv6AddressGroup => "ipv6-address-group",
+            Self::Ipv6NetworkGroup => "ipv6-network-group",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == name)
+    }
+
+    /// Configuration node holding the members of a group
+    pub fn member_key(&self) -> &'static str {
+        match self {
+            Self::AddressGroup | Self::Ipv6AddressGroup => "address",
+            Self::NetworkGroup | Self::Ipv6NetworkGroup => "network",
+            Self::PortGroup => "port",
+        }
+    }
+}
+
+/// Settings of a firewall group
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallGroupSettings {
+    pub description: Option<String>,
+    /// Addresses, networks, or ports, by the kind of the group
+    pub members: Vec<String>,
+}
+
+/// Firewall group of a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FirewallGroup {
+    pub kind: FirewallGroupKind,
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: FirewallGroupSettings,
+}
+
+/// Traffic from another zone and the rule sets filtering it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallZoneFrom {
+    pub zone: String,
+    /// Named IPv4 rule set
+    pub ipv4_rule_set: Option<String>,
+    /// Named IPv6 rule set
+    pub ipv6_rule_set: Option<String>,
+}
+
+/// Settings of a firewall zone
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallZoneSettings {
+    pub description: Option<String>,
+    /// Action for traffic no rule set accepts: drop or reject
+    pub default_action: Option<FirewallAction>,
+    /// Whether the zone stands for the router itself, with no interfaces
+    #[serde(default)]
+    pub local_zone: bool,
+    #[serde(default)]
+    pub interfaces: Vec<String>,
+    #[serde(default)]
+    pub from: Vec<FirewallZoneFrom>,
+}
+
+/// Firewall zone of a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FirewallZone {
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: FirewallZoneSettings,
+}
+
+/// Firewall configuration of a node
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FirewallConfig {
+    pub rule_sets: Vec<FirewallRuleSet>,
+    pub groups: Vec<FirewallGroup>,
+    pub zones: Vec<FirewallZone>,
+}
@@ -0,0 +1,184 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::geoip::GeoIpInfo;
+use crate::models::time_range::{RelativeDuration, TimePreset};
+
+/// Firewall log entries are kept this long
+pub const FIREWALL_LOG_RETENTION: Duration = Duration::days(7);
+
+/// Default time window of rule hit statistics
+pub const DEFAULT_RULE_HITS_WINDOW: Duration = Duration::hours(24);
+
+/// Most recent entries returned per rule unless a smaller number is asked for
+pub const MAX_RECENT_RULE_HITS: u32 = 50;
+
+/// What a firewall rule did with a logged packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirewallLogAction {
+    Accept,
+    Drop,
+    Reject,
+    Continue,
+    Jump,
+    Return,
+    Queue,
+    Unknown,
+}
+
+impl FirewallLogAction {
+    /// Action of a VyOS log prefix suffix (`A`, `D`, `R`, ...)
+    pub fn from_suffix(suffix: &str) -> Self {
+        match suffix {
+            "A" => FirewallLogAction::Accept,
+            "D" => FirewallLogAction::Drop,
+            "R" => FirewallLogAction::Reject,
+            "C" => FirewallLogAction::Continue,
+            "J" => FirewallLogAction::Jump,
+            "RT" => FirewallLogAction::Return,
+            "Q" => FirewallLogAction::Queue,
+            _ => FirewallLogAction::Unknown,
+        }
+    }
+
+    /// Stored representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FirewallLogAction::Accept => "accept",
+            FirewallLogAction::Drop => "drop",
+            FirewallLogAction::Reject => "reject",
+            FirewallLogAction::Continue => "continue",
+            FirewallLogAction::Jump => "jump",
+            FirewallLogAction::Return => "return",
+            FirewallLogAction::Queue => "queue",
+            FirewallLogAction::Unknown => "unknown",
+        }
+    }
+
+    /// Parse the stored representation
+    pub fn from_db(s: &str) -> Self {
+        match s {
+            "accept" => FirewallLogAction::Accept,
+            "drop" => FirewallLogAction::Drop,
+            "reject" => FirewallLogAction::Reject,
+            "continue" => FirewallLogAction::Continue,
+            "jump" => FirewallLogAction::Jump,
+            "return" => FirewallLogAction::Return,
+            "queue" => FirewallLogAction::Queue,
+            _ => FirewallLogAction::Unknown,
+        }
+    }
+}
+
+/// Packet logged by a firewall rule
+#[derive(Debug, Clone, Serialize)]
+pub struct FirewallLogEntry {
+    pub id: i64,
+    pub node_id: Uuid,
+    pub node_name: String,
+    /// When the log line was received
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub logged_at: DateTime<Utc>,
+    /// Log prefix of the rule without the action, e.g. `ipv4-FWD-filter-10`
+    pub rule_id: Option<String>,
+    /// Configuration path of the rule, e.g. `firewall ipv4 forward filter rule 10`
+    pub rule_path: Option<String>,
+    /// Rule set the rule belongs to, e.g. `ipv4-FWD-filter` or `WAN_IN`
+    pub chain: String,
+    /// Rule number, or `None` for the rule set's default action
+    pub rule_number: Option<u32>,
+    pub action: FirewallLogAction,
+    pub protocol: Option<String>,
+    pub in_interface: Option<String>,
+    pub out_interface: Option<String>,
+    pub src_ip: Option<String>,
+    pub src_port: Option<u16>,
+    pub dst_ip: Option<String>,
+    pub dst_port: Option<u16>,
+    /// Packet length in bytes
+    pub length: Option<u32>,
+    /// Location of an external source address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub src_geo: Option<GeoIpInfo>,
+    /// Location of an external destination address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dst_geo: Option<GeoIpInfo>,
+    /// Log line as received
+    pub message: String,
+}
+
+/// Firewall log query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct FirewallLogQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub node_id: Option<Uuid>,
+    pub rule_id: Option<String>,
+    pub action: Option<FirewallLogAction>,
+    /// Protocol name as logged, e.g. `TCP`; matched case-insensitively
+    pub protocol: Option<String>,
+    pub src_ip: Option<String>,
+    pub dst_ip: Option<String>,
+    pub dst_port: Option<u16>,
+    /// Only entries at or after this time, absolute or relative (`-6h`)
+    #[serde(default, deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub from: Option<DateTime<Utc>>,
+    /// Only entries before this time
+    #[serde(default, deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub to: Option<DateTime<Utc>>,
+    /// Span ending at `to`, or now, such as `7d`
+    pub window: Option<RelativeDuration>,
+    /// Named time range replacing `from` and `to`
+    pub preset: Option<TimePreset>,
+}
+
+/// Paginated firewall log response, newest entries first
+#[derive(Debug, Serialize)]
+pub struct FirewallLogListResponse {
+    pub entries: Vec<FirewallLogEntry>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+}
+
+/// Rule hit statistics query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct FirewallRuleHitsQuery {
+    pub node_id: Option<Uuid>,
+    /// Only hits at or after this time, absolute or relative (`-6h`);
+    /// defaults to the last 24 hours
+    #[serde(default, deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub since: Option<DateTime<Utc>>,
+    /// Recent entries returned per rule, at most 50; defaults to 5
+    pub recent: Option<u32>,
+}
+
+/// Packets logged by one firewall rule of a node
+#[derive(Debug, Serialize)]
+pub struct FirewallRuleHits {
+    pub node_id: Uuid,
+    pub node_name: String,
+    pub rule_id: String,
+    pub rule_path: Option<String>,
+    pub chain: String,
+    pub rule_number: Option<u32>,
+    /// Action of the most recent hit
+    pub action: FirewallLogAction,
+    pub hits: u64,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub last_hit: DateTime<Utc>,
+    /// Most recent hits, newest first
+    pub recent: Vec<FirewallLogEntry>,
+}
+
+/// Hit statistics of the firewall rules that logged packets
+#[derive(Debug, Serialize)]
+pub struct FirewallRuleHitsResponse {
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub since: DateTime<Utc>,
+    /// Rules with the most hits first
+    pub rules: Vec<FirewallRuleHits>,
+}
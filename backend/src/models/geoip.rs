@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Most addresses looked up in one batch request
+pub const MAX_GEOIP_LOOKUPS: usize = 1000;
+
+/// Query for the location of one IP address
+#[derive(Debug, Deserialize)]
+pub struct GeoIpQuery {
+    pub ip: String,
+}
+
+/// Request for the locations of several IP addresses
+#[derive(Debug, Deserialize)]
+pub struct GeoIpBatchRequest {
+    pub ips: Vec<String>,
+}
+
+/// Country and network an external IP address belongs to
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GeoIpInfo {
+    /// ISO 3166-1 alpha-2 country code, e.g. `DE`
+    pub country_code: Option<String>,
+    /// English country name
+    pub country_name: Option<String>,
+    /// Autonomous system number
+    pub asn: Option<u32>,
+    /// Organization the autonomous system is registered to
+    pub as_organization: Option<String>,
+}
+
+/// Location of an IP address
+#[derive(Debug, Clone, Serialize)]
+pub struct GeoIpLookup {
+    pub ip: String,
+    /// Set for publicly routable addresses; private, loopback, and other
+    /// special-purpose addresses are never looked up
+    pub external: bool,
+    /// Location, if the address is external and found in a GeoIP database
+    pub geo: Option<GeoIpInfo>,
+}
+
+/// Locations of several IP addresses, in request order
+#[derive(Debug, Serialize)]
+pub struct GeoIpBatchResponse {
+    /// Whether any GeoIP database is loaded
+    pub enabled: bool,
+    pub results: Vec<GeoIpLookup>,
+}
+
+/// A loaded GeoIP database
+#[derive(Debug, Clone, Serialize)]
+pub struct GeoIpDatabaseInfo {
+    pub path: String,
+    /// Database edition, e.g. `GeoLite2-City`
+    pub database_type: String,
+    /// When the database was built
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub built_at: Option<DateTime<Utc>>,
+}
+
+/// State of the GeoIP databases
+#[derive(Debug, Serialize)]
+pub struct GeoIpStatus {
+    /// Whether any GeoIP database is loaded
+    pub enabled: bool,
+    /// Country or city database, if loaded
+    pub country_database: Option<GeoIpDatabaseInfo>,
+    /// ASN database, if loaded
+    pub asn_database: Option<GeoIpDatabaseInfo>,
+}
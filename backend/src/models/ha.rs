@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::vyos_client::{VyOSConntrackSyncStatistics, VyOSVrrpGroup};
+
+/// Number of VRRP transitions kept per node; older ones are dropped
+pub const VRRP_HISTORY_LIMIT: i64 = 500;
+
+/// State of a VRRP group owning the virtual addresses
+pub const VRRP_MASTER: &str = "MASTER";
+
+/// State of a VRRP group that failed its health checks
+pub const VRRP_FAULT: &str = "FAULT";
+
+/// High-availability state of a node: its VRRP groups and the
+/// synchronization of its connection table with the peer
+#[derive(Debug, Clone, Serialize)]
+pub struct HaStatus {
+    pub node_id: Uuid,
+    pub vrrp_groups: Vec<VyOSVrrpGroup>,
+    /// Unset if the node does not run conntrack-sync
+    pub conntrack_sync: Option<VyOSConntrackSyncStatistics>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Change of state of a VRRP group on a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VrrpTransition {
+    pub id: Uuid,
+    pub node_id: Uuid,
+    pub group: String,
+    pub vrid: u8,
+    pub from_state: String,
+    pub to_state: String,
+    /// Priority of the node in the group at the time
+    pub priority: u8,
+    /// Whether the transition was alerted on: the group failed, or a node
+    /// other than the preferred master took over outside of a reboot
+    pub unexpected: bool,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// VRRP transition history query parameters
+#[derive(Debug, Deserialize)]
+pub struct VrrpTransitionQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+/// Page of a node's VRRP transitions, newest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VrrpTransitionListResponse {
+    pub transitions: Vec<VrrpTransition>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+}
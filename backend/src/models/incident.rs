@@ -0,0 +1,164 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::monitoring::AlertSeverity;
+
+/// WebSocket channel carrying incident changes
+pub const INCIDENTS_CHANNEL: &str = "incidents";
+
+/// Incident lifecycle state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IncidentStatus {
+    /// New alerts on the node are still grouped into the incident
+    Open,
+    /// Someone is working on the incident; alerts are still grouped into it
+    Acknowledged,
+    /// Closed; later alerts open a new incident
+    Resolved,
+}
+
+impl IncidentStatus {
+    /// Convert status to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IncidentStatus::Open => "open",
+            IncidentStatus::Acknowledged => "acknowledged",
+            IncidentStatus::Resolved => "resolved",
+        }
+    }
+
+    /// Parse status from database string
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "acknowledged" => IncidentStatus::Acknowledged,
+            "resolved" => IncidentStatus::Resolved,
+            _ => IncidentStatus::Open,
+        }
+    }
+}
+
+/// Group of correlated alerts on one node
+#[derive(Debug, Clone, Serialize)]
+pub struct Incident {
+    pub id: Uuid,
+    pub node_id: String,
+    /// Title of the alert that opened the incident
+    pub title: String,
+    /// Highest severity among the incident's alerts
+    pub severity: AlertSeverity,
+    pub status: IncidentStatus,
+    pub alert_count: u64,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub opened_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub last_alert_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub acknowledged_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Alert grouped into an incident
+#[derive(Debug, Clone, Serialize)]
+pub struct IncidentAlert {
+    pub alert_id: Uuid,
+    pub title: String,
+    pub severity: AlertSeverity,
+    pub metric_name: Option<String>,
+    pub actual_value: Option<f64>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub triggered_at: DateTime<Utc>,
+}
+
+/// Comment on an incident
+#[derive(Debug, Clone, Serialize)]
+pub struct IncidentComment {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub username: String,
+    pub body: String,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Configuration commit made on the incident's node while it was unresolved
+#[derive(Debug, Clone, Serialize)]
+pub struct IncidentConfigChange {
+    pub history_id: String,
+    pub version: String,
+    pub user_id: Option<String>,
+    pub change_summary: Option<String>,
+    pub is_rollback_point: bool,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Incident with everything needed for a post-mortem
+#[derive(Debug, Clone, Serialize)]
+pub struct IncidentDetail {
+    #[serde(flatten)]
+    pub incident: Incident,
+    pub alerts: Vec<IncidentAlert>,
+    pub comments: Vec<IncidentComment>,
+    pub config_changes: Vec<IncidentConfigChange>,
+}
+
+/// Incident list query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct IncidentListQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub status: Option<IncidentStatus>,
+    pub node_id: Option<String>,
+}
+
+/// Incident list response
+#[derive(Debug, Serialize)]
+pub struct IncidentListResponse {
+    pub incidents: Vec<Incident>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+}
+
+/// Request to move an incident through its lifecycle
+#[derive(Debug, Deserialize)]
+pub struct UpdateIncidentRequest {
+    pub status: IncidentStatus,
+}
+
+/// Request to comment on an incident
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateIncidentCommentRequest {
+    #[validate(length(min = 1, max = 4000))]
+    pub body: String,
+}
+
+/// Incident change broadcast on [`INCIDENTS_CHANNEL`]
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum IncidentEvent {
+    Opened { incident: Incident },
+    Updated { incident: Incident },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incident_status_round_trip() {
+        for status in [IncidentStatus::Open, IncidentStatus::Acknowledged, IncidentStatus::Resolved] {
+            assert_eq!(IncidentStatus::parse(status.as_str()), status);
+        }
+        assert_eq!(IncidentStatus::parse("unknown"), IncidentStatus::Open);
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::monitoring::AlertSeverity;
+
+/// Address configured on an interface of a node
+#[derive(Debug, Clone, Serialize)]
+pub struct IpamAddress {
+    pub node_id: Uuid,
+    pub node_name: String,
+    pub interface: String,
+    /// Interface description, as configured on the node
+    pub description: Option<String>,
+    /// Address with prefix length, e.g. `192.0.2.1/24`
+    pub address: String,
+    /// Whether the address comes from the last-known data of an unreachable
+    /// node
+    pub stale: bool,
+}
+
+/// Subnet in use on one or more nodes
+#[derive(Debug, Clone, Serialize)]
+pub struct IpamPrefix {
+    /// Network address with prefix length, e.g. `192.0.2.0/24`
+    pub prefix: String,
+    /// Distinct addresses in use
+    pub used: usize,
+    /// Assignable host addresses, or `None` if there are more than fit a u64
+    pub capacity: Option<u64>,
+    pub usage_percent: Option<f64>,
+    pub addresses: Vec<IpamAddress>,
+}
+
+/// Kind of addressing conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpamConflictKind {
+    /// The same address is configured on more than one interface
+    DuplicateAddress,
+    /// The same subnet is configured on several interfaces of one node
+    DuplicateSubnet,
+    /// A subnet contains another subnet that is in use, e.g. because of a
+    /// mistyped prefix length
+    OverlappingPrefix,
+}
+
+impl IpamConflictKind {
+    /// Severity the conflict is reported with
+    pub fn severity(&self) -> AlertSeverity {
+        match self {
+            IpamConflictKind::DuplicateAddress => AlertSeverity::Critical,
+            IpamConflictKind::DuplicateSubnet | IpamConflictKind::OverlappingPrefix => AlertSeverity::Warning,
+        }
+    }
+}
+
+/// Addressing conflict between interfaces
+#[derive(Debug, Clone, Serialize)]
+pub struct IpamConflict {
+    pub kind: IpamConflictKind,
+    pub severity: AlertSeverity,
+    /// Conflicting address or prefix
+    pub subject: String,
+    pub message: String,
+    /// Addresses involved in the conflict
+    pub addresses: Vec<IpamAddress>,
+}
+
+/// Node whose interfaces could not be read
+#[derive(Debug, Clone, Serialize)]
+pub struct IpamUnavailableNode {
+    pub node_id: Uuid,
+    pub node_name: String,
+    pub error: String,
+}
+
+/// Address plan across all nodes the caller can access
+#[derive(Debug, Clone, Serialize)]
+pub struct IpamResponse {
+    pub prefixes: Vec<IpamPrefix>,
+    pub conflicts: Vec<IpamConflict>,
+    /// Nodes left out because their interfaces could not be read
+    pub unavailable_nodes: Vec<IpamUnavailableNode>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub generated_at: DateTime<Utc>,
+}
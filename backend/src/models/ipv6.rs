@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+/// Router preference announced in router advertisements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouterPreference {
+    Low,
+    Medium,
+    High,
+}
+
+impl RouterPreference {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// Prefix announced in router advertisements
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouterAdvertPrefix {
+    /// IPv6 network, e.g. `2001:db8:1::/64`
+    pub prefix: String,
+    /// Seconds the prefix is valid for on-link determination
+    pub valid_lifetime: Option<u32>,
+    /// Seconds addresses formed from the prefix remain preferred
+    pub preferred_lifetime: Option<u32>,
+    /// Whether hosts may form addresses from the prefix with SLAAC
+    #[serde(default = "default_true")]
+    pub autonomous: bool,
+    /// Whether the prefix is announced as on-link
+    #[serde(default = "default_true")]
+    pub on_link: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Router advertisement settings of an interface
+///
+/// Unset values are left to the VyOS defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouterAdvertSettings {
+    #[serde(default)]
+    pub prefixes: Vec<RouterAdvertPrefix>,
+    /// Tell hosts to get their addresses from DHCPv6
+    #[serde(default)]
+    pub managed_flag: bool,
+    /// Tell hosts to get other settings, such as DNS servers, from DHCPv6
+    #[serde(default)]
+    pub other_config_flag: bool,
+    /// DNS servers announced with RDNSS
+    #[serde(default)]
+    pub name_servers: Vec<String>,
+    pub default_preference: Option<RouterPreference>,
+    /// Seconds the router may be used as a default router; 0 announces that
+    /// it is not one
+    pub default_lifetime: Option<u32>,
+    /// Most seconds between unsolicited advertisements
+    pub max_interval: Option<u32>,
+}
+
+/// Router advertisement settings of one interface of a node
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RouterAdvertInterface {
+    pub interface: String,
+    #[serde(flatten)]
+    pub settings: RouterAdvertSettings,
+}
+
+/// Address range a DHCPv6 server leases from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dhcpv6Range {
+    pub start: String,
+    pub stop: String,
+}
+
+/// Pool of prefixes a DHCPv6 server delegates to requesting routers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrefixDelegationPool {
+    /// First prefix of the pool
+    pub start: String,
+    /// Last prefix of the pool
+    pub stop: String,
+    /// Length of each delegated prefix, e.g. 56
+    pub prefix_length: u8,
+}
+
+/// Subnet served by a DHCPv6 shared network
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dhcpv6Subnet {
+    /// IPv6 network, e.g. `2001:db8:1::/64`
+    pub subnet: String,
+    /// Identifier of the subnet, required by VyOS 1.4 and later
+    pub subnet_id: Option<u32>,
+    #[serde(default)]
+    pub ranges: Vec<Dhcpv6Range>,
+    #[serde(default)]
+    pub name_servers: Vec<String>,
+    #[serde(default)]
+    pub prefix_delegation: Vec<PrefixDelegationPool>,
+}
+
+/// DHCPv6 shared network settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Dhcpv6NetworkSettings {
+    pub subnets: Vec<Dhcpv6Subnet>,
+}
+
+/// DHCPv6 shared network of a node
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Dhcpv6SharedNetwork {
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: Dhcpv6NetworkSettings,
+}
+
+/// Scope of an IPv6 address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ipv6Scope {
+    Global,
+    UniqueLocal,
+    LinkLocal,
+    Loopback,
+}
+
+/// IPv6 prefix configured on an interface of a node
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Ipv6Prefix {
+    pub interface: String,
+    /// Address of the interface within the prefix, with its length
+    pub address: String,
+    /// Network of the address, e.g. `2001:db8:1::/64`
+    pub prefix: String,
+    pub scope: Ipv6Scope,
+    /// Whether the interface announces the prefix in router advertisements
+    pub advertised: bool,
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+/// Most configuration nodes in one generated tree
+pub const MAX_LOAD_PROFILE_NODES: usize = 100_000;
+
+/// Most metric samples aggregated in one request
+pub const MAX_LOAD_PROFILE_SAMPLES: usize = 1_000_000;
+
+fn default_breadth() -> usize {
+    8
+}
+
+fn default_depth() -> usize {
+    3
+}
+
+fn default_samples() -> usize {
+    1000
+}
+
+/// Shape of a generated configuration tree
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ConfigTreeProfile {
+    /// Children of every container node
+    #[serde(default = "default_breadth")]
+    pub breadth: usize,
+    /// Levels below the root; the deepest level holds the leaves
+    #[serde(default = "default_depth")]
+    pub depth: usize,
+}
+
+/// Number of metric samples to aggregate
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct MetricsProfile {
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+}
+
+/// Outcome of diffing two generated configuration trees
+#[derive(Debug, Serialize)]
+pub struct ConfigDiffProfile {
+    /// Nodes in each tree
+    pub nodes: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    pub modifications: usize,
+}
+
+/// Outcome of the database round trip
+#[derive(Debug, Serialize)]
+pub struct DatabaseProfile {
+    /// Nodes registered, as counted by the query
+    pub nodes: i64,
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Number of logins kept per user; older ones are dropped
+pub const LOGIN_HISTORY_LIMIT: i64 = 200;
+
+/// A successful login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginRecord {
+    pub id: Uuid,
+    /// Client address, as reported by the proxy if there is one
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// Browser and operating system, e.g. `Firefox on Linux`
+    pub device: String,
+    /// ISO 3166-1 alpha-2 country code, when GeoIP locates the address
+    pub country_code: Option<String>,
+    pub country_name: Option<String>,
+    /// First login from this device
+    pub new_device: bool,
+    /// First login from this country
+    pub new_location: bool,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl LoginRecord {
+    /// Whether the user was told about the login
+    pub fn is_suspicious(&self) -> bool {
+        self.new_device || self.new_location
+    }
+}
+
+/// Login history query parameters
+#[derive(Debug, Deserialize)]
+pub struct LoginHistoryQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+/// Page of a user's login history, newest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginHistoryResponse {
+    pub logins: Vec<LoginRecord>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+/// Short description of the browser and operating system of a user agent
+///
+/// Version numbers are left out so browser updates do not count as a new
+/// device.
+pub fn describe_user_agent(user_agent: Option<&str>) -> String {
+    let Some(ua) = user_agent.map(str::trim).filter(|ua| !ua.is_empty()) else {
+        return "Unknown device".to_string();
+    };
+
+    // Order matters: Edge and Opera also claim to be Chrome, and Chrome
+    // claims to be Safari
+    const BROWSERS: [(&str, &str); 7] = [
+        ("Edg/", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("Chromium/", "Chromium"),
+        ("Safari/", "Safari"),
+        ("curl/", "curl"),
+    ];
+    const SYSTEMS: [(&str, &str); 6] = [
+        ("Android", "Android"),
+        ("iPhone", "iOS"),
+        ("iPad", "iOS"),
+        ("Windows", "Windows"),
+        ("Mac OS X", "macOS"),
+        ("Linux", "Linux"),
+    ];
+
+    let browser = BROWSERS.iter().find(|(token, _)| ua.contains(token)).map(|(_, name)| *name);
+    let system = SYSTEMS.iter().find(|(token, _)| ua.contains(token)).map(|(_, name)| *name);
+    match (browser, system) {
+        (Some(browser), Some(system)) => format!("{} on {}", browser, system),
+        (Some(browser), None) => browser.to_string(),
+        (None, Some(system)) => format!("Unknown browser on {}", system),
+        // Keep the product name of other clients, such as `python-requests`
+        (None, None) => ua.split(['/', ' ']).next().unwrap_or(ua).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_user_agent() {
+        let firefox = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+        let edge = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+                    Chrome/126.0.0.0 Safari/537.36 Edg/126.0.0.0";
+        let safari = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 \
+                      (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1";
+
+        assert_eq!(describe_user_agent(Some(firefox)), "Firefox on Linux");
+        assert_eq!(describe_user_agent(Some(edge)), "Edge on Windows");
+        assert_eq!(describe_user_agent(Some(safari)), "Safari on iOS");
+        assert_eq!(describe_user_agent(Some("python-requests/2.32")), "python-requests");
+        assert_eq!(describe_user_agent(None), "Unknown device");
+    }
+}
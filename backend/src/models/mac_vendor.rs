@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Most MAC addresses looked up in one batch request
+pub const MAX_MAC_LOOKUPS: usize = 1000;
+
+/// Query for the vendor of one MAC address
+#[derive(Debug, Deserialize)]
+pub struct MacLookupQuery {
+    /// MAC address in any common notation (`52:54:00:12:34:56`,
+    /// `5254.0012.3456`, `52-54-00-12-34-56`)
+    pub mac: String,
+}
+
+/// Request for the vendors of several MAC addresses
+#[derive(Debug, Deserialize)]
+pub struct MacLookupBatchRequest {
+    pub macs: Vec<String>,
+}
+
+/// Vendor of a MAC address
+#[derive(Debug, Clone, Serialize)]
+pub struct MacLookup {
+    /// Address as colon-separated lowercase hex
+    pub mac: String,
+    /// Block the vendor was found under, e.g. `00:50:56`
+    pub oui: Option<String>,
+    pub vendor: Option<String>,
+    /// Set for addresses not assigned by the IEEE, e.g. of virtual machines
+    pub locally_administered: bool,
+    pub multicast: bool,
+}
+
+/// Vendors of several MAC addresses, in request order
+#[derive(Debug, Serialize)]
+pub struct MacLookupBatchResponse {
+    pub results: Vec<MacLookup>,
+}
+
+/// Where MAC vendors are looked up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OuiSource {
+    /// Database shipped with the server
+    Bundled,
+    /// Database downloaded by an administrator
+    Downloaded,
+}
+
+/// State of the MAC vendor database
+#[derive(Debug, Serialize)]
+pub struct OuiDatabaseStatus {
+    pub source: OuiSource,
+    /// Number of vendor blocks
+    pub entries: u64,
+    /// When the database was downloaded, for downloaded databases
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// URL updates are downloaded from
+    pub update_url: String,
+}
@@ -3,19 +3,119 @@
 //! This module contains all data models used throughout the application,
 //! organized by domain/functionality.
 
+pub mod announcement;
+pub mod api_token;
+pub mod api_usage;
+pub mod audit;
 pub mod auth;
+pub mod backend_health;
+pub mod backup;
+pub mod baseline;
+pub mod certificate;
+pub mod chaos;
+pub mod client_error;
+pub mod cluster;
 pub mod config;
+pub mod config_backup;
+pub mod config_drift;
+pub mod config_lock;
+pub mod config_session;
+pub mod config_template;
+pub mod custom_field;
+pub mod desired_state;
+pub mod event;
+pub mod fields;
+pub mod firewall;
+pub mod firewall_log;
+pub mod geoip;
+pub mod ha;
+pub mod incident;
+pub mod ipam;
+pub mod ipv6;
+pub mod load_profile;
+pub mod login_history;
+pub mod mac_vendor;
 pub mod monitoring;
-// pub mod network;
-// pub mod node;
+pub mod network;
+pub mod node;
+pub mod node_file;
+pub mod notification;
+pub mod permission;
+pub mod quota;
+pub mod redact;
+pub mod report;
+pub mod response;
+pub mod router_login;
+pub mod routing;
+pub mod status_page;
+pub mod storage;
+pub mod subnet;
+pub mod support_bundle;
 pub mod system;
+pub mod team;
+pub mod terminal;
+pub mod time_range;
+pub mod timestamp;
+pub mod upload;
 pub mod user;
+pub mod vpn_mesh;
+pub mod wizard;
 
 // Re-export models for convenience
+pub use announcement::*;
+pub use api_token::*;
+pub use api_usage::*;
+pub use audit::*;
 pub use auth::*;
+pub use backend_health::*;
+pub use backup::*;
+pub use baseline::*;
+pub use certificate::*;
+pub use chaos::*;
+pub use client_error::*;
+pub use cluster::*;
 pub use config::*;
+pub use config_backup::*;
+pub use config_drift::*;
+pub use config_lock::*;
+pub use config_session::*;
+pub use config_template::*;
+pub use custom_field::*;
+pub use desired_state::*;
+pub use event::*;
+pub use fields::*;
+pub use firewall::*;
+pub use firewall_log::*;
+pub use geoip::*;
+pub use ha::*;
+pub use incident::*;
+pub use ipam::*;
+pub use ipv6::*;
+pub use load_profile::*;
+pub use login_history::*;
+pub use mac_vendor::*;
 pub use monitoring::*;
-// pub use network::*;
-// pub use node::*;
+pub use network::*;
+pub use node::*;
+pub use node_file::*;
+pub use notification::*;
+pub use permission::*;
+pub use quota::*;
+pub use redact::*;
+pub use report::*;
+pub use response::*;
+pub use router_login::*;
+pub use routing::*;
+pub use status_page::*;
+pub use storage::*;
+pub use subnet::*;
+pub use support_bundle::*;
 pub use system::*;
-pub use user::*;
\ No newline at end of file
+pub use team::*;
+pub use terminal::*;
+pub use time_range::*;
+pub use timestamp::*;
+pub use upload::*;
+pub use user::*;
+pub use vpn_mesh::*;
+pub use wizard::*;
\ No newline at end of file
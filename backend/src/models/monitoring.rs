@@ -5,7 +5,9 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
+use validator::Validate;
 
 /// Metric type enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -119,12 +121,22 @@ pub struct MetricsQuery {
     /// Optional metric type filter
     pub metric_type: Option<MetricType>,
 
-    /// Start time for the query
+    /// Start time for the query, absolute or relative (`-6h`)
+    #[serde(default, alias = "start", deserialize_with = "crate::models::time_range::deserialize_time_bound")]
     pub start_time: Option<DateTime<Utc>>,
 
-    /// End time for the query
+    /// End time for the query, absolute or relative
+    #[serde(default, alias = "end", deserialize_with = "crate::models::time_range::deserialize_time_bound")]
     pub end_time: Option<DateTime<Utc>>,
 
+    /// Span of the query, such as `7d`
+    #[serde(default, skip_serializing)]
+    pub window: Option<crate::models::time_range::RelativeDuration>,
+
+    /// Named time range replacing the start and end times
+    #[serde(default)]
+    pub preset: Option<crate::models::time_range::TimePreset>,
+
     /// Limit on the number of results
     pub limit: Option<usize>,
 
@@ -133,6 +145,121 @@ pub struct MetricsQuery {
     pub sort_order: SortOrder,
 }
 
+/// Layout of a metrics export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsExportFormat {
+    /// One row per data point
+    #[default]
+    Long,
+    /// One row per node and time, with a column per metric and label set
+    Wide,
+}
+
+/// Query of a CSV export of metrics
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetricsExportQuery {
+    /// Comma-separated metric names, e.g. `cpu_usage_percent,interface_rx_bps`
+    pub metrics: String,
+
+    /// Comma-separated node IDs; every node the caller can access if unset
+    pub node_ids: Option<String>,
+
+    /// Start time, absolute or relative (`-30d`)
+    #[serde(default, alias = "start", deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time, absolute or relative
+    #[serde(default, alias = "end", deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Span of the export, such as `7d`
+    #[serde(default)]
+    pub window: Option<crate::models::time_range::RelativeDuration>,
+
+    /// Named time range replacing the start and end times
+    #[serde(default)]
+    pub preset: Option<crate::models::time_range::TimePreset>,
+
+    #[serde(default)]
+    pub format: MetricsExportFormat,
+}
+
+/// Query of the nodes with the worst values of a metric
+#[derive(Debug, Clone, Deserialize)]
+pub struct TopNodesQuery {
+    /// Metric name, e.g. `cpu_usage_percent`
+    pub metric: String,
+
+    /// Number of nodes to return
+    #[serde(default = "default_top_nodes")]
+    pub n: usize,
+
+    /// `desc` (default) ranks the highest values worst, `asc` the lowest
+    #[serde(default)]
+    pub order: SortOrder,
+
+    /// Start of the window averaged over, absolute or relative (`-6h`)
+    #[serde(default, alias = "start", deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End of the window, absolute or relative
+    #[serde(default, alias = "end", deserialize_with = "crate::models::time_range::deserialize_time_bound")]
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Span of the window, such as `24h`; the last hour if no range is given
+    #[serde(default)]
+    pub window: Option<crate::models::time_range::RelativeDuration>,
+
+    /// Named time range replacing the start and end times
+    #[serde(default)]
+    pub preset: Option<crate::models::time_range::TimePreset>,
+}
+
+fn default_top_nodes() -> usize {
+    10
+}
+
+/// Latest value of a metric on a node
+///
+/// Of a metric with several label sets, such as one per interface, the
+/// worst of the latest values is taken.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopNodeValue {
+    pub node_id: String,
+    pub node_name: String,
+    pub value: f64,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Values of a metric on a node over a window
+#[derive(Debug, Clone, Serialize)]
+pub struct TopNodeAverage {
+    pub node_id: String,
+    pub node_name: String,
+    /// Mean of all data points in the window, by which nodes are ranked
+    pub average: f64,
+    /// Worst data point in the window
+    pub peak: f64,
+    pub samples: usize,
+}
+
+/// Nodes with the worst values of a metric, worst first
+#[derive(Debug, Clone, Serialize)]
+pub struct TopNodesResponse {
+    pub metric: String,
+    pub order: SortOrder,
+    /// Ranked by their latest value, if recent
+    pub current: Vec<TopNodeValue>,
+    /// Ranked by their average over the window
+    pub window: Vec<TopNodeAverage>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub start_time: Option<DateTime<Utc>>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub end_time: Option<DateTime<Utc>>,
+}
+
 /// Sort order for query results
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -495,6 +622,26 @@ pub enum AlertSeverity {
     Critical,
 }
 
+impl AlertSeverity {
+    /// Convert severity to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSeverity::Info => "info",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Critical => "critical",
+        }
+    }
+
+    /// Parse severity from database string
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "critical" => AlertSeverity::Critical,
+            "warning" => AlertSeverity::Warning,
+            _ => AlertSeverity::Info,
+        }
+    }
+}
+
 /// Alert status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -607,9 +754,25 @@ pub struct AlertRule {
     pub enabled: bool,
 
     /// Labels to apply to alerts from this rule
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub labels: Vec<MetricLabel>,
 
+    /// Only evaluate the rule on nodes carrying this tag
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_tag: Option<String>,
+
+    /// Per-node thresholds replacing `threshold`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub threshold_overrides: Vec<ThresholdOverride>,
+
+    /// Further conditions checked together with the rule's own metric
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<AlertCondition>,
+
+    /// How the rule's conditions are combined
+    #[serde(default, skip_serializing_if = "ConditionCombinator::is_and")]
+    pub combine: ConditionCombinator,
+
     /// Created timestamp
     pub created_at: DateTime<Utc>,
 
@@ -617,6 +780,109 @@ pub struct AlertRule {
     pub updated_at: DateTime<Utc>,
 }
 
+impl AlertRule {
+    /// Whether the rule is evaluated on a node
+    ///
+    /// Rules apply to all nodes unless restricted to a tag, or to a single
+    /// node through a `node_id` label.
+    pub fn applies_to(&self, node_id: Uuid, tags: &[String]) -> bool {
+        let node_label = self.labels.iter().find(|label| label.key == "node_id");
+        if node_label.is_some_and(|label| label.value != node_id.to_string()) {
+            return false;
+        }
+
+        self.node_tag
+            .as_ref()
+            .is_none_or(|tag| tags.iter().any(|t| t == tag))
+    }
+
+    /// Threshold the rule uses on a node, honouring per-node overrides
+    pub fn threshold_for(&self, node_id: Uuid) -> f64 {
+        self.threshold_overrides
+            .iter()
+            .find(|o| o.node_id == node_id)
+            .map_or(self.threshold, |o| o.threshold)
+    }
+
+    /// Check the rule's conditions against a node's current metric values
+    ///
+    /// The rule's own metric is the first condition; per-node overrides only
+    /// replace its threshold. Conditions on metrics without a value are not met.
+    pub fn is_triggered(&self, node_id: Uuid, values: &HashMap<String, f64>) -> bool {
+        let primary = AlertCondition {
+            metric_name: self.metric_name.clone(),
+            metric_type: self.metric_type,
+            operator: self.operator,
+            threshold: self.threshold_for(node_id),
+        };
+        let mut conditions = std::iter::once(&primary).chain(&self.conditions);
+
+        match self.combine {
+            ConditionCombinator::And => conditions.all(|c| c.is_met(values)),
+            ConditionCombinator::Or => conditions.any(|c| c.is_met(values)),
+        }
+    }
+}
+
+/// Single metric comparison within an alert rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertCondition {
+    pub metric_name: String,
+    pub metric_type: MetricType,
+    pub operator: AlertOperator,
+    pub threshold: f64,
+}
+
+impl AlertCondition {
+    /// Whether the metric's current value satisfies the condition
+    pub fn is_met(&self, values: &HashMap<String, f64>) -> bool {
+        values
+            .get(&self.metric_name)
+            .is_some_and(|&value| self.operator.compare(value, self.threshold))
+    }
+}
+
+/// How the conditions of an alert rule are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConditionCombinator {
+    /// All conditions must be met
+    #[default]
+    And,
+    /// Any condition must be met
+    Or,
+}
+
+impl ConditionCombinator {
+    fn is_and(&self) -> bool {
+        *self == ConditionCombinator::And
+    }
+}
+
+/// Threshold replacing a rule's default on one node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdOverride {
+    pub node_id: Uuid,
+    pub threshold: f64,
+}
+
+/// Node an alert rule is evaluated on, with the threshold used there
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertRuleTarget {
+    pub node_id: Uuid,
+    pub node_name: String,
+    pub threshold: f64,
+    /// Whether the threshold comes from a per-node override
+    pub overridden: bool,
+}
+
+/// Nodes an alert rule currently expands to
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertRuleTargetsResponse {
+    pub rule_id: Uuid,
+    pub targets: Vec<AlertRuleTarget>,
+}
+
 /// Alert comparison operator
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -635,6 +901,133 @@ pub enum AlertOperator {
     NotEqual,
 }
 
+impl AlertOperator {
+    /// Compare a metric value with a threshold
+    pub fn compare(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlertOperator::GreaterThan => value > threshold,
+            AlertOperator::LessThan => value < threshold,
+            AlertOperator::GreaterThanOrEqual => value >= threshold,
+            AlertOperator::LessThanOrEqual => value <= threshold,
+            AlertOperator::Equal => value == threshold,
+            AlertOperator::NotEqual => value != threshold,
+        }
+    }
+}
+
+/// Default alert rule bundle
+///
+/// Applying a bundle creates one rule per node, labelled with `bundle` and
+/// `node_id`, and keeps creating it for nodes registered afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertRuleBundle {
+    /// CPU usage above 90%
+    Cpu,
+    /// Disk usage above 85%
+    Disk,
+    /// Memory usage above 90%
+    Memory,
+    /// Interface error counters rising
+    InterfaceErrors,
+}
+
+impl AlertRuleBundle {
+    /// All bundles, in the order they are listed
+    pub const ALL: [AlertRuleBundle; 4] = [
+        AlertRuleBundle::Cpu,
+        AlertRuleBundle::Disk,
+        AlertRuleBundle::Memory,
+        AlertRuleBundle::InterfaceErrors,
+    ];
+
+    /// Convert bundle to the string used in rule labels
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertRuleBundle::Cpu => "cpu",
+            AlertRuleBundle::Disk => "disk",
+            AlertRuleBundle::Memory => "memory",
+            AlertRuleBundle::InterfaceErrors => "interface_errors",
+        }
+    }
+}
+
+/// Metric of how busy an interface is: its busier direction as a share of
+/// its link speed, in percent
+pub const INTERFACE_UTILIZATION_METRIC: &str = "interface_utilization_percent";
+
+/// Request to alert when an interface stays busy, at a share of its link
+/// speed the node reports
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct InterfaceBandwidthAlertRequest {
+    /// Utilization in percent above which the rule triggers
+    #[serde(default = "default_threshold_percent")]
+    #[validate(range(min = 1.0, max = 100.0))]
+    pub threshold_percent: f64,
+    /// Minutes the utilization must stay above the threshold
+    #[serde(default = "default_for_minutes")]
+    #[validate(range(min = 1, max = 1440))]
+    pub for_minutes: u32,
+    #[serde(default = "default_bandwidth_severity")]
+    pub severity: AlertSeverity,
+}
+
+impl Default for InterfaceBandwidthAlertRequest {
+    fn default() -> Self {
+        Self {
+            threshold_percent: default_threshold_percent(),
+            for_minutes: default_for_minutes(),
+            severity: default_bandwidth_severity(),
+        }
+    }
+}
+
+fn default_threshold_percent() -> f64 {
+    80.0
+}
+
+fn default_for_minutes() -> u32 {
+    10
+}
+
+fn default_bandwidth_severity() -> AlertSeverity {
+    AlertSeverity::Warning
+}
+
+/// Default alert rule bundle and where it is applied
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertBundleInfo {
+    pub bundle: AlertRuleBundle,
+    pub name: String,
+    pub description: String,
+    pub metric_name: String,
+    pub metric_type: MetricType,
+    pub threshold: f64,
+    pub operator: AlertOperator,
+    pub severity: AlertSeverity,
+    /// Whether the bundle is applied to all nodes, including new ones
+    pub applied: bool,
+    /// Number of rules currently created from the bundle
+    pub rule_count: usize,
+}
+
+/// Request to apply default alert rule bundles
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ApplyAlertBundlesRequest {
+    /// Bundles to apply; all bundles when omitted
+    pub bundles: Option<Vec<AlertRuleBundle>>,
+}
+
+/// Result of applying default alert rule bundles
+#[derive(Debug, Clone, Serialize)]
+pub struct ApplyAlertBundlesResponse {
+    pub bundles: Vec<AlertRuleBundle>,
+    /// Number of nodes the bundles were applied to
+    pub nodes: usize,
+    /// Number of rules created; rules that already existed are kept
+    pub created: usize,
+}
+
 /// Network topology node
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopologyNode {
@@ -740,6 +1133,30 @@ pub enum TopologyLinkType {
     Logical,
 }
 
+impl TopologyLinkType {
+    /// Stable lowercase name, as serialized and stored
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TopologyLinkType::Ethernet => "ethernet",
+            TopologyLinkType::Fiber => "fiber",
+            TopologyLinkType::Wireless => "wireless",
+            TopologyLinkType::Vpn => "vpn",
+            TopologyLinkType::Logical => "logical",
+        }
+    }
+
+    /// Parse a stored link type; unknown values are treated as logical links
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "ethernet" => TopologyLinkType::Ethernet,
+            "fiber" => TopologyLinkType::Fiber,
+            "wireless" => TopologyLinkType::Wireless,
+            "vpn" => TopologyLinkType::Vpn,
+            _ => TopologyLinkType::Logical,
+        }
+    }
+}
+
 /// Link status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -768,6 +1185,67 @@ pub struct NetworkTopology {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Query parameters for the network topology
+#[derive(Debug, Clone, Deserialize)]
+pub struct TopologyQuery {
+    /// Run `show lldp neighbors` and `show arp` on every node; without it
+    /// the topology is built from interfaces and stored links only
+    #[serde(default = "default_discover")]
+    pub discover: bool,
+}
+
+fn default_discover() -> bool {
+    true
+}
+
+/// Request to add a topology link by hand
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateTopologyLinkRequest {
+    /// Source node ID, or the ID of a discovered device
+    #[validate(length(min = 1, max = 191))]
+    pub source: String,
+    /// Target node ID, or the ID of a discovered device
+    #[validate(length(min = 1, max = 191))]
+    pub target: String,
+    pub link_type: TopologyLinkType,
+    #[validate(length(min = 1, max = 64))]
+    pub source_interface: Option<String>,
+    #[validate(length(min = 1, max = 64))]
+    pub target_interface: Option<String>,
+    pub bandwidth_mbps: Option<u64>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+/// Request to change a topology link added by hand; unset fields are kept
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UpdateTopologyLinkRequest {
+    pub link_type: Option<TopologyLinkType>,
+    #[validate(length(max = 64))]
+    pub source_interface: Option<String>,
+    #[validate(length(max = 64))]
+    pub target_interface: Option<String>,
+    pub bandwidth_mbps: Option<u64>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+/// Position of one topology node in the map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyNodePosition {
+    /// Node ID, or the ID of a discovered device
+    pub node_id: String,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Request to place topology nodes in the map
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UpdateTopologyPositionsRequest {
+    #[validate(length(min = 1, max = 1000))]
+    pub positions: Vec<TopologyNodePosition>,
+}
+
 /// Metrics history response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsHistoryResponse {
@@ -806,6 +1284,53 @@ pub struct MetricsStatistics {
     pub percentiles: Percentiles,
 }
 
+impl MetricsStatistics {
+    /// Aggregate a set of metric values, or `None` if there are none
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let sum: f64 = values.iter().sum();
+        let avg = sum / values.len() as f64;
+
+        // Calculate median
+        let mut sorted_values = values.to_vec();
+        sorted_values.sort_by(f64::total_cmp);
+        let len = sorted_values.len();
+        let median = if len.is_multiple_of(2) {
+            (sorted_values[len / 2 - 1] + sorted_values[len / 2]) / 2.0
+        } else {
+            sorted_values[len / 2]
+        };
+
+        // Calculate percentiles
+        let percentile = |p: f64| -> f64 {
+            let idx = ((len - 1) as f64 * p / 100.0) as usize;
+            sorted_values[idx]
+        };
+
+        let variance: f64 = values.iter().map(|&x| (x - avg).powi(2)).sum::<f64>() / len as f64;
+
+        Some(Self {
+            min,
+            max,
+            avg,
+            median,
+            std_dev: Some(variance.sqrt()),
+            percentiles: Percentiles {
+                p50: percentile(50.0),
+                p75: percentile(75.0),
+                p90: percentile(90.0),
+                p95: percentile(95.0),
+                p99: percentile(99.0),
+            },
+        })
+    }
+}
+
 /// Percentile values
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Percentiles {
@@ -934,6 +1459,20 @@ mod tests {
         assert_eq!(json, r#""cpu""#);
     }
 
+    #[test]
+    fn test_metrics_statistics_from_values() {
+        assert!(MetricsStatistics::from_values(&[]).is_none());
+
+        let values: Vec<f64> = (1..=100).map(f64::from).collect();
+        let statistics = MetricsStatistics::from_values(&values).unwrap();
+        assert_eq!(statistics.min, 1.0);
+        assert_eq!(statistics.max, 100.0);
+        assert_eq!(statistics.avg, 50.5);
+        assert_eq!(statistics.median, 50.5);
+        assert_eq!(statistics.percentiles.p90, 90.0);
+        assert_eq!(statistics.percentiles.p99, 99.0);
+    }
+
     #[test]
     fn test_alert_severity_ordering() {
         assert!(AlertSeverity::Critical > AlertSeverity::Warning);
@@ -990,4 +1529,78 @@ mod tests {
         assert_eq!(alert.severity, AlertSeverity::Critical);
         assert_eq!(alert.status, AlertStatus::Active);
     }
+
+    #[test]
+    fn test_alert_rule_targets_tagged_nodes() {
+        let (edge, core) = (Uuid::new_v4(), Uuid::new_v4());
+        let rule = AlertRule {
+            id: Uuid::new_v4(),
+            name: "High CPU".to_string(),
+            description: None,
+            metric_name: "cpu_usage_percent".to_string(),
+            metric_type: MetricType::Cpu,
+            threshold: 90.0,
+            operator: AlertOperator::GreaterThan,
+            severity: AlertSeverity::Warning,
+            for_seconds: 300,
+            enabled: true,
+            labels: vec![],
+            node_tag: Some("edge".to_string()),
+            threshold_overrides: vec![ThresholdOverride { node_id: edge, threshold: 75.0 }],
+            conditions: vec![],
+            combine: ConditionCombinator::And,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        assert!(rule.applies_to(edge, &["edge".to_string()]));
+        assert!(!rule.applies_to(core, &["core".to_string()]));
+        assert_eq!(rule.threshold_for(edge), 75.0);
+        assert_eq!(rule.threshold_for(core), 90.0);
+    }
+
+    #[test]
+    fn test_composite_alert_rule() {
+        let node = Uuid::new_v4();
+        let json = serde_json::json!({
+            "id": Uuid::new_v4(),
+            "name": "CPU saturated",
+            "description": null,
+            "metric_name": "cpu_usage_percent",
+            "metric_type": "cpu",
+            "threshold": 90.0,
+            "operator": "greater_than",
+            "severity": "critical",
+            "for_seconds": 300,
+            "enabled": true,
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+        });
+
+        // Rules stored before composite conditions keep their format
+        let mut rule: AlertRule = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(rule.combine, ConditionCombinator::And);
+        assert_eq!(serde_json::to_value(&rule).unwrap(), json);
+
+        rule.conditions.push(AlertCondition {
+            metric_name: "load_average_1m".to_string(),
+            metric_type: MetricType::Load,
+            operator: AlertOperator::GreaterThan,
+            threshold: 8.0,
+        });
+        let values = |cpu: f64, load: f64| {
+            HashMap::from([
+                ("cpu_usage_percent".to_string(), cpu),
+                ("load_average_1m".to_string(), load),
+            ])
+        };
+
+        assert!(rule.is_triggered(node, &values(95.0, 9.0)));
+        assert!(!rule.is_triggered(node, &values(95.0, 2.0)));
+        assert!(!rule.is_triggered(node, &HashMap::from([("cpu_usage_percent".to_string(), 95.0)])));
+
+        rule.combine = ConditionCombinator::Or;
+        assert!(rule.is_triggered(node, &values(95.0, 2.0)));
+        assert!(!rule.is_triggered(node, &values(50.0, 2.0)));
+    }
 }
\ No newline at end of file
@@ -1,106 +1,190 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
-/// Network interface status
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum InterfaceStatus {
-    Up,
-    Down,
-    Unknown,
-}
+use crate::vyos_client::{ConfigOperation, VyOSModemStatus};
+
+/// Lowest MTU VyOS accepts on an interface
+pub const MIN_MTU: u32 = 68;
+
+/// Highest MTU VyOS accepts on an interface
+pub const MAX_MTU: u32 = 16_000;
 
-/// Network interface type
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum InterfaceType {
+/// Highest VLAN id of a sub-interface
+pub const MAX_VLAN_ID: u16 = 4094;
+
+/// Address values standing for an address obtained by DHCP
+pub const DHCP_ADDRESSES: &[&str] = &["dhcp", "dhcpv6"];
+
+/// Kind of an interface whose addressing can be configured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceKind {
     Ethernet,
-    Loopback,
-    Vlan,
+    Bonding,
     Bridge,
-    Bond,
-    Wireless,
-    Other,
+    Dummy,
+    Pppoe,
+    Wwan,
 }
 
-/// Network interface
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NetworkInterface {
-    pub id: Uuid,
-    pub name: String,
-    pub description: Option<String>,
-    #[serde(rename = "type")]
-    pub interface_type: InterfaceType,
-    pub status: InterfaceStatus,
-    pub mac_address: Option<String>,
-    pub mtu: Option<u32>,
-    pub ip_addresses: Vec<IpAddress>,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+impl InterfaceKind {
+    pub const ALL: [Self; 6] = [Self::Ethernet, Self::Bonding, Self::Bridge, Self::Dummy, Self::Pppoe, Self::Wwan];
+
+    /// Name of the kind, as in the configuration
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ethernet => "ethernet",
+            Self::Bonding => "bonding",
+            Self::Bridge => "bridge",
+            Self::Dummy => "dummy",
+            Self::Pppoe => "pppoe",
+            Self::Wwan => "wwan",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == name)
+    }
+
+    /// Prefix of the names of interfaces of the kind, e.g. `eth` for `eth0`
+    pub fn name_prefix(&self) -> &'static str {
+        match self {
+            Self::Ethernet => "eth",
+            Self::Bonding => "bond",
+            Self::Bridge => "br",
+            Self::Dummy => "dum",
+            Self::Pppoe => "pppoe",
+            Self::Wwan => "wwan",
+        }
+    }
+
+    /// Whether interfaces of the kind are created by configuring them, rather
+    /// than standing for hardware that must already be known to the node
+    pub fn is_virtual(&self) -> bool {
+        !matches!(self, Self::Ethernet)
+    }
+
+    /// Whether interfaces of the kind may have VLAN sub-interfaces
+    pub fn has_vlans(&self) -> bool {
+        !matches!(self, Self::Dummy | Self::Pppoe | Self::Wwan)
+    }
+
+    /// Whether interfaces of the kind dial a provider, which assigns their
+    /// addresses
+    pub fn is_dialup(&self) -> bool {
+        matches!(self, Self::Pppoe | Self::Wwan)
+    }
 }
 
-/// IP address configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct IpAddress {
-    pub address: String,
-    pub prefix_length: u8,
-    #[serde(rename = "type")]
-    pub ip_type: IpType,
-    pub is_primary: bool,
+/// Connection settings of a PPPoE or WWAN interface
+///
+/// The password is only ever written; the current one is kept if unset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DialerSettings {
+    /// Interface a PPPoE session runs over, e.g. `eth0` or `eth0.7`
+    pub source_interface: Option<String>,
+    /// Access point name of a WWAN interface's mobile network
+    pub apn: Option<String>,
+    /// User name the provider authenticates, by CHAP or PAP as it asks
+    pub username: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,
+    /// Whether a password is configured
+    #[serde(default, skip_deserializing)]
+    pub has_password: bool,
 }
 
-/// IP address type
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum IpType {
-    IPv4,
-    IPv6,
+/// Addressing settings of an interface or a VLAN sub-interface
+///
+/// Settings left out are removed from the interface; other settings, such
+/// as the hardware id or firewall bindings, are kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceAddressing {
+    /// Addresses with their prefix length, e.g. `192.0.2.1/24`, or `dhcp` and
+    /// `dhcpv6`
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    pub mtu: Option<u32>,
+    pub description: Option<String>,
+    /// Whether the interface is administratively down
+    #[serde(default)]
+    pub disabled: bool,
 }
 
-/// Route entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Route {
-    pub id: Uuid,
-    pub destination: String,
-    pub gateway: Option<String>,
-    pub interface: Option<String>,
-    pub metric: Option<u32>,
-    pub route_type: RouteType,
-    pub created_at: DateTime<Utc>,
+/// VLAN sub-interface
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceVlan {
+    pub vlan_id: u16,
+    #[serde(flatten)]
+    pub settings: InterfaceAddressing,
 }
 
-/// Route type
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum RouteType {
-    Connected,
-    Static,
-    Dynamic,
+/// Settings of an interface
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceSettings {
+    #[serde(flatten)]
+    pub addressing: InterfaceAddressing,
+    /// VLAN sub-interfaces; those left out are removed
+    #[serde(default)]
+    pub vlans: Vec<InterfaceVlan>,
+    /// Connection settings; required for PPPoE and WWAN interfaces, which
+    /// have no addresses of their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialer: Option<DialerSettings>,
 }
 
-/// Firewall rule
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FirewallRule {
-    pub id: Uuid,
+/// Configured interface of a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InterfaceConfig {
+    pub kind: InterfaceKind,
     pub name: String,
-    pub description: Option<String>,
-    pub action: FirewallAction,
-    pub source_address: Option<String>,
-    pub destination_address: Option<String>,
-    pub source_port: Option<u16>,
-    pub destination_port: Option<u16>,
-    pub protocol: Option<String>,
-    pub enabled: bool,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+    /// Configuration path of the interface, e.g. `interfaces ethernet eth0`
+    pub path: String,
+    #[serde(flatten)]
+    pub settings: InterfaceSettings,
 }
 
-/// Firewall action
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum FirewallAction {
-    Accept,
-    Drop,
-    Reject,
-}
\ No newline at end of file
+/// Request to change the settings of an interface
+#[derive(Debug, Clone, Deserialize)]
+pub struct InterfaceConfigRequest {
+    #[serde(flatten)]
+    pub settings: InterfaceSettings,
+    /// Only compute the operations, without applying them
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Operations changing an interface to the requested settings
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceConfigPlan {
+    /// Interface with the requested settings
+    pub interface: InterfaceConfig,
+    /// Deletes followed by sets, applied as one commit
+    pub operations: Vec<ConfigOperation>,
+    /// Whether the operations were applied; false for dry runs and when the
+    /// interface already has the requested settings
+    pub applied: bool,
+}
+
+/// Operational state of a PPPoE or WWAN interface
+#[derive(Debug, Clone, Serialize)]
+pub struct DialerStatus {
+    pub kind: InterfaceKind,
+    pub name: String,
+    /// Whether the link is up, i.e. a session is established
+    pub is_up: bool,
+    /// Addresses the provider assigned
+    pub addresses: Vec<String>,
+    /// Address of the provider's end of the link
+    pub peer_address: Option<String>,
+    pub mtu: Option<u32>,
+    /// When the current PPPoE session was established, as the node logged it
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub connected_since: Option<DateTime<Utc>>,
+    pub uptime_secs: Option<u64>,
+    /// Protocol the provider authenticated the current PPPoE session with,
+    /// `CHAP` or `PAP`
+    pub auth_protocol: Option<String>,
+    /// State of a WWAN interface's modem
+    pub modem: Option<VyOSModemStatus>,
+}
@@ -1,9 +1,16 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
+use crate::models::redact::sanitized_debug;
+use crate::vyos_client::{
+    Capability, NodeRecording, SshAuth, SshLogin, VyOSRelease, VyOSShowResult, DEFAULT_SSH_PORT,
+};
+
 /// Node status
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum NodeStatus {
     /// Node is online and responding
@@ -14,10 +21,83 @@ pub enum NodeStatus {
     Error,
     /// Node is being tested
     Testing,
+    /// Node is rebooting and expected to answer again shortly
+    Rebooting,
+}
+
+impl NodeStatus {
+    /// Stable lowercase name, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeStatus::Online => "online",
+            NodeStatus::Offline => "offline",
+            NodeStatus::Error => "error",
+            NodeStatus::Testing => "testing",
+            NodeStatus::Rebooting => "rebooting",
+        }
+    }
+}
+
+/// How a node is reached
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    /// Router reached over the VyOS API
+    #[default]
+    Vyos,
+    /// Stand-in answering from a recording, for training and demos
+    Simulated,
+}
+
+impl NodeKind {
+    /// Stable lowercase name, as serialized and stored
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeKind::Vyos => "vyos",
+            NodeKind::Simulated => "simulated",
+        }
+    }
+
+    /// Parse a stored kind; unknown values are treated as routers
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "simulated" => NodeKind::Simulated,
+            _ => NodeKind::Vyos,
+        }
+    }
+}
+
+/// How a router is reached
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeTransportKind {
+    /// Over the VyOS REST API with the node's API key
+    #[default]
+    Api,
+    /// Through the VyOS CLI over SSH, for routers without the API
+    Ssh,
+}
+
+impl NodeTransportKind {
+    /// Stable lowercase name, as serialized and stored
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeTransportKind::Api => "api",
+            NodeTransportKind::Ssh => "ssh",
+        }
+    }
+
+    /// Parse a stored transport; unknown values are treated as the API
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "ssh" => NodeTransportKind::Ssh,
+            _ => NodeTransportKind::Api,
+        }
+    }
 }
 
 /// Node model representing a VyOS device
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Node {
     pub id: Uuid,
     pub name: String,
@@ -27,6 +107,7 @@ pub struct Node {
     pub api_key: String,
     pub status: NodeStatus,
     /// Last successful connection timestamp
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
     pub last_seen: Option<DateTime<Utc>>,
     /// VyOS version
     pub version: Option<String>,
@@ -40,26 +121,96 @@ pub struct Node {
     pub tags: Vec<String>,
     /// Default timeout for API requests in seconds
     pub timeout: u64,
+    /// Team owning the node; unowned nodes are shared with all users
+    pub team_id: Option<Uuid>,
+    /// Whether the node is a router or a simulation
+    #[serde(default)]
+    pub kind: NodeKind,
+    /// Whether the router is reached over the API or over SSH
+    #[serde(default)]
+    pub transport: NodeTransportKind,
+    /// SSH port, 22 unless set
+    #[serde(default)]
+    pub ssh_port: Option<u16>,
+    #[serde(default)]
+    pub ssh_username: Option<String>,
+    /// SSH password; never sent to clients
+    #[serde(default, skip_serializing)]
+    pub ssh_password: Option<String>,
+    /// SSH private key in PEM format, used instead of the password when
+    /// both are set; never sent to clients
+    #[serde(default, skip_serializing)]
+    pub ssh_private_key: Option<String>,
+    /// Values of the inventory's custom fields, by field name
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, Value>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
     pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
     pub updated_at: DateTime<Utc>,
 }
 
+sanitized_debug!(Node {
+    id, name, description, host, port, status, last_seen, version, uptime, use_https, verify_ssl, tags, timeout,
+    team_id, kind, transport, ssh_port, ssh_username, custom_fields, created_at, updated_at,
+} secret {
+    api_key, ssh_password, ssh_private_key,
+});
+
+impl Node {
+    /// SSH login of a router reached over SSH
+    pub fn ssh_login(&self) -> Option<SshLogin> {
+        let auth = match (&self.ssh_private_key, &self.ssh_password) {
+            (Some(key), _) => SshAuth::PrivateKey(key.clone()),
+            (None, Some(password)) => SshAuth::Password(password.clone()),
+            (None, None) => return None,
+        };
+        Some(SshLogin {
+            port: self.ssh_port.unwrap_or(DEFAULT_SSH_PORT),
+            username: self.ssh_username.clone()?,
+            auth,
+        })
+    }
+}
+
 /// Create node request
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize)]
 pub struct CreateNodeRequest {
     pub name: String,
     pub description: Option<String>,
     pub host: String,
     pub port: Option<u16>,
+    /// API key; not needed for routers reached over SSH
+    #[serde(default)]
     pub api_key: String,
     pub use_https: Option<bool>,
     pub verify_ssl: Option<bool>,
     pub tags: Option<Vec<String>>,
     pub timeout: Option<u64>,
+    /// Team that will own the node
+    pub team_id: Option<Uuid>,
+    /// How the router is reached, over the API unless set
+    pub transport: Option<NodeTransportKind>,
+    pub ssh_port: Option<u16>,
+    /// SSH user, required for routers reached over SSH with a password or
+    /// a private key
+    pub ssh_username: Option<String>,
+    pub ssh_password: Option<String>,
+    pub ssh_private_key: Option<String>,
+    /// Values of custom fields; required fields must be given
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, Value>,
 }
 
+sanitized_debug!(CreateNodeRequest {
+    name, description, host, port, use_https, verify_ssl, tags, timeout, team_id, transport, ssh_port, ssh_username,
+    custom_fields,
+} secret {
+    api_key, ssh_password, ssh_private_key,
+});
+
 /// Update node request
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize)]
 pub struct UpdateNodeRequest {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -70,6 +221,52 @@ pub struct UpdateNodeRequest {
     pub verify_ssl: Option<bool>,
     pub tags: Option<Vec<String>>,
     pub timeout: Option<u64>,
+    pub transport: Option<NodeTransportKind>,
+    pub ssh_port: Option<u16>,
+    pub ssh_username: Option<String>,
+    pub ssh_password: Option<String>,
+    pub ssh_private_key: Option<String>,
+    /// Custom fields to set; `null` clears a field, others are kept
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, Value>,
+}
+
+sanitized_debug!(UpdateNodeRequest {
+    name, description, host, port, use_https, verify_ssl, tags, timeout, transport, ssh_port, ssh_username,
+    custom_fields,
+} secret {
+    api_key, ssh_password, ssh_private_key,
+});
+
+/// Create simulated node request
+///
+/// The node answers either from `recording` or from a recording taken of the
+/// router `record_from` when the request is made.
+#[derive(Debug, Deserialize)]
+pub struct CreateSimulatedNodeRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// Team that will own the node
+    pub team_id: Option<Uuid>,
+    /// Router to record
+    pub record_from: Option<Uuid>,
+    /// Recording to answer from, e.g. one exported from another installation
+    pub recording: Option<NodeRecording>,
+    /// Values of custom fields; required fields must be given
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, Value>,
+}
+
+/// Recording a simulated node answers from
+#[derive(Debug, Serialize)]
+pub struct NodeRecordingResponse {
+    pub node_id: Uuid,
+    /// Router the recording was taken of, while it still exists
+    pub recorded_from: Option<Uuid>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub recorded_at: DateTime<Utc>,
+    pub recording: NodeRecording,
 }
 
 /// Node list query parameters
@@ -79,10 +276,21 @@ pub struct NodeListQuery {
     pub page_size: Option<u32>,
     pub status: Option<NodeStatus>,
     pub search: Option<String>,
+    /// Filter by owning team
+    pub team_id: Option<Uuid>,
+    /// Filter by custom field values, as comma-separated `name:value` pairs
+    pub custom_fields: Option<String>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
 }
 
+/// Change node ownership request
+#[derive(Debug, Deserialize)]
+pub struct NodeOwnerRequest {
+    /// New owning team, or null to share the node with everyone
+    pub team_id: Option<Uuid>,
+}
+
 /// Paginated node list response
 #[derive(Debug, Serialize)]
 pub struct NodeListResponse {
@@ -104,16 +312,160 @@ pub struct NodeTestResult {
     pub uptime: Option<u64>,
 }
 
+/// API features supported by a node
+///
+/// Derived from the node's VyOS release, with optional endpoints confirmed by
+/// probing the node. Cached until the node is changed or probed again.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeCapabilities {
+    pub node_id: Uuid,
+    /// Version reported by the node
+    pub version: String,
+    /// Release train, or `None` if the version is not recognized
+    pub release: Option<VyOSRelease>,
+    pub capabilities: BTreeMap<Capability, bool>,
+    /// When the node was probed
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub checked_at: DateTime<Utc>,
+}
+
+impl NodeCapabilities {
+    /// Whether the node supports a capability
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.capabilities.get(&capability).copied().unwrap_or(false)
+    }
+}
+
+/// Data read from a node
+///
+/// While the node cannot be reached, the data last read from it is served
+/// instead, marked as stale.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeData<T> {
+    pub data: T,
+    /// Whether `data` is the last-known data of an unreachable node
+    pub stale: bool,
+    /// When `data` was read from the node
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub fetched_at: DateTime<Utc>,
+    /// Seconds since `data` was read from the node
+    pub age_secs: u64,
+    /// Why the node could not be read, for stale data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<T> NodeData<T> {
+    /// Derive other data from the data read from the node
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> NodeData<U> {
+        NodeData {
+            data: f(self.data),
+            stale: self.stale,
+            fetched_at: self.fetched_at,
+            age_secs: self.age_secs,
+            error: self.error,
+        }
+    }
+}
+
+/// Result of a show command, possibly answered from the show cache
+#[derive(Debug, Clone, Serialize)]
+pub struct CachedShowResult {
+    #[serde(flatten)]
+    pub result: VyOSShowResult,
+    /// Whether the result was answered from the cache rather than the node
+    pub cached: bool,
+    /// When the node produced the result
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub fetched_at: DateTime<Utc>,
+    /// Seconds since the node produced the result
+    pub age_secs: u64,
+}
+
+impl CachedShowResult {
+    /// A result the node produced just now
+    pub fn fresh(result: VyOSShowResult) -> Self {
+        Self {
+            result,
+            cached: false,
+            fetched_at: Utc::now(),
+            age_secs: 0,
+        }
+    }
+
+    /// The result as answered from the cache, with its current age
+    pub fn served_from_cache(&self) -> Self {
+        Self {
+            result: self.result.clone(),
+            cached: true,
+            fetched_at: self.fetched_at,
+            age_secs: (Utc::now() - self.fetched_at).num_seconds().max(0) as u64,
+        }
+    }
+}
+
+/// Query parameters of reads that may be answered from the show cache
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ShowCacheQuery {
+    /// Ask the node even if a fresh result is cached
+    #[serde(default)]
+    pub bypass_cache: bool,
+}
+
 /// Node health information
 #[derive(Debug, Serialize)]
 pub struct NodeHealthInfo {
     pub node_id: Uuid,
     pub status: NodeStatus,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
     pub last_check: DateTime<Utc>,
     pub latency_ms: Option<u64>,
     pub error_message: Option<String>,
 }
 
+/// Outcome on one node of a change made to many nodes
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeChangeResult {
+    pub node_id: Uuid,
+    pub node_name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Reboot of a node in progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeReboot {
+    pub node_id: Uuid,
+    /// Operation that took the node down, such as `reboot`
+    pub operation: String,
+    pub requested_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub started_at: DateTime<Utc>,
+    /// Time after which a node that has not answered is considered failed
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub expected_back_by: DateTime<Utc>,
+}
+
+/// Channel of the status changes of a node
+pub fn node_channel(node_id: Uuid) -> String {
+    format!("node:{}", node_id)
+}
+
+/// Node change broadcast on [`node_channel`]
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NodeEvent {
+    /// A health check found the node in a different status
+    StatusChanged {
+        node_id: Uuid,
+        previous: NodeStatus,
+        status: NodeStatus,
+        #[serde(with = "crate::models::timestamp::rfc3339")]
+        last_check: DateTime<Utc>,
+        error_message: Option<String>,
+    },
+}
+
 /// Node statistics summary
 #[derive(Debug, Serialize)]
 pub struct NodeStatistics {
@@ -149,6 +501,13 @@ mod tests {
             verify_ssl: None,
             tags: None,
             timeout: None,
+            team_id: None,
+            transport: None,
+            ssh_port: None,
+            ssh_username: None,
+            ssh_password: None,
+            ssh_private_key: None,
+            custom_fields: BTreeMap::new(),
         };
 
         assert_eq!(request.name, "test-node");
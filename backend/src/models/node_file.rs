@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::storage::Compression;
+
+/// Largest accepted version of a node's notes, in bytes
+pub const MAX_NOTE_BYTES: usize = 256 * 1024;
+
+/// Markdown notes of a node, at one version
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeNote {
+    pub node_id: Uuid,
+    pub version: u32,
+    pub content: String,
+    pub author: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// A saved version of a node's notes, without its content
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeNoteVersion {
+    pub version: u32,
+    pub size_bytes: u64,
+    /// Hex-encoded SHA-256 of the content
+    pub sha256: String,
+    pub author: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to save a new version of a node's notes
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateNodeNoteRequest {
+    /// Markdown text; empty to clear the notes
+    pub content: String,
+}
+
+/// Metadata of a file attached to a node
+///
+/// Attaching a file under a name the node already has an attachment of
+/// adds a version; every version keeps its own ID and content.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeAttachment {
+    pub id: Uuid,
+    pub node_id: Uuid,
+    pub file_name: String,
+    pub version: u32,
+    pub content_type: Option<String>,
+    /// Size of the content as uploaded and downloaded
+    pub size_bytes: u64,
+    /// Hex-encoded SHA-256 of the content
+    pub sha256: String,
+    pub compression: Compression,
+    /// Size of the content in the artifact store
+    pub stored_bytes: u64,
+    pub uploaded_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Attachment list query parameters
+#[derive(Debug, Deserialize)]
+pub struct NodeAttachmentListQuery {
+    /// List every version of this file instead of the latest version of each
+    pub file_name: Option<String>,
+}
+
+/// Whether attachments of a content type are compressed at rest
+///
+/// Photos, PDFs, and archives are compressed already and would only cost
+/// CPU time.
+pub fn attachment_compression(content_type: Option<&str>) -> Compression {
+    let essence = content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|essence| essence.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let textual = essence.starts_with("text/")
+        || essence.ends_with("+xml")
+        || essence.ends_with("+json")
+        || matches!(essence.as_str(), "application/json" | "application/xml" | "image/bmp" | "image/tiff");
+    if textual {
+        Compression::Zstd
+    } else {
+        Compression::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_compression() {
+        assert_eq!(attachment_compression(Some("text/markdown; charset=utf-8")), Compression::Zstd);
+        assert_eq!(attachment_compression(Some("image/svg+xml")), Compression::Zstd);
+        assert_eq!(attachment_compression(Some("application/json")), Compression::Zstd);
+        assert_eq!(attachment_compression(Some("image/jpeg")), Compression::None);
+        assert_eq!(attachment_compression(Some("application/pdf")), Compression::None);
+        assert_eq!(attachment_compression(None), Compression::None);
+    }
+}
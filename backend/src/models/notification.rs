@@ -0,0 +1,271 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::monitoring::AlertSeverity;
+
+/// Times a failed delivery is attempted before it is given up
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Deliveries listed when a query does not set a limit
+pub const DEFAULT_DELIVERY_LIMIT: u32 = 100;
+
+/// Where a channel sends notifications
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChannelTarget {
+    /// Email through the configured SMTP relay
+    Email {
+        /// Addresses, or team aliases written as `@alias`
+        recipients: Vec<String>,
+    },
+    /// JSON POST to any URL
+    Webhook {
+        url: String,
+        /// Key of the HMAC-SHA256 body signature sent in the
+        /// `X-Signature-256` header; never returned, and kept on updates
+        /// that leave it out
+        #[serde(default, skip_serializing)]
+        secret: Option<String>,
+    },
+    /// Slack-compatible incoming webhook
+    Slack { webhook_url: String },
+}
+
+impl ChannelTarget {
+    /// Convert the kind to string for database storage
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ChannelTarget::Email { .. } => "email",
+            ChannelTarget::Webhook { .. } => "webhook",
+            ChannelTarget::Slack { .. } => "slack",
+        }
+    }
+}
+
+/// Notification channel
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationChannel {
+    pub id: Uuid,
+    pub name: String,
+    pub target: ChannelTarget,
+    pub enabled: bool,
+    pub created_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to create a notification channel
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateNotificationChannelRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub target: ChannelTarget,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// Request to update a notification channel; absent fields are kept
+#[derive(Debug, Clone, Default, Deserialize, Validate)]
+pub struct UpdateNotificationChannelRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    pub target: Option<ChannelTarget>,
+    pub enabled: Option<bool>,
+}
+
+/// Route sending the alerts it matches to a channel
+///
+/// An alert matches when it is at least as severe as `min_severity` and
+/// comes from the route's rule and node, where set.
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationRoute {
+    pub id: Uuid,
+    pub name: String,
+    pub channel_id: Uuid,
+    /// Only alerts raised by this alert rule
+    pub rule_id: Option<Uuid>,
+    /// Only alerts on this node
+    pub node_id: Option<Uuid>,
+    pub min_severity: AlertSeverity,
+    /// Also notify when a matched alert is resolved
+    pub notify_resolved: bool,
+    pub enabled: bool,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl NotificationRoute {
+    /// Whether an alert from a rule and node, at a severity, is routed here
+    pub fn matches(&self, rule_id: Option<Uuid>, node_id: &str, severity: AlertSeverity) -> bool {
+        self.enabled
+            && severity >= self.min_severity
+            && self.rule_id.is_none_or(|id| rule_id == Some(id))
+            && self.node_id.is_none_or(|id| id.to_string() == node_id)
+    }
+}
+
+/// Request to create a notification route, or to replace one
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateNotificationRouteRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub channel_id: Uuid,
+    pub rule_id: Option<Uuid>,
+    pub node_id: Option<Uuid>,
+    #[serde(default = "default_min_severity")]
+    pub min_severity: AlertSeverity,
+    #[serde(default = "default_enabled")]
+    pub notify_resolved: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_min_severity() -> AlertSeverity {
+    AlertSeverity::Warning
+}
+
+/// What a notification was sent for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    Triggered,
+    Resolved,
+    /// Sent on request to check a channel
+    Test,
+}
+
+impl NotificationEvent {
+    /// Convert event to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEvent::Triggered => "triggered",
+            NotificationEvent::Resolved => "resolved",
+            NotificationEvent::Test => "test",
+        }
+    }
+
+    /// Parse event from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "resolved" => NotificationEvent::Resolved,
+            "test" => NotificationEvent::Test,
+            _ => NotificationEvent::Triggered,
+        }
+    }
+}
+
+/// Outcome of a delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Delivered,
+    /// Retried on later dispatches until it was attempted
+    /// [`MAX_DELIVERY_ATTEMPTS`] times
+    Failed,
+}
+
+impl DeliveryStatus {
+    /// Convert status to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+
+    /// Parse status from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "delivered" => DeliveryStatus::Delivered,
+            _ => DeliveryStatus::Failed,
+        }
+    }
+}
+
+/// Entry of the delivery log
+///
+/// One entry per alert, channel, and event; retries update it.
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationDelivery {
+    pub id: Uuid,
+    pub channel_id: Uuid,
+    /// First route that matched the alert; `None` for test notifications
+    pub route_id: Option<Uuid>,
+    pub alert_id: Option<Uuid>,
+    pub event: NotificationEvent,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    /// Error of the last failed attempt
+    pub error: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Delivery log query parameters
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DeliveryListQuery {
+    pub channel_id: Option<Uuid>,
+    pub alert_id: Option<Uuid>,
+    pub status: Option<DeliveryStatus>,
+    /// Newest deliveries returned, [`DEFAULT_DELIVERY_LIMIT`] by default
+    pub limit: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(rule_id: Option<Uuid>, node_id: Option<Uuid>) -> NotificationRoute {
+        NotificationRoute {
+            id: Uuid::new_v4(),
+            name: "on-call".to_string(),
+            channel_id: Uuid::new_v4(),
+            rule_id,
+            node_id,
+            min_severity: AlertSeverity::Warning,
+            notify_resolved: true,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_route_matching() {
+        let rule = Uuid::new_v4();
+        let node = Uuid::new_v4();
+
+        let any = route(None, None);
+        assert!(any.matches(None, &node.to_string(), AlertSeverity::Critical));
+        assert!(!any.matches(None, &node.to_string(), AlertSeverity::Info));
+
+        let scoped = route(Some(rule), Some(node));
+        assert!(scoped.matches(Some(rule), &node.to_string(), AlertSeverity::Warning));
+        assert!(!scoped.matches(None, &node.to_string(), AlertSeverity::Warning));
+        assert!(!scoped.matches(Some(rule), &Uuid::new_v4().to_string(), AlertSeverity::Warning));
+
+        let disabled = NotificationRoute { enabled: false, ..any };
+        assert!(!disabled.matches(None, &node.to_string(), AlertSeverity::Critical));
+    }
+
+    #[test]
+    fn test_webhook_secret_is_write_only() {
+        let json = serde_json::json!({ "kind": "webhook", "url": "https://hooks.example.com", "secret": "s3cret" });
+        let target: ChannelTarget = serde_json::from_value(json).unwrap();
+        assert_eq!(target.kind(), "webhook");
+        let json = serde_json::to_value(&target).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "webhook", "url": "https://hooks.example.com" }));
+    }
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Permission to read secrets in node configuration, such as pre-shared
+/// keys and SNMP communities
+pub const SECRETS_READ: &str = "secrets:read";
+
+/// Permission to change nodes, including their notes and attachments
+pub const NODES_WRITE: &str = "nodes.write";
+
+/// Permissions of a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserPermissions {
+    pub user_id: Uuid,
+    /// Every permission the user holds, through their roles or a grant;
+    /// administrators hold all permissions
+    pub permissions: Vec<String>,
+    /// Permissions granted to the user directly
+    pub granted: Vec<String>,
+}
+
+/// Request to replace the permissions granted to a user directly
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetUserPermissionsRequest {
+    pub permissions: Vec<String>,
+}
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Resource kinds that count against a team quota
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaResource {
+    Nodes,
+    Templates,
+    ApiKeys,
+}
+
+impl QuotaResource {
+    /// All countable quota resources
+    pub const ALL: [QuotaResource; 3] = [
+        QuotaResource::Nodes,
+        QuotaResource::Templates,
+        QuotaResource::ApiKeys,
+    ];
+
+    /// Human readable resource name used in error messages
+    pub fn label(&self) -> &'static str {
+        match self {
+            QuotaResource::Nodes => "nodes",
+            QuotaResource::Templates => "templates",
+            QuotaResource::ApiKeys => "API keys",
+        }
+    }
+}
+
+/// Effective quota limits for a team (`None` means unlimited)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TeamQuota {
+    pub max_nodes: Option<u32>,
+    pub max_templates: Option<u32>,
+    pub max_api_keys: Option<u32>,
+    /// How long metrics of the team's nodes are kept
+    pub metric_retention_days: Option<u32>,
+}
+
+impl TeamQuota {
+    /// Get the limit for a countable resource
+    pub fn limit(&self, resource: QuotaResource) -> Option<u32> {
+        match resource {
+            QuotaResource::Nodes => self.max_nodes,
+            QuotaResource::Templates => self.max_templates,
+            QuotaResource::ApiKeys => self.max_api_keys,
+        }
+    }
+
+    /// Fill unset limits from another quota
+    pub fn or(self, defaults: &TeamQuota) -> TeamQuota {
+        TeamQuota {
+            max_nodes: self.max_nodes.or(defaults.max_nodes),
+            max_templates: self.max_templates.or(defaults.max_templates),
+            max_api_keys: self.max_api_keys.or(defaults.max_api_keys),
+            metric_retention_days: self.metric_retention_days.or(defaults.metric_retention_days),
+        }
+    }
+}
+
+/// Team quota response
+#[derive(Debug, Serialize)]
+pub struct TeamQuotaResponse {
+    pub team_id: Uuid,
+    /// Limits explicitly configured for the team
+    pub overrides: TeamQuota,
+    /// Limits in effect after applying server defaults
+    pub effective: TeamQuota,
+}
+
+/// Update team quota request
+///
+/// Replaces all overrides; `null` fields fall back to the server defaults.
+pub type UpdateTeamQuotaRequest = TeamQuota;
+
+/// Consumption of a single quota resource
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaUsage {
+    pub resource: QuotaResource,
+    pub used: u64,
+    pub limit: Option<u32>,
+    pub remaining: Option<u64>,
+}
+
+impl QuotaUsage {
+    /// Build a usage entry from a count and limit
+    pub fn new(resource: QuotaResource, used: u64, limit: Option<u32>) -> Self {
+        Self {
+            resource,
+            used,
+            limit,
+            remaining: limit.map(|l| (l as u64).saturating_sub(used)),
+        }
+    }
+
+    /// Check whether another resource can be created
+    pub fn has_capacity(&self) -> bool {
+        self.remaining.is_none_or(|r| r > 0)
+    }
+}
+
+/// Team usage response
+#[derive(Debug, Serialize)]
+pub struct TeamUsageResponse {
+    pub team_id: Uuid,
+    pub usage: Vec<QuotaUsage>,
+    pub metric_retention_days: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_fallback_to_defaults() {
+        let defaults = TeamQuota {
+            max_nodes: Some(10),
+            max_templates: Some(5),
+            max_api_keys: None,
+            metric_retention_days: Some(30),
+        };
+        let overrides = TeamQuota {
+            max_nodes: Some(2),
+            ..Default::default()
+        };
+
+        let effective = overrides.or(&defaults);
+        assert_eq!(effective.limit(QuotaResource::Nodes), Some(2));
+        assert_eq!(effective.limit(QuotaResource::Templates), Some(5));
+        assert_eq!(effective.limit(QuotaResource::ApiKeys), None);
+        assert_eq!(effective.metric_retention_days, Some(30));
+    }
+
+    #[test]
+    fn test_quota_usage_capacity() {
+        let usage = QuotaUsage::new(QuotaResource::Nodes, 3, Some(3));
+        assert_eq!(usage.remaining, Some(0));
+        assert!(!usage.has_capacity());
+
+        let usage = QuotaUsage::new(QuotaResource::Nodes, 5, Some(3));
+        assert_eq!(usage.remaining, Some(0));
+
+        let usage = QuotaUsage::new(QuotaResource::ApiKeys, 100, None);
+        assert!(usage.has_capacity());
+    }
+
+    #[test]
+    fn test_quota_resource_serialization() {
+        let json = serde_json::to_string(&QuotaResource::ApiKeys).unwrap();
+        assert_eq!(json, "\"api_keys\"");
+    }
+}
@@ -0,0 +1,248 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Reporting period used when a request does not set one
+pub const DEFAULT_REPORT_PERIOD_DAYS: u32 = 7;
+
+/// Availability below this percentage breaches the SLA
+pub const SLA_TARGET_PERCENT: f64 = 99.9;
+
+/// Report content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportKind {
+    /// Node status and active alerts
+    FleetHealth,
+    /// Per-node availability derived from critical incidents
+    Sla,
+    /// Configuration commits made during the period
+    ConfigChanges,
+    /// Per-node security and backup checks
+    Compliance,
+}
+
+impl ReportKind {
+    /// Convert kind to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportKind::FleetHealth => "fleet_health",
+            ReportKind::Sla => "sla",
+            ReportKind::ConfigChanges => "config_changes",
+            ReportKind::Compliance => "compliance",
+        }
+    }
+
+    /// Parse kind from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "sla" => ReportKind::Sla,
+            "config_changes" => ReportKind::ConfigChanges,
+            "compliance" => ReportKind::Compliance,
+            _ => ReportKind::FleetHealth,
+        }
+    }
+
+    /// Human-readable report title
+    pub fn title(&self) -> &'static str {
+        match self {
+            ReportKind::FleetHealth => "Fleet Health Report",
+            ReportKind::Sla => "SLA Report",
+            ReportKind::ConfigChanges => "Configuration Change Summary",
+            ReportKind::Compliance => "Compliance Report",
+        }
+    }
+}
+
+/// Rendered report format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Html,
+    Pdf,
+}
+
+impl ReportFormat {
+    /// Convert format to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Pdf => "pdf",
+        }
+    }
+
+    /// Parse format from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "pdf" => ReportFormat::Pdf,
+            _ => ReportFormat::Html,
+        }
+    }
+
+    /// MIME type of the rendered artifact
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "text/html; charset=utf-8",
+            ReportFormat::Pdf => "application/pdf",
+        }
+    }
+}
+
+/// How often a scheduled report is generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl ReportFrequency {
+    /// Convert frequency to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportFrequency::Daily => "daily",
+            ReportFrequency::Weekly => "weekly",
+            ReportFrequency::Monthly => "monthly",
+        }
+    }
+
+    /// Parse frequency from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "daily" => ReportFrequency::Daily,
+            "monthly" => ReportFrequency::Monthly,
+            _ => ReportFrequency::Weekly,
+        }
+    }
+
+    /// Time between runs, which is also the period each run covers
+    pub fn interval(&self) -> Duration {
+        match self {
+            ReportFrequency::Daily => Duration::days(1),
+            ReportFrequency::Weekly => Duration::days(7),
+            ReportFrequency::Monthly => Duration::days(30),
+        }
+    }
+}
+
+/// Generated report artifact
+///
+/// The rendered content is downloaded separately.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub id: Uuid,
+    pub kind: ReportKind,
+    pub format: ReportFormat,
+    pub title: String,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub period_start: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub period_end: DateTime<Utc>,
+    pub size_bytes: u64,
+    /// Schedule that produced the report; unset for on-demand reports
+    pub schedule_id: Option<Uuid>,
+    pub created_by: Option<String>,
+    /// Addresses the report was emailed to
+    pub emailed_to: Vec<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Rendered report ready for download
+#[derive(Debug, Clone)]
+pub struct ReportArtifact {
+    pub report: Report,
+    pub content: Vec<u8>,
+}
+
+impl ReportArtifact {
+    /// File name offered to browsers and used for email attachments
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}-{}.{}",
+            self.report.kind.as_str().replace('_', "-"),
+            self.report.period_end.format("%Y%m%d-%H%M%S"),
+            self.report.format.as_str()
+        )
+    }
+}
+
+/// Report generation request
+#[derive(Debug, Deserialize, Validate)]
+pub struct GenerateReportRequest {
+    pub kind: ReportKind,
+    #[serde(default)]
+    pub format: ReportFormat,
+    /// Days before now that the report covers
+    #[validate(range(min = 1, max = 366))]
+    pub period_days: Option<u32>,
+    /// Addresses to email the report to
+    #[serde(default)]
+    pub recipients: Vec<String>,
+}
+
+/// Recurring report
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportSchedule {
+    pub id: Uuid,
+    pub kind: ReportKind,
+    pub format: ReportFormat,
+    pub frequency: ReportFrequency,
+    pub recipients: Vec<String>,
+    pub created_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub next_run_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub last_run_at: Option<DateTime<Utc>>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to schedule a recurring report
+#[derive(Debug, Deserialize)]
+pub struct CreateReportScheduleRequest {
+    pub kind: ReportKind,
+    #[serde(default)]
+    pub format: ReportFormat,
+    pub frequency: ReportFrequency,
+    #[serde(default)]
+    pub recipients: Vec<String>,
+}
+
+/// Report list query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct ReportListQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub kind: Option<ReportKind>,
+}
+
+/// Report list response
+#[derive(Debug, Serialize)]
+pub struct ReportListResponse {
+    pub reports: Vec<Report>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_enums_round_trip() {
+        for kind in [ReportKind::FleetHealth, ReportKind::Sla, ReportKind::ConfigChanges, ReportKind::Compliance] {
+            assert_eq!(ReportKind::parse(kind.as_str()), kind);
+        }
+        for frequency in [ReportFrequency::Daily, ReportFrequency::Weekly, ReportFrequency::Monthly] {
+            assert_eq!(ReportFrequency::parse(frequency.as_str()), frequency);
+        }
+        assert_eq!(ReportFormat::parse("pdf"), ReportFormat::Pdf);
+        assert_eq!(ReportFormat::parse("unknown"), ReportFormat::Html);
+    }
+}
@@ -0,0 +1,187 @@
+//! Response envelope
+//!
+//! New endpoints answer with an [`ApiResponse`], holding the requested data
+//! along with warnings that did not stop the request, such as lint findings,
+//! commit warnings, or the nodes a bulk change skipped, and metadata about
+//! the response, such as paging:
+//!
+//! ```json
+//! { "data": { ... }, "warnings": [{ "message": "...", "path": "..." }], "meta": { "total": 3 } }
+//! ```
+//!
+//! Errors keep the shape of [`ErrorResponse`](crate::error::ErrorResponse).
+
+use actix_web::body::BoxBody;
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::AppResult;
+
+/// Envelope of a successful response
+#[derive(Debug, Serialize)]
+pub struct ApiResponse<T> {
+    pub data: T,
+    pub warnings: Vec<ApiWarning>,
+    pub meta: Map<String, Value>,
+    #[serde(skip)]
+    status: StatusCode,
+}
+
+/// Problem that did not stop a request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiWarning {
+    /// Machine-readable kind of the warning, e.g. a lint rule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub message: String,
+    /// What the warning is about, e.g. a configuration path or a node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl ApiWarning {
+    /// Create a warning with a message
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            code: None,
+            message: message.into(),
+            path: None,
+        }
+    }
+
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl From<String> for ApiWarning {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for ApiWarning {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl<T: Serialize> ApiResponse<T> {
+    /// Wrap data in an envelope answered with 200 OK
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            warnings: Vec::new(),
+            meta: Map::new(),
+            status: StatusCode::OK,
+        }
+    }
+
+    /// Wrap data in an envelope answered with 201 Created
+    pub fn created(data: T) -> Self {
+        Self::new(data).with_status(StatusCode::CREATED)
+    }
+
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_warning(mut self, warning: impl Into<ApiWarning>) -> Self {
+        self.warnings.push(warning.into());
+        self
+    }
+
+    pub fn with_warnings<W: Into<ApiWarning>>(mut self, warnings: impl IntoIterator<Item = W>) -> Self {
+        self.warnings.extend(warnings.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add metadata about the response; values that cannot be serialized
+    /// are left out
+    pub fn with_meta(mut self, key: &str, value: impl Serialize) -> Self {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.meta.insert(key.to_string(), value);
+        }
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+impl<T: Serialize> Responder for ApiResponse<T> {
+    type Body = BoxBody;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::build(self.status).json(&self)
+    }
+}
+
+/// Helpers wrapping the outcome of a service call in an [`ApiResponse`],
+/// e.g. `service.get_baseline(id).await.enveloped()`
+pub trait ApiResultExt<T> {
+    /// Wrap the value in an envelope without warnings
+    fn enveloped(self) -> AppResult<ApiResponse<T>>;
+
+    /// Wrap the value in an envelope with warnings
+    fn with_warnings<W: Into<ApiWarning>>(self, warnings: impl IntoIterator<Item = W>) -> AppResult<ApiResponse<T>>;
+}
+
+impl<T: Serialize> ApiResultExt<T> for AppResult<T> {
+    fn enveloped(self) -> AppResult<ApiResponse<T>> {
+        self.map(ApiResponse::new)
+    }
+
+    fn with_warnings<W: Into<ApiWarning>>(self, warnings: impl IntoIterator<Item = W>) -> AppResult<ApiResponse<T>> {
+        self.map(|data| ApiResponse::new(data).with_warnings(warnings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+    use crate::error::AppError;
+
+    #[actix_web::test]
+    async fn test_api_response_envelope() {
+        let response = ApiResponse::created(vec![1, 2])
+            .with_warning("Node edge-2 skipped")
+            .with_warning(ApiWarning::new("Deprecated option").with_code("deprecated").with_path("system ntp"))
+            .with_meta("total", 2);
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({
+                "data": [1, 2],
+                "warnings": [
+                    { "message": "Node edge-2 skipped" },
+                    { "code": "deprecated", "message": "Deprecated option", "path": "system ntp" },
+                ],
+                "meta": { "total": 2 },
+            })
+        );
+
+        let http = response.respond_to(&TestRequest::default().to_http_request());
+        assert_eq!(http.status(), StatusCode::CREATED);
+
+        let ok: AppResult<&str> = Ok("done");
+        let enveloped = ok.with_warnings(["partial"]).unwrap();
+        assert_eq!(enveloped.status(), StatusCode::OK);
+        assert_eq!(enveloped.warnings, [ApiWarning::new("partial")]);
+
+        let failed: AppResult<&str> = Err(AppError::NotFound("node".to_string()));
+        assert!(matches!(failed.enveloped(), Err(AppError::NotFound(_))));
+    }
+}
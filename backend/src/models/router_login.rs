@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Key types VyOS accepts for a login user's public key
+pub const SSH_KEY_TYPES: &[&str] = &[
+    "ssh-rsa",
+    "ssh-dss",
+    "ssh-ed25519",
+    "ecdsa-sha2-nistp256",
+    "ecdsa-sha2-nistp384",
+    "ecdsa-sha2-nistp521",
+    "sk-ssh-ed25519@openssh.com",
+    "sk-ecdsa-sha2-nistp256@openssh.com",
+];
+
+/// SSH public key of a login user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshPublicKey {
+    /// Name of the key on the node, e.g. `alice@laptop`
+    pub name: String,
+    /// Key type, e.g. `ssh-ed25519`
+    #[serde(rename = "type")]
+    pub key_type: String,
+    /// Base64 encoded key, without the type and comment
+    pub key: String,
+}
+
+impl SshPublicKey {
+    /// Whether two keys have the same key material, whatever their names
+    pub fn same_key(&self, other: &SshPublicKey) -> bool {
+        self.key_type == other.key_type && self.key == other.key
+    }
+}
+
+/// Settings of a login user
+///
+/// The password is only ever written; it is sent to the node, which stores
+/// its hash.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RouterLoginUserSettings {
+    pub full_name: Option<String>,
+    /// New password; the user's current password is kept if unset
+    pub password: Option<String>,
+    /// Public keys; those left out are removed
+    #[serde(default)]
+    pub ssh_keys: Vec<SshPublicKey>,
+}
+
+/// Login user of a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouterLoginUser {
+    pub name: String,
+    pub full_name: Option<String>,
+    /// Whether the user can log in with a password
+    pub has_password: bool,
+    pub ssh_keys: Vec<SshPublicKey>,
+}
+
+/// Request to add a public key to a login user on many nodes, replacing
+/// an older one
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct RotateSshKeyRequest {
+    /// Login user holding the key
+    #[validate(length(min = 1, max = 64))]
+    pub user: String,
+    pub key: SshPublicKey,
+    /// Name of the key being replaced, removed once the new key is set
+    pub replaces: Option<String>,
+    /// Nodes to change; every node the caller can access if unset
+    #[validate(length(min = 1, max = 1000))]
+    pub node_ids: Option<Vec<Uuid>>,
+}
+
+/// Login user the operators expect on every node, with their keys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DesiredLoginUser {
+    pub name: String,
+    #[serde(default)]
+    pub ssh_keys: Vec<SshPublicKey>,
+}
+
+/// Request to compare the login users of nodes with the desired ones
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct LoginDriftRequest {
+    #[validate(length(min = 1, max = 1000))]
+    pub users: Vec<DesiredLoginUser>,
+    /// Nodes to check; every node the caller can access if unset
+    #[validate(length(min = 1, max = 1000))]
+    pub node_ids: Option<Vec<Uuid>>,
+}
+
+/// Keys of a desired user that differ on a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoginUserKeyDrift {
+    pub user: String,
+    /// Names of desired keys the node lacks
+    pub missing_keys: Vec<String>,
+    /// Names, on the node, of keys that are not desired
+    pub unexpected_keys: Vec<String>,
+}
+
+/// How the login users of a node differ from the desired ones
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NodeLoginDrift {
+    pub node_id: Uuid,
+    pub node_name: String,
+    /// Desired users the node lacks
+    pub missing_users: Vec<String>,
+    /// Users of the node that are not desired
+    pub unexpected_users: Vec<String>,
+    pub key_drift: Vec<LoginUserKeyDrift>,
+    pub in_sync: bool,
+    /// Why the node could not be checked
+    pub error: Option<String>,
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vyos_client::ConfigOperation;
+
+/// Highest policy routing table id VyOS accepts
+pub const MAX_ROUTING_TABLE: u32 = 200;
+
+/// Highest policy route rule number VyOS accepts
+pub const MAX_POLICY_RULE_NUMBER: u32 = 999_999;
+
+/// Table a policy route rule may send packets to besides the numbered ones
+pub const MAIN_TABLE: &str = "main";
+
+/// Address family of a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl RouteFamily {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ipv4 => "ipv4",
+            Self::Ipv6 => "ipv6",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ipv4" => Some(Self::Ipv4),
+            "ipv6" => Some(Self::Ipv6),
+            _ => None,
+        }
+    }
+
+    /// Configuration node holding routes of the family, e.g. `route6`
+    pub fn route_node(&self) -> &'static str {
+        match self {
+            Self::Ipv4 => "route",
+            Self::Ipv6 => "route6",
+        }
+    }
+}
+
+/// Gateway of a static route
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteNextHop {
+    pub address: String,
+    /// Interface the gateway is reached through, for link-local gateways
+    pub interface: Option<String>,
+    /// Administrative distance, 1-255
+    pub distance: Option<u8>,
+}
+
+/// Interface a static route sends packets out of, without a gateway
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteInterface {
+    pub interface: String,
+    pub distance: Option<u8>,
+}
+
+/// Settings of a static route
+///
+/// A route has next hops and interfaces, or is a blackhole silently
+/// dropping its packets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticRouteSettings {
+    pub description: Option<String>,
+    #[serde(default)]
+    pub next_hops: Vec<RouteNextHop>,
+    #[serde(default)]
+    pub interfaces: Vec<RouteInterface>,
+    /// Whether the route is a blackhole
+    #[serde(default)]
+    pub blackhole: bool,
+    /// Administrative distance of a blackhole route
+    pub blackhole_distance: Option<u8>,
+}
+
+/// Static route of a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StaticRoute {
+    pub family: RouteFamily,
+    /// Destination network, e.g. `10.0.0.0/8`
+    pub destination: String,
+    /// Policy routing table holding the route; the main table if unset
+    pub table: Option<u32>,
+    /// Configuration path of the route, e.g. `protocols static route 10.0.0.0/8`
+    pub path: String,
+    #[serde(flatten)]
+    pub settings: StaticRouteSettings,
+}
+
+/// Request to add or replace a static route
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaticRouteRequest {
+    pub destination: String,
+    pub table: Option<u32>,
+    #[serde(flatten)]
+    pub settings: StaticRouteSettings,
+    /// Only compute the commands, without applying them
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Query selecting a static route to delete
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaticRouteQuery {
+    pub destination: String,
+    pub table: Option<u32>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Source or destination a policy route rule matches
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyRouteMatch {
+    /// Address or network
+    pub address: Option<String>,
+    /// Ports, e.g. `443` or `8000-8080`, with a TCP or UDP protocol
+    pub port: Option<String>,
+}
+
+impl PolicyRouteMatch {
+    /// Whether no criterion is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Numbered rule of a policy route
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyRouteRule {
+    pub number: u32,
+    pub description: Option<String>,
+    /// Protocol name or number, e.g. `tcp`
+    pub protocol: Option<String>,
+    #[serde(default, skip_serializing_if = "PolicyRouteMatch::is_empty")]
+    pub source: PolicyRouteMatch,
+    #[serde(default, skip_serializing_if = "PolicyRouteMatch::is_empty")]
+    pub destination: PolicyRouteMatch,
+    /// Table matching packets are routed by: a table id or `main`
+    pub table: String,
+}
+
+/// Settings of a policy route
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyRouteSettings {
+    pub description: Option<String>,
+    /// Interfaces whose incoming packets the policy applies to
+    #[serde(default)]
+    pub interfaces: Vec<String>,
+    /// Rules, evaluated in ascending order of their numbers
+    #[serde(default)]
+    pub rules: Vec<PolicyRouteRule>,
+}
+
+/// Policy route of a node, choosing the routing table of packets by their
+/// source, destination, and protocol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolicyRoute {
+    pub family: RouteFamily,
+    pub name: String,
+    /// Configuration path of the policy, e.g. `policy route LAN_PBR`
+    pub path: String,
+    #[serde(flatten)]
+    pub settings: PolicyRouteSettings,
+}
+
+/// Request to create or replace a policy route
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolicyRouteRequest {
+    #[serde(flatten)]
+    pub settings: PolicyRouteSettings,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Dry-run flag of a policy route removal, passed in the query string
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PolicyRouteQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Commands making a routing change
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutingPlan {
+    /// The change as configuration-mode commands, e.g.
+    /// `set protocols static route 10.0.0.0/8 next-hop 192.0.2.1`
+    pub commands: Vec<String>,
+    pub operations: Vec<ConfigOperation>,
+    /// Whether the operations were applied; false for dry runs
+    pub applied: bool,
+}
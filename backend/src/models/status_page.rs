@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::models::monitoring::AlertStatus;
+
+/// Section of the status page whose visibility can be configured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusComponent {
+    /// Node counts by status
+    Nodes,
+    /// Active alert counts
+    Alerts,
+    /// Most recent critical alert
+    LastIncident,
+}
+
+impl StatusComponent {
+    /// All components, in the order they are listed
+    pub const ALL: [StatusComponent; 3] = [
+        StatusComponent::Nodes,
+        StatusComponent::Alerts,
+        StatusComponent::LastIncident,
+    ];
+
+    /// Convert component to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusComponent::Nodes => "nodes",
+            StatusComponent::Alerts => "alerts",
+            StatusComponent::LastIncident => "last_incident",
+        }
+    }
+
+    /// Parse component from database string
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|component| component.as_str() == s)
+    }
+}
+
+/// Who may see a status page component
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentVisibility {
+    /// Everyone, including anonymous wallboards
+    Public,
+    /// Signed-in users
+    #[default]
+    Authenticated,
+    /// Nobody
+    Hidden,
+}
+
+impl ComponentVisibility {
+    /// Convert visibility to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComponentVisibility::Public => "public",
+            ComponentVisibility::Authenticated => "authenticated",
+            ComponentVisibility::Hidden => "hidden",
+        }
+    }
+
+    /// Parse visibility from database string
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "public" => ComponentVisibility::Public,
+            "hidden" => ComponentVisibility::Hidden,
+            _ => ComponentVisibility::Authenticated,
+        }
+    }
+
+    /// Whether a caller may see the component
+    pub fn allows(&self, authenticated: bool) -> bool {
+        match self {
+            ComponentVisibility::Public => true,
+            ComponentVisibility::Authenticated => authenticated,
+            ComponentVisibility::Hidden => false,
+        }
+    }
+}
+
+/// Overall fleet health
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FleetStatus {
+    /// All nodes online and no active critical alerts
+    Operational,
+    /// Some nodes are not online
+    Degraded,
+    /// Critical alerts are active, or no node is online
+    MajorOutage,
+}
+
+/// Node counts on the status page
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeStatusSummary {
+    pub total: u64,
+    /// Node counts keyed by node status
+    pub by_status: BTreeMap<String, u64>,
+}
+
+/// Active alert counts on the status page
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertStatusSummary {
+    pub active_critical: u64,
+    pub active_warning: u64,
+}
+
+/// Most recent critical alert on the status page
+#[derive(Debug, Clone, Serialize)]
+pub struct IncidentSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub node_id: String,
+    pub status: AlertStatus,
+    pub triggered_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Fleet status page
+///
+/// Components the caller may not see are left out.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusPage {
+    pub status: FleetStatus,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub generated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nodes: Option<NodeStatusSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alerts: Option<AlertStatusSummary>,
+    /// Unset when the component is hidden; null when there was no incident
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_incident: Option<Option<IncidentSummary>>,
+}
+
+/// Status page component visibility settings
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusPageSettings {
+    pub components: BTreeMap<StatusComponent, ComponentVisibility>,
+}
+
+/// Request to change status page component visibility
+///
+/// Components that are left out keep their visibility.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateStatusPageSettingsRequest {
+    pub components: HashMap<StatusComponent, ComponentVisibility>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_visibility() {
+        assert!(ComponentVisibility::Public.allows(false));
+        assert!(!ComponentVisibility::Authenticated.allows(false));
+        assert!(ComponentVisibility::Authenticated.allows(true));
+        assert!(!ComponentVisibility::Hidden.allows(true));
+        assert_eq!(ComponentVisibility::parse("unknown"), ComponentVisibility::Authenticated);
+        assert_eq!(StatusComponent::parse("last_incident"), Some(StatusComponent::LastIncident));
+    }
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+/// How stored content is compressed at rest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    /// Stored as is, for content that is already compressed
+    #[default]
+    None,
+    /// Zstandard frames
+    Zstd,
+}
+
+impl Compression {
+    /// Convert compression to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// Parse compression from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "zstd" => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Size of stored content before and after compression
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StoredSize {
+    /// Bytes of the content as written and read back
+    pub original_bytes: u64,
+    /// Bytes taken on disk
+    pub stored_bytes: u64,
+}
+
+/// Kinds of data the backend keeps on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageCategory {
+    /// SQLite database with configuration history and metrics
+    Database,
+    /// Database backups
+    Backups,
+    /// Uploaded configurations, images, and CA certificates, and node notes
+    /// and attachments
+    Uploads,
+}
+
+impl StorageCategory {
+    /// All categories, in the order they are reported
+    pub const ALL: [StorageCategory; 3] = [
+        StorageCategory::Database,
+        StorageCategory::Backups,
+        StorageCategory::Uploads,
+    ];
+
+    /// Human readable category name used in messages
+    pub fn label(&self) -> &'static str {
+        match self {
+            StorageCategory::Database => "database",
+            StorageCategory::Backups => "backups",
+            StorageCategory::Uploads => "uploads",
+        }
+    }
+}
+
+/// Disk space taken by one category
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageCategoryUsage {
+    pub category: StorageCategory,
+    /// File, directory, or bucket prefix the category is stored in; `None`
+    /// for in-memory databases
+    pub path: Option<String>,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+/// Where uploads and database backups are kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactStorage {
+    /// Below the data directories on local disk
+    #[default]
+    Filesystem,
+    /// In a bucket of an S3-compatible object store
+    S3,
+}
+
+impl ArtifactStorage {
+    /// Stable lowercase name, as configured
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArtifactStorage::Filesystem => "filesystem",
+            ArtifactStorage::S3 => "s3",
+        }
+    }
+}
+
+impl std::str::FromStr for ArtifactStorage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "filesystem" => Ok(ArtifactStorage::Filesystem),
+            "s3" => Ok(ArtifactStorage::S3),
+            other => Err(format!("unknown artifact storage {:?}, expected filesystem or s3", other)),
+        }
+    }
+}
+
+/// How close storage is to the configured cap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageStatus {
+    Ok,
+    /// Usage passed the warning threshold
+    Warning,
+    /// The cap is used up; new uploads and backups are refused
+    Full,
+}
+
+impl StorageStatus {
+    /// Classify total usage against an optional cap
+    pub fn of(total_bytes: u64, cap_bytes: Option<u64>, warning_percent: u8) -> Self {
+        match cap_bytes {
+            Some(cap) if total_bytes >= cap => StorageStatus::Full,
+            Some(cap) if total_bytes as u128 * 100 >= cap as u128 * warning_percent as u128 => {
+                StorageStatus::Warning
+            }
+            _ => StorageStatus::Ok,
+        }
+    }
+}
+
+/// Storage usage response
+#[derive(Debug, Serialize)]
+pub struct StorageUsageResponse {
+    pub categories: Vec<StorageCategoryUsage>,
+    pub total_bytes: u64,
+    /// Configured cap; storage is unlimited when `None`
+    pub cap_bytes: Option<u64>,
+    pub remaining_bytes: Option<u64>,
+    /// Share of the cap in use, in percent
+    pub used_percent: Option<f64>,
+    /// Usage at which `status` turns to warning, in percent of the cap
+    pub warning_percent: u8,
+    pub status: StorageStatus,
+    pub warnings: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_status_thresholds() {
+        assert_eq!(StorageStatus::of(u64::MAX, None, 80), StorageStatus::Ok);
+        assert_eq!(StorageStatus::of(79, Some(100), 80), StorageStatus::Ok);
+        assert_eq!(StorageStatus::of(80, Some(100), 80), StorageStatus::Warning);
+        assert_eq!(StorageStatus::of(100, Some(100), 80), StorageStatus::Full);
+        assert_eq!(StorageStatus::of(150, Some(100), 80), StorageStatus::Full);
+        assert_eq!(StorageStatus::of(u64::MAX - 1, Some(u64::MAX), 100), StorageStatus::Ok);
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::ipam::IpamUnavailableNode;
+
+/// Most subnets a split may produce
+pub const MAX_SPLIT_SUBNETS: u64 = 1024;
+
+/// Query describing a single subnet
+#[derive(Debug, Deserialize)]
+pub struct SubnetQuery {
+    /// Subnet in CIDR notation; host bits are ignored
+    pub cidr: String,
+}
+
+/// Properties of a subnet
+#[derive(Debug, Clone, Serialize)]
+pub struct SubnetInfo {
+    /// Network address with prefix length, e.g. `192.0.2.0/24`
+    pub network: String,
+    /// IP version, 4 or 6
+    pub version: u8,
+    pub prefix_len: u8,
+    pub netmask: String,
+    pub hostmask: String,
+    /// Broadcast address, for IPv4 subnets of two or more addresses
+    pub broadcast: Option<String>,
+    pub first_host: String,
+    pub last_host: String,
+    /// Assignable host addresses, or `None` if there are more than fit a u64
+    pub capacity: Option<u64>,
+}
+
+/// Request to split a subnet into smaller subnets
+#[derive(Debug, Deserialize)]
+pub struct SplitSubnetRequest {
+    pub cidr: String,
+    /// Prefix length of the resulting subnets
+    pub prefix_len: u8,
+}
+
+/// Subnets a subnet was split into
+#[derive(Debug, Serialize)]
+pub struct SplitSubnetResponse {
+    pub network: String,
+    pub subnets: Vec<String>,
+}
+
+/// Request to summarize subnets into the fewest covering prefixes
+#[derive(Debug, Deserialize)]
+pub struct SummarizeSubnetsRequest {
+    pub cidrs: Vec<String>,
+}
+
+/// Shortest list of prefixes covering exactly the requested subnets
+#[derive(Debug, Serialize)]
+pub struct SummarizeSubnetsResponse {
+    pub summary: Vec<String>,
+}
+
+/// Request to check which addresses fall into a subnet
+#[derive(Debug, Deserialize)]
+pub struct ContainsRequest {
+    pub cidr: String,
+    /// Addresses or subnets to check
+    pub addresses: Vec<String>,
+}
+
+/// Whether one address or subnet falls into the requested subnet
+#[derive(Debug, Serialize)]
+pub struct ContainsResult {
+    pub address: String,
+    pub contained: bool,
+}
+
+/// Membership of addresses in a subnet
+#[derive(Debug, Serialize)]
+pub struct ContainsResponse {
+    pub network: String,
+    pub results: Vec<ContainsResult>,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+/// Request for free subnets inside an address block
+#[derive(Debug, Deserialize, Validate)]
+pub struct NextAvailableRequest {
+    /// Address block to allocate from
+    pub within: String,
+    /// Prefix length of the subnets to allocate
+    pub prefix_len: u8,
+    /// Number of subnets wanted, at most 256
+    #[serde(default = "default_count")]
+    #[validate(range(min = 1, max = 256))]
+    pub count: usize,
+}
+
+/// Free subnets inside an address block
+#[derive(Debug, Serialize)]
+pub struct NextAvailableResponse {
+    pub within: String,
+    /// Lowest free subnets, fewer than requested if the block is full
+    pub subnets: Vec<String>,
+    /// Nodes whose addresses are unknown, so the result may overlap them
+    pub unavailable_nodes: Vec<IpamUnavailableNode>,
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Support bundle query parameters
+#[derive(Debug, Deserialize)]
+pub struct SupportBundleQuery {
+    /// Replace IP addresses, MAC addresses and hostnames with consistent
+    /// placeholders; secrets are always redacted
+    #[serde(default = "default_anonymize")]
+    pub anonymize: bool,
+}
+
+fn default_anonymize() -> bool {
+    true
+}
+
+/// Description of a support bundle, stored in it as `manifest.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportBundleManifest {
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub generated_at: DateTime<Utc>,
+    pub generated_by: String,
+    pub backend_version: String,
+    pub anonymized: bool,
+    /// Paths of the other files in the bundle
+    pub files: Vec<String>,
+}
+
+/// Gzipped tarball with diagnostics
+#[derive(Debug, Clone)]
+pub struct SupportBundle {
+    pub file_name: String,
+    pub content: Vec<u8>,
+}
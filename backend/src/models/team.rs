@@ -0,0 +1,232 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::timestamp::parse_db_timestamp;
+
+/// Role of a user within a team
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TeamRole {
+    /// Can access resources owned by the team
+    Member,
+    /// Can additionally manage the team and its members
+    Maintainer,
+}
+
+impl TeamRole {
+    /// Convert TeamRole to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TeamRole::Member => "member",
+            TeamRole::Maintainer => "maintainer",
+        }
+    }
+
+    /// Parse team role from database string
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "maintainer" => TeamRole::Maintainer,
+            _ => TeamRole::Member,
+        }
+    }
+}
+
+/// Team model
+///
+/// Teams group users so that nodes and other resources can be owned
+/// collectively, and so notifications can address everyone in the team by
+/// its alias.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Team {
+    pub id: Uuid,
+    pub name: String,
+    /// Short unique handle used to address the team (e.g. in notifications)
+    pub alias: String,
+    pub description: Option<String>,
+    pub member_count: u64,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Team member entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMember {
+    pub user_id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub role: TeamRole,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Team with its members
+#[derive(Debug, Serialize)]
+pub struct TeamDetail {
+    #[serde(flatten)]
+    pub team: Team,
+    pub members: Vec<TeamMember>,
+}
+
+/// Create team request
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTeamRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 2, max = 50))]
+    pub alias: String,
+    pub description: Option<String>,
+}
+
+/// Update team request
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTeamRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(min = 2, max = 50))]
+    pub alias: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Add team member request
+#[derive(Debug, Deserialize)]
+pub struct AddTeamMemberRequest {
+    pub user_id: Uuid,
+    pub role: Option<TeamRole>,
+}
+
+/// Team list response
+#[derive(Debug, Serialize)]
+pub struct TeamListResponse {
+    pub teams: Vec<Team>,
+    pub total: u64,
+}
+
+/// Team database record
+#[derive(Debug, Clone)]
+pub struct TeamRecord {
+    pub id: String,
+    pub name: String,
+    pub alias: String,
+    pub description: Option<String>,
+    pub member_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Team member database record
+#[derive(Debug, Clone)]
+pub struct TeamMemberRecord {
+    pub user_id: String,
+    pub username: String,
+    pub email: String,
+    pub role: String,
+    pub created_at: String,
+}
+
+/// Set of teams whose resources a user may access
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccessScope {
+    /// Superusers can access every resource
+    All,
+    /// Regular users can access unowned resources and those of their teams
+    Teams(Vec<Uuid>),
+}
+
+impl AccessScope {
+    /// Check whether a resource with the given owning team is accessible
+    ///
+    /// Resources without an owning team are shared with everyone.
+    pub fn can_access(&self, owner_team_id: Option<Uuid>) -> bool {
+        match (self, owner_team_id) {
+            (AccessScope::All, _) => true,
+            (AccessScope::Teams(_), None) => true,
+            (AccessScope::Teams(teams), Some(team_id)) => teams.contains(&team_id),
+        }
+    }
+
+    /// Check whether the scope includes a specific team
+    pub fn includes_team(&self, team_id: Uuid) -> bool {
+        match self {
+            AccessScope::All => true,
+            AccessScope::Teams(teams) => teams.contains(&team_id),
+        }
+    }
+}
+
+/// Validate a team alias
+///
+/// Aliases are lowercase ASCII letters, digits, and hyphens, and must start
+/// with a letter.
+pub fn is_valid_team_alias(alias: &str) -> bool {
+    alias.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && alias
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+impl TeamRecord {
+    /// Convert database record to public Team model
+    pub fn to_team(&self) -> Team {
+        Team {
+            id: Uuid::parse_str(&self.id).unwrap_or_else(|_| Uuid::nil()),
+            name: self.name.clone(),
+            alias: self.alias.clone(),
+            description: self.description.clone(),
+            member_count: self.member_count.max(0) as u64,
+            created_at: parse_db_timestamp(&self.created_at),
+            updated_at: parse_db_timestamp(&self.updated_at),
+        }
+    }
+}
+
+impl TeamMemberRecord {
+    /// Convert database record to public TeamMember model
+    pub fn to_member(&self) -> TeamMember {
+        TeamMember {
+            user_id: Uuid::parse_str(&self.user_id).unwrap_or_else(|_| Uuid::nil()),
+            username: self.username.clone(),
+            email: self.email.clone(),
+            role: TeamRole::parse(&self.role),
+            joined_at: parse_db_timestamp(&self.created_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_team_role_round_trip() {
+        assert_eq!(TeamRole::parse(TeamRole::Maintainer.as_str()), TeamRole::Maintainer);
+        assert_eq!(TeamRole::parse("member"), TeamRole::Member);
+        assert_eq!(TeamRole::parse("unknown"), TeamRole::Member);
+    }
+
+    #[test]
+    fn test_team_alias_validation() {
+        assert!(is_valid_team_alias("netops"));
+        assert!(is_valid_team_alias("noc-2"));
+        assert!(!is_valid_team_alias("NetOps"));
+        assert!(!is_valid_team_alias("2noc"));
+        assert!(!is_valid_team_alias("net ops"));
+        assert!(!is_valid_team_alias(""));
+    }
+
+    #[test]
+    fn test_access_scope() {
+        let team_a = Uuid::new_v4();
+        let team_b = Uuid::new_v4();
+        let scope = AccessScope::Teams(vec![team_a]);
+
+        assert!(scope.can_access(None));
+        assert!(scope.can_access(Some(team_a)));
+        assert!(!scope.can_access(Some(team_b)));
+        assert!(AccessScope::All.can_access(Some(team_b)));
+        assert!(!scope.includes_team(team_b));
+    }
+}
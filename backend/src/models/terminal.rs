@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Lines of command output sent per terminal output message
+pub const TERMINAL_OUTPUT_CHUNK_LINES: usize = 40;
+
+/// Allowlist entry permitting every command
+pub const ALLOW_ALL_COMMANDS: &str = "*";
+
+/// Operational commands a user may run in terminal sessions
+///
+/// Each entry is a command prefix made of whole words: `show interfaces`
+/// allows `show interfaces ethernet eth0` but not `show interfaces-all`,
+/// and `*` allows everything.
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminalAllowlist {
+    pub user_id: Uuid,
+    pub commands: Vec<String>,
+    pub updated_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl TerminalAllowlist {
+    /// Check whether the allowlist permits a command line
+    pub fn allows(&self, command: &str) -> bool {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        self.commands.iter().any(|entry| {
+            let entry: Vec<&str> = entry.split_whitespace().collect();
+            entry == [ALLOW_ALL_COMMANDS] || (!entry.is_empty() && words.starts_with(&entry))
+        })
+    }
+}
+
+/// Request to replace the terminal allowlist of a user
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct SetTerminalAllowlistRequest {
+    /// Command prefixes; an empty list closes the terminal to the user
+    #[validate(length(max = 200))]
+    pub commands: Vec<String>,
+}
+
+/// Outcome of a command run in a terminal session
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminalCommandResult {
+    pub command: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowlist(commands: &[&str]) -> TerminalAllowlist {
+        TerminalAllowlist {
+            user_id: Uuid::new_v4(),
+            commands: commands.iter().map(|c| c.to_string()).collect(),
+            updated_by: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_allowlist_matches_whole_words() {
+        let routing = allowlist(&["show interfaces", "show  ip route"]);
+        assert!(routing.allows("show interfaces"));
+        assert!(routing.allows("  show interfaces ethernet eth0"));
+        assert!(routing.allows("show ip route 10.0.0.0/8"));
+        assert!(!routing.allows("show interfaces-all"));
+        assert!(!routing.allows("show"));
+        assert!(!routing.allows("show version"));
+        assert!(!routing.allows(""));
+
+        assert!(allowlist(&["*"]).allows("show version"));
+        assert!(!allowlist(&[]).allows("show version"));
+    }
+}
@@ -0,0 +1,270 @@
+//! Time range parsing for list and history queries
+//!
+//! Query bounds such as `from`, `to`, or `start_time` accept an RFC3339
+//! timestamp, a date (`2026-02-09`, midnight UTC), `now`, or a time relative
+//! to now (`-6h`, `now-30m`). A `window` (`7d`) selects the span ending at
+//! the end bound, or now, and a named `preset` (`last_24h`, `today`, …)
+//! replaces the bounds altogether. Everything resolves to UTC.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::AppError;
+
+/// Length of a relative time span, such as `6h` or `7d`
+///
+/// Units are `s`, `m`, `h`, `d`, and `w`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelativeDuration(pub Duration);
+
+impl FromStr for RelativeDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| format!("Duration {:?} lacks a unit (s, m, h, d, w)", s))?;
+        let (count, unit) = s.split_at(split);
+        let count: i64 = count
+            .parse()
+            .map_err(|_| format!("Duration {:?} must start with a number", s))?;
+        let seconds_per_unit = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 3600,
+            "d" => 86_400,
+            "w" => 604_800,
+            _ => return Err(format!("Unknown duration unit {:?}; use s, m, h, d, or w", unit)),
+        };
+        let seconds = count
+            .checked_mul(seconds_per_unit)
+            .filter(|seconds| *seconds <= MAX_RELATIVE_SECS)
+            .ok_or_else(|| format!("Duration {:?} is too long", s))?;
+
+        Ok(RelativeDuration(Duration::seconds(seconds)))
+    }
+}
+
+impl<'de> Deserialize<'de> for RelativeDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Longest relative span accepted, about ten years
+const MAX_RELATIVE_SECS: i64 = 3650 * 86_400;
+
+/// Named time range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimePreset {
+    #[serde(rename = "last_hour")]
+    LastHour,
+    #[serde(rename = "last_6h")]
+    Last6Hours,
+    #[serde(rename = "last_24h")]
+    Last24Hours,
+    #[serde(rename = "last_7d")]
+    Last7Days,
+    #[serde(rename = "last_30d")]
+    Last30Days,
+    /// Since midnight UTC
+    #[serde(rename = "today")]
+    Today,
+    /// The previous day, midnight to midnight UTC
+    #[serde(rename = "yesterday")]
+    Yesterday,
+    /// Since Monday midnight UTC
+    #[serde(rename = "this_week")]
+    ThisWeek,
+    /// Since the first of the month, midnight UTC
+    #[serde(rename = "this_month")]
+    ThisMonth,
+}
+
+impl TimePreset {
+    /// Start and end of the range as of `now`
+    pub fn range(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let midnight = now.date_naive().and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        match self {
+            TimePreset::LastHour => (now - Duration::hours(1), now),
+            TimePreset::Last6Hours => (now - Duration::hours(6), now),
+            TimePreset::Last24Hours => (now - Duration::hours(24), now),
+            TimePreset::Last7Days => (now - Duration::days(7), now),
+            TimePreset::Last30Days => (now - Duration::days(30), now),
+            TimePreset::Today => (midnight, now),
+            TimePreset::Yesterday => (midnight - Duration::days(1), midnight),
+            TimePreset::ThisWeek => (
+                midnight - Duration::days(i64::from(now.weekday().num_days_from_monday())),
+                now,
+            ),
+            TimePreset::ThisMonth => (midnight - Duration::days(i64::from(now.day0())), now),
+        }
+    }
+}
+
+/// Parse a time bound relative to `now`
+pub fn parse_time_bound(s: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let s = s.trim();
+    if s == "now" {
+        return Ok(now);
+    }
+    if let Some(offset) = s.strip_prefix("now-").or_else(|| s.strip_prefix('-')) {
+        let RelativeDuration(offset) = offset.parse()?;
+        return Ok(now - offset);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    Err(format!(
+        "Invalid time {:?}; use an RFC3339 timestamp, a date, now, or a relative time such as -6h",
+        s
+    ))
+}
+
+/// Resolve a query's bounds, window, and preset into a UTC range
+///
+/// A preset excludes the other parameters. A window runs forward from the
+/// start bound when one is given, and back from the end bound, or now,
+/// otherwise.
+pub fn resolve_time_range(
+    start: &mut Option<DateTime<Utc>>,
+    end: &mut Option<DateTime<Utc>>,
+    window: Option<RelativeDuration>,
+    preset: Option<TimePreset>,
+) -> Result<(), AppError> {
+    let now = Utc::now();
+
+    if let Some(preset) = preset {
+        if start.is_some() || end.is_some() || window.is_some() {
+            return Err(AppError::Validation(
+                "A time preset cannot be combined with start, end, or window".to_string(),
+            ));
+        }
+        let (from, to) = preset.range(now);
+        *start = Some(from);
+        *end = Some(to);
+        return Ok(());
+    }
+
+    if let Some(RelativeDuration(window)) = window {
+        match (*start, *end) {
+            (Some(_), Some(_)) => {
+                return Err(AppError::Validation(
+                    "A time window cannot be combined with both start and end".to_string(),
+                ))
+            }
+            (Some(from), None) => *end = Some(from + window),
+            (None, to) => {
+                let to = to.unwrap_or(now);
+                *start = Some(to - window);
+                *end = Some(to);
+            }
+        }
+    }
+
+    if let (Some(from), Some(to)) = (*start, *end) {
+        if from > to {
+            return Err(AppError::Validation("The start of the time range is after its end".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Deserialize an optional query bound with [`parse_time_bound`]
+///
+/// Use with `#[serde(default, deserialize_with = "crate::models::time_range::deserialize_time_bound")]`.
+pub fn deserialize_time_bound<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+    struct Visitor;
+
+    impl<'de> serde::de::Visitor<'de> for Visitor {
+        type Value = Option<DateTime<Utc>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a timestamp or relative time")
+        }
+
+        fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_str(self)
+        }
+
+        fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Self::Value, E> {
+            parse_time_bound(s, Utc::now()).map(Some).map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_option(Visitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_time_bound() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 11, 15, 30, 0).unwrap();
+
+        assert_eq!(parse_time_bound("now", now).unwrap(), now);
+        assert_eq!(parse_time_bound("-6h", now).unwrap(), now - Duration::hours(6));
+        assert_eq!(parse_time_bound("now-30m", now).unwrap(), now - Duration::minutes(30));
+        assert_eq!(
+            parse_time_bound("2026-02-09T08:00:00+01:00", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 2, 9, 7, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_time_bound("2026-02-09", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 2, 9, 0, 0, 0).unwrap()
+        );
+        assert!(parse_time_bound("-6", now).is_err());
+        assert!(parse_time_bound("-6y", now).is_err());
+        assert!(parse_time_bound("yesterday", now).is_err());
+    }
+
+    #[test]
+    fn test_presets() {
+        // A Wednesday
+        let now = Utc.with_ymd_and_hms(2026, 2, 11, 15, 30, 0).unwrap();
+        let midnight = Utc.with_ymd_and_hms(2026, 2, 11, 0, 0, 0).unwrap();
+
+        assert_eq!(TimePreset::Last24Hours.range(now), (now - Duration::hours(24), now));
+        assert_eq!(TimePreset::Today.range(now), (midnight, now));
+        assert_eq!(TimePreset::Yesterday.range(now), (midnight - Duration::days(1), midnight));
+        assert_eq!(TimePreset::ThisWeek.range(now).0, Utc.with_ymd_and_hms(2026, 2, 9, 0, 0, 0).unwrap());
+        assert_eq!(TimePreset::ThisMonth.range(now).0, Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_resolve_time_range() {
+        let week = Some("7d".parse().unwrap());
+        let from = Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap();
+
+        let (mut start, mut end) = (Some(from), None);
+        resolve_time_range(&mut start, &mut end, week, None).unwrap();
+        assert_eq!(end, Some(from + Duration::days(7)));
+
+        let (mut start, mut end) = (None, Some(from));
+        resolve_time_range(&mut start, &mut end, week, None).unwrap();
+        assert_eq!(start, Some(from - Duration::days(7)));
+
+        let (mut start, mut end) = (None, None);
+        resolve_time_range(&mut start, &mut end, None, Some(TimePreset::LastHour)).unwrap();
+        assert_eq!(end.unwrap() - start.unwrap(), Duration::hours(1));
+
+        let (mut start, mut end) = (Some(from), None);
+        assert!(resolve_time_range(&mut start, &mut end, None, Some(TimePreset::Today)).is_err());
+
+        let (mut start, mut end) = (Some(from), Some(from - Duration::hours(1)));
+        assert!(resolve_time_range(&mut start, &mut end, None, None).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+//! Timestamp storage and serialization helpers
+//!
+//! Timestamps are stored and returned as UTC RFC3339 strings with millisecond
+//! precision (`2026-02-09T08:58:56.000Z`). This is the same format SQLite's
+//! `strftime('%Y-%m-%dT%H:%M:%fZ', 'now')` produces, so values written by the
+//! application and by triggers compare and sort correctly as text.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use validator::ValidationError;
+
+/// Format a timestamp for storage or API output
+pub fn format_timestamp(dt: &DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Current time formatted for storage
+pub fn db_now() -> String {
+    format_timestamp(&Utc::now())
+}
+
+/// Parse a stored timestamp
+///
+/// Accepts any RFC3339 offset as well as the legacy SQLite `datetime('now')`
+/// format, which is UTC without a zone designator. Unparseable values map to
+/// the Unix epoch.
+pub fn parse_db_timestamp(s: &str) -> DateTime<Utc> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return dt.with_timezone(&Utc);
+    }
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f") {
+        return DateTime::from_naive_utc_and_offset(dt, Utc);
+    }
+    DateTime::UNIX_EPOCH
+}
+
+/// Parse an IANA timezone name (e.g. `Europe/Berlin`)
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.parse().ok()
+}
+
+/// Validator for IANA timezone name fields
+pub fn validate_timezone(name: &str) -> Result<(), ValidationError> {
+    match parse_timezone(name) {
+        Some(_) => Ok(()),
+        None => Err(ValidationError::new("timezone")),
+    }
+}
+
+/// Serde helpers for `DateTime<Utc>` fields in the canonical format
+///
+/// Use with `#[serde(with = "crate::models::timestamp::rfc3339")]`.
+pub mod rfc3339 {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(dt: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format_timestamp(dt))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        DateTime::parse_from_rfc3339(&s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(serde::de::Error::custom)
+    }
+
+    /// Serde helpers for `Option<DateTime<Utc>>` fields
+    ///
+    /// Use with `#[serde(default, with = "crate::models::timestamp::rfc3339::option")]`.
+    pub mod option {
+        use chrono::{DateTime, Utc};
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(dt: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+            match dt {
+                Some(dt) => super::serialize(dt, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<DateTime<Utc>>, D::Error> {
+            Option::<String>::deserialize(deserializer)?
+                .map(|s| {
+                    DateTime::parse_from_rfc3339(&s)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .map_err(serde::de::Error::custom)
+                })
+                .transpose()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[test]
+    fn test_parse_db_timestamp() {
+        let dt = parse_db_timestamp("2026-02-09 08:58:56");
+        assert_eq!(format_timestamp(&dt), "2026-02-09T08:58:56.000Z");
+
+        let dt = parse_db_timestamp("2026-02-09T10:58:56.250+02:00");
+        assert_eq!(format_timestamp(&dt), "2026-02-09T08:58:56.250Z");
+
+        assert_eq!(parse_db_timestamp("not a timestamp"), DateTime::UNIX_EPOCH);
+    }
+
+    #[test]
+    fn test_timezone_validation() {
+        assert_eq!(parse_timezone("Asia/Tokyo"), Some(chrono_tz::Asia::Tokyo));
+        assert!(validate_timezone("UTC").is_ok());
+        assert!(validate_timezone("Mars/Olympus_Mons").is_err());
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Sample {
+        #[serde(with = "rfc3339")]
+        at: DateTime<Utc>,
+        #[serde(default, with = "rfc3339::option")]
+        seen: Option<DateTime<Utc>>,
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let sample: Sample = serde_json::from_str(r#"{"at":"2026-02-09T17:58:56+09:00"}"#).unwrap();
+        assert!(sample.seen.is_none());
+
+        let json = serde_json::to_string(&sample).unwrap();
+        assert_eq!(json, r#"{"at":"2026-02-09T08:58:56.000Z","seen":null}"#);
+    }
+}
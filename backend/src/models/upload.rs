@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::storage::Compression;
+
+/// Largest accepted CA certificate bundle, in bytes
+pub const MAX_CA_CERTIFICATE_BYTES: u64 = 256 * 1024;
+
+/// Largest accepted plain form field of a multipart request, in bytes
+pub const MAX_FORM_FIELD_BYTES: usize = 4 * 1024;
+
+/// What an uploaded file is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadKind {
+    /// Node configuration in JSON form, imported into the configuration history
+    Config,
+    /// VyOS installation image
+    Image,
+    /// PEM-encoded CA certificates
+    CaCertificate,
+}
+
+impl UploadKind {
+    /// Convert kind to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UploadKind::Config => "config",
+            UploadKind::Image => "image",
+            UploadKind::CaCertificate => "ca_certificate",
+        }
+    }
+
+    /// Parse kind from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "image" => UploadKind::Image,
+            "ca_certificate" => UploadKind::CaCertificate,
+            _ => UploadKind::Config,
+        }
+    }
+
+    /// How new uploads of this kind are compressed at rest
+    ///
+    /// Images are already compressed and would only cost CPU time.
+    pub fn compression(&self) -> Compression {
+        match self {
+            UploadKind::Image => Compression::None,
+            UploadKind::Config | UploadKind::CaCertificate => Compression::Zstd,
+        }
+    }
+}
+
+/// Metadata of an uploaded file
+///
+/// The content is stored on disk below the configured upload directory,
+/// compressed as given by `compression`. Downloads are decompressed.
+#[derive(Debug, Clone, Serialize)]
+pub struct Upload {
+    pub id: Uuid,
+    pub kind: UploadKind,
+    pub file_name: String,
+    pub content_type: Option<String>,
+    /// Size of the content as uploaded and downloaded
+    pub size_bytes: u64,
+    /// Hex-encoded SHA-256 of the content
+    pub sha256: String,
+    pub compression: Compression,
+    /// Size of the content on disk
+    pub stored_bytes: u64,
+    pub uploaded_by: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Upload query parameters
+#[derive(Debug, Deserialize)]
+pub struct UploadQuery {
+    pub kind: UploadKind,
+}
+
+/// Upload list query parameters
+#[derive(Debug, Deserialize)]
+pub struct UploadListQuery {
+    pub kind: Option<UploadKind>,
+}
+
+/// Configuration imported from an uploaded file
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigImportResponse {
+    /// Configuration history entry created for the node
+    pub history_id: Uuid,
+    pub node_id: Uuid,
+    pub version: String,
+    pub upload: Upload,
+}
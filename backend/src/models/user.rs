@@ -3,6 +3,11 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::error::AppError;
+use crate::models::redact::sanitized_debug;
+use crate::models::team::TeamRole;
+use crate::models::timestamp::{parse_db_timestamp, validate_timezone};
+
 /// User role
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -12,6 +17,25 @@ pub enum UserRole {
     Viewer,
 }
 
+impl UserRole {
+    /// Built-in roles, one of which every user holds
+    pub const ALL: [UserRole; 3] = [UserRole::Admin, UserRole::Operator, UserRole::Viewer];
+
+    /// Name of the role in the roles table
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::Admin => "admin",
+            UserRole::Operator => "operator",
+            UserRole::Viewer => "viewer",
+        }
+    }
+
+    /// Built-in role of a name in the roles table
+    pub fn from_name(name: &str) -> Option<UserRole> {
+        Self::ALL.into_iter().find(|role| role.as_str() == name)
+    }
+}
+
 /// User status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -30,13 +54,21 @@ pub struct User {
     pub full_name: Option<String>,
     pub role: UserRole,
     pub status: UserStatus,
+    /// Preferred IANA timezone for displaying timestamps
+    pub timezone: String,
+    /// Whether the user must choose a new password
+    #[serde(default)]
+    pub password_reset_required: bool,
+    #[serde(default, with = "crate::models::timestamp::rfc3339::option")]
     pub last_login: Option<DateTime<Utc>>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
     pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
     pub updated_at: DateTime<Utc>,
 }
 
 /// Create user request
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize)]
 pub struct CreateUserRequest {
     pub username: String,
     pub email: String,
@@ -45,6 +77,8 @@ pub struct CreateUserRequest {
     pub role: UserRole,
 }
 
+sanitized_debug!(CreateUserRequest { username, email, full_name, role } secret { password });
+
 /// Update user request
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpdateUserRequest {
@@ -53,6 +87,8 @@ pub struct UpdateUserRequest {
     pub full_name: Option<String>,
     pub role: Option<UserRole>,
     pub status: Option<UserStatus>,
+    #[validate(custom = "validate_timezone")]
+    pub timezone: Option<String>,
 }
 
 /// Update user profile request
@@ -61,10 +97,12 @@ pub struct UpdateProfileRequest {
     #[validate(email)]
     pub email: Option<String>,
     pub full_name: Option<String>,
+    #[validate(custom = "validate_timezone")]
+    pub timezone: Option<String>,
 }
 
 /// Change password request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Deserialize, Validate)]
 pub struct ChangePasswordRequest {
     #[validate(length(min = 6))]
     pub current_password: String,
@@ -72,13 +110,43 @@ pub struct ChangePasswordRequest {
     pub new_password: String,
 }
 
+sanitized_debug!(ChangePasswordRequest {} secret { current_password, new_password });
+
+/// Email change request
+#[derive(Deserialize, Validate)]
+pub struct ChangeEmailRequest {
+    #[validate(email)]
+    pub new_email: String,
+    /// Confirms the request comes from the account owner
+    pub current_password: String,
+}
+
+sanitized_debug!(ChangeEmailRequest { new_email } secret { current_password });
+
+/// Email change confirmation, carrying the token sent to the new address
+#[derive(Deserialize)]
+pub struct ConfirmEmailChangeRequest {
+    pub token: String,
+}
+
+sanitized_debug!(ConfirmEmailChangeRequest {} secret { token });
+
+/// Email change awaiting confirmation from the new address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingEmailChange {
+    pub new_email: String,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub expires_at: DateTime<Utc>,
+}
+
 /// User list query parameters for filtering and pagination
 #[derive(Debug, Deserialize, Clone)]
 pub struct UserListQuery {
     /// Filter by status
     pub status: Option<UserStatus>,
-    /// Filter by role
-    pub role: Option<UserRole>,
+    /// Filter by role names, comma-separated, e.g. `admin,operator`; users
+    /// holding any of the roles match
+    pub role: Option<String>,
     /// Search by username or email
     pub search: Option<String>,
     /// Page number (1-based)
@@ -87,84 +155,216 @@ pub struct UserListQuery {
     pub per_page: Option<u32>,
 }
 
+impl UserListQuery {
+    /// Roles filtered by
+    ///
+    /// Fails on names that are not a built-in role.
+    pub fn roles(&self) -> Result<Vec<UserRole>, AppError> {
+        let Some(roles) = self.role.as_deref() else {
+            return Ok(Vec::new());
+        };
+        roles
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                UserRole::from_name(name).ok_or_else(|| {
+                    let known: Vec<&str> = UserRole::ALL.iter().map(UserRole::as_str).collect();
+                    AppError::Validation(format!("Unknown role '{}', expected one of {}", name, known.join(", ")))
+                })
+            })
+            .collect()
+    }
+}
+
+/// User in a user list, with the roles they hold
+#[derive(Debug, Serialize)]
+pub struct UserListEntry {
+    #[serde(flatten)]
+    pub user: User,
+    /// Names of the user's roles, sorted
+    pub roles: Vec<String>,
+}
+
 /// User list response
 #[derive(Debug, Serialize)]
 pub struct UserListResponse {
-    pub users: Vec<User>,
+    pub users: Vec<UserListEntry>,
     pub total: u64,
     pub page: u32,
     pub per_page: u32,
     pub total_pages: u32,
 }
 
+/// Maximum number of users in one bulk operation
+pub const MAX_BULK_USERS: usize = 500;
+
+/// Dry-run flag of a bulk import, passed in the query string
+#[derive(Debug, Deserialize)]
+pub struct BulkImportQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Bulk role assignment request
+#[derive(Debug, Deserialize)]
+pub struct BulkRoleRequest {
+    pub user_ids: Vec<Uuid>,
+    pub role: UserRole,
+    /// Report what would change without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Bulk team assignment request
+#[derive(Debug, Deserialize)]
+pub struct BulkTeamRequest {
+    pub user_ids: Vec<Uuid>,
+    pub team_id: Uuid,
+    /// Role within the team; defaults to member
+    pub role: Option<TeamRole>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Bulk request applying the same change to several users
+#[derive(Debug, Deserialize)]
+pub struct BulkUsersRequest {
+    pub user_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Outcome of a bulk operation for one user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkOutcome {
+    Created,
+    Changed,
+    Unchanged,
+    Failed,
+}
+
+/// Result of a bulk operation for one user
+#[derive(Serialize, Deserialize)]
+pub struct BulkUserResult {
+    /// Not set for rows of an import that were not created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Uuid>,
+    /// Not set for unknown user IDs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    pub outcome: BulkOutcome,
+    /// Human-readable description of each change
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<String>,
+    /// Generated password of an imported user, shown only once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temporary_password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+sanitized_debug!(BulkUserResult { user_id, username, outcome, changes, error } secret { temporary_password });
+
+impl BulkUserResult {
+    fn new(user_id: Option<Uuid>, username: Option<&str>, outcome: BulkOutcome) -> Self {
+        Self {
+            user_id,
+            username: username.map(str::to_string),
+            outcome,
+            changes: Vec::new(),
+            temporary_password: None,
+            error: None,
+        }
+    }
+
+    /// A user that was, or would be, changed
+    pub fn changed(user_id: Uuid, username: &str, changes: Vec<String>) -> Self {
+        Self {
+            changes,
+            ..Self::new(Some(user_id), Some(username), BulkOutcome::Changed)
+        }
+    }
+
+    /// A user that already matches the requested state
+    pub fn unchanged(user_id: Uuid, username: &str) -> Self {
+        Self::new(Some(user_id), Some(username), BulkOutcome::Unchanged)
+    }
+
+    /// A user that was, or would be, created
+    pub fn created(user_id: Option<Uuid>, username: &str, changes: Vec<String>) -> Self {
+        Self {
+            changes,
+            ..Self::new(user_id, Some(username), BulkOutcome::Created)
+        }
+    }
+
+    /// A user the operation could not be applied to
+    pub fn failed(user_id: Option<Uuid>, username: Option<&str>, error: impl Into<String>) -> Self {
+        Self {
+            error: Some(error.into()),
+            ..Self::new(user_id, username, BulkOutcome::Failed)
+        }
+    }
+}
+
+/// Bulk operation response
+///
+/// In a dry run, outcomes report what would happen, and nothing is changed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUserResponse {
+    pub dry_run: bool,
+    pub created: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+    pub results: Vec<BulkUserResult>,
+}
+
+impl BulkUserResponse {
+    pub fn new(dry_run: bool, results: Vec<BulkUserResult>) -> Self {
+        let count = |outcome| results.iter().filter(|result| result.outcome == outcome).count();
+        Self {
+            dry_run,
+            created: count(BulkOutcome::Created),
+            changed: count(BulkOutcome::Changed),
+            unchanged: count(BulkOutcome::Unchanged),
+            failed: count(BulkOutcome::Failed),
+            results,
+        }
+    }
+}
+
 /// User database record (includes password_hash)
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct UserRecord {
-    pub id: i64,
+    pub id: String,
     pub username: String,
     pub email: String,
     pub password_hash: String,
     pub full_name: Option<String>,
     pub is_active: bool,
     pub is_superuser: bool,
+    pub timezone: String,
     pub last_login: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    pub password_reset_required: bool,
 }
 
-/// Extract the database i64 ID from a UUID that was created by i64_to_uuid()
-/// This reverses the conversion done in to_user()
-pub fn extract_db_id_from_uuid(uuid: &Uuid) -> i64 {
-    let uuid_bytes = *uuid.as_bytes();
-    // Extract the last 8 bytes and reverse the variant bit operation
-    let mut id_bytes = [0u8; 8];
-    id_bytes.copy_from_slice(&uuid_bytes[8..16]);
-    // The variant bit was set: (byte & 0x3f) | 0x80
-    // To reverse, we clear the top 2 bits that were set by 0x80
-    id_bytes[0] = id_bytes[0] & 0x3f;
-    i64::from_be_bytes(id_bytes)
-}
-
-/// Convert an i64 database ID to a UUID deterministically
-pub fn i64_to_uuid(id: i64) -> Uuid {
-    let id_bytes = id.to_be_bytes();
-    let mut uuid_bytes = [0u8; 16];
-    // Put the i64 in the last 8 bytes (lower 64 bits)
-    uuid_bytes[8..16].copy_from_slice(&id_bytes);
-    // Set version 4 (random) and variant bits to make it a valid UUID
-    uuid_bytes[6] = (uuid_bytes[6] & 0x0f) | 0x40; // Version 4
-    uuid_bytes[8] = (uuid_bytes[8] & 0x3f) | 0x80; // Variant 1
-    Uuid::from_bytes(uuid_bytes)
-}
-
-impl User {
-    /// Get the database ID (i64) from this user's UUID
-    pub fn db_id(&self) -> i64 {
-        extract_db_id_from_uuid(&self.id)
-    }
-}
+sanitized_debug!(UserRecord {
+    id, username, email, full_name, is_active, is_superuser, timezone, last_login, created_at, updated_at,
+    password_reset_required,
+} secret {
+    password_hash,
+});
 
 impl UserRecord {
     /// Convert database record to public User model
     pub fn to_user(&self) -> User {
-        let id = i64_to_uuid(self.id);
-
-        // Parse SQLite datetime format (e.g., "2026-02-09 08:58:56")
-        let parse_sqlite_datetime = |s: &str| -> DateTime<Utc> {
-            // Try RFC3339 first
-            if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
-                return dt.with_timezone(&Utc);
-            }
-            // Try SQLite format: "YYYY-MM-DD HH:MM:SS"
-            if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
-                return DateTime::from_naive_utc_and_offset(dt, Utc);
-            }
-            // Fallback to epoch
-            DateTime::UNIX_EPOCH
-        };
-
         User {
-            id,
+            id: Uuid::parse_str(&self.id).unwrap_or_else(|_| Uuid::nil()),
             username: self.username.clone(),
             email: self.email.clone(),
             full_name: self.full_name.clone(),
@@ -178,9 +378,11 @@ impl UserRecord {
             } else {
                 UserStatus::Disabled
             },
-            last_login: self.last_login.as_ref().map(|s| parse_sqlite_datetime(s)),
-            created_at: parse_sqlite_datetime(&self.created_at),
-            updated_at: parse_sqlite_datetime(&self.updated_at),
+            timezone: self.timezone.clone(),
+            password_reset_required: self.password_reset_required,
+            last_login: self.last_login.as_deref().map(parse_db_timestamp),
+            created_at: parse_db_timestamp(&self.created_at),
+            updated_at: parse_db_timestamp(&self.updated_at),
         }
     }
 
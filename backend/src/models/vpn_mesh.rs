@@ -0,0 +1,199 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::config_session::StagedOperation;
+
+/// Default WireGuard interface of a mesh
+pub const DEFAULT_MESH_INTERFACE: &str = "wg100";
+
+/// Default WireGuard listen port of a mesh
+pub const DEFAULT_MESH_LISTEN_PORT: u16 = 51820;
+
+/// Which members of a mesh have a tunnel between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeshTopology {
+    /// Every member with every other member
+    Full,
+    /// Every member with the hub only; the hub forwards between them
+    HubAndSpoke,
+}
+
+impl MeshTopology {
+    /// Convert topology to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MeshTopology::Full => "full",
+            MeshTopology::HubAndSpoke => "hub_and_spoke",
+        }
+    }
+
+    /// Parse topology from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "hub_and_spoke" => MeshTopology::HubAndSpoke,
+            _ => MeshTopology::Full,
+        }
+    }
+}
+
+/// Whether a mesh's configuration is on its nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VpnMeshStatus {
+    /// Not pushed yet, or the last push was rolled back
+    Pending,
+    /// Pushed to every member
+    Applied,
+    /// A push failed and could not be rolled back; see the mesh's error
+    Failed,
+}
+
+impl VpnMeshStatus {
+    /// Convert status to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VpnMeshStatus::Pending => "pending",
+            VpnMeshStatus::Applied => "applied",
+            VpnMeshStatus::Failed => "failed",
+        }
+    }
+
+    /// Parse status from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "applied" => VpnMeshStatus::Applied,
+            "failed" => VpnMeshStatus::Failed,
+            _ => VpnMeshStatus::Pending,
+        }
+    }
+}
+
+/// Health of a tunnel as last checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TunnelStatus {
+    /// Not checked yet, or neither end could be read
+    Unknown,
+    /// An end completed a handshake with the other recently
+    Up,
+    /// No recent handshake between the ends
+    Down,
+}
+
+impl TunnelStatus {
+    /// Convert status to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TunnelStatus::Unknown => "unknown",
+            TunnelStatus::Up => "up",
+            TunnelStatus::Down => "down",
+        }
+    }
+
+    /// Parse status from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "up" => TunnelStatus::Up,
+            "down" => TunnelStatus::Down,
+            _ => TunnelStatus::Unknown,
+        }
+    }
+}
+
+/// Node of a mesh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VpnMeshMember {
+    pub node_id: Uuid,
+    /// Address or host name the other members connect to
+    pub endpoint: String,
+    /// Address of the node's WireGuard interface, with the pool's prefix
+    /// length
+    pub tunnel_address: String,
+    pub public_key: String,
+}
+
+/// Tunnel between two members of a mesh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VpnMeshTunnel {
+    pub node_a: Uuid,
+    pub node_b: Uuid,
+    pub status: TunnelStatus,
+    /// Latest handshake seen by either end
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub last_handshake_at: Option<DateTime<Utc>>,
+    #[serde(with = "crate::models::timestamp::rfc3339::option")]
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+/// WireGuard mesh among managed nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VpnMesh {
+    pub id: Uuid,
+    pub name: String,
+    pub topology: MeshTopology,
+    /// Hub of a hub-and-spoke mesh
+    pub hub_node_id: Option<Uuid>,
+    /// Prefix the tunnel addresses are allocated from
+    pub address_pool: String,
+    /// WireGuard interface on every member
+    pub interface: String,
+    pub listen_port: u16,
+    pub status: VpnMeshStatus,
+    /// Why the last push failed
+    pub error: Option<String>,
+    pub created_by: String,
+    pub members: Vec<VpnMeshMember>,
+    pub tunnels: Vec<VpnMeshTunnel>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Node to add to a new mesh
+#[derive(Debug, Clone, Deserialize)]
+pub struct VpnMeshMemberRequest {
+    pub node_id: Uuid,
+    /// Address or host name the other members connect to; defaults to the
+    /// node's API host
+    pub endpoint: Option<String>,
+}
+
+/// Request to create a mesh
+///
+/// Tunnel addresses are allocated from `address_pool` in the order of
+/// `members`, and a key pair is generated for every member.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateVpnMeshRequest {
+    pub name: String,
+    pub topology: MeshTopology,
+    /// Required for a hub-and-spoke mesh, and must be a member
+    pub hub_node_id: Option<Uuid>,
+    pub address_pool: String,
+    pub interface: Option<String>,
+    pub listen_port: Option<u16>,
+    pub members: Vec<VpnMeshMemberRequest>,
+}
+
+/// Meshes, by name
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VpnMeshListResponse {
+    pub meshes: Vec<VpnMesh>,
+}
+
+/// Configuration a mesh pushes to one member
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VpnMeshNodePlan {
+    pub node_id: Uuid,
+    /// Delete of the interface being replaced, then sets
+    pub operations: Vec<StagedOperation>,
+}
+
+/// Configuration a mesh pushes to every member, with private keys masked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VpnMeshPlan {
+    pub mesh_id: Uuid,
+    pub nodes: Vec<VpnMeshNodePlan>,
+}
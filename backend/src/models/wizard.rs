@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::config_session::StagedOperation;
+
+/// Setup flow a wizard walks through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WizardKind {
+    /// IPsec tunnel between two managed nodes
+    SiteToSiteVpn,
+    /// VRRP pair sharing a virtual address
+    HaPair,
+}
+
+impl WizardKind {
+    /// Convert kind to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WizardKind::SiteToSiteVpn => "site_to_site_vpn",
+            WizardKind::HaPair => "ha_pair",
+        }
+    }
+
+    /// Parse kind from database string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "site_to_site_vpn" => Some(WizardKind::SiteToSiteVpn),
+            "ha_pair" => Some(WizardKind::HaPair),
+            _ => None,
+        }
+    }
+
+    /// Steps of the flow, in the order they are answered
+    pub fn steps(&self) -> &'static [&'static str] {
+        match self {
+            WizardKind::SiteToSiteVpn => &["endpoints", "tunnel", "networks"],
+            WizardKind::HaPair => &["nodes", "vrrp"],
+        }
+    }
+}
+
+/// State of a wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WizardStatus {
+    /// Collecting answers
+    Open,
+    /// Configuration is being applied to the nodes
+    Applying,
+    /// Configuration was applied to every node
+    Applied,
+    /// Applying failed and could not be undone; see the wizard's error
+    Failed,
+    /// Closed without applying
+    Discarded,
+}
+
+impl WizardStatus {
+    /// Convert status to string for database storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WizardStatus::Open => "open",
+            WizardStatus::Applying => "applying",
+            WizardStatus::Applied => "applied",
+            WizardStatus::Failed => "failed",
+            WizardStatus::Discarded => "discarded",
+        }
+    }
+
+    /// Parse status from database string
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "open" => WizardStatus::Open,
+            "applying" => WizardStatus::Applying,
+            "applied" => WizardStatus::Applied,
+            "discarded" => WizardStatus::Discarded,
+            _ => WizardStatus::Failed,
+        }
+    }
+}
+
+/// Multi-step setup flow, with the answers given so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wizard {
+    pub id: Uuid,
+    pub kind: WizardKind,
+    /// User who started the wizard
+    pub username: String,
+    pub status: WizardStatus,
+    /// Answers by step; secrets are masked
+    pub answers: BTreeMap<String, Value>,
+    /// First step not answered yet, `None` once every step is
+    pub next_step: Option<String>,
+    /// Why the last attempt to apply failed
+    pub error: Option<String>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp::rfc3339")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to start a wizard
+#[derive(Debug, Deserialize)]
+pub struct StartWizardRequest {
+    pub kind: WizardKind,
+}
+
+/// Wizard list query parameters
+#[derive(Debug, Deserialize)]
+pub struct WizardListQuery {
+    /// Include applied, failed, and discarded wizards
+    #[serde(default)]
+    pub all: bool,
+}
+
+/// Wizards, newest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WizardListResponse {
+    pub wizards: Vec<Wizard>,
+}
+
+/// Configuration a wizard generates for one node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WizardNodePlan {
+    pub node_id: Uuid,
+    /// Deletes of the configuration being replaced, then sets
+    pub operations: Vec<StagedOperation>,
+}
+
+/// Configuration a wizard generates for every node it sets up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WizardPlan {
+    pub wizard_id: Uuid,
+    pub nodes: Vec<WizardNodePlan>,
+}
+
+// ============================================================================
+// Site-to-site VPN steps
+// ============================================================================
+
+/// `endpoints` step: the two nodes and the addresses they peer from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VpnEndpointsStep {
+    pub local_node_id: Uuid,
+    pub local_address: IpAddr,
+    pub remote_node_id: Uuid,
+    pub remote_address: IpAddr,
+}
+
+/// `tunnel` step: naming and authentication of the tunnel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VpnTunnelStep {
+    /// Name of the peer and of its IKE and ESP groups on both nodes
+    pub name: String,
+    pub pre_shared_key: String,
+    #[serde(default = "default_encryption")]
+    pub encryption: String,
+    #[serde(default = "default_hash")]
+    pub hash: String,
+}
+
+fn default_encryption() -> String {
+    "aes256".to_string()
+}
+
+fn default_hash() -> String {
+    "sha256".to_string()
+}
+
+/// `networks` step: the prefixes routed through the tunnel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VpnNetworksStep {
+    /// Prefix behind the local node, e.g. `10.1.0.0/16`
+    pub local_prefix: String,
+    /// Prefix behind the remote node
+    pub remote_prefix: String,
+}
+
+// ============================================================================
+// HA pair steps
+// ============================================================================
+
+/// `nodes` step: the two nodes of the pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HaNodesStep {
+    /// Node holding the virtual address while it is up
+    pub primary_node_id: Uuid,
+    pub secondary_node_id: Uuid,
+}
+
+/// `vrrp` step: the VRRP group shared by the pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HaVrrpStep {
+    pub group: String,
+    /// Interface carrying the virtual address on both nodes
+    pub interface: String,
+    /// Virtual router ID, 1 to 255
+    pub vrid: u8,
+    /// Virtual address with its prefix length, e.g. `192.0.2.1/24`
+    pub virtual_address: String,
+}
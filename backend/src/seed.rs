@@ -0,0 +1,284 @@
+//! Sample Data Seeding
+//!
+//! This module populates the application with a fixed set of sample users,
+//! nodes, alert rules, and configuration history entries. IDs, names, and
+//! passwords are constants so integration tests and demo environments can refer
+//! to them directly.
+//!
+//! Seeding is idempotent: fixtures that already exist are left untouched.
+//! Alert rules are held in memory by the monitoring service, so they only
+//! survive as long as the [`AppState`] they were seeded into.
+
+use serde::Serialize;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::app::AppState;
+use crate::error::AppError;
+use crate::models::config::ConfigHistoryRecord;
+use crate::models::monitoring::{AlertOperator, AlertSeverity, ConditionCombinator, MetricType};
+use crate::models::node::CreateNodeRequest;
+use crate::services::monitoring::AlertRuleCreate;
+
+/// Password of every seeded user
+pub const SEED_PASSWORD: &str = "vyos-demo-password";
+
+/// API key configured on every seeded node
+pub const SEED_NODE_API_KEY: &str = "vyos-demo-api-key";
+
+/// Seeded administrator
+pub const ADMIN_USER_ID: Uuid = Uuid::from_u128(0x5eed0000_0000_4000_8000_000000000001);
+/// Seeded regular user
+pub const OPERATOR_USER_ID: Uuid = Uuid::from_u128(0x5eed0000_0000_4000_8000_000000000002);
+/// Seeded disabled user
+pub const DISABLED_USER_ID: Uuid = Uuid::from_u128(0x5eed0000_0000_4000_8000_000000000003);
+
+/// Seeded edge router
+pub const EDGE_NODE_ID: Uuid = Uuid::from_u128(0x5eed0000_0000_4000_8000_000000000101);
+/// Seeded core router
+pub const CORE_NODE_ID: Uuid = Uuid::from_u128(0x5eed0000_0000_4000_8000_000000000102);
+/// Seeded branch router
+pub const BRANCH_NODE_ID: Uuid = Uuid::from_u128(0x5eed0000_0000_4000_8000_000000000103);
+
+/// Seeded CPU alert rule
+pub const CPU_ALERT_RULE_ID: Uuid = Uuid::from_u128(0x5eed0000_0000_4000_8000_000000000201);
+/// Seeded memory alert rule
+pub const MEMORY_ALERT_RULE_ID: Uuid = Uuid::from_u128(0x5eed0000_0000_4000_8000_000000000202);
+/// Seeded interface alert rule
+pub const INTERFACE_ALERT_RULE_ID: Uuid = Uuid::from_u128(0x5eed0000_0000_4000_8000_000000000203);
+
+/// Number of fixtures created by a seeding run
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct SeedSummary {
+    pub users: usize,
+    pub nodes: usize,
+    pub alert_rules: usize,
+    pub history_entries: usize,
+}
+
+/// Populate the application with the sample data set
+pub async fn seed(state: &AppState) -> Result<SeedSummary, AppError> {
+    let summary = SeedSummary {
+        users: seed_users(state).await?,
+        nodes: seed_nodes(state).await?,
+        alert_rules: seed_alert_rules(state).await?,
+        history_entries: seed_history(state).await?,
+    };
+
+    info!(
+        "Seeded {} users, {} nodes, {} alert rules, {} history entries",
+        summary.users, summary.nodes, summary.alert_rules, summary.history_entries
+    );
+
+    Ok(summary)
+}
+
+async fn seed_users(state: &AppState) -> Result<usize, AppError> {
+    // (id, username, full name, administrator, active)
+    let users = [
+        (ADMIN_USER_ID, "alice", "Alice Admin", true, true),
+        (OPERATOR_USER_ID, "bob", "Bob Operator", false, true),
+        (DISABLED_USER_ID, "carol", "Carol Former", false, false),
+    ];
+
+    let db = state.db.get_ref();
+    let password_hash = state.auth_service.hash_password(SEED_PASSWORD)?;
+    let mut created = 0;
+
+    for (id, username, full_name, is_superuser, is_active) in users {
+        let id = id.to_string();
+        if db.find_user_by_id(&id).await?.is_some() {
+            continue;
+        }
+
+        let email = format!("{}@example.com", username);
+        db.create_user(&id, username, &email, &password_hash, Some(full_name)).await?;
+        if is_superuser {
+            db.update_user_superuser(&id, true).await?;
+        }
+        if !is_active {
+            db.update_user_status(&id, false).await?;
+        }
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+async fn seed_nodes(state: &AppState) -> Result<usize, AppError> {
+    // (id, name, host, tags)
+    let nodes = [
+        (EDGE_NODE_ID, "edge-1", "192.0.2.1", vec!["edge", "production"]),
+        (CORE_NODE_ID, "core-1", "198.51.100.1", vec!["core", "production"]),
+        (BRANCH_NODE_ID, "branch-1", "203.0.113.1", vec!["branch", "staging"]),
+    ];
+
+    let mut created = 0;
+
+    for (id, name, host, tags) in nodes {
+        if state.node_service.get_node(id).await?.is_some() {
+            continue;
+        }
+
+        let request = CreateNodeRequest {
+            name: name.to_string(),
+            description: Some(format!("Sample router {}", name)),
+            host: host.to_string(),
+            port: Some(8443),
+            api_key: SEED_NODE_API_KEY.to_string(),
+            use_https: Some(true),
+            verify_ssl: Some(false),
+            tags: Some(tags.into_iter().map(String::from).collect()),
+            timeout: Some(10),
+            team_id: None,
+            transport: None,
+            ssh_port: None,
+            ssh_username: None,
+            ssh_password: None,
+            ssh_private_key: None,
+            custom_fields: Default::default(),
+        };
+        state.node_service.create_node_with_id(id, request).await?;
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+async fn seed_alert_rules(state: &AppState) -> Result<usize, AppError> {
+    let rules = [
+        (
+            CPU_ALERT_RULE_ID,
+            "High CPU usage",
+            "cpu_usage_percent",
+            MetricType::Cpu,
+            90.0,
+            AlertOperator::GreaterThan,
+            AlertSeverity::Warning,
+        ),
+        (
+            MEMORY_ALERT_RULE_ID,
+            "Memory exhausted",
+            "memory_usage_percent",
+            MetricType::Memory,
+            95.0,
+            AlertOperator::GreaterThanOrEqual,
+            AlertSeverity::Critical,
+        ),
+        (
+            INTERFACE_ALERT_RULE_ID,
+            "Interface down",
+            "interface_up",
+            MetricType::Interface,
+            0.0,
+            AlertOperator::Equal,
+            AlertSeverity::Critical,
+        ),
+    ];
+
+    let existing = state.monitoring_service.get_alert_rules().await?;
+    let mut created = 0;
+
+    for (id, name, metric_name, metric_type, threshold, operator, severity) in rules {
+        if existing.iter().any(|rule| rule.id == id) {
+            continue;
+        }
+
+        let rule = AlertRuleCreate {
+            name: name.to_string(),
+            description: Some(format!("Sample rule: {}", name)),
+            metric_name: metric_name.to_string(),
+            metric_type,
+            threshold,
+            operator,
+            severity,
+            for_seconds: 300,
+            labels: vec![],
+            node_tag: None,
+            threshold_overrides: vec![],
+            conditions: vec![],
+            combine: ConditionCombinator::And,
+        };
+        state.monitoring_service.create_alert_rule_with_id(id, rule).await?;
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+async fn seed_history(state: &AppState) -> Result<usize, AppError> {
+    // (node, version, host name, summary, created at)
+    let entries = [
+        (EDGE_NODE_ID, 1, "edge-1", "Initial configuration", "2026-01-05T09:00:00.000Z"),
+        (EDGE_NODE_ID, 2, "edge-1", "Add NTP servers", "2026-01-12T14:30:00.000Z"),
+        (CORE_NODE_ID, 1, "core-1", "Initial configuration", "2026-01-06T10:00:00.000Z"),
+    ];
+
+    let db = state.db.get_ref();
+    let mut created = 0;
+
+    for (node_id, version, host_name, summary, created_at) in entries {
+        let id = history_entry_id(node_id, version).to_string();
+        if db.find_config_history(&id).await?.is_some() {
+            continue;
+        }
+
+        let config_data = serde_json::json!({
+            "system": { "host-name": host_name, "time-zone": "UTC" },
+        });
+        db.create_config_history(&ConfigHistoryRecord {
+            id,
+            node_id: node_id.to_string(),
+            user_id: Some(ADMIN_USER_ID.to_string()),
+            version: version.to_string(),
+            config_data: config_data.to_string(),
+            change_summary: Some(summary.to_string()),
+            is_rollback_point: version == 1,
+            created_at: created_at.to_string(),
+        })
+        .await?;
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+/// Stable ID of a seeded history entry, derived from its node and version
+pub fn history_entry_id(node_id: Uuid, version: u32) -> Uuid {
+    Uuid::from_u128(node_id.as_u128() + ((version as u128) << 16))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::db::create_database;
+
+    #[tokio::test]
+    async fn test_seed_is_idempotent() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = create_database(pool).await.unwrap();
+        let state = AppState::new(AppConfig::from_env().unwrap(), db);
+
+        let summary = seed(&state).await.unwrap();
+        assert_eq!(
+            summary,
+            SeedSummary { users: 3, nodes: 3, alert_rules: 3, history_entries: 3 }
+        );
+        assert_eq!(seed(&state).await.unwrap(), SeedSummary::default());
+
+        let admin = state.db.find_user_by_username("alice").await.unwrap().unwrap();
+        assert_eq!(admin.id, ADMIN_USER_ID.to_string());
+        assert!(admin.is_superuser);
+        assert!(state.auth_service.verify_password(SEED_PASSWORD, &admin.password_hash).unwrap());
+
+        let history = state.db.list_config_history(&EDGE_NODE_ID.to_string()).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].version, "2");
+        assert_eq!(history[1].id, history_entry_id(EDGE_NODE_ID, 1).to_string());
+    }
+}
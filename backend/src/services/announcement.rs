@@ -0,0 +1,209 @@
+//! Announcement Service
+//!
+//! Manages admin-authored announcements such as maintenance banners and
+//! pushes every change to connected UIs over the WebSocket.
+
+use chrono::{DateTime, Utc};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::announcement::{
+    Announcement, AnnouncementEvent, AnnouncementListResponse, AnnouncementSeverity,
+    CreateAnnouncementRequest, UpdateAnnouncementRequest, ANNOUNCEMENTS_CHANNEL,
+};
+use crate::models::auth::Claims;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::services::EventBus;
+
+const ANNOUNCEMENT_COLUMNS: &str = "id, message, severity, starts_at, ends_at, created_by, created_at, updated_at";
+
+/// Announcement service
+#[derive(Clone)]
+pub struct AnnouncementService {
+    db: Database,
+    events: EventBus,
+}
+
+impl AnnouncementService {
+    /// Create a new announcement service
+    pub fn new(db: Database, events: EventBus) -> Self {
+        Self { db, events }
+    }
+
+    /// List announcements, newest first
+    ///
+    /// Only currently shown announcements are returned unless `all` is set.
+    pub async fn list_announcements(&self, all: bool) -> Result<AnnouncementListResponse, AppError> {
+        let query = if all {
+            format!(
+                "SELECT {} FROM announcements ORDER BY starts_at DESC, id",
+                ANNOUNCEMENT_COLUMNS
+            )
+        } else {
+            format!(
+                "SELECT {} FROM announcements
+                 WHERE starts_at <= ? AND (ends_at IS NULL OR ends_at > ?)
+                 ORDER BY starts_at DESC, id",
+                ANNOUNCEMENT_COLUMNS
+            )
+        };
+
+        let now = db_now();
+        let mut rows_builder = sqlx::query_as::<_, AnnouncementRow>(&query);
+        if !all {
+            rows_builder = rows_builder.bind(&now).bind(&now);
+        }
+        let rows = rows_builder.fetch_all(self.db.pool()).await?;
+
+        let announcements: Vec<Announcement> = rows.into_iter().map(announcement_from_row).collect();
+        Ok(AnnouncementListResponse {
+            total: announcements.len() as u64,
+            announcements,
+        })
+    }
+
+    /// Get an announcement by ID
+    pub async fn get_announcement(&self, id: Uuid) -> Result<Announcement, AppError> {
+        let query = format!("SELECT {} FROM announcements WHERE id = ?", ANNOUNCEMENT_COLUMNS);
+
+        sqlx::query_as::<_, AnnouncementRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(announcement_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Announcement {} not found", id)))
+    }
+
+    /// Create an announcement and broadcast it
+    pub async fn create_announcement(
+        &self,
+        claims: &Claims,
+        request: CreateAnnouncementRequest,
+    ) -> Result<Announcement, AppError> {
+        let starts_at = request.starts_at.unwrap_or_else(Utc::now);
+        validate_window(starts_at, request.ends_at)?;
+
+        let id = Uuid::new_v4();
+        let now = db_now();
+        sqlx::query(
+            r#"
+            INSERT INTO announcements (id, message, severity, starts_at, ends_at, created_by, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(&request.message)
+        .bind(request.severity.as_str())
+        .bind(format_timestamp(&starts_at))
+        .bind(request.ends_at.as_ref().map(format_timestamp))
+        .bind(&claims.sub)
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await?;
+
+        let announcement = self.get_announcement(id).await?;
+        info!("Announcement {} created by {}", id, claims.username);
+        self.publish(AnnouncementEvent::Created {
+            announcement: announcement.clone(),
+        })
+        .await;
+
+        Ok(announcement)
+    }
+
+    /// Update an announcement and broadcast the change
+    pub async fn update_announcement(
+        &self,
+        id: Uuid,
+        request: UpdateAnnouncementRequest,
+    ) -> Result<Announcement, AppError> {
+        let current = self.get_announcement(id).await?;
+
+        let message = request.message.unwrap_or(current.message);
+        let severity = request.severity.unwrap_or(current.severity);
+        let starts_at = request.starts_at.unwrap_or(current.starts_at);
+        let ends_at = request.ends_at.or(current.ends_at);
+        validate_window(starts_at, ends_at)?;
+
+        sqlx::query(
+            r#"
+            UPDATE announcements
+            SET message = ?, severity = ?, starts_at = ?, ends_at = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&message)
+        .bind(severity.as_str())
+        .bind(format_timestamp(&starts_at))
+        .bind(ends_at.as_ref().map(format_timestamp))
+        .bind(db_now())
+        .bind(id.to_string())
+        .execute(self.db.pool())
+        .await?;
+
+        let announcement = self.get_announcement(id).await?;
+        self.publish(AnnouncementEvent::Updated {
+            announcement: announcement.clone(),
+        })
+        .await;
+
+        Ok(announcement)
+    }
+
+    /// Delete an announcement and tell connected UIs to hide it
+    pub async fn delete_announcement(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM announcements WHERE id = ?")
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Announcement {} not found", id)));
+        }
+
+        self.publish(AnnouncementEvent::Deleted { id }).await;
+        Ok(())
+    }
+
+    /// Send an announcement change to every connected UI
+    ///
+    /// Announcements concern all users, so connections receive them without
+    /// subscribing to the channel.
+    async fn publish(&self, event: AnnouncementEvent) {
+        self.events.publish_to_all(ANNOUNCEMENTS_CHANNEL, &event).await;
+    }
+}
+
+/// Ensure an announcement ends after it starts
+fn validate_window(
+    starts_at: DateTime<Utc>,
+    ends_at: Option<DateTime<Utc>>,
+) -> Result<(), AppError> {
+    match ends_at {
+        Some(ends_at) if ends_at <= starts_at => Err(AppError::Validation(
+            "Announcement must end after it starts".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Announcement columns as selected by the queries above
+type AnnouncementRow = (String, String, String, String, Option<String>, Option<String>, String, String);
+
+fn announcement_from_row(
+    (id, message, severity, starts_at, ends_at, created_by, created_at, updated_at): AnnouncementRow,
+) -> Announcement {
+    Announcement {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        message,
+        severity: AnnouncementSeverity::parse(&severity),
+        starts_at: parse_db_timestamp(&starts_at),
+        ends_at: ends_at.as_deref().map(parse_db_timestamp),
+        created_by: created_by.and_then(|id| Uuid::parse_str(&id).ok()),
+        created_at: parse_db_timestamp(&created_at),
+        updated_at: parse_db_timestamp(&updated_at),
+    }
+}
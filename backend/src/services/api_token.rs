@@ -0,0 +1,263 @@
+//! API Token Service
+//!
+//! Manages read-only API tokens for wallboards and other unattended
+//! displays. A token acts for the user who created it, on the GET routes of
+//! its scopes only, until it expires or is revoked; the authentication
+//! middleware accepts it as a bearer token or in the `token` query
+//! parameter, so it can be embedded in a URL. Only the SHA-256 of a token is
+//! stored.
+
+use std::collections::BTreeSet;
+
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::api_token::{
+    ApiToken, ApiTokenScope, CreateApiTokenRequest, CreatedApiToken, API_TOKEN_PREFIX, MAX_API_TOKENS_PER_USER,
+    MAX_API_TOKEN_DAYS,
+};
+use crate::models::auth::Claims;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+
+/// Characters of a token kept to recognize it by
+const PREFIX_LENGTH: usize = 12;
+
+const TOKEN_COLUMNS: &str = "t.id, t.name, t.token_prefix, t.user_id, u.username, t.scopes, t.node_ids, \
+                             t.expires_at, t.last_used_at, t.revoked_at, t.created_at";
+
+/// API token service
+#[derive(Clone)]
+pub struct ApiTokenService {
+    db: Database,
+}
+
+impl ApiTokenService {
+    /// Create a new API token service
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List API tokens, newest first, of one user or of everyone
+    pub async fn list_tokens(&self, user_id: Option<Uuid>) -> Result<Vec<ApiToken>, AppError> {
+        let query = format!(
+            "SELECT {} FROM api_tokens t JOIN users u ON u.id = t.user_id \
+             WHERE (? IS NULL OR t.user_id = ?) ORDER BY t.created_at DESC",
+            TOKEN_COLUMNS
+        );
+        let user_id = user_id.map(|id| id.to_string());
+        let rows: Vec<TokenRow> = sqlx::query_as(&query)
+            .bind(&user_id)
+            .bind(&user_id)
+            .fetch_all(self.db.pool())
+            .await?;
+
+        rows.into_iter().map(token_from_row).collect()
+    }
+
+    /// Get an API token
+    pub async fn get_token(&self, id: Uuid) -> Result<ApiToken, AppError> {
+        let query = format!(
+            "SELECT {} FROM api_tokens t JOIN users u ON u.id = t.user_id WHERE t.id = ?",
+            TOKEN_COLUMNS
+        );
+
+        sqlx::query_as::<_, TokenRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(token_from_row)
+            .transpose()?
+            .ok_or_else(|| AppError::NotFound(format!("API token {} not found", id)))
+    }
+
+    /// Create an API token for the signed-in user
+    ///
+    /// The caller checks that the user may read the nodes the token is
+    /// limited to.
+    pub async fn create_token(
+        &self,
+        claims: &Claims,
+        request: CreateApiTokenRequest,
+    ) -> Result<CreatedApiToken, AppError> {
+        let now = Utc::now();
+        if request.expires_at <= now {
+            return Err(AppError::Validation("expires_at must be in the future".to_string()));
+        }
+        if request.expires_at > now + Duration::days(MAX_API_TOKEN_DAYS) {
+            return Err(AppError::Validation(format!(
+                "API tokens expire within {} days",
+                MAX_API_TOKEN_DAYS
+            )));
+        }
+
+        let user_id = claims.user_id()?.to_string();
+        let (active,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM api_tokens WHERE user_id = ? AND revoked_at IS NULL AND expires_at > ?")
+                .bind(&user_id)
+                .bind(format_timestamp(&now))
+                .fetch_one(self.db.pool())
+                .await?;
+        if active >= MAX_API_TOKENS_PER_USER {
+            return Err(AppError::Validation(format!(
+                "A user may hold at most {} active API tokens",
+                MAX_API_TOKENS_PER_USER
+            )));
+        }
+
+        // Scopes and nodes are sets; keep them in a stable order
+        let scopes: Vec<ApiTokenScope> = request.scopes.iter().fold(Vec::new(), |mut scopes, scope| {
+            if !scopes.contains(scope) {
+                scopes.push(*scope);
+            }
+            scopes
+        });
+        let node_ids = request
+            .node_ids
+            .map(|ids| ids.into_iter().collect::<BTreeSet<Uuid>>().into_iter().collect::<Vec<_>>());
+
+        let id = Uuid::new_v4();
+        let token = new_api_token();
+        sqlx::query(
+            "INSERT INTO api_tokens (id, user_id, name, token_prefix, token_hash, scopes, node_ids, expires_at, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id.to_string())
+        .bind(&user_id)
+        .bind(request.name.trim())
+        .bind(&token[..PREFIX_LENGTH])
+        .bind(token_hash(&token))
+        .bind(serde_json::to_string(&scopes)?)
+        .bind(node_ids.as_ref().map(serde_json::to_string).transpose()?)
+        .bind(format_timestamp(&request.expires_at))
+        .bind(format_timestamp(&now))
+        .execute(self.db.pool())
+        .await?;
+
+        info!("API token {} created by {}", request.name.trim(), claims.username);
+        Ok(CreatedApiToken {
+            api_token: self.get_token(id).await?,
+            token,
+        })
+    }
+
+    /// Revoke an API token; revoking it again changes nothing
+    pub async fn revoke_token(&self, id: Uuid) -> Result<ApiToken, AppError> {
+        sqlx::query("UPDATE api_tokens SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL")
+            .bind(db_now())
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        let token = self.get_token(id).await?;
+        info!("API token {} of {} revoked", token.name, token.username);
+        Ok(token)
+    }
+
+    /// Authenticate a read of a path with an API token
+    ///
+    /// Returns the claims of the user the token acts for. The token must be
+    /// active, its user active, and the path within its scopes.
+    pub async fn authenticate(&self, token: &str, path: &str, query_node_id: Option<&str>) -> Result<Claims, AppError> {
+        let query = format!(
+            "SELECT {} FROM api_tokens t JOIN users u ON u.id = t.user_id WHERE t.token_hash = ? AND u.is_active = 1",
+            TOKEN_COLUMNS
+        );
+        let api_token = sqlx::query_as::<_, TokenRow>(&query)
+            .bind(token_hash(token.trim()))
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(token_from_row)
+            .transpose()?
+            .ok_or_else(|| AppError::Auth("Invalid API token".to_string()))?;
+
+        let now = Utc::now();
+        if api_token.revoked_at.is_some() {
+            return Err(AppError::Auth("API token has been revoked".to_string()));
+        }
+        if !api_token.is_active(now) {
+            return Err(AppError::Auth("API token has expired".to_string()));
+        }
+        if !api_token.permits(path, query_node_id) {
+            return Err(AppError::Forbidden("API token does not cover this resource".to_string()));
+        }
+
+        sqlx::query("UPDATE api_tokens SET last_used_at = ? WHERE id = ?")
+            .bind(format_timestamp(&now))
+            .bind(api_token.id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(Claims {
+            sub: api_token.user_id,
+            username: api_token.username,
+            exp: api_token.expires_at.timestamp(),
+            iat: api_token.created_at.timestamp(),
+            sid: None,
+        })
+    }
+}
+
+/// Check whether a credential is an API token rather than an access token
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(API_TOKEN_PREFIX)
+}
+
+/// New random API token
+fn new_api_token() -> String {
+    format!("{}{}{}", API_TOKEN_PREFIX, Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// SHA-256 of an API token, the form it is stored in
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+type TokenRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+);
+
+fn token_from_row(
+    (id, name, prefix, user_id, username, scopes, node_ids, expires_at, last_used_at, revoked_at, created_at): TokenRow,
+) -> Result<ApiToken, AppError> {
+    Ok(ApiToken {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        name,
+        prefix,
+        user_id,
+        username,
+        scopes: serde_json::from_str(&scopes)?,
+        node_ids: node_ids.map(|ids| serde_json::from_str(&ids)).transpose()?,
+        expires_at: parse_db_timestamp(&expires_at),
+        last_used_at: last_used_at.map(|at| parse_db_timestamp(&at)),
+        revoked_at: revoked_at.map(|at| parse_db_timestamp(&at)),
+        created_at: parse_db_timestamp(&created_at),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_api_token() {
+        let token = new_api_token();
+        assert!(is_api_token(&token));
+        assert_eq!(token.len(), API_TOKEN_PREFIX.len() + 64);
+        assert_ne!(token, new_api_token());
+        assert_eq!(token_hash(&token).len(), 64);
+    }
+}
@@ -0,0 +1,254 @@
+//! Node API Usage
+//!
+//! Counts the VyOS API calls made to each node, per operation, with their
+//! failures and latency, so operators can see what is loading a router.
+//! Every [`NodeTransport`] the node service creates is wrapped in a
+//! [`UsageRecorder`], which also runs each call in a `node_call` span naming
+//! the node and operation. Counts are kept in memory and start over when the
+//! backend restarts.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::api_usage::{ApiOperationUsage, NodeApiUsage};
+use crate::vyos_client::{
+    Capability, ConfigOperation, NodeTransport, VyOSConnectionTest, VyOSInfo, VyOSInterface,
+    VyOSShowResult,
+};
+
+/// Calls of one operation to one node
+#[derive(Debug, Default)]
+struct OperationCounts {
+    calls: u64,
+    errors: u64,
+    total_latency_ms: u64,
+    last_called_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+/// Per-node API usage counters
+#[derive(Clone)]
+pub struct ApiUsageTracker {
+    since: DateTime<Utc>,
+    nodes: Arc<Mutex<HashMap<Uuid, HashMap<&'static str, OperationCounts>>>>,
+}
+
+impl Default for ApiUsageTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApiUsageTracker {
+    /// Create a tracker with no calls counted
+    pub fn new() -> Self {
+        Self {
+            since: Utc::now(),
+            nodes: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Wrap a node's transport so its calls are counted
+    pub fn track(&self, node_id: Uuid, inner: Box<dyn NodeTransport>) -> Box<dyn NodeTransport> {
+        Box::new(UsageRecorder {
+            node_id,
+            tracker: self.clone(),
+            inner,
+        })
+    }
+
+    /// Count one call to a node
+    pub fn record(&self, node_id: Uuid, operation: &'static str, latency_ms: u64, error: Option<String>) {
+        let mut nodes = self.nodes();
+        let counts = nodes.entry(node_id).or_default().entry(operation).or_default();
+        counts.calls += 1;
+        counts.total_latency_ms = counts.total_latency_ms.saturating_add(latency_ms);
+        counts.last_called_at = Some(Utc::now());
+        if error.is_some() {
+            counts.errors += 1;
+            counts.last_error = error;
+        }
+    }
+
+    /// Usage of a node, with the busiest operations first
+    pub fn usage(&self, node_id: Uuid) -> NodeApiUsage {
+        let nodes = self.nodes();
+        let mut operations: Vec<ApiOperationUsage> = nodes
+            .get(&node_id)
+            .into_iter()
+            .flatten()
+            .map(|(operation, counts)| ApiOperationUsage {
+                operation: operation.to_string(),
+                calls: counts.calls,
+                errors: counts.errors,
+                error_rate: error_rate(counts.calls, counts.errors),
+                avg_latency_ms: if counts.calls == 0 {
+                    0.0
+                } else {
+                    counts.total_latency_ms as f64 / counts.calls as f64
+                },
+                last_called_at: counts.last_called_at,
+                last_error: counts.last_error.clone(),
+            })
+            .collect();
+        operations.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.operation.cmp(&b.operation)));
+
+        let total_calls = operations.iter().map(|operation| operation.calls).sum();
+        let total_errors = operations.iter().map(|operation| operation.errors).sum();
+        NodeApiUsage {
+            node_id,
+            since: self.since,
+            total_calls,
+            total_errors,
+            error_rate: error_rate(total_calls, total_errors),
+            operations,
+        }
+    }
+
+    /// Drop the counts of a deleted node
+    pub fn forget(&self, node_id: Uuid) {
+        self.nodes().remove(&node_id);
+    }
+
+    fn nodes(&self) -> MutexGuard<'_, HashMap<Uuid, HashMap<&'static str, OperationCounts>>> {
+        // Counters stay usable even if a holder panicked
+        self.nodes.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Share of calls that failed, or 0 without calls
+fn error_rate(calls: u64, errors: u64) -> f64 {
+    if calls == 0 {
+        0.0
+    } else {
+        errors as f64 / calls as f64
+    }
+}
+
+/// Transport counting the calls made through it
+pub struct UsageRecorder {
+    node_id: Uuid,
+    tracker: ApiUsageTracker,
+    inner: Box<dyn NodeTransport>,
+}
+
+impl UsageRecorder {
+    /// Span of one call, attributing the node's HTTP calls to the node and
+    /// operation
+    fn span(&self, operation: &'static str) -> tracing::Span {
+        tracing::info_span!("node_call", node_id = %self.node_id, operation)
+    }
+
+    fn record<T>(&self, operation: &'static str, start: Instant, result: &Result<T, AppError>) {
+        let error = result.as_ref().err().map(ToString::to_string);
+        self.tracker
+            .record(self.node_id, operation, start.elapsed().as_millis() as u64, error);
+    }
+}
+
+#[async_trait]
+impl NodeTransport for UsageRecorder {
+    async fn get_info(&self) -> Result<VyOSInfo, AppError> {
+        let start = Instant::now();
+        let result = self.inner.get_info().instrument(self.span("get_info")).await;
+        self.record("get_info", start, &result);
+        result
+    }
+
+    async fn retrieve_config(&self, path: Option<String>) -> Result<serde_json::Value, AppError> {
+        let start = Instant::now();
+        let result = self.inner.retrieve_config(path).instrument(self.span("retrieve_config")).await;
+        self.record("retrieve_config", start, &result);
+        result
+    }
+
+    async fn show(&self, command: &str) -> Result<VyOSShowResult, AppError> {
+        let start = Instant::now();
+        let result = self.inner.show(command).instrument(self.span("show")).await;
+        self.record("show", start, &result);
+        result
+    }
+
+    async fn run_op_command(&self, command: &str, output: &mut (dyn FnMut(String) + Send)) -> Result<(), AppError> {
+        let start = Instant::now();
+        let result = self.inner.run_op_command(command, output).instrument(self.span("run_op_command")).await;
+        self.record("run_op_command", start, &result);
+        result
+    }
+
+    async fn get_interfaces(&self) -> Result<Vec<VyOSInterface>, AppError> {
+        let start = Instant::now();
+        let result = self.inner.get_interfaces().instrument(self.span("get_interfaces")).await;
+        self.record("get_interfaces", start, &result);
+        result
+    }
+
+    async fn supports(&self, capability: Capability) -> Result<bool, AppError> {
+        let start = Instant::now();
+        let result = self.inner.supports(capability).instrument(self.span("supports")).await;
+        self.record("supports", start, &result);
+        result
+    }
+
+    /// Failed tests are answered with `success: false`, and count as errors
+    async fn test_connection(&self) -> Result<VyOSConnectionTest, AppError> {
+        let start = Instant::now();
+        let result = self.inner.test_connection().instrument(self.span("test_connection")).await;
+        let error = match &result {
+            Ok(test) if !test.success => Some(test.error.clone().unwrap_or_else(|| "Connection failed".to_string())),
+            Ok(_) => None,
+            Err(e) => Some(e.to_string()),
+        };
+        self.tracker
+            .record(self.node_id, "test_connection", start.elapsed().as_millis() as u64, error);
+        result
+    }
+
+    async fn configure(&self, operations: &[ConfigOperation]) -> Result<(), AppError> {
+        let start = Instant::now();
+        let result = self.inner.configure(operations).instrument(self.span("configure")).await;
+        self.record("configure", start, &result);
+        result
+    }
+
+    async fn reboot(&self) -> Result<(), AppError> {
+        let start = Instant::now();
+        let result = self.inner.reboot().instrument(self.span("reboot")).await;
+        self.record("reboot", start, &result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_per_operation() {
+        let tracker = ApiUsageTracker::new();
+        let node_id = Uuid::new_v4();
+        tracker.record(node_id, "show", 10, None);
+        tracker.record(node_id, "show", 30, Some("timed out".to_string()));
+        tracker.record(node_id, "get_info", 5, None);
+        tracker.record(Uuid::new_v4(), "show", 1, None);
+
+        let usage = tracker.usage(node_id);
+        assert_eq!(usage.total_calls, 3);
+        assert_eq!(usage.total_errors, 1);
+        assert_eq!(usage.operations[0].operation, "show");
+        assert_eq!(usage.operations[0].error_rate, 0.5);
+        assert_eq!(usage.operations[0].avg_latency_ms, 20.0);
+        assert_eq!(usage.operations[0].last_error.as_deref(), Some("timed out"));
+        assert_eq!(usage.operations[1].operation, "get_info");
+
+        tracker.forget(node_id);
+        assert_eq!(tracker.usage(node_id).total_calls, 0);
+    }
+}
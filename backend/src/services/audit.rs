@@ -0,0 +1,529 @@
+//! Audit Service
+//!
+//! Records user actions and system operations, and serves them as filtered,
+//! sorted, paginated lists or as NDJSON exports.
+
+use chrono::Utc;
+use futures::stream::{self, Stream};
+use serde::Serialize;
+use sqlx::sqlite::SqliteRow;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::audit::{
+    AuditEntry, AuditEvent, AuditListQuery, AuditListResponse, AuditResult, Operation,
+    OperationListQuery, OperationListResponse, OperationStatus, AUDIT_SORT_COLUMNS,
+    OPERATION_SORT_COLUMNS,
+};
+use crate::models::auth::Claims;
+use crate::models::system::OperationResult;
+use crate::models::time_range::resolve_time_range;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+
+/// Rows fetched per query while exporting
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+const AUDIT_COLUMNS: &str = "id, user_id, username, node_id, action, target, result, details, created_at";
+
+const OPERATION_COLUMNS: &str = "id, kind, node_id, user_id, status, message, started_at, completed_at";
+
+/// Audit service for the audit log and recorded operations
+#[derive(Clone)]
+pub struct AuditService {
+    db: Database,
+}
+
+impl AuditService {
+    /// Create a new audit service
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Ensure the caller is an administrator
+    ///
+    /// The audit log and operation history cover all teams, so only
+    /// administrators may read them.
+    pub async fn ensure_admin(&self, claims: &Claims) -> Result<(), AppError> {
+        let user = self
+            .db
+            .find_user_by_id(&claims.sub)
+            .await?
+            .ok_or_else(|| AppError::Auth("User not found".to_string()))?;
+
+        if !user.is_superuser {
+            return Err(AppError::Forbidden("Admin access required".to_string()));
+        }
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Audit Log
+    // ========================================================================
+
+    /// Write an entry to the audit log
+    ///
+    /// Failures are logged rather than returned so auditing never fails the
+    /// action being audited.
+    pub async fn record(&self, event: AuditEvent) {
+        let query = r#"
+            INSERT INTO audit_log (id, user_id, username, node_id, action, target, result, details, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#;
+
+        let result = sqlx::query(query)
+            .bind(Uuid::new_v4().to_string())
+            .bind(event.user_id.map(|id| id.to_string()))
+            .bind(&event.username)
+            .bind(event.node_id.map(|id| id.to_string()))
+            .bind(&event.action)
+            .bind(&event.target)
+            .bind(event.result.as_str())
+            .bind(event.details.as_ref().map(|d| d.to_string()))
+            .bind(db_now())
+            .execute(self.db.pool())
+            .await;
+
+        if let Err(e) = result {
+            warn!("Failed to record audit entry {}: {}", event.action, e);
+        }
+    }
+
+    /// List audit entries
+    pub async fn list_entries(&self, mut query: AuditListQuery) -> Result<AuditListResponse, AppError> {
+        resolve_time_range(&mut query.from, &mut query.to, query.window, query.preset)?;
+        let (page, page_size, offset) = paging(query.page, query.page_size);
+        let (where_clause, bind_values) = audit_filter(&query);
+        let order = order_clause(&query.sort_by, &query.sort_order, AUDIT_SORT_COLUMNS, "created_at")?;
+
+        let total = self.count("audit_log", &where_clause, &bind_values).await?;
+
+        let data_query = format!(
+            "SELECT {} FROM audit_log WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+            AUDIT_COLUMNS, where_clause, order
+        );
+        let mut rows_builder = sqlx::query_as::<_, AuditRow>(&data_query);
+        for value in &bind_values {
+            rows_builder = rows_builder.bind(value);
+        }
+        let rows = rows_builder
+            .bind(page_size as i64)
+            .bind(offset as i64)
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(AuditListResponse {
+            entries: rows.into_iter().map(audit_entry_from_row).collect(),
+            total,
+            page,
+            page_size,
+            total_pages: total_pages(total, page_size),
+        })
+    }
+
+    /// Export audit entries as NDJSON, one entry per line
+    ///
+    /// Paging parameters are ignored. Entries created after the export
+    /// started are left out so a long export does not chase new rows.
+    pub fn export_entries(
+        &self,
+        mut query: AuditListQuery,
+    ) -> Result<impl Stream<Item = Result<String, AppError>>, AppError> {
+        let order = order_clause(&query.sort_by, &query.sort_order, AUDIT_SORT_COLUMNS, "created_at")?;
+        resolve_time_range(&mut query.from, &mut query.to, query.window, query.preset)?;
+        let now = Utc::now();
+        query.to = Some(query.to.map_or(now, |to| to.min(now)));
+        let (where_clause, bind_values) = audit_filter(&query);
+
+        let sql = format!(
+            "SELECT {} FROM audit_log WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+            AUDIT_COLUMNS, where_clause, order
+        );
+        Ok(export_rows(self.db.clone(), sql, bind_values, audit_entry_from_row))
+    }
+
+    // ========================================================================
+    // Operations
+    // ========================================================================
+
+    /// Record the outcome of a system operation
+    ///
+    /// Accepted operations without a completion time are recorded as running.
+    /// Like [`AuditService::record`], failures are only logged.
+    pub async fn record_operation(&self, kind: &str, claims: Option<&Claims>, result: &OperationResult) {
+        let status = match (result.success, result.completed_at) {
+            (false, _) => OperationStatus::Failed,
+            (true, None) => OperationStatus::Running,
+            (true, Some(_)) => OperationStatus::Completed,
+        };
+
+        let query = r#"
+            INSERT INTO operations (id, kind, node_id, user_id, status, message, started_at, completed_at)
+            VALUES (?, ?, NULL, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                status = excluded.status,
+                message = excluded.message,
+                completed_at = excluded.completed_at
+        "#;
+
+        let insert = sqlx::query(query)
+            .bind(&result.operation_id)
+            .bind(kind)
+            .bind(claims.map(|c| c.sub.clone()))
+            .bind(status.as_str())
+            .bind(&result.message)
+            .bind(format_timestamp(&result.started_at))
+            .bind(result.completed_at.as_ref().map(format_timestamp))
+            .execute(self.db.pool())
+            .await;
+
+        if let Err(e) = insert {
+            warn!("Failed to record operation {}: {}", result.operation_id, e);
+        }
+    }
+
+    /// Get a recorded operation
+    pub async fn get_operation(&self, operation_id: &str) -> Result<Option<Operation>, AppError> {
+        let query = format!("SELECT {} FROM operations WHERE id = ?", OPERATION_COLUMNS);
+
+        let row = sqlx::query_as::<_, OperationRow>(&query)
+            .bind(operation_id)
+            .fetch_optional(self.db.pool())
+            .await?;
+
+        Ok(row.map(operation_from_row))
+    }
+
+    /// List recorded operations
+    pub async fn list_operations(&self, mut query: OperationListQuery) -> Result<OperationListResponse, AppError> {
+        resolve_time_range(&mut query.from, &mut query.to, query.window, query.preset)?;
+        let (page, page_size, offset) = paging(query.page, query.page_size);
+        let (where_clause, bind_values) = operation_filter(&query);
+        let order = order_clause(&query.sort_by, &query.sort_order, OPERATION_SORT_COLUMNS, "started_at")?;
+
+        let total = self.count("operations", &where_clause, &bind_values).await?;
+
+        let data_query = format!(
+            "SELECT {} FROM operations WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+            OPERATION_COLUMNS, where_clause, order
+        );
+        let mut rows_builder = sqlx::query_as::<_, OperationRow>(&data_query);
+        for value in &bind_values {
+            rows_builder = rows_builder.bind(value);
+        }
+        let rows = rows_builder
+            .bind(page_size as i64)
+            .bind(offset as i64)
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(OperationListResponse {
+            operations: rows.into_iter().map(operation_from_row).collect(),
+            total,
+            page,
+            page_size,
+            total_pages: total_pages(total, page_size),
+        })
+    }
+
+    /// Export recorded operations as NDJSON, one operation per line
+    ///
+    /// Same semantics as [`AuditService::export_entries`].
+    pub fn export_operations(
+        &self,
+        mut query: OperationListQuery,
+    ) -> Result<impl Stream<Item = Result<String, AppError>>, AppError> {
+        let order = order_clause(&query.sort_by, &query.sort_order, OPERATION_SORT_COLUMNS, "started_at")?;
+        resolve_time_range(&mut query.from, &mut query.to, query.window, query.preset)?;
+        let now = Utc::now();
+        query.to = Some(query.to.map_or(now, |to| to.min(now)));
+        let (where_clause, bind_values) = operation_filter(&query);
+
+        let sql = format!(
+            "SELECT {} FROM operations WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+            OPERATION_COLUMNS, where_clause, order
+        );
+        Ok(export_rows(self.db.clone(), sql, bind_values, operation_from_row))
+    }
+
+    async fn count(&self, table: &str, where_clause: &str, bind_values: &[String]) -> Result<u64, AppError> {
+        let count_query = format!("SELECT COUNT(*) FROM {} WHERE {}", table, where_clause);
+        let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
+        for value in bind_values {
+            count_query_builder = count_query_builder.bind(value);
+        }
+        Ok(count_query_builder.fetch_one(self.db.pool()).await? as u64)
+    }
+}
+
+// ============================================================================
+// Query Building
+// ============================================================================
+
+/// Page number, page size, and row offset of a list request
+fn paging(page: Option<u32>, page_size: Option<u32>) -> (u32, u32, u32) {
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size.unwrap_or(50).clamp(1, 500);
+    (page, page_size, (page - 1) * page_size)
+}
+
+fn total_pages(total: u64, page_size: u32) -> u32 {
+    ((total as f64) / (page_size as f64)).ceil() as u32
+}
+
+/// Build an ORDER BY clause from a whitelisted column
+///
+/// Rows are additionally ordered by ID so pages stay stable when the sort
+/// column has duplicates.
+fn order_clause(
+    sort_by: &Option<String>,
+    sort_order: &Option<String>,
+    allowed: &[&str],
+    default: &str,
+) -> Result<String, AppError> {
+    let column = sort_by.as_deref().unwrap_or(default);
+    if !allowed.contains(&column) {
+        return Err(AppError::Validation(format!(
+            "Cannot sort by '{}'; expected one of: {}",
+            column,
+            allowed.join(", ")
+        )));
+    }
+
+    let direction = match sort_order.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("desc") => "DESC",
+        Some("asc") => "ASC",
+        Some(other) => {
+            return Err(AppError::Validation(format!(
+                "Invalid sort order '{}'; expected 'asc' or 'desc'",
+                other
+            )))
+        }
+    };
+
+    Ok(format!("{column} {direction}, id {direction}"))
+}
+
+/// WHERE clause and bind values of an audit log query
+fn audit_filter(query: &AuditListQuery) -> (String, Vec<String>) {
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut bind_values: Vec<String> = vec![];
+
+    if let Some(user_id) = query.user_id {
+        where_clauses.push("user_id = ?".to_string());
+        bind_values.push(user_id.to_string());
+    }
+
+    if let Some(node_id) = query.node_id {
+        where_clauses.push("node_id = ?".to_string());
+        bind_values.push(node_id.to_string());
+    }
+
+    if let Some(action) = &query.action {
+        if action.ends_with('.') {
+            where_clauses.push("substr(action, 1, ?) = ?".to_string());
+            bind_values.push(action.len().to_string());
+        } else {
+            where_clauses.push("action = ?".to_string());
+        }
+        bind_values.push(action.clone());
+    }
+
+    if let Some(result) = query.result {
+        where_clauses.push("result = ?".to_string());
+        bind_values.push(result.as_str().to_string());
+    }
+
+    if let Some(from) = query.from {
+        where_clauses.push("created_at >= ?".to_string());
+        bind_values.push(format_timestamp(&from));
+    }
+
+    if let Some(to) = query.to {
+        where_clauses.push("created_at < ?".to_string());
+        bind_values.push(format_timestamp(&to));
+    }
+
+    (where_clauses.join(" AND "), bind_values)
+}
+
+/// WHERE clause and bind values of an operation query
+fn operation_filter(query: &OperationListQuery) -> (String, Vec<String>) {
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut bind_values: Vec<String> = vec![];
+
+    if let Some(user_id) = query.user_id {
+        where_clauses.push("user_id = ?".to_string());
+        bind_values.push(user_id.to_string());
+    }
+
+    if let Some(node_id) = query.node_id {
+        where_clauses.push("node_id = ?".to_string());
+        bind_values.push(node_id.to_string());
+    }
+
+    if let Some(kind) = &query.kind {
+        where_clauses.push("kind = ?".to_string());
+        bind_values.push(kind.clone());
+    }
+
+    if let Some(status) = query.status {
+        where_clauses.push("status = ?".to_string());
+        bind_values.push(status.as_str().to_string());
+    }
+
+    if let Some(from) = query.from {
+        where_clauses.push("started_at >= ?".to_string());
+        bind_values.push(format_timestamp(&from));
+    }
+
+    if let Some(to) = query.to {
+        where_clauses.push("started_at < ?".to_string());
+        bind_values.push(format_timestamp(&to));
+    }
+
+    (where_clauses.join(" AND "), bind_values)
+}
+
+/// Stream the rows of a query as NDJSON chunks, one batch per chunk
+///
+/// `sql` must end in `LIMIT ? OFFSET ?`.
+fn export_rows<R, T>(
+    db: Database,
+    sql: String,
+    bind_values: Vec<String>,
+    to_item: fn(R) -> T,
+) -> impl Stream<Item = Result<String, AppError>>
+where
+    R: for<'r> sqlx::FromRow<'r, SqliteRow> + Send + Unpin + 'static,
+    T: Serialize,
+{
+    stream::unfold(Some(0i64), move |offset| {
+        let db = db.clone();
+        let sql = sql.clone();
+        let bind_values = bind_values.clone();
+        async move {
+            let offset = offset?;
+
+            let mut rows_builder = sqlx::query_as::<_, R>(&sql);
+            for value in &bind_values {
+                rows_builder = rows_builder.bind(value);
+            }
+            let rows = match rows_builder
+                .bind(EXPORT_BATCH_SIZE)
+                .bind(offset)
+                .fetch_all(db.pool())
+                .await
+            {
+                Ok(rows) if rows.is_empty() => return None,
+                Ok(rows) => rows,
+                Err(e) => return Some((Err(e.into()), None)),
+            };
+
+            let next = (rows.len() as i64 == EXPORT_BATCH_SIZE).then_some(offset + EXPORT_BATCH_SIZE);
+            let mut chunk = String::new();
+            for row in rows {
+                match serde_json::to_string(&to_item(row)) {
+                    Ok(line) => {
+                        chunk.push_str(&line);
+                        chunk.push('\n');
+                    }
+                    Err(e) => return Some((Err(AppError::Internal(e.to_string())), None)),
+                }
+            }
+
+            Some((Ok(chunk), next))
+        }
+    })
+}
+
+// ============================================================================
+// Row Mapping
+// ============================================================================
+
+/// Audit log columns as selected by the queries above
+type AuditRow = (
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    Option<String>,
+    String,
+    Option<String>,
+    String,
+);
+
+fn audit_entry_from_row(
+    (id, user_id, username, node_id, action, target, result, details, created_at): AuditRow,
+) -> AuditEntry {
+    AuditEntry {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        user_id: user_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        username,
+        node_id: node_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        action,
+        target,
+        result: AuditResult::from_db(&result),
+        details: details.and_then(|d| serde_json::from_str(&d).ok()),
+        created_at: parse_db_timestamp(&created_at),
+    }
+}
+
+/// Operation columns as selected by the queries above
+type OperationRow = (String, String, Option<String>, Option<String>, String, String, String, Option<String>);
+
+fn operation_from_row(
+    (id, kind, node_id, user_id, status, message, started_at, completed_at): OperationRow,
+) -> Operation {
+    Operation {
+        id,
+        kind,
+        node_id: node_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        user_id: user_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        status: OperationStatus::from_db(&status),
+        message,
+        started_at: parse_db_timestamp(&started_at),
+        completed_at: completed_at.as_deref().map(parse_db_timestamp),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_clause_whitelist() {
+        assert_eq!(
+            order_clause(&None, &None, AUDIT_SORT_COLUMNS, "created_at").unwrap(),
+            "created_at DESC, id DESC"
+        );
+        assert_eq!(
+            order_clause(&Some("action".into()), &Some("ASC".into()), AUDIT_SORT_COLUMNS, "created_at").unwrap(),
+            "action ASC, id ASC"
+        );
+        assert!(order_clause(&Some("details; DROP TABLE audit_log".into()), &None, AUDIT_SORT_COLUMNS, "created_at").is_err());
+        assert!(order_clause(&None, &Some("sideways".into()), AUDIT_SORT_COLUMNS, "created_at").is_err());
+    }
+
+    #[test]
+    fn test_audit_filter() {
+        let query = AuditListQuery {
+            action: Some("node.".to_string()),
+            result: Some(AuditResult::Failure),
+            ..Default::default()
+        };
+        let (where_clause, bind_values) = audit_filter(&query);
+        assert_eq!(where_clause, "1=1 AND substr(action, 1, ?) = ? AND result = ?");
+        assert_eq!(bind_values, vec!["5", "node.", "failure"]);
+    }
+
+    #[test]
+    fn test_paging() {
+        assert_eq!(paging(None, None), (1, 50, 0));
+        assert_eq!(paging(Some(3), Some(10)), (3, 10, 20));
+        assert_eq!(paging(Some(0), Some(10_000)), (1, 500, 0));
+    }
+}
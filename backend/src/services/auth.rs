@@ -1,19 +1,28 @@
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
-use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tracing::{info, warn};
+use uuid::Uuid;
 
 use crate::config::AppConfig;
 use crate::db::Database;
 use crate::error::AppError;
-use crate::models::auth::Claims;
+use crate::i18n::{t, t_args};
+use crate::models::auth::{Claims, RefreshTokenResponse, SessionTokens, UserSession};
+use crate::models::login_history::describe_user_agent;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
 use crate::models::user::{User, UserRecord};
 
 /// Authentication service
+///
+/// Access tokens are short-lived JWTs. Each login starts a session, stored
+/// in the `refresh_tokens` table, whose opaque refresh token is exchanged
+/// for new tokens and replaced on every use.
 #[derive(Clone)]
 pub struct AuthService {
     jwt_secret: String,
     jwt_expiration: i64,
+    refresh_expiration: Duration,
     db: Database,
 }
 
@@ -23,12 +32,23 @@ impl AuthService {
         Self {
             jwt_secret: config.jwt_secret_key.clone(),
             jwt_expiration: (config.jwt_expiration_minutes * 60) as i64,
+            refresh_expiration: Duration::days(config.refresh_token_expiration_days as i64),
             db,
         }
     }
 
     /// Generate a JWT token for a user
     pub fn generate_token(&self, user_id: &str, username: &str) -> Result<String, AppError> {
+        self.generate_session_token(user_id, username, None)
+    }
+
+    /// Generate a JWT token for a user's login session
+    fn generate_session_token(
+        &self,
+        user_id: &str,
+        username: &str,
+        session_id: Option<Uuid>,
+    ) -> Result<String, AppError> {
         let now = Utc::now();
         let exp = now.timestamp() + self.jwt_expiration;
 
@@ -37,6 +57,7 @@ impl AuthService {
             username: username.to_string(),
             exp,
             iat: now.timestamp(),
+            sid: session_id.map(|id| id.to_string()),
         };
 
         encode(
@@ -47,6 +68,11 @@ impl AuthService {
         .map_err(|e| AppError::Jwt(format!("Token generation failed: {}", e)))
     }
 
+    /// Get the access token lifetime in seconds
+    pub fn get_expiration(&self) -> i64 {
+        self.jwt_expiration
+    }
+
     /// Validate a JWT token and return claims
     pub fn validate_token(&self, token: &str) -> Result<Claims, AppError> {
         decode::<Claims>(
@@ -81,8 +107,8 @@ impl AuthService {
     }
 
     /// Find user by ID
-    pub async fn find_user_by_id(&self, user_id: i64) -> Result<Option<UserRecord>, AppError> {
-        self.db.find_user_by_id(user_id).await
+    pub async fn find_user_by_id(&self, user_id: Uuid) -> Result<Option<UserRecord>, AppError> {
+        self.db.find_user_by_id(&user_id.to_string()).await
     }
 
     /// Authenticate a user with username/email and password
@@ -110,7 +136,7 @@ impl AuthService {
         }
 
         // Update last login timestamp
-        if let Err(e) = self.db.update_last_login(user_record.id).await {
+        if let Err(e) = self.db.update_last_login(&user_record.id).await {
             warn!("Failed to update last login for user {}: {}", user_record.username, e);
         }
 
@@ -127,46 +153,40 @@ impl AuthService {
     ) -> Result<User, AppError> {
         // Validate username
         if username.len() < 3 {
-            return Err(AppError::Validation(
-                "Username must be at least 3 characters long".to_string(),
-            ));
+            return Err(AppError::Validation(t_args("validation.username_too_short", &[("min", &3)])));
         }
 
         if username.len() > 50 {
-            return Err(AppError::Validation(
-                "Username must be at most 50 characters long".to_string(),
-            ));
+            return Err(AppError::Validation(t_args("validation.username_too_long", &[("max", &50)])));
         }
 
         // Validate email
         if !email.contains('@') || !email.contains('.') {
-            return Err(AppError::Validation("Invalid email address".to_string()));
+            return Err(AppError::Validation(t("validation.invalid_email")));
         }
 
         // Validate password
         if password.len() < 6 {
-            return Err(AppError::Validation(
-                "Password must be at least 6 characters long".to_string(),
-            ));
+            return Err(AppError::Validation(t_args("validation.password_too_short", &[("min", &6)])));
         }
 
         // Check if username already exists
         if self.find_user_by_username(username).await?.is_some() {
-            return Err(AppError::Validation("Username already exists".to_string()));
+            return Err(AppError::Validation(t("validation.username_exists")));
         }
 
         // Check if email already exists
         if self.find_user_by_email(email).await?.is_some() {
-            return Err(AppError::Validation("Email already exists".to_string()));
+            return Err(AppError::Validation(t("validation.email_exists")));
         }
 
         // Hash the password
         let password_hash = self.hash_password(password)?;
 
         // Create the user
-        let user_id = self
-            .db
-            .create_user(username, email, &password_hash, full_name.as_deref())
+        let user_id = Uuid::new_v4();
+        self.db
+            .create_user(&user_id.to_string(), username, email, &password_hash, full_name.as_deref())
             .await?;
 
         info!("Created new user: {}", username);
@@ -180,45 +200,205 @@ impl AuthService {
         Ok(user_record.to_user())
     }
 
-    /// Refresh an access token
-    pub fn refresh_token(&self, claims: &Claims) -> Result<(String, Claims), AppError> {
+    /// Start a login session, returning its access and refresh tokens
+    pub async fn start_session(
+        &self,
+        user: &User,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<SessionTokens, AppError> {
+        let session_id = Uuid::new_v4();
+        let refresh_token = new_refresh_token();
         let now = Utc::now();
-        let exp = now.timestamp() + self.jwt_expiration;
 
-        let new_claims = Claims {
-            sub: claims.sub.clone(),
-            username: claims.username.clone(),
-            exp,
-            iat: now.timestamp(),
+        sqlx::query(
+            "INSERT INTO refresh_tokens (id, user_id, token_hash, ip_address, user_agent, device, \
+                                         created_at, last_used_at, expires_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(session_id.to_string())
+        .bind(user.id.to_string())
+        .bind(token_hash(&refresh_token))
+        .bind(ip_address)
+        .bind(user_agent)
+        .bind(describe_user_agent(user_agent))
+        .bind(format_timestamp(&now))
+        .bind(format_timestamp(&now))
+        .bind(format_timestamp(&(now + self.refresh_expiration)))
+        .execute(self.db.pool())
+        .await?;
+
+        // Expired and revoked sessions are only kept until the next login
+        sqlx::query("DELETE FROM refresh_tokens WHERE user_id = ? AND (revoked_at IS NOT NULL OR expires_at <= ?)")
+            .bind(user.id.to_string())
+            .bind(format_timestamp(&now))
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(SessionTokens {
+            session_id,
+            access_token: self.generate_session_token(&user.id.to_string(), &user.username, Some(session_id))?,
+            refresh_token,
+        })
+    }
+
+    /// Exchange a refresh token for a new access token and refresh token
+    ///
+    /// A refresh token that was already exchanged may have been stolen, so
+    /// presenting it again revokes its session.
+    pub async fn refresh_token(&self, refresh_token: &str) -> Result<RefreshTokenResponse, AppError> {
+        let invalid = || AppError::Auth("Invalid or expired refresh token".to_string());
+        let hash = token_hash(refresh_token.trim());
+        let now = Utc::now();
+
+        let session = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT id, user_id, expires_at FROM refresh_tokens WHERE token_hash = ? AND revoked_at IS NULL",
+        )
+        .bind(&hash)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        let Some((session_id, user_id, expires_at)) = session else {
+            let reused = sqlx::query(
+                "UPDATE refresh_tokens SET revoked_at = ? WHERE previous_token_hash = ? AND revoked_at IS NULL",
+            )
+            .bind(format_timestamp(&now))
+            .bind(&hash)
+            .execute(self.db.pool())
+            .await?;
+            if reused.rows_affected() > 0 {
+                warn!("Revoked a session whose previous refresh token was presented again");
+            }
+            return Err(invalid());
         };
+        if parse_db_timestamp(&expires_at) <= now {
+            return Err(invalid());
+        }
 
-        let token = encode(
-            &Header::default(),
-            &new_claims,
-            &EncodingKey::from_secret(self.jwt_secret.as_bytes()),
+        let user = self
+            .db
+            .find_user_by_id(&user_id)
+            .await?
+            .filter(|user| user.is_active)
+            .ok_or_else(invalid)?;
+
+        let next = new_refresh_token();
+        let rotated = sqlx::query(
+            "UPDATE refresh_tokens SET token_hash = ?, previous_token_hash = ?, last_used_at = ?, expires_at = ? \
+             WHERE id = ? AND token_hash = ? AND revoked_at IS NULL",
+        )
+        .bind(token_hash(&next))
+        .bind(&hash)
+        .bind(format_timestamp(&now))
+        .bind(format_timestamp(&(now + self.refresh_expiration)))
+        .bind(&session_id)
+        .bind(&hash)
+        .execute(self.db.pool())
+        .await?;
+        // Another request exchanged the same token first
+        if rotated.rows_affected() == 0 {
+            return Err(invalid());
+        }
+
+        let session_id = Uuid::parse_str(&session_id).map_err(|_| invalid())?;
+        Ok(RefreshTokenResponse {
+            access_token: self.generate_session_token(&user.id, &user.username, Some(session_id))?,
+            refresh_token: next,
+            expires_in: self.jwt_expiration,
+        })
+    }
+
+    /// Active login sessions of a user, most recently used first
+    pub async fn list_sessions(&self, user_id: Uuid, current: Option<Uuid>) -> Result<Vec<UserSession>, AppError> {
+        let rows = sqlx::query_as::<_, (String, Option<String>, Option<String>, String, String, String, String)>(
+            "SELECT id, ip_address, user_agent, device, created_at, last_used_at, expires_at FROM refresh_tokens \
+             WHERE user_id = ? AND revoked_at IS NULL AND expires_at > ? ORDER BY last_used_at DESC",
+        )
+        .bind(user_id.to_string())
+        .bind(db_now())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, ip_address, user_agent, device, created_at, last_used_at, expires_at)| {
+                let id = Uuid::parse_str(&id).ok()?;
+                Some(UserSession {
+                    id,
+                    ip_address,
+                    user_agent,
+                    device,
+                    current: current == Some(id),
+                    created_at: parse_db_timestamp(&created_at),
+                    last_used_at: parse_db_timestamp(&last_used_at),
+                    expires_at: parse_db_timestamp(&expires_at),
+                })
+            })
+            .collect())
+    }
+
+    /// Revoke one login session of a user
+    pub async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = ? WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
         )
-        .map_err(|e| AppError::Jwt(format!("Token refresh failed: {}", e)))?;
+        .bind(db_now())
+        .bind(session_id.to_string())
+        .bind(user_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Session {} not found", session_id)));
+        }
+        Ok(())
+    }
+
+    /// Revoke every login session of a user, returning how many were active
+    pub async fn revoke_all_sessions(&self, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query("UPDATE refresh_tokens SET revoked_at = ? WHERE user_id = ? AND revoked_at IS NULL")
+            .bind(db_now())
+            .bind(user_id.to_string())
+            .execute(self.db.pool())
+            .await?;
 
-        Ok((token, new_claims))
+        Ok(result.rows_affected())
     }
 
-    /// Logout a user (invalidate session)
-    pub async fn logout(&self, _user_id: i64) -> Result<(), AppError> {
-        // In a real implementation, this would invalidate the JWT token
-        // by adding it to a blacklist or revoking the session
-        // For now, we just log the action
-        info!("User logged out");
+    /// Log out of the session an access token was issued for
+    ///
+    /// The session's refresh token stops working at once; access tokens
+    /// already issued remain valid until they expire.
+    pub async fn logout(&self, claims: &Claims) -> Result<(), AppError> {
+        if let Some(session_id) = claims.session_id() {
+            match self.revoke_session(claims.user_id()?, session_id).await {
+                Ok(()) | Err(AppError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        info!("User logged out: {}", claims.username);
         Ok(())
     }
 }
 
+/// New random refresh token
+fn new_refresh_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// SHA-256 of a refresh token, the form it is stored in
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_password_hashing() {
-        let service = AuthService::new(&AppConfig::from_env().unwrap());
+    #[tokio::test]
+    async fn test_password_hashing() {
+        let pool = sqlx::SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        let service = AuthService::new(&AppConfig::from_env().unwrap(), Database::new(pool));
         let password = "test_password_123";
 
         let hash = service.hash_password(password).unwrap();
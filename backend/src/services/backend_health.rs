@@ -0,0 +1,419 @@
+//! Backend Health Service
+//!
+//! Watches the backend itself — database errors, the report job queue,
+//! lagging background jobs, and the rate of server errors — and raises
+//! alerts for it under [`BACKEND_NODE_ID`], so backend problems reach
+//! operators through the same alerts and incidents as router problems.
+//!
+//! Built-in rules on the `backend_*` metrics are installed on the first
+//! check and can be edited or deleted like any other alert rule; operators
+//! may add their own rules on these metrics, too.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::backend_health::{
+    BackendHealthResponse, BackendHealthSnapshot, BackendMetric, BackgroundJobStatus, BACKEND_NODE_ID,
+};
+use crate::models::monitoring::{
+    Alert, AlertCondition, AlertOperator, AlertRule, AlertSeverity, AlertStatus, ConditionCombinator, MetricLabel,
+    MetricType,
+};
+use crate::services::{AlertRuleCreate, MonitoringService, ReportService};
+
+/// Seconds between two checks of the backend's health
+pub const BACKEND_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Window the per-minute request metrics are counted over
+const REQUEST_WINDOW_SECS: i64 = 60;
+
+/// Rule and alert label marking the backend as their source
+const SOURCE_LABEL: &str = "source";
+
+/// Alert label holding the rule an alert was raised by
+const RULE_LABEL: &str = "rule_id";
+
+/// Prefix of the metrics only the backend reports
+const BACKEND_METRIC_PREFIX: &str = "backend_";
+
+/// Requests counted during one second
+#[derive(Debug, Clone, Copy, Default)]
+struct RequestCounts {
+    requests: u64,
+    server_errors: u64,
+    db_errors: u64,
+}
+
+/// Last run of a periodic background job
+#[derive(Debug, Clone, Copy)]
+struct JobHeartbeat {
+    period: Duration,
+    last_run_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct HealthState {
+    /// Request counts per second of the last minute, oldest first
+    requests: VecDeque<(i64, RequestCounts)>,
+    jobs: HashMap<&'static str, JobHeartbeat>,
+    /// Since when the condition of each backend rule has been met
+    pending: HashMap<Uuid, DateTime<Utc>>,
+    /// Jobs are not expected to have run before this time
+    tracking_since: DateTime<Utc>,
+    last_checked_at: Option<DateTime<Utc>>,
+    rules_installed: bool,
+}
+
+impl HealthState {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            requests: VecDeque::new(),
+            jobs: HashMap::new(),
+            pending: HashMap::new(),
+            tracking_since: now,
+            last_checked_at: None,
+            rules_installed: false,
+        }
+    }
+
+    fn prune_requests(&mut self, now: DateTime<Utc>) {
+        let oldest = now.timestamp() - REQUEST_WINDOW_SECS;
+        while self.requests.front().is_some_and(|(second, _)| *second <= oldest) {
+            self.requests.pop_front();
+        }
+    }
+
+    fn record_response(&mut self, now: DateTime<Utc>, server_error: bool, db_error: bool) {
+        self.prune_requests(now);
+        let second = now.timestamp();
+        if self.requests.back().is_none_or(|(last, _)| *last != second) {
+            self.requests.push_back((second, RequestCounts::default()));
+        }
+        if let Some((_, counts)) = self.requests.back_mut() {
+            counts.requests += 1;
+            counts.server_errors += u64::from(server_error);
+            counts.db_errors += u64::from(db_error);
+        }
+    }
+
+    fn request_totals(&mut self, now: DateTime<Utc>) -> RequestCounts {
+        self.prune_requests(now);
+        self.requests.iter().fold(RequestCounts::default(), |total, (_, counts)| RequestCounts {
+            requests: total.requests + counts.requests,
+            server_errors: total.server_errors + counts.server_errors,
+            db_errors: total.db_errors + counts.db_errors,
+        })
+    }
+
+    fn job_statuses(&self, now: DateTime<Utc>) -> Vec<BackgroundJobStatus> {
+        let mut jobs: Vec<BackgroundJobStatus> = self
+            .jobs
+            .iter()
+            .map(|(name, heartbeat)| {
+                let expected_after = heartbeat.last_run_at.max(self.tracking_since);
+                let overdue = (now - expected_after).num_seconds() - heartbeat.period.as_secs() as i64;
+                BackgroundJobStatus {
+                    name: name.to_string(),
+                    period_secs: heartbeat.period.as_secs(),
+                    last_run_at: heartbeat.last_run_at,
+                    lag_secs: overdue.max(0) as u64,
+                }
+            })
+            .collect();
+        jobs.sort_by(|a, b| a.name.cmp(&b.name));
+        jobs
+    }
+}
+
+/// Backend health service
+#[derive(Clone)]
+pub struct BackendHealthService {
+    monitoring: MonitoringService,
+    reports: ReportService,
+    state: Arc<Mutex<HealthState>>,
+}
+
+impl BackendHealthService {
+    /// Create a new backend health service
+    pub fn new(monitoring: MonitoringService, reports: ReportService) -> Self {
+        Self {
+            monitoring,
+            reports,
+            state: Arc::new(Mutex::new(HealthState::new(Utc::now()))),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, HealthState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Count a handled API request
+    ///
+    /// `db_error` marks requests that failed because of the database.
+    pub fn record_response(&self, server_error: bool, db_error: bool) {
+        self.state().record_response(Utc::now(), server_error, db_error);
+    }
+
+    /// Note that a periodic background job ran
+    pub fn record_job_run(&self, job: &'static str, period: Duration) {
+        self.state().jobs.insert(
+            job,
+            JobHeartbeat {
+                period,
+                last_run_at: Utc::now(),
+            },
+        );
+    }
+
+    /// Measure the backend's current health
+    pub async fn snapshot(&self) -> Result<BackendHealthSnapshot, AppError> {
+        let job_queue_depth = self.reports.count_due_schedules().await?;
+
+        let now = Utc::now();
+        let mut state = self.state();
+        let totals = state.request_totals(now);
+        let jobs = state.job_statuses(now);
+
+        Ok(BackendHealthSnapshot {
+            db_errors_per_minute: totals.db_errors,
+            job_queue_depth,
+            collector_lag_secs: jobs.iter().map(|job| job.lag_secs).max().unwrap_or(0),
+            http_5xx_percent: match totals.requests {
+                0 => 0.0,
+                requests => totals.server_errors as f64 * 100.0 / requests as f64,
+            },
+            requests_per_minute: totals.requests,
+            jobs,
+            checked_at: now,
+        })
+    }
+
+    /// Current health together with the unresolved backend alerts
+    pub async fn get_health(&self) -> Result<BackendHealthResponse, AppError> {
+        let snapshot = self.snapshot().await?;
+        let alerts = self.unresolved_alerts().await?;
+
+        Ok(BackendHealthResponse { snapshot, alerts })
+    }
+
+    /// Evaluate the backend rules, raising alerts for newly failing checks
+    /// and resolving those that recovered
+    ///
+    /// Returns the number of raised alerts.
+    pub async fn check(&self) -> Result<usize, AppError> {
+        let now = Utc::now();
+        let install_rules = {
+            let mut state = self.state();
+            // Jobs only run on the leader; after a break in the checks, give
+            // them one period to catch up before counting them as lagging
+            let max_gap = chrono::Duration::from_std(BACKEND_HEALTH_CHECK_INTERVAL * 2).unwrap_or_default();
+            if state.last_checked_at.is_none_or(|last| now - last > max_gap) {
+                state.tracking_since = now;
+            }
+            state.last_checked_at = Some(now);
+            !std::mem::replace(&mut state.rules_installed, true)
+        };
+        if install_rules {
+            for rule in builtin_rules() {
+                self.monitoring.create_alert_rule(rule).await?;
+            }
+        }
+
+        let snapshot = self.snapshot().await?;
+        let values = snapshot.values();
+        let rules: Vec<AlertRule> = self
+            .monitoring
+            .get_alert_rules()
+            .await?
+            .into_iter()
+            .filter(|rule| rule.metric_name.starts_with(BACKEND_METRIC_PREFIX) && rule.applies_to(BACKEND_NODE_ID, &[]))
+            .collect();
+        let alerts = self.unresolved_alerts().await?;
+
+        let mut raised = 0;
+        for rule in &rules {
+            let open = alerts.iter().find(|alert| alert_rule_id(alert) == Some(rule.id));
+            let triggered = rule.enabled && rule.is_triggered(BACKEND_NODE_ID, &values);
+
+            if !triggered {
+                self.state().pending.remove(&rule.id);
+                if let Some(alert) = open {
+                    self.monitoring.resolve_alert(&alert.id).await?;
+                }
+                continue;
+            }
+
+            let since = *self.state().pending.entry(rule.id).or_insert(now);
+            if open.is_some() || (now - since).num_seconds() < i64::from(rule.for_seconds) {
+                continue;
+            }
+
+            let value = values.get(&rule.metric_name).copied();
+            warn!("Backend check failed: {} ({:?})", rule.name, value);
+            self.monitoring.raise_alert(backend_alert(rule, value, now)).await;
+            raised += 1;
+        }
+
+        // Forget rules that were deleted meanwhile
+        self.state().pending.retain(|id, _| rules.iter().any(|rule| rule.id == *id));
+
+        Ok(raised)
+    }
+
+    async fn unresolved_alerts(&self) -> Result<Vec<Alert>, AppError> {
+        let mut alerts = self
+            .monitoring
+            .get_alerts(Some(&BACKEND_NODE_ID.to_string()), None, None)
+            .await?;
+        alerts.retain(|alert| alert.status != AlertStatus::Resolved);
+        Ok(alerts)
+    }
+}
+
+/// Rule an alert was raised by, from its `rule_id` label
+pub(crate) fn alert_rule_id(alert: &Alert) -> Option<Uuid> {
+    alert
+        .labels
+        .iter()
+        .find(|label| label.key == RULE_LABEL)
+        .and_then(|label| label.value.parse().ok())
+}
+
+fn backend_alert(rule: &AlertRule, value: Option<f64>, now: DateTime<Utc>) -> Alert {
+    Alert {
+        id: Uuid::new_v4(),
+        node_id: BACKEND_NODE_ID.to_string(),
+        severity: rule.severity,
+        title: rule.name.clone(),
+        description: rule.description.clone().unwrap_or_else(|| rule.name.clone()),
+        status: AlertStatus::Active,
+        metric_name: Some(rule.metric_name.clone()),
+        threshold_value: Some(rule.threshold_for(BACKEND_NODE_ID)),
+        actual_value: value,
+        triggered_at: now,
+        updated_at: now,
+        acknowledged_at: None,
+        acknowledged_by: None,
+        resolved_at: None,
+        trigger_count: 1,
+        labels: vec![
+            label(SOURCE_LABEL, "backend"),
+            label(RULE_LABEL, &rule.id.to_string()),
+        ],
+        data: None,
+    }
+}
+
+fn label(key: &str, value: &str) -> MetricLabel {
+    MetricLabel {
+        key: key.to_string(),
+        value: value.to_string(),
+    }
+}
+
+/// Rules installed for the backend on its first health check
+fn builtin_rules() -> Vec<AlertRuleCreate> {
+    let rule = |name: &str, description: &str, metric: BackendMetric, threshold: f64, severity: AlertSeverity| {
+        AlertRuleCreate {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            metric_name: metric.metric_name().to_string(),
+            metric_type: MetricType::Custom,
+            threshold,
+            operator: AlertOperator::GreaterThan,
+            severity,
+            for_seconds: 0,
+            labels: vec![
+                label(SOURCE_LABEL, "backend"),
+                label("node_id", &BACKEND_NODE_ID.to_string()),
+            ],
+            node_tag: None,
+            threshold_overrides: vec![],
+            conditions: vec![],
+            combine: ConditionCombinator::And,
+        }
+    };
+
+    let mut server_errors = rule(
+        "Backend server errors",
+        "More than 5% of API requests failed with a server error in the last minute",
+        BackendMetric::HttpErrors,
+        5.0,
+        AlertSeverity::Critical,
+    );
+    // A single failed request among a handful must not page anyone
+    server_errors.conditions.push(AlertCondition {
+        metric_name: BackendMetric::Requests.metric_name().to_string(),
+        metric_type: MetricType::Custom,
+        operator: AlertOperator::GreaterThanOrEqual,
+        threshold: 20.0,
+    });
+
+    vec![
+        rule(
+            "Backend database errors",
+            "API requests failed because of database errors in the last minute",
+            BackendMetric::DbErrors,
+            0.0,
+            AlertSeverity::Critical,
+        ),
+        rule(
+            "Backend job queue backlog",
+            "More than 10 scheduled reports are waiting to be generated",
+            BackendMetric::JobQueueDepth,
+            10.0,
+            AlertSeverity::Warning,
+        ),
+        rule(
+            "Backend jobs lagging",
+            "A background job is more than 5 minutes behind its schedule",
+            BackendMetric::CollectorLag,
+            300.0,
+            AlertSeverity::Warning,
+        ),
+        server_errors,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_window_drops_old_seconds() {
+        let start = Utc::now();
+        let mut state = HealthState::new(start);
+        state.record_response(start, false, false);
+        state.record_response(start, true, true);
+        state.record_response(start + chrono::Duration::seconds(30), true, false);
+
+        let totals = state.request_totals(start + chrono::Duration::seconds(45));
+        assert_eq!((totals.requests, totals.server_errors, totals.db_errors), (3, 2, 1));
+
+        let totals = state.request_totals(start + chrono::Duration::seconds(75));
+        assert_eq!((totals.requests, totals.server_errors, totals.db_errors), (1, 1, 0));
+    }
+
+    #[test]
+    fn test_job_lag_counts_from_tracking_start() {
+        let start = Utc::now();
+        let mut state = HealthState::new(start);
+        state.jobs.insert(
+            "report",
+            JobHeartbeat {
+                period: Duration::from_secs(300),
+                last_run_at: start - chrono::Duration::hours(1),
+            },
+        );
+
+        // A job that last ran before tracking started gets one period
+        assert_eq!(state.job_statuses(start + chrono::Duration::seconds(200))[0].lag_secs, 0);
+        assert_eq!(state.job_statuses(start + chrono::Duration::seconds(420))[0].lag_secs, 120);
+    }
+}
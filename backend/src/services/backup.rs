@@ -0,0 +1,378 @@
+//! Database Backup Service
+//!
+//! Takes online snapshots of the SQLite database with `VACUUM INTO`, so the
+//! server keeps serving requests while a backup is written, and restores
+//! them in place. A restore only accepts backups that pass SQLite's
+//! integrity check and were taken at the current schema version, and always
+//! snapshots the current database first so it can be undone.
+//!
+//! Backups are kept in the artifact store. SQLite can only write and attach
+//! local files, so with object storage a backup is written to a temporary
+//! file and uploaded, and downloaded to one again to be restored.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::Utc;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, Connection, SqliteConnection};
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::config::AppConfig;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::backup::{
+    DatabaseBackup, RestoreBackupResponse, BACKUP_FILE_EXTENSION, BACKUP_FILE_PREFIX,
+};
+use crate::models::storage::StorageCategory;
+use crate::services::object_store::{artifact_store, ObjectInfo, ObjectStore};
+use crate::services::StorageUsageService;
+
+/// Schema version of a database, as recorded by the migrations
+const SCHEMA_VERSION_QUERY: &str = "SELECT COALESCE(MAX(version), 1) FROM _sqlx_migrations WHERE success";
+
+/// Schema version recorded by releases before the embedded migrations
+const LEGACY_SCHEMA_VERSION_QUERY: &str = "SELECT COALESCE(MAX(version), 1) FROM _migrations";
+
+/// Size of the chunks backups are copied to and from the store in
+const COPY_CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Database backup service
+#[derive(Clone)]
+pub struct BackupService {
+    db: Database,
+    storage: StorageUsageService,
+    store: Arc<dyn ObjectStore>,
+    /// Serializes backups and restores
+    lock: Arc<Mutex<()>>,
+}
+
+impl BackupService {
+    /// Create a new backup service
+    pub fn new(config: &AppConfig, db: Database, storage: StorageUsageService) -> Self {
+        Self {
+            db,
+            storage,
+            store: artifact_store(config, &config.backup_dir, StorageCategory::Backups.label()),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Write a backup of the running database to a timestamped file
+    ///
+    /// Refused once the storage cap is used up.
+    pub async fn create_backup(&self) -> Result<DatabaseBackup, AppError> {
+        self.storage.ensure_capacity(StorageCategory::Backups).await?;
+        let _guard = self.lock.lock().await;
+        self.snapshot().await
+    }
+
+    /// List the stored backups
+    pub async fn list_backups(&self) -> Result<Vec<DatabaseBackup>, AppError> {
+        let mut backups: Vec<DatabaseBackup> = self
+            .store
+            .list()
+            .await?
+            .into_iter()
+            .filter(|object| is_backup_file_name(&object.key))
+            .map(backup_from_object)
+            .collect();
+        // Timestamped names sort chronologically
+        backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+
+        Ok(backups)
+    }
+
+    /// Replace the contents of the running database with a backup
+    ///
+    /// The backup must pass SQLite's integrity check and have the schema
+    /// version of the running database. The current contents are backed up
+    /// first, and all tables are replaced in a single transaction, so a
+    /// failed restore leaves the database unchanged.
+    pub async fn restore_backup(&self, file_name: &str) -> Result<RestoreBackupResponse, AppError> {
+        if !is_backup_file_name(file_name) {
+            return Err(AppError::Validation(format!("Invalid backup file name: {}", file_name)));
+        }
+
+        let _guard = self.lock.lock().await;
+        let restored_from = self.backup_info(file_name).await?;
+
+        let (path, downloaded) = match self.store.local_path(file_name) {
+            Some(path) => (path, false),
+            None => (self.download(file_name).await?, true),
+        };
+        let result = self.restore_from(&path).await;
+        if downloaded {
+            remove_temporary(&path).await;
+        }
+        let (safety_backup, tables) = result?;
+
+        info!(
+            "Database restored from {} ({} tables), previous contents saved to {}",
+            file_name, tables, safety_backup.file_name
+        );
+
+        Ok(RestoreBackupResponse {
+            restored_from,
+            safety_backup,
+            tables,
+        })
+    }
+
+    /// Replace the tables of the running database with those of a local
+    /// backup file, returning the safety backup and the number of tables
+    async fn restore_from(&self, path: &Path) -> Result<(DatabaseBackup, usize), AppError> {
+        let current_version: i64 = sqlx::query_scalar(SCHEMA_VERSION_QUERY).fetch_one(self.db.pool()).await?;
+        let backup_version = verify_backup(path).await?;
+        if backup_version != current_version {
+            return Err(AppError::Validation(format!(
+                "Backup has schema version {}, the database has version {}",
+                backup_version, current_version
+            )));
+        }
+
+        let safety_backup = self.snapshot().await?;
+
+        let mut conn = self.db.pool().acquire().await?;
+        sqlx::query("ATTACH DATABASE ? AS backup")
+            .bind(path.to_string_lossy().as_ref())
+            .execute(&mut *conn)
+            .await?;
+        let restored = replace_tables(&mut conn).await;
+        sqlx::query("DETACH DATABASE backup").execute(&mut *conn).await?;
+
+        Ok((safety_backup, restored?))
+    }
+
+    /// Write a backup without taking the lock
+    async fn snapshot(&self) -> Result<DatabaseBackup, AppError> {
+        // In-memory databases would write the backup to memory, too
+        let database_file: String = sqlx::query_scalar("SELECT file FROM pragma_database_list WHERE name = 'main'")
+            .fetch_one(self.db.pool())
+            .await?;
+        if database_file.is_empty() {
+            return Err(AppError::Unsupported(
+                "Backups require a file-backed SQLite database".to_string(),
+            ));
+        }
+
+        let file_name = format!(
+            "{}{}{}",
+            BACKUP_FILE_PREFIX,
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+            BACKUP_FILE_EXTENSION
+        );
+        match self.store.local_path(&file_name) {
+            Some(path) => {
+                if let Some(directory) = path.parent() {
+                    fs::create_dir_all(directory).await?;
+                }
+                vacuum_into(&self.db, &path).await?;
+            }
+            None => {
+                let path = temporary_path();
+                let uploaded = async {
+                    vacuum_into(&self.db, &path).await?;
+                    self.upload(&path, &file_name).await
+                }
+                .await;
+                remove_temporary(&path).await;
+                uploaded?;
+            }
+        }
+
+        info!("Database backed up to {} in {}", file_name, self.store.location());
+        self.backup_info(&file_name).await
+    }
+
+    async fn backup_info(&self, file_name: &str) -> Result<DatabaseBackup, AppError> {
+        self.store
+            .head(file_name)
+            .await?
+            .map(backup_from_object)
+            .ok_or_else(|| AppError::NotFound(format!("Backup {} not found", file_name)))
+    }
+
+    /// Copy a local backup file to the store
+    async fn upload(&self, path: &Path, file_name: &str) -> Result<(), AppError> {
+        let mut file = fs::File::open(path).await?;
+        let mut out = self.store.create(file_name).await?;
+        let mut buffer = vec![0; COPY_CHUNK_BYTES];
+        let copied = async {
+            loop {
+                let read = file.read(&mut buffer).await?;
+                if read == 0 {
+                    return Ok(());
+                }
+                out.write(&buffer[..read]).await?;
+            }
+        }
+        .await;
+
+        match copied {
+            Ok(()) => out.finish().await.map(|_| ()),
+            Err(e) => {
+                out.abort().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Copy a stored backup to a temporary local file
+    async fn download(&self, file_name: &str) -> Result<PathBuf, AppError> {
+        let path = temporary_path();
+        let copied = async {
+            let mut reader = self.store.open(file_name).await?;
+            let mut file = fs::File::create(&path).await?;
+            let mut buffer = vec![0; COPY_CHUNK_BYTES];
+            loop {
+                let read = reader.read(&mut buffer).await?;
+                if read == 0 {
+                    break;
+                }
+                file.write_all(&buffer[..read]).await?;
+            }
+            file.flush().await?;
+            Ok::<_, AppError>(())
+        }
+        .await;
+
+        match copied {
+            Ok(()) => Ok(path),
+            Err(e) => {
+                remove_temporary(&path).await;
+                Err(e)
+            }
+        }
+    }
+}
+
+fn backup_from_object(object: ObjectInfo) -> DatabaseBackup {
+    DatabaseBackup {
+        file_name: object.key,
+        size_bytes: object.size_bytes,
+        created_at: object.modified_at,
+    }
+}
+
+/// Write a snapshot of the database to a local file
+async fn vacuum_into(db: &Database, path: &Path) -> Result<(), AppError> {
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().as_ref())
+        .execute(db.pool())
+        .await?;
+    Ok(())
+}
+
+/// Local file a backup passes through on its way to or from object storage
+fn temporary_path() -> PathBuf {
+    std::env::temp_dir().join(format!("vyos-webui-backup-{}{}", uuid::Uuid::new_v4(), BACKUP_FILE_EXTENSION))
+}
+
+async fn remove_temporary(path: &Path) {
+    match fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            warn!("Failed to remove {}: {}", path.display(), e);
+        }
+        _ => {}
+    }
+}
+
+/// Whether a name could be a backup written by this service
+///
+/// Rejects anything that could address a file outside the backup directory.
+fn is_backup_file_name(name: &str) -> bool {
+    name.starts_with(BACKUP_FILE_PREFIX)
+        && name.ends_with(BACKUP_FILE_EXTENSION)
+        && !name.contains("..")
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+}
+
+/// Check a backup file and return its schema version
+async fn verify_backup(path: &Path) -> Result<i64, AppError> {
+    let invalid = |e: sqlx::Error| AppError::Validation(format!("Not a valid database backup: {}", e));
+
+    let mut conn: SqliteConnection = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .connect()
+        .await
+        .map_err(invalid)?;
+
+    let integrity: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut conn)
+        .await
+        .map_err(invalid)?;
+    if integrity != "ok" {
+        return Err(AppError::Validation(format!("Backup failed the integrity check: {}", integrity)));
+    }
+
+    // Backups taken by older releases only have the legacy bookkeeping
+    let migrated: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(&mut conn)
+    .await
+    .map_err(invalid)?;
+    let query = if migrated { SCHEMA_VERSION_QUERY } else { LEGACY_SCHEMA_VERSION_QUERY };
+    let version = sqlx::query_scalar(query).fetch_one(&mut conn).await.map_err(invalid)?;
+    let _ = conn.close().await;
+
+    Ok(version)
+}
+
+/// Copy the rows of every table from the attached backup, in one transaction
+///
+/// Returns the number of tables copied. The migration bookkeeping is left
+/// alone since both databases have the same schema version.
+async fn replace_tables(conn: &mut SqliteConnection) -> Result<usize, AppError> {
+    let mut tx = conn.begin().await?;
+    // Rows are replaced table by table, so references are only checked at commit
+    sqlx::query("PRAGMA defer_foreign_keys = ON").execute(&mut *tx).await?;
+
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM main.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT IN ('_migrations', '_sqlx_migrations') ORDER BY name",
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for table in &tables {
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?, 'main')")
+            .bind(table)
+            .fetch_all(&mut *tx)
+            .await?;
+        let columns = columns.iter().map(|column| quote_identifier(column)).collect::<Vec<_>>().join(", ");
+        let table = quote_identifier(table);
+
+        sqlx::query(&format!("DELETE FROM main.{}", table)).execute(&mut *tx).await?;
+        sqlx::query(&format!(
+            "INSERT INTO main.{table} ({columns}) SELECT {columns} FROM backup.{table}"
+        ))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(tables.len())
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_file_names() {
+        assert!(is_backup_file_name("vyos-webui-20261016T101500.123Z.sqlite"));
+        assert!(!is_backup_file_name("vyos-webui-../../database.db.sqlite"));
+        assert!(!is_backup_file_name("vyos-webui-a/b.sqlite"));
+        assert!(!is_backup_file_name("database.db"));
+        assert!(!is_backup_file_name("vyos-webui-20261016T101500.123Z.db"));
+    }
+}
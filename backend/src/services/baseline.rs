@@ -0,0 +1,637 @@
+//! Baseline Service
+//!
+//! The services every node is expected to run the same way: NTP time
+//! servers, remote syslog, and SNMP communities and v3 users. Settings are
+//! read from a node's configuration and each service given in a change
+//! replaces the node's settings of that service, on one node or pushed to
+//! many, one commit per node.
+
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+use serde_json::Value;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::baseline::{
+    BaselineSettings, NtpServer, NtpSettings, PushBaselineRequest, SnmpAccess, SnmpCommunity, SnmpSettings,
+    SnmpV3Group, SnmpV3Secret, SnmpV3User, SnmpV3View, SyslogFacility, SyslogProtocol, SyslogRemote, SyslogSettings,
+    SYSLOG_FACILITIES, SYSLOG_LEVELS,
+};
+use crate::models::node::{NodeChangeResult, NodeData};
+use crate::services::config_lock::ConfigLockService;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::config_tree::{entries, lookup, number, path, text, validate_name, values, ConfigTreeWriter};
+use crate::services::node_service::NodeService;
+use crate::services::team::TeamService;
+use crate::vyos_client::ConfigOperation;
+
+/// Configuration path of the NTP servers
+const NTP_SERVER_PATH: &[&str] = &["service", "ntp", "server"];
+
+/// Configuration path of the syslog remotes
+const SYSLOG_HOST_PATH: &[&str] = &["system", "syslog", "host"];
+
+/// Configuration path of the SNMP service
+const SNMP_PATH: &[&str] = &["service", "snmp"];
+
+/// Parts of the SNMP service a change replaces; others, such as the listen
+/// addresses, are kept
+const SNMP_PARTS: &[&str] = &["contact", "location", "community", "v3"];
+
+/// Shortest SNMPv3 password accepted
+const MIN_SNMP_PASSWORD_LEN: usize = 8;
+
+/// Baseline service
+#[derive(Clone)]
+pub struct BaselineService {
+    nodes: NodeService,
+    teams: TeamService,
+    writer: ConfigTreeWriter,
+}
+
+impl BaselineService {
+    /// Create a new baseline service
+    pub fn new(
+        nodes: NodeService,
+        teams: TeamService,
+        locks: ConfigLockService,
+        sessions: ConfigSessionService,
+    ) -> Self {
+        Self {
+            writer: ConfigTreeWriter::new(nodes.clone(), locks, sessions),
+            nodes,
+            teams,
+        }
+    }
+
+    /// NTP, syslog, and SNMP settings of a node
+    pub async fn settings(&self, node_id: Uuid) -> Result<NodeData<BaselineSettings>, AppError> {
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        Ok(config.map(|config| parse_baseline(&config)))
+    }
+
+    /// Replace the settings of the services given
+    pub async fn set_settings(
+        &self,
+        node_id: Uuid,
+        settings: BaselineSettings,
+        claims: &Claims,
+    ) -> Result<BaselineSettings, AppError> {
+        let settings = normalize_baseline(settings)?;
+        self.apply(node_id, &settings, claims).await?;
+        info!("{} set the baseline services of node {}", claims.username, node_id);
+        Ok(settings)
+    }
+
+    /// Replace the settings of the services given on many nodes, one commit
+    /// per node
+    ///
+    /// A node failing does not stop the others; each node's outcome is
+    /// returned.
+    pub async fn push(&self, request: PushBaselineRequest, claims: &Claims) -> Result<Vec<NodeChangeResult>, AppError> {
+        let settings = normalize_baseline(request.settings)?;
+        let scope = self.teams.access_scope(claims).await?;
+
+        let mut results = Vec::new();
+        for node in self.nodes.fleet_nodes(&scope, request.node_ids.as_deref()).await? {
+            let result = self.apply(node.id, &settings, claims).await;
+            if let Err(e) = &result {
+                warn!("Could not push the baseline services to node {}: {}", node.id, e);
+            }
+            results.push(NodeChangeResult {
+                node_id: node.id,
+                node_name: node.name,
+                success: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+        info!(
+            "{} pushed the baseline services to {} of {} nodes",
+            claims.username,
+            results.iter().filter(|result| result.success).count(),
+            results.len()
+        );
+        Ok(results)
+    }
+
+    async fn apply(&self, node_id: Uuid, settings: &BaselineSettings, claims: &Claims) -> Result<(), AppError> {
+        let current = self.writer.current(node_id).await?;
+        let operations = baseline_operations(&current, settings)?;
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.writer.apply(node_id, &operations, claims).await
+    }
+}
+
+fn parse_baseline(config: &Value) -> BaselineSettings {
+    let ntp = NtpSettings {
+        servers: entries(lookup(config, NTP_SERVER_PATH))
+            .into_iter()
+            .map(|(address, server)| NtpServer {
+                address,
+                pool: server.get("pool").is_some(),
+                prefer: server.get("prefer").is_some(),
+            })
+            .collect(),
+    };
+    let syslog = SyslogSettings {
+        remotes: entries(lookup(config, SYSLOG_HOST_PATH))
+            .into_iter()
+            .map(|(host, remote)| SyslogRemote {
+                host,
+                port: number(remote.get("port")),
+                protocol: text(remote.get("protocol")).and_then(|protocol| SyslogProtocol::from_name(&protocol)),
+                facilities: entries(remote.get("facility"))
+                    .into_iter()
+                    .filter_map(|(facility, settings)| {
+                        Some(SyslogFacility {
+                            facility,
+                            level: text(settings.get("level"))?,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+    BaselineSettings {
+        ntp: Some(ntp),
+        syslog: Some(syslog),
+        snmp: Some(parse_snmp(lookup(config, SNMP_PATH))),
+    }
+}
+
+fn parse_snmp(config: Option<&Value>) -> SnmpSettings {
+    let get = |key: &str| config.and_then(|config| config.get(key));
+    let v3 = get("v3");
+    let secret = |config: Option<&Value>| {
+        Some(SnmpV3Secret {
+            kind: text(config?.get("type"))?,
+            password: None,
+        })
+    };
+    SnmpSettings {
+        contact: text(get("contact")),
+        location: text(get("location")),
+        communities: entries(get("community"))
+            .into_iter()
+            .filter_map(|(name, community)| {
+                Some(SnmpCommunity {
+                    name,
+                    authorization: SnmpAccess::from_name(&text(community.get("authorization"))?)?,
+                    networks: values(community.get("network")),
+                })
+            })
+            .collect(),
+        v3_views: entries(v3.and_then(|v3| v3.get("view")))
+            .into_iter()
+            .map(|(name, view)| SnmpV3View {
+                name,
+                oids: entries(view.get("oid")).into_iter().map(|(oid, _)| oid).collect(),
+            })
+            .collect(),
+        v3_groups: entries(v3.and_then(|v3| v3.get("group")))
+            .into_iter()
+            .filter_map(|(name, group)| {
+                Some(SnmpV3Group {
+                    name,
+                    view: text(group.get("view"))?,
+                    mode: SnmpAccess::from_name(&text(group.get("mode"))?)?,
+                })
+            })
+            .collect(),
+        v3_users: entries(v3.and_then(|v3| v3.get("user")))
+            .into_iter()
+            .filter_map(|(name, user)| {
+                Some(SnmpV3User {
+                    name,
+                    group: text(user.get("group"))?,
+                    auth: secret(user.get("auth"))?,
+                    privacy: secret(user.get("privacy")),
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Operations replacing the services given, against a node's current
+/// configuration; parts neither configured nor given are left out
+///
+/// SNMPv3 users given without passwords keep the encrypted passwords the
+/// node has.
+fn baseline_operations(current: &Value, settings: &BaselineSettings) -> Result<Vec<ConfigOperation>, AppError> {
+    let mut replaced: Vec<(Vec<String>, Vec<Vec<String>>)> = Vec::new();
+    if let Some(ntp) = &settings.ntp {
+        replaced.push((path(NTP_SERVER_PATH, &[]), ntp_paths(ntp)));
+    }
+    if let Some(syslog) = &settings.syslog {
+        replaced.push((path(SYSLOG_HOST_PATH, &[]), syslog_paths(syslog)));
+    }
+    if let Some(snmp) = &settings.snmp {
+        let mut parts = snmp_paths(snmp, lookup(current, SNMP_PATH))?;
+        for part in SNMP_PARTS {
+            let paths = parts.iter().filter(|path| path[0] == *part).map(|path| path[1..].to_vec()).collect();
+            parts.retain(|path| path[0] != *part);
+            replaced.push((path(SNMP_PATH, &[part]), paths));
+        }
+    }
+
+    let mut operations = Vec::new();
+    for (root, paths) in replaced {
+        let words: Vec<&str> = root.iter().map(String::as_str).collect();
+        if lookup(current, &words).is_some() {
+            operations.push(ConfigOperation::delete(root.clone()));
+        }
+        for relative in paths {
+            operations.push(ConfigOperation::set(root.iter().cloned().chain(relative).collect()));
+        }
+    }
+    Ok(operations)
+}
+
+fn ntp_paths(settings: &NtpSettings) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    for server in &settings.servers {
+        let root = [server.address.as_str()];
+        if server.pool {
+            paths.push(path(&root, &["pool"]));
+        }
+        if server.prefer {
+            paths.push(path(&root, &["prefer"]));
+        }
+        if !server.pool && !server.prefer {
+            paths.push(path(&root, &[]));
+        }
+    }
+    paths
+}
+
+fn syslog_paths(settings: &SyslogSettings) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    for remote in &settings.remotes {
+        let root = [remote.host.as_str()];
+        for facility in &remote.facilities {
+            paths.push(path(&root, &["facility", &facility.facility, "level", &facility.level]));
+        }
+        if let Some(port) = remote.port {
+            paths.push(path(&root, &["port", &port.to_string()]));
+        }
+        if let Some(protocol) = remote.protocol {
+            paths.push(path(&root, &["protocol", protocol.as_str()]));
+        }
+    }
+    paths
+}
+
+/// Paths of the SNMP settings, relative to the service
+fn snmp_paths(settings: &SnmpSettings, current: Option<&Value>) -> Result<Vec<Vec<String>>, AppError> {
+    let mut paths = Vec::new();
+    if let Some(contact) = &settings.contact {
+        paths.push(path(&["contact", contact], &[]));
+    }
+    if let Some(location) = &settings.location {
+        paths.push(path(&["location", location], &[]));
+    }
+    for community in &settings.communities {
+        let root = ["community", community.name.as_str()];
+        paths.push(path(&root, &["authorization", community.authorization.as_str()]));
+        for network in &community.networks {
+            paths.push(path(&root, &["network", network]));
+        }
+    }
+    for view in &settings.v3_views {
+        for oid in &view.oids {
+            paths.push(path(&["v3", "view", &view.name, "oid", oid], &[]));
+        }
+    }
+    for group in &settings.v3_groups {
+        let root = ["v3", "group", group.name.as_str()];
+        paths.push(path(&root, &["view", &group.view]));
+        paths.push(path(&root, &["mode", group.mode.as_str()]));
+    }
+    for user in &settings.v3_users {
+        let root = ["v3", "user", user.name.as_str()];
+        let existing = current.and_then(|snmp| lookup(snmp, &root));
+        paths.push(path(&root, &["group", &user.group]));
+        for (part, secret) in [("auth", Some(&user.auth)), ("privacy", user.privacy.as_ref())] {
+            let Some(secret) = secret else { continue };
+            let password = match &secret.password {
+                Some(password) => ("plaintext-password", password.clone()),
+                None => {
+                    let encrypted = existing
+                        .and_then(|user| user.get(part))
+                        .and_then(|secret| text(secret.get("encrypted-password")))
+                        .ok_or_else(|| {
+                            AppError::Validation(format!("SNMPv3 user {} needs a {} password", user.name, part))
+                        })?;
+                    ("encrypted-password", encrypted)
+                }
+            };
+            paths.push(path(&root, &[part, "type", &secret.kind]));
+            paths.push(path(&root, &[part, password.0, &password.1]));
+        }
+    }
+    Ok(paths)
+}
+
+/// Check an address or host name of a server
+fn validate_host(what: &str, host: &str) -> Result<(), AppError> {
+    let is_name = host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if host.parse::<IpAddr>().is_ok() || is_name {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("{} '{}' is not an address or host name", what, host)))
+    }
+}
+
+/// Check a single-line text setting, dropping it when blank
+fn normalize_text(what: &str, value: Option<String>) -> Result<Option<String>, AppError> {
+    let value = value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+    if value.as_deref().is_some_and(|value| value.len() > 255 || value.chars().any(char::is_control)) {
+        return Err(AppError::Validation(format!("{} must be one line of at most 255 characters", what)));
+    }
+    Ok(value)
+}
+
+fn check_unique<'a>(what: &str, names: impl IntoIterator<Item = &'a str>) -> Result<(), AppError> {
+    let mut seen = BTreeSet::new();
+    for name in names {
+        if !seen.insert(name) {
+            return Err(AppError::Validation(format!("{} {} is listed twice", what, name)));
+        }
+    }
+    Ok(())
+}
+
+fn normalize_baseline(settings: BaselineSettings) -> Result<BaselineSettings, AppError> {
+    Ok(BaselineSettings {
+        ntp: settings.ntp.map(normalize_ntp).transpose()?,
+        syslog: settings.syslog.map(normalize_syslog).transpose()?,
+        snmp: settings.snmp.map(normalize_snmp).transpose()?,
+    })
+}
+
+fn normalize_ntp(settings: NtpSettings) -> Result<NtpSettings, AppError> {
+    let servers: Vec<NtpServer> = settings
+        .servers
+        .into_iter()
+        .map(|server| NtpServer {
+            address: server.address.trim().to_ascii_lowercase(),
+            ..server
+        })
+        .collect();
+    for server in &servers {
+        validate_host("NTP server", &server.address)?;
+    }
+    check_unique("NTP server", servers.iter().map(|server| server.address.as_str()))?;
+    Ok(NtpSettings { servers })
+}
+
+fn normalize_syslog(settings: SyslogSettings) -> Result<SyslogSettings, AppError> {
+    let mut remotes = Vec::with_capacity(settings.remotes.len());
+    for remote in settings.remotes {
+        let host = remote.host.trim().to_ascii_lowercase();
+        validate_host("Syslog host", &host)?;
+        if remote.port == Some(0) {
+            return Err(AppError::Validation(format!("Syslog host {} has port 0", host)));
+        }
+        if remote.facilities.is_empty() {
+            return Err(AppError::Validation(format!("Syslog host {} needs a facility", host)));
+        }
+        let mut facilities = Vec::with_capacity(remote.facilities.len());
+        for facility in remote.facilities {
+            let facility = SyslogFacility {
+                facility: facility.facility.trim().to_ascii_lowercase(),
+                level: facility.level.trim().to_ascii_lowercase(),
+            };
+            if !SYSLOG_FACILITIES.contains(&facility.facility.as_str()) {
+                return Err(AppError::Validation(format!("'{}' is not a syslog facility", facility.facility)));
+            }
+            if !SYSLOG_LEVELS.contains(&facility.level.as_str()) {
+                return Err(AppError::Validation(format!("'{}' is not a syslog level", facility.level)));
+            }
+            facilities.push(facility);
+        }
+        check_unique("Facility", facilities.iter().map(|facility| facility.facility.as_str()))?;
+        remotes.push(SyslogRemote { host, facilities, ..remote });
+    }
+    check_unique("Syslog host", remotes.iter().map(|remote| remote.host.as_str()))?;
+    Ok(SyslogSettings { remotes })
+}
+
+fn normalize_secret(user: &str, secret: SnmpV3Secret, kinds: &[&str]) -> Result<SnmpV3Secret, AppError> {
+    let kind = secret.kind.trim().to_ascii_lowercase();
+    if !kinds.contains(&kind.as_str()) {
+        return Err(AppError::Validation(format!(
+            "SNMPv3 user {} cannot use {}; use {}",
+            user,
+            kind,
+            kinds.join(" or ")
+        )));
+    }
+    if secret
+        .password
+        .as_ref()
+        .is_some_and(|password| password.len() < MIN_SNMP_PASSWORD_LEN || password.chars().any(char::is_control))
+    {
+        return Err(AppError::Validation(format!(
+            "SNMPv3 passwords are single lines of at least {} characters",
+            MIN_SNMP_PASSWORD_LEN
+        )));
+    }
+    Ok(SnmpV3Secret { kind, ..secret })
+}
+
+/// Validate SNMP settings: each group's view and each user's group must be
+/// given along with them
+fn normalize_snmp(settings: SnmpSettings) -> Result<SnmpSettings, AppError> {
+    for community in &settings.communities {
+        validate_name("Community", &community.name)?;
+        for network in &community.networks {
+            network
+                .parse::<IpNet>()
+                .map_err(|_| AppError::Validation(format!("'{}' is not a network", network)))?;
+        }
+    }
+    check_unique("Community", settings.communities.iter().map(|community| community.name.as_str()))?;
+
+    for view in &settings.v3_views {
+        validate_name("View", &view.name)?;
+        if view.oids.is_empty() {
+            return Err(AppError::Validation(format!("View {} needs an OID", view.name)));
+        }
+        for oid in &view.oids {
+            if !oid.split('.').all(|arc| !arc.is_empty() && arc.chars().all(|c| c.is_ascii_digit())) {
+                return Err(AppError::Validation(format!("'{}' is not an OID", oid)));
+            }
+        }
+    }
+    check_unique("View", settings.v3_views.iter().map(|view| view.name.as_str()))?;
+    for group in &settings.v3_groups {
+        validate_name("Group", &group.name)?;
+        if !settings.v3_views.iter().any(|view| view.name == group.view) {
+            return Err(AppError::Validation(format!(
+                "Group {} uses view {}, which is not given",
+                group.name, group.view
+            )));
+        }
+    }
+    check_unique("Group", settings.v3_groups.iter().map(|group| group.name.as_str()))?;
+
+    let mut users = Vec::with_capacity(settings.v3_users.len());
+    for user in settings.v3_users {
+        validate_name("SNMPv3 user", &user.name)?;
+        if !settings.v3_groups.iter().any(|group| group.name == user.group) {
+            return Err(AppError::Validation(format!(
+                "SNMPv3 user {} is in group {}, which is not given",
+                user.name, user.group
+            )));
+        }
+        users.push(SnmpV3User {
+            auth: normalize_secret(&user.name, user.auth, &["md5", "sha"])?,
+            privacy: user.privacy.map(|privacy| normalize_secret(&user.name, privacy, &["des", "aes"])).transpose()?,
+            ..user
+        });
+    }
+    check_unique("SNMPv3 user", users.iter().map(|user| user.name.as_str()))?;
+
+    Ok(SnmpSettings {
+        contact: normalize_text("Contact", settings.contact)?,
+        location: normalize_text("Location", settings.location)?,
+        v3_users: users,
+        ..settings
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn commands(operations: &[ConfigOperation]) -> Vec<String> {
+        operations.iter().map(ConfigOperation::command).collect()
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let config = json!({
+            "service": {
+                "ntp": { "server": { "0.pool.ntp.org": { "pool": {} }, "192.0.2.123": {} } },
+                "snmp": {
+                    "listen-address": { "192.0.2.1": {} },
+                    "community": { "monitor": { "authorization": "ro", "network": ["192.0.2.0/24"] } },
+                    "v3": {
+                        "view": { "all": { "oid": { "1": {} } } },
+                        "group": { "ops": { "view": "all", "mode": "ro" } },
+                        "user": { "nms": {
+                            "group": "ops",
+                            "auth": { "type": "sha", "encrypted-password": "0x1234" }
+                        } }
+                    }
+                }
+            },
+            "system": { "syslog": { "host": { "192.0.2.50": {
+                "facility": { "all": { "level": "warning" } },
+                "protocol": "tcp"
+            } } } }
+        });
+        let settings = parse_baseline(&config);
+        assert_eq!(settings.ntp.as_ref().unwrap().servers.len(), 2);
+        assert!(settings.ntp.as_ref().unwrap().servers[0].pool);
+        assert_eq!(settings.syslog.as_ref().unwrap().remotes[0].protocol, Some(SyslogProtocol::Tcp));
+        let snmp = settings.snmp.as_ref().unwrap();
+        assert_eq!(snmp.v3_users[0].auth.kind, "sha");
+        assert_eq!(normalize_baseline(settings.clone()).unwrap(), settings);
+
+        // The stored password is kept, and the listen address left alone
+        let operations = baseline_operations(&config, &BaselineSettings { snmp: settings.snmp, ..Default::default() });
+        let commands = commands(&operations.unwrap());
+        assert!(commands.contains(&"delete service snmp v3".to_string()));
+        assert!(commands.contains(&"set service snmp v3 user nms auth encrypted-password 0x1234".to_string()));
+        assert!(!commands.iter().any(|command| command.contains("listen-address")));
+        assert!(!commands.iter().any(|command| command.contains("ntp")));
+    }
+
+    #[test]
+    fn test_new_services_are_set() {
+        let settings = normalize_baseline(BaselineSettings {
+            ntp: Some(NtpSettings {
+                servers: vec![NtpServer { address: " Time.Example.NET".to_string(), pool: false, prefer: true }],
+            }),
+            syslog: Some(SyslogSettings {
+                remotes: vec![SyslogRemote {
+                    host: "192.0.2.50".to_string(),
+                    port: Some(1514),
+                    protocol: None,
+                    facilities: vec![SyslogFacility { facility: "auth".to_string(), level: "info".to_string() }],
+                }],
+            }),
+            snmp: None,
+        })
+        .unwrap();
+        assert_eq!(
+            commands(&baseline_operations(&json!({}), &settings).unwrap()),
+            [
+                "set service ntp server time.example.net prefer",
+                "set system syslog host 192.0.2.50 facility auth level info",
+                "set system syslog host 192.0.2.50 port 1514",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_settings_are_validated() {
+        let remote = |facility: &str, level: &str| SyslogRemote {
+            host: "logs.example.net".to_string(),
+            port: None,
+            protocol: None,
+            facilities: vec![SyslogFacility { facility: facility.to_string(), level: level.to_string() }],
+        };
+        assert!(normalize_syslog(SyslogSettings { remotes: vec![remote("auth", "info")] }).is_ok());
+        assert!(normalize_syslog(SyslogSettings { remotes: vec![remote("bogus", "info")] }).is_err());
+        assert!(normalize_syslog(SyslogSettings { remotes: vec![remote("auth", "loud")] }).is_err());
+        let server = |address: &str| NtpServer { address: address.to_string(), pool: false, prefer: false };
+        assert!(normalize_ntp(NtpSettings { servers: vec![server("bad host")] }).is_err());
+        assert!(normalize_ntp(NtpSettings { servers: vec![server("192.0.2.1"), server("192.0.2.1")] }).is_err());
+
+        let user = SnmpV3User {
+            name: "nms".to_string(),
+            group: "ops".to_string(),
+            auth: SnmpV3Secret { kind: "sha".to_string(), password: Some("secret-password".to_string()) },
+            privacy: None,
+        };
+        let snmp = SnmpSettings {
+            v3_views: vec![SnmpV3View { name: "all".to_string(), oids: vec!["1".to_string()] }],
+            v3_groups: vec![SnmpV3Group { name: "ops".to_string(), view: "all".to_string(), mode: SnmpAccess::Ro }],
+            v3_users: vec![user.clone()],
+            ..Default::default()
+        };
+        assert!(normalize_snmp(snmp.clone()).is_ok());
+        let without_group = SnmpSettings { v3_groups: Vec::new(), ..snmp.clone() };
+        assert!(normalize_snmp(without_group).is_err());
+        let weak = SnmpV3User {
+            auth: SnmpV3Secret { kind: "sha".to_string(), password: Some("short".to_string()) },
+            ..user.clone()
+        };
+        assert!(normalize_snmp(SnmpSettings { v3_users: vec![weak], ..snmp.clone() }).is_err());
+
+        // A new user needs a password
+        let no_password = SnmpV3User { auth: SnmpV3Secret { kind: "sha".to_string(), password: None }, ..user };
+        let settings = BaselineSettings {
+            snmp: Some(SnmpSettings { v3_users: vec![no_password], ..snmp }),
+            ..Default::default()
+        };
+        assert!(baseline_operations(&json!({}), &settings).is_err());
+    }
+}
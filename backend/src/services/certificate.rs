@@ -0,0 +1,430 @@
+//! Certificate Expiry Service
+//!
+//! Watches the TLS certificates of registered endpoints: the routers' API
+//! certificates, the web UI's own certificate, or any other HTTPS service.
+//! A scheduled job reads each certificate and raises an alert when it is
+//! about to expire, warning at `warning_days` and critical at
+//! `critical_days` before expiry. The alert is resolved once the certificate
+//! is renewed.
+//!
+//! Certificates are read without verifying them, so self-signed and already
+//! expired certificates are reported like any other.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use openssl::asn1::Asn1Time;
+use openssl::nid::Nid;
+use openssl::x509::{X509NameRef, X509};
+use reqwest::Url;
+use sqlx::sqlite::SqliteRow;
+use sqlx::Row;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::backend_health::BACKEND_NODE_ID;
+use crate::models::certificate::{
+    CertificateEndpoint, CertificateEndpointListResponse, CreateCertificateEndpointRequest, DEFAULT_CRITICAL_DAYS,
+    DEFAULT_WARNING_DAYS,
+};
+use crate::models::monitoring::{Alert, AlertSeverity, AlertStatus, MetricLabel};
+use crate::models::node::NodeKind;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::services::{MonitoringService, NodeService};
+
+/// How often the scheduled job checks every endpoint
+pub const CERTIFICATE_CHECK_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+
+/// Longest wait for an endpoint to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Label naming the endpoint an expiry alert was raised for
+const ENDPOINT_LABEL: &str = "certificate_endpoint";
+
+/// Fields of a certificate read from an endpoint
+#[derive(Debug, Clone, PartialEq)]
+struct PeerCertificate {
+    subject: Option<String>,
+    issuer: Option<String>,
+    not_after: DateTime<Utc>,
+}
+
+/// Certificate expiry service
+#[derive(Clone)]
+pub struct CertificateService {
+    db: Database,
+    nodes: NodeService,
+    monitoring: MonitoringService,
+}
+
+impl CertificateService {
+    /// Create a new certificate expiry service
+    pub fn new(db: Database, nodes: NodeService, monitoring: MonitoringService) -> Self {
+        Self { db, nodes, monitoring }
+    }
+
+    /// List monitored endpoints
+    pub async fn list(&self) -> Result<CertificateEndpointListResponse, AppError> {
+        let rows = sqlx::query(&format!("SELECT {} FROM certificate_endpoints ORDER BY name", ENDPOINT_COLUMNS))
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(CertificateEndpointListResponse {
+            endpoints: rows.iter().map(endpoint_from_row).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Get a monitored endpoint
+    pub async fn get(&self, endpoint_id: Uuid) -> Result<CertificateEndpoint, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM certificate_endpoints WHERE id = ?", ENDPOINT_COLUMNS))
+            .bind(endpoint_id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Certificate endpoint {} not found", endpoint_id)))?;
+
+        endpoint_from_row(&row)
+    }
+
+    /// Start monitoring an endpoint and check it right away
+    pub async fn create(
+        &self,
+        request: CreateCertificateEndpointRequest,
+        claims: &Claims,
+    ) -> Result<CertificateEndpoint, AppError> {
+        let (host, port) = match (request.url.as_deref(), request.node_id) {
+            (Some(url), None) => parse_endpoint(url)?,
+            (None, Some(node_id)) => {
+                let node = self
+                    .nodes
+                    .get_node(node_id)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+                if node.kind != NodeKind::Vyos || !node.use_https {
+                    return Err(AppError::Validation(format!(
+                        "Node {} is not reached over HTTPS",
+                        node.name
+                    )));
+                }
+                (node.host, node.port)
+            }
+            _ => {
+                return Err(AppError::Validation(
+                    "Give either the URL or the node of the endpoint".to_string(),
+                ))
+            }
+        };
+        let warning_days = request.warning_days.unwrap_or(DEFAULT_WARNING_DAYS);
+        let critical_days = request.critical_days.unwrap_or(DEFAULT_CRITICAL_DAYS.min(warning_days));
+        if critical_days > warning_days {
+            return Err(AppError::Validation(
+                "critical_days must not exceed warning_days".to_string(),
+            ));
+        }
+
+        let endpoint_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO certificate_endpoints (id, name, host, port, node_id, warning_days, critical_days, \
+                                                created_by, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(endpoint_id.to_string())
+        .bind(request.name.trim())
+        .bind(&host)
+        .bind(i64::from(port))
+        .bind(request.node_id.map(|id| id.to_string()))
+        .bind(i64::from(warning_days))
+        .bind(i64::from(critical_days))
+        .bind(&claims.username)
+        .bind(db_now())
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Monitoring the certificate of {}:{}", host, port);
+        self.check(endpoint_id).await
+    }
+
+    /// Stop monitoring an endpoint, resolving its expiry alert
+    pub async fn delete(&self, endpoint_id: Uuid) -> Result<(), AppError> {
+        let endpoint = self.get(endpoint_id).await?;
+        sqlx::query("DELETE FROM certificate_endpoints WHERE id = ?")
+            .bind(endpoint_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if let Some(alert) = self.open_alert(&endpoint).await? {
+            self.monitoring.resolve_alert(&alert.id).await?;
+        }
+        Ok(())
+    }
+
+    /// Check every endpoint
+    ///
+    /// Returns the number of raised alerts.
+    pub async fn check_all(&self) -> Result<usize, AppError> {
+        let mut raised = 0;
+        for endpoint in self.list().await?.endpoints {
+            let before = self.open_alert(&endpoint).await?.map(|alert| alert.id);
+            let endpoint = self.check(endpoint.id).await?;
+            let after = self.open_alert(&endpoint).await?.map(|alert| alert.id);
+            if after.is_some() && after != before {
+                raised += 1;
+            }
+        }
+        Ok(raised)
+    }
+
+    /// Read an endpoint's certificate and raise or resolve its expiry alert
+    ///
+    /// An endpoint that cannot be reached keeps its alert as it is.
+    pub async fn check(&self, endpoint_id: Uuid) -> Result<CertificateEndpoint, AppError> {
+        let endpoint = self.get(endpoint_id).await?;
+
+        match read_certificate(&endpoint.host, endpoint.port).await {
+            Ok(certificate) => {
+                sqlx::query(
+                    "UPDATE certificate_endpoints \
+                     SET subject = ?, issuer = ?, not_after = ?, last_checked_at = ?, last_error = NULL \
+                     WHERE id = ?",
+                )
+                .bind(&certificate.subject)
+                .bind(&certificate.issuer)
+                .bind(format_timestamp(&certificate.not_after))
+                .bind(db_now())
+                .bind(endpoint_id.to_string())
+                .execute(self.db.pool())
+                .await?;
+            }
+            Err(e) => {
+                warn!("Failed to read the certificate of {}:{}: {}", endpoint.host, endpoint.port, e);
+                sqlx::query("UPDATE certificate_endpoints SET last_checked_at = ?, last_error = ? WHERE id = ?")
+                    .bind(db_now())
+                    .bind(e.to_string())
+                    .bind(endpoint_id.to_string())
+                    .execute(self.db.pool())
+                    .await?;
+                return self.get(endpoint_id).await;
+            }
+        }
+
+        let endpoint = self.get(endpoint_id).await?;
+        let severity = endpoint
+            .days_remaining
+            .and_then(|days| expiry_severity(days, endpoint.warning_days, endpoint.critical_days));
+        match (self.open_alert(&endpoint).await?, severity) {
+            (Some(alert), Some(severity)) if alert.severity == severity => {}
+            (open, severity) => {
+                if let Some(alert) = open {
+                    self.monitoring.resolve_alert(&alert.id).await?;
+                }
+                if let Some(severity) = severity {
+                    self.monitoring.raise_alert(expiry_alert(&endpoint, severity)).await;
+                }
+            }
+        }
+
+        Ok(endpoint)
+    }
+
+    async fn open_alert(&self, endpoint: &CertificateEndpoint) -> Result<Option<Alert>, AppError> {
+        let endpoint_id = endpoint.id.to_string();
+        Ok(self
+            .monitoring
+            .get_alerts(Some(&alert_node_id(endpoint)), None, None)
+            .await?
+            .into_iter()
+            .find(|alert| {
+                alert.status != AlertStatus::Resolved
+                    && alert
+                        .labels
+                        .iter()
+                        .any(|label| label.key == ENDPOINT_LABEL && label.value == endpoint_id)
+            }))
+    }
+}
+
+const ENDPOINT_COLUMNS: &str = "id, name, host, port, node_id, warning_days, critical_days, subject, issuer, \
+                                not_after, last_checked_at, last_error, created_by, created_at";
+
+fn endpoint_from_row(row: &SqliteRow) -> Result<CertificateEndpoint, AppError> {
+    let parse_id = |id: String| Uuid::parse_str(&id).unwrap_or_else(|_| Uuid::nil());
+    let not_after = row
+        .try_get::<Option<String>, _>("not_after")?
+        .map(|s| parse_db_timestamp(&s));
+
+    Ok(CertificateEndpoint {
+        id: parse_id(row.try_get("id")?),
+        name: row.try_get("name")?,
+        host: row.try_get("host")?,
+        port: row.try_get::<i64, _>("port")? as u16,
+        node_id: row.try_get::<Option<String>, _>("node_id")?.map(parse_id),
+        warning_days: row.try_get::<i64, _>("warning_days")? as u32,
+        critical_days: row.try_get::<i64, _>("critical_days")? as u32,
+        subject: row.try_get("subject")?,
+        issuer: row.try_get("issuer")?,
+        days_remaining: not_after.map(|not_after| (not_after - Utc::now()).num_days()),
+        not_after,
+        last_checked_at: row
+            .try_get::<Option<String>, _>("last_checked_at")?
+            .map(|s| parse_db_timestamp(&s)),
+        last_error: row.try_get("last_error")?,
+        created_by: row.try_get("created_by")?,
+        created_at: parse_db_timestamp(&row.try_get::<String, _>("created_at")?),
+    })
+}
+
+/// Host and port of an endpoint given as a URL or as `host[:port]`
+///
+/// The port defaults to 443.
+fn parse_endpoint(url: &str) -> Result<(String, u16), AppError> {
+    let url = url.trim();
+    let parsed = if url.contains("://") {
+        Url::parse(url)
+    } else {
+        Url::parse(&format!("https://{}", url))
+    }
+    .map_err(|e| AppError::Validation(format!("Invalid endpoint URL {}: {}", url, e)))?;
+
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| AppError::Validation(format!("Endpoint URL {} has no host", url)))?;
+    let port = parsed.port().unwrap_or(443);
+    Ok((host.trim_start_matches('[').trim_end_matches(']').to_string(), port))
+}
+
+/// Alert severity for a certificate expiring in `days`, if it is due one
+fn expiry_severity(days: i64, warning_days: u32, critical_days: u32) -> Option<AlertSeverity> {
+    if days <= i64::from(critical_days) {
+        Some(AlertSeverity::Critical)
+    } else if days <= i64::from(warning_days) {
+        Some(AlertSeverity::Warning)
+    } else {
+        None
+    }
+}
+
+/// Node an endpoint's alerts are raised on; the backend's own for endpoints
+/// that are not a router
+fn alert_node_id(endpoint: &CertificateEndpoint) -> String {
+    endpoint.node_id.unwrap_or(BACKEND_NODE_ID).to_string()
+}
+
+fn expiry_alert(endpoint: &CertificateEndpoint, severity: AlertSeverity) -> Alert {
+    let now = Utc::now();
+    let days = endpoint.days_remaining.unwrap_or(0);
+    Alert {
+        id: Uuid::new_v4(),
+        node_id: alert_node_id(endpoint),
+        severity,
+        title: format!("Certificate of {} expiring", endpoint.name),
+        description: if days < 0 {
+            format!("The certificate of {}:{} has expired", endpoint.host, endpoint.port)
+        } else {
+            format!(
+                "The certificate of {}:{} expires in {} days",
+                endpoint.host, endpoint.port, days
+            )
+        },
+        status: AlertStatus::Active,
+        metric_name: Some("certificate_days_remaining".to_string()),
+        threshold_value: Some(f64::from(match severity {
+            AlertSeverity::Critical => endpoint.critical_days,
+            _ => endpoint.warning_days,
+        })),
+        actual_value: Some(days as f64),
+        triggered_at: now,
+        updated_at: now,
+        acknowledged_at: None,
+        acknowledged_by: None,
+        resolved_at: None,
+        trigger_count: 1,
+        labels: vec![MetricLabel {
+            key: ENDPOINT_LABEL.to_string(),
+            value: endpoint.id.to_string(),
+        }],
+        data: None,
+    }
+}
+
+/// Read the certificate an endpoint presents, without verifying it
+async fn read_certificate(host: &str, port: u16) -> Result<PeerCertificate, AppError> {
+    let handshake = async {
+        let stream = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| AppError::HttpClient(format!("Failed to connect: {}", e)))?;
+        let connector = native_tls::TlsConnector::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to set up TLS: {}", e)))?;
+        let mut stream = tokio_native_tls::TlsConnector::from(connector)
+            .connect(host, stream)
+            .await
+            .map_err(|e| AppError::HttpClient(format!("TLS handshake failed: {}", e)))?;
+
+        let certificate = stream
+            .get_ref()
+            .peer_certificate()
+            .map_err(|e| AppError::HttpClient(format!("Failed to read the certificate: {}", e)))?
+            .ok_or_else(|| AppError::HttpClient("The endpoint presented no certificate".to_string()))?
+            .to_der()
+            .map_err(|e| AppError::HttpClient(format!("Failed to read the certificate: {}", e)))?;
+        let _ = stream.shutdown().await;
+        Ok::<_, AppError>(certificate)
+    };
+    let der = tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake)
+        .await
+        .map_err(|_| AppError::HttpClient("TLS handshake timed out".to_string()))??;
+
+    parse_certificate(&der)
+}
+
+fn parse_certificate(der: &[u8]) -> Result<PeerCertificate, AppError> {
+    let invalid = |e: openssl::error::ErrorStack| AppError::HttpClient(format!("Invalid certificate: {}", e));
+    let certificate = X509::from_der(der).map_err(invalid)?;
+    let epoch = Asn1Time::from_unix(0).map_err(invalid)?;
+    let lifetime = epoch.diff(certificate.not_after()).map_err(invalid)?;
+    let not_after = DateTime::from_timestamp(i64::from(lifetime.days) * 86_400 + i64::from(lifetime.secs), 0)
+        .ok_or_else(|| AppError::HttpClient("Invalid certificate: expiry out of range".to_string()))?;
+
+    Ok(PeerCertificate {
+        subject: name_of(certificate.subject_name()),
+        issuer: name_of(certificate.issuer_name()),
+        not_after,
+    })
+}
+
+/// Common name of a certificate name, or its organization
+fn name_of(name: &X509NameRef) -> Option<String> {
+    [Nid::COMMONNAME, Nid::ORGANIZATIONNAME].into_iter().find_map(|nid| {
+        name.entries_by_nid(nid)
+            .next()
+            .and_then(|entry| entry.data().as_utf8().ok())
+            .map(|value| value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_endpoint() {
+        assert_eq!(parse_endpoint("https://vyos.example.com/").unwrap(), ("vyos.example.com".to_string(), 443));
+        assert_eq!(parse_endpoint("mail.example.com:465").unwrap(), ("mail.example.com".to_string(), 465));
+        assert_eq!(parse_endpoint("https://[2001:db8::1]:8443").unwrap(), ("2001:db8::1".to_string(), 8443));
+        assert!(parse_endpoint("https://").is_err());
+    }
+
+    #[test]
+    fn test_expiry_severity() {
+        assert_eq!(expiry_severity(45, 30, 7), None);
+        assert_eq!(expiry_severity(30, 30, 7), Some(AlertSeverity::Warning));
+        assert_eq!(expiry_severity(7, 30, 7), Some(AlertSeverity::Critical));
+        assert_eq!(expiry_severity(-3, 30, 7), Some(AlertSeverity::Critical));
+    }
+}
@@ -0,0 +1,52 @@
+//! Channel Access Service
+//!
+//! Decides which WebSocket channels a user may subscribe to. Channels of a
+//! node such as `config:{node_id}` follow the node's team ownership, the
+//! same way the node endpoints do, and `admin:` channels are reserved for
+//! administrators.
+
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::event::ChannelScope;
+use crate::models::team::AccessScope;
+use crate::services::{NodeService, TeamService};
+
+/// Channel access service
+#[derive(Clone)]
+pub struct ChannelAccessService {
+    nodes: NodeService,
+    teams: TeamService,
+}
+
+impl ChannelAccessService {
+    /// Create a new channel access service
+    pub fn new(nodes: NodeService, teams: TeamService) -> Self {
+        Self { nodes, teams }
+    }
+
+    /// Ensure the user may subscribe to a channel
+    pub async fn ensure_can_subscribe(&self, claims: &Claims, channel: &str) -> Result<(), AppError> {
+        match ChannelScope::of(channel)? {
+            ChannelScope::Public => Ok(()),
+            ChannelScope::Admin => match self.teams.access_scope(claims).await? {
+                AccessScope::All => Ok(()),
+                AccessScope::Teams(_) => Err(AppError::Forbidden(format!(
+                    "Channel {} is reserved for administrators",
+                    channel
+                ))),
+            },
+            ChannelScope::Node(node_id) => {
+                let node = self
+                    .nodes
+                    .get_node(node_id)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+                if !self.teams.access_scope(claims).await?.can_access(node.team_id) {
+                    return Err(AppError::Forbidden("Node is owned by another team".to_string()));
+                }
+                Ok(())
+            }
+        }
+    }
+}
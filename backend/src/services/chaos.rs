@@ -0,0 +1,330 @@
+//! Failure Injection
+//!
+//! Lets administrators make chosen nodes time out, answer with server
+//! errors, or answer slowly, and delay database queries, so that the
+//! frontend's error handling and the node circuit breaker can be exercised
+//! deterministically. Only available with `CHAOS_ENABLED=true`, which is
+//! refused in production.
+//!
+//! Injected failures are process-wide, like the failpoints of test
+//! harnesses: node faults apply to every [`NodeTransport`] created for the
+//! node, and the database delay to every pool set up with
+//! [`ChaosService::delay_queries`].
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use sqlx::sqlite::SqlitePoolOptions;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::chaos::{ChaosStatus, NodeFault, NodeFaultEntry, NodeFaultKind, MAX_INJECTED_DELAY_MS};
+use crate::vyos_client::{
+    Capability, ConfigOperation, NodeTransport, VyOSConnectionTest, VyOSInfo, VyOSInterface,
+    VyOSShowResult,
+};
+
+/// Failures currently injected
+#[derive(Debug, Default)]
+struct Faults {
+    nodes: HashMap<Uuid, NodeFault>,
+    database_delay_ms: u64,
+}
+
+static FAULTS: OnceLock<Mutex<Faults>> = OnceLock::new();
+
+fn faults() -> MutexGuard<'static, Faults> {
+    // The faults stay consistent even if a holder panicked
+    FAULTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Failure injection service
+#[derive(Clone)]
+pub struct ChaosService {
+    enabled: bool,
+}
+
+impl ChaosService {
+    /// Create a new failure injection service
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            enabled: config.chaos_enabled,
+        }
+    }
+
+    /// Failures currently injected
+    pub fn status(&self) -> ChaosStatus {
+        let faults = faults();
+        let mut node_faults: Vec<NodeFaultEntry> = faults
+            .nodes
+            .iter()
+            .map(|(node_id, fault)| NodeFaultEntry {
+                node_id: *node_id,
+                fault: fault.clone(),
+            })
+            .collect();
+        node_faults.sort_by_key(|entry| entry.node_id);
+
+        ChaosStatus {
+            enabled: self.enabled,
+            node_faults,
+            database_delay_ms: faults.database_delay_ms,
+        }
+    }
+
+    /// Inject a failure into requests to a node, replacing any previous one
+    pub fn set_node_fault(&self, node_id: Uuid, fault: NodeFault) -> Result<NodeFaultEntry, AppError> {
+        self.ensure_enabled()?;
+        if fault.kind == NodeFaultKind::ServerError && !(500..=599).contains(&fault.status) {
+            return Err(AppError::Validation("Status must be between 500 and 599".to_string()));
+        }
+        check_delay(fault.delay_ms)?;
+        if fault.remaining == Some(0) {
+            return Err(AppError::Validation("Remaining must be at least 1".to_string()));
+        }
+
+        warn!("Injecting {:?} fault into requests to node {}", fault.kind, node_id);
+        faults().nodes.insert(node_id, fault.clone());
+        Ok(NodeFaultEntry { node_id, fault })
+    }
+
+    /// Stop injecting failures into requests to a node
+    pub fn clear_node_fault(&self, node_id: Uuid) -> Result<(), AppError> {
+        self.ensure_enabled()?;
+        match faults().nodes.remove(&node_id) {
+            Some(_) => {
+                info!("Removed injected fault of node {}", node_id);
+                Ok(())
+            }
+            None => Err(AppError::NotFound(format!("No fault is injected into node {}", node_id))),
+        }
+    }
+
+    /// Delay every database query, or stop delaying with 0
+    pub fn set_database_delay(&self, delay_ms: u64) -> Result<ChaosStatus, AppError> {
+        self.ensure_enabled()?;
+        check_delay(delay_ms)?;
+
+        warn!("Delaying database queries by {}ms", delay_ms);
+        faults().database_delay_ms = delay_ms;
+        Ok(self.status())
+    }
+
+    /// Remove all injected failures
+    pub fn reset(&self) -> Result<(), AppError> {
+        self.ensure_enabled()?;
+        *faults() = Faults::default();
+        info!("Removed all injected faults");
+        Ok(())
+    }
+
+    /// Apply the injected database delay to a pool's connections
+    ///
+    /// Each query or transaction waits for the delay when it takes a
+    /// connection from the pool.
+    pub fn delay_queries(options: SqlitePoolOptions) -> SqlitePoolOptions {
+        options
+            .after_connect(|_, _| Box::pin(async { database_delay().await; Ok(()) }))
+            .before_acquire(|_, _| Box::pin(async { database_delay().await; Ok(true) }))
+    }
+
+    fn ensure_enabled(&self) -> Result<(), AppError> {
+        if self.enabled {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(
+                "Failure injection is disabled; set CHAOS_ENABLED=true outside production".to_string(),
+            ))
+        }
+    }
+}
+
+fn check_delay(delay_ms: u64) -> Result<(), AppError> {
+    if delay_ms > MAX_INJECTED_DELAY_MS {
+        return Err(AppError::Validation(format!(
+            "Delay must be at most {}ms",
+            MAX_INJECTED_DELAY_MS
+        )));
+    }
+    Ok(())
+}
+
+async fn database_delay() {
+    let delay_ms = faults().database_delay_ms;
+    if delay_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+    }
+}
+
+/// Fault of a node for one request, counting it against `remaining`
+fn take_node_fault(node_id: Uuid) -> Option<NodeFault> {
+    let mut faults = faults();
+    let fault = faults.nodes.get_mut(&node_id)?;
+    let taken = fault.clone();
+    if let Some(remaining) = fault.remaining.as_mut() {
+        *remaining -= 1;
+        if *remaining == 0 {
+            faults.nodes.remove(&node_id);
+        }
+    }
+    Some(taken)
+}
+
+/// Transport failing requests to a node as injected
+pub struct FaultInjector {
+    node_id: Uuid,
+    inner: Box<dyn NodeTransport>,
+}
+
+impl FaultInjector {
+    /// Wrap a node's transport if a fault is injected into the node
+    pub fn wrap(node_id: Uuid, inner: Box<dyn NodeTransport>) -> Box<dyn NodeTransport> {
+        if faults().nodes.contains_key(&node_id) {
+            Box::new(Self { node_id, inner })
+        } else {
+            inner
+        }
+    }
+
+    async fn inject(&self) -> Result<(), AppError> {
+        let Some(fault) = take_node_fault(self.node_id) else {
+            return Ok(());
+        };
+        if fault.delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(fault.delay_ms)).await;
+        }
+
+        match fault.kind {
+            NodeFaultKind::Timeout => Err(AppError::HttpClient(format!(
+                "Request to node {} timed out (injected fault)",
+                self.node_id
+            ))),
+            NodeFaultKind::ServerError => {
+                let status = reqwest::StatusCode::from_u16(fault.status)
+                    .unwrap_or(reqwest::StatusCode::SERVICE_UNAVAILABLE);
+                Err(AppError::ExternalApi(format!("VyOS API error: {} - injected fault", status)))
+            }
+            NodeFaultKind::Delay => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl NodeTransport for FaultInjector {
+    async fn get_info(&self) -> Result<VyOSInfo, AppError> {
+        self.inject().await?;
+        self.inner.get_info().await
+    }
+
+    async fn retrieve_config(&self, path: Option<String>) -> Result<serde_json::Value, AppError> {
+        self.inject().await?;
+        self.inner.retrieve_config(path).await
+    }
+
+    async fn show(&self, command: &str) -> Result<VyOSShowResult, AppError> {
+        self.inject().await?;
+        self.inner.show(command).await
+    }
+
+    async fn run_op_command(&self, command: &str, output: &mut (dyn FnMut(String) + Send)) -> Result<(), AppError> {
+        self.inject().await?;
+        self.inner.run_op_command(command, output).await
+    }
+
+    async fn get_interfaces(&self) -> Result<Vec<VyOSInterface>, AppError> {
+        self.inject().await?;
+        self.inner.get_interfaces().await
+    }
+
+    async fn supports(&self, capability: Capability) -> Result<bool, AppError> {
+        self.inject().await?;
+        self.inner.supports(capability).await
+    }
+
+    async fn test_connection(&self) -> Result<VyOSConnectionTest, AppError> {
+        let start = Instant::now();
+        if let Err(e) = self.inject().await {
+            return Ok(VyOSConnectionTest {
+                success: false,
+                latency_ms: Some(start.elapsed().as_millis() as u64),
+                version: None,
+                hostname: None,
+                uptime: None,
+                error: Some(e.to_string()),
+            });
+        }
+        self.inner.test_connection().await
+    }
+
+    async fn configure(&self, operations: &[ConfigOperation]) -> Result<(), AppError> {
+        self.inject().await?;
+        self.inner.configure(operations).await
+    }
+
+    async fn reboot(&self) -> Result<(), AppError> {
+        self.inject().await?;
+        self.inner.reboot().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> ChaosService {
+        let mut config = AppConfig::from_env().unwrap();
+        config.chaos_enabled = true;
+        ChaosService::new(&config)
+    }
+
+    #[test]
+    fn test_node_fault_clears_after_remaining_requests() {
+        let chaos = service();
+        let node_id = Uuid::new_v4();
+        let fault = NodeFault {
+            kind: NodeFaultKind::Timeout,
+            status: 503,
+            delay_ms: 0,
+            remaining: Some(2),
+        };
+        chaos.set_node_fault(node_id, fault).unwrap();
+
+        assert!(take_node_fault(node_id).is_some());
+        assert!(take_node_fault(node_id).is_some());
+        assert!(take_node_fault(node_id).is_none());
+        assert!(chaos.clear_node_fault(node_id).is_err());
+    }
+
+    #[test]
+    fn test_disabled_service_injects_nothing() {
+        let mut config = AppConfig::from_env().unwrap();
+        config.chaos_enabled = false;
+        let chaos = ChaosService::new(&config);
+        assert!(matches!(chaos.set_database_delay(10), Err(AppError::NotFound(_))));
+        assert!(!chaos.status().enabled);
+    }
+
+    #[tokio::test]
+    async fn test_database_delay_applies_to_queries() {
+        let pool = ChaosService::delay_queries(SqlitePoolOptions::new().max_connections(1))
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let chaos = service();
+
+        chaos.set_database_delay(50).unwrap();
+        let start = Instant::now();
+        sqlx::query("SELECT 1").execute(&pool).await.unwrap();
+        let delayed = start.elapsed();
+        chaos.set_database_delay(0).unwrap();
+
+        assert!(delayed >= Duration::from_millis(50));
+    }
+}
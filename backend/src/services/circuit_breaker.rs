@@ -0,0 +1,141 @@
+//! Node Circuit Breaker
+//!
+//! Tracks transport failures per node. After a number of consecutive
+//! failures a node's circuit opens: requests to the node fail fast instead of
+//! waiting for timeouts, and callers serve the node's last-known data. Once
+//! the retry delay has passed, the next request tries the node again and a
+//! successful answer closes the circuit.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+/// Failure state of one node
+#[derive(Debug, Default)]
+struct Circuit {
+    /// Consecutive transport failures
+    failures: u32,
+    /// While set and in the future, the node is not contacted
+    open_until: Option<DateTime<Utc>>,
+}
+
+/// Per-node circuit breaker for requests to VyOS nodes
+#[derive(Clone)]
+pub struct NodeCircuitBreaker {
+    failure_threshold: u32,
+    retry_after: Duration,
+    circuits: Arc<Mutex<HashMap<Uuid, Circuit>>>,
+}
+
+impl NodeCircuitBreaker {
+    /// Create a new circuit breaker with all circuits closed
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            failure_threshold: config.node_failure_threshold,
+            retry_after: Duration::seconds(config.node_retry_after_secs as i64),
+            circuits: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Whether an error means the node could not be reached
+    ///
+    /// Errors reported by a node that answered, e.g. a rejected API key, do
+    /// not count against its circuit.
+    pub fn is_transport_error(error: &AppError) -> bool {
+        matches!(error, AppError::HttpClient(_))
+    }
+
+    /// When the node is tried again, if its circuit is open
+    pub fn open_until(&self, node_id: Uuid) -> Option<DateTime<Utc>> {
+        self.circuits()
+            .get(&node_id)
+            .and_then(|circuit| circuit.open_until)
+            .filter(|until| *until > Utc::now())
+    }
+
+    /// Record that the node answered, closing its circuit
+    pub fn record_success(&self, node_id: Uuid) {
+        if let Some(circuit) = self.circuits().remove(&node_id) {
+            if circuit.open_until.is_some() {
+                info!("Node {} is reachable again, circuit closed", node_id);
+            }
+        }
+    }
+
+    /// Record that the node could not be reached
+    ///
+    /// Opens the circuit once the failure threshold is reached; a failed
+    /// retry after the delay opens it again right away.
+    pub fn record_failure(&self, node_id: Uuid) {
+        let mut circuits = self.circuits();
+        let circuit = circuits.entry(node_id).or_default();
+        circuit.failures = circuit.failures.saturating_add(1);
+        if circuit.failures >= self.failure_threshold {
+            let until = Utc::now() + self.retry_after;
+            circuit.open_until = Some(until);
+            warn!(
+                "Node {} failed {} times in a row, circuit open until {}",
+                node_id, circuit.failures, until
+            );
+        }
+    }
+
+    fn circuits(&self) -> MutexGuard<'_, HashMap<Uuid, Circuit>> {
+        // The map stays consistent even if a holder panicked
+        self.circuits.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(failure_threshold: u32, retry_after_secs: u64) -> NodeCircuitBreaker {
+        let mut config = AppConfig::from_env().unwrap();
+        config.node_failure_threshold = failure_threshold;
+        config.node_retry_after_secs = retry_after_secs;
+        NodeCircuitBreaker::new(&config)
+    }
+
+    #[test]
+    fn test_circuit_opens_after_consecutive_failures() {
+        let breaker = breaker(2, 30);
+        let node_id = Uuid::new_v4();
+
+        breaker.record_failure(node_id);
+        assert!(breaker.open_until(node_id).is_none());
+
+        // A success in between resets the count
+        breaker.record_success(node_id);
+        breaker.record_failure(node_id);
+        assert!(breaker.open_until(node_id).is_none());
+
+        breaker.record_failure(node_id);
+        assert!(breaker.open_until(node_id).is_some());
+        assert!(breaker.open_until(Uuid::new_v4()).is_none());
+
+        breaker.record_success(node_id);
+        assert!(breaker.open_until(node_id).is_none());
+    }
+
+    #[test]
+    fn test_circuit_allows_a_retry_after_the_delay() {
+        let breaker = breaker(1, 0);
+        let node_id = Uuid::new_v4();
+
+        breaker.record_failure(node_id);
+        assert!(breaker.open_until(node_id).is_none());
+    }
+
+    #[test]
+    fn test_only_transport_errors_count() {
+        assert!(NodeCircuitBreaker::is_transport_error(&AppError::HttpClient("connection refused".to_string())));
+        assert!(!NodeCircuitBreaker::is_transport_error(&AppError::ExternalApi("401".to_string())));
+    }
+}
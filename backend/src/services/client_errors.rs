@@ -0,0 +1,136 @@
+//! Client Error Service
+//!
+//! Ingests errors reported by the frontend and writes them to the backend log
+//! under the `client_errors` target, so UI failures show up next to the
+//! server-side events around them.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::client_error::ClientErrorReport;
+
+/// Length of a rate limiting window
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Number of tracked clients above which expired windows are dropped
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Reports counted for a client in the current window
+#[derive(Debug, Clone, Copy)]
+struct RateWindow {
+    started: Instant,
+    count: u32,
+}
+
+/// Client error service for frontend error reports
+#[derive(Clone)]
+pub struct ClientErrorService {
+    rate_limit: u32,
+    windows: Arc<Mutex<HashMap<String, RateWindow>>>,
+}
+
+impl ClientErrorService {
+    /// Create a new client error service
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            rate_limit: config.client_error_rate_limit,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Log a frontend error report
+    ///
+    /// `client` identifies the reporter for rate limiting: the user ID of
+    /// authenticated callers, otherwise their IP address.
+    pub fn ingest(
+        &self,
+        mut report: ClientErrorReport,
+        claims: Option<&Claims>,
+        client: &str,
+        user_agent: Option<&str>,
+    ) -> Result<Uuid, AppError> {
+        self.check_rate(client, Instant::now())?;
+
+        report.truncate();
+        let id = Uuid::new_v4();
+
+        warn!(
+            target: "client_errors",
+            report_id = %id,
+            user_id = claims.map(|c| c.sub.as_str()),
+            username = claims.map(|c| c.username.as_str()),
+            session_id = report.session_id.as_deref(),
+            client = client,
+            user_agent = user_agent,
+            url = report.url.as_deref(),
+            source = report.source.as_deref(),
+            line = report.line,
+            column = report.column,
+            release = report.release.as_deref(),
+            occurred_at = report.occurred_at.map(|t| t.to_rfc3339()),
+            name = report.name.as_deref(),
+            stack = report.stack.as_deref(),
+            component_stack = report.component_stack.as_deref(),
+            "Frontend error: {}",
+            report.message
+        );
+
+        Ok(id)
+    }
+
+    /// Count a report against the client's window
+    fn check_rate(&self, client: &str, now: Instant) -> Result<(), AppError> {
+        let mut windows = self
+            .windows
+            .lock()
+            .map_err(|_| AppError::Internal("Rate limiter lock poisoned".to_string()))?;
+
+        if windows.len() > MAX_TRACKED_CLIENTS {
+            windows.retain(|_, w| now.duration_since(w.started) < RATE_WINDOW);
+        }
+
+        let window = windows.entry(client.to_string()).or_insert(RateWindow { started: now, count: 0 });
+        if now.duration_since(window.started) >= RATE_WINDOW {
+            *window = RateWindow { started: now, count: 0 };
+        }
+
+        if window.count >= self.rate_limit {
+            return Err(AppError::RateLimited(format!(
+                "at most {} error reports per minute are accepted",
+                self.rate_limit
+            )));
+        }
+
+        window.count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_per_client_and_window() {
+        let mut config = AppConfig::from_env().unwrap();
+        config.client_error_rate_limit = 2;
+        let service = ClientErrorService::new(&config);
+        let start = Instant::now();
+
+        assert!(service.check_rate("10.0.0.1", start).is_ok());
+        assert!(service.check_rate("10.0.0.1", start).is_ok());
+        assert!(matches!(
+            service.check_rate("10.0.0.1", start),
+            Err(AppError::RateLimited(_))
+        ));
+        assert!(service.check_rate("10.0.0.2", start).is_ok());
+        assert!(service.check_rate("10.0.0.1", start + RATE_WINDOW).is_ok());
+    }
+}
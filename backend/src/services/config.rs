@@ -1,56 +1,153 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
 use crate::config::AppConfig;
 use crate::db::Database;
 use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::config_backup::{
+    ConfigBackup, ConfigBackupArtifact, ConfigBackupKind, ConfigBackupListResponse, ConfigBackupRetention,
+    RestoreConfigBackupRequest,
+};
+use crate::models::desired_state::{DesiredStatePlan, DesiredStateRequest};
+use crate::models::permission::SECRETS_READ;
+use crate::models::storage::Compression;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::services::config_lint::ConfigLinter;
+use crate::services::config_lock::{path_segments, ConfigLockService};
+use crate::services::config_secrets::{ConfigSecretPolicy, REDACTED_SECRET};
+use crate::services::config_session::ConfigSessionService;
+use crate::services::desired_state::DesiredStateService;
+use crate::services::git_export::{GitAuthor, GitExportService};
+use crate::services::node_service::NodeService;
+use crate::services::permission::PermissionService;
+use crate::services::storage;
+use crate::vyos_client::ConfigOperation;
+
+/// History entries returned when no limit is given
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Most history entries returned at once
+const MAX_HISTORY_LIMIT: usize = 500;
+
+/// Most backups of a node returned by a list
+const BACKUP_LIST_LIMIT: i64 = 500;
 
 /// Configuration service for managing VyOS configuration
+///
+/// Every operation addresses one node: its configuration is read and
+/// changed through the [`NodeService`], and the history of each node's
+/// committed configuration is kept apart.
+///
+/// Secrets are sealed in stored snapshots and backups, redacted from
+/// snapshots exported to Git, and only served to users with the
+/// `secrets:read` permission (see [`ConfigSecretPolicy`]).
 #[derive(Clone)]
 pub struct ConfigService {
     db: Database,
     config: AppConfig,
+    git_export: GitExportService,
+    locks: ConfigLockService,
+    linter: ConfigLinter,
+    nodes: NodeService,
+    sessions: ConfigSessionService,
+    desired_state: DesiredStateService,
+    permissions: PermissionService,
+    secrets: ConfigSecretPolicy,
 }
 
 impl ConfigService {
     /// Create a new configuration service
-    pub fn new(db: Database, config: AppConfig) -> Self {
-        Self { db, config }
+    pub fn new(
+        db: Database,
+        config: AppConfig,
+        locks: ConfigLockService,
+        nodes: NodeService,
+        sessions: ConfigSessionService,
+        desired_state: DesiredStateService,
+        permissions: PermissionService,
+    ) -> Self {
+        let git_export = GitExportService::new(&config);
+        let linter = ConfigLinter::new(config.config_lint_disabled_rules.clone());
+        let secrets = ConfigSecretPolicy::new(&config);
+        Self { db, config, git_export, locks, linter, nodes, sessions, desired_state, permissions, secrets }
+    }
+
+    /// Whether the authenticated user may read configuration secrets
+    pub(crate) async fn reveals_secrets(&self, claims: &Claims) -> Result<bool, AppError> {
+        self.permissions.has_permission(claims, SECRETS_READ).await
+    }
+
+    /// Redact the secrets of configuration found under `path`, unless the
+    /// user may read them
+    pub async fn conceal_secrets(
+        &self,
+        claims: &Claims,
+        path: &[&str],
+        config: &mut serde_json::Value,
+    ) -> Result<(), AppError> {
+        if !self.reveals_secrets(claims).await? {
+            self.secrets.redact(path, config);
+        }
+        Ok(())
     }
 
-    /// Retrieve configuration from VyOS
+    /// Retrieve the configuration of a node
     ///
-    /// This method fetches the current running configuration from VyOS API
-    /// and returns it as a tree structure.
+    /// The running configuration, or the subtree under the requested path,
+    /// is returned as a tree. While the node cannot be reached, the last
+    /// configuration read from it is returned marked as stale. Secrets are
+    /// redacted unless the user may read them; the hash always covers them.
     pub async fn retrieve_config(
         &self,
         request: crate::models::config::ConfigRetrieveRequest,
+        claims: &Claims,
     ) -> Result<crate::models::config::ConfigRetrieveResponse, AppError> {
-        // TODO: Integrate with vyos_client module for actual VyOS API calls
-        // For now, return a mock configuration tree
-
-        let root_node = self.build_mock_config_tree(&request.path).await?;
+        let reveal_secrets = self.reveals_secrets(claims).await?;
+        self.read_config(request, reveal_secrets).await
+    }
 
-        let node_count = self.count_nodes(&root_node);
+    /// Retrieve the configuration of a node, with or without its secrets
+    async fn read_config(
+        &self,
+        request: crate::models::config::ConfigRetrieveRequest,
+        reveal_secrets: bool,
+    ) -> Result<crate::models::config::ConfigRetrieveResponse, AppError> {
+        let scope = path_segments(request.path.as_deref().unwrap_or_default());
+        let path = (!scope.is_empty()).then(|| scope.join(" "));
+        let mut config = self.nodes.retrieve_node_config(request.node_id, path).await?;
+
+        let mut root_node = config_tree(&scope, &config.data);
+        let (config_hash, stats) = summarize_tree(&root_node);
+        if !reveal_secrets {
+            self.secrets.redact(&scope, &mut config.data);
+            root_node = config_tree(&scope, &config.data);
+        }
 
         Ok(crate::models::config::ConfigRetrieveResponse {
+            node_id: request.node_id,
             config_tree: root_node,
-            retrieved_at: chrono::Utc::now(),
-            node_count,
+            retrieved_at: config.fetched_at,
+            stale: config.stale,
+            node_count: stats.total_nodes,
+            config_hash,
+            stats,
         })
     }
 
-    /// Set configuration value at a specific path
+    /// Set configuration value at a specific path of a node
     ///
     /// Validates and sets a configuration value. If value is None, deletes the path.
+    /// Paths under a configuration lock can only be set by its holders.
     pub async fn set_config(
         &self,
         request: crate::models::config::ConfigSetRequest,
+        claims: &Claims,
     ) -> Result<crate::models::config::ConfigSetResponse, AppError> {
-        // Validate the request
-        if request.validate {
-            self.validate_config_path(&request.path, &request.value).await?;
-        }
-
-        // TODO: Integrate with vyos_client module for actual VyOS API calls
-        // This would call the VyOS configure API with the path and value
+        self.apply_change(request.node_id, &request.path, request.value.clone(), request.validate, claims)
+            .await?;
 
         let changes_made = vec![format!("Set {} to {:?}", request.path, request.value)];
 
@@ -61,18 +158,16 @@ impl ConfigService {
         })
     }
 
-    /// Delete configuration at a specific path
+    /// Delete configuration at a specific path of a node
+    ///
+    /// Fails if the path or anything below it is locked to someone else.
     pub async fn delete_config(
         &self,
         request: crate::models::config::ConfigDeleteRequest,
+        claims: &Claims,
     ) -> Result<crate::models::config::ConfigSetResponse, AppError> {
-        // Validate the request
-        if request.validate {
-            self.validate_config_deletion(&request.path).await?;
-        }
-
-        // TODO: Integrate with vyos_client module for actual VyOS API calls
-        // This would call the VyOS delete API with the path
+        self.apply_change(request.node_id, &request.path, None, request.validate, claims)
+            .await?;
 
         let changes_made = vec![format!("Deleted {}", request.path)];
 
@@ -85,112 +180,181 @@ impl ConfigService {
 
     /// Generate/commit configuration changes
     ///
-    /// Commits the configuration changes to the running configuration.
-    /// Optionally saves to startup config.
+    /// Changes made through the configuration API are committed on the node
+    /// as they are applied, so this records the node's running configuration
+    /// in its history. Lint findings on the committed configuration are
+    /// added to the warnings.
     pub async fn generate_config(
         &self,
         request: crate::models::config::ConfigGenerateRequest,
-        _changed_by: String,
+        changed_by: String,
     ) -> Result<crate::models::config::ConfigGenerateResponse, AppError> {
         // Validate before commit if requested
-        let warnings = if request.validate {
-            self.validate_configuration().await?
+        let mut warnings = if request.validate {
+            self.validate_configuration(request.node_id).await?
         } else {
             Vec::new()
         };
 
-        // TODO: Integrate with vyos_client module for actual VyOS API calls
-        // This would call the VyOS generate API to commit the configuration
-        // If save is true, also call the save API
-
         // Create a snapshot of the current configuration
-        let config_snapshot = self.create_config_snapshot().await?;
+        let config_snapshot = self.create_config_snapshot(request.node_id).await?;
+        warnings.extend(self.linter.lint(&config_snapshot.config_tree));
 
         // Store in history
         self.store_config_history(
+            request.node_id,
             &config_snapshot,
             crate::models::config::ConfigChangeType::Generate,
-            _changed_by,
+            changed_by.clone(),
             &request.comment,
             false,
-            crate::models::config::ConfigCommitStatus::Success,
         )
         .await?;
 
+        let git_commit = self
+            .export_snapshot_to_git(request.node_id, &config_snapshot, &changed_by, &request.comment, &mut warnings)
+            .await;
+
         Ok(crate::models::config::ConfigGenerateResponse {
             success: true,
             message: "Configuration committed successfully".to_string(),
             config_snapshot_id: Some(config_snapshot.id),
             warnings,
+            git_commit,
         })
     }
 
-    /// Get configuration history
+    /// Get a page of a node's configuration history, newest first
+    ///
+    /// `total_count` is the number of entries in the node's whole history.
     pub async fn get_history(
         &self,
+        node_id: Uuid,
         limit: Option<usize>,
+        offset: Option<usize>,
+        claims: &Claims,
     ) -> Result<crate::models::config::ConfigHistoryResponse, AppError> {
-        // TODO: Query database for configuration history
-        // This would select from config_history table ordered by created_at DESC
+        let reveal_secrets = self.reveals_secrets(claims).await?;
+        let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(1, MAX_HISTORY_LIMIT);
+        let offset = offset.unwrap_or(0);
+
+        let (records, total) = self
+            .db
+            .list_config_changes(&node_id.to_string(), limit as i64, i64::try_from(offset).unwrap_or(i64::MAX))
+            .await?;
+        let history = records
+            .iter()
+            .map(|record| {
+                let mut entry = record.to_history()?;
+                if reveal_secrets {
+                    self.unseal_snapshot(&mut entry.config_snapshot)?;
+                } else {
+                    self.redact_snapshot(&mut entry.config_snapshot);
+                }
+                Ok(entry)
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
 
-        // For now, return empty list
         Ok(crate::models::config::ConfigHistoryResponse {
-            history: vec![],
-            total_count: 0,
+            history,
+            total_count: total as usize,
         })
     }
 
-    /// Rollback to a previous configuration
+    /// Roll a node back to a configuration from its history
+    ///
+    /// The node is converged to the configuration of the history entry,
+    /// respecting locks and commits awaiting confirmation like any other
+    /// change.
     pub async fn rollback_config(
         &self,
         request: crate::models::config::ConfigRollbackRequest,
-        _changed_by: String,
+        claims: &Claims,
     ) -> Result<crate::models::config::ConfigRollbackResponse, AppError> {
         // Retrieve the history entry
-        let history_entry = self.get_history_entry(request.history_id).await?;
+        let mut history_entry = self.history_entry(request.node_id, request.history_id).await?;
+
+        self.desired_state
+            .converge(
+                request.node_id,
+                DesiredStateRequest {
+                    config: Some(config_value(&history_entry.config_snapshot.config_tree)),
+                    comment: Some(request.comment.clone()),
+                    ..Default::default()
+                },
+                claims,
+            )
+            .await?;
 
-        // TODO: Integrate with vyos_client module for actual VyOS API calls
-        // This would:
-        // 1. Load the configuration snapshot
-        // 2. Apply the configuration to VyOS
-        // 3. Optionally commit immediately if apply_immediately is true
+        // Record the restored configuration as a new history entry
+        let new_snapshot = crate::models::config::ConfigSnapshot {
+            id: uuid::Uuid::new_v4(),
+            config_tree: history_entry.config_snapshot.config_tree.clone(),
+            hash: history_entry.config_snapshot.hash.clone(),
+            created_at: chrono::Utc::now(),
+        };
 
-        // Create a new history entry for the rollback
-        let new_snapshot = self.create_config_snapshot().await?;
-        let new_history_id = uuid::Uuid::new_v4();
+        let new_history_id = self
+            .store_config_history(
+                request.node_id,
+                &new_snapshot,
+                crate::models::config::ConfigChangeType::Rollback,
+                claims.username.clone(),
+                &request.comment,
+                false,
+            )
+            .await?;
 
-        self.store_config_history(
-            &new_snapshot,
-            crate::models::config::ConfigChangeType::Rollback,
-            _changed_by,
-            &request.comment,
-            false,
-            crate::models::config::ConfigCommitStatus::Success,
-        )
-        .await?;
+        let mut warnings = Vec::new();
+        let git_commit = self
+            .export_snapshot_to_git(request.node_id, &new_snapshot, &claims.username, &request.comment, &mut warnings)
+            .await;
+
+        if !self.reveals_secrets(claims).await? {
+            self.redact_snapshot(&mut history_entry.config_snapshot);
+        }
 
         Ok(crate::models::config::ConfigRollbackResponse {
             success: true,
             message: format!("Rolled back to configuration from {}", history_entry.changed_at),
             rolled_back_to: history_entry.config_snapshot,
             new_history_id,
+            git_commit,
+            warnings,
         })
     }
 
-    /// Compare two configuration snapshots
+    /// Compare two configuration snapshots of a node
     pub async fn diff_configs(
         &self,
+        node_id: Uuid,
         snapshot_id1: uuid::Uuid,
         snapshot_id2: uuid::Uuid,
+        format: crate::models::config::ConfigDiffFormat,
+        claims: &Claims,
     ) -> Result<crate::models::config::ConfigDiffResult, AppError> {
         // Retrieve both snapshots
-        let snapshot1 = self.get_config_snapshot(snapshot_id1).await?;
-        let snapshot2 = self.get_config_snapshot(snapshot_id2).await?;
+        let mut snapshot1 = self.get_config_snapshot(node_id, snapshot_id1).await?;
+        let mut snapshot2 = self.get_config_snapshot(node_id, snapshot_id2).await?;
 
         // Calculate differences
-        let (additions, deletions, modifications) = self
-            .calculate_diff(&snapshot1.config_tree, &snapshot2.config_tree)
-            .await?;
+        let (mut additions, mut deletions, mut modifications) =
+            Self::calculate_diff(&snapshot1.config_tree, &snapshot2.config_tree);
+        if !self.reveals_secrets(claims).await? {
+            for change in additions.iter_mut().chain(&mut deletions).chain(&mut modifications) {
+                self.redact_change(change);
+            }
+            self.redact_snapshot(&mut snapshot1);
+            self.redact_snapshot(&mut snapshot2);
+        }
+        let unified = match format {
+            crate::models::config::ConfigDiffFormat::Unified => Some(render_unified_diff(
+                &snapshot1,
+                &snapshot2,
+                [&additions, &deletions, &modifications].into_iter().flatten(),
+            )),
+            crate::models::config::ConfigDiffFormat::Structured => None,
+        };
 
         Ok(crate::models::config::ConfigDiffResult {
             id: uuid::Uuid::new_v4(),
@@ -199,37 +363,48 @@ impl ConfigService {
             additions,
             deletions,
             modifications,
+            unified,
             generated_at: chrono::Utc::now(),
         })
     }
 
-    /// Validate configuration
+    /// Validate the configuration of a node
+    ///
+    /// Fails when the node's configuration cannot be read.
     pub async fn validate_configuration(
         &self,
+        node_id: Uuid,
     ) -> Result<Vec<String>, AppError> {
         let mut warnings = Vec::new();
 
-        // TODO: Integrate with vyos_client module for actual VyOS API calls
-        // This would call the VyOS validation API
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        if config.stale {
+            warnings.push(format!(
+                "Node {} is unreachable; validated its last-known configuration",
+                node_id
+            ));
+        }
 
         warnings.push("Validation completed successfully".to_string());
 
         Ok(warnings)
     }
 
-    /// Search configuration
+    /// Search the configuration of a node
     pub async fn search_config(
         &self,
         request: crate::models::config::ConfigSearchRequest,
+        claims: &Claims,
     ) -> Result<crate::models::config::ConfigSearchResponse, AppError> {
         // Retrieve full config
         let retrieve_request = crate::models::config::ConfigRetrieveRequest {
+            node_id: request.node_id,
             path: request.path_filter.clone(),
             include_defaults: true,
             include_readonly: true,
         };
 
-        let full_config = self.retrieve_config(retrieve_request).await?;
+        let full_config = self.retrieve_config(retrieve_request, claims).await?;
 
         // Filter based on search criteria
         let results = self.search_in_tree(&full_config.config_tree, &request).await;
@@ -241,22 +416,18 @@ impl ConfigService {
         })
     }
 
-    /// Bulk configuration changes
+    /// Bulk configuration changes to a node
     pub async fn bulk_config_change(
         &self,
         request: crate::models::config::BulkConfigChangeRequest,
-        _changed_by: String,
+        claims: &Claims,
     ) -> Result<crate::models::config::BulkConfigChangeResponse, AppError> {
         let mut applied = Vec::new();
         let mut failed = Vec::new();
 
         for change in &request.changes {
             let result = self
-                .set_config(crate::models::config::ConfigSetRequest {
-                    path: change.path.clone(),
-                    value: change.value.clone(),
-                    validate: request.validate,
-                })
+                .apply_change(request.node_id, &change.path, change.value.clone(), request.validate, claims)
                 .await;
 
             match result {
@@ -294,37 +465,96 @@ impl ConfigService {
 
     // Private helper methods
 
-    async fn build_mock_config_tree(
+    /// Set a value at a path of a node, or delete the path without a value
+    ///
+    /// Paths under a lock the caller does not hold are rejected, as is any
+    /// change while a commit to the node awaits confirmation.
+    async fn apply_change(
         &self,
-        path_filter: &Option<String>,
-    ) -> Result<crate::models::config::ConfigNode, AppError> {
-        let now = chrono::Utc::now();
-        let root_id = uuid::Uuid::new_v4();
-
-        let root_node = crate::models::config::ConfigNode {
-            id: root_id,
-            path: "/".to_string(),
-            name: "root".to_string(),
-            value: None,
-            node_type: crate::models::config::ConfigNodeType::Container,
-            description: Some("Root configuration node".to_string()),
-            children: vec![],
-            metadata: crate::models::config::ConfigMetadata {
-                is_readonly: false,
-                is_required: false,
-                default_value: None,
-                validation: None,
-                help_text: None,
-            },
-            created_at: now,
-            updated_at: now,
+        node_id: Uuid,
+        path: &str,
+        value: Option<String>,
+        validate: bool,
+        claims: &Claims,
+    ) -> Result<(), AppError> {
+        let mut words: Vec<String> = path_segments(path).into_iter().map(str::to_string).collect();
+        if words.is_empty() {
+            return Err(AppError::Validation("Configuration path must not be empty".to_string()));
+        }
+
+        self.locks.ensure_can_modify(Some(claims), path, value.is_none()).await?;
+
+        // Validate the request
+        if validate {
+            match &value {
+                Some(_) => self.validate_config_path(path, &value).await?,
+                None => self.validate_config_deletion(path).await?,
+            }
+        }
+
+        if self.sessions.awaiting_confirmation(node_id).await? {
+            return Err(AppError::Validation(format!(
+                "A commit to node {} is awaiting confirmation",
+                node_id
+            )));
+        }
+
+        let operation = match value {
+            Some(value) => {
+                words.push(value);
+                ConfigOperation::set(words)
+            }
+            None => ConfigOperation::delete(words),
         };
+        self.nodes.configure_node(node_id, &[operation]).await
+    }
+
+    /// Commit an applied snapshot of a node, without its secrets, to the Git
+    /// export repository
+    ///
+    /// Export failures never fail the configuration change itself; they are
+    /// reported back to the caller as warnings instead.
+    async fn export_snapshot_to_git(
+        &self,
+        node_id: Uuid,
+        snapshot: &crate::models::config::ConfigSnapshot,
+        changed_by: &str,
+        description: &str,
+        warnings: &mut Vec<String>,
+    ) -> Option<String> {
+        if !self.git_export.is_enabled() {
+            return None;
+        }
 
-        Ok(root_node)
+        let author = self.git_author(changed_by).await;
+        let mut snapshot = snapshot.clone();
+        self.redact_snapshot(&mut snapshot);
+
+        match self.git_export.commit_snapshot(node_id, &snapshot, &author, description).await {
+            Ok(commit) => commit,
+            Err(e) => {
+                tracing::warn!("Failed to export config snapshot {} to Git: {}", snapshot.id, e);
+                warnings.push(format!("Configuration was applied but not exported to Git: {}", e));
+                None
+            }
+        }
     }
 
-    fn count_nodes(&self, node: &crate::models::config::ConfigNode) -> usize {
-        1 + node.children.iter().map(|child| self.count_nodes(child)).sum::<usize>()
+    /// Resolve the Git author for the operator who made a change
+    async fn git_author(&self, changed_by: &str) -> GitAuthor {
+        match self.db.find_user_by_username(changed_by).await {
+            Ok(Some(user)) => GitAuthor {
+                name: user
+                    .full_name
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or(user.username),
+                email: user.email,
+            },
+            _ => GitAuthor {
+                name: changed_by.to_string(),
+                email: format!("{}@vyos-web-ui.local", changed_by),
+            },
+        }
     }
 
     async fn validate_config_path(
@@ -349,101 +579,174 @@ impl ConfigService {
         Ok(())
     }
 
-    async fn create_config_snapshot(
+    /// Snapshot the running configuration of a node
+    ///
+    /// Fails while the node cannot be reached, rather than snapshotting its
+    /// last-known configuration.
+    pub(crate) async fn create_config_snapshot(
         &self,
+        node_id: Uuid,
     ) -> Result<crate::models::config::ConfigSnapshot, AppError> {
         let retrieve_request = crate::models::config::ConfigRetrieveRequest {
+            node_id,
             path: None,
             include_defaults: true,
             include_readonly: true,
         };
 
-        let config_response = self.retrieve_config(retrieve_request).await?;
-
-        let hash = self.calculate_config_hash(&config_response.config_tree);
+        let config_response = self.read_config(retrieve_request, true).await?;
+        if config_response.stale {
+            return Err(AppError::HttpClient(format!("Node {} is unreachable", node_id)));
+        }
 
         Ok(crate::models::config::ConfigSnapshot {
             id: uuid::Uuid::new_v4(),
             config_tree: config_response.config_tree,
-            hash,
+            hash: config_response.config_hash,
             created_at: chrono::Utc::now(),
         })
     }
 
-    fn calculate_config_hash(&self, _node: &crate::models::config::ConfigNode) -> String {
-        // TODO: Implement proper hash calculation using serde_json and a hash function
-        format!("hash_{}", chrono::Utc::now().timestamp())
-    }
-
+    /// Store a committed snapshot in a node's configuration history,
+    /// returning the ID of the new history entry
     async fn store_config_history(
         &self,
+        node_id: Uuid,
         config_snapshot: &crate::models::config::ConfigSnapshot,
         change_type: crate::models::config::ConfigChangeType,
         changed_by: String,
         description: &str,
         is_rollback_point: bool,
-        commit_status: crate::models::config::ConfigCommitStatus,
-    ) -> Result<(), AppError> {
-        // TODO: Store in database
-        // This would insert into the config_history table
-        // For now, just log the action
+    ) -> Result<uuid::Uuid, AppError> {
+        let id = uuid::Uuid::new_v4();
+
+        self.db
+            .create_config_change(&crate::models::config::ConfigChangeRecord {
+                id: id.to_string(),
+                node_id: node_id.to_string(),
+                snapshot_id: config_snapshot.id.to_string(),
+                config_hash: config_snapshot.hash.clone(),
+                snapshot: serde_json::to_string(&self.seal_tree(&config_snapshot.config_tree)?)?,
+                change_type: change_type.as_str().to_string(),
+                changed_by: changed_by.clone(),
+                description: description.to_string(),
+                is_rollback_point,
+                commit_status: crate::models::config::ConfigCommitStatus::Success.as_str().to_string(),
+                created_at: format_timestamp(&config_snapshot.created_at),
+            })
+            .await?;
+
         tracing::info!(
-            "Storing config history: {:?} by {} - {}",
+            "Stored config history {} of node {}: {:?} by {} - {}",
+            id,
+            node_id,
             change_type,
             changed_by,
             description
         );
-        Ok(())
+        Ok(id)
     }
 
+    /// Get an entry of a node's configuration history
     pub async fn get_history_entry(
         &self,
-        _history_id: uuid::Uuid,
+        node_id: Uuid,
+        history_id: uuid::Uuid,
+        claims: &Claims,
     ) -> Result<crate::models::config::ConfigHistory, AppError> {
-        // TODO: Query from database
-        // For now, return a mock history entry
-        let now = chrono::Utc::now();
-        let snapshot = self.create_config_snapshot().await?;
-
-        Ok(crate::models::config::ConfigHistory {
-            id: _history_id,
-            config_snapshot: snapshot,
-            change_type: crate::models::config::ConfigChangeType::Generate,
-            changed_by: "system".to_string(),
-            changed_at: now,
-            description: "Mock history entry".to_string(),
-            is_rollback_point: false,
-            commit_status: crate::models::config::ConfigCommitStatus::Success,
-        })
+        let mut entry = self.history_entry(node_id, history_id).await?;
+        if !self.reveals_secrets(claims).await? {
+            self.redact_snapshot(&mut entry.config_snapshot);
+        }
+        Ok(entry)
     }
 
-    async fn get_config_snapshot(
+    /// Get an entry of a node's configuration history with its secrets
+    async fn history_entry(
         &self,
-        _snapshot_id: uuid::Uuid,
+        node_id: Uuid,
+        history_id: uuid::Uuid,
+    ) -> Result<crate::models::config::ConfigHistory, AppError> {
+        let record = self
+            .db
+            .find_config_change(&node_id.to_string(), &history_id.to_string())
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Configuration history entry {} not found", history_id)))?;
+
+        let mut entry = record.to_history()?;
+        self.unseal_snapshot(&mut entry.config_snapshot)?;
+        Ok(entry)
+    }
+
+    /// Get a stored snapshot of a node's configuration with its secrets
+    pub(crate) async fn get_config_snapshot(
+        &self,
+        node_id: Uuid,
+        snapshot_id: uuid::Uuid,
     ) -> Result<crate::models::config::ConfigSnapshot, AppError> {
-        // TODO: Query from database
-        // For now, return a mock snapshot
-        self.create_config_snapshot().await
+        let record = self
+            .db
+            .find_config_change_by_snapshot(&node_id.to_string(), &snapshot_id.to_string())
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Configuration snapshot {} not found", snapshot_id)))?;
+
+        let mut snapshot = record.to_snapshot()?;
+        self.unseal_snapshot(&mut snapshot)?;
+        Ok(snapshot)
     }
 
-    async fn calculate_diff(
+    /// Configuration tree with its secrets sealed for storage
+    fn seal_tree(
         &self,
+        tree: &crate::models::config::ConfigNode,
+    ) -> Result<crate::models::config::ConfigNode, AppError> {
+        if !self.secrets.seals() {
+            return Ok(tree.clone());
+        }
+        let mut config = config_value(tree);
+        self.secrets.seal(&mut config)?;
+        Ok(config_tree(&[], &config))
+    }
+
+    /// Decrypt the sealed secrets of a stored snapshot
+    fn unseal_snapshot(&self, snapshot: &mut crate::models::config::ConfigSnapshot) -> Result<(), AppError> {
+        let mut config = config_value(&snapshot.config_tree);
+        self.secrets.unseal(&mut config)?;
+        snapshot.config_tree = config_tree(&[], &config);
+        Ok(())
+    }
+
+    /// Replace the secrets of a snapshot, sealed or not
+    fn redact_snapshot(&self, snapshot: &mut crate::models::config::ConfigSnapshot) {
+        let mut config = config_value(&snapshot.config_tree);
+        self.secrets.redact(&[], &mut config);
+        snapshot.config_tree = config_tree(&[], &config);
+    }
+
+    /// Redact the secret words and values of a configuration change
+    pub(crate) fn redact_change(&self, change: &mut crate::models::config::ConfigChange) {
+        let mut words: Vec<String> = path_segments(&change.path).into_iter().map(str::to_string).collect();
+        if self.secrets.redact_words(&mut words) {
+            change.path = words.join(" ");
+            for value in [&mut change.old_value, &mut change.new_value].into_iter().flatten() {
+                *value = REDACTED_SECRET.to_string();
+            }
+        }
+    }
+
+    /// Additions, deletions, and modifications from one configuration tree
+    /// to another
+    pub fn calculate_diff(
         tree1: &crate::models::config::ConfigNode,
         tree2: &crate::models::config::ConfigNode,
-    ) -> Result<
-        (
-            Vec<crate::models::config::ConfigChange>,
-            Vec<crate::models::config::ConfigChange>,
-            Vec<crate::models::config::ConfigChange>,
-        ),
-        AppError,
-    > {
-        // TODO: Implement proper diff algorithm
-        // This would recursively compare the two trees and identify:
-        // - Nodes added in tree2
-        // - Nodes deleted from tree1
-        // - Nodes with modified values
-        Ok((vec![], vec![], vec![]))
+    ) -> (
+        Vec<crate::models::config::ConfigChange>,
+        Vec<crate::models::config::ConfigChange>,
+        Vec<crate::models::config::ConfigChange>,
+    ) {
+        let mut diff = TreeDiff::default();
+        diff.compare(tree1, tree2);
+        (diff.additions, diff.deletions, diff.modifications)
     }
 
     async fn search_in_tree(
@@ -510,13 +813,725 @@ impl ConfigService {
     }
 }
 
+impl ConfigService {
+    /// Back up the configuration of a node on behalf of a user
+    pub async fn backup_node(&self, node_id: Uuid, created_by: &str) -> Result<ConfigBackup, AppError> {
+        self.take_backup(node_id, ConfigBackupKind::Manual, Some(created_by))
+            .await?
+            .ok_or_else(|| AppError::Internal(format!("Backup of node {} was not stored", node_id)))
+    }
+
+    /// Pull the full configuration of a node and store it as a backup
+    ///
+    /// A scheduled backup is skipped, returning `None`, when the
+    /// configuration is unchanged since the node's latest backup. The node's
+    /// backups are pruned by the retention policy afterwards.
+    async fn take_backup(
+        &self,
+        node_id: Uuid,
+        kind: ConfigBackupKind,
+        created_by: Option<&str>,
+    ) -> Result<Option<ConfigBackup>, AppError> {
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        if config.stale {
+            return Err(AppError::HttpClient(
+                config.error.unwrap_or_else(|| format!("Node {} is unreachable", node_id)),
+            ));
+        }
+        let config_hash = format!("{:x}", Sha256::digest(serde_json::to_vec(&config.data)?));
+        let mut sealed = config.data;
+        self.secrets.seal(&mut sealed)?;
+        let content = serde_json::to_vec(&sealed)?;
+
+        if kind == ConfigBackupKind::Scheduled {
+            let latest: Option<String> = sqlx::query_scalar(
+                "SELECT config_hash FROM config_backups WHERE node_id = ? ORDER BY created_at DESC LIMIT 1",
+            )
+            .bind(node_id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?;
+            if latest.as_deref() == Some(config_hash.as_str()) {
+                debug!("Configuration of node {} is unchanged since its latest backup", node_id);
+                return Ok(None);
+            }
+        }
+
+        let stored = storage::compress(&content)?;
+        let id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO config_backups \
+             (id, node_id, kind, config_hash, compression, size_bytes, stored_bytes, content, created_by, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id.to_string())
+        .bind(node_id.to_string())
+        .bind(kind.as_str())
+        .bind(&config_hash)
+        .bind(Compression::Zstd.as_str())
+        .bind(content.len() as i64)
+        .bind(stored.len() as i64)
+        .bind(&stored)
+        .bind(created_by)
+        .bind(db_now())
+        .execute(self.db.pool())
+        .await?;
+        info!(
+            "Backed up the configuration of node {} ({} bytes, {} stored)",
+            node_id,
+            content.len(),
+            stored.len()
+        );
+
+        self.prune_backups(node_id).await?;
+        self.get_backup(node_id, id).await.map(Some)
+    }
+
+    /// Back up the configuration of every node, returning how many backups
+    /// were stored
+    ///
+    /// Nodes that cannot be backed up are logged and skipped.
+    pub async fn backup_all_nodes(&self) -> Result<usize, AppError> {
+        let mut stored = 0;
+        for node in self.nodes.list_all_nodes().await? {
+            match self.take_backup(node.id, ConfigBackupKind::Scheduled, None).await {
+                Ok(Some(_)) => stored += 1,
+                Ok(None) => {}
+                Err(e) => warn!("Failed to back up the configuration of node {}: {}", node.name, e),
+            }
+        }
+        Ok(stored)
+    }
+
+    /// List the backups of a node, newest first
+    pub async fn list_backups(&self, node_id: Uuid) -> Result<ConfigBackupListResponse, AppError> {
+        let rows: Vec<ConfigBackupRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM config_backups WHERE node_id = ? ORDER BY created_at DESC LIMIT ?",
+            BACKUP_COLUMNS
+        ))
+        .bind(node_id.to_string())
+        .bind(BACKUP_LIST_LIMIT)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(ConfigBackupListResponse {
+            backups: rows.into_iter().map(backup_from_row).collect(),
+        })
+    }
+
+    /// Get a backup of a node
+    pub async fn get_backup(&self, node_id: Uuid, backup_id: Uuid) -> Result<ConfigBackup, AppError> {
+        let row: Option<ConfigBackupRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM config_backups WHERE id = ? AND node_id = ?",
+            BACKUP_COLUMNS
+        ))
+        .bind(backup_id.to_string())
+        .bind(node_id.to_string())
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(backup_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Configuration backup {} not found", backup_id)))
+    }
+
+    /// Get a backup of a node with its configuration, decompressed
+    ///
+    /// Secrets are redacted unless the user may read them.
+    pub async fn download_backup(
+        &self,
+        node_id: Uuid,
+        backup_id: Uuid,
+        claims: &Claims,
+    ) -> Result<ConfigBackupArtifact, AppError> {
+        let mut artifact = self.backup_artifact(node_id, backup_id).await?;
+        if !self.reveals_secrets(claims).await? {
+            let mut config = serde_json::from_slice(&artifact.content)?;
+            self.secrets.redact(&[], &mut config);
+            artifact.content = serde_json::to_vec(&config)?;
+        }
+        Ok(artifact)
+    }
+
+    /// Get a backup of a node with its configuration, decompressed and with
+    /// its secrets unsealed
+    async fn backup_artifact(&self, node_id: Uuid, backup_id: Uuid) -> Result<ConfigBackupArtifact, AppError> {
+        let backup = self.get_backup(node_id, backup_id).await?;
+        let stored: Vec<u8> = sqlx::query_scalar("SELECT content FROM config_backups WHERE id = ?")
+            .bind(backup_id.to_string())
+            .fetch_one(self.db.pool())
+            .await?;
+        let content = match backup.compression {
+            Compression::Zstd => storage::decompress(&stored)?,
+            Compression::None => stored,
+        };
+        let mut config = serde_json::from_slice(&content)?;
+        self.secrets.unseal(&mut config)?;
+
+        Ok(ConfigBackupArtifact { backup, content: serde_json::to_vec(&config)? })
+    }
+
+    /// Converge a node back to the configuration of one of its backups
+    ///
+    /// Locks and commits awaiting confirmation are respected as for any
+    /// other change; with `dry_run`, the operations are only returned.
+    /// Secrets in the returned operations are redacted unless the user may
+    /// read them.
+    pub async fn restore_backup(
+        &self,
+        node_id: Uuid,
+        backup_id: Uuid,
+        request: RestoreConfigBackupRequest,
+        claims: &Claims,
+    ) -> Result<DesiredStatePlan, AppError> {
+        let artifact = self.backup_artifact(node_id, backup_id).await?;
+        let config = serde_json::from_slice(&artifact.content)?;
+        let mut plan = self
+            .desired_state
+            .converge(
+                node_id,
+                DesiredStateRequest {
+                    config: Some(config),
+                    dry_run: request.dry_run,
+                    comment: request.comment,
+                    ..Default::default()
+                },
+                claims,
+            )
+            .await?;
+        if plan.applied {
+            info!("{} restored backup {} to node {}", claims.username, backup_id, node_id);
+        }
+        if !self.reveals_secrets(claims).await? {
+            self.secrets.redact_operations(&mut plan.operations);
+        }
+        Ok(plan)
+    }
+
+    /// Delete the backups of a node the retention policy no longer keeps
+    async fn prune_backups(&self, node_id: Uuid) -> Result<usize, AppError> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, created_at FROM config_backups WHERE node_id = ? ORDER BY created_at DESC")
+                .bind(node_id.to_string())
+                .fetch_all(self.db.pool())
+                .await?;
+        let backups: Vec<(String, DateTime<Utc>)> = rows
+            .into_iter()
+            .map(|(id, created_at)| (id, parse_db_timestamp(&created_at)))
+            .collect();
+        let retention = ConfigBackupRetention {
+            keep_last: self.config.config_backup_keep_last as usize,
+            keep_daily_days: self.config.config_backup_keep_daily_days,
+        };
+
+        let expired = expired_backups(&backups, retention, Utc::now());
+        for id in &expired {
+            sqlx::query("DELETE FROM config_backups WHERE id = ?")
+                .bind(id)
+                .execute(self.db.pool())
+                .await?;
+        }
+        if !expired.is_empty() {
+            debug!("Pruned {} configuration backups of node {}", expired.len(), node_id);
+        }
+        Ok(expired.len())
+    }
+}
+
+/// Backups, newest first, that a retention policy no longer keeps
+///
+/// The newest `keep_last` backups are kept, and so is the newest backup of
+/// each day within the last `keep_daily_days` days.
+fn expired_backups(
+    backups: &[(String, DateTime<Utc>)],
+    retention: ConfigBackupRetention,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let daily_since = (now - chrono::Duration::days(retention.keep_daily_days as i64)).date_naive();
+    let mut days_kept = std::collections::HashSet::new();
+
+    backups
+        .iter()
+        .enumerate()
+        .filter_map(|(position, (id, created_at))| {
+            let day = created_at.date_naive();
+            let daily = retention.keep_daily_days > 0 && day > daily_since && days_kept.insert(day);
+            let kept = position < retention.keep_last.max(1) || daily;
+            (!kept).then(|| id.clone())
+        })
+        .collect()
+}
+
+/// Backup columns as selected by [`BACKUP_COLUMNS`]
+const BACKUP_COLUMNS: &str =
+    "id, node_id, kind, config_hash, compression, size_bytes, stored_bytes, created_by, created_at";
+
+type ConfigBackupRow = (String, String, String, String, String, i64, i64, Option<String>, String);
+
+fn backup_from_row(
+    (id, node_id, kind, config_hash, compression, size_bytes, stored_bytes, created_by, created_at): ConfigBackupRow,
+) -> ConfigBackup {
+    ConfigBackup {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        node_id: Uuid::parse_str(&node_id).unwrap_or_default(),
+        kind: ConfigBackupKind::parse(&kind),
+        config_hash,
+        compression: Compression::parse(&compression),
+        size_bytes: size_bytes as u64,
+        stored_bytes: stored_bytes as u64,
+        created_by,
+        created_at: parse_db_timestamp(&created_at),
+    }
+}
+
+/// Changes between two configuration trees, in the order of the trees
+///
+/// Children are matched by name. Where several children share a name, as
+/// the values of a multi-value node do, they are matched by name and value
+/// instead, so a changed value is a deletion and an addition rather than a
+/// modification. A subtree only in one tree is reported as its leaves, each
+/// with its full path and value.
+#[derive(Default)]
+struct TreeDiff {
+    additions: Vec<crate::models::config::ConfigChange>,
+    deletions: Vec<crate::models::config::ConfigChange>,
+    modifications: Vec<crate::models::config::ConfigChange>,
+}
+
+impl TreeDiff {
+    fn compare(&mut self, old: &crate::models::config::ConfigNode, new: &crate::models::config::ConfigNode) {
+        use crate::models::config::{ConfigChange, DiffChangeType};
+
+        match (&old.value, &new.value) {
+            (Some(old_value), Some(new_value)) if old_value != new_value => self.modifications.push(ConfigChange {
+                path: new.path.clone(),
+                old_value: Some(old_value.clone()),
+                new_value: Some(new_value.clone()),
+                change_type: DiffChangeType::Modified,
+            }),
+            (Some(_), None) => self.deletions.push(change(old, DiffChangeType::Deleted)),
+            (None, Some(_)) => self.additions.push(change(new, DiffChangeType::Added)),
+            _ => {}
+        }
+
+        let old_children = child_keys(old);
+        let new_children = child_keys(new);
+        for (key, old_child) in &old_children {
+            match new_children.iter().find(|(new_key, _)| new_key == key) {
+                Some((_, new_child)) => self.compare(old_child, new_child),
+                None => self.deletions.extend(leaves(old_child, DiffChangeType::Deleted)),
+            }
+        }
+        for (key, new_child) in &new_children {
+            if !old_children.iter().any(|(old_key, _)| old_key == key) {
+                self.additions.extend(leaves(new_child, DiffChangeType::Added));
+            }
+        }
+    }
+}
+
+/// Children of a node with the key they are matched by
+fn child_keys(
+    node: &crate::models::config::ConfigNode,
+) -> Vec<((&str, Option<&str>), &crate::models::config::ConfigNode)> {
+    let mut counts: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for child in &node.children {
+        *counts.entry(child.name.as_str()).or_default() += 1;
+    }
+    node.children
+        .iter()
+        .map(|child| {
+            let value = (counts[child.name.as_str()] > 1).then_some(child.value.as_deref()).flatten();
+            ((child.name.as_str(), value), child)
+        })
+        .collect()
+}
+
+/// Leaves of a subtree, and the subtree itself when it is empty
+fn leaves(
+    root: &crate::models::config::ConfigNode,
+    change_type: crate::models::config::DiffChangeType,
+) -> Vec<crate::models::config::ConfigChange> {
+    let mut changes = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.value.is_some() || node.children.is_empty() {
+            changes.push(change(node, change_type.clone()));
+        }
+        stack.extend(node.children.iter().rev());
+    }
+    changes
+}
+
+fn change(
+    node: &crate::models::config::ConfigNode,
+    change_type: crate::models::config::DiffChangeType,
+) -> crate::models::config::ConfigChange {
+    use crate::models::config::DiffChangeType;
+
+    let (old_value, new_value) = match change_type {
+        DiffChangeType::Deleted => (node.value.clone(), None),
+        _ => (None, node.value.clone()),
+    };
+    crate::models::config::ConfigChange {
+        path: node.path.clone(),
+        old_value,
+        new_value,
+        change_type,
+    }
+}
+
+/// Changes as a unified diff of the two snapshots, one configuration line
+/// per path, ordered by path
+fn render_unified_diff<'a>(
+    snapshot1: &crate::models::config::ConfigSnapshot,
+    snapshot2: &crate::models::config::ConfigSnapshot,
+    changes: impl Iterator<Item = &'a crate::models::config::ConfigChange>,
+) -> String {
+    use crate::models::config::DiffChangeType;
+
+    let line = |path: &str, value: &Option<String>| match value {
+        Some(value) => format!("{} '{}'", path, value),
+        None => path.to_string(),
+    };
+
+    let mut changes: Vec<&crate::models::config::ConfigChange> = changes.collect();
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut text = format!(
+        "--- {} {}\n+++ {} {}\n",
+        snapshot1.id,
+        format_timestamp(&snapshot1.created_at),
+        snapshot2.id,
+        format_timestamp(&snapshot2.created_at)
+    );
+    for change in changes {
+        if !matches!(change.change_type, DiffChangeType::Added) {
+            text.push_str(&format!("-{}\n", line(&change.path, &change.old_value)));
+        }
+        if !matches!(change.change_type, DiffChangeType::Deleted) {
+            text.push_str(&format!("+{}\n", line(&change.path, &change.new_value)));
+        }
+    }
+    text
+}
+
+/// Configuration tree of a retrieved configuration found under `path`
+///
+/// Objects become containers, and the values of a multi-value node list
+/// items sharing its path. A node without a value or children, such as
+/// `disable`, is a leaf without a value.
+fn config_tree(path: &[&str], config: &serde_json::Value) -> crate::models::config::ConfigNode {
+    use crate::models::config::{ConfigMetadata, ConfigNode, ConfigNodeType};
+    use serde_json::Value;
+
+    let now = Utc::now();
+    let node = |node_type, value, children| ConfigNode {
+        id: Uuid::new_v4(),
+        path: if path.is_empty() { "/".to_string() } else { path.join(" ") },
+        name: path.last().copied().unwrap_or("root").to_string(),
+        value,
+        node_type,
+        description: None,
+        children,
+        metadata: ConfigMetadata {
+            is_readonly: false,
+            is_required: false,
+            default_value: None,
+            validation: None,
+            help_text: None,
+        },
+        created_at: now,
+        updated_at: now,
+    };
+
+    match config {
+        Value::Object(children) if !children.is_empty() || path.is_empty() => node(
+            ConfigNodeType::Container,
+            None,
+            children
+                .iter()
+                .map(|(name, child)| {
+                    let mut child_path = path.to_vec();
+                    child_path.push(name);
+                    config_tree(&child_path, child)
+                })
+                .collect(),
+        ),
+        Value::Object(_) | Value::Null => node(ConfigNodeType::Leaf, None, Vec::new()),
+        Value::Array(values) => node(
+            ConfigNodeType::List,
+            None,
+            values.iter().map(|value| config_tree(path, value)).collect(),
+        ),
+        Value::String(value) => node(ConfigNodeType::Leaf, Some(value.clone()), Vec::new()),
+        value => node(ConfigNodeType::Leaf, Some(value.to_string()), Vec::new()),
+    }
+}
+
+/// Configuration tree shaped like a retrieved configuration, the inverse of
+/// [`config_tree`]
+fn config_value(node: &crate::models::config::ConfigNode) -> serde_json::Value {
+    use crate::models::config::ConfigNodeType;
+    use serde_json::Value;
+
+    match (&node.node_type, &node.value) {
+        (ConfigNodeType::List, _) => Value::Array(node.children.iter().map(config_value).collect()),
+        (_, Some(value)) => Value::String(value.clone()),
+        _ => Value::Object(
+            node.children
+                .iter()
+                .map(|child| (child.name.clone(), config_value(child)))
+                .collect(),
+        ),
+    }
+}
+
+/// Hash and statistics of a configuration tree, in a single walk
+///
+/// Node IDs and timestamps are left out of the hash, so it only changes
+/// when the configuration does.
+fn summarize_tree(
+    root: &crate::models::config::ConfigNode,
+) -> (String, crate::models::config::ConfigTreeStats) {
+    use crate::models::config::ConfigNodeType;
+
+    let mut hasher = Sha256::new();
+    let mut stats = crate::models::config::ConfigTreeStats::default();
+    let mut stack = vec![(root, 0)];
+
+    while let Some((node, depth)) = stack.pop() {
+        stats.total_nodes += 1;
+        stats.max_depth = stats.max_depth.max(depth);
+        match node.node_type {
+            ConfigNodeType::Leaf => stats.leaf_nodes += 1,
+            ConfigNodeType::Container => stats.container_nodes += 1,
+            ConfigNodeType::Tag | ConfigNodeType::List => {}
+        }
+
+        hasher.update(node.path.as_bytes());
+        hasher.update([0]);
+        hasher.update(node.value.as_deref().unwrap_or_default().as_bytes());
+        hasher.update([0]);
+        hasher.update(format!("{:?}\n", node.node_type).as_bytes());
+
+        stack.extend(node.children.iter().rev().map(|child| (child, depth + 1)));
+    }
+
+    (format!("{:x}", hasher.finalize()), stats)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::config::{ConfigMetadata, ConfigNode, ConfigNodeType, ConfigTreeStats};
 
     #[test]
     fn test_config_service_creation() {
         // This would be expanded with actual tests in the future
         assert!(true);
     }
-}
\ No newline at end of file
+
+    fn node(path: &str, node_type: ConfigNodeType, value: Option<&str>, children: Vec<ConfigNode>) -> ConfigNode {
+        let now = chrono::Utc::now();
+        ConfigNode {
+            id: uuid::Uuid::new_v4(),
+            path: path.to_string(),
+            name: path.rsplit(' ').next().unwrap_or(path).to_string(),
+            value: value.map(str::to_string),
+            node_type,
+            description: None,
+            children,
+            metadata: ConfigMetadata {
+                is_readonly: false,
+                is_required: false,
+                default_value: None,
+                validation: None,
+                help_text: None,
+            },
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_expired_backups_follow_the_retention_policy() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-05-10T12:00:00Z").unwrap().with_timezone(&Utc);
+        let backups: Vec<(String, DateTime<Utc>)> = [
+            ("today-2", 0),
+            ("today-1", 1),
+            ("yesterday-2", 24),
+            ("yesterday-1", 26),
+            ("last-week", 24 * 7),
+            ("last-month", 24 * 30),
+        ]
+        .into_iter()
+        .map(|(id, hours_ago)| (id.to_string(), now - chrono::Duration::hours(hours_ago)))
+        .collect();
+
+        let expired = |keep_last, keep_daily_days| {
+            expired_backups(&backups, ConfigBackupRetention { keep_last, keep_daily_days }, now)
+        };
+        assert_eq!(expired(3, 0), ["yesterday-1", "last-week", "last-month"]);
+        assert_eq!(expired(1, 10), ["today-1", "yesterday-1", "last-month"]);
+        assert_eq!(expired(0, 0).len(), 5);
+        assert!(expired(10, 0).is_empty());
+    }
+
+    #[test]
+    fn test_config_tree_round_trips() {
+        let config = serde_json::json!({
+            "interfaces": {
+                "ethernet": {
+                    "eth0": {
+                        "address": ["192.0.2.1/24", "2001:db8::1/64"],
+                        "disable": {},
+                    },
+                },
+            },
+            "system": { "host-name": "edge-1" },
+        });
+
+        let tree = config_tree(&[], &config);
+        assert_eq!(tree.path, "/");
+        let eth0 = &tree.children[0].children[0].children[0];
+        assert_eq!(eth0.path, "interfaces ethernet eth0");
+        assert!(matches!(eth0.children[0].node_type, ConfigNodeType::List));
+        assert_eq!(eth0.children[0].children[1].path, "interfaces ethernet eth0 address");
+        assert_eq!(eth0.children[0].children[1].value.as_deref(), Some("2001:db8::1/64"));
+        assert!(matches!(eth0.children[1].node_type, ConfigNodeType::Leaf));
+        assert_eq!(eth0.children[1].value, None);
+        assert_eq!(config_value(&tree), config);
+
+        let subtree = config_tree(&["system"], &config["system"]);
+        assert_eq!(subtree.name, "system");
+        assert_eq!(subtree.children[0].path, "system host-name");
+        assert_eq!(config_value(&config_tree(&[], &serde_json::json!({}))), serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_summarize_tree() {
+        let tree = |host_name| {
+            node(
+                "/",
+                ConfigNodeType::Container,
+                None,
+                vec![
+                    node(
+                        "system",
+                        ConfigNodeType::Container,
+                        None,
+                        vec![node("system host-name", ConfigNodeType::Leaf, Some(host_name), vec![])],
+                    ),
+                    node(
+                        "interfaces ethernet",
+                        ConfigNodeType::Tag,
+                        None,
+                        vec![node("interfaces ethernet eth0", ConfigNodeType::Container, None, vec![])],
+                    ),
+                ],
+            )
+        };
+
+        let (hash, stats) = summarize_tree(&tree("edge-1"));
+        assert_eq!(
+            stats,
+            ConfigTreeStats {
+                total_nodes: 5,
+                leaf_nodes: 1,
+                container_nodes: 3,
+                max_depth: 2,
+            }
+        );
+        assert_eq!(hash.len(), 64);
+        assert_eq!(summarize_tree(&tree("edge-1")).0, hash);
+        assert_ne!(summarize_tree(&tree("edge-2")).0, hash);
+    }
+
+    #[test]
+    fn test_calculate_diff() {
+        use crate::models::config::ConfigNodeType::{Container, Leaf, List, Tag};
+
+        let leaf = |path: &str, value: &str| node(path, Leaf, Some(value), vec![]);
+        let tree = |host_name, addresses: &[&str], ethernets: Vec<ConfigNode>| {
+            let eth0 = node(
+                "interfaces ethernet eth0",
+                Container,
+                None,
+                vec![node(
+                    "interfaces ethernet eth0 address",
+                    List,
+                    None,
+                    addresses
+                        .iter()
+                        .map(|address| leaf("interfaces ethernet eth0 address", address))
+                        .collect(),
+                )],
+            );
+            node(
+                "/",
+                Container,
+                None,
+                vec![
+                    node("system", Container, None, vec![leaf("system host-name", host_name)]),
+                    node("interfaces ethernet", Tag, None, [eth0].into_iter().chain(ethernets).collect()),
+                ],
+            )
+        };
+        let eth1 = node(
+            "interfaces ethernet eth1",
+            Container,
+            None,
+            vec![
+                leaf("interfaces ethernet eth1 description", "uplink"),
+                node("interfaces ethernet eth1 disable", Leaf, None, vec![]),
+            ],
+        );
+
+        let old = tree("edge-1", &["192.0.2.1/24", "2001:db8::1/64"], vec![]);
+        let new = tree("edge-2", &["192.0.2.2/24", "2001:db8::1/64"], vec![eth1]);
+        let (additions, deletions, modifications) = ConfigService::calculate_diff(&old, &new);
+
+        let lines = |changes: &[crate::models::config::ConfigChange]| {
+            changes
+                .iter()
+                .map(|change| format!("{} {:?} {:?}", change.path, change.old_value, change.new_value))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(&modifications), [r#"system host-name Some("edge-1") Some("edge-2")"#]);
+        assert_eq!(lines(&deletions), [r#"interfaces ethernet eth0 address Some("192.0.2.1/24") None"#]);
+        assert_eq!(
+            lines(&additions),
+            [
+                r#"interfaces ethernet eth0 address None Some("192.0.2.2/24")"#,
+                r#"interfaces ethernet eth1 description None Some("uplink")"#,
+                "interfaces ethernet eth1 disable None None",
+            ]
+        );
+
+        let (additions, deletions, modifications) = ConfigService::calculate_diff(&new, &new);
+        assert!(additions.is_empty() && deletions.is_empty() && modifications.is_empty());
+
+        let snapshot = |config_tree| crate::models::config::ConfigSnapshot {
+            id: uuid::Uuid::nil(),
+            config_tree,
+            hash: String::new(),
+            created_at: DateTime::parse_from_rfc3339("2024-05-10T12:00:00Z").unwrap().with_timezone(&Utc),
+        };
+        let (additions, deletions, modifications) = ConfigService::calculate_diff(&old, &new);
+        let text = render_unified_diff(
+            &snapshot(old),
+            &snapshot(new),
+            [&additions, &deletions, &modifications].into_iter().flatten(),
+        );
+        assert!(text.starts_with("--- 00000000-0000-0000-0000-000000000000 2024-05-10T12:00:00.000Z\n+++ "));
+        assert_eq!(
+            text.lines().skip(2).collect::<Vec<_>>(),
+            [
+                "+interfaces ethernet eth0 address '192.0.2.2/24'",
+                "-interfaces ethernet eth0 address '192.0.2.1/24'",
+                "+interfaces ethernet eth1 description 'uplink'",
+                "+interfaces ethernet eth1 disable",
+                "-system host-name 'edge-1'",
+                "+system host-name 'edge-2'",
+            ]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! Configuration Drift
+//!
+//! A node's baseline is a stored snapshot of its configuration that its
+//! running configuration is expected to match, e.g. the one an approved
+//! change left it in. Comparing the two shows the changes made since,
+//! whether out-of-band on the node itself or through this UI. A scheduled
+//! job compares every node with a baseline, and raises an alert when a node
+//! starts drifting from it.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::config::ConfigChange;
+use crate::models::config_drift::{ConfigBaseline, ConfigDriftReport, SetConfigBaselineRequest};
+use crate::models::monitoring::{Alert, AlertSeverity, AlertStatus};
+use crate::models::node::{Node, NodeStatus};
+use crate::models::timestamp::{format_timestamp, parse_db_timestamp};
+use crate::services::{ConfigService, MonitoringService, NodeService};
+
+/// How often the scheduled job compares every node with its baseline
+pub const CONFIG_DRIFT_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+const BASELINE_COLUMNS: &str = "node_id, snapshot_id, alert_on_drift, set_by, set_at, drifted, checked_at";
+
+/// Differences of a running configuration from its baseline: additions,
+/// deletions, and modifications
+type Drift = (Vec<ConfigChange>, Vec<ConfigChange>, Vec<ConfigChange>);
+
+/// Configuration drift service
+#[derive(Clone)]
+pub struct ConfigDriftService {
+    db: Database,
+    config: ConfigService,
+    nodes: NodeService,
+    monitoring: MonitoringService,
+}
+
+impl ConfigDriftService {
+    /// Create a new configuration drift service
+    pub fn new(db: Database, config: ConfigService, nodes: NodeService, monitoring: MonitoringService) -> Self {
+        Self {
+            db,
+            config,
+            nodes,
+            monitoring,
+        }
+    }
+
+    /// Get the baseline of a node
+    pub async fn get_baseline(&self, node_id: Uuid) -> Result<ConfigBaseline, AppError> {
+        let query = format!("SELECT {} FROM config_baselines WHERE node_id = ?", BASELINE_COLUMNS);
+
+        sqlx::query_as::<_, BaselineRow>(&query)
+            .bind(node_id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(baseline_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Node {} has no configuration baseline", node_id)))
+    }
+
+    /// Mark a stored snapshot of a node's configuration as its baseline,
+    /// replacing any previous one
+    pub async fn set_baseline(
+        &self,
+        node_id: Uuid,
+        request: SetConfigBaselineRequest,
+        claims: &Claims,
+    ) -> Result<ConfigBaseline, AppError> {
+        // The snapshot must be one of the node's own
+        self.config.get_config_snapshot(node_id, request.snapshot_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO config_baselines (node_id, snapshot_id, alert_on_drift, set_by, set_at, drifted, checked_at)
+            VALUES (?, ?, ?, ?, ?, NULL, NULL)
+            ON CONFLICT (node_id) DO UPDATE SET
+                snapshot_id = excluded.snapshot_id,
+                alert_on_drift = excluded.alert_on_drift,
+                set_by = excluded.set_by,
+                set_at = excluded.set_at,
+                drifted = NULL,
+                checked_at = NULL
+            "#,
+        )
+        .bind(node_id.to_string())
+        .bind(request.snapshot_id.to_string())
+        .bind(request.alert_on_drift)
+        .bind(&claims.username)
+        .bind(format_timestamp(&Utc::now()))
+        .execute(self.db.pool())
+        .await?;
+
+        info!(
+            "Snapshot {} set as the configuration baseline of node {} by {}",
+            request.snapshot_id, node_id, claims.username
+        );
+        self.get_baseline(node_id).await
+    }
+
+    /// Stop comparing a node with a baseline
+    pub async fn clear_baseline(&self, node_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM config_baselines WHERE node_id = ?")
+            .bind(node_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Node {} has no configuration baseline", node_id)));
+        }
+        Ok(())
+    }
+
+    /// Compare the running configuration of a node with its baseline
+    ///
+    /// Secrets in the changes are redacted unless the user may read them.
+    pub async fn drift(&self, node_id: Uuid, claims: &Claims) -> Result<ConfigDriftReport, AppError> {
+        let node = self
+            .nodes
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+        let baseline = self.get_baseline(node_id).await?;
+
+        let (mut additions, mut deletions, mut modifications) = self.compare(&baseline).await?;
+        let changes = additions.len() + deletions.len() + modifications.len();
+        let checked_at = Utc::now();
+        let baseline = self.record(&node, baseline, changes, checked_at).await?;
+
+        if !self.config.reveals_secrets(claims).await? {
+            for change in additions.iter_mut().chain(&mut deletions).chain(&mut modifications) {
+                self.config.redact_change(change);
+            }
+        }
+
+        Ok(ConfigDriftReport {
+            baseline,
+            drifted: changes > 0,
+            additions,
+            deletions,
+            modifications,
+            checked_at,
+        })
+    }
+
+    /// Compare every node that has a baseline with it, returning how many
+    /// nodes have drifted
+    ///
+    /// Rebooting nodes, and nodes that cannot be read, are skipped.
+    pub async fn check_all(&self) -> Result<usize, AppError> {
+        let query = format!("SELECT {} FROM config_baselines", BASELINE_COLUMNS);
+        let baselines = sqlx::query_as::<_, BaselineRow>(&query).fetch_all(self.db.pool()).await?;
+
+        let mut drifted_nodes = 0;
+        for baseline in baselines.into_iter().map(baseline_from_row) {
+            let Some(node) = self.nodes.get_node(baseline.node_id).await? else {
+                continue;
+            };
+            if node.status == NodeStatus::Rebooting {
+                continue;
+            }
+            let (additions, deletions, modifications) = match self.compare(&baseline).await {
+                Ok(drift) => drift,
+                Err(e) => {
+                    debug!("Could not compare node {} with its configuration baseline: {}", node.id, e);
+                    continue;
+                }
+            };
+
+            let changes = additions.len() + deletions.len() + modifications.len();
+            if changes > 0 {
+                drifted_nodes += 1;
+            }
+            self.record(&node, baseline, changes, Utc::now()).await?;
+        }
+        Ok(drifted_nodes)
+    }
+
+    /// Differences of a node's running configuration from its baseline
+    async fn compare(&self, baseline: &ConfigBaseline) -> Result<Drift, AppError> {
+        let expected = self.config.get_config_snapshot(baseline.node_id, baseline.snapshot_id).await?;
+        let running = self.config.create_config_snapshot(baseline.node_id).await?;
+
+        Ok(ConfigService::calculate_diff(&expected.config_tree, &running.config_tree))
+    }
+
+    /// Store the outcome of a check, alerting when the node starts drifting
+    async fn record(
+        &self,
+        node: &Node,
+        mut baseline: ConfigBaseline,
+        changes: usize,
+        checked_at: DateTime<Utc>,
+    ) -> Result<ConfigBaseline, AppError> {
+        let drifted = changes > 0;
+        sqlx::query("UPDATE config_baselines SET drifted = ?, checked_at = ? WHERE node_id = ?")
+            .bind(drifted)
+            .bind(format_timestamp(&checked_at))
+            .bind(node.id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if drifted && baseline.drifted != Some(true) {
+            warn!("Configuration of node {} drifted from its baseline: {} changes", node.name, changes);
+            if baseline.alert_on_drift {
+                self.monitoring.raise_alert(drift_alert(node, changes)).await;
+            }
+        }
+
+        baseline.drifted = Some(drifted);
+        baseline.checked_at = Some(checked_at);
+        Ok(baseline)
+    }
+}
+
+type BaselineRow = (String, String, bool, String, String, Option<bool>, Option<String>);
+
+fn baseline_from_row(
+    (node_id, snapshot_id, alert_on_drift, set_by, set_at, drifted, checked_at): BaselineRow,
+) -> ConfigBaseline {
+    ConfigBaseline {
+        node_id: Uuid::parse_str(&node_id).unwrap_or_default(),
+        snapshot_id: Uuid::parse_str(&snapshot_id).unwrap_or_default(),
+        alert_on_drift,
+        set_by,
+        set_at: parse_db_timestamp(&set_at),
+        drifted,
+        checked_at: checked_at.as_deref().map(parse_db_timestamp),
+    }
+}
+
+fn drift_alert(node: &Node, changes: usize) -> Alert {
+    let now = Utc::now();
+    Alert {
+        id: Uuid::new_v4(),
+        node_id: node.id.to_string(),
+        severity: AlertSeverity::Warning,
+        title: format!("Configuration of {} drifted from its baseline", node.name),
+        description: format!(
+            "The running configuration of {} differs from its baseline in {} paths",
+            node.name, changes
+        ),
+        status: AlertStatus::Active,
+        metric_name: Some("config_drift".to_string()),
+        threshold_value: None,
+        actual_value: Some(changes as f64),
+        triggered_at: now,
+        updated_at: now,
+        acknowledged_at: None,
+        acknowledged_by: None,
+        resolved_at: None,
+        trigger_count: 1,
+        labels: Vec::new(),
+        data: None,
+    }
+}
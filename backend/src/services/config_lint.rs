@@ -0,0 +1,315 @@
+//! Configuration Lint Service
+//!
+//! Checks a configuration tree for common mistakes before it is committed:
+//! internet-facing interfaces without a firewall, SSH listening on every
+//! address, no default route, and no DNS servers. Findings are advisory and
+//! returned as warnings; the commit goes ahead either way.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::models::config::{ConfigLintRule, ConfigNode};
+
+/// Addresses that make a service listen on every interface
+const ANY_ADDRESSES: &[&str] = &["0.0.0.0", "::", "0.0.0.0/0", "::/0"];
+
+/// Default route prefixes
+const DEFAULT_ROUTES: &[&str] = &["0.0.0.0/0", "::/0"];
+
+/// Firewall chains that filter traffic on every interface
+const GLOBAL_FIREWALL_CHAINS: &[&str] = &["firewall ipv4 input filter", "firewall ipv4 forward filter"];
+
+/// Configuration linter
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLinter {
+    disabled: BTreeSet<ConfigLintRule>,
+}
+
+impl ConfigLinter {
+    /// Create a linter that skips the given rules
+    pub fn new(disabled: BTreeSet<ConfigLintRule>) -> Self {
+        Self { disabled }
+    }
+
+    /// Warnings for every enabled rule the configuration breaks
+    pub fn lint(&self, tree: &ConfigNode) -> Vec<String> {
+        let config = FlatConfig::new(tree);
+        let mut warnings = Vec::new();
+
+        for rule in ConfigLintRule::ALL {
+            if self.disabled.contains(&rule) {
+                continue;
+            }
+            let findings = match rule {
+                ConfigLintRule::WanFirewall => config.unprotected_wan_interfaces(),
+                ConfigLintRule::SshOpenToAny => config.ssh_open_to_any(),
+                ConfigLintRule::DefaultRoute => config.missing_default_route(),
+                ConfigLintRule::DnsUnset => config.missing_name_server(),
+            };
+            warnings.extend(
+                findings
+                    .into_iter()
+                    .map(|finding| format!("{} (lint rule {})", finding, rule.as_str())),
+            );
+        }
+
+        warnings
+    }
+}
+
+/// Configuration tree flattened into the values set at each path
+///
+/// Paths are the space-separated node names from the root down, as in
+/// `interfaces ethernet eth0 address`. Nodes without a value are present
+/// with no values.
+struct FlatConfig {
+    paths: BTreeMap<String, Vec<String>>,
+}
+
+impl FlatConfig {
+    fn new(root: &ConfigNode) -> Self {
+        let mut paths: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut stack: Vec<(&ConfigNode, String)> =
+            root.children.iter().map(|child| (child, child.name.clone())).collect();
+
+        while let Some((node, path)) = stack.pop() {
+            let values = paths.entry(path.clone()).or_default();
+            values.extend(node.value.clone());
+            stack.extend(
+                node.children
+                    .iter()
+                    .map(|child| (child, format!("{} {}", path, child.name))),
+            );
+        }
+
+        Self { paths }
+    }
+
+    fn has(&self, path: &str) -> bool {
+        self.paths.contains_key(path)
+    }
+
+    fn values(&self, path: &str) -> &[String] {
+        self.paths.get(path).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Paths starting with a prefix, including the prefix itself
+    fn below<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        self.paths
+            .range(prefix.to_string()..)
+            .map(|(path, _)| path)
+            .take_while(move |path| path.starts_with(prefix))
+            .filter(move |path| path.len() == prefix.len() || path.as_bytes()[prefix.len()] == b' ')
+    }
+
+    /// Paths of the configured interfaces, such as `interfaces ethernet eth0`
+    fn interfaces(&self) -> Vec<&str> {
+        self.paths
+            .keys()
+            .filter(|path| path.starts_with("interfaces ") && path.split(' ').count() == 3)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Whether an interface gets its address, and with it a default route
+    /// and name servers, from DHCP
+    fn uses_dhcp(&self, interface: &str) -> bool {
+        self.values(&format!("{} address", interface))
+            .iter()
+            .any(|address| address == "dhcp" || address == "dhcpv6")
+    }
+
+    /// Whether an interface faces the internet: described as WAN or
+    /// addressed by DHCP
+    fn is_wan(&self, interface: &str) -> bool {
+        let described_wan = self
+            .values(&format!("{} description", interface))
+            .iter()
+            .any(|description| description.to_lowercase().contains("wan"));
+        described_wan || self.uses_dhcp(interface)
+    }
+
+    fn unprotected_wan_interfaces(&self) -> Vec<String> {
+        if GLOBAL_FIREWALL_CHAINS.iter().any(|chain| self.below(chain).count() > 1) {
+            return Vec::new();
+        }
+
+        self.interfaces()
+            .into_iter()
+            .filter(|interface| self.is_wan(interface))
+            .filter(|interface| {
+                let name = interface.rsplit(' ').next().unwrap_or(interface);
+                let own_firewall = self.has(&format!("{} firewall", interface));
+                let referenced = self.below("firewall").any(|path| {
+                    path.split(' ').any(|part| part == name) || self.values(path).iter().any(|value| value == name)
+                });
+                !own_firewall && !referenced
+            })
+            .map(|interface| format!("WAN interface '{}' has no firewall", interface))
+            .collect()
+    }
+
+    fn ssh_open_to_any(&self) -> Vec<String> {
+        if !self.has("service ssh") {
+            return Vec::new();
+        }
+
+        let listen = self.values("service ssh listen-address");
+        if listen.is_empty() || listen.iter().any(|address| ANY_ADDRESSES.contains(&address.as_str())) {
+            vec!["SSH is reachable on every address (0.0.0.0/0); set service ssh listen-address".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn missing_default_route(&self) -> Vec<String> {
+        let static_route = DEFAULT_ROUTES.iter().any(|prefix| {
+            self.has(&format!("protocols static route {}", prefix))
+                || self.has(&format!("protocols static route6 {}", prefix))
+        });
+        let dhcp = self.interfaces().into_iter().any(|interface| self.uses_dhcp(interface));
+
+        if static_route || dhcp {
+            Vec::new()
+        } else {
+            vec!["No default route is configured".to_string()]
+        }
+    }
+
+    fn missing_name_server(&self) -> Vec<String> {
+        let name_server =
+            !self.values("system name-server").is_empty() || self.below("system name-server").count() > 1;
+        let dhcp = self.interfaces().into_iter().any(|interface| self.uses_dhcp(interface));
+
+        if name_server || dhcp {
+            Vec::new()
+        } else {
+            vec!["No DNS name server is configured".to_string()]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::config::{ConfigMetadata, ConfigNodeType};
+
+    /// Configuration tree from `path` or `path=value` lines
+    fn tree(lines: &[&str]) -> ConfigNode {
+        let mut root = node("root", None);
+        for line in lines {
+            let (path, value) = match line.split_once('=') {
+                Some((path, value)) => (path, Some(value)),
+                None => (*line, None),
+            };
+            let names: Vec<&str> = path.split(' ').collect();
+            let mut parent = &mut root;
+            for (depth, name) in names.iter().enumerate() {
+                let is_leaf = depth == names.len() - 1 && value.is_some();
+                let index = match parent.children.iter().position(|child| child.name == *name) {
+                    Some(index) if !is_leaf => index,
+                    _ => {
+                        parent.children.push(node(name, if is_leaf { value } else { None }));
+                        parent.children.len() - 1
+                    }
+                };
+                parent = &mut parent.children[index];
+            }
+        }
+        root
+    }
+
+    fn node(name: &str, value: Option<&str>) -> ConfigNode {
+        let now = chrono::Utc::now();
+        ConfigNode {
+            id: uuid::Uuid::new_v4(),
+            path: name.to_string(),
+            name: name.to_string(),
+            value: value.map(str::to_string),
+            node_type: if value.is_some() { ConfigNodeType::Leaf } else { ConfigNodeType::Container },
+            description: None,
+            children: vec![],
+            metadata: ConfigMetadata {
+                is_readonly: false,
+                is_required: false,
+                default_value: None,
+                validation: None,
+                help_text: None,
+            },
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn rules_broken(linter: &ConfigLinter, lines: &[&str]) -> Vec<String> {
+        linter
+            .lint(&tree(lines))
+            .into_iter()
+            .map(|warning| {
+                let rule = warning.rsplit("lint rule ").next().unwrap_or_default();
+                rule.trim_end_matches(')').to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_empty_config() {
+        assert_eq!(
+            rules_broken(&ConfigLinter::default(), &[]),
+            vec!["default-route", "dns-unset"]
+        );
+    }
+
+    #[test]
+    fn test_lint_dhcp_wan_without_firewall() {
+        let linter = ConfigLinter::default();
+        let config = [
+            "interfaces ethernet eth0 address=dhcp",
+            "interfaces ethernet eth1 address=192.168.1.1/24",
+            "interfaces ethernet eth1 description=LAN",
+            "service ssh port=22",
+        ];
+        assert_eq!(rules_broken(&linter, &config), vec!["wan-firewall", "ssh-open-to-any"]);
+
+        let warnings = linter.lint(&tree(&config));
+        assert!(warnings[0].contains("interfaces ethernet eth0"));
+
+        let mut protected = config.to_vec();
+        protected.push("firewall ipv4 input filter rule 10 action=accept");
+        protected.push("service ssh listen-address=192.168.1.1");
+        assert!(rules_broken(&linter, &protected).is_empty());
+    }
+
+    #[test]
+    fn test_lint_static_wan() {
+        let linter = ConfigLinter::default();
+        let config = [
+            "interfaces ethernet eth0 address=203.0.113.2/30",
+            "interfaces ethernet eth0 description=Uplink WAN",
+            "interfaces ethernet eth0 firewall in name=WAN_IN",
+            "protocols static route 0.0.0.0/0 next-hop 203.0.113.1",
+            "system name-server=192.0.2.53",
+            "system name-server=192.0.2.54",
+        ];
+        assert!(rules_broken(&linter, &config).is_empty());
+
+        assert_eq!(
+            rules_broken(&linter, &config[..2]),
+            vec!["wan-firewall", "default-route", "dns-unset"]
+        );
+    }
+
+    #[test]
+    fn test_lint_disabled_rules() {
+        let linter = ConfigLinter::new([ConfigLintRule::DefaultRoute, ConfigLintRule::DnsUnset].into());
+        assert!(linter.lint(&tree(&[])).is_empty());
+        assert_eq!(rules_broken(&linter, &["service ssh listen-address=0.0.0.0"]), vec!["ssh-open-to-any"]);
+    }
+
+    #[test]
+    fn test_lint_rule_names_round_trip() {
+        for rule in ConfigLintRule::ALL {
+            assert_eq!(rule.as_str().parse::<ConfigLintRule>(), Ok(rule));
+        }
+        assert!("no-such-rule".parse::<ConfigLintRule>().is_err());
+    }
+}
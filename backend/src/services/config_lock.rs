@@ -0,0 +1,267 @@
+//! Configuration Lock Service
+//!
+//! Keeps configuration subtrees such as `protocols bgp` in the hands of the
+//! team that owns them. [`ConfigService`](crate::services::ConfigService)
+//! checks every set and delete against the locks before applying it.
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::config_lock::{ConfigLock, ConfigLockListResponse, CreateConfigLockRequest};
+use crate::models::team::TeamRole;
+use crate::models::timestamp::{db_now, parse_db_timestamp};
+
+/// Configuration lock service
+#[derive(Clone)]
+pub struct ConfigLockService {
+    db: Database,
+}
+
+impl ConfigLockService {
+    /// Create a new configuration lock service
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List all locks
+    pub async fn list(&self) -> Result<ConfigLockListResponse, AppError> {
+        Ok(ConfigLockListResponse {
+            locks: self.all_locks().await?,
+        })
+    }
+
+    /// Lock a configuration subtree
+    pub async fn create(&self, request: CreateConfigLockRequest, claims: &Claims) -> Result<ConfigLock, AppError> {
+        let segments = path_segments(&request.path);
+        if segments.is_empty() {
+            return Err(AppError::Validation("Lock path must not be empty".to_string()));
+        }
+        let team_name = match request.team_id {
+            Some(team_id) => Some(
+                self.db
+                    .find_team_by_id(&team_id.to_string())
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(format!("Team {} not found", team_id)))?
+                    .name,
+            ),
+            None => None,
+        };
+
+        let lock = ConfigLock {
+            id: Uuid::new_v4(),
+            path: segments.join(" "),
+            team_id: request.team_id,
+            team_name,
+            role: request.role,
+            description: request.description.filter(|d| !d.trim().is_empty()),
+            created_by: Some(claims.username.clone()),
+            created_at: Utc::now(),
+        };
+
+        sqlx::query(
+            "INSERT INTO config_locks (id, path, team_id, role, description, created_by, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(lock.id.to_string())
+        .bind(&lock.path)
+        .bind(lock.team_id.map(|id| id.to_string()))
+        .bind(lock.role.map(|role| role.as_str()))
+        .bind(&lock.description)
+        .bind(&lock.created_by)
+        .bind(db_now())
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(lock)
+    }
+
+    /// Remove a lock
+    pub async fn delete(&self, lock_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM config_locks WHERE id = ?")
+            .bind(lock_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Config lock {} not found", lock_id)));
+        }
+
+        Ok(())
+    }
+
+    /// Check that a user may modify the configuration at a path
+    ///
+    /// Setting a value is covered by the locks on the path and its parents.
+    /// Deleting a path also removes everything below it, so it is further
+    /// covered by the locks inside the deleted subtree. Anonymous callers
+    /// may not modify any locked path.
+    pub async fn ensure_can_modify(&self, claims: Option<&Claims>, path: &str, delete: bool) -> Result<(), AppError> {
+        let segments = path_segments(path);
+        let locks: Vec<ConfigLock> = self
+            .all_locks()
+            .await?
+            .into_iter()
+            .filter(|lock| covers(&path_segments(&lock.path), &segments, delete))
+            .collect();
+        if locks.is_empty() {
+            return Ok(());
+        }
+
+        let user = match claims {
+            Some(claims) => self.db.find_user_by_id(&claims.sub).await?,
+            None => None,
+        };
+        let Some(user) = user else {
+            return Err(locked(&locks[0], path));
+        };
+        if user.is_superuser {
+            return Ok(());
+        }
+
+        let memberships: Vec<(String, String)> =
+            sqlx::query_as("SELECT team_id, role FROM team_members WHERE user_id = ?")
+                .bind(&user.id)
+                .fetch_all(self.db.pool())
+                .await?;
+        let memberships: Vec<(Uuid, TeamRole)> = memberships
+            .into_iter()
+            .filter_map(|(team_id, role)| Some((Uuid::parse_str(&team_id).ok()?, TeamRole::parse(&role))))
+            .collect();
+
+        match locks.iter().find(|lock| !holds(lock, &memberships)) {
+            Some(lock) => Err(locked(lock, path)),
+            None => Ok(()),
+        }
+    }
+
+    async fn all_locks(&self) -> Result<Vec<ConfigLock>, AppError> {
+        let rows: Vec<ConfigLockRow> = sqlx::query_as(
+            "SELECT l.id, l.path, l.team_id, t.name, l.role, l.description, l.created_by, l.created_at \
+             FROM config_locks l LEFT JOIN teams t ON t.id = l.team_id ORDER BY l.path, l.created_at",
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows.into_iter().map(lock_from_row).collect())
+    }
+}
+
+/// Lock columns as selected by [`ConfigLockService::all_locks`]
+type ConfigLockRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+);
+
+fn lock_from_row(
+    (id, path, team_id, team_name, role, description, created_by, created_at): ConfigLockRow,
+) -> ConfigLock {
+    ConfigLock {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        path,
+        team_id: team_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        team_name,
+        role: role.as_deref().map(TeamRole::parse),
+        description,
+        created_by,
+        created_at: parse_db_timestamp(&created_at),
+    }
+}
+
+/// Words of a configuration path written with spaces or slashes
+///
+/// `protocols bgp`, `/protocols/bgp/`, and `protocols/bgp` are the same path.
+pub(crate) fn path_segments(path: &str) -> Vec<&str> {
+    path.split(|c: char| c == '/' || c.is_whitespace())
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// Whether a change at `path` falls under a lock on `locked`
+fn covers(locked: &[&str], path: &[&str], delete: bool) -> bool {
+    path.starts_with(locked) || (delete && locked.starts_with(path))
+}
+
+/// Whether a user with the given team memberships holds a lock
+fn holds(lock: &ConfigLock, memberships: &[(Uuid, TeamRole)]) -> bool {
+    let role_suffices = |held: TeamRole| match lock.role {
+        None | Some(TeamRole::Member) => true,
+        Some(TeamRole::Maintainer) => held == TeamRole::Maintainer,
+    };
+
+    match (lock.team_id, lock.role) {
+        (Some(team_id), _) => memberships
+            .iter()
+            .any(|(team, held)| *team == team_id && role_suffices(*held)),
+        (None, Some(_)) => memberships.iter().any(|(_, held)| role_suffices(*held)),
+        (None, None) => false,
+    }
+}
+
+fn locked(lock: &ConfigLock, path: &str) -> AppError {
+    let holder = match (&lock.team_name, lock.role) {
+        (Some(team), Some(role)) => format!("{}s of team {}", role.as_str(), team),
+        (Some(team), None) => format!("team {}", team),
+        (None, Some(role)) => format!("team {}s", role.as_str()),
+        (None, None) => "administrators".to_string(),
+    };
+    AppError::Forbidden(format!(
+        "Configuration at '{}' is locked by '{}' to {}",
+        path.trim(),
+        lock.path,
+        holder
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock(team_id: Option<Uuid>, role: Option<TeamRole>) -> ConfigLock {
+        ConfigLock {
+            id: Uuid::nil(),
+            path: "protocols bgp".to_string(),
+            team_id,
+            team_name: None,
+            role,
+            description: None,
+            created_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_covers() {
+        let locked = path_segments("protocols bgp");
+        assert_eq!(path_segments("/protocols/bgp/ system-as"), vec!["protocols", "bgp", "system-as"]);
+        assert!(covers(&locked, &path_segments("protocols bgp system-as 65000"), false));
+        assert!(covers(&locked, &path_segments("/protocols/bgp"), false));
+        assert!(!covers(&locked, &path_segments("protocols bgpx"), false));
+        assert!(!covers(&locked, &path_segments("protocols"), false));
+        assert!(covers(&locked, &path_segments("protocols"), true));
+        assert!(!covers(&locked, &path_segments("protocols ospf"), true));
+    }
+
+    #[test]
+    fn test_holds() {
+        let core = Uuid::from_u128(1);
+        let edge = Uuid::from_u128(2);
+        let member_of_core = [(core, TeamRole::Member)];
+        let maintainer_of_edge = [(edge, TeamRole::Maintainer)];
+
+        assert!(holds(&lock(Some(core), None), &member_of_core));
+        assert!(!holds(&lock(Some(core), None), &maintainer_of_edge));
+        assert!(!holds(&lock(Some(core), Some(TeamRole::Maintainer)), &member_of_core));
+        assert!(holds(&lock(None, Some(TeamRole::Maintainer)), &maintainer_of_edge));
+        assert!(!holds(&lock(None, Some(TeamRole::Maintainer)), &member_of_core));
+        assert!(!holds(&lock(None, None), &maintainer_of_edge));
+    }
+}
@@ -0,0 +1,293 @@
+//! Configuration Secrets
+//!
+//! Router configuration holds secrets such as pre-shared keys, SNMP
+//! communities, and password hashes. The [`ConfigSecretPolicy`] names them
+//! by configuration path (`CONFIG_SECRET_PATHS`, where `*` matches any
+//! single word) and:
+//!
+//! - seals them in stored snapshots and backups, encrypted with AES-256-GCM
+//!   under `CONFIG_SECRETS_KEY`, and unseals them when the snapshot is read
+//!   back; without a key they are stored in the clear
+//! - redacts them from configuration served to users without the
+//!   `secrets:read` permission
+//!
+//! The value at a secret path is secret as a whole. For a tag node such as
+//! `service snmp community`, that includes the tag values, so the whole
+//! subtree is replaced.
+
+use std::sync::Arc;
+
+use base64ct::{Base64, Encoding};
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::vyos_client::ConfigOperation;
+
+/// Replacement for redacted secrets
+pub const REDACTED_SECRET: &str = "<redacted>";
+
+/// Prefix of sealed secret values
+const SEALED_PREFIX: &str = "sealed:v1:";
+
+/// Length of the AES-GCM nonce, in bytes
+const NONCE_LEN: usize = 12;
+
+/// Length of the AES-GCM authentication tag, in bytes
+const TAG_LEN: usize = 16;
+
+/// Which configuration values are secrets, and how they are protected
+#[derive(Clone)]
+pub struct ConfigSecretPolicy {
+    paths: Arc<Vec<Vec<String>>>,
+    key: Option<[u8; 32]>,
+}
+
+impl ConfigSecretPolicy {
+    /// Create the policy of the configured secret paths and key
+    pub fn new(config: &AppConfig) -> Self {
+        let paths = config
+            .config_secret_paths
+            .iter()
+            .map(|path| path.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+            .filter(|words| !words.is_empty())
+            .collect();
+        let key = config
+            .config_secrets_key
+            .as_ref()
+            .map(|key| Sha256::digest(key.as_bytes()).into());
+        Self { paths: Arc::new(paths), key }
+    }
+
+    /// Whether secrets are encrypted in stored configuration
+    pub fn seals(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Number of leading words of a path that name a secret, if the path
+    /// is, or is below, a secret path
+    fn secret_depth(&self, path: &[impl AsRef<str>]) -> Option<usize> {
+        self.paths
+            .iter()
+            .filter(|pattern| {
+                pattern.len() <= path.len()
+                    && pattern
+                        .iter()
+                        .zip(path)
+                        .all(|(word, segment)| word == "*" || word == segment.as_ref())
+            })
+            .map(Vec::len)
+            .min()
+    }
+
+    /// Replace the secrets in the configuration found under `path`
+    pub fn redact(&self, path: &[&str], config: &mut Value) {
+        let mut path: Vec<String> = path.iter().map(|word| word.to_string()).collect();
+        let _ = self.visit(&mut path, config, &mut |secret| {
+            *secret = Value::String(REDACTED_SECRET.to_string());
+            Ok(())
+        });
+    }
+
+    /// Replace the words of a path below a secret path, such as the value
+    /// of a set operation, returning whether the path is secret
+    pub fn redact_words(&self, path: &mut [String]) -> bool {
+        let Some(depth) = self.secret_depth(path) else {
+            return false;
+        };
+        for word in path.iter_mut().skip(depth) {
+            *word = REDACTED_SECRET.to_string();
+        }
+        true
+    }
+
+    /// Replace the secret words of configuration operations
+    pub fn redact_operations(&self, operations: &mut [ConfigOperation]) {
+        for operation in operations {
+            self.redact_words(&mut operation.path);
+        }
+    }
+
+    /// Encrypt the secrets of a full configuration for storage
+    ///
+    /// Leaves the configuration as it is without a key.
+    pub fn seal(&self, config: &mut Value) -> Result<(), AppError> {
+        let Some(key) = &self.key else {
+            return Ok(());
+        };
+        self.visit(&mut Vec::new(), config, &mut |secret| {
+            *secret = Value::String(seal_value(key, secret)?);
+            Ok(())
+        })
+    }
+
+    /// Decrypt the sealed secrets of a stored configuration
+    pub fn unseal(&self, config: &mut Value) -> Result<(), AppError> {
+        match config {
+            Value::String(text) => {
+                if let Some(sealed) = text.strip_prefix(SEALED_PREFIX) {
+                    let key = self.key.as_ref().ok_or_else(|| {
+                        AppError::Config("CONFIG_SECRETS_KEY is required to read stored configuration secrets".to_string())
+                    })?;
+                    *config = unseal_value(key, sealed)?;
+                }
+            }
+            Value::Array(values) => {
+                for value in values {
+                    self.unseal(value)?;
+                }
+            }
+            Value::Object(children) => {
+                for child in children.values_mut() {
+                    self.unseal(child)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Apply `protect` to each secret in the configuration found under
+    /// `path`
+    fn visit(
+        &self,
+        path: &mut Vec<String>,
+        config: &mut Value,
+        protect: &mut impl FnMut(&mut Value) -> Result<(), AppError>,
+    ) -> Result<(), AppError> {
+        if self.secret_depth(path).is_some() {
+            return protect(config);
+        }
+        if let Value::Object(children) = config {
+            for (name, child) in children.iter_mut() {
+                path.push(name.clone());
+                let result = self.visit(path, child, protect);
+                path.pop();
+                result?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Encrypt a secret value, with a random nonce, as a sealed string
+fn seal_value(key: &[u8; 32], value: &Value) -> Result<String, AppError> {
+    let failed = |e: openssl::error::ErrorStack| AppError::Internal(format!("Failed to seal a secret: {}", e));
+
+    let mut nonce = [0u8; NONCE_LEN];
+    openssl::rand::rand_bytes(&mut nonce).map_err(failed)?;
+    let mut tag = [0u8; TAG_LEN];
+    let plaintext = serde_json::to_vec(value)?;
+    let ciphertext = encrypt_aead(Cipher::aes_256_gcm(), key, Some(&nonce), &[], &plaintext, &mut tag)
+        .map_err(failed)?;
+
+    let sealed = [nonce.as_slice(), &ciphertext, &tag].concat();
+    Ok(format!("{}{}", SEALED_PREFIX, Base64::encode_string(&sealed)))
+}
+
+/// Decrypt a sealed string, without its prefix, back into the secret value
+fn unseal_value(key: &[u8; 32], sealed: &str) -> Result<Value, AppError> {
+    let invalid = || AppError::Internal("Stored configuration secret cannot be decrypted".to_string());
+
+    let sealed = Base64::decode_vec(sealed).map_err(|_| invalid())?;
+    if sealed.len() < NONCE_LEN + TAG_LEN {
+        return Err(invalid());
+    }
+    let (nonce, rest) = sealed.split_at(NONCE_LEN);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+    let plaintext =
+        decrypt_aead(Cipher::aes_256_gcm(), key, Some(nonce), &[], ciphertext, tag).map_err(|_| invalid())?;
+
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(key: Option<&str>) -> ConfigSecretPolicy {
+        ConfigSecretPolicy {
+            paths: Arc::new(
+                ["vpn ipsec authentication psk * secret", "service snmp community"]
+                    .iter()
+                    .map(|path| path.split(' ').map(str::to_string).collect())
+                    .collect(),
+            ),
+            key: key.map(|key| Sha256::digest(key.as_bytes()).into()),
+        }
+    }
+
+    fn config() -> Value {
+        json!({
+            "service": {"snmp": {"community": {"public": {"authorization": "ro"}}, "location": "rack 4"}},
+            "vpn": {"ipsec": {"authentication": {"psk": {"peer1": {"id": ["a", "b"], "secret": "hunter2"}}}}}
+        })
+    }
+
+    #[test]
+    fn test_redact_replaces_secret_values_and_subtrees() {
+        let mut config = config();
+        policy(None).redact(&[], &mut config);
+
+        assert_eq!(config["vpn"]["ipsec"]["authentication"]["psk"]["peer1"]["secret"], REDACTED_SECRET);
+        assert_eq!(config["vpn"]["ipsec"]["authentication"]["psk"]["peer1"]["id"], json!(["a", "b"]));
+        assert_eq!(config["service"]["snmp"]["community"], REDACTED_SECRET);
+        assert_eq!(config["service"]["snmp"]["location"], "rack 4");
+
+        // A subtree is matched by its full path
+        let mut subtree = json!({"public": {"authorization": "ro"}});
+        policy(None).redact(&["service", "snmp", "community"], &mut subtree);
+        assert_eq!(subtree, REDACTED_SECRET);
+    }
+
+    #[test]
+    fn test_redact_operations_hides_secret_words() {
+        let mut operations = vec![
+            ConfigOperation::set(
+                ["vpn", "ipsec", "authentication", "psk", "peer1", "secret", "hunter2"].map(String::from).to_vec(),
+            ),
+            ConfigOperation::set(["service", "snmp", "community", "public"].map(String::from).to_vec()),
+            ConfigOperation::set(["service", "snmp", "location", "rack 4"].map(String::from).to_vec()),
+        ];
+        policy(None).redact_operations(&mut operations);
+
+        assert_eq!(operations[0].path[6], REDACTED_SECRET);
+        assert_eq!(operations[0].path[4], "peer1");
+        assert_eq!(operations[1].path[3], REDACTED_SECRET);
+        assert_eq!(operations[2].path[3], "rack 4");
+    }
+
+    #[test]
+    fn test_seal_round_trips() {
+        let policy = policy(Some("key"));
+        let mut config = config();
+        policy.seal(&mut config).unwrap();
+
+        let sealed = config["vpn"]["ipsec"]["authentication"]["psk"]["peer1"]["secret"].as_str().unwrap();
+        assert!(sealed.starts_with(SEALED_PREFIX));
+        assert!(!config.to_string().contains("hunter2"));
+        assert!(!config.to_string().contains("public"));
+
+        policy.unseal(&mut config).unwrap();
+        assert_eq!(config, self::config());
+    }
+
+    #[test]
+    fn test_unseal_needs_the_right_key() {
+        let mut config = config();
+        policy(Some("key")).seal(&mut config).unwrap();
+
+        assert!(policy(Some("other")).unseal(&mut config.clone()).is_err());
+        assert!(policy(None).unseal(&mut config).is_err());
+    }
+
+    #[test]
+    fn test_seal_without_key_keeps_secrets() {
+        let mut config = config();
+        policy(None).seal(&mut config).unwrap();
+        assert_eq!(config, self::config());
+    }
+}
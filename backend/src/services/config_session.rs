@@ -0,0 +1,1134 @@
+//! Configuration Sessions
+//!
+//! A session stages set and delete operations against one node and commits
+//! them to the node as a single change. A commit may ask for confirmation,
+//! like VyOS's `commit-confirm`: the configuration it replaces is kept, and
+//! unless the operator confirms within the timeout, the commit is rolled
+//! back. The timer runs in the backend, so this works on every VyOS release;
+//! [`ConfigSessionService::resume`] picks up pending commits after a
+//! restart.
+//!
+//! While a commit awaits confirmation, no other session may commit to the
+//! node, so the rollback never undoes someone else's change.
+//!
+//! A session may also be scheduled to be committed later, say at 02:00.
+//! Its operations are checked and previewed against the node's
+//! configuration when it is scheduled, and [`ConfigSessionService::run_scheduled`]
+//! commits it once due, unless the node is not online, a change freeze is in
+//! effect, or the configuration the operations change was changed in the
+//! meantime. Until then, the schedule can be cancelled.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::ConfigReloader;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::config_session::{
+    CommitConfigSessionRequest, ConfigChangeEffect, ConfigChangePreview, ConfigSession, ConfigSessionListQuery,
+    ConfigSessionListResponse, ConfigSessionStatus, ScheduleConfigSessionRequest, StagedOperation,
+    MAX_CONFIRM_TIMEOUT_SECS, MAX_SCHEDULE_AHEAD_DAYS, MAX_SESSION_OPERATIONS,
+};
+use crate::models::node::NodeStatus;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::services::audit::AuditService;
+use crate::services::circuit_breaker::NodeCircuitBreaker;
+use crate::services::config_lock::{path_segments, ConfigLockService};
+use crate::services::node_service::NodeService;
+use crate::vyos_client::{ConfigOperation, ConfigOperationKind};
+
+/// Delay before retrying the rollback of a node that could not be reached
+const ROLLBACK_RETRY_SECS: u64 = 30;
+
+/// Most sessions returned by a list
+const SESSION_LIST_LIMIT: i64 = 200;
+
+/// Configuration of a path before a commit changed it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RestorePoint {
+    path: Vec<String>,
+    /// `None` if the path did not exist
+    config: Option<Value>,
+}
+
+/// Change to one node, checked to be applied together with changes to
+/// other nodes by [`ConfigSessionService::apply_changes`]
+pub(crate) struct NodeChange {
+    pub(crate) node_id: Uuid,
+    operations: Vec<StagedOperation>,
+    restore: Vec<RestorePoint>,
+}
+
+/// Why a change to several nodes failed
+#[derive(Debug)]
+pub(crate) struct NodeChangeFailure {
+    /// Node whose change failed to apply
+    pub(crate) node_id: Uuid,
+    pub(crate) error: AppError,
+    /// Why the nodes changed before it could not be rolled back
+    pub(crate) rollback_error: Option<AppError>,
+}
+
+/// Configuration session service
+#[derive(Clone)]
+pub struct ConfigSessionService {
+    db: Database,
+    nodes: NodeService,
+    locks: ConfigLockService,
+    audit: AuditService,
+    /// Source of the change freeze windows in effect
+    reloader: ConfigReloader,
+}
+
+impl ConfigSessionService {
+    /// Create a new configuration session service
+    pub fn new(
+        db: Database,
+        nodes: NodeService,
+        locks: ConfigLockService,
+        audit: AuditService,
+        reloader: ConfigReloader,
+    ) -> Self {
+        Self {
+            db,
+            nodes,
+            locks,
+            audit,
+            reloader,
+        }
+    }
+
+    /// Open a session against a node
+    pub async fn open(&self, node_id: Uuid, claims: &Claims) -> Result<ConfigSession, AppError> {
+        let id = Uuid::new_v4();
+        let now = db_now();
+        sqlx::query(
+            "INSERT INTO config_sessions (id, node_id, user_id, username, status, operations, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, '[]', ?, ?)",
+        )
+        .bind(id.to_string())
+        .bind(node_id.to_string())
+        .bind(&claims.sub)
+        .bind(&claims.username)
+        .bind(ConfigSessionStatus::Open.as_str())
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await?;
+
+        info!("{} opened configuration session {} on node {}", claims.username, id, node_id);
+        self.get(id).await
+    }
+
+    /// Get a session
+    pub async fn get(&self, session_id: Uuid) -> Result<ConfigSession, AppError> {
+        let row: Option<ConfigSessionRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM config_sessions WHERE id = ?",
+            SESSION_COLUMNS
+        ))
+        .bind(session_id.to_string())
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(session_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Configuration session {} not found", session_id)))
+    }
+
+    /// List sessions, newest first
+    ///
+    /// Only open sessions, scheduled commits, and commits awaiting
+    /// confirmation are listed unless `all` is set.
+    pub async fn list(&self, query: &ConfigSessionListQuery) -> Result<ConfigSessionListResponse, AppError> {
+        let rows: Vec<ConfigSessionRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM config_sessions \
+             WHERE (? IS NULL OR node_id = ?) AND (? OR status IN ('open', 'scheduled', 'pending_confirm')) \
+             ORDER BY created_at DESC LIMIT ?",
+            SESSION_COLUMNS
+        ))
+        .bind(query.node_id.map(|id| id.to_string()))
+        .bind(query.node_id.map(|id| id.to_string()))
+        .bind(query.all)
+        .bind(SESSION_LIST_LIMIT)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(ConfigSessionListResponse {
+            sessions: rows.into_iter().map(session_from_row).collect(),
+        })
+    }
+
+    /// Whether a commit to a node awaits confirmation
+    pub async fn awaiting_confirmation(&self, node_id: Uuid) -> Result<bool, AppError> {
+        let pending: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM config_sessions WHERE node_id = ? AND status = 'pending_confirm'",
+        )
+        .bind(node_id.to_string())
+        .fetch_one(self.db.pool())
+        .await?;
+        Ok(pending > 0)
+    }
+
+    /// Stage operations in an open session
+    ///
+    /// Operations on paths locked to someone else are rejected.
+    pub async fn stage(
+        &self,
+        session_id: Uuid,
+        operations: Vec<StagedOperation>,
+        claims: &Claims,
+    ) -> Result<ConfigSession, AppError> {
+        let mut session = self.owned_session(session_id, claims).await?;
+        ensure_status(&session, ConfigSessionStatus::Open)?;
+        if operations.is_empty() {
+            return Err(AppError::Validation("No operations to stage".to_string()));
+        }
+        if session.operations.len() + operations.len() > MAX_SESSION_OPERATIONS {
+            return Err(AppError::Validation(format!(
+                "A session holds at most {} operations",
+                MAX_SESSION_OPERATIONS
+            )));
+        }
+        for operation in &operations {
+            validate_operation(operation)?;
+            self.ensure_can_apply(operation, claims).await?;
+        }
+
+        session.operations.extend(operations);
+        let updated = sqlx::query(
+            "UPDATE config_sessions SET operations = ?, updated_at = ? WHERE id = ? AND status = 'open'",
+        )
+        .bind(serde_json::to_string(&session.operations)?)
+        .bind(db_now())
+        .bind(session_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(not_in_status(session_id, ConfigSessionStatus::Open));
+        }
+
+        self.get(session_id).await
+    }
+
+    /// Commit the staged operations to the node
+    ///
+    /// With a confirm timeout, the configuration the commit replaces is kept
+    /// and restored unless [`confirm`](Self::confirm) is called in time.
+    pub async fn commit(
+        &self,
+        session_id: Uuid,
+        request: CommitConfigSessionRequest,
+        claims: &Claims,
+    ) -> Result<ConfigSession, AppError> {
+        let session = self.owned_session(session_id, claims).await?;
+        ensure_status(&session, ConfigSessionStatus::Open)?;
+        if session.operations.is_empty() {
+            return Err(AppError::Validation("The session has no staged operations".to_string()));
+        }
+        let confirm_timeout = match request.confirm_timeout_secs {
+            Some(secs) if secs == 0 || secs > MAX_CONFIRM_TIMEOUT_SECS => {
+                return Err(AppError::Validation(format!(
+                    "Confirm timeout must be between 1 and {} seconds",
+                    MAX_CONFIRM_TIMEOUT_SECS
+                )));
+            }
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => None,
+        };
+        // Locks may have changed since the operations were staged
+        for operation in &session.operations {
+            self.ensure_can_apply(operation, claims).await?;
+        }
+
+        let operations = to_config_operations(&session.operations);
+        let restore = match confirm_timeout {
+            Some(_) => {
+                let current = self.current_config(session.node_id).await?;
+                Some(restore_points(&current, &session.operations))
+            }
+            None => None,
+        };
+        let confirm_by = confirm_timeout
+            .map(|timeout| Utc::now() + chrono::Duration::from_std(timeout).unwrap_or_default());
+        let status = if confirm_by.is_some() {
+            ConfigSessionStatus::PendingConfirm
+        } else {
+            ConfigSessionStatus::Committed
+        };
+
+        // Claim the session and the node before touching the router
+        let claimed = sqlx::query(
+            "UPDATE config_sessions SET status = ?, comment = ?, restore = ?, confirm_by = ?, updated_at = ? \
+             WHERE id = ? AND status = 'open' AND NOT EXISTS \
+             (SELECT 1 FROM config_sessions WHERE node_id = ? AND status = 'pending_confirm')",
+        )
+        .bind(status.as_str())
+        .bind(request.comment.as_deref().map(str::trim).filter(|c| !c.is_empty()))
+        .bind(restore.as_ref().map(serde_json::to_string).transpose()?)
+        .bind(confirm_by.as_ref().map(format_timestamp))
+        .bind(db_now())
+        .bind(session_id.to_string())
+        .bind(session.node_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+        if claimed.rows_affected() == 0 {
+            let session = self.get(session_id).await?;
+            ensure_status(&session, ConfigSessionStatus::Open)?;
+            return Err(AppError::Validation(format!(
+                "A commit to node {} is awaiting confirmation",
+                session.node_id
+            )));
+        }
+
+        if let Err(e) = self.nodes.configure_node(session.node_id, &operations).await {
+            warn!("Failed to commit configuration session {}: {}", session_id, e);
+            self.fail(session_id, &e.to_string()).await?;
+            return Err(e);
+        }
+
+        match confirm_by {
+            Some(deadline) => {
+                info!(
+                    "Committed configuration session {} to node {}, to be confirmed by {}",
+                    session_id,
+                    session.node_id,
+                    format_timestamp(&deadline)
+                );
+                self.schedule_rollback(session_id, deadline);
+            }
+            None => info!("Committed configuration session {} to node {}", session_id, session.node_id),
+        }
+        self.get(session_id).await
+    }
+
+    /// Schedule the staged operations to be committed at a later time
+    ///
+    /// The operations are checked like for a commit, and their effect on
+    /// the node's configuration now is kept with the session, along with
+    /// what they replace, so the scheduler can tell whether the
+    /// configuration changed before the commit is due.
+    pub async fn schedule(
+        &self,
+        session_id: Uuid,
+        request: ScheduleConfigSessionRequest,
+        claims: &Claims,
+    ) -> Result<ConfigSession, AppError> {
+        let session = self.owned_session(session_id, claims).await?;
+        ensure_status(&session, ConfigSessionStatus::Open)?;
+        if session.operations.is_empty() {
+            return Err(AppError::Validation("The session has no staged operations".to_string()));
+        }
+        let now = Utc::now();
+        if request.apply_at <= now || request.apply_at > now + chrono::Duration::days(MAX_SCHEDULE_AHEAD_DAYS) {
+            return Err(AppError::Validation(format!(
+                "A commit can be scheduled up to {} days ahead",
+                MAX_SCHEDULE_AHEAD_DAYS
+            )));
+        }
+        let config = self.reloader.current();
+        if let Some(window) = config.change_freeze_windows.iter().find(|window| window.contains(request.apply_at)) {
+            return Err(AppError::Validation(format!(
+                "{} falls in the change freeze from {} to {}",
+                format_timestamp(&request.apply_at),
+                format_timestamp(&window.starts_at),
+                format_timestamp(&window.ends_at)
+            )));
+        }
+        for operation in &session.operations {
+            self.ensure_can_apply(operation, claims).await?;
+        }
+
+        let current = self.current_config(session.node_id).await?;
+        let diff = preview(&current, &session.operations);
+        let restore = restore_points(&current, &session.operations);
+        let scheduled = sqlx::query(
+            "UPDATE config_sessions SET status = 'scheduled', comment = ?, apply_at = ?, diff = ?, restore = ?, \
+             updated_at = ? WHERE id = ? AND status = 'open'",
+        )
+        .bind(request.comment.as_deref().map(str::trim).filter(|c| !c.is_empty()))
+        .bind(format_timestamp(&request.apply_at))
+        .bind(serde_json::to_string(&diff)?)
+        .bind(serde_json::to_string(&restore)?)
+        .bind(db_now())
+        .bind(session_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+        if scheduled.rows_affected() == 0 {
+            return Err(not_in_status(session_id, ConfigSessionStatus::Open));
+        }
+
+        info!(
+            "{} scheduled configuration session {} on node {} for {}",
+            claims.username,
+            session_id,
+            session.node_id,
+            format_timestamp(&request.apply_at)
+        );
+        self.get(session_id).await
+    }
+
+    /// Cancel a scheduled commit, reopening the session so its operations
+    /// can be changed or scheduled again
+    pub async fn unschedule(&self, session_id: Uuid, claims: &Claims) -> Result<ConfigSession, AppError> {
+        self.owned_session(session_id, claims).await?;
+
+        let reopened = sqlx::query(
+            "UPDATE config_sessions SET status = 'open', apply_at = NULL, diff = NULL, restore = NULL, \
+             updated_at = ? WHERE id = ? AND status = 'scheduled'",
+        )
+        .bind(db_now())
+        .bind(session_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+        if reopened.rows_affected() == 0 {
+            return Err(not_in_status(session_id, ConfigSessionStatus::Scheduled));
+        }
+
+        info!("{} cancelled the scheduled commit of configuration session {}", claims.username, session_id);
+        self.get(session_id).await
+    }
+
+    /// Commit the scheduled sessions that are due, oldest first
+    ///
+    /// A commit whose checks fail is not retried: the session fails with the
+    /// reason as its error, since a change planned for a quiet hour should
+    /// not go out later in the day. Returns the number of sessions
+    /// committed.
+    pub async fn run_scheduled(&self) -> Result<usize, AppError> {
+        let due: Vec<(String,)> = sqlx::query_as(
+            "SELECT id FROM config_sessions WHERE status = 'scheduled' AND apply_at <= ? ORDER BY apply_at, id",
+        )
+        .bind(db_now())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut committed = 0;
+        for (id,) in due {
+            let Ok(session_id) = Uuid::parse_str(&id) else {
+                continue;
+            };
+            let session = self.get(session_id).await?;
+            let event = AuditEvent::new(None, "config.session.scheduled_commit", AuditResult::Success)
+                .with_node(session.node_id)
+                .with_target(session_id.to_string());
+            match self.commit_scheduled(&session).await {
+                Ok(true) => {
+                    committed += 1;
+                    self.audit
+                        .record(event.with_details(serde_json::json!({ "operations": session.operations.len() })))
+                        .await;
+                }
+                // Cancelled in the meantime
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("Scheduled commit of configuration session {} failed: {}", session_id, e);
+                    sqlx::query(
+                        "UPDATE config_sessions SET status = 'failed', error = ?, updated_at = ? \
+                         WHERE id = ? AND status IN ('scheduled', 'committed')",
+                    )
+                    .bind(format!("Scheduled commit failed: {}", e))
+                    .bind(db_now())
+                    .bind(session_id.to_string())
+                    .execute(self.db.pool())
+                    .await?;
+                    self.audit
+                        .record(
+                            AuditEvent { result: AuditResult::Failure, ..event }
+                                .with_details(serde_json::json!({ "error": e.to_string() })),
+                        )
+                        .await;
+                }
+            }
+        }
+        Ok(committed)
+    }
+
+    /// Check a due session and commit it; false if it is no longer scheduled
+    async fn commit_scheduled(&self, session: &ConfigSession) -> Result<bool, AppError> {
+        let now = Utc::now();
+        let config = self.reloader.current();
+        if let Some(window) = config.change_freeze_windows.iter().find(|window| window.contains(now)) {
+            return Err(AppError::Validation(format!(
+                "A change freeze is in effect until {}",
+                format_timestamp(&window.ends_at)
+            )));
+        }
+        let node = self
+            .nodes
+            .get_node(session.node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", session.node_id)))?;
+        if node.status != NodeStatus::Online {
+            return Err(AppError::Validation(format!(
+                "Node {} is {}",
+                node.name,
+                node.status.as_str()
+            )));
+        }
+
+        // Locks are checked as the user who scheduled the commit
+        let user_id: String = sqlx::query_scalar("SELECT user_id FROM config_sessions WHERE id = ?")
+            .bind(session.id.to_string())
+            .fetch_one(self.db.pool())
+            .await?;
+        let claims = Claims {
+            sub: user_id,
+            username: session.username.clone(),
+            exp: 0,
+            iat: 0,
+            sid: None,
+        };
+        for operation in &session.operations {
+            self.ensure_can_apply(operation, &claims).await?;
+        }
+
+        let restore: Option<String> = sqlx::query_scalar("SELECT restore FROM config_sessions WHERE id = ?")
+            .bind(session.id.to_string())
+            .fetch_one(self.db.pool())
+            .await?;
+        let restore: Vec<RestorePoint> = serde_json::from_str(restore.as_deref().unwrap_or("[]"))?;
+        let current = self.current_config(session.node_id).await?;
+        if restore_points(&current, &session.operations) != restore {
+            return Err(AppError::Validation(
+                "The configuration the operations change was changed since the commit was scheduled".to_string(),
+            ));
+        }
+
+        let claimed = sqlx::query(
+            "UPDATE config_sessions SET status = 'committed', restore = NULL, updated_at = ? \
+             WHERE id = ? AND status = 'scheduled' AND NOT EXISTS \
+             (SELECT 1 FROM config_sessions WHERE node_id = ? AND status = 'pending_confirm')",
+        )
+        .bind(db_now())
+        .bind(session.id.to_string())
+        .bind(session.node_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+        if claimed.rows_affected() == 0 {
+            if self.get(session.id).await?.status != ConfigSessionStatus::Scheduled {
+                return Ok(false);
+            }
+            return Err(AppError::Validation(format!(
+                "A commit to node {} is awaiting confirmation",
+                session.node_id
+            )));
+        }
+
+        self.nodes
+            .configure_node(session.node_id, &to_config_operations(&session.operations))
+            .await?;
+        info!("Committed scheduled configuration session {} to node {}", session.id, session.node_id);
+        Ok(true)
+    }
+
+    /// Confirm a commit, keeping it
+    pub async fn confirm(&self, session_id: Uuid, claims: &Claims) -> Result<ConfigSession, AppError> {
+        self.owned_session(session_id, claims).await?;
+
+        let confirmed = sqlx::query(
+            "UPDATE config_sessions SET status = 'confirmed', restore = NULL, error = NULL, updated_at = ? \
+             WHERE id = ? AND status = 'pending_confirm'",
+        )
+        .bind(db_now())
+        .bind(session_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+        if confirmed.rows_affected() == 0 {
+            return Err(not_in_status(session_id, ConfigSessionStatus::PendingConfirm));
+        }
+
+        info!("Confirmed configuration session {}", session_id);
+        self.get(session_id).await
+    }
+
+    /// Close an open session without committing it
+    pub async fn discard(&self, session_id: Uuid, claims: &Claims) -> Result<ConfigSession, AppError> {
+        self.owned_session(session_id, claims).await?;
+
+        let discarded = sqlx::query(
+            "UPDATE config_sessions SET status = 'discarded', updated_at = ? WHERE id = ? AND status = 'open'",
+        )
+        .bind(db_now())
+        .bind(session_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+        if discarded.rows_affected() == 0 {
+            return Err(not_in_status(session_id, ConfigSessionStatus::Open));
+        }
+
+        self.get(session_id).await
+    }
+
+    /// Schedule the rollback of every commit awaiting confirmation
+    ///
+    /// Called at startup; commits whose deadline passed while the backend
+    /// was down are rolled back right away.
+    pub async fn resume(&self) -> Result<(), AppError> {
+        let pending: Vec<(String, Option<String>)> =
+            sqlx::query_as("SELECT id, confirm_by FROM config_sessions WHERE status = 'pending_confirm'")
+                .fetch_all(self.db.pool())
+                .await?;
+
+        for (id, confirm_by) in pending {
+            let Ok(session_id) = Uuid::parse_str(&id) else {
+                continue;
+            };
+            let deadline = confirm_by.as_deref().map(parse_db_timestamp).unwrap_or_else(Utc::now);
+            info!("Resuming confirm timeout of configuration session {}", session_id);
+            self.schedule_rollback(session_id, deadline);
+        }
+        Ok(())
+    }
+
+    /// Roll a commit back at its deadline unless it is confirmed by then
+    fn schedule_rollback(&self, session_id: Uuid, deadline: DateTime<Utc>) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let wait = (deadline - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+            if let Err(e) = service.roll_back(session_id).await {
+                error!("Failed to roll back configuration session {}: {}", session_id, e);
+            }
+        });
+    }
+
+    /// Restore the configuration an unconfirmed commit replaced
+    ///
+    /// Does nothing if the commit was confirmed. A node that cannot be
+    /// reached is tried again later; the commit can still be confirmed in
+    /// the meantime.
+    async fn roll_back(&self, session_id: Uuid) -> Result<(), AppError> {
+        let claimed = sqlx::query(
+            "UPDATE config_sessions SET status = 'rolled_back', updated_at = ? \
+             WHERE id = ? AND status = 'pending_confirm' AND confirm_by <= ?",
+        )
+        .bind(db_now())
+        .bind(session_id.to_string())
+        .bind(db_now())
+        .execute(self.db.pool())
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        let session = self.get(session_id).await?;
+        let restore: Option<String> = sqlx::query_scalar("SELECT restore FROM config_sessions WHERE id = ?")
+            .bind(session_id.to_string())
+            .fetch_one(self.db.pool())
+            .await?;
+        let restore: Vec<RestorePoint> = serde_json::from_str(restore.as_deref().unwrap_or("[]"))?;
+
+        warn!(
+            "Configuration session {} was not confirmed in time, rolling back node {}",
+            session_id, session.node_id
+        );
+        let result = match self.current_config(session.node_id).await {
+            Ok(current) => {
+                let operations = rollback_operations(&current, &restore);
+                self.nodes.configure_node(session.node_id, &operations).await
+            }
+            Err(e) => Err(e),
+        };
+
+        let event = AuditEvent::new(None, "config.session.rollback", AuditResult::Success)
+            .with_node(session.node_id)
+            .with_target(session_id.to_string());
+        match result {
+            Ok(()) => {
+                sqlx::query("UPDATE config_sessions SET restore = NULL, error = NULL WHERE id = ?")
+                    .bind(session_id.to_string())
+                    .execute(self.db.pool())
+                    .await?;
+                self.audit.record(event).await;
+                info!("Rolled back configuration session {}", session_id);
+                Ok(())
+            }
+            Err(e) if NodeCircuitBreaker::is_transport_error(&e) => {
+                let retry_at = Utc::now() + chrono::Duration::seconds(ROLLBACK_RETRY_SECS as i64);
+                sqlx::query(
+                    "UPDATE config_sessions SET status = 'pending_confirm', confirm_by = ?, error = ?, updated_at = ? \
+                     WHERE id = ?",
+                )
+                .bind(format_timestamp(&retry_at))
+                .bind(format!("Rollback failed, retrying: {}", e))
+                .bind(db_now())
+                .bind(session_id.to_string())
+                .execute(self.db.pool())
+                .await?;
+                warn!("Node {} is unreachable, retrying rollback of session {}", session.node_id, session_id);
+                self.schedule_rollback(session_id, retry_at);
+                Ok(())
+            }
+            Err(e) => {
+                self.fail(session_id, &format!("Rollback failed: {}", e)).await?;
+                self.audit
+                    .record(
+                        AuditEvent { result: AuditResult::Failure, ..event }
+                            .with_details(serde_json::json!({ "error": e.to_string() })),
+                    )
+                    .await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn fail(&self, session_id: Uuid, message: &str) -> Result<(), AppError> {
+        sqlx::query("UPDATE config_sessions SET status = 'failed', error = ?, updated_at = ? WHERE id = ?")
+            .bind(message)
+            .bind(db_now())
+            .bind(session_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Whole configuration of a node, read from the node itself
+    pub(crate) async fn current_config(&self, node_id: Uuid) -> Result<Value, AppError> {
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        if config.stale {
+            return Err(AppError::HttpClient(config.error.unwrap_or_else(|| {
+                format!("Node {} is unreachable", node_id)
+            })));
+        }
+        Ok(config.data)
+    }
+
+    /// Check that operations may be applied to a node as part of a change
+    /// to several nodes, keeping what is needed to roll them back
+    ///
+    /// `current` is the node's whole configuration, as returned by
+    /// [`Self::current_config`].
+    pub(crate) async fn prepare_change(
+        &self,
+        node_id: Uuid,
+        current: &Value,
+        operations: Vec<StagedOperation>,
+        claims: &Claims,
+    ) -> Result<NodeChange, AppError> {
+        for operation in &operations {
+            self.ensure_can_apply(operation, claims).await?;
+        }
+        if self.awaiting_confirmation(node_id).await? {
+            return Err(AppError::Validation(format!(
+                "A commit to node {} is awaiting confirmation",
+                node_id
+            )));
+        }
+
+        Ok(NodeChange {
+            node_id,
+            restore: restore_points(current, &operations),
+            operations,
+        })
+    }
+
+    /// Apply changes to several nodes, one after the other
+    ///
+    /// If a node fails, the nodes changed before it are rolled back, last
+    /// first, so either every node is changed or none is, unless the
+    /// rollback fails too.
+    pub(crate) async fn apply_changes(&self, changes: Vec<NodeChange>) -> Result<(), NodeChangeFailure> {
+        let mut applied: Vec<NodeChange> = Vec::new();
+        for change in changes {
+            let operations = to_config_operations(&change.operations);
+            if let Err(error) = self.nodes.configure_node(change.node_id, &operations).await {
+                warn!("Failed to change node {}: {}", change.node_id, error);
+                let rollback_error = self.roll_back_changes(&applied).await.err();
+                return Err(NodeChangeFailure {
+                    node_id: change.node_id,
+                    error,
+                    rollback_error,
+                });
+            }
+            applied.push(change);
+        }
+        Ok(())
+    }
+
+    async fn roll_back_changes(&self, applied: &[NodeChange]) -> Result<(), AppError> {
+        for change in applied.iter().rev() {
+            let current = self.current_config(change.node_id).await?;
+            if let Err(e) = self
+                .nodes
+                .configure_node(change.node_id, &rollback_operations(&current, &change.restore))
+                .await
+            {
+                error!("Failed to roll back node {}: {}", change.node_id, e);
+                return Err(e);
+            }
+            info!("Rolled back node {}", change.node_id);
+        }
+        Ok(())
+    }
+
+    /// A session the caller may change: their own, or any for administrators
+    async fn owned_session(&self, session_id: Uuid, claims: &Claims) -> Result<ConfigSession, AppError> {
+        let session = self.get(session_id).await?;
+        let row: Option<(String,)> = sqlx::query_as("SELECT user_id FROM config_sessions WHERE id = ?")
+            .bind(session_id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?;
+        if row.is_some_and(|(user_id,)| user_id == claims.sub) {
+            return Ok(session);
+        }
+
+        let is_admin = self
+            .db
+            .find_user_by_id(&claims.sub)
+            .await?
+            .is_some_and(|user| user.is_superuser);
+        if !is_admin {
+            return Err(AppError::Forbidden(format!(
+                "Configuration session {} belongs to {}",
+                session_id, session.username
+            )));
+        }
+        Ok(session)
+    }
+
+    async fn ensure_can_apply(&self, operation: &StagedOperation, claims: &Claims) -> Result<(), AppError> {
+        self.locks
+            .ensure_can_modify(Some(claims), &operation.path, operation.op == ConfigOperationKind::Delete)
+            .await
+    }
+}
+
+/// Session columns in the order of [`ConfigSessionRow`]
+const SESSION_COLUMNS: &str =
+    "id, node_id, username, status, operations, comment, confirm_by, apply_at, diff, error, created_at, updated_at";
+
+/// Session columns as selected by [`SESSION_COLUMNS`]
+type ConfigSessionRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+fn session_from_row(
+    (id, node_id, username, status, operations, comment, confirm_by, apply_at, diff, error, created_at, updated_at):
+        ConfigSessionRow,
+) -> ConfigSession {
+    ConfigSession {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        node_id: Uuid::parse_str(&node_id).unwrap_or_default(),
+        username,
+        status: ConfigSessionStatus::parse(&status),
+        operations: serde_json::from_str(&operations).unwrap_or_default(),
+        comment,
+        confirm_by: confirm_by.as_deref().map(parse_db_timestamp),
+        apply_at: apply_at.as_deref().map(parse_db_timestamp),
+        diff: diff.and_then(|diff| serde_json::from_str(&diff).ok()).unwrap_or_default(),
+        error,
+        created_at: parse_db_timestamp(&created_at),
+        updated_at: parse_db_timestamp(&updated_at),
+    }
+}
+
+fn ensure_status(session: &ConfigSession, status: ConfigSessionStatus) -> Result<(), AppError> {
+    if session.status == status {
+        Ok(())
+    } else {
+        Err(not_in_status(session.id, status))
+    }
+}
+
+fn not_in_status(session_id: Uuid, status: ConfigSessionStatus) -> AppError {
+    let state = match status {
+        ConfigSessionStatus::PendingConfirm => "awaiting confirmation",
+        ConfigSessionStatus::Scheduled => "scheduled",
+        _ => "open",
+    };
+    AppError::Validation(format!("Configuration session {} is not {}", session_id, state))
+}
+
+fn validate_operation(operation: &StagedOperation) -> Result<(), AppError> {
+    if path_segments(&operation.path).is_empty() {
+        return Err(AppError::Validation("Operation path must not be empty".to_string()));
+    }
+    if operation.op == ConfigOperationKind::Delete && operation.value.is_some() {
+        return Err(AppError::Validation(format!(
+            "Delete of {} must not have a value",
+            operation.path
+        )));
+    }
+    Ok(())
+}
+
+/// Words of a staged operation's path, without its value
+fn operation_path(operation: &StagedOperation) -> Vec<String> {
+    path_segments(&operation.path).into_iter().map(str::to_string).collect()
+}
+
+/// The `POST /configure` batch applying staged operations
+fn to_config_operations(operations: &[StagedOperation]) -> Vec<ConfigOperation> {
+    operations
+        .iter()
+        .map(|operation| {
+            let mut path = operation_path(operation);
+            match operation.op {
+                ConfigOperationKind::Set => {
+                    path.extend(operation.value.clone());
+                    ConfigOperation::set(path)
+                }
+                ConfigOperationKind::Delete => ConfigOperation::delete(path),
+            }
+        })
+        .collect()
+}
+
+/// Effect of each staged operation on a retrieved configuration
+///
+/// A multi-value node holding one value looks like a single-value node, so
+/// setting another value of it is reported as a replacement.
+fn preview(config: &Value, operations: &[StagedOperation]) -> Vec<ConfigChangePreview> {
+    operations
+        .iter()
+        .map(|operation| {
+            let node = subtree(config, &operation_path(operation));
+            let effect = match (operation.op, node, &operation.value) {
+                (ConfigOperationKind::Delete, Some(_), _) => ConfigChangeEffect::Remove,
+                (ConfigOperationKind::Delete, None, _) => ConfigChangeEffect::Unchanged,
+                (ConfigOperationKind::Set, None, _) => ConfigChangeEffect::Add,
+                (ConfigOperationKind::Set, Some(_), None) => ConfigChangeEffect::Unchanged,
+                (ConfigOperationKind::Set, Some(Value::Array(values)), Some(value)) => {
+                    if values.iter().any(|current| leaf_text(current) == *value) {
+                        ConfigChangeEffect::Unchanged
+                    } else {
+                        ConfigChangeEffect::Add
+                    }
+                }
+                (ConfigOperationKind::Set, Some(Value::Object(children)), Some(value)) => {
+                    if children.contains_key(value) {
+                        ConfigChangeEffect::Unchanged
+                    } else {
+                        ConfigChangeEffect::Add
+                    }
+                }
+                (ConfigOperationKind::Set, Some(current), Some(value)) => {
+                    if leaf_text(current) == *value {
+                        ConfigChangeEffect::Unchanged
+                    } else {
+                        ConfigChangeEffect::Replace
+                    }
+                }
+            };
+            ConfigChangePreview {
+                operation: operation.clone(),
+                effect,
+            }
+        })
+        .collect()
+}
+
+/// Value of a leaf node as it would be given to `set`
+fn leaf_text(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Configuration below a path of a retrieved configuration
+fn subtree<'a>(config: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(config, |node, segment| node.as_object()?.get(segment))
+}
+
+/// What to keep so the staged operations can be undone
+///
+/// A set creating new nodes is undone by deleting the first node it
+/// created, and anything else by restoring the changed path. Paths below
+/// another restored path are covered by it.
+fn restore_points(config: &Value, operations: &[StagedOperation]) -> Vec<RestorePoint> {
+    let mut paths: Vec<Vec<String>> = Vec::new();
+    for operation in operations {
+        let mut path = operation_path(operation);
+        if let Some(missing) = (1..=path.len()).find(|len| subtree(config, &path[..*len]).is_none()) {
+            path.truncate(missing);
+        }
+        if paths.iter().any(|kept| path.starts_with(kept)) {
+            continue;
+        }
+        paths.retain(|kept| !kept.starts_with(&path));
+        paths.push(path);
+    }
+
+    paths
+        .into_iter()
+        .map(|path| RestorePoint {
+            config: subtree(config, &path).cloned(),
+            path,
+        })
+        .collect()
+}
+
+/// The `POST /configure` batch restoring the kept configuration
+fn rollback_operations(current: &Value, restore: &[RestorePoint]) -> Vec<ConfigOperation> {
+    let mut operations: Vec<ConfigOperation> = restore
+        .iter()
+        .filter(|point| subtree(current, &point.path).is_some())
+        .map(|point| ConfigOperation::delete(point.path.clone()))
+        .collect();
+    for point in restore {
+        if let Some(config) = &point.config {
+            let mut path = point.path.clone();
+            set_operations(&mut path, config, &mut operations);
+        }
+    }
+    operations
+}
+
+/// Set operations recreating a retrieved configuration subtree
+fn set_operations(path: &mut Vec<String>, config: &Value, operations: &mut Vec<ConfigOperation>) {
+    match config {
+        Value::Object(children) if !children.is_empty() => {
+            for (name, child) in children {
+                path.push(name.clone());
+                set_operations(path, child, operations);
+                path.pop();
+            }
+        }
+        // Valueless nodes such as `disable`
+        Value::Object(_) | Value::Null => operations.push(ConfigOperation::set(path.clone())),
+        // Multi-value nodes such as `address`
+        Value::Array(values) => {
+            for value in values {
+                set_operations(path, value, operations);
+            }
+        }
+        Value::String(value) => {
+            let mut path = path.clone();
+            path.push(value.clone());
+            operations.push(ConfigOperation::set(path));
+        }
+        value => {
+            let mut path = path.clone();
+            path.push(value.to_string());
+            operations.push(ConfigOperation::set(path));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn staged(op: ConfigOperationKind, path: &str, value: Option<&str>) -> StagedOperation {
+        StagedOperation {
+            op,
+            path: path.to_string(),
+            value: value.map(str::to_string),
+        }
+    }
+
+    fn words(path: &str) -> Vec<String> {
+        path.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_rollback_restores_replaced_configuration() {
+        let before = serde_json::json!({
+            "system": { "host-name": "edge-1", "time-zone": "UTC" },
+            "interfaces": { "ethernet": { "eth0": { "address": ["192.0.2.1/24", "192.0.2.2/24"], "disable": {} } } },
+        });
+        let operations = [
+            staged(ConfigOperationKind::Set, "system host-name", Some("edge-2")),
+            staged(ConfigOperationKind::Set, "interfaces ethernet eth1 address", Some("198.51.100.1/24")),
+            staged(ConfigOperationKind::Set, "interfaces ethernet eth1 description", Some("uplink")),
+            staged(ConfigOperationKind::Delete, "interfaces ethernet eth0", None),
+            staged(ConfigOperationKind::Delete, "interfaces ethernet eth0 disable", None),
+        ];
+
+        let restore = restore_points(&before, &operations);
+        let paths: Vec<&[String]> = restore.iter().map(|point| point.path.as_slice()).collect();
+        assert_eq!(
+            paths,
+            [words("system host-name"), words("interfaces ethernet eth1"), words("interfaces ethernet eth0")]
+        );
+        assert_eq!(restore[1].config, None);
+
+        let after = serde_json::json!({
+            "system": { "host-name": "edge-2", "time-zone": "UTC" },
+            "interfaces": { "ethernet": { "eth1": { "address": "198.51.100.1/24", "description": "uplink" } } },
+        });
+        assert_eq!(
+            rollback_operations(&after, &restore),
+            [
+                ConfigOperation::delete(words("system host-name")),
+                ConfigOperation::delete(words("interfaces ethernet eth1")),
+                ConfigOperation::set(words("system host-name edge-1")),
+                ConfigOperation::set(words("interfaces ethernet eth0 address 192.0.2.1/24")),
+                ConfigOperation::set(words("interfaces ethernet eth0 address 192.0.2.2/24")),
+                ConfigOperation::set(words("interfaces ethernet eth0 disable")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preview_reports_the_effect_of_each_operation() {
+        let config = serde_json::json!({
+            "system": { "host-name": "edge-1", "name-server": ["192.0.2.53", "192.0.2.54"] },
+            "interfaces": { "ethernet": { "eth0": { "disable": {} } } },
+        });
+        let operations = [
+            staged(ConfigOperationKind::Set, "system host-name", Some("edge-2")),
+            staged(ConfigOperationKind::Set, "system host-name", Some("edge-1")),
+            staged(ConfigOperationKind::Set, "system name-server", Some("192.0.2.55")),
+            staged(ConfigOperationKind::Set, "system name-server", Some("192.0.2.54")),
+            staged(ConfigOperationKind::Set, "system time-zone", Some("UTC")),
+            staged(ConfigOperationKind::Set, "interfaces ethernet eth0 disable", None),
+            staged(ConfigOperationKind::Set, "interfaces ethernet", Some("eth0")),
+            staged(ConfigOperationKind::Delete, "interfaces ethernet eth0", None),
+            staged(ConfigOperationKind::Delete, "interfaces ethernet eth1", None),
+        ];
+        let effects: Vec<ConfigChangeEffect> =
+            preview(&config, &operations).into_iter().map(|change| change.effect).collect();
+        assert_eq!(
+            effects,
+            [
+                ConfigChangeEffect::Replace,
+                ConfigChangeEffect::Unchanged,
+                ConfigChangeEffect::Add,
+                ConfigChangeEffect::Unchanged,
+                ConfigChangeEffect::Add,
+                ConfigChangeEffect::Unchanged,
+                ConfigChangeEffect::Unchanged,
+                ConfigChangeEffect::Remove,
+                ConfigChangeEffect::Unchanged,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_staged_operations_become_a_configure_batch() {
+        let operations = [
+            staged(ConfigOperationKind::Set, "/system/host-name/", Some("edge-2")),
+            staged(ConfigOperationKind::Set, "interfaces ethernet eth0 disable", None),
+            staged(ConfigOperationKind::Delete, "system time-zone", None),
+        ];
+        assert_eq!(
+            to_config_operations(&operations),
+            [
+                ConfigOperation::set(words("system host-name edge-2")),
+                ConfigOperation::set(words("interfaces ethernet eth0 disable")),
+                ConfigOperation::delete(words("system time-zone")),
+            ]
+        );
+        assert!(validate_operation(&staged(ConfigOperationKind::Delete, "system", Some("x"))).is_err());
+        assert!(validate_operation(&staged(ConfigOperationKind::Set, " / ", Some("x"))).is_err());
+    }
+}
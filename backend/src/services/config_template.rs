@@ -0,0 +1,444 @@
+//! Configuration Template Service
+//!
+//! Reusable configuration blueprints, such as a branch router baseline,
+//! which administrators write once as Handlebars templates taking variables
+//! like the LAN subnet or the WAN address. A template renders to `set` and
+//! `delete` commands that can be previewed and then applied to many nodes,
+//! one commit per node.
+
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+use handlebars::Handlebars;
+use ipnet::IpNet;
+use serde_json::{Map, Value};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::config_template::{
+    ApplyTemplateRequest, ConfigTemplate, ConfigTemplateListResponse, CreateConfigTemplateRequest, TemplatePreview,
+    TemplateVariable, TemplateVariableKind, UpdateConfigTemplateRequest,
+};
+use crate::models::node::NodeChangeResult;
+use crate::models::timestamp::{db_now, parse_db_timestamp};
+use crate::services::config_lock::ConfigLockService;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::config_tree::ConfigTreeWriter;
+use crate::services::desired_state::tokenize;
+use crate::services::node_service::NodeService;
+use crate::services::team::TeamService;
+use crate::vyos_client::ConfigOperation;
+
+const TEMPLATE_COLUMNS: &str = "id, name, description, body, variables, created_by, created_at, updated_at";
+
+/// Longest variable name accepted
+const MAX_VARIABLE_NAME_LEN: usize = 64;
+
+/// Configuration template service
+#[derive(Clone)]
+pub struct ConfigTemplateService {
+    db: Database,
+    nodes: NodeService,
+    teams: TeamService,
+    writer: ConfigTreeWriter,
+}
+
+impl ConfigTemplateService {
+    /// Create a new configuration template service
+    pub fn new(
+        db: Database,
+        nodes: NodeService,
+        teams: TeamService,
+        locks: ConfigLockService,
+        sessions: ConfigSessionService,
+    ) -> Self {
+        Self {
+            db,
+            writer: ConfigTreeWriter::new(nodes.clone(), locks, sessions),
+            nodes,
+            teams,
+        }
+    }
+
+    /// List templates by name
+    pub async fn list_templates(&self) -> Result<ConfigTemplateListResponse, AppError> {
+        let query = format!("SELECT {} FROM config_templates ORDER BY name", TEMPLATE_COLUMNS);
+        let rows = sqlx::query_as::<_, TemplateRow>(&query).fetch_all(self.db.pool()).await?;
+
+        let templates: Vec<ConfigTemplate> = rows.into_iter().map(template_from_row).collect();
+        Ok(ConfigTemplateListResponse {
+            total: templates.len() as u64,
+            templates,
+        })
+    }
+
+    /// Get a template by ID
+    pub async fn get_template(&self, id: Uuid) -> Result<ConfigTemplate, AppError> {
+        let query = format!("SELECT {} FROM config_templates WHERE id = ?", TEMPLATE_COLUMNS);
+
+        sqlx::query_as::<_, TemplateRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(template_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Configuration template {} not found", id)))
+    }
+
+    /// Create a template
+    pub async fn create_template(
+        &self,
+        claims: &Claims,
+        request: CreateConfigTemplateRequest,
+    ) -> Result<ConfigTemplate, AppError> {
+        validate_template(&request.body, &request.variables)?;
+        self.ensure_name_free(&request.name, None).await?;
+
+        let id = Uuid::new_v4();
+        let now = db_now();
+        sqlx::query(
+            r#"
+            INSERT INTO config_templates (id, name, description, body, variables, created_by, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(&request.name)
+        .bind(&request.description)
+        .bind(&request.body)
+        .bind(serde_json::to_string(&request.variables)?)
+        .bind(&claims.sub)
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Configuration template {} created by {}", request.name, claims.username);
+        self.get_template(id).await
+    }
+
+    /// Update a template
+    pub async fn update_template(
+        &self,
+        id: Uuid,
+        request: UpdateConfigTemplateRequest,
+    ) -> Result<ConfigTemplate, AppError> {
+        let current = self.get_template(id).await?;
+
+        let name = request.name.unwrap_or(current.name);
+        let description = request.description.or(current.description);
+        let body = request.body.unwrap_or(current.body);
+        let variables = request.variables.unwrap_or(current.variables);
+        validate_template(&body, &variables)?;
+        self.ensure_name_free(&name, Some(id)).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE config_templates
+            SET name = ?, description = ?, body = ?, variables = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&name)
+        .bind(&description)
+        .bind(&body)
+        .bind(serde_json::to_string(&variables)?)
+        .bind(db_now())
+        .bind(id.to_string())
+        .execute(self.db.pool())
+        .await?;
+
+        self.get_template(id).await
+    }
+
+    /// Delete a template
+    pub async fn delete_template(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM config_templates WHERE id = ?")
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Configuration template {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Render a template with the given variables, without applying it
+    pub async fn render(&self, id: Uuid, values: &HashMap<String, String>) -> Result<TemplatePreview, AppError> {
+        let template = self.get_template(id).await?;
+        let operations = render_operations(&template.body, &template.variables, values)?;
+        Ok(TemplatePreview {
+            commands: operations.iter().map(ConfigOperation::command).collect(),
+            operations,
+        })
+    }
+
+    /// Render a template and apply it to many nodes, one commit per node
+    ///
+    /// A node failing does not stop the others; each node's outcome is
+    /// returned.
+    pub async fn apply(
+        &self,
+        id: Uuid,
+        request: ApplyTemplateRequest,
+        claims: &Claims,
+    ) -> Result<Vec<NodeChangeResult>, AppError> {
+        let template = self.get_template(id).await?;
+        let operations = render_operations(&template.body, &template.variables, &request.variables)?;
+        if operations.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Template {} renders to no commands",
+                template.name
+            )));
+        }
+        let scope = self.teams.access_scope(claims).await?;
+
+        let mut results = Vec::new();
+        for node in self.nodes.fleet_nodes(&scope, request.node_ids.as_deref()).await? {
+            let result = self.writer.apply(node.id, &operations, claims).await;
+            if let Err(e) = &result {
+                warn!("Could not apply template {} to node {}: {}", template.name, node.id, e);
+            }
+            results.push(NodeChangeResult {
+                node_id: node.id,
+                node_name: node.name,
+                success: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+        info!(
+            "{} applied template {} to {} of {} nodes",
+            claims.username,
+            template.name,
+            results.iter().filter(|result| result.success).count(),
+            results.len()
+        );
+        Ok(results)
+    }
+
+    async fn ensure_name_free(&self, name: &str, id: Option<Uuid>) -> Result<(), AppError> {
+        let existing: Option<(String,)> = sqlx::query_as("SELECT id FROM config_templates WHERE name = ?")
+            .bind(name)
+            .fetch_optional(self.db.pool())
+            .await?;
+
+        match existing {
+            Some((existing,)) if id.is_none_or(|id| id.to_string() != existing) => Err(AppError::Validation(format!(
+                "A configuration template named {} already exists",
+                name
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Ensure a template's variables are well-formed and its body compiles
+fn validate_template(body: &str, variables: &[TemplateVariable]) -> Result<(), AppError> {
+    let mut names = HashSet::new();
+    for variable in variables {
+        let name = &variable.name;
+        if name.is_empty()
+            || name.len() > MAX_VARIABLE_NAME_LEN
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(AppError::Validation(format!(
+                "Invalid variable name {:?}: use letters, digits, and underscores, not starting with a digit",
+                name
+            )));
+        }
+        if !names.insert(name.as_str()) {
+            return Err(AppError::Validation(format!("Variable {} is declared twice", name)));
+        }
+        if let Some(default) = &variable.default {
+            validate_value(variable, default)?;
+        }
+    }
+
+    handlebars::Template::compile(body)
+        .map_err(|e| AppError::Validation(format!("Invalid template body: {}", e)))?;
+    Ok(())
+}
+
+/// Ensure a value is of its variable's kind
+///
+/// Values are pasted into commands, so none may contain quotes or line
+/// breaks that would change the commands around them.
+fn validate_value(variable: &TemplateVariable, value: &str) -> Result<(), AppError> {
+    let valid = match variable.kind {
+        TemplateVariableKind::String => !value.contains(|c: char| c.is_control() || c == '\'' || c == '"'),
+        TemplateVariableKind::Address => value.parse::<IpAddr>().is_ok(),
+        TemplateVariableKind::Prefix => value.parse::<IpNet>().is_ok(),
+        TemplateVariableKind::Integer => value.parse::<i64>().is_ok(),
+    };
+    if valid {
+        return Ok(());
+    }
+
+    let expected = match variable.kind {
+        TemplateVariableKind::String => "text without quotes or line breaks",
+        TemplateVariableKind::Address => "an IP address",
+        TemplateVariableKind::Prefix => "an address with its prefix length, e.g. 192.0.2.1/24",
+        TemplateVariableKind::Integer => "an integer",
+    };
+    Err(AppError::Validation(format!(
+        "Variable {} must be {}, got {:?}",
+        variable.name, expected, value
+    )))
+}
+
+/// Render a template body with the given values and parse the commands it
+/// renders to
+///
+/// Variables without a value take their default; unknown variables and
+/// missing required ones are rejected.
+pub(crate) fn render_operations(
+    body: &str,
+    variables: &[TemplateVariable],
+    values: &HashMap<String, String>,
+) -> Result<Vec<ConfigOperation>, AppError> {
+    if let Some(unknown) = values.keys().find(|name| !variables.iter().any(|variable| &variable.name == *name)) {
+        return Err(AppError::Validation(format!("Unknown template variable {}", unknown)));
+    }
+
+    let mut data = Map::new();
+    for variable in variables {
+        let value = match values.get(&variable.name).or(variable.default.as_ref()) {
+            Some(value) => {
+                validate_value(variable, value)?;
+                Value::String(value.clone())
+            }
+            None if variable.required => {
+                return Err(AppError::Validation(format!("Variable {} is required", variable.name)));
+            }
+            None => Value::Null,
+        };
+        data.insert(variable.name.clone(), value);
+    }
+
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(true);
+    handlebars.register_escape_fn(handlebars::no_escape);
+    let rendered = handlebars
+        .render_template(body, &data)
+        .map_err(|e| AppError::Validation(format!("Could not render the template: {}", e)))?;
+
+    parse_commands(&rendered)
+}
+
+/// Operations of rendered `set` and `delete` commands, skipping blank lines
+/// and `#` comments
+fn parse_commands(rendered: &str) -> Result<Vec<ConfigOperation>, AppError> {
+    let mut operations = Vec::new();
+    for (number, line) in rendered.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid =
+            |reason: &str| AppError::Validation(format!("Line {} of the rendered template {}", number + 1, reason));
+        let mut words = tokenize(line).ok_or_else(|| invalid("has an unterminated quote"))?;
+        let verb = words.remove(0);
+        if words.is_empty() {
+            return Err(invalid("has no configuration path"));
+        }
+        match verb.as_str() {
+            "set" => operations.push(ConfigOperation::set(words)),
+            "delete" => operations.push(ConfigOperation::delete(words)),
+            _ => return Err(invalid("is not a set or delete command")),
+        }
+    }
+    Ok(operations)
+}
+
+/// Template columns as selected by the queries above
+type TemplateRow = (String, String, Option<String>, String, String, Option<String>, String, String);
+
+fn template_from_row(
+    (id, name, description, body, variables, created_by, created_at, updated_at): TemplateRow,
+) -> ConfigTemplate {
+    ConfigTemplate {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        name,
+        description,
+        body,
+        variables: serde_json::from_str(&variables).unwrap_or_default(),
+        created_by: created_by.and_then(|id| Uuid::parse_str(&id).ok()),
+        created_at: parse_db_timestamp(&created_at),
+        updated_at: parse_db_timestamp(&updated_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(name: &str, kind: TemplateVariableKind, default: Option<&str>, required: bool) -> TemplateVariable {
+        TemplateVariable {
+            name: name.to_string(),
+            description: None,
+            kind,
+            default: default.map(str::to_string),
+            required,
+        }
+    }
+
+    #[test]
+    fn test_render_operations() {
+        let body = "# Branch router baseline\n\
+                    set interfaces ethernet eth1 address {{lan_address}}\n\
+                    set system host-name {{hostname}}\n\
+                    {{#if wan_address}}set interfaces ethernet eth0 address {{wan_address}}{{/if}}\n\
+                    \n\
+                    delete service telnet\n\
+                    set system login banner pre-login '{{banner}}'\n";
+        let variables = vec![
+            variable("lan_address", TemplateVariableKind::Prefix, None, true),
+            variable("hostname", TemplateVariableKind::String, Some("branch"), false),
+            variable("wan_address", TemplateVariableKind::Prefix, None, false),
+            variable("banner", TemplateVariableKind::String, Some("Authorized use only"), false),
+        ];
+        validate_template(body, &variables).unwrap();
+
+        let values = HashMap::from([("lan_address".to_string(), "192.168.10.1/24".to_string())]);
+        let commands: Vec<String> = render_operations(body, &variables, &values)
+            .unwrap()
+            .iter()
+            .map(ConfigOperation::command)
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                "set interfaces ethernet eth1 address 192.168.10.1/24",
+                "set system host-name branch",
+                "delete service telnet",
+                "set system login banner pre-login 'Authorized use only'",
+            ]
+        );
+
+        // Required variables, kinds, and unknown variables are checked
+        assert!(render_operations(body, &variables, &HashMap::new()).is_err());
+        let bad = HashMap::from([("lan_address".to_string(), "192.168.10.1".to_string())]);
+        assert!(render_operations(body, &variables, &bad).is_err());
+        let injected = HashMap::from([
+            ("lan_address".to_string(), "192.168.10.1/24".to_string()),
+            ("hostname".to_string(), "x\ndelete system".to_string()),
+        ]);
+        assert!(render_operations(body, &variables, &injected).is_err());
+        let unknown = HashMap::from([
+            ("lan_address".to_string(), "192.168.10.1/24".to_string()),
+            ("lan_mask".to_string(), "24".to_string()),
+        ]);
+        assert!(render_operations(body, &variables, &unknown).is_err());
+
+        // Undeclared variables in the body and lines other than commands fail
+        assert!(render_operations("set system host-name {{name}}", &[], &HashMap::new()).is_err());
+        assert!(render_operations("commit", &[], &HashMap::new()).is_err());
+        assert!(validate_template("{{#if x}}", &[]).is_err());
+        assert!(validate_template("", &[variable("1st", TemplateVariableKind::String, None, false)]).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! Configuration Trees
+//!
+//! Services that manage one part of a node's configuration, such as the
+//! IPv6 or firewall settings, read it from the node's retrieved
+//! configuration and write it back as one commit replacing a subtree. This
+//! module holds what they share: reading values out of the JSON tree, which
+//! gives a multi-value node with one value as a plain string, and the
+//! [`ConfigTreeWriter`] that replaces subtrees under the configuration locks.
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::services::config_lock::ConfigLockService;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::node_service::NodeService;
+use crate::vyos_client::{ConfigOperation, ConfigOperationKind};
+
+/// Replaces subtrees of a node's configuration
+#[derive(Clone)]
+pub struct ConfigTreeWriter {
+    nodes: NodeService,
+    locks: ConfigLockService,
+    sessions: ConfigSessionService,
+}
+
+impl ConfigTreeWriter {
+    /// Create a writer honouring configuration locks and pending commits
+    pub fn new(nodes: NodeService, locks: ConfigLockService, sessions: ConfigSessionService) -> Self {
+        Self { nodes, locks, sessions }
+    }
+
+    /// Configuration of a node as it is now, failing rather than answering
+    /// from the cache when the node cannot be read
+    pub async fn current(&self, node_id: Uuid) -> Result<Value, AppError> {
+        let current = self.nodes.retrieve_node_config(node_id, None).await?;
+        if current.stale {
+            return Err(AppError::HttpClient(
+                current.error.unwrap_or_else(|| format!("Node {} is unreachable", node_id)),
+            ));
+        }
+        Ok(current.data)
+    }
+
+    /// Replace the configuration below `root` by the given paths, relative to
+    /// it, in one commit; no paths removes `root`
+    pub async fn replace(
+        &self,
+        node_id: Uuid,
+        root: Vec<String>,
+        paths: Vec<Vec<String>>,
+        claims: &Claims,
+    ) -> Result<(), AppError> {
+        let current = self.current(node_id).await?;
+        let operations = replacement(&current, node_id, root, paths)?;
+        self.apply(node_id, &operations, claims).await
+    }
+
+    /// Apply operations to a node in one commit, unless a path is under a
+    /// configuration lock the caller does not hold or a commit to the node
+    /// awaits confirmation
+    pub async fn apply(&self, node_id: Uuid, operations: &[ConfigOperation], claims: &Claims) -> Result<(), AppError> {
+        for operation in operations {
+            self.locks
+                .ensure_can_modify(
+                    Some(claims),
+                    &operation.path.join(" "),
+                    operation.op == ConfigOperationKind::Delete,
+                )
+                .await?;
+        }
+        if self.sessions.awaiting_confirmation(node_id).await? {
+            return Err(AppError::Validation(format!(
+                "A commit to node {} is awaiting confirmation",
+                node_id
+            )));
+        }
+
+        self.nodes.configure_node(node_id, operations).await
+    }
+}
+
+/// Operations replacing the configuration below `root` by the given paths,
+/// relative to it; no paths removes `root`, which must then exist
+pub(crate) fn replacement(
+    current: &Value,
+    node_id: Uuid,
+    root: Vec<String>,
+    paths: Vec<Vec<String>>,
+) -> Result<Vec<ConfigOperation>, AppError> {
+    let exists = root
+        .iter()
+        .try_fold(current, |node, segment| node.as_object()?.get(segment))
+        .is_some();
+    if !exists && paths.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "{} is not configured on node {}",
+            root.join(" "),
+            node_id
+        )));
+    }
+
+    let mut operations = Vec::new();
+    if exists {
+        operations.push(ConfigOperation::delete(root.clone()));
+    }
+    for relative in paths {
+        let mut full = root.clone();
+        full.extend(relative);
+        operations.push(ConfigOperation::set(full));
+    }
+    Ok(operations)
+}
+
+/// Configuration path from fixed words followed by names
+pub(crate) fn path(base: &[&str], names: &[&str]) -> Vec<String> {
+    base.iter().chain(names).map(|word| word.to_string()).collect()
+}
+
+/// Node of a retrieved configuration at a path
+pub(crate) fn lookup<'a>(config: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(config, |node, segment| node.as_object()?.get(*segment))
+}
+
+/// Children of a tag node, by name
+pub(crate) fn entries(node: Option<&Value>) -> Vec<(String, &Value)> {
+    node.and_then(Value::as_object)
+        .map(|children| children.iter().map(|(name, child)| (name.clone(), child)).collect())
+        .unwrap_or_default()
+}
+
+/// Values of a multi-value node, which is a string when it has one value
+pub(crate) fn values(node: Option<&Value>) -> Vec<String> {
+    match node {
+        Some(Value::Array(items)) => items.iter().filter_map(|item| text(Some(item))).collect(),
+        node => text(node).into_iter().collect(),
+    }
+}
+
+/// Value of a single-value node
+pub(crate) fn text(node: Option<&Value>) -> Option<String> {
+    match node? {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Numeric value of a single-value node
+pub(crate) fn number<T: std::str::FromStr>(node: Option<&Value>) -> Option<T> {
+    text(node)?.parse().ok()
+}
+
+/// Check a configuration node name taken from the URL
+pub(crate) fn validate_name(what: &str, name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("{} '{}' is not valid", what, name)))
+    }
+}
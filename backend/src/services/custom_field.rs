@@ -0,0 +1,392 @@
+//! Custom Field Service
+//!
+//! Manages the admin-defined fields of the node inventory and their values
+//! on nodes. Nodes carry their values under `custom_fields`, keyed by field
+//! name; the node list filters on them and inventory exports get a column
+//! per field.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde_json::Value;
+use sqlx::SqliteConnection;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::custom_field::{
+    anchored_pattern, CreateCustomFieldRequest, CustomField, CustomFieldType, UpdateCustomFieldRequest,
+    MAX_CUSTOM_FIELDS,
+};
+use crate::models::timestamp::{db_now, parse_db_timestamp};
+
+const FIELD_COLUMNS: &str = "id, name, label, field_type, required, options, pattern, description, created_at, updated_at";
+
+/// Checked change to the custom field values of a node
+///
+/// `None` clears the field.
+pub type CustomFieldChanges = Vec<(CustomField, Option<String>)>;
+
+/// Custom field service
+#[derive(Clone)]
+pub struct CustomFieldService {
+    db: Database,
+}
+
+impl CustomFieldService {
+    /// Create a new custom field service
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List custom fields by name
+    pub async fn list_fields(&self) -> Result<Vec<CustomField>, AppError> {
+        let query = format!("SELECT {} FROM node_custom_fields ORDER BY name", FIELD_COLUMNS);
+        let rows: Vec<FieldRow> = sqlx::query_as(&query).fetch_all(self.db.pool()).await?;
+
+        rows.into_iter().map(field_from_row).collect()
+    }
+
+    /// Get a custom field
+    pub async fn get_field(&self, id: Uuid) -> Result<CustomField, AppError> {
+        let query = format!("SELECT {} FROM node_custom_fields WHERE id = ?", FIELD_COLUMNS);
+
+        sqlx::query_as::<_, FieldRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(field_from_row)
+            .transpose()?
+            .ok_or_else(|| AppError::NotFound(format!("Custom field {} not found", id)))
+    }
+
+    /// Define a custom field
+    ///
+    /// Nodes that exist already have no value for it, even when it is
+    /// required.
+    pub async fn create_field(&self, request: CreateCustomFieldRequest) -> Result<CustomField, AppError> {
+        check_name(&request.name)?;
+        check_rules(request.field_type, &request.options, request.pattern.as_deref())?;
+
+        let fields = self.list_fields().await?;
+        if fields.len() >= MAX_CUSTOM_FIELDS {
+            return Err(AppError::Validation(format!("At most {} custom fields can be defined", MAX_CUSTOM_FIELDS)));
+        }
+        if fields.iter().any(|field| field.name == request.name) {
+            return Err(AppError::Validation(format!("A custom field named {} already exists", request.name)));
+        }
+
+        let id = Uuid::new_v4();
+        let now = db_now();
+        sqlx::query(
+            r#"
+            INSERT INTO node_custom_fields (
+                id, name, label, field_type, required, options, pattern, description, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(&request.name)
+        .bind(&request.label)
+        .bind(request.field_type.as_str())
+        .bind(request.required)
+        .bind(serde_json::to_string(&request.options)?)
+        .bind(&request.pattern)
+        .bind(&request.description)
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Custom field {} ({}) defined", request.name, request.field_type.as_str());
+        self.get_field(id).await
+    }
+
+    /// Update a custom field
+    ///
+    /// An empty pattern removes it. A new pattern only applies to values set
+    /// from now on, but options still in use cannot be removed.
+    pub async fn update_field(&self, id: Uuid, request: UpdateCustomFieldRequest) -> Result<CustomField, AppError> {
+        let mut field = self.get_field(id).await?;
+
+        if let Some(label) = request.label {
+            field.label = label;
+        }
+        if let Some(required) = request.required {
+            field.required = required;
+        }
+        if let Some(options) = request.options {
+            let removed: Vec<&String> = field.options.iter().filter(|option| !options.contains(option)).collect();
+            for option in removed {
+                let in_use: i64 =
+                    sqlx::query_scalar("SELECT COUNT(*) FROM node_custom_field_values WHERE field_id = ? AND value = ?")
+                        .bind(id.to_string())
+                        .bind(option)
+                        .fetch_one(self.db.pool())
+                        .await?;
+                if in_use > 0 {
+                    return Err(AppError::Validation(format!(
+                        "Option {} of field {} is set on {} nodes",
+                        option, field.name, in_use
+                    )));
+                }
+            }
+            field.options = options;
+        }
+        if let Some(pattern) = request.pattern {
+            field.pattern = Some(pattern).filter(|pattern| !pattern.is_empty());
+        }
+        if let Some(description) = request.description {
+            field.description = Some(description);
+        }
+        check_rules(field.field_type, &field.options, field.pattern.as_deref())?;
+
+        sqlx::query(
+            "UPDATE node_custom_fields SET label = ?, required = ?, options = ?, pattern = ?, description = ?, \
+             updated_at = ? WHERE id = ?",
+        )
+        .bind(&field.label)
+        .bind(field.required)
+        .bind(serde_json::to_string(&field.options)?)
+        .bind(&field.pattern)
+        .bind(&field.description)
+        .bind(db_now())
+        .bind(id.to_string())
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Custom field {} updated", field.name);
+        self.get_field(id).await
+    }
+
+    /// Delete a custom field with its values on every node
+    pub async fn delete_field(&self, id: Uuid) -> Result<CustomField, AppError> {
+        let field = self.get_field(id).await?;
+        sqlx::query("DELETE FROM node_custom_fields WHERE id = ?")
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        info!("Custom field {} deleted", field.name);
+        Ok(field)
+    }
+
+    /// Values of the custom fields set on a node, by field name
+    pub async fn node_values(&self, node_id: Uuid) -> Result<BTreeMap<String, Value>, AppError> {
+        let rows: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT f.name, f.field_type, v.value
+            FROM node_custom_field_values v
+            JOIN node_custom_fields f ON f.id = v.field_id
+            WHERE v.node_id = ?
+            "#,
+        )
+        .bind(node_id.to_string())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, field_type, value)| {
+                let value = CustomFieldType::parse(&field_type).decode(&value);
+                (name, value)
+            })
+            .collect())
+    }
+
+    /// Check values given for the custom fields of a node, where `null`
+    /// clears a field
+    ///
+    /// New nodes must be given every required field.
+    pub async fn check_values(
+        &self,
+        values: &BTreeMap<String, Value>,
+        creating: bool,
+    ) -> Result<CustomFieldChanges, AppError> {
+        let fields = self.list_fields().await?;
+        if let Some(unknown) = values.keys().find(|name| !fields.iter().any(|field| &field.name == *name)) {
+            return Err(AppError::Validation(format!("Unknown custom field: {}", unknown)));
+        }
+
+        let mut changes = Vec::new();
+        for field in fields {
+            let value = match values.get(&field.name) {
+                Some(Value::Null) | None if field.required && (creating || values.contains_key(&field.name)) => {
+                    return Err(AppError::Validation(format!("Custom field {} is required", field.name)));
+                }
+                Some(Value::Null) => None,
+                Some(value) => Some(field.normalize(value)?),
+                None => continue,
+            };
+            changes.push((field, value));
+        }
+        Ok(changes)
+    }
+
+    /// Write checked custom field values of a node
+    pub async fn write_values(
+        &self,
+        conn: &mut SqliteConnection,
+        node_id: Uuid,
+        changes: &CustomFieldChanges,
+    ) -> Result<(), AppError> {
+        let now = db_now();
+        for (field, value) in changes {
+            match value {
+                Some(value) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO node_custom_field_values (node_id, field_id, value, updated_at)
+                        VALUES (?, ?, ?, ?)
+                        ON CONFLICT (node_id, field_id) DO UPDATE SET
+                            value = excluded.value,
+                            updated_at = excluded.updated_at
+                        "#,
+                    )
+                    .bind(node_id.to_string())
+                    .bind(field.id.to_string())
+                    .bind(value)
+                    .bind(&now)
+                    .execute(&mut *conn)
+                    .await?;
+                }
+                None => {
+                    sqlx::query("DELETE FROM node_custom_field_values WHERE node_id = ? AND field_id = ?")
+                        .bind(node_id.to_string())
+                        .bind(field.id.to_string())
+                        .execute(&mut *conn)
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// SQL conditions on `nodes` for a custom field filter, with the values
+    /// they bind
+    ///
+    /// The filter is a comma-separated list of `name:value` pairs, e.g.
+    /// `site_code:AMS1,rack:12`; nodes must have every value.
+    pub async fn filter_clauses(&self, filter: &str) -> Result<(Vec<String>, Vec<String>), AppError> {
+        let fields = self.list_fields().await?;
+        let mut clauses = Vec::new();
+        let mut bind_values = Vec::new();
+
+        for pair in filter.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair
+                .split_once(':')
+                .ok_or_else(|| AppError::Validation(format!("Invalid custom field filter: {}", pair)))?;
+            let field = fields
+                .iter()
+                .find(|field| field.name == name.trim())
+                .ok_or_else(|| AppError::Validation(format!("Unknown custom field: {}", name.trim())))?;
+
+            clauses.push(
+                "EXISTS (SELECT 1 FROM node_custom_field_values cf \
+                 WHERE cf.node_id = nodes.id AND cf.field_id = ? AND cf.value = ?)"
+                    .to_string(),
+            );
+            bind_values.push(field.id.to_string());
+            bind_values.push(field.normalize_text(value.trim())?);
+        }
+
+        Ok((clauses, bind_values))
+    }
+}
+
+/// Check that a field name can key JSON objects and CSV columns
+fn check_name(name: &str) -> Result<(), AppError> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid custom field name {}: use lowercase letters, digits, and underscores, starting with a letter",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Check the validation rules of a field against its type
+fn check_rules(field_type: CustomFieldType, options: &[String], pattern: Option<&str>) -> Result<(), AppError> {
+    match field_type {
+        CustomFieldType::Select => {
+            if options.is_empty() {
+                return Err(AppError::Validation("Select fields need at least one option".to_string()));
+            }
+            let mut seen = HashSet::new();
+            if let Some(option) = options.iter().find(|option| option.is_empty() || !seen.insert(option.as_str())) {
+                return Err(AppError::Validation(format!("Invalid or repeated option: {:?}", option)));
+            }
+        }
+        _ if !options.is_empty() => {
+            return Err(AppError::Validation("Only select fields have options".to_string()));
+        }
+        _ => {}
+    }
+
+    match (field_type, pattern) {
+        (CustomFieldType::Text, Some(pattern)) => anchored_pattern(pattern).map(|_| ()),
+        (_, Some(_)) => Err(AppError::Validation("Only text fields have a pattern".to_string())),
+        (_, None) => Ok(()),
+    }
+}
+
+type FieldRow = (
+    String,
+    String,
+    String,
+    String,
+    bool,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+fn field_from_row(
+    (id, name, label, field_type, required, options, pattern, description, created_at, updated_at): FieldRow,
+) -> Result<CustomField, AppError> {
+    Ok(CustomField {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        name,
+        label,
+        field_type: CustomFieldType::parse(&field_type),
+        required,
+        options: serde_json::from_str(&options)?,
+        pattern,
+        description,
+        created_at: parse_db_timestamp(&created_at),
+        updated_at: parse_db_timestamp(&updated_at),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_name() {
+        assert!(check_name("site_code").is_ok());
+        assert!(check_name("rack2").is_ok());
+        assert!(check_name("2rack").is_err());
+        assert!(check_name("Site").is_err());
+        assert!(check_name("site-code").is_err());
+        assert!(check_name("").is_err());
+    }
+
+    #[test]
+    fn test_check_rules() {
+        let options = vec!["ams1".to_string(), "fra2".to_string()];
+        assert!(check_rules(CustomFieldType::Select, &options, None).is_ok());
+        assert!(check_rules(CustomFieldType::Select, &[], None).is_err());
+        assert!(check_rules(CustomFieldType::Select, &["a".to_string(), "a".to_string()], None).is_err());
+        assert!(check_rules(CustomFieldType::Text, &options, None).is_err());
+        assert!(check_rules(CustomFieldType::Text, &[], Some("[A-Z]+")).is_ok());
+        assert!(check_rules(CustomFieldType::Text, &[], Some("[A-Z")).is_err());
+        assert!(check_rules(CustomFieldType::Integer, &[], Some("[0-9]+")).is_err());
+    }
+}
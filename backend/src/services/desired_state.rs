@@ -0,0 +1,377 @@
+//! Desired State Service
+//!
+//! Converges a node to a desired configuration. The desired state is given
+//! as a configuration tree or as `set` commands; it is compared with the
+//! node's running configuration and the set and delete operations closing
+//! the gap are applied as a single commit. A dry run only returns the
+//! operations, so callers can preview the change before applying it.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::desired_state::{DesiredStatePlan, DesiredStateRequest, MAX_DESIRED_COMMANDS};
+use crate::services::config_lock::ConfigLockService;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::node_service::NodeService;
+use crate::vyos_client::{ConfigOperation, ConfigOperationKind};
+
+/// Desired state service
+#[derive(Clone)]
+pub struct DesiredStateService {
+    nodes: NodeService,
+    locks: ConfigLockService,
+    sessions: ConfigSessionService,
+}
+
+impl DesiredStateService {
+    /// Create a new desired state service
+    pub fn new(nodes: NodeService, locks: ConfigLockService, sessions: ConfigSessionService) -> Self {
+        Self { nodes, locks, sessions }
+    }
+
+    /// Compute the operations converging a node to a desired state, and
+    /// apply them unless it is a dry run
+    ///
+    /// Paths under a configuration lock the caller does not hold are
+    /// rejected, as is any change while a commit to the node awaits
+    /// confirmation.
+    pub async fn converge(
+        &self,
+        node_id: Uuid,
+        request: DesiredStateRequest,
+        claims: &Claims,
+    ) -> Result<DesiredStatePlan, AppError> {
+        let scope = words(request.path.as_deref().unwrap_or_default());
+        let desired = match (&request.config, &request.commands) {
+            (Some(config), None) => config_paths(config).into_keys().collect(),
+            (None, Some(commands)) => command_paths(commands, &scope)?,
+            _ => {
+                return Err(AppError::Validation(
+                    "Give the desired state either as config or as commands".to_string(),
+                ))
+            }
+        };
+
+        let current = self.nodes.retrieve_node_config(node_id, None).await?;
+        if current.stale {
+            return Err(AppError::HttpClient(
+                current.error.unwrap_or_else(|| format!("Node {} is unreachable", node_id)),
+            ));
+        }
+        let current = subtree(&current.data, &scope).map(config_paths).unwrap_or_default();
+        let base_hash = paths_hash(current.keys());
+        if request.base_hash.as_ref().is_some_and(|hash| *hash != base_hash) {
+            return Err(AppError::Validation(format!(
+                "The configuration of node {} has changed since the preview",
+                node_id
+            )));
+        }
+
+        let operations: Vec<ConfigOperation> = plan_operations(&current, &desired)
+            .into_iter()
+            .map(|operation| {
+                let mut path = scope.clone();
+                path.extend(operation.path);
+                ConfigOperation { path, ..operation }
+            })
+            .collect();
+        let deletes = operations
+            .iter()
+            .filter(|operation| operation.op == ConfigOperationKind::Delete)
+            .count();
+        let mut plan = DesiredStatePlan {
+            node_id,
+            base_hash,
+            sets: operations.len() - deletes,
+            deletes,
+            operations,
+            applied: false,
+        };
+        if request.dry_run || plan.operations.is_empty() {
+            return Ok(plan);
+        }
+
+        for operation in &plan.operations {
+            self.locks
+                .ensure_can_modify(
+                    Some(claims),
+                    &operation.path.join(" "),
+                    operation.op == ConfigOperationKind::Delete,
+                )
+                .await?;
+        }
+        if self.sessions.awaiting_confirmation(node_id).await? {
+            return Err(AppError::Validation(format!(
+                "A commit to node {} is awaiting confirmation",
+                node_id
+            )));
+        }
+
+        self.nodes.configure_node(node_id, &plan.operations).await?;
+        info!(
+            "{} converged node {} to its desired state: {} deletes, {} sets",
+            claims.username, node_id, plan.deletes, plan.sets
+        );
+        plan.applied = true;
+        Ok(plan)
+    }
+}
+
+/// How a path of a retrieved configuration ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathEnd {
+    /// In the value of a single-value node, which a set replaces
+    SingleValue,
+    /// In a valueless node or one value of a multi-value node
+    Other,
+}
+
+/// Words of a configuration path
+fn words(path: &str) -> Vec<String> {
+    path.split_whitespace().map(str::to_string).collect()
+}
+
+/// Configuration below a path of a retrieved configuration
+fn subtree<'a>(config: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(config, |node, segment| node.as_object()?.get(segment))
+}
+
+/// Full paths, values included, of everything set in a configuration tree
+fn config_paths(config: &Value) -> BTreeMap<Vec<String>, PathEnd> {
+    fn walk(path: &mut Vec<String>, config: &Value, end: PathEnd, paths: &mut BTreeMap<Vec<String>, PathEnd>) {
+        match config {
+            Value::Object(children) if !children.is_empty() => {
+                for (name, child) in children {
+                    path.push(name.clone());
+                    walk(path, child, PathEnd::SingleValue, paths);
+                    path.pop();
+                }
+            }
+            Value::Object(_) | Value::Null => {
+                if !path.is_empty() {
+                    paths.insert(path.clone(), PathEnd::Other);
+                }
+            }
+            Value::Array(values) => {
+                for value in values {
+                    walk(path, value, PathEnd::Other, paths);
+                }
+            }
+            value => {
+                let value = match value {
+                    Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                };
+                path.push(value);
+                paths.insert(path.clone(), end);
+                path.pop();
+            }
+        }
+    }
+
+    let mut paths = BTreeMap::new();
+    walk(&mut Vec::new(), config, PathEnd::SingleValue, &mut paths);
+    paths
+}
+
+/// Paths below `scope` set by a list of `set` commands
+fn command_paths(commands: &[String], scope: &[String]) -> Result<BTreeSet<Vec<String>>, AppError> {
+    if commands.len() > MAX_DESIRED_COMMANDS {
+        return Err(AppError::Validation(format!(
+            "A desired state has at most {} commands",
+            MAX_DESIRED_COMMANDS
+        )));
+    }
+
+    let mut paths = BTreeSet::new();
+    for command in commands {
+        let command = command.trim();
+        if command.is_empty() || command.starts_with('#') {
+            continue;
+        }
+        let tokens = tokenize(command).ok_or_else(|| {
+            AppError::Validation(format!("Unterminated quote in command: {}", command))
+        })?;
+        let Some(("set", path)) = tokens.split_first().map(|(verb, path)| (verb.as_str(), path)) else {
+            return Err(AppError::Validation(format!(
+                "A desired state consists of set commands only: {}",
+                command
+            )));
+        };
+        if path.len() <= scope.len() || !path.starts_with(scope) {
+            return Err(AppError::Validation(format!(
+                "Command is outside of {}: {}",
+                scope.join(" "),
+                command
+            )));
+        }
+        paths.insert(path[scope.len()..].to_vec());
+    }
+    Ok(paths)
+}
+
+/// Words of a command, with single- or double-quoted words unquoted
+///
+/// Returns `None` for an unterminated quote.
+pub(crate) fn tokenize(command: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = command.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next() {
+                    Some(next) if next == c => break,
+                    Some(next) => token.push(next),
+                    None => return None,
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&next) = chars.peek() {
+                if next.is_whitespace() {
+                    break;
+                }
+                token.push(next);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    Some(tokens)
+}
+
+/// SHA-256 of a set of configuration paths
+fn paths_hash<'a>(paths: impl Iterator<Item = &'a Vec<String>>) -> String {
+    let mut hasher = Sha256::new();
+    for path in paths {
+        hasher.update(path.join("\0").as_bytes());
+        hasher.update([b'\n']);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Operations turning the current paths into the desired ones
+///
+/// Each path missing from the desired state is deleted at the highest node
+/// nothing desired lives under. The value of a single-value node is not
+/// deleted when a new value replaces it. Deletes come first.
+fn plan_operations(
+    current: &BTreeMap<Vec<String>, PathEnd>,
+    desired: &BTreeSet<Vec<String>>,
+) -> Vec<ConfigOperation> {
+    let mut deletes: Vec<Vec<String>> = Vec::new();
+    for (path, end) in current.iter().filter(|(path, _)| !desired.contains(*path)) {
+        let len = (1..=path.len())
+            .find(|len| !desired.iter().any(|wanted| wanted.starts_with(&path[..*len])))
+            .unwrap_or(path.len());
+        if len == path.len() && *end == PathEnd::SingleValue {
+            let node = &path[..path.len() - 1];
+            let mut values = desired.iter().filter(|wanted| wanted.starts_with(node));
+            let replaced = matches!(
+                (values.next(), values.next()),
+                (Some(value), None) if value.len() == path.len()
+            );
+            if replaced {
+                continue;
+            }
+        }
+        let target = path[..len].to_vec();
+        if !deletes.iter().any(|deleted| target.starts_with(deleted)) {
+            deletes.push(target);
+        }
+    }
+
+    deletes
+        .into_iter()
+        .map(ConfigOperation::delete)
+        .chain(
+            desired
+                .iter()
+                .filter(|path| !current.contains_key(*path))
+                .cloned()
+                .map(ConfigOperation::set),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(words: &str) -> Vec<String> {
+        super::words(words)
+    }
+
+    fn plan(current: Value, desired: &[&str]) -> Vec<ConfigOperation> {
+        let desired = desired.iter().map(|command| path(command)).collect();
+        plan_operations(&config_paths(&current), &desired)
+    }
+
+    #[test]
+    fn test_plan_converges_to_desired_state() {
+        let current = serde_json::json!({
+            "system": { "host-name": "edge-1", "time-zone": "UTC" },
+            "interfaces": { "ethernet": {
+                "eth0": { "address": ["192.0.2.1/24", "192.0.2.2/24"], "disable": {} },
+                "eth1": { "address": "198.51.100.1/24" },
+            } },
+        });
+        let desired = [
+            "system host-name edge-2",
+            "system time-zone UTC",
+            "interfaces ethernet eth0 address 192.0.2.1/24",
+            "interfaces ethernet eth0 description uplink",
+        ];
+
+        assert_eq!(
+            plan(current, &desired),
+            [
+                ConfigOperation::delete(path("interfaces ethernet eth0 address 192.0.2.2/24")),
+                ConfigOperation::delete(path("interfaces ethernet eth0 disable")),
+                ConfigOperation::delete(path("interfaces ethernet eth1")),
+                ConfigOperation::set(path("interfaces ethernet eth0 description uplink")),
+                ConfigOperation::set(path("system host-name edge-2")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_is_empty_when_converged() {
+        let current = serde_json::json!({ "system": { "host-name": "edge-1", "ntp": { "server": { "pool.ntp.org": {} } } } });
+        assert!(plan(current.clone(), &["system host-name edge-1", "system ntp server pool.ntp.org"]).is_empty());
+        assert_eq!(plan(current, &[]), [ConfigOperation::delete(path("system"))]);
+    }
+
+    #[test]
+    fn test_set_commands_become_paths() {
+        let commands = [
+            "# uplink".to_string(),
+            "set interfaces ethernet eth0 description 'Uplink to ISP'".to_string(),
+            "set interfaces ethernet eth0 address \"203.0.113.2/30\"".to_string(),
+            String::new(),
+        ];
+        let paths = command_paths(&commands, &path("interfaces")).unwrap();
+        assert_eq!(
+            paths.into_iter().collect::<Vec<_>>(),
+            [
+                vec!["ethernet".to_string(), "eth0".to_string(), "address".to_string(), "203.0.113.2/30".to_string()],
+                vec!["ethernet".to_string(), "eth0".to_string(), "description".to_string(), "Uplink to ISP".to_string()],
+            ]
+        );
+
+        assert!(command_paths(&["delete system".to_string()], &[]).is_err());
+        assert!(command_paths(&["set system host-name edge".to_string()], &path("interfaces")).is_err());
+        assert!(command_paths(&["set system host-name 'edge".to_string()], &[]).is_err());
+    }
+}
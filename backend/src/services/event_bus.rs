@@ -0,0 +1,205 @@
+//! Event Bus
+//!
+//! Publishes domain events to WebSocket clients. Every event is stored in the
+//! `events` table with a sequence number before it is broadcast, so clients
+//! that reconnect can replay the events they missed, starting from the last
+//! sequence number they received.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::event::{DomainEvent, EventReplayQuery, EventReplayResponse, MAX_REPLAY_EVENTS};
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::services::ChannelAccessService;
+use crate::websocket::{ConnectionManager, WsMessage};
+
+/// Event bus
+#[derive(Clone)]
+pub struct EventBus {
+    db: Database,
+    connections: ConnectionManager,
+    /// Keeps broadcasts in sequence order
+    publish_lock: Arc<Mutex<()>>,
+}
+
+impl EventBus {
+    /// Create a new event bus
+    pub fn new(db: Database, connections: ConnectionManager) -> Self {
+        Self {
+            db,
+            connections,
+            publish_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Publish an event to the subscribers of a channel
+    pub async fn publish<T: Serialize>(&self, channel: &str, event: &T) {
+        self.send(channel, event, false).await;
+    }
+
+    /// Publish an event to every connection, subscribed to the channel or not
+    ///
+    /// For notices every UI must show, such as announcements.
+    pub async fn publish_to_all<T: Serialize>(&self, channel: &str, event: &T) {
+        self.send(channel, event, true).await;
+    }
+
+    /// Persist and broadcast an event
+    ///
+    /// Events that cannot be persisted are still broadcast, without a
+    /// sequence number.
+    async fn send<T: Serialize>(&self, channel: &str, event: &T, to_all: bool) {
+        let data = match serde_json::to_value(event) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Failed to serialize event on channel {}: {}", channel, e);
+                return;
+            }
+        };
+
+        let _guard = self.publish_lock.lock().await;
+        let sequence = match self.store(channel, &data, to_all).await {
+            Ok(sequence) => Some(sequence),
+            Err(e) => {
+                warn!("Failed to persist event on channel {}: {}", channel, e);
+                None
+            }
+        };
+
+        let message = WsMessage::Broadcast {
+            channel: channel.to_string(),
+            data,
+            sequence,
+        };
+        if to_all {
+            self.connections.broadcast_all(&message);
+        } else {
+            self.connections.broadcast(channel, &message);
+        }
+    }
+
+    async fn store(&self, channel: &str, data: &serde_json::Value, to_all: bool) -> Result<u64, AppError> {
+        let result = sqlx::query("INSERT INTO events (channel, data, to_all, created_at) VALUES (?, ?, ?, ?)")
+            .bind(channel)
+            .bind(data.to_string())
+            .bind(to_all)
+            .bind(db_now())
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(result.last_insert_rowid() as u64)
+    }
+
+    /// Events published after a sequence number that a user may see, oldest
+    /// first
+    ///
+    /// When channels are given, only events on them and events sent to every
+    /// connection are returned, and the user must be entitled to each of the
+    /// channels. Events on channels the user may not subscribe to are left
+    /// out, without holding back the cursor.
+    pub async fn replay(
+        &self,
+        query: EventReplayQuery,
+        claims: &Claims,
+        channel_access: &ChannelAccessService,
+    ) -> Result<EventReplayResponse, AppError> {
+        let limit = query.limit.unwrap_or(MAX_REPLAY_EVENTS).clamp(1, MAX_REPLAY_EVENTS);
+        let channels: Option<Vec<String>> = query.channels.map(|channels| {
+            channels
+                .split(',')
+                .map(str::trim)
+                .filter(|channel| !channel.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+        for channel in channels.iter().flatten() {
+            channel_access.ensure_can_subscribe(claims, channel).await?;
+        }
+
+        let mut where_clauses = vec!["sequence > ?".to_string()];
+        if let Some(channels) = &channels {
+            let mut audience = vec!["to_all = 1".to_string()];
+            if !channels.is_empty() {
+                audience.push(format!("channel IN ({})", vec!["?"; channels.len()].join(", ")));
+            }
+            where_clauses.push(format!("({})", audience.join(" OR ")));
+        }
+
+        let query_sql = format!(
+            "SELECT sequence, channel, data, to_all, created_at FROM events WHERE {} ORDER BY sequence LIMIT ?",
+            where_clauses.join(" AND ")
+        );
+        let mut rows_builder = sqlx::query_as::<_, EventRow>(&query_sql).bind(query.after as i64);
+        for channel in channels.iter().flatten() {
+            rows_builder = rows_builder.bind(channel);
+        }
+        let mut fetched: Vec<DomainEvent> = rows_builder
+            .bind(limit as i64 + 1)
+            .fetch_all(self.db.pool())
+            .await?
+            .into_iter()
+            .map(event_from_row)
+            .collect();
+
+        let has_more = fetched.len() > limit as usize;
+        fetched.truncate(limit as usize);
+        let cursor = fetched.last().map_or(query.after, |event| event.sequence);
+
+        let mut visible: HashMap<String, bool> = HashMap::new();
+        let mut events = Vec::with_capacity(fetched.len());
+        for event in fetched {
+            if !event.to_all {
+                let allowed = match visible.get(&event.channel) {
+                    Some(allowed) => *allowed,
+                    None => {
+                        let allowed = channel_access.ensure_can_subscribe(claims, &event.channel).await.is_ok();
+                        visible.insert(event.channel.clone(), allowed);
+                        allowed
+                    }
+                };
+                if !allowed {
+                    continue;
+                }
+            }
+            events.push(event);
+        }
+
+        Ok(EventReplayResponse {
+            events,
+            cursor,
+            has_more,
+        })
+    }
+
+    /// Drop events published before a point in time
+    ///
+    /// Returns the number of dropped events.
+    pub async fn prune(&self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM events WHERE created_at < ?")
+            .bind(format_timestamp(&before))
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+type EventRow = (i64, String, String, bool, String);
+
+fn event_from_row((sequence, channel, data, to_all, created_at): EventRow) -> DomainEvent {
+    DomainEvent {
+        sequence: sequence as u64,
+        channel,
+        data: serde_json::from_str(&data).unwrap_or(serde_json::Value::Null),
+        to_all,
+        created_at: parse_db_timestamp(&created_at),
+    }
+}
@@ -0,0 +1,1207 @@
+//! Firewall Service
+//!
+//! Rule sets, groups, and zones of a node's firewall, using the
+//! configuration syntax of VyOS 1.4 and later. The base chains of each
+//! family (`firewall ipv4 forward filter` and so on) are managed as rule sets
+//! named `forward`, `input`, and `output`, next to the named rule sets
+//! (`firewall ipv4 name WAN_IN`) that rules and zones jump to.
+//!
+//! Changes are checked against the rest of the firewall before they are
+//! written: rule numbers must be unique within a set, jump targets, groups,
+//! and zone rule sets must exist, and two enabled rules may not match
+//! overlapping source networks with otherwise the same criteria, since the
+//! later one would only ever see what the earlier one let through.
+
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+use serde_json::Value;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::firewall::{
+    ConnectionState, FirewallAction, FirewallConfig, FirewallFamily, FirewallGroup, FirewallGroupKind,
+    FirewallGroupSettings, FirewallMatch, FirewallRule, FirewallRuleSet, FirewallRuleSetSettings,
+    FirewallRuleSettings, FirewallZone, FirewallZoneFrom, FirewallZoneSettings, BASE_CHAINS, MAX_RULE_NUMBER,
+};
+use crate::models::node::NodeData;
+use crate::services::config_lock::ConfigLockService;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::config_tree::{entries, lookup, path, text, validate_name, values, ConfigTreeWriter};
+use crate::services::node_service::NodeService;
+
+/// Configuration path of the firewall groups
+const GROUP_PATH: &[&str] = &["firewall", "group"];
+
+/// Configuration path of the firewall zones
+const ZONE_PATH: &[&str] = &["firewall", "zone"];
+
+/// Protocols whose rules may match ports
+pub(crate) const PORT_PROTOCOLS: &[&str] = &["tcp", "udp", "tcp_udp"];
+
+/// Longest description accepted
+const MAX_DESCRIPTION_LEN: usize = 255;
+
+/// Firewall service
+#[derive(Clone)]
+pub struct FirewallService {
+    nodes: NodeService,
+    writer: ConfigTreeWriter,
+}
+
+impl FirewallService {
+    /// Create a new firewall service
+    pub fn new(nodes: NodeService, locks: ConfigLockService, sessions: ConfigSessionService) -> Self {
+        Self {
+            writer: ConfigTreeWriter::new(nodes.clone(), locks, sessions),
+            nodes,
+        }
+    }
+
+    /// Rule sets, groups, and zones of a node
+    pub async fn firewall(&self, node_id: Uuid) -> Result<NodeData<FirewallConfig>, AppError> {
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        Ok(config.map(|config| parse_firewall(&config)))
+    }
+
+    /// One rule set of a node, as configured now
+    pub async fn rule_set(
+        &self,
+        node_id: Uuid,
+        family: FirewallFamily,
+        name: &str,
+    ) -> Result<FirewallRuleSet, AppError> {
+        validate_name("Rule set", name)?;
+        let firewall = parse_firewall(&self.writer.current(node_id).await?);
+        find_rule_set(&firewall, family, name).cloned().ok_or_else(|| rule_set_not_found(family, name, node_id))
+    }
+
+    /// Create or replace a rule set with all of its rules
+    pub async fn set_rule_set(
+        &self,
+        node_id: Uuid,
+        family: FirewallFamily,
+        name: &str,
+        settings: FirewallRuleSetSettings,
+        claims: &Claims,
+    ) -> Result<FirewallRuleSet, AppError> {
+        validate_name("Rule set", name)?;
+        let firewall = parse_firewall(&self.writer.current(node_id).await?);
+        let settings = normalize_rule_set(&firewall, family, name, settings)?;
+        let rule_set = FirewallRuleSet {
+            family,
+            name: name.to_string(),
+            path: rule_set_path(family, name).join(" "),
+            settings,
+        };
+        self.writer
+            .replace(node_id, rule_set_path(family, name), rule_set_paths(&rule_set.settings), claims)
+            .await?;
+
+        info!("{} set firewall rule set {} {} on node {}", claims.username, family.as_str(), name, node_id);
+        Ok(rule_set)
+    }
+
+    /// Remove a rule set that no rule or zone refers to
+    pub async fn delete_rule_set(
+        &self,
+        node_id: Uuid,
+        family: FirewallFamily,
+        name: &str,
+        claims: &Claims,
+    ) -> Result<(), AppError> {
+        validate_name("Rule set", name)?;
+        let firewall = parse_firewall(&self.writer.current(node_id).await?);
+        if let Some(user) = rule_set_users(&firewall, family, name).first() {
+            return Err(AppError::Validation(format!("Rule set {} is used by {}", name, user)));
+        }
+        self.writer.replace(node_id, rule_set_path(family, name), Vec::new(), claims).await?;
+
+        info!("{} removed firewall rule set {} {} on node {}", claims.username, family.as_str(), name, node_id);
+        Ok(())
+    }
+
+    /// Create or replace one rule of an existing rule set
+    pub async fn set_rule(
+        &self,
+        node_id: Uuid,
+        family: FirewallFamily,
+        name: &str,
+        number: u32,
+        settings: FirewallRuleSettings,
+        claims: &Claims,
+    ) -> Result<FirewallRule, AppError> {
+        validate_name("Rule set", name)?;
+        let firewall = parse_firewall(&self.writer.current(node_id).await?);
+        let mut rule_set = find_rule_set(&firewall, family, name)
+            .cloned()
+            .ok_or_else(|| rule_set_not_found(family, name, node_id))?;
+        rule_set.settings.rules.retain(|rule| rule.number != number);
+        rule_set.settings.rules.push(FirewallRule { number, settings });
+        let normalized = normalize_rule_set(&firewall, family, name, rule_set.settings)?;
+        let rule = normalized
+            .rules
+            .into_iter()
+            .find(|rule| rule.number == number)
+            .ok_or_else(|| AppError::Internal(format!("Rule {} went missing", number)))?;
+
+        let mut root = rule_set_path(family, name);
+        root.extend(path(&["rule"], &[&number.to_string()]));
+        self.writer.replace(node_id, root, rule_paths(&rule.settings), claims).await?;
+
+        info!("{} set firewall rule {} {} {} on node {}", claims.username, family.as_str(), name, number, node_id);
+        Ok(rule)
+    }
+
+    /// Remove one rule of a rule set
+    pub async fn delete_rule(
+        &self,
+        node_id: Uuid,
+        family: FirewallFamily,
+        name: &str,
+        number: u32,
+        claims: &Claims,
+    ) -> Result<(), AppError> {
+        validate_name("Rule set", name)?;
+        let mut root = rule_set_path(family, name);
+        root.extend(path(&["rule"], &[&number.to_string()]));
+        self.writer.replace(node_id, root, Vec::new(), claims).await?;
+
+        info!("{} removed firewall rule {} {} {} on node {}", claims.username, family.as_str(), name, number, node_id);
+        Ok(())
+    }
+
+    /// Put the rules of a rule set in a new order
+    ///
+    /// `order` lists every rule number of the set once. The rules keep the
+    /// numbers the set already uses, handed out in ascending order, so the
+    /// gaps left for inserting rules stay where they were.
+    pub async fn reorder_rules(
+        &self,
+        node_id: Uuid,
+        family: FirewallFamily,
+        name: &str,
+        order: &[u32],
+        claims: &Claims,
+    ) -> Result<FirewallRuleSet, AppError> {
+        validate_name("Rule set", name)?;
+        let firewall = parse_firewall(&self.writer.current(node_id).await?);
+        let mut rule_set = find_rule_set(&firewall, family, name)
+            .cloned()
+            .ok_or_else(|| rule_set_not_found(family, name, node_id))?;
+        rule_set.settings.rules = reorder(std::mem::take(&mut rule_set.settings.rules), order)?;
+        rule_set.settings = normalize_rule_set(&firewall, family, name, rule_set.settings)?;
+        self.writer
+            .replace(node_id, rule_set_path(family, name), rule_set_paths(&rule_set.settings), claims)
+            .await?;
+
+        info!("{} reordered firewall rule set {} {} on node {}", claims.username, family.as_str(), name, node_id);
+        Ok(rule_set)
+    }
+
+    /// Create or replace a group
+    pub async fn set_group(
+        &self,
+        node_id: Uuid,
+        kind: FirewallGroupKind,
+        name: &str,
+        settings: FirewallGroupSettings,
+        claims: &Claims,
+    ) -> Result<FirewallGroup, AppError> {
+        validate_name("Group", name)?;
+        let settings = normalize_group(kind, settings)?;
+        let root = path(GROUP_PATH, &[kind.as_str(), name]);
+        self.writer.replace(node_id, root, group_paths(kind, &settings), claims).await?;
+
+        info!("{} set firewall {} {} on node {}", claims.username, kind.as_str(), name, node_id);
+        Ok(FirewallGroup {
+            kind,
+            name: name.to_string(),
+            settings,
+        })
+    }
+
+    /// Remove a group that no rule refers to
+    pub async fn delete_group(
+        &self,
+        node_id: Uuid,
+        kind: FirewallGroupKind,
+        name: &str,
+        claims: &Claims,
+    ) -> Result<(), AppError> {
+        validate_name("Group", name)?;
+        let firewall = parse_firewall(&self.writer.current(node_id).await?);
+        if let Some(user) = group_users(&firewall, kind, name).first() {
+            return Err(AppError::Validation(format!("Group {} is used by {}", name, user)));
+        }
+        self.writer.replace(node_id, path(GROUP_PATH, &[kind.as_str(), name]), Vec::new(), claims).await?;
+
+        info!("{} removed firewall {} {} on node {}", claims.username, kind.as_str(), name, node_id);
+        Ok(())
+    }
+
+    /// Create or replace a zone
+    pub async fn set_zone(
+        &self,
+        node_id: Uuid,
+        name: &str,
+        settings: FirewallZoneSettings,
+        claims: &Claims,
+    ) -> Result<FirewallZone, AppError> {
+        validate_name("Zone", name)?;
+        let firewall = parse_firewall(&self.writer.current(node_id).await?);
+        let settings = normalize_zone(&firewall, name, settings)?;
+        self.writer.replace(node_id, path(ZONE_PATH, &[name]), zone_paths(&settings), claims).await?;
+
+        info!("{} set firewall zone {} on node {}", claims.username, name, node_id);
+        Ok(FirewallZone {
+            name: name.to_string(),
+            settings,
+        })
+    }
+
+    /// Remove a zone that no other zone filters traffic from
+    pub async fn delete_zone(&self, node_id: Uuid, name: &str, claims: &Claims) -> Result<(), AppError> {
+        validate_name("Zone", name)?;
+        let firewall = parse_firewall(&self.writer.current(node_id).await?);
+        if let Some(zone) = firewall.zones.iter().find(|zone| zone.settings.from.iter().any(|from| from.zone == name)) {
+            return Err(AppError::Validation(format!("Zone {} is used by zone {}", name, zone.name)));
+        }
+        self.writer.replace(node_id, path(ZONE_PATH, &[name]), Vec::new(), claims).await?;
+
+        info!("{} removed firewall zone {} on node {}", claims.username, name, node_id);
+        Ok(())
+    }
+}
+
+fn rule_set_not_found(family: FirewallFamily, name: &str, node_id: Uuid) -> AppError {
+    AppError::NotFound(format!("{} is not configured on node {}", rule_set_path(family, name).join(" "), node_id))
+}
+
+/// Configuration path of a rule set: a base chain's filter or a named set
+fn rule_set_path(family: FirewallFamily, name: &str) -> Vec<String> {
+    if BASE_CHAINS.contains(&name) {
+        path(&["firewall", family.as_str(), name, "filter"], &[])
+    } else {
+        path(&["firewall", family.as_str(), "name"], &[name])
+    }
+}
+
+fn find_rule_set<'a>(firewall: &'a FirewallConfig, family: FirewallFamily, name: &str) -> Option<&'a FirewallRuleSet> {
+    firewall.rule_sets.iter().find(|set| set.family == family && set.name == name)
+}
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+fn parse_firewall(config: &Value) -> FirewallConfig {
+    let mut rule_sets = Vec::new();
+    for family in [FirewallFamily::Ipv4, FirewallFamily::Ipv6] {
+        let root = lookup(config, &["firewall", family.as_str()]);
+        for chain in BASE_CHAINS {
+            if let Some(filter) = root.and_then(|root| lookup(root, &[chain, "filter"])) {
+                rule_sets.push(FirewallRuleSet {
+                    family,
+                    name: chain.to_string(),
+                    path: rule_set_path(family, chain).join(" "),
+                    settings: parse_rule_set(filter),
+                });
+            }
+        }
+        for (name, set) in entries(root.and_then(|root| root.get("name"))) {
+            rule_sets.push(FirewallRuleSet {
+                family,
+                path: rule_set_path(family, &name).join(" "),
+                name,
+                settings: parse_rule_set(set),
+            });
+        }
+    }
+
+    let groups = lookup(config, GROUP_PATH);
+    let groups = FirewallGroupKind::ALL
+        .into_iter()
+        .flat_map(|kind| {
+            entries(groups.and_then(|groups| groups.get(kind.as_str())))
+                .into_iter()
+                .map(move |(name, group)| FirewallGroup {
+                    kind,
+                    name,
+                    settings: FirewallGroupSettings {
+                        description: text(group.get("description")),
+                        members: values(group.get(kind.member_key())),
+                    },
+                })
+        })
+        .collect();
+
+    let zones = entries(lookup(config, ZONE_PATH))
+        .into_iter()
+        .map(|(name, zone)| FirewallZone {
+            name,
+            settings: parse_zone(zone),
+        })
+        .collect();
+
+    FirewallConfig { rule_sets, groups, zones }
+}
+
+fn parse_rule_set(config: &Value) -> FirewallRuleSetSettings {
+    let mut rules: Vec<FirewallRule> = entries(config.get("rule"))
+        .into_iter()
+        .filter_map(|(number, rule)| {
+            Some(FirewallRule {
+                number: number.parse().ok()?,
+                settings: parse_rule(rule)?,
+            })
+        })
+        .collect();
+    rules.sort_by_key(|rule| rule.number);
+
+    FirewallRuleSetSettings {
+        default_action: text(config.get("default-action")).as_deref().and_then(FirewallAction::from_name),
+        description: text(config.get("description")),
+        rules,
+    }
+}
+
+/// Rule settings; rules without a known action are left out
+fn parse_rule(config: &Value) -> Option<FirewallRuleSettings> {
+    // VyOS 1.3 wrote states as `state established enable`
+    let state = match config.get("state") {
+        Some(Value::Object(states)) => states
+            .iter()
+            .filter(|(_, enabled)| text(Some(enabled)).as_deref() == Some("enable"))
+            .map(|(state, _)| state.clone())
+            .collect(),
+        state => values(state),
+    };
+
+    Some(FirewallRuleSettings {
+        action: FirewallAction::from_name(&text(config.get("action"))?)?,
+        jump_target: text(config.get("jump-target")),
+        description: text(config.get("description")),
+        protocol: text(config.get("protocol")),
+        source: parse_match(config.get("source")),
+        destination: parse_match(config.get("destination")),
+        state: state.iter().filter_map(|state| ConnectionState::from_name(state)).collect(),
+        log: config.get("log").is_some_and(|log| text(Some(log)).as_deref() != Some("disable")),
+        disabled: config.get("disable").is_some(),
+    })
+}
+
+fn parse_match(config: Option<&Value>) -> FirewallMatch {
+    let Some(config) = config else {
+        return FirewallMatch::default();
+    };
+    let group = |kind: &str| text(config.get("group").and_then(|group| group.get(kind)));
+    FirewallMatch {
+        address: text(config.get("address")),
+        port: text(config.get("port")),
+        address_group: group("address-group"),
+        network_group: group("network-group"),
+        port_group: group("port-group"),
+    }
+}
+
+fn parse_zone(config: &Value) -> FirewallZoneSettings {
+    // VyOS 1.4 lists interfaces under `member`, earlier releases directly
+    let interfaces = match config.get("member") {
+        Some(member) => values(member.get("interface")),
+        None => values(config.get("interface")),
+    };
+
+    FirewallZoneSettings {
+        description: text(config.get("description")),
+        default_action: text(config.get("default-action")).as_deref().and_then(FirewallAction::from_name),
+        local_zone: config.get("local-zone").is_some(),
+        interfaces,
+        from: entries(config.get("from"))
+            .into_iter()
+            .map(|(zone, from)| {
+                let rule_set = |key: &str| text(from.get("firewall").and_then(|firewall| firewall.get(key)));
+                FirewallZoneFrom {
+                    zone,
+                    ipv4_rule_set: rule_set("name"),
+                    ipv6_rule_set: rule_set("ipv6-name"),
+                }
+            })
+            .collect(),
+    }
+}
+
+// ============================================================================
+// Writing
+// ============================================================================
+
+/// Configuration paths of a rule set, relative to it
+fn rule_set_paths(settings: &FirewallRuleSetSettings) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    if let Some(action) = settings.default_action {
+        paths.push(path(&["default-action", action.as_str()], &[]));
+    }
+    if let Some(description) = &settings.description {
+        paths.push(path(&["description"], &[description]));
+    }
+    for rule in &settings.rules {
+        let number = rule.number.to_string();
+        for relative in rule_paths(&rule.settings) {
+            paths.push(path(&["rule", &number], &relative.iter().map(String::as_str).collect::<Vec<_>>()));
+        }
+    }
+    if paths.is_empty() {
+        // Keep the rule set even without rules or options
+        paths.push(Vec::new());
+    }
+    paths
+}
+
+/// Configuration paths of a rule, relative to it
+fn rule_paths(settings: &FirewallRuleSettings) -> Vec<Vec<String>> {
+    let mut paths = vec![path(&["action", settings.action.as_str()], &[])];
+    if let Some(target) = &settings.jump_target {
+        paths.push(path(&["jump-target"], &[target]));
+    }
+    if let Some(description) = &settings.description {
+        paths.push(path(&["description"], &[description]));
+    }
+    if let Some(protocol) = &settings.protocol {
+        paths.push(path(&["protocol"], &[protocol]));
+    }
+    for (side, criteria) in [("source", &settings.source), ("destination", &settings.destination)] {
+        if let Some(address) = &criteria.address {
+            paths.push(path(&[side, "address"], &[address]));
+        }
+        if let Some(port) = &criteria.port {
+            paths.push(path(&[side, "port"], &[port]));
+        }
+        for (kind, group) in [
+            ("address-group", &criteria.address_group),
+            ("network-group", &criteria.network_group),
+            ("port-group", &criteria.port_group),
+        ] {
+            if let Some(group) = group {
+                paths.push(path(&[side, "group", kind], &[group]));
+            }
+        }
+    }
+    for state in &settings.state {
+        paths.push(path(&["state", state.as_str()], &[]));
+    }
+    if settings.log {
+        paths.push(path(&["log"], &[]));
+    }
+    if settings.disabled {
+        paths.push(path(&["disable"], &[]));
+    }
+    paths
+}
+
+/// Configuration paths of a group, relative to it
+fn group_paths(kind: FirewallGroupKind, settings: &FirewallGroupSettings) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    if let Some(description) = &settings.description {
+        paths.push(path(&["description"], &[description]));
+    }
+    for member in &settings.members {
+        paths.push(path(&[kind.member_key()], &[member]));
+    }
+    paths
+}
+
+/// Configuration paths of a zone, relative to it
+fn zone_paths(settings: &FirewallZoneSettings) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    if let Some(description) = &settings.description {
+        paths.push(path(&["description"], &[description]));
+    }
+    if let Some(action) = settings.default_action {
+        paths.push(path(&["default-action", action.as_str()], &[]));
+    }
+    if settings.local_zone {
+        paths.push(path(&["local-zone"], &[]));
+    }
+    for interface in &settings.interfaces {
+        paths.push(path(&["member", "interface"], &[interface]));
+    }
+    for from in &settings.from {
+        if let Some(rule_set) = &from.ipv4_rule_set {
+            paths.push(path(&["from", &from.zone, "firewall", "name"], &[rule_set]));
+        }
+        if let Some(rule_set) = &from.ipv6_rule_set {
+            paths.push(path(&["from", &from.zone, "firewall", "ipv6-name"], &[rule_set]));
+        }
+    }
+    paths
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// Rules and zones referring to a rule set
+fn rule_set_users(firewall: &FirewallConfig, family: FirewallFamily, name: &str) -> Vec<String> {
+    let jumps = firewall
+        .rule_sets
+        .iter()
+        .filter(|set| set.family == family && set.name != name)
+        .flat_map(|set| {
+            set.settings
+                .rules
+                .iter()
+                .filter(|rule| rule.settings.jump_target.as_deref() == Some(name))
+                .map(move |rule| format!("{} rule {}", set.path, rule.number))
+        });
+    let zones = firewall
+        .zones
+        .iter()
+        .filter(|zone| {
+            zone.settings.from.iter().any(|from| match family {
+                FirewallFamily::Ipv4 => from.ipv4_rule_set.as_deref() == Some(name),
+                FirewallFamily::Ipv6 => from.ipv6_rule_set.as_deref() == Some(name),
+            })
+        })
+        .map(|zone| format!("zone {}", zone.name));
+    jumps.chain(zones).collect()
+}
+
+/// Rules referring to a group
+fn group_users(firewall: &FirewallConfig, kind: FirewallGroupKind, name: &str) -> Vec<String> {
+    firewall
+        .rule_sets
+        .iter()
+        .flat_map(|set| {
+            set.settings
+                .rules
+                .iter()
+                .filter(move |rule| {
+                    [&rule.settings.source, &rule.settings.destination]
+                        .into_iter()
+                        .any(|criteria| group_kind_refs(set.family, criteria).contains(&(kind, name)))
+                })
+                .map(move |rule| format!("{} rule {}", set.path, rule.number))
+        })
+        .collect()
+}
+
+/// Groups a match refers to, with their kinds for the rule set's family
+fn group_kind_refs(family: FirewallFamily, criteria: &FirewallMatch) -> Vec<(FirewallGroupKind, &str)> {
+    let (address, network) = match family {
+        FirewallFamily::Ipv4 => (FirewallGroupKind::AddressGroup, FirewallGroupKind::NetworkGroup),
+        FirewallFamily::Ipv6 => (FirewallGroupKind::Ipv6AddressGroup, FirewallGroupKind::Ipv6NetworkGroup),
+    };
+    [
+        (address, &criteria.address_group),
+        (network, &criteria.network_group),
+        (FirewallGroupKind::PortGroup, &criteria.port_group),
+    ]
+    .into_iter()
+    .filter_map(|(kind, group)| Some((kind, group.as_deref()?)))
+    .collect()
+}
+
+pub(crate) fn normalize_description(description: Option<String>) -> Result<Option<String>, AppError> {
+    let description = description.map(|text| text.trim().to_string()).filter(|text| !text.is_empty());
+    if description
+        .as_deref()
+        .is_some_and(|text| text.len() > MAX_DESCRIPTION_LEN || text.chars().any(char::is_control))
+    {
+        return Err(AppError::Validation(format!(
+            "Descriptions are single lines of at most {} characters",
+            MAX_DESCRIPTION_LEN
+        )));
+    }
+    Ok(description)
+}
+
+fn normalize_rule_set(
+    firewall: &FirewallConfig,
+    family: FirewallFamily,
+    name: &str,
+    settings: FirewallRuleSetSettings,
+) -> Result<FirewallRuleSetSettings, AppError> {
+    let base_chain = BASE_CHAINS.contains(&name);
+    if let Some(action) = settings.default_action {
+        let allowed: &[FirewallAction] = if base_chain {
+            &[FirewallAction::Accept, FirewallAction::Drop]
+        } else {
+            &[
+                FirewallAction::Accept,
+                FirewallAction::Drop,
+                FirewallAction::Reject,
+                FirewallAction::Return,
+                FirewallAction::Continue,
+            ]
+        };
+        if !allowed.contains(&action) {
+            return Err(AppError::Validation(format!(
+                "Rule set {} cannot have {} as its default action",
+                name,
+                action.as_str()
+            )));
+        }
+    }
+
+    let mut rules: Vec<FirewallRule> = Vec::new();
+    for rule in settings.rules {
+        if !(1..=MAX_RULE_NUMBER).contains(&rule.number) {
+            return Err(AppError::Validation(format!(
+                "Rule numbers are between 1 and {}, not {}",
+                MAX_RULE_NUMBER, rule.number
+            )));
+        }
+        if rules.iter().any(|other| other.number == rule.number) {
+            return Err(AppError::Validation(format!("Rule {} is listed twice", rule.number)));
+        }
+        let settings = normalize_rule(firewall, family, name, rule.number, rule.settings)?;
+        rules.push(FirewallRule { number: rule.number, settings });
+    }
+    rules.sort_by_key(|rule| rule.number);
+    check_overlapping_sources(&rules)?;
+
+    Ok(FirewallRuleSetSettings {
+        default_action: settings.default_action,
+        description: normalize_description(settings.description)?,
+        rules,
+    })
+}
+
+fn normalize_rule(
+    firewall: &FirewallConfig,
+    family: FirewallFamily,
+    rule_set: &str,
+    number: u32,
+    settings: FirewallRuleSettings,
+) -> Result<FirewallRuleSettings, AppError> {
+    let invalid = |message: String| AppError::Validation(format!("Rule {}: {}", number, message));
+
+    let jump_target = match (settings.action, settings.jump_target) {
+        (FirewallAction::Jump, Some(target)) => {
+            if target == rule_set || BASE_CHAINS.contains(&target.as_str()) {
+                return Err(invalid(format!("cannot jump to {}", target)));
+            }
+            if find_rule_set(firewall, family, &target).is_none() {
+                return Err(invalid(format!("jump target {} is not a {} rule set", target, family.as_str())));
+            }
+            Some(target)
+        }
+        (FirewallAction::Jump, None) => return Err(invalid("the jump action needs a jump target".to_string())),
+        (_, Some(_)) => return Err(invalid("only the jump action takes a jump target".to_string())),
+        (_, None) => None,
+    };
+
+    let protocol = match settings.protocol.map(|protocol| protocol.trim().to_ascii_lowercase()) {
+        Some(protocol) if protocol.is_empty() => None,
+        Some(protocol) => {
+            let valid = match protocol.parse::<u16>() {
+                Ok(number) => number <= 255,
+                Err(_) => protocol.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-')),
+            };
+            if !valid {
+                return Err(invalid(format!("'{}' is not a protocol", protocol)));
+            }
+            Some(protocol)
+        }
+        None => None,
+    };
+
+    let source = normalize_match(firewall, family, settings.source).map_err(|e| invalid(format!("source {}", e)))?;
+    let destination =
+        normalize_match(firewall, family, settings.destination).map_err(|e| invalid(format!("destination {}", e)))?;
+    let matches_ports = [&source, &destination]
+        .iter()
+        .any(|criteria| criteria.port.is_some() || criteria.port_group.is_some());
+    if matches_ports && !protocol.as_deref().is_some_and(|protocol| PORT_PROTOCOLS.contains(&protocol)) {
+        return Err(invalid("ports can only be matched for tcp, udp, or tcp_udp".to_string()));
+    }
+
+    let state: BTreeSet<ConnectionState> = settings.state.into_iter().collect();
+
+    Ok(FirewallRuleSettings {
+        action: settings.action,
+        jump_target,
+        description: normalize_description(settings.description)?,
+        protocol,
+        source,
+        destination,
+        state: state.into_iter().collect(),
+        log: settings.log,
+        disabled: settings.disabled,
+    })
+}
+
+/// Check one side of a rule; errors read after "source" or "destination"
+fn normalize_match(
+    firewall: &FirewallConfig,
+    family: FirewallFamily,
+    criteria: FirewallMatch,
+) -> Result<FirewallMatch, String> {
+    let blank = |value: Option<String>| value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+    let criteria = FirewallMatch {
+        address: blank(criteria.address).map(|address| normalize_rule_address(family, &address)).transpose()?,
+        port: blank(criteria.port).map(|port| normalize_ports(&port)).transpose()?,
+        address_group: blank(criteria.address_group),
+        network_group: blank(criteria.network_group),
+        port_group: blank(criteria.port_group),
+    };
+
+    let addresses = [&criteria.address, &criteria.address_group, &criteria.network_group];
+    if addresses.iter().filter(|value| value.is_some()).count() > 1 {
+        return Err("matches either an address, an address group, or a network group".to_string());
+    }
+    if criteria.port.is_some() && criteria.port_group.is_some() {
+        return Err("matches either ports or a port group".to_string());
+    }
+    for (kind, name) in group_kind_refs(family, &criteria) {
+        if !firewall.groups.iter().any(|group| group.kind == kind && group.name == name) {
+            return Err(format!("{} {} does not exist", kind.as_str(), name));
+        }
+    }
+    Ok(criteria)
+}
+
+/// Address, network, or range of a rule in canonical form, optionally
+/// negated with a leading `!`
+fn normalize_rule_address(family: FirewallFamily, address: &str) -> Result<String, String> {
+    let (negated, address) = match address.strip_prefix('!') {
+        Some(address) => ("!", address),
+        None => ("", address),
+    };
+    let normalized = match address.split_once('-') {
+        Some((start, stop)) => {
+            let (start, stop) = (parse_address(family, start)?, parse_address(family, stop)?);
+            if start > stop {
+                return Err(format!("range {}-{} is not ascending", start, stop));
+            }
+            format!("{}-{}", start, stop)
+        }
+        None if address.contains('/') => parse_network(family, address)?.to_string(),
+        None => parse_address(family, address)?.to_string(),
+    };
+    Ok(format!("{}{}", negated, normalized))
+}
+
+fn parse_address(family: FirewallFamily, address: &str) -> Result<IpAddr, String> {
+    let parsed: IpAddr = address.trim().parse().map_err(|_| format!("'{}' is not an address", address))?;
+    match (family, parsed) {
+        (FirewallFamily::Ipv4, IpAddr::V4(_)) | (FirewallFamily::Ipv6, IpAddr::V6(_)) => Ok(parsed),
+        _ => Err(format!("'{}' is not an {} address", address, family.as_str())),
+    }
+}
+
+/// Network in canonical form, rejecting addresses with host bits set
+fn parse_network(family: FirewallFamily, network: &str) -> Result<IpNet, String> {
+    let net: IpNet = network.trim().parse().map_err(|_| format!("'{}' is not a network", network))?;
+    parse_address(family, &net.addr().to_string())?;
+    if net != net.trunc() {
+        return Err(format!("'{}' has host bits set; did you mean {}?", network, net.trunc()));
+    }
+    Ok(net)
+}
+
+/// Comma-separated ports, port ranges, and service names
+pub(crate) fn normalize_ports(ports: &str) -> Result<String, String> {
+    let port = |port: &str| port.parse::<u16>().ok().filter(|port| *port > 0);
+    let mut items = Vec::new();
+    for item in ports.split(',').map(str::trim) {
+        let valid = match item.split_once('-') {
+            Some((start, stop)) => port(start).zip(port(stop)).is_some_and(|(start, stop)| start <= stop),
+            None => {
+                port(item).is_some()
+                    || (!item.is_empty()
+                        && item.starts_with(|c: char| c.is_ascii_alphabetic())
+                        && item.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+            }
+        };
+        if !valid {
+            return Err(format!("port '{}' is not a port, port range, or service name", item));
+        }
+        items.push(item.to_string());
+    }
+    Ok(items.join(","))
+}
+
+/// Reject enabled rules that match overlapping source networks with
+/// otherwise the same criteria
+fn check_overlapping_sources(rules: &[FirewallRule]) -> Result<(), AppError> {
+    let network = |rule: &FirewallRule| -> Option<IpNet> {
+        let address = rule.settings.source.address.as_deref()?;
+        if rule.settings.disabled || address.starts_with('!') || address.contains('-') {
+            return None;
+        }
+        address.parse().ok().or_else(|| address.parse::<IpAddr>().ok().map(IpNet::from))
+    };
+    let criteria = |rule: &FirewallRule| {
+        let mut settings = rule.settings.clone();
+        settings.source.address = None;
+        (settings.protocol, settings.source, settings.destination, settings.state)
+    };
+
+    for (index, earlier) in rules.iter().enumerate() {
+        let Some(earlier_net) = network(earlier) else {
+            continue;
+        };
+        for later in &rules[index + 1..] {
+            let Some(later_net) = network(later) else {
+                continue;
+            };
+            let overlap = earlier_net.contains(&later_net) || later_net.contains(&earlier_net);
+            if overlap && criteria(earlier) == criteria(later) {
+                return Err(AppError::Validation(format!(
+                    "Rules {} and {} match overlapping sources {} and {} with otherwise the same criteria",
+                    earlier.number, later.number, earlier_net, later_net
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Rules in the requested order, taking the existing numbers in ascending
+/// order
+fn reorder(rules: Vec<FirewallRule>, order: &[u32]) -> Result<Vec<FirewallRule>, AppError> {
+    let mut numbers: Vec<u32> = rules.iter().map(|rule| rule.number).collect();
+    let mut requested = order.to_vec();
+    numbers.sort_unstable();
+    requested.sort_unstable();
+    if numbers != requested {
+        return Err(AppError::Validation(
+            "The new order must list every rule number of the rule set once".to_string(),
+        ));
+    }
+
+    let mut rules = rules;
+    Ok(order
+        .iter()
+        .zip(numbers)
+        .map(|(old, number)| {
+            let index = rules.iter().position(|rule| rule.number == *old).unwrap_or_default();
+            FirewallRule {
+                number,
+                settings: rules.swap_remove(index).settings,
+            }
+        })
+        .collect())
+}
+
+fn normalize_group(kind: FirewallGroupKind, settings: FirewallGroupSettings) -> Result<FirewallGroupSettings, AppError> {
+    if settings.members.is_empty() {
+        return Err(AppError::Validation("A group needs at least one member".to_string()));
+    }
+
+    let family = match kind {
+        FirewallGroupKind::Ipv6AddressGroup | FirewallGroupKind::Ipv6NetworkGroup => FirewallFamily::Ipv6,
+        _ => FirewallFamily::Ipv4,
+    };
+    let mut members: Vec<String> = Vec::new();
+    for member in &settings.members {
+        let member = member.trim();
+        let normalized = match kind {
+            FirewallGroupKind::AddressGroup | FirewallGroupKind::Ipv6AddressGroup => match member.split_once('-') {
+                Some(_) => normalize_rule_address(family, member),
+                None => parse_address(family, member).map(|address| address.to_string()),
+            },
+            FirewallGroupKind::NetworkGroup | FirewallGroupKind::Ipv6NetworkGroup => {
+                parse_network(family, member).map(|network| network.to_string())
+            }
+            FirewallGroupKind::PortGroup => normalize_ports(member),
+        }
+        .map_err(|e| AppError::Validation(format!("Member {}", e)))?;
+        if normalized.starts_with('!') || members.contains(&normalized) {
+            return Err(AppError::Validation(format!("Member {} is not valid here or listed twice", normalized)));
+        }
+        members.push(normalized);
+    }
+
+    Ok(FirewallGroupSettings {
+        description: normalize_description(settings.description)?,
+        members,
+    })
+}
+
+fn normalize_zone(
+    firewall: &FirewallConfig,
+    name: &str,
+    settings: FirewallZoneSettings,
+) -> Result<FirewallZoneSettings, AppError> {
+    if let Some(action) = settings.default_action {
+        if !matches!(action, FirewallAction::Drop | FirewallAction::Reject) {
+            return Err(AppError::Validation("Zones drop or reject by default".to_string()));
+        }
+    }
+    if settings.local_zone != settings.interfaces.is_empty() {
+        return Err(AppError::Validation(
+            "A zone has interfaces unless it is the local zone, which has none".to_string(),
+        ));
+    }
+    if settings.local_zone {
+        if let Some(local) = firewall.zones.iter().find(|zone| zone.settings.local_zone && zone.name != name) {
+            return Err(AppError::Validation(format!("Zone {} is already the local zone", local.name)));
+        }
+    }
+
+    let mut interfaces: Vec<String> = Vec::new();
+    for interface in settings.interfaces {
+        validate_name("Interface", &interface)?;
+        if let Some(zone) = firewall
+            .zones
+            .iter()
+            .find(|zone| zone.name != name && zone.settings.interfaces.contains(&interface))
+        {
+            return Err(AppError::Validation(format!("Interface {} is already in zone {}", interface, zone.name)));
+        }
+        if !interfaces.contains(&interface) {
+            interfaces.push(interface);
+        }
+    }
+
+    let mut from: Vec<FirewallZoneFrom> = Vec::new();
+    for source in settings.from {
+        validate_name("Zone", &source.zone)?;
+        if source.zone == name || from.iter().any(|other| other.zone == source.zone) {
+            return Err(AppError::Validation(format!("Zone {} cannot be listed here", source.zone)));
+        }
+        if source.ipv4_rule_set.is_none() && source.ipv6_rule_set.is_none() {
+            return Err(AppError::Validation(format!("Traffic from zone {} needs a rule set", source.zone)));
+        }
+        for (family, rule_set) in [
+            (FirewallFamily::Ipv4, &source.ipv4_rule_set),
+            (FirewallFamily::Ipv6, &source.ipv6_rule_set),
+        ] {
+            if let Some(rule_set) = rule_set {
+                if BASE_CHAINS.contains(&rule_set.as_str()) || find_rule_set(firewall, family, rule_set).is_none() {
+                    return Err(AppError::Validation(format!(
+                        "{} is not a named {} rule set",
+                        rule_set,
+                        family.as_str()
+                    )));
+                }
+            }
+        }
+        from.push(source);
+    }
+
+    Ok(FirewallZoneSettings {
+        description: normalize_description(settings.description)?,
+        default_action: settings.default_action,
+        local_zone: settings.local_zone,
+        interfaces,
+        from,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn words(path: &str) -> Vec<String> {
+        path.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn rule(number: u32, action: FirewallAction, source: Option<&str>) -> FirewallRule {
+        FirewallRule {
+            number,
+            settings: FirewallRuleSettings {
+                action,
+                jump_target: None,
+                description: None,
+                protocol: Some("tcp".to_string()),
+                source: FirewallMatch {
+                    address: source.map(str::to_string),
+                    ..Default::default()
+                },
+                destination: FirewallMatch {
+                    port: Some("22".to_string()),
+                    ..Default::default()
+                },
+                state: vec![],
+                log: false,
+                disabled: false,
+            },
+        }
+    }
+
+    fn rule_set(rules: Vec<FirewallRule>) -> FirewallRuleSetSettings {
+        FirewallRuleSetSettings {
+            default_action: Some(FirewallAction::Drop),
+            description: None,
+            rules,
+        }
+    }
+
+    fn config() -> Value {
+        json!({
+            "firewall": {
+                "group": {
+                    "address-group": { "ADMINS": { "address": ["192.0.2.10", "192.0.2.11"] } },
+                    "port-group": { "WEB": { "port": "80", "description": "Web" } },
+                },
+                "ipv4": {
+                    "forward": { "filter": {
+                        "default-action": "accept",
+                        "rule": {
+                            "20": { "action": "jump", "jump-target": "WAN_IN" },
+                            "10": { "action": "accept", "state": ["established", "related"] },
+                        },
+                    } },
+                    "name": { "WAN_IN": {
+                        "default-action": "drop",
+                        "rule": { "10": {
+                            "action": "accept",
+                            "protocol": "tcp",
+                            "source": { "group": { "address-group": "ADMINS" } },
+                            "destination": { "port": "22" },
+                            "log": {},
+                        } },
+                    } },
+                },
+                "zone": {
+                    "LAN": { "member": { "interface": "eth1" }, "from": { "WAN": { "firewall": { "name": "WAN_IN" } } } },
+                    "WAN": { "member": { "interface": ["eth0"] }, "default-action": "drop" },
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn test_firewall_round_trip() {
+        let firewall = parse_firewall(&config());
+        assert_eq!(firewall.rule_sets.len(), 2);
+        let forward = &firewall.rule_sets[0];
+        assert_eq!((forward.name.as_str(), forward.path.as_str()), ("forward", "firewall ipv4 forward filter"));
+        assert_eq!(forward.settings.rules.iter().map(|rule| rule.number).collect::<Vec<_>>(), [10, 20]);
+        assert_eq!(forward.settings.rules[0].settings.state, [ConnectionState::Established, ConnectionState::Related]);
+
+        let wan_in = &firewall.rule_sets[1];
+        assert_eq!(wan_in.path, "firewall ipv4 name WAN_IN");
+        assert_eq!(
+            rule_set_paths(&wan_in.settings),
+            [
+                words("default-action drop"),
+                words("rule 10 action accept"),
+                words("rule 10 protocol tcp"),
+                words("rule 10 source group address-group ADMINS"),
+                words("rule 10 destination port 22"),
+                words("rule 10 log"),
+            ]
+        );
+
+        assert_eq!(firewall.groups.len(), 2);
+        assert_eq!(firewall.groups[0].settings.members, ["192.0.2.10", "192.0.2.11"]);
+        assert_eq!(firewall.groups[1].kind, FirewallGroupKind::PortGroup);
+
+        let lan = &firewall.zones[0];
+        assert_eq!(lan.settings.interfaces, ["eth1"]);
+        assert_eq!(
+            zone_paths(&lan.settings),
+            [words("member interface eth1"), words("from WAN firewall name WAN_IN")]
+        );
+        assert_eq!(rule_set_users(&firewall, FirewallFamily::Ipv4, "WAN_IN").len(), 2);
+        assert_eq!(group_users(&firewall, FirewallGroupKind::AddressGroup, "ADMINS"), ["firewall ipv4 name WAN_IN rule 10"]);
+        assert!(group_users(&firewall, FirewallGroupKind::PortGroup, "WEB").is_empty());
+    }
+
+    #[test]
+    fn test_rule_sets_are_validated() {
+        let firewall = parse_firewall(&config());
+        let check = |rules| normalize_rule_set(&firewall, FirewallFamily::Ipv4, "SSH", rule_set(rules));
+
+        let normalized = check(vec![
+            rule(20, FirewallAction::Drop, Some("10.0.0.0/8")),
+            rule(10, FirewallAction::Accept, Some("192.0.2.0/24")),
+        ])
+        .unwrap();
+        assert_eq!(normalized.rules[0].number, 10);
+
+        // Duplicate numbers and overlapping sources
+        assert!(check(vec![rule(10, FirewallAction::Accept, None), rule(10, FirewallAction::Drop, None)]).is_err());
+        let overlap = check(vec![
+            rule(10, FirewallAction::Accept, Some("10.1.0.0/16")),
+            rule(20, FirewallAction::Drop, Some("10.0.0.0/8")),
+        ]);
+        assert!(matches!(overlap, Err(AppError::Validation(message)) if message.contains("Rules 10 and 20")));
+        let mut other_port = rule(20, FirewallAction::Drop, Some("10.0.0.0/8"));
+        other_port.settings.destination.port = Some("443".to_string());
+        assert!(check(vec![rule(10, FirewallAction::Accept, Some("10.1.0.0/16")), other_port]).is_ok());
+        let mut disabled = rule(20, FirewallAction::Drop, Some("10.0.0.0/8"));
+        disabled.settings.disabled = true;
+        assert!(check(vec![rule(10, FirewallAction::Accept, Some("10.1.0.0/16")), disabled]).is_ok());
+
+        // Addresses, ports, jumps, and groups
+        assert!(check(vec![rule(10, FirewallAction::Accept, Some("10.0.0.1/8"))]).is_err());
+        assert!(check(vec![rule(10, FirewallAction::Accept, Some("2001:db8::/32"))]).is_err());
+        let mut udp_less = rule(10, FirewallAction::Accept, None);
+        udp_less.settings.protocol = Some("icmp".to_string());
+        assert!(check(vec![udp_less]).is_err());
+        let mut jump = rule(10, FirewallAction::Jump, None);
+        jump.settings.jump_target = Some("WAN_IN".to_string());
+        assert!(check(vec![jump.clone()]).is_ok());
+        jump.settings.jump_target = Some("MISSING".to_string());
+        assert!(check(vec![jump]).is_err());
+        let mut grouped = rule(10, FirewallAction::Accept, None);
+        grouped.settings.destination = FirewallMatch {
+            port_group: Some("WEB".to_string()),
+            ..Default::default()
+        };
+        assert!(check(vec![grouped.clone()]).is_ok());
+        grouped.settings.destination.port_group = Some("MAIL".to_string());
+        assert!(check(vec![grouped]).is_err());
+
+        // Base chains only accept or drop by default
+        let mut settings = rule_set(vec![]);
+        settings.default_action = Some(FirewallAction::Reject);
+        assert!(normalize_rule_set(&firewall, FirewallFamily::Ipv4, "input", settings).is_err());
+    }
+
+    #[test]
+    fn test_reorder_keeps_the_numbers() {
+        let rules = vec![
+            rule(10, FirewallAction::Accept, None),
+            rule(20, FirewallAction::Drop, None),
+            rule(30, FirewallAction::Reject, None),
+        ];
+        let reordered = reorder(rules.clone(), &[30, 10, 20]).unwrap();
+        let summary: Vec<(u32, FirewallAction)> =
+            reordered.iter().map(|rule| (rule.number, rule.settings.action)).collect();
+        assert_eq!(
+            summary,
+            [(10, FirewallAction::Reject), (20, FirewallAction::Accept), (30, FirewallAction::Drop)]
+        );
+        assert!(reorder(rules.clone(), &[30, 10]).is_err());
+        assert!(reorder(rules, &[30, 10, 10]).is_err());
+    }
+
+    #[test]
+    fn test_groups_and_zones_are_validated() {
+        let settings = |members: &[&str]| FirewallGroupSettings {
+            description: None,
+            members: members.iter().map(|member| member.to_string()).collect(),
+        };
+        let group = normalize_group(FirewallGroupKind::NetworkGroup, settings(&["10.0.0.0/8"])).unwrap();
+        assert_eq!(group_paths(FirewallGroupKind::NetworkGroup, &group), [words("network 10.0.0.0/8")]);
+        assert!(normalize_group(FirewallGroupKind::NetworkGroup, settings(&["10.0.0.1/8"])).is_err());
+        assert!(normalize_group(FirewallGroupKind::Ipv6AddressGroup, settings(&["192.0.2.1"])).is_err());
+        assert!(normalize_group(FirewallGroupKind::PortGroup, settings(&["443", "8000-8080", "https"])).is_ok());
+        assert!(normalize_group(FirewallGroupKind::PortGroup, settings(&[])).is_err());
+
+        let firewall = parse_firewall(&config());
+        let zone = |interfaces: &[&str], rule_set: &str| FirewallZoneSettings {
+            interfaces: interfaces.iter().map(|interface| interface.to_string()).collect(),
+            from: vec![FirewallZoneFrom {
+                zone: "WAN".to_string(),
+                ipv4_rule_set: Some(rule_set.to_string()),
+                ipv6_rule_set: None,
+            }],
+            ..Default::default()
+        };
+        assert!(normalize_zone(&firewall, "DMZ", zone(&["eth2"], "WAN_IN")).is_ok());
+        assert!(normalize_zone(&firewall, "DMZ", zone(&["eth0"], "WAN_IN")).is_err());
+        assert!(normalize_zone(&firewall, "DMZ", zone(&["eth2"], "forward")).is_err());
+        assert!(normalize_zone(&firewall, "DMZ", zone(&[], "WAN_IN")).is_err());
+    }
+}
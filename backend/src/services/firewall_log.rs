@@ -0,0 +1,465 @@
+//! Firewall Log Service
+//!
+//! Stores the packets that node firewall rules log, as received over syslog,
+//! and ties each one to the rule that logged it through the rule's log
+//! prefix. Operators can then search the log and see what each rule has
+//! recently matched.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::firewall_log::{
+    FirewallLogAction, FirewallLogEntry, FirewallLogListResponse, FirewallLogQuery, FirewallRuleHits,
+    FirewallRuleHitsQuery, FirewallRuleHitsResponse, DEFAULT_RULE_HITS_WINDOW, MAX_RECENT_RULE_HITS,
+};
+use crate::models::node::Node;
+use crate::models::team::AccessScope;
+use crate::models::time_range::resolve_time_range;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::services::geoip::GeoIpService;
+use crate::services::node_service::NodeService;
+
+const FIREWALL_LOG_COLUMNS: &str = "id, node_id, logged_at, rule_id, chain, rule_number, action, protocol, \
+     in_interface, out_interface, src_ip, src_port, dst_ip, dst_port, length, message";
+
+/// Firewall log service
+#[derive(Clone)]
+pub struct FirewallLogService {
+    db: Database,
+    nodes: NodeService,
+    geoip: GeoIpService,
+}
+
+impl FirewallLogService {
+    /// Create a new firewall log service
+    pub fn new(db: Database, nodes: NodeService, geoip: GeoIpService) -> Self {
+        Self { db, nodes, geoip }
+    }
+
+    /// Store a log line received from a node
+    ///
+    /// Returns whether the line was a firewall log entry; other lines are
+    /// ignored.
+    pub async fn ingest(&self, node_id: Uuid, message: &str) -> Result<bool, AppError> {
+        let Some(parsed) = parse_firewall_log(message) else {
+            return Ok(false);
+        };
+
+        sqlx::query(
+            "INSERT INTO firewall_logs (node_id, logged_at, rule_id, chain, rule_number, action, protocol, \
+             in_interface, out_interface, src_ip, src_port, dst_ip, dst_port, length, message) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(node_id.to_string())
+        .bind(db_now())
+        .bind(&parsed.rule_id)
+        .bind(&parsed.chain)
+        .bind(parsed.rule_number.map(i64::from))
+        .bind(parsed.action.as_str())
+        .bind(&parsed.protocol)
+        .bind(&parsed.in_interface)
+        .bind(&parsed.out_interface)
+        .bind(&parsed.src_ip)
+        .bind(parsed.src_port.map(i64::from))
+        .bind(&parsed.dst_ip)
+        .bind(parsed.dst_port.map(i64::from))
+        .bind(parsed.length.map(i64::from))
+        .bind(message.trim())
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(true)
+    }
+
+    /// List firewall log entries of the nodes within the caller's scope
+    pub async fn list(&self, mut query: FirewallLogQuery, scope: &AccessScope) -> Result<FirewallLogListResponse, AppError> {
+        resolve_time_range(&mut query.from, &mut query.to, query.window, query.preset)?;
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(50).clamp(1, 500);
+        let offset = (page - 1) * page_size;
+
+        let nodes = self.accessible_nodes(scope, query.node_id).await?;
+        let (where_clause, bind_values) = log_filter(&query, &nodes);
+
+        let count_query = format!("SELECT COUNT(*) FROM firewall_logs WHERE {}", where_clause);
+        let mut count = sqlx::query_scalar::<_, i64>(&count_query);
+        for value in &bind_values {
+            count = count.bind(value);
+        }
+        let total = count.fetch_one(self.db.pool()).await? as u64;
+
+        let data_query = format!(
+            "SELECT {} FROM firewall_logs WHERE {} ORDER BY logged_at DESC, id DESC LIMIT ? OFFSET ?",
+            FIREWALL_LOG_COLUMNS, where_clause
+        );
+        let mut rows = sqlx::query_as::<_, FirewallLogRow>(&data_query);
+        for value in &bind_values {
+            rows = rows.bind(value);
+        }
+        let rows = rows
+            .bind(page_size as i64)
+            .bind(offset as i64)
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(FirewallLogListResponse {
+            entries: rows.into_iter().map(|row| self.entry_from_row(row, &nodes)).collect(),
+            total,
+            page,
+            page_size,
+            total_pages: ((total as f64) / (page_size as f64)).ceil() as u32,
+        })
+    }
+
+    /// Hit counts and recent hits of each rule that logged packets
+    pub async fn rule_hits(
+        &self,
+        query: FirewallRuleHitsQuery,
+        scope: &AccessScope,
+    ) -> Result<FirewallRuleHitsResponse, AppError> {
+        let since = query.since.unwrap_or_else(|| Utc::now() - DEFAULT_RULE_HITS_WINDOW);
+        let recent = query.recent.unwrap_or(5).min(MAX_RECENT_RULE_HITS);
+
+        let nodes = self.accessible_nodes(scope, query.node_id).await?;
+        let filter = FirewallLogQuery {
+            from: Some(since),
+            ..Default::default()
+        };
+        let (where_clause, bind_values) = log_filter(&filter, &nodes);
+
+        let summary_query = format!(
+            "SELECT node_id, rule_id, COUNT(*), MAX(logged_at) FROM firewall_logs \
+             WHERE {} AND rule_id IS NOT NULL GROUP BY node_id, rule_id ORDER BY COUNT(*) DESC, rule_id",
+            where_clause
+        );
+        let mut summary = sqlx::query_as::<_, (String, String, i64, String)>(&summary_query);
+        for value in &bind_values {
+            summary = summary.bind(value);
+        }
+        let summary = summary.fetch_all(self.db.pool()).await?;
+
+        let recent_query = format!(
+            "SELECT {} FROM firewall_logs WHERE {} AND node_id = ? AND rule_id = ? \
+             ORDER BY logged_at DESC, id DESC LIMIT ?",
+            FIREWALL_LOG_COLUMNS, where_clause
+        );
+        let mut rules = Vec::with_capacity(summary.len());
+        for (node_id, rule_id, hits, last_hit) in summary {
+            let mut rows = sqlx::query_as::<_, FirewallLogRow>(&recent_query);
+            for value in &bind_values {
+                rows = rows.bind(value);
+            }
+            let entries: Vec<FirewallLogEntry> = rows
+                .bind(&node_id)
+                .bind(&rule_id)
+                .bind(i64::from(recent.max(1)))
+                .fetch_all(self.db.pool())
+                .await?
+                .into_iter()
+                .map(|row| self.entry_from_row(row, &nodes))
+                .collect();
+            let Some(latest) = entries.first() else {
+                continue;
+            };
+
+            rules.push(FirewallRuleHits {
+                node_id: latest.node_id,
+                node_name: latest.node_name.clone(),
+                rule_path: latest.rule_path.clone(),
+                chain: latest.chain.clone(),
+                rule_number: latest.rule_number,
+                action: latest.action,
+                rule_id,
+                hits: hits as u64,
+                last_hit: parse_db_timestamp(&last_hit),
+                recent: entries.into_iter().take(recent as usize).collect(),
+            });
+        }
+
+        Ok(FirewallRuleHitsResponse { since, rules })
+    }
+
+    /// Drop entries logged before a point in time
+    pub async fn prune(&self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM firewall_logs WHERE logged_at < ?")
+            .bind(format_timestamp(&before))
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Nodes within the caller's scope, narrowed to one node if requested
+    async fn accessible_nodes(&self, scope: &AccessScope, node_id: Option<Uuid>) -> Result<HashMap<Uuid, Node>, AppError> {
+        Ok(self
+            .nodes
+            .list_all_nodes()
+            .await?
+            .into_iter()
+            .filter(|node| scope.can_access(node.team_id) && node_id.is_none_or(|id| id == node.id))
+            .map(|node| (node.id, node))
+            .collect())
+    }
+
+    fn entry_from_row(
+        &self,
+        (id, node_id, logged_at, rule_id, chain, rule_number, action, protocol, in_interface, out_interface, src_ip, src_port, dst_ip, dst_port, length, message): FirewallLogRow,
+        nodes: &HashMap<Uuid, Node>,
+    ) -> FirewallLogEntry {
+        let node_id = Uuid::parse_str(&node_id).unwrap_or_default();
+        let rule_number = rule_number.and_then(|n| u32::try_from(n).ok());
+        let locate = |ip: &Option<String>| ip.as_deref().and_then(|ip| ip.parse::<IpAddr>().ok()).and_then(|ip| self.geoip.locate(ip));
+
+        FirewallLogEntry {
+            id,
+            node_id,
+            node_name: nodes.get(&node_id).map(|node| node.name.clone()).unwrap_or_default(),
+            logged_at: parse_db_timestamp(&logged_at),
+            rule_path: rule_path(&chain, rule_number),
+            rule_id,
+            chain,
+            rule_number,
+            action: FirewallLogAction::from_db(&action),
+            protocol,
+            in_interface,
+            out_interface,
+            src_geo: locate(&src_ip),
+            dst_geo: locate(&dst_ip),
+            src_ip,
+            src_port: src_port.and_then(|port| u16::try_from(port).ok()),
+            dst_ip,
+            dst_port: dst_port.and_then(|port| u16::try_from(port).ok()),
+            length: length.and_then(|length| u32::try_from(length).ok()),
+            message,
+        }
+    }
+}
+
+/// Firewall log columns as selected by [`FIREWALL_LOG_COLUMNS`]
+type FirewallLogRow = (
+    i64,
+    String,
+    String,
+    Option<String>,
+    String,
+    Option<i64>,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+    Option<String>,
+    Option<i64>,
+    Option<i64>,
+    String,
+);
+
+/// WHERE clause and bind values of a firewall log query
+///
+/// Entries are always limited to the given nodes.
+fn log_filter(query: &FirewallLogQuery, nodes: &HashMap<Uuid, Node>) -> (String, Vec<String>) {
+    let mut where_clauses = vec![];
+    let mut bind_values: Vec<String> = nodes.keys().map(Uuid::to_string).collect();
+
+    where_clauses.push(if nodes.is_empty() {
+        "0=1".to_string()
+    } else {
+        format!("node_id IN ({})", vec!["?"; nodes.len()].join(", "))
+    });
+
+    if let Some(rule_id) = &query.rule_id {
+        where_clauses.push("rule_id = ?".to_string());
+        bind_values.push(rule_id.clone());
+    }
+
+    if let Some(action) = query.action {
+        where_clauses.push("action = ?".to_string());
+        bind_values.push(action.as_str().to_string());
+    }
+
+    if let Some(protocol) = &query.protocol {
+        where_clauses.push("UPPER(protocol) = ?".to_string());
+        bind_values.push(protocol.to_uppercase());
+    }
+
+    if let Some(src_ip) = &query.src_ip {
+        where_clauses.push("src_ip = ?".to_string());
+        bind_values.push(src_ip.clone());
+    }
+
+    if let Some(dst_ip) = &query.dst_ip {
+        where_clauses.push("dst_ip = ?".to_string());
+        bind_values.push(dst_ip.clone());
+    }
+
+    if let Some(dst_port) = query.dst_port {
+        where_clauses.push("dst_port = ?".to_string());
+        bind_values.push(dst_port.to_string());
+    }
+
+    if let Some(from) = query.from {
+        where_clauses.push("logged_at >= ?".to_string());
+        bind_values.push(format_timestamp(&from));
+    }
+
+    if let Some(to) = query.to {
+        where_clauses.push("logged_at < ?".to_string());
+        bind_values.push(format_timestamp(&to));
+    }
+
+    (where_clauses.join(" AND "), bind_values)
+}
+
+/// Fields of a firewall log line
+#[derive(Debug, PartialEq)]
+struct ParsedFirewallLog {
+    rule_id: Option<String>,
+    chain: String,
+    rule_number: Option<u32>,
+    action: FirewallLogAction,
+    protocol: Option<String>,
+    in_interface: Option<String>,
+    out_interface: Option<String>,
+    src_ip: Option<String>,
+    src_port: Option<u16>,
+    dst_ip: Option<String>,
+    dst_port: Option<u16>,
+    length: Option<u32>,
+}
+
+/// Parse a kernel firewall log line
+///
+/// VyOS rules with logging enabled prefix the netfilter packet description
+/// with `[<rule set>-<rule>-<action>]`, where the rule is a number or
+/// `default` for the rule set's default action:
+///
+/// ```text
+/// kernel: [ipv4-FWD-filter-10-D]IN=eth0 OUT=eth1 SRC=203.0.113.7 DST=10.0.0.5 LEN=60 PROTO=TCP SPT=51234 DPT=22
+/// kernel: [WAN_IN-default-D]IN=eth0 OUT= SRC=198.51.100.2 DST=192.0.2.1 LEN=84 PROTO=ICMP TYPE=8
+/// ```
+fn parse_firewall_log(line: &str) -> Option<ParsedFirewallLog> {
+    let packet_start = line.find("]IN=")?;
+    let prefix_start = line[..packet_start].rfind('[')? + 1;
+    let prefix = &line[prefix_start..packet_start];
+
+    let (rest, action) = prefix.rsplit_once('-')?;
+    let (chain, rule) = rest.rsplit_once('-')?;
+    if chain.is_empty() {
+        return None;
+    }
+    let rule_number = match rule {
+        "default" => None,
+        number => Some(number.parse().ok()?),
+    };
+
+    let fields: HashMap<&str, &str> = line[packet_start + 1..]
+        .split_whitespace()
+        .filter_map(|field| field.split_once('='))
+        .collect();
+    let text = |key: &str| fields.get(key).filter(|value| !value.is_empty()).map(|value| value.to_string());
+
+    Some(ParsedFirewallLog {
+        rule_id: Some(rest.to_string()),
+        chain: chain.to_string(),
+        rule_number,
+        action: FirewallLogAction::from_suffix(action),
+        protocol: text("PROTO"),
+        in_interface: text("IN"),
+        out_interface: text("OUT"),
+        src_ip: text("SRC"),
+        src_port: number(&fields, "SPT"),
+        dst_ip: text("DST"),
+        dst_port: number(&fields, "DPT"),
+        length: number(&fields, "LEN"),
+    })
+}
+
+fn number<T: FromStr>(fields: &HashMap<&str, &str>, key: &str) -> Option<T> {
+    fields.get(key).and_then(|value| value.parse().ok())
+}
+
+/// Configuration path of the rule that logged a packet
+///
+/// Rule sets are named `<family>-<hook>-<chain>` since VyOS 1.4, e.g.
+/// `ipv4-FWD-filter` or `ipv6-NAM-WAN_IN`, and by their name before.
+fn rule_path(chain: &str, rule_number: Option<u32>) -> Option<String> {
+    let rule_set = match chain.split_once('-') {
+        Some((family @ ("ipv4" | "ipv6"), rest)) => {
+            let (hook, name) = rest.split_once('-')?;
+            match hook {
+                "FWD" => format!("firewall {} forward {}", family, name),
+                "INP" => format!("firewall {} input {}", family, name),
+                "OUT" => format!("firewall {} output {}", family, name),
+                "PRE" => format!("firewall {} prerouting {}", family, name),
+                "NAM" => format!("firewall {} name {}", family, name),
+                _ => return None,
+            }
+        }
+        _ => format!("firewall name {}", chain),
+    };
+
+    Some(match rule_number {
+        Some(number) => format!("{} rule {}", rule_set, number),
+        None => format!("{} default-action", rule_set),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_firewall_log() {
+        let line = "<4>Oct 16 10:00:00 edge-1 kernel: [ 1234.567890] [ipv4-FWD-filter-10-D]IN=eth0 OUT=eth1 \
+                    MAC=52:54:00:12:34:56:52:54:00:65:43:21:08:00 SRC=203.0.113.7 DST=10.0.0.5 LEN=60 TOS=0x00 \
+                    PREC=0x00 TTL=52 ID=4321 DF PROTO=TCP SPT=51234 DPT=22 WINDOW=64240 RES=0x00 SYN URGP=0";
+        assert_eq!(
+            parse_firewall_log(line),
+            Some(ParsedFirewallLog {
+                rule_id: Some("ipv4-FWD-filter-10".to_string()),
+                chain: "ipv4-FWD-filter".to_string(),
+                rule_number: Some(10),
+                action: FirewallLogAction::Drop,
+                protocol: Some("TCP".to_string()),
+                in_interface: Some("eth0".to_string()),
+                out_interface: Some("eth1".to_string()),
+                src_ip: Some("203.0.113.7".to_string()),
+                src_port: Some(51234),
+                dst_ip: Some("10.0.0.5".to_string()),
+                dst_port: Some(22),
+                length: Some(60),
+            })
+        );
+
+        let parsed = parse_firewall_log("kernel: [WAN-IN-default-R]IN=eth0 OUT= SRC=198.51.100.2 DST=192.0.2.1 PROTO=ICMP").unwrap();
+        assert_eq!(parsed.chain, "WAN-IN");
+        assert_eq!(parsed.rule_number, None);
+        assert_eq!(parsed.action, FirewallLogAction::Reject);
+        assert_eq!(parsed.out_interface, None);
+        assert_eq!(parsed.dst_port, None);
+
+        assert_eq!(parse_firewall_log("sshd[812]: Accepted publickey for vyos"), None);
+        assert_eq!(parse_firewall_log("kernel: [ipv4-FWD-filter-x1-D]IN=eth0"), None);
+    }
+
+    #[test]
+    fn test_rule_path() {
+        assert_eq!(
+            rule_path("ipv4-FWD-filter", Some(10)).as_deref(),
+            Some("firewall ipv4 forward filter rule 10")
+        );
+        assert_eq!(
+            rule_path("ipv6-NAM-WAN_IN", None).as_deref(),
+            Some("firewall ipv6 name WAN_IN default-action")
+        );
+        assert_eq!(rule_path("WAN_IN", Some(20)).as_deref(), Some("firewall name WAN_IN rule 20"));
+        assert_eq!(rule_path("ipv4-XYZ-filter", Some(1)), None);
+    }
+}
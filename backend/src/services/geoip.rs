@@ -0,0 +1,208 @@
+//! GeoIP Enrichment Service
+//!
+//! Tags external IP addresses with their country and autonomous system from
+//! MaxMind-format (MMDB) databases, such as GeoLite2-Country or -City and
+//! GeoLite2-ASN. Both databases are optional: without them every lookup
+//! simply finds nothing, so views that enrich addresses keep working.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use maxminddb::{geoip2, Reader};
+use tracing::{info, warn};
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::geoip::{GeoIpDatabaseInfo, GeoIpInfo, GeoIpLookup, GeoIpStatus};
+
+/// An opened GeoIP database
+struct GeoIpDatabase {
+    path: String,
+    reader: Reader<Vec<u8>>,
+}
+
+impl GeoIpDatabase {
+    /// Open a database, or `None` with a warning if it cannot be read
+    fn open(path: Option<&str>, kind: &str) -> Option<Self> {
+        let path = path?;
+        match Reader::open_readfile(path) {
+            Ok(reader) => {
+                info!("Loaded GeoIP {} database {} ({})", kind, path, reader.metadata.database_type);
+                Some(Self {
+                    path: path.to_string(),
+                    reader,
+                })
+            }
+            Err(e) => {
+                warn!("GeoIP {} database {} could not be loaded, lookups are disabled: {}", kind, path, e);
+                None
+            }
+        }
+    }
+
+    fn info(&self) -> GeoIpDatabaseInfo {
+        let metadata = &self.reader.metadata;
+        GeoIpDatabaseInfo {
+            path: self.path.clone(),
+            database_type: metadata.database_type.clone(),
+            built_at: i64::try_from(metadata.build_epoch)
+                .ok()
+                .and_then(|epoch| Utc.timestamp_opt(epoch, 0).single()),
+        }
+    }
+}
+
+/// GeoIP enrichment service
+#[derive(Clone)]
+pub struct GeoIpService {
+    country: Option<Arc<GeoIpDatabase>>,
+    asn: Option<Arc<GeoIpDatabase>>,
+}
+
+impl GeoIpService {
+    /// Create a new GeoIP service with the configured databases
+    ///
+    /// Databases that are not configured or cannot be read are left out.
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            country: GeoIpDatabase::open(config.geoip_database_path.as_deref(), "country").map(Arc::new),
+            asn: GeoIpDatabase::open(config.geoip_asn_database_path.as_deref(), "ASN").map(Arc::new),
+        }
+    }
+
+    /// Whether any GeoIP database is loaded
+    pub fn is_enabled(&self) -> bool {
+        self.country.is_some() || self.asn.is_some()
+    }
+
+    /// Location of an IP address, if it is external and found
+    pub fn locate(&self, ip: IpAddr) -> Option<GeoIpInfo> {
+        if !is_external(ip) {
+            return None;
+        }
+
+        let mut info = GeoIpInfo::default();
+        if let Some(db) = &self.country {
+            if let Ok(record) = db.reader.lookup::<geoip2::Country>(ip) {
+                if let Some(country) = record.country.or(record.registered_country) {
+                    info.country_code = country.iso_code.map(str::to_string);
+                    info.country_name = country
+                        .names
+                        .and_then(|names| names.get("en").map(|name| name.to_string()));
+                }
+            }
+        }
+        if let Some(db) = &self.asn {
+            if let Ok(record) = db.reader.lookup::<geoip2::Asn>(ip) {
+                info.asn = record.autonomous_system_number;
+                info.as_organization = record.autonomous_system_organization.map(str::to_string);
+            }
+        }
+
+        (info != GeoIpInfo::default()).then_some(info)
+    }
+
+    /// Look up an IP address given as text
+    pub fn lookup(&self, ip: &str) -> Result<GeoIpLookup, AppError> {
+        let addr: IpAddr = ip
+            .trim()
+            .parse()
+            .map_err(|_| AppError::Validation(format!("Invalid IP address: {}", ip)))?;
+
+        Ok(GeoIpLookup {
+            ip: addr.to_string(),
+            external: is_external(addr),
+            geo: self.locate(addr),
+        })
+    }
+
+    /// State of the GeoIP databases
+    pub fn status(&self) -> GeoIpStatus {
+        GeoIpStatus {
+            enabled: self.is_enabled(),
+            country_database: self.country.as_ref().map(|db| db.info()),
+            asn_database: self.asn.as_ref().map(|db| db.info()),
+        }
+    }
+}
+
+/// Whether an address is publicly routable
+///
+/// Private, shared, loopback, link-local, documentation, multicast, and
+/// other special-purpose ranges are not.
+pub fn is_external(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_external_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_external_v4(v4),
+            None => is_external_v6(ip),
+        },
+    }
+}
+
+fn is_external_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // Shared address space for carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (b & 0xc0) == 64)
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_external_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+        // Documentation, 2001:db8::/32
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_external() {
+        let external = |ip: &str| is_external(ip.parse().unwrap());
+        assert!(external("8.8.8.8"));
+        assert!(external("2606:4700::1111"));
+        assert!(external("::ffff:1.1.1.1"));
+        assert!(!external("10.1.2.3"));
+        assert!(!external("172.16.0.1"));
+        assert!(!external("192.168.1.1"));
+        assert!(!external("100.64.0.1"));
+        assert!(!external("169.254.0.1"));
+        assert!(!external("192.0.2.1"));
+        assert!(!external("255.255.255.255"));
+        assert!(!external("fd00::1"));
+        assert!(!external("fe80::1"));
+        assert!(!external("2001:db8::1"));
+        assert!(!external("::ffff:10.0.0.1"));
+    }
+
+    #[test]
+    fn test_missing_database_disables_lookups() {
+        let service = GeoIpService {
+            country: GeoIpDatabase::open(Some("/nonexistent/GeoLite2-Country.mmdb"), "country").map(Arc::new),
+            asn: GeoIpDatabase::open(None, "ASN").map(Arc::new),
+        };
+        assert!(!service.is_enabled());
+
+        let lookup = service.lookup("8.8.8.8").unwrap();
+        assert!(lookup.external);
+        assert_eq!(lookup.geo, None);
+        assert!(service.lookup("not-an-ip").is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! Git export service
+//!
+//! This module commits every applied configuration snapshot to a Git
+//! repository, giving teams an external, immutable record of configuration
+//! changes. It drives the `git` command line tool so that the host's existing
+//! credential helpers and SSH keys are used when pushing to a remote.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::config::ConfigSnapshot;
+
+/// Directory inside the repository holding the exported configuration tree
+/// of each node, as `<node id>.json`
+const SNAPSHOT_DIR: &str = "nodes";
+
+/// Author information recorded on snapshot commits
+#[derive(Debug, Clone)]
+pub struct GitAuthor {
+    pub name: String,
+    pub email: String,
+}
+
+/// Service committing configuration snapshots to a Git repository
+#[derive(Clone)]
+pub struct GitExportService {
+    repo_path: Option<PathBuf>,
+    remote: Option<String>,
+    branch: String,
+    /// Serializes commits, which stage, commit, and push in the one working tree
+    lock: Arc<Mutex<()>>,
+}
+
+impl GitExportService {
+    /// Create a new Git export service
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            repo_path: config.git_export_repo_path.as_ref().map(PathBuf::from),
+            remote: config.git_export_remote.clone(),
+            branch: config.git_export_branch.clone(),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Check if Git export is configured
+    pub fn is_enabled(&self) -> bool {
+        self.repo_path.is_some()
+    }
+
+    /// Commit a configuration snapshot of a node
+    ///
+    /// Returns the commit hash, or `None` when export is disabled or the
+    /// snapshot is identical to the last one exported for the node.
+    pub async fn commit_snapshot(
+        &self,
+        node_id: Uuid,
+        snapshot: &ConfigSnapshot,
+        author: &GitAuthor,
+        description: &str,
+    ) -> Result<Option<String>, AppError> {
+        let repo_path = match &self.repo_path {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        let _guard = self.lock.lock().await;
+        self.ensure_repository(repo_path).await?;
+
+        let contents = serde_json::to_string_pretty(&snapshot.config_tree)?;
+        let file = format!("{}/{}.json", SNAPSHOT_DIR, node_id);
+        tokio::fs::create_dir_all(repo_path.join(SNAPSHOT_DIR)).await?;
+        tokio::fs::write(repo_path.join(&file), format!("{}\n", contents)).await?;
+
+        self.git(repo_path, &["add", &file], None).await?;
+
+        let status = self.git(repo_path, &["status", "--porcelain"], None).await?;
+        if status.trim().is_empty() {
+            debug!("Snapshot {} unchanged, skipping Git commit", snapshot.id);
+            return Ok(None);
+        }
+
+        let message = commit_message(node_id, snapshot, description);
+        self.git(repo_path, &["commit", "--quiet", "-m", &message], Some(author))
+            .await?;
+
+        let commit = self.git(repo_path, &["rev-parse", "HEAD"], None).await?;
+        let commit = commit.trim().to_string();
+
+        info!("Committed config snapshot {} of node {} to Git as {}", snapshot.id, node_id, commit);
+
+        if let Some(remote) = &self.remote {
+            let refspec = format!("HEAD:{}", self.branch);
+            if let Err(e) = self.git(repo_path, &["push", remote, &refspec], None).await {
+                // The commit is kept locally and will go out with the next successful push
+                warn!("Failed to push config snapshot to {}: {}", remote, e);
+            }
+        }
+
+        Ok(Some(commit))
+    }
+
+    /// Initialize the repository if it does not exist yet
+    async fn ensure_repository(&self, repo_path: &Path) -> Result<(), AppError> {
+        if repo_path.join(".git").exists() {
+            return Ok(());
+        }
+
+        info!("Initializing Git export repository at {}", repo_path.display());
+        tokio::fs::create_dir_all(repo_path).await?;
+
+        let initial_branch = format!("--initial-branch={}", self.branch);
+        self.git(repo_path, &["init", "--quiet", &initial_branch], None)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Run a git command inside the repository and return its stdout
+    async fn git(
+        &self,
+        repo_path: &Path,
+        args: &[&str],
+        author: Option<&GitAuthor>,
+    ) -> Result<String, AppError> {
+        let mut command = Command::new("git");
+        command.arg("-C").arg(repo_path).args(args);
+
+        if let Some(author) = author {
+            command
+                .env("GIT_AUTHOR_NAME", &author.name)
+                .env("GIT_AUTHOR_EMAIL", &author.email)
+                .env("GIT_COMMITTER_NAME", &author.name)
+                .env("GIT_COMMITTER_EMAIL", &author.email);
+        }
+
+        let output = command
+            .output()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to run git: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(AppError::Internal(format!(
+                "git {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Build the commit message for a snapshot
+fn commit_message(node_id: Uuid, snapshot: &ConfigSnapshot, description: &str) -> String {
+    let subject = if description.trim().is_empty() {
+        "Apply configuration"
+    } else {
+        description.trim()
+    };
+
+    format!(
+        "{}\n\nNode-Id: {}\nSnapshot-Id: {}\nSnapshot-Hash: {}",
+        subject, node_id, snapshot.id, snapshot.hash
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::config::{ConfigMetadata, ConfigNode, ConfigNodeType};
+    use chrono::Utc;
+
+    fn test_snapshot() -> ConfigSnapshot {
+        let now = Utc::now();
+        ConfigSnapshot {
+            id: uuid::Uuid::new_v4(),
+            config_tree: ConfigNode {
+                id: uuid::Uuid::new_v4(),
+                path: "/".to_string(),
+                name: "root".to_string(),
+                value: None,
+                node_type: ConfigNodeType::Container,
+                description: None,
+                children: vec![],
+                metadata: ConfigMetadata {
+                    is_readonly: false,
+                    is_required: false,
+                    default_value: None,
+                    validation: None,
+                    help_text: None,
+                },
+                created_at: now,
+                updated_at: now,
+            },
+            hash: "abc123".to_string(),
+            created_at: now,
+        }
+    }
+
+    #[test]
+    fn test_commit_message() {
+        let snapshot = test_snapshot();
+        let node_id = uuid::Uuid::new_v4();
+
+        let message = commit_message(node_id, &snapshot, "Enable SSH");
+        assert!(message.starts_with("Enable SSH\n\n"));
+        assert!(message.contains(&format!("Node-Id: {}", node_id)));
+        assert!(message.contains(&format!("Snapshot-Id: {}", snapshot.id)));
+        assert!(message.contains("Snapshot-Hash: abc123"));
+
+        let message = commit_message(node_id, &snapshot, "  ");
+        assert!(message.starts_with("Apply configuration"));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_export_is_noop() {
+        let service = GitExportService {
+            repo_path: None,
+            remote: None,
+            branch: "main".to_string(),
+            lock: Arc::new(Mutex::new(())),
+        };
+        let author = GitAuthor {
+            name: "admin".to_string(),
+            email: "admin@vyos.local".to_string(),
+        };
+
+        assert!(!service.is_enabled());
+        let commit = service
+            .commit_snapshot(uuid::Uuid::new_v4(), &test_snapshot(), &author, "test")
+            .await
+            .unwrap();
+        assert!(commit.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_commits() {
+        let repo_path = std::env::temp_dir().join(format!("vyos-webui-git-export-{}", uuid::Uuid::new_v4()));
+        let service = GitExportService {
+            repo_path: Some(repo_path.clone()),
+            remote: None,
+            branch: "main".to_string(),
+            lock: Arc::new(Mutex::new(())),
+        };
+        let author = GitAuthor {
+            name: "admin".to_string(),
+            email: "admin@vyos.local".to_string(),
+        };
+
+        let snapshot = test_snapshot();
+        let commits = futures::future::join_all(
+            (0..4).map(|_| service.commit_snapshot(uuid::Uuid::new_v4(), &snapshot, &author, "test")),
+        )
+        .await;
+        for commit in commits {
+            assert!(commit.unwrap().is_some());
+        }
+        let count = service.git(&repo_path, &["rev-list", "--count", "HEAD"], None).await.unwrap();
+        assert_eq!(count.trim(), "4");
+
+        std::fs::remove_dir_all(&repo_path).unwrap();
+    }
+}
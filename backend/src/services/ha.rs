@@ -0,0 +1,355 @@
+//! High Availability
+//!
+//! Reads the VRRP groups and the conntrack-sync statistics of the nodes of
+//! HA pairs. Each read of a node's VRRP groups is compared with the states
+//! last read, and every change of state is kept as the node's transition
+//! history. A scheduled job reads every node, and alerts on transitions
+//! nobody asked for: a group failing, or a node other than the preferred
+//! master of a group, the one with the highest priority, taking over while
+//! none of the group's nodes is rebooting.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::ha::{
+    HaStatus, VrrpTransition, VrrpTransitionListResponse, VrrpTransitionQuery, VRRP_FAULT, VRRP_HISTORY_LIMIT,
+    VRRP_MASTER,
+};
+use crate::models::monitoring::{Alert, AlertSeverity, AlertStatus, MetricLabel};
+use crate::models::node::{Node, NodeStatus};
+use crate::models::timestamp::{format_timestamp, parse_db_timestamp};
+use crate::services::{MonitoringService, NodeService};
+use crate::vyos_client::VyOSVrrpGroup;
+
+/// How often the scheduled job reads the VRRP groups of every node
+pub const HA_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Label of a transition alert holding the VRRP group
+const GROUP_LABEL: &str = "vrrp_group";
+
+/// High availability service
+#[derive(Clone)]
+pub struct HaService {
+    db: Database,
+    nodes: NodeService,
+    monitoring: MonitoringService,
+}
+
+impl HaService {
+    /// Create a new high availability service
+    pub fn new(db: Database, nodes: NodeService, monitoring: MonitoringService) -> Self {
+        Self { db, nodes, monitoring }
+    }
+
+    /// VRRP groups and conntrack-sync statistics of a node, as the node sees
+    /// them now
+    ///
+    /// Changes of state since the last read are recorded.
+    pub async fn status(&self, node_id: Uuid) -> Result<HaStatus, AppError> {
+        let node = self
+            .nodes
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        let vrrp_groups = self.nodes.get_vrrp_groups(node_id).await?;
+        self.record(&node, &vrrp_groups).await?;
+        let conntrack_sync = match self.nodes.get_conntrack_sync_statistics(node_id).await {
+            Ok(statistics) => statistics,
+            Err(e) => {
+                debug!("Could not read the conntrack-sync statistics of node {}: {}", node_id, e);
+                None
+            }
+        };
+
+        Ok(HaStatus {
+            node_id,
+            vrrp_groups,
+            conntrack_sync,
+            checked_at: Utc::now(),
+        })
+    }
+
+    /// Read the VRRP groups of every node, returning how many unexpected
+    /// transitions were found
+    ///
+    /// Rebooting nodes, and nodes that cannot be read, are skipped.
+    pub async fn check_all(&self) -> Result<usize, AppError> {
+        let mut unexpected = 0;
+        for node in self.nodes.list_all_nodes().await? {
+            if node.status == NodeStatus::Rebooting {
+                continue;
+            }
+            match self.nodes.get_vrrp_groups(node.id).await {
+                Ok(groups) => unexpected += self.record(&node, &groups).await?,
+                Err(e) => debug!("Could not read the VRRP groups of node {}: {}", node.id, e),
+            }
+        }
+        Ok(unexpected)
+    }
+
+    /// VRRP transitions of a node, newest first
+    pub async fn transitions(
+        &self,
+        node_id: Uuid,
+        query: VrrpTransitionQuery,
+    ) -> Result<VrrpTransitionListResponse, AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(50).clamp(1, 200);
+
+        let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM vrrp_transitions WHERE node_id = ?")
+            .bind(node_id.to_string())
+            .fetch_one(self.db.pool())
+            .await?;
+
+        let rows = sqlx::query_as::<_, (String, String, i64, String, String, i64, bool, String)>(
+            "SELECT id, group_name, vrid, from_state, to_state, priority, unexpected, occurred_at \
+             FROM vrrp_transitions WHERE node_id = ? ORDER BY occurred_at DESC LIMIT ? OFFSET ?",
+        )
+        .bind(node_id.to_string())
+        .bind(i64::from(page_size))
+        .bind(i64::from((page - 1) * page_size))
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let transitions = rows
+            .into_iter()
+            .map(
+                |(id, group, vrid, from_state, to_state, priority, unexpected, occurred_at)| VrrpTransition {
+                    id: Uuid::parse_str(&id).unwrap_or_else(|_| Uuid::nil()),
+                    node_id,
+                    group,
+                    vrid: vrid as u8,
+                    from_state,
+                    to_state,
+                    priority: priority as u8,
+                    unexpected,
+                    occurred_at: parse_db_timestamp(&occurred_at),
+                },
+            )
+            .collect();
+
+        Ok(VrrpTransitionListResponse {
+            transitions,
+            total: total as u64,
+            page,
+            page_size,
+        })
+    }
+
+    /// Store the states of a node's VRRP groups, recording the changes from
+    /// the states last read and alerting on the unexpected ones
+    ///
+    /// A group seen for the first time has no transition. Returns the number
+    /// of unexpected transitions.
+    async fn record(&self, node: &Node, groups: &[VyOSVrrpGroup]) -> Result<usize, AppError> {
+        let node_id = node.id.to_string();
+        let previous: HashMap<String, String> =
+            sqlx::query_as::<_, (String, String)>("SELECT group_name, state FROM vrrp_group_states WHERE node_id = ?")
+                .bind(&node_id)
+                .fetch_all(self.db.pool())
+                .await?
+                .into_iter()
+                .collect();
+
+        let now = Utc::now();
+        let mut unexpected = 0;
+        for group in groups {
+            if let Some(from_state) = previous.get(&group.name).filter(|state| **state != group.state) {
+                let is_unexpected = self.is_unexpected(node, group).await?;
+                sqlx::query(
+                    "INSERT INTO vrrp_transitions \
+                     (id, node_id, group_name, vrid, from_state, to_state, priority, unexpected, occurred_at) \
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(Uuid::new_v4().to_string())
+                .bind(&node_id)
+                .bind(&group.name)
+                .bind(i64::from(group.vrid))
+                .bind(from_state)
+                .bind(&group.state)
+                .bind(i64::from(group.priority))
+                .bind(is_unexpected)
+                .bind(format_timestamp(&now))
+                .execute(self.db.pool())
+                .await?;
+
+                info!(
+                    "VRRP group {} of node {} went from {} to {}",
+                    group.name, node.name, from_state, group.state
+                );
+                if is_unexpected {
+                    unexpected += 1;
+                    warn!("Unexpected VRRP transition of group {} on node {}", group.name, node.name);
+                    self.monitoring.raise_alert(transition_alert(node, group)).await;
+                }
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO vrrp_group_states (node_id, group_name, vrid, state, priority, checked_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT (node_id, group_name) DO UPDATE SET
+                    vrid = excluded.vrid,
+                    state = excluded.state,
+                    priority = excluded.priority,
+                    checked_at = excluded.checked_at
+                "#,
+            )
+            .bind(&node_id)
+            .bind(&group.name)
+            .bind(i64::from(group.vrid))
+            .bind(&group.state)
+            .bind(i64::from(group.priority))
+            .bind(format_timestamp(&now))
+            .execute(self.db.pool())
+            .await?;
+        }
+
+        // Groups the node no longer has
+        for name in previous.keys().filter(|name| !groups.iter().any(|group| &group.name == *name)) {
+            sqlx::query("DELETE FROM vrrp_group_states WHERE node_id = ? AND group_name = ?")
+                .bind(&node_id)
+                .bind(name)
+                .execute(self.db.pool())
+                .await?;
+        }
+
+        if groups.iter().any(|group| previous.get(&group.name).is_some_and(|state| *state != group.state)) {
+            sqlx::query(
+                "DELETE FROM vrrp_transitions WHERE node_id = ? AND id NOT IN \
+                 (SELECT id FROM vrrp_transitions WHERE node_id = ? ORDER BY occurred_at DESC LIMIT ?)",
+            )
+            .bind(&node_id)
+            .bind(&node_id)
+            .bind(VRRP_HISTORY_LIMIT)
+            .execute(self.db.pool())
+            .await?;
+        }
+
+        Ok(unexpected)
+    }
+
+    /// Whether a group's new state on a node is one nobody asked for
+    async fn is_unexpected(&self, node: &Node, group: &VyOSVrrpGroup) -> Result<bool, AppError> {
+        let peers: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT node_id, priority FROM vrrp_group_states WHERE group_name = ? AND vrid = ? AND node_id != ?",
+        )
+        .bind(&group.name)
+        .bind(i64::from(group.vrid))
+        .bind(node.id.to_string())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut rebooting = self.nodes.get_reboot(node.id).await?.is_some();
+        for (peer_id, _) in &peers {
+            if rebooting {
+                break;
+            }
+            if let Ok(peer_id) = Uuid::parse_str(peer_id) {
+                rebooting = self.nodes.get_reboot(peer_id).await?.is_some();
+            }
+        }
+
+        let peer_priorities: Vec<u8> = peers.iter().map(|(_, priority)| *priority as u8).collect();
+        Ok(unexpected_transition(group, &peer_priorities, rebooting))
+    }
+}
+
+/// Whether a group entering its state is unexpected, given the priorities
+/// of the group's other nodes and whether any of its nodes is rebooting
+///
+/// Failing is always unexpected outside of a reboot; becoming master is
+/// unless no other node of the group has a higher priority, as the node is
+/// then the preferred master taking over again.
+fn unexpected_transition(group: &VyOSVrrpGroup, peer_priorities: &[u8], rebooting: bool) -> bool {
+    if rebooting {
+        return false;
+    }
+    match group.state.as_str() {
+        VRRP_FAULT => true,
+        VRRP_MASTER => peer_priorities.iter().any(|priority| *priority > group.priority),
+        _ => false,
+    }
+}
+
+fn transition_alert(node: &Node, group: &VyOSVrrpGroup) -> Alert {
+    let now = Utc::now();
+    let (severity, title, description) = if group.state == VRRP_FAULT {
+        (
+            AlertSeverity::Critical,
+            format!("VRRP group {} failed on {}", group.name, node.name),
+            format!(
+                "VRRP group {} (VRID {}) on interface {} of {} is in the FAULT state",
+                group.name, group.vrid, group.interface, node.name
+            ),
+        )
+    } else {
+        (
+            AlertSeverity::Warning,
+            format!("VRRP group {} failed over to {}", group.name, node.name),
+            format!(
+                "{} became master of VRRP group {} (VRID {}) with priority {}, below that of its peer",
+                node.name, group.name, group.vrid, group.priority
+            ),
+        )
+    };
+    Alert {
+        id: Uuid::new_v4(),
+        node_id: node.id.to_string(),
+        severity,
+        title,
+        description,
+        status: AlertStatus::Active,
+        metric_name: Some("vrrp_priority".to_string()),
+        threshold_value: None,
+        actual_value: Some(f64::from(group.priority)),
+        triggered_at: now,
+        updated_at: now,
+        acknowledged_at: None,
+        acknowledged_by: None,
+        resolved_at: None,
+        trigger_count: 1,
+        labels: vec![MetricLabel {
+            key: GROUP_LABEL.to_string(),
+            value: group.name.clone(),
+        }],
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(state: &str, priority: u8) -> VyOSVrrpGroup {
+        VyOSVrrpGroup {
+            name: "LAN".to_string(),
+            interface: "eth1".to_string(),
+            vrid: 10,
+            state: state.to_string(),
+            priority,
+            last_transition_secs: Some(0),
+        }
+    }
+
+    #[test]
+    fn test_unexpected_transition() {
+        // The backup taking over from the preferred master
+        assert!(unexpected_transition(&group("MASTER", 100), &[200], false));
+        // The preferred master taking over again, or a lone node
+        assert!(!unexpected_transition(&group("MASTER", 200), &[100], false));
+        assert!(!unexpected_transition(&group("MASTER", 100), &[], false));
+        assert!(!unexpected_transition(&group("BACKUP", 200), &[100], false));
+        assert!(unexpected_transition(&group("FAULT", 200), &[], false));
+        // Nothing is unexpected while a node of the group reboots
+        assert!(!unexpected_transition(&group("MASTER", 100), &[200], true));
+        assert!(!unexpected_transition(&group("FAULT", 200), &[100], true));
+    }
+}
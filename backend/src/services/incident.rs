@@ -0,0 +1,446 @@
+//! Incident Service
+//!
+//! Groups correlated alerts into incidents, tracks their lifecycle and
+//! comments, and links the configuration commits made on the affected node
+//! while an incident was unresolved for post-mortems.
+
+use chrono::{Duration, Utc};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::incident::{
+    CreateIncidentCommentRequest, Incident, IncidentAlert, IncidentComment, IncidentConfigChange,
+    IncidentDetail, IncidentEvent, IncidentListQuery, IncidentListResponse, IncidentStatus, INCIDENTS_CHANNEL,
+};
+use crate::models::monitoring::{Alert, AlertSeverity};
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::services::{EventBus, MonitoringService};
+
+/// Alerts on a node within this window of an unresolved incident's last alert
+/// are grouped into that incident
+pub const INCIDENT_CORRELATION_WINDOW: Duration = Duration::minutes(15);
+
+const INCIDENT_COLUMNS: &str = "i.id, i.node_id, i.title, i.severity, i.status, \
+    (SELECT COUNT(*) FROM incident_alerts a WHERE a.incident_id = i.id), \
+    i.opened_at, i.last_alert_at, i.acknowledged_at, i.acknowledged_by, \
+    i.resolved_at, i.resolved_by, i.updated_at";
+
+/// Incident service
+#[derive(Clone)]
+pub struct IncidentService {
+    db: Database,
+    monitoring: MonitoringService,
+    events: EventBus,
+}
+
+impl IncidentService {
+    /// Create a new incident service
+    pub fn new(db: Database, monitoring: MonitoringService, events: EventBus) -> Self {
+        Self { db, monitoring, events }
+    }
+
+    // ========================================================================
+    // Correlation
+    // ========================================================================
+
+    /// Group alerts that are not part of an incident yet
+    ///
+    /// Alerts are processed in the order they triggered. Returns the number
+    /// of newly grouped alerts.
+    pub async fn sync_alerts(&self) -> Result<usize, AppError> {
+        let mut alerts = self.monitoring.get_alerts(None, None, None).await?;
+        alerts.sort_by_key(|alert| alert.triggered_at);
+
+        let mut grouped = 0;
+        for alert in &alerts {
+            if self.correlate_alert(alert).await?.is_some() {
+                grouped += 1;
+            }
+        }
+
+        Ok(grouped)
+    }
+
+    /// Add an alert to its node's unresolved incident, or open a new one
+    ///
+    /// Returns the incident ID, or `None` when the alert was grouped before.
+    pub async fn correlate_alert(&self, alert: &Alert) -> Result<Option<Uuid>, AppError> {
+        let mut tx = self.db.pool().begin().await?;
+
+        let known: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM incident_alerts WHERE alert_id = ?)")
+            .bind(alert.id.to_string())
+            .fetch_one(&mut *tx)
+            .await?;
+        if known {
+            return Ok(None);
+        }
+
+        let triggered_at = format_timestamp(&alert.triggered_at);
+        let window_start = format_timestamp(&(alert.triggered_at - INCIDENT_CORRELATION_WINDOW));
+        let existing = sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT id, severity, last_alert_at FROM incidents
+            WHERE node_id = ? AND status != 'resolved' AND last_alert_at >= ?
+            ORDER BY last_alert_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&alert.node_id)
+        .bind(&window_start)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let now = db_now();
+        let opened = existing.is_none();
+        let incident_id = match existing {
+            Some((id, severity, last_alert_at)) => {
+                let severity = AlertSeverity::parse(&severity).max(alert.severity);
+                let last_alert_at = parse_db_timestamp(&last_alert_at).max(alert.triggered_at);
+                sqlx::query("UPDATE incidents SET severity = ?, last_alert_at = ?, updated_at = ? WHERE id = ?")
+                    .bind(severity.as_str())
+                    .bind(format_timestamp(&last_alert_at))
+                    .bind(&now)
+                    .bind(&id)
+                    .execute(&mut *tx)
+                    .await?;
+                Uuid::parse_str(&id).unwrap_or_default()
+            }
+            None => {
+                let id = Uuid::new_v4();
+                sqlx::query(
+                    r#"
+                    INSERT INTO incidents (id, node_id, title, severity, status, opened_at, last_alert_at, updated_at)
+                    VALUES (?, ?, ?, ?, 'open', ?, ?, ?)
+                    "#,
+                )
+                .bind(id.to_string())
+                .bind(&alert.node_id)
+                .bind(&alert.title)
+                .bind(alert.severity.as_str())
+                .bind(&triggered_at)
+                .bind(&triggered_at)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+                info!("Incident {} opened on node {}: {}", id, alert.node_id, alert.title);
+                id
+            }
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO incident_alerts (alert_id, incident_id, title, severity, metric_name, actual_value, triggered_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(alert.id.to_string())
+        .bind(incident_id.to_string())
+        .bind(&alert.title)
+        .bind(alert.severity.as_str())
+        .bind(&alert.metric_name)
+        .bind(alert.actual_value)
+        .bind(&triggered_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let incident = self.get_incident(incident_id).await?;
+        self.events
+            .publish(
+                INCIDENTS_CHANNEL,
+                &if opened {
+                    IncidentEvent::Opened { incident }
+                } else {
+                    IncidentEvent::Updated { incident }
+                },
+            )
+            .await;
+
+        Ok(Some(incident_id))
+    }
+
+    // ========================================================================
+    // Incidents
+    // ========================================================================
+
+    /// List incidents, most recently opened first
+    pub async fn list_incidents(&self, query: IncidentListQuery) -> Result<IncidentListResponse, AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(50).clamp(1, 500);
+        let offset = (page - 1) * page_size;
+
+        let mut where_clauses = vec!["1=1".to_string()];
+        let mut bind_values: Vec<String> = vec![];
+
+        if let Some(status) = query.status {
+            where_clauses.push("i.status = ?".to_string());
+            bind_values.push(status.as_str().to_string());
+        }
+
+        if let Some(node_id) = &query.node_id {
+            where_clauses.push("i.node_id = ?".to_string());
+            bind_values.push(node_id.clone());
+        }
+
+        let where_clause = where_clauses.join(" AND ");
+
+        let count_query = format!("SELECT COUNT(*) FROM incidents i WHERE {}", where_clause);
+        let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
+        for value in &bind_values {
+            count_query_builder = count_query_builder.bind(value);
+        }
+        let total = count_query_builder.fetch_one(self.db.pool()).await? as u64;
+
+        let data_query = format!(
+            "SELECT {} FROM incidents i WHERE {} ORDER BY i.opened_at DESC, i.id LIMIT ? OFFSET ?",
+            INCIDENT_COLUMNS, where_clause
+        );
+        let mut rows_builder = sqlx::query_as::<_, IncidentRow>(&data_query);
+        for value in &bind_values {
+            rows_builder = rows_builder.bind(value);
+        }
+        let rows = rows_builder
+            .bind(page_size as i64)
+            .bind(offset as i64)
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(IncidentListResponse {
+            incidents: rows.into_iter().map(incident_from_row).collect(),
+            total,
+            page,
+            page_size,
+            total_pages: ((total as f64) / (page_size as f64)).ceil() as u32,
+        })
+    }
+
+    /// Get an incident by ID
+    pub async fn get_incident(&self, id: Uuid) -> Result<Incident, AppError> {
+        let query = format!("SELECT {} FROM incidents i WHERE i.id = ?", INCIDENT_COLUMNS);
+
+        sqlx::query_as::<_, IncidentRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(incident_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Incident {} not found", id)))
+    }
+
+    /// Get an incident with its alerts, comments and configuration changes
+    pub async fn get_incident_detail(&self, id: Uuid) -> Result<IncidentDetail, AppError> {
+        let incident = self.get_incident(id).await?;
+
+        let alerts = sqlx::query_as::<_, (String, String, String, Option<String>, Option<f64>, String)>(
+            r#"
+            SELECT alert_id, title, severity, metric_name, actual_value, triggered_at
+            FROM incident_alerts
+            WHERE incident_id = ?
+            ORDER BY triggered_at, alert_id
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_all(self.db.pool())
+        .await?
+        .into_iter()
+        .map(|(alert_id, title, severity, metric_name, actual_value, triggered_at)| IncidentAlert {
+            alert_id: Uuid::parse_str(&alert_id).unwrap_or_default(),
+            title,
+            severity: AlertSeverity::parse(&severity),
+            metric_name,
+            actual_value,
+            triggered_at: parse_db_timestamp(&triggered_at),
+        })
+        .collect();
+
+        let comments = sqlx::query_as::<_, (String, Option<String>, String, String, String)>(
+            r#"
+            SELECT id, user_id, username, body, created_at
+            FROM incident_comments
+            WHERE incident_id = ?
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_all(self.db.pool())
+        .await?
+        .into_iter()
+        .map(|(id, user_id, username, body, created_at)| IncidentComment {
+            id: Uuid::parse_str(&id).unwrap_or_default(),
+            user_id: user_id.and_then(|id| Uuid::parse_str(&id).ok()),
+            username,
+            body,
+            created_at: parse_db_timestamp(&created_at),
+        })
+        .collect();
+
+        let config_changes = self.config_changes(&incident).await?;
+
+        Ok(IncidentDetail {
+            incident,
+            alerts,
+            comments,
+            config_changes,
+        })
+    }
+
+    /// Move an incident through its lifecycle
+    ///
+    /// Acknowledging and resolving record who did it; reopening a resolved
+    /// incident clears its resolution.
+    pub async fn update_status(
+        &self,
+        id: Uuid,
+        claims: &Claims,
+        status: IncidentStatus,
+    ) -> Result<Incident, AppError> {
+        let current = self.get_incident(id).await?;
+        if current.status == status {
+            return Ok(current);
+        }
+
+        let now = db_now();
+        let query = match status {
+            IncidentStatus::Open => {
+                "UPDATE incidents SET status = 'open', resolved_at = NULL, resolved_by = NULL, updated_at = ? WHERE id = ?"
+            }
+            IncidentStatus::Acknowledged => {
+                "UPDATE incidents SET status = 'acknowledged', acknowledged_at = ?, acknowledged_by = ?,
+                 resolved_at = NULL, resolved_by = NULL, updated_at = ? WHERE id = ?"
+            }
+            IncidentStatus::Resolved => {
+                "UPDATE incidents SET status = 'resolved', resolved_at = ?, resolved_by = ?, updated_at = ? WHERE id = ?"
+            }
+        };
+
+        let mut update = sqlx::query(query);
+        if status != IncidentStatus::Open {
+            update = update.bind(&now).bind(&claims.username);
+        }
+        update
+            .bind(&now)
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        info!("Incident {} {} by {}", id, status.as_str(), claims.username);
+        let incident = self.get_incident(id).await?;
+        self.events
+            .publish(INCIDENTS_CHANNEL, &IncidentEvent::Updated { incident: incident.clone() })
+            .await;
+
+        Ok(incident)
+    }
+
+    /// Comment on an incident
+    pub async fn add_comment(
+        &self,
+        id: Uuid,
+        claims: &Claims,
+        request: CreateIncidentCommentRequest,
+    ) -> Result<IncidentComment, AppError> {
+        self.get_incident(id).await?;
+
+        let comment = IncidentComment {
+            id: Uuid::new_v4(),
+            user_id: claims.user_id().ok(),
+            username: claims.username.clone(),
+            body: request.body,
+            created_at: Utc::now(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO incident_comments (id, incident_id, user_id, username, body, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(comment.id.to_string())
+        .bind(id.to_string())
+        .bind(comment.user_id.map(|id| id.to_string()))
+        .bind(&comment.username)
+        .bind(&comment.body)
+        .bind(format_timestamp(&comment.created_at))
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(comment)
+    }
+
+    /// Configuration commits on the incident's node between its opening and
+    /// resolution, oldest first
+    async fn config_changes(&self, incident: &Incident) -> Result<Vec<IncidentConfigChange>, AppError> {
+        let until = incident.resolved_at.unwrap_or_else(Utc::now);
+        let history = self.db.list_config_history(&incident.node_id).await?;
+
+        let mut changes: Vec<IncidentConfigChange> = history
+            .into_iter()
+            .map(|entry| IncidentConfigChange {
+                created_at: parse_db_timestamp(&entry.created_at),
+                history_id: entry.id,
+                version: entry.version,
+                user_id: entry.user_id,
+                change_summary: entry.change_summary,
+                is_rollback_point: entry.is_rollback_point,
+            })
+            .filter(|change| incident.opened_at <= change.created_at && change.created_at <= until)
+            .collect();
+        changes.reverse();
+
+        Ok(changes)
+    }
+}
+
+/// Incident columns as selected by [`INCIDENT_COLUMNS`]
+type IncidentRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    i64,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+);
+
+fn incident_from_row(
+    (
+        id,
+        node_id,
+        title,
+        severity,
+        status,
+        alert_count,
+        opened_at,
+        last_alert_at,
+        acknowledged_at,
+        acknowledged_by,
+        resolved_at,
+        resolved_by,
+        updated_at,
+    ): IncidentRow,
+) -> Incident {
+    Incident {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        node_id,
+        title,
+        severity: AlertSeverity::parse(&severity),
+        status: IncidentStatus::parse(&status),
+        alert_count: alert_count as u64,
+        opened_at: parse_db_timestamp(&opened_at),
+        last_alert_at: parse_db_timestamp(&last_alert_at),
+        acknowledged_at: acknowledged_at.as_deref().map(parse_db_timestamp),
+        acknowledged_by,
+        resolved_at: resolved_at.as_deref().map(parse_db_timestamp),
+        resolved_by,
+        updated_at: parse_db_timestamp(&updated_at),
+    }
+}
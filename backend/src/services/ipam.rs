@@ -0,0 +1,291 @@
+//! IP Address Management Service
+//!
+//! Collects the interface addresses of all nodes into a read-only address
+//! plan: the subnets in use with their utilization, and conflicts between
+//! interfaces such as the same address on two routers. Nodes that cannot
+//! be reached contribute their last-known interfaces.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+
+use chrono::Utc;
+use futures::future::join_all;
+use ipnet::IpNet;
+
+use crate::error::AppError;
+use crate::models::ipam::{
+    IpamAddress, IpamConflict, IpamConflictKind, IpamPrefix, IpamResponse, IpamUnavailableNode,
+};
+use crate::models::team::AccessScope;
+use crate::services::node_service::NodeService;
+
+/// IP address management service
+#[derive(Clone)]
+pub struct IpamService {
+    nodes: NodeService,
+}
+
+impl IpamService {
+    /// Create a new IPAM service
+    pub fn new(nodes: NodeService) -> Self {
+        Self { nodes }
+    }
+
+    /// Build the address plan of the nodes within the caller's access scope
+    pub async fn address_plan(&self, scope: &AccessScope) -> Result<IpamResponse, AppError> {
+        let nodes: Vec<_> = self
+            .nodes
+            .list_all_nodes()
+            .await?
+            .into_iter()
+            .filter(|node| scope.can_access(node.team_id))
+            .collect();
+
+        let results = join_all(nodes.iter().map(|node| self.nodes.get_node_interfaces(node.id))).await;
+
+        let mut addresses = Vec::new();
+        let mut unavailable_nodes = Vec::new();
+        for (node, result) in nodes.iter().zip(results) {
+            let interfaces = match result {
+                Ok(interfaces) => interfaces,
+                Err(e) => {
+                    unavailable_nodes.push(IpamUnavailableNode {
+                        node_id: node.id,
+                        node_name: node.name.clone(),
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            for interface in interfaces.data {
+                for address in &interface.addresses {
+                    addresses.push(IpamAddress {
+                        node_id: node.id,
+                        node_name: node.name.clone(),
+                        interface: interface.name.clone(),
+                        description: interface.description.clone(),
+                        address: address.clone(),
+                        stale: interfaces.stale,
+                    });
+                }
+            }
+        }
+
+        let (prefixes, conflicts) = analyze(addresses);
+
+        Ok(IpamResponse {
+            prefixes,
+            conflicts,
+            unavailable_nodes,
+            generated_at: Utc::now(),
+        })
+    }
+}
+
+/// Group addresses by subnet and find conflicts between them
+///
+/// Loopback and link-local addresses are left out, as every router has the
+/// same ones. Two routers sharing a subnet is a normal point-to-point or LAN
+/// link and is not a conflict.
+fn analyze(addresses: Vec<IpamAddress>) -> (Vec<IpamPrefix>, Vec<IpamConflict>) {
+    let mut by_prefix: BTreeMap<IpNet, Vec<(IpNet, IpamAddress)>> = BTreeMap::new();
+    for address in addresses {
+        let Ok(net) = address.address.parse::<IpNet>() else {
+            continue;
+        };
+        if is_local_only(net.addr()) {
+            continue;
+        }
+        by_prefix.entry(net.trunc()).or_default().push((net, address));
+    }
+
+    let mut conflicts = Vec::new();
+
+    // The same address on more than one interface
+    let mut by_address: BTreeMap<IpAddr, Vec<&IpamAddress>> = BTreeMap::new();
+    for (net, address) in by_prefix.values().flatten() {
+        by_address.entry(net.addr()).or_default().push(address);
+    }
+    for (ip, holders) in &by_address {
+        if holders.len() > 1 {
+            conflicts.push(conflict(
+                IpamConflictKind::DuplicateAddress,
+                ip.to_string(),
+                format!("{} is configured on {}", ip, describe(holders)),
+                holders,
+            ));
+        }
+    }
+
+    // The same subnet on several interfaces of one node
+    for (prefix, members) in &by_prefix {
+        let mut by_node: HashMap<_, Vec<&IpamAddress>> = HashMap::new();
+        for (_, address) in members {
+            by_node.entry(address.node_id).or_default().push(address);
+        }
+        let mut duplicates: Vec<_> = by_node
+            .into_values()
+            .filter(|holders| {
+                holders.iter().any(|address| address.interface != holders[0].interface)
+            })
+            .collect();
+        duplicates.sort_by(|a, b| a[0].node_name.cmp(&b[0].node_name));
+        for holders in duplicates {
+            conflicts.push(conflict(
+                IpamConflictKind::DuplicateSubnet,
+                prefix.to_string(),
+                format!("{} is configured on several interfaces of {}: {}", prefix, holders[0].node_name, describe(&holders)),
+                &holders,
+            ));
+        }
+    }
+
+    // Subnets inside other subnets; host routes such as loopback router IDs
+    // are expected to fall into larger ranges
+    let networks: Vec<&IpNet> = by_prefix.keys().filter(|net| net.prefix_len() < net.max_prefix_len()).collect();
+    for outer in &networks {
+        for inner in &networks {
+            if outer == inner || !outer.contains(*inner) {
+                continue;
+            }
+            let holders: Vec<&IpamAddress> = by_prefix[*outer]
+                .iter()
+                .chain(&by_prefix[*inner])
+                .map(|(_, address)| address)
+                .collect();
+            conflicts.push(conflict(
+                IpamConflictKind::OverlappingPrefix,
+                outer.to_string(),
+                format!("{} overlaps {}: {}", outer, inner, describe(&holders)),
+                &holders,
+            ));
+        }
+    }
+
+    let prefixes = by_prefix
+        .into_iter()
+        .map(|(prefix, members)| {
+            let mut used: Vec<IpAddr> = members.iter().map(|(net, _)| net.addr()).collect();
+            used.sort_unstable();
+            used.dedup();
+            let capacity = capacity(&prefix);
+            IpamPrefix {
+                prefix: prefix.to_string(),
+                used: used.len(),
+                capacity,
+                usage_percent: capacity
+                    .filter(|capacity| *capacity > 0)
+                    .map(|capacity| used.len() as f64 * 100.0 / capacity as f64),
+                addresses: members.into_iter().map(|(_, address)| address).collect(),
+            }
+        })
+        .collect();
+
+    (prefixes, conflicts)
+}
+
+fn conflict(kind: IpamConflictKind, subject: String, message: String, holders: &[&IpamAddress]) -> IpamConflict {
+    IpamConflict {
+        kind,
+        severity: kind.severity(),
+        subject,
+        message,
+        addresses: holders.iter().map(|address| (*address).clone()).collect(),
+    }
+}
+
+/// `edge-1 eth0, edge-2 eth1`
+fn describe(holders: &[&IpamAddress]) -> String {
+    holders
+        .iter()
+        .map(|address| format!("{} {}", address.node_name, address.interface))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Assignable host addresses of a subnet
+///
+/// IPv4 subnets lose the network and broadcast addresses, except for /31
+/// point-to-point links and /32 host routes.
+pub(crate) fn capacity(prefix: &IpNet) -> Option<u64> {
+    let host_bits = u32::from(prefix.max_prefix_len() - prefix.prefix_len());
+    let total = 1u64.checked_shl(host_bits)?;
+    Some(match prefix {
+        IpNet::V4(_) if host_bits >= 2 => total - 2,
+        _ => total,
+    })
+}
+
+/// Check whether an address is loopback or link-local, the same on every router
+pub(crate) fn is_local_only(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_link_local(),
+        IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xffc0) == 0xfe80,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn address(node: &str, interface: &str, address: &str) -> IpamAddress {
+        IpamAddress {
+            node_id: Uuid::from_u128(node.bytes().map(u128::from).sum()),
+            node_name: node.to_string(),
+            interface: interface.to_string(),
+            description: None,
+            address: address.to_string(),
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn test_shared_link_is_not_a_conflict() {
+        let (prefixes, conflicts) = analyze(vec![
+            address("edge-1", "eth0", "192.0.2.1/30"),
+            address("edge-2", "eth0", "192.0.2.2/30"),
+            address("edge-1", "lo", "127.0.0.1/8"),
+            address("edge-2", "eth0", "fe80::1/64"),
+        ]);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(prefixes.len(), 1);
+        assert_eq!(prefixes[0].prefix, "192.0.2.0/30");
+        assert_eq!(prefixes[0].used, 2);
+        assert_eq!(prefixes[0].capacity, Some(2));
+        assert_eq!(prefixes[0].usage_percent, Some(100.0));
+    }
+
+    #[test]
+    fn test_conflicts_are_detected() {
+        let (prefixes, conflicts) = analyze(vec![
+            address("edge-1", "eth1", "10.0.0.1/24"),
+            address("edge-2", "eth1", "10.0.0.1/16"),
+            address("edge-2", "eth2", "198.51.100.1/24"),
+            address("edge-2", "eth3", "198.51.100.2/24"),
+            address("edge-2", "lo", "10.255.0.2/32"),
+        ]);
+
+        let kinds: Vec<_> = conflicts.iter().map(|c| (c.kind, c.subject.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (IpamConflictKind::DuplicateAddress, "10.0.0.1"),
+                (IpamConflictKind::DuplicateSubnet, "198.51.100.0/24"),
+                (IpamConflictKind::OverlappingPrefix, "10.0.0.0/16"),
+            ]
+        );
+        assert_eq!(conflicts[0].message, "10.0.0.1 is configured on edge-2 eth1, edge-1 eth1");
+        assert_eq!(prefixes.len(), 4);
+    }
+
+    #[test]
+    fn test_capacity() {
+        assert_eq!(capacity(&"10.0.0.0/24".parse().unwrap()), Some(254));
+        assert_eq!(capacity(&"10.0.0.0/31".parse().unwrap()), Some(2));
+        assert_eq!(capacity(&"10.0.0.1/32".parse().unwrap()), Some(1));
+        assert_eq!(capacity(&"2001:db8::/64".parse().unwrap()), None);
+        assert_eq!(capacity(&"2001:db8::/120".parse().unwrap()), Some(256));
+    }
+}
@@ -0,0 +1,604 @@
+//! IPv6 Service
+//!
+//! Router advertisements, the DHCPv6 server with prefix delegation, and the
+//! IPv6 neighbor and prefix state of a node. Settings are read from the
+//! node's configuration and written as one commit replacing the interface's
+//! or shared network's subtree, using the configuration syntax of VyOS 1.4
+//! and later.
+
+use std::net::Ipv6Addr;
+
+use ipnet::Ipv6Net;
+use serde_json::Value;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::ipv6::{
+    Dhcpv6NetworkSettings, Dhcpv6Range, Dhcpv6SharedNetwork, Dhcpv6Subnet, Ipv6Prefix, Ipv6Scope,
+    PrefixDelegationPool, RouterAdvertInterface, RouterAdvertPrefix, RouterAdvertSettings,
+    RouterPreference,
+};
+use crate::models::node::NodeData;
+use crate::services::config_lock::ConfigLockService;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::config_tree::{entries, lookup, number, path, text, validate_name, values, ConfigTreeWriter};
+use crate::services::node_service::NodeService;
+use crate::vyos_client::VyOSNeighbor;
+
+/// Configuration path of the per-interface router advertisement settings
+const ROUTER_ADVERT_PATH: &[&str] = &["service", "router-advert", "interface"];
+
+/// Configuration path of the DHCPv6 shared networks
+const DHCPV6_SERVER_PATH: &[&str] = &["service", "dhcpv6-server", "shared-network-name"];
+
+/// Router advertisement prefix standing for every /64 on the interface
+const ALL_PREFIXES: &str = "::/64";
+
+/// IPv6 service
+#[derive(Clone)]
+pub struct Ipv6Service {
+    nodes: NodeService,
+    writer: ConfigTreeWriter,
+}
+
+impl Ipv6Service {
+    /// Create a new IPv6 service
+    pub fn new(nodes: NodeService, locks: ConfigLockService, sessions: ConfigSessionService) -> Self {
+        Self {
+            writer: ConfigTreeWriter::new(nodes.clone(), locks, sessions),
+            nodes,
+        }
+    }
+
+    /// Router advertisement settings of every interface of a node
+    pub async fn router_adverts(&self, node_id: Uuid) -> Result<NodeData<Vec<RouterAdvertInterface>>, AppError> {
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        Ok(config.map(|config| {
+            entries(lookup(&config, ROUTER_ADVERT_PATH))
+                .into_iter()
+                .map(|(interface, settings)| RouterAdvertInterface {
+                    interface,
+                    settings: parse_router_advert(settings),
+                })
+                .collect()
+        }))
+    }
+
+    /// Replace the router advertisement settings of an interface
+    pub async fn set_router_advert(
+        &self,
+        node_id: Uuid,
+        interface: &str,
+        settings: RouterAdvertSettings,
+        claims: &Claims,
+    ) -> Result<RouterAdvertInterface, AppError> {
+        validate_name("Interface", interface)?;
+        let settings = normalize_router_advert(settings)?;
+        let root = path(ROUTER_ADVERT_PATH, &[interface]);
+        self.writer.replace(node_id, root, router_advert_paths(&settings), claims).await?;
+
+        info!("{} set router advertisements of {} on node {}", claims.username, interface, node_id);
+        Ok(RouterAdvertInterface {
+            interface: interface.to_string(),
+            settings,
+        })
+    }
+
+    /// Stop sending router advertisements on an interface
+    pub async fn delete_router_advert(&self, node_id: Uuid, interface: &str, claims: &Claims) -> Result<(), AppError> {
+        validate_name("Interface", interface)?;
+        let root = path(ROUTER_ADVERT_PATH, &[interface]);
+        self.writer.replace(node_id, root, Vec::new(), claims).await?;
+
+        info!("{} removed router advertisements of {} on node {}", claims.username, interface, node_id);
+        Ok(())
+    }
+
+    /// DHCPv6 shared networks of a node
+    pub async fn dhcpv6_networks(&self, node_id: Uuid) -> Result<NodeData<Vec<Dhcpv6SharedNetwork>>, AppError> {
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        Ok(config.map(|config| {
+            entries(lookup(&config, DHCPV6_SERVER_PATH))
+                .into_iter()
+                .map(|(name, network)| Dhcpv6SharedNetwork {
+                    name,
+                    settings: parse_dhcpv6_network(network),
+                })
+                .collect()
+        }))
+    }
+
+    /// Replace a DHCPv6 shared network
+    pub async fn set_dhcpv6_network(
+        &self,
+        node_id: Uuid,
+        name: &str,
+        settings: Dhcpv6NetworkSettings,
+        claims: &Claims,
+    ) -> Result<Dhcpv6SharedNetwork, AppError> {
+        validate_name("Shared network name", name)?;
+        let settings = normalize_dhcpv6_network(settings)?;
+        let root = path(DHCPV6_SERVER_PATH, &[name]);
+        self.writer.replace(node_id, root, dhcpv6_network_paths(&settings), claims).await?;
+
+        info!("{} set DHCPv6 shared network {} on node {}", claims.username, name, node_id);
+        Ok(Dhcpv6SharedNetwork {
+            name: name.to_string(),
+            settings,
+        })
+    }
+
+    /// Remove a DHCPv6 shared network
+    pub async fn delete_dhcpv6_network(&self, node_id: Uuid, name: &str, claims: &Claims) -> Result<(), AppError> {
+        validate_name("Shared network name", name)?;
+        let root = path(DHCPV6_SERVER_PATH, &[name]);
+        self.writer.replace(node_id, root, Vec::new(), claims).await?;
+
+        info!("{} removed DHCPv6 shared network {} on node {}", claims.username, name, node_id);
+        Ok(())
+    }
+
+    /// IPv6 neighbor cache of a node
+    pub async fn neighbors(&self, node_id: Uuid) -> Result<NodeData<Vec<VyOSNeighbor>>, AppError> {
+        self.nodes.get_ipv6_neighbors(node_id).await
+    }
+
+    /// IPv6 prefixes on the interfaces of a node, marked with whether router
+    /// advertisements announce them
+    pub async fn prefixes(&self, node_id: Uuid) -> Result<NodeData<Vec<Ipv6Prefix>>, AppError> {
+        let adverts = self.router_adverts(node_id).await?;
+        let interfaces = self.nodes.get_node_interfaces(node_id).await?;
+        let stale = adverts.stale;
+
+        let mut prefixes = interfaces.map(|interfaces| {
+            interfaces
+                .iter()
+                .flat_map(|interface| {
+                    interface
+                        .addresses
+                        .iter()
+                        .filter_map(|address| address.parse::<Ipv6Net>().ok())
+                        .map(|address| {
+                            let advertised = adverts
+                                .data
+                                .iter()
+                                .find(|advert| advert.interface == interface.name)
+                                .is_some_and(|advert| advertises(&advert.settings, &address));
+                            Ipv6Prefix {
+                                interface: interface.name.clone(),
+                                address: address.to_string(),
+                                prefix: address.trunc().to_string(),
+                                scope: scope(&address.addr()),
+                                advertised,
+                            }
+                        })
+                })
+                .collect::<Vec<_>>()
+        });
+        prefixes.stale |= stale;
+        Ok(prefixes)
+    }
+}
+
+fn parse_router_advert(config: &Value) -> RouterAdvertSettings {
+    RouterAdvertSettings {
+        prefixes: entries(config.get("prefix"))
+            .into_iter()
+            .map(|(prefix, settings)| RouterAdvertPrefix {
+                prefix,
+                valid_lifetime: number(settings.get("valid-lifetime")),
+                preferred_lifetime: number(settings.get("preferred-lifetime")),
+                autonomous: settings.get("no-autonomous-flag").is_none(),
+                on_link: settings.get("no-on-link-flag").is_none(),
+            })
+            .collect(),
+        managed_flag: config.get("managed-flag").is_some(),
+        other_config_flag: config.get("other-config-flag").is_some(),
+        name_servers: values(config.get("name-server")),
+        default_preference: text(config.get("default-preference"))
+            .as_deref()
+            .and_then(RouterPreference::from_name),
+        default_lifetime: number(config.get("default-lifetime")),
+        max_interval: config.get("interval").and_then(|interval| number(interval.get("max"))),
+    }
+}
+
+/// Configuration paths of router advertisement settings, relative to the
+/// interface
+fn router_advert_paths(settings: &RouterAdvertSettings) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    for prefix in &settings.prefixes {
+        let words = |words: &[&str]| path(&["prefix", &prefix.prefix], words);
+        let before = paths.len();
+        if let Some(lifetime) = prefix.valid_lifetime {
+            paths.push(words(&["valid-lifetime", &lifetime.to_string()]));
+        }
+        if let Some(lifetime) = prefix.preferred_lifetime {
+            paths.push(words(&["preferred-lifetime", &lifetime.to_string()]));
+        }
+        if !prefix.autonomous {
+            paths.push(words(&["no-autonomous-flag"]));
+        }
+        if !prefix.on_link {
+            paths.push(words(&["no-on-link-flag"]));
+        }
+        if paths.len() == before {
+            paths.push(words(&[]));
+        }
+    }
+    if settings.managed_flag {
+        paths.push(path(&["managed-flag"], &[]));
+    }
+    if settings.other_config_flag {
+        paths.push(path(&["other-config-flag"], &[]));
+    }
+    for server in &settings.name_servers {
+        paths.push(path(&["name-server"], &[server]));
+    }
+    if let Some(preference) = settings.default_preference {
+        paths.push(path(&["default-preference"], &[preference.as_str()]));
+    }
+    if let Some(lifetime) = settings.default_lifetime {
+        paths.push(path(&["default-lifetime"], &[&lifetime.to_string()]));
+    }
+    if let Some(interval) = settings.max_interval {
+        paths.push(path(&["interval", "max"], &[&interval.to_string()]));
+    }
+    if paths.is_empty() {
+        // Advertise the router without any options
+        paths.push(Vec::new());
+    }
+    paths
+}
+
+fn parse_dhcpv6_network(config: &Value) -> Dhcpv6NetworkSettings {
+    let subnets = entries(config.get("subnet"))
+        .into_iter()
+        .map(|(subnet, settings)| Dhcpv6Subnet {
+            subnet,
+            subnet_id: number(settings.get("subnet-id")),
+            ranges: entries(settings.get("range"))
+                .into_iter()
+                .filter_map(|(_, range)| {
+                    Some(Dhcpv6Range {
+                        start: text(range.get("start"))?,
+                        stop: text(range.get("stop"))?,
+                    })
+                })
+                .collect(),
+            name_servers: values(settings.get("option").and_then(|option| option.get("name-server"))),
+            prefix_delegation: entries(settings.get("prefix-delegation").and_then(|pd| pd.get("start")))
+                .into_iter()
+                .filter_map(|(start, pool)| {
+                    Some(PrefixDelegationPool {
+                        start,
+                        stop: text(pool.get("stop"))?,
+                        prefix_length: number(pool.get("prefix-length"))?,
+                    })
+                })
+                .collect(),
+        })
+        .collect();
+
+    Dhcpv6NetworkSettings { subnets }
+}
+
+/// Configuration paths of a DHCPv6 shared network, relative to it
+fn dhcpv6_network_paths(settings: &Dhcpv6NetworkSettings) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    for subnet in &settings.subnets {
+        let words = |words: &[&str]| path(&["subnet", &subnet.subnet], words);
+        let before = paths.len();
+        if let Some(id) = subnet.subnet_id {
+            paths.push(words(&["subnet-id", &id.to_string()]));
+        }
+        for (index, range) in subnet.ranges.iter().enumerate() {
+            let id = (index + 1).to_string();
+            paths.push(words(&["range", &id, "start", &range.start]));
+            paths.push(words(&["range", &id, "stop", &range.stop]));
+        }
+        for server in &subnet.name_servers {
+            paths.push(words(&["option", "name-server", server]));
+        }
+        for pool in &subnet.prefix_delegation {
+            paths.push(words(&["prefix-delegation", "start", &pool.start, "stop", &pool.stop]));
+            paths.push(words(&[
+                "prefix-delegation",
+                "start",
+                &pool.start,
+                "prefix-length",
+                &pool.prefix_length.to_string(),
+            ]));
+        }
+        if paths.len() == before {
+            paths.push(words(&[]));
+        }
+    }
+    paths
+}
+
+/// IPv6 network in canonical form, rejecting addresses with host bits set
+fn parse_network(network: &str) -> Result<Ipv6Net, AppError> {
+    let net: Ipv6Net = network
+        .trim()
+        .parse()
+        .map_err(|_| AppError::Validation(format!("'{}' is not an IPv6 network", network)))?;
+    if net != net.trunc() {
+        return Err(AppError::Validation(format!(
+            "'{}' has host bits set; did you mean {}?",
+            network,
+            net.trunc()
+        )));
+    }
+    Ok(net)
+}
+
+fn parse_address(address: &str) -> Result<Ipv6Addr, AppError> {
+    address
+        .trim()
+        .parse()
+        .map_err(|_| AppError::Validation(format!("'{}' is not an IPv6 address", address)))
+}
+
+fn normalize_name_servers(servers: Vec<String>) -> Result<Vec<String>, AppError> {
+    servers
+        .iter()
+        .map(|server| parse_address(server).map(|address| address.to_string()))
+        .collect()
+}
+
+fn normalize_router_advert(settings: RouterAdvertSettings) -> Result<RouterAdvertSettings, AppError> {
+    let mut prefixes: Vec<RouterAdvertPrefix> = Vec::new();
+    for prefix in settings.prefixes {
+        let network = parse_network(&prefix.prefix)?.to_string();
+        if prefixes.iter().any(|other| other.prefix == network) {
+            return Err(AppError::Validation(format!("Prefix {} is listed twice", network)));
+        }
+        if let (Some(valid), Some(preferred)) = (prefix.valid_lifetime, prefix.preferred_lifetime) {
+            if preferred > valid {
+                return Err(AppError::Validation(format!(
+                    "The preferred lifetime of {} exceeds its valid lifetime",
+                    network
+                )));
+            }
+        }
+        prefixes.push(RouterAdvertPrefix { prefix: network, ..prefix });
+    }
+    if settings.max_interval.is_some_and(|interval| !(4..=1800).contains(&interval)) {
+        return Err(AppError::Validation(
+            "The maximum advertisement interval is between 4 and 1800 seconds".to_string(),
+        ));
+    }
+
+    Ok(RouterAdvertSettings {
+        prefixes,
+        name_servers: normalize_name_servers(settings.name_servers)?,
+        ..settings
+    })
+}
+
+fn normalize_dhcpv6_network(settings: Dhcpv6NetworkSettings) -> Result<Dhcpv6NetworkSettings, AppError> {
+    if settings.subnets.is_empty() {
+        return Err(AppError::Validation("A shared network needs at least one subnet".to_string()));
+    }
+
+    let mut subnets: Vec<Dhcpv6Subnet> = Vec::new();
+    for subnet in settings.subnets {
+        let network = parse_network(&subnet.subnet)?;
+        if subnets.iter().any(|other| other.subnet == network.to_string()) {
+            return Err(AppError::Validation(format!("Subnet {} is listed twice", network)));
+        }
+
+        let mut ranges = Vec::new();
+        for range in subnet.ranges {
+            let (start, stop) = (parse_address(&range.start)?, parse_address(&range.stop)?);
+            if !network.contains(&start) || !network.contains(&stop) || start > stop {
+                return Err(AppError::Validation(format!(
+                    "Range {} to {} is not an ascending range within {}",
+                    start, stop, network
+                )));
+            }
+            ranges.push(Dhcpv6Range {
+                start: start.to_string(),
+                stop: stop.to_string(),
+            });
+        }
+
+        let mut prefix_delegation = Vec::new();
+        for pool in subnet.prefix_delegation {
+            let (start, stop) = (parse_address(&pool.start)?, parse_address(&pool.stop)?);
+            if start > stop || !(1..=128).contains(&pool.prefix_length) {
+                return Err(AppError::Validation(format!(
+                    "Delegation pool {} to {} needs an ascending range and a prefix length up to 128",
+                    start, stop
+                )));
+            }
+            prefix_delegation.push(PrefixDelegationPool {
+                start: start.to_string(),
+                stop: stop.to_string(),
+                prefix_length: pool.prefix_length,
+            });
+        }
+
+        subnets.push(Dhcpv6Subnet {
+            subnet: network.to_string(),
+            subnet_id: subnet.subnet_id,
+            ranges,
+            name_servers: normalize_name_servers(subnet.name_servers)?,
+            prefix_delegation,
+        });
+    }
+
+    Ok(Dhcpv6NetworkSettings { subnets })
+}
+
+/// Scope of an IPv6 address
+fn scope(address: &Ipv6Addr) -> Ipv6Scope {
+    let first = address.segments()[0];
+    if address.is_loopback() {
+        Ipv6Scope::Loopback
+    } else if first & 0xffc0 == 0xfe80 {
+        Ipv6Scope::LinkLocal
+    } else if first & 0xfe00 == 0xfc00 {
+        Ipv6Scope::UniqueLocal
+    } else {
+        Ipv6Scope::Global
+    }
+}
+
+/// Whether router advertisement settings announce the prefix of an address
+fn advertises(settings: &RouterAdvertSettings, address: &Ipv6Net) -> bool {
+    let network = address.trunc().to_string();
+    settings.prefixes.iter().any(|prefix| {
+        prefix.prefix == network
+            || (prefix.prefix == ALL_PREFIXES
+                && address.prefix_len() == 64
+                && matches!(scope(&address.addr()), Ipv6Scope::Global | Ipv6Scope::UniqueLocal))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn words(path: &str) -> Vec<String> {
+        path.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_router_advert_round_trip() {
+        let config = json!({
+            "prefix": {
+                "2001:db8:1::/64": { "valid-lifetime": "86400", "preferred-lifetime": "14400" },
+                "fd00:1::/64": { "no-autonomous-flag": {} },
+            },
+            "other-config-flag": {},
+            "name-server": ["2001:db8::53", "2001:db8::54"],
+            "default-preference": "high",
+            "interval": { "max": "600" },
+        });
+        let settings = parse_router_advert(&config);
+        assert_eq!(settings.prefixes.len(), 2);
+        assert_eq!(settings.prefixes[0].valid_lifetime, Some(86400));
+        assert!(!settings.prefixes[1].autonomous && settings.prefixes[1].on_link);
+        assert!(settings.other_config_flag && !settings.managed_flag);
+        assert_eq!(settings.default_preference, Some(RouterPreference::High));
+
+        assert_eq!(
+            router_advert_paths(&settings),
+            [
+                words("prefix 2001:db8:1::/64 valid-lifetime 86400"),
+                words("prefix 2001:db8:1::/64 preferred-lifetime 14400"),
+                words("prefix fd00:1::/64 no-autonomous-flag"),
+                words("other-config-flag"),
+                words("name-server 2001:db8::53"),
+                words("name-server 2001:db8::54"),
+                words("default-preference high"),
+                words("interval max 600"),
+            ]
+        );
+        assert_eq!(router_advert_paths(&RouterAdvertSettings::default()), [Vec::<String>::new()]);
+    }
+
+    #[test]
+    fn test_dhcpv6_network_round_trip() {
+        let config = json!({
+            "subnet": {
+                "2001:db8:1::/64": {
+                    "subnet-id": "1",
+                    "range": { "1": { "start": "2001:db8:1::100", "stop": "2001:db8:1::1ff" } },
+                    "option": { "name-server": "2001:db8::53" },
+                    "prefix-delegation": { "start": {
+                        "2001:db8:100::": { "stop": "2001:db8:1ff::", "prefix-length": "56" },
+                    } },
+                },
+            },
+        });
+        let settings = parse_dhcpv6_network(&config);
+        assert_eq!(
+            settings.subnets[0].prefix_delegation,
+            [PrefixDelegationPool {
+                start: "2001:db8:100::".to_string(),
+                stop: "2001:db8:1ff::".to_string(),
+                prefix_length: 56,
+            }]
+        );
+        assert_eq!(
+            dhcpv6_network_paths(&settings),
+            [
+                words("subnet 2001:db8:1::/64 subnet-id 1"),
+                words("subnet 2001:db8:1::/64 range 1 start 2001:db8:1::100"),
+                words("subnet 2001:db8:1::/64 range 1 stop 2001:db8:1::1ff"),
+                words("subnet 2001:db8:1::/64 option name-server 2001:db8::53"),
+                words("subnet 2001:db8:1::/64 prefix-delegation start 2001:db8:100:: stop 2001:db8:1ff::"),
+                words("subnet 2001:db8:1::/64 prefix-delegation start 2001:db8:100:: prefix-length 56"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_settings_are_validated() {
+        let prefix = |prefix: &str| RouterAdvertPrefix {
+            prefix: prefix.to_string(),
+            valid_lifetime: Some(600),
+            preferred_lifetime: Some(300),
+            autonomous: true,
+            on_link: true,
+        };
+        let settings = |prefixes| RouterAdvertSettings { prefixes, ..Default::default() };
+
+        let normalized = normalize_router_advert(settings(vec![prefix("2001:DB8:0:1::/64")])).unwrap();
+        assert_eq!(normalized.prefixes[0].prefix, "2001:db8:0:1::/64");
+        assert!(normalize_router_advert(settings(vec![prefix("2001:db8::1/64")])).is_err());
+        assert!(normalize_router_advert(settings(vec![prefix("192.0.2.0/24")])).is_err());
+        assert!(normalize_router_advert(settings(vec![RouterAdvertPrefix {
+            preferred_lifetime: Some(6000),
+            ..prefix("2001:db8::/64")
+        }]))
+        .is_err());
+
+        let network = |start: &str| Dhcpv6NetworkSettings {
+            subnets: vec![Dhcpv6Subnet {
+                subnet: "2001:db8:1::/64".to_string(),
+                subnet_id: Some(1),
+                ranges: vec![Dhcpv6Range {
+                    start: start.to_string(),
+                    stop: "2001:db8:1::1ff".to_string(),
+                }],
+                name_servers: vec![],
+                prefix_delegation: vec![],
+            }],
+        };
+        assert!(normalize_dhcpv6_network(network("2001:db8:1::100")).is_ok());
+        assert!(normalize_dhcpv6_network(network("2001:db8:2::100")).is_err());
+        assert!(normalize_dhcpv6_network(Dhcpv6NetworkSettings::default()).is_err());
+
+        assert!(validate_name("Interface", "eth0.10").is_ok());
+        assert!(validate_name("Interface", "eth0 disable").is_err());
+    }
+
+    #[test]
+    fn test_prefix_scope_and_advertisement() {
+        let settings = RouterAdvertSettings {
+            prefixes: vec![RouterAdvertPrefix {
+                prefix: ALL_PREFIXES.to_string(),
+                valid_lifetime: None,
+                preferred_lifetime: None,
+                autonomous: true,
+                on_link: true,
+            }],
+            ..Default::default()
+        };
+        let global: Ipv6Net = "2001:db8::1/64".parse().unwrap();
+        let link_local: Ipv6Net = "fe80::1/64".parse().unwrap();
+        assert_eq!(scope(&global.addr()), Ipv6Scope::Global);
+        assert_eq!(scope(&link_local.addr()), Ipv6Scope::LinkLocal);
+        assert_eq!(scope(&"fd12::1".parse().unwrap()), Ipv6Scope::UniqueLocal);
+        assert!(advertises(&settings, &global));
+        assert!(!advertises(&settings, &link_local));
+        assert!(!advertises(&RouterAdvertSettings::default(), &global));
+    }
+}
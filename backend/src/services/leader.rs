@@ -0,0 +1,156 @@
+//! Leader Election Service
+//!
+//! When several instances share the database, the scheduled background jobs
+//! must run on exactly one of them. Instances compete for a time-limited
+//! lease in the `leader_leases` table; the holder renews it well before it
+//! expires, and another instance takes over once a lease runs out, e.g.
+//! because its holder crashed. Jobs check [`LeaderElection::is_leader`]
+//! before each run and can watch leadership changes with
+//! [`LeaderElection::subscribe`].
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::config::AppConfig;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::cluster::{ClusterStatus, LeaderLease, BACKGROUND_JOBS_LEASE};
+use crate::models::timestamp::{format_timestamp, parse_db_timestamp};
+
+/// Leader election service
+#[derive(Clone)]
+pub struct LeaderElection {
+    db: Database,
+    instance_id: String,
+    lease_duration: Duration,
+    leadership: Arc<watch::Sender<bool>>,
+}
+
+impl LeaderElection {
+    /// Create a new leader election service; the instance starts as follower
+    pub fn new(config: &AppConfig, db: Database) -> Self {
+        let (leadership, _) = watch::channel(false);
+        Self {
+            db,
+            instance_id: config.instance_id.clone(),
+            lease_duration: Duration::seconds(config.leader_lease_secs as i64),
+            leadership: Arc::new(leadership),
+        }
+    }
+
+    /// ID this instance holds leases under
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Whether this instance currently runs the background jobs
+    pub fn is_leader(&self) -> bool {
+        *self.leadership.borrow()
+    }
+
+    /// Receiver that is notified whenever this instance gains or loses
+    /// leadership
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.leadership.subscribe()
+    }
+
+    /// Acquire or renew the lease
+    ///
+    /// Succeeds when this instance already holds the lease or the lease has
+    /// expired. Returns whether this instance is the leader afterwards.
+    pub async fn try_acquire(&self) -> Result<bool, AppError> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO leader_leases (name, holder, acquired_at, expires_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(name) DO UPDATE SET
+                holder = excluded.holder,
+                acquired_at = CASE WHEN leader_leases.holder = excluded.holder
+                    THEN leader_leases.acquired_at ELSE excluded.acquired_at END,
+                expires_at = excluded.expires_at
+            WHERE leader_leases.holder = excluded.holder OR leader_leases.expires_at < ?
+            "#,
+        )
+        .bind(BACKGROUND_JOBS_LEASE)
+        .bind(&self.instance_id)
+        .bind(format_timestamp(&now))
+        .bind(format_timestamp(&(now + self.lease_duration)))
+        .bind(format_timestamp(&now))
+        .execute(self.db.pool())
+        .await?;
+
+        let leader = result.rows_affected() > 0;
+        self.set_leader(leader);
+        Ok(leader)
+    }
+
+    /// Give up the lease so another instance can take over right away
+    pub async fn release(&self) -> Result<(), AppError> {
+        self.set_leader(false);
+        sqlx::query("DELETE FROM leader_leases WHERE name = ? AND holder = ?")
+            .bind(BACKGROUND_JOBS_LEASE)
+            .bind(&self.instance_id)
+            .execute(self.db.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Compete for the lease until the process exits
+    ///
+    /// Renews three times per lease duration, so a leader keeps its lease
+    /// through a failed renewal. When the lease cannot be confirmed, the
+    /// instance steps down rather than risk two leaders.
+    pub async fn run(self) {
+        let period = (self.lease_duration / 3).to_std().unwrap_or(std::time::Duration::from_secs(1));
+        loop {
+            if let Err(e) = self.try_acquire().await {
+                warn!("Failed to renew the leader lease: {}", e);
+                self.set_leader(false);
+            }
+            tokio::time::sleep(period).await;
+        }
+    }
+
+    /// Leadership as seen by this instance
+    pub async fn status(&self) -> Result<ClusterStatus, AppError> {
+        let lease = sqlx::query_as::<_, LeaseRow>(
+            "SELECT name, holder, acquired_at, expires_at FROM leader_leases WHERE name = ?",
+        )
+        .bind(BACKGROUND_JOBS_LEASE)
+        .fetch_optional(self.db.pool())
+        .await?
+        .map(lease_from_row);
+
+        Ok(ClusterStatus {
+            instance_id: self.instance_id.clone(),
+            is_leader: self.is_leader(),
+            lease,
+        })
+    }
+
+    fn set_leader(&self, leader: bool) {
+        let changed = self.leadership.send_if_modified(|current| std::mem::replace(current, leader) != leader);
+        if changed {
+            if leader {
+                info!("Instance {} became the leader, running background jobs", self.instance_id);
+            } else {
+                info!("Instance {} is no longer the leader", self.instance_id);
+            }
+        }
+    }
+}
+
+type LeaseRow = (String, String, String, String);
+
+fn lease_from_row((name, holder, acquired_at, expires_at): LeaseRow) -> LeaderLease {
+    LeaderLease {
+        name,
+        holder,
+        acquired_at: parse_db_timestamp(&acquired_at),
+        expires_at: parse_db_timestamp(&expires_at),
+    }
+}
@@ -0,0 +1,241 @@
+//! Load Profile
+//!
+//! Self-contained targets for load generators such as k6 or oha. Each
+//! target exercises one hot path of the service layer on generated data —
+//! configuration tree serialization, configuration diffs, metric
+//! aggregation, and a database round trip — so that runs need no routers,
+//! users, or tokens and stay comparable between builds. Only available with
+//! `LOAD_PROFILE_ENABLED=true`, which is refused in production.
+//!
+//! The generators are public so that the criterion benchmarks in
+//! `benches/` measure the same work as the HTTP targets.
+
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::config::{ConfigMetadata, ConfigNode, ConfigNodeType};
+use crate::models::load_profile::{
+    ConfigDiffProfile, ConfigTreeProfile, DatabaseProfile, MetricsProfile, MAX_LOAD_PROFILE_NODES,
+    MAX_LOAD_PROFILE_SAMPLES,
+};
+use crate::models::monitoring::MetricsStatistics;
+use crate::services::ConfigService;
+
+/// Load profile service
+#[derive(Clone)]
+pub struct LoadProfileService {
+    db: Database,
+    enabled: bool,
+}
+
+impl LoadProfileService {
+    /// Create a new load profile service
+    pub fn new(db: Database, config: &AppConfig) -> Self {
+        Self {
+            db,
+            enabled: config.load_profile_enabled,
+        }
+    }
+
+    /// Ensure the load profile is enabled
+    pub fn ensure_enabled(&self) -> Result<(), AppError> {
+        if self.enabled {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(
+                "Load profile is disabled; set LOAD_PROFILE_ENABLED=true outside production".to_string(),
+            ))
+        }
+    }
+
+    /// Generate a configuration tree of the given shape
+    pub fn config_tree(&self, profile: ConfigTreeProfile) -> Result<ConfigNode, AppError> {
+        self.ensure_enabled()?;
+        check_tree_size(profile)?;
+        Ok(generate_config_tree(profile, 0))
+    }
+
+    /// Diff two generated configuration trees that differ in every third
+    /// leaf value
+    pub fn config_diff(&self, profile: ConfigTreeProfile) -> Result<ConfigDiffProfile, AppError> {
+        self.ensure_enabled()?;
+        let nodes = check_tree_size(profile)?;
+
+        let (additions, deletions, modifications) = ConfigService::calculate_diff(
+            &generate_config_tree(profile, 0),
+            &generate_config_tree(profile, 1),
+        );
+
+        Ok(ConfigDiffProfile {
+            nodes,
+            additions: additions.len(),
+            deletions: deletions.len(),
+            modifications: modifications.len(),
+        })
+    }
+
+    /// Aggregate generated metric samples
+    pub fn metrics(&self, profile: MetricsProfile) -> Result<Option<MetricsStatistics>, AppError> {
+        self.ensure_enabled()?;
+        if profile.samples > MAX_LOAD_PROFILE_SAMPLES {
+            return Err(AppError::Validation(format!(
+                "Samples must be at most {}",
+                MAX_LOAD_PROFILE_SAMPLES
+            )));
+        }
+
+        Ok(MetricsStatistics::from_values(&generate_metric_samples(profile.samples)))
+    }
+
+    /// Run one query through the connection pool
+    pub async fn database(&self) -> Result<DatabaseProfile, AppError> {
+        self.ensure_enabled()?;
+
+        let nodes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM nodes")
+            .fetch_one(self.db.pool())
+            .await?;
+
+        Ok(DatabaseProfile { nodes })
+    }
+}
+
+/// Number of nodes in a tree of the given shape, including the root
+pub fn config_tree_size(profile: ConfigTreeProfile) -> Option<usize> {
+    let mut level = 1usize;
+    let mut total = 1usize;
+    for _ in 0..profile.depth {
+        level = level.checked_mul(profile.breadth)?;
+        total = total.checked_add(level)?;
+    }
+    Some(total)
+}
+
+fn check_tree_size(profile: ConfigTreeProfile) -> Result<usize, AppError> {
+    config_tree_size(profile)
+        .filter(|nodes| *nodes <= MAX_LOAD_PROFILE_NODES)
+        .ok_or_else(|| {
+            AppError::Validation(format!(
+                "A tree of breadth {} and depth {} exceeds {} nodes",
+                profile.breadth, profile.depth, MAX_LOAD_PROFILE_NODES
+            ))
+        })
+}
+
+/// Generate a configuration tree of the given shape
+///
+/// Trees of the same shape and revision are identical apart from node IDs.
+/// Revisions other than 0 change the value of every third leaf.
+pub fn generate_config_tree(profile: ConfigTreeProfile, revision: u32) -> ConfigNode {
+    let mut leaf_index = 0;
+    generate_node(profile, revision, "", "root", profile.depth, &mut leaf_index)
+}
+
+fn generate_node(
+    profile: ConfigTreeProfile,
+    revision: u32,
+    parent_path: &str,
+    name: &str,
+    levels_below: usize,
+    leaf_index: &mut usize,
+) -> ConfigNode {
+    let path = if parent_path.is_empty() {
+        "/".to_string()
+    } else {
+        format!("{}/{}", parent_path.trim_end_matches('/'), name)
+    };
+    // Fixed timestamps keep serialized trees the same size between runs
+    let timestamp = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+    let (node_type, value, children) = if levels_below == 0 {
+        let value = if revision != 0 && leaf_index.is_multiple_of(3) {
+            format!("192.0.2.{}/24", (*leaf_index + revision as usize) % 254 + 1)
+        } else {
+            format!("192.0.2.{}/24", *leaf_index % 254 + 1)
+        };
+        *leaf_index += 1;
+        (ConfigNodeType::Leaf, Some(value), vec![])
+    } else {
+        let children = (0..profile.breadth)
+            .map(|i| {
+                generate_node(
+                    profile,
+                    revision,
+                    &path,
+                    &format!("node{}", i),
+                    levels_below - 1,
+                    leaf_index,
+                )
+            })
+            .collect();
+        (ConfigNodeType::Container, None, children)
+    };
+
+    ConfigNode {
+        id: Uuid::new_v4(),
+        path,
+        name: name.to_string(),
+        value,
+        node_type,
+        description: Some(format!("Generated node {}", name)),
+        children,
+        metadata: ConfigMetadata {
+            is_readonly: false,
+            is_required: false,
+            default_value: None,
+            validation: None,
+            help_text: None,
+        },
+        created_at: timestamp,
+        updated_at: timestamp,
+    }
+}
+
+/// Generate metric samples resembling a CPU usage series
+///
+/// The samples follow a daily curve with deterministic noise, so that every
+/// run sorts and aggregates the same values.
+pub fn generate_metric_samples(count: usize) -> Vec<f64> {
+    let mut noise: u64 = 0x2545_F491_4F6C_DD1D;
+    (0..count)
+        .map(|i| {
+            noise ^= noise << 13;
+            noise ^= noise >> 7;
+            noise ^= noise << 17;
+            let curve = 40.0 + 25.0 * (i as f64 / 1440.0 * std::f64::consts::TAU).sin();
+            (curve + (noise % 2000) as f64 / 100.0 - 10.0).clamp(0.0, 100.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(node: &ConfigNode) -> usize {
+        1 + node.children.iter().map(count).sum::<usize>()
+    }
+
+    #[test]
+    fn test_generated_tree_has_profiled_size() {
+        let profile = ConfigTreeProfile { breadth: 4, depth: 3 };
+        let tree = generate_config_tree(profile, 0);
+
+        assert_eq!(config_tree_size(profile), Some(85));
+        assert_eq!(count(&tree), 85);
+        assert_eq!(tree.children[3].children[0].children[2].path, "/node3/node0/node2");
+        assert_eq!(
+            config_tree_size(ConfigTreeProfile { breadth: usize::MAX, depth: 2 }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_generated_samples_are_deterministic() {
+        let samples = generate_metric_samples(500);
+        assert_eq!(samples, generate_metric_samples(500));
+        assert!(samples.iter().all(|value| (0.0..=100.0).contains(value)));
+    }
+}
@@ -0,0 +1,220 @@
+//! Login History Service
+//!
+//! Records every successful login with the client's address, device, and
+//! country, and emails the user when a login comes from a device or country
+//! they have not signed in from before. Countries are only known when a
+//! GeoIP database is configured.
+
+use std::net::{IpAddr, SocketAddr};
+
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::login_history::{
+    describe_user_agent, LoginHistoryQuery, LoginHistoryResponse, LoginRecord, LOGIN_HISTORY_LIMIT,
+};
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::models::user::User;
+use crate::services::{GeoIpService, Mailer};
+
+/// Login history service
+#[derive(Clone)]
+pub struct LoginHistoryService {
+    db: Database,
+    geoip: GeoIpService,
+    mailer: Mailer,
+}
+
+impl LoginHistoryService {
+    /// Create a new login history service
+    pub fn new(db: Database, geoip: GeoIpService, mailer: Mailer) -> Self {
+        Self { db, geoip, mailer }
+    }
+
+    /// Record a successful login
+    ///
+    /// A login from a new device or country is flagged and, unless it is the
+    /// user's first login, reported to them by email in the background.
+    pub async fn record(
+        &self,
+        user: &User,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<LoginRecord, AppError> {
+        let user_id = user.id.to_string();
+        let ip = ip_address.and_then(parse_ip);
+        let geo = ip.and_then(|ip| self.geoip.locate(ip));
+        let country_code = geo.as_ref().and_then(|geo| geo.country_code.clone());
+        let country_name = geo.and_then(|geo| geo.country_name);
+        let device = describe_user_agent(user_agent);
+
+        let (previous, known_device, known_country) = sqlx::query_as::<_, (i64, i64, i64)>(
+            "SELECT COUNT(*), \
+                    COALESCE(SUM(CASE WHEN device = ? THEN 1 ELSE 0 END), 0), \
+                    COALESCE(SUM(CASE WHEN country_code = ? THEN 1 ELSE 0 END), 0) \
+             FROM user_logins WHERE user_id = ?",
+        )
+        .bind(&device)
+        .bind(&country_code)
+        .bind(&user_id)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        let first_login = previous == 0;
+        let record = LoginRecord {
+            id: Uuid::new_v4(),
+            ip_address: ip.map(|ip| ip.to_string()),
+            user_agent: user_agent.map(|ua| ua.chars().take(512).collect()),
+            device,
+            new_device: !first_login && known_device == 0,
+            new_location: !first_login && country_code.is_some() && known_country == 0,
+            country_code,
+            country_name,
+            created_at: chrono::Utc::now(),
+        };
+
+        sqlx::query(
+            "INSERT INTO user_logins (id, user_id, ip_address, user_agent, device, country_code, country_name, \
+                                      new_device, new_location, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(record.id.to_string())
+        .bind(&user_id)
+        .bind(&record.ip_address)
+        .bind(&record.user_agent)
+        .bind(&record.device)
+        .bind(&record.country_code)
+        .bind(&record.country_name)
+        .bind(record.new_device)
+        .bind(record.new_location)
+        .bind(db_now())
+        .execute(self.db.pool())
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM user_logins WHERE user_id = ? AND id NOT IN \
+             (SELECT id FROM user_logins WHERE user_id = ? ORDER BY created_at DESC LIMIT ?)",
+        )
+        .bind(&user_id)
+        .bind(&user_id)
+        .bind(LOGIN_HISTORY_LIMIT)
+        .execute(self.db.pool())
+        .await?;
+
+        if record.is_suspicious() {
+            info!(
+                "Login of {} from a new {} ({}, {})",
+                user.username,
+                if record.new_device { "device" } else { "location" },
+                record.device,
+                record.ip_address.as_deref().unwrap_or("unknown address")
+            );
+            if self.mailer.is_enabled() {
+                let service = self.clone();
+                let user = user.clone();
+                let record = record.clone();
+                tokio::spawn(async move { service.notify(&user, &record).await });
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Email the user about a login from a new device or location
+    async fn notify(&self, user: &User, record: &LoginRecord) {
+        let location = match (&record.country_name, &record.country_code) {
+            (Some(name), _) => name.clone(),
+            (None, Some(code)) => code.clone(),
+            (None, None) => "an unknown location".to_string(),
+        };
+        let body = format!(
+            "Hello {},\n\n\
+             Your VyOS Web UI account was signed in to from a {} you have not used before:\n\n\
+             \x20   Time:     {}\n\
+             \x20   Device:   {}\n\
+             \x20   Address:  {}\n\
+             \x20   Location: {}\n\n\
+             If this was you, there is nothing to do. Otherwise, change your password right away \
+             and contact an administrator.\n",
+            user.username,
+            match (record.new_device, record.new_location) {
+                (true, true) => "device and location",
+                (true, false) => "device",
+                _ => "location",
+            },
+            format_timestamp(&record.created_at),
+            record.device,
+            record.ip_address.as_deref().unwrap_or("unknown"),
+            location,
+        );
+
+        if let Err(e) = self
+            .mailer
+            .send(std::slice::from_ref(&user.email), "New sign-in to your account", &body, None)
+            .await
+        {
+            warn!("Failed to notify {} of a new sign-in: {}", user.username, e);
+        }
+    }
+
+    /// A user's logins, newest first
+    pub async fn list(&self, user_id: Uuid, query: LoginHistoryQuery) -> Result<LoginHistoryResponse, AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(50).clamp(1, 200);
+        let user_id = user_id.to_string();
+
+        let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM user_logins WHERE user_id = ?")
+            .bind(&user_id)
+            .fetch_one(self.db.pool())
+            .await?;
+
+        let rows = sqlx::query_as::<
+            _,
+            (String, Option<String>, Option<String>, String, Option<String>, Option<String>, bool, bool, String),
+        >(
+            "SELECT id, ip_address, user_agent, device, country_code, country_name, new_device, new_location, created_at \
+             FROM user_logins WHERE user_id = ? ORDER BY created_at DESC LIMIT ? OFFSET ?",
+        )
+        .bind(&user_id)
+        .bind(i64::from(page_size))
+        .bind(i64::from((page - 1) * page_size))
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let logins = rows
+            .into_iter()
+            .map(
+                |(id, ip_address, user_agent, device, country_code, country_name, new_device, new_location, created_at)| {
+                    LoginRecord {
+                        id: Uuid::parse_str(&id).unwrap_or_else(|_| Uuid::nil()),
+                        ip_address,
+                        user_agent,
+                        device,
+                        country_code,
+                        country_name,
+                        new_device,
+                        new_location,
+                        created_at: parse_db_timestamp(&created_at),
+                    }
+                },
+            )
+            .collect();
+
+        Ok(LoginHistoryResponse {
+            logins,
+            total: total as u64,
+            page,
+            page_size,
+        })
+    }
+}
+
+/// Parse a client address, which may carry a port
+fn parse_ip(address: &str) -> Option<IpAddr> {
+    address
+        .parse::<IpAddr>()
+        .ok()
+        .or_else(|| address.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
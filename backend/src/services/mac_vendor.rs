@@ -0,0 +1,264 @@
+//! MAC Vendor Lookup Service
+//!
+//! Resolves MAC addresses to the vendor the IEEE assigned their address
+//! block to, so device listings can show vendor names. A small database of
+//! vendors common in networks ships with the server; administrators can
+//! download the full IEEE registry, which then takes precedence.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use reqwest::Client;
+use sqlx::{QueryBuilder, Sqlite};
+use tracing::info;
+
+use crate::config::AppConfig;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::mac_vendor::{MacLookup, OuiDatabaseStatus, OuiSource};
+use crate::models::timestamp::{db_now, parse_db_timestamp};
+use crate::utils::csv::csv_records;
+
+/// Vendor database shipped with the server, in the IEEE CSV format
+const BUNDLED_OUI_CSV: &str = include_str!("../../resources/oui.csv");
+
+/// Largest vendor database download accepted, in bytes
+const MAX_OUI_DATABASE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Rows inserted per statement, below SQLite's limit of bound parameters
+const INSERT_BATCH_ROWS: usize = 300;
+
+/// Hex digits of IEEE address blocks: MA-S, MA-M, and MA-L, longest first
+const BLOCK_LENGTHS: [usize; 3] = [9, 7, 6];
+
+/// MAC vendor lookup service
+#[derive(Clone)]
+pub struct MacVendorService {
+    db: Database,
+    client: Client,
+    update_url: String,
+}
+
+impl MacVendorService {
+    /// Create a new MAC vendor lookup service
+    pub fn new(config: &AppConfig, db: Database) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self {
+            db,
+            client,
+            update_url: config.oui_database_url.clone(),
+        }
+    }
+
+    /// Look up the vendor of a MAC address
+    pub async fn lookup(&self, mac: &str) -> Result<MacLookup, AppError> {
+        let octets = parse_mac(mac).ok_or_else(|| AppError::Validation(format!("Invalid MAC address: {}", mac)))?;
+        let locally_administered = octets[0] & 0x02 != 0;
+
+        // Locally administered addresses carry no vendor block
+        let found = if locally_administered {
+            None
+        } else {
+            self.find_block(&octets).await?
+        };
+
+        Ok(MacLookup {
+            mac: octets.iter().map(|o| format!("{:02x}", o)).collect::<Vec<_>>().join(":"),
+            oui: found.as_ref().map(|(block, _)| format_block(block)),
+            vendor: found.map(|(_, vendor)| vendor),
+            locally_administered,
+            multicast: octets[0] & 0x01 != 0,
+        })
+    }
+
+    /// Vendor of a MAC address, or `None` if it is unknown or not a MAC
+    pub async fn vendor_of(&self, mac: &str) -> Option<String> {
+        self.lookup(mac).await.ok().and_then(|lookup| lookup.vendor)
+    }
+
+    /// State of the vendor database
+    pub async fn status(&self) -> Result<OuiDatabaseStatus, AppError> {
+        let (entries, updated_at): (i64, Option<String>) =
+            sqlx::query_as("SELECT COUNT(*), MAX(updated_at) FROM oui_vendors")
+                .fetch_one(self.db.pool())
+                .await?;
+
+        Ok(if entries > 0 {
+            OuiDatabaseStatus {
+                source: OuiSource::Downloaded,
+                entries: entries as u64,
+                updated_at: updated_at.map(|at| parse_db_timestamp(&at)),
+                update_url: self.update_url.clone(),
+            }
+        } else {
+            OuiDatabaseStatus {
+                source: OuiSource::Bundled,
+                entries: bundled().len() as u64,
+                updated_at: None,
+                update_url: self.update_url.clone(),
+            }
+        })
+    }
+
+    /// Download the IEEE registry and replace the vendor database with it
+    ///
+    /// The download must contain at least one vendor block; the previous
+    /// database is kept otherwise.
+    pub async fn update(&self) -> Result<OuiDatabaseStatus, AppError> {
+        info!("Downloading MAC vendor database from {}", self.update_url);
+
+        let mut response = self.client.get(&self.update_url).send().await?;
+        if !response.status().is_success() {
+            return Err(AppError::ExternalApi(format!(
+                "Vendor database download failed: {}",
+                response.status()
+            )));
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > MAX_OUI_DATABASE_BYTES {
+                return Err(AppError::ExternalApi(format!(
+                    "Vendor database is larger than {} bytes",
+                    MAX_OUI_DATABASE_BYTES
+                )));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let entries = parse_oui_csv(&String::from_utf8_lossy(&body));
+        if entries.is_empty() {
+            return Err(AppError::ExternalApi(
+                "Vendor database download contains no vendor blocks".to_string(),
+            ));
+        }
+
+        let now = db_now();
+        let mut tx = self.db.pool().begin().await?;
+        sqlx::query("DELETE FROM oui_vendors").execute(&mut *tx).await?;
+        for batch in entries.chunks(INSERT_BATCH_ROWS) {
+            let mut insert: QueryBuilder<Sqlite> =
+                QueryBuilder::new("INSERT OR REPLACE INTO oui_vendors (prefix, vendor, updated_at) ");
+            insert.push_values(batch, |mut row, (prefix, vendor)| {
+                row.push_bind(prefix).push_bind(vendor).push_bind(&now);
+            });
+            insert.build().execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        info!("MAC vendor database updated with {} vendor blocks", entries.len());
+        self.status().await
+    }
+
+    /// Longest vendor block containing an address, with its vendor
+    async fn find_block(&self, octets: &[u8; 6]) -> Result<Option<(String, String)>, AppError> {
+        let hex: String = octets.iter().map(|o| format!("{:02X}", o)).collect();
+        let candidates: Vec<&str> = BLOCK_LENGTHS.iter().map(|len| &hex[..*len]).collect();
+
+        let downloaded: Option<(String, String)> = sqlx::query_as(
+            "SELECT prefix, vendor FROM oui_vendors WHERE prefix IN (?, ?, ?) ORDER BY LENGTH(prefix) DESC LIMIT 1",
+        )
+        .bind(candidates[0])
+        .bind(candidates[1])
+        .bind(candidates[2])
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        Ok(downloaded.or_else(|| {
+            candidates
+                .iter()
+                .find_map(|block| bundled().get(*block).map(|vendor| (block.to_string(), vendor.clone())))
+        }))
+    }
+}
+
+/// Vendor blocks of the bundled database
+fn bundled() -> &'static HashMap<String, String> {
+    static BUNDLED: OnceLock<HashMap<String, String>> = OnceLock::new();
+    BUNDLED.get_or_init(|| parse_oui_csv(BUNDLED_OUI_CSV).into_iter().collect())
+}
+
+/// Parse a MAC address written with colons, dashes, dots, or no separators
+fn parse_mac(mac: &str) -> Option<[u8; 6]> {
+    let hex: String = mac.trim().chars().filter(|c| !matches!(c, ':' | '-' | '.')).collect();
+    if hex.len() != 12 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let mut octets = [0u8; 6];
+    for (i, octet) in octets.iter_mut().enumerate() {
+        *octet = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(octets)
+}
+
+/// `0050C2` as `00:50:c2`, `70B3D51` as `70:b3:d5:1`
+fn format_block(block: &str) -> String {
+    block
+        .to_ascii_lowercase()
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| String::from_utf8_lossy(pair).into_owned())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Vendor blocks of an IEEE registry in CSV format
+///
+/// Reads the `Assignment` and `Organization Name` columns of the MA-L,
+/// MA-M, and MA-S registries; rows with a malformed assignment are skipped.
+fn parse_oui_csv(csv: &str) -> Vec<(String, String)> {
+    csv_records(csv)
+        .into_iter()
+        .filter_map(|record| {
+            let assignment = record.get(1)?.trim().to_ascii_uppercase();
+            let vendor = record.get(2)?.trim();
+            let valid = BLOCK_LENGTHS.contains(&assignment.len())
+                && assignment.chars().all(|c| c.is_ascii_hexdigit())
+                && !vendor.is_empty();
+            valid.then(|| (assignment, vendor.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mac() {
+        let expected = Some([0x00, 0x50, 0x56, 0xab, 0xcd, 0xef]);
+        assert_eq!(parse_mac("00:50:56:ab:cd:ef"), expected);
+        assert_eq!(parse_mac("00-50-56-AB-CD-EF"), expected);
+        assert_eq!(parse_mac("0050.56ab.cdef"), expected);
+        assert_eq!(parse_mac("005056abcdef"), expected);
+        assert_eq!(parse_mac("00:50:56:ab:cd"), None);
+        assert_eq!(parse_mac("00:50:56:ab:cd:eg"), None);
+    }
+
+    #[test]
+    fn test_parse_oui_csv() {
+        let csv = "Registry,Assignment,Organization Name,Organization Address\r\n\
+                   MA-L,00000C,\"Cisco Systems, Inc\",\"170 West Tasman Drive\nSan Jose\"\r\n\
+                   MA-M,70B3D51,\"Example \"\"Quoted\"\" GmbH\",\r\n\
+                   MA-L,XYZ123,Broken,\r\n";
+        assert_eq!(
+            parse_oui_csv(csv),
+            vec![
+                ("00000C".to_string(), "Cisco Systems, Inc".to_string()),
+                ("70B3D51".to_string(), "Example \"Quoted\" GmbH".to_string()),
+            ]
+        );
+        assert_eq!(format_block("70B3D51"), "70:b3:d5:1");
+    }
+
+    #[test]
+    fn test_bundled_database_parses() {
+        assert_eq!(bundled().get("005056").map(String::as_str), Some("VMware, Inc."));
+        assert_eq!(bundled().len(), BUNDLED_OUI_CSV.lines().count() - 1);
+    }
+}
@@ -0,0 +1,140 @@
+//! Mailer
+//!
+//! Sends email through the SMTP relay from the configuration. Email is
+//! disabled when no relay is configured.
+
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tracing::{info, warn};
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+/// File attached to an email
+#[derive(Debug, Clone)]
+pub struct MailAttachment {
+    pub file_name: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+/// SMTP mailer
+#[derive(Clone)]
+pub struct Mailer {
+    transport: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    from: Option<Mailbox>,
+}
+
+impl Mailer {
+    /// Create a mailer from the SMTP settings
+    ///
+    /// Invalid settings are logged and leave email disabled rather than
+    /// keeping the server from starting.
+    pub fn new(config: &AppConfig) -> Self {
+        let Some(host) = &config.smtp_host else {
+            return Self::disabled();
+        };
+
+        let from = match config.smtp_from.parse::<Mailbox>() {
+            Ok(from) => from,
+            Err(e) => {
+                warn!("Email disabled, invalid SMTP_FROM {:?}: {}", config.smtp_from, e);
+                return Self::disabled();
+            }
+        };
+
+        let builder = if config.smtp_starttls {
+            match AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host) {
+                Ok(builder) => builder,
+                Err(e) => {
+                    warn!("Email disabled, invalid SMTP relay {}: {}", host, e);
+                    return Self::disabled();
+                }
+            }
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+        };
+
+        let mut builder = builder.port(config.smtp_port);
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        info!("Email enabled via {}:{}", host, config.smtp_port);
+        Self {
+            transport: Some(builder.build()),
+            from: Some(from),
+        }
+    }
+
+    fn disabled() -> Self {
+        Self { transport: None, from: None }
+    }
+
+    /// Whether an SMTP relay is configured
+    pub fn is_enabled(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    /// Check that email can be sent to the given addresses
+    pub fn check_recipients(&self, recipients: &[String]) -> Result<(), AppError> {
+        if recipients.is_empty() {
+            return Ok(());
+        }
+        if !self.is_enabled() {
+            return Err(AppError::Validation("Email delivery is not configured".to_string()));
+        }
+        for recipient in recipients {
+            recipient
+                .parse::<Mailbox>()
+                .map_err(|_| AppError::Validation(format!("Invalid email address: {}", recipient)))?;
+        }
+        Ok(())
+    }
+
+    /// Send a plain text email with an optional attachment to each recipient
+    pub async fn send(
+        &self,
+        recipients: &[String],
+        subject: &str,
+        body: &str,
+        attachment: Option<MailAttachment>,
+    ) -> Result<(), AppError> {
+        let (Some(transport), Some(from)) = (&self.transport, &self.from) else {
+            return Err(AppError::Validation("Email delivery is not configured".to_string()));
+        };
+        self.check_recipients(recipients)?;
+
+        let mut builder = Message::builder().from(from.clone()).subject(subject);
+        for recipient in recipients {
+            builder = builder.to(recipient
+                .parse()
+                .map_err(|_| AppError::Validation(format!("Invalid email address: {}", recipient)))?);
+        }
+
+        let text = SinglePart::plain(body.to_string());
+        let message = match attachment {
+            Some(attachment) => {
+                let content_type = ContentType::parse(&attachment.content_type)
+                    .map_err(|e| AppError::Internal(format!("Invalid attachment content type: {}", e)))?;
+                builder.multipart(
+                    MultiPart::mixed()
+                        .singlepart(text)
+                        .singlepart(Attachment::new(attachment.file_name).body(attachment.content, content_type)),
+                )
+            }
+            None => builder.singlepart(text),
+        }
+        .map_err(|e| AppError::Internal(format!("Failed to build email: {}", e)))?;
+
+        transport
+            .send(message)
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Failed to send email: {}", e)))?;
+
+        info!("Sent email {:?} to {} recipient(s)", subject, recipients.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,609 @@
+//! Metrics Collector
+//!
+//! Reads CPU, memory, disk, and interface counters from every node in the
+//! background, on `METRICS_COLLECTION_INTERVAL_SECS`, so the system metrics
+//! served by the monitoring API are those of the real devices. Each
+//! collection replaces the node's system metrics and adds the headline
+//! values to the metrics history, where alert rules and charts find them.
+//!
+//! The counters are read with operational-mode show commands. VyOS has none
+//! for CPU usage, so it is estimated from the one-minute load average over
+//! the number of CPUs. Interface bitrates and error rates are derived from
+//! the counters of consecutive collections.
+//!
+//! Each node's fresh metrics are also published live on its
+//! `metrics:{node_id}` WebSocket channel.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::monitoring::{
+    CpuMetrics, DiskMetrics, IpAddressInfo, IpType, MemoryMetrics, MetricData, MetricLabel, MetricType, MetricUnit,
+    NetworkInterfaceStatus, NetworkMetrics, SystemMetrics, INTERFACE_UTILIZATION_METRIC,
+};
+use crate::models::node::{Node, NodeStatus};
+use crate::services::{MonitoringService, NodeService};
+use crate::vyos_client::VyOSInterface;
+use crate::websocket::ConnectionManager;
+
+const SHOW_UPTIME: &str = "show system uptime";
+const SHOW_CPU: &str = "show system cpu";
+const SHOW_MEMORY: &str = "show system memory";
+const SHOW_STORAGE: &str = "show system storage";
+const SHOW_COUNTERS: &str = "show interfaces counters";
+
+/// Metrics collector
+#[derive(Clone)]
+pub struct MetricsCollector {
+    nodes: NodeService,
+    monitoring: MonitoringService,
+    connections: ConnectionManager,
+}
+
+impl MetricsCollector {
+    /// Create a new metrics collector
+    pub fn new(nodes: NodeService, monitoring: MonitoringService, connections: ConnectionManager) -> Self {
+        Self {
+            nodes,
+            monitoring,
+            connections,
+        }
+    }
+
+    /// Collect the metrics of every node once
+    ///
+    /// Returns the number of nodes metrics were collected from. Rebooting
+    /// nodes, and nodes that cannot be read, are skipped.
+    pub async fn collect(&self) -> Result<usize, AppError> {
+        let mut collected = 0;
+        for node in self.nodes.list_all_nodes().await? {
+            if node.status == NodeStatus::Rebooting {
+                continue;
+            }
+            match self.collect_node(&node).await {
+                Ok(_) => collected += 1,
+                Err(e) => debug!("Could not collect the metrics of node {}: {}", node.id, e),
+            }
+        }
+        if collected > 0 {
+            info!("Collected the system metrics of {} nodes", collected);
+        }
+        Ok(collected)
+    }
+
+    /// Collect and store the metrics of one node
+    ///
+    /// Fails when the node's uptime cannot be read; any other reading the
+    /// node cannot answer is left empty.
+    pub async fn collect_node(&self, node: &Node) -> Result<SystemMetrics, AppError> {
+        let now = Utc::now();
+        let uptime = parse_uptime(&self.show(node.id, SHOW_UPTIME).await?);
+        let core_count = self.show_optional(node.id, SHOW_CPU).await.and_then(|output| parse_cpu_count(&output));
+        let memory = self.show_optional(node.id, SHOW_MEMORY).await.map(|output| parse_memory(&output));
+        let disks = self.show_optional(node.id, SHOW_STORAGE).await.map(|output| parse_storage(&output));
+        let counters = self.show_optional(node.id, SHOW_COUNTERS).await.map(|output| parse_counters(&output));
+        let interfaces = match self.nodes.get_node_interfaces(node.id).await {
+            Ok(interfaces) => interfaces.data,
+            Err(e) => {
+                debug!("Could not read the interfaces of node {}: {}", node.id, e);
+                Vec::new()
+            }
+        };
+
+        let previous = self.monitoring.last_system_metrics(&node.id.to_string()).await;
+        let metrics = SystemMetrics {
+            node_id: node.id.to_string(),
+            node_name: node.name.clone(),
+            timestamp: now,
+            cpu: cpu_metrics(&uptime, core_count.unwrap_or(1)),
+            memory: memory.unwrap_or_else(|| parse_memory("")),
+            disks: disks.unwrap_or_default(),
+            network: network_metrics(counters.unwrap_or_default(), &interfaces, previous.as_ref(), now),
+            load_average: uptime.load_average,
+            uptime_seconds: uptime.seconds,
+            process_count: 0,
+            system_time: now,
+        };
+
+        for point in data_points(&metrics, previous.as_ref()) {
+            self.monitoring.record_metric(point).await;
+        }
+        self.monitoring.record_system_metrics(metrics.clone()).await;
+        if let Ok(data) = serde_json::to_value(&metrics) {
+            self.connections.publish(&format!("metrics:{}", node.id), data);
+        }
+        Ok(metrics)
+    }
+
+    /// Run a show command that must succeed
+    ///
+    /// The show cache is bypassed so counters are read fresh each collection.
+    async fn show(&self, node_id: Uuid, command: &str) -> Result<String, AppError> {
+        let result = self.nodes.execute_show_command(node_id, command, true).await?.result;
+        if !result.success {
+            return Err(AppError::ExternalApi(result.error.unwrap_or_else(|| format!("{} failed", command))));
+        }
+        Ok(result.output)
+    }
+
+    /// Run a show command the node may not support
+    async fn show_optional(&self, node_id: Uuid, command: &str) -> Option<String> {
+        match self.show(node_id, command).await {
+            Ok(output) => Some(output),
+            Err(e) => {
+                debug!("Could not run `{}` on node {}: {}", command, node_id, e);
+                None
+            }
+        }
+    }
+}
+
+/// Uptime and load read from `show system uptime`
+#[derive(Debug, Default, PartialEq)]
+struct Uptime {
+    seconds: u64,
+    load_average: [f64; 3],
+    /// Whether the load is given as a percentage of the CPUs' capacity,
+    /// as VyOS 1.4 and later do, rather than as a run queue length
+    load_is_percent: bool,
+}
+
+/// Parse the output of `show system uptime`
+///
+/// Accepts both the VyOS 1.4 layout (`Uptime: 2d 3h 4m 5s` followed by one
+/// line per load average) and the classic `uptime` line.
+fn parse_uptime(output: &str) -> Uptime {
+    let mut uptime = Uptime::default();
+    for line in output.lines().map(str::trim) {
+        if let Some(duration) = line.strip_prefix("Uptime:") {
+            uptime.seconds = parse_duration(duration);
+        } else if let Some((_, loads)) = line.split_once("load average:") {
+            for (slot, load) in uptime.load_average.iter_mut().zip(loads.split(',')) {
+                *slot = load.trim().parse().unwrap_or(0.0);
+            }
+            if let Some((_, up)) = line.split_once(" up ") {
+                uptime.seconds = parse_classic_duration(up);
+            }
+        } else if let Some((period, load)) = line.split_once(':') {
+            let slot = match period.split_whitespace().next() {
+                Some("1") => 0,
+                Some("5") => 1,
+                Some("15") => 2,
+                _ => continue,
+            };
+            let load = load.trim();
+            uptime.load_is_percent |= load.ends_with('%');
+            uptime.load_average[slot] = load.trim_end_matches('%').trim().parse().unwrap_or(0.0);
+        }
+    }
+    uptime
+}
+
+/// Seconds of a duration such as `2d 3h 4m 5s`
+fn parse_duration(text: &str) -> u64 {
+    text.split_whitespace()
+        .filter_map(|part| {
+            let split = part.find(|c: char| !c.is_ascii_digit())?;
+            let (value, unit) = part.split_at(split);
+            let scale = match unit {
+                "w" => 7 * 86_400,
+                "d" => 86_400,
+                "h" => 3_600,
+                "m" => 60,
+                "s" => 1,
+                _ => return None,
+            };
+            Some(value.parse::<u64>().ok()? * scale)
+        })
+        .sum()
+}
+
+/// Seconds of the duration of an `uptime` line after `up`, such as
+/// `2 days,  3:04,  1 user, ...`
+fn parse_classic_duration(text: &str) -> u64 {
+    let mut seconds = 0;
+    for part in text.split(',').map(str::trim) {
+        if let Some(days) = part.strip_suffix("days").or_else(|| part.strip_suffix("day")) {
+            seconds += days.trim().parse::<u64>().unwrap_or(0) * 86_400;
+        } else if let Some(minutes) = part.strip_suffix("min") {
+            seconds += minutes.trim().parse::<u64>().unwrap_or(0) * 60;
+        } else if let Some((hours, minutes)) = part.split_once(':') {
+            seconds += hours.parse::<u64>().unwrap_or(0) * 3_600 + minutes.parse::<u64>().unwrap_or(0) * 60;
+        } else {
+            break;
+        }
+    }
+    seconds
+}
+
+/// Number of CPUs listed by `show system cpu`
+fn parse_cpu_count(output: &str) -> Option<u32> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        matches!(key.trim(), "CPU(s)" | "CPUs").then(|| value.trim().parse().ok())?
+    })
+}
+
+/// Estimated CPU metrics of a node
+fn cpu_metrics(uptime: &Uptime, core_count: u32) -> CpuMetrics {
+    let load = uptime.load_average[0];
+    let usage_percent = if uptime.load_is_percent {
+        load
+    } else {
+        load * 100.0 / core_count.max(1) as f64
+    }
+    .clamp(0.0, 100.0);
+
+    CpuMetrics {
+        usage_percent,
+        core_count,
+        core_usage: Vec::new(),
+        user_percent: 0.0,
+        system_percent: 0.0,
+        idle_percent: 100.0 - usage_percent,
+        iowait_percent: 0.0,
+        steal_percent: 0.0,
+        frequency_mhz: None,
+        temperature_celsius: None,
+    }
+}
+
+/// Parse a size such as `1.9G`, `512M`, or `3941 MB`
+///
+/// Sizes without a unit are in `default_unit` bytes.
+fn parse_size(text: &str, default_unit: u64) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let (value, unit) = text.split_at(split);
+    let value: f64 = value.parse().ok()?;
+    let scale = match unit.trim().trim_end_matches(['B', 'i']) {
+        "" if unit.trim().is_empty() => default_unit,
+        "" => 1,
+        "K" | "k" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return None,
+    };
+    Some((value * scale as f64) as u64)
+}
+
+/// Parse the output of `show system memory`
+///
+/// Older releases print plain numbers, in MiB.
+fn parse_memory(output: &str) -> MemoryMetrics {
+    let mut values = HashMap::new();
+    for line in output.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            if let Some(bytes) = parse_size(value, 1 << 20) {
+                values.insert(key.trim().to_ascii_lowercase(), bytes);
+            }
+        }
+    }
+
+    let total_bytes = values.get("total").copied().unwrap_or(0);
+    let used_bytes = values
+        .get("used")
+        .copied()
+        .or_else(|| values.get("free").map(|free| total_bytes.saturating_sub(*free)))
+        .unwrap_or(0);
+    let available_bytes = values
+        .get("available")
+        .or_else(|| values.get("free"))
+        .copied()
+        .unwrap_or_else(|| total_bytes.saturating_sub(used_bytes));
+
+    MemoryMetrics {
+        total_bytes,
+        available_bytes,
+        used_bytes,
+        usage_percent: percent(used_bytes, total_bytes),
+        cached_bytes: values.get("cached").copied().unwrap_or(0),
+        buffer_bytes: values.get("buffers").copied().unwrap_or(0),
+        swap_total_bytes: 0,
+        swap_used_bytes: 0,
+        swap_usage_percent: 0.0,
+    }
+}
+
+/// Parse the `df`-style table of `show system storage`
+fn parse_storage(output: &str) -> Vec<DiskMetrics> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let [device, size, used, available, _, mount_point] = columns[..] else {
+                return None;
+            };
+            let total_bytes = parse_size(size, 1 << 10)?;
+            let used_bytes = parse_size(used, 1 << 10)?;
+            Some(DiskMetrics {
+                device: device.to_string(),
+                mount_point: mount_point.to_string(),
+                fs_type: String::new(),
+                total_bytes,
+                used_bytes,
+                available_bytes: parse_size(available, 1 << 10)?,
+                usage_percent: percent(used_bytes, total_bytes),
+                inodes_total: 0,
+                inodes_used: 0,
+                inodes_usage_percent: 0.0,
+                read_ops: 0,
+                write_ops: 0,
+                read_bytes: 0,
+                write_bytes: 0,
+                io_in_progress: None,
+            })
+        })
+        .collect()
+}
+
+/// Counters of one interface read from `show interfaces counters`
+#[derive(Debug, Default, Clone, PartialEq)]
+struct InterfaceCounters {
+    interface: String,
+    rx_packets: u64,
+    rx_bytes: u64,
+    tx_packets: u64,
+    tx_bytes: u64,
+    rx_drops: u64,
+    tx_drops: u64,
+    rx_errors: u64,
+    tx_errors: u64,
+}
+
+/// Parse the table of `show interfaces counters`
+///
+/// The columns are packets and bytes received and transmitted, followed on
+/// newer releases by the drop and error counts.
+fn parse_counters(output: &str) -> Vec<InterfaceCounters> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let interface = columns.next()?;
+            let values: Vec<u64> = columns.map(str::parse).collect::<Result<_, _>>().ok()?;
+            if values.len() < 4 {
+                return None;
+            }
+            let value = |i: usize| values.get(i).copied().unwrap_or(0);
+            Some(InterfaceCounters {
+                interface: interface.to_string(),
+                rx_packets: value(0),
+                rx_bytes: value(1),
+                tx_packets: value(2),
+                tx_bytes: value(3),
+                rx_drops: value(4),
+                tx_drops: value(5),
+                rx_errors: value(6),
+                tx_errors: value(7),
+            })
+        })
+        .collect()
+}
+
+/// Network metrics of a node's interfaces, with rates since the previous
+/// collection
+fn network_metrics(
+    counters: Vec<InterfaceCounters>,
+    interfaces: &[VyOSInterface],
+    previous: Option<&SystemMetrics>,
+    now: DateTime<Utc>,
+) -> Vec<NetworkMetrics> {
+    let elapsed = previous.map_or(0.0, |previous| (now - previous.timestamp).num_milliseconds() as f64 / 1000.0);
+    counters
+        .into_iter()
+        .map(|counters| {
+            let interface = interfaces.iter().find(|interface| interface.name == counters.interface);
+            let before = previous
+                .filter(|_| elapsed > 0.0)
+                .and_then(|previous| previous.network.iter().find(|n| n.interface == counters.interface));
+            // Counters that went backwards were reset, so give no rate
+            let rate = |now: u64, then: fn(&NetworkMetrics) -> u64| {
+                before.map_or(0.0, |before| now.saturating_sub(then(before)) as f64 / elapsed)
+            };
+            let packets = counters.rx_packets + counters.tx_packets;
+
+            NetworkMetrics {
+                status: match interface {
+                    Some(interface) if interface.is_up => NetworkInterfaceStatus::Up,
+                    Some(_) => NetworkInterfaceStatus::Down,
+                    None => NetworkInterfaceStatus::Unknown,
+                },
+                rx_bps: rate(counters.rx_bytes, |n| n.rx_bytes) * 8.0,
+                tx_bps: rate(counters.tx_bytes, |n| n.tx_bytes) * 8.0,
+                avg_packet_size: (packets > 0).then(|| (counters.rx_bytes + counters.tx_bytes) as f64 / packets as f64),
+                link_speed_mbps: interface
+                    .and_then(|interface| interface.speed.as_deref())
+                    .and_then(|speed| speed.trim_end_matches("Mb/s").parse().ok()),
+                mac_address: interface.and_then(|interface| interface.mac_address.clone()),
+                ip_addresses: interface
+                    .map(|interface| interface.addresses.iter().filter_map(|address| ip_address(address)).collect())
+                    .unwrap_or_default(),
+                interface: counters.interface,
+                rx_bytes: counters.rx_bytes,
+                tx_bytes: counters.tx_bytes,
+                rx_packets: counters.rx_packets,
+                tx_packets: counters.tx_packets,
+                rx_errors: counters.rx_errors,
+                tx_errors: counters.tx_errors,
+                rx_drops: counters.rx_drops,
+                tx_drops: counters.tx_drops,
+            }
+        })
+        .collect()
+}
+
+/// Address information of an address in prefix notation
+fn ip_address(address: &str) -> Option<IpAddressInfo> {
+    let (address, prefix_length) = address.split_once('/')?;
+    Some(IpAddressInfo {
+        address: address.to_string(),
+        prefix_length: prefix_length.parse().ok()?,
+        ip_type: if address.contains(':') { IpType::IPv6 } else { IpType::IPv4 },
+    })
+}
+
+/// Headline values of collected metrics, for the metrics history
+///
+/// Interface error rates are only known from the second collection on.
+fn data_points(metrics: &SystemMetrics, previous: Option<&SystemMetrics>) -> Vec<MetricData> {
+    let point = |name: &str, metric_type: MetricType, value: f64, unit: MetricUnit, labels: Vec<MetricLabel>| {
+        MetricData {
+            id: Uuid::new_v4(),
+            node_id: metrics.node_id.clone(),
+            metric_name: name.to_string(),
+            metric_type,
+            value,
+            unit,
+            timestamp: metrics.timestamp,
+            labels,
+            metadata: None,
+        }
+    };
+    let label = |key: &str, value: &str| {
+        vec![MetricLabel {
+            key: key.to_string(),
+            value: value.to_string(),
+        }]
+    };
+
+    let mut points = vec![
+        point("cpu_usage_percent", MetricType::Cpu, metrics.cpu.usage_percent, MetricUnit::Percentage, Vec::new()),
+        point("load_average_1m", MetricType::Load, metrics.load_average[0], MetricUnit::Count, Vec::new()),
+    ];
+    if metrics.memory.total_bytes > 0 {
+        points.push(point(
+            "memory_usage_percent",
+            MetricType::Memory,
+            metrics.memory.usage_percent,
+            MetricUnit::Percentage,
+            Vec::new(),
+        ));
+    }
+    for disk in &metrics.disks {
+        points.push(point(
+            "disk_usage_percent",
+            MetricType::Disk,
+            disk.usage_percent,
+            MetricUnit::Percentage,
+            label("mount_point", &disk.mount_point),
+        ));
+    }
+    let elapsed = previous.map_or(0.0, |previous| {
+        (metrics.timestamp - previous.timestamp).num_milliseconds() as f64 / 1000.0
+    });
+    for network in &metrics.network {
+        let labels = label("interface", &network.interface);
+        points.push(point("interface_rx_bps", MetricType::Network, network.rx_bps, MetricUnit::BitsPerSecond, labels.clone()));
+        points.push(point("interface_tx_bps", MetricType::Network, network.tx_bps, MetricUnit::BitsPerSecond, labels.clone()));
+        if let Some(speed) = network.link_speed_mbps.filter(|&speed| speed > 0) {
+            let utilization = network.rx_bps.max(network.tx_bps) * 100.0 / (speed as f64 * 1_000_000.0);
+            points.push(point(
+                INTERFACE_UTILIZATION_METRIC,
+                MetricType::Interface,
+                utilization,
+                MetricUnit::Percentage,
+                labels.clone(),
+            ));
+        }
+
+        let before = previous
+            .filter(|_| elapsed > 0.0)
+            .and_then(|previous| previous.network.iter().find(|n| n.interface == network.interface));
+        if let Some(before) = before {
+            let errors = (network.rx_errors + network.tx_errors).saturating_sub(before.rx_errors + before.tx_errors);
+            points.push(point(
+                "interface_errors_per_second",
+                MetricType::Interface,
+                errors as f64 / elapsed,
+                MetricUnit::Count,
+                labels,
+            ));
+        }
+    }
+    points
+}
+
+/// Share of `part` in `total`, as a percentage
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_uptime_layouts() {
+        let current = parse_uptime("Uptime: 2d 3h 4m 5s\n\nLoad averages:\n1  minute:   12.5%\n5  minutes:  6.0%\n15 minutes:  3.1%\n");
+        assert_eq!(current.seconds, 2 * 86_400 + 3 * 3_600 + 4 * 60 + 5);
+        assert_eq!(current.load_average, [12.5, 6.0, 3.1]);
+        assert!(current.load_is_percent);
+
+        let classic = parse_uptime(" 10:12:53 up 2 days,  3:12,  1 user,  load average: 0.50, 0.25, 0.05");
+        assert_eq!(classic.seconds, 2 * 86_400 + 3 * 3_600 + 12 * 60);
+        assert_eq!(classic.load_average, [0.5, 0.25, 0.05]);
+        assert!(!classic.load_is_percent);
+        assert_eq!(cpu_metrics(&classic, 2).usage_percent, 25.0);
+    }
+
+    #[test]
+    fn test_parse_memory_and_storage() {
+        let memory = parse_memory("Total: 4.0G\nFree:  3.0G\nUsed:  1.0G\n");
+        assert_eq!(memory.total_bytes, 4 << 30);
+        assert_eq!(memory.used_bytes, 1 << 30);
+        assert_eq!(memory.usage_percent, 25.0);
+        assert_eq!(parse_memory("Total: 2000\nFree: 1500\n").used_bytes, 500 << 20);
+
+        let disks = parse_storage(
+            "Filesystem      Size  Used Avail Use% Mounted on\noverlay         8.0G  2.0G  6.0G  25% /\n",
+        );
+        assert_eq!(disks.len(), 1);
+        assert_eq!(disks[0].mount_point, "/");
+        assert_eq!(disks[0].total_bytes, 8 << 30);
+        assert_eq!(disks[0].usage_percent, 25.0);
+    }
+
+    #[test]
+    fn test_network_rates_come_from_consecutive_counters() {
+        let output = "Interface    Rx Packets    Rx Bytes    Tx Packets    Tx Bytes\n\
+                      -----------  ------------  ----------  ------------  ----------\n\
+                      eth0         100           10000       50            5000\n";
+        let counters = parse_counters(output);
+        assert_eq!(counters.len(), 1);
+        assert_eq!(counters[0].rx_bytes, 10_000);
+
+        let then = Utc::now();
+        let first = network_metrics(counters.clone(), &[], None, then);
+        assert_eq!(first[0].rx_bps, 0.0);
+        assert_eq!(first[0].status, NetworkInterfaceStatus::Unknown);
+
+        let previous = SystemMetrics {
+            node_id: "n".to_string(),
+            node_name: "n".to_string(),
+            timestamp: then,
+            cpu: cpu_metrics(&Uptime::default(), 1),
+            memory: parse_memory(""),
+            disks: Vec::new(),
+            network: first,
+            load_average: [0.0; 3],
+            uptime_seconds: 0,
+            process_count: 0,
+            system_time: then,
+        };
+        let mut later = counters;
+        later[0].rx_bytes += 1_000;
+        let second = network_metrics(later, &[], Some(&previous), then + chrono::Duration::seconds(10));
+        assert_eq!(second[0].rx_bps, 800.0);
+        assert_eq!(second[0].tx_bps, 0.0);
+    }
+}
@@ -3,21 +3,139 @@
 //! This module contains service layer components that handle business logic
 //! and interact with the data layer.
 
+pub mod announcement;
+pub mod api_token;
+pub mod api_usage;
+pub mod audit;
 pub mod auth;
+pub mod backend_health;
+pub mod backup;
+pub mod baseline;
+pub mod certificate;
+pub mod channel_access;
+pub mod chaos;
+pub mod circuit_breaker;
+pub mod client_errors;
 pub mod config;
+pub mod config_drift;
+pub mod config_lint;
+pub mod config_lock;
+pub mod config_secrets;
+pub mod config_session;
+pub mod config_template;
+pub mod config_tree;
+pub mod custom_field;
+pub mod desired_state;
+pub mod event_bus;
+pub mod firewall;
+pub mod firewall_log;
+pub mod geoip;
+pub mod git_export;
+pub mod ha;
+pub mod incident;
+pub mod ipam;
+pub mod ipv6;
+pub mod leader;
+pub mod load_profile;
+pub mod login_history;
+pub mod mac_vendor;
+pub mod mailer;
+pub mod metrics_collector;
 pub mod monitoring;
+pub mod node_files;
+pub mod node_health;
+pub mod node_service;
+pub mod notification;
+pub mod object_store;
+pub mod permission;
+pub mod quota;
+pub mod rate_limit;
+pub mod report;
+pub mod router_login;
+pub mod routing;
+pub mod show_cache;
+pub mod status_page;
+pub mod storage;
+pub mod storage_usage;
+pub mod subnet;
+pub mod support_bundle;
+pub mod syslog;
 pub mod system_service;
+pub mod team;
+pub mod terminal;
+pub mod topology;
+pub mod upload;
 pub mod user;
-// pub mod network;
-// pub mod node_service;
+pub mod vpn_mesh;
+pub mod wizard;
+pub mod network;
 // pub mod vyos_api;
 
 // Re-export services for convenience
+pub use announcement::*;
+pub use api_token::*;
+pub use api_usage::*;
+pub use audit::*;
 pub use auth::*;
+pub use backend_health::*;
+pub use backup::*;
+pub use baseline::*;
+pub use certificate::*;
+pub use channel_access::*;
+pub use chaos::*;
+pub use circuit_breaker::*;
+pub use client_errors::*;
 pub use config::*;
+pub use config_drift::*;
+pub use config_lint::*;
+pub use config_lock::*;
+pub use config_secrets::*;
+pub use config_session::*;
+pub use config_template::*;
+pub use config_tree::*;
+pub use custom_field::*;
+pub use desired_state::*;
+pub use event_bus::*;
+pub use firewall::*;
+pub use firewall_log::*;
+pub use geoip::*;
+pub use git_export::*;
+pub use ha::*;
+pub use incident::*;
+pub use ipam::*;
+pub use ipv6::*;
+pub use leader::*;
+pub use load_profile::*;
+pub use login_history::*;
+pub use mac_vendor::*;
+pub use mailer::*;
+pub use metrics_collector::*;
 pub use monitoring::*;
+pub use node_files::*;
+pub use node_health::*;
+pub use node_service::*;
+pub use notification::*;
+pub use object_store::*;
+pub use permission::*;
+pub use quota::*;
+pub use rate_limit::*;
+pub use report::*;
+pub use router_login::*;
+pub use routing::*;
+pub use show_cache::*;
+pub use status_page::*;
+pub use storage::*;
+pub use storage_usage::*;
+pub use subnet::*;
+pub use support_bundle::*;
+pub use syslog::*;
 pub use system_service::*;
+pub use team::*;
+pub use terminal::*;
+pub use topology::*;
+pub use upload::*;
 pub use user::*;
-// pub use network::*;
-// pub use node_service::*;
+pub use vpn_mesh::*;
+pub use wizard::*;
+pub use network::*;
 // pub use vyos_api::*;
\ No newline at end of file
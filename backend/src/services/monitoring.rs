@@ -1,17 +1,26 @@
 //! Monitoring service
 //!
 //! This module provides the business logic for monitoring system metrics,
-//! collecting historical data, and managing alert rules.
+//! collecting historical data, exporting it as CSV, and managing alert
+//! rules.
 
 use crate::config::AppConfig;
 use crate::error::AppError;
 use crate::models::monitoring::{
-    Alert, AlertOperator, AlertRule, AlertSeverity, AlertStatus, CpuMetrics,
-    DiskMetrics, MemoryMetrics, MetricsHistoryResponse, MetricsQuery, MetricsStatistics,
-    MetricType, NetworkMetrics, SystemMetrics,
+    Alert, AlertBundleInfo, AlertCondition, AlertOperator, AlertRule, AlertRuleBundle, AlertRuleTarget,
+    AlertRuleTargetsResponse, AlertSeverity, AlertStatus, ApplyAlertBundlesResponse,
+    CpuMetrics, DiskMetrics, MemoryMetrics, MetricLabel, MetricsHistoryResponse, MetricsQuery,
+    ConditionCombinator, InterfaceBandwidthAlertRequest, MetricData, MetricUnit, MetricsExportFormat,
+    MetricsExportQuery, MetricsStatistics, MetricType, NetworkMetrics, SortOrder, SystemMetrics, ThresholdOverride,
+    TopNodeAverage, TopNodeValue, TopNodesQuery, TopNodesResponse, INTERFACE_UTILIZATION_METRIC,
 };
-use chrono::Utc;
-use std::collections::HashMap;
+use crate::models::node::Node;
+use crate::models::time_range::{resolve_time_range, RelativeDuration};
+use crate::models::timestamp::format_timestamp;
+use crate::utils::csv::csv_line;
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info};
@@ -20,7 +29,7 @@ use uuid::Uuid;
 /// In-memory storage for monitoring data
 #[derive(Debug, Clone, Default)]
 struct MonitoringStore {
-    /// Historical metrics data
+    /// Historical metrics data, in time order
     metrics_history: Vec<crate::models::monitoring::MetricData>,
 
     /// Active alerts
@@ -29,6 +38,9 @@ struct MonitoringStore {
     /// Alert rules
     alert_rules: Vec<AlertRule>,
 
+    /// Default rule bundles applied to all nodes
+    applied_bundles: HashSet<AlertRuleBundle>,
+
     /// Last collected system metrics
     system_metrics: HashMap<String, SystemMetrics>,
 }
@@ -163,12 +175,60 @@ impl MonitoringService {
         }
     }
 
+    /// Get the system metrics last collected from a node, if any
+    pub async fn last_system_metrics(&self, node_id: &str) -> Option<SystemMetrics> {
+        self.store.read().await.system_metrics.get(node_id).cloned()
+    }
+
+    /// Store the system metrics collected from a node, replacing the
+    /// previous ones
+    pub async fn record_system_metrics(&self, metrics: SystemMetrics) {
+        self.store
+            .write()
+            .await
+            .system_metrics
+            .insert(metrics.node_id.clone(), metrics);
+    }
+
+    /// Add a data point to the metrics history
+    pub async fn record_metric(&self, metric: crate::models::monitoring::MetricData) {
+        let mut store = self.store.write().await;
+        let index = store.metrics_history.partition_point(|point| point.timestamp <= metric.timestamp);
+        store.metrics_history.insert(index, metric);
+    }
+
+    /// Drop historical metrics older than each node's retention cutoff
+    ///
+    /// Nodes without a cutoff keep all their metrics. Returns the number of
+    /// removed data points.
+    pub async fn prune_metrics_history(
+        &self,
+        cutoffs: &HashMap<String, DateTime<Utc>>,
+    ) -> usize {
+        let mut store = self.store.write().await;
+        let before = store.metrics_history.len();
+
+        store.metrics_history.retain(|metric| {
+            cutoffs
+                .get(&metric.node_id)
+                .is_none_or(|cutoff| metric.timestamp >= *cutoff)
+        });
+
+        let removed = before - store.metrics_history.len();
+        if removed > 0 {
+            info!("Pruned {} metric data points past their retention period", removed);
+        }
+        removed
+    }
+
     /// Get historical monitoring data
     pub async fn get_metrics_history(
         &self,
         query: &MetricsQuery,
     ) -> Result<MetricsHistoryResponse, AppError> {
         debug!("Fetching metrics history with query: {:?}", query);
+        let (mut start_time, mut end_time) = (query.start_time, query.end_time);
+        resolve_time_range(&mut start_time, &mut end_time, query.window, query.preset)?;
 
         let store = self.store.read().await;
 
@@ -198,13 +258,13 @@ impl MonitoringService {
                 }
 
                 // Filter by time range
-                if let Some(start_time) = query.start_time {
+                if let Some(start_time) = start_time {
                     if metric.timestamp < start_time {
                         return false;
                     }
                 }
 
-                if let Some(end_time) = query.end_time {
+                if let Some(end_time) = end_time {
                     if metric.timestamp > end_time {
                         return false;
                     }
@@ -232,54 +292,8 @@ impl MonitoringService {
 
         let total_count = data.len();
 
-        // Calculate statistics if we have data
-        let statistics = if data.is_empty() {
-            None
-        } else {
-            let values: Vec<f64> = data.iter().map(|m| m.value).collect();
-            let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
-            let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-            let sum: f64 = values.iter().sum();
-            let avg = sum / values.len() as f64;
-
-            // Calculate median
-            let mut sorted_values = values.clone();
-            sorted_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-            let len = sorted_values.len();
-            let median = if len % 2 == 0 {
-                (sorted_values[len / 2 - 1] + sorted_values[len / 2]) / 2.0
-            } else {
-                sorted_values[len / 2]
-            };
-
-            // Calculate percentiles
-            let percentile = |p: f64| -> f64 {
-                let idx = ((values.len() - 1) as f64 * p / 100.0) as usize;
-                sorted_values[idx]
-            };
-
-            Some(MetricsStatistics {
-                min,
-                max,
-                avg,
-                median,
-                std_dev: {
-                    let variance: f64 = values
-                        .iter()
-                        .map(|&x| (x - avg).powi(2))
-                        .sum::<f64>()
-                        / values.len() as f64;
-                    Some(variance.sqrt())
-                },
-                percentiles: crate::models::monitoring::Percentiles {
-                    p50: percentile(50.0),
-                    p75: percentile(75.0),
-                    p90: percentile(90.0),
-                    p95: percentile(95.0),
-                    p99: percentile(99.0),
-                },
-            })
-        };
+        let values: Vec<f64> = data.iter().map(|m| m.value).collect();
+        let statistics = MetricsStatistics::from_values(&values);
 
         Ok(MetricsHistoryResponse {
             query: query.clone(),
@@ -289,6 +303,145 @@ impl MonitoringService {
         })
     }
 
+    /// Stream the selected metrics of nodes as CSV
+    ///
+    /// `nodes` maps the IDs of the nodes to export to their names. Each chunk
+    /// reads the next batch of data points from the history, picking up after
+    /// the last exported timestamp, so neither the CSV nor the selection is
+    /// held in memory whole and metric writers wait only for one batch; each
+    /// chunk ends with all points of its last timestamp. Points recorded
+    /// during the export are included if they are newer than that. The first
+    /// chunk is the header; in the wide format it has a column per metric and
+    /// label set found in the range.
+    pub fn export_metrics(
+        &self,
+        mut query: MetricsExportQuery,
+        nodes: HashMap<String, String>,
+    ) -> Result<impl Stream<Item = Result<String, AppError>>, AppError> {
+        let metrics: HashSet<String> = query
+            .metrics
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+        if metrics.is_empty() || metrics.len() > MAX_EXPORT_METRICS {
+            return Err(AppError::Validation(format!(
+                "Select between 1 and {} metrics to export",
+                MAX_EXPORT_METRICS
+            )));
+        }
+        resolve_time_range(&mut query.start_time, &mut query.end_time, query.window, query.preset)?;
+
+        let export = MetricsExport {
+            store: self.store.clone(),
+            nodes,
+            metrics,
+            start: query.start_time,
+            end: query.end_time,
+            format: query.format,
+            after: None,
+            columns: None,
+        };
+        Ok(stream::unfold(Some(export), |export| async move {
+            let mut export = export?;
+            let chunk = export.next_chunk().await?;
+            Some((Ok(chunk), Some(export)))
+        }))
+    }
+
+    /// Rank nodes by a metric, worst first, by their latest value and by
+    /// their average over a window
+    ///
+    /// `nodes` maps the IDs of the nodes to rank to their names. A latest
+    /// value is current only if it is at most a few collection intervals old,
+    /// or, while metrics are not collected, if it falls in the window; nodes
+    /// that stopped reporting are left out of the current ranking. The
+    /// history is scanned once, keeping a running latest value and sum per
+    /// node, and only the worst `n` nodes are sorted.
+    pub async fn top_nodes(
+        &self,
+        mut query: TopNodesQuery,
+        nodes: &HashMap<String, String>,
+    ) -> Result<TopNodesResponse, AppError> {
+        if query.n == 0 || query.n > MAX_TOP_NODES {
+            return Err(AppError::Validation(format!(
+                "The number of nodes must be between 1 and {}",
+                MAX_TOP_NODES
+            )));
+        }
+        if query.start_time.is_none() && query.end_time.is_none() && query.preset.is_none() {
+            query.window.get_or_insert(RelativeDuration(chrono::Duration::hours(DEFAULT_TOP_WINDOW_HOURS)));
+        }
+        resolve_time_range(&mut query.start_time, &mut query.end_time, query.window, query.preset)?;
+
+        let descending = query.order == SortOrder::Desc;
+        let worse = |a: f64, b: f64| if descending { a > b } else { a < b };
+        let in_window = |timestamp: DateTime<Utc>| {
+            query.start_time.is_none_or(|start| timestamp >= start) && query.end_time.is_none_or(|end| timestamp <= end)
+        };
+        let interval = self.config.metrics_collection_interval_secs;
+        let current_since = if interval > 0 {
+            Some(Utc::now() - chrono::Duration::seconds((interval * CURRENT_VALUE_INTERVALS) as i64))
+        } else {
+            query.start_time
+        };
+
+        let store = self.store.read().await;
+        let mut latest: HashMap<&str, (DateTime<Utc>, f64)> = HashMap::new();
+        let mut windowed: HashMap<&str, (f64, f64, usize)> = HashMap::new();
+        for metric in store
+            .metrics_history
+            .iter()
+            .filter(|metric| metric.metric_name == query.metric && nodes.contains_key(&metric.node_id))
+        {
+            let (timestamp, value) = latest.entry(&metric.node_id).or_insert((metric.timestamp, metric.value));
+            if metric.timestamp > *timestamp || (metric.timestamp == *timestamp && worse(metric.value, *value)) {
+                *timestamp = metric.timestamp;
+                *value = metric.value;
+            }
+
+            if in_window(metric.timestamp) {
+                let (sum, peak, samples) = windowed.entry(&metric.node_id).or_insert((0.0, metric.value, 0));
+                *sum += metric.value;
+                if worse(metric.value, *peak) {
+                    *peak = metric.value;
+                }
+                *samples += 1;
+            }
+        }
+
+        let current = latest
+            .into_iter()
+            .filter(|(_, (timestamp, _))| current_since.is_none_or(|since| *timestamp >= since))
+            .map(|(node_id, (timestamp, value))| TopNodeValue {
+                node_id: node_id.to_string(),
+                node_name: nodes[node_id].clone(),
+                value,
+                timestamp,
+            })
+            .collect();
+        let window = windowed
+            .into_iter()
+            .map(|(node_id, (sum, peak, samples))| TopNodeAverage {
+                node_id: node_id.to_string(),
+                node_name: nodes[node_id].clone(),
+                average: sum / samples as f64,
+                peak,
+                samples,
+            })
+            .collect();
+
+        Ok(TopNodesResponse {
+            current: worst_first(current, query.n, descending, |node| node.value),
+            window: worst_first(window, query.n, descending, |node| node.average),
+            metric: query.metric,
+            order: query.order,
+            start_time: query.start_time,
+            end_time: query.end_time,
+        })
+    }
+
     /// Get all alerts
     pub async fn get_alerts(
         &self,
@@ -345,14 +498,53 @@ impl MonitoringService {
             .ok_or_else(|| AppError::NotFound(format!("Alert {} not found", id)))
     }
 
+    /// Record a triggered alert
+    pub async fn raise_alert(&self, alert: Alert) -> Alert {
+        info!("Alert raised on node {}: {}", alert.node_id, alert.title);
+
+        let mut store = self.store.write().await;
+        store.alerts.push(alert.clone());
+        alert
+    }
+
+    /// Mark an alert as resolved
+    pub async fn resolve_alert(&self, id: &Uuid) -> Result<Alert, AppError> {
+        let mut store = self.store.write().await;
+        let alert = store
+            .alerts
+            .iter_mut()
+            .find(|a| &a.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Alert {} not found", id)))?;
+
+        let now = Utc::now();
+        alert.status = AlertStatus::Resolved;
+        alert.resolved_at = Some(now);
+        alert.updated_at = now;
+
+        info!("Alert resolved on node {}: {}", alert.node_id, alert.title);
+        Ok(alert.clone())
+    }
+
     /// Create a new alert rule
     pub async fn create_alert_rule(
         &self,
         rule: AlertRuleCreate,
+    ) -> Result<AlertRule, AppError> {
+        self.create_alert_rule_with_id(Uuid::new_v4(), rule).await
+    }
+
+    /// Create a new alert rule with a caller-chosen ID
+    ///
+    /// Used for fixtures that need stable rule IDs.
+    pub async fn create_alert_rule_with_id(
+        &self,
+        id: Uuid,
+        rule: AlertRuleCreate,
     ) -> Result<AlertRule, AppError> {
         info!("Creating alert rule: {}", rule.name);
+        validate_overrides(&rule.threshold_overrides)?;
+        validate_conditions(&rule.conditions)?;
 
-        let id = Uuid::new_v4();
         let now = Utc::now();
 
         let alert_rule = AlertRule {
@@ -367,6 +559,10 @@ impl MonitoringService {
             for_seconds: rule.for_seconds,
             enabled: true,
             labels: rule.labels,
+            node_tag: rule.node_tag.filter(|tag| !tag.is_empty()),
+            threshold_overrides: rule.threshold_overrides,
+            conditions: rule.conditions,
+            combine: rule.combine,
             created_at: now,
             updated_at: now,
         };
@@ -385,6 +581,12 @@ impl MonitoringService {
         rule: AlertRuleUpdate,
     ) -> Result<AlertRule, AppError> {
         info!("Updating alert rule: {}", id);
+        if let Some(overrides) = &rule.threshold_overrides {
+            validate_overrides(overrides)?;
+        }
+        if let Some(conditions) = &rule.conditions {
+            validate_conditions(conditions)?;
+        }
 
         let mut store = self.store.write().await;
 
@@ -418,6 +620,18 @@ impl MonitoringService {
         if let Some(labels) = rule.labels {
             alert_rule.labels = labels;
         }
+        if let Some(node_tag) = rule.node_tag {
+            alert_rule.node_tag = Some(node_tag).filter(|tag| !tag.is_empty());
+        }
+        if let Some(threshold_overrides) = rule.threshold_overrides {
+            alert_rule.threshold_overrides = threshold_overrides;
+        }
+        if let Some(conditions) = rule.conditions {
+            alert_rule.conditions = conditions;
+        }
+        if let Some(combine) = rule.combine {
+            alert_rule.combine = combine;
+        }
 
         alert_rule.updated_at = Utc::now();
 
@@ -449,6 +663,543 @@ impl MonitoringService {
         let store = self.store.read().await;
         Ok(store.alert_rules.clone())
     }
+
+    /// Expand an alert rule to the nodes it is evaluated on
+    ///
+    /// Targets are resolved against the given nodes on every call, so rules
+    /// follow nodes being added, removed or retagged.
+    pub async fn expand_alert_rule(
+        &self,
+        id: &Uuid,
+        nodes: &[Node],
+    ) -> Result<AlertRuleTargetsResponse, AppError> {
+        let store = self.store.read().await;
+        let rule = store
+            .alert_rules
+            .iter()
+            .find(|r| &r.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Alert rule {} not found", id)))?;
+
+        let targets = nodes
+            .iter()
+            .filter(|node| rule.applies_to(node.id, &node.tags))
+            .map(|node| AlertRuleTarget {
+                node_id: node.id,
+                node_name: node.name.clone(),
+                threshold: rule.threshold_for(node.id),
+                overridden: rule.threshold_overrides.iter().any(|o| o.node_id == node.id),
+            })
+            .collect();
+
+        Ok(AlertRuleTargetsResponse { rule_id: rule.id, targets })
+    }
+
+    /// List the default rule bundles and where they are applied
+    pub async fn get_alert_bundles(&self) -> Vec<AlertBundleInfo> {
+        let store = self.store.read().await;
+
+        AlertRuleBundle::ALL
+            .iter()
+            .map(|&bundle| {
+                let template = bundle_template(bundle);
+                AlertBundleInfo {
+                    bundle,
+                    name: template.name,
+                    description: template.description.unwrap_or_default(),
+                    metric_name: template.metric_name,
+                    metric_type: template.metric_type,
+                    threshold: template.threshold,
+                    operator: template.operator,
+                    severity: template.severity,
+                    applied: store.applied_bundles.contains(&bundle),
+                    rule_count: store
+                        .alert_rules
+                        .iter()
+                        .filter(|rule| rule_bundle(rule) == Some(bundle))
+                        .count(),
+                }
+            })
+            .collect()
+    }
+
+    /// Apply default rule bundles to the given nodes
+    ///
+    /// The bundles stay applied, so nodes registered later receive their rules
+    /// through [`MonitoringService::sync_node_alert_bundles`]. Rules that
+    /// already exist for a node are left untouched, including any edits.
+    pub async fn apply_alert_bundles(
+        &self,
+        bundles: &[AlertRuleBundle],
+        node_ids: &[Uuid],
+    ) -> ApplyAlertBundlesResponse {
+        let mut store = self.store.write().await;
+        let mut created = 0;
+
+        for &bundle in bundles {
+            store.applied_bundles.insert(bundle);
+            for node_id in node_ids {
+                if add_bundle_rule(&mut store, bundle, node_id) {
+                    created += 1;
+                }
+            }
+        }
+
+        info!(
+            "Applied alert bundles {:?} to {} nodes, {} rules created",
+            bundles,
+            node_ids.len(),
+            created
+        );
+
+        ApplyAlertBundlesResponse {
+            bundles: bundles.to_vec(),
+            nodes: node_ids.len(),
+            created,
+        }
+    }
+
+    /// Stop applying a default rule bundle and delete the rules created from it
+    ///
+    /// Returns the number of deleted rules.
+    pub async fn remove_alert_bundle(&self, bundle: AlertRuleBundle) -> usize {
+        let mut store = self.store.write().await;
+        store.applied_bundles.remove(&bundle);
+
+        let initial_len = store.alert_rules.len();
+        store.alert_rules.retain(|rule| rule_bundle(rule) != Some(bundle));
+        let removed = initial_len - store.alert_rules.len();
+
+        info!("Removed alert bundle {}, {} rules deleted", bundle.as_str(), removed);
+        removed
+    }
+
+    /// Create the rules of all applied bundles for a newly registered node
+    ///
+    /// Returns the number of created rules.
+    pub async fn sync_node_alert_bundles(&self, node_id: &Uuid) -> usize {
+        let mut store = self.store.write().await;
+        let bundles: Vec<AlertRuleBundle> = store.applied_bundles.iter().copied().collect();
+
+        let created = bundles
+            .into_iter()
+            .filter(|&bundle| add_bundle_rule(&mut store, bundle, node_id))
+            .count();
+
+        if created > 0 {
+            info!("Created {} bundled alert rules for node {}", created, node_id);
+        }
+        created
+    }
+
+    /// Delete the bundled and interface bandwidth rules of a removed node
+    pub async fn remove_node_alert_bundles(&self, node_id: &Uuid) {
+        let node_id = node_id.to_string();
+        let mut store = self.store.write().await;
+        store.alert_rules.retain(|rule| {
+            (rule_bundle(rule).is_none() && rule_label(rule, PRESET_LABEL).is_none())
+                || rule_label(rule, BUNDLE_NODE_LABEL) != Some(node_id.as_str())
+        });
+    }
+
+    /// Create the rule alerting when an interface of a node stays busy,
+    /// replacing the one created for the interface before
+    ///
+    /// The rule watches the interface's utilization of `link_speed_mbps`,
+    /// which is kept in its labels and description.
+    pub async fn create_bandwidth_alert(
+        &self,
+        node_id: Uuid,
+        interface: &str,
+        link_speed_mbps: u64,
+        request: InterfaceBandwidthAlertRequest,
+    ) -> Result<AlertRule, AppError> {
+        info!("Creating bandwidth alert rule for {} on node {}", interface, node_id);
+        let label = |key: &str, value: String| MetricLabel {
+            key: key.to_string(),
+            value,
+        };
+        let now = Utc::now();
+        let mut rule = AlertRule {
+            id: Uuid::new_v4(),
+            name: format!("{} bandwidth above {}%", interface, request.threshold_percent),
+            description: Some(format!(
+                "Traffic on {} above {}% of its {} Mb/s link for {} minutes",
+                interface, request.threshold_percent, link_speed_mbps, request.for_minutes
+            )),
+            metric_name: INTERFACE_UTILIZATION_METRIC.to_string(),
+            metric_type: MetricType::Interface,
+            threshold: request.threshold_percent,
+            operator: AlertOperator::GreaterThan,
+            severity: request.severity,
+            for_seconds: request.for_minutes * 60,
+            enabled: true,
+            labels: vec![
+                label(PRESET_LABEL, INTERFACE_BANDWIDTH_PRESET.to_string()),
+                label(BUNDLE_NODE_LABEL, node_id.to_string()),
+                label("interface", interface.to_string()),
+                label("link_speed_mbps", link_speed_mbps.to_string()),
+            ],
+            node_tag: None,
+            threshold_overrides: vec![],
+            conditions: vec![],
+            combine: ConditionCombinator::And,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let node_id = node_id.to_string();
+        let mut store = self.store.write().await;
+        let existing = store.alert_rules.iter_mut().find(|existing| {
+            rule_label(existing, PRESET_LABEL) == Some(INTERFACE_BANDWIDTH_PRESET)
+                && rule_label(existing, BUNDLE_NODE_LABEL) == Some(node_id.as_str())
+                && rule_label(existing, "interface") == Some(interface)
+        });
+        match existing {
+            Some(existing) => {
+                rule.id = existing.id;
+                rule.created_at = existing.created_at;
+                *existing = rule.clone();
+            }
+            None => store.alert_rules.push(rule.clone()),
+        }
+        Ok(rule)
+    }
+}
+
+/// Data points read from the metrics history per chunk of an export
+const EXPORT_BATCH_SIZE: usize = 1000;
+
+/// Maximum number of metrics one export can select
+const MAX_EXPORT_METRICS: usize = 50;
+
+/// Maximum number of nodes a top-N query can return
+const MAX_TOP_NODES: usize = 100;
+
+/// Window a top-N query averages over when it gives no time range
+const DEFAULT_TOP_WINDOW_HOURS: i64 = 1;
+
+/// Collection intervals a node's latest data point counts as its current
+/// value for, allowing for one missed collection
+const CURRENT_VALUE_INTERVALS: u64 = 2;
+
+/// The `n` items with the worst values, worst first
+fn worst_first<T>(mut items: Vec<T>, n: usize, descending: bool, value: impl Fn(&T) -> f64) -> Vec<T> {
+    let compare = |a: &T, b: &T| {
+        let ordering = value(a).total_cmp(&value(b));
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    };
+    if items.len() > n {
+        items.select_nth_unstable_by(n - 1, compare);
+        items.truncate(n);
+    }
+    items.sort_by(compare);
+    items
+}
+
+/// Progress of a CSV export of the metrics history
+struct MetricsExport {
+    store: Arc<RwLock<MonitoringStore>>,
+    nodes: HashMap<String, String>,
+    metrics: HashSet<String>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    format: MetricsExportFormat,
+    /// Timestamp of the last exported data points
+    after: Option<DateTime<Utc>>,
+    /// Series of the wide format's columns, once the header is written
+    columns: Option<Vec<String>>,
+}
+
+impl MetricsExport {
+    fn selects(&self, metric: &MetricData) -> bool {
+        self.nodes.contains_key(&metric.node_id)
+            && self.metrics.contains(&metric.metric_name)
+            && self.start.is_none_or(|start| metric.timestamp >= start)
+            && self.end.is_none_or(|end| metric.timestamp <= end)
+    }
+
+    /// Data points of the time-ordered history still to be exported, up to
+    /// the end of the range
+    fn remaining<'a>(&self, history: &'a [MetricData]) -> impl Iterator<Item = &'a MetricData> {
+        let first = match (self.after, self.start) {
+            (Some(after), _) => history.partition_point(|metric| metric.timestamp <= after),
+            (None, Some(start)) => history.partition_point(|metric| metric.timestamp < start),
+            (None, None) => 0,
+        };
+        let end = self.end;
+        history[first..].iter().take_while(move |metric| end.is_none_or(|end| metric.timestamp <= end))
+    }
+
+    /// Next chunk of CSV lines, or `None` once all data points are exported
+    async fn next_chunk(&mut self) -> Option<String> {
+        let Some(columns) = &self.columns else {
+            let columns: Vec<String> = match self.format {
+                MetricsExportFormat::Long => Vec::new(),
+                MetricsExportFormat::Wide => {
+                    let store = self.store.read().await;
+                    let series: BTreeSet<String> = self
+                        .remaining(&store.metrics_history)
+                        .filter(|metric| self.selects(metric))
+                        .map(series_name)
+                        .collect();
+                    series.into_iter().collect()
+                }
+            };
+            let mut header: Vec<String> = ["timestamp", "node_id", "node_name"].map(str::to_string).to_vec();
+            match self.format {
+                MetricsExportFormat::Long => {
+                    header.extend(["metric_name", "labels", "value", "unit"].map(str::to_string))
+                }
+                MetricsExportFormat::Wide => header.extend(columns.iter().cloned()),
+            }
+            self.columns = Some(columns);
+            return Some(csv_line(&header));
+        };
+
+        let mut batch: Vec<MetricData> = Vec::new();
+        {
+            let store = self.store.read().await;
+            for metric in self.remaining(&store.metrics_history) {
+                let full = batch.len() >= EXPORT_BATCH_SIZE;
+                if full && batch.last().is_some_and(|last| last.timestamp < metric.timestamp) {
+                    break;
+                }
+                if self.selects(metric) {
+                    batch.push(metric.clone());
+                }
+            }
+        }
+        let last = batch.last()?.timestamp;
+        batch.sort_by(|a, b| {
+            (a.timestamp, &a.node_id, &a.metric_name).cmp(&(b.timestamp, &b.node_id, &b.metric_name))
+        });
+
+        let mut chunk = String::new();
+        let node_name = |metric: &MetricData| self.nodes.get(&metric.node_id).cloned().unwrap_or_default();
+        match self.format {
+            MetricsExportFormat::Long => {
+                for metric in &batch {
+                    chunk.push_str(&csv_line(&[
+                        format_timestamp(&metric.timestamp),
+                        metric.node_id.clone(),
+                        node_name(metric),
+                        metric.metric_name.clone(),
+                        sorted_labels(metric).join(";"),
+                        metric.value.to_string(),
+                        unit_name(&metric.unit),
+                    ]));
+                }
+            }
+            MetricsExportFormat::Wide => {
+                for row in batch.chunk_by(|a, b| a.timestamp == b.timestamp && a.node_id == b.node_id) {
+                    let values: HashMap<String, f64> =
+                        row.iter().map(|metric| (series_name(metric), metric.value)).collect();
+                    let first = &row[0];
+                    let mut record = vec![format_timestamp(&first.timestamp), first.node_id.clone(), node_name(first)];
+                    record.extend(
+                        columns.iter().map(|column| values.get(column).map(f64::to_string).unwrap_or_default()),
+                    );
+                    chunk.push_str(&csv_line(&record));
+                }
+            }
+        }
+        self.after = Some(last);
+        Some(chunk)
+    }
+}
+
+/// Labels of a data point as `key=value`, ordered by key
+fn sorted_labels(metric: &MetricData) -> Vec<String> {
+    let mut labels: Vec<String> = metric.labels.iter().map(|label| format!("{}={}", label.key, label.value)).collect();
+    labels.sort();
+    labels
+}
+
+/// Name of the series of a data point: its metric with its labels, e.g.
+/// `interface_rx_bps{interface=eth0}`
+fn series_name(metric: &MetricData) -> String {
+    let labels = sorted_labels(metric);
+    if labels.is_empty() {
+        metric.metric_name.clone()
+    } else {
+        format!("{}{{{}}}", metric.metric_name, labels.join(","))
+    }
+}
+
+fn unit_name(unit: &MetricUnit) -> String {
+    match unit {
+        MetricUnit::Custom(name) => name.clone(),
+        unit => serde_json::to_value(unit)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default(),
+    }
+}
+
+/// Ensure threshold overrides are usable and name each node once
+fn validate_overrides(overrides: &[ThresholdOverride]) -> Result<(), AppError> {
+    let mut nodes = HashSet::new();
+    for o in overrides {
+        if !o.threshold.is_finite() {
+            return Err(AppError::Validation(format!(
+                "Threshold override for node {} must be a number",
+                o.node_id
+            )));
+        }
+        if !nodes.insert(o.node_id) {
+            return Err(AppError::Validation(format!(
+                "Node {} has more than one threshold override",
+                o.node_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Ensure additional rule conditions are usable
+fn validate_conditions(conditions: &[AlertCondition]) -> Result<(), AppError> {
+    if conditions.len() > MAX_EXTRA_CONDITIONS {
+        return Err(AppError::Validation(format!(
+            "An alert rule can have at most {} additional conditions",
+            MAX_EXTRA_CONDITIONS
+        )));
+    }
+    if let Some(c) = conditions.iter().find(|c| c.metric_name.is_empty() || !c.threshold.is_finite()) {
+        return Err(AppError::Validation(format!(
+            "Condition on '{}' needs a metric name and a numeric threshold",
+            c.metric_name
+        )));
+    }
+    Ok(())
+}
+
+/// Maximum number of conditions a rule can have besides its own metric
+const MAX_EXTRA_CONDITIONS: usize = 8;
+
+/// Rule label holding the bundle a rule was created from
+const BUNDLE_LABEL: &str = "bundle";
+
+/// Rule label holding the node a bundled rule watches
+const BUNDLE_NODE_LABEL: &str = "node_id";
+
+/// Rule label holding the preset a rule was created from
+const PRESET_LABEL: &str = "preset";
+
+/// Preset of the rules alerting on the utilization of an interface
+const INTERFACE_BANDWIDTH_PRESET: &str = "interface_bandwidth";
+
+/// Rule created for every node by a default bundle
+fn bundle_template(bundle: AlertRuleBundle) -> AlertRuleCreate {
+    let (name, description, metric_name, metric_type, threshold, severity) = match bundle {
+        AlertRuleBundle::Cpu => (
+            "High CPU usage",
+            "CPU usage above 90% for 5 minutes",
+            "cpu_usage_percent",
+            MetricType::Cpu,
+            90.0,
+            AlertSeverity::Warning,
+        ),
+        AlertRuleBundle::Disk => (
+            "Disk almost full",
+            "Disk usage above 85% for 5 minutes",
+            "disk_usage_percent",
+            MetricType::Disk,
+            85.0,
+            AlertSeverity::Warning,
+        ),
+        AlertRuleBundle::Memory => (
+            "High memory usage",
+            "Memory usage above 90% for 5 minutes",
+            "memory_usage_percent",
+            MetricType::Memory,
+            90.0,
+            AlertSeverity::Warning,
+        ),
+        AlertRuleBundle::InterfaceErrors => (
+            "Interface errors rising",
+            "Interface error counters increasing for 5 minutes",
+            "interface_errors_per_second",
+            MetricType::Interface,
+            0.0,
+            AlertSeverity::Warning,
+        ),
+    };
+
+    AlertRuleCreate {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        metric_name: metric_name.to_string(),
+        metric_type,
+        threshold,
+        operator: AlertOperator::GreaterThan,
+        severity,
+        for_seconds: 300,
+        labels: vec![],
+        node_tag: None,
+        threshold_overrides: vec![],
+        conditions: vec![],
+        combine: ConditionCombinator::And,
+    }
+}
+
+fn rule_label<'a>(rule: &'a AlertRule, key: &str) -> Option<&'a str> {
+    rule.labels
+        .iter()
+        .find(|label| label.key == key)
+        .map(|label| label.value.as_str())
+}
+
+/// Bundle a rule was created from, if any
+fn rule_bundle(rule: &AlertRule) -> Option<AlertRuleBundle> {
+    let value = rule_label(rule, BUNDLE_LABEL)?;
+    AlertRuleBundle::ALL.into_iter().find(|bundle| bundle.as_str() == value)
+}
+
+/// Add a bundle's rule for a node unless it already exists
+fn add_bundle_rule(store: &mut MonitoringStore, bundle: AlertRuleBundle, node_id: &Uuid) -> bool {
+    let node_id = node_id.to_string();
+    let exists = store.alert_rules.iter().any(|rule| {
+        rule_bundle(rule) == Some(bundle) && rule_label(rule, BUNDLE_NODE_LABEL) == Some(node_id.as_str())
+    });
+    if exists {
+        return false;
+    }
+
+    let template = bundle_template(bundle);
+    let now = Utc::now();
+    store.alert_rules.push(AlertRule {
+        id: Uuid::new_v4(),
+        name: template.name,
+        description: template.description,
+        metric_name: template.metric_name,
+        metric_type: template.metric_type,
+        threshold: template.threshold,
+        operator: template.operator,
+        severity: template.severity,
+        for_seconds: template.for_seconds,
+        enabled: true,
+        labels: vec![
+            MetricLabel {
+                key: BUNDLE_LABEL.to_string(),
+                value: bundle.as_str().to_string(),
+            },
+            MetricLabel {
+                key: BUNDLE_NODE_LABEL.to_string(),
+                value: node_id,
+            },
+        ],
+        node_tag: None,
+        threshold_overrides: vec![],
+        conditions: vec![],
+        combine: ConditionCombinator::And,
+        created_at: now,
+        updated_at: now,
+    });
+    true
 }
 
 /// Request to create an alert rule
@@ -463,6 +1214,12 @@ pub struct AlertRuleCreate {
     pub severity: AlertSeverity,
     pub for_seconds: u32,
     pub labels: Vec<crate::models::monitoring::MetricLabel>,
+    /// Only evaluate the rule on nodes carrying this tag
+    pub node_tag: Option<String>,
+    pub threshold_overrides: Vec<ThresholdOverride>,
+    /// Further conditions checked together with `metric_name`
+    pub conditions: Vec<AlertCondition>,
+    pub combine: ConditionCombinator,
 }
 
 /// Request to update an alert rule
@@ -476,6 +1233,11 @@ pub struct AlertRuleUpdate {
     pub for_seconds: Option<u32>,
     pub enabled: Option<bool>,
     pub labels: Option<Vec<crate::models::monitoring::MetricLabel>>,
+    /// New tag to restrict the rule to; an empty tag targets all nodes again
+    pub node_tag: Option<String>,
+    pub threshold_overrides: Option<Vec<ThresholdOverride>>,
+    pub conditions: Option<Vec<AlertCondition>>,
+    pub combine: Option<ConditionCombinator>,
 }
 
 #[cfg(test)]
@@ -489,6 +1251,40 @@ mod tests {
         assert_eq!(service.config.server_host, "127.0.0.1");
     }
 
+    #[tokio::test]
+    async fn test_prune_metrics_history() {
+        let config = AppConfig::from_env().unwrap();
+        let service = MonitoringService::new(config);
+        let now = Utc::now();
+
+        let metric = |node_id: &str, age_days: i64| crate::models::monitoring::MetricData {
+            id: Uuid::new_v4(),
+            node_id: node_id.to_string(),
+            metric_name: "cpu_usage".to_string(),
+            metric_type: MetricType::Cpu,
+            value: 10.0,
+            unit: crate::models::monitoring::MetricUnit::Percentage,
+            timestamp: now - chrono::Duration::days(age_days),
+            labels: vec![],
+            metadata: None,
+        };
+
+        {
+            let mut store = service.store.write().await;
+            store.metrics_history = vec![
+                metric("node-a", 10),
+                metric("node-a", 1),
+                metric("node-b", 10),
+            ];
+        }
+
+        let mut cutoffs = HashMap::new();
+        cutoffs.insert("node-a".to_string(), now - chrono::Duration::days(7));
+
+        assert_eq!(service.prune_metrics_history(&cutoffs).await, 1);
+        assert_eq!(service.store.read().await.metrics_history.len(), 2);
+    }
+
     #[test]
     fn test_alert_rule_create() {
         let rule = AlertRuleCreate {
@@ -501,9 +1297,228 @@ mod tests {
             severity: AlertSeverity::Critical,
             for_seconds: 300,
             labels: vec![],
+            node_tag: None,
+            threshold_overrides: vec![],
+            conditions: vec![],
+            combine: ConditionCombinator::And,
         };
 
         assert_eq!(rule.name, "High CPU");
         assert_eq!(rule.severity, AlertSeverity::Critical);
     }
+
+    #[tokio::test]
+    async fn test_alert_bundles_follow_nodes() {
+        let config = AppConfig::from_env().unwrap();
+        let service = MonitoringService::new(config);
+        let (node_a, node_b) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let applied = service
+            .apply_alert_bundles(&[AlertRuleBundle::Cpu, AlertRuleBundle::Disk], &[node_a])
+            .await;
+        assert_eq!(applied.created, 2);
+
+        // Applying again keeps the existing rules
+        let applied = service.apply_alert_bundles(&[AlertRuleBundle::Cpu], &[node_a]).await;
+        assert_eq!(applied.created, 0);
+
+        assert_eq!(service.sync_node_alert_bundles(&node_b).await, 2);
+        assert_eq!(service.get_alert_rules().await.unwrap().len(), 4);
+
+        service.remove_node_alert_bundles(&node_a).await;
+        assert_eq!(service.get_alert_rules().await.unwrap().len(), 2);
+
+        assert_eq!(service.remove_alert_bundle(AlertRuleBundle::Disk).await, 1);
+        let bundles = service.get_alert_bundles().await;
+        let cpu = bundles.iter().find(|b| b.bundle == AlertRuleBundle::Cpu).unwrap();
+        assert!(cpu.applied);
+        assert_eq!(cpu.rule_count, 1);
+        assert!(!bundles.iter().any(|b| b.bundle == AlertRuleBundle::Disk && b.applied));
+    }
+
+    #[tokio::test]
+    async fn test_export_metrics_as_csv() {
+        use futures::StreamExt;
+
+        let config = AppConfig::from_env().unwrap();
+        let service = MonitoringService::new(config);
+        let start = "2026-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let point = |node_id: &str, name: &str, interface: Option<&str>, seconds: i64, value: f64| MetricData {
+            id: Uuid::new_v4(),
+            node_id: node_id.to_string(),
+            metric_name: name.to_string(),
+            metric_type: MetricType::Network,
+            value,
+            unit: MetricUnit::BitsPerSecond,
+            timestamp: start + chrono::Duration::seconds(seconds),
+            labels: interface
+                .map(|interface| vec![MetricLabel { key: "interface".to_string(), value: interface.to_string() }])
+                .unwrap_or_default(),
+            metadata: None,
+        };
+        for metric in [
+            point("a", "interface_rx_bps", Some("eth1"), 60, 2.0),
+            point("a", "interface_rx_bps", Some("eth0"), 0, 1.0),
+            point("a", "cpu_usage_percent", None, 0, 50.0),
+            point("b", "interface_rx_bps", Some("eth0"), 0, 9.0),
+            point("c", "interface_rx_bps", Some("eth0"), 0, 7.0),
+        ] {
+            service.record_metric(metric).await;
+        }
+        let nodes = HashMap::from([("a".to_string(), "edge-1".to_string()), ("b".to_string(), "edge, 2".to_string())]);
+        let export = |format: MetricsExportFormat| {
+            let query = MetricsExportQuery {
+                metrics: "interface_rx_bps, cpu_usage_percent".to_string(),
+                node_ids: None,
+                start_time: None,
+                end_time: None,
+                window: None,
+                preset: None,
+                format,
+            };
+            let chunks = service.export_metrics(query, nodes.clone()).unwrap();
+            async move { chunks.map(|chunk| chunk.unwrap()).collect::<Vec<_>>().await.concat() }
+        };
+
+        assert_eq!(
+            export(MetricsExportFormat::Long).await,
+            "timestamp,node_id,node_name,metric_name,labels,value,unit\n\
+             2026-01-01T00:00:00.000Z,a,edge-1,cpu_usage_percent,,50,bitspersecond\n\
+             2026-01-01T00:00:00.000Z,a,edge-1,interface_rx_bps,interface=eth0,1,bitspersecond\n\
+             2026-01-01T00:00:00.000Z,b,\"edge, 2\",interface_rx_bps,interface=eth0,9,bitspersecond\n\
+             2026-01-01T00:01:00.000Z,a,edge-1,interface_rx_bps,interface=eth1,2,bitspersecond\n"
+        );
+        assert_eq!(
+            export(MetricsExportFormat::Wide).await,
+            "timestamp,node_id,node_name,cpu_usage_percent,interface_rx_bps{interface=eth0},interface_rx_bps{interface=eth1}\n\
+             2026-01-01T00:00:00.000Z,a,edge-1,50,1,\n\
+             2026-01-01T00:00:00.000Z,b,\"edge, 2\",,9,\n\
+             2026-01-01T00:01:00.000Z,a,edge-1,,,2\n"
+        );
+        assert!(service
+            .export_metrics(
+                MetricsExportQuery {
+                    metrics: " , ".to_string(),
+                    node_ids: None,
+                    start_time: None,
+                    end_time: None,
+                    window: None,
+                    preset: None,
+                    format: MetricsExportFormat::Long,
+                },
+                nodes.clone(),
+            )
+            .is_err());
+
+        // Chunks end with all points of their last timestamp
+        for seconds in 0..EXPORT_BATCH_SIZE as i64 + 1 {
+            service.record_metric(point("b", "cpu_usage_percent", None, 120 + seconds / 2, 1.0)).await;
+        }
+        let query = MetricsExportQuery {
+            metrics: "cpu_usage_percent".to_string(),
+            node_ids: None,
+            start_time: Some(start + chrono::Duration::seconds(120)),
+            end_time: None,
+            window: None,
+            preset: None,
+            format: MetricsExportFormat::Long,
+        };
+        let chunks: Vec<String> =
+            service.export_metrics(query, nodes).unwrap().map(|chunk| chunk.unwrap()).collect().await;
+        let rows: Vec<usize> = chunks.iter().map(|chunk| chunk.lines().count()).collect();
+        assert_eq!(rows, [1, EXPORT_BATCH_SIZE, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_top_nodes() {
+        let config = AppConfig::from_env().unwrap();
+        let service = MonitoringService::new(config);
+        let now = Utc::now();
+        let point = |node_id: &str, minutes_ago: i64, value: f64| MetricData {
+            id: Uuid::new_v4(),
+            node_id: node_id.to_string(),
+            metric_name: "cpu_usage_percent".to_string(),
+            metric_type: MetricType::Cpu,
+            value,
+            unit: MetricUnit::Percentage,
+            timestamp: now - chrono::Duration::minutes(minutes_ago),
+            labels: Vec::new(),
+            metadata: None,
+        };
+        for metric in [
+            // a: busy for the last hour, quiet now
+            point("a", 50, 90.0),
+            point("a", 30, 80.0),
+            point("a", 1, 10.0),
+            // b: spiking now
+            point("b", 30, 20.0),
+            point("b", 1, 95.0),
+            // c: busy, but only before the window
+            point("c", 120, 99.0),
+            // d: not accessible
+            point("d", 1, 100.0),
+        ] {
+            service.record_metric(metric).await;
+        }
+        let nodes: HashMap<String, String> =
+            ["a", "b", "c"].into_iter().map(|id| (id.to_string(), format!("edge-{}", id))).collect();
+        let query = |n: usize, order: SortOrder| TopNodesQuery {
+            metric: "cpu_usage_percent".to_string(),
+            n,
+            order,
+            start_time: None,
+            end_time: None,
+            window: None,
+            preset: None,
+        };
+
+        // c stopped reporting two hours ago, so has no current value
+        let top = service.top_nodes(query(2, SortOrder::Desc), &nodes).await.unwrap();
+        let current: Vec<(&str, f64)> = top.current.iter().map(|node| (node.node_id.as_str(), node.value)).collect();
+        assert_eq!(current, vec![("b", 95.0), ("a", 10.0)]);
+        let window: Vec<(&str, f64, f64)> =
+            top.window.iter().map(|node| (node.node_id.as_str(), node.average, node.peak)).collect();
+        assert_eq!(window, vec![("a", 60.0, 90.0), ("b", 57.5, 95.0)]);
+
+        let top = service.top_nodes(query(10, SortOrder::Asc), &nodes).await.unwrap();
+        assert_eq!(top.current[0].node_id, "a");
+        assert_eq!(top.current.len(), 2);
+        assert_eq!(top.window[0].peak, 20.0);
+
+        assert!(service.top_nodes(query(0, SortOrder::Desc), &nodes).await.is_err());
+        assert!(service.top_nodes(query(101, SortOrder::Desc), &nodes).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bandwidth_alert_replaces_rule_of_interface() {
+        let config = AppConfig::from_env().unwrap();
+        let service = MonitoringService::new(config);
+        let node_id = Uuid::new_v4();
+
+        let first = service
+            .create_bandwidth_alert(node_id, "eth1", 1000, InterfaceBandwidthAlertRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(first.metric_name, INTERFACE_UTILIZATION_METRIC);
+        assert_eq!(first.threshold, 80.0);
+        assert_eq!(first.for_seconds, 600);
+        assert_eq!(rule_label(&first, "link_speed_mbps"), Some("1000"));
+
+        let request = InterfaceBandwidthAlertRequest {
+            threshold_percent: 95.0,
+            ..Default::default()
+        };
+        let second = service.create_bandwidth_alert(node_id, "eth1", 10_000, request).await.unwrap();
+        assert_eq!(second.id, first.id);
+        service
+            .create_bandwidth_alert(node_id, "eth2", 1000, InterfaceBandwidthAlertRequest::default())
+            .await
+            .unwrap();
+        let rules = service.get_alert_rules().await.unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].threshold, 95.0);
+
+        service.remove_node_alert_bundles(&node_id).await;
+        assert!(service.get_alert_rules().await.unwrap().is_empty());
+    }
 }
\ No newline at end of file
@@ -1,68 +1,760 @@
-use crate::config::AppConfig;
+//! Network Service
+//!
+//! Addressing of a node's interfaces: addresses, MTU, description, the
+//! administrative state, and VLAN sub-interfaces. A request is compared with
+//! the interface's configuration and turned into the set and delete
+//! operations that change only what differs, leaving settings the request
+//! does not cover, such as the hardware id, alone. A dry run only returns
+//! the operations, so callers can preview the change before applying it.
+//!
+//! PPPoE and WWAN interfaces get their addresses from the provider they
+//! dial, so they are configured with connection settings instead: the
+//! interface a PPPoE session runs over or the APN of a mobile network, and
+//! the credentials. VyOS cannot be told whether to authenticate by CHAP or
+//! PAP; the provider picks one, and the status of an interface reports the
+//! protocol its session was authenticated with.
+
+use std::collections::BTreeSet;
+
+use chrono::Utc;
+use ipnet::IpNet;
+use serde_json::Value;
+use tracing::info;
+use uuid::Uuid;
+
 use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::network::{
+    DialerSettings, DialerStatus, InterfaceAddressing, InterfaceConfig, InterfaceConfigPlan, InterfaceConfigRequest,
+    InterfaceKind, InterfaceSettings, InterfaceVlan, DHCP_ADDRESSES, MAX_MTU, MAX_VLAN_ID, MIN_MTU,
+};
+use crate::services::config_lock::ConfigLockService;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::config_tree::{
+    entries, lookup, number, path, text, validate_name, values, ConfigTreeWriter,
+};
+use crate::services::node_service::NodeService;
+use crate::vyos_client::{parsers, ConfigOperation, ConfigOperationKind};
 
-/// Network service for interacting with VyOS network configuration
+/// Shown in place of passwords in returned operations
+const MASKED: &str = "********";
+
+/// Network service
+#[derive(Clone)]
 pub struct NetworkService {
-    config: AppConfig,
+    nodes: NodeService,
+    writer: ConfigTreeWriter,
 }
 
 impl NetworkService {
     /// Create a new network service
-    pub fn new(config: AppConfig) -> Self {
-        Self { config }
+    pub fn new(nodes: NodeService, locks: ConfigLockService, sessions: ConfigSessionService) -> Self {
+        Self {
+            writer: ConfigTreeWriter::new(nodes.clone(), locks, sessions),
+            nodes,
+        }
     }
 
-    /// Get all network interfaces
-    pub async fn get_interfaces(&self) -> Result<Vec<crate::models::network::NetworkInterface>, AppError> {
-        // This would typically call the VyOS API
-        Ok(vec![])
+    /// Addressing settings of an interface
+    pub async fn interface(&self, node_id: Uuid, kind: InterfaceKind, name: &str) -> Result<InterfaceConfig, AppError> {
+        validate_interface_name(kind, name)?;
+        let config = self.writer.current(node_id).await?;
+        let settings = lookup(&config, &["interfaces", kind.as_str(), name])
+            .ok_or_else(|| interface_not_found(kind, name, node_id))?;
+        Ok(interface_config(kind, name, parse_interface(kind, settings)))
     }
 
-    /// Get interface by ID
-    pub async fn get_interface(&self, _id: uuid::Uuid) -> Result<Option<crate::models::network::NetworkInterface>, AppError> {
-        // This would typically call the VyOS API
-        Ok(None)
+    /// Compute the operations giving an interface the requested settings, and
+    /// apply them unless it is a dry run
+    ///
+    /// Interfaces of virtual kinds are created when they do not exist yet;
+    /// ethernet interfaces must already be configured on the node.
+    pub async fn configure_interface(
+        &self,
+        node_id: Uuid,
+        kind: InterfaceKind,
+        name: &str,
+        request: InterfaceConfigRequest,
+        claims: &Claims,
+    ) -> Result<InterfaceConfigPlan, AppError> {
+        validate_interface_name(kind, name)?;
+        let settings = normalize_interface(kind, request.settings)?;
+        let config = self.writer.current(node_id).await?;
+        let current = lookup(&config, &["interfaces", kind.as_str(), name]);
+        if current.is_none() && !kind.is_virtual() {
+            return Err(interface_not_found(kind, name, node_id));
+        }
+
+        let root = path(&["interfaces", kind.as_str()], &[name]);
+        let operations = interface_operations(&root, current, &settings);
+        let mut interface = interface_config(kind, name, settings);
+        if let Some(dialer) = interface.settings.dialer.as_mut() {
+            dialer.has_password = dialer.password.take().is_some()
+                || current.is_some_and(|config| lookup(config, &["authentication", "password"]).is_some());
+        }
+        let mut plan = InterfaceConfigPlan {
+            operations: operations.iter().cloned().map(mask_password).collect(),
+            interface,
+            applied: false,
+        };
+        if request.dry_run || operations.is_empty() {
+            return Ok(plan);
+        }
+
+        self.writer.apply(node_id, &operations, claims).await?;
+        info!(
+            "{} changed interface {} on node {} with {} operations",
+            claims.username,
+            name,
+            node_id,
+            plan.operations.len()
+        );
+        plan.applied = true;
+        Ok(plan)
     }
 
-    /// Configure an interface
-    pub async fn configure_interface(&self, _interface_id: uuid::Uuid, _config: serde_json::Value) -> Result<(), AppError> {
-        // This would typically call the VyOS API
-        Ok(())
+    /// Operational state of a PPPoE or WWAN interface: its link and assigned
+    /// addresses, and the uptime of its PPPoE session or the state of its
+    /// modem
+    pub async fn dialer_status(
+        &self,
+        node_id: Uuid,
+        kind: InterfaceKind,
+        name: &str,
+    ) -> Result<DialerStatus, AppError> {
+        validate_interface_name(kind, name)?;
+        if !kind.is_dialup() {
+            return Err(AppError::Validation(format!(
+                "{} interfaces do not dial a provider",
+                kind.as_str()
+            )));
+        }
+
+        let show = |detail: &str| format!("show interfaces {} {}{}", kind.as_str(), name, detail);
+        let output = self.nodes.execute_show_command(node_id, &show(""), true).await?.result.output;
+        let link = parsers::parse_show_interface_link(&output).ok_or_else(|| {
+            AppError::NotFound(format!("Interface {} {} does not exist on node {}", kind.as_str(), name, node_id))
+        })?;
+        let mut status = DialerStatus {
+            kind,
+            name: name.to_string(),
+            is_up: link.is_up,
+            addresses: link.addresses,
+            peer_address: link.peer_address,
+            mtu: link.mtu,
+            connected_since: None,
+            uptime_secs: None,
+            auth_protocol: None,
+            modem: None,
+        };
+
+        let now = Utc::now();
+        match kind {
+            InterfaceKind::Pppoe => {
+                let log = self.nodes.execute_show_command(node_id, &show(" log"), true).await?.result.output;
+                let session = parsers::parse_ppp_log(&log, now);
+                if status.is_up {
+                    status.connected_since = session.connected_since;
+                    status.uptime_secs = session.connected_since.map(|since| (now - since).num_seconds().max(0) as u64);
+                    status.auth_protocol = session.auth_protocol;
+                }
+            }
+            _ => {
+                let details = self.nodes.execute_show_command(node_id, &show(" details"), true).await?.result.output;
+                status.modem = Some(parsers::parse_show_wwan_details(&details));
+            }
+        }
+        Ok(status)
     }
+}
 
-    /// Get routing table
-    pub async fn get_routes(&self) -> Result<Vec<crate::models::network::Route>, AppError> {
-        // This would typically call the VyOS API
-        Ok(vec![])
+/// Operation with the password it sets masked
+fn mask_password(mut operation: ConfigOperation) -> ConfigOperation {
+    let len = operation.path.len();
+    if operation.op == ConfigOperationKind::Set
+        && len >= 3
+        && operation.path[len - 2] == "password"
+        && operation.path[len - 3] == "authentication"
+    {
+        operation.path[len - 1] = MASKED.to_string();
     }
+    operation
+}
 
-    /// Add a static route
-    pub async fn add_route(&self, _route: crate::models::network::Route) -> Result<(), AppError> {
-        // This would typically call the VyOS API
-        Ok(())
+fn interface_not_found(kind: InterfaceKind, name: &str, node_id: Uuid) -> AppError {
+    AppError::NotFound(format!("Interface {} {} is not configured on node {}", kind.as_str(), name, node_id))
+}
+
+fn interface_config(kind: InterfaceKind, name: &str, settings: InterfaceSettings) -> InterfaceConfig {
+    InterfaceConfig {
+        kind,
+        name: name.to_string(),
+        path: format!("interfaces {} {}", kind.as_str(), name),
+        settings,
     }
+}
 
-    /// Delete a route
-    pub async fn delete_route(&self, _route_id: uuid::Uuid) -> Result<(), AppError> {
-        // This would typically call the VyOS API
+/// Check an interface name against the naming of its kind, e.g. `eth0` or
+/// `bond1`
+fn validate_interface_name(kind: InterfaceKind, name: &str) -> Result<(), AppError> {
+    let valid = name
+        .strip_prefix(kind.name_prefix())
+        .is_some_and(|index| !index.is_empty() && index.len() <= 5 && index.chars().all(|c| c.is_ascii_digit()));
+    if valid {
         Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "'{}' is not a valid {} interface name; use {}N",
+            name,
+            kind.as_str(),
+            kind.name_prefix()
+        )))
     }
+}
 
-    /// Get firewall rules
-    pub async fn get_firewall_rules(&self) -> Result<Vec<crate::models::network::FirewallRule>, AppError> {
-        // This would typically call the VyOS API
-        Ok(vec![])
+fn parse_addressing(config: &Value) -> InterfaceAddressing {
+    InterfaceAddressing {
+        addresses: values(config.get("address")),
+        mtu: number(config.get("mtu")),
+        description: text(config.get("description")),
+        disabled: config.get("disable").is_some(),
     }
+}
 
-    /// Add a firewall rule
-    pub async fn add_firewall_rule(&self, _rule: crate::models::network::FirewallRule) -> Result<(), AppError> {
-        // This would typically call the VyOS API
-        Ok(())
+fn parse_dialer(config: &Value) -> DialerSettings {
+    DialerSettings {
+        source_interface: text(config.get("source-interface")),
+        apn: text(config.get("apn")),
+        username: text(lookup(config, &["authentication", "username"])),
+        password: None,
+        has_password: lookup(config, &["authentication", "password"]).is_some(),
     }
+}
 
-    /// Delete a firewall rule
-    pub async fn delete_firewall_rule(&self, _rule_id: uuid::Uuid) -> Result<(), AppError> {
-        // This would typically call the VyOS API
-        Ok(())
+fn parse_interface(kind: InterfaceKind, config: &Value) -> InterfaceSettings {
+    InterfaceSettings {
+        dialer: kind.is_dialup().then(|| parse_dialer(config)),
+        addressing: parse_addressing(config),
+        vlans: entries(config.get("vif"))
+            .into_iter()
+            .filter_map(|(id, settings)| {
+                Some(InterfaceVlan {
+                    vlan_id: id.parse().ok()?,
+                    settings: parse_addressing(settings),
+                })
+            })
+            .collect(),
+    }
+}
+
+fn normalize_addressing(
+    what: &str,
+    settings: InterfaceAddressing,
+    max_mtu: u32,
+    seen: &mut BTreeSet<String>,
+) -> Result<InterfaceAddressing, AppError> {
+    let mut addresses = Vec::with_capacity(settings.addresses.len());
+    for address in settings.addresses {
+        let address = address.trim().to_ascii_lowercase();
+        let normalized = if DHCP_ADDRESSES.contains(&address.as_str()) {
+            if !seen.insert(format!("{} {}", what, address)) {
+                return Err(AppError::Validation(format!("{} has address {} twice", what, address)));
+            }
+            address
+        } else {
+            let network: IpNet = address.parse().map_err(|_| {
+                AppError::Validation(format!(
+                    "Address '{}' of {} is not an address with a prefix length, dhcp, or dhcpv6",
+                    address, what
+                ))
+            })?;
+            if network.addr() == network.network() && network.prefix_len() < network.max_prefix_len() - 1 {
+                return Err(AppError::Validation(format!(
+                    "Address {} of {} is the network address of its prefix",
+                    network, what
+                )));
+            }
+            if !seen.insert(network.addr().to_string()) {
+                return Err(AppError::Validation(format!(
+                    "Address {} is assigned more than once",
+                    network.addr()
+                )));
+            }
+            network.to_string()
+        };
+        addresses.push(normalized);
+    }
+
+    if let Some(mtu) = settings.mtu {
+        if !(MIN_MTU..=max_mtu).contains(&mtu) {
+            return Err(AppError::Validation(format!(
+                "MTU {} of {} is outside {}-{}",
+                mtu, what, MIN_MTU, max_mtu
+            )));
+        }
+    }
+    let description = settings
+        .description
+        .map(|description| description.trim().to_string())
+        .filter(|description| !description.is_empty());
+    if description.as_ref().is_some_and(|description| description.len() > 256 || description.contains('\n')) {
+        return Err(AppError::Validation(format!(
+            "Description of {} must be one line of at most 256 characters",
+            what
+        )));
+    }
+
+    Ok(InterfaceAddressing {
+        addresses,
+        mtu: settings.mtu,
+        description,
+        disabled: settings.disabled,
+    })
+}
+
+/// Validate interface settings, ordering VLANs by id
+///
+/// An address may be assigned once across the interface and its VLANs, and
+/// a VLAN's MTU may not exceed the interface's.
+fn normalize_interface(kind: InterfaceKind, settings: InterfaceSettings) -> Result<InterfaceSettings, AppError> {
+    if kind.is_dialup() && !settings.addressing.addresses.is_empty() {
+        return Err(AppError::Validation(format!(
+            "{} interfaces get their addresses from the provider",
+            kind.as_str()
+        )));
+    }
+    if !kind.has_vlans() && !settings.vlans.is_empty() {
+        return Err(AppError::Validation(format!(
+            "{} interfaces have no VLAN sub-interfaces",
+            kind.as_str()
+        )));
+    }
+
+    let mut seen = BTreeSet::new();
+    let addressing = normalize_addressing("the interface", settings.addressing, MAX_MTU, &mut seen)?;
+    let max_mtu = addressing.mtu.unwrap_or(MAX_MTU);
+    let mut ids = BTreeSet::new();
+    let mut vlans = Vec::with_capacity(settings.vlans.len());
+    for vlan in settings.vlans {
+        if !(1..=MAX_VLAN_ID).contains(&vlan.vlan_id) {
+            return Err(AppError::Validation(format!(
+                "VLAN id {} is outside 1-{}",
+                vlan.vlan_id, MAX_VLAN_ID
+            )));
+        }
+        if !ids.insert(vlan.vlan_id) {
+            return Err(AppError::Validation(format!("VLAN {} is given twice", vlan.vlan_id)));
+        }
+        let what = format!("VLAN {}", vlan.vlan_id);
+        vlans.push(InterfaceVlan {
+            vlan_id: vlan.vlan_id,
+            settings: normalize_addressing(&what, vlan.settings, max_mtu, &mut seen)?,
+        });
+    }
+    vlans.sort_by_key(|vlan| vlan.vlan_id);
+
+    let dialer = match (kind.is_dialup(), settings.dialer) {
+        (true, Some(dialer)) => Some(normalize_dialer(kind, dialer)?),
+        (true, None) => {
+            return Err(AppError::Validation(format!(
+                "{} interfaces need connection settings",
+                kind.as_str()
+            )))
+        }
+        (false, Some(_)) => {
+            return Err(AppError::Validation(format!(
+                "{} interfaces have no connection settings",
+                kind.as_str()
+            )))
+        }
+        (false, None) => None,
+    };
+
+    Ok(InterfaceSettings {
+        addressing,
+        vlans,
+        dialer,
+    })
+}
+
+/// Validate the connection settings of a PPPoE or WWAN interface
+///
+/// A PPPoE session needs the interface it runs over and a WWAN interface
+/// the APN of its network; the credentials are optional.
+fn normalize_dialer(kind: InterfaceKind, dialer: DialerSettings) -> Result<DialerSettings, AppError> {
+    let trimmed = |value: Option<String>| value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+    let dialer = DialerSettings {
+        source_interface: trimmed(dialer.source_interface),
+        apn: trimmed(dialer.apn),
+        username: trimmed(dialer.username),
+        password: dialer.password.filter(|password| !password.is_empty()),
+        has_password: false,
+    };
+
+    let source_interface = ("source interface", &dialer.source_interface);
+    let apn = ("APN", &dialer.apn);
+    let ((required, value), (other, unexpected)) = match kind {
+        InterfaceKind::Pppoe => (source_interface, apn),
+        _ => (apn, source_interface),
+    };
+    let Some(value) = value else {
+        return Err(AppError::Validation(format!("{} interfaces need their {}", kind.as_str(), required)));
+    };
+    if unexpected.is_some() {
+        return Err(AppError::Validation(format!("{} interfaces have no {}", kind.as_str(), other)));
+    }
+    validate_name(required, value)?;
+
+    if let Some(username) = &dialer.username {
+        if username.len() > 128 || !username.chars().all(|c| c.is_ascii_graphic()) {
+            return Err(AppError::Validation(format!("User name '{}' is not valid", username)));
+        }
+    }
+    if let Some(password) = &dialer.password {
+        if password.len() > 128 || password.chars().any(|c| c.is_control()) {
+            return Err(AppError::Validation(
+                "Password must be at most 128 characters without control characters".to_string(),
+            ));
+        }
     }
-}
\ No newline at end of file
+    Ok(dialer)
+}
+
+/// Operations changing the addressing at `root`, whose configuration is
+/// `current`, to `desired`
+fn addressing_operations(
+    root: &[String],
+    current: Option<&Value>,
+    desired: &InterfaceAddressing,
+    operations: &mut Vec<ConfigOperation>,
+) {
+    let at = |words: &[&str]| -> Vec<String> {
+        root.iter().cloned().chain(words.iter().map(|word| word.to_string())).collect()
+    };
+    let existing = current.map(parse_addressing).unwrap_or_default();
+    if current.is_none() {
+        operations.push(ConfigOperation::set(root.to_vec()));
+    }
+
+    for address in &existing.addresses {
+        if !desired.addresses.contains(address) {
+            operations.push(ConfigOperation::delete(at(&["address", address])));
+        }
+    }
+    for address in &desired.addresses {
+        if !existing.addresses.contains(address) {
+            operations.push(ConfigOperation::set(at(&["address", address])));
+        }
+    }
+
+    match (existing.mtu, desired.mtu) {
+        (Some(_), None) => operations.push(ConfigOperation::delete(at(&["mtu"]))),
+        (existing, Some(mtu)) if existing != Some(mtu) => {
+            operations.push(ConfigOperation::set(at(&["mtu", &mtu.to_string()])))
+        }
+        _ => {}
+    }
+    match (&existing.description, &desired.description) {
+        (Some(_), None) => operations.push(ConfigOperation::delete(at(&["description"]))),
+        (existing, Some(description)) if existing.as_ref() != Some(description) => {
+            operations.push(ConfigOperation::set(at(&["description", description])))
+        }
+        _ => {}
+    }
+    match (existing.disabled, desired.disabled) {
+        (true, false) => operations.push(ConfigOperation::delete(at(&["disable"]))),
+        (false, true) => operations.push(ConfigOperation::set(at(&["disable"]))),
+        _ => {}
+    }
+}
+
+/// Operations changing the interface at `root` to the requested settings:
+/// deletes followed by sets
+///
+/// A new interface or VLAN is created with a set of its own path, so it
+/// exists even without other settings.
+fn interface_operations(root: &[String], current: Option<&Value>, desired: &InterfaceSettings) -> Vec<ConfigOperation> {
+    let mut operations = Vec::new();
+    addressing_operations(root, current, &desired.addressing, &mut operations);
+    if let Some(dialer) = &desired.dialer {
+        dialer_operations(root, current, dialer, &mut operations);
+    }
+
+    let existing = entries(current.and_then(|config| config.get("vif")));
+    for (id, _) in &existing {
+        if !desired.vlans.iter().any(|vlan| vlan.vlan_id.to_string() == *id) {
+            operations.push(ConfigOperation::delete(vlan_path(root, id)));
+        }
+    }
+    for vlan in &desired.vlans {
+        let id = vlan.vlan_id.to_string();
+        let current = existing.iter().find(|(existing, _)| *existing == id).map(|(_, config)| *config);
+        addressing_operations(&vlan_path(root, &id), current, &vlan.settings, &mut operations);
+    }
+
+    operations.sort_by_key(|operation| operation.op != ConfigOperationKind::Delete);
+    operations
+}
+
+/// Operations changing the connection settings of the interface at `root`
+///
+/// An unset password keeps the current one.
+fn dialer_operations(
+    root: &[String],
+    current: Option<&Value>,
+    desired: &DialerSettings,
+    operations: &mut Vec<ConfigOperation>,
+) {
+    let at = |words: &[&str]| -> Vec<String> {
+        root.iter().cloned().chain(words.iter().map(|word| word.to_string())).collect()
+    };
+    let current = current.unwrap_or(&Value::Null);
+    let existing = parse_dialer(current);
+    let settings: [(&[&str], &Option<String>, Option<String>); 4] = [
+        (&["source-interface"], &desired.source_interface, existing.source_interface),
+        (&["apn"], &desired.apn, existing.apn),
+        (&["authentication", "username"], &desired.username, existing.username),
+        (
+            &["authentication", "password"],
+            &desired.password,
+            text(lookup(current, &["authentication", "password"])),
+        ),
+    ];
+    for (words, desired, existing) in settings {
+        let is_password = words.last() == Some(&"password");
+        match (existing, desired) {
+            (Some(_), None) if !is_password => operations.push(ConfigOperation::delete(at(words))),
+            (existing, Some(value)) if existing.as_ref() != Some(value) => {
+                let mut path = at(words);
+                path.push(value.clone());
+                operations.push(ConfigOperation::set(path));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn vlan_path(root: &[String], id: &str) -> Vec<String> {
+    let mut path = root.to_vec();
+    path.extend(["vif".to_string(), id.to_string()]);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn commands(operations: &[ConfigOperation]) -> Vec<String> {
+        operations
+            .iter()
+            .map(|operation| {
+                let verb = match operation.op {
+                    ConfigOperationKind::Set => "set",
+                    _ => "delete",
+                };
+                format!("{} {}", verb, operation.path.join(" "))
+            })
+            .collect()
+    }
+
+    fn addressing(addresses: &[&str]) -> InterfaceAddressing {
+        InterfaceAddressing {
+            addresses: addresses.iter().map(|address| address.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_operations_change_only_what_differs() {
+        let current = json!({
+            "hw-id": "00:53:00:00:00:01",
+            "address": ["192.0.2.1/24", "2001:db8::1/64"],
+            "mtu": "1500",
+            "description": "WAN",
+            "vif": {
+                "10": { "address": "198.51.100.1/24" },
+                "20": { "address": "203.0.113.1/24" }
+            }
+        });
+        let root = path(&["interfaces", "ethernet"], &["eth0"]);
+        let mut desired = parse_interface(InterfaceKind::Ethernet, &current);
+        assert_eq!(desired.addressing.addresses, ["192.0.2.1/24", "2001:db8::1/64"]);
+        assert!(interface_operations(&root, Some(&current), &desired).is_empty());
+
+        desired.addressing.addresses = vec!["192.0.2.1/24".to_string(), "dhcpv6".to_string()];
+        desired.addressing.mtu = Some(9000);
+        desired.addressing.description = None;
+        desired.addressing.disabled = true;
+        desired.vlans = vec![
+            InterfaceVlan { vlan_id: 10, settings: addressing(&["198.51.100.1/24"]) },
+            InterfaceVlan { vlan_id: 30, settings: InterfaceAddressing::default() },
+        ];
+        assert_eq!(
+            commands(&interface_operations(&root, Some(&current), &desired)),
+            [
+                "delete interfaces ethernet eth0 address 2001:db8::1/64",
+                "delete interfaces ethernet eth0 description",
+                "delete interfaces ethernet eth0 vif 20",
+                "set interfaces ethernet eth0 address dhcpv6",
+                "set interfaces ethernet eth0 mtu 9000",
+                "set interfaces ethernet eth0 disable",
+                "set interfaces ethernet eth0 vif 30",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_new_interface_is_created() {
+        let root = path(&["interfaces", "bonding"], &["bond0"]);
+        let desired = InterfaceSettings {
+            addressing: InterfaceAddressing {
+                description: Some("Uplink".to_string()),
+                ..Default::default()
+            },
+            vlans: vec![InterfaceVlan { vlan_id: 5, settings: addressing(&["dhcp"]) }],
+            dialer: None,
+        };
+        assert_eq!(
+            commands(&interface_operations(&root, None, &desired)),
+            [
+                "set interfaces bonding bond0",
+                "set interfaces bonding bond0 description Uplink",
+                "set interfaces bonding bond0 vif 5",
+                "set interfaces bonding bond0 vif 5 address dhcp",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_settings_are_validated() {
+        assert!(validate_interface_name(InterfaceKind::Ethernet, "eth0").is_ok());
+        assert!(validate_interface_name(InterfaceKind::Ethernet, "bond0").is_err());
+        assert!(validate_interface_name(InterfaceKind::Bridge, "br").is_err());
+
+        let settings = |addresses: &[&str], mtu: Option<u32>, vlans: Vec<InterfaceVlan>| InterfaceSettings {
+            addressing: InterfaceAddressing { mtu, ..addressing(addresses) },
+            vlans,
+            dialer: None,
+        };
+        let normalized = normalize_interface(
+            InterfaceKind::Ethernet,
+            settings(
+                &[" 2001:DB8::1/64 ", "DHCP"],
+                None,
+                vec![
+                    InterfaceVlan { vlan_id: 20, settings: addressing(&["dhcp"]) },
+                    InterfaceVlan { vlan_id: 10, settings: addressing(&["10.0.0.1/31"]) },
+                ],
+            ),
+        )
+        .unwrap();
+        assert_eq!(normalized.addressing.addresses, ["2001:db8::1/64", "dhcp"]);
+        assert_eq!(normalized.vlans.iter().map(|vlan| vlan.vlan_id).collect::<Vec<_>>(), [10, 20]);
+
+        let invalid = [
+            settings(&["192.0.2.1"], None, vec![]),
+            settings(&["192.0.2.0/24"], None, vec![]),
+            settings(&["192.0.2.1/24", "192.0.2.1/25"], None, vec![]),
+            settings(&[], Some(40), vec![]),
+            settings(&[], None, vec![InterfaceVlan { vlan_id: 4095, settings: Default::default() }]),
+            settings(
+                &[],
+                None,
+                vec![
+                    InterfaceVlan { vlan_id: 7, settings: Default::default() },
+                    InterfaceVlan { vlan_id: 7, settings: Default::default() },
+                ],
+            ),
+            settings(
+                &[],
+                Some(1500),
+                vec![InterfaceVlan {
+                    vlan_id: 7,
+                    settings: InterfaceAddressing { mtu: Some(9000), ..Default::default() },
+                }],
+            ),
+            settings(
+                &["192.0.2.1/24"],
+                None,
+                vec![InterfaceVlan { vlan_id: 7, settings: addressing(&["192.0.2.1/24"]) }],
+            ),
+        ];
+        for settings in invalid {
+            assert!(normalize_interface(InterfaceKind::Ethernet, settings.clone()).is_err(), "{:?}", settings);
+        }
+        assert!(normalize_interface(
+            InterfaceKind::Dummy,
+            settings(&[], None, vec![InterfaceVlan { vlan_id: 7, settings: Default::default() }])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_dialer_settings() {
+        let current = json!({
+            "source-interface": "eth0",
+            "authentication": { "username": "old@isp", "password": "secret" },
+            "mtu": "1492"
+        });
+        let desired = parse_interface(InterfaceKind::Pppoe, &current);
+        let dialer = desired.dialer.clone().unwrap();
+        assert_eq!(dialer.source_interface.as_deref(), Some("eth0"));
+        assert!(dialer.has_password && dialer.password.is_none());
+        let root = path(&["interfaces", "pppoe"], &["pppoe0"]);
+        assert!(interface_operations(&root, Some(&current), &desired).is_empty());
+
+        let desired = normalize_interface(
+            InterfaceKind::Pppoe,
+            InterfaceSettings {
+                addressing: InterfaceAddressing { mtu: Some(1492), ..Default::default() },
+                dialer: Some(DialerSettings {
+                    source_interface: Some("eth0.7".to_string()),
+                    username: Some("new@isp".to_string()),
+                    password: Some("hunter2".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let operations = interface_operations(&root, Some(&current), &desired);
+        assert_eq!(
+            commands(&operations),
+            [
+                "set interfaces pppoe pppoe0 source-interface eth0.7",
+                "set interfaces pppoe pppoe0 authentication username new@isp",
+                "set interfaces pppoe pppoe0 authentication password hunter2",
+            ]
+        );
+        assert_eq!(
+            commands(&operations.into_iter().map(mask_password).collect::<Vec<_>>())[2],
+            "set interfaces pppoe pppoe0 authentication password ********"
+        );
+
+        let dialer = |source_interface: Option<&str>, apn: Option<&str>| InterfaceSettings {
+            dialer: Some(DialerSettings {
+                source_interface: source_interface.map(str::to_string),
+                apn: apn.map(str::to_string),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(normalize_interface(InterfaceKind::Wwan, dialer(None, Some("internet"))).is_ok());
+        let invalid = [
+            (InterfaceKind::Pppoe, dialer(None, None)),
+            (InterfaceKind::Pppoe, dialer(Some("eth0"), Some("internet"))),
+            (InterfaceKind::Wwan, dialer(Some("eth0"), None)),
+            (InterfaceKind::Wwan, dialer(None, Some("bad apn"))),
+            (InterfaceKind::Wwan, InterfaceSettings::default()),
+            (InterfaceKind::Ethernet, dialer(Some("eth1"), None)),
+            (
+                InterfaceKind::Pppoe,
+                InterfaceSettings { addressing: addressing(&["dhcp"]), ..dialer(Some("eth0"), None) },
+            ),
+        ];
+        for (kind, settings) in invalid {
+            assert!(normalize_interface(kind, settings.clone()).is_err(), "{:?}", settings);
+        }
+    }
+}
@@ -0,0 +1,511 @@
+//! Node File Service
+//!
+//! Keeps the markdown notes of nodes and the files attached to them, such as
+//! rack photos and cabling diagrams. Content lives in the uploads artifact
+//! store below `nodes/<node id>/`, so it counts against the storage cap like
+//! any upload; metadata is kept in the `node_note_versions` and
+//! `node_attachments` tables. Saving notes or attaching a file under a name
+//! already in use adds a version rather than replacing the previous one.
+//!
+//! Anyone who can access a node can read its notes and attachments; changing
+//! them takes the `nodes.write` permission.
+
+use std::sync::Arc;
+
+use actix_multipart::{Field, Multipart};
+use actix_web::web::Bytes;
+use futures_util::{Stream, TryStreamExt};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::node_file::{
+    attachment_compression, NodeAttachment, NodeAttachmentListQuery, NodeNote, NodeNoteVersion, MAX_NOTE_BYTES,
+};
+use crate::models::permission::NODES_WRITE;
+use crate::models::storage::{Compression, StorageCategory};
+use crate::models::timestamp::{db_now, parse_db_timestamp};
+use crate::services::object_store::{artifact_store, ObjectStore};
+use crate::services::storage::{self, StorageWriter};
+use crate::services::upload::sanitize_file_name;
+use crate::services::{PermissionService, StorageUsageService};
+
+const ATTACHMENT_COLUMNS: &str = "id, node_id, file_name, version, content_type, size_bytes, sha256, compression, \
+     stored_bytes, uploaded_by, created_at";
+
+/// Form field carrying the attached file
+const FILE_FIELD: &str = "file";
+
+/// Node file service
+#[derive(Clone)]
+pub struct NodeFileService {
+    db: Database,
+    permissions: PermissionService,
+    storage: StorageUsageService,
+    store: Arc<dyn ObjectStore>,
+    max_attachment_bytes: u64,
+}
+
+impl NodeFileService {
+    /// Create a new node file service
+    pub fn new(config: &AppConfig, db: Database, permissions: PermissionService, storage: StorageUsageService) -> Self {
+        Self {
+            db,
+            permissions,
+            storage,
+            store: artifact_store(config, &config.upload_dir, StorageCategory::Uploads.label()),
+            max_attachment_bytes: config.max_attachment_bytes,
+        }
+    }
+
+    /// Ensure the user may change the notes and attachments of nodes
+    pub async fn ensure_can_write(&self, claims: &Claims) -> Result<(), AppError> {
+        if !self.permissions.has_permission(claims, NODES_WRITE).await? {
+            return Err(AppError::Forbidden(format!(
+                "Changing node notes and attachments requires the {} permission",
+                NODES_WRITE
+            )));
+        }
+        Ok(())
+    }
+
+    // ========================================================================
+    // Notes
+    // ========================================================================
+
+    /// Get a node's notes, at their latest version unless one is given
+    pub async fn get_note(&self, node_id: Uuid, version: Option<u32>) -> Result<NodeNote, AppError> {
+        let row: Option<NoteVersionRow> = match version {
+            Some(version) => {
+                sqlx::query_as(
+                    "SELECT version, size_bytes, sha256, author, created_at FROM node_note_versions \
+                     WHERE node_id = ? AND version = ?",
+                )
+                .bind(node_id.to_string())
+                .bind(version as i64)
+                .fetch_optional(self.db.pool())
+                .await?
+            }
+            None => {
+                sqlx::query_as(
+                    "SELECT version, size_bytes, sha256, author, created_at FROM node_note_versions \
+                     WHERE node_id = ? ORDER BY version DESC LIMIT 1",
+                )
+                .bind(node_id.to_string())
+                .fetch_optional(self.db.pool())
+                .await?
+            }
+        };
+        let version = row.map(note_version_from_row).ok_or_else(|| match version {
+            Some(version) => {
+                AppError::NotFound(format!("Version {} of the notes of node {} not found", version, node_id))
+            }
+            None => AppError::NotFound(format!("Node {} has no notes", node_id)),
+        })?;
+
+        let content = storage::read_object(self.store.as_ref(), &note_key(node_id, version.version), Compression::Zstd)
+            .await
+            .map_err(|e| match e {
+                AppError::NotFound(_) => AppError::NotFound(format!(
+                    "Content of version {} of the notes of node {} is missing",
+                    version.version, node_id
+                )),
+                e => e,
+            })?;
+
+        Ok(NodeNote {
+            node_id,
+            version: version.version,
+            content: String::from_utf8_lossy(&content).into_owned(),
+            author: version.author,
+            created_at: version.created_at,
+        })
+    }
+
+    /// List the saved versions of a node's notes, newest first
+    pub async fn list_note_versions(&self, node_id: Uuid) -> Result<Vec<NodeNoteVersion>, AppError> {
+        let rows: Vec<NoteVersionRow> = sqlx::query_as(
+            "SELECT version, size_bytes, sha256, author, created_at FROM node_note_versions \
+             WHERE node_id = ? ORDER BY version DESC",
+        )
+        .bind(node_id.to_string())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows.into_iter().map(note_version_from_row).collect())
+    }
+
+    /// Save a new version of a node's notes
+    pub async fn save_note(&self, claims: &Claims, node_id: Uuid, content: String) -> Result<NodeNote, AppError> {
+        if content.len() > MAX_NOTE_BYTES {
+            return Err(AppError::PayloadTooLarge(format!(
+                "Node notes are limited to {} bytes",
+                MAX_NOTE_BYTES
+            )));
+        }
+        self.storage.ensure_capacity(StorageCategory::Uploads).await?;
+
+        let latest: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM node_note_versions WHERE node_id = ?")
+            .bind(node_id.to_string())
+            .fetch_one(self.db.pool())
+            .await?;
+        let version = latest.unwrap_or(0) as u32 + 1;
+
+        // The row claims the version, so concurrent saves never share a key
+        let created_at = db_now();
+        sqlx::query(
+            "INSERT INTO node_note_versions (node_id, version, size_bytes, sha256, author, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(node_id.to_string())
+        .bind(version as i64)
+        .bind(content.len() as i64)
+        .bind(format!("{:x}", Sha256::digest(content.as_bytes())))
+        .bind(&claims.username)
+        .bind(&created_at)
+        .execute(self.db.pool())
+        .await?;
+
+        let key = note_key(node_id, version);
+        let written = storage::write_object(self.store.as_ref(), &key, Compression::Zstd, content.as_bytes()).await;
+        if let Err(e) = written {
+            let removed = sqlx::query("DELETE FROM node_note_versions WHERE node_id = ? AND version = ?")
+                .bind(node_id.to_string())
+                .bind(version as i64)
+                .execute(self.db.pool())
+                .await;
+            if let Err(removed) = removed {
+                warn!("Failed to remove version {} of the notes of node {}: {}", version, node_id, removed);
+            }
+            return Err(e);
+        }
+
+        info!("Version {} of the notes of node {} saved by {}", version, node_id, claims.username);
+
+        Ok(NodeNote {
+            node_id,
+            version,
+            content,
+            author: Some(claims.username.clone()),
+            created_at: parse_db_timestamp(&created_at),
+        })
+    }
+
+    // ========================================================================
+    // Attachments
+    // ========================================================================
+
+    /// Attach the file sent in the `file` field of a multipart request
+    pub async fn attach(
+        &self,
+        claims: &Claims,
+        node_id: Uuid,
+        mut multipart: Multipart,
+    ) -> Result<NodeAttachment, AppError> {
+        let mut attachment: Option<NodeAttachment> = None;
+
+        let result = async {
+            while let Some(field) = multipart.try_next().await? {
+                match field.name() {
+                    Some(FILE_FIELD) if attachment.is_none() => {
+                        attachment = Some(self.write_file(claims, node_id, field).await?)
+                    }
+                    Some(FILE_FIELD) => {
+                        return Err(AppError::Validation("Only one file can be attached per request".to_string()))
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        }
+        .await;
+
+        let attachment = match (result, attachment) {
+            (Ok(()), Some(attachment)) => attachment,
+            (Ok(()), None) => return Err(AppError::Validation(format!("The {} field is required", FILE_FIELD))),
+            (Err(e), Some(attachment)) => {
+                self.remove(&attachment_key(node_id, attachment.id)).await;
+                return Err(e);
+            }
+            (Err(e), None) => return Err(e),
+        };
+
+        let key = attachment_key(node_id, attachment.id);
+        let attachment = match self.save_attachment(attachment).await {
+            Ok(attachment) => attachment,
+            Err(e) => {
+                self.remove(&key).await;
+                return Err(e);
+            }
+        };
+
+        info!(
+            "Version {} of {} ({} bytes) attached to node {} by {}",
+            attachment.version, attachment.file_name, attachment.size_bytes, node_id, claims.username
+        );
+
+        Ok(attachment)
+    }
+
+    /// List the attachments of a node, by file name
+    ///
+    /// Lists the latest version of every file, or every version of one file,
+    /// newest first.
+    pub async fn list_attachments(
+        &self,
+        node_id: Uuid,
+        query: NodeAttachmentListQuery,
+    ) -> Result<Vec<NodeAttachment>, AppError> {
+        let rows: Vec<AttachmentRow> = match query.file_name {
+            Some(file_name) => {
+                let sql = format!(
+                    "SELECT {} FROM node_attachments WHERE node_id = ? AND file_name = ? ORDER BY version DESC",
+                    ATTACHMENT_COLUMNS
+                );
+                sqlx::query_as(&sql)
+                    .bind(node_id.to_string())
+                    .bind(file_name)
+                    .fetch_all(self.db.pool())
+                    .await?
+            }
+            None => {
+                let sql = format!(
+                    "SELECT {} FROM node_attachments a WHERE node_id = ? \
+                     AND version = (SELECT MAX(version) FROM node_attachments \
+                                    WHERE node_id = a.node_id AND file_name = a.file_name) \
+                     ORDER BY file_name",
+                    ATTACHMENT_COLUMNS
+                );
+                sqlx::query_as(&sql).bind(node_id.to_string()).fetch_all(self.db.pool()).await?
+            }
+        };
+
+        Ok(rows.into_iter().map(attachment_from_row).collect())
+    }
+
+    /// Get the metadata of a version of an attachment
+    pub async fn get_attachment(&self, node_id: Uuid, id: Uuid) -> Result<NodeAttachment, AppError> {
+        let sql = format!("SELECT {} FROM node_attachments WHERE id = ? AND node_id = ?", ATTACHMENT_COLUMNS);
+
+        sqlx::query_as::<_, AttachmentRow>(&sql)
+            .bind(id.to_string())
+            .bind(node_id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(attachment_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Attachment {} of node {} not found", id, node_id)))
+    }
+
+    /// Stream the content of a version of an attachment, decompressed
+    pub async fn open_attachment(
+        &self,
+        node_id: Uuid,
+        id: Uuid,
+    ) -> Result<(NodeAttachment, impl Stream<Item = Result<Bytes, std::io::Error>>), AppError> {
+        let attachment = self.get_attachment(node_id, id).await?;
+        let chunks =
+            match storage::stream_object(self.store.as_ref(), &attachment_key(node_id, id), attachment.compression)
+                .await
+            {
+                Err(AppError::NotFound(_)) => {
+                    return Err(AppError::NotFound(format!("Content of attachment {} is missing", id)))
+                }
+                chunks => chunks?,
+            };
+
+        Ok((attachment, chunks))
+    }
+
+    /// Delete a version of an attachment
+    ///
+    /// Deleting the latest version makes the one before it current again.
+    pub async fn delete_attachment(&self, node_id: Uuid, id: Uuid) -> Result<NodeAttachment, AppError> {
+        let attachment = self.get_attachment(node_id, id).await?;
+        sqlx::query("DELETE FROM node_attachments WHERE id = ?")
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+        self.remove(&attachment_key(node_id, id)).await;
+
+        Ok(attachment)
+    }
+
+    /// Remove the stored content of a deleted node
+    ///
+    /// The metadata goes with the node itself.
+    pub async fn remove_node_files(&self, node_id: Uuid) {
+        let prefix = node_prefix(node_id);
+        match self.store.list().await {
+            Ok(objects) => {
+                for object in objects.iter().filter(|object| object.key.starts_with(&prefix)) {
+                    self.remove(&object.key).await;
+                }
+            }
+            Err(e) => warn!("Failed to list the files of deleted node {}: {}", node_id, e),
+        }
+    }
+
+    /// Stream a file field to the store, hashing and compressing it on the way
+    ///
+    /// Refused once the storage cap is used up.
+    async fn write_file(&self, claims: &Claims, node_id: Uuid, mut field: Field) -> Result<NodeAttachment, AppError> {
+        self.storage.ensure_capacity(StorageCategory::Uploads).await?;
+
+        let file_name = field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .map(sanitize_file_name)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| AppError::Validation("The attached file has no file name".to_string()))?;
+        let content_type = field.content_type().map(|mime| mime.to_string());
+
+        let id = Uuid::new_v4();
+        let compression = attachment_compression(content_type.as_deref());
+        let mut out = StorageWriter::create(self.store.as_ref(), &attachment_key(node_id, id), compression).await?;
+        let mut hasher = Sha256::new();
+        let written = async {
+            while let Some(chunk) = field.try_next().await? {
+                if out.original_bytes() + chunk.len() as u64 > self.max_attachment_bytes {
+                    return Err(AppError::PayloadTooLarge(format!(
+                        "Node attachments are limited to {} bytes",
+                        self.max_attachment_bytes
+                    )));
+                }
+                hasher.update(&chunk);
+                out.write(&chunk).await?;
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = written {
+            out.discard().await;
+            return Err(e);
+        }
+        let size = out.finish().await?;
+
+        Ok(NodeAttachment {
+            id,
+            node_id,
+            file_name,
+            // Assigned once the metadata is recorded
+            version: 0,
+            content_type,
+            size_bytes: size.original_bytes,
+            sha256: format!("{:x}", hasher.finalize()),
+            compression,
+            stored_bytes: size.stored_bytes,
+            uploaded_by: Some(claims.username.clone()),
+            created_at: parse_db_timestamp(&db_now()),
+        })
+    }
+
+    /// Record the metadata of a received attachment as the next version of its file
+    async fn save_attachment(&self, mut attachment: NodeAttachment) -> Result<NodeAttachment, AppError> {
+        let created_at = db_now();
+        let version: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(version), 0) + 1 FROM node_attachments WHERE node_id = ? AND file_name = ?",
+        )
+        .bind(attachment.node_id.to_string())
+        .bind(&attachment.file_name)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO node_attachments (
+                id, node_id, file_name, version, content_type, size_bytes, sha256, compression,
+                stored_bytes, uploaded_by, created_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(attachment.id.to_string())
+        .bind(attachment.node_id.to_string())
+        .bind(&attachment.file_name)
+        .bind(version)
+        .bind(&attachment.content_type)
+        .bind(attachment.size_bytes as i64)
+        .bind(&attachment.sha256)
+        .bind(attachment.compression.as_str())
+        .bind(attachment.stored_bytes as i64)
+        .bind(&attachment.uploaded_by)
+        .bind(&created_at)
+        .execute(self.db.pool())
+        .await?;
+
+        attachment.version = version as u32;
+        attachment.created_at = parse_db_timestamp(&created_at);
+        Ok(attachment)
+    }
+
+    /// Remove stored content, logging rather than failing
+    async fn remove(&self, key: &str) {
+        if let Err(e) = self.store.delete(key).await {
+            warn!("Failed to remove node file {}: {}", key, e);
+        }
+    }
+}
+
+/// Prefix of the keys of all files of a node in the store
+fn node_prefix(node_id: Uuid) -> String {
+    format!("nodes/{}/", node_id)
+}
+
+/// Key of a version of a node's notes in the store
+fn note_key(node_id: Uuid, version: u32) -> String {
+    format!("{}notes/{}", node_prefix(node_id), version)
+}
+
+/// Key of the content of an attachment in the store
+fn attachment_key(node_id: Uuid, id: Uuid) -> String {
+    format!("{}attachments/{}", node_prefix(node_id), id)
+}
+
+type NoteVersionRow = (i64, i64, String, Option<String>, String);
+
+fn note_version_from_row((version, size_bytes, sha256, author, created_at): NoteVersionRow) -> NodeNoteVersion {
+    NodeNoteVersion {
+        version: version as u32,
+        size_bytes: size_bytes as u64,
+        sha256,
+        author,
+        created_at: parse_db_timestamp(&created_at),
+    }
+}
+
+type AttachmentRow = (
+    String,
+    String,
+    String,
+    i64,
+    Option<String>,
+    i64,
+    String,
+    String,
+    i64,
+    Option<String>,
+    String,
+);
+
+fn attachment_from_row(
+    (id, node_id, file_name, version, content_type, size_bytes, sha256, compression, stored_bytes, uploaded_by, created_at): AttachmentRow,
+) -> NodeAttachment {
+    NodeAttachment {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        node_id: Uuid::parse_str(&node_id).unwrap_or_default(),
+        file_name,
+        version: version as u32,
+        content_type,
+        size_bytes: size_bytes as u64,
+        sha256,
+        compression: Compression::parse(&compression),
+        stored_bytes: stored_bytes as u64,
+        uploaded_by,
+        created_at: parse_db_timestamp(&created_at),
+    }
+}
@@ -0,0 +1,193 @@
+//! Node Health Checker
+//!
+//! Polls every node in the background, on `NODE_HEALTH_CHECK_INTERVAL_SECS`,
+//! so node status and last-seen times stay current without anyone asking
+//! for a health check. Status changes are published on the node's
+//! `node:{node_id}` channel, so the UI sees nodes go offline as it happens.
+//!
+//! Each check also compares the clock of every online node against the
+//! backend's and records the drift as the `clock_drift_seconds` metric.
+//! Drifted clocks break VPNs and log correlation, so a drift beyond
+//! `NODE_CLOCK_DRIFT_THRESHOLD_SECS` raises an alert, which is resolved once
+//! the clock is back in line.
+//!
+//! Rebooting nodes are not treated as down while they are expected back:
+//! they stay `rebooting`, raise no alerts, and go back online once they
+//! answer again and have been re-verified.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::monitoring::{Alert, AlertSeverity, AlertStatus, MetricData, MetricType, MetricUnit};
+use crate::models::node::{node_channel, NodeEvent, NodeListQuery, NodeStatus};
+use crate::models::team::AccessScope;
+use crate::services::{EventBus, MonitoringService, NodeService};
+
+/// Metric recording how many seconds a node's clock is ahead of the backend's
+pub const CLOCK_DRIFT_METRIC: &str = "clock_drift_seconds";
+
+/// Node health checker
+#[derive(Clone)]
+pub struct NodeHealthChecker {
+    nodes: NodeService,
+    events: EventBus,
+    monitoring: MonitoringService,
+    drift_threshold_secs: f64,
+}
+
+impl NodeHealthChecker {
+    /// Create a new node health checker
+    pub fn new(nodes: NodeService, events: EventBus, monitoring: MonitoringService, config: &AppConfig) -> Self {
+        Self {
+            nodes,
+            events,
+            monitoring,
+            drift_threshold_secs: config.node_clock_drift_threshold_secs as f64,
+        }
+    }
+
+    /// Check every node once and publish the status changes
+    ///
+    /// Returns the number of nodes whose status changed.
+    pub async fn check(&self) -> Result<usize, AppError> {
+        let previous: HashMap<_, _> = self
+            .nodes
+            .list_nodes(
+                NodeListQuery {
+                    page: None,
+                    page_size: Some(1000),
+                    status: None,
+                    search: None,
+                    team_id: None,
+                    custom_fields: None,
+                    sort_by: None,
+                    sort_order: None,
+                },
+                &AccessScope::All,
+            )
+            .await?
+            .nodes
+            .into_iter()
+            .map(|node| (node.id, node.status))
+            .collect();
+
+        let mut changed = 0;
+        for health in self.nodes.check_all_nodes_health().await? {
+            if health.status == NodeStatus::Online {
+                if let Err(e) = self.check_clock(health.node_id).await {
+                    warn!("Failed to check the clock of node {}: {}", health.node_id, e);
+                }
+            }
+
+            let Some(&previous) = previous.get(&health.node_id) else {
+                continue;
+            };
+            if previous == health.status {
+                continue;
+            }
+
+            info!(
+                "Node {} went from {} to {}",
+                health.node_id,
+                previous.as_str(),
+                health.status.as_str()
+            );
+            changed += 1;
+            self.events
+                .publish(
+                    &node_channel(health.node_id),
+                    &NodeEvent::StatusChanged {
+                        node_id: health.node_id,
+                        previous,
+                        status: health.status,
+                        last_check: health.last_check,
+                        error_message: health.error_message,
+                    },
+                )
+                .await;
+        }
+
+        Ok(changed)
+    }
+
+    /// Record a node's clock drift and raise or resolve its drift alert
+    ///
+    /// Nodes whose clock cannot be read, such as simulated nodes, are
+    /// skipped.
+    async fn check_clock(&self, node_id: Uuid) -> Result<(), AppError> {
+        let drift = match self.nodes.measure_clock_drift(node_id).await {
+            Ok(drift) => drift,
+            Err(e) => {
+                debug!("Could not read the clock of node {}: {}", node_id, e);
+                return Ok(());
+            }
+        };
+
+        let now = Utc::now();
+        self.monitoring
+            .record_metric(MetricData {
+                id: Uuid::new_v4(),
+                node_id: node_id.to_string(),
+                metric_name: CLOCK_DRIFT_METRIC.to_string(),
+                metric_type: MetricType::Custom,
+                value: drift,
+                unit: MetricUnit::Seconds,
+                timestamp: now,
+                labels: Vec::new(),
+                metadata: None,
+            })
+            .await;
+
+        let open = self
+            .monitoring
+            .get_alerts(Some(&node_id.to_string()), None, None)
+            .await?
+            .into_iter()
+            .find(|alert| {
+                alert.status != AlertStatus::Resolved && alert.metric_name.as_deref() == Some(CLOCK_DRIFT_METRIC)
+            });
+        match open {
+            None if drift.abs() > self.drift_threshold_secs => {
+                warn!("Clock of node {} is {:.1} seconds off", node_id, drift);
+                self.monitoring.raise_alert(self.drift_alert(node_id, drift)).await;
+            }
+            Some(alert) if drift.abs() <= self.drift_threshold_secs => {
+                self.monitoring.resolve_alert(&alert.id).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn drift_alert(&self, node_id: Uuid, drift: f64) -> Alert {
+        let now = Utc::now();
+        Alert {
+            id: Uuid::new_v4(),
+            node_id: node_id.to_string(),
+            severity: AlertSeverity::Warning,
+            title: "Clock drift".to_string(),
+            description: format!(
+                "The node's clock is {:.1} seconds {} the backend's; check its NTP configuration",
+                drift.abs(),
+                if drift > 0.0 { "ahead of" } else { "behind" }
+            ),
+            status: AlertStatus::Active,
+            metric_name: Some(CLOCK_DRIFT_METRIC.to_string()),
+            threshold_value: Some(self.drift_threshold_secs),
+            actual_value: Some(drift),
+            triggered_at: now,
+            updated_at: now,
+            acknowledged_at: None,
+            acknowledged_by: None,
+            resolved_at: None,
+            trigger_count: 1,
+            labels: Vec::new(),
+            data: None,
+        }
+    }
+}
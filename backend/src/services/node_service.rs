@@ -3,51 +3,124 @@
 //! This module provides business logic for managing VyOS nodes, including
 //! CRUD operations, health checking, and configuration retrieval.
 
+use crate::config::AppConfig;
+use crate::db::Database;
 use crate::error::AppError;
+use crate::models::api_usage::NodeApiUsage;
+use crate::models::custom_field::custom_field_text;
 use crate::models::node::{
-    CreateNodeRequest, Node, NodeHealthInfo, NodeListQuery, NodeListResponse,
-    NodeStatistics, NodeStatus, NodeTestResult, UpdateNodeRequest,
+    CachedShowResult, CreateNodeRequest, CreateSimulatedNodeRequest, Node, NodeCapabilities, NodeData,
+    NodeHealthInfo, NodeKind, NodeListQuery, NodeListResponse, NodeReboot, NodeRecordingResponse,
+    NodeStatistics, NodeStatus, NodeTestResult, NodeTransportKind, UpdateNodeRequest,
 };
-use crate::vyos_client::{VyOSClient, VyOSClientConfig, VyOSConnectionTest, VyOSInfo};
-use chrono::{DateTime, Utc};
-use sqlx::{AnyPool, Row};
+use crate::models::quota::QuotaResource;
+use crate::models::team::AccessScope;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::services::api_usage::ApiUsageTracker;
+use crate::services::chaos::FaultInjector;
+use crate::services::circuit_breaker::NodeCircuitBreaker;
+use crate::services::custom_field::CustomFieldService;
+use crate::services::quota::QuotaService;
+use crate::services::show_cache::ShowCache;
+use crate::utils::csv::csv_line;
+use crate::vyos_client::{
+    capability_matrix, parsers, Capability, ConfigOperation, NodeRecording, NodeTransport,
+    SimulatedNode, VyOSClient, VyOSClientConfig, VyOSConnectionTest, VyOSConntrackSyncStatistics, VyOSInfo,
+    VyOSInterface, VyOSLldpNeighbor, VyOSNeighbor, VyOSRelease, VyOSSshClient, VyOSVrrpGroup, VyOSWireGuardPeer,
+    PROBED_CAPABILITIES, RECORDED_SHOW_COMMANDS,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sqlx::sqlite::SqliteRow;
+use sqlx::Row;
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+/// Host stored for simulated nodes, which are not reached over the network
+const SIMULATED_NODE_HOST: &str = "simulated";
+
 /// Node service for managing VyOS nodes
 #[derive(Clone)]
 pub struct NodeService {
-    pool: AnyPool,
+    db: Database,
+    quotas: QuotaService,
+    fields: CustomFieldService,
+    breaker: NodeCircuitBreaker,
+    usage: ApiUsageTracker,
+    show_cache: ShowCache,
+    reboot_grace: Duration,
 }
 
 impl NodeService {
     /// Create a new node service
-    pub fn new(pool: AnyPool) -> Self {
-        Self { pool }
-    }
-
-    /// Create a VyOS client for a specific node
-    fn create_vyos_client(&self, node: &Node) -> Result<VyOSClient, AppError> {
-        let config = VyOSClientConfig::new(
-            node.host.clone(),
-            node.api_key.clone(),
-            node.port,
-            node.use_https,
-            node.verify_ssl,
-            node.timeout,
-        );
-        VyOSClient::new(config)
+    pub fn new(
+        db: Database,
+        quotas: QuotaService,
+        fields: CustomFieldService,
+        breaker: NodeCircuitBreaker,
+        config: &AppConfig,
+    ) -> Self {
+        Self {
+            db,
+            quotas,
+            fields,
+            breaker,
+            usage: ApiUsageTracker::new(),
+            show_cache: ShowCache::new(config),
+            reboot_grace: Duration::seconds(config.node_reboot_grace_secs as i64),
+        }
+    }
+
+    /// Create the transport reaching a specific node
+    ///
+    /// Routers are reached over the VyOS API, or through the CLI over SSH
+    /// when their transport says so; simulated nodes answer locally from
+    /// their recording. Failures injected into the node are applied on
+    /// top, and every call is counted in the node's API usage.
+    async fn transport(&self, node: &Node) -> Result<Box<dyn NodeTransport>, AppError> {
+        let transport: Box<dyn NodeTransport> = match node.kind {
+            NodeKind::Vyos => {
+                let config = VyOSClientConfig::new(
+                    node.host.clone(),
+                    node.api_key.clone(),
+                    node.port,
+                    node.use_https,
+                    node.verify_ssl,
+                    node.timeout,
+                );
+                match node.transport {
+                    NodeTransportKind::Api => Box::new(VyOSClient::new(config)?),
+                    NodeTransportKind::Ssh => {
+                        let login = node.ssh_login().ok_or_else(|| {
+                            AppError::Config(format!("Node {} has no complete SSH login", node.id))
+                        })?;
+                        Box::new(VyOSSshClient::new(config.with_ssh(login))?)
+                    }
+                }
+            }
+            NodeKind::Simulated => {
+                let recording = self.get_recording(node.id).await?;
+                Box::new(SimulatedNode::new(recording.recording))
+            }
+        };
+        Ok(self.usage.track(node.id, FaultInjector::wrap(node.id, transport)))
     }
 
     /// Query a single row and convert to Node
     async fn query_row_to_node(
         &self,
-        row: sqlx::any::AnyRow,
+        row: SqliteRow,
     ) -> Result<Node, AppError> {
         let id_str: String = row.try_get("id")?;
         let id = Uuid::parse_str(&id_str).unwrap_or_else(|_| Uuid::nil());
         let status_str: String = row.try_get("status").unwrap_or_else(|_| "offline".to_string());
         let tags_str: String = row.try_get("tags").unwrap_or_else(|_| "[]".to_string());
+        let team_id: Option<String> = row.try_get("team_id")?;
+        let kind: String = row.try_get("kind")?;
+        let transport: String = row.try_get("transport")?;
         let created_at_str: String = row.try_get("created_at")?;
         let updated_at_str: String = row.try_get("updated_at")?;
 
@@ -60,20 +133,23 @@ impl NodeService {
             api_key: row.try_get("api_key")?,
             status: parse_node_status(&status_str),
             last_seen: row.try_get::<Option<String>, _>("last_seen")?
-                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                .map(|dt| dt.with_timezone(&Utc)),
+                .map(|s| parse_db_timestamp(&s)),
             version: row.try_get("version")?,
             uptime: row.try_get::<Option<i64>, _>("uptime")?.map(|u| u as u64),
             use_https: row.try_get::<i64, _>("use_https")? != 0,
             verify_ssl: row.try_get::<i64, _>("verify_ssl")? != 0,
             tags: serde_json::from_str(&tags_str).unwrap_or_default(),
             timeout: row.try_get::<i64, _>("timeout")? as u64,
-            created_at: DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            updated_at: DateTime::parse_from_rfc3339(&updated_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
+            team_id: team_id.and_then(|id| Uuid::parse_str(&id).ok()),
+            kind: NodeKind::from_db(&kind),
+            transport: NodeTransportKind::from_db(&transport),
+            ssh_port: row.try_get::<Option<i64>, _>("ssh_port")?.map(|port| port as u16),
+            ssh_username: row.try_get("ssh_username")?,
+            ssh_password: row.try_get("ssh_password")?,
+            ssh_private_key: row.try_get("ssh_private_key")?,
+            custom_fields: self.fields.node_values(id).await?,
+            created_at: parse_db_timestamp(&created_at_str),
+            updated_at: parse_db_timestamp(&updated_at_str),
         })
     }
 
@@ -82,29 +158,20 @@ impl NodeService {
     // ========================================================================
 
     /// List all nodes with optional filtering and pagination
-    pub async fn list_nodes(&self, query: NodeListQuery) -> Result<NodeListResponse, AppError> {
+    ///
+    /// Only nodes within the caller's access scope are returned.
+    pub async fn list_nodes(
+        &self,
+        query: NodeListQuery,
+        scope: &AccessScope,
+    ) -> Result<NodeListResponse, AppError> {
         debug!("Listing nodes with query: {:?}", query);
 
         let page = query.page.unwrap_or(1).max(1);
         let page_size = query.page_size.unwrap_or(20).min(100);
         let offset = (page - 1) * page_size;
 
-        let mut where_clauses = vec!["1=1".to_string()];
-        let mut bind_values: Vec<String> = vec![];
-
-        if let Some(status) = query.status {
-            where_clauses.push("status = ?".to_string());
-            bind_values.push(status.to_string().to_lowercase());
-        }
-
-        if let Some(search) = &query.search {
-            where_clauses.push("(name LIKE ? OR description LIKE ? OR host LIKE ?)".to_string());
-            bind_values.push(format!("%{}%", search));
-            bind_values.push(format!("%{}%", search));
-            bind_values.push(format!("%{}%", search));
-        }
-
-        let where_clause = where_clauses.join(" AND ");
+        let (where_clause, bind_values) = self.node_filter(&query, scope).await?;
 
         // Count query
         let count_query = format!("SELECT COUNT(*) FROM nodes WHERE {}", where_clause);
@@ -112,14 +179,15 @@ impl NodeService {
         for value in &bind_values {
             count_query_builder = count_query_builder.bind(value);
         }
-        let total = count_query_builder.fetch_one(&self.pool).await? as u64;
+        let total = count_query_builder.fetch_one(self.db.pool()).await? as u64;
 
         // Data query with sorting
         let sort_by = query.sort_by.unwrap_or_else(|| "name".to_string());
         let sort_order = query.sort_order.unwrap_or_else(|| "asc".to_string());
         let data_query = format!(
             "SELECT id, name, description, host, port, api_key, status, last_seen, version, uptime,
-             use_https, verify_ssl, tags, timeout, created_at, updated_at
+             use_https, verify_ssl, tags, timeout, team_id, kind, transport,
+             ssh_port, ssh_username, ssh_password, ssh_private_key, created_at, updated_at
              FROM nodes WHERE {} ORDER BY {} {} LIMIT ? OFFSET ?",
             where_clause, sort_by, sort_order
         );
@@ -130,7 +198,7 @@ impl NodeService {
         }
         rows_builder = rows_builder.bind(page_size as i64).bind(offset as i64);
 
-        let rows_result = rows_builder.fetch_all(&self.pool).await?;
+        let rows_result = rows_builder.fetch_all(self.db.pool()).await?;
 
         let mut nodes = vec![];
         for row in rows_result {
@@ -148,20 +216,110 @@ impl NodeService {
         })
     }
 
+    /// Export the nodes matching the list filters as CSV, one row per node
+    ///
+    /// Paging parameters are ignored. Custom fields get a column each, after
+    /// the built-in columns; API keys are left out.
+    pub async fn export_nodes(&self, query: NodeListQuery, scope: &AccessScope) -> Result<String, AppError> {
+        let (where_clause, bind_values) = self.node_filter(&query, scope).await?;
+        let data_query = format!(
+            "SELECT id, name, description, host, port, api_key, status, last_seen, version, uptime,
+             use_https, verify_ssl, tags, timeout, team_id, kind, transport,
+             ssh_port, ssh_username, ssh_password, ssh_private_key, created_at, updated_at
+             FROM nodes WHERE {} ORDER BY name",
+            where_clause
+        );
+        let mut rows_builder = sqlx::query(&data_query);
+        for value in &bind_values {
+            rows_builder = rows_builder.bind(value);
+        }
+        let rows = rows_builder.fetch_all(self.db.pool()).await?;
+
+        let fields = self.fields.list_fields().await?;
+        let mut header: Vec<String> = [
+            "id", "name", "description", "host", "port", "status", "version", "last_seen", "tags", "team_id",
+            "kind", "created_at",
+        ]
+        .iter()
+        .map(|column| column.to_string())
+        .collect();
+        header.extend(fields.iter().map(|field| field.name.clone()));
+
+        let mut csv = csv_line(&header);
+        for row in rows {
+            let node = self.query_row_to_node(row).await?;
+            let mut record = vec![
+                node.id.to_string(),
+                node.name,
+                node.description.unwrap_or_default(),
+                node.host,
+                node.port.to_string(),
+                node.status.as_str().to_string(),
+                node.version.unwrap_or_default(),
+                node.last_seen.map(|t| format_timestamp(&t)).unwrap_or_default(),
+                node.tags.join(";"),
+                node.team_id.map(|id| id.to_string()).unwrap_or_default(),
+                node.kind.as_str().to_string(),
+                format_timestamp(&node.created_at),
+            ];
+            record.extend(fields.iter().map(|field| custom_field_text(node.custom_fields.get(&field.name))));
+            csv.push_str(&csv_line(&record));
+        }
+
+        Ok(csv)
+    }
+
+    /// WHERE clause and bound values selecting the nodes matching the list
+    /// filters within an access scope
+    async fn node_filter(&self, query: &NodeListQuery, scope: &AccessScope) -> Result<(String, Vec<String>), AppError> {
+        let mut where_clauses = vec!["1=1".to_string()];
+        let mut bind_values: Vec<String> = vec![];
+
+        if let Some(status) = query.status {
+            where_clauses.push("status = ?".to_string());
+            bind_values.push(status.to_string().to_lowercase());
+        }
+
+        if let Some(search) = &query.search {
+            where_clauses.push("(name LIKE ? OR description LIKE ? OR host LIKE ?)".to_string());
+            bind_values.push(format!("%{}%", search));
+            bind_values.push(format!("%{}%", search));
+            bind_values.push(format!("%{}%", search));
+        }
+
+        if let Some(team_id) = query.team_id {
+            where_clauses.push("team_id = ?".to_string());
+            bind_values.push(team_id.to_string());
+        }
+
+        if let Some(filter) = &query.custom_fields {
+            let (clauses, values) = self.fields.filter_clauses(filter).await?;
+            where_clauses.extend(clauses);
+            bind_values.extend(values);
+        }
+
+        let (clause, values) = scope_filter(scope);
+        where_clauses.push(clause);
+        bind_values.extend(values);
+
+        Ok((where_clauses.join(" AND "), bind_values))
+    }
+
     /// Get a node by ID
     pub async fn get_node(&self, node_id: Uuid) -> Result<Option<Node>, AppError> {
         debug!("Getting node: {}", node_id);
 
         let query = r#"
             SELECT id, name, description, host, port, api_key, status, last_seen, version, uptime,
-                   use_https, verify_ssl, tags, timeout, created_at, updated_at
+                   use_https, verify_ssl, tags, timeout, team_id, kind, transport,
+             ssh_port, ssh_username, ssh_password, ssh_private_key, created_at, updated_at
             FROM nodes
             WHERE id = ?
         "#;
 
         let row = sqlx::query(query)
             .bind(node_id.to_string())
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.db.pool())
             .await?;
 
         match row {
@@ -176,14 +334,15 @@ impl NodeService {
 
         let query = r#"
             SELECT id, name, description, host, port, api_key, status, last_seen, version, uptime,
-                   use_https, verify_ssl, tags, timeout, created_at, updated_at
+                   use_https, verify_ssl, tags, timeout, team_id, kind, transport,
+             ssh_port, ssh_username, ssh_password, ssh_private_key, created_at, updated_at
             FROM nodes
             WHERE name = ?
         "#;
 
         let row = sqlx::query(query)
             .bind(name)
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.db.pool())
             .await?;
 
         match row {
@@ -192,11 +351,74 @@ impl NodeService {
         }
     }
 
+    /// Get all nodes, regardless of access scope
+    pub async fn list_all_nodes(&self) -> Result<Vec<Node>, AppError> {
+        let query = r#"
+            SELECT id, name, description, host, port, api_key, status, last_seen, version, uptime,
+                   use_https, verify_ssl, tags, timeout, team_id, kind, transport,
+             ssh_port, ssh_username, ssh_password, ssh_private_key, created_at, updated_at
+            FROM nodes
+            ORDER BY name
+        "#;
+
+        let rows = sqlx::query(query).fetch_all(self.db.pool()).await?;
+
+        let mut nodes = vec![];
+        for row in rows {
+            nodes.push(self.query_row_to_node(row).await?);
+        }
+        Ok(nodes)
+    }
+
+    /// Nodes a change to many nodes applies to: those given, which must be in
+    /// the access scope, or every node in it
+    pub async fn fleet_nodes(&self, scope: &AccessScope, node_ids: Option<&[Uuid]>) -> Result<Vec<Node>, AppError> {
+        let Some(node_ids) = node_ids else {
+            let nodes = self.list_all_nodes().await?;
+            return Ok(nodes.into_iter().filter(|node| scope.can_access(node.team_id)).collect());
+        };
+
+        let mut nodes = Vec::with_capacity(node_ids.len());
+        for node_id in node_ids.iter().collect::<BTreeSet<_>>() {
+            let node = self
+                .get_node(*node_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+            if !scope.can_access(node.team_id) {
+                return Err(AppError::Forbidden(format!("Node {} is owned by another team", node_id)));
+            }
+            nodes.push(node);
+        }
+        Ok(nodes)
+    }
+
     /// Create a new node
     pub async fn create_node(&self, request: CreateNodeRequest) -> Result<Node, AppError> {
+        self.create_node_with_id(Uuid::new_v4(), request).await
+    }
+
+    /// Create a new node with a caller-chosen ID
+    ///
+    /// Used for fixtures that need stable node IDs.
+    pub async fn create_node_with_id(&self, id: Uuid, request: CreateNodeRequest) -> Result<Node, AppError> {
         info!("Creating node: {}", request.name);
 
-        let id = Uuid::new_v4();
+        self.insert_node(id, request, None).await
+    }
+
+    /// Insert a node, with the recording it answers from for simulated nodes
+    async fn insert_node(
+        &self,
+        id: Uuid,
+        request: CreateNodeRequest,
+        recording: Option<(&NodeRecording, Option<Uuid>)>,
+    ) -> Result<Node, AppError> {
+
+        self.quotas
+            .ensure_capacity(request.team_id, QuotaResource::Nodes)
+            .await?;
+        let custom_fields = self.fields.check_values(&request.custom_fields, true).await?;
+
         let now = Utc::now();
 
         // Use defaults from request
@@ -207,28 +429,62 @@ impl NodeService {
         let tags = request.tags.unwrap_or_default();
         let tags_json = serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string());
 
+        let kind = match recording {
+            Some(_) => NodeKind::Simulated,
+            None => NodeKind::Vyos,
+        };
+        let transport = request.transport.unwrap_or_default();
+        check_ssh_login(
+            transport,
+            request.ssh_username.as_deref(),
+            request.ssh_password.is_some() || request.ssh_private_key.is_some(),
+        )?;
+
         let query = r#"
             INSERT INTO nodes (id, name, description, host, port, api_key, status, use_https,
-                              verify_ssl, tags, timeout, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                              verify_ssl, tags, timeout, team_id, kind, transport, ssh_port, ssh_username,
+                              ssh_password, ssh_private_key, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#;
 
+        let mut tx = self.db.pool().begin().await?;
         sqlx::query(query)
             .bind(id.to_string())
             .bind(&request.name)
             .bind(&request.description)
             .bind(&request.host)
-            .bind(port)
+            .bind(port as i64)
             .bind(&request.api_key)
             .bind(NodeStatus::Offline.to_string())
             .bind(use_https)
             .bind(verify_ssl)
             .bind(&tags_json)
-            .bind(timeout)
-            .bind(now.to_rfc3339())
-            .bind(now.to_rfc3339())
-            .execute(&self.pool)
+            .bind(timeout as i64)
+            .bind(request.team_id.map(|id| id.to_string()))
+            .bind(kind.as_str())
+            .bind(transport.as_str())
+            .bind(request.ssh_port.map(i64::from))
+            .bind(&request.ssh_username)
+            .bind(&request.ssh_password)
+            .bind(&request.ssh_private_key)
+            .bind(format_timestamp(&now))
+            .bind(format_timestamp(&now))
+            .execute(&mut *tx)
+            .await?;
+        self.fields.write_values(&mut tx, id, &custom_fields).await?;
+
+        if let Some((recording, recorded_from)) = recording {
+            sqlx::query(
+                "INSERT INTO node_recordings (node_id, recording, recorded_from, recorded_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(id.to_string())
+            .bind(serde_json::to_string(recording)?)
+            .bind(recorded_from.map(|id| id.to_string()))
+            .bind(format_timestamp(&now))
+            .execute(&mut *tx)
             .await?;
+        }
+        tx.commit().await?;
 
         // Fetch the created node
         self.get_node(id).await?
@@ -243,8 +499,25 @@ impl NodeService {
     ) -> Result<Node, AppError> {
         info!("Updating node: {}", node_id);
 
+        let custom_fields = self.fields.check_values(&request.custom_fields, false).await?;
+
+        if request.transport.is_some() || request.ssh_username.is_some() {
+            let node = self
+                .get_node(node_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+            check_ssh_login(
+                request.transport.unwrap_or(node.transport),
+                request.ssh_username.as_deref().or(node.ssh_username.as_deref()),
+                request.ssh_password.is_some()
+                    || request.ssh_private_key.is_some()
+                    || node.ssh_password.is_some()
+                    || node.ssh_private_key.is_some(),
+            )?;
+        }
+
         // Build update query dynamically
-        let mut updates = vec!["updated_at = ?".to_string()];
+        let mut updates: Vec<&str> = vec![];
         let mut params: Vec<String> = vec![];
 
         if let Some(name) = &request.name {
@@ -284,9 +557,29 @@ impl NodeService {
             updates.push("tags = ?");
             params.push(tags_json);
         }
+        if let Some(transport) = request.transport {
+            updates.push("transport = ?");
+            params.push(transport.as_str().to_string());
+        }
+        if let Some(ssh_port) = request.ssh_port {
+            updates.push("ssh_port = ?");
+            params.push(ssh_port.to_string());
+        }
+        if let Some(ssh_username) = &request.ssh_username {
+            updates.push("ssh_username = ?");
+            params.push(ssh_username.clone());
+        }
+        if let Some(ssh_password) = &request.ssh_password {
+            updates.push("ssh_password = ?");
+            params.push(ssh_password.clone());
+        }
+        if let Some(ssh_private_key) = &request.ssh_private_key {
+            updates.push("ssh_private_key = ?");
+            params.push(ssh_private_key.clone());
+        }
 
         updates.push("updated_at = ?");
-        params.push(Utc::now().to_rfc3339());
+        params.push(db_now());
 
         let set_clause = updates.join(", ");
         let query = format!(
@@ -300,7 +593,14 @@ impl NodeService {
         }
         query_builder = query_builder.bind(node_id.to_string());
 
-        query_builder.execute(&self.pool).await?;
+        let mut tx = self.db.pool().begin().await?;
+        query_builder.execute(&mut *tx).await?;
+        self.fields.write_values(&mut tx, node_id, &custom_fields).await?;
+        tx.commit().await?;
+
+        // The node may now point at a different router
+        self.clear_capabilities(node_id).await?;
+        self.show_cache.forget(node_id);
 
         // Get the updated node
         self.get_node(node_id)
@@ -308,24 +608,66 @@ impl NodeService {
             .ok_or_else(|| AppError::NotFound(format!("Node {} not found after update", node_id)))
     }
 
+    /// Change the team owning a node
+    pub async fn set_node_owner(&self, node_id: Uuid, team_id: Option<Uuid>) -> Result<Node, AppError> {
+        info!("Setting owner of node {} to {:?}", node_id, team_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+        if node.team_id != team_id {
+            self.quotas.ensure_capacity(team_id, QuotaResource::Nodes).await?;
+        }
+
+        let query = "UPDATE nodes SET team_id = ?, updated_at = ? WHERE id = ?";
+        let result = sqlx::query(query)
+            .bind(team_id.map(|id| id.to_string()))
+            .bind(db_now())
+            .bind(node_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Node {} not found", node_id)));
+        }
+
+        self.get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))
+    }
+
     /// Delete a node
     pub async fn delete_node(&self, node_id: Uuid) -> Result<(), AppError> {
         info!("Deleting node: {}", node_id);
 
+        self.clear_capabilities(node_id).await?;
+
         let query = "DELETE FROM nodes WHERE id = ?";
         let result = sqlx::query(query)
             .bind(node_id.to_string())
-            .execute(&self.pool)
+            .execute(self.db.pool())
             .await?;
 
         if result.rows_affected() == 0 {
             return Err(AppError::NotFound(format!("Node {} not found", node_id)));
         }
 
+        self.usage.forget(node_id);
+        self.show_cache.forget(node_id);
         info!("Node deleted successfully: {}", node_id);
         Ok(())
     }
 
+    /// VyOS API calls made to a node since the backend started
+    pub async fn get_api_usage(&self, node_id: Uuid) -> Result<NodeApiUsage, AppError> {
+        self.get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        Ok(self.usage.usage(node_id))
+    }
+
     // ========================================================================
     // Health Checking
     // ========================================================================
@@ -339,11 +681,26 @@ impl NodeService {
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
 
+        // A rebooting node is verified by the health check once it is back
+        if let Some(reboot) = self.get_reboot(node_id).await? {
+            return Ok(NodeTestResult {
+                success: false,
+                message: rebooting_error(&reboot).to_string(),
+                latency_ms: None,
+                version: None,
+                hostname: None,
+                uptime: None,
+            });
+        }
+
         // Update node status to testing
         self.update_node_status(node_id, NodeStatus::Testing).await?;
 
-        let vyos_client = self.create_vyos_client(&node)?;
-        let test_result = vyos_client.test_connection().await?;
+        let transport = self.transport(&node).await?;
+        let test_result = transport.test_connection().await?;
+        if test_result.success {
+            self.breaker.record_success(node_id);
+        }
 
         // Update node status based on test result
         let new_status = if test_result.success {
@@ -393,9 +750,9 @@ impl NodeService {
 
         sqlx::query(query)
             .bind(status.to_string())
-            .bind(now.to_rfc3339())
+            .bind(format_timestamp(&now))
             .bind(node_id.to_string())
-            .execute(&self.pool)
+            .execute(self.db.pool())
             .await?;
 
         Ok(())
@@ -418,29 +775,28 @@ impl NodeService {
 
         sqlx::query(query)
             .bind(version.unwrap_or_default())
-            .bind(uptime.unwrap_or(0))
-            .bind(now.to_rfc3339())
-            .bind(now.to_rfc3339())
+            .bind(uptime.unwrap_or(0) as i64)
+            .bind(format_timestamp(&now))
+            .bind(format_timestamp(&now))
             .bind(node_id.to_string())
-            .execute(&self.pool)
+            .execute(self.db.pool())
             .await?;
 
         Ok(())
     }
 
-    /// Get health information for all nodes
-    pub async fn get_all_nodes_health(&self) -> Result<Vec<NodeHealthInfo>, AppError> {
+    /// Get health information for all nodes in an access scope
+    pub async fn get_all_nodes_health(&self, scope: &AccessScope) -> Result<Vec<NodeHealthInfo>, AppError> {
         debug!("Getting health information for all nodes");
 
-        let query = r#"
-            SELECT id, status, updated_at
-            FROM nodes
-            ORDER BY name
-        "#;
+        let (clause, values) = scope_filter(scope);
+        let query = format!("SELECT id, status, updated_at FROM nodes WHERE {} ORDER BY name", clause);
 
-        let rows = sqlx::query(query)
-            .fetch_all(&self.pool)
-            .await?;
+        let mut rows = sqlx::query(&query);
+        for value in &values {
+            rows = rows.bind(value);
+        }
+        let rows = rows.fetch_all(self.db.pool()).await?;
 
         let now = Utc::now();
         let mut health_infos = vec![];
@@ -479,7 +835,7 @@ impl NodeService {
 
         let row = sqlx::query(query)
             .bind(node_id.to_string())
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.db.pool())
             .await?;
 
         let row = row.ok_or_else(|| {
@@ -513,14 +869,34 @@ impl NodeService {
             page_size: Some(1000),
             status: None,
             search: None,
+            team_id: None,
+            custom_fields: None,
             sort_by: None,
             sort_order: None,
-        }).await?;
+        }, &AccessScope::All).await?;
 
         let mut health_infos = vec![];
 
         for node in nodes.nodes {
             let node_id = node.id;
+            if let Some(reboot) = self.get_reboot(node_id).await? {
+                let health = match self.check_reboot(&node, &reboot).await {
+                    Ok(health) => health,
+                    Err(e) => {
+                        warn!("Failed to check on rebooting node {}: {}", node_id, e);
+                        NodeHealthInfo {
+                            node_id,
+                            status: NodeStatus::Rebooting,
+                            last_check: Utc::now(),
+                            latency_ms: None,
+                            error_message: Some(e.to_string()),
+                        }
+                    }
+                };
+                health_infos.push(health);
+                continue;
+            }
+
             match self.test_connection(node_id).await {
                 Ok(result) => {
                     health_infos.push(NodeHealthInfo {
@@ -541,6 +917,10 @@ impl NodeService {
                 }
                 Err(e) => {
                     warn!("Health check failed for node {}: {}", node_id, e);
+                    // Do not leave the node marked as being tested
+                    if let Err(e) = self.update_node_status(node_id, NodeStatus::Error).await {
+                        warn!("Failed to update status of node {}: {}", node_id, e);
+                    }
                     health_infos.push(NodeHealthInfo {
                         node_id,
                         status: NodeStatus::Error,
@@ -555,6 +935,214 @@ impl NodeService {
         Ok(health_infos)
     }
 
+    /// Seconds a node's clock is ahead of the backend's, negative if behind
+    ///
+    /// The node's time is read with `show date utc` and compared against the
+    /// middle of the round trip, so the result is accurate to about a second.
+    pub async fn measure_clock_drift(&self, node_id: Uuid) -> Result<f64, AppError> {
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        self.require_capability(&node, Capability::Show).await?;
+        let transport = self.transport(&node).await?;
+        let sent_at = Utc::now();
+        let result = transport.show("show date utc").await?;
+        let received_at = Utc::now();
+
+        let node_time = parsers::parse_show_date(&result.output).ok_or_else(|| {
+            AppError::ExternalApi("VyOS API error: unrecognized show date output".to_string())
+        })?;
+        let reference = sent_at + (received_at - sent_at) / 2;
+        Ok((node_time - reference).num_milliseconds() as f64 / 1000.0)
+    }
+
+    // ========================================================================
+    // Reboots
+    // ========================================================================
+
+    /// Reboot a node
+    ///
+    /// Routers often drop the connection before answering, so a dropped
+    /// connection counts as the reboot having started. The node is then
+    /// marked rebooting: it is not contacted, reads are served from the
+    /// cache, and health checks wait for it to answer again.
+    pub async fn reboot_node(&self, node_id: Uuid, requested_by: &str) -> Result<NodeReboot, AppError> {
+        info!("Rebooting node: {}", node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        self.require_capability(&node, Capability::Reboot).await?;
+        if let Some(error) = self.unavailable(node_id).await? {
+            return Err(error);
+        }
+
+        let transport = self.transport(&node).await?;
+        match transport.reboot().await {
+            Ok(()) => {}
+            Err(e) if NodeCircuitBreaker::is_transport_error(&e) => {
+                info!("Node {} dropped the connection while rebooting: {}", node_id, e);
+            }
+            Err(e) => return Err(e),
+        }
+
+        self.begin_reboot(node_id, "reboot", Some(requested_by)).await
+    }
+
+    /// Mark a node as rebooting after an operation took it down
+    ///
+    /// The node is expected to answer again within the reboot grace period.
+    pub async fn begin_reboot(
+        &self,
+        node_id: Uuid,
+        operation: &str,
+        requested_by: Option<&str>,
+    ) -> Result<NodeReboot, AppError> {
+        let started_at = Utc::now();
+        let reboot = NodeReboot {
+            node_id,
+            operation: operation.to_string(),
+            requested_by: requested_by.map(str::to_string),
+            started_at,
+            expected_back_by: started_at + self.reboot_grace,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO node_reboots (node_id, operation, requested_by, started_at, expected_back_by)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (node_id) DO UPDATE SET
+                operation = excluded.operation,
+                requested_by = excluded.requested_by,
+                started_at = excluded.started_at,
+                expected_back_by = excluded.expected_back_by
+            "#,
+        )
+        .bind(node_id.to_string())
+        .bind(&reboot.operation)
+        .bind(&reboot.requested_by)
+        .bind(format_timestamp(&reboot.started_at))
+        .bind(format_timestamp(&reboot.expected_back_by))
+        .execute(self.db.pool())
+        .await?;
+        self.update_node_status(node_id, NodeStatus::Rebooting).await?;
+        self.show_cache.forget(node_id);
+
+        info!(
+            "Node {} is rebooting ({}), expected back by {}",
+            node_id,
+            reboot.operation,
+            format_timestamp(&reboot.expected_back_by)
+        );
+        Ok(reboot)
+    }
+
+    /// Reboot of a node in progress, if any
+    pub async fn get_reboot(&self, node_id: Uuid) -> Result<Option<NodeReboot>, AppError> {
+        let row: Option<(String, Option<String>, String, String)> = sqlx::query_as(
+            "SELECT operation, requested_by, started_at, expected_back_by FROM node_reboots WHERE node_id = ?",
+        )
+        .bind(node_id.to_string())
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        Ok(row.map(|(operation, requested_by, started_at, expected_back_by)| NodeReboot {
+            node_id,
+            operation,
+            requested_by,
+            started_at: parse_db_timestamp(&started_at),
+            expected_back_by: parse_db_timestamp(&expected_back_by),
+        }))
+    }
+
+    async fn clear_reboot(&self, node_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM node_reboots WHERE node_id = ?")
+            .bind(node_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Check whether a rebooting node is back
+    ///
+    /// A node answering again is re-verified: its version and uptime are
+    /// recorded and its capabilities probed again, as it may have booted a
+    /// new image. Until the grace period runs out, a node that does not
+    /// answer stays rebooting rather than failing.
+    async fn check_reboot(&self, node: &Node, reboot: &NodeReboot) -> Result<NodeHealthInfo, AppError> {
+        let transport = self.transport(node).await?;
+        let test = transport.test_connection().await.ok().filter(|test| test.success);
+        let now = Utc::now();
+
+        if let Some(test) = test {
+            info!("Node {} is back from {}", node.id, reboot.operation);
+            self.clear_reboot(node.id).await?;
+            self.breaker.record_success(node.id);
+            self.update_node_status(node.id, NodeStatus::Online).await?;
+            self.update_node_metadata(node.id, test.version, test.uptime).await?;
+            if let Err(e) = self.probe_capabilities(node.id).await {
+                warn!("Failed to probe capabilities of node {} after {}: {}", node.id, reboot.operation, e);
+            }
+            return Ok(NodeHealthInfo {
+                node_id: node.id,
+                status: NodeStatus::Online,
+                last_check: now,
+                latency_ms: test.latency_ms,
+                error_message: None,
+            });
+        }
+
+        if now < reboot.expected_back_by {
+            debug!("Node {} is still rebooting", node.id);
+            return Ok(NodeHealthInfo {
+                node_id: node.id,
+                status: NodeStatus::Rebooting,
+                last_check: now,
+                latency_ms: None,
+                error_message: None,
+            });
+        }
+
+        let message = format!(
+            "Node did not come back within {} seconds of the {}",
+            self.reboot_grace.num_seconds(),
+            reboot.operation
+        );
+        warn!("{}: {}", node.id, message);
+        self.clear_reboot(node.id).await?;
+        self.update_node_status(node.id, NodeStatus::Error).await?;
+        Ok(NodeHealthInfo {
+            node_id: node.id,
+            status: NodeStatus::Error,
+            last_check: now,
+            latency_ms: None,
+            error_message: Some(message),
+        })
+    }
+
+    /// Why a node must not be contacted right now, if it must not
+    ///
+    /// Rebooting nodes are left alone until they are back, and nodes whose
+    /// circuit is open until their next attempt.
+    async fn unavailable(&self, node_id: Uuid) -> Result<Option<AppError>, AppError> {
+        if let Some(reboot) = self.get_reboot(node_id).await? {
+            return Ok(Some(rebooting_error(&reboot)));
+        }
+
+        Ok(self.breaker.open_until(node_id).map(|until| {
+            AppError::HttpClient(format!(
+                "Node {} is unreachable, next attempt at {}",
+                node_id,
+                format_timestamp(&until)
+            ))
+        }))
+    }
+
     // ========================================================================
     // Configuration Retrieval
     // ========================================================================
@@ -564,7 +1152,7 @@ impl NodeService {
         &self,
         node_id: Uuid,
         path: Option<String>,
-    ) -> Result<serde_json::Value, AppError> {
+    ) -> Result<NodeData<serde_json::Value>, AppError> {
         info!("Retrieving configuration for node: {}, path: {:?}", node_id, path);
 
         let node = self
@@ -572,12 +1160,51 @@ impl NodeService {
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
 
-        let vyos_client = self.create_vyos_client(&node)?;
-        vyos_client.retrieve_config(path).await
+        self.require_capability(&node, Capability::Retrieve).await?;
+        let transport = self.transport(&node).await?;
+        let key = match &path {
+            Some(path) => format!("config:{}", path),
+            None => "config".to_string(),
+        };
+        self.read_node_data(node_id, &key, || transport.retrieve_config(path)).await
+    }
+
+    /// Apply and commit a batch of configuration operations on a node
+    ///
+    /// Unlike reads, changes are never served from the cache: a node that is
+    /// rebooting or whose circuit is open is not contacted and the change
+    /// fails.
+    pub async fn configure_node(&self, node_id: Uuid, operations: &[ConfigOperation]) -> Result<(), AppError> {
+        info!("Applying {} configuration operations to node: {}", operations.len(), node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        self.require_capability(&node, Capability::Configure).await?;
+        if let Some(error) = self.unavailable(node_id).await? {
+            return Err(error);
+        }
+
+        let transport = self.transport(&node).await?;
+        match transport.configure(operations).await {
+            Ok(()) => {
+                self.breaker.record_success(node_id);
+                self.show_cache.forget(node_id);
+                Ok(())
+            }
+            Err(e) => {
+                if NodeCircuitBreaker::is_transport_error(&e) {
+                    self.breaker.record_failure(node_id);
+                }
+                Err(e)
+            }
+        }
     }
 
     /// Get system information from a node
-    pub async fn get_node_info(&self, node_id: Uuid) -> Result<VyOSInfo, AppError> {
+    pub async fn get_node_info(&self, node_id: Uuid) -> Result<NodeData<VyOSInfo>, AppError> {
         info!("Getting system info for node: {}", node_id);
 
         let node = self
@@ -585,21 +1212,152 @@ impl NodeService {
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
 
-        let vyos_client = self.create_vyos_client(&node)?;
-        let info = vyos_client.get_info().await?;
+        let transport = self.transport(&node).await?;
+        let info = self.read_node_data(node_id, "info", || transport.get_info()).await?;
 
         // Update node metadata
+        if !info.stale {
+            self.update_node_metadata(
+                node_id,
+                Some(info.data.version.clone()),
+                Some(info.data.uptime_seconds),
+            ).await?;
+        }
+
+        Ok(info)
+    }
+
+    /// Get the API features supported by a node
+    ///
+    /// Returns the cached result of the last probe, probing the node if it has
+    /// not been probed yet.
+    pub async fn get_node_capabilities(&self, node_id: Uuid) -> Result<NodeCapabilities, AppError> {
+        match self.cached_capabilities(node_id).await? {
+            Some(capabilities) => Ok(capabilities),
+            None => self.probe_capabilities(node_id).await,
+        }
+    }
+
+    /// Probe a node for the API features it supports and cache the result
+    ///
+    /// The node's version determines the baseline; endpoints in
+    /// [`PROBED_CAPABILITIES`] are then checked on the node itself.
+    pub async fn probe_capabilities(&self, node_id: Uuid) -> Result<NodeCapabilities, AppError> {
+        info!("Probing capabilities of node: {}", node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        let transport = self.transport(&node).await?;
+        let info = transport.get_info().await?;
         self.update_node_metadata(
             node_id,
             Some(info.version.clone()),
             Some(info.uptime_seconds),
         ).await?;
 
-        Ok(info)
+        let release = VyOSRelease::from_version(&info.version);
+        let mut capabilities = capability_matrix(release);
+        for capability in PROBED_CAPABILITIES {
+            let supported = transport.supports(*capability).await?;
+            capabilities.insert(*capability, supported);
+        }
+
+        let result = NodeCapabilities {
+            node_id,
+            version: info.version,
+            release,
+            capabilities,
+            checked_at: Utc::now(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO node_capabilities (node_id, version, release, capabilities, checked_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (node_id) DO UPDATE SET
+                version = excluded.version,
+                release = excluded.release,
+                capabilities = excluded.capabilities,
+                checked_at = excluded.checked_at
+            "#,
+        )
+        .bind(node_id.to_string())
+        .bind(&result.version)
+        .bind(result.release.map(|r| r.as_str()))
+        .bind(serde_json::to_string(&result.capabilities)?)
+        .bind(format_timestamp(&result.checked_at))
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Cached capabilities of a node, if it has been probed
+    async fn cached_capabilities(&self, node_id: Uuid) -> Result<Option<NodeCapabilities>, AppError> {
+        let row = sqlx::query(
+            "SELECT version, release, capabilities, checked_at FROM node_capabilities WHERE node_id = ?",
+        )
+        .bind(node_id.to_string())
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let release: Option<String> = row.try_get("release")?;
+        let capabilities: String = row.try_get("capabilities")?;
+        let checked_at: String = row.try_get("checked_at")?;
+
+        Ok(Some(NodeCapabilities {
+            node_id,
+            version: row.try_get("version")?,
+            release: release.as_deref().and_then(VyOSRelease::from_name),
+            capabilities: serde_json::from_str(&capabilities)?,
+            checked_at: parse_db_timestamp(&checked_at),
+        }))
+    }
+
+    /// Drop the cached capabilities of a node
+    async fn clear_capabilities(&self, node_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM node_capabilities WHERE node_id = ?")
+            .bind(node_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fail with a clear error if a node is known not to support a capability
+    ///
+    /// Nodes that have not been probed yet are given the benefit of the doubt.
+    async fn require_capability(&self, node: &Node, capability: Capability) -> Result<(), AppError> {
+        match self.cached_capabilities(node.id).await? {
+            Some(capabilities) if !capabilities.supports(capability) => {
+                Err(AppError::Unsupported(format!(
+                    "{} is not supported on node {} (VyOS {})",
+                    capability, node.name, capabilities.version
+                )))
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Get network interfaces from a node
-    pub async fn get_node_interfaces(&self, node_id: Uuid) -> Result<Vec<crate::vyos_client::VyOSInterface>, AppError> {
+    pub async fn get_node_interfaces(&self, node_id: Uuid) -> Result<NodeData<Vec<VyOSInterface>>, AppError> {
+        self.read_node_interfaces(node_id, false).await
+    }
+
+    /// Get network interfaces from a node, asking the node even if they are
+    /// cached with `bypass_cache`
+    pub async fn read_node_interfaces(
+        &self,
+        node_id: Uuid,
+        bypass_cache: bool,
+    ) -> Result<NodeData<Vec<VyOSInterface>>, AppError> {
         info!("Getting interfaces for node: {}", node_id);
 
         let node = self
@@ -607,16 +1365,142 @@ impl NodeService {
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
 
-        let vyos_client = self.create_vyos_client(&node)?;
-        vyos_client.get_interfaces().await
+        self.read_show_data(&node, "interfaces", "show interfaces", bypass_cache, parsers::parse_show_interfaces)
+            .await
+    }
+
+    /// Get the IPv6 neighbor cache of a node
+    pub async fn get_ipv6_neighbors(&self, node_id: Uuid) -> Result<NodeData<Vec<VyOSNeighbor>>, AppError> {
+        info!("Getting IPv6 neighbors for node: {}", node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        self.read_show_data(&node, "ipv6-neighbors", "show ipv6 neighbors", false, parsers::parse_show_ipv6_neighbors).await
+    }
+
+    /// Get the ARP table of a node
+    pub async fn get_arp_table(&self, node_id: Uuid) -> Result<NodeData<Vec<VyOSNeighbor>>, AppError> {
+        info!("Getting ARP table for node: {}", node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        self.read_show_data(&node, "arp", "show arp", false, parsers::parse_show_arp).await
+    }
+
+    /// Get the LLDP neighbors of a node
+    pub async fn get_lldp_neighbors(&self, node_id: Uuid) -> Result<NodeData<Vec<VyOSLldpNeighbor>>, AppError> {
+        info!("Getting LLDP neighbors for node: {}", node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        self.read_show_data(&node, "lldp-neighbors", "show lldp neighbors", false, parsers::parse_show_lldp_neighbors).await
+    }
+
+    /// Get the peers of a WireGuard interface of a node, as the node sees
+    /// them now
+    ///
+    /// Unlike most reads, this is never answered from the last-known data,
+    /// so handshake ages are current.
+    pub async fn get_wireguard_peers(&self, node_id: Uuid, interface: &str) -> Result<Vec<VyOSWireGuardPeer>, AppError> {
+        debug!("Getting WireGuard peers of {} on node: {}", interface, node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        if let Some(e) = self.unavailable(node_id).await? {
+            return Err(e);
+        }
+        self.require_capability(&node, Capability::Show).await?;
+        let transport = self.transport(&node).await?;
+        let result = transport
+            .show(&format!("show interfaces wireguard {} summary", interface))
+            .await?;
+        Ok(parsers::parse_show_wireguard_summary(&result.output))
+    }
+
+    /// Get the VRRP groups of a node, as the node sees them now
+    pub async fn get_vrrp_groups(&self, node_id: Uuid) -> Result<Vec<VyOSVrrpGroup>, AppError> {
+        debug!("Getting VRRP groups of node: {}", node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        if let Some(e) = self.unavailable(node_id).await? {
+            return Err(e);
+        }
+        self.require_capability(&node, Capability::Show).await?;
+        let transport = self.transport(&node).await?;
+        let result = transport.show("show vrrp").await?;
+        Ok(parsers::parse_show_vrrp(&result.output))
+    }
+
+    /// Get the conntrack-sync statistics of a node, if it synchronizes its
+    /// connection table
+    pub async fn get_conntrack_sync_statistics(
+        &self,
+        node_id: Uuid,
+    ) -> Result<Option<VyOSConntrackSyncStatistics>, AppError> {
+        debug!("Getting conntrack-sync statistics of node: {}", node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        if let Some(e) = self.unavailable(node_id).await? {
+            return Err(e);
+        }
+        self.require_capability(&node, Capability::Show).await?;
+        let transport = self.transport(&node).await?;
+        let result = transport.show("show conntrack-sync statistics").await?;
+        Ok(parsers::parse_show_conntrack_sync_statistics(&result.output))
+    }
+
+    /// Get the link speed of an ethernet interface in Mbps, if the link is up
+    /// and its driver reports it
+    pub async fn get_link_speed(&self, node_id: Uuid, interface: &str) -> Result<Option<u64>, AppError> {
+        debug!("Getting link speed of {} on node: {}", interface, node_id);
+        if interface.is_empty() || !interface.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-')) {
+            return Err(AppError::Validation(format!("'{}' is not a valid interface name", interface)));
+        }
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        if let Some(e) = self.unavailable(node_id).await? {
+            return Err(e);
+        }
+        self.require_capability(&node, Capability::Show).await?;
+        let transport = self.transport(&node).await?;
+        let result = transport.show(&format!("show interfaces ethernet {} physical", interface)).await?;
+        Ok(parsers::parse_ethtool_speed(&result.output))
     }
 
     /// Execute a show command on a node
+    ///
+    /// A result cached within the command's TTL is returned unless
+    /// `bypass_cache` is set.
     pub async fn execute_show_command(
         &self,
         node_id: Uuid,
         command: &str,
-    ) -> Result<crate::vyos_client::VyOSShowResult, AppError> {
+        bypass_cache: bool,
+    ) -> Result<CachedShowResult, AppError> {
         info!("Executing show command on node {}: {}", node_id, command);
 
         let node = self
@@ -624,30 +1508,288 @@ impl NodeService {
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
 
-        let vyos_client = self.create_vyos_client(&node)?;
-        vyos_client.show(command).await
+        self.require_capability(&node, Capability::Show).await?;
+        let transport = self.transport(&node).await?;
+        self.show_cache.show(node_id, transport.as_ref(), command, bypass_cache).await
+    }
+
+    /// Run an operational-mode command on a node, passing its output to
+    /// `output` as the node's transport reads it
+    ///
+    /// Never answered from the show cache. Over the API only `show` commands
+    /// run, and their output arrives whole; over SSH any operational command
+    /// runs and its output is passed on line by line.
+    pub async fn run_op_command(
+        &self,
+        node_id: Uuid,
+        command: &str,
+        output: &mut (dyn FnMut(String) + Send),
+    ) -> Result<(), AppError> {
+        info!("Running operational command on node {}: {}", node_id, command);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        self.require_capability(&node, Capability::Show).await?;
+        let transport = self.transport(&node).await?;
+        transport.run_op_command(command, output).await
+    }
+
+    // ========================================================================
+    // Simulation
+    // ========================================================================
+
+    /// Record a node's system information, configuration, and the outputs of
+    /// [`RECORDED_SHOW_COMMANDS`]
+    ///
+    /// Show commands the node rejects are left out of the recording.
+    pub async fn record_node(&self, node_id: Uuid) -> Result<NodeRecording, AppError> {
+        info!("Recording node: {}", node_id);
+
+        let node = self
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        let transport = self.transport(&node).await?;
+        let info = transport.get_info().await?;
+        let config = transport.retrieve_config(None).await?;
+
+        let mut show_outputs = BTreeMap::new();
+        for command in RECORDED_SHOW_COMMANDS {
+            match transport.show(command).await {
+                Ok(result) => {
+                    show_outputs.insert(command.to_string(), result.output);
+                }
+                Err(e) if NodeCircuitBreaker::is_transport_error(&e) => return Err(e),
+                Err(e) => debug!("Not recording \"{}\" of node {}: {}", command, node_id, e),
+            }
+        }
+
+        Ok(NodeRecording {
+            info,
+            config,
+            show_outputs,
+        })
+    }
+
+    /// Create a simulated node answering from a recording
+    ///
+    /// The recording is either part of the request or taken of the node
+    /// named by `record_from`.
+    pub async fn create_simulated_node(&self, request: CreateSimulatedNodeRequest) -> Result<Node, AppError> {
+        info!("Creating simulated node: {}", request.name);
+
+        let (recording, recorded_from) = match (request.recording, request.record_from) {
+            (Some(recording), None) => (recording, None),
+            (None, Some(source)) => (self.record_node(source).await?, Some(source)),
+            _ => {
+                return Err(AppError::Validation(
+                    "Either a recording or a node to record is required".to_string(),
+                ))
+            }
+        };
+
+        let node_request = CreateNodeRequest {
+            name: request.name,
+            description: request.description,
+            host: SIMULATED_NODE_HOST.to_string(),
+            port: None,
+            api_key: String::new(),
+            use_https: None,
+            verify_ssl: None,
+            tags: request.tags,
+            timeout: None,
+            team_id: request.team_id,
+            transport: None,
+            ssh_port: None,
+            ssh_username: None,
+            ssh_password: None,
+            ssh_private_key: None,
+            custom_fields: request.custom_fields,
+        };
+        self.insert_node(Uuid::new_v4(), node_request, Some((&recording, recorded_from))).await
+    }
+
+    /// Recording a simulated node answers from
+    pub async fn get_recording(&self, node_id: Uuid) -> Result<NodeRecordingResponse, AppError> {
+        let row: Option<(String, Option<String>, String)> = sqlx::query_as(
+            "SELECT recording, recorded_from, recorded_at FROM node_recordings WHERE node_id = ?",
+        )
+        .bind(node_id.to_string())
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        let Some((recording, recorded_from, recorded_at)) = row else {
+            return Err(AppError::NotFound(format!("Node {} is not a simulated node", node_id)));
+        };
+
+        Ok(NodeRecordingResponse {
+            node_id,
+            recorded_from: recorded_from.and_then(|id| Uuid::parse_str(&id).ok()),
+            recorded_at: parse_db_timestamp(&recorded_at),
+            recording: serde_json::from_str(&recording)?,
+        })
+    }
+
+    // ========================================================================
+    // Last-Known Data
+    // ========================================================================
+
+    /// Read parsed show output from a node through the show cache, falling
+    /// back to the data last read under `key`
+    ///
+    /// Data answered from the cache keeps the time the node produced it.
+    async fn read_show_data<T>(
+        &self,
+        node: &Node,
+        key: &str,
+        command: &str,
+        bypass_cache: bool,
+        parse: fn(&str) -> T,
+    ) -> Result<NodeData<T>, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.require_capability(node, Capability::Show).await?;
+        let transport = self.transport(node).await?;
+        let mut fetched_at = None;
+        let produced_at = &mut fetched_at;
+        let mut data = self
+            .read_node_data(node.id, key, move || async move {
+                let show = self.show_cache.show(node.id, transport.as_ref(), command, bypass_cache).await?;
+                *produced_at = Some(show.fetched_at);
+                Ok(parse(&show.result.output))
+            })
+            .await?;
+
+        if let Some(fetched_at) = fetched_at.filter(|_| !data.stale) {
+            data.fetched_at = fetched_at;
+            data.age_secs = (Utc::now() - fetched_at).num_seconds().max(0) as u64;
+        }
+        Ok(data)
+    }
+
+    /// Read data from a node, falling back to the data last read under `key`
+    ///
+    /// While the node is rebooting or its circuit is open, the node is not
+    /// contacted at all.
+    /// When it cannot be reached, the cached data is returned marked as
+    /// stale; without cached data the transport error is returned. Errors
+    /// reported by the node itself are returned as they are.
+    async fn read_node_data<T, F, Fut>(
+        &self,
+        node_id: Uuid,
+        key: &str,
+        read: F,
+    ) -> Result<NodeData<T>, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        let error = match self.unavailable(node_id).await? {
+            Some(error) => error,
+            None => match read().await {
+                Ok(data) => {
+                    self.breaker.record_success(node_id);
+                    let fetched_at = Utc::now();
+                    self.cache_node_data(node_id, key, &data, &fetched_at).await?;
+                    return Ok(NodeData {
+                        data,
+                        stale: false,
+                        fetched_at,
+                        age_secs: 0,
+                        error: None,
+                    });
+                }
+                Err(e) if NodeCircuitBreaker::is_transport_error(&e) => {
+                    self.breaker.record_failure(node_id);
+                    e
+                }
+                Err(e) => return Err(e),
+            },
+        };
+
+        let row: Option<(String, String)> = sqlx::query_as(
+            "SELECT data, fetched_at FROM node_data_cache WHERE node_id = ? AND data_key = ?",
+        )
+        .bind(node_id.to_string())
+        .bind(key)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        let Some((data, fetched_at)) = row else {
+            return Err(error);
+        };
+
+        warn!("Serving last-known {} of node {}: {}", key, node_id, error);
+        let fetched_at = parse_db_timestamp(&fetched_at);
+        Ok(NodeData {
+            data: serde_json::from_str(&data)?,
+            stale: true,
+            fetched_at,
+            age_secs: (Utc::now() - fetched_at).num_seconds().max(0) as u64,
+            error: Some(error.to_string()),
+        })
+    }
+
+    /// Remember data read from a node
+    async fn cache_node_data<T: Serialize>(
+        &self,
+        node_id: Uuid,
+        key: &str,
+        data: &T,
+        fetched_at: &DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO node_data_cache (node_id, data_key, data, fetched_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (node_id, data_key) DO UPDATE SET
+                data = excluded.data,
+                fetched_at = excluded.fetched_at
+            "#,
+        )
+        .bind(node_id.to_string())
+        .bind(key)
+        .bind(serde_json::to_string(data)?)
+        .bind(format_timestamp(fetched_at))
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
     }
 
     // ========================================================================
     // Statistics
     // ========================================================================
 
-    /// Get node statistics
-    pub async fn get_statistics(&self) -> Result<NodeStatistics, AppError> {
+    /// Get statistics of the nodes in an access scope
+    pub async fn get_statistics(&self, scope: &AccessScope) -> Result<NodeStatistics, AppError> {
         debug!("Getting node statistics");
 
-        let query = r#"
+        let (clause, values) = scope_filter(scope);
+        let query = format!(
+            r#"
             SELECT
                 COUNT(*) as total,
-                SUM(CASE WHEN status = 'online' THEN 1 ELSE 0 END) as online,
-                SUM(CASE WHEN status = 'offline' THEN 1 ELSE 0 END) as offline,
-                SUM(CASE WHEN status = 'error' THEN 1 ELSE 0 END) as error
+                COALESCE(SUM(CASE WHEN status = 'online' THEN 1 ELSE 0 END), 0) as online,
+                COALESCE(SUM(CASE WHEN status = 'offline' THEN 1 ELSE 0 END), 0) as offline,
+                COALESCE(SUM(CASE WHEN status = 'error' THEN 1 ELSE 0 END), 0) as error
             FROM nodes
-        "#;
+            WHERE {}
+        "#,
+            clause
+        );
 
-        let row = sqlx::query_as::<_, (i64, i64, i64, i64)>(query)
-            .fetch_one(&self.pool)
-            .await?;
+        let mut row = sqlx::query_as::<_, (i64, i64, i64, i64)>(&query);
+        for value in &values {
+            row = row.bind(value);
+        }
+        let row = row.fetch_one(self.db.pool()).await?;
 
         Ok(NodeStatistics {
             total_nodes: row.0 as u64,
@@ -662,6 +1804,27 @@ impl NodeService {
 // Helper Functions
 // ========================================================================
 
+/// WHERE clause and bound values selecting the nodes in an access scope
+fn scope_filter(scope: &AccessScope) -> (String, Vec<String>) {
+    match scope {
+        AccessScope::All => ("1=1".to_string(), Vec::new()),
+        AccessScope::Teams(team_ids) if team_ids.is_empty() => ("team_id IS NULL".to_string(), Vec::new()),
+        AccessScope::Teams(team_ids) => (
+            format!("(team_id IS NULL OR team_id IN ({}))", vec!["?"; team_ids.len()].join(", ")),
+            team_ids.iter().map(|id| id.to_string()).collect(),
+        ),
+    }
+}
+
+/// Error for a request to a node that is rebooting
+fn rebooting_error(reboot: &NodeReboot) -> AppError {
+    AppError::HttpClient(format!(
+        "Node {} is rebooting, expected back by {}",
+        reboot.node_id,
+        format_timestamp(&reboot.expected_back_by)
+    ))
+}
+
 /// Parse node status from string
 fn parse_node_status(s: &str) -> NodeStatus {
     match s.to_lowercase().as_str() {
@@ -669,10 +1832,21 @@ fn parse_node_status(s: &str) -> NodeStatus {
         "offline" => NodeStatus::Offline,
         "error" => NodeStatus::Error,
         "testing" => NodeStatus::Testing,
+        "rebooting" => NodeStatus::Rebooting,
         _ => NodeStatus::Offline,
     }
 }
 
+/// Check that a router reached over SSH has a user and a password or key
+fn check_ssh_login(transport: NodeTransportKind, username: Option<&str>, has_secret: bool) -> Result<(), AppError> {
+    if transport == NodeTransportKind::Ssh && (username.is_none_or(str::is_empty) || !has_secret) {
+        return Err(AppError::Validation(
+            "Nodes reached over SSH need an SSH username and a password or private key".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 impl NodeStatus {
     /// Convert NodeStatus to string for database storage
     fn to_string(&self) -> String {
@@ -681,6 +1855,7 @@ impl NodeStatus {
             NodeStatus::Offline => "offline".to_string(),
             NodeStatus::Error => "error".to_string(),
             NodeStatus::Testing => "testing".to_string(),
+            NodeStatus::Rebooting => "rebooting".to_string(),
         }
     }
 }
@@ -694,6 +1869,7 @@ mod tests {
         assert_eq!(parse_node_status("online"), NodeStatus::Online);
         assert_eq!(parse_node_status("OFFLINE"), NodeStatus::Offline);
         assert_eq!(parse_node_status("Error"), NodeStatus::Error);
+        assert_eq!(parse_node_status("rebooting"), NodeStatus::Rebooting);
         assert_eq!(parse_node_status("unknown"), NodeStatus::Offline);
     }
 
@@ -703,5 +1879,6 @@ mod tests {
         assert_eq!(NodeStatus::Offline.to_string(), "offline");
         assert_eq!(NodeStatus::Error.to_string(), "error");
         assert_eq!(NodeStatus::Testing.to_string(), "testing");
+        assert_eq!(NodeStatus::Rebooting.to_string(), "rebooting");
     }
 }
\ No newline at end of file
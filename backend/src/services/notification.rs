@@ -0,0 +1,852 @@
+//! Notification Service
+//!
+//! Tells people about alerts. Channels say where notifications go: email
+//! through the SMTP relay, a generic JSON webhook, or a Slack-compatible
+//! incoming webhook. Routes say which alerts go to which channel, by alert
+//! rule, node, and severity.
+//!
+//! Alerts live in memory, so they are dispatched periodically rather than as
+//! they are raised: every dispatch sends the alerts that triggered or were
+//! resolved since a matching route was created and that were not delivered
+//! yet. The delivery log keeps one entry per alert, channel, and event, so
+//! an alert reaches a channel once even when several routes match it, and
+//! failed deliveries are retried on later dispatches up to
+//! [`MAX_DELIVERY_ATTEMPTS`] times.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Url};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::backend_health::BACKEND_NODE_ID;
+use crate::models::monitoring::{Alert, AlertSeverity, AlertStatus};
+use crate::models::notification::{
+    ChannelTarget, CreateNotificationChannelRequest, CreateNotificationRouteRequest, DeliveryListQuery,
+    DeliveryStatus, NotificationChannel, NotificationDelivery, NotificationEvent, NotificationRoute,
+    UpdateNotificationChannelRequest, DEFAULT_DELIVERY_LIMIT, MAX_DELIVERY_ATTEMPTS,
+};
+use crate::models::timestamp::{db_now, parse_db_timestamp};
+use crate::services::backend_health::alert_rule_id;
+use crate::services::{Mailer, MonitoringService, NodeService, TeamService};
+
+/// How long a webhook may take to answer
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header carrying the HMAC-SHA256 signature of a webhook body
+const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+const CHANNEL_COLUMNS: &str = "id, name, kind, settings, enabled, created_by, created_at, updated_at";
+
+const ROUTE_COLUMNS: &str =
+    "id, name, channel_id, rule_id, node_id, min_severity, notify_resolved, enabled, created_at, updated_at";
+
+const DELIVERY_COLUMNS: &str = "id, channel_id, route_id, alert_id, event, status, attempts, error, created_at, updated_at";
+
+/// Notification service
+#[derive(Clone)]
+pub struct NotificationService {
+    db: Database,
+    monitoring: MonitoringService,
+    nodes: NodeService,
+    teams: TeamService,
+    mailer: Mailer,
+    client: Client,
+}
+
+/// Content of a notification, rendered for every kind of channel
+struct Notification {
+    subject: String,
+    text: String,
+    /// Body of generic webhooks
+    payload: Value,
+}
+
+impl NotificationService {
+    /// Create a new notification service
+    pub fn new(db: Database, monitoring: MonitoringService, nodes: NodeService, teams: TeamService, mailer: Mailer) -> Self {
+        let client = Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self {
+            db,
+            monitoring,
+            nodes,
+            teams,
+            mailer,
+            client,
+        }
+    }
+
+    // ========================================================================
+    // Channels
+    // ========================================================================
+
+    /// List notification channels by name
+    pub async fn list_channels(&self) -> Result<Vec<NotificationChannel>, AppError> {
+        let query = format!("SELECT {} FROM notification_channels ORDER BY name", CHANNEL_COLUMNS);
+        let rows: Vec<ChannelRow> = sqlx::query_as(&query).fetch_all(self.db.pool()).await?;
+
+        rows.into_iter().map(channel_from_row).collect()
+    }
+
+    /// Get a notification channel
+    pub async fn get_channel(&self, id: Uuid) -> Result<NotificationChannel, AppError> {
+        let query = format!("SELECT {} FROM notification_channels WHERE id = ?", CHANNEL_COLUMNS);
+
+        sqlx::query_as::<_, ChannelRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(channel_from_row)
+            .transpose()?
+            .ok_or_else(|| AppError::NotFound(format!("Notification channel {} not found", id)))
+    }
+
+    /// Create a notification channel
+    pub async fn create_channel(
+        &self,
+        claims: &Claims,
+        request: CreateNotificationChannelRequest,
+    ) -> Result<NotificationChannel, AppError> {
+        self.check_target(&request.target).await?;
+        self.ensure_unique_name(&request.name, None).await?;
+
+        let id = Uuid::new_v4();
+        let now = db_now();
+        sqlx::query(
+            "INSERT INTO notification_channels (id, name, kind, settings, enabled, created_by, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id.to_string())
+        .bind(&request.name)
+        .bind(request.target.kind())
+        .bind(target_settings(&request.target)?)
+        .bind(request.enabled)
+        .bind(&claims.username)
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Notification channel {} ({}) created by {}", request.name, request.target.kind(), claims.username);
+        self.get_channel(id).await
+    }
+
+    /// Update a notification channel
+    ///
+    /// A webhook's signing secret is kept when the new target leaves it out.
+    pub async fn update_channel(
+        &self,
+        id: Uuid,
+        request: UpdateNotificationChannelRequest,
+    ) -> Result<NotificationChannel, AppError> {
+        let mut channel = self.get_channel(id).await?;
+
+        if let Some(name) = request.name {
+            self.ensure_unique_name(&name, Some(id)).await?;
+            channel.name = name;
+        }
+        if let Some(mut target) = request.target {
+            if let (
+                ChannelTarget::Webhook { secret: secret @ None, .. },
+                ChannelTarget::Webhook { secret: Some(existing), .. },
+            ) = (&mut target, &channel.target)
+            {
+                *secret = Some(existing.clone());
+            }
+            self.check_target(&target).await?;
+            channel.target = target;
+        }
+        if let Some(enabled) = request.enabled {
+            channel.enabled = enabled;
+        }
+
+        sqlx::query(
+            "UPDATE notification_channels SET name = ?, kind = ?, settings = ?, enabled = ?, updated_at = ? \
+             WHERE id = ?",
+        )
+        .bind(&channel.name)
+        .bind(channel.target.kind())
+        .bind(target_settings(&channel.target)?)
+        .bind(channel.enabled)
+        .bind(db_now())
+        .bind(id.to_string())
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Notification channel {} updated", id);
+        self.get_channel(id).await
+    }
+
+    /// Delete a notification channel with its routes and delivery log
+    pub async fn delete_channel(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM notification_channels WHERE id = ?")
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Notification channel {} not found", id)));
+        }
+
+        info!("Notification channel {} deleted", id);
+        Ok(())
+    }
+
+    /// Send a test notification through a channel, enabled or not
+    ///
+    /// The attempt is recorded in the delivery log either way.
+    pub async fn test_channel(&self, claims: &Claims, id: Uuid) -> Result<NotificationDelivery, AppError> {
+        let channel = self.get_channel(id).await?;
+        let notification = Notification {
+            subject: format!("Test notification for {}", channel.name),
+            text: format!(
+                "{} sent this test notification to check the {} channel {}.",
+                claims.username,
+                channel.target.kind(),
+                channel.name
+            ),
+            payload: json!({
+                "event": NotificationEvent::Test,
+                "channel": channel.name,
+                "sent_by": claims.username,
+                "sent_at": Utc::now().to_rfc3339(),
+            }),
+        };
+
+        let result = self.send(&channel, &notification).await;
+        if let Err(e) = &result {
+            warn!("Test notification through channel {} failed: {}", channel.name, e);
+        }
+        let id = self.record_delivery(None, &channel, None, NotificationEvent::Test, &result).await?;
+        self.get_delivery(id).await
+    }
+
+    // ========================================================================
+    // Routes
+    // ========================================================================
+
+    /// List notification routes by name
+    pub async fn list_routes(&self) -> Result<Vec<NotificationRoute>, AppError> {
+        let query = format!("SELECT {} FROM notification_routes ORDER BY name, created_at", ROUTE_COLUMNS);
+        let rows: Vec<RouteRow> = sqlx::query_as(&query).fetch_all(self.db.pool()).await?;
+
+        Ok(rows.into_iter().map(route_from_row).collect())
+    }
+
+    /// Get a notification route
+    pub async fn get_route(&self, id: Uuid) -> Result<NotificationRoute, AppError> {
+        let query = format!("SELECT {} FROM notification_routes WHERE id = ?", ROUTE_COLUMNS);
+
+        sqlx::query_as::<_, RouteRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(route_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Notification route {} not found", id)))
+    }
+
+    /// Create a notification route
+    ///
+    /// Only alerts that trigger or resolve from now on are sent through it.
+    pub async fn create_route(&self, request: CreateNotificationRouteRequest) -> Result<NotificationRoute, AppError> {
+        self.check_route(&request).await?;
+
+        let id = Uuid::new_v4();
+        let now = db_now();
+        sqlx::query(
+            r#"
+            INSERT INTO notification_routes (
+                id, name, channel_id, rule_id, node_id, min_severity, notify_resolved, enabled,
+                created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(&request.name)
+        .bind(request.channel_id.to_string())
+        .bind(request.rule_id.map(|id| id.to_string()))
+        .bind(request.node_id.map(|id| id.to_string()))
+        .bind(request.min_severity.as_str())
+        .bind(request.notify_resolved)
+        .bind(request.enabled)
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Notification route {} to channel {} created", request.name, request.channel_id);
+        self.get_route(id).await
+    }
+
+    /// Replace a notification route
+    pub async fn update_route(
+        &self,
+        id: Uuid,
+        request: CreateNotificationRouteRequest,
+    ) -> Result<NotificationRoute, AppError> {
+        self.get_route(id).await?;
+        self.check_route(&request).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE notification_routes
+            SET name = ?, channel_id = ?, rule_id = ?, node_id = ?, min_severity = ?, notify_resolved = ?,
+                enabled = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&request.name)
+        .bind(request.channel_id.to_string())
+        .bind(request.rule_id.map(|id| id.to_string()))
+        .bind(request.node_id.map(|id| id.to_string()))
+        .bind(request.min_severity.as_str())
+        .bind(request.notify_resolved)
+        .bind(request.enabled)
+        .bind(db_now())
+        .bind(id.to_string())
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Notification route {} updated", id);
+        self.get_route(id).await
+    }
+
+    /// Delete a notification route
+    pub async fn delete_route(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM notification_routes WHERE id = ?")
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Notification route {} not found", id)));
+        }
+
+        info!("Notification route {} deleted", id);
+        Ok(())
+    }
+
+    // ========================================================================
+    // Delivery
+    // ========================================================================
+
+    /// List the delivery log, newest first
+    pub async fn list_deliveries(&self, query: DeliveryListQuery) -> Result<Vec<NotificationDelivery>, AppError> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(channel_id) = query.channel_id {
+            conditions.push("channel_id = ?");
+            values.push(channel_id.to_string());
+        }
+        if let Some(alert_id) = query.alert_id {
+            conditions.push("alert_id = ?");
+            values.push(alert_id.to_string());
+        }
+        if let Some(status) = query.status {
+            conditions.push("status = ?");
+            values.push(status.as_str().to_string());
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let sql = format!(
+            "SELECT {} FROM notification_deliveries {} ORDER BY updated_at DESC, id LIMIT ?",
+            DELIVERY_COLUMNS, filter
+        );
+        let mut select = sqlx::query_as::<_, DeliveryRow>(&sql);
+        for value in &values {
+            select = select.bind(value);
+        }
+        let rows = select
+            .bind(query.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT) as i64)
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(rows.into_iter().map(delivery_from_row).collect())
+    }
+
+    /// Send the alerts that triggered or were resolved to the channels of
+    /// their routes
+    ///
+    /// Returns the number of notifications delivered.
+    pub async fn dispatch(&self) -> Result<usize, AppError> {
+        let routes: Vec<NotificationRoute> =
+            self.list_routes().await?.into_iter().filter(|route| route.enabled).collect();
+        if routes.is_empty() {
+            return Ok(0);
+        }
+        let channels: HashMap<Uuid, NotificationChannel> = self
+            .list_channels()
+            .await?
+            .into_iter()
+            .filter(|channel| channel.enabled)
+            .map(|channel| (channel.id, channel))
+            .collect();
+
+        let mut alerts = self.monitoring.get_alerts(None, None, None).await?;
+        alerts.retain(|alert| alert.status != AlertStatus::Suppressed);
+        alerts.sort_by_key(|alert| alert.triggered_at);
+
+        let mut delivered = 0;
+        for alert in &alerts {
+            let rule_id = alert_rule_id(alert);
+            let mut events = vec![(NotificationEvent::Triggered, alert.triggered_at)];
+            if let (AlertStatus::Resolved, Some(resolved_at)) = (alert.status, alert.resolved_at) {
+                events.push((NotificationEvent::Resolved, resolved_at));
+            }
+
+            for (event, happened_at) in events {
+                // The first matching route of each channel is the one recorded
+                let mut matched: Vec<&NotificationRoute> = Vec::new();
+                for route in &routes {
+                    let wanted = route.matches(rule_id, &alert.node_id, alert.severity)
+                        && happened_at >= route.created_at
+                        && (event != NotificationEvent::Resolved || route.notify_resolved)
+                        && !matched.iter().any(|other| other.channel_id == route.channel_id);
+                    if wanted {
+                        matched.push(route);
+                    }
+                }
+
+                for route in matched {
+                    let Some(channel) = channels.get(&route.channel_id) else {
+                        continue;
+                    };
+                    if self.deliver_alert(channel, route, alert, event).await? {
+                        delivered += 1;
+                    }
+                }
+            }
+        }
+
+        if delivered > 0 {
+            info!("Delivered {} alert notifications", delivered);
+        }
+        Ok(delivered)
+    }
+
+    /// Deliver an alert event through a channel unless it was delivered or
+    /// given up before
+    ///
+    /// Returns whether it was delivered now.
+    async fn deliver_alert(
+        &self,
+        channel: &NotificationChannel,
+        route: &NotificationRoute,
+        alert: &Alert,
+        event: NotificationEvent,
+    ) -> Result<bool, AppError> {
+        let previous: Option<(String, i64)> = sqlx::query_as(
+            "SELECT status, attempts FROM notification_deliveries WHERE alert_id = ? AND channel_id = ? AND event = ?",
+        )
+        .bind(alert.id.to_string())
+        .bind(channel.id.to_string())
+        .bind(event.as_str())
+        .fetch_optional(self.db.pool())
+        .await?;
+        if let Some((status, attempts)) = &previous {
+            if DeliveryStatus::parse(status) == DeliveryStatus::Delivered || *attempts >= MAX_DELIVERY_ATTEMPTS as i64 {
+                return Ok(false);
+            }
+        }
+
+        let notification = self.alert_notification(alert, event).await;
+        let result = self.send(channel, &notification).await;
+        if let Err(e) = &result {
+            warn!("Failed to notify channel {} of alert {}: {}", channel.name, alert.id, e);
+        }
+        self.record_delivery(Some(route), channel, Some(alert.id), event, &result).await?;
+
+        Ok(result.is_ok())
+    }
+
+    /// Add a delivery attempt to the log, returning the ID of its entry
+    async fn record_delivery(
+        &self,
+        route: Option<&NotificationRoute>,
+        channel: &NotificationChannel,
+        alert_id: Option<Uuid>,
+        event: NotificationEvent,
+        result: &Result<(), AppError>,
+    ) -> Result<Uuid, AppError> {
+        let (status, error) = match result {
+            Ok(()) => (DeliveryStatus::Delivered, None),
+            Err(e) => (DeliveryStatus::Failed, Some(e.to_string())),
+        };
+        let now = db_now();
+
+        if let Some(alert_id) = alert_id {
+            let existing: Option<String> = sqlx::query_scalar(
+                "SELECT id FROM notification_deliveries WHERE alert_id = ? AND channel_id = ? AND event = ?",
+            )
+            .bind(alert_id.to_string())
+            .bind(channel.id.to_string())
+            .bind(event.as_str())
+            .fetch_optional(self.db.pool())
+            .await?;
+            if let Some(id) = existing {
+                sqlx::query(
+                    "UPDATE notification_deliveries SET status = ?, attempts = attempts + 1, error = ?, updated_at = ? \
+                     WHERE id = ?",
+                )
+                .bind(status.as_str())
+                .bind(&error)
+                .bind(&now)
+                .bind(&id)
+                .execute(self.db.pool())
+                .await?;
+                return Ok(Uuid::parse_str(&id).unwrap_or_default());
+            }
+        }
+
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO notification_deliveries (
+                id, channel_id, route_id, alert_id, event, status, attempts, error, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(channel.id.to_string())
+        .bind(route.map(|route| route.id.to_string()))
+        .bind(alert_id.map(|id| id.to_string()))
+        .bind(event.as_str())
+        .bind(status.as_str())
+        .bind(&error)
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get_delivery(&self, id: Uuid) -> Result<NotificationDelivery, AppError> {
+        let query = format!("SELECT {} FROM notification_deliveries WHERE id = ?", DELIVERY_COLUMNS);
+
+        sqlx::query_as::<_, DeliveryRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(delivery_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Notification delivery {} not found", id)))
+    }
+
+    /// Render the notification of an alert event
+    async fn alert_notification(&self, alert: &Alert, event: NotificationEvent) -> Notification {
+        let node_name = match Uuid::parse_str(&alert.node_id) {
+            Ok(BACKEND_NODE_ID) => Some("backend".to_string()),
+            Ok(node_id) => self.nodes.get_node(node_id).await.ok().flatten().map(|node| node.name),
+            Err(_) => None,
+        };
+        let node = node_name.clone().unwrap_or_else(|| alert.node_id.clone());
+
+        let state = match event {
+            NotificationEvent::Resolved => "RESOLVED".to_string(),
+            _ => alert.severity.as_str().to_uppercase(),
+        };
+        let subject = format!("[{}] {} on {}", state, alert.title, node);
+
+        let mut text = alert.description.clone();
+        if let (Some(metric), Some(value)) = (&alert.metric_name, alert.actual_value) {
+            text.push_str(&format!("\n{} is {}", metric, value));
+            if let Some(threshold) = alert.threshold_value {
+                text.push_str(&format!(" (threshold {})", threshold));
+            }
+        }
+        text.push_str(&format!("\nTriggered at {}", alert.triggered_at.to_rfc3339()));
+        if let Some(resolved_at) = alert.resolved_at.filter(|_| event == NotificationEvent::Resolved) {
+            text.push_str(&format!(", resolved at {}", resolved_at.to_rfc3339()));
+        }
+
+        Notification {
+            subject,
+            text,
+            payload: json!({
+                "event": event,
+                "alert": alert,
+                "node_name": node_name,
+                "sent_at": Utc::now().to_rfc3339(),
+            }),
+        }
+    }
+
+    /// Send a notification through a channel
+    async fn send(&self, channel: &NotificationChannel, notification: &Notification) -> Result<(), AppError> {
+        match &channel.target {
+            ChannelTarget::Email { recipients } => {
+                let recipients = self.email_addresses(recipients).await?;
+                self.mailer
+                    .send(&recipients, &notification.subject, &notification.text, None)
+                    .await
+            }
+            ChannelTarget::Webhook { url, secret } => {
+                let body = serde_json::to_vec(&notification.payload)?;
+                let mut request = self.client.post(url).header("Content-Type", "application/json");
+                if let Some(secret) = secret {
+                    request = request.header(SIGNATURE_HEADER, format!("sha256={}", sign(secret, &body)));
+                }
+                check_response(request.body(body).send().await).await
+            }
+            ChannelTarget::Slack { webhook_url } => {
+                let message = json!({ "text": format!("*{}*\n{}", notification.subject, notification.text) });
+                check_response(self.client.post(webhook_url).json(&message).send().await).await
+            }
+        }
+    }
+
+    /// Resolve the recipients of an email channel, expanding team aliases
+    async fn email_addresses(&self, recipients: &[String]) -> Result<Vec<String>, AppError> {
+        let mut addresses = Vec::new();
+        for recipient in recipients {
+            if recipient.starts_with('@') {
+                addresses.extend(self.teams.resolve_alias_recipients(recipient).await?);
+            } else {
+                addresses.push(recipient.clone());
+            }
+        }
+        addresses.sort();
+        addresses.dedup();
+        Ok(addresses)
+    }
+
+    /// Check that a channel target can be delivered to
+    async fn check_target(&self, target: &ChannelTarget) -> Result<(), AppError> {
+        match target {
+            ChannelTarget::Email { recipients } => {
+                if recipients.is_empty() {
+                    return Err(AppError::Validation("Email channels need at least one recipient".to_string()));
+                }
+                if !self.mailer.is_enabled() {
+                    return Err(AppError::Validation("Email delivery is not configured".to_string()));
+                }
+                let mut addresses = Vec::new();
+                for recipient in recipients {
+                    if recipient.starts_with('@') {
+                        self.teams.resolve_alias_recipients(recipient).await.map_err(|_| {
+                            AppError::Validation(format!("Unknown team alias: {}", recipient))
+                        })?;
+                    } else {
+                        addresses.push(recipient.clone());
+                    }
+                }
+                self.mailer.check_recipients(&addresses)
+            }
+            ChannelTarget::Webhook { url, .. } => check_url(url),
+            ChannelTarget::Slack { webhook_url } => check_url(webhook_url),
+        }
+    }
+
+    /// Check that a route's channel, rule, and node exist
+    async fn check_route(&self, request: &CreateNotificationRouteRequest) -> Result<(), AppError> {
+        self.get_channel(request.channel_id).await.map_err(|e| match e {
+            AppError::NotFound(message) => AppError::Validation(message),
+            e => e,
+        })?;
+        if let Some(rule_id) = request.rule_id {
+            let rules = self.monitoring.get_alert_rules().await?;
+            if !rules.iter().any(|rule| rule.id == rule_id) {
+                return Err(AppError::Validation(format!("Alert rule {} not found", rule_id)));
+            }
+        }
+        if let Some(node_id) = request.node_id.filter(|id| *id != BACKEND_NODE_ID) {
+            if self.nodes.get_node(node_id).await?.is_none() {
+                return Err(AppError::Validation(format!("Node {} not found", node_id)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Ensure no other channel has a name
+    async fn ensure_unique_name(&self, name: &str, except: Option<Uuid>) -> Result<(), AppError> {
+        let existing: Option<String> = sqlx::query_scalar("SELECT id FROM notification_channels WHERE name = ?")
+            .bind(name)
+            .fetch_optional(self.db.pool())
+            .await?;
+        match existing {
+            Some(id) if Some(id.as_str()) != except.map(|id| id.to_string()).as_deref() => Err(AppError::Validation(
+                format!("A notification channel named {} already exists", name),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Check that a webhook URL is absolute HTTP or HTTPS
+fn check_url(url: &str) -> Result<(), AppError> {
+    match Url::parse(url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => Ok(()),
+        _ => Err(AppError::Validation(format!("Invalid webhook URL: {}", url))),
+    }
+}
+
+/// Fail on webhook responses outside the 2xx range
+async fn check_response(response: reqwest::Result<reqwest::Response>) -> Result<(), AppError> {
+    let response = response.map_err(|e| AppError::ExternalApi(format!("Webhook request failed: {}", e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(AppError::ExternalApi(format!(
+            "Webhook answered {}: {}",
+            status,
+            body.chars().take(200).collect::<String>()
+        )));
+    }
+    Ok(())
+}
+
+/// Hex-encoded HMAC-SHA256 of a webhook body
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.finalize().into_bytes().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Settings column of a channel target, secrets included
+fn target_settings(target: &ChannelTarget) -> Result<String, AppError> {
+    let mut settings = serde_json::to_value(target)?;
+    if let (ChannelTarget::Webhook { secret: Some(secret), .. }, Value::Object(fields)) = (target, &mut settings) {
+        fields.insert("secret".to_string(), Value::String(secret.clone()));
+    }
+    if let Value::Object(fields) = &mut settings {
+        fields.remove("kind");
+    }
+    Ok(settings.to_string())
+}
+
+type ChannelRow = (String, String, String, String, bool, Option<String>, String, String);
+
+fn channel_from_row(
+    (id, name, kind, settings, enabled, created_by, created_at, updated_at): ChannelRow,
+) -> Result<NotificationChannel, AppError> {
+    let mut settings: Value = serde_json::from_str(&settings)?;
+    if let Value::Object(fields) = &mut settings {
+        fields.insert("kind".to_string(), Value::String(kind));
+    }
+
+    Ok(NotificationChannel {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        name,
+        target: serde_json::from_value(settings)?,
+        enabled,
+        created_by,
+        created_at: parse_db_timestamp(&created_at),
+        updated_at: parse_db_timestamp(&updated_at),
+    })
+}
+
+type RouteRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    bool,
+    bool,
+    String,
+    String,
+);
+
+fn route_from_row(
+    (id, name, channel_id, rule_id, node_id, min_severity, notify_resolved, enabled, created_at, updated_at): RouteRow,
+) -> NotificationRoute {
+    NotificationRoute {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        name,
+        channel_id: Uuid::parse_str(&channel_id).unwrap_or_default(),
+        rule_id: rule_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        node_id: node_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        min_severity: AlertSeverity::parse(&min_severity),
+        notify_resolved,
+        enabled,
+        created_at: parse_db_timestamp(&created_at),
+        updated_at: parse_db_timestamp(&updated_at),
+    }
+}
+
+type DeliveryRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+    i64,
+    Option<String>,
+    String,
+    String,
+);
+
+fn delivery_from_row(
+    (id, channel_id, route_id, alert_id, event, status, attempts, error, created_at, updated_at): DeliveryRow,
+) -> NotificationDelivery {
+    NotificationDelivery {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        channel_id: Uuid::parse_str(&channel_id).unwrap_or_default(),
+        route_id: route_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        alert_id: alert_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        event: NotificationEvent::parse(&event),
+        status: DeliveryStatus::parse(&status),
+        attempts: attempts as u32,
+        error,
+        created_at: parse_db_timestamp(&created_at),
+        updated_at: parse_db_timestamp(&updated_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::timestamp::format_timestamp;
+
+    #[test]
+    fn test_target_settings_keep_the_webhook_secret() {
+        let target = ChannelTarget::Webhook {
+            url: "https://hooks.example.com/alerts".to_string(),
+            secret: Some("s3cret".to_string()),
+        };
+        let settings = target_settings(&target).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&settings).unwrap(),
+            json!({ "url": "https://hooks.example.com/alerts", "secret": "s3cret" })
+        );
+
+        let now = format_timestamp(&Utc::now());
+        let row = (Uuid::new_v4().to_string(), "ops".to_string(), "webhook".to_string(), settings, true, None, now.clone(), now);
+        assert_eq!(channel_from_row(row).unwrap().target, target);
+    }
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url("https://hooks.slack.com/services/T000/B000/XXXX").is_ok());
+        assert!(check_url("http://127.0.0.1:8080/hook").is_ok());
+        assert!(check_url("ftp://example.com/hook").is_err());
+        assert!(check_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,794 @@
+//! Object Storage
+//!
+//! Artifacts, that is uploads and database backups, are kept in an
+//! [`ObjectStore`] selected with `ARTIFACT_STORAGE`: below the data
+//! directories on local disk ([`FilesystemStore`]), or in a bucket of an
+//! S3-compatible service ([`S3Store`]). Each kind of artifact gets its own
+//! store, a directory or a key prefix in the bucket, and keys are relative
+//! to it.
+//!
+//! Objects are written as a stream. The S3 store sends content larger than
+//! `ARTIFACT_S3_PART_SIZE_BYTES` as a multipart upload, one part at a time,
+//! so large images never have to fit in memory; smaller objects are sent in
+//! a single request.
+
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, Method, Response, StatusCode};
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tokio::io::{AsyncRead, AsyncWriteExt};
+use tokio_util::io::StreamReader;
+use tracing::{debug, warn};
+
+use crate::config::secrets::{aws_signature, AwsCredentials};
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::storage::ArtifactStorage;
+
+/// Timeout of requests to the object store
+const S3_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Hash of an empty payload, signed for requests without a body
+const EMPTY_PAYLOAD_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+/// Stored object
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectInfo {
+    /// Key relative to the store
+    pub key: String,
+    pub size_bytes: u64,
+    pub modified_at: DateTime<Utc>,
+}
+
+/// Content read back from a store
+pub type ObjectReader = Pin<Box<dyn AsyncRead + Send>>;
+
+/// Place artifacts are kept
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Where the objects are kept, for display
+    fn location(&self) -> String;
+
+    /// Path of an object for stores that keep objects on local disk
+    fn local_path(&self, _key: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// Start writing an object, replacing any object with the same key
+    async fn create(&self, key: &str) -> Result<Box<dyn ObjectWriter>, AppError>;
+
+    /// Read an object; fails with [`AppError::NotFound`] when it is missing
+    async fn open(&self, key: &str) -> Result<ObjectReader, AppError>;
+
+    /// Size and age of an object, if it exists
+    async fn head(&self, key: &str) -> Result<Option<ObjectInfo>, AppError>;
+
+    /// Every object of the store
+    async fn list(&self) -> Result<Vec<ObjectInfo>, AppError>;
+
+    /// Remove an object; removing a missing object succeeds
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// Object being written
+#[async_trait]
+pub trait ObjectWriter: Send {
+    /// Append a chunk of content
+    async fn write(&mut self, chunk: &[u8]) -> Result<(), AppError>;
+
+    /// Complete the object and return its size in bytes
+    async fn finish(self: Box<Self>) -> Result<u64, AppError>;
+
+    /// Give up on the object, removing what was written of it
+    async fn abort(self: Box<Self>);
+}
+
+/// Store of one kind of artifact: `directory` on local disk, or the
+/// `prefix` of the bucket
+pub fn artifact_store(config: &AppConfig, directory: &str, prefix: &str) -> Arc<dyn ObjectStore> {
+    match config.artifact_storage {
+        ArtifactStorage::Filesystem => Arc::new(FilesystemStore::new(directory)),
+        ArtifactStorage::S3 => Arc::new(S3Store::new(config, prefix)),
+    }
+}
+
+// ============================================================================
+// Filesystem
+// ============================================================================
+
+/// Objects kept as files below a directory
+#[derive(Clone)]
+pub struct FilesystemStore {
+    root: PathBuf,
+}
+
+impl FilesystemStore {
+    /// Store keeping its objects below `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for FilesystemStore {
+    fn location(&self) -> String {
+        self.root.to_string_lossy().into_owned()
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        Some(self.path(key))
+    }
+
+    async fn create(&self, key: &str) -> Result<Box<dyn ObjectWriter>, AppError> {
+        let path = self.path(key);
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).await?;
+        }
+        let file = fs::File::create(&path).await?;
+
+        Ok(Box::new(FileWriter { path, file }))
+    }
+
+    async fn open(&self, key: &str) -> Result<ObjectReader, AppError> {
+        match fs::File::open(self.path(key)).await {
+            Ok(file) => Ok(Box::pin(file)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(AppError::NotFound(format!("Object {} not found", key)))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn head(&self, key: &str) -> Result<Option<ObjectInfo>, AppError> {
+        match fs::metadata(self.path(key)).await {
+            Ok(metadata) if metadata.is_file() => Ok(Some(ObjectInfo {
+                key: key.to_string(),
+                size_bytes: metadata.len(),
+                modified_at: metadata.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now()),
+            })),
+            Ok(_) => Ok(None),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<ObjectInfo>, AppError> {
+        let root = self.root.clone();
+        tokio::task::spawn_blocking(move || walk_directory(&root))
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to list stored objects: {}", e)))?
+            .map_err(AppError::from)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match fs::remove_file(self.path(key)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Files below a directory, with keys relative to it; a missing directory
+/// is empty
+fn walk_directory(root: &Path) -> std::io::Result<Vec<ObjectInfo>> {
+    let mut objects = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(directory) = pending.pop() {
+        let entries = match std::fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let entry = entry?;
+            // Symbolic links are not followed, so nothing is counted twice
+            let metadata = entry.path().symlink_metadata()?;
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                let path = entry.path();
+                let key = path.strip_prefix(root).unwrap_or(&path);
+                objects.push(ObjectInfo {
+                    key: key.components().map(|part| part.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"),
+                    size_bytes: metadata.len(),
+                    modified_at: metadata.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now()),
+                });
+            }
+        }
+    }
+
+    Ok(objects)
+}
+
+/// Object written straight to its file
+struct FileWriter {
+    path: PathBuf,
+    file: fs::File,
+}
+
+#[async_trait]
+impl ObjectWriter for FileWriter {
+    async fn write(&mut self, chunk: &[u8]) -> Result<(), AppError> {
+        self.file.write_all(chunk).await?;
+        Ok(())
+    }
+
+    async fn finish(mut self: Box<Self>) -> Result<u64, AppError> {
+        self.file.shutdown().await?;
+        Ok(fs::metadata(&self.path).await?.len())
+    }
+
+    async fn abort(self: Box<Self>) {
+        let FileWriter { path, file } = *self;
+        drop(file);
+        if let Err(e) = fs::remove_file(&path).await {
+            warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+// ============================================================================
+// S3
+// ============================================================================
+
+/// Objects kept below a key prefix of a bucket of an S3-compatible service
+///
+/// Requests are signed with Signature Version 4.
+#[derive(Clone)]
+pub struct S3Store {
+    client: Client,
+    /// Scheme of the endpoint, `https` or `http`
+    scheme: String,
+    /// Host, and port, of the endpoint
+    endpoint_host: String,
+    bucket: String,
+    region: String,
+    credentials: Option<AwsCredentials>,
+    path_style: bool,
+    /// Prefix of the keys, ending in `/`
+    prefix: String,
+    part_size: usize,
+}
+
+impl S3Store {
+    /// Store keeping its objects below `prefix` of the configured bucket
+    pub fn new(config: &AppConfig, prefix: &str) -> Self {
+        let endpoint = config.artifact_s3_endpoint.clone().unwrap_or_default();
+        let (scheme, host) = endpoint.split_once("://").unwrap_or(("https", endpoint.as_str()));
+        let credentials = match (&config.artifact_s3_access_key_id, &config.artifact_s3_secret_access_key) {
+            (Some(access_key_id), Some(secret_access_key)) => Some(AwsCredentials {
+                access_key_id: access_key_id.clone(),
+                secret_access_key: secret_access_key.clone(),
+                session_token: None,
+            }),
+            _ => None,
+        };
+
+        Self {
+            client: Client::builder().timeout(S3_REQUEST_TIMEOUT).build().unwrap_or_default(),
+            scheme: scheme.to_string(),
+            endpoint_host: host.trim_end_matches('/').to_string(),
+            bucket: config.artifact_s3_bucket.clone().unwrap_or_default(),
+            region: config.artifact_s3_region.clone(),
+            credentials,
+            path_style: config.artifact_s3_path_style,
+            prefix: format!("{}/", prefix.trim_matches('/')),
+            part_size: config.artifact_s3_part_size_bytes as usize,
+        }
+    }
+
+    /// Host and canonical path of an object, or of the bucket
+    fn address(&self, key: Option<&str>) -> (String, String) {
+        let object = key.map(|key| uri_encode(&format!("{}{}", self.prefix, key), false)).unwrap_or_default();
+        if self.path_style {
+            (self.endpoint_host.clone(), format!("/{}/{}", self.bucket, object))
+        } else {
+            (format!("{}.{}", self.bucket, self.endpoint_host), format!("/{}", object))
+        }
+    }
+
+    /// Send a signed request for an object, or for the bucket
+    ///
+    /// Missing objects fail with [`AppError::NotFound`], other error
+    /// responses with [`AppError::ExternalApi`].
+    async fn request(
+        &self,
+        method: Method,
+        key: Option<&str>,
+        query: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> Result<Response, AppError> {
+        let (host, path) = self.address(key);
+        let mut query: Vec<(String, String)> =
+            query.iter().map(|(name, value)| (uri_encode(name, true), uri_encode(value, true))).collect();
+        query.sort();
+        let query = query.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join("&");
+
+        let payload_hash = if body.is_empty() {
+            EMPTY_PAYLOAD_SHA256.to_string()
+        } else {
+            format!("{:x}", Sha256::digest(&body))
+        };
+        let url = if query.is_empty() {
+            format!("{}://{}{}", self.scheme, host, path)
+        } else {
+            format!("{}://{}{}?{}", self.scheme, host, path, query)
+        };
+
+        let mut builder = self.client.request(method.clone(), url);
+        for (name, value) in self.sign(&method, &host, &path, &query, &payload_hash, Utc::now()) {
+            builder = builder.header(name, value);
+        }
+        let response = builder.body(body).send().await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            StatusCode::NOT_FOUND => Err(AppError::NotFound(format!(
+                "Object {} not found",
+                key.unwrap_or(&self.bucket)
+            ))),
+            status => {
+                let text = response.text().await.unwrap_or_default();
+                let code = xml_values(&text, "Code").into_iter().next().unwrap_or_default();
+                Err(AppError::ExternalApi(format!(
+                    "Object store answered {} {} {}",
+                    method,
+                    status,
+                    code
+                )))
+            }
+        }
+    }
+
+    /// Headers authenticating a request, without the host header, which is
+    /// sent anyway
+    fn sign(
+        &self,
+        method: &Method,
+        host: &str,
+        path: &str,
+        query: &str,
+        payload_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<(&'static str, String)> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let mut headers = vec![
+            ("x-amz-content-sha256", payload_hash.to_string()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        let Some(credentials) = &self.credentials else {
+            return headers;
+        };
+        if let Some(token) = &credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+
+        // Canonical headers must be sorted by name
+        let mut signed = vec![("host", host.to_string())];
+        signed.extend(headers.iter().cloned());
+        let signed_headers = signed.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+        let canonical_headers: String = signed.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method, path, query, canonical_headers, signed_headers, payload_hash
+        );
+
+        let date = now.format("%Y%m%d").to_string();
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+            amz_date,
+            scope,
+            Sha256::digest(canonical_request.as_bytes())
+        );
+        let signature = aws_signature(&credentials.secret_access_key, &date, &self.region, "s3", &string_to_sign);
+
+        headers.push((
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                credentials.access_key_id, scope, signed_headers, signature
+            ),
+        ));
+        headers
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3Store {
+    fn location(&self) -> String {
+        format!("s3://{}/{}", self.bucket, self.prefix)
+    }
+
+    async fn create(&self, key: &str) -> Result<Box<dyn ObjectWriter>, AppError> {
+        Ok(Box::new(S3Writer {
+            store: self.clone(),
+            key: key.to_string(),
+            buffer: Vec::new(),
+            upload_id: None,
+            etags: Vec::new(),
+            size: 0,
+        }))
+    }
+
+    async fn open(&self, key: &str) -> Result<ObjectReader, AppError> {
+        let response = self.request(Method::GET, Some(key), &[], Vec::new()).await?;
+        let chunks = futures_util::stream::try_unfold(response, |mut response| async move {
+            let chunk = response.chunk().await.map_err(std::io::Error::other)?;
+            Ok::<_, std::io::Error>(chunk.map(|chunk| (chunk, response)))
+        });
+
+        Ok(Box::pin(StreamReader::new(Box::pin(chunks))))
+    }
+
+    async fn head(&self, key: &str) -> Result<Option<ObjectInfo>, AppError> {
+        let response = match self.request(Method::HEAD, Some(key), &[], Vec::new()).await {
+            Ok(response) => response,
+            Err(AppError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let header = |name: &str| response.headers().get(name).and_then(|value| value.to_str().ok());
+
+        Ok(Some(ObjectInfo {
+            key: key.to_string(),
+            size_bytes: header("content-length").and_then(|length| length.parse().ok()).unwrap_or(0),
+            modified_at: header("last-modified")
+                .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+                .map(|date| date.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now),
+        }))
+    }
+
+    async fn list(&self) -> Result<Vec<ObjectInfo>, AppError> {
+        let mut objects = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", self.prefix.as_str())];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token));
+            }
+            let body = self.request(Method::GET, None, &query, Vec::new()).await?.text().await?;
+
+            for contents in xml_values(&body, "Contents") {
+                let value = |tag| xml_values(&contents, tag).into_iter().next().unwrap_or_default();
+                let key = value("Key");
+                objects.push(ObjectInfo {
+                    key: key.strip_prefix(&self.prefix).unwrap_or(&key).to_string(),
+                    size_bytes: value("Size").parse().unwrap_or(0),
+                    modified_at: DateTime::parse_from_rfc3339(&value("LastModified"))
+                        .map(|date| date.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                });
+            }
+
+            token = xml_values(&body, "NextContinuationToken").into_iter().next();
+            if token.is_none() {
+                return Ok(objects);
+            }
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match self.request(Method::DELETE, Some(key), &[], Vec::new()).await {
+            Ok(_) | Err(AppError::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Object sent in a single request, or part by part once its content
+/// outgrows one part
+struct S3Writer {
+    store: S3Store,
+    key: String,
+    /// Content not sent yet
+    buffer: Vec<u8>,
+    /// Multipart upload, once started
+    upload_id: Option<String>,
+    /// ETags of the sent parts, in order
+    etags: Vec<String>,
+    size: u64,
+}
+
+impl S3Writer {
+    async fn send_part(&mut self, part: Vec<u8>) -> Result<(), AppError> {
+        let upload_id = match &self.upload_id {
+            Some(upload_id) => upload_id.clone(),
+            None => {
+                let body = self
+                    .store
+                    .request(Method::POST, Some(&self.key), &[("uploads", "")], Vec::new())
+                    .await?
+                    .text()
+                    .await?;
+                let upload_id = xml_values(&body, "UploadId").into_iter().next().ok_or_else(|| {
+                    AppError::ExternalApi("Object store did not start a multipart upload".to_string())
+                })?;
+                debug!("Started multipart upload {} of {}", upload_id, self.key);
+                self.upload_id = Some(upload_id.clone());
+                upload_id
+            }
+        };
+
+        let part_number = (self.etags.len() + 1).to_string();
+        let response = self
+            .store
+            .request(
+                Method::PUT,
+                Some(&self.key),
+                &[("partNumber", &part_number), ("uploadId", &upload_id)],
+                part,
+            )
+            .await?;
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|etag| etag.to_str().ok())
+            .ok_or_else(|| AppError::ExternalApi("Object store did not return the ETag of a part".to_string()))?;
+        self.etags.push(etag.to_string());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ObjectWriter for S3Writer {
+    async fn write(&mut self, chunk: &[u8]) -> Result<(), AppError> {
+        self.buffer.extend_from_slice(chunk);
+        self.size += chunk.len() as u64;
+        while self.buffer.len() > self.store.part_size {
+            let rest = self.buffer.split_off(self.store.part_size);
+            let part = std::mem::replace(&mut self.buffer, rest);
+            self.send_part(part).await?;
+        }
+        Ok(())
+    }
+
+    async fn finish(mut self: Box<Self>) -> Result<u64, AppError> {
+        let Some(upload_id) = self.upload_id.clone() else {
+            let content = std::mem::take(&mut self.buffer);
+            self.store.request(Method::PUT, Some(&self.key), &[], content).await?;
+            return Ok(self.size);
+        };
+
+        let part = std::mem::take(&mut self.buffer);
+        if !part.is_empty() {
+            if let Err(e) = self.send_part(part).await {
+                self.abort().await;
+                return Err(e);
+            }
+        }
+        let parts: String = self
+            .etags
+            .iter()
+            .enumerate()
+            .map(|(i, etag)| format!("<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>", i + 1, xml_escape(etag)))
+            .collect();
+        let body = format!("<CompleteMultipartUpload>{}</CompleteMultipartUpload>", parts);
+        let completed = self
+            .store
+            .request(Method::POST, Some(&self.key), &[("uploadId", &upload_id)], body.into_bytes())
+            .await;
+        if let Err(e) = completed {
+            self.abort().await;
+            return Err(e);
+        }
+
+        debug!("Completed multipart upload of {} in {} parts", self.key, self.etags.len());
+        Ok(self.size)
+    }
+
+    async fn abort(self: Box<Self>) {
+        // Nothing reached the store before a multipart upload started
+        let Some(upload_id) = &self.upload_id else {
+            return;
+        };
+        if let Err(e) = self
+            .store
+            .request(Method::DELETE, Some(&self.key), &[("uploadId", upload_id)], Vec::new())
+            .await
+        {
+            warn!("Failed to abort multipart upload of {}: {}", self.key, e);
+        }
+    }
+}
+
+/// Percent-encode all but the unreserved characters, and `/` unless
+/// `encode_slash`
+fn uri_encode(text: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Unescaped contents of every `tag` element of an XML document
+///
+/// Enough for the flat responses of the S3 API; nested elements of the
+/// same name are not supported.
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find(&close) else {
+            break;
+        };
+        values.push(xml_unescape(&after[..end]));
+        rest = &after[end + close.len()..];
+    }
+    values
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use wiremock::matchers::{body_string_contains, header_exists, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn s3_config(endpoint: &str) -> AppConfig {
+        let mut config = AppConfig::from_env().unwrap();
+        config.artifact_storage = ArtifactStorage::S3;
+        config.artifact_s3_endpoint = Some(endpoint.to_string());
+        config.artifact_s3_bucket = Some("artifacts".to_string());
+        config.artifact_s3_access_key_id = Some("AKIDEXAMPLE".to_string());
+        config.artifact_s3_secret_access_key = Some("secret".to_string());
+        config.artifact_s3_part_size_bytes = 10;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_filesystem_store_round_trip() {
+        let root = std::env::temp_dir().join(format!("vyos-webui-objects-{}", uuid::Uuid::new_v4()));
+        let store = FilesystemStore::new(&root);
+
+        let mut writer = store.create("image/vyos.iso").await.unwrap();
+        writer.write(b"ISO").await.unwrap();
+        assert_eq!(writer.finish().await.unwrap(), 3);
+
+        let mut content = Vec::new();
+        store.open("image/vyos.iso").await.unwrap().read_to_end(&mut content).await.unwrap();
+        assert_eq!(content, b"ISO");
+        let objects = store.list().await.unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].key, "image/vyos.iso");
+        assert_eq!(store.head("image/vyos.iso").await.unwrap().unwrap().size_bytes, 3);
+
+        store.delete("image/vyos.iso").await.unwrap();
+        store.delete("image/vyos.iso").await.unwrap();
+        assert!(matches!(store.open("image/vyos.iso").await, Err(AppError::NotFound(_))));
+        assert!(store.head("image/vyos.iso").await.unwrap().is_none());
+
+        fs::remove_dir_all(&root).await.unwrap();
+    }
+
+    #[test]
+    fn test_walk_directory_lists_nested_files() {
+        let root = std::env::temp_dir().join(format!("vyos-webui-storage-usage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("image")).unwrap();
+        std::fs::write(root.join("config.json"), b"{}").unwrap();
+        std::fs::write(root.join("image").join("vyos.iso"), vec![0u8; 1000]).unwrap();
+
+        let mut objects = walk_directory(&root).unwrap();
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        let listed: Vec<_> = objects.iter().map(|object| (object.key.as_str(), object.size_bytes)).collect();
+        assert_eq!(listed, [("config.json", 2), ("image/vyos.iso", 1000)]);
+        assert!(walk_directory(&root.join("missing")).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_s3_store_uploads_large_objects_in_parts() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/artifacts/uploads/image/vyos.iso"))
+            .and(query_param("uploads", ""))
+            .and(header_exists("authorization"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<InitiateMultipartUploadResult><UploadId>up-1</UploadId></InitiateMultipartUploadResult>",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/artifacts/uploads/image/vyos.iso"))
+            .and(query_param("uploadId", "up-1"))
+            .respond_with(ResponseTemplate::new(200).insert_header("etag", "\"part\""))
+            .expect(4)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/artifacts/uploads/image/vyos.iso"))
+            .and(query_param("uploadId", "up-1"))
+            .and(body_string_contains("<PartNumber>4</PartNumber>"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let store = S3Store::new(&s3_config(&server.uri()), "uploads");
+        let mut writer = store.create("image/vyos.iso").await.unwrap();
+        for chunk in b"0123456789abcdefghijklmnopqrstuvwxyz".chunks(7) {
+            writer.write(chunk).await.unwrap();
+        }
+        assert_eq!(writer.finish().await.unwrap(), 36);
+    }
+
+    #[tokio::test]
+    async fn test_s3_store_reads_and_lists_objects() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/artifacts/uploads/config/a%20b"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/artifacts/uploads/config/a%20b"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/artifacts/"))
+            .and(query_param("prefix", "uploads/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<ListBucketResult><Contents><Key>uploads/config/a b</Key>\
+                 <LastModified>2026-01-02T03:04:05.000Z</LastModified><Size>2</Size></Contents></ListBucketResult>",
+            ))
+            .mount(&server)
+            .await;
+
+        let store = S3Store::new(&s3_config(&server.uri()), "uploads");
+        let mut writer = store.create("config/a b").await.unwrap();
+        writer.write(b"{}").await.unwrap();
+        writer.finish().await.unwrap();
+
+        let mut content = Vec::new();
+        store.open("config/a b").await.unwrap().read_to_end(&mut content).await.unwrap();
+        assert_eq!(content, b"{}");
+        assert!(matches!(store.open("config/missing").await, Err(AppError::NotFound(_))));
+
+        let objects = store.list().await.unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].key, "config/a b");
+        assert_eq!(objects[0].size_bytes, 2);
+    }
+
+    #[test]
+    fn test_uri_encode() {
+        assert_eq!(uri_encode("uploads/image/vyos 1.4.iso", false), "uploads/image/vyos%201.4.iso");
+        assert_eq!(uri_encode("a/b=c", true), "a%2Fb%3Dc");
+    }
+}
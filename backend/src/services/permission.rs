@@ -0,0 +1,139 @@
+//! Permission Service
+//!
+//! A user holds a permission when they are an administrator, when one of
+//! their roles has it, or when it was granted to them directly. Grants are
+//! managed by administrators.
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::permission::{SetUserPermissionsRequest, UserPermissions};
+use crate::models::timestamp::db_now;
+
+/// Permission service
+#[derive(Clone)]
+pub struct PermissionService {
+    db: Database,
+}
+
+impl PermissionService {
+    /// Create a new permission service
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Whether the authenticated user holds a permission
+    pub async fn has_permission(&self, claims: &Claims, permission: &str) -> Result<bool, AppError> {
+        let user = self
+            .db
+            .find_user_by_id(&claims.sub)
+            .await?
+            .ok_or_else(|| AppError::Auth("User not found".to_string()))?;
+        if user.is_superuser {
+            return Ok(true);
+        }
+
+        let held: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM permissions p \
+             WHERE p.name = ? \
+               AND (p.id IN (SELECT rp.permission_id FROM role_permissions rp \
+                             JOIN user_roles ur ON ur.role_id = rp.role_id WHERE ur.user_id = ?) \
+                    OR p.id IN (SELECT permission_id FROM user_permissions WHERE user_id = ?))",
+        )
+        .bind(permission)
+        .bind(&user.id)
+        .bind(&user.id)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        Ok(held > 0)
+    }
+
+    /// Get the permissions of a user
+    pub async fn get_user_permissions(&self, user_id: Uuid) -> Result<UserPermissions, AppError> {
+        let user = self
+            .db
+            .find_user_by_id(&user_id.to_string())
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let granted: Vec<String> = sqlx::query_scalar(
+            "SELECT p.name FROM user_permissions up JOIN permissions p ON p.id = up.permission_id \
+             WHERE up.user_id = ? ORDER BY p.name",
+        )
+        .bind(&user.id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let permissions: Vec<String> = if user.is_superuser {
+            sqlx::query_scalar("SELECT name FROM permissions ORDER BY name")
+                .fetch_all(self.db.pool())
+                .await?
+        } else {
+            sqlx::query_scalar(
+                "SELECT DISTINCT p.name FROM permissions p \
+                 WHERE p.id IN (SELECT rp.permission_id FROM role_permissions rp \
+                                JOIN user_roles ur ON ur.role_id = rp.role_id WHERE ur.user_id = ?) \
+                    OR p.id IN (SELECT permission_id FROM user_permissions WHERE user_id = ?) \
+                 ORDER BY p.name",
+            )
+            .bind(&user.id)
+            .bind(&user.id)
+            .fetch_all(self.db.pool())
+            .await?
+        };
+
+        Ok(UserPermissions { user_id, permissions, granted })
+    }
+
+    /// Replace the permissions granted to a user directly
+    ///
+    /// Fails without changes when a permission does not exist.
+    pub async fn set_user_permissions(
+        &self,
+        user_id: Uuid,
+        request: SetUserPermissionsRequest,
+        granted_by: &str,
+    ) -> Result<UserPermissions, AppError> {
+        if self.db.find_user_by_id(&user_id.to_string()).await?.is_none() {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        let mut permission_ids = Vec::new();
+        for name in &request.permissions {
+            let id: Option<String> = sqlx::query_scalar("SELECT id FROM permissions WHERE name = ?")
+                .bind(name)
+                .fetch_optional(self.db.pool())
+                .await?;
+            match id {
+                Some(id) if !permission_ids.contains(&id) => permission_ids.push(id),
+                Some(_) => {}
+                None => return Err(AppError::Validation(format!("Unknown permission: {}", name))),
+            }
+        }
+
+        let mut tx = self.db.pool().begin().await?;
+        sqlx::query("DELETE FROM user_permissions WHERE user_id = ?")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        for permission_id in &permission_ids {
+            sqlx::query(
+                "INSERT INTO user_permissions (user_id, permission_id, granted_by, created_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(user_id.to_string())
+            .bind(permission_id)
+            .bind(granted_by)
+            .bind(db_now())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        info!("{} set the permissions granted to user {}: {:?}", granted_by, user_id, request.permissions);
+        self.get_user_permissions(user_id).await
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::quota::{
+    QuotaResource, QuotaUsage, TeamQuota, TeamQuotaResponse, TeamUsageResponse,
+    UpdateTeamQuotaRequest,
+};
+
+/// Quota service for per-team resource limits
+#[derive(Clone)]
+pub struct QuotaService {
+    db: Database,
+    defaults: TeamQuota,
+}
+
+impl QuotaService {
+    /// Create a new quota service
+    pub fn new(db: Database, config: &AppConfig) -> Self {
+        Self {
+            db,
+            defaults: config.default_team_quota.clone(),
+        }
+    }
+
+    /// Get the configured and effective quota of a team
+    pub async fn get_quota(&self, team_id: Uuid) -> Result<TeamQuotaResponse, AppError> {
+        self.ensure_team_exists(team_id).await?;
+
+        let overrides = self
+            .db
+            .find_team_quota(&team_id.to_string())
+            .await?
+            .unwrap_or_default();
+        let effective = overrides.clone().or(&self.defaults);
+
+        Ok(TeamQuotaResponse {
+            team_id,
+            overrides,
+            effective,
+        })
+    }
+
+    /// Replace the quota overrides of a team
+    pub async fn set_quota(
+        &self,
+        team_id: Uuid,
+        request: UpdateTeamQuotaRequest,
+    ) -> Result<TeamQuotaResponse, AppError> {
+        self.ensure_team_exists(team_id).await?;
+
+        self.db
+            .upsert_team_quota(&team_id.to_string(), &request)
+            .await?;
+
+        info!("Updated quota for team {}: {:?}", team_id, request);
+
+        self.get_quota(team_id).await
+    }
+
+    /// Get the team's consumption of each quota resource
+    pub async fn get_usage(&self, team_id: Uuid) -> Result<TeamUsageResponse, AppError> {
+        let quota = self.get_quota(team_id).await?.effective;
+
+        let mut usage = vec![];
+        for resource in QuotaResource::ALL {
+            let used = self.count_usage(team_id, resource).await?;
+            usage.push(QuotaUsage::new(resource, used, quota.limit(resource)));
+        }
+
+        Ok(TeamUsageResponse {
+            team_id,
+            usage,
+            metric_retention_days: quota.metric_retention_days,
+        })
+    }
+
+    /// Ensure the team can own one more resource of the given kind
+    ///
+    /// Unowned resources (`team_id` of `None`) are not subject to quotas.
+    pub async fn ensure_capacity(
+        &self,
+        team_id: Option<Uuid>,
+        resource: QuotaResource,
+    ) -> Result<(), AppError> {
+        let Some(team_id) = team_id else {
+            return Ok(());
+        };
+
+        let limit = self.get_quota(team_id).await?.effective.limit(resource);
+        let used = self.count_usage(team_id, resource).await?;
+        let usage = QuotaUsage::new(resource, used, limit);
+
+        if !usage.has_capacity() {
+            return Err(AppError::QuotaExceeded(format!(
+                "Team has reached its limit of {} {}",
+                limit.unwrap_or_default(),
+                resource.label()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Compute the oldest metric timestamp to keep for each node
+    ///
+    /// Nodes whose team has no retention limit, and unowned nodes when no
+    /// default is configured, are omitted and keep all their metrics.
+    pub async fn metric_retention_cutoffs(&self) -> Result<HashMap<String, DateTime<Utc>>, AppError> {
+        let now = Utc::now();
+
+        Ok(self
+            .db
+            .list_node_retention_overrides()
+            .await?
+            .into_iter()
+            .filter_map(|(node_id, days)| {
+                let days = days
+                    .map(|d| d.max(0) as u32)
+                    .or(self.defaults.metric_retention_days)?;
+                Some((node_id, now - Duration::days(days as i64)))
+            })
+            .collect())
+    }
+
+    /// Count how many resources of a kind a team owns
+    ///
+    /// Templates and API keys are not stored yet, so they always count as
+    /// zero until those resources are introduced.
+    async fn count_usage(&self, team_id: Uuid, resource: QuotaResource) -> Result<u64, AppError> {
+        match resource {
+            QuotaResource::Nodes => self.db.count_team_nodes(&team_id.to_string()).await,
+            QuotaResource::Templates | QuotaResource::ApiKeys => Ok(0),
+        }
+    }
+
+    /// Fail with NotFound if the team does not exist
+    async fn ensure_team_exists(&self, team_id: Uuid) -> Result<(), AppError> {
+        if self.db.find_team_by_id(&team_id.to_string()).await?.is_none() {
+            return Err(AppError::NotFound(format!("Team {} not found", team_id)));
+        }
+
+        Ok(())
+    }
+}
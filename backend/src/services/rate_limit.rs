@@ -0,0 +1,156 @@
+//! Rate Limiter
+//!
+//! Counts requests per client in one-minute windows. Sign-in and
+//! registration are limited per IP address to slow down brute force, and
+//! calls proxied to nodes are limited per user so no one can saturate a
+//! router. Counts are kept in memory per backend instance.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+/// Length of a rate limiting window
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Number of tracked clients above which expired windows are dropped
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Group of endpoints sharing a limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitBucket {
+    /// Sign-in and registration, per IP address
+    Auth,
+    /// Calls proxied to a node, per user
+    NodeProxy,
+}
+
+impl RateLimitBucket {
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::Auth => "sign-in attempts",
+            Self::NodeProxy => "node requests",
+        }
+    }
+}
+
+/// Requests counted for a client in the current window
+#[derive(Debug, Clone, Copy)]
+struct RateWindow {
+    started: Instant,
+    count: u32,
+}
+
+/// Request was refused by the rate limiter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitExceeded {
+    pub limit: u32,
+    /// Time until the client's window starts over
+    pub retry_after: Duration,
+    bucket: RateLimitBucket,
+}
+
+impl From<RateLimitExceeded> for AppError {
+    fn from(exceeded: RateLimitExceeded) -> Self {
+        AppError::RateLimited(format!(
+            "at most {} {} per minute are accepted",
+            exceeded.limit,
+            exceeded.bucket.describe()
+        ))
+    }
+}
+
+/// Per-client request limiter
+#[derive(Clone)]
+pub struct RateLimiter {
+    auth_limit: u32,
+    node_limit: u32,
+    windows: Arc<Mutex<HashMap<(RateLimitBucket, String), RateWindow>>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter with the configured limits
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            auth_limit: config.rate_limit_auth_per_minute,
+            node_limit: config.rate_limit_node_per_minute,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Requests a client may make per minute, 0 when unlimited
+    pub fn limit(&self, bucket: RateLimitBucket) -> u32 {
+        match bucket {
+            RateLimitBucket::Auth => self.auth_limit,
+            RateLimitBucket::NodeProxy => self.node_limit,
+        }
+    }
+
+    /// Count a request of a client against its window
+    ///
+    /// `client` identifies the caller: the user ID of authenticated callers,
+    /// otherwise their IP address.
+    pub fn check(&self, bucket: RateLimitBucket, client: &str) -> Result<(), RateLimitExceeded> {
+        self.check_at(bucket, client, Instant::now())
+    }
+
+    fn check_at(&self, bucket: RateLimitBucket, client: &str, now: Instant) -> Result<(), RateLimitExceeded> {
+        let limit = self.limit(bucket);
+        if limit == 0 {
+            return Ok(());
+        }
+
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.len() > MAX_TRACKED_CLIENTS {
+            windows.retain(|_, w| now.duration_since(w.started) < RATE_WINDOW);
+        }
+
+        let window = windows
+            .entry((bucket, client.to_string()))
+            .or_insert(RateWindow { started: now, count: 0 });
+        if now.duration_since(window.started) >= RATE_WINDOW {
+            *window = RateWindow { started: now, count: 0 };
+        }
+
+        if window.count >= limit {
+            return Err(RateLimitExceeded {
+                limit,
+                retry_after: RATE_WINDOW.saturating_sub(now.duration_since(window.started)),
+                bucket,
+            });
+        }
+
+        window.count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_per_bucket_and_client() {
+        let mut config = AppConfig::from_env().unwrap();
+        config.rate_limit_auth_per_minute = 2;
+        config.rate_limit_node_per_minute = 0;
+        let limiter = RateLimiter::new(&config);
+        let start = Instant::now();
+
+        assert!(limiter.check_at(RateLimitBucket::Auth, "10.0.0.1", start).is_ok());
+        assert!(limiter.check_at(RateLimitBucket::Auth, "10.0.0.1", start).is_ok());
+        let exceeded = limiter
+            .check_at(RateLimitBucket::Auth, "10.0.0.1", start + Duration::from_secs(20))
+            .unwrap_err();
+        assert_eq!(exceeded.retry_after, Duration::from_secs(40));
+        assert!(matches!(AppError::from(exceeded), AppError::RateLimited(_)));
+
+        assert!(limiter.check_at(RateLimitBucket::Auth, "10.0.0.2", start).is_ok());
+        assert!(limiter.check_at(RateLimitBucket::Auth, "10.0.0.1", start + RATE_WINDOW).is_ok());
+        for _ in 0..10 {
+            assert!(limiter.check_at(RateLimitBucket::NodeProxy, "10.0.0.1", start).is_ok());
+        }
+    }
+}
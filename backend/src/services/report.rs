@@ -0,0 +1,1046 @@
+//! Report Service
+//!
+//! Generates fleet health, SLA, configuration change and compliance reports
+//! on demand or on a schedule. Reports are rendered to HTML or PDF, stored
+//! as downloadable artifacts, and optionally emailed as attachments.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::custom_field::custom_field_text;
+use crate::models::monitoring::{AlertSeverity, AlertStatus};
+use crate::models::report::{
+    CreateReportScheduleRequest, GenerateReportRequest, Report, ReportArtifact, ReportFormat, ReportFrequency,
+    ReportKind, ReportListQuery, ReportListResponse, ReportSchedule, DEFAULT_REPORT_PERIOD_DAYS, SLA_TARGET_PERCENT,
+};
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::services::{CustomFieldService, MailAttachment, Mailer, MonitoringService, NodeService};
+
+const REPORT_COLUMNS: &str = "id, kind, format, title, period_start, period_end, size_bytes, \
+    schedule_id, created_by, emailed_to, created_at";
+
+const SCHEDULE_COLUMNS: &str = "id, kind, format, frequency, recipients, created_by, \
+    next_run_at, last_run_at, created_at";
+
+/// Report service
+#[derive(Clone)]
+pub struct ReportService {
+    db: Database,
+    monitoring: MonitoringService,
+    nodes: NodeService,
+    fields: CustomFieldService,
+    mailer: Mailer,
+}
+
+impl ReportService {
+    /// Create a new report service
+    pub fn new(
+        db: Database,
+        monitoring: MonitoringService,
+        nodes: NodeService,
+        fields: CustomFieldService,
+        mailer: Mailer,
+    ) -> Self {
+        Self {
+            db,
+            monitoring,
+            nodes,
+            fields,
+            mailer,
+        }
+    }
+
+    // ========================================================================
+    // Reports
+    // ========================================================================
+
+    /// Generate a report covering the requested number of days up to now
+    ///
+    /// The report is stored before it is emailed, so it can still be
+    /// downloaded when delivery fails.
+    pub async fn generate_report(&self, claims: &Claims, request: GenerateReportRequest) -> Result<Report, AppError> {
+        self.mailer.check_recipients(&request.recipients)?;
+
+        let period_end = Utc::now();
+        let days = request.period_days.unwrap_or(DEFAULT_REPORT_PERIOD_DAYS);
+        let period_start = period_end - Duration::days(days as i64);
+
+        let mut artifact = self
+            .render(request.kind, request.format, period_start, period_end)
+            .await?;
+        artifact.report.created_by = Some(claims.username.clone());
+        self.store(&artifact).await?;
+
+        if !request.recipients.is_empty() {
+            self.email(&mut artifact, &request.recipients).await?;
+        }
+
+        info!("Report {} ({}) generated by {}", artifact.report.id, request.kind.as_str(), claims.username);
+        Ok(artifact.report)
+    }
+
+    /// List generated reports, newest first
+    pub async fn list_reports(&self, query: ReportListQuery) -> Result<ReportListResponse, AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(50).clamp(1, 500);
+        let offset = (page - 1) * page_size;
+
+        let mut where_clauses = vec!["1=1".to_string()];
+        let mut bind_values: Vec<String> = vec![];
+
+        if let Some(kind) = query.kind {
+            where_clauses.push("kind = ?".to_string());
+            bind_values.push(kind.as_str().to_string());
+        }
+
+        let where_clause = where_clauses.join(" AND ");
+
+        let count_query = format!("SELECT COUNT(*) FROM reports WHERE {}", where_clause);
+        let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
+        for value in &bind_values {
+            count_query_builder = count_query_builder.bind(value);
+        }
+        let total = count_query_builder.fetch_one(self.db.pool()).await? as u64;
+
+        let data_query = format!(
+            "SELECT {} FROM reports WHERE {} ORDER BY created_at DESC, id LIMIT ? OFFSET ?",
+            REPORT_COLUMNS, where_clause
+        );
+        let mut rows_builder = sqlx::query_as::<_, ReportRow>(&data_query);
+        for value in &bind_values {
+            rows_builder = rows_builder.bind(value);
+        }
+        let rows = rows_builder
+            .bind(page_size as i64)
+            .bind(offset as i64)
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(ReportListResponse {
+            reports: rows.into_iter().map(report_from_row).collect(),
+            total,
+            page,
+            page_size,
+            total_pages: ((total as f64) / (page_size as f64)).ceil() as u32,
+        })
+    }
+
+    /// Get a report by ID
+    pub async fn get_report(&self, id: Uuid) -> Result<Report, AppError> {
+        let query = format!("SELECT {} FROM reports WHERE id = ?", REPORT_COLUMNS);
+
+        sqlx::query_as::<_, ReportRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(report_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Report {} not found", id)))
+    }
+
+    /// Get a report together with its rendered content
+    pub async fn get_artifact(&self, id: Uuid) -> Result<ReportArtifact, AppError> {
+        let report = self.get_report(id).await?;
+        let content: Vec<u8> = sqlx::query_scalar("SELECT content FROM reports WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_one(self.db.pool())
+            .await?;
+
+        Ok(ReportArtifact { report, content })
+    }
+
+    /// Delete a report
+    pub async fn delete_report(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM reports WHERE id = ?")
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Report {} not found", id)));
+        }
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Schedules
+    // ========================================================================
+
+    /// List report schedules, oldest first
+    pub async fn list_schedules(&self) -> Result<Vec<ReportSchedule>, AppError> {
+        let query = format!("SELECT {} FROM report_schedules ORDER BY created_at, id", SCHEDULE_COLUMNS);
+
+        let rows = sqlx::query_as::<_, ScheduleRow>(&query)
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(rows.into_iter().map(schedule_from_row).collect())
+    }
+
+    /// Schedule a recurring report
+    ///
+    /// The first run happens one interval from now and covers that interval.
+    pub async fn create_schedule(
+        &self,
+        claims: &Claims,
+        request: CreateReportScheduleRequest,
+    ) -> Result<ReportSchedule, AppError> {
+        self.mailer.check_recipients(&request.recipients)?;
+
+        let now = Utc::now();
+        let schedule = ReportSchedule {
+            id: Uuid::new_v4(),
+            kind: request.kind,
+            format: request.format,
+            frequency: request.frequency,
+            recipients: request.recipients,
+            created_by: Some(claims.username.clone()),
+            next_run_at: now + request.frequency.interval(),
+            last_run_at: None,
+            created_at: now,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO report_schedules
+                (id, kind, format, frequency, recipients, created_by, next_run_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(schedule.id.to_string())
+        .bind(schedule.kind.as_str())
+        .bind(schedule.format.as_str())
+        .bind(schedule.frequency.as_str())
+        .bind(serde_json::to_string(&schedule.recipients)?)
+        .bind(&schedule.created_by)
+        .bind(format_timestamp(&schedule.next_run_at))
+        .bind(format_timestamp(&schedule.created_at))
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Report schedule {} ({} {}) created by {}", schedule.id, schedule.frequency.as_str(), schedule.kind.as_str(), claims.username);
+        Ok(schedule)
+    }
+
+    /// Delete a report schedule; reports it produced are kept
+    pub async fn delete_schedule(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM report_schedules WHERE id = ?")
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Report schedule {} not found", id)));
+        }
+
+        Ok(())
+    }
+
+    /// Number of schedules whose next run is due
+    pub async fn count_due_schedules(&self) -> Result<u64, AppError> {
+        let due: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM report_schedules WHERE next_run_at <= ?")
+            .bind(db_now())
+            .fetch_one(self.db.pool())
+            .await?;
+
+        Ok(due as u64)
+    }
+
+    /// Generate the reports of all schedules that are due
+    ///
+    /// Each schedule runs at most once per call; the next run is planned one
+    /// interval from now, so runs missed while the server was down are not
+    /// caught up. Returns the number of generated reports.
+    pub async fn run_due_schedules(&self) -> Result<usize, AppError> {
+        let query = format!(
+            "SELECT {} FROM report_schedules WHERE next_run_at <= ? ORDER BY next_run_at, id",
+            SCHEDULE_COLUMNS
+        );
+        let due: Vec<ReportSchedule> = sqlx::query_as::<_, ScheduleRow>(&query)
+            .bind(db_now())
+            .fetch_all(self.db.pool())
+            .await?
+            .into_iter()
+            .map(schedule_from_row)
+            .collect();
+
+        let mut generated = 0;
+        for schedule in due {
+            let now = Utc::now();
+            sqlx::query("UPDATE report_schedules SET last_run_at = ?, next_run_at = ? WHERE id = ?")
+                .bind(format_timestamp(&now))
+                .bind(format_timestamp(&(now + schedule.frequency.interval())))
+                .bind(schedule.id.to_string())
+                .execute(self.db.pool())
+                .await?;
+
+            let mut artifact = self
+                .render(schedule.kind, schedule.format, now - schedule.frequency.interval(), now)
+                .await?;
+            artifact.report.schedule_id = Some(schedule.id);
+            artifact.report.created_by = schedule.created_by.clone();
+            self.store(&artifact).await?;
+            generated += 1;
+
+            if !schedule.recipients.is_empty() {
+                if let Err(e) = self.email(&mut artifact, &schedule.recipients).await {
+                    warn!("Failed to email scheduled report {}: {}", artifact.report.id, e);
+                }
+            }
+        }
+
+        Ok(generated)
+    }
+
+    // ========================================================================
+    // Generation
+    // ========================================================================
+
+    /// Collect the report data and render it
+    async fn render(
+        &self,
+        kind: ReportKind,
+        format: ReportFormat,
+        period_start: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+    ) -> Result<ReportArtifact, AppError> {
+        let sections = match kind {
+            ReportKind::FleetHealth => self.fleet_health_sections().await?,
+            ReportKind::Sla => self.sla_sections(period_start, period_end).await?,
+            ReportKind::ConfigChanges => self.config_change_sections(period_start, period_end).await?,
+            ReportKind::Compliance => self.compliance_sections(period_start, period_end).await?,
+        };
+
+        let created_at = Utc::now();
+        let document = ReportDocument {
+            title: kind.title().to_string(),
+            period: format!("{} to {}", format_timestamp(&period_start), format_timestamp(&period_end)),
+            generated_at: format_timestamp(&created_at),
+            sections,
+        };
+        let content = match format {
+            ReportFormat::Html => render_html(&document).into_bytes(),
+            ReportFormat::Pdf => render_pdf(&document),
+        };
+
+        Ok(ReportArtifact {
+            report: Report {
+                id: Uuid::new_v4(),
+                kind,
+                format,
+                title: document.title,
+                period_start,
+                period_end,
+                size_bytes: content.len() as u64,
+                schedule_id: None,
+                created_by: None,
+                emailed_to: vec![],
+                created_at,
+            },
+            content,
+        })
+    }
+
+    /// Node status, inventory fields, and active alerts
+    async fn fleet_health_sections(&self) -> Result<Vec<ReportSection>, AppError> {
+        let nodes = self.nodes.list_all_nodes().await?;
+        let fields = self.fields.list_fields().await?;
+        let node_names: HashMap<String, String> =
+            nodes.iter().map(|n| (n.id.to_string(), n.name.clone())).collect();
+        let mut alerts = self.monitoring.get_alerts(None, None, Some(AlertStatus::Active)).await?;
+        alerts.sort_by(|a, b| b.severity.cmp(&a.severity).then(b.triggered_at.cmp(&a.triggered_at)));
+
+        let mut by_status: BTreeMap<&str, usize> = BTreeMap::new();
+        for node in &nodes {
+            *by_status.entry(node.status.as_str()).or_default() += 1;
+        }
+
+        let count = |severity: AlertSeverity| alerts.iter().filter(|a| a.severity == severity).count();
+        let mut facts = vec![("Nodes".to_string(), nodes.len().to_string())];
+        facts.extend(by_status.into_iter().map(|(status, count)| (format!("Nodes {}", status), count.to_string())));
+        facts.push(("Active critical alerts".to_string(), count(AlertSeverity::Critical).to_string()));
+        facts.push(("Active warning alerts".to_string(), count(AlertSeverity::Warning).to_string()));
+
+        let mut columns = vec!["Name", "Host", "Status", "Version", "Last seen"];
+        columns.extend(fields.iter().map(|field| field.label.as_str()));
+
+        Ok(vec![
+            ReportSection::facts("Summary", facts),
+            ReportSection::table(
+                "Nodes",
+                &columns,
+                nodes
+                    .iter()
+                    .map(|n| {
+                        let mut row = vec![
+                            n.name.clone(),
+                            n.host.clone(),
+                            n.status.as_str().to_string(),
+                            n.version.clone().unwrap_or_default(),
+                            n.last_seen.map(|t| format_timestamp(&t)).unwrap_or_else(|| "never".to_string()),
+                        ];
+                        row.extend(fields.iter().map(|field| custom_field_text(n.custom_fields.get(&field.name))));
+                        row
+                    })
+                    .collect(),
+            ),
+            ReportSection::table(
+                "Active alerts",
+                &["Node", "Severity", "Title", "Triggered"],
+                alerts
+                    .iter()
+                    .map(|a| {
+                        vec![
+                            node_names.get(&a.node_id).cloned().unwrap_or_else(|| a.node_id.clone()),
+                            a.severity.as_str().to_string(),
+                            a.title.clone(),
+                            format_timestamp(&a.triggered_at),
+                        ]
+                    })
+                    .collect(),
+            ),
+        ])
+    }
+
+    /// Per-node availability, counting time covered by critical incidents as
+    /// downtime
+    async fn sla_sections(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<ReportSection>, AppError> {
+        let nodes = self.nodes.list_all_nodes().await?;
+        let incidents = sqlx::query_as::<_, (String, String, Option<String>)>(
+            r#"
+            SELECT node_id, opened_at, resolved_at FROM incidents
+            WHERE severity = 'critical' AND opened_at < ? AND (resolved_at IS NULL OR resolved_at > ?)
+            "#,
+        )
+        .bind(format_timestamp(&end))
+        .bind(format_timestamp(&start))
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut outages: HashMap<String, Vec<Interval>> = HashMap::new();
+        for (node_id, opened_at, resolved_at) in incidents {
+            let from = parse_db_timestamp(&opened_at).max(start);
+            let until = resolved_at.map(|t| parse_db_timestamp(&t)).unwrap_or(end).min(end);
+            outages.entry(node_id).or_default().push((from, until));
+        }
+
+        let period = (end - start).num_seconds().max(1) as f64;
+        let mut rows = vec![];
+        let mut breaching = 0;
+        let mut total_availability = 0.0;
+        for node in &nodes {
+            let intervals = outages.remove(&node.id.to_string()).unwrap_or_default();
+            let downtime = merged_duration(&intervals);
+            let availability = 100.0 * (1.0 - downtime.num_seconds() as f64 / period);
+            let met = availability >= SLA_TARGET_PERCENT;
+            if !met {
+                breaching += 1;
+            }
+            total_availability += availability;
+            rows.push(vec![
+                node.name.clone(),
+                intervals.len().to_string(),
+                format!("{} min", downtime.num_minutes()),
+                format!("{:.3}%", availability),
+                if met { "yes" } else { "no" }.to_string(),
+            ]);
+        }
+
+        let fleet_availability = if nodes.is_empty() { 100.0 } else { total_availability / nodes.len() as f64 };
+
+        Ok(vec![
+            ReportSection::facts(
+                "Summary",
+                vec![
+                    ("SLA target".to_string(), format!("{}%", SLA_TARGET_PERCENT)),
+                    ("Fleet availability".to_string(), format!("{:.3}%", fleet_availability)),
+                    ("Nodes breaching SLA".to_string(), breaching.to_string()),
+                ],
+            ),
+            ReportSection::table(
+                "Availability by node",
+                &["Node", "Critical incidents", "Downtime", "Availability", "Target met"],
+                rows,
+            ),
+        ])
+    }
+
+    /// Configuration commits made during the period
+    async fn config_change_sections(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ReportSection>, AppError> {
+        let changes = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, bool)>(
+            r#"
+            SELECT h.created_at, COALESCE(n.name, h.node_id), h.version,
+                   COALESCE(u.username, h.user_id), h.change_summary, h.is_rollback_point
+            FROM config_history h
+            LEFT JOIN nodes n ON n.id = h.node_id
+            LEFT JOIN users u ON u.id = h.user_id
+            WHERE h.created_at >= ? AND h.created_at <= ?
+            ORDER BY h.created_at, h.id
+            "#,
+        )
+        .bind(format_timestamp(&start))
+        .bind(format_timestamp(&end))
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut nodes_changed: Vec<&str> = changes.iter().map(|(_, node, ..)| node.as_str()).collect();
+        nodes_changed.sort_unstable();
+        nodes_changed.dedup();
+        let rollback_points = changes.iter().filter(|(.., rollback)| *rollback).count();
+
+        Ok(vec![
+            ReportSection::facts(
+                "Summary",
+                vec![
+                    ("Commits".to_string(), changes.len().to_string()),
+                    ("Nodes changed".to_string(), nodes_changed.len().to_string()),
+                    ("Rollback points".to_string(), rollback_points.to_string()),
+                ],
+            ),
+            ReportSection::table(
+                "Commits",
+                &["Time", "Node", "Version", "User", "Summary"],
+                changes
+                    .iter()
+                    .map(|(created_at, node, version, user, summary, _)| {
+                        vec![
+                            format_timestamp(&parse_db_timestamp(created_at)),
+                            node.clone(),
+                            version.clone(),
+                            user.clone().unwrap_or_default(),
+                            summary.clone().unwrap_or_default(),
+                        ]
+                    })
+                    .collect(),
+            ),
+        ])
+    }
+
+    /// Per-node checks: encrypted and verified API connections, a
+    /// configuration snapshot during the period, and a responsible team
+    async fn compliance_sections(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ReportSection>, AppError> {
+        let nodes = self.nodes.list_all_nodes().await?;
+        let snapshot_nodes: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT node_id FROM config_history WHERE created_at >= ? AND created_at <= ?",
+        )
+        .bind(format_timestamp(&start))
+        .bind(format_timestamp(&end))
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let check = |passed: bool| if passed { "pass" } else { "fail" }.to_string();
+        let mut compliant = 0;
+        let rows: Vec<Vec<String>> = nodes
+            .iter()
+            .map(|node| {
+                let checks = [
+                    node.use_https,
+                    node.use_https && node.verify_ssl,
+                    snapshot_nodes.contains(&node.id.to_string()),
+                    node.team_id.is_some(),
+                ];
+                let passed = checks.iter().all(|&c| c);
+                if passed {
+                    compliant += 1;
+                }
+                let mut row = vec![node.name.clone()];
+                row.extend(checks.into_iter().map(check));
+                row.push(if passed { "compliant" } else { "non-compliant" }.to_string());
+                row
+            })
+            .collect();
+
+        Ok(vec![
+            ReportSection::facts(
+                "Summary",
+                vec![
+                    ("Nodes".to_string(), nodes.len().to_string()),
+                    ("Compliant".to_string(), compliant.to_string()),
+                    ("Non-compliant".to_string(), (nodes.len() - compliant).to_string()),
+                ],
+            ),
+            ReportSection::table(
+                "Checks by node",
+                &["Node", "HTTPS", "TLS verification", "Config snapshot", "Team owner", "Result"],
+                rows,
+            ),
+        ])
+    }
+
+    /// Store a rendered report
+    async fn store(&self, artifact: &ReportArtifact) -> Result<(), AppError> {
+        let report = &artifact.report;
+        sqlx::query(
+            r#"
+            INSERT INTO reports
+                (id, kind, format, title, period_start, period_end, content, size_bytes,
+                 schedule_id, created_by, emailed_to, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(report.id.to_string())
+        .bind(report.kind.as_str())
+        .bind(report.format.as_str())
+        .bind(&report.title)
+        .bind(format_timestamp(&report.period_start))
+        .bind(format_timestamp(&report.period_end))
+        .bind(&artifact.content)
+        .bind(report.size_bytes as i64)
+        .bind(report.schedule_id.map(|id| id.to_string()))
+        .bind(&report.created_by)
+        .bind(serde_json::to_string(&report.emailed_to)?)
+        .bind(format_timestamp(&report.created_at))
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Email a stored report as an attachment and record the recipients
+    async fn email(&self, artifact: &mut ReportArtifact, recipients: &[String]) -> Result<(), AppError> {
+        let report = &artifact.report;
+        let body = format!(
+            "{} for {} to {} is attached.",
+            report.title,
+            format_timestamp(&report.period_start),
+            format_timestamp(&report.period_end)
+        );
+        let attachment = MailAttachment {
+            file_name: artifact.file_name(),
+            content_type: report.format.content_type().to_string(),
+            content: artifact.content.clone(),
+        };
+        self.mailer.send(recipients, &report.title, &body, Some(attachment)).await?;
+
+        artifact.report.emailed_to = recipients.to_vec();
+        sqlx::query("UPDATE reports SET emailed_to = ? WHERE id = ?")
+            .bind(serde_json::to_string(&artifact.report.emailed_to)?)
+            .bind(artifact.report.id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(())
+    }
+}
+
+type ReportRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    i64,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+fn report_from_row(
+    (id, kind, format, title, period_start, period_end, size_bytes, schedule_id, created_by, emailed_to, created_at): ReportRow,
+) -> Report {
+    Report {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        kind: ReportKind::parse(&kind),
+        format: ReportFormat::parse(&format),
+        title,
+        period_start: parse_db_timestamp(&period_start),
+        period_end: parse_db_timestamp(&period_end),
+        size_bytes: size_bytes as u64,
+        schedule_id: schedule_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        created_by,
+        emailed_to: serde_json::from_str(&emailed_to).unwrap_or_default(),
+        created_at: parse_db_timestamp(&created_at),
+    }
+}
+
+type ScheduleRow = (String, String, String, String, String, Option<String>, String, Option<String>, String);
+
+fn schedule_from_row(
+    (id, kind, format, frequency, recipients, created_by, next_run_at, last_run_at, created_at): ScheduleRow,
+) -> ReportSchedule {
+    ReportSchedule {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        kind: ReportKind::parse(&kind),
+        format: ReportFormat::parse(&format),
+        frequency: ReportFrequency::parse(&frequency),
+        recipients: serde_json::from_str(&recipients).unwrap_or_default(),
+        created_by,
+        next_run_at: parse_db_timestamp(&next_run_at),
+        last_run_at: last_run_at.map(|t| parse_db_timestamp(&t)),
+        created_at: parse_db_timestamp(&created_at),
+    }
+}
+
+/// Time span from its start to its end
+type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+/// Total time covered by possibly overlapping intervals
+fn merged_duration(intervals: &[Interval]) -> Duration {
+    let mut sorted: Vec<_> = intervals.iter().filter(|(from, until)| from < until).copied().collect();
+    sorted.sort();
+
+    let mut total = Duration::zero();
+    let mut current: Option<Interval> = None;
+    for (from, until) in sorted {
+        current = match current {
+            Some((start, end)) if from <= end => Some((start, end.max(until))),
+            Some((start, end)) => {
+                total += end - start;
+                Some((from, until))
+            }
+            None => Some((from, until)),
+        };
+    }
+    if let Some((start, end)) = current {
+        total += end - start;
+    }
+
+    total
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+/// Format-independent report content
+struct ReportDocument {
+    title: String,
+    period: String,
+    generated_at: String,
+    sections: Vec<ReportSection>,
+}
+
+/// Report section with key figures or a table
+struct ReportSection {
+    heading: String,
+    facts: Vec<(String, String)>,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl ReportSection {
+    fn facts(heading: &str, facts: Vec<(String, String)>) -> Self {
+        Self {
+            heading: heading.to_string(),
+            facts,
+            columns: vec![],
+            rows: vec![],
+        }
+    }
+
+    fn table(heading: &str, columns: &[&str], rows: Vec<Vec<String>>) -> Self {
+        Self {
+            heading: heading.to_string(),
+            facts: vec![],
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows,
+        }
+    }
+}
+
+/// Escape text for HTML element content and attribute values
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render a standalone HTML page
+fn render_html(document: &ReportDocument) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape_html(&document.title)));
+    html.push_str(
+        "<style>body{font-family:sans-serif;margin:2em;color:#222}\
+         table{border-collapse:collapse;margin-bottom:1.5em}\
+         th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+         th{background:#f0f0f0}.meta{color:#666}</style>\n",
+    );
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(&document.title)));
+    html.push_str(&format!(
+        "<p class=\"meta\">Period: {}<br>Generated: {}</p>\n",
+        escape_html(&document.period),
+        escape_html(&document.generated_at)
+    ));
+
+    for section in &document.sections {
+        html.push_str(&format!("<h2>{}</h2>\n", escape_html(&section.heading)));
+        if !section.facts.is_empty() {
+            html.push_str("<table>\n");
+            for (key, value) in &section.facts {
+                html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", escape_html(key), escape_html(value)));
+            }
+            html.push_str("</table>\n");
+        }
+        if !section.columns.is_empty() {
+            if section.rows.is_empty() {
+                html.push_str("<p>None.</p>\n");
+                continue;
+            }
+            html.push_str("<table>\n<tr>");
+            for column in &section.columns {
+                html.push_str(&format!("<th>{}</th>", escape_html(column)));
+            }
+            html.push_str("</tr>\n");
+            for row in &section.rows {
+                html.push_str("<tr>");
+                for cell in row {
+                    html.push_str(&format!("<td>{}</td>", escape_html(cell)));
+                }
+                html.push_str("</tr>\n");
+            }
+            html.push_str("</table>\n");
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// PDF page size (US Letter) and layout, in points
+const PDF_PAGE_WIDTH: u32 = 612;
+const PDF_PAGE_HEIGHT: u32 = 792;
+const PDF_MARGIN: u32 = 50;
+const PDF_FONT_SIZE: u32 = 9;
+const PDF_LEADING: u32 = 12;
+
+/// Characters per line; Courier glyphs are 0.6 em wide
+const PDF_LINE_CHARS: usize = ((PDF_PAGE_WIDTH - 2 * PDF_MARGIN) * 10 / (PDF_FONT_SIZE * 6)) as usize;
+const PDF_PAGE_LINES: usize = ((PDF_PAGE_HEIGHT - 2 * PDF_MARGIN) / PDF_LEADING) as usize;
+
+/// Widest a table column is laid out in the PDF
+const PDF_MAX_COLUMN_CHARS: usize = 32;
+
+/// Truncate text to a number of characters, marking the cut
+fn truncate_chars(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let mut truncated: String = s.chars().take(max.saturating_sub(1)).collect();
+    truncated.push('~');
+    truncated
+}
+
+/// Lay the document out as lines of monospaced text; `true` marks bold lines
+fn pdf_lines(document: &ReportDocument) -> Vec<(bool, String)> {
+    let mut lines = vec![
+        (true, document.title.clone()),
+        (false, format!("Period: {}", document.period)),
+        (false, format!("Generated: {}", document.generated_at)),
+    ];
+
+    for section in &document.sections {
+        lines.push((false, String::new()));
+        lines.push((true, section.heading.clone()));
+        for (key, value) in &section.facts {
+            lines.push((false, format!("{}: {}", key, value)));
+        }
+        if section.columns.is_empty() {
+            continue;
+        }
+        if section.rows.is_empty() {
+            lines.push((false, "None.".to_string()));
+            continue;
+        }
+
+        let widths: Vec<usize> = section
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                section
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .map(|cell| cell.chars().count())
+                    .chain([column.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+                    .min(PDF_MAX_COLUMN_CHARS)
+            })
+            .collect();
+        let layout = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:width$}", truncate_chars(cell, width), width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        lines.push((true, layout(&section.columns)));
+        for row in &section.rows {
+            lines.push((false, layout(row)));
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|(bold, line)| (bold, truncate_chars(&line, PDF_LINE_CHARS)))
+        .collect()
+}
+
+/// Escape text for a PDF string literal; the standard fonts only cover
+/// ASCII reliably, so other characters are replaced
+fn escape_pdf_text(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+/// Render a PDF document using the built-in Courier fonts
+fn render_pdf(document: &ReportDocument) -> Vec<u8> {
+    let lines = pdf_lines(document);
+    let pages: Vec<&[(bool, String)]> = lines.chunks(PDF_PAGE_LINES).collect();
+
+    // Objects 1-4 are the catalog, page tree and fonts; each page adds a
+    // page object and its content stream
+    let mut objects: Vec<String> = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len()).map(|i| format!("{} 0 R", 5 + 2 * i)).collect::<Vec<_>>().join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold >>".to_string(),
+    ];
+
+    for (i, page) in pages.iter().enumerate() {
+        let mut stream = format!(
+            "BT\n{} TL\n{} {} Td\n",
+            PDF_LEADING,
+            PDF_MARGIN,
+            PDF_PAGE_HEIGHT - PDF_MARGIN - PDF_FONT_SIZE
+        );
+        for (bold, line) in page.iter() {
+            stream.push_str(&format!(
+                "/{} {} Tf ({}) Tj T*\n",
+                if *bold { "F2" } else { "F1" },
+                PDF_FONT_SIZE,
+                escape_pdf_text(line)
+            ));
+        }
+        stream.push_str("ET");
+
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PDF_PAGE_WIDTH,
+            PDF_PAGE_HEIGHT,
+            6 + 2 * i
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}\nendstream", stream.len(), stream));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+
+    let xref_offset = pdf.len();
+    pdf.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    ));
+
+    pdf.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(rows: usize) -> ReportDocument {
+        ReportDocument {
+            title: "Fleet <Health>".to_string(),
+            period: "2024-01-01T00:00:00.000Z to 2024-01-08T00:00:00.000Z".to_string(),
+            generated_at: "2024-01-08T00:00:00.000Z".to_string(),
+            sections: vec![
+                ReportSection::facts("Summary", vec![("Nodes".to_string(), rows.to_string())]),
+                ReportSection::table(
+                    "Nodes",
+                    &["Name", "Status"],
+                    (0..rows).map(|i| vec![format!("edge-(#{})", i), "online".to_string()]).collect(),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_render_html_escapes_content() {
+        let html = render_html(&document(1));
+        assert!(html.contains("<h1>Fleet &lt;Health&gt;</h1>"));
+        assert!(html.contains("<td>edge-(#0)</td>"));
+        assert!(!html.contains("<Health>"));
+    }
+
+    #[test]
+    fn test_render_pdf_structure() {
+        let pdf = String::from_utf8(render_pdf(&document(100))).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(edge-\\(#99\\)  online) Tj"));
+        assert!(pdf.contains("/Count 2 "), "100 rows span two pages");
+
+        // Every cross-reference entry points at the start of its object
+        let xref = pdf.rfind("\nxref\n").unwrap() + 1;
+        let startxref: usize = pdf.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert_eq!(startxref, xref);
+        for (i, entry) in pdf[xref..].lines().skip(3).take_while(|l| l.ends_with(" n ")).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn test_merged_duration_overlapping_intervals() {
+        let t = |minutes: i64| DateTime::UNIX_EPOCH + Duration::minutes(minutes);
+        let intervals = [(t(0), t(10)), (t(5), t(20)), (t(30), t(40)), (t(50), t(45))];
+        assert_eq!(merged_duration(&intervals), Duration::minutes(30));
+        assert_eq!(merged_duration(&[]), Duration::zero());
+    }
+}
@@ -0,0 +1,480 @@
+//! Router Login Service
+//!
+//! Login users of a node's operating system, `system login user`, with
+//! their passwords and SSH public keys. Besides managing the users of one
+//! node, it rotates a user's key across many nodes and compares the users
+//! and keys of nodes with the operators expected on every node.
+
+use std::collections::BTreeSet;
+
+use base64ct::{Base64, Encoding};
+use serde_json::Value;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::node::{NodeChangeResult, NodeData};
+use crate::models::router_login::{
+    DesiredLoginUser, LoginDriftRequest, LoginUserKeyDrift, NodeLoginDrift, RotateSshKeyRequest, RouterLoginUser,
+    RouterLoginUserSettings, SshPublicKey, SSH_KEY_TYPES,
+};
+use crate::services::config_lock::ConfigLockService;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::config_tree::{entries, lookup, path, text, validate_name, ConfigTreeWriter};
+use crate::services::node_service::NodeService;
+use crate::services::team::TeamService;
+use crate::vyos_client::ConfigOperation;
+
+/// Configuration path of the login users
+const LOGIN_USER_PATH: &[&str] = &["system", "login", "user"];
+
+/// Router login service
+#[derive(Clone)]
+pub struct RouterLoginService {
+    nodes: NodeService,
+    teams: TeamService,
+    writer: ConfigTreeWriter,
+}
+
+impl RouterLoginService {
+    /// Create a new router login service
+    pub fn new(
+        nodes: NodeService,
+        teams: TeamService,
+        locks: ConfigLockService,
+        sessions: ConfigSessionService,
+    ) -> Self {
+        Self {
+            writer: ConfigTreeWriter::new(nodes.clone(), locks, sessions),
+            nodes,
+            teams,
+        }
+    }
+
+    /// Login users of a node
+    pub async fn users(&self, node_id: Uuid) -> Result<NodeData<Vec<RouterLoginUser>>, AppError> {
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        Ok(config.map(|config| parse_users(&config)))
+    }
+
+    /// Create or replace a login user
+    ///
+    /// The user's password is kept unless a new one is given. A new user
+    /// needs a password or a key to log in with.
+    pub async fn set_user(
+        &self,
+        node_id: Uuid,
+        name: &str,
+        settings: RouterLoginUserSettings,
+        claims: &Claims,
+    ) -> Result<RouterLoginUser, AppError> {
+        validate_user_name(name)?;
+        let settings = normalize_user(settings)?;
+        let config = self.writer.current(node_id).await?;
+        let encrypted_password = lookup(&config, &["system", "login", "user", name, "authentication"])
+            .and_then(|authentication| text(authentication.get("encrypted-password")));
+        if settings.password.is_none() && encrypted_password.is_none() && settings.ssh_keys.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Login user {} needs a password or an SSH key",
+                name
+            )));
+        }
+
+        let mut paths = Vec::new();
+        if let Some(full_name) = &settings.full_name {
+            paths.push(path(&["full-name", full_name], &[]));
+        }
+        match (&settings.password, &encrypted_password) {
+            (Some(password), _) => paths.push(path(&["authentication", "plaintext-password", password], &[])),
+            (None, Some(hash)) => paths.push(path(&["authentication", "encrypted-password", hash], &[])),
+            (None, None) => {}
+        }
+        for key in &settings.ssh_keys {
+            paths.extend(key_paths(key));
+        }
+        self.writer.replace(node_id, path(LOGIN_USER_PATH, &[name]), paths, claims).await?;
+        info!("{} set login user {} on node {}", claims.username, name, node_id);
+
+        Ok(RouterLoginUser {
+            name: name.to_string(),
+            full_name: settings.full_name,
+            has_password: settings.password.is_some() || encrypted_password.is_some(),
+            ssh_keys: settings.ssh_keys,
+        })
+    }
+
+    /// Remove a login user
+    ///
+    /// The node's last user and the user the backend logs in as over SSH
+    /// cannot be removed.
+    pub async fn delete_user(&self, node_id: Uuid, name: &str, claims: &Claims) -> Result<(), AppError> {
+        validate_user_name(name)?;
+        let node = self
+            .nodes
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+        if node.ssh_username.as_deref() == Some(name) {
+            return Err(AppError::Validation(format!(
+                "Login user {} is the user the node is reached as over SSH",
+                name
+            )));
+        }
+        let users = parse_users(&self.writer.current(node_id).await?);
+        if users.len() == 1 && users[0].name == name {
+            return Err(AppError::Validation(format!("Login user {} is the last user of the node", name)));
+        }
+
+        self.writer.replace(node_id, path(LOGIN_USER_PATH, &[name]), Vec::new(), claims).await?;
+        info!("{} removed login user {} from node {}", claims.username, name, node_id);
+        Ok(())
+    }
+
+    /// Add a key to a login user on many nodes, removing the key it
+    /// replaces, one commit per node
+    ///
+    /// A node failing does not stop the others; each node's outcome is
+    /// returned.
+    pub async fn rotate_key(
+        &self,
+        request: RotateSshKeyRequest,
+        claims: &Claims,
+    ) -> Result<Vec<NodeChangeResult>, AppError> {
+        validate_user_name(&request.user)?;
+        validate_key(&request.key)?;
+        if let Some(replaces) = &request.replaces {
+            validate_name("Key name", replaces)?;
+        }
+
+        let mut results = Vec::new();
+        let scope = self.teams.access_scope(claims).await?;
+        for node in self.nodes.fleet_nodes(&scope, request.node_ids.as_deref()).await? {
+            let result = self.rotate_node_key(node.id, &request, claims).await;
+            if let Err(e) = &result {
+                warn!("Could not rotate key {} of {} on node {}: {}", request.key.name, request.user, node.id, e);
+            }
+            results.push(NodeChangeResult {
+                node_id: node.id,
+                node_name: node.name,
+                success: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+        info!(
+            "{} rotated key {} of login user {} on {} of {} nodes",
+            claims.username,
+            request.key.name,
+            request.user,
+            results.iter().filter(|result| result.success).count(),
+            results.len()
+        );
+        Ok(results)
+    }
+
+    async fn rotate_node_key(
+        &self,
+        node_id: Uuid,
+        request: &RotateSshKeyRequest,
+        claims: &Claims,
+    ) -> Result<(), AppError> {
+        let users = parse_users(&self.writer.current(node_id).await?);
+        let user = users
+            .iter()
+            .find(|user| user.name == request.user)
+            .ok_or_else(|| AppError::NotFound(format!("Login user {} is not configured", request.user)))?;
+
+        let root = path(LOGIN_USER_PATH, &[&request.user]);
+        let mut operations = Vec::new();
+        if let Some(replaces) = &request.replaces {
+            if *replaces != request.key.name && user.ssh_keys.iter().any(|key| key.name == *replaces) {
+                operations.push(ConfigOperation::delete(
+                    root.iter().cloned().chain(path(&["authentication", "public-keys", replaces], &[])).collect(),
+                ));
+            }
+        }
+        for relative in key_paths(&request.key) {
+            operations.push(ConfigOperation::set(root.iter().cloned().chain(relative).collect()));
+        }
+        self.writer.apply(node_id, &operations, claims).await
+    }
+
+    /// Compare the login users of many nodes with the desired ones
+    ///
+    /// Keys are compared by their key material, so a key named differently
+    /// on a node is not reported.
+    pub async fn drift(&self, request: LoginDriftRequest, claims: &Claims) -> Result<Vec<NodeLoginDrift>, AppError> {
+        let mut names = BTreeSet::new();
+        for user in &request.users {
+            validate_user_name(&user.name)?;
+            if !names.insert(&user.name) {
+                return Err(AppError::Validation(format!("Login user {} is given twice", user.name)));
+            }
+            for key in &user.ssh_keys {
+                validate_key(key)?;
+            }
+        }
+
+        let mut drifts = Vec::new();
+        let scope = self.teams.access_scope(claims).await?;
+        for node in self.nodes.fleet_nodes(&scope, request.node_ids.as_deref()).await? {
+            let drift = match self.writer.current(node.id).await {
+                Ok(config) => user_drift(&request.users, &parse_users(&config)),
+                Err(e) => NodeLoginDrift {
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                },
+            };
+            drifts.push(NodeLoginDrift {
+                node_id: node.id,
+                node_name: node.name,
+                ..drift
+            });
+        }
+        Ok(drifts)
+    }
+}
+
+fn parse_users(config: &Value) -> Vec<RouterLoginUser> {
+    entries(lookup(config, LOGIN_USER_PATH))
+        .into_iter()
+        .map(|(name, user)| {
+            let authentication = user.get("authentication");
+            RouterLoginUser {
+                name,
+                full_name: text(user.get("full-name")),
+                has_password: authentication.and_then(|auth| auth.get("encrypted-password")).is_some(),
+                ssh_keys: entries(authentication.and_then(|auth| auth.get("public-keys")))
+                    .into_iter()
+                    .filter_map(|(name, key)| {
+                        Some(SshPublicKey {
+                            name,
+                            key_type: text(key.get("type"))?,
+                            key: text(key.get("key"))?,
+                        })
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Paths of a key, relative to its user
+fn key_paths(key: &SshPublicKey) -> Vec<Vec<String>> {
+    let root = ["authentication", "public-keys", &key.name];
+    vec![path(&root, &["type", &key.key_type]), path(&root, &["key", &key.key])]
+}
+
+/// How the users of a node differ from the desired ones
+fn user_drift(desired: &[DesiredLoginUser], users: &[RouterLoginUser]) -> NodeLoginDrift {
+    let mut drift = NodeLoginDrift {
+        unexpected_users: users
+            .iter()
+            .filter(|user| !desired.iter().any(|desired| desired.name == user.name))
+            .map(|user| user.name.clone())
+            .collect(),
+        ..Default::default()
+    };
+    for wanted in desired {
+        let Some(user) = users.iter().find(|user| user.name == wanted.name) else {
+            drift.missing_users.push(wanted.name.clone());
+            continue;
+        };
+        let keys = LoginUserKeyDrift {
+            user: wanted.name.clone(),
+            missing_keys: wanted
+                .ssh_keys
+                .iter()
+                .filter(|key| !user.ssh_keys.iter().any(|existing| existing.same_key(key)))
+                .map(|key| key.name.clone())
+                .collect(),
+            unexpected_keys: user
+                .ssh_keys
+                .iter()
+                .filter(|existing| !wanted.ssh_keys.iter().any(|key| key.same_key(existing)))
+                .map(|key| key.name.clone())
+                .collect(),
+        };
+        if !keys.missing_keys.is_empty() || !keys.unexpected_keys.is_empty() {
+            drift.key_drift.push(keys);
+        }
+    }
+    drift.in_sync = drift.missing_users.is_empty() && drift.unexpected_users.is_empty() && drift.key_drift.is_empty();
+    drift
+}
+
+/// Check a login user name: lowercase letters, digits, `_`, and `-`,
+/// starting with a letter or `_`
+fn validate_user_name(name: &str) -> Result<(), AppError> {
+    let valid = name.len() <= 32
+        && name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("Login user name '{}' is not valid", name)))
+    }
+}
+
+/// Check a public key: the type must be known, and the key base64 encoded
+/// and, as the SSH wire format requires, start with its own type
+fn validate_key(key: &SshPublicKey) -> Result<(), AppError> {
+    validate_name("Key name", &key.name)?;
+    if !SSH_KEY_TYPES.contains(&key.key_type.as_str()) {
+        return Err(AppError::Validation(format!("Key type '{}' is not supported", key.key_type)));
+    }
+    let blob = Base64::decode_vec(&key.key)
+        .map_err(|_| AppError::Validation(format!("Key {} is not base64 encoded", key.name)))?;
+    let embedded = blob
+        .get(..4)
+        .map(|length| u32::from_be_bytes([length[0], length[1], length[2], length[3]]) as usize)
+        .and_then(|length| blob.get(4..4 + length));
+    if embedded != Some(key.key_type.as_bytes()) {
+        return Err(AppError::Validation(format!(
+            "Key {} is not a {} key",
+            key.name, key.key_type
+        )));
+    }
+    Ok(())
+}
+
+fn normalize_user(settings: RouterLoginUserSettings) -> Result<RouterLoginUserSettings, AppError> {
+    let full_name = settings
+        .full_name
+        .map(|full_name| full_name.trim().to_string())
+        .filter(|full_name| !full_name.is_empty());
+    if full_name.as_ref().is_some_and(|full_name| full_name.len() > 128 || full_name.contains(['\n', '"'])) {
+        return Err(AppError::Validation(
+            "Full name must be one line of at most 128 characters without quotes".to_string(),
+        ));
+    }
+    if let Some(password) = &settings.password {
+        if password.len() < 8 || password.len() > 128 || password.contains(['\n', '\r']) {
+            return Err(AppError::Validation(
+                "Password must be one line of 8 to 128 characters".to_string(),
+            ));
+        }
+    }
+
+    let mut names = BTreeSet::new();
+    for (index, key) in settings.ssh_keys.iter().enumerate() {
+        validate_key(key)?;
+        if !names.insert(&key.name) {
+            return Err(AppError::Validation(format!("Key name {} is given twice", key.name)));
+        }
+        if settings.ssh_keys[..index].iter().any(|other| other.same_key(key)) {
+            return Err(AppError::Validation(format!("Key {} is given twice", key.name)));
+        }
+    }
+
+    Ok(RouterLoginUserSettings { full_name, ..settings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Public key blob of the given type, with made-up key material
+    fn key(name: &str, key_type: &str, material: u8) -> SshPublicKey {
+        let mut blob = (key_type.len() as u32).to_be_bytes().to_vec();
+        blob.extend(key_type.as_bytes());
+        blob.extend([0, 0, 0, 32]);
+        blob.extend([material; 32]);
+        SshPublicKey {
+            name: name.to_string(),
+            key_type: key_type.to_string(),
+            key: Base64::encode_string(&blob),
+        }
+    }
+
+    #[test]
+    fn test_users_are_parsed() {
+        let alice = key("alice@laptop", "ssh-ed25519", 1);
+        let config = json!({ "system": { "login": { "user": {
+            "vyos": { "authentication": { "encrypted-password": "$6$hash" } },
+            "alice": {
+                "full-name": "Alice",
+                "authentication": { "public-keys": { "alice@laptop": { "type": "ssh-ed25519", "key": alice.key } } }
+            }
+        } } } });
+        let users = parse_users(&config);
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].name, "alice");
+        assert_eq!(users[0].ssh_keys, [alice]);
+        assert!(!users[0].has_password);
+        assert!(users[1].has_password);
+    }
+
+    #[test]
+    fn test_keys_and_names_are_validated() {
+        assert!(validate_key(&key("alice", "ssh-ed25519", 1)).is_ok());
+        assert!(validate_key(&key("alice", "ssh-foo", 1)).is_err());
+        let mismatched = SshPublicKey {
+            key_type: "ssh-rsa".to_string(),
+            ..key("alice", "ssh-ed25519", 1)
+        };
+        assert!(validate_key(&mismatched).is_err());
+        let garbled = SshPublicKey {
+            key: "not base64!".to_string(),
+            ..key("alice", "ssh-ed25519", 1)
+        };
+        assert!(validate_key(&garbled).is_err());
+
+        assert!(validate_user_name("ops_1").is_ok());
+        assert!(validate_user_name("Admin").is_err());
+        assert!(validate_user_name("1ops").is_err());
+
+        let twice = RouterLoginUserSettings {
+            ssh_keys: vec![key("a", "ssh-ed25519", 1), key("b", "ssh-ed25519", 1)],
+            ..Default::default()
+        };
+        assert!(normalize_user(twice).is_err());
+        let short = RouterLoginUserSettings {
+            password: Some("short".to_string()),
+            ..Default::default()
+        };
+        assert!(normalize_user(short).is_err());
+    }
+
+    #[test]
+    fn test_drift_compares_key_material() {
+        let users = vec![
+            RouterLoginUser {
+                name: "ops".to_string(),
+                full_name: None,
+                has_password: false,
+                ssh_keys: vec![key("renamed", "ssh-ed25519", 1), key("old", "ssh-ed25519", 2)],
+            },
+            RouterLoginUser {
+                name: "vyos".to_string(),
+                full_name: None,
+                has_password: true,
+                ssh_keys: Vec::new(),
+            },
+        ];
+        let desired = vec![
+            DesiredLoginUser {
+                name: "ops".to_string(),
+                ssh_keys: vec![key("laptop", "ssh-ed25519", 1), key("new", "ssh-ed25519", 3)],
+            },
+            DesiredLoginUser {
+                name: "backup".to_string(),
+                ssh_keys: Vec::new(),
+            },
+        ];
+
+        let drift = user_drift(&desired, &users);
+        assert_eq!(drift.missing_users, ["backup"]);
+        assert_eq!(drift.unexpected_users, ["vyos"]);
+        assert_eq!(
+            drift.key_drift,
+            [LoginUserKeyDrift {
+                user: "ops".to_string(),
+                missing_keys: vec!["new".to_string()],
+                unexpected_keys: vec!["old".to_string()],
+            }]
+        );
+        assert!(!drift.in_sync);
+    }
+}
@@ -0,0 +1,646 @@
+//! Routing Service
+//!
+//! Static routes, in the main table and in the numbered tables used by
+//! policy-based routing, and the policy routes choosing a table for packets
+//! by their source, destination, and protocol. Changes replace the route's
+//! or policy's subtree in one commit; a dry run only returns the generated
+//! commands.
+
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+use serde_json::Value;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::node::NodeData;
+use crate::models::routing::{
+    PolicyRoute, PolicyRouteMatch, PolicyRouteRequest, PolicyRouteRule, PolicyRouteSettings, RouteFamily,
+    RouteInterface, RouteNextHop, RoutingPlan, StaticRoute, StaticRouteQuery, StaticRouteRequest, StaticRouteSettings,
+    MAIN_TABLE, MAX_POLICY_RULE_NUMBER, MAX_ROUTING_TABLE,
+};
+use crate::services::config_lock::ConfigLockService;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::config_tree::{
+    entries, lookup, number, path, replacement, text, validate_name, values, ConfigTreeWriter,
+};
+use crate::services::firewall::{normalize_description, normalize_ports, PORT_PROTOCOLS};
+use crate::services::node_service::NodeService;
+use crate::vyos_client::ConfigOperation;
+
+/// Configuration path of the static routes
+const STATIC_PATH: &[&str] = &["protocols", "static"];
+
+/// Configuration path of the policy routes
+const POLICY_PATH: &[&str] = &["policy"];
+
+/// Routing service
+#[derive(Clone)]
+pub struct RoutingService {
+    nodes: NodeService,
+    writer: ConfigTreeWriter,
+}
+
+impl RoutingService {
+    /// Create a new routing service
+    pub fn new(nodes: NodeService, locks: ConfigLockService, sessions: ConfigSessionService) -> Self {
+        Self {
+            writer: ConfigTreeWriter::new(nodes.clone(), locks, sessions),
+            nodes,
+        }
+    }
+
+    /// Static routes of a node, those of the main table first
+    pub async fn static_routes(&self, node_id: Uuid) -> Result<NodeData<Vec<StaticRoute>>, AppError> {
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        Ok(config.map(|config| parse_static_routes(&config)))
+    }
+
+    /// Add a static route, or replace the route to the same destination in
+    /// the same table
+    pub async fn set_static_route(
+        &self,
+        node_id: Uuid,
+        request: StaticRouteRequest,
+        claims: &Claims,
+    ) -> Result<RoutingPlan, AppError> {
+        let (family, destination) = parse_destination(&request.destination)?;
+        validate_table(request.table)?;
+        let settings = normalize_static_route(family, request.settings)?;
+        let current = self.writer.current(node_id).await?;
+        let root = static_route_path(family, &destination, request.table);
+        let operations = replacement(&current, node_id, root, static_route_paths(&settings))?;
+        self.plan(node_id, operations, request.dry_run, claims).await
+    }
+
+    /// Remove a static route
+    pub async fn delete_static_route(
+        &self,
+        node_id: Uuid,
+        query: StaticRouteQuery,
+        claims: &Claims,
+    ) -> Result<RoutingPlan, AppError> {
+        let (family, destination) = parse_destination(&query.destination)?;
+        validate_table(query.table)?;
+        let current = self.writer.current(node_id).await?;
+        let root = static_route_path(family, &destination, query.table);
+        let operations = replacement(&current, node_id, root, Vec::new())?;
+        self.plan(node_id, operations, query.dry_run, claims).await
+    }
+
+    /// Policy routes of a node
+    pub async fn policy_routes(&self, node_id: Uuid) -> Result<NodeData<Vec<PolicyRoute>>, AppError> {
+        let config = self.nodes.retrieve_node_config(node_id, None).await?;
+        Ok(config.map(|config| parse_policy_routes(&config)))
+    }
+
+    /// Create or replace a policy route with all of its rules
+    ///
+    /// Rules may only send packets to the main table or to numbered tables
+    /// that have static routes.
+    pub async fn set_policy_route(
+        &self,
+        node_id: Uuid,
+        family: RouteFamily,
+        name: &str,
+        request: PolicyRouteRequest,
+        claims: &Claims,
+    ) -> Result<RoutingPlan, AppError> {
+        validate_name("Policy route", name)?;
+        let current = self.writer.current(node_id).await?;
+        let settings = normalize_policy_route(&current, family, request.settings)?;
+        let root = path(POLICY_PATH, &[family.route_node(), name]);
+        let operations = replacement(&current, node_id, root, policy_route_paths(&settings))?;
+        self.plan(node_id, operations, request.dry_run, claims).await
+    }
+
+    /// Remove a policy route
+    pub async fn delete_policy_route(
+        &self,
+        node_id: Uuid,
+        family: RouteFamily,
+        name: &str,
+        dry_run: bool,
+        claims: &Claims,
+    ) -> Result<RoutingPlan, AppError> {
+        validate_name("Policy route", name)?;
+        let current = self.writer.current(node_id).await?;
+        let root = path(POLICY_PATH, &[family.route_node(), name]);
+        let operations = replacement(&current, node_id, root, Vec::new())?;
+        self.plan(node_id, operations, dry_run, claims).await
+    }
+
+    /// Commands of a change, applied unless it is a dry run
+    async fn plan(
+        &self,
+        node_id: Uuid,
+        operations: Vec<ConfigOperation>,
+        dry_run: bool,
+        claims: &Claims,
+    ) -> Result<RoutingPlan, AppError> {
+        let mut plan = RoutingPlan {
+            commands: operations.iter().map(ConfigOperation::command).collect(),
+            operations,
+            applied: false,
+        };
+        if dry_run {
+            return Ok(plan);
+        }
+
+        self.writer.apply(node_id, &plan.operations, claims).await?;
+        info!(
+            "{} changed the routing of node {} with {} operations",
+            claims.username,
+            node_id,
+            plan.operations.len()
+        );
+        plan.applied = true;
+        Ok(plan)
+    }
+}
+
+/// Configuration path of a static route in the main or a numbered table
+fn static_route_path(family: RouteFamily, destination: &str, table: Option<u32>) -> Vec<String> {
+    match table {
+        Some(table) => path(STATIC_PATH, &["table", &table.to_string(), family.route_node(), destination]),
+        None => path(STATIC_PATH, &[family.route_node(), destination]),
+    }
+}
+
+fn parse_static_routes(config: &Value) -> Vec<StaticRoute> {
+    let statics = lookup(config, STATIC_PATH);
+    let tables = entries(statics.and_then(|statics| statics.get("table")))
+        .into_iter()
+        .filter_map(|(table, routes)| Some((Some(table.parse::<u32>().ok()?), Some(routes))));
+    let mut routes = Vec::new();
+    for (table, routes_config) in std::iter::once((None, statics)).chain(tables) {
+        for family in [RouteFamily::Ipv4, RouteFamily::Ipv6] {
+            for (destination, route) in entries(routes_config.and_then(|config| config.get(family.route_node()))) {
+                routes.push(StaticRoute {
+                    family,
+                    path: static_route_path(family, &destination, table).join(" "),
+                    destination,
+                    table,
+                    settings: parse_static_route(route),
+                });
+            }
+        }
+    }
+    routes
+}
+
+fn parse_static_route(config: &Value) -> StaticRouteSettings {
+    let blackhole = config.get("blackhole");
+    StaticRouteSettings {
+        description: text(config.get("description")),
+        next_hops: entries(config.get("next-hop"))
+            .into_iter()
+            .map(|(address, hop)| RouteNextHop {
+                address,
+                interface: text(hop.get("interface")),
+                distance: number(hop.get("distance")),
+            })
+            .collect(),
+        interfaces: entries(config.get("interface"))
+            .into_iter()
+            .map(|(interface, hop)| RouteInterface {
+                interface,
+                distance: number(hop.get("distance")),
+            })
+            .collect(),
+        blackhole: blackhole.is_some(),
+        blackhole_distance: blackhole.and_then(|blackhole| number(blackhole.get("distance"))),
+    }
+}
+
+fn static_route_paths(settings: &StaticRouteSettings) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    if let Some(description) = &settings.description {
+        paths.push(path(&["description", description], &[]));
+    }
+    for hop in &settings.next_hops {
+        let root = ["next-hop", hop.address.as_str()];
+        if hop.interface.is_none() && hop.distance.is_none() {
+            paths.push(path(&root, &[]));
+        }
+        if let Some(interface) = &hop.interface {
+            paths.push(path(&root, &["interface", interface]));
+        }
+        if let Some(distance) = hop.distance {
+            paths.push(path(&root, &["distance", &distance.to_string()]));
+        }
+    }
+    for hop in &settings.interfaces {
+        let root = ["interface", hop.interface.as_str()];
+        match hop.distance {
+            Some(distance) => paths.push(path(&root, &["distance", &distance.to_string()])),
+            None => paths.push(path(&root, &[])),
+        }
+    }
+    if settings.blackhole {
+        match settings.blackhole_distance {
+            Some(distance) => paths.push(path(&["blackhole", "distance", &distance.to_string()], &[])),
+            None => paths.push(path(&["blackhole"], &[])),
+        }
+    }
+    paths
+}
+
+fn parse_policy_routes(config: &Value) -> Vec<PolicyRoute> {
+    let mut policies = Vec::new();
+    for family in [RouteFamily::Ipv4, RouteFamily::Ipv6] {
+        for (name, policy) in entries(lookup(config, &["policy", family.route_node()])) {
+            let rules = entries(policy.get("rule"))
+                .into_iter()
+                .filter_map(|(number, rule)| {
+                    Some(PolicyRouteRule {
+                        number: number.parse().ok()?,
+                        description: text(rule.get("description")),
+                        protocol: text(rule.get("protocol")),
+                        source: parse_policy_match(rule.get("source")),
+                        destination: parse_policy_match(rule.get("destination")),
+                        table: text(lookup(rule, &["set", "table"]))?,
+                    })
+                })
+                .collect::<Vec<_>>();
+            policies.push(PolicyRoute {
+                family,
+                path: path(POLICY_PATH, &[family.route_node(), &name]).join(" "),
+                name,
+                settings: PolicyRouteSettings {
+                    description: text(policy.get("description")),
+                    interfaces: values(policy.get("interface")),
+                    rules: sorted_rules(rules),
+                },
+            });
+        }
+    }
+    policies
+}
+
+fn sorted_rules(mut rules: Vec<PolicyRouteRule>) -> Vec<PolicyRouteRule> {
+    rules.sort_by_key(|rule| rule.number);
+    rules
+}
+
+fn parse_policy_match(config: Option<&Value>) -> PolicyRouteMatch {
+    PolicyRouteMatch {
+        address: text(config.and_then(|config| config.get("address"))),
+        port: text(config.and_then(|config| config.get("port"))),
+    }
+}
+
+fn policy_route_paths(settings: &PolicyRouteSettings) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    if let Some(description) = &settings.description {
+        paths.push(path(&["description", description], &[]));
+    }
+    for interface in &settings.interfaces {
+        paths.push(path(&["interface", interface], &[]));
+    }
+    for rule in &settings.rules {
+        let number = rule.number.to_string();
+        let root = ["rule", number.as_str()];
+        if let Some(description) = &rule.description {
+            paths.push(path(&root, &["description", description]));
+        }
+        if let Some(protocol) = &rule.protocol {
+            paths.push(path(&root, &["protocol", protocol]));
+        }
+        for (side, criteria) in [("source", &rule.source), ("destination", &rule.destination)] {
+            if let Some(address) = &criteria.address {
+                paths.push(path(&root, &[side, "address", address]));
+            }
+            if let Some(port) = &criteria.port {
+                paths.push(path(&root, &[side, "port", port]));
+            }
+        }
+        paths.push(path(&root, &["set", "table", &rule.table]));
+    }
+    paths
+}
+
+/// Family and canonical form of a route's destination network, rejecting
+/// addresses with host bits set
+fn parse_destination(destination: &str) -> Result<(RouteFamily, String), AppError> {
+    let network: IpNet = destination
+        .trim()
+        .parse()
+        .map_err(|_| AppError::Validation(format!("Destination '{}' is not a network", destination)))?;
+    if network != network.trunc() {
+        return Err(AppError::Validation(format!(
+            "Destination '{}' has host bits set; did you mean {}?",
+            destination,
+            network.trunc()
+        )));
+    }
+    let family = match network {
+        IpNet::V4(_) => RouteFamily::Ipv4,
+        IpNet::V6(_) => RouteFamily::Ipv6,
+    };
+    Ok((family, network.to_string()))
+}
+
+fn validate_table(table: Option<u32>) -> Result<(), AppError> {
+    match table {
+        Some(table) if !(1..=MAX_ROUTING_TABLE).contains(&table) => Err(AppError::Validation(format!(
+            "Routing tables are numbered 1 to {}, not {}",
+            MAX_ROUTING_TABLE, table
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn validate_distance(distance: Option<u8>) -> Result<(), AppError> {
+    if distance == Some(0) {
+        return Err(AppError::Validation("Distances are between 1 and 255".to_string()));
+    }
+    Ok(())
+}
+
+fn family_of(address: &IpAddr) -> RouteFamily {
+    match address {
+        IpAddr::V4(_) => RouteFamily::Ipv4,
+        IpAddr::V6(_) => RouteFamily::Ipv6,
+    }
+}
+
+/// Validate a static route: gateways of the destination's family, each
+/// gateway and interface once, and either gateways and interfaces or a
+/// blackhole
+fn normalize_static_route(family: RouteFamily, settings: StaticRouteSettings) -> Result<StaticRouteSettings, AppError> {
+    let mut next_hops: Vec<RouteNextHop> = Vec::with_capacity(settings.next_hops.len());
+    for hop in settings.next_hops {
+        let address: IpAddr = hop
+            .address
+            .trim()
+            .parse()
+            .map_err(|_| AppError::Validation(format!("Next hop '{}' is not an address", hop.address)))?;
+        if family_of(&address) != family {
+            return Err(AppError::Validation(format!(
+                "Next hop {} is not an {} address",
+                address,
+                family.as_str()
+            )));
+        }
+        if address.is_unspecified() || address.is_multicast() {
+            return Err(AppError::Validation(format!("Next hop {} cannot be a gateway", address)));
+        }
+        if let Some(interface) = &hop.interface {
+            validate_name("Interface", interface)?;
+        }
+        validate_distance(hop.distance)?;
+        let address = address.to_string();
+        if next_hops.iter().any(|other| other.address == address) {
+            return Err(AppError::Validation(format!("Next hop {} is listed twice", address)));
+        }
+        next_hops.push(RouteNextHop { address, ..hop });
+    }
+
+    let mut interfaces: Vec<RouteInterface> = Vec::with_capacity(settings.interfaces.len());
+    for hop in settings.interfaces {
+        validate_name("Interface", &hop.interface)?;
+        validate_distance(hop.distance)?;
+        if interfaces.iter().any(|other| other.interface == hop.interface) {
+            return Err(AppError::Validation(format!("Interface {} is listed twice", hop.interface)));
+        }
+        interfaces.push(hop);
+    }
+
+    let forwards = !next_hops.is_empty() || !interfaces.is_empty();
+    if settings.blackhole && forwards {
+        return Err(AppError::Validation(
+            "A blackhole route has no next hops or interfaces".to_string(),
+        ));
+    }
+    if !settings.blackhole && !forwards {
+        return Err(AppError::Validation(
+            "A route needs a next hop, an interface, or to be a blackhole".to_string(),
+        ));
+    }
+    if !settings.blackhole && settings.blackhole_distance.is_some() {
+        return Err(AppError::Validation("Only blackhole routes take a blackhole distance".to_string()));
+    }
+    validate_distance(settings.blackhole_distance)?;
+
+    Ok(StaticRouteSettings {
+        description: normalize_description(settings.description)?,
+        next_hops,
+        interfaces,
+        blackhole: settings.blackhole,
+        blackhole_distance: settings.blackhole_distance,
+    })
+}
+
+/// Validate a policy route, ordering its rules by number
+fn normalize_policy_route(
+    config: &Value,
+    family: RouteFamily,
+    settings: PolicyRouteSettings,
+) -> Result<PolicyRouteSettings, AppError> {
+    let mut interfaces: Vec<String> = Vec::with_capacity(settings.interfaces.len());
+    for interface in settings.interfaces {
+        validate_name("Interface", &interface)?;
+        if !interfaces.contains(&interface) {
+            interfaces.push(interface);
+        }
+    }
+
+    let tables: Vec<String> = entries(lookup(config, &["protocols", "static", "table"]))
+        .into_iter()
+        .map(|(table, _)| table)
+        .collect();
+    let mut rules: Vec<PolicyRouteRule> = Vec::with_capacity(settings.rules.len());
+    for rule in settings.rules {
+        let invalid = |message: String| AppError::Validation(format!("Rule {}: {}", rule.number, message));
+        if !(1..=MAX_POLICY_RULE_NUMBER).contains(&rule.number) {
+            return Err(AppError::Validation(format!(
+                "Rule numbers are between 1 and {}, not {}",
+                MAX_POLICY_RULE_NUMBER, rule.number
+            )));
+        }
+        if rules.iter().any(|other| other.number == rule.number) {
+            return Err(AppError::Validation(format!("Rule {} is listed twice", rule.number)));
+        }
+
+        let table = rule.table.trim().to_ascii_lowercase();
+        if table != MAIN_TABLE && !tables.contains(&table) {
+            return Err(invalid(format!("table {} has no static routes", table)));
+        }
+        let protocol = rule
+            .protocol
+            .map(|protocol| protocol.trim().to_ascii_lowercase())
+            .filter(|protocol| !protocol.is_empty());
+        if protocol
+            .as_deref()
+            .is_some_and(|protocol| !protocol.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        {
+            return Err(invalid("protocol is not a protocol name or number".to_string()));
+        }
+        let source =
+            normalize_policy_match(family, rule.source).map_err(|e| invalid(format!("source {}", e)))?;
+        let destination =
+            normalize_policy_match(family, rule.destination).map_err(|e| invalid(format!("destination {}", e)))?;
+        let matches_ports = source.port.is_some() || destination.port.is_some();
+        if matches_ports && !protocol.as_deref().is_some_and(|protocol| PORT_PROTOCOLS.contains(&protocol)) {
+            return Err(invalid("ports can only be matched for tcp, udp, or tcp_udp".to_string()));
+        }
+
+        rules.push(PolicyRouteRule {
+            number: rule.number,
+            description: normalize_description(rule.description)?,
+            protocol,
+            source,
+            destination,
+            table,
+        });
+    }
+
+    Ok(PolicyRouteSettings {
+        description: normalize_description(settings.description)?,
+        interfaces,
+        rules: sorted_rules(rules),
+    })
+}
+
+/// Check one side of a policy route rule; errors read after "source" or
+/// "destination"
+fn normalize_policy_match(family: RouteFamily, criteria: PolicyRouteMatch) -> Result<PolicyRouteMatch, String> {
+    let blank = |value: Option<String>| value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+    let address = match blank(criteria.address) {
+        Some(address) => {
+            let network: IpNet = address
+                .parse()
+                .or_else(|_| address.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| format!("'{}' is not an address or network", address))?;
+            if family_of(&network.addr()) != family {
+                return Err(format!("'{}' is not an {} address", address, family.as_str()));
+            }
+            if network != network.trunc() {
+                return Err(format!("'{}' has host bits set; did you mean {}?", address, network.trunc()));
+            }
+            Some(if network.prefix_len() == network.max_prefix_len() {
+                network.addr().to_string()
+            } else {
+                network.to_string()
+            })
+        }
+        None => None,
+    };
+    Ok(PolicyRouteMatch {
+        address,
+        port: blank(criteria.port).map(|port| normalize_ports(&port)).transpose()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn commands(root: Vec<String>, paths: Vec<Vec<String>>) -> Vec<String> {
+        replacement(&json!({}), Uuid::nil(), root, paths)
+            .unwrap()
+            .iter()
+            .map(ConfigOperation::command)
+            .collect()
+    }
+
+    #[test]
+    fn test_static_route_round_trip() {
+        let config = json!({ "protocols": { "static": {
+            "route": { "10.0.0.0/8": {
+                "description": "Branch offices",
+                "next-hop": { "192.0.2.1": { "distance": "10" }, "192.0.2.2": {} }
+            } },
+            "route6": { "2001:db8::/32": { "blackhole": { "distance": "200" } } },
+            "table": { "10": { "route": { "0.0.0.0/0": { "interface": { "pppoe0": {} } } } } }
+        } } });
+        let routes = parse_static_routes(&config);
+        assert_eq!(routes.len(), 3);
+        assert_eq!(routes[0].settings.next_hops[0].distance, Some(10));
+        assert_eq!(routes[1].family, RouteFamily::Ipv6);
+        assert_eq!(routes[1].settings.blackhole_distance, Some(200));
+        assert_eq!(routes[2].table, Some(10));
+        assert_eq!(routes[2].path, "protocols static table 10 route 0.0.0.0/0");
+
+        let route = &routes[0];
+        assert_eq!(normalize_static_route(route.family, route.settings.clone()).unwrap(), route.settings);
+        assert_eq!(
+            commands(
+                static_route_path(route.family, &route.destination, route.table),
+                static_route_paths(&route.settings)
+            ),
+            [
+                "set protocols static route 10.0.0.0/8 description 'Branch offices'",
+                "set protocols static route 10.0.0.0/8 next-hop 192.0.2.1 distance 10",
+                "set protocols static route 10.0.0.0/8 next-hop 192.0.2.2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_static_routes_are_validated() {
+        assert_eq!(parse_destination(" 10.0.0.0/8").unwrap(), (RouteFamily::Ipv4, "10.0.0.0/8".to_string()));
+        assert!(parse_destination("10.0.0.1/8").is_err());
+        assert!(parse_destination("10.0.0.1").is_err());
+        assert!(validate_table(Some(201)).is_err());
+
+        let hop = |address: &str| RouteNextHop {
+            address: address.to_string(),
+            interface: None,
+            distance: None,
+        };
+        let invalid = [
+            StaticRouteSettings::default(),
+            StaticRouteSettings { next_hops: vec![hop("2001:db8::1")], ..Default::default() },
+            StaticRouteSettings { next_hops: vec![hop("0.0.0.0")], ..Default::default() },
+            StaticRouteSettings { next_hops: vec![hop("192.0.2.1"), hop("192.0.2.1")], ..Default::default() },
+            StaticRouteSettings { next_hops: vec![hop("192.0.2.1")], blackhole: true, ..Default::default() },
+            StaticRouteSettings {
+                next_hops: vec![RouteNextHop { distance: Some(0), ..hop("192.0.2.1") }],
+                ..Default::default()
+            },
+        ];
+        for settings in invalid {
+            assert!(normalize_static_route(RouteFamily::Ipv4, settings.clone()).is_err(), "{:?}", settings);
+        }
+    }
+
+    #[test]
+    fn test_policy_route_round_trip_and_tables() {
+        let config = json!({
+            "protocols": { "static": { "table": { "10": {
+                "route": { "0.0.0.0/0": { "next-hop": { "198.51.100.1": {} } } }
+            } } } },
+            "policy": { "route": { "LAN_PBR": {
+                "interface": "eth1",
+                "rule": { "20": {
+                    "protocol": "tcp",
+                    "destination": { "port": "443" },
+                    "set": { "table": "main" }
+                }, "10": {
+                    "source": { "address": "192.168.10.0/24" },
+                    "set": { "table": "10" }
+                } }
+            } } }
+        });
+        let policies = parse_policy_routes(&config);
+        assert_eq!(policies[0].path, "policy route LAN_PBR");
+        let settings = &policies[0].settings;
+        assert_eq!(settings.interfaces, ["eth1"]);
+        assert_eq!(settings.rules.iter().map(|rule| rule.number).collect::<Vec<_>>(), [10, 20]);
+        assert_eq!(normalize_policy_route(&config, RouteFamily::Ipv4, settings.clone()).unwrap(), *settings);
+        assert!(policy_route_paths(settings).contains(&path(&["rule", "10", "set", "table", "10"], &[])));
+
+        let mut unknown_table = settings.clone();
+        unknown_table.rules[0].table = "11".to_string();
+        assert!(normalize_policy_route(&config, RouteFamily::Ipv4, unknown_table).is_err());
+        let mut ports_without_protocol = settings.clone();
+        ports_without_protocol.rules[1].protocol = None;
+        assert!(normalize_policy_route(&config, RouteFamily::Ipv4, ports_without_protocol).is_err());
+        assert!(normalize_policy_route(&config, RouteFamily::Ipv6, settings.clone()).is_err());
+    }
+}
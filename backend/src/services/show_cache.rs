@@ -0,0 +1,224 @@
+//! Show Result Cache
+//!
+//! Dashboards open several widgets at once that all read the same `show`
+//! output from a node, such as `show interfaces`. Results of these
+//! read-only commands are cached per node and command for a few seconds, so
+//! the router is asked once; requests arriving while the call is in flight
+//! wait for it rather than making their own. Each command is cached for
+//! `SHOW_CACHE_TTL_SECS` unless `SHOW_CACHE_COMMAND_TTLS` gives it a TTL of
+//! its own, and a TTL of 0 turns caching off. Failed calls are not cached,
+//! and a node's entries are dropped when it is changed or reconfigured.
+//! Entries are kept in memory and are not shared between instances.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::node::CachedShowResult;
+use crate::vyos_client::NodeTransport;
+
+type Slot = Arc<tokio::sync::Mutex<Option<CachedShowResult>>>;
+
+/// Short-lived cache of show command results
+#[derive(Clone)]
+pub struct ShowCache {
+    default_ttl_secs: u64,
+    command_ttls: Arc<BTreeMap<String, u64>>,
+    entries: Arc<Mutex<HashMap<(Uuid, String), Slot>>>,
+}
+
+impl ShowCache {
+    /// Create an empty cache with the configured TTLs
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            default_ttl_secs: config.show_cache_ttl_secs,
+            command_ttls: Arc::new(
+                config
+                    .show_cache_command_ttls
+                    .iter()
+                    .map(|(command, ttl)| (normalize(command), *ttl))
+                    .collect(),
+            ),
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Seconds the result of a command is cached for; 0 if it is not
+    pub fn ttl_secs(&self, command: &str) -> u64 {
+        self.command_ttls.get(&normalize(command)).copied().unwrap_or(self.default_ttl_secs)
+    }
+
+    /// Run a show command on a node, or answer it from the cache
+    ///
+    /// With `bypass`, the node is asked even if a fresh result is cached,
+    /// and the cache is refreshed with its answer.
+    pub async fn show(
+        &self,
+        node_id: Uuid,
+        transport: &dyn NodeTransport,
+        command: &str,
+        bypass: bool,
+    ) -> Result<CachedShowResult, AppError> {
+        let ttl_secs = self.ttl_secs(command);
+        if ttl_secs == 0 {
+            return Ok(CachedShowResult::fresh(transport.show(command).await?));
+        }
+
+        let slot = {
+            let mut entries = self.entries();
+            prune(&mut entries, self);
+            entries.entry((node_id, normalize(command))).or_default().clone()
+        };
+        let mut entry = slot.lock().await;
+        if !bypass {
+            if let Some(cached) = entry.as_ref().filter(|cached| is_fresh(cached, ttl_secs)) {
+                return Ok(cached.served_from_cache());
+            }
+        }
+
+        let result = CachedShowResult::fresh(transport.show(command).await?);
+        *entry = Some(result.clone());
+        Ok(result)
+    }
+
+    /// Drop the cached results of a node
+    pub fn forget(&self, node_id: Uuid) {
+        self.entries().retain(|(node, _), _| *node != node_id);
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<(Uuid, String), Slot>> {
+        // The cache stays usable even if a holder panicked
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Drop expired entries no request is using, so one-off commands do not
+/// accumulate
+fn prune(entries: &mut HashMap<(Uuid, String), Slot>, cache: &ShowCache) {
+    entries.retain(|(_, command), slot| {
+        Arc::strong_count(slot) > 1
+            || slot
+                .try_lock()
+                .map_or(true, |entry| entry.as_ref().is_some_and(|cached| is_fresh(cached, cache.ttl_secs(command))))
+    });
+}
+
+fn is_fresh(cached: &CachedShowResult, ttl_secs: u64) -> bool {
+    (Utc::now() - cached.fetched_at).num_milliseconds() < (ttl_secs as i64).saturating_mul(1000)
+}
+
+/// Command with its whitespace collapsed, as cached
+fn normalize(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use crate::vyos_client::{
+        Capability, ConfigOperation, VyOSConnectionTest, VyOSInfo, VyOSInterface, VyOSShowResult,
+    };
+
+    /// Transport counting its show calls
+    #[derive(Default)]
+    struct CountingTransport {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl NodeTransport for CountingTransport {
+        async fn get_info(&self) -> Result<VyOSInfo, AppError> {
+            Err(AppError::Unsupported("get_info".to_string()))
+        }
+
+        async fn retrieve_config(&self, _path: Option<String>) -> Result<serde_json::Value, AppError> {
+            Err(AppError::Unsupported("retrieve_config".to_string()))
+        }
+
+        async fn show(&self, command: &str) -> Result<VyOSShowResult, AppError> {
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            Ok(VyOSShowResult {
+                success: true,
+                output: format!("{} #{}", command, calls),
+                error: None,
+                data: None,
+            })
+        }
+
+        async fn get_interfaces(&self) -> Result<Vec<VyOSInterface>, AppError> {
+            Ok(vec![])
+        }
+
+        async fn supports(&self, _capability: Capability) -> Result<bool, AppError> {
+            Ok(true)
+        }
+
+        async fn test_connection(&self) -> Result<VyOSConnectionTest, AppError> {
+            Err(AppError::Unsupported("test_connection".to_string()))
+        }
+
+        async fn configure(&self, _operations: &[ConfigOperation]) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        async fn reboot(&self) -> Result<(), AppError> {
+            Ok(())
+        }
+    }
+
+    fn cache(default_ttl_secs: u64, command_ttls: &[(&str, u64)]) -> ShowCache {
+        let mut config = AppConfig::from_env().unwrap();
+        config.show_cache_ttl_secs = default_ttl_secs;
+        config.show_cache_command_ttls =
+            command_ttls.iter().map(|(command, ttl)| (command.to_string(), *ttl)).collect();
+        ShowCache::new(&config)
+    }
+
+    #[test]
+    fn test_ttl_per_command() {
+        let cache = cache(5, &[("show  version", 300), ("show arp", 0)]);
+        assert_eq!(cache.ttl_secs("show version"), 300);
+        assert_eq!(cache.ttl_secs("show arp"), 0);
+        assert_eq!(cache.ttl_secs("show interfaces"), 5);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_reads_reach_the_node_once() {
+        let cache = cache(5, &[("show arp", 0)]);
+        let transport = CountingTransport::default();
+        let node = Uuid::new_v4();
+
+        let (a, b) = tokio::join!(
+            cache.show(node, &transport, "show interfaces", false),
+            cache.show(node, &transport, "show interfaces", false)
+        );
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert_eq!(transport.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(a.result.output, b.result.output);
+        assert!(a.cached != b.cached);
+
+        let bypassed = cache.show(node, &transport, "show interfaces", true).await.unwrap();
+        assert!(!bypassed.cached);
+        assert_eq!(bypassed.result.output, "show interfaces #2");
+        let cached = cache.show(node, &transport, "show interfaces", false).await.unwrap();
+        assert_eq!(cached.result.output, "show interfaces #2");
+
+        // Uncached commands and other nodes always reach their node
+        cache.show(node, &transport, "show arp", false).await.unwrap();
+        cache.show(node, &transport, "show arp", false).await.unwrap();
+        cache.show(Uuid::new_v4(), &transport, "show interfaces", false).await.unwrap();
+        assert_eq!(transport.calls.load(Ordering::SeqCst), 5);
+
+        cache.forget(node);
+        assert!(!cache.show(node, &transport, "show interfaces", false).await.unwrap().cached);
+    }
+}
@@ -0,0 +1,163 @@
+//! Status Page Service
+//!
+//! Summarizes overall fleet health for the status page, e.g. an ops
+//! wallboard, and manages which components of it are shown to whom.
+
+use chrono::Utc;
+use std::collections::BTreeMap;
+use tracing::info;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::monitoring::{AlertSeverity, AlertStatus};
+use crate::models::status_page::{
+    AlertStatusSummary, ComponentVisibility, FleetStatus, IncidentSummary, NodeStatusSummary,
+    StatusComponent, StatusPage, StatusPageSettings, UpdateStatusPageSettingsRequest,
+};
+use crate::models::timestamp::db_now;
+use crate::services::MonitoringService;
+
+/// Status page service
+#[derive(Clone)]
+pub struct StatusPageService {
+    db: Database,
+    monitoring: MonitoringService,
+}
+
+impl StatusPageService {
+    /// Create a new status page service
+    pub fn new(db: Database, monitoring: MonitoringService) -> Self {
+        Self { db, monitoring }
+    }
+
+    /// Get the visibility of every status page component
+    pub async fn get_settings(&self) -> Result<StatusPageSettings, AppError> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT component, visibility FROM status_page_components",
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut components: BTreeMap<StatusComponent, ComponentVisibility> = StatusComponent::ALL
+            .into_iter()
+            .map(|component| (component, ComponentVisibility::default()))
+            .collect();
+        for (component, visibility) in rows {
+            if let Some(component) = StatusComponent::parse(&component) {
+                components.insert(component, ComponentVisibility::parse(&visibility));
+            }
+        }
+
+        Ok(StatusPageSettings { components })
+    }
+
+    /// Change the visibility of status page components
+    pub async fn update_settings(
+        &self,
+        request: UpdateStatusPageSettingsRequest,
+    ) -> Result<StatusPageSettings, AppError> {
+        let now = db_now();
+        for (component, visibility) in &request.components {
+            sqlx::query(
+                r#"
+                INSERT INTO status_page_components (component, visibility, updated_at)
+                VALUES (?, ?, ?)
+                ON CONFLICT (component) DO UPDATE SET
+                    visibility = excluded.visibility,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(component.as_str())
+            .bind(visibility.as_str())
+            .bind(&now)
+            .execute(self.db.pool())
+            .await?;
+        }
+
+        info!("Status page visibility updated: {:?}", request.components);
+        self.get_settings().await
+    }
+
+    /// Build the status page as seen by a caller
+    ///
+    /// Anonymous callers only get public components and are turned away when
+    /// there are none, so the page stays private until an administrator
+    /// publishes part of it.
+    pub async fn get_status_page(&self, authenticated: bool) -> Result<StatusPage, AppError> {
+        let settings = self.get_settings().await?;
+        let visible = |component: StatusComponent| {
+            settings
+                .components
+                .get(&component)
+                .copied()
+                .unwrap_or_default()
+                .allows(authenticated)
+        };
+
+        if !authenticated && !StatusComponent::ALL.into_iter().any(visible) {
+            return Err(AppError::Auth("Authentication required".to_string()));
+        }
+
+        let nodes = self.node_summary().await?;
+        let alerts = self.monitoring.get_alerts(None, None, None).await?;
+
+        let active = |severity: AlertSeverity| {
+            alerts
+                .iter()
+                .filter(|a| a.status == AlertStatus::Active && a.severity == severity)
+                .count() as u64
+        };
+        let alert_summary = AlertStatusSummary {
+            active_critical: active(AlertSeverity::Critical),
+            active_warning: active(AlertSeverity::Warning),
+        };
+        let last_incident = alerts
+            .iter()
+            .filter(|a| a.severity == AlertSeverity::Critical)
+            .max_by_key(|a| a.triggered_at)
+            .map(|a| IncidentSummary {
+                id: a.id,
+                title: a.title.clone(),
+                node_id: a.node_id.clone(),
+                status: a.status,
+                triggered_at: a.triggered_at,
+                resolved_at: a.resolved_at,
+            });
+
+        let online = nodes.by_status.get("online").copied().unwrap_or(0);
+        let status = if alert_summary.active_critical > 0 || (nodes.total > 0 && online == 0) {
+            FleetStatus::MajorOutage
+        } else if online < nodes.total {
+            FleetStatus::Degraded
+        } else {
+            FleetStatus::Operational
+        };
+
+        Ok(StatusPage {
+            status,
+            generated_at: Utc::now(),
+            nodes: visible(StatusComponent::Nodes).then_some(nodes),
+            alerts: visible(StatusComponent::Alerts).then_some(alert_summary),
+            last_incident: visible(StatusComponent::LastIncident).then_some(last_incident),
+        })
+    }
+
+    /// Count nodes by status
+    async fn node_summary(&self) -> Result<NodeStatusSummary, AppError> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT status, COUNT(*) FROM nodes GROUP BY status",
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let by_status: BTreeMap<String, u64> = rows
+            .into_iter()
+            .map(|(status, count)| (status, count as u64))
+            .collect();
+
+        Ok(NodeStatusSummary {
+            total: by_status.values().sum(),
+            by_status,
+        })
+    }
+}
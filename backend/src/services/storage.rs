@@ -0,0 +1,228 @@
+//! Compressed Storage
+//!
+//! Stores files and blobs compressed at rest. Content is compressed with
+//! zstd as it is written to an [`ObjectStore`] and decompressed as it is
+//! read, so callers only ever see the original bytes and large files never
+//! have to fit in memory. Each write reports the original and stored size,
+//! so callers can account for both.
+//!
+//! Content that is already compressed, such as installation images, is
+//! stored with [`Compression::None`] and passes through unchanged.
+
+use actix_web::web::Bytes;
+use async_compression::tokio::bufread::ZstdDecoder;
+use async_compression::tokio::write::ZstdEncoder;
+use async_compression::Level;
+use futures_util::Stream;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+
+use crate::error::AppError;
+use crate::models::storage::{Compression, StoredSize};
+use crate::services::object_store::{ObjectReader, ObjectStore, ObjectWriter};
+
+/// zstd level of stored content; favors write speed over the last few
+/// percent of size
+pub const ZSTD_LEVEL: i32 = 3;
+
+/// Size of the chunks stored content is read back in
+const READ_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Writer storing content to an object as it arrives
+pub struct StorageWriter {
+    out: Box<dyn ObjectWriter>,
+    /// Compresses into a buffer that is passed on after every write
+    encoder: Option<ZstdEncoder<Vec<u8>>>,
+    original_bytes: u64,
+}
+
+impl StorageWriter {
+    /// Start writing the object at `key`
+    pub async fn create(store: &dyn ObjectStore, key: &str, compression: Compression) -> Result<Self, AppError> {
+        let out = store.create(key).await?;
+        let encoder = match compression {
+            Compression::None => None,
+            Compression::Zstd => Some(ZstdEncoder::with_quality(Vec::new(), Level::Precise(ZSTD_LEVEL))),
+        };
+
+        Ok(Self {
+            out,
+            encoder,
+            original_bytes: 0,
+        })
+    }
+
+    /// Bytes of original content written so far
+    pub fn original_bytes(&self) -> u64 {
+        self.original_bytes
+    }
+
+    /// Store a chunk of content
+    pub async fn write(&mut self, chunk: &[u8]) -> Result<(), AppError> {
+        match &mut self.encoder {
+            Some(encoder) => {
+                encoder.write_all(chunk).await?;
+                let compressed = std::mem::take(encoder.get_mut());
+                if !compressed.is_empty() {
+                    self.out.write(&compressed).await?;
+                }
+            }
+            None => self.out.write(chunk).await?,
+        }
+        self.original_bytes += chunk.len() as u64;
+        Ok(())
+    }
+
+    /// Complete the object and report its size
+    pub async fn finish(mut self) -> Result<StoredSize, AppError> {
+        if let Some(mut encoder) = self.encoder.take() {
+            // Shutting down writes the end of the zstd frame
+            encoder.shutdown().await?;
+            self.out.write(&encoder.into_inner()).await?;
+        }
+        let stored_bytes = self.out.finish().await?;
+
+        Ok(StoredSize {
+            original_bytes: self.original_bytes,
+            stored_bytes,
+        })
+    }
+
+    /// Remove the partially written object
+    pub async fn discard(self) {
+        self.out.abort().await;
+    }
+}
+
+/// Store content at once
+pub async fn write_object(
+    store: &dyn ObjectStore,
+    key: &str,
+    compression: Compression,
+    content: &[u8],
+) -> Result<StoredSize, AppError> {
+    let mut writer = StorageWriter::create(store, key, compression).await?;
+    writer.write(content).await?;
+    writer.finish().await
+}
+
+/// Open stored content for reading, decompressing as it is read
+pub async fn open_object(
+    store: &dyn ObjectStore,
+    key: &str,
+    compression: Compression,
+) -> Result<ObjectReader, AppError> {
+    let reader = store.open(key).await?;
+
+    Ok(match compression {
+        Compression::None => reader,
+        Compression::Zstd => Box::pin(ZstdDecoder::new(BufReader::new(reader))),
+    })
+}
+
+/// Read stored content completely
+pub async fn read_object(store: &dyn ObjectStore, key: &str, compression: Compression) -> Result<Vec<u8>, AppError> {
+    let mut content = Vec::new();
+    open_object(store, key, compression).await?.read_to_end(&mut content).await?;
+    Ok(content)
+}
+
+/// Stream stored content in chunks of original bytes, e.g. as a download body
+pub async fn stream_object(
+    store: &dyn ObjectStore,
+    key: &str,
+    compression: Compression,
+) -> Result<impl Stream<Item = Result<Bytes, std::io::Error>>, AppError> {
+    let reader = open_object(store, key, compression).await?;
+
+    Ok(futures_util::stream::try_unfold(reader, |mut reader| async move {
+        let mut buffer = vec![0; READ_CHUNK_BYTES];
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buffer.truncate(read);
+        Ok(Some((Bytes::from(buffer), reader)))
+    }))
+}
+
+/// Compress a blob stored elsewhere than in a file, e.g. in a database column
+pub fn compress(content: &[u8]) -> Result<Vec<u8>, AppError> {
+    zstd::bulk::compress(content, ZSTD_LEVEL)
+        .map_err(|e| AppError::Internal(format!("Failed to compress content: {}", e)))
+}
+
+/// Decompress a blob compressed with [`compress`]
+pub fn decompress(content: &[u8]) -> Result<Vec<u8>, AppError> {
+    zstd::stream::decode_all(content)
+        .map_err(|e| AppError::Internal(format!("Failed to decompress content: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::object_store::FilesystemStore;
+    use futures_util::TryStreamExt;
+    use std::path::PathBuf;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("vyos-webui-storage-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_zstd_object_round_trip() {
+        let root = temp_dir();
+        let store = FilesystemStore::new(&root);
+        let content = br#"{"interfaces":{"ethernet":{"eth0":{"address":["192.0.2.1/24"]}}}}"#.repeat(2000);
+
+        let mut writer = StorageWriter::create(&store, "config.json", Compression::Zstd).await.unwrap();
+        for chunk in content.chunks(1000) {
+            writer.write(chunk).await.unwrap();
+        }
+        let size = writer.finish().await.unwrap();
+
+        assert_eq!(size.original_bytes, content.len() as u64);
+        assert!(size.stored_bytes < size.original_bytes / 10);
+        assert_eq!(read_object(&store, "config.json", Compression::Zstd).await.unwrap(), content);
+
+        let chunks: Vec<Bytes> =
+            stream_object(&store, "config.json", Compression::Zstd).await.unwrap().try_collect().await.unwrap();
+        assert_eq!(chunks.concat(), content);
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_uncompressed_object_is_stored_as_is() {
+        let root = temp_dir();
+        let store = FilesystemStore::new(&root);
+        let size = write_object(&store, "image.iso", Compression::None, b"ISO").await.unwrap();
+
+        assert_eq!(size, StoredSize { original_bytes: 3, stored_bytes: 3 });
+        assert_eq!(tokio::fs::read(root.join("image.iso")).await.unwrap(), b"ISO");
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_discarded_object_is_removed() {
+        let root = temp_dir();
+        let store = FilesystemStore::new(&root);
+        let mut writer = StorageWriter::create(&store, "partial", Compression::Zstd).await.unwrap();
+        writer.write(b"partial content").await.unwrap();
+        writer.discard().await;
+
+        assert!(store.head("partial").await.unwrap().is_none());
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+
+    #[test]
+    fn test_blob_round_trip() {
+        let content = b"set system host-name vyos-edge\n".repeat(100);
+        let compressed = compress(&content).unwrap();
+
+        assert!(compressed.len() < content.len());
+        assert_eq!(decompress(&compressed).unwrap(), content);
+        assert!(decompress(b"not zstd").is_err());
+    }
+}
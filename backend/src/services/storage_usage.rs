@@ -0,0 +1,160 @@
+//! Storage Usage Service
+//!
+//! Accounts for the space taken by the database, its backups, and uploaded
+//! files, and enforces the configured storage cap. Usage is measured on
+//! demand by listing the artifact stores, so files added or removed outside
+//! the backend are counted, too.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tracing::warn;
+
+use crate::config::AppConfig;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::storage::{StorageCategory, StorageCategoryUsage, StorageStatus, StorageUsageResponse};
+use crate::services::object_store::{artifact_store, ObjectStore};
+
+/// Files SQLite keeps next to a database file
+const DATABASE_FILE_SUFFIXES: [&str; 4] = ["", "-wal", "-shm", "-journal"];
+
+/// Storage usage service
+#[derive(Clone)]
+pub struct StorageUsageService {
+    db: Database,
+    backups: Arc<dyn ObjectStore>,
+    uploads: Arc<dyn ObjectStore>,
+    cap_bytes: Option<u64>,
+    warning_percent: u8,
+}
+
+impl StorageUsageService {
+    /// Create a new storage usage service
+    pub fn new(config: &AppConfig, db: Database) -> Self {
+        Self {
+            db,
+            backups: artifact_store(config, &config.backup_dir, StorageCategory::Backups.label()),
+            uploads: artifact_store(config, &config.upload_dir, StorageCategory::Uploads.label()),
+            cap_bytes: config.storage_cap_bytes,
+            warning_percent: config.storage_warning_percent,
+        }
+    }
+
+    /// Measure the space taken by each category
+    pub async fn get_usage(&self) -> Result<StorageUsageResponse, AppError> {
+        let mut categories = Vec::new();
+        for category in StorageCategory::ALL {
+            categories.push(self.category_usage(category).await?);
+        }
+
+        let total_bytes = categories.iter().map(|usage| usage.bytes).sum();
+        let status = StorageStatus::of(total_bytes, self.cap_bytes, self.warning_percent);
+        let used_percent = self
+            .cap_bytes
+            .map(|cap| if cap == 0 { 100.0 } else { total_bytes as f64 * 100.0 / cap as f64 });
+
+        let mut warnings = Vec::new();
+        match (status, self.cap_bytes) {
+            (StorageStatus::Full, Some(cap)) => warnings.push(format!(
+                "Storage cap of {} bytes is used up; new uploads and backups are refused",
+                cap
+            )),
+            (StorageStatus::Warning, Some(cap)) => warnings.push(format!(
+                "Storage is at {:.0}% of its cap of {} bytes",
+                used_percent.unwrap_or_default(),
+                cap
+            )),
+            _ => {}
+        }
+
+        Ok(StorageUsageResponse {
+            categories,
+            total_bytes,
+            cap_bytes: self.cap_bytes,
+            remaining_bytes: self.cap_bytes.map(|cap| cap.saturating_sub(total_bytes)),
+            used_percent,
+            warning_percent: self.warning_percent,
+            status,
+            warnings,
+        })
+    }
+
+    /// Check that there is room left to store more data of a category
+    ///
+    /// Always succeeds when no cap is configured.
+    pub async fn ensure_capacity(&self, category: StorageCategory) -> Result<(), AppError> {
+        let Some(cap) = self.cap_bytes else {
+            return Ok(());
+        };
+
+        let usage = self.get_usage().await?;
+        if usage.status == StorageStatus::Full {
+            warn!("Refusing new {}: {} of {} bytes of storage used", category.label(), usage.total_bytes, cap);
+            return Err(AppError::QuotaExceeded(format!(
+                "Storage cap of {} bytes is used up, no new {} can be stored",
+                cap,
+                category.label()
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn category_usage(&self, category: StorageCategory) -> Result<StorageCategoryUsage, AppError> {
+        let (path, (bytes, files)) = match category {
+            StorageCategory::Database => match self.database_file().await? {
+                Some(path) => {
+                    let usage = database_usage(&path).await?;
+                    (Some(path.to_string_lossy().into_owned()), usage)
+                }
+                None => (None, (0, 0)),
+            },
+            StorageCategory::Backups => (Some(self.backups.location()), store_usage(self.backups.as_ref()).await?),
+            StorageCategory::Uploads => (Some(self.uploads.location()), store_usage(self.uploads.as_ref()).await?),
+        };
+
+        Ok(StorageCategoryUsage {
+            category,
+            path,
+            bytes,
+            files,
+        })
+    }
+
+    /// Path of the main database file; `None` for in-memory databases
+    async fn database_file(&self) -> Result<Option<PathBuf>, AppError> {
+        let file: String = sqlx::query_scalar("SELECT file FROM pragma_database_list WHERE name = 'main'")
+            .fetch_one(self.db.pool())
+            .await?;
+
+        Ok(Some(PathBuf::from(file)).filter(|path| !path.as_os_str().is_empty()))
+    }
+}
+
+/// Bytes and number of the files of a SQLite database, including its
+/// write-ahead log and journal
+async fn database_usage(path: &Path) -> Result<(u64, u64), AppError> {
+    let mut bytes = 0;
+    let mut files = 0;
+    for suffix in DATABASE_FILE_SUFFIXES {
+        let mut file = path.as_os_str().to_owned();
+        file.push(suffix);
+        match tokio::fs::metadata(&file).await {
+            Ok(metadata) => {
+                bytes += metadata.len();
+                files += 1;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok((bytes, files))
+}
+
+/// Bytes and number of the objects of a store
+async fn store_usage(store: &dyn ObjectStore) -> Result<(u64, u64), AppError> {
+    let objects = store.list().await?;
+    Ok((objects.iter().map(|object| object.size_bytes).sum(), objects.len() as u64))
+}
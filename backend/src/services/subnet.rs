@@ -0,0 +1,277 @@
+//! Subnet Calculator Service
+//!
+//! CIDR arithmetic for address planning: describing, splitting, and
+//! summarizing subnets, checking membership, and finding free subnets in an
+//! address block. Free subnets avoid every prefix in use on the nodes the
+//! caller can access, as collected by the [`IpamService`].
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use ipnet::IpNet;
+
+use crate::error::AppError;
+use crate::models::subnet::{
+    ContainsRequest, ContainsResponse, ContainsResult, NextAvailableRequest, NextAvailableResponse,
+    SplitSubnetRequest, SplitSubnetResponse, SubnetInfo, SummarizeSubnetsRequest,
+    SummarizeSubnetsResponse, MAX_SPLIT_SUBNETS,
+};
+use crate::models::team::AccessScope;
+use crate::services::ipam::{capacity, IpamService};
+
+/// Subnet calculator service
+#[derive(Clone)]
+pub struct SubnetService {
+    ipam: IpamService,
+}
+
+impl SubnetService {
+    /// Create a new subnet calculator service
+    pub fn new(ipam: IpamService) -> Self {
+        Self { ipam }
+    }
+
+    /// Describe a subnet
+    pub fn describe(&self, cidr: &str) -> Result<SubnetInfo, AppError> {
+        let net = parse_network(cidr)?;
+        let (first, last) = host_range(&net);
+
+        Ok(SubnetInfo {
+            network: net.to_string(),
+            version: if net.addr().is_ipv4() { 4 } else { 6 },
+            prefix_len: net.prefix_len(),
+            netmask: net.netmask().to_string(),
+            hostmask: net.hostmask().to_string(),
+            broadcast: match net {
+                IpNet::V4(v4) if v4.prefix_len() < 32 => Some(v4.broadcast().to_string()),
+                _ => None,
+            },
+            first_host: first.to_string(),
+            last_host: last.to_string(),
+            capacity: capacity(&net),
+        })
+    }
+
+    /// Split a subnet into subnets of a longer prefix length
+    pub fn split(&self, request: &SplitSubnetRequest) -> Result<SplitSubnetResponse, AppError> {
+        let net = parse_network(&request.cidr)?;
+        if request.prefix_len < net.prefix_len() || request.prefix_len > net.max_prefix_len() {
+            return Err(AppError::Validation(format!(
+                "Prefix length must be between {} and {}",
+                net.prefix_len(),
+                net.max_prefix_len()
+            )));
+        }
+        let count = 1u64.checked_shl(u32::from(request.prefix_len - net.prefix_len()));
+        if !matches!(count, Some(count) if count <= MAX_SPLIT_SUBNETS) {
+            return Err(AppError::Validation(format!(
+                "Splitting {} into /{} subnets yields more than {} subnets",
+                net, request.prefix_len, MAX_SPLIT_SUBNETS
+            )));
+        }
+
+        let subnets = net
+            .subnets(request.prefix_len)
+            .map_err(|e| AppError::Validation(e.to_string()))?
+            .map(|subnet| subnet.to_string())
+            .collect();
+
+        Ok(SplitSubnetResponse {
+            network: net.to_string(),
+            subnets,
+        })
+    }
+
+    /// Summarize subnets into the fewest prefixes covering exactly the same
+    /// addresses
+    pub fn summarize(&self, request: &SummarizeSubnetsRequest) -> Result<SummarizeSubnetsResponse, AppError> {
+        let networks = request
+            .cidrs
+            .iter()
+            .map(|cidr| parse_network(cidr))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(SummarizeSubnetsResponse {
+            summary: IpNet::aggregate(&networks).iter().map(|net| net.to_string()).collect(),
+        })
+    }
+
+    /// Check which addresses or subnets fall into a subnet
+    pub fn contains(&self, request: &ContainsRequest) -> Result<ContainsResponse, AppError> {
+        let net = parse_network(&request.cidr)?;
+        let results = request
+            .addresses
+            .iter()
+            .map(|address| {
+                let contained = match address.parse::<IpAddr>() {
+                    Ok(ip) => net.contains(&ip),
+                    Err(_) => net.contains(&parse_network(address)?),
+                };
+                Ok(ContainsResult {
+                    address: address.clone(),
+                    contained,
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        Ok(ContainsResponse {
+            network: net.to_string(),
+            results,
+        })
+    }
+
+    /// Find the lowest free subnets of a prefix length inside a block
+    ///
+    /// A subnet is free when it overlaps no prefix in use on the nodes the
+    /// caller can access. Nodes that cannot be read are reported, since their
+    /// prefixes could not be taken into account.
+    pub async fn next_available(
+        &self,
+        request: &NextAvailableRequest,
+        scope: &AccessScope,
+    ) -> Result<NextAvailableResponse, AppError> {
+        let within = parse_network(&request.within)?;
+        if request.prefix_len < within.prefix_len() || request.prefix_len > within.max_prefix_len() {
+            return Err(AppError::Validation(format!(
+                "Prefix length must be between {} and {}",
+                within.prefix_len(),
+                within.max_prefix_len()
+            )));
+        }
+
+        let plan = self.ipam.address_plan(scope).await?;
+        let in_use: Vec<IpNet> = plan
+            .prefixes
+            .iter()
+            .filter_map(|prefix| prefix.prefix.parse().ok())
+            .collect();
+
+        Ok(NextAvailableResponse {
+            within: within.to_string(),
+            subnets: free_subnets(&within, request.prefix_len, &in_use, request.count)
+                .iter()
+                .map(|net| net.to_string())
+                .collect(),
+            unavailable_nodes: plan.unavailable_nodes,
+        })
+    }
+}
+
+/// Parse a subnet in CIDR notation, dropping host bits
+fn parse_network(cidr: &str) -> Result<IpNet, AppError> {
+    cidr.trim()
+        .parse::<IpNet>()
+        .map(|net| net.trunc())
+        .map_err(|_| AppError::Validation(format!("Invalid CIDR: {}", cidr)))
+}
+
+/// First and last assignable address of a subnet
+fn host_range(net: &IpNet) -> (IpAddr, IpAddr) {
+    let (start, end) = bounds(net);
+    match net {
+        IpNet::V4(v4) if v4.prefix_len() < 31 => (to_ip(net, start + 1), to_ip(net, end - 1)),
+        _ => (to_ip(net, start), to_ip(net, end)),
+    }
+}
+
+/// First and last address of a subnet as integers
+fn bounds(net: &IpNet) -> (u128, u128) {
+    let start = to_int(net.network());
+    (start, start + host_mask(net.max_prefix_len(), net.prefix_len()))
+}
+
+/// Host part of an address with `max_len` bits under a prefix length
+fn host_mask(max_len: u8, prefix_len: u8) -> u128 {
+    u128::MAX.checked_shr(128 - u32::from(max_len - prefix_len)).unwrap_or(0)
+}
+
+fn to_int(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ip) => u128::from(u32::from(ip)),
+        IpAddr::V6(ip) => u128::from(ip),
+    }
+}
+
+/// Address of the same family as `net`
+fn to_ip(net: &IpNet, value: u128) -> IpAddr {
+    match net {
+        IpNet::V4(_) => IpAddr::V4(Ipv4Addr::from(value as u32)),
+        IpNet::V6(_) => IpAddr::V6(Ipv6Addr::from(value)),
+    }
+}
+
+/// Lowest subnets of `prefix_len` in `within` that overlap nothing in use
+///
+/// Skips over each prefix in use instead of testing every candidate, so a
+/// large block with a few big allocations is searched quickly.
+fn free_subnets(within: &IpNet, prefix_len: u8, in_use: &[IpNet], count: usize) -> Vec<IpNet> {
+    let used: Vec<(u128, u128)> = in_use
+        .iter()
+        .filter(|net| net.addr().is_ipv4() == within.addr().is_ipv4())
+        .map(bounds)
+        .collect();
+    let (mut start, block_end) = bounds(within);
+    let host = host_mask(within.max_prefix_len(), prefix_len);
+
+    let mut free = Vec::new();
+    while free.len() < count && start <= block_end {
+        let end = start + host;
+        let overlap_end = used
+            .iter()
+            .filter(|(used_start, used_end)| *used_start <= end && *used_end >= start)
+            .map(|(_, used_end)| *used_end)
+            .max();
+        let next = match overlap_end {
+            // Continue at the first aligned candidate after the prefix in use
+            Some(used_end) => used_end.checked_add(1).and_then(|next| next.checked_add(host)).map(|next| next & !host),
+            None => {
+                free.push(IpNet::new(to_ip(within, start), prefix_len).expect("prefix length is checked"));
+                end.checked_add(1)
+            }
+        };
+        match next {
+            Some(next) => start = next,
+            None => break,
+        }
+    }
+
+    free
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn net(cidr: &str) -> IpNet {
+        cidr.parse().unwrap()
+    }
+
+    #[test]
+    fn test_host_range() {
+        let range = |cidr: &str| {
+            let (first, last) = host_range(&net(cidr));
+            (first.to_string(), last.to_string())
+        };
+        assert_eq!(range("192.0.2.0/24"), ("192.0.2.1".to_string(), "192.0.2.254".to_string()));
+        assert_eq!(range("192.0.2.0/31"), ("192.0.2.0".to_string(), "192.0.2.1".to_string()));
+        assert_eq!(range("192.0.2.7/32"), ("192.0.2.7".to_string(), "192.0.2.7".to_string()));
+        assert_eq!(range("2001:db8::/127"), ("2001:db8::".to_string(), "2001:db8::1".to_string()));
+        assert_eq!(range("::/0").1, "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff");
+    }
+
+    #[test]
+    fn test_free_subnets_skip_prefixes_in_use() {
+        let in_use = [net("10.0.0.0/24"), net("10.0.1.5/32"), net("10.0.4.0/22"), net("2001:db8::/64")];
+        let free = free_subnets(&net("10.0.0.0/16"), 24, &in_use, 4);
+        let free: Vec<String> = free.iter().map(|n| n.to_string()).collect();
+        assert_eq!(free, vec!["10.0.2.0/24", "10.0.3.0/24", "10.0.8.0/24", "10.0.9.0/24"]);
+
+        // A block that is fully in use has nothing free
+        assert!(free_subnets(&net("10.0.4.0/23"), 24, &in_use, 1).is_empty());
+
+        // The last subnet of the address space ends the search
+        let free = free_subnets(&net("255.255.255.252/30"), 31, &[], 4);
+        assert_eq!(free.len(), 2);
+        let free = free_subnets(&net("::/0"), 1, &[net("::/1")], 4);
+        assert_eq!(free, vec![net("8000::/1")]);
+    }
+}
@@ -0,0 +1,159 @@
+//! Syslog Receiver
+//!
+//! Listens for syslog messages that nodes send over UDP, in either the BSD
+//! (RFC 3164) or the structured (RFC 5424) format, and passes those of known
+//! nodes on to the firewall log. Senders are recognized by source address,
+//! or by the hostname in the message when it matches a node's name.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use tokio::net::UdpSocket;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::services::firewall_log::FirewallLogService;
+use crate::services::node_service::NodeService;
+
+/// Largest syslog datagram accepted
+const MAX_DATAGRAM_BYTES: usize = 8192;
+
+/// How long the known node addresses are used before they are reloaded
+const NODE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Syslog receiver
+pub struct SyslogReceiver {
+    socket: UdpSocket,
+    nodes: NodeService,
+    firewall_logs: FirewallLogService,
+    /// Node IDs by address and by lowercase name
+    known: HashMap<String, Uuid>,
+    refreshed_at: Option<Instant>,
+}
+
+impl SyslogReceiver {
+    /// Bind the receiver to a UDP address
+    pub async fn bind(
+        addr: SocketAddr,
+        nodes: NodeService,
+        firewall_logs: FirewallLogService,
+    ) -> Result<Self, AppError> {
+        let socket = UdpSocket::bind(addr)
+            .await
+            .map_err(|e| AppError::Config(format!("Cannot listen for syslog on {}: {}", addr, e)))?;
+
+        Ok(Self {
+            socket,
+            nodes,
+            firewall_logs,
+            known: HashMap::new(),
+            refreshed_at: None,
+        })
+    }
+
+    /// Address the receiver is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr, AppError> {
+        self.socket
+            .local_addr()
+            .map_err(|e| AppError::Internal(format!("Syslog socket has no address: {}", e)))
+    }
+
+    /// Receive messages until the socket fails
+    pub async fn run(mut self) {
+        let mut buffer = vec![0u8; MAX_DATAGRAM_BYTES];
+        loop {
+            let (len, from) = match self.socket.recv_from(&mut buffer).await {
+                Ok(received) => received,
+                Err(e) => {
+                    warn!("Syslog receiver stopped: {}", e);
+                    return;
+                }
+            };
+            let datagram = String::from_utf8_lossy(&buffer[..len]).into_owned();
+            self.handle(from.ip(), &datagram).await;
+        }
+    }
+
+    async fn handle(&mut self, from: IpAddr, datagram: &str) {
+        let (hostname, message) = parse_syslog(datagram);
+        let Some(node_id) = self.node_for(from, hostname).await else {
+            debug!("Ignoring syslog message from unknown sender {}", from);
+            return;
+        };
+
+        if let Err(e) = self.firewall_logs.ingest(node_id, message).await {
+            warn!("Failed to store firewall log entry of node {}: {}", node_id, e);
+        }
+    }
+
+    /// Node that sent a message
+    async fn node_for(&mut self, from: IpAddr, hostname: Option<&str>) -> Option<Uuid> {
+        if self.refreshed_at.is_none_or(|at| at.elapsed() >= NODE_REFRESH_INTERVAL) {
+            match self.nodes.list_all_nodes().await {
+                Ok(nodes) => {
+                    self.known.clear();
+                    for node in nodes {
+                        self.known.insert(node.name.to_lowercase(), node.id);
+                        self.known.insert(node.host.to_lowercase(), node.id);
+                    }
+                }
+                Err(e) => warn!("Failed to load nodes for syslog senders: {}", e),
+            }
+            self.refreshed_at = Some(Instant::now());
+        }
+
+        self.known.get(&from.to_string()).copied().or_else(|| {
+            hostname.and_then(|hostname| self.known.get(&hostname.to_lowercase()).copied())
+        })
+    }
+}
+
+/// Hostname and message of a syslog datagram
+///
+/// RFC 5424 messages keep their MSGID and structured data in front of the
+/// text.
+fn parse_syslog(datagram: &str) -> (Option<&str>, &str) {
+    let datagram = datagram.trim_end_matches(['\r', '\n', '\0']);
+    let rest = match datagram.strip_prefix('<').and_then(|rest| rest.split_once('>')) {
+        Some((priority, rest)) if priority.chars().all(|c| c.is_ascii_digit()) => rest,
+        _ => datagram,
+    };
+
+    // RFC 5424: VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID ...
+    if let Some(rest) = rest.strip_prefix("1 ") {
+        let mut parts = rest.splitn(5, ' ');
+        let hostname = parts.nth(1).filter(|hostname| *hostname != "-");
+        return (hostname, parts.nth(2).unwrap_or(""));
+    }
+
+    // RFC 3164: "Mmm dd hh:mm:ss HOSTNAME MSG"
+    let bytes = rest.as_bytes();
+    if bytes.len() > 16 && bytes[3] == b' ' && bytes[6] == b' ' && bytes[15] == b' ' {
+        if let Some((hostname, message)) = rest[16..].split_once(' ') {
+            return (Some(hostname), message);
+        }
+    }
+
+    (None, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_syslog() {
+        assert_eq!(
+            parse_syslog("<4>Oct  6 10:00:00 edge-1 kernel: [WAN_IN-10-D]IN=eth0\n"),
+            (Some("edge-1"), "kernel: [WAN_IN-10-D]IN=eth0")
+        );
+        assert_eq!(
+            parse_syslog("<12>1 2026-10-16T10:00:00Z edge-2 kernel - - - [WAN_IN-10-D]IN=eth0"),
+            (Some("edge-2"), "- - [WAN_IN-10-D]IN=eth0")
+        );
+        assert_eq!(parse_syslog("<12>1 - - kernel - - - hello"), (None, "- - hello"));
+        assert_eq!(parse_syslog("kernel: hello"), (None, "kernel: hello"));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::config::AppConfig;
 use crate::error::AppError;
+use crate::i18n::{t, t_args};
 use crate::models::system::{
     AddImageRequest, DeleteImageRequest, ImageManagementRequest, OperationResult,
     ResetConfigRequest, SetDefaultImageRequest, ShowCommandRequest, ShowCommandResult,
@@ -117,7 +118,7 @@ impl SystemService {
                 info!("Reboot command executed successfully");
                 Ok(OperationResult {
                     success: true,
-                    message: "System reboot initiated successfully".to_string(),
+                    message: t("operation.reboot_initiated"),
                     operation_id: operation_id.clone(),
                     started_at,
                     completed_at: Some(started_at),
@@ -132,7 +133,7 @@ impl SystemService {
                 error!("Failed to initiate reboot: {}", e);
                 Ok(OperationResult {
                     success: false,
-                    message: format!("Failed to initiate reboot: {}", e),
+                    message: t_args("operation.reboot_failed", &[("error", &e)]),
                     operation_id,
                     started_at,
                     completed_at: Some(Utc::now()),
@@ -156,7 +157,7 @@ impl SystemService {
                 info!("Poweroff command executed successfully");
                 Ok(OperationResult {
                     success: true,
-                    message: "System poweroff initiated successfully".to_string(),
+                    message: t("operation.poweroff_initiated"),
                     operation_id: operation_id.clone(),
                     started_at,
                     completed_at: Some(started_at),
@@ -171,7 +172,7 @@ impl SystemService {
                 error!("Failed to initiate poweroff: {}", e);
                 Ok(OperationResult {
                     success: false,
-                    message: format!("Failed to initiate poweroff: {}", e),
+                    message: t_args("operation.poweroff_failed", &[("error", &e)]),
                     operation_id,
                     started_at,
                     completed_at: Some(Utc::now()),
@@ -195,7 +196,7 @@ impl SystemService {
         if !request.confirmed {
             return Ok(OperationResult {
                 success: false,
-                message: "Configuration reset not confirmed. Set confirmed=true to proceed.".to_string(),
+                message: t("operation.reset_not_confirmed"),
                 operation_id,
                 started_at,
                 completed_at: Some(Utc::now()),
@@ -227,9 +228,9 @@ impl SystemService {
                 info!("Configuration reset executed successfully");
                 Ok(OperationResult {
                     success: true,
-                    message: format!(
-                        "Configuration reset ({:?}) completed successfully",
-                        request.reset_type
+                    message: t_args(
+                        "operation.reset_completed",
+                        &[("reset_type", &format!("{:?}", request.reset_type))],
                     ),
                     operation_id: operation_id.clone(),
                     started_at,
@@ -245,7 +246,7 @@ impl SystemService {
                 error!("Failed to reset configuration: {}", e);
                 Ok(OperationResult {
                     success: false,
-                    message: format!("Failed to reset configuration: {}", e),
+                    message: t_args("operation.reset_failed", &[("error", &e)]),
                     operation_id,
                     started_at,
                     completed_at: Some(Utc::now()),
@@ -331,7 +332,7 @@ impl SystemService {
                 info!("Image addition initiated successfully");
                 Ok(OperationResult {
                     success: true,
-                    message: "Image download and installation initiated".to_string(),
+                    message: t("operation.image_add_initiated"),
                     operation_id: operation_id.clone(),
                     started_at,
                     completed_at: None, // Still running
@@ -346,7 +347,7 @@ impl SystemService {
                 error!("Failed to add image: {}", e);
                 Ok(OperationResult {
                     success: false,
-                    message: format!("Failed to add image: {}", e),
+                    message: t_args("operation.image_add_failed", &[("error", &e)]),
                     operation_id,
                     started_at,
                     completed_at: Some(Utc::now()),
@@ -373,7 +374,7 @@ impl SystemService {
                 info!("Image deleted successfully");
                 Ok(OperationResult {
                     success: true,
-                    message: format!("Image '{}' deleted successfully", request.name),
+                    message: t_args("operation.image_deleted", &[("name", &request.name)]),
                     operation_id: operation_id.clone(),
                     started_at,
                     completed_at: Some(Utc::now()),
@@ -388,7 +389,7 @@ impl SystemService {
                 error!("Failed to delete image: {}", e);
                 Ok(OperationResult {
                     success: false,
-                    message: format!("Failed to delete image: {}", e),
+                    message: t_args("operation.image_delete_failed", &[("error", &e)]),
                     operation_id,
                     started_at,
                     completed_at: Some(Utc::now()),
@@ -418,10 +419,7 @@ impl SystemService {
                 info!("Default image set successfully");
                 Ok(OperationResult {
                     success: true,
-                    message: format!(
-                        "Default boot image set to '{}' successfully",
-                        request.name
-                    ),
+                    message: t_args("operation.image_default_set", &[("name", &request.name)]),
                     operation_id: operation_id.clone(),
                     started_at,
                     completed_at: Some(Utc::now()),
@@ -436,7 +434,7 @@ impl SystemService {
                 error!("Failed to set default image: {}", e);
                 Ok(OperationResult {
                     success: false,
-                    message: format!("Failed to set default image: {}", e),
+                    message: t_args("operation.image_default_failed", &[("error", &e)]),
                     operation_id,
                     started_at,
                     completed_at: Some(Utc::now()),
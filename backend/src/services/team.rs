@@ -0,0 +1,308 @@
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::i18n::{t, t_args};
+use crate::models::auth::Claims;
+use crate::models::team::{
+    is_valid_team_alias, AccessScope, AddTeamMemberRequest, CreateTeamRequest, Team, TeamDetail,
+    TeamListResponse, TeamMember, TeamRole, UpdateTeamRequest,
+};
+use crate::models::user::UserRecord;
+
+/// Team service for team management and ownership checks
+#[derive(Clone)]
+pub struct TeamService {
+    db: Database,
+}
+
+impl TeamService {
+    /// Create a new team service
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Resolve the authenticated user behind a set of claims
+    pub async fn current_user(&self, claims: &Claims) -> Result<UserRecord, AppError> {
+        let user_id = claims.user_id()?;
+        self.db
+            .find_user_by_id(&user_id.to_string())
+            .await?
+            .ok_or_else(|| AppError::Auth("User no longer exists".to_string()))
+    }
+
+    /// Determine which teams' resources the user may access
+    pub async fn access_scope(&self, claims: &Claims) -> Result<AccessScope, AppError> {
+        let user = self.current_user(claims).await?;
+        if user.is_superuser {
+            return Ok(AccessScope::All);
+        }
+
+        let team_ids = self
+            .db
+            .find_team_ids_for_user(&user.id)
+            .await?
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+
+        Ok(AccessScope::Teams(team_ids))
+    }
+
+    /// List teams visible to the user
+    ///
+    /// Superusers see every team; other users see the teams they belong to.
+    pub async fn list_teams(&self, claims: &Claims) -> Result<TeamListResponse, AppError> {
+        let user = self.current_user(claims).await?;
+        let member_filter = if user.is_superuser { None } else { Some(user.id.as_str()) };
+
+        let teams: Vec<Team> = self
+            .db
+            .list_teams(member_filter)
+            .await?
+            .iter()
+            .map(|r| r.to_team())
+            .collect();
+        let total = teams.len() as u64;
+
+        Ok(TeamListResponse { teams, total })
+    }
+
+    /// Get a team with its members
+    pub async fn get_team(&self, claims: &Claims, team_id: Uuid) -> Result<TeamDetail, AppError> {
+        let scope = self.access_scope(claims).await?;
+        if !scope.includes_team(team_id) {
+            return Err(AppError::Forbidden("You are not a member of this team".to_string()));
+        }
+
+        let team = self.find_team(team_id).await?;
+        let members = self.list_members(team_id).await?;
+
+        Ok(TeamDetail { team, members })
+    }
+
+    /// Create a new team (superuser only)
+    pub async fn create_team(&self, claims: &Claims, request: CreateTeamRequest) -> Result<Team, AppError> {
+        let user = self.current_user(claims).await?;
+        if !user.is_superuser {
+            return Err(AppError::Forbidden("Only administrators can create teams".to_string()));
+        }
+
+        validate_alias(&request.alias)?;
+        self.ensure_alias_available(&request.alias, None).await?;
+
+        let team_id = Uuid::new_v4();
+        self.db
+            .create_team(
+                &team_id.to_string(),
+                &request.name,
+                &request.alias,
+                request.description.as_deref(),
+            )
+            .await?;
+
+        info!("Created team {} ({})", request.name, request.alias);
+
+        self.find_team(team_id).await
+    }
+
+    /// Update a team (superuser or team maintainer)
+    pub async fn update_team(
+        &self,
+        claims: &Claims,
+        team_id: Uuid,
+        request: UpdateTeamRequest,
+    ) -> Result<Team, AppError> {
+        self.ensure_can_manage(claims, team_id).await?;
+
+        if let Some(alias) = &request.alias {
+            validate_alias(alias)?;
+            self.ensure_alias_available(alias, Some(team_id)).await?;
+        }
+
+        self.db
+            .update_team(
+                &team_id.to_string(),
+                request.name.as_deref(),
+                request.alias.as_deref(),
+                request.description.as_deref(),
+            )
+            .await?;
+
+        self.find_team(team_id).await
+    }
+
+    /// Delete a team (superuser only)
+    ///
+    /// Resources owned by the team become unowned rather than being deleted.
+    pub async fn delete_team(&self, claims: &Claims, team_id: Uuid) -> Result<(), AppError> {
+        let user = self.current_user(claims).await?;
+        if !user.is_superuser {
+            return Err(AppError::Forbidden("Only administrators can delete teams".to_string()));
+        }
+
+        if !self.db.delete_team(&team_id.to_string()).await? {
+            return Err(AppError::NotFound(format!("Team {} not found", team_id)));
+        }
+
+        info!("Deleted team {}", team_id);
+
+        Ok(())
+    }
+
+    /// Add a member to a team or change their role
+    pub async fn add_member(
+        &self,
+        claims: &Claims,
+        team_id: Uuid,
+        request: AddTeamMemberRequest,
+    ) -> Result<Vec<TeamMember>, AppError> {
+        self.ensure_can_manage(claims, team_id).await?;
+
+        let user_id = request.user_id.to_string();
+        if self.db.find_user_by_id(&user_id).await?.is_none() {
+            return Err(AppError::NotFound(format!("User {} not found", request.user_id)));
+        }
+
+        let role = request.role.unwrap_or(TeamRole::Member);
+        self.db
+            .upsert_team_member(&team_id.to_string(), &user_id, role.as_str())
+            .await?;
+
+        info!("Added user {} to team {} as {}", request.user_id, team_id, role.as_str());
+
+        self.list_members(team_id).await
+    }
+
+    /// Remove a member from a team
+    pub async fn remove_member(&self, claims: &Claims, team_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        self.ensure_can_manage(claims, team_id).await?;
+
+        let removed = self
+            .db
+            .remove_team_member(&team_id.to_string(), &user_id.to_string())
+            .await?;
+        if !removed {
+            return Err(AppError::NotFound(format!(
+                "User {} is not a member of team {}",
+                user_id, team_id
+            )));
+        }
+
+        info!("Removed user {} from team {}", user_id, team_id);
+
+        Ok(())
+    }
+
+    /// Ensure the user may assign a resource to the given owning team
+    ///
+    /// Superusers can assign any team; other users can only hand resources to
+    /// teams they belong to.
+    pub async fn ensure_can_assign(&self, claims: &Claims, team_id: Option<Uuid>) -> Result<(), AppError> {
+        let Some(team_id) = team_id else {
+            return Ok(());
+        };
+
+        self.find_team(team_id).await?;
+
+        let scope = self.access_scope(claims).await?;
+        if !scope.includes_team(team_id) {
+            return Err(AppError::Forbidden(
+                "Resources can only be assigned to teams you belong to".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Resolve a team alias to the email addresses of its members
+    ///
+    /// Used by notification delivery so that rules can target `@alias`
+    /// instead of individual addresses.
+    pub async fn resolve_alias_recipients(&self, alias: &str) -> Result<Vec<String>, AppError> {
+        let alias = alias.trim_start_matches('@');
+        let team = self
+            .db
+            .find_team_by_alias(alias)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Team alias '{}' not found", alias)))?;
+
+        Ok(self
+            .db
+            .list_team_members(&team.id)
+            .await?
+            .into_iter()
+            .map(|m| m.email)
+            .collect())
+    }
+
+    /// Fetch a team or fail with NotFound
+    async fn find_team(&self, team_id: Uuid) -> Result<Team, AppError> {
+        self.db
+            .find_team_by_id(&team_id.to_string())
+            .await?
+            .map(|r| r.to_team())
+            .ok_or_else(|| AppError::NotFound(format!("Team {} not found", team_id)))
+    }
+
+    /// List members of a team
+    async fn list_members(&self, team_id: Uuid) -> Result<Vec<TeamMember>, AppError> {
+        Ok(self
+            .db
+            .list_team_members(&team_id.to_string())
+            .await?
+            .iter()
+            .map(|r| r.to_member())
+            .collect())
+    }
+
+    /// Ensure the user is a superuser or a maintainer of the team
+    async fn ensure_can_manage(&self, claims: &Claims, team_id: Uuid) -> Result<(), AppError> {
+        self.find_team(team_id).await?;
+
+        let user = self.current_user(claims).await?;
+        if user.is_superuser {
+            return Ok(());
+        }
+
+        let role = self.db.find_team_role(&team_id.to_string(), &user.id).await?;
+        match role.as_deref().map(TeamRole::parse) {
+            Some(TeamRole::Maintainer) => Ok(()),
+            _ => Err(AppError::Forbidden(
+                "Only administrators and team maintainers can manage this team".to_string(),
+            )),
+        }
+    }
+
+    /// Ensure no other team uses the alias
+    async fn ensure_alias_available(&self, alias: &str, team_id: Option<Uuid>) -> Result<(), AppError> {
+        if let Some(existing) = self.db.find_team_by_alias(alias).await? {
+            if team_id.map(|id| id.to_string()) != Some(existing.id) {
+                return Err(AppError::Validation(t_args("validation.team_alias_in_use", &[("alias", &alias)])));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Validate the format of a team alias
+fn validate_alias(alias: &str) -> Result<(), AppError> {
+    if !is_valid_team_alias(alias) {
+        return Err(AppError::Validation(t("validation.team_alias_format")));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_alias() {
+        assert!(validate_alias("netops").is_ok());
+        assert!(matches!(validate_alias("Net Ops"), Err(AppError::Validation(_))));
+    }
+}
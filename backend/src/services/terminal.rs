@@ -0,0 +1,465 @@
+//! Terminal Service
+//!
+//! Runs interactive operational-mode sessions on nodes for the WebSocket
+//! terminal. Each command line is checked against the user's allowlist, run
+//! through the node's transport, and its output handed back in chunks of
+//! whole lines as the transport reads it. Nodes reached over SSH run any
+//! operational command and stream its output; the VyOS API only runs `show`
+//! commands and answers with the whole output at once. Administrators may
+//! run any command; other users only those their allowlist permits, and
+//! none without one. Opening and closing a session and every command line,
+//! allowed or not, are written to the audit log.
+
+use std::time::Instant;
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::audit::{AuditEvent, AuditResult};
+use crate::models::auth::Claims;
+use crate::models::terminal::{
+    SetTerminalAllowlistRequest, TerminalAllowlist, TerminalCommandResult, TERMINAL_OUTPUT_CHUNK_LINES,
+};
+use crate::models::timestamp::{db_now, parse_db_timestamp};
+use crate::services::{AuditService, NodeService, TeamService};
+
+/// Open terminal session of a user on a node
+#[derive(Debug, Clone)]
+pub struct TerminalSession {
+    pub id: Uuid,
+    pub node_id: Uuid,
+    claims: Claims,
+    /// Commands the user may run; `None` for administrators
+    allowlist: Option<TerminalAllowlist>,
+    /// Command lines received so far
+    commands: u32,
+}
+
+impl TerminalSession {
+    /// Command prefixes the user may run, or `None` if unrestricted
+    pub fn allowed_commands(&self) -> Option<&[String]> {
+        self.allowlist.as_ref().map(|allowlist| allowlist.commands.as_slice())
+    }
+}
+
+/// Terminal service
+#[derive(Clone)]
+pub struct TerminalService {
+    db: Database,
+    nodes: NodeService,
+    teams: TeamService,
+    audit: AuditService,
+}
+
+impl TerminalService {
+    /// Create a new terminal service
+    pub fn new(db: Database, nodes: NodeService, teams: TeamService, audit: AuditService) -> Self {
+        Self { db, nodes, teams, audit }
+    }
+
+    /// Get the terminal allowlist of a user; empty if none was set
+    pub async fn get_allowlist(&self, user_id: Uuid) -> Result<TerminalAllowlist, AppError> {
+        if self.db.find_user_by_id(&user_id.to_string()).await?.is_none() {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        let row: Option<(String, String, String)> =
+            sqlx::query_as("SELECT commands, updated_by, updated_at FROM terminal_allowlists WHERE user_id = ?")
+                .bind(user_id.to_string())
+                .fetch_optional(self.db.pool())
+                .await?;
+
+        Ok(match row {
+            Some((commands, updated_by, updated_at)) => TerminalAllowlist {
+                user_id,
+                commands: serde_json::from_str(&commands)?,
+                updated_by: Some(updated_by),
+                updated_at: Some(parse_db_timestamp(&updated_at)),
+            },
+            None => TerminalAllowlist {
+                user_id,
+                commands: Vec::new(),
+                updated_by: None,
+                updated_at: None,
+            },
+        })
+    }
+
+    /// Replace the terminal allowlist of a user
+    ///
+    /// Entries are stored with their whitespace collapsed, without blanks
+    /// and duplicates.
+    pub async fn set_allowlist(
+        &self,
+        user_id: Uuid,
+        request: SetTerminalAllowlistRequest,
+        updated_by: &str,
+    ) -> Result<TerminalAllowlist, AppError> {
+        if self.db.find_user_by_id(&user_id.to_string()).await?.is_none() {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        let mut commands: Vec<String> = Vec::new();
+        for entry in &request.commands {
+            let entry = entry.split_whitespace().collect::<Vec<_>>().join(" ");
+            if entry.len() > 200 {
+                return Err(AppError::Validation(format!("Allowlist entry is too long: {}", entry)));
+            }
+            if !entry.is_empty() && !commands.contains(&entry) {
+                commands.push(entry);
+            }
+        }
+
+        let mut tx = self.db.pool().begin().await?;
+        sqlx::query("DELETE FROM terminal_allowlists WHERE user_id = ?")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO terminal_allowlists (user_id, commands, updated_by, updated_at) VALUES (?, ?, ?, ?)")
+            .bind(user_id.to_string())
+            .bind(serde_json::to_string(&commands)?)
+            .bind(updated_by)
+            .bind(db_now())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        info!("{} set the terminal allowlist of user {}: {:?}", updated_by, user_id, commands);
+        self.get_allowlist(user_id).await
+    }
+
+    /// Open a terminal session on a node
+    ///
+    /// The user must be able to access the node and, unless an
+    /// administrator, have a non-empty allowlist.
+    pub async fn open(&self, claims: &Claims, node_id: Uuid) -> Result<TerminalSession, AppError> {
+        let opened = self.authorize(claims, node_id).await;
+        let details = match &opened {
+            Ok(allowlist) => serde_json::json!({
+                "allowed_commands": allowlist.as_ref().map(|allowlist| &allowlist.commands),
+            }),
+            Err(e) => serde_json::json!({ "error": e.to_string() }),
+        };
+        let result = if opened.is_ok() { AuditResult::Success } else { AuditResult::Failure };
+        let session_id = Uuid::new_v4();
+        self.audit
+            .record(
+                AuditEvent::new(Some(claims), "terminal.open", result)
+                    .with_node(node_id)
+                    .with_target(session_id.to_string())
+                    .with_details(details),
+            )
+            .await;
+
+        let allowlist = opened?;
+        info!("{} opened terminal session {} on node {}", claims.username, session_id, node_id);
+        Ok(TerminalSession {
+            id: session_id,
+            node_id,
+            claims: claims.clone(),
+            allowlist,
+            commands: 0,
+        })
+    }
+
+    /// Run a command line in a session
+    ///
+    /// Output is passed to `output` in chunks of lines as it is read. Lines
+    /// the allowlist does not permit are refused without reaching the node.
+    pub async fn run(
+        &self,
+        session: &mut TerminalSession,
+        line: &str,
+        mut output: impl FnMut(String) + Send,
+    ) -> TerminalCommandResult {
+        let command = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        let started = Instant::now();
+
+        let mut output_bytes = 0;
+        let outcome = if command.is_empty() {
+            Ok(())
+        } else if session.allowlist.as_ref().is_some_and(|allowlist| !allowlist.allows(&command)) {
+            Err(AppError::Forbidden(format!("`{}` is not in your terminal allowlist", command)))
+        } else {
+            self.execute(session.node_id, &command, |chunk| {
+                output_bytes += chunk.len();
+                output(chunk);
+            })
+            .await
+        };
+
+        let result = TerminalCommandResult {
+            command,
+            success: outcome.is_ok(),
+            error: outcome.err().map(|e| e.to_string()),
+        };
+        if !result.command.is_empty() {
+            session.commands += 1;
+            self.audit
+                .record(
+                    AuditEvent::new(
+                        Some(&session.claims),
+                        "terminal.command",
+                        if result.success { AuditResult::Success } else { AuditResult::Failure },
+                    )
+                    .with_node(session.node_id)
+                    .with_target(session.id.to_string())
+                    .with_details(serde_json::json!({
+                        "command": result.command,
+                        "output_bytes": output_bytes,
+                        "duration_ms": started.elapsed().as_millis() as u64,
+                        "error": result.error,
+                    })),
+                )
+                .await;
+        }
+        result
+    }
+
+    /// Close a session
+    pub async fn close(&self, session: &TerminalSession) {
+        info!("Terminal session {} on node {} closed", session.id, session.node_id);
+        self.audit
+            .record(
+                AuditEvent::new(Some(&session.claims), "terminal.close", AuditResult::Success)
+                    .with_node(session.node_id)
+                    .with_target(session.id.to_string())
+                    .with_details(serde_json::json!({ "commands": session.commands })),
+            )
+            .await;
+    }
+
+    /// Allowlist a user may open a session on a node with; `None` for
+    /// administrators
+    async fn authorize(&self, claims: &Claims, node_id: Uuid) -> Result<Option<TerminalAllowlist>, AppError> {
+        let node = self
+            .nodes
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+        if !self.teams.access_scope(claims).await?.can_access(node.team_id) {
+            return Err(AppError::Forbidden("Node is owned by another team".to_string()));
+        }
+
+        let user = self
+            .db
+            .find_user_by_id(&claims.sub)
+            .await?
+            .ok_or_else(|| AppError::Auth("User not found".to_string()))?;
+        if user.is_superuser {
+            return Ok(None);
+        }
+
+        let allowlist = self.get_allowlist(claims.user_id()?).await?;
+        if allowlist.commands.is_empty() {
+            return Err(AppError::Forbidden("No terminal commands are allowed for you".to_string()));
+        }
+        Ok(Some(allowlist))
+    }
+
+    /// Run an operational command through the node's transport
+    ///
+    /// Output is passed on in chunks as soon as its lines are complete.
+    /// Results are always read from the node, never from the show cache.
+    async fn execute(
+        &self,
+        node_id: Uuid,
+        command: &str,
+        mut output: impl FnMut(String) + Send,
+    ) -> Result<(), AppError> {
+        let mut lines = OutputLines::default();
+        let result = self
+            .nodes
+            .run_op_command(node_id, command, &mut |piece| {
+                for chunk in lines.push(&piece) {
+                    output(chunk);
+                }
+            })
+            .await;
+        if let Some(rest) = lines.finish() {
+            output(rest);
+        }
+        result
+    }
+}
+
+/// Output read in pieces, held back until its lines are complete
+#[derive(Default)]
+struct OutputLines {
+    /// Start of a line not yet ended
+    partial: String,
+}
+
+impl OutputLines {
+    /// Chunks of the lines a piece of output completes
+    fn push(&mut self, piece: &str) -> Vec<String> {
+        self.partial.push_str(piece);
+        let Some(end) = self.partial.rfind('\n') else {
+            return Vec::new();
+        };
+        let lines: String = self.partial.drain(..=end).collect();
+        output_chunks(&lines)
+    }
+
+    /// The last line, if the output did not end with a line break
+    fn finish(self) -> Option<String> {
+        (!self.partial.is_empty()).then_some(self.partial)
+    }
+}
+
+/// Command output split into chunks of whole lines
+fn output_chunks(output: &str) -> Vec<String> {
+    let lines: Vec<&str> = output.split_inclusive('\n').collect();
+    lines.chunks(TERMINAL_OUTPUT_CHUNK_LINES).map(|chunk| chunk.concat()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::app::AppState;
+    use crate::config::AppConfig;
+    use crate::db::create_database;
+    use crate::models::audit::AuditListQuery;
+    use crate::seed::{self, ADMIN_USER_ID, CORE_NODE_ID, OPERATOR_USER_ID};
+
+    #[test]
+    fn test_output_chunks() {
+        assert!(output_chunks("").is_empty());
+        assert_eq!(output_chunks("a\nb"), vec!["a\nb".to_string()]);
+
+        let output: String = (0..TERMINAL_OUTPUT_CHUNK_LINES + 1).map(|i| format!("{}\n", i)).collect();
+        let chunks = output_chunks(&output);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1], format!("{}\n", TERMINAL_OUTPUT_CHUNK_LINES));
+        assert_eq!(chunks.concat(), output);
+    }
+
+    #[test]
+    fn test_output_lines_wait_for_line_breaks() {
+        let mut lines = OutputLines::default();
+        assert!(lines.push("eth0 ").is_empty());
+        assert_eq!(lines.push("up\neth1"), vec!["eth0 up\n".to_string()]);
+        assert_eq!(lines.push(" down\nlo up"), vec!["eth1 down\n".to_string()]);
+        assert_eq!(lines.finish(), Some("lo up".to_string()));
+        assert_eq!(OutputLines::default().finish(), None);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_follow_the_allowlist_and_are_audited() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let state = AppState::new(AppConfig::from_env().unwrap(), create_database(pool).await.unwrap());
+        seed::seed(&state).await.unwrap();
+        let claims = |id: Uuid, username: &str| Claims {
+            sub: id.to_string(),
+            username: username.to_string(),
+            exp: i64::MAX,
+            iat: 0,
+            sid: None,
+        };
+        let admin = claims(ADMIN_USER_ID, "alice");
+        let operator = claims(OPERATOR_USER_ID, "bob");
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/show"))
+            .and(body_partial_json(serde_json::json!({ "command": "show interfaces" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": true,
+                "data": "eth0 up\neth1 down\n",
+                "error": null,
+            })))
+            .mount(&server)
+            .await;
+        sqlx::query("UPDATE nodes SET host = ?, port = ?, use_https = ? WHERE id = ?")
+            .bind(server.address().ip().to_string())
+            .bind(server.address().port() as i64)
+            .bind(false)
+            .bind(CORE_NODE_ID.to_string())
+            .execute(state.db.pool())
+            .await
+            .unwrap();
+
+        let terminal = &state.terminal_service;
+        assert!(matches!(terminal.open(&operator, CORE_NODE_ID).await, Err(AppError::Forbidden(_))));
+
+        terminal
+            .set_allowlist(
+                OPERATOR_USER_ID,
+                SetTerminalAllowlistRequest {
+                    commands: vec!["show  interfaces".to_string(), " ".to_string(), "show interfaces".to_string()],
+                },
+                "alice",
+            )
+            .await
+            .unwrap();
+        let mut session = terminal.open(&operator, CORE_NODE_ID).await.unwrap();
+        assert_eq!(session.allowed_commands(), Some(&["show interfaces".to_string()][..]));
+
+        let mut output = Vec::new();
+        let result = terminal.run(&mut session, "show interfaces", |chunk| output.push(chunk)).await;
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(output.concat(), "eth0 up\neth1 down\n");
+
+        let result = terminal.run(&mut session, "show version", |_| panic!("no output expected")).await;
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("allowlist"));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+        terminal.close(&session).await;
+
+        // Administrators are not restricted, but the API only runs show commands
+        let mut session = terminal.open(&admin, CORE_NODE_ID).await.unwrap();
+        assert!(session.allowed_commands().is_none());
+        let result = terminal.run(&mut session, "reset ip bgp all", |_| {}).await;
+        assert!(result.error.unwrap().contains("only runs show commands"));
+
+        // Over SSH they reach the node; nothing listens on the port, so the connection is refused
+        let ssh_port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        sqlx::query("UPDATE nodes SET transport = 'ssh', ssh_port = ?, ssh_username = ?, ssh_password = ? WHERE id = ?")
+            .bind(ssh_port as i64)
+            .bind("vyos")
+            .bind("vyos")
+            .bind(CORE_NODE_ID.to_string())
+            .execute(state.db.pool())
+            .await
+            .unwrap();
+        let result = terminal.run(&mut session, "reset ip bgp all", |_| {}).await;
+        assert!(result.error.unwrap().contains("SSH connection"));
+
+        let audit = state
+            .audit_service
+            .list_entries(AuditListQuery {
+                action: Some("terminal.".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let mut actions: Vec<String> = audit
+            .entries
+            .into_iter()
+            .map(|entry| format!("{} {}", entry.action, entry.result.as_str()))
+            .collect();
+        actions.sort();
+        assert_eq!(
+            actions,
+            [
+                "terminal.close success",
+                "terminal.command failure",
+                "terminal.command failure",
+                "terminal.command failure",
+                "terminal.command success",
+                "terminal.open failure",
+                "terminal.open success",
+                "terminal.open success",
+            ]
+        );
+    }
+}
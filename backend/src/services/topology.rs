@@ -0,0 +1,867 @@
+//! Network Topology Service
+//!
+//! Builds the network map of the nodes a user can see. Links come from
+//! interfaces of two nodes sharing a subnet, from the LLDP neighbors and ARP
+//! tables the nodes report, and from links entered by hand for what
+//! discovery cannot see, such as carrier circuits. LLDP neighbors and ARP
+//! hosts that are not nodes appear as discovered devices, with IDs such as
+//! `lldp:core-sw1` and `arp:52:54:00:12:34:56`. Nodes that cannot be reached
+//! contribute their last-known data.
+//!
+//! Links entered by hand and the positions of nodes in the map are kept in
+//! the `topology_links` and `topology_positions` tables; changing them takes
+//! the `nodes.write` permission.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use ipnet::IpNet;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::monitoring::{
+    CreateTopologyLinkRequest, HealthStatus, LinkStatus, NetworkTopology, TopologyLink, TopologyLinkType,
+    TopologyNode, TopologyNodeType, TopologyPosition, UpdateTopologyLinkRequest, UpdateTopologyPositionsRequest,
+};
+use crate::models::node::{Node, NodeData, NodeStatus};
+use crate::models::permission::NODES_WRITE;
+use crate::models::timestamp::{db_now, parse_db_timestamp};
+use crate::services::ipam::is_local_only;
+use crate::services::node_service::NodeService;
+use crate::services::permission::PermissionService;
+use crate::vyos_client::{VyOSInterface, VyOSLldpNeighbor, VyOSNeighbor};
+
+/// ARP entries of one node shown as devices; busy LANs have many more hosts
+const MAX_ARP_DEVICES_PER_NODE: usize = 256;
+
+const LINK_COLUMNS: &str = "id, source, target, link_type, source_interface, target_interface, bandwidth_mbps, \
+                            description, created_by, created_at, updated_at";
+
+/// Network topology service
+#[derive(Clone)]
+pub struct TopologyService {
+    db: Database,
+    nodes: NodeService,
+    permissions: PermissionService,
+}
+
+/// Topology link entered by hand
+#[derive(Debug, Clone)]
+pub struct StoredTopologyLink {
+    pub id: Uuid,
+    pub source: String,
+    pub target: String,
+    pub link_type: TopologyLinkType,
+    pub source_interface: Option<String>,
+    pub target_interface: Option<String>,
+    pub bandwidth_mbps: Option<u64>,
+    pub description: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StoredTopologyLink {
+    /// The link as shown in the topology
+    pub fn to_link(&self, status: LinkStatus) -> TopologyLink {
+        TopologyLink {
+            id: self.id.to_string(),
+            source: self.source.clone(),
+            target: self.target.clone(),
+            link_type: self.link_type,
+            source_interface: self.source_interface.clone(),
+            target_interface: self.target_interface.clone(),
+            status,
+            bandwidth_mbps: self.bandwidth_mbps,
+            utilization_percent: None,
+            metadata: Some(json!({
+                "source": "manual",
+                "description": self.description,
+                "created_by": self.created_by,
+                "updated_at": self.updated_at.to_rfc3339(),
+            })),
+        }
+    }
+}
+
+/// What was read from one node
+struct Observation {
+    node: Node,
+    interfaces: Option<NodeData<Vec<VyOSInterface>>>,
+    error: Option<String>,
+    arp: Vec<VyOSNeighbor>,
+    lldp: Vec<VyOSLldpNeighbor>,
+}
+
+impl TopologyService {
+    /// Create a new topology service
+    pub fn new(db: Database, nodes: NodeService, permissions: PermissionService) -> Self {
+        Self { db, nodes, permissions }
+    }
+
+    /// Ensure the user may change links and positions of the topology
+    pub async fn ensure_can_write(&self, claims: &Claims) -> Result<(), AppError> {
+        if !self.permissions.has_permission(claims, NODES_WRITE).await? {
+            return Err(AppError::Forbidden(format!(
+                "Changing the network topology requires the {} permission",
+                NODES_WRITE
+            )));
+        }
+        Ok(())
+    }
+
+    /// Build the topology of some nodes
+    ///
+    /// The caller passes the nodes the user may access; links entered by
+    /// hand that end at any other node are left out. With `discover`, the
+    /// LLDP neighbors and ARP table of every node are read as well as its
+    /// interfaces.
+    pub async fn topology(&self, nodes: Vec<Node>, discover: bool) -> Result<NetworkTopology, AppError> {
+        let observations = join_all(nodes.into_iter().map(|node| self.observe(node, discover))).await;
+
+        let visible: HashSet<Uuid> = observations.iter().map(|observation| observation.node.id).collect();
+        let stored: Vec<StoredTopologyLink> = self
+            .list_links()
+            .await?
+            .into_iter()
+            .filter(|link| {
+                [&link.source, &link.target]
+                    .iter()
+                    .all(|endpoint| Uuid::parse_str(endpoint).map_or(true, |id| visible.contains(&id)))
+            })
+            .collect();
+
+        let (mut nodes, links) = build(&observations, &stored);
+        let positions = self.positions().await?;
+        for node in &mut nodes {
+            node.position = positions.get(&node.id).cloned();
+        }
+
+        Ok(NetworkTopology {
+            version: version(&nodes, &links)?,
+            nodes,
+            links,
+            updated_at: Utc::now(),
+        })
+    }
+
+    /// Read the interfaces, and with `discover` the neighbors, of a node
+    ///
+    /// Failures leave the data out rather than failing the topology.
+    async fn observe(&self, node: Node, discover: bool) -> Observation {
+        let interfaces = self.nodes.get_node_interfaces(node.id);
+        let (interfaces, arp, lldp) = if discover {
+            let (interfaces, arp, lldp) = tokio::join!(
+                interfaces,
+                self.nodes.get_arp_table(node.id),
+                self.nodes.get_lldp_neighbors(node.id)
+            );
+            if let Err(e) = &arp {
+                debug!("No ARP table for topology of node {}: {}", node.name, e);
+            }
+            if let Err(e) = &lldp {
+                debug!("No LLDP neighbors for topology of node {}: {}", node.name, e);
+            }
+            (
+                interfaces,
+                arp.map(|arp| arp.data).unwrap_or_default(),
+                lldp.map(|lldp| lldp.data).unwrap_or_default(),
+            )
+        } else {
+            (interfaces.await, vec![], vec![])
+        };
+
+        let (interfaces, error) = match interfaces {
+            Ok(interfaces) => (Some(interfaces), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        Observation {
+            node,
+            interfaces,
+            error,
+            arp,
+            lldp,
+        }
+    }
+
+    // ========================================================================
+    // Links
+    // ========================================================================
+
+    /// List the links entered by hand
+    pub async fn list_links(&self) -> Result<Vec<StoredTopologyLink>, AppError> {
+        let query = format!("SELECT {} FROM topology_links ORDER BY created_at", LINK_COLUMNS);
+        let rows: Vec<LinkRow> = sqlx::query_as(&query).fetch_all(self.db.pool()).await?;
+
+        Ok(rows.into_iter().map(link_from_row).collect())
+    }
+
+    /// Get a link entered by hand
+    pub async fn get_link(&self, id: Uuid) -> Result<StoredTopologyLink, AppError> {
+        let query = format!("SELECT {} FROM topology_links WHERE id = ?", LINK_COLUMNS);
+
+        sqlx::query_as::<_, LinkRow>(&query)
+            .bind(id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .map(link_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Topology link {} not found", id)))
+    }
+
+    /// Add a link by hand
+    ///
+    /// The caller checks that the user may access the nodes the link ends at.
+    pub async fn create_link(
+        &self,
+        claims: &Claims,
+        request: CreateTopologyLinkRequest,
+    ) -> Result<StoredTopologyLink, AppError> {
+        let source = request.source.trim();
+        let target = request.target.trim();
+        validate_endpoint(source)?;
+        validate_endpoint(target)?;
+        if source == target {
+            return Err(AppError::Validation("A link cannot end where it starts".to_string()));
+        }
+
+        let id = Uuid::new_v4();
+        let now = db_now();
+        sqlx::query(
+            "INSERT INTO topology_links (id, source, target, link_type, source_interface, target_interface, \
+             bandwidth_mbps, description, created_by, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id.to_string())
+        .bind(source)
+        .bind(target)
+        .bind(request.link_type.as_str())
+        .bind(non_empty(request.source_interface))
+        .bind(non_empty(request.target_interface))
+        .bind(request.bandwidth_mbps.map(|mbps| mbps as i64))
+        .bind(non_empty(request.description))
+        .bind(&claims.username)
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await?;
+
+        info!("Topology link {} -> {} added by {}", source, target, claims.username);
+        self.get_link(id).await
+    }
+
+    /// Change a link entered by hand
+    ///
+    /// Unset fields are kept; an empty interface or description clears it.
+    pub async fn update_link(
+        &self,
+        id: Uuid,
+        request: UpdateTopologyLinkRequest,
+    ) -> Result<StoredTopologyLink, AppError> {
+        let mut link = self.get_link(id).await?;
+        if let Some(link_type) = request.link_type {
+            link.link_type = link_type;
+        }
+        if let Some(interface) = request.source_interface {
+            link.source_interface = non_empty(Some(interface));
+        }
+        if let Some(interface) = request.target_interface {
+            link.target_interface = non_empty(Some(interface));
+        }
+        if let Some(mbps) = request.bandwidth_mbps {
+            link.bandwidth_mbps = Some(mbps);
+        }
+        if let Some(description) = request.description {
+            link.description = non_empty(Some(description));
+        }
+
+        sqlx::query(
+            "UPDATE topology_links SET link_type = ?, source_interface = ?, target_interface = ?, bandwidth_mbps = ?, \
+             description = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(link.link_type.as_str())
+        .bind(&link.source_interface)
+        .bind(&link.target_interface)
+        .bind(link.bandwidth_mbps.map(|mbps| mbps as i64))
+        .bind(&link.description)
+        .bind(db_now())
+        .bind(id.to_string())
+        .execute(self.db.pool())
+        .await?;
+
+        self.get_link(id).await
+    }
+
+    /// Delete a link entered by hand
+    pub async fn delete_link(&self, id: Uuid) -> Result<StoredTopologyLink, AppError> {
+        let link = self.get_link(id).await?;
+        sqlx::query("DELETE FROM topology_links WHERE id = ?")
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await?;
+
+        info!("Topology link {} -> {} deleted", link.source, link.target);
+        Ok(link)
+    }
+
+    // ========================================================================
+    // Positions
+    // ========================================================================
+
+    /// Positions of the nodes placed in the map, by node or device ID
+    pub async fn positions(&self) -> Result<HashMap<String, TopologyPosition>, AppError> {
+        let rows: Vec<(String, f64, f64)> = sqlx::query_as("SELECT node_key, x, y FROM topology_positions")
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(rows.into_iter().map(|(key, x, y)| (key, TopologyPosition { x, y })).collect())
+    }
+
+    /// Place nodes in the map, keeping the positions of other nodes
+    ///
+    /// The caller checks that the user may access the nodes.
+    pub async fn set_positions(&self, request: UpdateTopologyPositionsRequest) -> Result<usize, AppError> {
+        for position in &request.positions {
+            validate_endpoint(position.node_id.trim())?;
+            if !position.x.is_finite() || !position.y.is_finite() {
+                return Err(AppError::Validation(format!(
+                    "Position of {} must be finite",
+                    position.node_id
+                )));
+            }
+        }
+
+        let now = db_now();
+        let mut tx = self.db.pool().begin().await?;
+        for position in &request.positions {
+            sqlx::query(
+                "INSERT INTO topology_positions (node_key, x, y, updated_at) VALUES (?, ?, ?, ?) \
+                 ON CONFLICT (node_key) DO UPDATE SET x = excluded.x, y = excluded.y, updated_at = excluded.updated_at",
+            )
+            .bind(position.node_id.trim())
+            .bind(position.x)
+            .bind(position.y)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(request.positions.len())
+    }
+}
+
+/// Check that a link endpoint is a node ID or a device ID such as
+/// `lldp:core-sw1`
+pub fn validate_endpoint(endpoint: &str) -> Result<(), AppError> {
+    if Uuid::parse_str(endpoint).is_ok() {
+        return Ok(());
+    }
+    match endpoint.split_once(':') {
+        Some((kind, name)) if !kind.trim().is_empty() && !name.trim().is_empty() => Ok(()),
+        _ => Err(AppError::Validation(format!(
+            "{} is neither a node ID nor a device ID such as lldp:core-sw1",
+            endpoint
+        ))),
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// Topology nodes and links from what was read from the nodes and the links
+/// entered by hand
+///
+/// Links entered by hand come first; a discovered link between the same
+/// interfaces as an earlier one is left out, so LLDP, which sees cables,
+/// wins over shared subnets and ARP entries.
+fn build(observations: &[Observation], stored: &[StoredTopologyLink]) -> (Vec<TopologyNode>, Vec<TopologyLink>) {
+    let mut map = TopologyMap::default();
+
+    let mut interfaces: HashMap<(String, &str), (&VyOSInterface, bool)> = HashMap::new();
+    let mut node_by_name: HashMap<String, String> = HashMap::new();
+    let mut node_addresses: HashSet<IpAddr> = HashSet::new();
+    let mut node_macs: HashSet<String> = HashSet::new();
+    for observation in observations {
+        let node = &observation.node;
+        let id = node.id.to_string();
+        map.add_node(TopologyNode {
+            id: id.clone(),
+            name: node.name.clone(),
+            node_type: TopologyNodeType::Router,
+            host: node.host.clone(),
+            status: health_status(node.status),
+            position: None,
+            metadata: Some(json!({
+                "source": "node",
+                "stale": observation.interfaces.as_ref().map(|interfaces| interfaces.stale),
+                "error": observation.error,
+            })),
+        });
+        node_by_name.insert(node.name.to_ascii_lowercase(), id.clone());
+        node_by_name.insert(node.host.to_ascii_lowercase(), id.clone());
+
+        for interface in observation.interfaces.iter().flat_map(|interfaces| &interfaces.data) {
+            let stale = observation.interfaces.as_ref().is_some_and(|interfaces| interfaces.stale);
+            interfaces.insert((id.clone(), interface.name.as_str()), (interface, stale));
+            node_addresses.extend(interface.addresses.iter().filter_map(|address| address.parse::<IpNet>().ok()).map(|net| net.addr()));
+            node_macs.extend(interface.mac_address.iter().map(|mac| mac.to_ascii_lowercase()));
+        }
+    }
+    let status = |endpoints: &[(&str, Option<&str>)]| -> LinkStatus {
+        let mut up = true;
+        for (node, interface) in endpoints {
+            match interface.and_then(|interface| interfaces.get(&(node.to_string(), interface))) {
+                Some((_, true)) | None => return LinkStatus::Unknown,
+                Some((interface, false)) => up &= interface.is_up,
+            }
+        }
+        if up {
+            LinkStatus::Up
+        } else {
+            LinkStatus::Down
+        }
+    };
+    let speed = |node: &str, interface: &str| -> Option<u64> {
+        interfaces.get(&(node.to_string(), interface))?.0.speed.as_deref()?.parse().ok()
+    };
+
+    // Links entered by hand
+    for link in stored {
+        let endpoints: Vec<(&str, Option<&str>)> = [
+            (link.source.as_str(), link.source_interface.as_deref()),
+            (link.target.as_str(), link.target_interface.as_deref()),
+        ]
+        .into_iter()
+        .filter(|(endpoint, _)| Uuid::parse_str(endpoint).is_ok())
+        .collect();
+        let link_status = if endpoints.is_empty() { LinkStatus::Unknown } else { status(&endpoints) };
+        map.add_link(link.to_link(link_status));
+    }
+
+    // LLDP neighbors: other nodes, or devices
+    for observation in observations {
+        let id = observation.node.id.to_string();
+        for neighbor in &observation.lldp {
+            let device = neighbor.device.to_ascii_lowercase();
+            let short = device.split('.').next().unwrap_or(&device).to_string();
+            let peer = node_by_name.get(&device).or_else(|| node_by_name.get(&short));
+            let (target, target_status) = match peer {
+                Some(peer) if *peer == id => continue,
+                Some(peer) => (peer.clone(), status(&[(&id, Some(&neighbor.interface)), (peer, neighbor.port.as_deref())])),
+                None => {
+                    let target = format!("lldp:{}", neighbor.device);
+                    map.add_device(
+                        &target,
+                        neighbor.device.clone(),
+                        lldp_node_type(neighbor.capabilities.as_deref()),
+                        json!({ "source": "lldp", "platform": neighbor.platform, "capabilities": neighbor.capabilities }),
+                    );
+                    (target, status(&[(&id, Some(&neighbor.interface))]))
+                }
+            };
+            map.add_link(TopologyLink {
+                id: format!("lldp:{}/{}-{}/{}", id, neighbor.interface, target, neighbor.port.as_deref().unwrap_or("")),
+                source: id.clone(),
+                target,
+                link_type: link_type(&neighbor.interface),
+                source_interface: Some(neighbor.interface.clone()),
+                target_interface: neighbor.port.clone(),
+                status: target_status,
+                bandwidth_mbps: speed(&id, &neighbor.interface),
+                utilization_percent: None,
+                metadata: Some(json!({ "source": "lldp", "platform": neighbor.platform })),
+            });
+        }
+    }
+
+    // Interfaces of two nodes in the same subnet
+    let mut by_subnet: BTreeMap<IpNet, Vec<(String, &str, IpAddr)>> = BTreeMap::new();
+    for observation in observations {
+        let id = observation.node.id.to_string();
+        for interface in observation.interfaces.iter().flat_map(|interfaces| &interfaces.data) {
+            for net in interface.addresses.iter().filter_map(|address| address.parse::<IpNet>().ok()) {
+                if !is_local_only(net.addr()) {
+                    by_subnet.entry(net.trunc()).or_default().push((id.clone(), interface.name.as_str(), net.addr()));
+                }
+            }
+        }
+    }
+    for (subnet, members) in &by_subnet {
+        for (i, (a, a_interface, a_address)) in members.iter().enumerate() {
+            for (b, b_interface, b_address) in &members[i + 1..] {
+                // The same address on two routers is a shared virtual address
+                if a == b || a_address == b_address {
+                    continue;
+                }
+                map.add_link(TopologyLink {
+                    id: format!("subnet:{}/{}-{}/{}", a, a_interface, b, b_interface),
+                    source: a.clone(),
+                    target: b.clone(),
+                    link_type: link_type(a_interface),
+                    source_interface: Some(a_interface.to_string()),
+                    target_interface: Some(b_interface.to_string()),
+                    status: status(&[(a, Some(a_interface)), (b, Some(b_interface))]),
+                    bandwidth_mbps: speed(a, a_interface).into_iter().chain(speed(b, b_interface)).min(),
+                    utilization_percent: None,
+                    metadata: Some(json!({ "source": "subnet", "subnet": subnet.to_string() })),
+                });
+            }
+        }
+    }
+
+    // ARP entries of hosts that are not nodes
+    for observation in observations {
+        let id = observation.node.id.to_string();
+        let hosts = observation
+            .arp
+            .iter()
+            .filter(|entry| !matches!(entry.state.as_str(), "FAILED" | "INCOMPLETE"))
+            .filter(|entry| entry.address.parse::<IpAddr>().is_ok_and(|address| !node_addresses.contains(&address)))
+            .filter_map(|entry| Some((entry, entry.mac_address.as_ref()?.to_ascii_lowercase())))
+            .filter(|(_, mac)| !node_macs.contains(mac))
+            .take(MAX_ARP_DEVICES_PER_NODE);
+        for (entry, mac) in hosts {
+            let target = format!("arp:{}", mac);
+            map.add_device(
+                &target,
+                entry.address.clone(),
+                TopologyNodeType::Unknown,
+                json!({ "source": "arp", "mac": mac }),
+            );
+            map.add_link(TopologyLink {
+                id: format!("arp:{}/{}-{}", id, entry.interface, mac),
+                source: id.clone(),
+                target,
+                link_type: link_type(&entry.interface),
+                source_interface: Some(entry.interface.clone()),
+                target_interface: None,
+                status: status(&[(&id, Some(&entry.interface))]),
+                bandwidth_mbps: None,
+                utilization_percent: None,
+                metadata: Some(json!({ "source": "arp", "address": entry.address })),
+            });
+        }
+    }
+
+    // Devices of links entered by hand that were not discovered now
+    for link in stored {
+        for endpoint in [&link.source, &link.target] {
+            if Uuid::parse_str(endpoint).is_err() {
+                map.add_device(endpoint, device_name(endpoint), TopologyNodeType::Unknown, json!({ "source": "manual" }));
+            }
+        }
+    }
+
+    (map.nodes, map.links)
+}
+
+/// Topology nodes and links, without duplicates
+#[derive(Default)]
+struct TopologyMap {
+    nodes: Vec<TopologyNode>,
+    node_ids: HashSet<String>,
+    links: Vec<TopologyLink>,
+    /// Both ends of every link, in a stable order
+    link_ends: HashSet<[(String, Option<String>); 2]>,
+}
+
+impl TopologyMap {
+    fn add_node(&mut self, node: TopologyNode) {
+        if self.node_ids.insert(node.id.clone()) {
+            self.nodes.push(node);
+        }
+    }
+
+    /// Add a device that is not a node, unless it is already known
+    fn add_device(&mut self, id: &str, name: String, node_type: TopologyNodeType, metadata: serde_json::Value) {
+        self.add_node(TopologyNode {
+            id: id.to_string(),
+            name: name.clone(),
+            node_type,
+            host: name,
+            status: HealthStatus::Unknown,
+            position: None,
+            metadata: Some(metadata),
+        });
+    }
+
+    fn add_link(&mut self, link: TopologyLink) {
+        let mut ends = [
+            (link.source.clone(), link.source_interface.clone()),
+            (link.target.clone(), link.target_interface.clone()),
+        ];
+        ends.sort();
+        if self.link_ends.insert(ends) {
+            self.links.push(link);
+        }
+    }
+}
+
+/// Health of a node as shown in the topology
+fn health_status(status: NodeStatus) -> HealthStatus {
+    match status {
+        NodeStatus::Online => HealthStatus::Healthy,
+        NodeStatus::Testing | NodeStatus::Rebooting => HealthStatus::Warning,
+        NodeStatus::Offline | NodeStatus::Error => HealthStatus::Critical,
+    }
+}
+
+/// Kind of link an interface is, by its VyOS interface name
+fn link_type(interface: &str) -> TopologyLinkType {
+    const VPN: &[&str] = &["wg", "vti", "tun", "vtun", "l2tpeth", "sstpc", "pppoe"];
+    const WIRELESS: &[&str] = &["wlan", "wwan"];
+    const LOGICAL: &[&str] = &["vxlan", "gnv", "dum", "lo"];
+
+    let prefix = interface.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    if VPN.contains(&prefix) {
+        TopologyLinkType::Vpn
+    } else if WIRELESS.contains(&prefix) {
+        TopologyLinkType::Wireless
+    } else if LOGICAL.contains(&prefix) {
+        TopologyLinkType::Logical
+    } else {
+        TopologyLinkType::Ethernet
+    }
+}
+
+/// Kind of device an LLDP neighbor is, by its capability codes
+fn lldp_node_type(capabilities: Option<&str>) -> TopologyNodeType {
+    let capabilities = capabilities.unwrap_or_default();
+    if capabilities.contains('R') {
+        TopologyNodeType::Router
+    } else if capabilities.contains('B') || capabilities.contains('W') {
+        TopologyNodeType::Switch
+    } else if capabilities.contains('S') {
+        TopologyNodeType::Workstation
+    } else {
+        TopologyNodeType::Unknown
+    }
+}
+
+/// Name of a device from its ID, e.g. `core-sw1` for `lldp:core-sw1`
+fn device_name(id: &str) -> String {
+    id.split_once(':').map_or(id, |(_, name)| name).to_string()
+}
+
+/// Version of a topology, changing whenever anything in it changes
+fn version(nodes: &[TopologyNode], links: &[TopologyLink]) -> Result<String, AppError> {
+    let digest = Sha256::digest(serde_json::to_vec(&(nodes, links))?);
+    Ok(format!("{:x}", digest)[..16].to_string())
+}
+
+type LinkRow = (
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+fn link_from_row(
+    (id, source, target, link_type, source_interface, target_interface, bandwidth_mbps, description, created_by, created_at, updated_at): LinkRow,
+) -> StoredTopologyLink {
+    StoredTopologyLink {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        source,
+        target,
+        link_type: TopologyLinkType::from_db(&link_type),
+        source_interface,
+        target_interface,
+        bandwidth_mbps: bandwidth_mbps.and_then(|mbps| u64::try_from(mbps).ok()),
+        description,
+        created_by,
+        created_at: parse_db_timestamp(&created_at),
+        updated_at: parse_db_timestamp(&updated_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::node::{NodeKind, NodeTransportKind};
+
+    fn node(name: &str, host: &str) -> Node {
+        Node {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: None,
+            host: host.to_string(),
+            port: 443,
+            api_key: String::new(),
+            status: NodeStatus::Online,
+            last_seen: None,
+            version: None,
+            uptime: None,
+            use_https: true,
+            verify_ssl: true,
+            tags: vec![],
+            timeout: 30,
+            team_id: None,
+            kind: NodeKind::Vyos,
+            transport: NodeTransportKind::Api,
+            ssh_port: None,
+            ssh_username: None,
+            ssh_password: None,
+            ssh_private_key: None,
+            custom_fields: Default::default(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn interface(name: &str, address: &str, is_up: bool) -> VyOSInterface {
+        VyOSInterface {
+            name: name.to_string(),
+            description: None,
+            address: Some(address.to_string()),
+            netmask: None,
+            addresses: vec![address.to_string()],
+            mac_address: None,
+            vendor: None,
+            is_up,
+            mtu: None,
+            speed: None,
+            duplex: None,
+        }
+    }
+
+    fn observation(node: Node, interfaces: Vec<VyOSInterface>) -> Observation {
+        Observation {
+            node,
+            interfaces: Some(NodeData {
+                data: interfaces,
+                stale: false,
+                fetched_at: Utc::now(),
+                age_secs: 0,
+                error: None,
+            }),
+            error: None,
+            arp: vec![],
+            lldp: vec![],
+        }
+    }
+
+    #[test]
+    fn test_build_links_shared_subnets() {
+        let a = observation(
+            node("edge-1", "192.0.2.1"),
+            vec![interface("eth1", "10.0.0.1/24", true), interface("lo", "127.0.0.1/8", true)],
+        );
+        let b = observation(
+            node("edge-2", "192.0.2.2"),
+            vec![interface("eth2", "10.0.0.2/24", false), interface("lo", "127.0.0.1/8", true)],
+        );
+
+        let (nodes, links) = build(&[a, b], &[]);
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].source_interface.as_deref(), Some("eth1"));
+        assert_eq!(links[0].target_interface.as_deref(), Some("eth2"));
+        assert_eq!(links[0].status, LinkStatus::Down);
+        assert_eq!(links[0].metadata.as_ref().unwrap()["subnet"], "10.0.0.0/24");
+    }
+
+    #[test]
+    fn test_build_lldp_and_arp_devices() {
+        let b = observation(node("edge-2", "192.0.2.2"), vec![interface("eth1", "10.0.0.2/24", true)]);
+        let mut a = observation(node("edge-1", "192.0.2.1"), vec![interface("eth1", "10.0.0.1/24", true)]);
+        a.lldp = vec![
+            VyOSLldpNeighbor {
+                device: "edge-2.example.net".to_string(),
+                interface: "eth1".to_string(),
+                capabilities: Some("R".to_string()),
+                platform: None,
+                port: Some("eth1".to_string()),
+            },
+            VyOSLldpNeighbor {
+                device: "core-sw1".to_string(),
+                interface: "eth3".to_string(),
+                capabilities: Some("B".to_string()),
+                platform: None,
+                port: Some("Ethernet7".to_string()),
+            },
+        ];
+        let neighbor = |address: &str, mac: &str, state: &str| VyOSNeighbor {
+            address: address.to_string(),
+            interface: "eth1".to_string(),
+            mac_address: Some(mac.to_string()),
+            router: false,
+            state: state.to_string(),
+        };
+        a.arp = vec![
+            neighbor("10.0.0.2", "52:54:00:00:00:02", "REACHABLE"),
+            neighbor("10.0.0.50", "52:54:00:00:00:50", "STALE"),
+            neighbor("10.0.0.60", "52:54:00:00:00:60", "FAILED"),
+        ];
+        let b_id = b.node.id.to_string();
+
+        let (nodes, links) = build(&[a, b], &[]);
+        let ids: Vec<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+        assert!(ids.contains(&"lldp:core-sw1"));
+        assert!(ids.contains(&"arp:52:54:00:00:00:50"));
+        assert_eq!(nodes.len(), 4);
+        let switch = nodes.iter().find(|node| node.id == "lldp:core-sw1").unwrap();
+        assert_eq!(switch.node_type, TopologyNodeType::Switch);
+
+        // The LLDP link between the nodes wins over the shared subnet
+        let between: Vec<_> = links.iter().filter(|link| link.target == b_id).collect();
+        assert_eq!(between.len(), 1);
+        assert_eq!(between[0].metadata.as_ref().unwrap()["source"], "lldp");
+        assert_eq!(between[0].status, LinkStatus::Up);
+        assert_eq!(links.len(), 3);
+    }
+
+    #[test]
+    fn test_build_stored_links() {
+        let a = observation(node("edge-1", "192.0.2.1"), vec![interface("eth0", "198.51.100.2/30", true)]);
+        let stored = StoredTopologyLink {
+            id: Uuid::new_v4(),
+            source: a.node.id.to_string(),
+            target: "isp:acme".to_string(),
+            link_type: TopologyLinkType::Fiber,
+            source_interface: Some("eth0".to_string()),
+            target_interface: None,
+            bandwidth_mbps: Some(1000),
+            description: None,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let (nodes, links) = build(&[a], &[stored]);
+        assert_eq!(nodes[1].id, "isp:acme");
+        assert_eq!(nodes[1].name, "acme");
+        assert_eq!(links[0].status, LinkStatus::Up);
+        assert_eq!(links[0].link_type, TopologyLinkType::Fiber);
+    }
+
+    #[test]
+    fn test_link_type() {
+        assert_eq!(link_type("eth0"), TopologyLinkType::Ethernet);
+        assert_eq!(link_type("eth0.10"), TopologyLinkType::Ethernet);
+        assert_eq!(link_type("wg100"), TopologyLinkType::Vpn);
+        assert_eq!(link_type("wlan0"), TopologyLinkType::Wireless);
+        assert_eq!(link_type("vxlan10"), TopologyLinkType::Logical);
+    }
+
+    #[test]
+    fn test_validate_endpoint() {
+        assert!(validate_endpoint(&Uuid::new_v4().to_string()).is_ok());
+        assert!(validate_endpoint("lldp:core-sw1").is_ok());
+        assert!(validate_endpoint("core-sw1").is_err());
+        assert!(validate_endpoint("lldp:").is_err());
+    }
+}
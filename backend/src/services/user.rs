@@ -1,24 +1,38 @@
-use tracing::info;
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use tracing::{info, warn};
+use uuid::Uuid;
+use validator::Validate;
 
 use crate::db::Database;
 use crate::error::AppError;
-use crate::models::user::{ChangePasswordRequest, UpdateProfileRequest, UpdateUserRequest, User, UserListQuery, UserListResponse, UserRecord, UserRole, UserStatus};
+use crate::models::auth::RegisterRequest;
+use crate::models::team::TeamRole;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::models::user::{BulkUserResult, ChangeEmailRequest, ChangePasswordRequest, PendingEmailChange, UpdateProfileRequest, UpdateUserRequest, User, UserListEntry, UserListQuery, UserListResponse, UserRecord, UserRole, UserStatus, MAX_BULK_USERS};
+use crate::services::Mailer;
+use crate::utils::csv::csv_records;
+
+/// How long an email change can be confirmed
+const EMAIL_CHANGE_TTL_HOURS: i64 = 24;
 
 /// User service for user management operations
 #[derive(Clone)]
 pub struct UserService {
     db: Database,
+    mailer: Mailer,
 }
 
 impl UserService {
     /// Create a new user service
-    pub fn new(db: Database) -> Self {
-        Self { db }
+    pub fn new(db: Database, mailer: Mailer) -> Self {
+        Self { db, mailer }
     }
 
     /// Get user by internal ID
-    pub async fn get_user(&self, user_id: i64) -> Result<Option<User>, AppError> {
-        let record = self.db.find_user_by_id(user_id).await?;
+    pub async fn get_user(&self, user_id: Uuid) -> Result<Option<User>, AppError> {
+        let record = self.db.find_user_by_id(&user_id.to_string()).await?;
         Ok(record.map(|r| r.to_user()))
     }
 
@@ -36,7 +50,20 @@ impl UserService {
         let per_page = query.per_page.unwrap_or(20);
         let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
 
-        let users: Vec<User> = records.into_iter().map(|r| r.to_user()).collect();
+        let ids: Vec<String> = records.iter().map(|record| record.id.clone()).collect();
+        let mut roles = self.db.find_user_role_names(&ids).await?;
+        let users: Vec<UserListEntry> = records
+            .into_iter()
+            .map(|record| {
+                let roles = roles.remove(&record.id).unwrap_or_default();
+                let mut user = record.to_user();
+                // The built-in role held, rather than the one implied by is_superuser
+                if let Some(role) = roles.iter().find_map(|name| UserRole::from_name(name)) {
+                    user.role = role;
+                }
+                UserListEntry { user, roles }
+            })
+            .collect();
 
         Ok(UserListResponse {
             users,
@@ -60,9 +87,9 @@ impl UserService {
             .map_err(|e| AppError::Internal(format!("Password hashing failed: {}", e)))?;
 
         // Create user in database
-        let user_id = self
-            .db
-            .create_user(username, email, &password_hash, full_name.as_deref())
+        let user_id = Uuid::new_v4();
+        self.db
+            .create_user(&user_id.to_string(), username, email, &password_hash, full_name.as_deref())
             .await?;
 
         info!("Created new user: {}", username);
@@ -76,29 +103,33 @@ impl UserService {
     /// Update a user (admin only)
     pub async fn update_user(
         &self,
-        user_id: i64,
+        user_id: Uuid,
         request: UpdateUserRequest,
     ) -> Result<User, AppError> {
         // Update email if provided
         if let Some(email) = &request.email {
-            self.db.update_user_profile(user_id, Some(email), None).await?;
+            self.db.update_user_profile(&user_id.to_string(), Some(email), None, None).await?;
         }
 
         // Update full name if provided
         if let Some(full_name) = &request.full_name {
-            self.db.update_user_profile(user_id, None, Some(full_name)).await?;
+            self.db.update_user_profile(&user_id.to_string(), None, Some(full_name), None).await?;
+        }
+
+        // Update timezone if provided
+        if let Some(timezone) = &request.timezone {
+            self.db.update_user_profile(&user_id.to_string(), None, None, Some(timezone)).await?;
         }
 
         // Update status if provided
         if let Some(status) = &request.status {
             let is_active = matches!(status, UserStatus::Active);
-            self.db.update_user_status(user_id, is_active).await?;
+            self.db.update_user_status(&user_id.to_string(), is_active).await?;
         }
 
         // Update role if provided
         if let Some(role) = &request.role {
-            let is_superuser = matches!(role, UserRole::Admin);
-            self.db.update_user_superuser(user_id, is_superuser).await?;
+            self.db.set_user_role(&user_id.to_string(), role).await?;
         }
 
         info!("Updated user: {}", user_id);
@@ -110,8 +141,8 @@ impl UserService {
     }
 
     /// Delete a user
-    pub async fn delete_user(&self, user_id: i64) -> Result<(), AppError> {
-        self.db.delete_user(user_id).await?;
+    pub async fn delete_user(&self, user_id: Uuid) -> Result<(), AppError> {
+        self.db.delete_user(&user_id.to_string()).await?;
         info!("Deleted user: {}", user_id);
         Ok(())
     }
@@ -119,13 +150,13 @@ impl UserService {
     /// Change user password
     pub async fn change_password(
         &self,
-        user_id: i64,
+        user_id: Uuid,
         request: ChangePasswordRequest,
     ) -> Result<(), AppError> {
         // Verify current password
         let user_record = self
             .db
-            .find_user_by_id(user_id)
+            .find_user_by_id(&user_id.to_string())
             .await?
             .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
@@ -141,7 +172,7 @@ impl UserService {
             .map_err(|e| AppError::Internal(format!("Password hashing failed: {}", e)))?;
 
         // Update password in database
-        self.db.update_user_password(user_id, &new_password_hash).await?;
+        self.db.update_user_password(&user_id.to_string(), &new_password_hash).await?;
 
         info!("Password changed for user: {}", user_id);
 
@@ -149,16 +180,33 @@ impl UserService {
     }
 
     /// Update user profile
+    ///
+    /// The email address cannot be changed here; changes go through
+    /// [`request_email_change`](Self::request_email_change) instead.
     pub async fn update_profile(
         &self,
-        user_id: i64,
+        user_id: Uuid,
         request: UpdateProfileRequest,
     ) -> Result<User, AppError> {
+        if let Some(email) = &request.email {
+            let user = self
+                .db
+                .find_user_by_id(&user_id.to_string())
+                .await?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+            if !email.trim().eq_ignore_ascii_case(&user.email) {
+                return Err(AppError::Validation(
+                    "Email changes must be confirmed; use POST /api/users/me/email".to_string(),
+                ));
+            }
+        }
+
         self.db
             .update_user_profile(
-                user_id,
-                request.email.as_deref(),
+                &user_id.to_string(),
+                None,
                 request.full_name.as_deref(),
+                request.timezone.as_deref(),
             )
             .await?;
 
@@ -171,8 +219,445 @@ impl UserService {
     }
 
     /// Update last login timestamp
-    pub async fn update_last_login(&self, user_id: i64) -> Result<(), AppError> {
-        self.db.update_last_login(user_id).await?;
+    pub async fn update_last_login(&self, user_id: Uuid) -> Result<(), AppError> {
+        self.db.update_last_login(&user_id.to_string()).await?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    // ========================================================================
+    // Email Changes
+    // ========================================================================
+
+    /// Start changing a user's email address
+    ///
+    /// Emails a confirmation token to the new address and notifies the
+    /// current one. The change applies once the token is confirmed with
+    /// [`confirm_email_change`](Self::confirm_email_change); a new request
+    /// replaces any pending one.
+    pub async fn request_email_change(
+        &self,
+        user_id: Uuid,
+        request: ChangeEmailRequest,
+    ) -> Result<PendingEmailChange, AppError> {
+        let user = self
+            .db
+            .find_user_by_id(&user_id.to_string())
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let is_valid = bcrypt::verify(&request.current_password, &user.password_hash)
+            .map_err(|e| AppError::Internal(format!("Password verification failed: {}", e)))?;
+        if !is_valid {
+            return Err(AppError::Auth("Current password is incorrect".to_string()));
+        }
+
+        let new_email = request.new_email.trim().to_string();
+        if new_email.eq_ignore_ascii_case(&user.email) {
+            return Err(AppError::Validation("This is already your email address".to_string()));
+        }
+        if self.db.find_user_by_email(&new_email).await?.is_some() {
+            return Err(AppError::Validation("Email already in use".to_string()));
+        }
+        self.mailer.check_recipients(&[new_email.clone(), user.email.clone()])?;
+
+        let token = Uuid::new_v4().simple().to_string();
+        let expires_at = Utc::now() + Duration::hours(EMAIL_CHANGE_TTL_HOURS);
+        sqlx::query("DELETE FROM email_changes WHERE user_id = ?")
+            .bind(&user.id)
+            .execute(self.db.pool())
+            .await?;
+        sqlx::query(
+            "INSERT INTO email_changes (user_id, new_email, token_hash, expires_at, created_at) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&user.id)
+        .bind(&new_email)
+        .bind(token_hash(&token))
+        .bind(format_timestamp(&expires_at))
+        .bind(db_now())
+        .execute(self.db.pool())
+        .await?;
+
+        let confirmation = format!(
+            "Hello {},\n\n\
+             To make {} the email address of your VyOS Web UI account, confirm the change \
+             with this token:\n\n    {}\n\n\
+             The token expires at {}. If you did not request this change, ignore this email.\n",
+            user.username,
+            new_email,
+            token,
+            format_timestamp(&expires_at)
+        );
+        if let Err(e) = self
+            .mailer
+            .send(std::slice::from_ref(&new_email), "Confirm your new email address", &confirmation, None)
+            .await
+        {
+            sqlx::query("DELETE FROM email_changes WHERE user_id = ?")
+                .bind(&user.id)
+                .execute(self.db.pool())
+                .await?;
+            return Err(e);
+        }
+
+        // The change is pending either way, so a lost notice only gets logged
+        let notice = format!(
+            "Hello {},\n\n\
+             A change of the email address of your VyOS Web UI account to {} was requested. \
+             It takes effect once confirmed from the new address.\n\n\
+             If you did not request this change, change your password and contact an administrator.\n",
+            user.username, new_email
+        );
+        if let Err(e) = self
+            .mailer
+            .send(std::slice::from_ref(&user.email), "Your email address is being changed", &notice, None)
+            .await
+        {
+            warn!("Failed to notify {} of their email change: {}", user.username, e);
+        }
+
+        info!("Email change requested for user: {}", user_id);
+
+        Ok(PendingEmailChange { new_email, expires_at })
+    }
+
+    /// Apply the email change a token was sent for
+    pub async fn confirm_email_change(&self, token: &str) -> Result<User, AppError> {
+        let hash = token_hash(token.trim());
+        let pending = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT user_id, new_email, expires_at FROM email_changes WHERE token_hash = ?",
+        )
+        .bind(&hash)
+        .fetch_optional(self.db.pool())
+        .await?;
+        let invalid = || AppError::Validation("Invalid or expired confirmation token".to_string());
+        let (user_id, new_email, expires_at) = pending.ok_or_else(invalid)?;
+
+        sqlx::query("DELETE FROM email_changes WHERE token_hash = ?")
+            .bind(&hash)
+            .execute(self.db.pool())
+            .await?;
+        if parse_db_timestamp(&expires_at) < Utc::now() {
+            return Err(invalid());
+        }
+        if self.db.find_user_by_email(&new_email).await?.is_some() {
+            return Err(AppError::Validation("Email already in use".to_string()));
+        }
+
+        self.db.update_user_profile(&user_id, Some(&new_email), None, None).await?;
+
+        info!("Email changed for user: {}", user_id);
+
+        self.db
+            .find_user_by_id(&user_id)
+            .await?
+            .map(|record| record.to_user())
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+    }
+
+    // ========================================================================
+    // Bulk Operations
+    // ========================================================================
+
+    /// Create users from CSV
+    ///
+    /// The header row names the columns: `username` and `email` are required,
+    /// `full_name`, `role`, and `password` optional. Users imported without a
+    /// password get a temporary one, returned once, that they must replace.
+    /// Rows that cannot be imported are reported without stopping the import.
+    pub async fn bulk_import(&self, csv: &str, dry_run: bool) -> Result<Vec<BulkUserResult>, AppError> {
+        let mut records = csv_records(csv)
+            .into_iter()
+            .filter(|record| record.iter().any(|field| !field.trim().is_empty()));
+        let header: Vec<String> = records
+            .next()
+            .ok_or_else(|| AppError::Validation("The CSV is empty".to_string()))?
+            .iter()
+            .map(|column| column.trim().to_lowercase())
+            .collect();
+        if let Some(unknown) = header.iter().find(|column| !IMPORT_COLUMNS.contains(&column.as_str())) {
+            return Err(AppError::Validation(format!(
+                "Unknown CSV column {:?}; use {}",
+                unknown,
+                IMPORT_COLUMNS.join(", ")
+            )));
+        }
+        let column = |name: &str| header.iter().position(|column| column == name);
+        if column("username").is_none() || column("email").is_none() {
+            return Err(AppError::Validation(
+                "The CSV header must include the username and email columns".to_string(),
+            ));
+        }
+
+        let rows: Vec<Vec<String>> = records.collect();
+        if rows.len() > MAX_BULK_USERS {
+            return Err(AppError::Validation(format!(
+                "At most {} users can be imported at once",
+                MAX_BULK_USERS
+            )));
+        }
+
+        let mut seen = HashSet::new();
+        let mut results = Vec::with_capacity(rows.len());
+        for row in rows {
+            let field = |name: &str| {
+                column(name)
+                    .and_then(|index| row.get(index))
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+            };
+            let username = field("username").unwrap_or_default();
+            let email = field("email").unwrap_or_default();
+
+            if !seen.insert(username.to_lowercase()) || !seen.insert(email.to_lowercase()) {
+                results.push(BulkUserResult::failed(None, Some(&username), "Duplicate username or email in the CSV"));
+                continue;
+            }
+
+            let role = match field("role").map(|role| serde_json::from_value::<UserRole>(role.to_lowercase().into())) {
+                None => UserRole::Viewer,
+                Some(Ok(role)) => role,
+                Some(Err(_)) => {
+                    results.push(BulkUserResult::failed(None, Some(&username), "Unknown role; use admin, operator, or viewer"));
+                    continue;
+                }
+            };
+
+            let password = field("password");
+            let temporary = password.is_none();
+            let request = RegisterRequest {
+                username,
+                email,
+                password: password.unwrap_or_else(|| Uuid::new_v4().simple().to_string()),
+                full_name: field("full_name"),
+            };
+            results.push(self.import_user(request, role, temporary, dry_run).await?);
+        }
+
+        info!(
+            "Bulk import of {} users{}",
+            results.len(),
+            if dry_run { " (dry run)" } else { "" }
+        );
+
+        Ok(results)
+    }
+
+    async fn import_user(
+        &self,
+        request: RegisterRequest,
+        role: UserRole,
+        temporary_password: bool,
+        dry_run: bool,
+    ) -> Result<BulkUserResult, AppError> {
+        let username = request.username.as_str();
+        if let Err(e) = request.validate() {
+            return Ok(BulkUserResult::failed(None, Some(username), format!("Invalid row: {}", e)));
+        }
+        if self.db.find_user_by_username(username).await?.is_some() {
+            return Ok(BulkUserResult::failed(None, Some(username), "Username already exists"));
+        }
+        if self.db.find_user_by_email(&request.email).await?.is_some() {
+            return Ok(BulkUserResult::failed(None, Some(username), "Email already in use"));
+        }
+
+        // New users are viewers
+        let assigns_role = !matches!(role, UserRole::Viewer);
+        let mut changes = vec![format!("email: {}", request.email)];
+        if assigns_role {
+            changes.push(format!("role: {}", role.as_str()));
+        }
+        if temporary_password {
+            changes.push("password reset required".to_string());
+        }
+        if dry_run {
+            return Ok(BulkUserResult::created(None, username, changes));
+        }
+
+        let user = self
+            .create_user(username, &request.email, &request.password, request.full_name.clone())
+            .await?;
+        let user_id = user.id.to_string();
+        if assigns_role {
+            self.db.set_user_role(&user_id, &role).await?;
+        }
+        if temporary_password {
+            self.db.set_password_reset_required(&user_id).await?;
+        }
+
+        let mut result = BulkUserResult::created(Some(user.id), username, changes);
+        if temporary_password {
+            result.temporary_password = Some(request.password);
+        }
+        Ok(result)
+    }
+
+    /// Give several users the same role
+    ///
+    /// `actor` cannot remove their own admin role.
+    pub async fn bulk_set_role(
+        &self,
+        actor: Uuid,
+        user_ids: &[Uuid],
+        role: UserRole,
+        dry_run: bool,
+    ) -> Result<Vec<BulkUserResult>, AppError> {
+        let targets = self.bulk_targets(user_ids).await?;
+        let ids: Vec<String> = targets.iter().map(|(user_id, _)| user_id.to_string()).collect();
+        let held = self.db.find_user_role_names(&ids).await?;
+        let mut results = Vec::new();
+        for (user_id, user) in targets {
+            let Some(user) = user else {
+                results.push(BulkUserResult::failed(Some(user_id), None, "User not found"));
+                continue;
+            };
+            let current = held
+                .get(&user.id)
+                .and_then(|names| names.iter().find_map(|name| UserRole::from_name(name)))
+                .map_or_else(|| user.role_name(), |held| held.as_str().to_string());
+            if current == role.as_str() {
+                results.push(BulkUserResult::unchanged(user_id, &user.username));
+                continue;
+            }
+            if user_id == actor {
+                results.push(BulkUserResult::failed(Some(user_id), Some(&user.username), "You cannot remove your own admin role"));
+                continue;
+            }
+            if !dry_run {
+                self.db.set_user_role(&user.id, &role).await?;
+            }
+            let change = format!("role: {} -> {}", current, role.as_str());
+            results.push(BulkUserResult::changed(user_id, &user.username, vec![change]));
+        }
+
+        Ok(results)
+    }
+
+    /// Add several users to a team, or change their role within it
+    pub async fn bulk_assign_team(
+        &self,
+        user_ids: &[Uuid],
+        team_id: Uuid,
+        role: TeamRole,
+        dry_run: bool,
+    ) -> Result<Vec<BulkUserResult>, AppError> {
+        let team = self
+            .db
+            .find_team_by_id(&team_id.to_string())
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Team {} not found", team_id)))?;
+
+        let mut results = Vec::new();
+        for (user_id, user) in self.bulk_targets(user_ids).await? {
+            let Some(user) = user else {
+                results.push(BulkUserResult::failed(Some(user_id), None, "User not found"));
+                continue;
+            };
+            let change = match self.db.find_team_role(&team.id, &user.id).await?.map(|current| TeamRole::parse(&current)) {
+                Some(current) if current == role => {
+                    results.push(BulkUserResult::unchanged(user_id, &user.username));
+                    continue;
+                }
+                Some(current) => format!("team {}: {} -> {}", team.alias, current.as_str(), role.as_str()),
+                None => format!("team {}: added as {}", team.alias, role.as_str()),
+            };
+            if !dry_run {
+                self.db.upsert_team_member(&team.id, &user.id, role.as_str()).await?;
+            }
+            results.push(BulkUserResult::changed(user_id, &user.username, vec![change]));
+        }
+
+        Ok(results)
+    }
+
+    /// Deactivate several users
+    ///
+    /// `actor` cannot deactivate their own account.
+    pub async fn bulk_deactivate(
+        &self,
+        actor: Uuid,
+        user_ids: &[Uuid],
+        dry_run: bool,
+    ) -> Result<Vec<BulkUserResult>, AppError> {
+        let mut results = Vec::new();
+        for (user_id, user) in self.bulk_targets(user_ids).await? {
+            let Some(user) = user else {
+                results.push(BulkUserResult::failed(Some(user_id), None, "User not found"));
+                continue;
+            };
+            if !user.is_active {
+                results.push(BulkUserResult::unchanged(user_id, &user.username));
+                continue;
+            }
+            if user_id == actor {
+                results.push(BulkUserResult::failed(Some(user_id), Some(&user.username), "You cannot deactivate your own account"));
+                continue;
+            }
+            if !dry_run {
+                self.db.update_user_status(&user.id, false).await?;
+            }
+            results.push(BulkUserResult::changed(user_id, &user.username, vec!["status: active -> disabled".to_string()]));
+        }
+
+        Ok(results)
+    }
+
+    /// Require several users to choose a new password
+    ///
+    /// The requirement is reported at login and lifted once the user changes
+    /// their password.
+    pub async fn bulk_require_password_reset(
+        &self,
+        user_ids: &[Uuid],
+        dry_run: bool,
+    ) -> Result<Vec<BulkUserResult>, AppError> {
+        let mut results = Vec::new();
+        for (user_id, user) in self.bulk_targets(user_ids).await? {
+            let Some(user) = user else {
+                results.push(BulkUserResult::failed(Some(user_id), None, "User not found"));
+                continue;
+            };
+            if user.password_reset_required {
+                results.push(BulkUserResult::unchanged(user_id, &user.username));
+                continue;
+            }
+            if !dry_run {
+                self.db.set_password_reset_required(&user.id).await?;
+            }
+            results.push(BulkUserResult::changed(user_id, &user.username, vec!["password reset required".to_string()]));
+        }
+
+        Ok(results)
+    }
+
+    /// Look up the users of a bulk operation, once each, in request order
+    async fn bulk_targets(&self, user_ids: &[Uuid]) -> Result<Vec<(Uuid, Option<UserRecord>)>, AppError> {
+        if user_ids.is_empty() {
+            return Err(AppError::Validation("No users selected".to_string()));
+        }
+        if user_ids.len() > MAX_BULK_USERS {
+            return Err(AppError::Validation(format!(
+                "At most {} users can be changed at once",
+                MAX_BULK_USERS
+            )));
+        }
+
+        let mut seen = HashSet::new();
+        let mut targets = Vec::new();
+        for user_id in user_ids {
+            if seen.insert(*user_id) {
+                targets.push((*user_id, self.db.find_user_by_id(&user_id.to_string()).await?));
+            }
+        }
+
+        Ok(targets)
+    }
+}
+
+/// Stored form of an email change token
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Columns accepted by a CSV user import
+const IMPORT_COLUMNS: [&str; 5] = ["username", "email", "full_name", "role", "password"];
\ No newline at end of file
@@ -0,0 +1,860 @@
+//! WireGuard VPN Meshes
+//!
+//! Builds WireGuard meshes among managed nodes, either with a tunnel between
+//! every two members or with every member connected to a hub. Creating a
+//! mesh allocates each member a tunnel address from the mesh's pool and
+//! generates its key pair; pushing renders each member's interface and
+//! peers, using the configuration syntax of VyOS 1.4 and later, and applies
+//! them to all members as one change.
+//!
+//! The tunnels are resources of their own: a scheduled job reads the peers
+//! of every member of the pushed meshes and records each tunnel as up while
+//! either end has completed a recent handshake with the other.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use base64ct::{Base64, Encoding};
+use chrono::Utc;
+use ipnet::IpNet;
+use openssl::pkey::PKey;
+use serde_json::Value;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::config_session::StagedOperation;
+use crate::models::team::AccessScope;
+use crate::models::timestamp::{db_now, format_timestamp, parse_db_timestamp};
+use crate::models::vpn_mesh::{
+    CreateVpnMeshRequest, MeshTopology, TunnelStatus, VpnMesh, VpnMeshListResponse, VpnMeshMember,
+    VpnMeshNodePlan, VpnMeshPlan, VpnMeshStatus, VpnMeshTunnel, DEFAULT_MESH_INTERFACE, DEFAULT_MESH_LISTEN_PORT,
+};
+use crate::services::config_lock::path_segments;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::node_service::NodeService;
+use crate::services::team::TeamService;
+use crate::vyos_client::{ConfigOperationKind, VyOSWireGuardPeer};
+
+/// How often the scheduled job checks the tunnels of every pushed mesh
+pub const VPN_MESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Age after which a tunnel's latest handshake no longer counts as up
+///
+/// WireGuard renews the session of an active tunnel every two minutes, and
+/// the persistent keepalive keeps every tunnel active.
+const HANDSHAKE_STALE_SECS: u64 = 180;
+
+/// Keepalive sent by every peer, in seconds
+const PERSISTENT_KEEPALIVE_SECS: u16 = 25;
+
+/// Shown in place of private keys
+const MASKED: &str = "********";
+
+/// Longest endpoint accepted
+const MAX_ENDPOINT_LEN: usize = 255;
+
+/// Longest mesh name accepted
+const MAX_NAME_LEN: usize = 100;
+
+/// Mesh as stored, with the members' private keys
+struct StoredMesh {
+    mesh: VpnMesh,
+    private_keys: HashMap<Uuid, String>,
+}
+
+/// VPN mesh service
+#[derive(Clone)]
+pub struct VpnMeshService {
+    db: Database,
+    nodes: NodeService,
+    teams: TeamService,
+    sessions: ConfigSessionService,
+}
+
+impl VpnMeshService {
+    /// Create a new VPN mesh service
+    pub fn new(db: Database, nodes: NodeService, teams: TeamService, sessions: ConfigSessionService) -> Self {
+        Self { db, nodes, teams, sessions }
+    }
+
+    /// Create a mesh, allocating tunnel addresses and generating key pairs
+    ///
+    /// Nothing is pushed to the nodes until [`Self::push`].
+    pub async fn create(&self, request: CreateVpnMeshRequest, claims: &Claims) -> Result<VpnMesh, AppError> {
+        let name = request.name.trim().to_string();
+        if name.is_empty() || name.len() > MAX_NAME_LEN {
+            return Err(AppError::Validation(format!(
+                "Mesh name must be 1 to {} characters",
+                MAX_NAME_LEN
+            )));
+        }
+        let interface = request.interface.unwrap_or_else(|| DEFAULT_MESH_INTERFACE.to_string());
+        validate_interface(&interface)?;
+        let listen_port = request.listen_port.unwrap_or(DEFAULT_MESH_LISTEN_PORT);
+        if listen_port == 0 {
+            return Err(AppError::Validation("Listen port must be between 1 and 65535".to_string()));
+        }
+        let pool = IpNet::from_str(request.address_pool.trim())
+            .map(|net| net.trunc())
+            .map_err(|_| AppError::Validation(format!("Address pool {} is not a prefix", request.address_pool)))?;
+
+        let node_ids: Vec<Uuid> = request.members.iter().map(|member| member.node_id).collect();
+        validate_members(request.topology, request.hub_node_id, &node_ids)?;
+        let addresses: Vec<IpAddr> = pool.hosts().take(node_ids.len()).collect();
+        if addresses.len() < node_ids.len() {
+            return Err(AppError::Validation(format!(
+                "Address pool {} is too small for {} members",
+                pool,
+                node_ids.len()
+            )));
+        }
+
+        let scope = self.teams.access_scope(claims).await?;
+        let mut members = Vec::new();
+        let mut private_keys = Vec::new();
+        for (member, address) in request.members.into_iter().zip(addresses) {
+            let node = self
+                .nodes
+                .get_node(member.node_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Node {} not found", member.node_id)))?;
+            if !scope.can_access(node.team_id) {
+                return Err(AppError::Forbidden("Node is owned by another team".to_string()));
+            }
+            let endpoint = member.endpoint.map(|e| e.trim().to_string()).unwrap_or(node.host);
+            validate_endpoint(&endpoint)?;
+            let in_use: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM vpn_mesh_members JOIN vpn_meshes ON vpn_meshes.id = vpn_mesh_members.mesh_id \
+                 WHERE vpn_mesh_members.node_id = ? AND vpn_meshes.interface = ?",
+            )
+            .bind(member.node_id.to_string())
+            .bind(&interface)
+            .fetch_one(self.db.pool())
+            .await?;
+            if in_use > 0 {
+                return Err(AppError::Validation(format!(
+                    "Node {} already has {} in another mesh",
+                    member.node_id, interface
+                )));
+            }
+
+            let (private_key, public_key) = generate_key_pair()?;
+            private_keys.push(private_key);
+            members.push(VpnMeshMember {
+                node_id: member.node_id,
+                endpoint,
+                tunnel_address: format!("{}/{}", address, pool.prefix_len()),
+                public_key,
+            });
+        }
+
+        let id = Uuid::new_v4();
+        let now = db_now();
+        let mut tx = self.db.pool().begin().await?;
+        sqlx::query(
+            "INSERT INTO vpn_meshes (id, name, topology, hub_node_id, address_pool, interface, listen_port, \
+             status, created_by, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, 'pending', ?, ?, ?)",
+        )
+        .bind(id.to_string())
+        .bind(&name)
+        .bind(request.topology.as_str())
+        .bind(request.hub_node_id.map(|hub| hub.to_string()))
+        .bind(pool.to_string())
+        .bind(&interface)
+        .bind(listen_port as i64)
+        .bind(&claims.username)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.message().contains("UNIQUE") || db.message().contains("Duplicate") => {
+                AppError::Validation(format!("A mesh named {} already exists", name))
+            }
+            e => e.into(),
+        })?;
+        for (member, private_key) in members.iter().zip(&private_keys) {
+            sqlx::query(
+                "INSERT INTO vpn_mesh_members (mesh_id, node_id, endpoint, tunnel_address, public_key, private_key) \
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(id.to_string())
+            .bind(member.node_id.to_string())
+            .bind(&member.endpoint)
+            .bind(&member.tunnel_address)
+            .bind(&member.public_key)
+            .bind(private_key)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for (node_a, node_b) in tunnel_pairs(request.topology, request.hub_node_id, &node_ids) {
+            sqlx::query("INSERT INTO vpn_mesh_tunnels (mesh_id, node_a, node_b, status) VALUES (?, ?, ?, 'unknown')")
+                .bind(id.to_string())
+                .bind(node_a.to_string())
+                .bind(node_b.to_string())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        info!("{} created {} VPN mesh {} with {} members", claims.username, request.topology.as_str(), name, members.len());
+        self.get(id, claims).await
+    }
+
+    /// Get a mesh whose members the caller may all access
+    pub async fn get(&self, mesh_id: Uuid, claims: &Claims) -> Result<VpnMesh, AppError> {
+        Ok(self.accessible(mesh_id, claims).await?.mesh)
+    }
+
+    /// List the meshes whose members the caller may all access, by name
+    pub async fn list(&self, claims: &Claims) -> Result<VpnMeshListResponse, AppError> {
+        let scope = self.teams.access_scope(claims).await?;
+        let ids: Vec<(String,)> = sqlx::query_as("SELECT id FROM vpn_meshes ORDER BY name")
+            .fetch_all(self.db.pool())
+            .await?;
+
+        let mut meshes = Vec::new();
+        for (id,) in ids {
+            let Some(stored) = self.load(Uuid::parse_str(&id).unwrap_or_default()).await? else {
+                continue;
+            };
+            if self.can_access(&stored.mesh, &scope).await? {
+                meshes.push(stored.mesh);
+            }
+        }
+        Ok(VpnMeshListResponse { meshes })
+    }
+
+    /// Configuration a push would apply to each member, with private keys
+    /// masked
+    pub async fn plan(&self, mesh_id: Uuid, claims: &Claims) -> Result<VpnMeshPlan, AppError> {
+        let stored = self.accessible(mesh_id, claims).await?;
+        let mut nodes = Vec::new();
+        for member in &stored.mesh.members {
+            let current = self.sessions.current_config(member.node_id).await?;
+            let operations = replace_operations(&stored.mesh, &current, render_member(&stored, member))
+                .into_iter()
+                .map(|operation| match path_segments(&operation.path).last() {
+                    Some(&"private-key") => StagedOperation {
+                        value: Some(MASKED.to_string()),
+                        ..operation
+                    },
+                    _ => operation,
+                })
+                .collect();
+            nodes.push(VpnMeshNodePlan {
+                node_id: member.node_id,
+                operations,
+            });
+        }
+
+        Ok(VpnMeshPlan { mesh_id, nodes })
+    }
+
+    /// Push the mesh's configuration to every member
+    ///
+    /// The WireGuard interface of each member is replaced as a whole. If a
+    /// member fails, the members already changed are rolled back and the
+    /// mesh stays pending with the error; only if the rollback fails too is
+    /// the mesh marked failed.
+    pub async fn push(&self, mesh_id: Uuid, claims: &Claims) -> Result<VpnMesh, AppError> {
+        let stored = self.accessible(mesh_id, claims).await?;
+
+        // Check every member before touching any
+        let mut changes = Vec::new();
+        for member in &stored.mesh.members {
+            let current = self.sessions.current_config(member.node_id).await?;
+            let operations = replace_operations(&stored.mesh, &current, render_member(&stored, member));
+            changes.push(self.sessions.prepare_change(member.node_id, &current, operations, claims).await?);
+        }
+
+        if let Err(failure) = self.sessions.apply_changes(changes).await {
+            let message = format!("Pushing to node {} failed: {}", failure.node_id, failure.error);
+            let (status, message) = match failure.rollback_error {
+                None => (VpnMeshStatus::Pending, message),
+                Some(rollback) => (
+                    VpnMeshStatus::Failed,
+                    format!("{}; rolling back the other members failed: {}", message, rollback),
+                ),
+            };
+            warn!("Failed to push VPN mesh {}: {}", stored.mesh.name, message);
+            self.set_status(mesh_id, status, Some(&message)).await?;
+            return Err(failure.error);
+        }
+
+        self.set_status(mesh_id, VpnMeshStatus::Applied, None).await?;
+        info!(
+            "{} pushed VPN mesh {} to {} members",
+            claims.username,
+            stored.mesh.name,
+            stored.mesh.members.len()
+        );
+        self.get(mesh_id, claims).await
+    }
+
+    /// Remove a mesh from its members and delete it
+    ///
+    /// The WireGuard interface is deleted from every member as one change;
+    /// if that fails, the mesh is kept.
+    pub async fn delete(&self, mesh_id: Uuid, claims: &Claims) -> Result<(), AppError> {
+        let stored = self.accessible(mesh_id, claims).await?;
+
+        if stored.mesh.status != VpnMeshStatus::Pending {
+            let mut changes = Vec::new();
+            for member in &stored.mesh.members {
+                let current = self.sessions.current_config(member.node_id).await?;
+                let operations = replace_operations(&stored.mesh, &current, Vec::new());
+                if !operations.is_empty() {
+                    changes.push(self.sessions.prepare_change(member.node_id, &current, operations, claims).await?);
+                }
+            }
+            if let Err(failure) = self.sessions.apply_changes(changes).await {
+                warn!("Failed to remove VPN mesh {} from node {}", stored.mesh.name, failure.node_id);
+                return Err(failure.error);
+            }
+        }
+
+        sqlx::query("DELETE FROM vpn_meshes WHERE id = ?")
+            .bind(mesh_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+        info!("{} deleted VPN mesh {}", claims.username, stored.mesh.name);
+        Ok(())
+    }
+
+    /// Check the tunnels of a mesh now
+    pub async fn check(&self, mesh_id: Uuid, claims: &Claims) -> Result<VpnMesh, AppError> {
+        let stored = self.accessible(mesh_id, claims).await?;
+        self.check_tunnels(&stored.mesh).await?;
+        self.get(mesh_id, claims).await
+    }
+
+    /// Check the tunnels of every pushed mesh, returning how many are down
+    pub async fn check_all(&self) -> Result<usize, AppError> {
+        let ids: Vec<(String,)> = sqlx::query_as("SELECT id FROM vpn_meshes WHERE status = 'applied'")
+            .fetch_all(self.db.pool())
+            .await?;
+
+        let mut down = 0;
+        for (id,) in ids {
+            if let Some(stored) = self.load(Uuid::parse_str(&id).unwrap_or_default()).await? {
+                down += self.check_tunnels(&stored.mesh).await?;
+            }
+        }
+        Ok(down)
+    }
+
+    /// Read the peers of every member and record the health of each tunnel
+    ///
+    /// A tunnel is up if either end had a handshake with the other within
+    /// [`HANDSHAKE_STALE_SECS`], down if an end could be read but neither
+    /// had, and unknown if neither end could be read.
+    async fn check_tunnels(&self, mesh: &VpnMesh) -> Result<usize, AppError> {
+        let mut peers: HashMap<Uuid, Vec<VyOSWireGuardPeer>> = HashMap::new();
+        for member in &mesh.members {
+            match self.nodes.get_wireguard_peers(member.node_id, &mesh.interface).await {
+                Ok(node_peers) => {
+                    peers.insert(member.node_id, node_peers);
+                }
+                Err(e) => debug!("Failed to read WireGuard peers of node {}: {}", member.node_id, e),
+            }
+        }
+        let public_keys: HashMap<Uuid, &str> = mesh
+            .members
+            .iter()
+            .map(|member| (member.node_id, member.public_key.as_str()))
+            .collect();
+
+        let now = Utc::now();
+        let mut down = 0;
+        for tunnel in &mesh.tunnels {
+            let ends = [(tunnel.node_a, tunnel.node_b), (tunnel.node_b, tunnel.node_a)];
+            let read = ends.iter().any(|(node, _)| peers.contains_key(node));
+            let handshake = ends
+                .iter()
+                .filter_map(|(node, peer)| {
+                    let key = public_keys.get(peer)?;
+                    peers.get(node)?.iter().find(|p| p.public_key == *key)?.latest_handshake_secs
+                })
+                .min();
+            let status = match handshake {
+                Some(age) if age <= HANDSHAKE_STALE_SECS => TunnelStatus::Up,
+                _ if read => TunnelStatus::Down,
+                _ => TunnelStatus::Unknown,
+            };
+            let last_handshake_at = handshake
+                .map(|age| now - chrono::Duration::seconds(age as i64))
+                .or(tunnel.last_handshake_at);
+
+            if status == TunnelStatus::Down {
+                down += 1;
+                if tunnel.status != TunnelStatus::Down {
+                    warn!(
+                        "Tunnel between nodes {} and {} of VPN mesh {} is down",
+                        tunnel.node_a, tunnel.node_b, mesh.name
+                    );
+                }
+            }
+            sqlx::query(
+                "UPDATE vpn_mesh_tunnels SET status = ?, last_handshake_at = ?, checked_at = ? \
+                 WHERE mesh_id = ? AND node_a = ? AND node_b = ?",
+            )
+            .bind(status.as_str())
+            .bind(last_handshake_at.as_ref().map(format_timestamp))
+            .bind(format_timestamp(&now))
+            .bind(mesh.id.to_string())
+            .bind(tunnel.node_a.to_string())
+            .bind(tunnel.node_b.to_string())
+            .execute(self.db.pool())
+            .await?;
+        }
+        Ok(down)
+    }
+
+    async fn set_status(&self, mesh_id: Uuid, status: VpnMeshStatus, error: Option<&str>) -> Result<(), AppError> {
+        sqlx::query("UPDATE vpn_meshes SET status = ?, error = ?, updated_at = ? WHERE id = ?")
+            .bind(status.as_str())
+            .bind(error)
+            .bind(db_now())
+            .bind(mesh_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// A mesh whose members the caller may all access
+    async fn accessible(&self, mesh_id: Uuid, claims: &Claims) -> Result<StoredMesh, AppError> {
+        let stored = self
+            .load(mesh_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("VPN mesh {} not found", mesh_id)))?;
+
+        let scope = self.teams.access_scope(claims).await?;
+        if !self.can_access(&stored.mesh, &scope).await? {
+            return Err(AppError::Forbidden("A node of the mesh is owned by another team".to_string()));
+        }
+        Ok(stored)
+    }
+
+    async fn can_access(&self, mesh: &VpnMesh, scope: &AccessScope) -> Result<bool, AppError> {
+        if *scope == AccessScope::All {
+            return Ok(true);
+        }
+        for member in &mesh.members {
+            match self.nodes.get_node(member.node_id).await? {
+                Some(node) if scope.can_access(node.team_id) => {}
+                _ => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    async fn load(&self, mesh_id: Uuid) -> Result<Option<StoredMesh>, AppError> {
+        let row: Option<MeshRow> = sqlx::query_as(&format!("SELECT {} FROM vpn_meshes WHERE id = ?", MESH_COLUMNS))
+            .bind(mesh_id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let member_rows: Vec<MemberRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM vpn_mesh_members WHERE mesh_id = ?",
+            MEMBER_COLUMNS
+        ))
+        .bind(mesh_id.to_string())
+        .fetch_all(self.db.pool())
+        .await?;
+        let tunnel_rows: Vec<TunnelRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM vpn_mesh_tunnels WHERE mesh_id = ?",
+            TUNNEL_COLUMNS
+        ))
+        .bind(mesh_id.to_string())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut private_keys = HashMap::new();
+        let mut members: Vec<VpnMeshMember> = member_rows
+            .into_iter()
+            .map(|row| {
+                let (member, private_key) = member_from_row(row);
+                private_keys.insert(member.node_id, private_key);
+                member
+            })
+            .collect();
+        // Members are kept in the order their addresses were allocated in
+        members.sort_by_key(|member| {
+            IpNet::from_str(&member.tunnel_address).map(|net| net.addr()).ok()
+        });
+        let order = |node_id: &Uuid| members.iter().position(|member| member.node_id == *node_id);
+        let mut tunnels: Vec<VpnMeshTunnel> = tunnel_rows.into_iter().map(tunnel_from_row).collect();
+        tunnels.sort_by_key(|tunnel| (order(&tunnel.node_a), order(&tunnel.node_b)));
+
+        Ok(Some(StoredMesh {
+            mesh: mesh_from_row(row, members, tunnels),
+            private_keys,
+        }))
+    }
+}
+
+/// Mesh columns in the order of [`MeshRow`]
+const MESH_COLUMNS: &str = "id, name, topology, hub_node_id, address_pool, interface, listen_port, status, error, \
+                            created_by, created_at, updated_at";
+
+/// Mesh columns as selected by [`MESH_COLUMNS`]
+type MeshRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    String,
+    i64,
+    String,
+    Option<String>,
+    String,
+    String,
+    String,
+);
+
+fn mesh_from_row(
+    (id, name, topology, hub_node_id, address_pool, interface, listen_port, status, error, created_by, created_at, updated_at): MeshRow,
+    members: Vec<VpnMeshMember>,
+    tunnels: Vec<VpnMeshTunnel>,
+) -> VpnMesh {
+    VpnMesh {
+        id: Uuid::parse_str(&id).unwrap_or_default(),
+        name,
+        topology: MeshTopology::parse(&topology),
+        hub_node_id: hub_node_id.and_then(|hub| Uuid::parse_str(&hub).ok()),
+        address_pool,
+        interface,
+        listen_port: listen_port as u16,
+        status: VpnMeshStatus::parse(&status),
+        error,
+        created_by,
+        members,
+        tunnels,
+        created_at: parse_db_timestamp(&created_at),
+        updated_at: parse_db_timestamp(&updated_at),
+    }
+}
+
+/// Member columns in the order of [`MemberRow`]
+const MEMBER_COLUMNS: &str = "node_id, endpoint, tunnel_address, public_key, private_key";
+
+/// Member columns as selected by [`MEMBER_COLUMNS`]
+type MemberRow = (String, String, String, String, String);
+
+fn member_from_row((node_id, endpoint, tunnel_address, public_key, private_key): MemberRow) -> (VpnMeshMember, String) {
+    let member = VpnMeshMember {
+        node_id: Uuid::parse_str(&node_id).unwrap_or_default(),
+        endpoint,
+        tunnel_address,
+        public_key,
+    };
+    (member, private_key)
+}
+
+/// Tunnel columns in the order of [`TunnelRow`]
+const TUNNEL_COLUMNS: &str = "node_a, node_b, status, last_handshake_at, checked_at";
+
+/// Tunnel columns as selected by [`TUNNEL_COLUMNS`]
+type TunnelRow = (String, String, String, Option<String>, Option<String>);
+
+fn tunnel_from_row((node_a, node_b, status, last_handshake_at, checked_at): TunnelRow) -> VpnMeshTunnel {
+    VpnMeshTunnel {
+        node_a: Uuid::parse_str(&node_a).unwrap_or_default(),
+        node_b: Uuid::parse_str(&node_b).unwrap_or_default(),
+        status: TunnelStatus::parse(&status),
+        last_handshake_at: last_handshake_at.as_deref().map(parse_db_timestamp),
+        checked_at: checked_at.as_deref().map(parse_db_timestamp),
+    }
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// WireGuard interfaces are named `wg` followed by a number
+fn validate_interface(interface: &str) -> Result<(), AppError> {
+    let number = interface.strip_prefix("wg").unwrap_or_default();
+    if number.is_empty() || number.len() > 6 || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(AppError::Validation(format!(
+            "Interface {} must be named wg followed by a number",
+            interface
+        )));
+    }
+    Ok(())
+}
+
+fn validate_members(topology: MeshTopology, hub: Option<Uuid>, node_ids: &[Uuid]) -> Result<(), AppError> {
+    if node_ids.len() < 2 {
+        return Err(AppError::Validation("A mesh needs at least two members".to_string()));
+    }
+    for (index, node_id) in node_ids.iter().enumerate() {
+        if node_ids[..index].contains(node_id) {
+            return Err(AppError::Validation(format!("Node {} is listed twice", node_id)));
+        }
+        if node_ids[..index].iter().any(|other| peer_name(*other) == peer_name(*node_id)) {
+            return Err(AppError::Validation(format!(
+                "Nodes {} and another member share the peer name {}",
+                node_id,
+                peer_name(*node_id)
+            )));
+        }
+    }
+    match (topology, hub) {
+        (MeshTopology::HubAndSpoke, Some(hub)) if node_ids.contains(&hub) => Ok(()),
+        (MeshTopology::HubAndSpoke, _) => Err(AppError::Validation(
+            "A hub-and-spoke mesh needs a hub that is one of its members".to_string(),
+        )),
+        (MeshTopology::Full, Some(_)) => Err(AppError::Validation("A full mesh has no hub".to_string())),
+        (MeshTopology::Full, None) => Ok(()),
+    }
+}
+
+/// Endpoints are an address or a host name
+fn validate_endpoint(endpoint: &str) -> Result<(), AppError> {
+    let valid = !endpoint.is_empty()
+        && endpoint.len() <= MAX_ENDPOINT_LEN
+        && endpoint
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'-' || b == b':');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("Invalid endpoint {}", endpoint)))
+    }
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// WireGuard key pair as `(private, public)`, both base64 encoded
+fn generate_key_pair() -> Result<(String, String), AppError> {
+    let failed = |e: openssl::error::ErrorStack| AppError::Internal(format!("Failed to generate a key pair: {}", e));
+    let key = PKey::generate_x25519().map_err(failed)?;
+    let private_key = key.raw_private_key().map_err(failed)?;
+    let public_key = key.raw_public_key().map_err(failed)?;
+    Ok((Base64::encode_string(&private_key), Base64::encode_string(&public_key)))
+}
+
+/// Name of a member in its peers' configuration
+fn peer_name(node_id: Uuid) -> String {
+    format!("node-{}", &node_id.simple().to_string()[..8])
+}
+
+/// Members with a tunnel between them
+fn tunnel_pairs(topology: MeshTopology, hub: Option<Uuid>, node_ids: &[Uuid]) -> Vec<(Uuid, Uuid)> {
+    match (topology, hub) {
+        (MeshTopology::HubAndSpoke, Some(hub)) => node_ids
+            .iter()
+            .filter(|node_id| **node_id != hub)
+            .map(|spoke| (hub, *spoke))
+            .collect(),
+        _ => node_ids
+            .iter()
+            .enumerate()
+            .flat_map(|(index, a)| node_ids[index + 1..].iter().map(move |b| (*a, *b)))
+            .collect(),
+    }
+}
+
+fn set(path: String, value: impl ToString) -> StagedOperation {
+    StagedOperation {
+        op: ConfigOperationKind::Set,
+        path,
+        value: Some(value.to_string()),
+    }
+}
+
+/// Root of a mesh's configuration on every member
+fn interface_path(mesh: &VpnMesh) -> String {
+    format!("interfaces wireguard {}", mesh.interface)
+}
+
+/// Interface and peers of one member
+///
+/// In a hub-and-spoke mesh, spokes route the whole pool to the hub, which
+/// forwards between them.
+fn render_member(stored: &StoredMesh, member: &VpnMeshMember) -> Vec<StagedOperation> {
+    let mesh = &stored.mesh;
+    let root = interface_path(mesh);
+    let mut operations = vec![
+        set(format!("{} address", root), &member.tunnel_address),
+        set(format!("{} description", root), format!("VPN mesh {}", mesh.name)),
+        set(format!("{} port", root), mesh.listen_port),
+        set(
+            format!("{} private-key", root),
+            stored.private_keys.get(&member.node_id).map(String::as_str).unwrap_or_default(),
+        ),
+    ];
+
+    for tunnel in &mesh.tunnels {
+        let other = match (tunnel.node_a == member.node_id, tunnel.node_b == member.node_id) {
+            (true, _) => tunnel.node_b,
+            (_, true) => tunnel.node_a,
+            _ => continue,
+        };
+        let Some(peer) = mesh.members.iter().find(|m| m.node_id == other) else {
+            continue;
+        };
+        let allowed_ips = match IpNet::from_str(&peer.tunnel_address) {
+            _ if mesh.hub_node_id == Some(peer.node_id) => mesh.address_pool.clone(),
+            Ok(net) => IpNet::from(net.addr()).to_string(),
+            Err(_) => peer.tunnel_address.clone(),
+        };
+        let path = format!("{} peer {}", root, peer_name(peer.node_id));
+        let endpoint_key = if IpAddr::from_str(&peer.endpoint).is_ok() { "address" } else { "host-name" };
+        operations.extend([
+            set(format!("{} public-key", path), &peer.public_key),
+            set(format!("{} allowed-ips", path), allowed_ips),
+            set(format!("{} {}", path, endpoint_key), &peer.endpoint),
+            set(format!("{} port", path), mesh.listen_port),
+            set(format!("{} persistent-keepalive", path), PERSISTENT_KEEPALIVE_SECS),
+        ]);
+    }
+    operations
+}
+
+/// Operations replacing a member's mesh interface by `sets`
+fn replace_operations(mesh: &VpnMesh, current: &Value, sets: Vec<StagedOperation>) -> Vec<StagedOperation> {
+    let root = interface_path(mesh);
+    let exists = path_segments(&root)
+        .iter()
+        .try_fold(current, |node, segment| node.as_object()?.get(*segment))
+        .is_some();
+    let delete = exists.then_some(StagedOperation {
+        op: ConfigOperationKind::Delete,
+        path: root,
+        value: None,
+    });
+    delete.into_iter().chain(sets).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mesh(topology: MeshTopology, count: usize) -> StoredMesh {
+        let node_ids: Vec<Uuid> = (0..count).map(|_| Uuid::new_v4()).collect();
+        let hub = (topology == MeshTopology::HubAndSpoke).then(|| node_ids[0]);
+        let members = node_ids
+            .iter()
+            .enumerate()
+            .map(|(index, node_id)| VpnMeshMember {
+                node_id: *node_id,
+                endpoint: format!("198.51.100.{}", index + 1),
+                tunnel_address: format!("10.255.0.{}/24", index + 1),
+                public_key: format!("public-{}", index),
+            })
+            .collect();
+        let tunnels = tunnel_pairs(topology, hub, &node_ids)
+            .into_iter()
+            .map(|(node_a, node_b)| VpnMeshTunnel {
+                node_a,
+                node_b,
+                status: TunnelStatus::Unknown,
+                last_handshake_at: None,
+                checked_at: None,
+            })
+            .collect();
+        StoredMesh {
+            mesh: VpnMesh {
+                id: Uuid::new_v4(),
+                name: "branches".to_string(),
+                topology,
+                hub_node_id: hub,
+                address_pool: "10.255.0.0/24".to_string(),
+                interface: "wg100".to_string(),
+                listen_port: 51820,
+                status: VpnMeshStatus::Pending,
+                error: None,
+                created_by: "admin".to_string(),
+                members,
+                tunnels,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            private_keys: node_ids.iter().map(|node_id| (*node_id, format!("private-{}", node_id))).collect(),
+        }
+    }
+
+    fn values<'a>(operations: &'a [StagedOperation], suffix: &str) -> Vec<&'a str> {
+        operations
+            .iter()
+            .filter(|operation| operation.path.ends_with(suffix))
+            .filter_map(|operation| operation.value.as_deref())
+            .collect()
+    }
+
+    #[test]
+    fn test_tunnel_pairs() {
+        let nodes: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        assert_eq!(tunnel_pairs(MeshTopology::Full, None, &nodes).len(), 6);
+        let hub = tunnel_pairs(MeshTopology::HubAndSpoke, Some(nodes[2]), &nodes);
+        assert_eq!(hub, [(nodes[2], nodes[0]), (nodes[2], nodes[1]), (nodes[2], nodes[3])]);
+    }
+
+    #[test]
+    fn test_render_full_mesh_member() {
+        let stored = mesh(MeshTopology::Full, 3);
+        let member = &stored.mesh.members[1];
+        let operations = render_member(&stored, member);
+
+        assert_eq!(values(&operations, "wg100 address"), ["10.255.0.2/24"]);
+        assert_eq!(values(&operations, "private-key"), [format!("private-{}", member.node_id).as_str()]);
+        assert_eq!(values(&operations, "public-key"), ["public-0", "public-2"]);
+        assert_eq!(values(&operations, "allowed-ips"), ["10.255.0.1/32", "10.255.0.3/32"]);
+        assert_eq!(values(&operations, " address"), ["10.255.0.2/24", "198.51.100.1", "198.51.100.3"]);
+
+        let current = serde_json::json!({ "interfaces": { "wireguard": { "wg100": {} } } });
+        let replaced = replace_operations(&stored.mesh, &current, operations.clone());
+        assert_eq!(replaced[0].op, ConfigOperationKind::Delete);
+        assert_eq!(replaced[0].path, "interfaces wireguard wg100");
+        assert_eq!(replace_operations(&stored.mesh, &serde_json::json!({}), operations.clone()), operations);
+    }
+
+    #[test]
+    fn test_render_hub_and_spoke_members() {
+        let stored = mesh(MeshTopology::HubAndSpoke, 3);
+        let hub = render_member(&stored, &stored.mesh.members[0]);
+        assert_eq!(values(&hub, "allowed-ips"), ["10.255.0.2/32", "10.255.0.3/32"]);
+
+        let spoke = render_member(&stored, &stored.mesh.members[2]);
+        assert_eq!(values(&spoke, "public-key"), ["public-0"]);
+        assert_eq!(values(&spoke, "allowed-ips"), ["10.255.0.0/24"]);
+    }
+
+    #[test]
+    fn test_invalid_meshes_are_rejected() {
+        let nodes: Vec<Uuid> = (0..2).map(|_| Uuid::new_v4()).collect();
+        assert!(validate_members(MeshTopology::Full, None, &nodes).is_ok());
+        assert!(validate_members(MeshTopology::Full, None, &nodes[..1]).is_err());
+        assert!(validate_members(MeshTopology::Full, None, &[nodes[0], nodes[0]]).is_err());
+        assert!(validate_members(MeshTopology::HubAndSpoke, None, &nodes).is_err());
+        assert!(validate_members(MeshTopology::HubAndSpoke, Some(Uuid::new_v4()), &nodes).is_err());
+        assert!(validate_members(MeshTopology::Full, Some(nodes[0]), &nodes).is_err());
+
+        assert!(validate_interface("wg0").is_ok());
+        assert!(validate_interface("wg").is_err());
+        assert!(validate_interface("eth0").is_err());
+        assert!(validate_endpoint("vpn.example.net").is_ok());
+        assert!(validate_endpoint("203.0.113.1; reboot").is_err());
+    }
+
+    #[test]
+    fn test_generate_key_pair() {
+        let (private_key, public_key) = generate_key_pair().unwrap();
+        assert_eq!(Base64::decode_vec(&private_key).unwrap().len(), 32);
+        assert_eq!(Base64::decode_vec(&public_key).unwrap().len(), 32);
+        assert_ne!(generate_key_pair().unwrap().0, private_key);
+    }
+}
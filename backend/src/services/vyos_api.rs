@@ -5,6 +5,7 @@
 //! management, and system operations.
 
 use crate::error::AppError;
+use crate::models::redact::sanitized_debug;
 use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -16,7 +17,7 @@ use uuid::Uuid;
 const VYOS_API_BASE: &str = "/rest/conf";
 
 /// VyOS API client configuration
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct VyosApiClientConfig {
     /// API base URL (e.g., "https://10.10.5.51")
     pub base_url: String,
@@ -28,6 +29,8 @@ pub struct VyosApiClientConfig {
     pub verify_ssl: bool,
 }
 
+sanitized_debug!(VyosApiClientConfig { base_url, timeout, verify_ssl } secret { api_key });
+
 impl Default for VyosApiClientConfig {
     fn default() -> Self {
         Self {
@@ -0,0 +1,762 @@
+//! Setup Wizards
+//!
+//! Setup flows spanning two nodes, such as a site-to-site VPN or an HA pair,
+//! are answered one step at a time. The backend keeps the answers, checks
+//! each step as it is submitted, and generates the configuration of both
+//! nodes from them, using the configuration syntax of VyOS 1.4 and later.
+//!
+//! Applying is all or nothing: the nodes are configured one after the
+//! other, and if one fails, the nodes already configured are restored the
+//! way an unconfirmed configuration session is rolled back.
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use ipnet::IpNet;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::auth::Claims;
+use crate::models::config_session::StagedOperation;
+use crate::models::team::AccessScope;
+use crate::models::timestamp::{db_now, parse_db_timestamp};
+use crate::models::wizard::{
+    HaNodesStep, HaVrrpStep, VpnEndpointsStep, VpnNetworksStep, VpnTunnelStep, Wizard, WizardKind,
+    WizardListQuery, WizardListResponse, WizardNodePlan, WizardPlan, WizardStatus,
+};
+use crate::services::config_lock::path_segments;
+use crate::services::config_session::ConfigSessionService;
+use crate::services::node_service::NodeService;
+use crate::services::team::TeamService;
+use crate::vyos_client::ConfigOperationKind;
+
+/// Most wizards returned by a list
+const WIZARD_LIST_LIMIT: i64 = 200;
+
+/// Shown in place of secrets
+const MASKED: &str = "********";
+
+/// Answers holding secrets, by step, masked whenever a wizard is returned
+const SECRET_ANSWERS: &[(&str, &str)] = &[("tunnel", "pre_shared_key")];
+
+/// Last word of the configuration paths holding secrets
+const SECRET_PATH_SUFFIX: &str = "secret";
+
+/// IKE and ESP encryption algorithms offered by the VPN wizard
+const VPN_ENCRYPTIONS: &[&str] = &["aes128", "aes256", "aes128gcm128", "aes256gcm128", "chacha20poly1305"];
+
+/// IKE and ESP hash algorithms offered by the VPN wizard
+const VPN_HASHES: &[&str] = &["sha1", "sha256", "sha384", "sha512"];
+
+/// VRRP priorities of the primary and the secondary node of an HA pair
+const HA_PRIORITIES: [u8; 2] = [200, 100];
+
+/// Configuration a wizard generates for one node
+#[derive(Debug, Clone, PartialEq)]
+struct GeneratedConfig {
+    node_id: Uuid,
+    /// Subtrees owned by the wizard, replaced as a whole
+    roots: Vec<String>,
+    sets: Vec<StagedOperation>,
+}
+
+/// Wizard as stored, with its secrets and owner
+struct StoredWizard {
+    wizard: Wizard,
+    user_id: String,
+}
+
+/// Setup wizard service
+#[derive(Clone)]
+pub struct WizardService {
+    db: Database,
+    nodes: NodeService,
+    teams: TeamService,
+    sessions: ConfigSessionService,
+}
+
+impl WizardService {
+    /// Create a new setup wizard service
+    pub fn new(
+        db: Database,
+        nodes: NodeService,
+        teams: TeamService,
+        sessions: ConfigSessionService,
+    ) -> Self {
+        Self { db, nodes, teams, sessions }
+    }
+
+    /// Start a wizard
+    pub async fn start(&self, kind: WizardKind, claims: &Claims) -> Result<Wizard, AppError> {
+        let id = Uuid::new_v4();
+        let now = db_now();
+        sqlx::query(
+            "INSERT INTO wizards (id, kind, user_id, username, status, answers, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, '{}', ?, ?)",
+        )
+        .bind(id.to_string())
+        .bind(kind.as_str())
+        .bind(&claims.sub)
+        .bind(&claims.username)
+        .bind(WizardStatus::Open.as_str())
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await?;
+
+        info!("{} started {} wizard {}", claims.username, kind.as_str(), id);
+        self.get(id, claims).await
+    }
+
+    /// Get a wizard of the caller, or any for administrators
+    pub async fn get(&self, wizard_id: Uuid, claims: &Claims) -> Result<Wizard, AppError> {
+        Ok(masked(self.owned(wizard_id, claims).await?.wizard))
+    }
+
+    /// List the caller's wizards, or everyone's for administrators, newest
+    /// first
+    ///
+    /// Only open wizards are listed unless `all` is set.
+    pub async fn list(&self, query: &WizardListQuery, claims: &Claims) -> Result<WizardListResponse, AppError> {
+        let user_id = match self.teams.access_scope(claims).await? {
+            AccessScope::All => None,
+            AccessScope::Teams(_) => Some(claims.sub.clone()),
+        };
+        let rows: Vec<WizardRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM wizards \
+             WHERE (? IS NULL OR user_id = ?) AND (? OR status IN ('open', 'applying')) \
+             ORDER BY created_at DESC LIMIT ?",
+            WIZARD_COLUMNS
+        ))
+        .bind(&user_id)
+        .bind(&user_id)
+        .bind(query.all)
+        .bind(WIZARD_LIST_LIMIT)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(WizardListResponse {
+            wizards: rows.into_iter().map(|row| masked(wizard_from_row(row).wizard)).collect(),
+        })
+    }
+
+    /// Check and keep the answers to a step
+    ///
+    /// Steps are answered in order; an answered step may be answered again.
+    /// Nodes named in the answers must be accessible to the caller.
+    pub async fn answer(
+        &self,
+        wizard_id: Uuid,
+        step: &str,
+        answers: Value,
+        claims: &Claims,
+    ) -> Result<Wizard, AppError> {
+        let mut stored = self.owned(wizard_id, claims).await?;
+        ensure_open(&stored.wizard)?;
+        let kind = stored.wizard.kind;
+        let Some(index) = kind.steps().iter().position(|s| *s == step) else {
+            return Err(AppError::NotFound(format!("A {} wizard has no step {}", kind.as_str(), step)));
+        };
+        if let Some(next) = kind.steps().iter().position(|s| !stored.wizard.answers.contains_key(*s)) {
+            if index > next {
+                return Err(AppError::Validation(format!("Answer step {} first", kind.steps()[next])));
+            }
+        }
+
+        let answers = validate_step(kind, step, answers)?;
+        for node_id in step_nodes(step, &answers) {
+            self.ensure_node_access(node_id, claims).await?;
+        }
+        stored.wizard.answers.insert(step.to_string(), answers);
+
+        let updated = sqlx::query("UPDATE wizards SET answers = ?, updated_at = ? WHERE id = ? AND status = 'open'")
+            .bind(serde_json::to_string(&stored.wizard.answers)?)
+            .bind(db_now())
+            .bind(wizard_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+        if updated.rows_affected() == 0 {
+            return Err(not_open(wizard_id));
+        }
+
+        self.get(wizard_id, claims).await
+    }
+
+    /// Configuration the wizard would apply to each node, with secrets
+    /// masked
+    pub async fn plan(&self, wizard_id: Uuid, claims: &Claims) -> Result<WizardPlan, AppError> {
+        let stored = self.owned(wizard_id, claims).await?;
+        let mut nodes = Vec::new();
+        for config in self.generate(&stored.wizard, claims).await? {
+            let current = self.sessions.current_config(config.node_id).await?;
+            let operations = node_operations(&config, &current)
+                .into_iter()
+                .map(|operation| match path_segments(&operation.path).last() {
+                    Some(&SECRET_PATH_SUFFIX) => StagedOperation {
+                        value: Some(MASKED.to_string()),
+                        ..operation
+                    },
+                    _ => operation,
+                })
+                .collect();
+            nodes.push(WizardNodePlan {
+                node_id: config.node_id,
+                operations,
+            });
+        }
+
+        Ok(WizardPlan { wizard_id, nodes })
+    }
+
+    /// Apply the generated configuration to every node
+    ///
+    /// If a node fails, the nodes already configured are rolled back and the
+    /// wizard stays open with the error, so it can be fixed and applied
+    /// again. Only if the rollback fails too does the wizard fail.
+    pub async fn apply(&self, wizard_id: Uuid, claims: &Claims) -> Result<Wizard, AppError> {
+        let stored = self.owned(wizard_id, claims).await?;
+        ensure_open(&stored.wizard)?;
+
+        // Check every node before touching any
+        let mut changes = Vec::new();
+        for config in self.generate(&stored.wizard, claims).await? {
+            let current = self.sessions.current_config(config.node_id).await?;
+            let operations = node_operations(&config, &current);
+            changes.push(self.sessions.prepare_change(config.node_id, &current, operations, claims).await?);
+        }
+        let nodes = changes.len();
+
+        let claimed = sqlx::query(
+            "UPDATE wizards SET status = 'applying', error = NULL, updated_at = ? WHERE id = ? AND status = 'open'",
+        )
+        .bind(db_now())
+        .bind(wizard_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Err(not_open(wizard_id));
+        }
+
+        if let Err(failure) = self.sessions.apply_changes(changes).await {
+            warn!("Failed to apply wizard {} to node {}: {}", wizard_id, failure.node_id, failure.error);
+            let message = format!("Applying to node {} failed: {}", failure.node_id, failure.error);
+            match failure.rollback_error {
+                None => self.finish(wizard_id, WizardStatus::Open, Some(&message)).await?,
+                Some(rollback) => {
+                    error!("Failed to roll back wizard {}: {}", wizard_id, rollback);
+                    let message = format!("{}; rolling back the other nodes failed: {}", message, rollback);
+                    self.finish(wizard_id, WizardStatus::Failed, Some(&message)).await?;
+                }
+            }
+            return Err(failure.error);
+        }
+
+        self.finish(wizard_id, WizardStatus::Applied, None).await?;
+        info!(
+            "{} applied {} wizard {} to {} nodes",
+            claims.username,
+            stored.wizard.kind.as_str(),
+            wizard_id,
+            nodes
+        );
+        self.get(wizard_id, claims).await
+    }
+
+    /// Close an open wizard without applying it
+    pub async fn discard(&self, wizard_id: Uuid, claims: &Claims) -> Result<Wizard, AppError> {
+        self.owned(wizard_id, claims).await?;
+
+        let discarded =
+            sqlx::query("UPDATE wizards SET status = 'discarded', updated_at = ? WHERE id = ? AND status = 'open'")
+                .bind(db_now())
+                .bind(wizard_id.to_string())
+                .execute(self.db.pool())
+                .await?;
+        if discarded.rows_affected() == 0 {
+            return Err(not_open(wizard_id));
+        }
+
+        self.get(wizard_id, claims).await
+    }
+
+    async fn finish(&self, wizard_id: Uuid, status: WizardStatus, error: Option<&str>) -> Result<(), AppError> {
+        sqlx::query("UPDATE wizards SET status = ?, error = ?, updated_at = ? WHERE id = ?")
+            .bind(status.as_str())
+            .bind(error)
+            .bind(db_now())
+            .bind(wizard_id.to_string())
+            .execute(self.db.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Configuration of every node from the answers of a completed wizard
+    ///
+    /// Access to the nodes is checked again, as it may have changed since
+    /// the steps were answered.
+    async fn generate(&self, wizard: &Wizard, claims: &Claims) -> Result<Vec<GeneratedConfig>, AppError> {
+        if let Some(step) = &wizard.next_step {
+            return Err(AppError::Validation(format!("Step {} is not answered yet", step)));
+        }
+        let configs = generate_configs(wizard.kind, &wizard.answers)?;
+        for config in &configs {
+            self.ensure_node_access(config.node_id, claims).await?;
+        }
+        Ok(configs)
+    }
+
+    async fn ensure_node_access(&self, node_id: Uuid, claims: &Claims) -> Result<(), AppError> {
+        let node = self
+            .nodes
+            .get_node(node_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Node {} not found", node_id)))?;
+
+        if !self.teams.access_scope(claims).await?.can_access(node.team_id) {
+            return Err(AppError::Forbidden("Node is owned by another team".to_string()));
+        }
+        Ok(())
+    }
+
+    /// A wizard the caller may use: their own, or any for administrators
+    async fn owned(&self, wizard_id: Uuid, claims: &Claims) -> Result<StoredWizard, AppError> {
+        let row: Option<WizardRow> = sqlx::query_as(&format!("SELECT {} FROM wizards WHERE id = ?", WIZARD_COLUMNS))
+            .bind(wizard_id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?;
+        let stored = row
+            .map(wizard_from_row)
+            .ok_or_else(|| AppError::NotFound(format!("Wizard {} not found", wizard_id)))?;
+
+        if stored.user_id != claims.sub && self.teams.access_scope(claims).await? != AccessScope::All {
+            return Err(AppError::Forbidden(format!(
+                "Wizard {} belongs to {}",
+                wizard_id, stored.wizard.username
+            )));
+        }
+        Ok(stored)
+    }
+}
+
+/// Wizard columns in the order of [`WizardRow`]
+const WIZARD_COLUMNS: &str = "id, kind, user_id, username, status, answers, error, created_at, updated_at";
+
+/// Wizard columns as selected by [`WIZARD_COLUMNS`]
+type WizardRow = (String, String, String, String, String, String, Option<String>, String, String);
+
+fn wizard_from_row(
+    (id, kind, user_id, username, status, answers, error, created_at, updated_at): WizardRow,
+) -> StoredWizard {
+    let kind = WizardKind::parse(&kind).unwrap_or(WizardKind::SiteToSiteVpn);
+    let answers: BTreeMap<String, Value> = serde_json::from_str(&answers).unwrap_or_default();
+    let next_step = kind
+        .steps()
+        .iter()
+        .find(|step| !answers.contains_key(**step))
+        .map(|step| step.to_string());
+    StoredWizard {
+        wizard: Wizard {
+            id: Uuid::parse_str(&id).unwrap_or_default(),
+            kind,
+            username,
+            status: WizardStatus::parse(&status),
+            answers,
+            next_step,
+            error,
+            created_at: parse_db_timestamp(&created_at),
+            updated_at: parse_db_timestamp(&updated_at),
+        },
+        user_id,
+    }
+}
+
+/// A wizard as returned to callers, without its secrets
+fn masked(mut wizard: Wizard) -> Wizard {
+    for (step, field) in SECRET_ANSWERS {
+        if let Some(Value::Object(answers)) = wizard.answers.get_mut(*step) {
+            if let Some(secret) = answers.get_mut(*field) {
+                *secret = Value::String(MASKED.to_string());
+            }
+        }
+    }
+    wizard
+}
+
+fn ensure_open(wizard: &Wizard) -> Result<(), AppError> {
+    if wizard.status == WizardStatus::Open {
+        Ok(())
+    } else {
+        Err(not_open(wizard.id))
+    }
+}
+
+fn not_open(wizard_id: Uuid) -> AppError {
+    AppError::Validation(format!("Wizard {} is not open", wizard_id))
+}
+
+// ============================================================================
+// Steps
+// ============================================================================
+
+/// Check the answers to a step, returning them with defaults filled in
+fn validate_step(kind: WizardKind, step: &str, answers: Value) -> Result<Value, AppError> {
+    match (kind, step) {
+        (WizardKind::SiteToSiteVpn, "endpoints") => {
+            let endpoints: VpnEndpointsStep = parse_step(step, answers)?;
+            if endpoints.local_node_id == endpoints.remote_node_id {
+                return Err(AppError::Validation("The tunnel needs two different nodes".to_string()));
+            }
+            if endpoints.local_address == endpoints.remote_address {
+                return Err(AppError::Validation("The nodes need different peer addresses".to_string()));
+            }
+            if endpoints.local_address.is_ipv4() != endpoints.remote_address.is_ipv4() {
+                return Err(AppError::Validation("Peer addresses must be of the same IP version".to_string()));
+            }
+            to_answers(&endpoints)
+        }
+        (WizardKind::SiteToSiteVpn, "tunnel") => {
+            let tunnel: VpnTunnelStep = parse_step(step, answers)?;
+            validate_name("Tunnel name", &tunnel.name)?;
+            let key = &tunnel.pre_shared_key;
+            if !(8..=128).contains(&key.len()) || !key.bytes().all(|b| b.is_ascii_graphic() && b != b'\'' && b != b'"') {
+                return Err(AppError::Validation(
+                    "The pre-shared key must be 8 to 128 printable characters without spaces or quotes".to_string(),
+                ));
+            }
+            if !VPN_ENCRYPTIONS.contains(&tunnel.encryption.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "Encryption must be one of {}",
+                    VPN_ENCRYPTIONS.join(", ")
+                )));
+            }
+            if !VPN_HASHES.contains(&tunnel.hash.as_str()) {
+                return Err(AppError::Validation(format!("Hash must be one of {}", VPN_HASHES.join(", "))));
+            }
+            to_answers(&tunnel)
+        }
+        (WizardKind::SiteToSiteVpn, "networks") => {
+            let networks: VpnNetworksStep = parse_step(step, answers)?;
+            let local = parse_prefix("Local prefix", &networks.local_prefix)?;
+            let remote = parse_prefix("Remote prefix", &networks.remote_prefix)?;
+            if matches!(local, IpNet::V4(_)) != matches!(remote, IpNet::V4(_)) {
+                return Err(AppError::Validation("Prefixes must be of the same IP version".to_string()));
+            }
+            if local.contains(&remote) || remote.contains(&local) {
+                return Err(AppError::Validation("The local and remote prefixes overlap".to_string()));
+            }
+            to_answers(&VpnNetworksStep {
+                local_prefix: local.to_string(),
+                remote_prefix: remote.to_string(),
+            })
+        }
+        (WizardKind::HaPair, "nodes") => {
+            let nodes: HaNodesStep = parse_step(step, answers)?;
+            if nodes.primary_node_id == nodes.secondary_node_id {
+                return Err(AppError::Validation("The pair needs two different nodes".to_string()));
+            }
+            to_answers(&nodes)
+        }
+        (WizardKind::HaPair, "vrrp") => {
+            let vrrp: HaVrrpStep = parse_step(step, answers)?;
+            validate_name("Group name", &vrrp.group)?;
+            let interface_ok = !vrrp.interface.is_empty()
+                && vrrp.interface.len() <= 32
+                && vrrp
+                    .interface
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'-' || b == b'_');
+            if !interface_ok {
+                return Err(AppError::Validation(format!("Invalid interface name {}", vrrp.interface)));
+            }
+            if vrrp.vrid == 0 {
+                return Err(AppError::Validation("VRID must be between 1 and 255".to_string()));
+            }
+            let address = IpNet::from_str(vrrp.virtual_address.trim()).map_err(|_| {
+                AppError::Validation(format!(
+                    "Virtual address {} must be an address with its prefix length",
+                    vrrp.virtual_address
+                ))
+            })?;
+            to_answers(&HaVrrpStep {
+                virtual_address: address.to_string(),
+                ..vrrp
+            })
+        }
+        _ => Err(AppError::NotFound(format!("A {} wizard has no step {}", kind.as_str(), step))),
+    }
+}
+
+fn parse_step<T: DeserializeOwned>(step: &str, answers: Value) -> Result<T, AppError> {
+    serde_json::from_value(answers)
+        .map_err(|e| AppError::Validation(format!("Invalid answers to step {}: {}", step, e)))
+}
+
+fn to_answers<T: Serialize>(answers: &T) -> Result<Value, AppError> {
+    Ok(serde_json::to_value(answers)?)
+}
+
+/// Names used as configuration path words: letters, digits, `-` and `_`
+fn validate_name(what: &str, name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= 32
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "{} must be 1 to 32 letters, digits, dashes, or underscores",
+            what
+        )))
+    }
+}
+
+/// Network prefix, normalized to its network address
+fn parse_prefix(what: &str, prefix: &str) -> Result<IpNet, AppError> {
+    IpNet::from_str(prefix.trim())
+        .map(|net| net.trunc())
+        .map_err(|_| AppError::Validation(format!("{} {} is not a network prefix", what, prefix)))
+}
+
+/// Nodes named in the answers to a step
+fn step_nodes(step: &str, answers: &Value) -> Vec<Uuid> {
+    let fields: &[&str] = match step {
+        "endpoints" => &["local_node_id", "remote_node_id"],
+        "nodes" => &["primary_node_id", "secondary_node_id"],
+        _ => &[],
+    };
+    fields
+        .iter()
+        .filter_map(|field| answers.get(*field)?.as_str()?.parse().ok())
+        .collect()
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Configuration of every node from the answers to every step
+fn generate_configs(kind: WizardKind, answers: &BTreeMap<String, Value>) -> Result<Vec<GeneratedConfig>, AppError> {
+    fn answer<T: DeserializeOwned>(answers: &BTreeMap<String, Value>, step: &str) -> Result<T, AppError> {
+        let value = answers
+            .get(step)
+            .cloned()
+            .ok_or_else(|| AppError::Validation(format!("Step {} is not answered yet", step)))?;
+        parse_step(step, value)
+    }
+
+    match kind {
+        WizardKind::SiteToSiteVpn => Ok(vpn_configs(
+            &answer(answers, "endpoints")?,
+            &answer(answers, "tunnel")?,
+            &answer(answers, "networks")?,
+        )),
+        WizardKind::HaPair => Ok(ha_configs(&answer(answers, "nodes")?, &answer(answers, "vrrp")?)),
+    }
+}
+
+fn set(path: String, value: impl ToString) -> StagedOperation {
+    StagedOperation {
+        op: ConfigOperationKind::Set,
+        path,
+        value: Some(value.to_string()),
+    }
+}
+
+/// Both ends of an IPsec site-to-site tunnel, each the mirror of the other
+fn vpn_configs(endpoints: &VpnEndpointsStep, tunnel: &VpnTunnelStep, networks: &VpnNetworksStep) -> Vec<GeneratedConfig> {
+    let ends: [(Uuid, IpAddr, IpAddr, &str, &str); 2] = [
+        (
+            endpoints.local_node_id,
+            endpoints.local_address,
+            endpoints.remote_address,
+            &networks.local_prefix,
+            &networks.remote_prefix,
+        ),
+        (
+            endpoints.remote_node_id,
+            endpoints.remote_address,
+            endpoints.local_address,
+            &networks.remote_prefix,
+            &networks.local_prefix,
+        ),
+    ];
+
+    let name = &tunnel.name;
+    let ike = format!("vpn ipsec ike-group {}", name);
+    let esp = format!("vpn ipsec esp-group {}", name);
+    let psk = format!("vpn ipsec authentication psk {}", name);
+    let peer = format!("vpn ipsec site-to-site peer {}", name);
+    ends.into_iter()
+        .map(|(node_id, local, remote, local_prefix, remote_prefix)| GeneratedConfig {
+            node_id,
+            roots: vec![ike.clone(), esp.clone(), psk.clone(), peer.clone()],
+            sets: vec![
+                set(format!("{} key-exchange", ike), "ikev2"),
+                set(format!("{} proposal 1 encryption", ike), &tunnel.encryption),
+                set(format!("{} proposal 1 hash", ike), &tunnel.hash),
+                set(format!("{} proposal 1 encryption", esp), &tunnel.encryption),
+                set(format!("{} proposal 1 hash", esp), &tunnel.hash),
+                set(format!("{} id", psk), local),
+                set(format!("{} id", psk), remote),
+                set(format!("{} {}", psk, SECRET_PATH_SUFFIX), &tunnel.pre_shared_key),
+                set(format!("{} authentication mode", peer), "pre-shared-secret"),
+                set(format!("{} ike-group", peer), name),
+                set(format!("{} default-esp-group", peer), name),
+                set(format!("{} local-address", peer), local),
+                set(format!("{} remote-address", peer), remote),
+                set(format!("{} tunnel 1 local prefix", peer), local_prefix),
+                set(format!("{} tunnel 1 remote prefix", peer), remote_prefix),
+            ],
+        })
+        .collect()
+}
+
+/// Both members of a VRRP pair, the primary with the higher priority
+fn ha_configs(nodes: &HaNodesStep, vrrp: &HaVrrpStep) -> Vec<GeneratedConfig> {
+    let group = format!("high-availability vrrp group {}", vrrp.group);
+    [nodes.primary_node_id, nodes.secondary_node_id]
+        .into_iter()
+        .zip(HA_PRIORITIES)
+        .map(|(node_id, priority)| GeneratedConfig {
+            node_id,
+            roots: vec![group.clone()],
+            sets: vec![
+                set(format!("{} interface", group), &vrrp.interface),
+                set(format!("{} vrid", group), vrrp.vrid),
+                set(format!("{} address", group), &vrrp.virtual_address),
+                set(format!("{} priority", group), priority),
+            ],
+        })
+        .collect()
+}
+
+/// Operations replacing a node's wizard-owned subtrees by the generated
+/// configuration
+fn node_operations(config: &GeneratedConfig, current: &Value) -> Vec<StagedOperation> {
+    let deletes = config
+        .roots
+        .iter()
+        .filter(|root| {
+            path_segments(root)
+                .iter()
+                .try_fold(current, |node, segment| node.as_object()?.get(*segment))
+                .is_some()
+        })
+        .map(|root| StagedOperation {
+            op: ConfigOperationKind::Delete,
+            path: root.clone(),
+            value: None,
+        });
+    deletes.chain(config.sets.iter().cloned()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vpn_answers() -> BTreeMap<String, Value> {
+        let steps = [
+            (
+                "endpoints",
+                serde_json::json!({
+                    "local_node_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "local_address": "203.0.113.1",
+                    "remote_node_id": "0f8fad5b-d9cb-469f-a165-70867728950e",
+                    "remote_address": "198.51.100.1",
+                }),
+            ),
+            ("tunnel", serde_json::json!({ "name": "branch", "pre_shared_key": "s3cret-key" })),
+            ("networks", serde_json::json!({ "local_prefix": "10.1.0.1/16", "remote_prefix": "10.2.0.0/16" })),
+        ];
+        steps
+            .into_iter()
+            .map(|(step, answers)| {
+                let answers = validate_step(WizardKind::SiteToSiteVpn, step, answers).unwrap();
+                (step.to_string(), answers)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_vpn_configs_mirror_each_other() {
+        let answers = vpn_answers();
+        assert_eq!(answers["tunnel"]["encryption"], "aes256");
+        assert_eq!(answers["networks"]["local_prefix"], "10.1.0.0/16");
+
+        let configs = generate_configs(WizardKind::SiteToSiteVpn, &answers).unwrap();
+        assert_eq!(configs.len(), 2);
+        let value = |config: &GeneratedConfig, path: &str| -> Vec<String> {
+            config
+                .sets
+                .iter()
+                .filter(|operation| operation.path == path)
+                .filter_map(|operation| operation.value.clone())
+                .collect()
+        };
+        let peer = "vpn ipsec site-to-site peer branch";
+        assert_eq!(value(&configs[0], &format!("{} local-address", peer)), ["203.0.113.1"]);
+        assert_eq!(value(&configs[1], &format!("{} local-address", peer)), ["198.51.100.1"]);
+        assert_eq!(value(&configs[0], &format!("{} tunnel 1 remote prefix", peer)), ["10.2.0.0/16"]);
+        assert_eq!(value(&configs[1], &format!("{} tunnel 1 remote prefix", peer)), ["10.1.0.0/16"]);
+        assert_eq!(
+            value(&configs[1], "vpn ipsec authentication psk branch id"),
+            ["198.51.100.1", "203.0.113.1"]
+        );
+
+        let current = serde_json::json!({ "vpn": { "ipsec": { "site-to-site": { "peer": { "branch": {} } } } } });
+        let operations = node_operations(&configs[0], &current);
+        assert_eq!(operations[0].op, ConfigOperationKind::Delete);
+        assert_eq!(operations[0].path, peer);
+        assert_eq!(operations.len(), configs[0].sets.len() + 1);
+    }
+
+    #[test]
+    fn test_invalid_steps_are_rejected() {
+        let vpn = WizardKind::SiteToSiteVpn;
+        let same_node = serde_json::json!({
+            "local_node_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "local_address": "203.0.113.1",
+            "remote_node_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "remote_address": "198.51.100.1",
+        });
+        assert!(validate_step(vpn, "endpoints", same_node).is_err());
+        assert!(validate_step(vpn, "tunnel", serde_json::json!({ "name": "a b", "pre_shared_key": "s3cret-key" })).is_err());
+        assert!(validate_step(vpn, "tunnel", serde_json::json!({ "name": "ab", "pre_shared_key": "short" })).is_err());
+        assert!(validate_step(
+            vpn,
+            "networks",
+            serde_json::json!({ "local_prefix": "10.0.0.0/8", "remote_prefix": "10.2.0.0/16" })
+        )
+        .is_err());
+        assert!(matches!(validate_step(vpn, "vrrp", serde_json::json!({})), Err(AppError::NotFound(_))));
+
+        let vrrp = serde_json::json!({ "group": "lan", "interface": "eth1", "vrid": 0, "virtual_address": "192.0.2.1/24" });
+        assert!(validate_step(WizardKind::HaPair, "vrrp", vrrp).is_err());
+    }
+
+    #[test]
+    fn test_ha_primary_has_higher_priority() {
+        let nodes = HaNodesStep {
+            primary_node_id: Uuid::new_v4(),
+            secondary_node_id: Uuid::new_v4(),
+        };
+        let vrrp = HaVrrpStep {
+            group: "lan".to_string(),
+            interface: "eth1".to_string(),
+            vrid: 10,
+            virtual_address: "192.0.2.1/24".to_string(),
+        };
+        let configs = ha_configs(&nodes, &vrrp);
+        assert_eq!(configs[0].node_id, nodes.primary_node_id);
+        let priority = |config: &GeneratedConfig| config.sets.last().unwrap().value.clone().unwrap();
+        assert_eq!(priority(&configs[0]), "200");
+        assert_eq!(priority(&configs[1]), "100");
+        assert_eq!(configs[1].roots, ["high-availability vrrp group lan"]);
+    }
+}
@@ -0,0 +1,60 @@
+//! Client address resolution
+//!
+//! Works out which address a request came from. `X-Forwarded-For` is only
+//! believed when the connection comes from one of the configured
+//! `trusted_proxies`, since any client can send the header itself.
+
+use actix_web::{web, HttpRequest};
+use std::net::{IpAddr, SocketAddr};
+
+use crate::config::AppConfig;
+
+/// Address of the client that sent a request
+///
+/// The peer address, unless the peer is a trusted proxy, in which case the
+/// forwarded chain is walked from the right past any further trusted proxies.
+pub fn client_ip(req: &HttpRequest) -> Option<IpAddr> {
+    let trusted = req
+        .app_data::<web::Data<AppConfig>>()
+        .map(|config| config.trusted_proxies.as_slice())
+        .unwrap_or_default();
+    let forwarded = req.headers().get("X-Forwarded-For").and_then(|value| value.to_str().ok());
+    resolve(req.peer_addr()?.ip(), forwarded, trusted)
+}
+
+/// Resolve the client address from the peer and its forwarded chain
+fn resolve(peer: IpAddr, forwarded: Option<&str>, trusted: &[IpAddr]) -> Option<IpAddr> {
+    if !trusted.contains(&peer) {
+        return Some(peer);
+    }
+    let mut client = peer;
+    for hop in forwarded.into_iter().flat_map(|chain| chain.rsplit(',')) {
+        let hop = hop.trim();
+        match hop.parse::<IpAddr>().ok().or_else(|| hop.parse::<SocketAddr>().ok().map(|addr| addr.ip())) {
+            Some(address) => client = address,
+            None => break,
+        }
+        if !trusted.contains(&client) {
+            break;
+        }
+    }
+    Some(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_client_ip() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let client: IpAddr = "203.0.113.9".parse().unwrap();
+        let chain = Some("198.51.100.7, 203.0.113.9:5123, 10.0.0.1");
+
+        assert_eq!(resolve(client, chain, &[proxy]), Some(client));
+        assert_eq!(resolve(proxy, chain, &[]), Some(proxy));
+        assert_eq!(resolve(proxy, chain, &[proxy]), Some(client));
+        assert_eq!(resolve(proxy, None, &[proxy]), Some(proxy));
+        assert_eq!(resolve(proxy, Some("bogus"), &[proxy]), Some(proxy));
+    }
+}
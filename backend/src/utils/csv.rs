@@ -0,0 +1,64 @@
+//! CSV helpers
+//!
+//! Minimal reading and writing of RFC 4180 CSV, shared by the imports and
+//! exports that deal in it.
+
+/// Write a CSV record, quoting fields that need it
+pub fn csv_line(fields: &[String]) -> String {
+    let quoted: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    format!("{}\n", quoted.join(","))
+}
+
+/// Split CSV text into records, honoring quoted fields
+pub fn csv_records(csv: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_line_round_trips() {
+        let record = vec!["edge-1".to_string(), "Rack 4, row \"B\"".to_string(), "a\nb".to_string(), String::new()];
+        let line = csv_line(&record);
+        assert_eq!(line, "edge-1,\"Rack 4, row \"\"B\"\"\",\"a\nb\",\n");
+        assert_eq!(csv_records(&line), vec![record]);
+    }
+}
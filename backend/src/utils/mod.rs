@@ -0,0 +1,7 @@
+//! Utilities
+//!
+//! Helpers shared by handlers, middleware and services that do not belong
+//! to any one domain.
+
+pub mod client_ip;
+pub mod csv;
@@ -0,0 +1,229 @@
+//! VyOS Capability Matrix
+//!
+//! The HTTP API grew endpoints over the 1.3, 1.4, and 1.5 release trains.
+//! This module maps a reported version string to its release train and lists
+//! which API features that release supports, so callers can check before
+//! issuing a request that an older router would reject. Optional endpoints
+//! are additionally probed on the router itself (see [`PROBED_CAPABILITIES`]),
+//! since they can be disabled or missing from custom builds.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// VyOS release train
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VyOSRelease {
+    /// VyOS 1.3
+    Equuleus,
+    /// VyOS 1.4
+    Sagitta,
+    /// VyOS 1.5 and rolling releases
+    Circinus,
+}
+
+impl VyOSRelease {
+    /// Release train of a version string reported by a router
+    ///
+    /// Accepts plain versions (`1.4.0`), rolling builds
+    /// (`1.5-rolling-202409250007`), and the calendar-versioned rolling
+    /// builds (`2025.01.01-0020-rolling`), which track 1.5.
+    pub fn from_version(version: &str) -> Option<Self> {
+        let version = version.trim().trim_start_matches("VyOS").trim();
+        let mut parts = version.split(|c: char| !c.is_ascii_digit());
+        let major: u32 = parts.next()?.parse().ok()?;
+        let minor: u32 = parts.next()?.parse().ok()?;
+
+        match (major, minor) {
+            (1, 3) => Some(Self::Equuleus),
+            (1, 4) => Some(Self::Sagitta),
+            (1, 5) => Some(Self::Circinus),
+            (major, _) if major >= 2000 => Some(Self::Circinus),
+            _ => None,
+        }
+    }
+
+    /// Release train from its code name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "equuleus" => Some(Self::Equuleus),
+            "sagitta" => Some(Self::Sagitta),
+            "circinus" => Some(Self::Circinus),
+            _ => None,
+        }
+    }
+
+    /// Release train code name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equuleus => "equuleus",
+            Self::Sagitta => "sagitta",
+            Self::Circinus => "circinus",
+        }
+    }
+
+    /// Major and minor version of the release train
+    pub fn version(&self) -> &'static str {
+        match self {
+            Self::Equuleus => "1.3",
+            Self::Sagitta => "1.4",
+            Self::Circinus => "1.5",
+        }
+    }
+}
+
+/// VyOS HTTP API feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// `POST /retrieve`
+    Retrieve,
+    /// `POST /configure`
+    Configure,
+    /// `POST /show`
+    Show,
+    /// `POST /generate`
+    Generate,
+    /// `POST /config-file` (save and load)
+    ConfigFile,
+    /// `POST /image` (add and delete system images)
+    Image,
+    /// `POST /reset`
+    Reset,
+    /// `POST /reboot`
+    Reboot,
+    /// `POST /poweroff`
+    Poweroff,
+    /// `GET /info`
+    Info,
+    /// `commit-confirm` operation on `POST /configure`
+    CommitConfirm,
+    /// `POST /graphql`
+    Graphql,
+}
+
+/// Earliest release supporting each capability
+const MINIMUM_RELEASES: &[(Capability, VyOSRelease)] = &[
+    (Capability::Retrieve, VyOSRelease::Equuleus),
+    (Capability::Configure, VyOSRelease::Equuleus),
+    (Capability::Show, VyOSRelease::Equuleus),
+    (Capability::Generate, VyOSRelease::Equuleus),
+    (Capability::ConfigFile, VyOSRelease::Equuleus),
+    (Capability::Image, VyOSRelease::Equuleus),
+    (Capability::Reset, VyOSRelease::Equuleus),
+    (Capability::Reboot, VyOSRelease::Sagitta),
+    (Capability::Poweroff, VyOSRelease::Sagitta),
+    (Capability::Graphql, VyOSRelease::Sagitta),
+    (Capability::Info, VyOSRelease::Circinus),
+    (Capability::CommitConfirm, VyOSRelease::Circinus),
+];
+
+/// Capabilities whose endpoint is probed on the router
+pub const PROBED_CAPABILITIES: &[Capability] = &[
+    Capability::Info,
+    Capability::Retrieve,
+    Capability::Show,
+    Capability::Image,
+    Capability::Graphql,
+];
+
+impl Capability {
+    /// Capability name as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Retrieve => "retrieve",
+            Self::Configure => "configure",
+            Self::Show => "show",
+            Self::Generate => "generate",
+            Self::ConfigFile => "config_file",
+            Self::Image => "image",
+            Self::Reset => "reset",
+            Self::Reboot => "reboot",
+            Self::Poweroff => "poweroff",
+            Self::Info => "info",
+            Self::CommitConfirm => "commit_confirm",
+            Self::Graphql => "graphql",
+        }
+    }
+
+    /// API endpoint serving this capability, if it has one of its own
+    pub fn endpoint(&self) -> Option<&'static str> {
+        match self {
+            Self::Retrieve => Some("retrieve"),
+            Self::Configure => Some("configure"),
+            Self::Show => Some("show"),
+            Self::Generate => Some("generate"),
+            Self::ConfigFile => Some("config-file"),
+            Self::Image => Some("image"),
+            Self::Reset => Some("reset"),
+            Self::Reboot => Some("reboot"),
+            Self::Poweroff => Some("poweroff"),
+            Self::Info => Some("info"),
+            Self::Graphql => Some("graphql"),
+            Self::CommitConfirm => None,
+        }
+    }
+
+    /// Whether a release supports this capability
+    ///
+    /// Unknown releases are assumed to support the 1.3 baseline only.
+    pub fn supported_by(&self, release: Option<VyOSRelease>) -> bool {
+        let release = release.unwrap_or(VyOSRelease::Equuleus);
+        MINIMUM_RELEASES
+            .iter()
+            .any(|(capability, minimum)| capability == self && release >= *minimum)
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Support of every capability by a release
+pub fn capability_matrix(release: Option<VyOSRelease>) -> BTreeMap<Capability, bool> {
+    MINIMUM_RELEASES
+        .iter()
+        .map(|(capability, _)| (*capability, capability.supported_by(release)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_from_version() {
+        assert_eq!(VyOSRelease::from_version("1.3.8"), Some(VyOSRelease::Equuleus));
+        assert_eq!(VyOSRelease::from_version("VyOS 1.4.0"), Some(VyOSRelease::Sagitta));
+        assert_eq!(VyOSRelease::from_version("1.4-rolling-202310260023"), Some(VyOSRelease::Sagitta));
+        assert_eq!(VyOSRelease::from_version("1.5-rolling-202409250007"), Some(VyOSRelease::Circinus));
+        assert_eq!(VyOSRelease::from_version("2025.01.01-0020-rolling"), Some(VyOSRelease::Circinus));
+        assert_eq!(VyOSRelease::from_version("1.2.9"), None);
+        assert_eq!(VyOSRelease::from_version("unknown"), None);
+    }
+
+    #[test]
+    fn test_capability_matrix() {
+        let sagitta = capability_matrix(Some(VyOSRelease::Sagitta));
+        assert_eq!(sagitta.len(), MINIMUM_RELEASES.len());
+        assert!(sagitta[&Capability::Reboot]);
+        assert!(!sagitta[&Capability::Info]);
+
+        let unknown = capability_matrix(None);
+        assert!(unknown[&Capability::Show]);
+        assert!(!unknown[&Capability::Reboot]);
+
+        assert!(capability_matrix(Some(VyOSRelease::Circinus)).values().all(|supported| *supported));
+    }
+
+    #[test]
+    fn test_capability_names_match_serialization() {
+        for (capability, _) in MINIMUM_RELEASES {
+            assert_eq!(serde_json::to_value(capability).unwrap(), capability.as_str());
+        }
+        assert!(PROBED_CAPABILITIES.iter().all(|c| c.endpoint().is_some()));
+    }
+}
@@ -2,19 +2,36 @@
 //!
 //! This module provides a client for interacting with VyOS REST API endpoints.
 //! It handles HTTP requests, authentication, and certificate verification.
+//! Responses are decoded by [`parsers`], and [`capabilities`] describes which
+//! endpoints each VyOS release supports. Services reach nodes through the
+//! [`transport`] trait, which [`simulated`] nodes implement locally and
+//! [`ssh`] implements for routers without the REST API.
+
+pub mod capabilities;
+pub mod parsers;
+pub mod simulated;
+pub mod ssh;
+pub mod transport;
+
+pub use capabilities::*;
+pub use simulated::*;
+pub use ssh::*;
+pub use transport::*;
 
 use crate::error::AppError;
+use crate::middleware::request_id::{current_trace, REQUEST_ID_HEADER, TRACEPARENT_HEADER};
+use crate::models::redact::sanitized_debug;
 use chrono::{DateTime, Utc};
 use reqwest::Client;
 use reqwest::Certificate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 use uuid::Uuid;
 
 /// VyOS API client configuration
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct VyOSClientConfig {
     /// Base URL for the VyOS API (e.g., "https://10.10.5.51")
     pub base_url: String,
@@ -28,8 +45,12 @@ pub struct VyOSClientConfig {
     pub verify_ssl: bool,
     /// Timeout for API requests in seconds
     pub timeout_secs: u64,
+    /// Login for routers driven over SSH instead of the API
+    pub ssh: Option<SshLogin>,
 }
 
+sanitized_debug!(VyOSClientConfig { base_url, port, use_https, verify_ssl, timeout_secs, ssh } secret { api_key });
+
 impl VyOSClientConfig {
     /// Create a new VyOS client configuration
     pub fn new(
@@ -47,9 +68,16 @@ impl VyOSClientConfig {
             use_https,
             verify_ssl,
             timeout_secs,
+            ssh: None,
         }
     }
 
+    /// Reach the router over SSH with a login
+    pub fn with_ssh(mut self, login: SshLogin) -> Self {
+        self.ssh = Some(login);
+        self
+    }
+
     /// Build the full API URL for a given endpoint
     pub fn build_url(&self, endpoint: &str) -> String {
         let scheme = if self.use_https { "https" } else { "http" };
@@ -97,17 +125,37 @@ impl VyOSClient {
         &self.config
     }
 
+    /// Pass the trace context of the request being handled on to the node
+    ///
+    /// Each call carries the request's `X-Request-Id` and a `traceparent`
+    /// within its trace, so the router's logs can be matched to the UI
+    /// action that caused them.
+    fn traced(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match current_trace() {
+            Some(trace) => builder
+                .header(REQUEST_ID_HEADER, &trace.request_id)
+                .header(TRACEPARENT_HEADER, trace.child_traceparent()),
+            None => builder,
+        }
+    }
+
+    /// Span of one HTTP call to the node
+    fn span(&self, method: &str, endpoint: &str) -> tracing::Span {
+        tracing::info_span!("vyos_http", host = %self.config.base_url, method, endpoint)
+    }
+
     /// Execute an HTTP GET request
     async fn get(&self, endpoint: &str) -> Result<serde_json::Value, AppError> {
         let url = self.config.build_url(endpoint);
         debug!("GET request to: {}", url);
 
         let start = std::time::Instant::now();
-        let response = self.client
-            .get(&url)
+        let response = self
+            .traced(self.client.get(&url))
             .header("Authorization", format!("Bearer {}", self.config.api_key))
             .header("Content-Type", "application/json")
             .send()
+            .instrument(self.span("GET", endpoint))
             .await
             .map_err(|e| AppError::HttpClient(format!("GET request failed: {}", e)))?;
 
@@ -123,8 +171,8 @@ impl VyOSClient {
         debug!("POST request to: {}", url);
 
         let start = std::time::Instant::now();
-        let mut request_builder = self.client
-            .post(&url)
+        let mut request_builder = self
+            .traced(self.client.post(&url))
             .header("Authorization", format!("Bearer {}", self.config.api_key))
             .header("Content-Type", "application/json");
 
@@ -134,6 +182,7 @@ impl VyOSClient {
 
         let response = request_builder
             .send()
+            .instrument(self.span("POST", endpoint))
             .await
             .map_err(|e| AppError::HttpClient(format!("POST request failed: {}", e)))?;
 
@@ -149,8 +198,8 @@ impl VyOSClient {
         debug!("PUT request to: {}", url);
 
         let start = std::time::Instant::now();
-        let mut request_builder = self.client
-            .put(&url)
+        let mut request_builder = self
+            .traced(self.client.put(&url))
             .header("Authorization", format!("Bearer {}", self.config.api_key))
             .header("Content-Type", "application/json");
 
@@ -160,6 +209,7 @@ impl VyOSClient {
 
         let response = request_builder
             .send()
+            .instrument(self.span("PUT", endpoint))
             .await
             .map_err(|e| AppError::HttpClient(format!("PUT request failed: {}", e)))?;
 
@@ -175,10 +225,11 @@ impl VyOSClient {
         debug!("DELETE request to: {}", url);
 
         let start = std::time::Instant::now();
-        let response = self.client
-            .delete(&url)
+        let response = self
+            .traced(self.client.delete(&url))
             .header("Authorization", format!("Bearer {}", self.config.api_key))
             .send()
+            .instrument(self.span("DELETE", endpoint))
             .await
             .map_err(|e| AppError::HttpClient(format!("DELETE request failed: {}", e)))?;
 
@@ -191,6 +242,7 @@ impl VyOSClient {
     /// Handle HTTP response
     async fn handle_response(&self, response: reqwest::Response) -> Result<serde_json::Value, AppError> {
         let status = response.status();
+        let endpoint = response.url().path().to_string();
         let body_text = response
             .text()
             .await
@@ -198,16 +250,53 @@ impl VyOSClient {
 
         debug!("Response status: {}, body length: {}", status, body_text.len());
 
+        if status == reqwest::StatusCode::NOT_FOUND {
+            warn!("Endpoint {} not available: {}", endpoint, body_text);
+            return Err(AppError::Unsupported(format!(
+                "{} is not available on this VyOS node",
+                endpoint
+            )));
+        }
+
         if !status.is_success() {
             error!("API request failed: {} - {}", status, body_text);
+            // Prefer the message from the response envelope over the raw body
+            let message = serde_json::from_str::<serde_json::Value>(&body_text)
+                .ok()
+                .and_then(|body| parsers::envelope_error(&body))
+                .unwrap_or(body_text);
             return Err(AppError::ExternalApi(format!(
                 "VyOS API error: {} - {}",
-                status, body_text
+                status, message
             )));
         }
 
-        serde_json::from_str(&body_text)
-            .map_err(|e| AppError::Internal(format!("Failed to parse JSON response: {}", e)))
+        let body = serde_json::from_str(&body_text)
+            .map_err(|e| AppError::Internal(format!("Failed to parse JSON response: {}", e)))?;
+        parsers::unwrap_envelope(body)
+    }
+
+    /// Execute an HTTP GET request against an endpoint that older releases lack
+    ///
+    /// Returns `None` when the router answers 404.
+    async fn get_optional(&self, endpoint: &str) -> Result<Option<serde_json::Value>, AppError> {
+        let url = self.config.build_url(endpoint);
+        debug!("GET request to: {}", url);
+
+        let response = self.client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.config.api_key))
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(|e| AppError::HttpClient(format!("GET request failed: {}", e)))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            debug!("Endpoint not available: {}", url);
+            return Ok(None);
+        }
+
+        self.handle_response(response).await.map(Some)
     }
 
     // ========================================================================
@@ -217,35 +306,28 @@ impl VyOSClient {
     /// GET /info - Get VyOS system information
     ///
     /// Returns information about the VyOS system including version, hostname, etc.
+    /// Releases before 1.5 have no `/info` endpoint; for those the version and
+    /// host name are read from `show version` and `show host name`.
     pub async fn get_info(&self) -> Result<VyOSInfo, AppError> {
         info!("Getting VyOS system information");
 
-        let response = self.get("info").await?;
+        if let Some(response) = self.get_optional("info").await? {
+            return Ok(parsers::parse_info(&response));
+        }
+
+        let version = self.show("show version").await?;
+        let version = parsers::parse_show_version(&version.output).ok_or_else(|| {
+            AppError::ExternalApi("VyOS API error: unrecognized show version output".to_string())
+        })?;
+        let hostname = self.show("show host name").await?;
 
         Ok(VyOSInfo {
-            hostname: response.get("hostname")
-                .and_then(|v| v.as_str())
-                .unwrap_or("vyos")
-                .to_string(),
-            version: response.get("version")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string(),
-            uptime_seconds: response.get("uptime")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0),
-            boot_time: response.get("boot_time")
-                .and_then(|v| v.as_str())
-                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-                .map(|dt| dt.with_timezone(&Utc)),
-            architecture: response.get("architecture")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string(),
-            kernel_version: response.get("kernel_version")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string(),
+            hostname: hostname.output.trim().to_string(),
+            version: version.version,
+            uptime_seconds: 0,
+            boot_time: None,
+            architecture: version.architecture.unwrap_or_else(|| "unknown".to_string()),
+            kernel_version: "unknown".to_string(),
         })
     }
 
@@ -268,19 +350,7 @@ impl VyOSClient {
         let body = serde_json::json!({ "command": command });
         let response = self.post("show", Some(body)).await?;
 
-        Ok(VyOSShowResult {
-            success: response.get("success")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false),
-            output: response.get("output")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            error: response.get("error")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
-            data: response.get("data").cloned(),
-        })
+        Ok(parsers::parse_show_result(response))
     }
 
     /// POST /configure - Set configuration
@@ -296,6 +366,17 @@ impl VyOSClient {
         self.post("configure", Some(body)).await
     }
 
+    /// POST /configure - Apply a batch of set and delete operations
+    ///
+    /// The router applies and commits the operations as one change, so
+    /// either all of them take effect or none does.
+    pub async fn configure_batch(&self, operations: &[ConfigOperation]) -> Result<(), AppError> {
+        info!("Applying {} configuration operations", operations.len());
+
+        self.post("configure", Some(serde_json::to_value(operations)?)).await?;
+        Ok(())
+    }
+
     /// POST /delete - Delete configuration
     ///
     /// Deletes configuration from the VyOS system.
@@ -333,32 +414,25 @@ impl VyOSClient {
         self.post("discard", None).await
     }
 
-    /// GET /interfaces - Get network interfaces
+    /// Get network interfaces
     ///
-    /// Retrieves information about network interfaces.
+    /// Retrieves information about network interfaces from `show interfaces`,
+    /// since the API has no structured interface endpoint.
     pub async fn get_interfaces(&self) -> Result<Vec<VyOSInterface>, AppError> {
         info!("Getting network interfaces");
 
-        let response = self.get("interfaces").await?;
-
-        if let Some(interfaces) = response.get("interfaces").and_then(|v| v.as_array()) {
-            interfaces
-                .iter()
-                .map(|v| serde_json::from_value(v.clone())
-                    .map_err(|e| AppError::Internal(format!("Failed to parse interface: {}", e))))
-                .collect()
-        } else {
-            Ok(vec![])
-        }
+        let result = self.show("show interfaces").await?;
+        Ok(parsers::parse_show_interfaces(&result.output))
     }
 
     /// POST /reboot - Reboot the system
     ///
-    /// Initiates a system reboot.
+    /// Initiates an immediate system reboot.
     pub async fn reboot(&self) -> Result<serde_json::Value, AppError> {
         info!("Initiating system reboot");
 
-        self.post("reboot", None).await
+        let body = serde_json::json!({ "op": "reboot", "path": ["now"] });
+        self.post("reboot", Some(body)).await
     }
 
     /// POST /poweroff - Power off the system
@@ -370,6 +444,41 @@ impl VyOSClient {
         self.post("poweroff", None).await
     }
 
+    /// Check whether the router serves the endpoint behind a capability
+    ///
+    /// Sends a request without an operation, which the router rejects as
+    /// invalid if it knows the endpoint and answers 404 otherwise, so probing
+    /// never changes the router's state.
+    pub async fn supports(&self, capability: Capability) -> Result<bool, AppError> {
+        let Some(endpoint) = capability.endpoint() else {
+            return Ok(false);
+        };
+        let url = self.config.build_url(endpoint);
+        debug!("Probing endpoint: {}", url);
+
+        let request_builder = if capability == Capability::Info {
+            self.client.get(&url)
+        } else {
+            self.client.post(&url).json(&serde_json::json!({}))
+        };
+        let response = request_builder
+            .header("Authorization", format!("Bearer {}", self.config.api_key))
+            .send()
+            .await
+            .map_err(|e| AppError::HttpClient(format!("Probe request failed: {}", e)))?;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status @ (reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN) => {
+                Err(AppError::ExternalApi(format!(
+                    "VyOS API error: {} - API key rejected",
+                    status
+                )))
+            }
+            _ => Ok(true),
+        }
+    }
+
     /// Test connection to the VyOS API
     ///
     /// Returns latency and basic system information to verify connectivity.
@@ -441,6 +550,53 @@ pub struct VyOSInfo {
     pub kernel_version: String,
 }
 
+/// Kind of a configuration operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigOperationKind {
+    Set,
+    Delete,
+}
+
+/// One operation of a `POST /configure` batch
+///
+/// As in the VyOS API, the value of a set operation is the last element of
+/// its path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigOperation {
+    pub op: ConfigOperationKind,
+    pub path: Vec<String>,
+}
+
+impl ConfigOperation {
+    /// Set a path, with the value as its last element
+    pub fn set(path: Vec<String>) -> Self {
+        Self { op: ConfigOperationKind::Set, path }
+    }
+
+    /// Delete a path and everything below it
+    pub fn delete(path: Vec<String>) -> Self {
+        Self { op: ConfigOperationKind::Delete, path }
+    }
+
+    /// The operation as a configuration-mode command, quoting words with
+    /// spaces or quotes
+    pub fn command(&self) -> String {
+        let verb = match self.op {
+            ConfigOperationKind::Set => "set",
+            ConfigOperationKind::Delete => "delete",
+        };
+        let words = self.path.iter().map(|word| {
+            if word.is_empty() || word.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"') {
+                format!("'{}'", word.replace('\'', r"'\''"))
+            } else {
+                word.clone()
+            }
+        });
+        std::iter::once(verb.to_string()).chain(words).collect::<Vec<_>>().join(" ")
+    }
+}
+
 /// VyOS show command result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VyOSShowResult {
@@ -457,13 +613,135 @@ pub struct VyOSInterface {
     pub description: Option<String>,
     pub address: Option<String>,
     pub netmask: Option<String>,
+    /// All addresses of the interface, including `address`
+    #[serde(default)]
+    pub addresses: Vec<String>,
     pub mac_address: Option<String>,
+    /// Vendor of the MAC address, filled in when interfaces are listed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
     pub is_up: bool,
     pub mtu: Option<u32>,
     pub speed: Option<String>,
     pub duplex: Option<String>,
 }
 
+/// Neighbor cache entry, from the IPv6 neighbor or the ARP table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VyOSNeighbor {
+    pub address: String,
+    pub interface: String,
+    /// Link-layer address, unknown for incomplete or failed entries
+    pub mac_address: Option<String>,
+    /// Whether the neighbor announced itself as a router
+    pub router: bool,
+    /// Neighbor unreachability detection state, e.g. `REACHABLE` or `STALE`
+    pub state: String,
+}
+
+/// Neighbor announced over LLDP
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VyOSLldpNeighbor {
+    /// System name the neighbor announced
+    pub device: String,
+    /// Local interface the neighbor was seen on
+    pub interface: String,
+    /// Capability codes, e.g. `R` for a router or `B` for a bridge
+    pub capabilities: Option<String>,
+    pub platform: Option<String>,
+    /// Neighbor's port the link ends on
+    pub port: Option<String>,
+}
+
+/// Peer of a WireGuard interface
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VyOSWireGuardPeer {
+    pub public_key: String,
+    /// Address and port the peer was last seen at
+    pub endpoint: Option<String>,
+    /// Seconds since the latest handshake with the peer, if there was one
+    pub latest_handshake_secs: Option<u64>,
+}
+
+/// VRRP group of a node, as `show vrrp` lists it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VyOSVrrpGroup {
+    pub name: String,
+    pub interface: String,
+    pub vrid: u8,
+    /// `MASTER`, `BACKUP`, `FAULT`, or `INIT`
+    pub state: String,
+    pub priority: u8,
+    /// Seconds since the group last changed state
+    pub last_transition_secs: Option<u64>,
+}
+
+/// Connections in one of the caches of conntrack-sync
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VyOSConntrackSyncCache {
+    pub active: u64,
+    pub created: u64,
+    pub updated: u64,
+    pub destroyed: u64,
+    /// Creations, updates, and removals that failed
+    pub failed: u64,
+}
+
+/// Statistics of conntrack-sync, which copies the connection table to the
+/// other node of an HA pair
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VyOSConntrackSyncStatistics {
+    /// Connections of the node itself
+    pub internal: VyOSConntrackSyncCache,
+    /// Connections received from the peer
+    pub external: VyOSConntrackSyncCache,
+    /// Interface the synchronization traffic goes through
+    pub sync_interface: Option<String>,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub packets_sent: u64,
+    pub packets_received: u64,
+    pub send_errors: u64,
+    pub receive_errors: u64,
+    /// Messages from the peer that were lost in transit
+    pub lost_messages: u64,
+    pub malformed_messages: u64,
+}
+
+/// Link state and addresses of an interface, as `show interfaces <kind>
+/// <name>` prints them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VyOSLinkStatus {
+    pub is_up: bool,
+    pub mtu: Option<u32>,
+    /// Global addresses, with their prefix length if printed
+    pub addresses: Vec<String>,
+    /// Remote end of a point-to-point link
+    pub peer_address: Option<String>,
+}
+
+/// Current PPP session of an interface, from the log of its PPP daemon
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VyOSPppSession {
+    /// When the session got its addresses
+    pub connected_since: Option<DateTime<Utc>>,
+    /// `CHAP` or `PAP`
+    pub auth_protocol: Option<String>,
+}
+
+/// State of the modem of a WWAN interface, as ModemManager reports it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VyOSModemStatus {
+    /// e.g. `connected` or `registered`
+    pub state: Option<String>,
+    /// Radio access technology, e.g. `lte`
+    pub access_technology: Option<String>,
+    /// Signal quality in percent
+    pub signal_quality: Option<u8>,
+    /// Name of the mobile network
+    pub operator: Option<String>,
+}
+
 /// VyOS image information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VyOSImage {
@@ -500,6 +778,7 @@ mod tests {
             use_https: true,
             verify_ssl: false,
             timeout_secs: 30,
+            ssh: None,
         };
 
         assert_eq!(
@@ -522,6 +801,7 @@ mod tests {
             use_https: false,
             verify_ssl: false,
             timeout_secs: 30,
+            ssh: None,
         };
 
         assert_eq!(
@@ -0,0 +1,1053 @@
+//! VyOS Response Parsers
+//!
+//! Pure functions turning VyOS API payloads into typed values. VyOS wraps
+//! every response in a `{"success", "data", "error"}` envelope, and most
+//! operational data only exists as the text output of `show` commands, whose
+//! layout changes between releases. Keeping the parsing here lets the
+//! versioned fixtures under `tests/fixtures/vyos/` exercise it directly.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+
+use super::{
+    VyOSConntrackSyncStatistics, VyOSInfo, VyOSInterface, VyOSLinkStatus, VyOSLldpNeighbor, VyOSModemStatus,
+    VyOSNeighbor, VyOSPppSession, VyOSShowResult, VyOSVrrpGroup, VyOSWireGuardPeer,
+};
+use crate::error::AppError;
+
+/// Unwrap the VyOS response envelope
+///
+/// Returns the `data` member of a successful response and turns
+/// `"success": false` into an error. Payloads without an envelope are
+/// returned unchanged.
+pub fn unwrap_envelope(response: serde_json::Value) -> Result<serde_json::Value, AppError> {
+    let is_envelope = response.get("success").is_some_and(|v| v.is_boolean())
+        && response.get("data").is_some();
+    if !is_envelope {
+        return Ok(response);
+    }
+
+    if response["success"].as_bool() == Some(true) {
+        Ok(response["data"].clone())
+    } else {
+        Err(AppError::ExternalApi(format!(
+            "VyOS API error: {}",
+            envelope_error(&response).unwrap_or_else(|| "unknown error".to_string())
+        )))
+    }
+}
+
+/// Error message of a failed VyOS response envelope
+pub fn envelope_error(response: &serde_json::Value) -> Option<String> {
+    response
+        .get("error")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Parse the `/info` endpoint payload (VyOS 1.5 and later)
+pub fn parse_info(data: &serde_json::Value) -> VyOSInfo {
+    let text = |key: &str, default: &str| {
+        data.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or(default)
+            .to_string()
+    };
+
+    VyOSInfo {
+        hostname: text("hostname", "vyos"),
+        version: text("version", "unknown"),
+        uptime_seconds: data.get("uptime").and_then(|v| v.as_u64()).unwrap_or(0),
+        boot_time: data
+            .get("boot_time")
+            .and_then(|v| v.as_str())
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc)),
+        architecture: text("architecture", "unknown"),
+        kernel_version: text("kernel_version", "unknown"),
+    }
+}
+
+/// Parse a `/show` response
+///
+/// VyOS returns the command output as a string; structured payloads are kept
+/// in `data`.
+pub fn parse_show_result(data: serde_json::Value) -> VyOSShowResult {
+    match data {
+        serde_json::Value::String(output) => VyOSShowResult {
+            success: true,
+            output,
+            error: None,
+            data: None,
+        },
+        data => VyOSShowResult {
+            success: data.get("success").and_then(|v| v.as_bool()).unwrap_or(true),
+            output: data
+                .get("output")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            error: data.get("error").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            data: Some(data),
+        },
+    }
+}
+
+/// Fields of `show version` output
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowVersion {
+    /// Version without the `VyOS` prefix (e.g. `1.4.0`)
+    pub version: String,
+    /// Release train (e.g. `sagitta`)
+    pub release_train: Option<String>,
+    pub architecture: Option<String>,
+    pub built_on: Option<String>,
+}
+
+/// Parse `show version` output
+pub fn parse_show_version(output: &str) -> Option<ShowVersion> {
+    let fields: HashMap<&str, &str> = output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+
+    let version = fields.get("Version")?;
+    let field = |key: &str| fields.get(key).map(|v| v.to_string());
+
+    Some(ShowVersion {
+        version: version.trim_start_matches("VyOS").trim().to_string(),
+        release_train: field("Release train"),
+        architecture: field("Architecture"),
+        built_on: field("Built on"),
+    })
+}
+
+/// Parse `show date utc` output, e.g. `Fri Oct 16 17:29:13 UTC 2026`
+///
+/// Only UTC times are understood; the abbreviations `date` prints for
+/// other zones are ambiguous.
+pub fn parse_show_date(output: &str) -> Option<DateTime<Utc>> {
+    let output = output.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(output) {
+        return Some(time.with_timezone(&Utc));
+    }
+
+    let words: Vec<&str> = output
+        .split_whitespace()
+        .filter(|word| !matches!(*word, "UTC" | "GMT"))
+        .collect();
+    if words.len() + 1 != output.split_whitespace().count() {
+        return None;
+    }
+    NaiveDateTime::parse_from_str(&words.join(" "), "%a %b %d %H:%M:%S %Y")
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+/// Parse `show interfaces` output
+///
+/// Column positions are taken from the dashed line under the header, so both
+/// the 1.3/1.4 layout and the 1.5 layout with MAC, VRF, and MTU columns are
+/// understood. Indented lines carry additional addresses of the interface
+/// above them.
+pub fn parse_show_interfaces(output: &str) -> Vec<VyOSInterface> {
+    let lines: Vec<&str> = output.lines().collect();
+    let Some(header_index) = lines.iter().position(|l| l.starts_with("Interface")) else {
+        return vec![];
+    };
+    let Some(rule) = lines.get(header_index + 1) else {
+        return vec![];
+    };
+
+    let spans = column_spans(rule);
+    let header = lines[header_index];
+    let columns: Vec<String> = spans
+        .iter()
+        .map(|&(start, end)| slice_column(header, start, end).to_ascii_lowercase())
+        .collect();
+
+    let mut interfaces: Vec<VyOSInterface> = vec![];
+    for line in &lines[header_index + 2..] {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let values: HashMap<&str, &str> = columns
+            .iter()
+            .zip(&spans)
+            .enumerate()
+            .map(|(i, (name, &(start, end)))| {
+                // The last column runs to the end of the line
+                let end = if i + 1 == spans.len() { usize::MAX } else { end };
+                (name.as_str(), slice_column(line, start, end))
+            })
+            .collect();
+        let address = values
+            .get("ip address")
+            .filter(|a| !a.is_empty() && **a != "-")
+            .map(|a| a.to_string());
+
+        if line.starts_with(char::is_whitespace) {
+            // Continuation line with another address
+            if let (Some(interface), Some(address)) = (interfaces.last_mut(), address) {
+                interface.addresses.push(address);
+            }
+            continue;
+        }
+
+        let non_empty = |key: &str| values.get(key).filter(|v| !v.is_empty()).map(|v| v.to_string());
+        interfaces.push(VyOSInterface {
+            name: values.get("interface").unwrap_or(&"").to_string(),
+            description: non_empty("description"),
+            netmask: address.as_deref().and_then(ipv4_netmask),
+            address: address.clone(),
+            addresses: address.into_iter().collect(),
+            mac_address: non_empty("mac"),
+            vendor: None,
+            is_up: values.get("s/l").is_some_and(|s| *s == "u/u"),
+            mtu: values.get("mtu").and_then(|m| m.parse().ok()),
+            speed: None,
+            duplex: None,
+        });
+    }
+
+    interfaces
+}
+
+/// Parse `show ipv6 neighbors` output
+///
+/// Older releases print the `ip -6 neighbor` lines as they are, e.g.
+/// `fe80::1 dev eth0 lladdr 52:54:00:12:34:56 router REACHABLE`; newer
+/// ones print a table with address, interface, link-layer address, and
+/// state columns.
+pub fn parse_show_ipv6_neighbors(output: &str) -> Vec<VyOSNeighbor> {
+    parse_neighbor_table(output, parse_ip_neighbor)
+}
+
+/// Parse `show arp` output
+///
+/// Newer releases print the same table as `show ipv6 neighbors`; older ones
+/// print either `ip -4 neighbor` lines or the `arp -n` table, e.g.
+/// `192.0.2.10  ether  52:54:00:12:34:56  C  eth0`.
+pub fn parse_show_arp(output: &str) -> Vec<VyOSNeighbor> {
+    parse_neighbor_table(output, |line| parse_ip_neighbor(line).or_else(|| parse_arp_entry(line)))
+}
+
+/// Parse `show lldp neighbors` output
+///
+/// The table under the capability code legend has device, local interface,
+/// protocol, capability, platform, and port columns. Platforms and device
+/// names may hold spaces, so each column runs up to the next one.
+pub fn parse_show_lldp_neighbors(output: &str) -> Vec<VyOSLldpNeighbor> {
+    let lines: Vec<&str> = output.lines().collect();
+    let Some(rule_index) = lines.iter().position(|line| line.starts_with("---")).filter(|index| *index > 0) else {
+        return vec![];
+    };
+
+    let spans = column_spans(lines[rule_index]);
+    let header = lines[rule_index - 1];
+    let columns: Vec<String> = spans
+        .iter()
+        .map(|&(start, end)| slice_column(header, start, end).to_ascii_lowercase())
+        .collect();
+    let column = |line: &str, name: &str| -> Option<String> {
+        let index = columns.iter().position(|column| column.starts_with(name))?;
+        let start = spans[index].0;
+        let end = spans.get(index + 1).map_or(usize::MAX, |next| next.0.saturating_sub(1));
+        let value = line.get(start.min(line.len())..end.min(line.len()))?.trim();
+        Some(value.to_string()).filter(|value| !value.is_empty() && value != "-")
+    };
+
+    lines[rule_index + 1..]
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            Some(VyOSLldpNeighbor {
+                device: column(line, "device")?,
+                interface: column(line, "local")?,
+                capabilities: column(line, "cap"),
+                platform: column(line, "platform"),
+                port: column(line, "port"),
+            })
+        })
+        .collect()
+}
+
+/// Parse a neighbor table, or the lines of a command printing one neighbor
+/// per line without a table
+fn parse_neighbor_table(output: &str, parse_line: impl Fn(&str) -> Option<VyOSNeighbor>) -> Vec<VyOSNeighbor> {
+    let lines: Vec<&str> = output.lines().collect();
+    let rule = lines
+        .iter()
+        .position(|line| line.starts_with("---"))
+        .filter(|index| *index > 0);
+
+    let Some(rule_index) = rule else {
+        return lines.iter().filter_map(|line| parse_line(line)).collect();
+    };
+
+    let spans = column_spans(lines[rule_index]);
+    let header = lines[rule_index - 1];
+    let columns: Vec<String> = spans
+        .iter()
+        .map(|&(start, end)| slice_column(header, start, end).to_ascii_lowercase())
+        .collect();
+    let column = |line: &str, name: &str| -> Option<String> {
+        let index = columns.iter().position(|column| column.contains(name))?;
+        let (start, end) = spans[index];
+        let end = if index + 1 == spans.len() { usize::MAX } else { end };
+        Some(slice_column(line, start, end).to_string()).filter(|value| !value.is_empty() && value != "-")
+    };
+
+    lines[rule_index + 1..]
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let state = column(line, "state").unwrap_or_default();
+            Some(VyOSNeighbor {
+                address: column(line, "address")?,
+                interface: column(line, "interface")?,
+                mac_address: column(line, "link"),
+                router: state.contains("router"),
+                state: state.split_whitespace().last().unwrap_or("UNKNOWN").to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parse `show interfaces wireguard <interface> summary` output
+///
+/// The command prints `wg show` for the interface: a `peer: <public key>`
+/// line per peer, followed by indented `key: value` lines such as
+/// `endpoint: 198.51.100.1:51820` and `latest handshake: 1 minute, 5
+/// seconds ago`. Peers that never completed a handshake have no such line.
+pub fn parse_show_wireguard_summary(output: &str) -> Vec<VyOSWireGuardPeer> {
+    let mut peers: Vec<VyOSWireGuardPeer> = vec![];
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "peer" => peers.push(VyOSWireGuardPeer {
+                public_key: value.to_string(),
+                endpoint: None,
+                latest_handshake_secs: None,
+            }),
+            "endpoint" => {
+                if let Some(peer) = peers.last_mut() {
+                    peer.endpoint = Some(value.to_string()).filter(|endpoint| endpoint != "(none)");
+                }
+            }
+            "latest handshake" => {
+                if let Some(peer) = peers.last_mut() {
+                    peer.latest_handshake_secs = parse_handshake_age(value);
+                }
+            }
+            _ => {}
+        }
+    }
+    peers
+}
+
+/// Seconds in a `wg show` handshake age, e.g. `1 hour, 2 minutes, 5 seconds
+/// ago` or `Now`
+fn parse_handshake_age(age: &str) -> Option<u64> {
+    if age.eq_ignore_ascii_case("now") {
+        return Some(0);
+    }
+
+    let mut secs = 0;
+    for part in age.trim_end_matches("ago").split(',') {
+        let mut words = part.split_whitespace();
+        let count: u64 = words.next()?.parse().ok()?;
+        let unit = match words.next()?.trim_end_matches('s') {
+            "day" => 86_400,
+            "hour" => 3600,
+            "minute" => 60,
+            "second" => 1,
+            _ => return None,
+        };
+        secs += count * unit;
+    }
+    Some(secs)
+}
+
+/// Parse `show vrrp` output
+///
+/// The table has name, interface, VRID, state, priority, and last
+/// transition columns, the transition as an age such as `1h2m5s`. Nodes
+/// without VRRP print a notice instead of the table.
+pub fn parse_show_vrrp(output: &str) -> Vec<VyOSVrrpGroup> {
+    let lines: Vec<&str> = output.lines().collect();
+    let Some(rule_index) = lines.iter().position(|line| line.starts_with("---")).filter(|index| *index > 0) else {
+        return vec![];
+    };
+
+    let spans = column_spans(lines[rule_index]);
+    let header = lines[rule_index - 1];
+    let columns: Vec<String> = spans
+        .iter()
+        .map(|&(start, end)| slice_column(header, start, end).to_ascii_lowercase())
+        .collect();
+    let column = |line: &str, name: &str| -> Option<String> {
+        let index = columns.iter().position(|column| column.starts_with(name))?;
+        let (start, end) = spans[index];
+        let end = if index + 1 == spans.len() { usize::MAX } else { end };
+        Some(slice_column(line, start, end).to_string()).filter(|value| !value.is_empty())
+    };
+
+    lines[rule_index + 1..]
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            Some(VyOSVrrpGroup {
+                name: column(line, "name")?,
+                interface: column(line, "interface")?,
+                vrid: column(line, "vrid")?.parse().ok()?,
+                state: column(line, "state")?.to_ascii_uppercase(),
+                priority: column(line, "priority")?.parse().ok()?,
+                last_transition_secs: column(line, "last").and_then(|age| parse_transition_age(&age)),
+            })
+        })
+        .collect()
+}
+
+/// Seconds in a VRRP transition age, e.g. `1d2h3m4s`
+fn parse_transition_age(age: &str) -> Option<u64> {
+    let mut secs = 0;
+    let mut count = String::new();
+    for c in age.chars() {
+        if c.is_ascii_digit() {
+            count.push(c);
+            continue;
+        }
+        let unit = match c {
+            'd' => 86_400,
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        secs += count.parse::<u64>().ok()? * unit;
+        count.clear();
+    }
+    count.is_empty().then_some(secs)
+}
+
+/// Parse `show conntrack-sync statistics` output
+///
+/// The command prints the statistics of `conntrackd`: sections such as
+/// `cache internal:` with `connections created:  71  failed:  0` lines, and
+/// traffic sections whose lines pair counts with labels, e.g.
+/// `17296 Bytes sent  2512 Bytes recv`. The traffic section names the
+/// synchronization interface, e.g. `multicast traffic (active device=eth1):`.
+pub fn parse_show_conntrack_sync_statistics(output: &str) -> Option<VyOSConntrackSyncStatistics> {
+    let mut statistics = VyOSConntrackSyncStatistics::default();
+    let mut section = "";
+    let mut found = false;
+
+    for line in output.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if line.ends_with(':') && !line.starts_with(|c: char| c.is_ascii_digit()) {
+            section = if line.starts_with("cache internal") {
+                "internal"
+            } else if line.starts_with("cache external") {
+                "external"
+            } else {
+                if let Some((_, device)) = line.split_once("device=") {
+                    statistics.sync_interface = device.split(')').next().map(str::to_string);
+                }
+                ""
+            };
+            continue;
+        }
+
+        let cache = match section {
+            "internal" => Some(&mut statistics.internal),
+            "external" => Some(&mut statistics.external),
+            _ => None,
+        };
+        if let Some(cache) = cache {
+            for (label, count) in labelled_counts(line) {
+                found = true;
+                match label.as_str() {
+                    "current active connections" => cache.active = count,
+                    "connections created" => cache.created = count,
+                    "connections updated" => cache.updated = count,
+                    "connections destroyed" => cache.destroyed = count,
+                    "failed" => cache.failed += count,
+                    _ => {}
+                }
+            }
+            continue;
+        }
+
+        for (label, count) in counted_labels(line) {
+            found = true;
+            match label.to_ascii_lowercase().as_str() {
+                "bytes sent" => statistics.bytes_sent = count,
+                "bytes recv" => statistics.bytes_received = count,
+                "pckt sent" => statistics.packets_sent = count,
+                "pckt recv" => statistics.packets_received = count,
+                "error send" => statistics.send_errors = count,
+                "error recv" => statistics.receive_errors = count,
+                "lost msgs" => statistics.lost_messages = count,
+                "malformed msgs" => statistics.malformed_messages = count,
+                _ => {}
+            }
+        }
+    }
+
+    found.then_some(statistics)
+}
+
+/// `label: count` pairs of a line, e.g. `connections created: 71 failed: 0`
+fn labelled_counts(line: &str) -> Vec<(String, u64)> {
+    let parts: Vec<&str> = line.split(':').collect();
+    let mut pairs = vec![];
+    let mut label = parts[0].trim().to_string();
+    for part in &parts[1..] {
+        let mut words = part.split_whitespace();
+        let Some(count) = words.next().and_then(|count| count.parse().ok()) else {
+            break;
+        };
+        pairs.push((label, count));
+        label = words.collect::<Vec<_>>().join(" ");
+    }
+    pairs
+}
+
+/// Counts of a line each followed by its label, e.g. `17296 Bytes sent 2512
+/// Bytes recv`
+fn counted_labels(line: &str) -> Vec<(String, u64)> {
+    let mut pairs: Vec<(String, u64)> = vec![];
+    for word in line.split_whitespace() {
+        match word.parse() {
+            Ok(count) => pairs.push((String::new(), count)),
+            Err(_) => {
+                if let Some((label, _)) = pairs.last_mut() {
+                    if !label.is_empty() {
+                        label.push(' ');
+                    }
+                    label.push_str(word);
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// Parse `show interfaces <kind> <name>` output of one interface
+///
+/// The command prints `ip address show` for the interface, e.g.
+/// `pppoe0: <POINTOPOINT,MULTICAST,NOARP,UP,LOWER_UP> mtu 1492 ...` followed
+/// by `inet 198.51.100.7 peer 198.51.100.1/32 scope global pppoe0` lines,
+/// then its description and counters. Link-local addresses are left out.
+pub fn parse_show_interface_link(output: &str) -> Option<VyOSLinkStatus> {
+    let mut lines = output.lines().skip_while(|line| !line.contains(": <"));
+    let header = lines.next()?;
+    let flags = header.split_once('<')?.1.split_once('>')?.0;
+    let words: Vec<&str> = header.split_whitespace().collect();
+    let mut status = VyOSLinkStatus {
+        is_up: flags.split(',').any(|flag| flag == "LOWER_UP"),
+        mtu: words
+            .iter()
+            .position(|word| *word == "mtu")
+            .and_then(|index| words.get(index + 1)?.parse().ok()),
+        ..Default::default()
+    };
+
+    for line in lines {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if !matches!(words.first(), Some(&"inet") | Some(&"inet6")) || words.contains(&"link") {
+            continue;
+        }
+        let Some(address) = words.get(1) else {
+            continue;
+        };
+        status.addresses.push(address.to_string());
+        if words.get(2) == Some(&"peer") {
+            status.peer_address = words.get(3).map(|peer| peer.split('/').next().unwrap_or(peer).to_string());
+        }
+    }
+    Some(status)
+}
+
+/// Parse the link speed in Mbps from `show interfaces ethernet <name>
+/// physical` output
+///
+/// The command prints `ethtool` for the interface, with a `Speed: 1000Mb/s`
+/// line; links that are down report `Speed: Unknown!`.
+pub fn parse_ethtool_speed(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Speed:"))?
+        .trim()
+        .strip_suffix("Mb/s")?
+        .parse()
+        .ok()
+}
+
+/// Parse `show interfaces pppoe <name> log` output
+///
+/// The log holds the lines of `pppd`, each starting with a syslog
+/// timestamp without a year, e.g. `Oct  6 07:09:03 vyos pppd[2121]: local
+/// IP address 198.51.100.7`. A session is established once the local
+/// address is assigned and ends with `Connection terminated` or `Modem
+/// hangup`; timestamps are taken to be UTC and in the year up to `now`.
+pub fn parse_ppp_log(output: &str, now: DateTime<Utc>) -> VyOSPppSession {
+    let mut session = VyOSPppSession::default();
+    let mut auth_protocol = None;
+    for line in output.lines() {
+        let Some((_, message)) = line.split_once("]: ") else {
+            continue;
+        };
+        let message = message.trim();
+        if let Some(protocol) = message.strip_suffix(" authentication succeeded") {
+            auth_protocol = Some(protocol.to_ascii_uppercase());
+        } else if message.starts_with("local  IP address") || message.starts_with("local LL address") {
+            if session.connected_since.is_none() {
+                session.connected_since = parse_syslog_timestamp(line, now);
+                session.auth_protocol = auth_protocol.clone();
+            }
+        } else if message.starts_with("Connection terminated") || message.starts_with("Modem hangup") {
+            session = VyOSPppSession::default();
+            auth_protocol = None;
+        }
+    }
+    session
+}
+
+/// Timestamp at the start of a syslog line, e.g. `Oct  6 07:09:03`
+fn parse_syslog_timestamp(line: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let words: Vec<&str> = line.split_whitespace().take(3).collect();
+    let stamp = words.join(" ");
+    let parse = |year: i32| {
+        NaiveDateTime::parse_from_str(&format!("{} {}", year, stamp), "%Y %b %d %H:%M:%S")
+            .ok()
+            .map(|time| time.and_utc())
+    };
+    let time = parse(now.year())?;
+    if time > now {
+        parse(now.year() - 1)
+    } else {
+        Some(time)
+    }
+}
+
+/// Parse `show interfaces wwan <name> details` output
+///
+/// The command prints `mmcli` for the interface's modem: sections of
+/// `key: value` lines behind a `|`, e.g. `Status | state: connected` and
+/// `signal quality: 67% (recent)`.
+pub fn parse_show_wwan_details(output: &str) -> VyOSModemStatus {
+    let mut status = VyOSModemStatus::default();
+    for line in output.lines() {
+        let field = line.split_once('|').map_or(line, |(_, field)| field);
+        let Some((key, value)) = field.split_once(':') else {
+            continue;
+        };
+        let value = Some(value.trim().to_string()).filter(|value| !value.is_empty() && value != "--");
+        match key.trim() {
+            "state" => status.state = value,
+            "access tech" => status.access_technology = value,
+            "signal quality" => {
+                status.signal_quality = value.and_then(|value| value.split('%').next()?.trim().parse().ok())
+            }
+            "operator name" => status.operator = value,
+            _ => {}
+        }
+    }
+    status
+}
+
+/// Parse one `ip neighbor` line
+fn parse_ip_neighbor(line: &str) -> Option<VyOSNeighbor> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let address = words.first()?.parse::<std::net::IpAddr>().ok()?;
+    let after = |key: &str| {
+        let index = words.iter().position(|word| *word == key)?;
+        words.get(index + 1).map(|word| word.to_string())
+    };
+
+    Some(VyOSNeighbor {
+        address: address.to_string(),
+        interface: after("dev")?,
+        mac_address: after("lladdr"),
+        router: words.contains(&"router"),
+        state: words.last().filter(|_| words.len() > 1).unwrap_or(&"UNKNOWN").to_string(),
+    })
+}
+
+/// Parse one line of the `arp -n` table, whose entries without a
+/// link-layer address read `(incomplete)`
+fn parse_arp_entry(line: &str) -> Option<VyOSNeighbor> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let address = words.first()?.parse::<std::net::Ipv4Addr>().ok()?;
+    let incomplete = words.get(1) == Some(&"(incomplete)");
+
+    Some(VyOSNeighbor {
+        address: address.to_string(),
+        interface: words.last().filter(|_| words.len() > 2)?.to_string(),
+        mac_address: if incomplete { None } else { words.get(2).map(|mac| mac.to_string()) },
+        router: false,
+        state: if incomplete { "INCOMPLETE" } else { "REACHABLE" }.to_string(),
+    })
+}
+
+/// Character ranges of the dash runs in a table rule line
+fn column_spans(rule: &str) -> Vec<(usize, usize)> {
+    let mut spans = vec![];
+    let mut start = None;
+
+    for (i, c) in rule.char_indices() {
+        match (c == '-', start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, rule.len()));
+    }
+
+    spans
+}
+
+/// Text of a line between two byte offsets, trimmed
+///
+/// Values may overflow their column (right-aligned numbers, long names), so
+/// the slice is widened to the surrounding whitespace.
+fn slice_column(line: &str, start: usize, end: usize) -> &str {
+    if start >= line.len() {
+        return "";
+    }
+
+    let bytes = line.as_bytes();
+    let mut start = start;
+    let mut end = end.min(line.len());
+    while start > 0 && !bytes[start - 1].is_ascii_whitespace() {
+        start -= 1;
+    }
+    while end < line.len() && !bytes[end].is_ascii_whitespace() {
+        end += 1;
+    }
+    line.get(start..end).unwrap_or("").trim()
+}
+
+/// Dotted netmask of an IPv4 CIDR address
+fn ipv4_netmask(cidr: &str) -> Option<String> {
+    let (address, prefix) = cidr.split_once('/')?;
+    address.parse::<std::net::Ipv4Addr>().ok()?;
+    let prefix: u32 = prefix.parse().ok().filter(|p| *p <= 32)?;
+    let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+    Some(std::net::Ipv4Addr::from(mask).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vyos_client::VyOSConntrackSyncCache;
+    use serde_json::json;
+
+    #[test]
+    fn test_unwrap_envelope() {
+        let data = unwrap_envelope(json!({ "success": true, "data": "ok", "error": null })).unwrap();
+        assert_eq!(data, json!("ok"));
+
+        let err = unwrap_envelope(json!({ "success": false, "data": null, "error": "\n bad path \n" }));
+        assert_eq!(err.unwrap_err().to_string(), "External API error: VyOS API error: bad path");
+
+        let legacy = json!({ "hostname": "vyos" });
+        assert_eq!(unwrap_envelope(legacy.clone()).unwrap(), legacy);
+    }
+
+    #[test]
+    fn test_slice_column_widens_overflowing_values() {
+        let line = "lo           127.0.0.1/8     00:00:00:00:00:00  default  65536  u/u";
+        assert_eq!(slice_column(line, 58, 62), "65536");
+        assert_eq!(slice_column(line, 13, 27), "127.0.0.1/8");
+        assert_eq!(slice_column(line, 71, 84), "");
+    }
+
+    #[test]
+    fn test_parse_show_ipv6_neighbors() {
+        let raw = "fe80::1 dev eth0 lladdr 52:54:00:12:34:56 router REACHABLE\n\
+                   2001:db8::20 dev eth1 lladdr 52:54:00:ab:cd:ef STALE\n\
+                   2001:db8::30 dev eth1  FAILED\n";
+        let neighbors = parse_show_ipv6_neighbors(raw);
+        assert_eq!(neighbors.len(), 3);
+        assert_eq!(neighbors[0].interface, "eth0");
+        assert_eq!(neighbors[0].mac_address.as_deref(), Some("52:54:00:12:34:56"));
+        assert!(neighbors[0].router);
+        assert_eq!((neighbors[1].state.as_str(), neighbors[1].router), ("STALE", false));
+        assert_eq!((neighbors[2].mac_address.as_ref(), neighbors[2].state.as_str()), (None, "FAILED"));
+
+        let table = "Address                  Interface    Link layer address    State\n\
+                     -----------------------  -----------  --------------------  ---------\n\
+                     fe80::5054:ff:fe12:3456  eth1         52:54:00:12:34:56     REACHABLE\n\
+                     2001:db8::99             eth1         -                     INCOMPLETE\n";
+        let neighbors = parse_show_ipv6_neighbors(table);
+        assert_eq!(
+            neighbors,
+            [
+                VyOSNeighbor {
+                    address: "fe80::5054:ff:fe12:3456".to_string(),
+                    interface: "eth1".to_string(),
+                    mac_address: Some("52:54:00:12:34:56".to_string()),
+                    router: false,
+                    state: "REACHABLE".to_string(),
+                },
+                VyOSNeighbor {
+                    address: "2001:db8::99".to_string(),
+                    interface: "eth1".to_string(),
+                    mac_address: None,
+                    router: false,
+                    state: "INCOMPLETE".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_show_arp() {
+        let table = "Address        Interface    Link layer address    State\n\
+                     -------------  -----------  --------------------  ---------\n\
+                     192.0.2.10     eth0         52:54:00:12:34:56     REACHABLE\n";
+        let neighbors = parse_show_arp(table);
+        assert_eq!(neighbors.len(), 1);
+        assert_eq!((neighbors[0].address.as_str(), neighbors[0].interface.as_str()), ("192.0.2.10", "eth0"));
+
+        let net_tools = "Address                  HWtype  HWaddress           Flags Mask            Iface\n\
+                         192.0.2.20               ether   52:54:00:ab:cd:ef   C                     eth1\n\
+                         192.0.2.30                       (incomplete)                              eth1\n";
+        let neighbors = parse_show_arp(net_tools);
+        assert_eq!(neighbors.len(), 2);
+        assert_eq!(neighbors[0].mac_address.as_deref(), Some("52:54:00:ab:cd:ef"));
+        assert_eq!(neighbors[0].interface, "eth1");
+        assert_eq!((neighbors[1].mac_address.as_ref(), neighbors[1].state.as_str()), (None, "INCOMPLETE"));
+
+        let ip_neigh = parse_show_arp("192.0.2.40 dev eth0 lladdr 52:54:00:00:00:40 STALE\n");
+        assert_eq!((ip_neigh[0].interface.as_str(), ip_neigh[0].state.as_str()), ("eth0", "STALE"));
+    }
+
+    #[test]
+    fn test_parse_show_lldp_neighbors() {
+        let raw = "Capability Codes: R - Router, B - Bridge, W - Wlan r - Repeater, S - Station\n\
+                   \x20                 D - Docsis, T - Telephone, O - Other\n\
+                   \n\
+                   Device ID                 Local     Proto  Cap   Platform             Port ID\n\
+                   ---------                 -----     -----  ---   --------             -------\n\
+                   edge-2.example.net        eth1      LLDP   R     VyOS 1.4.0           eth1\n\
+                   core switch 1             eth2      LLDP   B     Arista vEOS          Ethernet7\n";
+        let neighbors = parse_show_lldp_neighbors(raw);
+        assert_eq!(
+            neighbors[0],
+            VyOSLldpNeighbor {
+                device: "edge-2.example.net".to_string(),
+                interface: "eth1".to_string(),
+                capabilities: Some("R".to_string()),
+                platform: Some("VyOS 1.4.0".to_string()),
+                port: Some("eth1".to_string()),
+            }
+        );
+        assert_eq!(neighbors[1].device, "core switch 1");
+        assert_eq!(neighbors[1].port.as_deref(), Some("Ethernet7"));
+        assert!(parse_show_lldp_neighbors("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_show_wireguard_summary() {
+        let raw = "interface: wg100\n\
+                   \x20 public key: hCvVfl4bUR7Ym+V4CSEnyWxPqZmd2wiRmy1Vjz9Zwm0=\n\
+                   \x20 private key: (hidden)\n\
+                   \x20 listening port: 51820\n\
+                   \n\
+                   peer: 6Dmfo5V8h9Y8kqG04BQ8Ez6Rf7fbKq1yV1Sz8h1r5DY=\n\
+                   \x20 endpoint: 198.51.100.1:51820\n\
+                   \x20 allowed ips: 10.255.0.2/32\n\
+                   \x20 latest handshake: 1 hour, 2 minutes, 5 seconds ago\n\
+                   \x20 transfer: 1.21 KiB received, 3.40 KiB sent\n\
+                   \n\
+                   peer: xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=\n\
+                   \x20 endpoint: (none)\n\
+                   \x20 allowed ips: 10.255.0.3/32\n";
+        let peers = parse_show_wireguard_summary(raw);
+        assert_eq!(
+            peers,
+            [
+                VyOSWireGuardPeer {
+                    public_key: "6Dmfo5V8h9Y8kqG04BQ8Ez6Rf7fbKq1yV1Sz8h1r5DY=".to_string(),
+                    endpoint: Some("198.51.100.1:51820".to_string()),
+                    latest_handshake_secs: Some(3725),
+                },
+                VyOSWireGuardPeer {
+                    public_key: "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=".to_string(),
+                    endpoint: None,
+                    latest_handshake_secs: None,
+                },
+            ]
+        );
+        assert_eq!(parse_handshake_age("Now"), Some(0));
+        assert_eq!(parse_handshake_age("1 day, 1 second ago"), Some(86_401));
+    }
+
+    #[test]
+    fn test_parse_show_vrrp() {
+        let raw = "Name    Interface      VRID  State      Priority  Last Transition\n\
+                   ------  -----------  ------  -------  ----------  -----------------\n\
+                   LAN     eth1             10  MASTER          200  2h41m5s\n\
+                   WAN     eth0.201         20  BACKUP          100  5s\n";
+        assert_eq!(
+            parse_show_vrrp(raw),
+            [
+                VyOSVrrpGroup {
+                    name: "LAN".to_string(),
+                    interface: "eth1".to_string(),
+                    vrid: 10,
+                    state: "MASTER".to_string(),
+                    priority: 200,
+                    last_transition_secs: Some(9665),
+                },
+                VyOSVrrpGroup {
+                    name: "WAN".to_string(),
+                    interface: "eth0.201".to_string(),
+                    vrid: 20,
+                    state: "BACKUP".to_string(),
+                    priority: 100,
+                    last_transition_secs: Some(5),
+                },
+            ]
+        );
+        assert!(parse_show_vrrp("VRRP is not running\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_show_conntrack_sync_statistics() {
+        let raw = "Main Table Statistics:\n\n\
+                   cache internal:\n\
+                   current active connections:                4\n\
+                   connections created:                      71    failed:            1\n\
+                   connections updated:                     178    failed:            0\n\
+                   connections destroyed:                    67    failed:            2\n\n\
+                   cache external:\n\
+                   current active connections:                3\n\
+                   connections created:                       9    failed:            0\n\n\
+                   traffic processed:\n\
+                   \x20                  0 Bytes                         0 Pckts\n\n\
+                   multicast traffic (active device=eth2):\n\
+                   \x20              17296 Bytes sent                 2512 Bytes recv\n\
+                   \x20                386 Pckt sent                    68 Pckt recv\n\
+                   \x20                  0 Error send                    4 Error recv\n\n\
+                   message tracking:\n\
+                   \x20                  0 Malformed msgs                    7 Lost msgs\n";
+        let statistics = parse_show_conntrack_sync_statistics(raw).unwrap();
+        assert_eq!(
+            statistics.internal,
+            VyOSConntrackSyncCache {
+                active: 4,
+                created: 71,
+                updated: 178,
+                destroyed: 67,
+                failed: 3,
+            }
+        );
+        assert_eq!(statistics.external.active, 3);
+        assert_eq!(statistics.sync_interface.as_deref(), Some("eth2"));
+        assert_eq!((statistics.bytes_sent, statistics.bytes_received), (17296, 2512));
+        assert_eq!((statistics.packets_sent, statistics.packets_received), (386, 68));
+        assert_eq!((statistics.send_errors, statistics.receive_errors), (0, 4));
+        assert_eq!(statistics.lost_messages, 7);
+        assert!(parse_show_conntrack_sync_statistics("conntrack-sync is not configured\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_show_interface_link() {
+        let raw = "pppoe0: <POINTOPOINT,MULTICAST,NOARP,UP,LOWER_UP> mtu 1492 qdisc pfifo_fast state UNKNOWN\n\
+                   \x20   link/ppp\n\
+                   \x20   inet 198.51.100.7 peer 198.51.100.1/32 scope global pppoe0\n\
+                   \x20      valid_lft forever preferred_lft forever\n\
+                   \x20   inet6 2001:db8:1::1/64 scope global\n\
+                   \x20   inet6 fe80::1/128 scope link\n\
+                   \x20   Description: ISP\n";
+        assert_eq!(
+            parse_show_interface_link(raw),
+            Some(VyOSLinkStatus {
+                is_up: true,
+                mtu: Some(1492),
+                addresses: vec!["198.51.100.7".to_string(), "2001:db8:1::1/64".to_string()],
+                peer_address: Some("198.51.100.1".to_string()),
+            })
+        );
+        let down = parse_show_interface_link("wwan0: <BROADCAST,MULTICAST> mtu 1500 state DOWN\n").unwrap();
+        assert!(!down.is_up);
+        assert!(parse_show_interface_link("Interface does not exist\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_ethtool_speed() {
+        let raw = "Settings for eth1:\n\tSupported ports: [ TP ]\n\tSpeed: 10000Mb/s\n\tDuplex: Full\n";
+        assert_eq!(parse_ethtool_speed(raw), Some(10_000));
+        assert_eq!(parse_ethtool_speed("Settings for eth2:\n\tSpeed: Unknown!\n"), None);
+    }
+
+    #[test]
+    fn test_parse_ppp_log() {
+        let now = "2026-01-02T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let raw = "Dec 31 22:00:00 vyos pppd[1000]: CHAP authentication succeeded\n\
+                   Dec 31 22:00:01 vyos pppd[1000]: local  IP address 198.51.100.5\n\
+                   Jan  1 09:00:00 vyos pppd[1000]: Connection terminated.\n\
+                   Jan  1 09:00:05 vyos pppd[2121]: PAP authentication succeeded\n\
+                   Jan  1 09:00:06 vyos pppd[2121]: local  IP address 198.51.100.7\n\
+                   Jan  1 09:00:06 vyos pppd[2121]: remote IP address 198.51.100.1\n";
+        let session = parse_ppp_log(raw, now);
+        assert_eq!(session.connected_since, Some("2026-01-01T09:00:06Z".parse().unwrap()));
+        assert_eq!(session.auth_protocol.as_deref(), Some("PAP"));
+
+        let earlier = parse_ppp_log(&raw.lines().take(2).collect::<Vec<_>>().join("\n"), now);
+        assert_eq!(earlier.connected_since, Some("2025-12-31T22:00:01Z".parse().unwrap()));
+        assert_eq!(parse_ppp_log(&raw.lines().take(3).collect::<Vec<_>>().join("\n"), now), VyOSPppSession::default());
+    }
+
+    #[test]
+    fn test_parse_show_wwan_details() {
+        let raw = "  --------------------------------\n\
+                   \x20 Status   |                state: connected\n\
+                   \x20          |          power state: on\n\
+                   \x20          |          access tech: lte\n\
+                   \x20          |       signal quality: 67% (recent)\n\
+                   \x20 3GPP     |        operator name: Example Mobile\n";
+        assert_eq!(
+            parse_show_wwan_details(raw),
+            VyOSModemStatus {
+                state: Some("connected".to_string()),
+                access_technology: Some("lte".to_string()),
+                signal_quality: Some(67),
+                operator: Some("Example Mobile".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_show_date() {
+        let expected = "2026-10-06T07:09:03Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(parse_show_date("Tue Oct  6 07:09:03 UTC 2026\n"), Some(expected));
+        assert_eq!(parse_show_date("2026-10-06T07:09:03+00:00"), Some(expected));
+        assert_eq!(parse_show_date("Tue Oct  6 09:09:03 CEST 2026"), None);
+        assert_eq!(parse_show_date("Invalid command"), None);
+    }
+
+    #[test]
+    fn test_ipv4_netmask() {
+        assert_eq!(ipv4_netmask("192.0.2.1/24").as_deref(), Some("255.255.255.0"));
+        assert_eq!(ipv4_netmask("10.0.0.1/0").as_deref(), Some("0.0.0.0"));
+        assert_eq!(ipv4_netmask("2001:db8::1/64"), None);
+    }
+}
@@ -0,0 +1,163 @@
+//! Simulated Nodes
+//!
+//! A simulated node stands in for a router in training environments and
+//! demos. It answers from a [`NodeRecording`]: a configuration snapshot and
+//! canned outputs of show commands, usually recorded from a real router.
+//! Simulated nodes are read-only; show commands without a recorded output
+//! fail the way an unknown command fails on a router.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Capability, VyOSInfo, VyOSShowResult};
+use crate::error::AppError;
+
+/// Show commands recorded from a router, in addition to its configuration
+pub const RECORDED_SHOW_COMMANDS: &[&str] = &[
+    "show version",
+    "show host name",
+    "show interfaces",
+    "show ip route",
+    "show ipv6 route",
+    "show ipv6 neighbors",
+    "show arp",
+    "show lldp neighbors",
+    "show system uptime",
+    "show system memory",
+    "show system storage",
+    "show firewall",
+    "show nat source rules",
+    "show nat destination rules",
+    "show vpn ipsec sa",
+    "show dhcp server leases",
+    "show vrrp",
+    "show conntrack-sync statistics",
+];
+
+/// What a simulated node answers with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeRecording {
+    /// System information, as returned by `GET /info`
+    pub info: VyOSInfo,
+    /// Whole configuration, as returned by `POST /retrieve` without a path
+    pub config: serde_json::Value,
+    /// Output of each recorded show command
+    #[serde(default)]
+    pub show_outputs: BTreeMap<String, String>,
+}
+
+/// Node answered locally from a recording
+#[derive(Debug, Clone)]
+pub struct SimulatedNode {
+    recording: NodeRecording,
+}
+
+impl SimulatedNode {
+    /// Create a simulated node answering from a recording
+    pub fn new(mut recording: NodeRecording) -> Self {
+        recording.show_outputs = recording
+            .show_outputs
+            .into_iter()
+            .map(|(command, output)| (normalize_command(&command), output))
+            .collect();
+        Self { recording }
+    }
+
+    /// Whether simulated nodes support a capability
+    ///
+    /// Only reading is simulated; changes, image management, and power
+    /// operations are not.
+    pub fn supports(capability: Capability) -> bool {
+        matches!(capability, Capability::Info | Capability::Retrieve | Capability::Show)
+    }
+
+    /// Recorded system information
+    pub fn info(&self) -> VyOSInfo {
+        self.recording.info.clone()
+    }
+
+    /// Configuration under a space-separated path, or the whole configuration
+    pub fn config_at(&self, path: Option<&str>) -> Result<serde_json::Value, AppError> {
+        let mut config = &self.recording.config;
+        for segment in path.unwrap_or_default().split_whitespace() {
+            config = config.get(segment).ok_or_else(|| {
+                AppError::ExternalApi(
+                    "VyOS API error: 400 Bad Request - Configuration under specified path is empty"
+                        .to_string(),
+                )
+            })?;
+        }
+        Ok(config.clone())
+    }
+
+    /// Recorded output of a show command
+    pub fn show_output(&self, command: &str) -> Result<VyOSShowResult, AppError> {
+        let output = self
+            .recording
+            .show_outputs
+            .get(&normalize_command(command))
+            .ok_or_else(|| {
+                AppError::ExternalApi(format!(
+                    "VyOS API error: 400 Bad Request - No output of \"{}\" is recorded for this simulated node",
+                    command.trim()
+                ))
+            })?;
+
+        Ok(VyOSShowResult {
+            success: true,
+            output: output.clone(),
+            error: None,
+            data: None,
+        })
+    }
+}
+
+/// `show  ip   route ` as `show ip route`
+fn normalize_command(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn simulated() -> SimulatedNode {
+        SimulatedNode::new(NodeRecording {
+            info: VyOSInfo {
+                hostname: "vyos-edge".to_string(),
+                version: "1.5-rolling-202409250007".to_string(),
+                uptime_seconds: 3600,
+                boot_time: None,
+                architecture: "x86_64".to_string(),
+                kernel_version: "6.6.52-amd64-vyos".to_string(),
+            },
+            config: json!({
+                "system": { "host-name": "vyos-edge" },
+                "interfaces": { "ethernet": { "eth0": { "address": ["192.0.2.1/24"] } } },
+            }),
+            show_outputs: BTreeMap::from([("show  host name".to_string(), "vyos-edge\n".to_string())]),
+        })
+    }
+
+    #[test]
+    fn test_config_at_path() {
+        let node = simulated();
+        assert_eq!(
+            node.config_at(Some("interfaces ethernet eth0")).unwrap(),
+            json!({ "address": ["192.0.2.1/24"] })
+        );
+        assert_eq!(node.config_at(None).unwrap()["system"]["host-name"], "vyos-edge");
+        assert!(node.config_at(Some("protocols bgp")).is_err());
+    }
+
+    #[test]
+    fn test_show_output_ignores_spacing() {
+        let node = simulated();
+        assert_eq!(node.show_output(" show host  name").unwrap().output, "vyos-edge\n");
+        assert!(matches!(node.show_output("show version"), Err(AppError::ExternalApi(_))));
+        assert!(SimulatedNode::supports(Capability::Show));
+        assert!(!SimulatedNode::supports(Capability::Configure));
+    }
+}
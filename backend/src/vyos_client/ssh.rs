@@ -0,0 +1,426 @@
+//! SSH Transport
+//!
+//! Some routers run without the REST API. [`VyOSSshClient`] reaches them
+//! over SSH instead and drives the VyOS CLI: operational commands run
+//! through `vyatta-op-cmd-wrapper`, and configuration changes are applied by
+//! a `vbash` script that enters configuration mode, sets and deletes the
+//! paths, then commits and saves. Configuration is read with
+//! `show configuration json`, which VyOS 1.3 and later provide.
+//!
+//! `ssh2` is blocking, so each command opens its own session on a blocking
+//! thread, which hands output back line by line as it is read. Host keys
+//! are not verified, as with `verify_ssl` turned off.
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use ssh2::{ExtendedData, Session};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info};
+
+use super::{
+    parsers, Capability, ConfigOperation, ConfigOperationKind, VyOSClientConfig, VyOSConnectionTest, VyOSInfo,
+    VyOSInterface, VyOSShowResult,
+};
+use crate::error::AppError;
+use crate::models::redact::Redacted;
+
+/// Port SSH logins use unless a node names another
+pub const DEFAULT_SSH_PORT: u16 = 22;
+
+/// Runs an operational-mode command outside of an interactive shell
+const OP_COMMAND_WRAPPER: &str = "/opt/vyatta/bin/vyatta-op-cmd-wrapper";
+
+/// Shell reading configuration scripts from standard input
+const CONFIG_SHELL: &str = "/bin/vbash -s";
+
+/// Defines the configuration-mode commands for scripts
+const SCRIPT_TEMPLATE: &str = "/opt/vyatta/etc/functions/script-template";
+
+/// Pieces of streamed output read ahead of the caller
+const STREAM_BUFFER: usize = 16;
+
+/// How an SSH login proves its identity
+#[derive(Clone)]
+pub enum SshAuth {
+    Password(String),
+    /// Private key in PEM format, without a passphrase
+    PrivateKey(String),
+}
+
+impl fmt::Debug for SshAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Password(password) => f.debug_tuple("Password").field(&Redacted(password)).finish(),
+            Self::PrivateKey(key) => f.debug_tuple("PrivateKey").field(&Redacted(key)).finish(),
+        }
+    }
+}
+
+/// SSH login to a router
+#[derive(Debug, Clone)]
+pub struct SshLogin {
+    pub port: u16,
+    pub username: String,
+    pub auth: SshAuth,
+}
+
+/// Output of a command run over SSH, with standard error merged in
+struct CommandOutput {
+    exit_status: i32,
+    output: String,
+}
+
+/// VyOS CLI client over SSH
+#[derive(Debug, Clone)]
+pub struct VyOSSshClient {
+    config: VyOSClientConfig,
+    login: SshLogin,
+}
+
+impl VyOSSshClient {
+    /// Create a client for a configuration with an SSH login
+    pub fn new(config: VyOSClientConfig) -> Result<Self, AppError> {
+        let login = config
+            .ssh
+            .clone()
+            .ok_or_else(|| AppError::Config(format!("No SSH login is configured for {}", config.base_url)))?;
+        Ok(Self { config, login })
+    }
+
+    /// Get the client configuration
+    pub fn config(&self) -> &VyOSClientConfig {
+        &self.config
+    }
+
+    /// System information from `show version` and `show host name`
+    pub async fn get_info(&self) -> Result<VyOSInfo, AppError> {
+        info!("Getting VyOS system information over SSH");
+
+        let version = self.show("show version").await?;
+        let version = parsers::parse_show_version(&version.output)
+            .ok_or_else(|| AppError::ExternalApi("VyOS CLI error: unrecognized show version output".to_string()))?;
+        let hostname = self.show("show host name").await?;
+
+        Ok(VyOSInfo {
+            hostname: hostname.output.trim().to_string(),
+            version: version.version,
+            uptime_seconds: 0,
+            boot_time: None,
+            architecture: version.architecture.unwrap_or_else(|| "unknown".to_string()),
+            kernel_version: "unknown".to_string(),
+        })
+    }
+
+    /// Configuration under a space-separated path, or the whole configuration
+    pub async fn retrieve_config(&self, path: Option<String>) -> Result<serde_json::Value, AppError> {
+        info!("Retrieving configuration over SSH: path={:?}", path);
+
+        let result = self.show("show configuration json").await?;
+        let config: serde_json::Value = serde_json::from_str(result.output.trim())
+            .map_err(|e| AppError::ExternalApi(format!("VyOS CLI error: unrecognized configuration: {}", e)))?;
+        config_at(config, path.as_deref())
+    }
+
+    /// Run an operational-mode command
+    pub async fn show(&self, command: &str) -> Result<VyOSShowResult, AppError> {
+        info!("Executing show command over SSH: {}", command);
+
+        let result = self.run(op_command(command), None).await?;
+        if result.exit_status != 0 {
+            return Err(AppError::ExternalApi(format!("VyOS CLI error: {}", result.output.trim())));
+        }
+
+        Ok(VyOSShowResult {
+            success: true,
+            output: result.output,
+            error: None,
+            data: None,
+        })
+    }
+
+    /// Run an operational-mode command, passing its output to `output` in
+    /// pieces of whole lines as it is read
+    pub async fn stream(&self, command: &str, output: &mut (dyn FnMut(String) + Send)) -> Result<(), AppError> {
+        info!("Streaming command over SSH: {}", command);
+
+        let host = self.config.base_url.clone();
+        let login = self.login.clone();
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let shell_command = op_command(command);
+        let (pieces, mut received) = mpsc::channel(STREAM_BUFFER);
+        let task = tokio::task::spawn_blocking(move || {
+            exec(&host, &login, timeout, &shell_command, None, &mut |piece| {
+                // A closed receiver means the caller has gone; the command ends with the session
+                let _ = pieces.blocking_send(piece.to_string());
+            })
+        });
+        while let Some(piece) = received.recv().await {
+            output(piece);
+        }
+
+        let exit_status = task.await.map_err(|e| AppError::Internal(format!("SSH task failed: {}", e)))??;
+        if exit_status != 0 {
+            return Err(AppError::ExternalApi(format!(
+                "VyOS CLI error: {} exited with status {}",
+                command, exit_status
+            )));
+        }
+        Ok(())
+    }
+
+    /// Apply, commit, and save a batch of set and delete operations
+    ///
+    /// The script stops at the first operation the router rejects, before
+    /// anything is committed, so either all operations take effect or none
+    /// does.
+    pub async fn configure_batch(&self, operations: &[ConfigOperation]) -> Result<(), AppError> {
+        info!("Applying {} configuration operations over SSH", operations.len());
+
+        let result = self.run(CONFIG_SHELL.to_string(), Some(config_script(operations))).await?;
+        if result.exit_status != 0 {
+            return Err(AppError::ExternalApi(format!("VyOS CLI error: {}", result.output.trim())));
+        }
+        Ok(())
+    }
+
+    /// Network interfaces from `show interfaces`
+    pub async fn get_interfaces(&self) -> Result<Vec<VyOSInterface>, AppError> {
+        let result = self.show("show interfaces").await?;
+        Ok(parsers::parse_show_interfaces(&result.output))
+    }
+
+    /// Reboot the router now
+    pub async fn reboot(&self) -> Result<(), AppError> {
+        info!("Initiating system reboot over SSH");
+
+        self.show("reboot now").await.map(|_| ())
+    }
+
+    /// Whether the CLI covers the operations behind a capability
+    ///
+    /// Only the operations of [`NodeTransport`](super::NodeTransport) are
+    /// driven over SSH.
+    pub fn supports(capability: Capability) -> bool {
+        matches!(
+            capability,
+            Capability::Info | Capability::Retrieve | Capability::Configure | Capability::Show | Capability::Reboot
+        )
+    }
+
+    /// Check that the router accepts the login, with its latency and version
+    pub async fn test_connection(&self) -> Result<VyOSConnectionTest, AppError> {
+        info!("Testing SSH connection to {}", self.config.base_url);
+
+        let start = std::time::Instant::now();
+        match self.get_info().await {
+            Ok(info) => Ok(VyOSConnectionTest {
+                success: true,
+                latency_ms: Some(start.elapsed().as_millis() as u64),
+                version: Some(info.version),
+                hostname: Some(info.hostname),
+                uptime: Some(info.uptime_seconds),
+                error: None,
+            }),
+            Err(e) => {
+                error!("SSH connection test failed: {}", e);
+                Ok(VyOSConnectionTest {
+                    success: false,
+                    latency_ms: Some(start.elapsed().as_millis() as u64),
+                    version: None,
+                    hostname: None,
+                    uptime: None,
+                    error: Some(e.to_string()),
+                })
+            }
+        }
+    }
+
+    /// Run a command in a new session, writing `input` to its standard input
+    async fn run(&self, command: String, input: Option<String>) -> Result<CommandOutput, AppError> {
+        let host = self.config.base_url.clone();
+        let login = self.login.clone();
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        tokio::task::spawn_blocking(move || {
+            let mut output = String::new();
+            let exit_status = exec(&host, &login, timeout, &command, input.as_deref(), &mut |piece| {
+                output.push_str(piece)
+            })?;
+            Ok(CommandOutput { exit_status, output })
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("SSH task failed: {}", e)))?
+    }
+}
+
+/// Connect, log in, and run one command, returning its exit status
+///
+/// Output is passed to `output` in pieces ending with whole lines, as it is
+/// read; only the last piece may end without a line break.
+fn exec(
+    host: &str,
+    login: &SshLogin,
+    timeout: Duration,
+    command: &str,
+    input: Option<&str>,
+    output: &mut dyn FnMut(&str),
+) -> Result<i32, AppError> {
+    debug!("SSH command on {}: {}", host, command);
+    let failed = |e: &dyn std::fmt::Display| AppError::HttpClient(format!("SSH connection to {} failed: {}", host, e));
+
+    let address = (host, login.port)
+        .to_socket_addrs()
+        .map_err(|e| failed(&e))?
+        .next()
+        .ok_or_else(|| failed(&"no address found"))?;
+    let tcp = TcpStream::connect_timeout(&address, Duration::from_secs(10)).map_err(|e| failed(&e))?;
+
+    let mut session = Session::new().map_err(|e| failed(&e))?;
+    session.set_timeout(timeout.as_millis().min(u32::MAX as u128) as u32);
+    session.set_tcp_stream(tcp);
+    session.handshake().map_err(|e| failed(&e))?;
+
+    let authenticated = match &login.auth {
+        SshAuth::Password(password) => session.userauth_password(&login.username, password),
+        SshAuth::PrivateKey(key) => session.userauth_pubkey_memory(&login.username, None, key, None),
+    };
+    if authenticated.is_err() || !session.authenticated() {
+        return Err(AppError::ExternalApi(format!(
+            "VyOS CLI error: SSH login as {} rejected",
+            login.username
+        )));
+    }
+
+    let mut channel = session.channel_session().map_err(|e| failed(&e))?;
+    channel.handle_extended_data(ExtendedData::Merge).map_err(|e| failed(&e))?;
+    channel.exec(command).map_err(|e| failed(&e))?;
+    if let Some(input) = input {
+        channel.write_all(input.as_bytes()).map_err(|e| failed(&e))?;
+    }
+    channel.send_eof().map_err(|e| failed(&e))?;
+
+    let mut pending = Vec::new();
+    let mut buffer = [0; 8192];
+    loop {
+        let read = channel.read(&mut buffer).map_err(|e| failed(&e))?;
+        if read == 0 {
+            break;
+        }
+        pending.extend_from_slice(&buffer[..read]);
+        if let Some(end) = pending.iter().rposition(|&byte| byte == b'\n') {
+            let lines: Vec<u8> = pending.drain(..=end).collect();
+            output(&String::from_utf8_lossy(&lines));
+        }
+    }
+    if !pending.is_empty() {
+        output(&String::from_utf8_lossy(&pending));
+    }
+    channel.wait_close().map_err(|e| failed(&e))?;
+    channel.exit_status().map_err(|e| failed(&e))
+}
+
+/// Quote a word for the shell
+fn shell_quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', r"'\''"))
+}
+
+/// Quoted words of a command line
+fn quoted(words: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    words.into_iter().map(|word| shell_quote(word.as_ref())).collect::<Vec<_>>().join(" ")
+}
+
+/// Shell command running an operational-mode command
+fn op_command(command: &str) -> String {
+    format!("{} {}", OP_COMMAND_WRAPPER, quoted(command.split_whitespace()))
+}
+
+/// `vbash` script applying, committing, and saving configuration operations
+///
+/// `builtin exit` leaves the script on the first failure; plain `exit` is
+/// redefined by the template to leave configuration mode.
+fn config_script(operations: &[ConfigOperation]) -> String {
+    let mut script = format!("source {}\nconfigure\n", SCRIPT_TEMPLATE);
+    for operation in operations {
+        let verb = match operation.op {
+            ConfigOperationKind::Set => "set",
+            ConfigOperationKind::Delete => "delete",
+        };
+        script.push_str(&format!("{} {} || builtin exit 1\n", verb, quoted(&operation.path)));
+    }
+    script.push_str("commit || builtin exit 1\nsave || builtin exit 1\nexit\n");
+    script
+}
+
+/// Configuration under a space-separated path
+fn config_at(mut config: serde_json::Value, path: Option<&str>) -> Result<serde_json::Value, AppError> {
+    for segment in path.unwrap_or_default().split_whitespace() {
+        config = config.get_mut(segment).map(serde_json::Value::take).ok_or_else(|| {
+            AppError::ExternalApi("VyOS CLI error: Configuration under specified path is empty".to_string())
+        })?;
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_commands_are_quoted_word_by_word() {
+        assert_eq!(
+            op_command("show  interfaces ethernet eth0"),
+            "/opt/vyatta/bin/vyatta-op-cmd-wrapper 'show' 'interfaces' 'ethernet' 'eth0'"
+        );
+        assert_eq!(
+            op_command("show host name; reboot"),
+            format!("{} 'show' 'host' 'name;' 'reboot'", OP_COMMAND_WRAPPER)
+        );
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_config_script_stops_at_the_first_failure() {
+        let path = |words: &[&str]| words.iter().map(|word| word.to_string()).collect();
+        let script = config_script(&[
+            ConfigOperation::set(path(&["interfaces", "ethernet", "eth1", "description", "Bob's link"])),
+            ConfigOperation::delete(path(&["protocols", "static"])),
+        ]);
+        assert_eq!(
+            script,
+            "source /opt/vyatta/etc/functions/script-template\n\
+             configure\n\
+             set 'interfaces' 'ethernet' 'eth1' 'description' 'Bob'\\''s link' || builtin exit 1\n\
+             delete 'protocols' 'static' || builtin exit 1\n\
+             commit || builtin exit 1\n\
+             save || builtin exit 1\n\
+             exit\n"
+        );
+    }
+
+    #[test]
+    fn test_config_at_path() {
+        let config = json!({ "system": { "host-name": "edge" }, "interfaces": {} });
+        assert_eq!(config_at(config.clone(), Some("system  host-name")).unwrap(), json!("edge"));
+        assert_eq!(config_at(config.clone(), None).unwrap(), config);
+        assert!(matches!(config_at(config, Some("protocols bgp")), Err(AppError::ExternalApi(_))));
+    }
+
+    #[test]
+    fn test_client_needs_an_ssh_login() {
+        let config = VyOSClientConfig::new("192.0.2.1".to_string(), String::new(), 0, false, false, 30);
+        assert!(matches!(VyOSSshClient::new(config.clone()), Err(AppError::Config(_))));
+
+        let client = VyOSSshClient::new(config.with_ssh(SshLogin {
+            port: DEFAULT_SSH_PORT,
+            username: "vyos".to_string(),
+            auth: SshAuth::Password("vyos".to_string()),
+        }))
+        .unwrap();
+        assert_eq!(client.config().base_url, "192.0.2.1");
+        assert!(VyOSSshClient::supports(Capability::Configure));
+        assert!(!VyOSSshClient::supports(Capability::Image));
+    }
+}
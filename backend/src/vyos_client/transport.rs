@@ -0,0 +1,193 @@
+//! Node Transports
+//!
+//! Services reach a node through a [`NodeTransport`] rather than a concrete
+//! client, so nodes that are not real routers can be served locally. Real
+//! routers are reached over the REST API by [`VyOSClient`], or over SSH by
+//! [`VyOSSshClient`] when the API is not enabled; simulated nodes are
+//! answered from a recording by [`SimulatedNode`].
+
+use async_trait::async_trait;
+
+use super::{
+    Capability, ConfigOperation, SimulatedNode, VyOSClient, VyOSConnectionTest, VyOSInfo,
+    VyOSInterface, VyOSShowResult, VyOSSshClient,
+};
+use crate::error::AppError;
+
+/// The operations the UI performs on a node
+#[async_trait]
+pub trait NodeTransport: Send + Sync {
+    /// System information such as version and host name
+    async fn get_info(&self) -> Result<VyOSInfo, AppError>;
+
+    /// Configuration under a path, or the whole configuration
+    async fn retrieve_config(&self, path: Option<String>) -> Result<serde_json::Value, AppError>;
+
+    /// Output of an operational-mode command
+    async fn show(&self, command: &str) -> Result<VyOSShowResult, AppError>;
+
+    /// Run an operational-mode command, passing its output to `output` as
+    /// it is read
+    ///
+    /// Transports that only return whole outputs pass it on in one piece.
+    async fn run_op_command(&self, command: &str, output: &mut (dyn FnMut(String) + Send)) -> Result<(), AppError> {
+        let result = self.show(command).await?;
+        output(result.output);
+        if !result.success {
+            return Err(AppError::ExternalApi(result.error.unwrap_or_else(|| format!("{} failed", command))));
+        }
+        Ok(())
+    }
+
+    /// Network interfaces with their addresses and state
+    async fn get_interfaces(&self) -> Result<Vec<VyOSInterface>, AppError>;
+
+    /// Whether the node serves the endpoint behind a capability
+    async fn supports(&self, capability: Capability) -> Result<bool, AppError>;
+
+    /// Check that the node answers, with its latency and version
+    async fn test_connection(&self) -> Result<VyOSConnectionTest, AppError>;
+
+    /// Apply and commit a batch of configuration operations
+    async fn configure(&self, operations: &[ConfigOperation]) -> Result<(), AppError>;
+
+    /// Reboot the node
+    ///
+    /// The node may drop the connection before answering, which surfaces as
+    /// an [`AppError::HttpClient`] error even though the reboot started.
+    async fn reboot(&self) -> Result<(), AppError>;
+}
+
+#[async_trait]
+impl NodeTransport for VyOSClient {
+    async fn get_info(&self) -> Result<VyOSInfo, AppError> {
+        VyOSClient::get_info(self).await
+    }
+
+    async fn retrieve_config(&self, path: Option<String>) -> Result<serde_json::Value, AppError> {
+        VyOSClient::retrieve_config(self, path).await
+    }
+
+    async fn show(&self, command: &str) -> Result<VyOSShowResult, AppError> {
+        VyOSClient::show(self, command).await
+    }
+
+    /// The API only runs `show` commands and answers with their whole output
+    async fn run_op_command(&self, command: &str, output: &mut (dyn FnMut(String) + Send)) -> Result<(), AppError> {
+        if command.split_whitespace().next() != Some("show") {
+            return Err(AppError::Unsupported(format!(
+                "`{}` cannot be run through the VyOS API, which only runs show commands",
+                command
+            )));
+        }
+        let result = VyOSClient::show(self, command).await?;
+        output(result.output);
+        if !result.success {
+            return Err(AppError::ExternalApi(result.error.unwrap_or_else(|| format!("{} failed", command))));
+        }
+        Ok(())
+    }
+
+    async fn get_interfaces(&self) -> Result<Vec<VyOSInterface>, AppError> {
+        VyOSClient::get_interfaces(self).await
+    }
+
+    async fn supports(&self, capability: Capability) -> Result<bool, AppError> {
+        VyOSClient::supports(self, capability).await
+    }
+
+    async fn test_connection(&self) -> Result<VyOSConnectionTest, AppError> {
+        VyOSClient::test_connection(self).await
+    }
+
+    async fn configure(&self, operations: &[ConfigOperation]) -> Result<(), AppError> {
+        VyOSClient::configure_batch(self, operations).await
+    }
+
+    async fn reboot(&self) -> Result<(), AppError> {
+        VyOSClient::reboot(self).await.map(|_| ())
+    }
+}
+
+#[async_trait]
+impl NodeTransport for VyOSSshClient {
+    async fn get_info(&self) -> Result<VyOSInfo, AppError> {
+        VyOSSshClient::get_info(self).await
+    }
+
+    async fn retrieve_config(&self, path: Option<String>) -> Result<serde_json::Value, AppError> {
+        VyOSSshClient::retrieve_config(self, path).await
+    }
+
+    async fn show(&self, command: &str) -> Result<VyOSShowResult, AppError> {
+        VyOSSshClient::show(self, command).await
+    }
+
+    async fn run_op_command(&self, command: &str, output: &mut (dyn FnMut(String) + Send)) -> Result<(), AppError> {
+        VyOSSshClient::stream(self, command, output).await
+    }
+
+    async fn get_interfaces(&self) -> Result<Vec<VyOSInterface>, AppError> {
+        VyOSSshClient::get_interfaces(self).await
+    }
+
+    async fn supports(&self, capability: Capability) -> Result<bool, AppError> {
+        Ok(VyOSSshClient::supports(capability))
+    }
+
+    async fn test_connection(&self) -> Result<VyOSConnectionTest, AppError> {
+        VyOSSshClient::test_connection(self).await
+    }
+
+    async fn configure(&self, operations: &[ConfigOperation]) -> Result<(), AppError> {
+        VyOSSshClient::configure_batch(self, operations).await
+    }
+
+    async fn reboot(&self) -> Result<(), AppError> {
+        VyOSSshClient::reboot(self).await
+    }
+}
+
+#[async_trait]
+impl NodeTransport for SimulatedNode {
+    async fn get_info(&self) -> Result<VyOSInfo, AppError> {
+        Ok(self.info())
+    }
+
+    async fn retrieve_config(&self, path: Option<String>) -> Result<serde_json::Value, AppError> {
+        self.config_at(path.as_deref())
+    }
+
+    async fn show(&self, command: &str) -> Result<VyOSShowResult, AppError> {
+        self.show_output(command)
+    }
+
+    async fn get_interfaces(&self) -> Result<Vec<VyOSInterface>, AppError> {
+        let result = self.show_output("show interfaces")?;
+        Ok(super::parsers::parse_show_interfaces(&result.output))
+    }
+
+    async fn supports(&self, capability: Capability) -> Result<bool, AppError> {
+        Ok(SimulatedNode::supports(capability))
+    }
+
+    async fn test_connection(&self) -> Result<VyOSConnectionTest, AppError> {
+        let info = self.info();
+        Ok(VyOSConnectionTest {
+            success: true,
+            latency_ms: Some(0),
+            version: Some(info.version),
+            hostname: Some(info.hostname),
+            uptime: Some(info.uptime_seconds),
+            error: None,
+        })
+    }
+
+    async fn configure(&self, _operations: &[ConfigOperation]) -> Result<(), AppError> {
+        Err(AppError::Unsupported("Simulated nodes cannot be configured".to_string()))
+    }
+
+    async fn reboot(&self) -> Result<(), AppError> {
+        Err(AppError::Unsupported("Simulated nodes cannot be rebooted".to_string()))
+    }
+}
@@ -1,12 +1,24 @@
 //! WebSocket handlers and connections
 //!
 //! This module provides real-time bidirectional communication capabilities
-//! for the application.
+//! for the application, including interactive terminal sessions on nodes.
 
 use actix_web::{web, Error, HttpRequest, HttpResponse};
-use futures_util::stream::StreamExt;
+use actix_ws::{Message, MessageStream, Session};
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::debug;
+use uuid::Uuid;
+
+mod outbox;
+
+pub use outbox::{Outbox, QueueStats};
+
+use crate::models::auth::Claims;
+use crate::models::event::EventReplayQuery;
+use crate::services::{AuthService, ChannelAccessService, EventBus, TerminalService, TerminalSession};
 
 /// WebSocket message types
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -28,7 +40,55 @@ pub enum WsMessage {
     Unsubscribe { channel: String },
 
     /// Server broadcast
-    Broadcast { channel: String, data: serde_json::Value },
+    ///
+    /// Events from the event bus carry their sequence number.
+    Broadcast {
+        channel: String,
+        data: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sequence: Option<u64>,
+    },
+
+    /// Replay the events of the subscribed channels published after a
+    /// sequence number
+    Replay { after: u64 },
+
+    /// Sent after the replayed broadcasts, with the sequence number to
+    /// continue from
+    Replayed { cursor: u64, has_more: bool },
+
+    /// Open a terminal session on a node
+    TerminalOpen { node_id: Uuid },
+
+    /// Terminal session opened, with the command prefixes the user may run;
+    /// every command is allowed if unset
+    TerminalOpened {
+        session_id: Uuid,
+        node_id: Uuid,
+        allowed_commands: Option<Vec<String>>,
+    },
+
+    /// Command line to run in a terminal session
+    ///
+    /// Lines of a session run one after another, in the order sent.
+    TerminalInput { session_id: Uuid, line: String },
+
+    /// Output of the running command, sent as it is read
+    TerminalOutput { session_id: Uuid, data: String },
+
+    /// The command of a line finished
+    TerminalDone {
+        session_id: Uuid,
+        command: String,
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Close a terminal session
+    TerminalClose { session_id: Uuid },
+
+    /// Terminal session closed, after the lines sent before finished
+    TerminalClosed { session_id: Uuid },
 
     /// Error message
     Error { message: String },
@@ -45,6 +105,12 @@ pub struct WebSocketConnection {
 
     /// Subscribed channels
     pub channels: Vec<String>,
+
+    /// Claims of the authenticated user, checked on every subscription
+    claims: Option<Claims>,
+
+    /// Queue of text frames to write to the socket
+    outbox: Option<Outbox>,
 }
 
 impl WebSocketConnection {
@@ -54,25 +120,75 @@ impl WebSocketConnection {
             id,
             user_id: None,
             channels: Vec::new(),
+            claims: None,
+            outbox: None,
+        }
+    }
+
+    /// Deliver frames queued for this connection through `outbox`
+    pub fn with_outbox(mut self, outbox: Outbox) -> Self {
+        self.outbox = Some(outbox);
+        self
+    }
+
+    /// Queue a text frame
+    fn send(&self, text: &str) {
+        if let Some(outbox) = &self.outbox {
+            outbox.push(text.to_string());
+        }
+    }
+
+    /// Queue a live update, replacing the one under the same key still
+    /// waiting to be sent
+    fn send_latest(&self, key: &str, text: &str) {
+        if let Some(outbox) = &self.outbox {
+            outbox.push_latest(key, text.to_string());
         }
     }
 }
 
+/// Frames queued per connection unless configured otherwise
+const DEFAULT_QUEUE_CAPACITY: usize = 256;
+
 /// WebSocket connection manager
 #[derive(Clone)]
 pub struct ConnectionManager {
     /// Map of connection ID to connection info
     connections: Arc<Mutex<HashMap<String, WebSocketConnection>>>,
+
+    /// Frames queued per connection
+    queue_capacity: usize,
+
+    /// Frames the connection queues did not deliver
+    queue_stats: Arc<QueueStats>,
 }
 
 impl ConnectionManager {
     /// Create a new connection manager
     pub fn new() -> Self {
+        Self::with_queue_capacity(DEFAULT_QUEUE_CAPACITY)
+    }
+
+    /// Create a new connection manager queueing at most `capacity` frames
+    /// per connection
+    pub fn with_queue_capacity(capacity: usize) -> Self {
         Self {
             connections: Arc::new(Mutex::new(HashMap::new())),
+            queue_capacity: capacity,
+            queue_stats: Arc::new(QueueStats::default()),
         }
     }
 
+    /// Create an empty frame queue for a new connection
+    pub fn outbox(&self) -> Outbox {
+        Outbox::new(self.queue_capacity, self.queue_stats.clone())
+    }
+
+    /// Frames the connection queues did not deliver
+    pub fn queue_stats(&self) -> &QueueStats {
+        &self.queue_stats
+    }
+
     /// Add a connection
     pub fn add_connection(&self, id: String, conn: WebSocketConnection) {
         let mut connections = self.connections.lock().unwrap();
@@ -91,17 +207,115 @@ impl ConnectionManager {
         connections.get(id).cloned()
     }
 
+    /// Record the authenticated user of a connection
+    ///
+    /// Subscriptions made as another user are dropped, since the new user
+    /// may not be entitled to them.
+    pub fn authenticate(&self, id: &str, claims: Claims) {
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(conn) = connections.get_mut(id) {
+            if conn.user_id.as_deref() != Some(claims.sub.as_str()) {
+                conn.channels.clear();
+            }
+            conn.user_id = Some(claims.sub.clone());
+            conn.claims = Some(claims);
+        }
+    }
+
+    /// Subscribe a connection to a channel
+    pub fn subscribe(&self, id: &str, channel: &str) {
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(conn) = connections.get_mut(id) {
+            if !conn.channels.iter().any(|c| c == channel) {
+                conn.channels.push(channel.to_string());
+            }
+        }
+    }
+
+    /// Unsubscribe a connection from a channel
+    pub fn unsubscribe(&self, id: &str, channel: &str) {
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(conn) = connections.get_mut(id) {
+            conn.channels.retain(|c| c != channel);
+        }
+    }
+
+    /// Number of open connections
+    pub fn connection_count(&self) -> usize {
+        self.connections.lock().unwrap().len()
+    }
+
+    /// Send a payload to the connections subscribed to a channel
+    ///
+    /// Unlike [`EventBus::publish`], the payload is not stored and cannot be
+    /// replayed, which suits frequent updates such as live counters that are
+    /// stale by the time a client reconnects. Each payload supersedes the
+    /// last one on the channel: a connection that has not been sent the
+    /// previous payload yet is only sent the newer one. Returns the number
+    /// of connections it was queued for.
+    pub fn publish(&self, channel: &str, data: serde_json::Value) -> usize {
+        let message = WsMessage::Broadcast {
+            channel: channel.to_string(),
+            data,
+            sequence: None,
+        };
+        let connections = self.connections.lock().unwrap();
+        let json = serde_json::to_string(&message).unwrap_or_default();
+        let mut sent = 0;
+        for conn in connections.values().filter(|conn| conn.channels.iter().any(|c| c == channel)) {
+            conn.send_latest(channel, &json);
+            sent += 1;
+        }
+        sent
+    }
+
     /// Broadcast a message to all connections subscribed to a channel
-    pub fn broadcast(&self, channel: &str, message: &WsMessage) {
+    ///
+    /// Connections not subscribed to the channel never see the message.
+    /// Returns the number of connections it was sent to.
+    pub fn broadcast(&self, channel: &str, message: &WsMessage) -> usize {
+        let connections = self.connections.lock().unwrap();
+        let json = serde_json::to_string(message).unwrap_or_default();
+        let mut sent = 0;
+        for conn in connections.values().filter(|conn| conn.channels.iter().any(|c| c == channel)) {
+            conn.send(&json);
+            sent += 1;
+        }
+        sent
+    }
+
+    /// Send a message to every open connection, subscribed or not
+    ///
+    /// For notices every UI must show, such as announcements.
+    pub fn broadcast_all(&self, message: &WsMessage) {
         let connections = self.connections.lock().unwrap();
-        let _json = serde_json::to_string(message).unwrap_or_default();
+        let json = serde_json::to_string(message).unwrap_or_default();
         for conn in connections.values() {
-            if conn.channels.contains(&channel.to_string()) {
-                // Send message to connected session
-                // Note: In a real implementation, you'd maintain session references
-            }
+            conn.send(&json);
         }
     }
+
+    /// Render connection and queue metrics in the Prometheus text
+    /// exposition format
+    pub fn render_prometheus(&self, out: &mut String) {
+        let (connections, queued) = {
+            let connections = self.connections.lock().unwrap();
+            let queued: usize = connections
+                .values()
+                .filter_map(|conn| conn.outbox.as_ref())
+                .map(Outbox::len)
+                .sum();
+            (connections.len(), queued)
+        };
+
+        out.push_str("# HELP vyos_webui_ws_connections Open WebSocket connections\n");
+        out.push_str("# TYPE vyos_webui_ws_connections gauge\n");
+        let _ = writeln!(out, "vyos_webui_ws_connections {}", connections);
+        out.push_str("# HELP vyos_webui_ws_queued_frames WebSocket frames waiting to be sent\n");
+        out.push_str("# TYPE vyos_webui_ws_queued_frames gauge\n");
+        let _ = writeln!(out, "vyos_webui_ws_queued_frames {}", queued);
+        self.queue_stats.render_prometheus(out);
+    }
 }
 
 impl Default for ConnectionManager {
@@ -111,17 +325,291 @@ impl Default for ConnectionManager {
 }
 
 /// Handle WebSocket connection
+///
+/// GET /ws
+///
+/// Upgrades the request and serves the connection until either side closes
+/// it. Clients authenticate with an `Auth` message before subscribing, and
+/// may only subscribe to channels they are entitled to read. Authenticated
+/// clients may also open terminal sessions on nodes, which are closed with
+/// the connection.
 pub async fn websocket_handler(
-    _req: HttpRequest,
-    _stream: web::Payload,
-    _manager: web::Data<ConnectionManager>,
+    req: HttpRequest,
+    stream: web::Payload,
+    manager: web::Data<ConnectionManager>,
+    auth_service: web::Data<AuthService>,
+    channel_access: web::Data<ChannelAccessService>,
+    event_bus: web::Data<EventBus>,
+    terminal_service: web::Data<TerminalService>,
 ) -> Result<HttpResponse, Error> {
-    // For now, return a placeholder response
-    // WebSocket functionality would require proper actix-ws integration
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "endpoint": "/ws",
-        "message": "WebSocket endpoint available - implement with proper WebSocket library"
-    })))
+    let (response, session, messages) = actix_ws::handle(&req, stream)?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let outbox = manager.outbox();
+    manager.add_connection(id.clone(), WebSocketConnection::new(id.clone()).with_outbox(outbox.clone()));
+    debug!("WebSocket connection {} opened", id);
+
+    let services = ConnectionServices {
+        manager: manager.get_ref().clone(),
+        auth_service: auth_service.get_ref().clone(),
+        channel_access: channel_access.get_ref().clone(),
+        event_bus: event_bus.get_ref().clone(),
+        terminal_service: terminal_service.get_ref().clone(),
+    };
+    actix_web::rt::spawn(serve_connection(id, session, messages, outbox, services));
+
+    Ok(response)
+}
+
+/// Services a connection's messages are handled with
+struct ConnectionServices {
+    manager: ConnectionManager,
+    auth_service: AuthService,
+    channel_access: ChannelAccessService,
+    event_bus: EventBus,
+    terminal_service: TerminalService,
+}
+
+/// Relay frames between a socket and the connection manager
+async fn serve_connection(
+    id: String,
+    mut session: Session,
+    mut messages: MessageStream,
+    outbox: Outbox,
+    services: ConnectionServices,
+) {
+    let ConnectionServices {
+        manager,
+        auth_service,
+        channel_access,
+        event_bus,
+        terminal_service,
+    } = services;
+    // Command lines queued for each open terminal session
+    let mut terminals: HashMap<Uuid, mpsc::UnboundedSender<String>> = HashMap::new();
+    'serve: loop {
+        tokio::select! {
+            frame = messages.recv() => match frame {
+                Some(Ok(Message::Text(text))) => {
+                    let replies = match serde_json::from_str::<WsMessage>(&text) {
+                        Ok(WsMessage::Subscribe { channel }) => {
+                            subscribe(&id, channel, &manager, &channel_access).await.into_iter().collect()
+                        }
+                        Ok(WsMessage::Replay { after }) => {
+                            replay_events(&id, after, &manager, &event_bus, &channel_access).await
+                        }
+                        Ok(WsMessage::TerminalOpen { node_id }) => {
+                            open_terminal(&id, node_id, &manager, &terminal_service, &mut terminals).await
+                        }
+                        Ok(WsMessage::TerminalInput { session_id, line }) => match terminals.get(&session_id) {
+                            Some(lines) if lines.send(line).is_ok() => vec![],
+                            _ => vec![WsMessage::Error { message: format!("No terminal session {}", session_id) }],
+                        },
+                        Ok(WsMessage::TerminalClose { session_id }) => {
+                            // The session closes once its queued lines are done
+                            terminals.remove(&session_id);
+                            vec![]
+                        }
+                        Ok(message) => handle_message(&id, message, &manager, &auth_service).into_iter().collect(),
+                        Err(e) => vec![WsMessage::Error { message: format!("Invalid message: {}", e) }],
+                    };
+                    for reply in replies {
+                        let json = serde_json::to_string(&reply).unwrap_or_default();
+                        if session.text(json).await.is_err() {
+                            break 'serve;
+                        }
+                    }
+                }
+                Some(Ok(Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            text = outbox.recv() => {
+                if session.text(text).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Dropping the queues closes the terminal sessions
+    drop(terminals);
+    manager.remove_connection(&id);
+    let _ = session.close(None).await;
+    debug!("WebSocket connection {} closed", id);
+}
+
+/// Subscribe a connection to a channel, and return an error message if the
+/// connection is not authenticated or its user is not entitled to the channel
+async fn subscribe(
+    id: &str,
+    channel: String,
+    manager: &ConnectionManager,
+    channel_access: &ChannelAccessService,
+) -> Option<WsMessage> {
+    let Some(claims) = manager.get_connection(id).and_then(|conn| conn.claims) else {
+        return Some(WsMessage::Error {
+            message: "Authenticate before subscribing".to_string(),
+        });
+    };
+
+    if let Err(e) = channel_access.ensure_can_subscribe(&claims, &channel).await {
+        debug!("Denied subscription of {} to {}: {}", claims.username, channel, e);
+        return Some(WsMessage::Error { message: e.to_string() });
+    }
+    manager.subscribe(id, &channel);
+    None
+}
+
+/// Open a terminal session for a connection and start running its lines
+///
+/// Output is queued to the connection while the socket keeps serving other
+/// messages.
+async fn open_terminal(
+    id: &str,
+    node_id: Uuid,
+    manager: &ConnectionManager,
+    terminal_service: &TerminalService,
+    terminals: &mut HashMap<Uuid, mpsc::UnboundedSender<String>>,
+) -> Vec<WsMessage> {
+    let Some(conn) = manager.get_connection(id) else {
+        return vec![];
+    };
+    let (Some(claims), Some(outbox)) = (conn.claims, conn.outbox) else {
+        return vec![WsMessage::Error {
+            message: "Authenticate before opening a terminal".to_string(),
+        }];
+    };
+
+    let session = match terminal_service.open(&claims, node_id).await {
+        Ok(session) => session,
+        Err(e) => return vec![WsMessage::Error { message: e.to_string() }],
+    };
+    let opened = WsMessage::TerminalOpened {
+        session_id: session.id,
+        node_id,
+        allowed_commands: session.allowed_commands().map(<[String]>::to_vec),
+    };
+    let (lines, queued) = mpsc::unbounded_channel();
+    terminals.insert(session.id, lines);
+    actix_web::rt::spawn(run_terminal(session, queued, outbox, terminal_service.clone()));
+    vec![opened]
+}
+
+/// Run the lines of a terminal session in order until it is closed
+async fn run_terminal(
+    mut session: TerminalSession,
+    mut lines: mpsc::UnboundedReceiver<String>,
+    outbox: Outbox,
+    terminal_service: TerminalService,
+) {
+    let session_id = session.id;
+    let send = |message: WsMessage| outbox.push(serde_json::to_string(&message).unwrap_or_default());
+
+    while let Some(line) = lines.recv().await {
+        let result = terminal_service
+            .run(&mut session, &line, |data| send(WsMessage::TerminalOutput { session_id, data }))
+            .await;
+        send(WsMessage::TerminalDone {
+            session_id,
+            command: result.command,
+            success: result.success,
+            error: result.error,
+        });
+    }
+
+    terminal_service.close(&session).await;
+    send(WsMessage::TerminalClosed { session_id });
+}
+
+/// Broadcasts missed by a connection since a sequence number
+///
+/// Covers the connection's subscribed channels and events sent to every
+/// connection, followed by a `Replayed` message with the new cursor.
+async fn replay_events(
+    id: &str,
+    after: u64,
+    manager: &ConnectionManager,
+    event_bus: &EventBus,
+    channel_access: &ChannelAccessService,
+) -> Vec<WsMessage> {
+    let Some(conn) = manager.get_connection(id) else {
+        return vec![WsMessage::Error {
+            message: "Authenticate before replaying events".to_string(),
+        }];
+    };
+    let Some(claims) = conn.claims else {
+        return vec![WsMessage::Error {
+            message: "Authenticate before replaying events".to_string(),
+        }];
+    };
+
+    let query = EventReplayQuery {
+        after,
+        channels: Some(conn.channels.join(",")),
+        limit: None,
+    };
+    match event_bus.replay(query, &claims, channel_access).await {
+        Ok(replay) => replay
+            .events
+            .into_iter()
+            .map(|event| WsMessage::Broadcast {
+                channel: event.channel,
+                data: event.data,
+                sequence: Some(event.sequence),
+            })
+            .chain(std::iter::once(WsMessage::Replayed {
+                cursor: replay.cursor,
+                has_more: replay.has_more,
+            }))
+            .collect(),
+        Err(e) => vec![WsMessage::Error { message: e.to_string() }],
+    }
+}
+
+/// Apply a client message and return the reply, if any
+fn handle_message(
+    id: &str,
+    message: WsMessage,
+    manager: &ConnectionManager,
+    auth_service: &AuthService,
+) -> Option<WsMessage> {
+    match message {
+        WsMessage::Ping => Some(WsMessage::Pong),
+        WsMessage::Pong => None,
+        WsMessage::Auth { token } => match auth_service.validate_token(&token) {
+            Ok(claims) => {
+                manager.authenticate(id, claims);
+                None
+            }
+            Err(e) => Some(WsMessage::Error { message: e.to_string() }),
+        },
+        WsMessage::Unsubscribe { channel } => {
+            manager.unsubscribe(id, &channel);
+            None
+        }
+        // Subscriptions need the permission checks and are served by
+        // `subscribe`, replays need the event store and are served by
+        // `replay_events`, and terminal sessions live with the socket
+        WsMessage::Subscribe { .. }
+        | WsMessage::Replay { .. }
+        | WsMessage::TerminalOpen { .. }
+        | WsMessage::TerminalInput { .. }
+        | WsMessage::TerminalClose { .. } => None,
+        WsMessage::TerminalOpened { .. }
+        | WsMessage::TerminalOutput { .. }
+        | WsMessage::TerminalDone { .. }
+        | WsMessage::TerminalClosed { .. } => Some(WsMessage::Error {
+            message: "Only the server sends terminal output".to_string(),
+        }),
+        WsMessage::Broadcast { .. } | WsMessage::Replayed { .. } | WsMessage::Error { .. } => Some(WsMessage::Error {
+            message: "Only the server sends broadcast, replayed and error messages".to_string(),
+        }),
+    }
 }
 
 /// Get WebSocket endpoint info
@@ -141,5 +629,142 @@ mod tests {
         let msg = WsMessage::Ping;
         let json = serde_json::to_string(&msg).unwrap();
         assert_eq!(json, r#"{"type":"Ping"}"#);
+
+        let msg = WsMessage::Broadcast {
+            channel: "incidents".to_string(),
+            data: serde_json::json!({}),
+            sequence: Some(7),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(json, r#"{"type":"Broadcast","data":{"channel":"incidents","data":{},"sequence":7}}"#);
+
+        let msg: WsMessage = serde_json::from_str(r#"{"type":"Replay","data":{"after":42}}"#).unwrap();
+        assert!(matches!(msg, WsMessage::Replay { after: 42 }));
+
+        let msg: WsMessage = serde_json::from_str(
+            r#"{"type":"TerminalInput","data":{"session_id":"5eed0000-0000-4000-8000-000000000101","line":"show arp"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(msg, WsMessage::TerminalInput { line, .. } if line == "show arp"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_broadcast_reaches_subscribers() {
+        let manager = ConnectionManager::new();
+        let mut outboxes = vec![];
+        for id in ["a", "b"] {
+            let outbox = manager.outbox();
+            let conn = WebSocketConnection::new(id.to_string()).with_outbox(outbox.clone());
+            manager.add_connection(id.to_string(), conn);
+            outboxes.push(outbox);
+        }
+        manager.subscribe("a", "metrics");
+
+        let message = WsMessage::Broadcast {
+            channel: "metrics".to_string(),
+            data: serde_json::json!({ "cpu": 12 }),
+            sequence: None,
+        };
+        assert_eq!(manager.broadcast("metrics", &message), 1);
+        assert!(outboxes[0].try_recv().is_some());
+        assert!(outboxes[1].try_recv().is_none());
+
+        manager.subscribe("b", "alerts");
+        assert_eq!(manager.publish("alerts", serde_json::json!({ "id": 1 })), 1);
+        assert!(outboxes[0].try_recv().is_none());
+        let frame: WsMessage = serde_json::from_str(&outboxes[1].try_recv().unwrap()).unwrap();
+        assert!(matches!(frame, WsMessage::Broadcast { channel, sequence: None, .. } if channel == "alerts"));
+        manager.unsubscribe("b", "alerts");
+        assert_eq!(manager.publish("alerts", serde_json::json!({ "id": 2 })), 0);
+
+        manager.broadcast_all(&message);
+        assert!(outboxes[0].try_recv().is_some());
+        assert!(outboxes[1].try_recv().is_some());
+
+        // Live updates not yet sent are replaced by newer ones
+        for cpu in [10, 20, 30] {
+            manager.publish("metrics", serde_json::json!({ "cpu": cpu }));
+        }
+        let frame: WsMessage = serde_json::from_str(&outboxes[0].try_recv().unwrap()).unwrap();
+        assert!(matches!(frame, WsMessage::Broadcast { data, .. } if data["cpu"] == 30));
+        assert!(outboxes[0].try_recv().is_none());
+        assert_eq!(manager.queue_stats().coalesced(), 2);
+        let mut metrics = String::new();
+        manager.render_prometheus(&mut metrics);
+        assert!(metrics.contains("vyos_webui_ws_connections 2\n"));
+        assert!(metrics.contains("vyos_webui_ws_frames_coalesced_total 2\n"));
+
+        manager.remove_connection("a");
+        assert_eq!(manager.connection_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_require_channel_access() {
+        use crate::app::AppState;
+        use crate::config::AppConfig;
+        use crate::db::create_database;
+        use crate::models::team::CreateTeamRequest;
+        use crate::seed::{self, ADMIN_USER_ID, CORE_NODE_ID, EDGE_NODE_ID, OPERATOR_USER_ID};
+
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let state = AppState::new(AppConfig::from_env().unwrap(), create_database(pool).await.unwrap());
+        seed::seed(&state).await.unwrap();
+        let claims = |id: uuid::Uuid, username: &str| Claims {
+            sub: id.to_string(),
+            username: username.to_string(),
+            exp: i64::MAX,
+            iat: 0,
+            sid: None,
+        };
+        let admin = claims(ADMIN_USER_ID, "alice");
+        let operator = claims(OPERATOR_USER_ID, "bob");
+
+        // The edge router belongs to a team the operator is not a member of
+        let team = state
+            .team_service
+            .create_team(
+                &admin,
+                CreateTeamRequest {
+                    name: "Edge".to_string(),
+                    alias: "edge".to_string(),
+                    description: None,
+                },
+            )
+            .await
+            .unwrap();
+        state.node_service.set_node_owner(EDGE_NODE_ID, Some(team.id)).await.unwrap();
+
+        let manager = ConnectionManager::new();
+        manager.add_connection("ws".to_string(), WebSocketConnection::new("ws".to_string()));
+        let access = &state.channel_access_service;
+        let try_subscribe = |channel: String| subscribe("ws", channel, &manager, access);
+
+        assert!(matches!(try_subscribe("incidents".to_string()).await, Some(WsMessage::Error { .. })));
+
+        manager.authenticate("ws", operator);
+        assert!(try_subscribe("incidents".to_string()).await.is_none());
+        assert!(try_subscribe(format!("config:{}", CORE_NODE_ID)).await.is_none());
+        for denied in [
+            format!("config:{}", EDGE_NODE_ID),
+            format!("metrics:{}", uuid::Uuid::new_v4()),
+            "admin:cluster".to_string(),
+            "teams:edge".to_string(),
+        ] {
+            assert!(matches!(try_subscribe(denied).await, Some(WsMessage::Error { .. })));
+        }
+        assert_eq!(
+            manager.get_connection("ws").unwrap().channels,
+            vec!["incidents".to_string(), format!("config:{}", CORE_NODE_ID)]
+        );
+
+        // Signing in as someone else drops the previous user's subscriptions
+        manager.authenticate("ws", admin);
+        assert!(manager.get_connection("ws").unwrap().channels.is_empty());
+        assert!(try_subscribe(format!("config:{}", EDGE_NODE_ID)).await.is_none());
+        assert!(try_subscribe("admin:cluster".to_string()).await.is_none());
+    }
+}
@@ -0,0 +1,210 @@
+//! Bounded per-connection frame queues
+//!
+//! A client that reads slower than frames are published for it must not
+//! hold up the others or make the backend buffer without limit. Each
+//! connection queues at most `WEBSOCKET_QUEUE_CAPACITY` frames. Live
+//! updates, such as a node's latest metrics, are keyed: a newer update
+//! replaces the one still waiting under the same key instead of queueing
+//! behind it, so a slow client skips to the latest values. When the queue
+//! is full anyway, the oldest live update is dropped to make room, or the
+//! oldest frame if none is queued; broadcast events dropped this way can be
+//! recovered with a replay.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::Notify;
+
+/// Counters of the frames the queues of all connections did not deliver
+#[derive(Debug, Default)]
+pub struct QueueStats {
+    /// Live updates replaced by a newer one before they were sent
+    coalesced: AtomicU64,
+    /// Live updates dropped from full queues
+    dropped_live: AtomicU64,
+    /// Other frames dropped from full queues
+    dropped_other: AtomicU64,
+}
+
+impl QueueStats {
+    /// Live updates replaced by a newer one before they were sent
+    pub fn coalesced(&self) -> u64 {
+        self.coalesced.load(Ordering::Relaxed)
+    }
+
+    /// Frames dropped from full queues, live updates first
+    pub fn dropped(&self) -> (u64, u64) {
+        (self.dropped_live.load(Ordering::Relaxed), self.dropped_other.load(Ordering::Relaxed))
+    }
+
+    /// Render the counters in the Prometheus text exposition format
+    pub fn render_prometheus(&self, out: &mut String) {
+        let (live, other) = self.dropped();
+        out.push_str("# HELP vyos_webui_ws_frames_coalesced_total Live WebSocket updates replaced before being sent\n");
+        out.push_str("# TYPE vyos_webui_ws_frames_coalesced_total counter\n");
+        let _ = writeln!(out, "vyos_webui_ws_frames_coalesced_total {}", self.coalesced());
+        out.push_str("# HELP vyos_webui_ws_frames_dropped_total WebSocket frames dropped from full client queues\n");
+        out.push_str("# TYPE vyos_webui_ws_frames_dropped_total counter\n");
+        let _ = writeln!(out, "vyos_webui_ws_frames_dropped_total{{kind=\"live\"}} {}", live);
+        let _ = writeln!(out, "vyos_webui_ws_frames_dropped_total{{kind=\"other\"}} {}", other);
+    }
+}
+
+/// Frame waiting to be written to a socket
+struct QueuedFrame {
+    /// Key of a live update, replaced by newer updates under the same key
+    key: Option<String>,
+    text: String,
+}
+
+/// Bounded queue of the text frames to write to one socket
+///
+/// Clones share the queue.
+#[derive(Clone)]
+pub struct Outbox {
+    capacity: usize,
+    frames: Arc<Mutex<VecDeque<QueuedFrame>>>,
+    ready: Arc<Notify>,
+    stats: Arc<QueueStats>,
+}
+
+impl Outbox {
+    /// Create an empty queue of at most `capacity` frames, counting what it
+    /// does not deliver in `stats`
+    pub fn new(capacity: usize, stats: Arc<QueueStats>) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            frames: Arc::new(Mutex::new(VecDeque::new())),
+            ready: Arc::new(Notify::new()),
+            stats,
+        }
+    }
+
+    /// Queue a frame to be sent in order
+    pub fn push(&self, text: String) {
+        self.enqueue(None, text);
+    }
+
+    /// Queue a live update, replacing the one waiting under the same key
+    pub fn push_latest(&self, key: &str, text: String) {
+        self.enqueue(Some(key), text);
+    }
+
+    /// Wait for the next frame to write
+    ///
+    /// Cancel safe: a frame is only taken off the queue when returned.
+    pub async fn recv(&self) -> String {
+        loop {
+            if let Some(frame) = self.frames().pop_front() {
+                return frame.text;
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    /// Take the next frame without waiting
+    pub fn try_recv(&self) -> Option<String> {
+        self.frames().pop_front().map(|frame| frame.text)
+    }
+
+    /// Number of frames waiting to be written
+    pub fn len(&self) -> usize {
+        self.frames().len()
+    }
+
+    /// Whether no frame is waiting to be written
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn enqueue(&self, key: Option<&str>, text: String) {
+        {
+            let mut frames = self.frames();
+            if let Some(key) = key {
+                if let Some(waiting) = frames.iter_mut().find(|frame| frame.key.as_deref() == Some(key)) {
+                    waiting.text = text;
+                    self.stats.coalesced.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+            }
+
+            if frames.len() >= self.capacity {
+                match frames.iter().position(|frame| frame.key.is_some()) {
+                    Some(oldest_live) => {
+                        frames.remove(oldest_live);
+                        self.stats.dropped_live.fetch_add(1, Ordering::Relaxed);
+                    }
+                    None => {
+                        frames.pop_front();
+                        self.stats.dropped_other.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+            frames.push_back(QueuedFrame {
+                key: key.map(str::to_string),
+                text,
+            });
+        }
+        self.ready.notify_one();
+    }
+
+    fn frames(&self) -> MutexGuard<'_, VecDeque<QueuedFrame>> {
+        // The queue stays usable even if a holder panicked
+        self.frames.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_updates_are_coalesced() {
+        let stats = Arc::new(QueueStats::default());
+        let outbox = Outbox::new(8, stats.clone());
+        outbox.push_latest("metrics:a", "a1".to_string());
+        outbox.push("event".to_string());
+        outbox.push_latest("metrics:b", "b1".to_string());
+        outbox.push_latest("metrics:a", "a2".to_string());
+
+        let frames: Vec<String> = std::iter::from_fn(|| outbox.try_recv()).collect();
+        assert_eq!(frames, ["a2", "event", "b1"]);
+        assert_eq!(stats.coalesced(), 1);
+        assert_eq!(stats.dropped(), (0, 0));
+    }
+
+    #[test]
+    fn test_full_queues_drop_live_updates_first() {
+        let stats = Arc::new(QueueStats::default());
+        let outbox = Outbox::new(3, stats.clone());
+        outbox.push("e1".to_string());
+        outbox.push_latest("metrics:a", "a1".to_string());
+        outbox.push("e2".to_string());
+        outbox.push("e3".to_string());
+        assert_eq!(stats.dropped(), (1, 0));
+        outbox.push("e4".to_string());
+        assert_eq!(stats.dropped(), (1, 1));
+        assert_eq!(outbox.len(), 3);
+
+        let frames: Vec<String> = std::iter::from_fn(|| outbox.try_recv()).collect();
+        assert_eq!(frames, ["e2", "e3", "e4"]);
+
+        let mut out = String::new();
+        stats.render_prometheus(&mut out);
+        assert!(out.contains("vyos_webui_ws_frames_dropped_total{kind=\"live\"} 1\n"));
+        assert!(out.contains("vyos_webui_ws_frames_dropped_total{kind=\"other\"} 1\n"));
+    }
+
+    #[tokio::test]
+    async fn test_recv_waits_for_a_frame() {
+        let outbox = Outbox::new(4, Arc::default());
+        let reader = outbox.clone();
+        let received = tokio::spawn(async move { reader.recv().await });
+        tokio::task::yield_now().await;
+        outbox.push("hello".to_string());
+        assert_eq!(received.await.unwrap(), "hello");
+        assert!(outbox.is_empty());
+    }
+}
@@ -0,0 +1,531 @@
+//! Shared fixtures for the end-to-end tests
+//!
+//! [`TestApp`] runs the real application (services, handlers, and middleware)
+//! against an in-memory SQLite database, and [`mock_vyos`] starts an HTTP
+//! server that answers like the VyOS REST API so node endpoints can be
+//! exercised without a router. [`mock_smtp`] collects the email the
+//! application sends, and [`mock_tls_endpoint`] serves a certificate for
+//! expiry checks.
+
+#![allow(dead_code)]
+
+use actix_http::Request;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::{test, web, App, Error};
+use serde_json::{json, Value};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use vyos_web_ui_backend::app::AppState;
+use vyos_web_ui_backend::config::AppConfig;
+use vyos_web_ui_backend::db::{create_database, Database};
+use vyos_web_ui_backend::middleware::locale::LocaleMiddleware;
+use vyos_web_ui_backend::models::quota::TeamQuota;
+use vyos_web_ui_backend::models::storage::ArtifactStorage;
+use vyos_web_ui_backend::seed;
+
+/// API key the mock VyOS server accepts
+pub const VYOS_API_KEY: &str = "test-api-key";
+
+/// Password used for every user created by [`TestApp::register`]
+pub const TEST_PASSWORD: &str = "password123";
+
+/// Application configuration for tests
+pub fn test_config() -> AppConfig {
+    AppConfig {
+        server_host: "127.0.0.1".to_string(),
+        server_port: 0,
+        server_workers: 1,
+        server_keep_alive_secs: 5,
+        max_payload_bytes: 1024 * 1024,
+        upload_dir: std::env::temp_dir()
+            .join(format!("vyos-webui-uploads-{}", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .into_owned(),
+        max_upload_bytes: 64 * 1024,
+        max_attachment_bytes: 16 * 1024,
+        app_env: "test".to_string(),
+        database_url: "sqlite::memory:".to_string(),
+        database_max_connections: 1,
+        database_min_connections: 0,
+        database_slow_query_ms: 500,
+        database_auto_migrate: true,
+        instance_id: format!("test-{}", uuid::Uuid::new_v4()),
+        leader_lease_secs: 30,
+        backup_dir: std::env::temp_dir()
+            .join(format!("vyos-webui-backups-{}", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .into_owned(),
+        storage_cap_bytes: None,
+        storage_warning_percent: 80,
+        artifact_storage: ArtifactStorage::Filesystem,
+        artifact_s3_endpoint: None,
+        artifact_s3_bucket: None,
+        artifact_s3_region: "us-east-1".to_string(),
+        artifact_s3_access_key_id: None,
+        artifact_s3_secret_access_key: None,
+        artifact_s3_path_style: true,
+        artifact_s3_part_size_bytes: 8 * 1024 * 1024,
+        jwt_secret_key: "test_secret_key".to_string(),
+        jwt_expiration_minutes: 60,
+        refresh_token_expiration_days: 30,
+        legacy_user_ids_sunset: chrono::NaiveDate::from_ymd_opt(2099, 1, 1).unwrap(),
+        node_encryption_key: None,
+        node_failure_threshold: 3,
+        node_retry_after_secs: 30,
+        node_health_check_interval_secs: 0,
+        node_clock_drift_threshold_secs: 30,
+        node_reboot_grace_secs: 300,
+        show_cache_ttl_secs: 0,
+        show_cache_command_ttls: Default::default(),
+        metrics_collection_interval_secs: 0,
+        websocket_queue_capacity: 256,
+        notification_interval_secs: 0,
+        oui_database_url: "http://127.0.0.1:9/oui.csv".to_string(),
+        geoip_database_path: None,
+        geoip_asn_database_path: None,
+        syslog_listen_addr: None,
+        chaos_enabled: false,
+        load_profile_enabled: false,
+        log_level: "warn".to_string(),
+        vyos_api_url: None,
+        vyos_api_username: None,
+        vyos_api_password: None,
+        git_export_repo_path: None,
+        git_export_remote: None,
+        git_export_branch: "main".to_string(),
+        config_lint_disabled_rules: Default::default(),
+        config_secret_paths: vyos_web_ui_backend::config::DEFAULT_SECRET_PATHS.iter().map(|path| path.to_string()).collect(),
+        config_secrets_key: Some("test_config_secrets_key".to_string()),
+        default_team_quota: TeamQuota::default(),
+        client_error_rate_limit: 5,
+        config_backup_interval_secs: 0,
+        config_backup_keep_last: 10,
+        config_backup_keep_daily_days: 30,
+        rate_limit_auth_per_minute: 1000,
+        rate_limit_node_per_minute: 1000,
+        trusted_proxies: Vec::new(),
+        smtp_host: None,
+        smtp_port: 587,
+        smtp_username: None,
+        smtp_password: None,
+        smtp_from: "VyOS Web UI <noreply@localhost>".to_string(),
+        smtp_starttls: true,
+        cors_allowed_origins: Vec::new(),
+        incident_sync_interval_secs: 60,
+        feature_flags: Default::default(),
+        change_freeze_windows: Vec::new(),
+        secret_references: Default::default(),
+    }
+}
+
+/// Fresh in-memory database with the schema and all migrations applied
+///
+/// The pool is limited to a single connection because every SQLite
+/// `:memory:` connection opens its own, empty database.
+pub async fn test_database() -> web::Data<Database> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("failed to open in-memory database");
+
+    create_database(pool).await.expect("failed to initialize database")
+}
+
+/// Fresh database in a temporary file, for features that need the database
+/// on disk such as backups
+pub async fn test_database_file() -> web::Data<Database> {
+    let path = std::env::temp_dir().join(format!("vyos-webui-test-{}.db", uuid::Uuid::new_v4()));
+    let pool = SqlitePoolOptions::new()
+        .connect_with(SqliteConnectOptions::new().filename(&path).create_if_missing(true))
+        .await
+        .expect("failed to open database file");
+
+    create_database(pool).await.expect("failed to initialize database")
+}
+
+/// Real application backed by an in-memory database
+pub struct TestApp {
+    pub state: AppState,
+}
+
+impl TestApp {
+    /// Build the services on a fresh database
+    pub async fn new() -> Self {
+        Self {
+            state: AppState::new(test_config(), test_database().await),
+        }
+    }
+
+    /// Build the services with a custom configuration on a fresh database
+    pub async fn with_config(config: AppConfig) -> Self {
+        Self {
+            state: AppState::new(config, test_database().await),
+        }
+    }
+
+    /// Build the services on a fresh database stored in a temporary file
+    pub async fn on_disk() -> Self {
+        Self {
+            state: AppState::new(test_config(), test_database_file().await),
+        }
+    }
+
+    /// Build the services on a fresh database populated with the sample data
+    /// set from [`vyos_web_ui_backend::seed`]
+    pub async fn seeded() -> Self {
+        let test_app = Self::new().await;
+        seed::seed(&test_app.state).await.expect("failed to seed database");
+        test_app
+    }
+
+    /// Database shared by the services
+    pub fn db(&self) -> &Database {
+        self.state.db.get_ref()
+    }
+
+    /// The application as the server builds it, minus CORS and logging
+    pub fn app(
+        &self,
+    ) -> App<
+        impl ServiceFactory<
+            ServiceRequest,
+            Config = (),
+            Response = ServiceResponse<impl MessageBody>,
+            Error = Error,
+            InitError = (),
+        >,
+    > {
+        let state = self.state.clone();
+        App::new()
+            .configure(move |cfg| state.configure(cfg))
+            .wrap(LocaleMiddleware)
+    }
+
+    /// Register a user through the API and return their ID and access token
+    pub async fn register<S, B>(&self, app: &S, username: &str) -> (String, String)
+    where
+        S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+        B: MessageBody,
+    {
+        let req = test::TestRequest::post()
+            .uri("/api/auth/register")
+            .set_json(json!({
+                "username": username,
+                "email": format!("{}@example.com", username),
+                "password": TEST_PASSWORD,
+            }))
+            .to_request();
+        let resp = test::call_service(app, req).await;
+        assert_eq!(resp.status(), 201, "registration of {} failed", username);
+
+        let body: Value = test::read_body_json(resp).await;
+        (
+            body["user"]["id"].as_str().unwrap().to_string(),
+            body["access_token"].as_str().unwrap().to_string(),
+        )
+    }
+
+    /// Register a user and grant them administrator rights
+    pub async fn register_admin<S, B>(&self, app: &S, username: &str) -> (String, String)
+    where
+        S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+        B: MessageBody,
+    {
+        let (user_id, token) = self.register(app, username).await;
+        self.db()
+            .update_user_superuser(&user_id, true)
+            .await
+            .expect("failed to promote user");
+        (user_id, token)
+    }
+
+    /// Register a node backed by a mock VyOS server and return its ID
+    pub async fn create_node<S, B>(&self, app: &S, token: &str, server: &MockServer, name: &str) -> String
+    where
+        S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+        B: MessageBody,
+    {
+        let req = test::TestRequest::post()
+            .uri("/api/nodes")
+            .insert_header(bearer(token))
+            .set_json(node_payload(server, name))
+            .to_request();
+        let resp = test::call_service(app, req).await;
+        assert_eq!(resp.status(), 201, "creation of node {} failed", name);
+
+        let node: Value = test::read_body_json(resp).await;
+        node["id"].as_str().unwrap().to_string()
+    }
+}
+
+/// `Authorization` header value for an access token
+pub fn bearer(token: &str) -> (&'static str, String) {
+    ("Authorization", format!("Bearer {}", token))
+}
+
+/// SMTP server that accepts every message, see [`mock_smtp`]
+pub struct MockSmtp {
+    pub port: u16,
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockSmtp {
+    /// Point a configuration's SMTP settings at this server
+    pub fn configure(&self, config: &mut AppConfig) {
+        config.smtp_host = Some("127.0.0.1".to_string());
+        config.smtp_port = self.port;
+        config.smtp_starttls = false;
+    }
+
+    /// Raw messages received so far, headers included
+    pub fn messages(&self) -> Vec<String> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+/// Start an SMTP server that accepts and keeps every message
+///
+/// It speaks just enough SMTP for the application's mailer, without TLS
+/// or authentication.
+pub async fn mock_smtp() -> MockSmtp {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("failed to bind SMTP port");
+    let port = listener.local_addr().unwrap().port();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+
+    let inbox = messages.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let inbox = inbox.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                let _ = writer.write_all(b"220 localhost ESMTP\r\n").await;
+                let mut message: Option<String> = None;
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let reply: &[u8] = match message.as_mut() {
+                        Some(_) if line == "." => {
+                            inbox.lock().unwrap().extend(message.take());
+                            b"250 OK\r\n"
+                        }
+                        Some(message) => {
+                            message.push_str(&line);
+                            message.push('\n');
+                            continue;
+                        }
+                        None => match line.get(..4).map(str::to_ascii_uppercase).as_deref() {
+                            Some("EHLO") => b"250-localhost\r\n250 8BITMIME\r\n",
+                            Some("DATA") => {
+                                message = Some(String::new());
+                                b"354 End data with <CR><LF>.<CR><LF>\r\n"
+                            }
+                            Some("QUIT") => {
+                                let _ = writer.write_all(b"221 Bye\r\n").await;
+                                break;
+                            }
+                            _ => b"250 OK\r\n",
+                        },
+                    };
+                    if writer.write_all(reply).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    MockSmtp { port, messages }
+}
+
+/// Start a TLS server whose self-signed certificate expires in `days`
+///
+/// Returns the port. Connections are dropped right after the handshake,
+/// which is all a certificate check needs.
+pub async fn mock_tls_endpoint(days: u32) -> u16 {
+    use openssl::asn1::Asn1Time;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+    use openssl::x509::{X509NameBuilder, X509};
+
+    let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_text("CN", "localhost").unwrap();
+    let name = name.build();
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_subject_name(&name).unwrap();
+    builder.set_issuer_name(&name).unwrap();
+    builder.set_pubkey(&key).unwrap();
+    builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+    builder.set_not_after(&Asn1Time::days_from_now(days).unwrap()).unwrap();
+    builder.sign(&key, MessageDigest::sha256()).unwrap();
+    let certificate = builder.build();
+
+    let identity = native_tls::Identity::from_pkcs8(
+        &certificate.to_pem().unwrap(),
+        &key.private_key_to_pem_pkcs8().unwrap(),
+    )
+    .unwrap();
+    let acceptor = tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("failed to bind TLS port");
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let _ = acceptor.accept(stream).await;
+            });
+        }
+    });
+
+    port
+}
+
+/// VyOS releases with captured response fixtures
+pub const VYOS_RELEASES: [&str; 3] = ["1.3", "1.4", "1.5"];
+
+/// POST endpoints served by each release
+fn vyos_endpoints(release: &str) -> &'static [&'static str] {
+    const EQUULEUS: &[&str] = &[
+        "retrieve", "configure", "show", "generate", "config-file", "image", "reset",
+    ];
+    const SAGITTA: &[&str] = &[
+        "retrieve", "configure", "show", "generate", "config-file", "image", "reset", "reboot",
+        "poweroff", "graphql",
+    ];
+    match release {
+        "1.3" => EQUULEUS,
+        _ => SAGITTA,
+    }
+}
+
+/// Captured API response of a VyOS release
+///
+/// Fixtures live in `tests/fixtures/vyos/<release>/<name>.json`. Returns
+/// `None` if the release has no such fixture (e.g. `info` before 1.5).
+pub fn vyos_fixture(release: &str, name: &str) -> Option<Value> {
+    let path = format!(
+        "{}/tests/fixtures/vyos/{}/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        release,
+        name
+    );
+    let text = std::fs::read_to_string(path).ok()?;
+    Some(serde_json::from_str(&text).expect("invalid fixture JSON"))
+}
+
+/// Start a mock VyOS API server answering like the latest release
+pub async fn mock_vyos() -> MockServer {
+    mock_vyos_release("1.5").await
+}
+
+/// Start a mock VyOS API server answering with a release's fixtures
+///
+/// Requests must carry [`VYOS_API_KEY`]; anything else gets a 401 the way a
+/// misconfigured node would answer. Endpoints the release lacks (such as
+/// `/info` before 1.5) answer 404, and requests to known endpoints that match
+/// no fixture are rejected with 422 like the router rejects invalid bodies.
+pub async fn mock_vyos_release(release: &str) -> MockServer {
+    let server = MockServer::start().await;
+    let authorization = format!("Bearer {}", VYOS_API_KEY);
+    let auth = || header("Authorization", authorization.as_str());
+    let fixture = |name: &str| {
+        vyos_fixture(release, name)
+            .unwrap_or_else(|| panic!("missing fixture {}/{}", release, name))
+    };
+
+    if let Some(info) = vyos_fixture(release, "info") {
+        Mock::given(method("GET"))
+            .and(path("/info"))
+            .and(auth())
+            .respond_with(ResponseTemplate::new(200).set_body_json(info))
+            .mount(&server)
+            .await;
+    }
+
+    for endpoint in vyos_endpoints(release) {
+        Mock::given(method("POST"))
+            .and(path(format!("/{}", endpoint)))
+            .and(auth())
+            .respond_with(ResponseTemplate::new(422).set_body_json(json!({
+                "success": false,
+                "data": null,
+                "error": "Invalid request body",
+            })))
+            .with_priority(10)
+            .mount(&server)
+            .await;
+    }
+
+    // (command, fixture, status)
+    let commands = [
+        ("show version", "show_version", 200),
+        ("show host name", "show_host_name", 200),
+        ("show interfaces", "show_interfaces", 200),
+        ("show foo", "show_invalid", 400),
+    ];
+    for (command, name, status) in commands {
+        Mock::given(method("POST"))
+            .and(path("/show"))
+            .and(body_partial_json(json!({ "command": command })))
+            .and(auth())
+            .respond_with(ResponseTemplate::new(status).set_body_json(fixture(name)))
+            .mount(&server)
+            .await;
+    }
+
+    Mock::given(method("POST"))
+        .and(path("/retrieve"))
+        .and(auth())
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("retrieve_system")))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/configure"))
+        .and(auth())
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "data": null,
+            "error": null,
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(wiremock::matchers::any())
+        .and(auth())
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({ "detail": "Not Found" })))
+        .with_priority(u8::MAX - 1)
+        .mount(&server)
+        .await;
+
+    Mock::given(wiremock::matchers::any())
+        .respond_with(ResponseTemplate::new(401).set_body_string("Invalid API key"))
+        .with_priority(u8::MAX)
+        .mount(&server)
+        .await;
+
+    server
+}
+
+/// Node creation payload pointing at a mock VyOS server
+pub fn node_payload(server: &MockServer, name: &str) -> Value {
+    let address = server.address();
+    json!({
+        "name": name,
+        "host": address.ip().to_string(),
+        "port": address.port(),
+        "api_key": VYOS_API_KEY,
+        "use_https": false,
+        "verify_ssl": false,
+        "timeout": 5,
+    })
+}
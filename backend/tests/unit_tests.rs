@@ -151,6 +151,7 @@ mod auth_tests {
             username: "testuser".to_string(),
             exp: Utc::now().timestamp() + 3600,
             iat: Utc::now().timestamp(),
+            sid: None,
         };
 
         assert_eq!(claims.sub, "123");
@@ -0,0 +1,329 @@
+//! VyOS API contract tests
+//!
+//! Replays responses captured from VyOS 1.3, 1.4, and 1.5 routers
+//! (`tests/fixtures/vyos/`) against the client and its parsers, so a change in
+//! either is checked against every supported release, along with capability
+//! probing and its cache. Run with
+//! `cargo test --test vyos_contract_tests`.
+
+mod common;
+
+use actix_web::test::{call_and_read_body_json, call_service, init_service, read_body_json, TestRequest};
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::{
+    bearer, mock_vyos_release, node_payload, vyos_fixture, TestApp, VYOS_API_KEY, VYOS_RELEASES,
+};
+use vyos_web_ui_backend::vyos_client::parsers::{parse_show_interfaces, parse_show_version};
+use vyos_web_ui_backend::vyos_client::{
+    Capability, VyOSClient, VyOSClientConfig, VyOSRelease, PROBED_CAPABILITIES,
+};
+
+/// Expected parse results of each release's fixtures
+struct Expected {
+    release: &'static str,
+    version: &'static str,
+    release_train: &'static str,
+    vyos_release: VyOSRelease,
+}
+
+const EXPECTED: [Expected; 3] = [
+    Expected {
+        release: "1.3",
+        version: "1.3.8",
+        release_train: "equuleus",
+        vyos_release: VyOSRelease::Equuleus,
+    },
+    Expected {
+        release: "1.4",
+        version: "1.4.0",
+        release_train: "sagitta",
+        vyos_release: VyOSRelease::Sagitta,
+    },
+    Expected {
+        release: "1.5",
+        version: "1.5-rolling-202409250007",
+        release_train: "current",
+        vyos_release: VyOSRelease::Circinus,
+    },
+];
+
+/// `data` member of a fixture envelope
+fn fixture_output(release: &str, name: &str) -> String {
+    let fixture = vyos_fixture(release, name).expect("missing fixture");
+    fixture["data"].as_str().expect("fixture has no text output").to_string()
+}
+
+fn client(server: &MockServer) -> VyOSClient {
+    let address = server.address();
+    VyOSClient::new(VyOSClientConfig::new(
+        address.ip().to_string(),
+        VYOS_API_KEY.to_string(),
+        address.port(),
+        false,
+        false,
+        5,
+    ))
+    .unwrap()
+}
+
+// ============================================================================
+// Parsers
+// ============================================================================
+
+#[test]
+fn test_every_release_has_fixtures() {
+    assert_eq!(VYOS_RELEASES.len(), EXPECTED.len());
+    for release in VYOS_RELEASES {
+        for name in ["show_version", "show_host_name", "show_interfaces", "show_invalid", "retrieve_system"] {
+            assert!(vyos_fixture(release, name).is_some(), "{}/{} is missing", release, name);
+        }
+    }
+}
+
+#[test]
+fn test_show_version_is_parsed() {
+    for expected in &EXPECTED {
+        let version = parse_show_version(&fixture_output(expected.release, "show_version"))
+            .unwrap_or_else(|| panic!("{}: show version not parsed", expected.release));
+
+        assert_eq!(version.version, expected.version);
+        assert_eq!(version.release_train.as_deref(), Some(expected.release_train));
+        assert_eq!(version.architecture.as_deref(), Some("x86_64"));
+        assert_eq!(VyOSRelease::from_version(&version.version), Some(expected.vyos_release));
+    }
+}
+
+#[test]
+fn test_show_interfaces_is_parsed() {
+    for release in VYOS_RELEASES {
+        let interfaces = parse_show_interfaces(&fixture_output(release, "show_interfaces"));
+        let names: Vec<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["eth0", "eth1", "eth2", "lo"], "{}", release);
+
+        let eth0 = &interfaces[0];
+        assert_eq!(eth0.address.as_deref(), Some("192.0.2.1/24"));
+        assert_eq!(eth0.netmask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(eth0.description.as_deref(), Some("WAN"));
+        assert!(eth0.is_up);
+
+        // Continuation lines add addresses to the interface above
+        assert_eq!(interfaces[1].addresses, ["10.0.0.1/24", "2001:db8::1/64"]);
+        assert_eq!(interfaces[3].addresses, ["127.0.0.1/8", "::1/128"]);
+
+        // Admin down, without address or description
+        let eth2 = &interfaces[2];
+        assert!(!eth2.is_up);
+        assert_eq!(eth2.address, None);
+        assert_eq!(eth2.description, None);
+    }
+
+    // 1.5 adds MAC and MTU columns
+    let interfaces = parse_show_interfaces(&fixture_output("1.5", "show_interfaces"));
+    assert_eq!(interfaces[0].mac_address.as_deref(), Some("52:54:00:12:34:56"));
+    assert_eq!(interfaces[0].mtu, Some(1500));
+    assert_eq!(interfaces[3].mtu, Some(65536));
+
+    let interfaces = parse_show_interfaces(&fixture_output("1.4", "show_interfaces"));
+    assert_eq!(interfaces[0].mac_address, None);
+    assert_eq!(interfaces[0].mtu, None);
+}
+
+// ============================================================================
+// Client
+// ============================================================================
+
+#[actix_web::test]
+async fn test_client_reads_every_release() {
+    for expected in &EXPECTED {
+        let server = mock_vyos_release(expected.release).await;
+        let client = client(&server);
+
+        let info = client.get_info().await.unwrap();
+        assert_eq!(info.hostname, "vyos-edge", "{}", expected.release);
+        assert_eq!(info.version, expected.version);
+
+        let interfaces = client.get_interfaces().await.unwrap();
+        assert_eq!(interfaces.len(), 4);
+
+        let config = client.retrieve_config(Some("system".to_string())).await.unwrap();
+        assert_eq!(config["host-name"], "vyos-edge");
+        // Only the 1.3 capture was taken from a router with NTP servers
+        assert_eq!(config.get("ntp").is_some(), expected.release == "1.3");
+
+        let error = client.show("show foo").await.unwrap_err().to_string();
+        assert!(error.contains("Invalid command: show [foo]"), "{}", error);
+    }
+}
+
+#[actix_web::test]
+async fn test_info_endpoint_is_preferred_when_available() {
+    let server = mock_vyos_release("1.5").await;
+    client(&server).get_info().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].url.path(), "/info");
+}
+
+// ============================================================================
+// Capabilities
+// ============================================================================
+
+#[actix_web::test]
+async fn test_node_capabilities_endpoint() {
+    let harness = TestApp::new().await;
+    let app = init_service(harness.app()).await;
+    let (_, token) = harness.register_admin(&app, "admin1").await;
+
+    for expected in &EXPECTED {
+        let server = mock_vyos_release(expected.release).await;
+        let req = TestRequest::post()
+            .uri("/api/nodes")
+            .insert_header(bearer(&token))
+            .set_json(node_payload(&server, &format!("vyos-{}", expected.release)))
+            .to_request();
+        let node: Value = call_and_read_body_json(&app, req).await;
+
+        let req = TestRequest::get()
+            .uri(&format!("/api/nodes/{}/capabilities", node["id"].as_str().unwrap()))
+            .insert_header(bearer(&token))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = read_body_json(resp).await;
+
+        assert_eq!(body["version"], expected.version);
+        assert_eq!(body["release"], expected.vyos_release.as_str());
+
+        let capabilities = &body["capabilities"];
+        for capability in [
+            Capability::Show,
+            Capability::Retrieve,
+            Capability::Reboot,
+            Capability::Info,
+            Capability::Graphql,
+        ] {
+            let key = serde_json::to_value(capability).unwrap();
+            assert_eq!(
+                capabilities[key.as_str().unwrap()],
+                capability.supported_by(Some(expected.vyos_release)),
+                "{} {:?}",
+                expected.release,
+                capability
+            );
+        }
+    }
+}
+
+#[actix_web::test]
+async fn test_client_probes_endpoints() {
+    let expected = [
+        ("1.3", [false, true, true, true, false]),
+        ("1.4", [false, true, true, true, true]),
+        ("1.5", [true, true, true, true, true]),
+    ];
+
+    for (release, supported) in expected {
+        let server = mock_vyos_release(release).await;
+        let client = client(&server);
+        for (capability, supported) in PROBED_CAPABILITIES.iter().zip(supported) {
+            assert_eq!(client.supports(*capability).await.unwrap(), supported, "{} {}", release, capability);
+        }
+    }
+}
+
+#[actix_web::test]
+async fn test_node_capabilities_are_cached() {
+    let server = mock_vyos_release("1.4").await;
+    let harness = TestApp::new().await;
+    let app = init_service(harness.app()).await;
+    let (_, token) = harness.register_admin(&app, "admin1").await;
+
+    let req = TestRequest::post()
+        .uri("/api/nodes")
+        .insert_header(bearer(&token))
+        .set_json(node_payload(&server, "edge-1"))
+        .to_request();
+    let node: Value = call_and_read_body_json(&app, req).await;
+    let uri = format!("/api/nodes/{}/capabilities", node["id"].as_str().unwrap());
+
+    let req = TestRequest::get().uri(&uri).insert_header(bearer(&token)).to_request();
+    let first: Value = call_and_read_body_json(&app, req).await;
+    let probes = server.received_requests().await.unwrap().len();
+
+    let req = TestRequest::get().uri(&uri).insert_header(bearer(&token)).to_request();
+    let second: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(first, second);
+    assert_eq!(server.received_requests().await.unwrap().len(), probes);
+
+    // Probing again asks the node
+    let req = TestRequest::post().uri(&uri).insert_header(bearer(&token)).to_request();
+    let resp = call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(server.received_requests().await.unwrap().len() > probes);
+}
+
+#[actix_web::test]
+async fn test_unsupported_endpoints_are_reported() {
+    // A router with the show API disabled
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/info"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vyos_fixture("1.5", "info").unwrap()))
+        .mount(&server)
+        .await;
+    Mock::given(wiremock::matchers::any())
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({ "detail": "Not Found" })))
+        .with_priority(u8::MAX)
+        .mount(&server)
+        .await;
+
+    let harness = TestApp::new().await;
+    let app = init_service(harness.app()).await;
+    let (_, token) = harness.register_admin(&app, "admin1").await;
+
+    let req = TestRequest::post()
+        .uri("/api/nodes")
+        .insert_header(bearer(&token))
+        .set_json(node_payload(&server, "edge-1"))
+        .to_request();
+    let node: Value = call_and_read_body_json(&app, req).await;
+    let node_id = node["id"].as_str().unwrap();
+
+    // Before probing, the router's 404 is reported as unsupported
+    let req = TestRequest::post()
+        .uri(&format!("/api/nodes/{}/show", node_id))
+        .insert_header(bearer(&token))
+        .set_json(json!({ "command": "show version" }))
+        .to_request();
+    let resp = call_service(&app, req).await;
+    assert_eq!(resp.status(), 501);
+    let body: Value = read_body_json(resp).await;
+    assert_eq!(body["error"], "Not supported: /show is not available on this VyOS node");
+
+    let req = TestRequest::post()
+        .uri(&format!("/api/nodes/{}/capabilities", node_id))
+        .insert_header(bearer(&token))
+        .to_request();
+    let capabilities: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(capabilities["capabilities"]["show"], false);
+    assert_eq!(capabilities["capabilities"]["info"], true);
+
+    // Once probed, the node is not asked at all
+    let requests = server.received_requests().await.unwrap().len();
+    let req = TestRequest::get()
+        .uri(&format!("/api/nodes/{}/interfaces", node_id))
+        .insert_header(bearer(&token))
+        .to_request();
+    let resp = call_service(&app, req).await;
+    assert_eq!(resp.status(), 501);
+    let body: Value = read_body_json(resp).await;
+    assert_eq!(
+        body["error"],
+        "Not supported: show is not supported on node edge-1 (VyOS 1.5-rolling-202409250007)"
+    );
+    assert_eq!(server.received_requests().await.unwrap().len(), requests);
+}